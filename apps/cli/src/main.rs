@@ -0,0 +1,99 @@
+//! glp
+//!
+//! Command-line companion to the desktop app for learners who would rather
+//! solve challenges in their own editor. It works on the desktop app's data,
+//! so attempts, XP and progress recorded here show up in the app.
+
+mod verify;
+mod workspace;
+
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use colored::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::verify::AppContext;
+
+#[derive(Parser)]
+#[command(name = "glp")]
+#[command(about = "Work on learning platform challenges from the command line", long_about = None)]
+struct Cli {
+    /// Data directory holding app.db and imported curricula (default: the desktop app's)
+    #[arg(short, long, global = true)]
+    data_dir: Option<PathBuf>,
+    /// Profile to record attempts for (default: the most recently used one)
+    #[arg(short, long, global = true)]
+    user: Option<String>,
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Run a challenge's tests against a file and record the attempt
+    Verify {
+        /// Challenge node id, as in the curriculum
+        node_id: String,
+        /// Source file with your solution
+        #[arg(short, long)]
+        file: PathBuf,
+    },
+    /// Compile a file against a challenge without running its tests
+    Check {
+        /// Challenge node id, as in the curriculum
+        node_id: String,
+        /// Source file with your solution
+        #[arg(short, long)]
+        file: PathBuf,
+    },
+    /// Write a challenge out as a cargo project to solve in your editor
+    Export {
+        /// Challenge node id, as in the curriculum
+        node_id: String,
+        /// Directory to create the project in (default: ./<node id>)
+        #[arg(short = 'o', long)]
+        dir: Option<PathBuf>,
+    },
+    /// Verify the solution in an exported challenge and record the attempt
+    Submit {
+        /// The exported project
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+    },
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    let data_dir = cli.data_dir.unwrap_or_else(verify::default_data_dir);
+
+    let result = run(cli.command, &data_dir, cli.user.as_deref()).await;
+    match result {
+        Ok(true) => {}
+        // Failing tests exit non-zero so editors and scripts can tell
+        Ok(false) => std::process::exit(1),
+        Err(e) => {
+            eprintln!("{} {:#}", "Error:".red().bold(), e);
+            std::process::exit(2);
+        }
+    }
+}
+
+/// Whether the command succeeded; for verification, whether the tests
+/// passed, and for a check, whether the code compiled
+async fn run(command: Commands, data_dir: &Path, user_id: Option<&str>) -> anyhow::Result<bool> {
+    let app = AppContext::open(data_dir, user_id)?;
+    match command {
+        Commands::Verify { node_id, file } => {
+            let code = fs::read_to_string(&file).with_context(|| format!("Couldn't read {}", file.display()))?;
+            verify::verify(&app, &node_id, &code, None).await
+        }
+        Commands::Check { node_id, file } => {
+            let code = fs::read_to_string(&file).with_context(|| format!("Couldn't read {}", file.display()))?;
+            verify::check(&app, &node_id, &code).await
+        }
+        Commands::Export { node_id, dir } => workspace::export(&app, &node_id, dir).map(|_| true),
+        Commands::Submit { dir } => workspace::submit(&app, &dir).await,
+    }
+}
@@ -0,0 +1,357 @@
+use anyhow::{anyhow, bail, Result};
+use chrono::Utc;
+use colored::*;
+use content::workspace::{self, WorkspaceInfo};
+use content::{ChallengeTemplate, ContentLoader, ContentNode};
+use glp_core::db::repos::{CurriculumRepository, UserRepository};
+use glp_core::gamification::{Difficulty, EventCalendar, GamificationEvent, XpActivity};
+use glp_core::models::{AttemptFlag, Curriculum, User};
+use glp_core::services::{ChallengeOutcome, ChallengeRun, ChallengeService, ChallengeSpec};
+use glp_core::AppDatabase;
+use glp_runner::{check_crates, matches_solution, DockerRunner, RuntimeError, VerificationResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const EVENTS_FILE: &str = "events.json";
+
+/// Where the desktop app keeps its data
+pub fn default_data_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("gamified-learning-platform")
+}
+
+/// The desktop app's database, profile and active curriculum
+pub struct AppContext {
+    pub data_dir: PathBuf,
+    pub db: AppDatabase,
+    pub user: User,
+    pub curriculum: Curriculum,
+    pub loader: ContentLoader,
+}
+
+impl AppContext {
+    /// Open the app's data as `user_id`, or the most recently used profile
+    pub fn open(data_dir: &Path, user_id: Option<&str>) -> Result<Self> {
+        let db_path = data_dir.join("app.db");
+        if !db_path.exists() {
+            bail!("No app data in {}; open the desktop app once first", data_dir.display());
+        }
+        let db = AppDatabase::new(db_path)?;
+
+        let (user, curriculum, content_key, license_key) = db.with_connection(|conn| {
+            let user = match user_id {
+                Some(id) => UserRepository::get_by_id(conn, id)?,
+                None => UserRepository::get_all(conn)?.into_iter().next(),
+            };
+            let curriculum = CurriculumRepository::get_active(conn)?;
+            let (content_key, license_key) = match &curriculum {
+                Some(curriculum) => (
+                    CurriculumRepository::get_content_key(conn, &curriculum.id)?,
+                    CurriculumRepository::get_license_key(conn, &curriculum.id)?,
+                ),
+                None => (None, None),
+            };
+            Ok((user, curriculum, content_key, license_key))
+        })?;
+        let user = user.ok_or_else(|| match user_id {
+            Some(id) => anyhow!("No profile with id {}", id),
+            None => anyhow!("No profile yet; create one in the desktop app"),
+        })?;
+        let curriculum = curriculum.ok_or_else(|| anyhow!("No curriculum is active; choose one in the desktop app"))?;
+
+        let loader = ContentLoader::open(
+            data_dir.join(&curriculum.content_path),
+            content_key.as_deref(),
+            license_key.as_deref(),
+        )?;
+        Ok(Self { data_dir: data_dir.to_path_buf(), db, user, curriculum, loader })
+    }
+}
+
+/// Test `code` against a challenge of the active curriculum and record the
+/// attempt as the desktop app would, flags for review included.
+/// `workspace` is the exported workspace the code came from, if any; its
+/// manifest is only used if the challenge lets learners add dependencies.
+/// Returns whether the tests passed.
+pub async fn verify(
+    app: &AppContext,
+    node_id: &str,
+    code: &str,
+    workspace: Option<(&Path, &WorkspaceInfo)>,
+) -> Result<bool> {
+    let LoadedChallenge { spec, test_code, template, dependencies_allowlist: allowlist, solution } =
+        load_challenge(&app.loader, node_id)?;
+    let mut flags = Vec::new();
+    if solution.as_deref().is_some_and(|solution| matches_solution(code, solution)) {
+        flags.push(AttemptFlag::MatchesReference);
+    }
+    let mut cargo_toml = None;
+    if let Some((dir, info)) = workspace {
+        cargo_toml = Some(workspace::read_cargo_toml(dir)?);
+        let editable: &[&str] = if allowlist.is_some() { &["Cargo.toml"] } else { &[] };
+        let files = workspace::edited_outside(dir, info, editable)?;
+        if !files.is_empty() {
+            flags.push(AttemptFlag::EditedOutsideAllowed { files });
+        }
+    }
+    let allowlist = allowlist.filter(|_| cargo_toml.is_some());
+    let mut allowed = template.crate_names();
+    allowed.extend(allowlist.iter().flatten().map(|name| name.replace('-', "_")));
+    check_crates(code, &allowed)?;
+
+    println!(
+        "{} {} for {} ({})",
+        "Verifying".cyan().bold(),
+        node_id,
+        app.user.display_name.as_deref().unwrap_or(&app.user.id),
+        app.curriculum.name
+    );
+    let template_dir = challenge_template_dir(&app.data_dir, node_id, &template)?;
+    let mut runner = DockerRunner::new().await?;
+    if let Some(image) = &template.image {
+        runner = runner.with_image(image);
+    }
+    let source = format!("{}\n\n{}", code, test_code);
+    let verification = match (&allowlist, &cargo_toml) {
+        (Some(allowlist), Some(cargo_toml)) => {
+            runner
+                .run_verification_with_dependencies(&template_dir, &source, cargo_toml, allowlist)
+                .await?
+        }
+        _ => runner.run_verification(&template_dir, &source).await?,
+    };
+
+    let run = ChallengeRun {
+        success: verification.success,
+        tests_passed: verification.tests_passed,
+        tests_failed: verification.tests_failed,
+        tests_total: verification.tests_total,
+        stdout: verification.stdout.clone(),
+        stderr: verification.stderr.clone(),
+        verification_json: serde_json::to_string(&verification)?,
+        flags,
+    };
+    let event_multiplier =
+        discover_events(&app.data_dir, &app.loader).multiplier_for(XpActivity::Challenge, Utc::now());
+    let (outcome, events) = app.db.with_connection(|conn| {
+        ChallengeService::submit(
+            conn,
+            &app.user.id,
+            Some(&app.curriculum.id),
+            node_id,
+            &spec,
+            code,
+            &run,
+            event_multiplier,
+        )
+    })?;
+
+    print_result(&verification, &outcome, &events);
+    Ok(outcome.passed)
+}
+
+/// Compile `code` for a challenge with `cargo check`, without running the
+/// tests or recording an attempt. Returns whether it compiled.
+pub async fn check(app: &AppContext, node_id: &str, code: &str) -> Result<bool> {
+    let LoadedChallenge { test_code, template, .. } = load_challenge(&app.loader, node_id)?;
+    check_crates(code, &template.crate_names())?;
+
+    println!("{} {}", "Checking".cyan().bold(), node_id);
+    let template_dir = challenge_template_dir(&app.data_dir, node_id, &template)?;
+    let mut runner = DockerRunner::new().await?;
+    if let Some(image) = &template.image {
+        runner = runner.with_image(image);
+    }
+    let result = runner
+        .run_check_only(&template_dir, &format!("{}\n\n{}", code, test_code))
+        .await?;
+
+    for diagnostic in &result.diagnostics {
+        match &diagnostic.rendered {
+            Some(rendered) => println!("\n{}", rendered.trim_end()),
+            None => println!("\n{}: {}", diagnostic.level, diagnostic.message),
+        }
+    }
+    if result.timed_out {
+        println!("\n{} {}", "✗ Check".red().bold(), "timed out".red());
+    } else if result.success {
+        println!("\n{} in {}ms", "✓ Compiles".green().bold(), result.duration_ms);
+    } else {
+        println!("\n{}", "✗ Didn't compile".red().bold());
+    }
+    Ok(result.success)
+}
+
+/// A node of the curriculum that can be verified here
+pub fn challenge_node<'a>(loader: &'a ContentLoader, node_id: &str) -> Result<&'a ContentNode> {
+    let node = loader
+        .get_node_by_id(node_id)
+        .ok_or_else(|| anyhow!("Node not found: {}", node_id))?;
+    if XpActivity::from_node_type(&node.node_type) != Some(XpActivity::Challenge) {
+        bail!("{} is not a challenge", node_id);
+    }
+    if node.track.is_some() {
+        bail!("{} is a project track day; submit it from the desktop app", node_id);
+    }
+    Ok(node)
+}
+
+/// The challenge behind a node
+struct LoadedChallenge {
+    spec: ChallengeSpec,
+    /// The tests it's verified with
+    test_code: String,
+    /// What its crate is built from
+    template: ChallengeTemplate,
+    /// Crates learners may add to it
+    dependencies_allowlist: Option<Vec<String>>,
+    solution: Option<String>,
+}
+
+fn load_challenge(loader: &ContentLoader, node_id: &str) -> Result<LoadedChallenge> {
+    let node = challenge_node(loader, node_id)?;
+    let challenge = loader.load_challenge(&node.content_path)?;
+    let template = loader.load_template(&challenge)?;
+
+    let mut skills = node.skills.clone();
+    for skill in challenge.skills {
+        if !skills.contains(&skill) {
+            skills.push(skill);
+        }
+    }
+
+    let spec = ChallengeSpec {
+        challenge_id: challenge.id,
+        difficulty: node.difficulty.parse().unwrap_or(Difficulty::Easy),
+        skills,
+        estimated_minutes: node.estimated_minutes,
+    };
+    Ok(LoadedChallenge {
+        spec,
+        test_code: challenge.test_code,
+        template,
+        dependencies_allowlist: challenge.dependencies_allowlist,
+        solution: challenge.solution,
+    })
+}
+
+/// Crate the submission is tested in, shared with the desktop app
+fn challenge_template_dir(data_dir: &Path, node_id: &str, template: &ChallengeTemplate) -> Result<PathBuf> {
+    let crate_name = workspace::crate_name(node_id);
+    let dir = data_dir.join("challenges").join(&crate_name);
+    template.write_crate(&dir, &format!("challenge_{}", crate_name))?;
+    Ok(dir)
+}
+
+/// XP events from the data directory and the content pack, as the desktop
+/// app finds them
+fn discover_events(data_dir: &Path, loader: &ContentLoader) -> EventCalendar {
+    let load = |path: PathBuf| {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| EventCalendar::from_json(&json).ok())
+    };
+    let mut calendar = load(data_dir.join(EVENTS_FILE)).unwrap_or_default();
+    if let Some(pack_events) = load(loader.content_dir().join(EVENTS_FILE)) {
+        calendar.merge(pack_events);
+    }
+    calendar
+}
+
+fn print_result(verification: &VerificationResult, outcome: &ChallengeOutcome, events: &[GamificationEvent]) {
+    if let Some(violation) = &verification.policy_violation {
+        println!("\n{} {}", "✗ Not run:".red().bold(), violation.message);
+    } else if let Some(error) = &verification.compile_error {
+        println!("\n{}", "✗ Didn't compile".red().bold());
+        let location = match (&error.file, error.line, error.column) {
+            (Some(file), Some(line), Some(column)) => format!("{}:{}:{}: ", file, line, column),
+            _ => String::new(),
+        };
+        println!("{}{}", location, error.message);
+    } else if let Some(error) = &verification.runtime_error {
+        let message = match error {
+            RuntimeError::Timeout => "timed out".to_string(),
+            RuntimeError::Panic { message } => format!("panicked: {}", message),
+            RuntimeError::OutOfMemory => "ran out of memory".to_string(),
+            RuntimeError::Unknown { stderr } => stderr.clone(),
+        };
+        println!("\n{} {}", "✗ Tests".red().bold(), message.red());
+    } else if outcome.passed {
+        println!(
+            "\n{} {}/{} tests in {}ms",
+            "✓ Passed".green().bold(),
+            verification.tests_passed,
+            verification.tests_total,
+            verification.duration_ms
+        );
+    } else {
+        println!(
+            "\n{} {}/{} tests passed",
+            "✗ Failed".red().bold(),
+            verification.tests_passed,
+            verification.tests_total
+        );
+        if !verification.stdout.is_empty() {
+            println!("\n{}", verification.stdout.trim_end().dimmed());
+        }
+    }
+
+    if !verification.flaky_tests.is_empty() {
+        println!(
+            "{} passed only when run again: {}",
+            "⚠".yellow(),
+            verification.flaky_tests.join(", ")
+        );
+    }
+    if let Some(usage) = &verification.resource_usage {
+        println!(
+            "{}",
+            format!(
+                "Used {:.0} MB, {:.1}s of CPU, wrote {:.1} MB",
+                usage.peak_memory_bytes as f64 / (1024.0 * 1024.0),
+                usage.cpu_time_ms as f64 / 1000.0,
+                usage.bytes_written as f64 / (1024.0 * 1024.0)
+            )
+            .dimmed()
+        );
+    }
+    println!("Attempt #{}", outcome.attempt_number);
+    if outcome.xp_earned > 0 {
+        let penalty = match outcome.hint_penalty_percent {
+            0 => String::new(),
+            percent => format!(" ({}% taken for hints)", percent),
+        };
+        println!("{}{}", format!("+{} XP", outcome.xp_earned).yellow().bold(), penalty);
+    }
+    let mut mastery: Vec<_> = outcome.mastery_updates.iter().collect();
+    mastery.sort_by(|a, b| a.0.cmp(b.0));
+    for (skill, score) in mastery {
+        println!("  {} mastery {:.0}%", skill, score * 100.0);
+    }
+    for event in events {
+        match event {
+            GamificationEvent::LevelUp { new_level, .. } => {
+                println!("{}", format!("★ Level up! You're now level {}", new_level).magenta().bold())
+            }
+            GamificationEvent::BadgeUnlocked { name, .. } => {
+                println!("{}", format!("★ Badge unlocked: {}", name).magenta().bold())
+            }
+            GamificationEvent::XpGranted { .. } => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_dir_is_the_desktop_apps() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let dir = challenge_template_dir(data_dir.path(), "week1-Day2", &ChallengeTemplate::default()).unwrap();
+        assert_eq!(dir, data_dir.path().join("challenges").join("week1_day2"));
+        let manifest = fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+        assert!(manifest.contains("name = \"challenge_week1_day2\""));
+    }
+}
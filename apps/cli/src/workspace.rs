@@ -0,0 +1,26 @@
+use anyhow::Result;
+use colored::*;
+use content::workspace;
+use std::path::{Path, PathBuf};
+
+use crate::verify::{self, AppContext};
+
+/// Write a challenge of the active curriculum out as a cargo project, in
+/// `dir` or a directory named after the node
+pub fn export(app: &AppContext, node_id: &str, dir: Option<PathBuf>) -> Result<()> {
+    let node = verify::challenge_node(&app.loader, node_id)?;
+    let challenge = app.loader.load_challenge(&node.content_path)?;
+    let template = app.loader.load_template(&challenge)?;
+    let dir = dir.unwrap_or_else(|| PathBuf::from(workspace::crate_name(node_id)));
+
+    workspace::export_workspace(&dir, node, &challenge, &template)?;
+    println!("{} {} to {}", "✓ Exported".green().bold(), challenge.title, dir.display());
+    println!("  Solve it in src/lib.rs, check it with `cargo test`, then run `glp submit` there");
+    Ok(())
+}
+
+/// Verify the solution in an exported challenge's `src/lib.rs`
+pub async fn submit(app: &AppContext, dir: &Path) -> Result<bool> {
+    let (info, code) = workspace::read_workspace(dir)?;
+    verify::verify(app, &info.node_id, &code, Some((dir, &info))).await
+}
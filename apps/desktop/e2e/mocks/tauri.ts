@@ -284,6 +284,16 @@ function createCommandHandlers(state: MockState) {
     // System commands
     check_docker_status: () => ({ installed: true, running: true }),
     check_api_key: () => ({ configured: true, valid: true }),
+    get_setup_state: () => ({
+      steps: [],
+      started_at: new Date().toISOString(),
+      completed_at: null,
+    }),
+    get_job_status: () => [],
+    get_notification_schedule: () => ({ prefs: { enabled: true, review_reminders: true, review_time: '18:00', streak_reminders: true, streak_time: '21:00', quiet_hours: { start: '22:00', end: '08:00' } }, upcoming: [] }),
+    get_capability_status: () => ({ docker: { available: true, detail: '24.0.7', checked_at: null }, llm: { available: true, detail: 'gpt-4', checked_at: null }, pending_work: [] }),
+    take_recovered_sessions: () => [],
+    get_focus_state: () => ({ settings: { work_minutes: 25, short_break_minutes: 5, long_break_minutes: 15, blocks_before_long_break: 4 }, block: null, remaining_seconds: 0, next_kind: 'Work', completed_work_blocks_today: 0, completed: null }),
     get_system_info: () => ({
       os: 'linux',
       arch: 'x86_64',
@@ -531,6 +541,12 @@ function serializeHandlers(handlers: ReturnType<typeof createCommandHandlers>, s
       
       check_docker_status: function() { return { installed: true, running: true }; },
       check_api_key: function() { return { configured: true, valid: true }; },
+      get_setup_state: function() { return { steps: [], started_at: new Date().toISOString(), completed_at: null }; },
+      get_job_status: function() { return []; },
+      get_notification_schedule: function() { return { prefs: { enabled: true, review_reminders: true, review_time: '18:00', streak_reminders: true, streak_time: '21:00', quiet_hours: { start: '22:00', end: '08:00' } }, upcoming: [] }; },
+      get_capability_status: function() { return { docker: { available: true, detail: '24.0.7', checked_at: null }, llm: { available: true, detail: 'gpt-4', checked_at: null }, pending_work: [] }; },
+      take_recovered_sessions: function() { return []; },
+      get_focus_state: function() { return { settings: { work_minutes: 25, short_break_minutes: 5, long_break_minutes: 15, blocks_before_long_break: 4 }, block: null, remaining_seconds: 0, next_kind: 'Work', completed_work_blocks_today: 0, completed: null }; },
       get_system_info: function() { return { os: 'linux', arch: 'x86_64', docker_available: true }; },
     };
   })()`
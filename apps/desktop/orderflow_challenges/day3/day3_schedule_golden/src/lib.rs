@@ -114,13 +114,11 @@ fn run_tick(agents: &mut [Box<dyn Agent>], ctx: &Ctx, rng: &mut Rng) -> Vec<(u32
 }
 
 fn fingerprint(actions: &[(u32, Action)]) -> String {
-    let string = String::from("");
-    for action in actions {
-        let agent_id = action.0;
-        let agent_action = action.1;
-        println!("a{:?}:{:?}", agent_id, agent_action)
-    }
-    string
+    actions
+        .iter()
+        .map(|(agent_id, action)| format!("a{}:{:?}", agent_id, action))
+        .collect::<Vec<_>>()
+        .join(",")
 }
 
 #[cfg(test)]
@@ -0,0 +1,115 @@
+use crate::state::AppState;
+use chrono::Local;
+use glp_core::analytics::{self, AnalyticsError, BATCH_SIZE};
+use glp_core::db::repos::{AnalyticsRepository, CurriculumRepository};
+use glp_core::models::{AnalyticsBatch, AnalyticsEvent, ErrorCategory};
+use glp_runner::{RuntimeError, VerificationResult};
+use std::time::Duration;
+
+const UPLOAD_TIMEOUT_SECS: u64 = 30;
+
+/// Queue an event for the user if they've opted in to analytics. Failures
+/// are logged rather than failing the command the event came from.
+pub async fn record(state: &AppState, user_id: String, curriculum_id: String, event: AnalyticsEvent) {
+    let recorded = state
+        .run_db(move |conn| {
+            let Some(curriculum) = CurriculumRepository::get(conn, &curriculum_id)? else {
+                return Ok(Ok(false));
+            };
+            let today = Local::now().date_naive();
+            Ok(analytics::record(conn, &user_id, (&curriculum.name, &curriculum.version), event, today))
+        })
+        .await;
+    match recorded {
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => tracing::warn!(error = %e, "Analytics event not recorded"),
+        Err(e) => tracing::warn!(error = %e, "Failed to record analytics event"),
+    }
+}
+
+/// Why a verified submission didn't pass, as analytics report it
+pub fn error_category(verification: &VerificationResult) -> Option<ErrorCategory> {
+    if verification.success {
+        return None;
+    }
+    Some(if verification.policy_violation.is_some() {
+        ErrorCategory::PolicyViolation
+    } else if verification.compile_error.is_some() {
+        ErrorCategory::CompileError
+    } else if verification.resource_limit_hit.is_some() {
+        ErrorCategory::ResourceLimit
+    } else {
+        match &verification.runtime_error {
+            Some(RuntimeError::Timeout) => ErrorCategory::Timeout,
+            Some(RuntimeError::Panic { .. }) => ErrorCategory::Panic,
+            Some(RuntimeError::OutOfMemory) => ErrorCategory::OutOfMemory,
+            Some(RuntimeError::Unknown { .. }) => ErrorCategory::RuntimeError,
+            None => ErrorCategory::TestFailure,
+        }
+    })
+}
+
+/// Upload queued events for every profile that has opted in and set an
+/// endpoint; run by the scheduler. A batch is only dropped once the endpoint
+/// accepts it, so a failed upload is retried next time.
+pub async fn upload_queued(state: &AppState) -> Result<String, String> {
+    let users = state.run_db(AnalyticsRepository::get_users_with_events).await?;
+    let mut uploaded = 0;
+    for user_id in users {
+        let uid = user_id.clone();
+        let settings = state.run_db(move |conn| Ok(analytics::load_settings(conn, &uid))).await?;
+        let settings = match settings {
+            Ok(settings) => settings,
+            Err(e) => {
+                tracing::warn!(error = %e, "Ignoring unreadable analytics settings");
+                continue;
+            }
+        };
+        let Some(endpoint) = settings.endpoint.filter(|_| settings.enabled) else {
+            continue;
+        };
+
+        loop {
+            let uid = user_id.clone();
+            let (ids, batch) = state
+                .run_db(move |conn| Ok(analytics::next_batch(conn, &uid, Some(BATCH_SIZE))))
+                .await?
+                .map_err(|e: AnalyticsError| e.to_string())?;
+            if ids.is_empty() {
+                break;
+            }
+            if let Err(e) = upload(&endpoint, &batch).await {
+                tracing::warn!(error = %e, "Analytics upload failed");
+                break;
+            }
+            let count = ids.len();
+            state
+                .run_db(move |conn| Ok(analytics::mark_uploaded(conn, &ids)))
+                .await?
+                .map_err(|e| e.to_string())?;
+            uploaded += count;
+            if count < BATCH_SIZE {
+                break;
+            }
+        }
+    }
+    Ok(format!("Uploaded {} analytics events", uploaded))
+}
+
+async fn upload(endpoint: &str, batch: &AnalyticsBatch) -> Result<(), String> {
+    let response = reqwest::Client::builder()
+        .timeout(Duration::from_secs(UPLOAD_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| e.to_string())?
+        .post(endpoint)
+        .header("Content-Type", "application/json")
+        .body(serde_json::to_string(batch).map_err(|e| e.to_string())?)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Endpoint returned HTTP {}", response.status()))
+    }
+}
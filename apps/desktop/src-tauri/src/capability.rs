@@ -0,0 +1,180 @@
+use crate::commands::{checkpoint, system};
+use crate::state::AppState;
+use chrono::{DateTime, Duration, Utc};
+use glp_core::db::repos::PendingWorkRepository;
+use glp_core::models::{Capability, PendingWork, PendingWorkKind};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// How long a capability check is trusted before probing again
+const CHECK_TTL_SECS: i64 = 60;
+
+/// Whether a capability was reachable when last checked
+#[derive(Debug, Clone, Default, Serialize, specta::Type)]
+pub struct CapabilityState {
+    pub available: bool,
+    /// Version, model or the reason it's unavailable
+    pub detail: Option<String>,
+    /// Never checked if missing
+    pub checked_at: Option<DateTime<Utc>>,
+}
+
+impl CapabilityState {
+    fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        self.checked_at
+            .map(|checked_at| now - checked_at < Duration::seconds(CHECK_TTL_SECS))
+            .unwrap_or(false)
+    }
+}
+
+/// Caches whether Docker and the grading provider are reachable, so
+/// commands can degrade without each one probing them
+#[derive(Default)]
+pub struct CapabilityService {
+    states: Mutex<HashMap<Capability, CapabilityState>>,
+}
+
+impl CapabilityService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The last known state, without probing
+    pub fn cached(&self, capability: Capability) -> CapabilityState {
+        self.states
+            .lock()
+            .ok()
+            .and_then(|states| states.get(&capability).cloned())
+            .unwrap_or_default()
+    }
+
+    /// The cached state, probing first if it's stale
+    pub async fn check(&self, capability: Capability) -> CapabilityState {
+        let cached = self.cached(capability);
+        if cached.is_fresh(Utc::now()) {
+            return cached;
+        }
+        self.refresh(capability).await
+    }
+
+    pub async fn refresh(&self, capability: Capability) -> CapabilityState {
+        let state = probe(capability).await;
+        let previous = self.store(capability, state.clone());
+        if previous.checked_at.is_some() && previous.available != state.available {
+            tracing::info!(
+                capability = capability.as_str(),
+                available = state.available,
+                detail = state.detail.as_deref().unwrap_or_default(),
+                "Capability changed"
+            );
+        }
+        state
+    }
+
+    /// Record that a call failed for lack of the capability, so work isn't
+    /// retried against it until the next check
+    pub fn mark_unavailable(&self, capability: Capability, detail: String) {
+        tracing::warn!(capability = capability.as_str(), detail = %detail, "Capability unavailable");
+        self.store(
+            capability,
+            CapabilityState {
+                available: false,
+                detail: Some(detail),
+                checked_at: Some(Utc::now()),
+            },
+        );
+    }
+
+    fn store(&self, capability: Capability, state: CapabilityState) -> CapabilityState {
+        self.states
+            .lock()
+            .ok()
+            .and_then(|mut states| states.insert(capability, state))
+            .unwrap_or_default()
+    }
+}
+
+async fn probe(capability: Capability) -> CapabilityState {
+    let (available, detail) = match capability {
+        Capability::Docker => {
+            let docker = tauri::async_runtime::spawn_blocking(system::check_docker_internal).await;
+            match docker {
+                Ok(docker) if docker.running => (true, docker.version),
+                Ok(docker) if docker.installed => (false, Some("Docker is not running".to_string())),
+                Ok(_) => (false, Some("Docker is not installed".to_string())),
+                Err(e) => (false, Some(e.to_string())),
+            }
+        }
+        Capability::Llm => match system::grading_credentials() {
+            None => (false, Some("No grading provider is configured".to_string())),
+            Some(credentials) => match glp_grader::check_credentials(&credentials).await {
+                Ok(check) => (true, Some(check.model)),
+                Err(e) => (false, Some(e.to_string())),
+            },
+        },
+    };
+
+    CapabilityState {
+        available,
+        detail,
+        checked_at: Some(Utc::now()),
+    }
+}
+
+/// Run queued work whose capability is available again. Work that fails is
+/// retried on later runs until it runs out of attempts.
+pub async fn run_pending_work(state: &AppState) -> Result<String, String> {
+    let mut completed = 0;
+    let mut failed = 0;
+    let mut waiting = 0;
+
+    for capability in [Capability::Docker, Capability::Llm] {
+        let queued = state
+            .run_db(move |conn| PendingWorkRepository::get_runnable(conn, capability))
+            .await?;
+        if queued.is_empty() {
+            continue;
+        }
+        if !state.capabilities.check(capability).await.available {
+            waiting += queued.len();
+            continue;
+        }
+
+        let mut remaining = queued.into_iter();
+        for mut work in remaining.by_ref() {
+            let outcome = run_work(state, &work).await;
+            let unreachable = matches!(outcome, Err(checkpoint::GradingFailure::Unreachable(_)));
+            match outcome {
+                Ok(()) => {
+                    work.complete(Utc::now());
+                    completed += 1;
+                }
+                Err(failure) => {
+                    if let checkpoint::GradingFailure::Unreachable(detail) = &failure {
+                        state.capabilities.mark_unavailable(capability, detail.clone());
+                    }
+                    tracing::warn!(work = %work.id, kind = work.kind.as_str(), error = %failure, "Pending work failed");
+                    work.record_failure(failure.to_string(), Utc::now());
+                    failed += 1;
+                }
+            }
+            state
+                .run_db(move |conn| PendingWorkRepository::update(conn, &work))
+                .await?;
+
+            if unreachable {
+                break;
+            }
+        }
+        waiting += remaining.count();
+    }
+
+    Ok(format!("Ran {} queued items, {} failed, {} waiting", completed + failed, failed, waiting))
+}
+
+async fn run_work(state: &AppState, work: &PendingWork) -> Result<(), checkpoint::GradingFailure> {
+    match work.kind {
+        PendingWorkKind::GradeArtifact => checkpoint::grade_queued(state, &work.payload).await.map(|_| ()),
+    }
+}
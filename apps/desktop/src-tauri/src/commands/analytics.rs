@@ -0,0 +1,83 @@
+use crate::dto::analytics::AnalyticsStatus;
+use crate::error::{CommandError, CommandResult};
+use crate::state::AppState;
+use glp_core::analytics;
+use glp_core::db::repos::AnalyticsRepository;
+use glp_core::models::{AnalyticsBatch, AnalyticsSettings};
+use tauri::State;
+
+/// Whether the current user shares analytics, and how much is waiting to go
+#[tauri::command]
+#[specta::specta]
+pub async fn get_analytics_settings(state: State<'_, AppState>) -> CommandResult<AnalyticsStatus> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    let (settings, queued) = state
+        .run_db(move |conn| {
+            let queued = AnalyticsRepository::count(conn, &user_id)?;
+            Ok(analytics::load_settings(conn, &user_id).map(|settings| (settings, queued)))
+        })
+        .await??;
+    Ok(AnalyticsStatus { settings, queued_events: queued as u32 })
+}
+
+/// Opt in to or out of analytics, or change where they're uploaded. Opting
+/// out drops anything not yet uploaded.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_analytics_settings(
+    state: State<'_, AppState>,
+    settings: AnalyticsSettings,
+) -> CommandResult<AnalyticsStatus> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    let (saved, queued) = state
+        .run_db(move |conn| {
+            let saved = match analytics::save_settings(conn, &user_id, &settings) {
+                Ok(saved) => saved,
+                Err(e) => return Ok(Err(e)),
+            };
+            let queued = AnalyticsRepository::count(conn, &user_id)?;
+            Ok(Ok((saved, queued)))
+        })
+        .await??;
+    tracing::info!(enabled = saved.enabled, "Analytics settings changed");
+    Ok(AnalyticsStatus { settings: saved, queued_events: queued as u32 })
+}
+
+/// Everything queued for upload, exactly as the endpoint will receive it
+#[tauri::command]
+#[specta::specta]
+pub async fn preview_analytics(state: State<'_, AppState>) -> CommandResult<AnalyticsBatch> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    Ok(state.run_db(move |conn| Ok(analytics::preview(conn, &user_id))).await??)
+}
+
+/// Drop all of the current user's queued analytics and start uploads afresh
+/// under a new anonymous id. Returns how many events were dropped.
+#[tauri::command]
+#[specta::specta]
+pub async fn purge_analytics(state: State<'_, AppState>) -> CommandResult<u32> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    let purged = state.run_db(move |conn| Ok(analytics::purge(conn, &user_id))).await??;
+    tracing::info!(purged, "Analytics purged");
+    Ok(purged as u32)
+}
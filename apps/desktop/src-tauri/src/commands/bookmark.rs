@@ -0,0 +1,71 @@
+use crate::dto::bookmark::BookmarkData;
+use crate::error::{CommandError, CommandResult};
+use crate::state::AppState;
+use glp_core::db::repos::BookmarkRepository;
+use tauri::State;
+
+/// Save a node for later, or remove it if it's already saved. Returns
+/// whether the node is bookmarked afterwards.
+#[tauri::command]
+#[specta::specta]
+pub async fn toggle_bookmark(state: State<'_, AppState>, node_id: String) -> CommandResult<bool> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    state
+        .run_db(move |conn| BookmarkRepository::toggle(conn, &user_id, Some(&curriculum_id), &node_id))
+        .await
+}
+
+/// The reading queue, front first
+#[tauri::command]
+#[specta::specta]
+pub async fn get_bookmarks(state: State<'_, AppState>) -> CommandResult<Vec<BookmarkData>> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    let bookmarks = state
+        .run_db(move |conn| BookmarkRepository::get_all(conn, &user_id, Some(&curriculum_id)))
+        .await?;
+
+    let loader = state.content_loader.lock()?;
+    Ok(bookmarks
+        .into_iter()
+        .map(|bookmark| {
+            let node = loader.as_ref().and_then(|l| l.get_node_by_id(&bookmark.node_id));
+            BookmarkData {
+                title: node.map(|n| n.title.clone()),
+                node_type: node.map(|n| n.node_type.clone()),
+                estimated_minutes: node.map(|n| n.estimated_minutes),
+                created_at: bookmark.created_at.to_rfc3339(),
+                position: bookmark.position,
+                node_id: bookmark.node_id,
+            }
+        })
+        .collect())
+}
+
+/// Reorder the reading queue. Bookmarks left out keep their order after
+/// the listed ones.
+#[tauri::command]
+#[specta::specta]
+pub async fn reorder_bookmarks(state: State<'_, AppState>, node_ids: Vec<String>) -> CommandResult<()> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    state
+        .run_db(move |conn| BookmarkRepository::reorder(conn, &user_id, Some(&curriculum_id), &node_ids))
+        .await
+}
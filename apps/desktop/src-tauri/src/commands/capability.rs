@@ -0,0 +1,34 @@
+use crate::dto::capability::CapabilityStatus;
+use crate::error::CommandResult;
+use crate::state::AppState;
+use glp_core::db::repos::PendingWorkRepository;
+use glp_core::models::Capability;
+use tauri::State;
+
+/// Whether Docker and the grading provider are reachable, from the cache
+/// unless it's stale or `refresh` is set
+#[tauri::command]
+#[specta::specta]
+pub async fn get_capability_status(
+    state: State<'_, AppState>,
+    refresh: Option<bool>,
+) -> CommandResult<CapabilityStatus> {
+    let (docker, llm) = if refresh.unwrap_or(false) {
+        (
+            state.capabilities.refresh(Capability::Docker).await,
+            state.capabilities.refresh(Capability::Llm).await,
+        )
+    } else {
+        (
+            state.capabilities.check(Capability::Docker).await,
+            state.capabilities.check(Capability::Llm).await,
+        )
+    };
+
+    let user_id = state.get_current_user_id();
+    let pending_work = state
+        .run_db(move |conn| PendingWorkRepository::get_unfinished_for_user(conn, &user_id))
+        .await?;
+
+    Ok(CapabilityStatus { docker, llm, pending_work })
+}
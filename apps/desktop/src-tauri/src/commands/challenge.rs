@@ -0,0 +1,478 @@
+use crate::analytics;
+use crate::commands::{checkpoint, events, system};
+use crate::dto::challenge::{ChallengeAttemptSummary, ChallengeResult, SolutionFeedback};
+use crate::error::{CommandError, CommandResult, ErrorCode};
+use crate::notifications::emit_gamification_events;
+use crate::state::AppState;
+use content::workspace::{self, WorkspaceInfo};
+use content::{ChallengeTemplate, TrackStep};
+use glp_core::db::repos::ChallengeRepository;
+use glp_core::gamification::{Difficulty, XpActivity};
+use glp_core::models::{AnalyticsEvent, AttemptFlag, Capability, ChallengeAttempt, ChallengeDraft};
+use glp_core::services::{ChallengeRun, ChallengeService, ChallengeSpec};
+use glp_grader::types::GraderConfig;
+use glp_grader::{CodeReview, GraderError, LLMGrader};
+use glp_runner::{
+    check_crates, compare_with_solution, matches_solution, CheckResult, DockerRunner, RunnerError, VerificationResult,
+};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+/// What the submission needs from the curriculum
+pub(crate) struct ChallengeNode {
+    challenge_id: String,
+    difficulty: Difficulty,
+    skills: Vec<String>,
+    estimated_minutes: u32,
+    pub(crate) test_code: String,
+    solution: Option<String>,
+    pub(crate) hints: Vec<String>,
+    /// Set for a day of a project track, which is verified against the
+    /// learner's repo instead of editor code
+    pub(crate) track: Option<TrackStep>,
+    pub(crate) template: ChallengeTemplate,
+    /// Crates an exported workspace's `Cargo.toml` may add
+    dependencies_allowlist: Option<Vec<String>>,
+}
+
+pub(crate) fn load_challenge_node(state: &AppState, node_id: &str) -> CommandResult<ChallengeNode> {
+    let loader = state.content_loader.lock()?;
+    let loader = loader
+        .as_ref()
+        .ok_or_else(|| CommandError::new(ErrorCode::CurriculumNotLoaded, "Content not loaded"))?;
+
+    let node = loader
+        .get_node_by_id(node_id)
+        .ok_or_else(|| CommandError::not_found(format!("Node not found: {}", node_id)))?;
+    if XpActivity::from_node_type(&node.node_type) != Some(XpActivity::Challenge) {
+        return Err(CommandError::validation(format!("{} is not a challenge", node_id)));
+    }
+    let challenge = loader.load_challenge(&node.content_path)?;
+    let template = loader.load_template(&challenge)?;
+
+    let mut skills = node.skills.clone();
+    for skill in challenge.skills {
+        if !skills.contains(&skill) {
+            skills.push(skill);
+        }
+    }
+
+    Ok(ChallengeNode {
+        challenge_id: challenge.id,
+        difficulty: node.difficulty.parse().unwrap_or(Difficulty::Easy),
+        skills,
+        estimated_minutes: node.estimated_minutes,
+        test_code: challenge.test_code,
+        solution: challenge.solution,
+        hints: challenge.hints,
+        track: node.track.clone(),
+        template,
+        dependencies_allowlist: challenge.dependencies_allowlist,
+    })
+}
+
+/// Crate the submission is tested in, built from `template`. The runner
+/// copies it and writes the code under test to `src/lib.rs`.
+pub(crate) fn challenge_template_dir(
+    app_data_dir: &Path,
+    node_id: &str,
+    template: &ChallengeTemplate,
+) -> CommandResult<PathBuf> {
+    let crate_name = workspace::crate_name(node_id);
+    let dir = app_data_dir.join("challenges").join(&crate_name);
+    template.write_crate(&dir, &format!("challenge_{}", crate_name))?;
+    Ok(dir)
+}
+
+/// Connect to Docker, marking it unavailable if it can't be reached
+pub(crate) async fn connect_runner(state: &AppState) -> CommandResult<DockerRunner> {
+    match DockerRunner::new().await {
+        Ok(runner) => Ok(runner),
+        Err(e) => {
+            if matches!(e, RunnerError::DockerNotAvailable) {
+                state.capabilities.mark_unavailable(Capability::Docker, e.to_string());
+            }
+            Err(e.into())
+        }
+    }
+}
+
+/// Run a mini-challenge's tests against the learner's code in the sandbox,
+/// then record the attempt. The first passing attempt completes the node
+/// and awards XP; every attempt whose tests ran updates mastery for the
+/// node's skills. Hints revealed before the first pass cost part of its XP.
+#[tauri::command]
+#[specta::specta]
+pub async fn submit_challenge(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    node_id: String,
+    code: String,
+) -> CommandResult<ChallengeResult> {
+    verify_submission(&app, &state, node_id, code, None).await
+}
+
+/// Compile the learner's code with `cargo check`, without running tests or
+/// recording an attempt, for the editor's "Check" button. Runs under
+/// shorter limits than a submission, so it comes back quickly.
+#[tauri::command]
+#[specta::specta]
+pub async fn check_challenge(
+    state: State<'_, AppState>,
+    node_id: String,
+    code: String,
+) -> CommandResult<CheckResult> {
+    let challenge = load_challenge_node(&state, &node_id)?;
+    if challenge.track.is_some() {
+        return Err(CommandError::validation(format!(
+            "{} is a project track day; check it in the track's repo",
+            node_id
+        )));
+    }
+    check_crates(&code, &challenge.template.crate_names())?;
+    let template_dir = challenge_template_dir(state.app_data_dir(), &node_id, &challenge.template)?;
+
+    let mut runner = connect_runner(&state).await?;
+    if let Some(image) = &challenge.template.image {
+        runner = runner.with_image(image);
+    }
+    let source = format!("{}\n\n{}", code, challenge.test_code);
+    Ok(runner.run_check_only(&template_dir, &source).await?)
+}
+
+/// Verify and record a submission. `workspace` is the exported workspace
+/// the code came from, if any: its manifest is only used if the challenge
+/// lets learners add dependencies, and edits to its other files are
+/// flagged for review.
+async fn verify_submission(
+    app: &AppHandle,
+    state: &AppState,
+    node_id: String,
+    code: String,
+    workspace: Option<(&Path, &WorkspaceInfo)>,
+) -> CommandResult<ChallengeResult> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    let challenge = load_challenge_node(state, &node_id)?;
+    if challenge.track.is_some() {
+        return Err(CommandError::validation(format!(
+            "{} is a project track day; submit it from the track's repo",
+            node_id
+        )));
+    }
+    let mut flags = Vec::new();
+    let mut cargo_toml = None;
+    if let Some((dir, info)) = workspace {
+        cargo_toml = Some(workspace::read_cargo_toml(dir)?);
+        let editable: &[&str] = if challenge.dependencies_allowlist.is_some() { &["Cargo.toml"] } else { &[] };
+        let files = workspace::edited_outside(dir, info, editable)?;
+        if !files.is_empty() {
+            flags.push(AttemptFlag::EditedOutsideAllowed { files });
+        }
+    }
+    let allowlist = challenge.dependencies_allowlist.as_ref().filter(|_| cargo_toml.is_some());
+    let mut allowed = challenge.template.crate_names();
+    allowed.extend(allowlist.into_iter().flatten().map(|name| name.replace('-', "_")));
+    check_crates(&code, &allowed)?;
+    let template_dir = challenge_template_dir(state.app_data_dir(), &node_id, &challenge.template)?;
+
+    let mut runner = connect_runner(state).await?;
+    if let Some(image) = &challenge.template.image {
+        runner = runner.with_image(image);
+    }
+    let source = format!("{}\n\n{}", code, challenge.test_code);
+    let verification = match (allowlist, &cargo_toml) {
+        (Some(allowlist), Some(cargo_toml)) => {
+            runner
+                .run_verification_with_dependencies(&template_dir, &source, cargo_toml, allowlist)
+                .await?
+        }
+        _ => runner.run_verification(&template_dir, &source).await?,
+    };
+
+    record_submission(app, state, user_id, curriculum_id, node_id, challenge, code, verification, flags).await
+}
+
+/// Record a verified submission. The first passing attempt completes the
+/// node and awards XP; every attempt whose tests ran updates mastery. Code
+/// that is the reference solution is flagged for review, along with
+/// `flags` from the caller.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn record_submission(
+    app: &AppHandle,
+    state: &AppState,
+    user_id: String,
+    curriculum_id: String,
+    node_id: String,
+    challenge: ChallengeNode,
+    code: String,
+    verification: VerificationResult,
+    mut flags: Vec<AttemptFlag>,
+) -> CommandResult<ChallengeResult> {
+    if challenge.solution.as_deref().is_some_and(|solution| matches_solution(&code, solution)) {
+        flags.insert(0, AttemptFlag::MatchesReference);
+    }
+    let run = ChallengeRun {
+        success: verification.success,
+        tests_passed: verification.tests_passed,
+        tests_failed: verification.tests_failed,
+        tests_total: verification.tests_total,
+        stdout: verification.stdout.clone(),
+        stderr: verification.stderr.clone(),
+        verification_json: serde_json::to_string(&verification)?,
+        flags,
+    };
+    let spec = ChallengeSpec {
+        challenge_id: challenge.challenge_id,
+        difficulty: challenge.difficulty,
+        skills: challenge.skills,
+        estimated_minutes: challenge.estimated_minutes,
+    };
+    let event_multiplier = events::current_multiplier(state, XpActivity::Challenge);
+    let attempted = AnalyticsEvent::ChallengeAttempted {
+        node_id: node_id.clone(),
+        passed: verification.success,
+        error: analytics::error_category(&verification),
+    };
+
+    let (uid, cid) = (user_id.clone(), curriculum_id.clone());
+    let (outcome, notifications) = state
+        .run_db(move |conn| {
+            ChallengeService::submit(
+                conn,
+                &uid,
+                Some(&cid),
+                &node_id,
+                &spec,
+                &code,
+                &run,
+                event_multiplier,
+            )
+        })
+        .await?;
+    let result = ChallengeResult {
+        attempt_id: outcome.attempt_id,
+        passed: outcome.passed,
+        attempt_number: outcome.attempt_number,
+        xp_earned: outcome.xp_earned,
+        mastery_updates: outcome.mastery_updates,
+        hint_penalty_percent: outcome.hint_penalty_percent,
+        verification,
+    };
+
+    tracing::info!(
+        attempt = %result.attempt_id,
+        passed = result.passed,
+        xp = result.xp_earned,
+        "Challenge submitted"
+    );
+    emit_gamification_events(app, &notifications);
+    analytics::record(state, user_id, curriculum_id, attempted).await;
+    Ok(result)
+}
+
+/// Autosave the code in the editor, so closing the app doesn't lose it
+#[tauri::command]
+#[specta::specta]
+pub async fn save_challenge_draft(
+    state: State<'_, AppState>,
+    node_id: String,
+    code: String,
+) -> CommandResult<ChallengeDraft> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    let draft = ChallengeDraft::new(user_id, Some(curriculum_id), node_id, code);
+    let saved = draft.clone();
+    state.run_db(move |conn| ChallengeRepository::save_draft(conn, &saved)).await?;
+    Ok(draft)
+}
+
+/// The saved editor code for a challenge, if any
+#[tauri::command]
+#[specta::specta]
+pub async fn get_challenge_draft(state: State<'_, AppState>, node_id: String) -> CommandResult<Option<ChallengeDraft>> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    state
+        .run_db(move |conn| ChallengeRepository::get_draft(conn, &user_id, Some(&curriculum_id), &node_id))
+        .await
+}
+
+/// Previous attempts at a challenge with their test results, newest first
+#[tauri::command]
+#[specta::specta]
+pub async fn list_challenge_attempts(
+    state: State<'_, AppState>,
+    node_id: String,
+) -> CommandResult<Vec<ChallengeAttemptSummary>> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    let attempts = state
+        .run_db(move |conn| ChallengeRepository::get_for_node(conn, &user_id, Some(&curriculum_id), &node_id))
+        .await?;
+
+    Ok(attempts
+        .into_iter()
+        .map(|attempt| ChallengeAttemptSummary {
+            verification: attempt
+                .verification_json
+                .as_deref()
+                .and_then(|json| serde_json::from_str(json).ok()),
+            attempt,
+        })
+        .collect())
+}
+
+/// Put an earlier attempt's code back in the editor, replacing the draft
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_challenge_attempt(
+    state: State<'_, AppState>,
+    attempt_id: String,
+) -> CommandResult<ChallengeDraft> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    let attempt = state
+        .run_db(move |conn| ChallengeRepository::get(conn, &attempt_id))
+        .await?
+        .filter(|attempt| attempt.user_id == user_id)
+        .ok_or_else(|| CommandError::not_found("Attempt not found"))?;
+    let draft = ChallengeDraft::restored(&attempt)
+        .ok_or_else(|| CommandError::new(ErrorCode::InvalidState, "This attempt was recorded before code was saved"))?;
+
+    let saved = draft.clone();
+    state.run_db(move |conn| ChallengeRepository::save_draft(conn, &saved)).await?;
+    Ok(draft)
+}
+
+/// The learner's newest passing attempt with its code kept
+async fn latest_passing_attempt(
+    state: &AppState,
+    user_id: String,
+    curriculum_id: String,
+    node_id: String,
+) -> CommandResult<ChallengeAttempt> {
+    // Attempts are newest first
+    state
+        .run_db(move |conn| ChallengeRepository::get_for_node(conn, &user_id, Some(&curriculum_id), &node_id))
+        .await?
+        .into_iter()
+        .find(|attempt| attempt.passed() && attempt.code.is_some())
+        .ok_or_else(|| CommandError::new(ErrorCode::InvalidState, "Pass the challenge first"))
+}
+
+/// Compare the learner's latest passing code with the reference solution,
+/// for the recap after a challenge is solved
+#[tauri::command]
+#[specta::specta]
+pub async fn get_solution_feedback(state: State<'_, AppState>, node_id: String) -> CommandResult<SolutionFeedback> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    let solution = load_challenge_node(&state, &node_id)?
+        .solution
+        .ok_or_else(|| CommandError::new(ErrorCode::ContentNotFound, "This challenge has no reference solution"))?;
+
+    let attempt = latest_passing_attempt(&state, user_id, curriculum_id, node_id).await?;
+    let code = attempt.code.as_deref().unwrap_or_default();
+
+    Ok(SolutionFeedback {
+        diff: compare_with_solution(code, &solution)?,
+        attempt_id: attempt.id,
+        solution,
+    })
+}
+
+/// Ask the grading provider to review the learner's latest passing code.
+/// The review is optional feedback and doesn't affect XP or progress.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_code_review(state: State<'_, AppState>, node_id: String) -> CommandResult<CodeReview> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    let challenge = load_challenge_node(&state, &node_id)?;
+
+    let attempt = latest_passing_attempt(&state, user_id, curriculum_id, node_id).await?;
+    let code = attempt.code.unwrap_or_default();
+    let tests_output = attempt.stdout.unwrap_or_default();
+
+    let reviewed = match system::grading_credentials() {
+        None => Err(GraderError::MissingApiKey("the grading provider".to_string())),
+        Some(credentials) => {
+            LLMGrader::with_credentials(&credentials, GraderConfig::default())
+                .review_code(&code, &tests_output, &challenge.skills)
+                .await
+        }
+    };
+    reviewed.map_err(|e| {
+        if checkpoint::is_unreachable(&e) {
+            state.capabilities.mark_unavailable(Capability::Llm, e.to_string());
+        }
+        e.into()
+    })
+}
+
+/// Write a challenge out as a cargo project in `dir`, to solve in an
+/// external editor and submit with `submit_challenge_workspace`
+#[tauri::command]
+#[specta::specta]
+pub async fn export_challenge_workspace(state: State<'_, AppState>, node_id: String, dir: String) -> CommandResult<()> {
+    let loader = state.content_loader.lock()?;
+    let loader = loader
+        .as_ref()
+        .ok_or_else(|| CommandError::new(ErrorCode::CurriculumNotLoaded, "Content not loaded"))?;
+
+    let node = loader
+        .get_node_by_id(&node_id)
+        .ok_or_else(|| CommandError::not_found(format!("Node not found: {}", node_id)))?;
+    if XpActivity::from_node_type(&node.node_type) != Some(XpActivity::Challenge) || node.track.is_some() {
+        return Err(CommandError::validation(format!("{} can't be exported", node_id)));
+    }
+    let challenge = loader.load_challenge(&node.content_path)?;
+    let template = loader.load_template(&challenge)?;
+    workspace::export_workspace(Path::new(&dir), node, &challenge, &template)?;
+    Ok(())
+}
+
+/// Submit the solution in an exported challenge's `src/lib.rs`
+#[tauri::command]
+#[specta::specta]
+pub async fn submit_challenge_workspace(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    dir: String,
+) -> CommandResult<ChallengeResult> {
+    let dir = Path::new(&dir);
+    let (info, code) = workspace::read_workspace(dir)?;
+    verify_submission(&app, &state, info.node_id.clone(), code, Some((dir, &info))).await
+}
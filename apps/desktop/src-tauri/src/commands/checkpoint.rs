@@ -0,0 +1,824 @@
+use crate::commands::challenge::connect_runner;
+use crate::commands::{events, system};
+use crate::dto::checkpoint::{ArtifactResult, CheckpointRepoResult, CheckpointSubmissionResult, GithubSubmissionResult};
+use crate::error::{CommandError, CommandResult, ErrorCode};
+use crate::hooks;
+use crate::notifications::emit_gamification_events;
+use crate::state::AppState;
+use glp_core::db::error::DbError;
+use chrono::{Duration, Utc};
+use glp_core::db::repos::{
+    ArtifactRepository, BestAttemptRepository, PendingWorkRepository, ProgressRepository, RepoSubmissionRepository,
+    UserRepository,
+};
+use glp_core::gamification::{
+    Difficulty, XpActivity, XpCalculator, XpSource, SELF_ASSESSED_XP_MULTIPLIER,
+};
+use glp_core::models::{
+    ArtifactSubmission, ArtifactType, Capability, HookEvent, NodeProgress, NodeStatus, PendingWork, PendingWorkKind,
+    RepoSubmission,
+};
+use glp_core::quests::QuestActivity;
+use glp_core::services::GamificationService;
+use glp_core::stats::{checkpoint_readiness, CheckpointReadiness};
+use glp_grader::git::{self, RepoSnapshot, DEFAULT_DIFF_COMMITS, DEFAULT_DIFF_LIMIT};
+use glp_grader::github::{self, GithubRepo};
+use glp_grader::rubrics::BuiltInRubrics;
+use glp_grader::types::GraderConfig;
+use glp_grader::{self_assess, GradeCache, GradeResult, GraderError, LLMGrader, Rubric, SelfScore};
+use glp_runner::VerificationResult;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+/// Grades cached by artifact content, so resubmitting an unchanged artifact
+/// doesn't call the grading provider again. Kept apart from the app database,
+/// whose `grade_cache` table has a different shape.
+pub(crate) const GRADE_CACHE_FILE: &str = "grade_cache.db";
+
+/// What a queued grading job needs; the artifact itself is only stored here
+#[derive(Debug, Serialize, Deserialize)]
+struct GradeArtifactPayload {
+    submission_id: String,
+    curriculum_id: Option<String>,
+    content: String,
+}
+
+/// A checkpoint's repo must have at least a Cargo project when the
+/// curriculum doesn't give a template
+const DEFAULT_TEMPLATE: &[&str] = &["Cargo.toml"];
+
+/// GitHub submissions accepted per user per hour. Each one clones a repo,
+/// runs its tests and may call the grading provider.
+const GITHUB_SUBMISSIONS_PER_HOUR: u32 = 5;
+
+/// Integration test target a checkpoint's tests are added to a repo as
+const CHECKPOINT_TEST_TARGET: &str = "checkpoint";
+
+/// What grading an artifact needs from the curriculum
+struct CheckpointArtifact {
+    title: String,
+    difficulty: Difficulty,
+    artifacts: Vec<ArtifactType>,
+    rubric: Rubric,
+}
+
+/// Why a submission wasn't graded
+#[derive(Debug)]
+pub(crate) enum GradingFailure {
+    /// The grading provider couldn't be reached or isn't set up
+    Unreachable(String),
+    Failed(String),
+}
+
+impl fmt::Display for GradingFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GradingFailure::Unreachable(detail) | GradingFailure::Failed(detail) => f.write_str(detail),
+        }
+    }
+}
+
+/// The grading provider couldn't be reached or isn't set up, as opposed to
+/// the artifact or rubric being at fault
+pub(crate) fn is_unreachable(e: &GraderError) -> bool {
+    matches!(
+        e,
+        GraderError::ApiError(_)
+            | GraderError::MissingApiKey(_)
+            | GraderError::InvalidApiKey(_)
+            | GraderError::RateLimit(_)
+            | GraderError::Timeout(_)
+    )
+}
+
+impl From<GraderError> for GradingFailure {
+    fn from(e: GraderError) -> Self {
+        if is_unreachable(&e) {
+            GradingFailure::Unreachable(e.to_string())
+        } else {
+            GradingFailure::Failed(e.to_string())
+        }
+    }
+}
+
+/// Manifest artifact names may be given as the type (`DESIGN`) or the file
+/// (`DESIGN.md`)
+fn parse_artifact_name(name: &str) -> Option<ArtifactType> {
+    let name = name.trim();
+    let name = name.strip_suffix(".md").or_else(|| name.strip_suffix(".MD")).unwrap_or(name);
+    ArtifactType::from_str(&name.to_uppercase()).ok()
+}
+
+fn load_checkpoint_artifact(
+    state: &AppState,
+    checkpoint_id: &str,
+    artifact_type: &ArtifactType,
+) -> CommandResult<CheckpointArtifact> {
+    let loader = state.content_loader.lock()?;
+    let loader = loader
+        .as_ref()
+        .ok_or_else(|| CommandError::new(ErrorCode::CurriculumNotLoaded, "Content not loaded"))?;
+
+    let checkpoint = loader
+        .get_manifest()
+        .checkpoints
+        .iter()
+        .find(|c| c.id == checkpoint_id)
+        .ok_or_else(|| CommandError::not_found(format!("Checkpoint not found: {}", checkpoint_id)))?;
+
+    let artifacts: Vec<ArtifactType> = checkpoint.artifacts.iter().filter_map(|a| parse_artifact_name(a)).collect();
+    if !artifacts.contains(artifact_type) {
+        return Err(CommandError::validation(format!(
+            "{} doesn't ask for a {} artifact",
+            checkpoint.title,
+            artifact_type.as_str()
+        )));
+    }
+
+    // The curriculum's own rubric wins; the built-in ones cover curricula that don't ship any
+    let rubric_path = checkpoint
+        .rubrics
+        .iter()
+        .find(|(name, _)| parse_artifact_name(name).as_ref() == Some(artifact_type))
+        .map(|(_, path)| path);
+    let rubric = match rubric_path {
+        Some(path) => Rubric::from_json(&loader.load_checkpoint_file(path)?)?,
+        None => BuiltInRubrics::get(artifact_type.as_str())
+            .ok_or_else(|| GraderError::RubricNotFound(artifact_type.as_str().to_string()))?,
+    };
+
+    Ok(CheckpointArtifact {
+        title: checkpoint.title.clone(),
+        difficulty: checkpoint.difficulty.parse().unwrap_or(Difficulty::Medium),
+        artifacts,
+        rubric,
+    })
+}
+
+/// Grade one of a checkpoint's artifacts against the curriculum's rubric.
+/// The first passing grade for each artifact awards XP scaled by the grade,
+/// with the checkpoint's XP split across its artifacts; once every artifact
+/// has passed, the checkpoint is complete.
+#[tauri::command]
+#[specta::specta]
+pub async fn submit_artifact(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    node_id: String,
+    artifact_type: String,
+    content: String,
+) -> CommandResult<ArtifactResult> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    let artifact_type = ArtifactType::from_str(&artifact_type).map_err(CommandError::validation)?;
+    let checkpoint = load_checkpoint_artifact(&state, &node_id, &artifact_type)?;
+
+    let grade = grade_with_cache(&state, checkpoint.rubric.clone(), content.clone()).await?;
+    record_artifact_grade(&app, &state, user_id, curriculum_id, node_id, artifact_type, content, None, checkpoint, grade)
+        .await
+}
+
+/// Grade a checkpoint's README and DESIGN artifacts as committed at the
+/// HEAD of the learner's repo, with the latest commits' diff as context.
+/// The repo must have the files the checkpoint's template lists, and each
+/// grade records the commit it was given for.
+#[tauri::command]
+#[specta::specta]
+pub async fn submit_checkpoint_repo(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    node_id: String,
+    repo_path: String,
+) -> CommandResult<CheckpointRepoResult> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    let checkpoint = load_repo_checkpoint(&state, &node_id)?;
+
+    let repo = Path::new(&repo_path);
+    check_template(repo, &checkpoint.template)?;
+    let snapshot = RepoSnapshot::read(repo, DEFAULT_DIFF_COMMITS, DEFAULT_DIFF_LIMIT)?;
+
+    let (graded, not_found) =
+        grade_repo_artifacts(&app, &state, &user_id, &curriculum_id, &node_id, &checkpoint.artifacts, &snapshot).await?;
+    if graded.is_empty() {
+        return Err(CommandError::validation(format!(
+            "Commit {} has none of {}",
+            snapshot.short_hash(),
+            not_found.join(", ")
+        )));
+    }
+
+    tracing::info!(commit = %snapshot.commit_hash, graded = graded.len(), "Checkpoint repo graded");
+    Ok(CheckpointRepoResult {
+        commit_hash: snapshot.commit_hash,
+        graded,
+        not_found,
+    })
+}
+
+/// Submit a checkpoint as a public GitHub repo. The repo is cloned into a
+/// temporary directory, the checkpoint's tests run against it in the
+/// sandbox and, if they pass, its README and DESIGN artifacts are graded.
+/// Every submission is kept with its outcome, failed ones included, and at
+/// most `GITHUB_SUBMISSIONS_PER_HOUR` are accepted an hour.
+#[tauri::command]
+#[specta::specta]
+pub async fn submit_checkpoint_github(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    node_id: String,
+    url: String,
+) -> CommandResult<GithubSubmissionResult> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    let checkpoint = load_repo_checkpoint(&state, &node_id)?;
+    let repo = GithubRepo::parse(&url)?;
+
+    let uid = user_id.clone();
+    let since = Utc::now() - Duration::hours(1);
+    let recent = state
+        .run_db(move |conn| RepoSubmissionRepository::count_since(conn, &uid, since))
+        .await?;
+    if recent >= GITHUB_SUBMISSIONS_PER_HOUR {
+        return Err(CommandError::new(
+            ErrorCode::InvalidState,
+            format!(
+                "Only {} GitHub submissions are accepted an hour; try again later",
+                GITHUB_SUBMISSIONS_PER_HOUR
+            ),
+        ));
+    }
+
+    let mut submission =
+        RepoSubmission::new(user_id.clone(), Some(curriculum_id.clone()), node_id.clone(), repo.clone_url());
+    let outcome = run_github_submission(&app, &state, &checkpoint, &repo, &mut submission).await;
+    let (verification, graded, not_found) = match outcome {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            let failed = submission.failed(e.message.clone());
+            state.run_db(move |conn| RepoSubmissionRepository::create(conn, &failed)).await?;
+            return Err(e);
+        }
+    };
+
+    let saved = submission.clone();
+    state.run_db(move |conn| RepoSubmissionRepository::create(conn, &saved)).await?;
+    tracing::info!(
+        repo = %repo.full_name(),
+        commit = submission.commit_hash.as_deref().unwrap_or_default(),
+        passed = submission.passed,
+        "GitHub checkpoint submission checked"
+    );
+    Ok(GithubSubmissionResult {
+        submission,
+        verification,
+        graded,
+        not_found,
+    })
+}
+
+/// Clone, test and grade a GitHub submission, filling in `submission` as
+/// each step completes
+async fn run_github_submission(
+    app: &AppHandle,
+    state: &AppState,
+    checkpoint: &RepoCheckpoint,
+    repo: &GithubRepo,
+    submission: &mut RepoSubmission,
+) -> CommandResult<(Option<VerificationResult>, Vec<ArtifactResult>, Vec<String>)> {
+    github::check_repo(repo).await?;
+    let to_clone = repo.clone();
+    let clone = tauri::async_runtime::spawn_blocking(move || github::shallow_clone(&to_clone, DEFAULT_DIFF_COMMITS))
+        .await
+        .map_err(|e| CommandError::internal("Clone task failed").with_details(e.to_string()))??;
+
+    check_template(clone.path(), &checkpoint.template)?;
+    let snapshot = RepoSnapshot::read(clone.path(), DEFAULT_DIFF_COMMITS, DEFAULT_DIFF_LIMIT)?;
+    submission.commit_hash = Some(snapshot.commit_hash.clone());
+
+    let verification = match &checkpoint.tests {
+        Some(test_code) => {
+            let runner = connect_runner(state).await?;
+            let verification = runner
+                .run_project_verification(clone.path(), CHECKPOINT_TEST_TARGET, test_code)
+                .await?;
+            submission.tests_passed = Some(verification.tests_passed as i32);
+            submission.tests_total = Some(verification.tests_total as i32);
+            submission.verification_json = Some(serde_json::to_string(&verification)?);
+            Some(verification)
+        }
+        None => None,
+    };
+    // Grading is only worth a provider call once the code works
+    if verification.as_ref().is_some_and(|v| !v.success || v.tests_passed == 0) {
+        return Ok((verification, Vec::new(), Vec::new()));
+    }
+
+    let (graded, not_found) = grade_repo_artifacts(
+        app,
+        state,
+        &submission.user_id,
+        submission.curriculum_id.as_deref().unwrap_or_default(),
+        &submission.checkpoint_id,
+        &checkpoint.artifacts,
+        &snapshot,
+    )
+    .await?;
+    submission.artifact_submission_ids = graded.iter().map(|r| r.submission.id.clone()).collect();
+    submission.passed = not_found.is_empty() && graded.iter().all(|r| r.submission.passed());
+    Ok((verification, graded, not_found))
+}
+
+/// What submitting a repo for a checkpoint needs from the curriculum
+struct RepoCheckpoint {
+    /// Files the repo must have committed
+    template: Vec<String>,
+    /// The checkpoint's artifacts that are read from the repo
+    artifacts: Vec<ArtifactType>,
+    /// Test code run against the repo, for checkpoints that have tests
+    tests: Option<String>,
+}
+
+fn load_repo_checkpoint(state: &AppState, checkpoint_id: &str) -> CommandResult<RepoCheckpoint> {
+    let loader = state.content_loader.lock()?;
+    let loader = loader
+        .as_ref()
+        .ok_or_else(|| CommandError::new(ErrorCode::CurriculumNotLoaded, "Content not loaded"))?;
+    // The template and tests are the pack's content, licensed like the rest
+    loader.check_license()?;
+
+    let checkpoint = loader
+        .get_manifest()
+        .checkpoints
+        .iter()
+        .find(|c| c.id == checkpoint_id)
+        .ok_or_else(|| CommandError::not_found(format!("Checkpoint not found: {}", checkpoint_id)))?;
+
+    let artifacts: Vec<ArtifactType> = checkpoint
+        .artifacts
+        .iter()
+        .filter_map(|a| parse_artifact_name(a))
+        .filter(|a| matches!(a, ArtifactType::Readme | ArtifactType::Design))
+        .collect();
+    if artifacts.is_empty() {
+        return Err(CommandError::validation(format!(
+            "{} doesn't ask for a README or DESIGN artifact",
+            checkpoint.title
+        )));
+    }
+
+    let template = if checkpoint.template.is_empty() {
+        DEFAULT_TEMPLATE.iter().map(|path| path.to_string()).collect()
+    } else {
+        checkpoint.template.clone()
+    };
+    let tests = checkpoint.tests.as_deref().map(|path| loader.load_checkpoint_file(path)).transpose()?;
+    Ok(RepoCheckpoint { template, artifacts, tests })
+}
+
+/// Refuse a repo missing any of the template's files
+fn check_template(repo: &Path, template: &[String]) -> CommandResult<()> {
+    let missing = git::missing_template_files(repo, template)?;
+    if !missing.is_empty() {
+        return Err(CommandError::validation(format!(
+            "The repo doesn't match the project template; missing {}",
+            missing.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+/// Grade each of `artifacts` as committed in `snapshot`. Returns the grades
+/// and the file names of artifacts that aren't committed.
+async fn grade_repo_artifacts(
+    app: &AppHandle,
+    state: &AppState,
+    user_id: &str,
+    curriculum_id: &str,
+    node_id: &str,
+    artifacts: &[ArtifactType],
+    snapshot: &RepoSnapshot,
+) -> CommandResult<(Vec<ArtifactResult>, Vec<String>)> {
+    let mut graded = Vec::new();
+    let mut not_found = Vec::new();
+    for artifact_type in artifacts {
+        let Some(artifact) = snapshot.artifact(artifact_type.as_str()) else {
+            not_found.push(format!("{}.md", artifact_type.as_str()));
+            continue;
+        };
+        let content = snapshot.grading_content(artifact);
+        let checkpoint = load_checkpoint_artifact(state, node_id, artifact_type)?;
+        let grade = grade_with_cache(state, checkpoint.rubric.clone(), content.clone()).await?;
+        graded.push(
+            record_artifact_grade(
+                app,
+                state,
+                user_id.to_string(),
+                curriculum_id.to_string(),
+                node_id.to_string(),
+                artifact_type.clone(),
+                content,
+                Some(snapshot.commit_hash.clone()),
+                checkpoint,
+                grade,
+            )
+            .await?,
+        );
+    }
+    Ok((graded, not_found))
+}
+
+/// The rubric a checkpoint artifact is graded against, for scoring it by hand
+#[tauri::command]
+#[specta::specta]
+pub async fn get_checkpoint_rubric(
+    state: State<'_, AppState>,
+    node_id: String,
+    artifact_type: String,
+) -> CommandResult<Rubric> {
+    let artifact_type = ArtifactType::from_str(&artifact_type).map_err(CommandError::validation)?;
+    Ok(load_checkpoint_artifact(&state, &node_id, &artifact_type)?.rubric)
+}
+
+/// How ready the learner is for a checkpoint, so they can be warned before a
+/// graded attempt. It covers the skills of its prerequisites or, when it has
+/// none, of its week's nodes.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_checkpoint_readiness(
+    state: State<'_, AppState>,
+    checkpoint_id: String,
+) -> CommandResult<CheckpointReadiness> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    let (skills, prerequisites, satisfying) = {
+        let loader = state.content_loader.lock()?;
+        let loader = loader
+            .as_ref()
+            .ok_or_else(|| CommandError::new(ErrorCode::CurriculumNotLoaded, "Content not loaded"))?;
+        let manifest = loader.get_manifest();
+        let checkpoint = manifest
+            .checkpoints
+            .iter()
+            .find(|c| c.id == checkpoint_id)
+            .ok_or_else(|| CommandError::not_found(format!("Checkpoint not found: {}", checkpoint_id)))?;
+
+        let mut covered: Vec<&content::ContentNode> =
+            checkpoint.prerequisites.iter().filter_map(|id| loader.get_node_by_id(id)).collect();
+        if covered.is_empty() {
+            covered = manifest
+                .weeks
+                .iter()
+                .filter(|week| week.id == checkpoint.week)
+                .flat_map(|week| week.days.iter().flat_map(|day| &day.nodes))
+                .filter(|node| !node.is_deprecated())
+                .collect();
+        }
+        let mut skills: Vec<String> = Vec::new();
+        for skill in covered.iter().flat_map(|node| &node.skills) {
+            if !skills.contains(skill) {
+                skills.push(skill.clone());
+            }
+        }
+        // A superseded prerequisite is met by completing its replacement
+        let satisfying: HashMap<String, Vec<String>> = checkpoint
+            .prerequisites
+            .iter()
+            .map(|id| (id.clone(), manifest.satisfying_nodes(id)))
+            .collect();
+        (skills, checkpoint.prerequisites.clone(), satisfying)
+    };
+
+    state
+        .run_db(move |conn| {
+            checkpoint_readiness(
+                conn,
+                &user_id,
+                Some(&curriculum_id),
+                &checkpoint_id,
+                &skills,
+                &prerequisites,
+                |id| satisfying.get(id).cloned().unwrap_or_else(|| vec![id.to_string()]),
+            )
+        })
+        .await
+}
+
+/// Grade one of a checkpoint's artifacts with the learner's own scores
+/// against its rubric, for when there's no grading provider. Counts like a
+/// graded submission, but the first pass earns only
+/// `SELF_ASSESSED_XP_MULTIPLIER` of the usual XP.
+#[tauri::command]
+#[specta::specta]
+pub async fn self_assess_artifact(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    node_id: String,
+    artifact_type: String,
+    content: String,
+    scores: Vec<SelfScore>,
+    reflection: Option<String>,
+) -> CommandResult<ArtifactResult> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    let artifact_type = ArtifactType::from_str(&artifact_type).map_err(CommandError::validation)?;
+    let checkpoint = load_checkpoint_artifact(&state, &node_id, &artifact_type)?;
+
+    let grade = self_assess(&checkpoint.rubric, &scores, reflection.as_deref().unwrap_or_default())?;
+    record_artifact_grade(&app, &state, user_id, curriculum_id, node_id, artifact_type, content, None, checkpoint, grade)
+        .await
+}
+
+/// Store a graded artifact, award XP for its first pass, or what a better
+/// pass adds to it, and move the checkpoint's progress on. `commit_hash` is the commit the artifact was
+/// read from, for artifacts collected from a repo.
+#[allow(clippy::too_many_arguments)]
+async fn record_artifact_grade(
+    app: &AppHandle,
+    state: &AppState,
+    user_id: String,
+    curriculum_id: String,
+    node_id: String,
+    artifact_type: ArtifactType,
+    content: String,
+    commit_hash: Option<String>,
+    checkpoint: CheckpointArtifact,
+    grade: GradeResult,
+) -> CommandResult<ArtifactResult> {
+    let reasoning = serde_json::to_string(&grade)?;
+
+    let event_multiplier = events::current_multiplier(state, XpActivity::Checkpoint);
+    let checkpoint_passed = HookEvent::CheckpointPassed { checkpoint_id: node_id.clone(), title: checkpoint.title.clone() };
+    let hook_user_id = user_id.clone();
+
+    let (result, notifications, newly_completed) = state
+        .run_db(move |conn| {
+            let previous = ArtifactRepository::get_for_checkpoint(conn, &user_id, &node_id)?;
+            let previous_attempts = previous.iter().filter(|s| s.artifact_type == artifact_type).count();
+            let mut best = BestAttemptRepository::get_or_new(
+                conn,
+                &user_id,
+                Some(&curriculum_id),
+                &node_id,
+                artifact_type.as_str(),
+            )?;
+
+            let user = UserRepository::get_by_id(conn, &user_id)?
+                .ok_or_else(|| DbError::NotFound("User not found".to_string()))?;
+
+            let mut submission = ArtifactSubmission::new(user_id.clone(), node_id.clone(), artifact_type.clone(), &content);
+            submission.commit_hash = commit_hash;
+            submission.set_grade(grade.score as i32, reasoning, 0);
+
+            // Only a pass that beats the artifact's best grade earns XP, and
+            // only the difference, so resubmitting can't farm it
+            if submission.passed() && best.improves_grade(grade.score as f64) {
+                let award = XpCalculator::new(XpActivity::Checkpoint, checkpoint.difficulty)
+                    .with_accuracy(grade.score as f64)
+                    .with_streak(user.current_streak as u32)
+                    .with_attempt(previous_attempts + 1)
+                    .with_event_multiplier(event_multiplier)
+                    .calculate();
+                let award = if grade.self_assessed {
+                    (award as f64 * SELF_ASSESSED_XP_MULTIPLIER) as i32
+                } else {
+                    award
+                };
+                submission.xp_earned = best.xp_delta(award / checkpoint.artifacts.len().max(1) as i32);
+            }
+            ArtifactRepository::create(conn, &submission)?;
+            best.record_grade(&submission.id, grade.score as f64, submission.xp_earned);
+            BestAttemptRepository::save(conn, &best)?;
+            let xp_earned = submission.xp_earned;
+
+            let remaining_artifacts: Vec<String> = checkpoint
+                .artifacts
+                .iter()
+                .filter(|artifact| {
+                    let passed_now = **artifact == artifact_type && submission.passed();
+                    !passed_now && !previous.iter().any(|s| s.artifact_type == **artifact && s.passed())
+                })
+                .map(|artifact| artifact.as_str().to_string())
+                .collect();
+
+            let mut progress = ProgressRepository::get(conn, &user_id, Some(&curriculum_id), &node_id)?
+                .unwrap_or_else(|| {
+                    NodeProgress::new(user_id.clone(), node_id.clone()).with_curriculum(curriculum_id.clone())
+                });
+            let checkpoint_completed = remaining_artifacts.is_empty();
+            let newly_completed = checkpoint_completed && progress.status != NodeStatus::Completed;
+            if progress.status == NodeStatus::Completed {
+                // A weaker resubmission doesn't undo a pass
+                progress.attempts += 1;
+            } else if checkpoint_completed {
+                progress.attempts += 1;
+                progress.complete();
+            } else if submission.passed() {
+                progress.attempts += 1;
+                progress.start();
+            } else {
+                progress.fail();
+            }
+            ProgressRepository::create_or_update(conn, &progress)?;
+
+            GamificationService::record_activity(conn, &user_id, QuestActivity::XpEarned(xp_earned))?;
+
+            let notifications = GamificationService::award_xp(conn, &user_id, XpSource::Checkpoint, xp_earned)?.events;
+
+            Ok((
+                ArtifactResult {
+                    submission,
+                    grade,
+                    xp_earned,
+                    checkpoint_completed,
+                    remaining_artifacts,
+                },
+                notifications,
+                newly_completed,
+            ))
+        })
+        .await?;
+
+    tracing::info!(
+        submission = %result.submission.id,
+        grade = result.grade.score,
+        xp = result.xp_earned,
+        completed = result.checkpoint_completed,
+        self_assessed = result.grade.self_assessed,
+        "Artifact graded"
+    );
+    emit_gamification_events(app, &notifications);
+    if newly_completed {
+        hooks::fire(state, hook_user_id, vec![checkpoint_passed]).await;
+    }
+    Ok(result)
+}
+
+/// Grade through the content-keyed cache, marking the grading provider
+/// unavailable if it couldn't be reached
+async fn grade_with_cache(state: &AppState, rubric: Rubric, content: String) -> CommandResult<GradeResult> {
+    let cache_path = state.app_data_dir().join(GRADE_CACHE_FILE);
+    let graded = match system::grading_credentials() {
+        None => Err(GraderError::MissingApiKey("the grading provider".to_string())),
+        // The cache holds a SQLite connection, which can't be held across an
+        // await in a command, so grading runs on its own thread
+        Some(credentials) => tauri::async_runtime::spawn_blocking(move || {
+            let cache = GradeCache::new(&cache_path)?;
+            let grader = LLMGrader::with_credentials(&credentials, GraderConfig::default());
+            tauri::async_runtime::block_on(grader.grade_with_cache(&content, &rubric, &cache))
+        })
+        .await
+        .map_err(|e| CommandError::internal("Grading task failed").with_details(e.to_string()))?,
+    };
+
+    graded.map_err(|e| {
+        if is_unreachable(&e) {
+            state.capabilities.mark_unavailable(Capability::Llm, e.to_string());
+        }
+        e.into()
+    })
+}
+
+/// Submit a checkpoint artifact for grading against the built-in rubric. If
+/// the grading provider is unreachable the submission is kept and graded
+/// once it's back, rather than failing. Unlike `submit_artifact`, this
+/// awards no XP.
+#[tauri::command]
+#[specta::specta]
+pub async fn submit_checkpoint(
+    state: State<'_, AppState>,
+    checkpoint_id: String,
+    artifact_type: String,
+    content: String,
+) -> CommandResult<CheckpointSubmissionResult> {
+    let artifact_type = ArtifactType::from_str(&artifact_type).map_err(CommandError::validation)?;
+    if BuiltInRubrics::get(artifact_type.as_str()).is_none() {
+        return Err(CommandError::validation(format!("{} artifacts can't be graded yet", artifact_type.as_str())));
+    }
+
+    let user_id = state.get_current_user_id();
+    let submission = ArtifactSubmission::new(user_id.clone(), checkpoint_id, artifact_type, &content);
+    let payload = GradeArtifactPayload {
+        submission_id: submission.id.clone(),
+        curriculum_id: state.get_active_curriculum_id(),
+        content,
+    };
+    let created = submission.clone();
+    state.run_db(move |conn| ArtifactRepository::create(conn, &created)).await?;
+
+    let llm = state.capabilities.check(Capability::Llm).await;
+    let failure = if llm.available {
+        match grade(&state, &payload).await {
+            Ok(graded) => {
+                return Ok(CheckpointSubmissionResult {
+                    submission: graded,
+                    queued: false,
+                    queued_reason: None,
+                })
+            }
+            Err(failure) => failure,
+        }
+    } else {
+        GradingFailure::Unreachable(llm.detail.unwrap_or_else(|| "Grading provider unavailable".to_string()))
+    };
+
+    if let GradingFailure::Unreachable(detail) = &failure {
+        state.capabilities.mark_unavailable(Capability::Llm, detail.clone());
+    }
+    tracing::info!(submission = %submission.id, reason = %failure, "Queued checkpoint for grading");
+
+    let payload = serde_json::to_string(&payload)?;
+    let work = PendingWork::new(user_id, PendingWorkKind::GradeArtifact, payload);
+    state.run_db(move |conn| PendingWorkRepository::enqueue(conn, &work)).await?;
+
+    Ok(CheckpointSubmissionResult {
+        submission,
+        queued: true,
+        queued_reason: Some(failure.to_string()),
+    })
+}
+
+/// Grade a submission queued by `submit_checkpoint`
+pub(crate) async fn grade_queued(state: &AppState, payload: &str) -> Result<ArtifactSubmission, GradingFailure> {
+    let payload: GradeArtifactPayload =
+        serde_json::from_str(payload).map_err(|e| GradingFailure::Failed(format!("Invalid queued grading: {}", e)))?;
+    grade(state, &payload).await
+}
+
+async fn grade(state: &AppState, payload: &GradeArtifactPayload) -> Result<ArtifactSubmission, GradingFailure> {
+    let submission_id = payload.submission_id.clone();
+    let mut submission = state
+        .run_db(move |conn| {
+            ArtifactRepository::get(conn, &submission_id)?
+                .ok_or_else(|| DbError::NotFound(format!("Submission not found: {}", submission_id)))
+        })
+        .await
+        .map_err(|e| GradingFailure::Failed(e.to_string()))?;
+
+    let result = grade_content(submission.artifact_type.as_str(), &payload.content).await?;
+    let reasoning = serde_json::to_string(&result).map_err(|e| GradingFailure::Failed(e.to_string()))?;
+    submission.set_grade(result.score as i32, reasoning, 0);
+
+    let graded = submission.clone();
+    let curriculum_id = payload.curriculum_id.clone();
+    state
+        .run_db(move |conn| {
+            ArtifactRepository::update_grade(conn, &graded)?;
+
+            let mut progress = ProgressRepository::get(conn, &graded.user_id, curriculum_id.as_deref(), &graded.checkpoint_id)?
+                .unwrap_or_else(|| {
+                    let progress = NodeProgress::new(graded.user_id.clone(), graded.checkpoint_id.clone());
+                    match &curriculum_id {
+                        Some(curriculum_id) => progress.with_curriculum(curriculum_id.clone()),
+                        None => progress,
+                    }
+                });
+            if graded.passed() {
+                progress.attempts += 1;
+                progress.complete();
+            } else if progress.status == NodeStatus::Completed {
+                // A weaker resubmission doesn't undo a pass
+                progress.attempts += 1;
+            } else {
+                progress.fail();
+            }
+            ProgressRepository::create_or_update(conn, &progress)
+        })
+        .await
+        .map_err(|e| GradingFailure::Failed(e.to_string()))?;
+
+    Ok(submission)
+}
+
+async fn grade_content(artifact_type: &str, content: &str) -> Result<GradeResult, GraderError> {
+    let credentials = system::grading_credentials()
+        .ok_or_else(|| GraderError::MissingApiKey("the grading provider".to_string()))?;
+    let rubric = BuiltInRubrics::get(artifact_type).ok_or_else(|| GraderError::RubricNotFound(artifact_type.to_string()))?;
+
+    LLMGrader::with_credentials(&credentials, GraderConfig::default())
+        .grade(content, &rubric)
+        .await
+}
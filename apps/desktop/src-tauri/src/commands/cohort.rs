@@ -0,0 +1,206 @@
+use crate::commands::system::{read_backup, restore_backup};
+use crate::dto::cohort::{CohortMember, CohortSummary, FlaggedAttempt};
+use crate::error::{CommandError, CommandResult};
+use crate::state::AppState;
+use glp_core::backup::ImportOptions;
+use glp_core::db::error::DbError;
+use glp_core::db::repos::{ChallengeRepository, CohortRepository, UserRepository};
+use glp_core::models::{Cohort, CohortReport};
+use std::collections::HashMap;
+use std::fs;
+use tauri::State;
+use uuid::Uuid;
+
+fn cohort_summary(conn: &rusqlite::Connection, cohort: Cohort) -> Result<CohortSummary, DbError> {
+    let mut members = Vec::new();
+    for user_id in CohortRepository::get_members(conn, &cohort.id)? {
+        if let Some(user) = UserRepository::get_by_id(conn, &user_id)? {
+            members.push(CohortMember {
+                display_name: user.profile_name(),
+                user_id: user.id,
+                total_xp: user.total_xp,
+                current_level: user.current_level,
+            });
+        }
+    }
+
+    Ok(CohortSummary {
+        id: cohort.id,
+        name: cohort.name,
+        created_at: cohort.created_at.to_rfc3339(),
+        members,
+    })
+}
+
+/// Skills taught by each node of the active curriculum
+fn node_skills(state: &AppState) -> HashMap<String, Vec<String>> {
+    let loader = state.content_loader.lock().ok();
+    loader
+        .as_ref()
+        .and_then(|guard| guard.as_ref())
+        .map(|loader| {
+            loader
+                .get_manifest()
+                .weeks
+                .iter()
+                .flat_map(|w| &w.days)
+                .flat_map(|d| &d.nodes)
+                .map(|node| (node.id.clone(), node.skills.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+async fn build_report(state: &AppState, cohort_id: String) -> CommandResult<CohortReport> {
+    let node_skills = node_skills(state);
+
+    state
+        .run_db(move |conn| {
+            let cohort = CohortRepository::get(conn, &cohort_id)?
+                .ok_or_else(|| DbError::NotFound(format!("Cohort not found: {}", cohort_id)))?;
+            let member_count = CohortRepository::get_members(conn, &cohort_id)?.len() as u32;
+            let nodes = CohortRepository::get_node_stats(conn, &cohort_id)?;
+            let skills = CohortRepository::get_skill_stats(conn, &cohort_id)?;
+
+            Ok(CohortReport::new(cohort, member_count, nodes, skills, &node_skills))
+        })
+        .await
+}
+
+/// Create a new cohort
+#[tauri::command]
+#[specta::specta]
+pub async fn cohort_create(state: State<'_, AppState>, name: String) -> CommandResult<CohortSummary> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(CommandError::validation("Cohort name cannot be empty"));
+    }
+
+    let cohort = Cohort::new(Uuid::new_v4().to_string(), name);
+
+    state
+        .run_db(move |conn| {
+            CohortRepository::create(conn, &cohort)?;
+            cohort_summary(conn, cohort)
+        })
+        .await
+}
+
+/// List cohorts with their members
+#[tauri::command]
+#[specta::specta]
+pub async fn cohort_list(state: State<'_, AppState>) -> CommandResult<Vec<CohortSummary>> {
+    state
+        .run_db(move |conn| {
+            let mut summaries = Vec::new();
+            for cohort in CohortRepository::list(conn)? {
+                summaries.push(cohort_summary(conn, cohort)?);
+            }
+            Ok(summaries)
+        })
+        .await
+}
+
+/// Delete a cohort (enrolled profiles are kept)
+#[tauri::command]
+#[specta::specta]
+pub async fn cohort_delete(state: State<'_, AppState>, cohort_id: String) -> CommandResult<()> {
+    state
+        .run_db(move |conn| CohortRepository::delete(conn, &cohort_id))
+        .await
+}
+
+/// Enroll an existing local profile in a cohort
+#[tauri::command]
+#[specta::specta]
+pub async fn cohort_enroll(state: State<'_, AppState>, cohort_id: String, user_id: String) -> CommandResult<()> {
+    state
+        .run_db(move |conn| {
+            CohortRepository::get(conn, &cohort_id)?
+                .ok_or_else(|| DbError::NotFound(format!("Cohort not found: {}", cohort_id)))?;
+            UserRepository::get_by_id(conn, &user_id)?
+                .ok_or_else(|| DbError::NotFound(format!("Profile not found: {}", user_id)))?;
+            CohortRepository::enroll(conn, &cohort_id, &user_id)
+        })
+        .await
+}
+
+/// Remove a profile from a cohort
+#[tauri::command]
+#[specta::specta]
+pub async fn cohort_unenroll(state: State<'_, AppState>, cohort_id: String, user_id: String) -> CommandResult<()> {
+    state
+        .run_db(move |conn| CohortRepository::unenroll(conn, &cohort_id, &user_id))
+        .await
+}
+
+/// Import a student's exported data file and enroll them.
+/// Returns the imported profile ID; the current profile is not changed.
+#[tauri::command]
+#[specta::specta]
+pub async fn cohort_import_student(
+    state: State<'_, AppState>,
+    cohort_id: String,
+    path: String,
+    passphrase: Option<String>,
+) -> CommandResult<String> {
+    let backup = read_backup(&path, passphrase.as_deref())?;
+
+    state
+        .run_db(move |conn| {
+            CohortRepository::get(conn, &cohort_id)?
+                .ok_or_else(|| DbError::NotFound(format!("Cohort not found: {}", cohort_id)))?;
+
+            let user_id = restore_backup(conn, &backup, &ImportOptions::default())?
+                .user_id
+                .ok_or_else(|| DbError::InvalidData("Export file has no user profile".to_string()))?;
+            CohortRepository::enroll(conn, &cohort_id, &user_id)?;
+            Ok(user_id)
+        })
+        .await
+}
+
+/// Get aggregate progress and mastery for a cohort
+#[tauri::command]
+#[specta::specta]
+pub async fn cohort_get_report(state: State<'_, AppState>, cohort_id: String) -> CommandResult<CohortReport> {
+    build_report(&state, cohort_id).await
+}
+
+/// Export a cohort's per-node report as CSV
+#[tauri::command]
+#[specta::specta]
+pub async fn cohort_export_report(state: State<'_, AppState>, cohort_id: String, path: String) -> CommandResult<()> {
+    let report = build_report(&state, cohort_id).await?;
+    fs::write(&path, report.to_csv())?;
+    Ok(())
+}
+
+/// Challenge attempts by the cohort's members that were flagged for review,
+/// newest first: pasted reference solutions, suspiciously quick solves and
+/// edits to files learners aren't meant to change. Flags are heuristics;
+/// none of them stopped the submission.
+#[tauri::command]
+#[specta::specta]
+pub async fn cohort_flagged_attempts(
+    state: State<'_, AppState>,
+    cohort_id: String,
+) -> CommandResult<Vec<FlaggedAttempt>> {
+    state
+        .run_db(move |conn| {
+            let mut names = HashMap::new();
+            let mut flagged = Vec::new();
+            for attempt in ChallengeRepository::get_flagged_in_cohort(conn, &cohort_id)? {
+                if !names.contains_key(&attempt.user_id) {
+                    let name = UserRepository::get_by_id(conn, &attempt.user_id)?
+                        .map(|user| user.profile_name())
+                        .unwrap_or_else(|| attempt.user_id.clone());
+                    names.insert(attempt.user_id.clone(), name);
+                }
+                let display_name = names[&attempt.user_id].clone();
+                flagged.push(FlaggedAttempt::new(attempt, display_name));
+            }
+            Ok(flagged)
+        })
+        .await
+}
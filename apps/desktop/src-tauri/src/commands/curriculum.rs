@@ -1,62 +1,20 @@
+use crate::commands::{related, trash};
+use crate::dto::curriculum::{CurriculumInfo, CurriculumLicense, ImportResponse, ValidationResponse};
+use crate::error::{CommandError, CommandResult};
 use crate::state::AppState;
-use content::{import_content_pack, validate_content_pack, get_content_stats, ContentStats};
-use glp_core::db::repos::CurriculumRepository;
+use content::{import_content_pack, validate_content_pack, get_content_stats, ContentKey, ContentLoader};
+use glp_core::db::repos::{CurriculumRepository, TrashRepository};
 use glp_core::models::Curriculum;
-use serde::Serialize;
+use chrono::Local;
 use std::path::PathBuf;
 use tauri::State;
 
-#[derive(Serialize)]
-pub struct CurriculumInfo {
-    pub id: String,
-    pub name: String,
-    pub version: String,
-    pub description: Option<String>,
-    pub author: Option<String>,
-    pub imported_at: String,
-    pub is_active: bool,
-    pub stats: Option<ContentStats>,
-}
-
-impl From<Curriculum> for CurriculumInfo {
-    fn from(c: Curriculum) -> Self {
-        Self {
-            id: c.id,
-            name: c.name,
-            version: c.version,
-            description: c.description,
-            author: c.author,
-            imported_at: c.imported_at.to_rfc3339(),
-            is_active: c.is_active,
-            stats: None,
-        }
-    }
-}
-
-#[derive(Serialize)]
-pub struct ValidationResponse {
-    pub is_valid: bool,
-    pub errors: Vec<String>,
-    pub warnings: Vec<String>,
-    pub name: Option<String>,
-    pub version: Option<String>,
-    pub description: Option<String>,
-    pub author: Option<String>,
-    pub stats: Option<ContentStats>,
-}
-
-#[derive(Serialize)]
-pub struct ImportResponse {
-    pub success: bool,
-    pub curriculum_id: Option<String>,
-    pub error: Option<String>,
-}
-
 /// Validate a content pack without importing it
 #[tauri::command]
-pub fn validate_curriculum(source_path: String) -> Result<ValidationResponse, String> {
+#[specta::specta]
+pub fn validate_curriculum(source_path: String) -> CommandResult<ValidationResponse> {
     let path = PathBuf::from(&source_path);
-    let result = validate_content_pack(&path).map_err(|e| e.to_string())?;
+    let result = validate_content_pack(&path)?;
     
     let (name, version, description, author, stats) = if let Some(ref manifest) = result.manifest {
         (
@@ -84,15 +42,23 @@ pub fn validate_curriculum(source_path: String) -> Result<ValidationResponse, St
 
 /// Import a curriculum from a folder path
 #[tauri::command]
-pub fn import_curriculum(
-    state: State<AppState>,
+#[specta::specta]
+pub async fn import_curriculum(
+    state: State<'_, AppState>,
     source_path: String,
     set_active: bool,
-) -> Result<ImportResponse, String> {
-    let source = PathBuf::from(&source_path);
-    
+) -> CommandResult<ImportResponse> {
+    import_from_path(&state, PathBuf::from(&source_path), set_active).await
+}
+
+/// Validate and import a content pack, copying it into the app data directory
+pub(crate) async fn import_from_path(
+    state: &AppState,
+    source: PathBuf,
+    set_active: bool,
+) -> CommandResult<ImportResponse> {
     // First validate
-    let validation = validate_content_pack(&source).map_err(|e| e.to_string())?;
+    let validation = validate_content_pack(&source)?;
     if !validation.is_valid {
         return Ok(ImportResponse {
             success: false,
@@ -104,11 +70,12 @@ pub fn import_curriculum(
     let manifest = validation.manifest.ok_or("No manifest found")?;
     
     // Check if already exists
-    let exists = state.db
-        .with_connection(|conn| {
-            CurriculumRepository::exists_by_name_version(conn, &manifest.title, &manifest.version)
+    let (title, version) = (manifest.title.clone(), manifest.version.clone());
+    let exists = state
+        .run_db(move |conn| {
+            CurriculumRepository::exists_by_name_version(conn, &title, &version)
         })
-        .map_err(|e| e.to_string())?;
+        .await?;
 
     if exists {
         return Ok(ImportResponse {
@@ -135,20 +102,32 @@ pub fn import_curriculum(
         &source,
         state.app_data_dir(),
         &curriculum.id,
-    ).map_err(|e| e.to_string())?;
+    )?;
 
     // Update curriculum with actual content path
     let mut curriculum = curriculum;
     curriculum.content_path = content_path.to_string_lossy().to_string();
 
+    let curriculum_id = curriculum.id.clone();
+
     // Save to database
-    state.db
-        .with_connection(|conn| {
+    state
+        .run_db(move |conn| {
             CurriculumRepository::create(conn, &curriculum)
         })
-        .map_err(|e| e.to_string())?;
+        .await?;
 
-    let curriculum_id = curriculum.id.clone();
+    // Index lectures and quiz questions for related-content suggestions. A
+    // failed index doesn't fail the import; it's rebuilt on first use.
+    match ContentLoader::new(content_path) {
+        Ok(loader) => {
+            let items = related::collect_items(&loader);
+            if let Err(e) = related::index_curriculum(state, curriculum_id.clone(), items).await {
+                tracing::warn!(error = %e, "Failed to index imported curriculum");
+            }
+        }
+        Err(e) => tracing::warn!(error = %e, "Failed to load imported curriculum for indexing"),
+    }
 
     // Optionally set as active
     if set_active {
@@ -164,73 +143,145 @@ pub fn import_curriculum(
 
 /// List all imported curricula
 #[tauri::command]
-pub fn list_curricula(state: State<AppState>) -> Result<Vec<CurriculumInfo>, String> {
-    let curricula = state.db
-        .with_connection(|conn| {
+#[specta::specta]
+pub async fn list_curricula(state: State<'_, AppState>) -> CommandResult<Vec<CurriculumInfo>> {
+    let curricula = state
+        .run_db(move |conn| {
             CurriculumRepository::get_all(conn)
         })
-        .map_err(|e| e.to_string())?;
+        .await?;
 
     Ok(curricula.into_iter().map(CurriculumInfo::from).collect())
 }
 
 /// Get the currently active curriculum
 #[tauri::command]
-pub fn get_active_curriculum(state: State<AppState>) -> Result<Option<CurriculumInfo>, String> {
-    let curriculum = state.db
-        .with_connection(|conn| {
+#[specta::specta]
+pub async fn get_active_curriculum(state: State<'_, AppState>) -> CommandResult<Option<CurriculumInfo>> {
+    let curriculum = state
+        .run_db(move |conn| {
             CurriculumRepository::get_active(conn)
         })
-        .map_err(|e| e.to_string())?;
+        .await?;
 
     Ok(curriculum.map(CurriculumInfo::from))
 }
 
 /// Switch to a different curriculum
 #[tauri::command]
-pub fn switch_curriculum(state: State<AppState>, curriculum_id: String) -> Result<(), String> {
+#[specta::specta]
+pub fn switch_curriculum(state: State<AppState>, curriculum_id: String) -> CommandResult<()> {
     state.load_curriculum(&curriculum_id)
 }
 
-/// Delete a curriculum
+/// Activate an encrypted curriculum with the content key that came with it.
+/// The key is checked against the pack and kept, so the curriculum opens
+/// without asking again.
 #[tauri::command]
-pub fn delete_curriculum(
-    state: State<AppState>,
+#[specta::specta]
+pub async fn unlock_curriculum(
+    state: State<'_, AppState>,
+    curriculum_id: String,
+    content_key: String,
+) -> CommandResult<()> {
+    let id = curriculum_id.clone();
+    let curriculum = state
+        .run_db(move |conn| CurriculumRepository::get(conn, &id))
+        .await?
+        .ok_or_else(|| CommandError::not_found(format!("Curriculum not found: {}", curriculum_id)))?;
+
+    let key = ContentKey::from_hex(&content_key)?;
+    ContentLoader::new(state.app_data_dir().join(&curriculum.content_path))?.with_key(key.clone())?;
+
+    let (id, key) = (curriculum_id.clone(), key.to_hex());
+    state
+        .run_db(move |conn| CurriculumRepository::set_content_key(conn, &id, &key))
+        .await?;
+    state.load_curriculum(&curriculum_id)
+}
+
+/// Activate a premium curriculum with its license key. The key is checked
+/// offline against the issuers pinned in the app and kept, and the
+/// curriculum made active.
+#[tauri::command]
+#[specta::specta]
+pub async fn activate_curriculum(
+    state: State<'_, AppState>,
+    curriculum_id: String,
+    license_key: String,
+) -> CommandResult<CurriculumLicense> {
+    let id = curriculum_id.clone();
+    let curriculum = state
+        .run_db(move |conn| CurriculumRepository::get(conn, &id))
+        .await?
+        .ok_or_else(|| CommandError::not_found(format!("Curriculum not found: {}", curriculum_id)))?;
+
+    let loader = ContentLoader::new(state.app_data_dir().join(&curriculum.content_path))?;
+    if !loader.requires_license() {
+        return Err(CommandError::validation(format!("{} doesn't need a license", curriculum.name)));
+    }
+    let loader = loader.with_license(&license_key)?;
+    loader.check_license()?;
+    let license = loader.license().ok_or("License missing after activation")?;
+    let info = CurriculumLicense::new(license, Local::now().date_naive());
+
+    let (id, key) = (curriculum_id.clone(), license_key.trim().to_string());
+    state
+        .run_db(move |conn| CurriculumRepository::set_license_key(conn, &id, &key))
+        .await?;
+    state.load_curriculum(&curriculum_id)?;
+    Ok(info)
+}
+
+/// The license the active curriculum was activated with, or `None` if it
+/// doesn't need one or hasn't been activated
+#[tauri::command]
+#[specta::specta]
+pub fn get_curriculum_license(state: State<AppState>) -> CommandResult<Option<CurriculumLicense>> {
+    let loader = state.content_loader.lock()?;
+    let license = loader.as_ref().and_then(|loader| loader.license());
+    Ok(license.map(|license| CurriculumLicense::new(license, Local::now().date_naive())))
+}
+
+/// Delete a curriculum. Can be undone with `undo_last_destructive_operation`
+/// until the undo window closes.
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_curriculum(
+    state: State<'_, AppState>,
     curriculum_id: String,
     delete_progress: bool,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     // Check if this is the active curriculum
     let active_id = state.get_active_curriculum_id();
     if active_id.as_ref() == Some(&curriculum_id) {
         state.unload_curriculum()?;
     }
 
-    // Delete from database (and optionally progress)
-    state.db
-        .with_connection(|conn| {
-            if delete_progress {
-                CurriculumRepository::delete_with_progress(conn, &curriculum_id)
-            } else {
-                CurriculumRepository::delete(conn, &curriculum_id)
-            }
-        })
-        .map_err(|e| e.to_string())?;
+    trash::purge_expired_trash(&state).await?;
+
+    // Delete from database (and optionally progress), keeping a snapshot for undo
+    let id = curriculum_id.clone();
+    let undo_window = trash::undo_window();
+    let entry = state
+        .run_db(move |conn| TrashRepository::delete_curriculum(conn, &id, delete_progress, undo_window))
+        .await?;
 
-    // Delete content files
-    content::delete_content_pack(state.app_data_dir(), &curriculum_id)
-        .map_err(|e| e.to_string())?;
+    // Move content files to the trash
+    content::trash_content_pack(state.app_data_dir(), &curriculum_id, &entry.id)?;
 
     Ok(())
 }
 
 /// Get a specific curriculum by ID
 #[tauri::command]
-pub fn get_curriculum(state: State<AppState>, curriculum_id: String) -> Result<Option<CurriculumInfo>, String> {
-    let curriculum = state.db
-        .with_connection(|conn| {
+#[specta::specta]
+pub async fn get_curriculum(state: State<'_, AppState>, curriculum_id: String) -> CommandResult<Option<CurriculumInfo>> {
+    let curriculum = state
+        .run_db(move |conn| {
             CurriculumRepository::get(conn, &curriculum_id)
         })
-        .map_err(|e| e.to_string())?;
+        .await?;
 
     Ok(curriculum.map(CurriculumInfo::from))
 }
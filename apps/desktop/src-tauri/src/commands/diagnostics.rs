@@ -0,0 +1,99 @@
+use crate::commands::{jobs, system};
+use crate::dto::system::{DockerStatus, SystemStatus};
+use crate::error::CommandResult;
+use crate::logging;
+use crate::state::AppState;
+use chrono::Utc;
+use glp_core::db::integrity::check_integrity;
+use glp_core::db::migrations::CURRENT_VERSION;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use tauri::State;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// Days of logs included in a diagnostics bundle
+const LOG_FILES_INCLUDED: usize = 3;
+
+#[derive(Debug, Serialize)]
+struct Versions {
+    app_version: &'static str,
+    schema_version: i32,
+    sqlite_version: &'static str,
+    os: &'static str,
+    arch: &'static str,
+    generated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+struct SystemReport {
+    status: SystemStatus,
+    docker: DockerStatus,
+}
+
+/// Write a zip of recent logs, system status, database integrity check
+/// results and version information, for attaching to bug reports. Contains
+/// no API keys or learner content.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_diagnostics(state: State<'_, AppState>, path: String) -> CommandResult<()> {
+    let versions = Versions {
+        app_version: env!("CARGO_PKG_VERSION"),
+        schema_version: CURRENT_VERSION,
+        sqlite_version: rusqlite::version(),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        generated_at: Utc::now().to_rfc3339(),
+    };
+    let system = SystemReport {
+        status: system::check_system_status(state.clone()).await?,
+        docker: system::check_docker_internal(),
+    };
+    let integrity = state.run_db(check_integrity).await?;
+    let jobs = jobs::get_job_status(state.clone()).await?;
+
+    let mut entries = vec![
+        ("versions.json".to_string(), to_json(&versions)?),
+        ("system_status.json".to_string(), to_json(&system)?),
+        ("db_integrity.json".to_string(), to_json(&integrity)?),
+        ("jobs.json".to_string(), to_json(&jobs)?),
+    ];
+    let log_files = logging::recent_log_files(state.app_data_dir(), LOG_FILES_INCLUDED);
+
+    tracing::info!(path = %path, logs = log_files.len(), "Exporting diagnostics");
+    tauri::async_runtime::spawn_blocking(move || {
+        for log_file in log_files {
+            if let Some(entry) = log_entry(&log_file) {
+                entries.push(entry);
+            }
+        }
+        write_zip(&PathBuf::from(path), entries)
+    })
+    .await?
+}
+
+fn to_json<T: Serialize>(value: &T) -> CommandResult<Vec<u8>> {
+    Ok(serde_json::to_vec_pretty(value)?)
+}
+
+/// Zip entry for a log file, skipping any that can't be read
+fn log_entry(path: &PathBuf) -> Option<(String, Vec<u8>)> {
+    let name = path.file_name()?.to_str()?;
+    let contents = fs::read(path).ok()?;
+    Some((format!("logs/{}", name), contents))
+}
+
+fn write_zip(path: &PathBuf, entries: Vec<(String, Vec<u8>)>) -> CommandResult<()> {
+    let file = File::create(path)?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    for (name, contents) in entries {
+        zip.start_file(name, options)?;
+        zip.write_all(&contents)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use crate::dto::events::XpEventResponse;
+use crate::error::CommandResult;
+use crate::state::AppState;
+use chrono::Utc;
+use glp_core::gamification::{EventCalendar, XpActivity};
+use std::fs;
+use std::path::Path;
+use tauri::State;
+
+const EVENTS_FILE: &str = "events.json";
+
+fn load_calendar(path: &Path) -> Option<EventCalendar> {
+    let json = fs::read_to_string(path).ok()?;
+    match EventCalendar::from_json(&json) {
+        Ok(calendar) => Some(calendar),
+        Err(e) => {
+            tracing::warn!(path = %path.display(), error = %e, "Ignoring events file");
+            None
+        }
+    }
+}
+
+/// Discover XP events from the app data directory and the active content pack.
+/// App-level events win when both define the same ID.
+pub fn discover_events(state: &AppState) -> EventCalendar {
+    let mut calendar = load_calendar(&state.app_data_dir().join(EVENTS_FILE)).unwrap_or_default();
+
+    if let Ok(loader) = state.content_loader.lock() {
+        if let Some(pack_events) = loader
+            .as_ref()
+            .and_then(|l| load_calendar(&l.content_dir().join(EVENTS_FILE)))
+        {
+            calendar.merge(pack_events);
+        }
+    }
+
+    calendar
+}
+
+/// Combined multiplier of the events active right now for an activity
+pub fn current_multiplier(state: &AppState, activity: XpActivity) -> f64 {
+    discover_events(state).multiplier_for(activity, Utc::now())
+}
+
+/// Get XP events that are currently running
+#[tauri::command]
+#[specta::specta]
+pub fn get_active_xp_events(state: State<AppState>) -> CommandResult<Vec<XpEventResponse>> {
+    let calendar = discover_events(&state);
+
+    Ok(calendar
+        .active_events(Utc::now())
+        .into_iter()
+        .map(|e| XpEventResponse {
+            id: e.id.clone(),
+            name: e.name.clone(),
+            description: e.description.clone(),
+            multiplier: e.multiplier,
+            starts_at: e.starts_at.to_rfc3339(),
+            ends_at: e.ends_at.to_rfc3339(),
+            activities: e.activities.clone(),
+        })
+        .collect())
+}
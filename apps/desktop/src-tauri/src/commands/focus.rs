@@ -0,0 +1,101 @@
+use crate::error::CommandResult;
+use crate::notifications::emit_gamification_events;
+use crate::state::AppState;
+use chrono::Utc;
+use glp_core::db::error::DbResult;
+use glp_core::focus::{self, FocusBlockKind, FocusResult, FocusSettings, FocusState};
+use glp_core::gamification::{xp_award_events, GamificationEvent, XpSource};
+use glp_core::services::GamificationService;
+use rusqlite::Connection;
+use tauri::{AppHandle, State};
+
+/// Run a focus timer action, collecting XP and badge events if a work
+/// block completed along the way
+async fn run_focus<F>(app: &AppHandle, state: &AppState, action: F) -> CommandResult<FocusState>
+where
+    F: FnOnce(&Connection, &str) -> FocusResult<FocusState> + Send + 'static,
+{
+    let user_id = state.get_current_user_id();
+
+    let result = state
+        .run_db(move |conn| -> DbResult<FocusResult<(FocusState, Vec<GamificationEvent>)>> {
+            let focus_state = match action(conn, &user_id) {
+                Ok(focus_state) => focus_state,
+                Err(e) => return Ok(Err(e)),
+            };
+
+            let mut notifications = Vec::new();
+            if let Some(completion) = &focus_state.completed {
+                if completion.xp_awarded > 0 {
+                    notifications = xp_award_events(XpSource::Focus, completion.xp_awarded, completion.previous_total_xp);
+                    notifications.extend(GamificationService::unlock_badge_events(conn, &user_id)?);
+                }
+            }
+            Ok(Ok((focus_state, notifications)))
+        })
+        .await?;
+    let (focus_state, notifications) = result?;
+
+    if let Some(completion) = &focus_state.completed {
+        tracing::info!(
+            block = %completion.block.id,
+            kind = completion.block.kind.as_str(),
+            xp = completion.xp_awarded,
+            "Focus block completed"
+        );
+    }
+    emit_gamification_events(app, &notifications);
+    Ok(focus_state)
+}
+
+/// The focus timer, completing the current block if its time ran out
+#[tauri::command]
+#[specta::specta]
+pub async fn get_focus_state(app: AppHandle, state: State<'_, AppState>) -> CommandResult<FocusState> {
+    run_focus(&app, &state, |conn, user_id| focus::get_state(conn, user_id, Utc::now())).await
+}
+
+/// Start a work or break block. Without a kind, starts whatever comes next
+/// in the work/break cycle.
+#[tauri::command]
+#[specta::specta]
+pub async fn start_focus_block(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    kind: Option<FocusBlockKind>,
+) -> CommandResult<FocusState> {
+    run_focus(&app, &state, move |conn, user_id| focus::start_block(conn, user_id, kind, Utc::now())).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn pause_focus_block(app: AppHandle, state: State<'_, AppState>) -> CommandResult<FocusState> {
+    run_focus(&app, &state, |conn, user_id| focus::pause_block(conn, user_id, Utc::now())).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn resume_focus_block(app: AppHandle, state: State<'_, AppState>) -> CommandResult<FocusState> {
+    run_focus(&app, &state, |conn, user_id| focus::resume_block(conn, user_id, Utc::now())).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn cancel_focus_block(app: AppHandle, state: State<'_, AppState>) -> CommandResult<FocusState> {
+    run_focus(&app, &state, |conn, user_id| focus::cancel_block(conn, user_id, Utc::now())).await
+}
+
+/// Change work and break lengths for blocks started from now on
+#[tauri::command]
+#[specta::specta]
+pub async fn update_focus_settings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    settings: FocusSettings,
+) -> CommandResult<FocusState> {
+    run_focus(&app, &state, move |conn, user_id| {
+        focus::save_settings(conn, user_id, &settings)?;
+        focus::get_state(conn, user_id, Utc::now())
+    })
+    .await
+}
@@ -0,0 +1,103 @@
+use crate::dto::goal::GoalTargetInput;
+use crate::error::{CommandError, CommandResult, ErrorCode};
+use crate::state::AppState;
+use chrono::{Local, NaiveDate, TimeZone, Utc};
+use glp_core::db::repos::GoalRepository;
+use glp_core::goals;
+use glp_core::models::{Goal, GoalStatus, GoalTarget};
+use tauri::State;
+
+/// Set a goal in the active curriculum, due by the end of `deadline`
+/// (YYYY-MM-DD, local time)
+#[tauri::command]
+#[specta::specta]
+pub async fn create_goal(
+    state: State<'_, AppState>,
+    title: String,
+    target: GoalTargetInput,
+    deadline: String,
+) -> CommandResult<Goal> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    let date = NaiveDate::parse_from_str(&deadline, "%Y-%m-%d")
+        .map_err(|e| CommandError::validation(format!("Invalid deadline {}: {}", deadline, e)))?;
+    let end_of_day = date.succ_opt().unwrap_or(date).and_hms_opt(0, 0, 0).expect("midnight is always valid");
+    let deadline = Local
+        .from_local_datetime(&end_of_day)
+        .earliest()
+        .map(|at| at.with_timezone(&Utc))
+        .unwrap_or_else(|| end_of_day.and_utc());
+
+    let target = {
+        let loader = state.content_loader.lock()?;
+        let manifest = loader
+            .as_ref()
+            .ok_or_else(|| CommandError::new(ErrorCode::CurriculumNotLoaded, "Content not loaded"))?
+            .get_manifest();
+
+        match target {
+            GoalTargetInput::CompleteWeek { week_id } => {
+                let week = manifest
+                    .weeks
+                    .iter()
+                    .find(|week| week.id == week_id)
+                    .ok_or_else(|| CommandError::not_found(format!("Week not found: {}", week_id)))?;
+                let node_ids = week.days.iter().flat_map(|day| day.nodes.iter().map(|node| node.id.clone())).collect();
+                GoalTarget::CompleteWeek { week_id, node_ids }
+            }
+            GoalTargetInput::ReachMastery { skill_id, score } => {
+                if !manifest.skills.iter().any(|skill| skill.id == skill_id) {
+                    return Err(CommandError::not_found(format!("Skill not found: {}", skill_id)));
+                }
+                GoalTarget::ReachMastery { skill_id, score }
+            }
+        }
+    };
+
+    let goal = Goal::new(user_id, Some(curriculum_id), title.trim().to_string(), target, deadline);
+    let saved = goal.clone();
+    state
+        .run_db(move |conn| Ok(goals::create_goal(conn, &saved, Utc::now())))
+        .await??;
+    Ok(goal)
+}
+
+/// The active curriculum's goals with progress and whether each is on track
+#[tauri::command]
+#[specta::specta]
+pub async fn get_goals_status(state: State<'_, AppState>) -> CommandResult<Vec<GoalStatus>> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    state
+        .run_db(move |conn| goals::goals_status(conn, &user_id, Some(&curriculum_id), Utc::now()))
+        .await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_goal(state: State<'_, AppState>, goal_id: String) -> CommandResult<()> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    state
+        .run_db(move |conn| {
+            match GoalRepository::get_by_id(conn, &goal_id)? {
+                Some(goal) if goal.user_id == user_id => GoalRepository::delete(conn, &goal_id),
+                _ => Err(glp_core::DbError::NotFound(format!("Goal not found: {}", goal_id))),
+            }
+        })
+        .await
+}
@@ -0,0 +1,69 @@
+use crate::commands::challenge::load_challenge_node;
+use crate::error::{CommandError, CommandResult};
+use crate::state::AppState;
+use chrono::Utc;
+use glp_core::db::repos::HintRepository;
+use glp_core::hints::{self, HintSettings, HintState};
+use tauri::State;
+
+/// The hints revealed so far for a challenge
+#[tauri::command]
+#[specta::specta]
+pub async fn get_hint_state(state: State<'_, AppState>, node_id: String) -> CommandResult<HintState> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    let challenge_hints = load_challenge_node(&state, &node_id)?.hints;
+
+    let result = state
+        .run_db(move |conn| {
+            Ok(hints::get_state(conn, &user_id, Some(&curriculum_id), &node_id, &challenge_hints))
+        })
+        .await?;
+    Ok(result?)
+}
+
+/// Reveal a challenge's next hint. Each hint revealed before the challenge
+/// is passed costs part of its completion XP.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_next_hint(state: State<'_, AppState>, node_id: String) -> CommandResult<HintState> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    let challenge_hints = load_challenge_node(&state, &node_id)?.hints;
+
+    let result = state
+        .run_db(move |conn| {
+            Ok(hints::reveal_next(conn, &user_id, Some(&curriculum_id), &node_id, &challenge_hints, Utc::now()))
+        })
+        .await?;
+    let hint_state = result?;
+
+    tracing::info!(node = %hint_state.node_id, revealed = hint_state.revealed.len(), "Hint revealed");
+    Ok(hint_state)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_hint_settings(state: State<'_, AppState>) -> CommandResult<HintSettings> {
+    let user_id = state.get_current_user_id();
+    state.run_db(move |conn| HintRepository::get_settings(conn, &user_id)).await
+}
+
+/// Change what revealing a hint costs, for challenges not yet passed
+#[tauri::command]
+#[specta::specta]
+pub async fn update_hint_settings(state: State<'_, AppState>, settings: HintSettings) -> CommandResult<HintSettings> {
+    let user_id = state.get_current_user_id();
+    let result = state
+        .run_db(move |conn| Ok(hints::save_settings(conn, &user_id, &settings).map(|()| settings)))
+        .await?;
+    Ok(result?)
+}
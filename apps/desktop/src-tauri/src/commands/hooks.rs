@@ -0,0 +1,48 @@
+use crate::dto::hooks::HookSettings;
+use crate::error::{CommandError, CommandResult};
+use crate::hooks::scripts_dir;
+use crate::state::AppState;
+use glp_core::hooks::{self, ALLOWED_WEBHOOK_HOSTS};
+use glp_core::models::Hook;
+use tauri::State;
+
+fn hook_settings(state: &AppState, hooks: Vec<Hook>) -> HookSettings {
+    HookSettings {
+        hooks,
+        scripts_dir: scripts_dir(state).display().to_string(),
+        allowed_webhook_hosts: ALLOWED_WEBHOOK_HOSTS.iter().map(|host| host.to_string()).collect(),
+    }
+}
+
+/// The current user's hooks and what they may target
+#[tauri::command]
+#[specta::specta]
+pub async fn get_hooks(state: State<'_, AppState>) -> CommandResult<HookSettings> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    let hooks = state.run_db(move |conn| Ok(hooks::load_hooks(conn, &user_id))).await??;
+    Ok(hook_settings(&state, hooks))
+}
+
+/// Replace the current user's hooks. Every hook's target must be on the
+/// allow-list; new hooks are given ids.
+#[tauri::command]
+#[specta::specta]
+pub async fn configure_hooks(state: State<'_, AppState>, hooks: Vec<Hook>) -> CommandResult<HookSettings> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    std::fs::create_dir_all(scripts_dir(&state))?;
+    let saved = state
+        .run_db(move |conn| Ok(hooks::save_hooks(conn, &user_id, &hooks)))
+        .await??;
+    tracing::info!(hooks = saved.len(), "Hooks configured");
+    Ok(hook_settings(&state, saved))
+}
@@ -0,0 +1,36 @@
+use crate::dto::jobs::JobStatus;
+use crate::error::CommandResult;
+use crate::scheduler::JOBS;
+use crate::state::AppState;
+use glp_core::db::repos::JobRepository;
+use tauri::State;
+
+/// Status of every background job
+#[tauri::command]
+#[specta::specta]
+pub async fn get_job_status(state: State<'_, AppState>) -> CommandResult<Vec<JobStatus>> {
+    let runs = state.run_db(JobRepository::get_runs).await?;
+
+    Ok(JOBS
+        .iter()
+        .map(|job| {
+            let run = runs.iter().find(|r| r.job_id == job.id);
+            JobStatus {
+                id: job.id.to_string(),
+                description: job.description.to_string(),
+                schedule: job.schedule.to_string(),
+                running: state.scheduler.is_running(job.id),
+                last_started_at: run.map(|r| r.last_started_at.to_rfc3339()),
+                last_duration_ms: run.map(|r| r.last_duration_ms),
+                last_result: run.and_then(|r| r.last_result.clone()),
+                last_error: run.and_then(|r| r.last_error.clone()),
+                last_success_at: run.and_then(|r| r.last_success_at).map(|t| t.to_rfc3339()),
+                run_count: run.map_or(0, |r| r.run_count),
+                failure_count: run.map_or(0, |r| r.failure_count),
+                next_run_at: run
+                    .and_then(|r| job.parsed_schedule().next_after(r.last_started_at))
+                    .map(|t| t.to_rfc3339()),
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,34 @@
+use crate::dto::leaderboard::{LeaderboardEntryResponse, LeaderboardResponse};
+use crate::error::CommandResult;
+use crate::state::AppState;
+use chrono::Utc;
+use glp_core::leaderboard::{build_leaderboard, LeaderboardPeriod, LocalLeaderboardSource};
+use tauri::State;
+
+/// Get the leaderboard across local profiles for a period ("Weekly" or "AllTime")
+#[tauri::command]
+#[specta::specta]
+pub async fn get_leaderboard(state: State<'_, AppState>, period: String) -> CommandResult<LeaderboardResponse> {
+    let period: LeaderboardPeriod = period.parse()?;
+    let user_id = state.get_current_user_id();
+
+    let leaderboard = state
+        .run_db(move |conn| {
+            let local = LocalLeaderboardSource::new(conn);
+            build_leaderboard(&[&local], period, Utc::now())
+        })
+        .await?;
+
+    Ok(LeaderboardResponse {
+        period: leaderboard.period,
+        generated_at: leaderboard.generated_at.to_rfc3339(),
+        entries: leaderboard
+            .entries
+            .into_iter()
+            .map(|entry| LeaderboardEntryResponse {
+                is_current_user: entry.standing.user_id == user_id,
+                entry,
+            })
+            .collect(),
+    })
+}
@@ -1,134 +1,163 @@
+use crate::analytics;
+use crate::commands::events;
+use crate::dto::lecture::{CompleteLectureRequest, CompletionResult, InlineAnswerFeedback};
+use crate::error::{CommandError, CommandResult, ErrorCode};
+use crate::notifications::emit_gamification_events;
 use crate::state::AppState;
-use glp_core::db::repos::{ProgressRepository, UserRepository};
-use glp_core::gamification::{calculate_lecture_xp, calculate_level, Difficulty};
-use glp_core::models::NodeProgress;
-use serde::{Deserialize, Serialize};
-use tauri::State;
-
-#[derive(Serialize)]
-pub struct LectureData {
-    pub id: String,
-    pub title: String,
-    pub content: String,
-    pub difficulty: String,
-    pub xp_reward: i32,
-}
-
-#[derive(Serialize)]
-pub struct CompletionResult {
-    pub xp_earned: i32,
-    pub new_total_xp: i32,
-    pub new_level: u32,
-    pub unlocked_nodes: Vec<String>,
-}
+use content::Lecture;
+use glp_core::gamification::{Difficulty, XpActivity};
+use glp_core::analytics::event_minutes;
+use glp_core::models::{AnalyticsEvent, ComprehensionCheck};
+use glp_core::services::{LectureCompletion, ProgressService};
+use tauri::{AppHandle, State};
 
 #[tauri::command]
-pub fn start_lecture(
-    state: State<AppState>,
+#[specta::specta]
+pub async fn start_lecture(
+    state: State<'_, AppState>,
     lecture_id: String,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     let user_id = state
         .current_user_id
-        .lock()
-        .map_err(|e| e.to_string())?
+        .lock()?
         .clone()
-        .ok_or_else(|| "No user logged in".to_string())?;
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
 
     state
-        .db
-        .with_connection(|conn| {
-            let mut progress = NodeProgress::new(user_id.clone(), lecture_id.clone());
-            progress.start();
-            ProgressRepository::create_or_update(conn, &progress)?;
+        .run_db(move |conn| {
+            ProgressService::start_node(conn, &user_id, Some(&curriculum_id), &lecture_id)?;
             Ok(())
         })
-        .map_err(|e| e.to_string())
+        .await
 }
 
 #[tauri::command]
-pub fn update_lecture_time(
-    state: State<AppState>,
+#[specta::specta]
+pub async fn update_lecture_time(
+    state: State<'_, AppState>,
     lecture_id: String,
     time_spent_ms: i64,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     let user_id = state
         .current_user_id
-        .lock()
-        .map_err(|e| e.to_string())?
+        .lock()?
         .clone()
-        .ok_or_else(|| "No user logged in".to_string())?;
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
 
     state
-        .db
-        .with_connection(|conn| {
-            let mut progress = ProgressRepository::get(conn, &user_id, &lecture_id)?
-                .ok_or_else(|| glp_core::db::error::DbError::NotFound("Progress not found".to_string()))?;
-
-            progress.add_time((time_spent_ms / 60000) as i32);
-            ProgressRepository::create_or_update(conn, &progress)?;
+        .run_db(move |conn| {
+            ProgressService::add_time(conn, &user_id, Some(&curriculum_id), &lecture_id, time_spent_ms)?;
             Ok(())
         })
-        .map_err(|e| e.to_string())
-}
-
-#[derive(Deserialize)]
-pub struct CompleteLectureRequest {
-    pub lecture_id: String,
-    pub time_spent_ms: i64,
-    pub difficulty: String,
+        .await
 }
 
 #[tauri::command]
-pub fn complete_lecture(
-    state: State<AppState>,
+#[specta::specta]
+pub async fn complete_lecture(
+    app: AppHandle,
+    state: State<'_, AppState>,
     request: CompleteLectureRequest,
-) -> Result<CompletionResult, String> {
+) -> CommandResult<CompletionResult> {
     let user_id = state
         .current_user_id
-        .lock()
-        .map_err(|e| e.to_string())?
+        .lock()?
         .clone()
-        .ok_or_else(|| "No user logged in".to_string())?;
-
-    state
-        .db
-        .with_connection(|conn| {
-            // Parse difficulty
-            let difficulty = match request.difficulty.as_str() {
-                "Easy" => Difficulty::Easy,
-                "Medium" => Difficulty::Medium,
-                "Hard" => Difficulty::Hard,
-                "VeryHard" => Difficulty::VeryHard,
-                _ => Difficulty::Easy,
-            };
-
-            // Get user's current streak
-            let user = UserRepository::get_by_id(conn, &user_id)?
-                .ok_or_else(|| glp_core::db::error::DbError::NotFound("User not found".to_string()))?;
-
-            // Calculate XP
-            let xp_earned = calculate_lecture_xp(difficulty, user.current_streak as u32);
-
-            // Update progress
-            let mut progress = ProgressRepository::get(conn, &user_id, &request.lecture_id)?
-                .unwrap_or_else(|| NodeProgress::new(user_id.clone(), request.lecture_id.clone()));
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    let comprehension_check =
+        check_comprehension(&state, &request.lecture_id, request.comprehension_answers.as_deref())?;
+
+    let event_multiplier = events::current_multiplier(&state, XpActivity::Lecture);
+    let lecture = LectureCompletion {
+        lecture_id: request.lecture_id,
+        time_spent_ms: request.time_spent_ms,
+        difficulty: request.difficulty.parse().unwrap_or(Difficulty::Easy),
+        comprehension_check,
+    };
+
+    let completed = AnalyticsEvent::NodeCompleted {
+        node_id: lecture.lecture_id.clone(),
+        node_type: "lecture".to_string(),
+        minutes: event_minutes(lecture.time_spent_ms),
+    };
+    let (uid, cid) = (user_id.clone(), curriculum_id.clone());
+    let award = state
+        .run_db(move |conn| {
+            ProgressService::complete_lecture(conn, &uid, Some(&cid), &lecture, event_multiplier)
+        })
+        .await?;
+
+    emit_gamification_events(&app, &award.events);
+    analytics::record(&state, user_id, curriculum_id, completed).await;
+    Ok(CompletionResult {
+        xp_earned: award.xp_earned,
+        new_total_xp: award.new_total_xp,
+        new_level: award.new_level,
+        unlocked_nodes: vec![], // TODO: Implement unlock logic
+    })
+}
 
-            progress.add_time((request.time_spent_ms / 60000) as i32);
-            progress.complete();
-            ProgressRepository::create_or_update(conn, &progress)?;
+/// Check the answer to one of a lecture's inline questions, given by its id
+/// in the lecture as `load_lecture` sent it. The right answer and the
+/// explanation only come back once an answer is given.
+#[tauri::command]
+#[specta::specta]
+pub fn check_lecture_question(
+    state: State<AppState>,
+    lecture_id: String,
+    question_id: String,
+    answer: u32,
+) -> CommandResult<InlineAnswerFeedback> {
+    let lecture = load_lecture_by_id(&state, &lecture_id)?;
+    let question = question_id
+        .parse::<usize>()
+        .ok()
+        .and_then(|index| lecture.inline_questions().nth(index))
+        .ok_or_else(|| CommandError::not_found(format!("Question not found: {}", question_id)))?;
+
+    Ok(InlineAnswerFeedback {
+        correct: question.correct_answer == answer as usize,
+        correct_answer: question.correct_answer as u32,
+        explanation: question.explanation.clone(),
+    })
+}
 
-            // Award XP and update level
-            UserRepository::update_xp(conn, &user_id, xp_earned)?;
-            let new_total_xp = user.total_xp + xp_earned;
-            let new_level = calculate_level(new_total_xp);
-            UserRepository::update_level(conn, &user_id, new_level as i32)?;
+/// The lecture content for a manifest node, answers included. Answers are
+/// only read here, to grade them.
+fn load_lecture_by_id(state: &AppState, lecture_id: &str) -> CommandResult<Lecture> {
+    let loader = state.content_loader.lock()?;
+    let loader = loader
+        .as_ref()
+        .ok_or_else(|| CommandError::new(ErrorCode::CurriculumNotLoaded, "Content not loaded"))?;
+    let node = loader
+        .get_node_by_id(lecture_id)
+        .ok_or_else(|| CommandError::not_found(format!("Lecture not found: {}", lecture_id)))?;
+    Ok(loader.load_lecture_content(&node.content_path)?)
+}
 
-            Ok(CompletionResult {
-                xp_earned,
-                new_total_xp,
-                new_level,
-                unlocked_nodes: vec![], // TODO: Implement unlock logic
-            })
-        })
-        .map_err(|e| e.to_string())
+/// Grade the answers to a lecture's comprehension check. Completing it
+/// without answers records the check as skipped; wrong answers don't
+/// complete it.
+fn check_comprehension(
+    state: &AppState,
+    lecture_id: &str,
+    answers: Option<&[u32]>,
+) -> CommandResult<Option<ComprehensionCheck>> {
+    let lecture = load_lecture_by_id(state, lecture_id)?;
+
+    if lecture.comprehension_check.is_empty() {
+        return Ok(None);
+    }
+    let Some(answers) = answers else {
+        return Ok(Some(ComprehensionCheck::Skipped));
+    };
+    let answers: Vec<usize> = answers.iter().map(|&answer| answer as usize).collect();
+    if lecture.passes_comprehension_check(&answers) {
+        Ok(Some(ComprehensionCheck::Passed))
+    } else {
+        Err(CommandError::validation("The comprehension check answers aren't all right"))
+    }
 }
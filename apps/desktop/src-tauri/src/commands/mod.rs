@@ -1,11 +1,41 @@
+pub mod analytics;
 pub mod badge;
+pub mod bookmark;
+pub mod capability;
+pub mod challenge;
+pub mod checkpoint;
+pub mod cohort;
 pub mod content;
 pub mod curriculum;
+pub mod diagnostics;
+pub mod events;
+pub mod focus;
+pub mod goal;
+pub mod hint;
+pub mod hooks;
+pub mod jobs;
+pub mod leaderboard;
 pub mod lecture;
+pub mod note;
+pub mod pacing;
+pub mod practice;
 pub mod progress;
+pub mod quest;
 pub mod quiz;
+pub mod related;
+pub mod reminders;
 pub mod review;
+pub mod sandbox;
+pub mod search;
 pub mod session;
+pub mod settings;
+pub mod setup;
+pub mod stats;
+pub mod sync;
 pub mod system;
+pub mod track;
+pub mod trash;
+pub mod tutor;
 pub mod update;
 pub mod user;
+pub mod xapi;
@@ -0,0 +1,119 @@
+use crate::dto::note::NoteListItem;
+use crate::error::{CommandError, CommandResult};
+use crate::state::AppState;
+use glp_core::db::repos::NoteRepository;
+use glp_core::models::Note;
+use glp_core::DbError;
+use tauri::State;
+
+fn current_user(state: &AppState) -> CommandResult<String> {
+    state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)
+}
+
+fn non_empty(body: String) -> CommandResult<String> {
+    let body = body.trim().to_string();
+    if body.is_empty() {
+        return Err(CommandError::validation("Note cannot be empty"));
+    }
+    Ok(body)
+}
+
+/// Add a note to a content node, optionally annotating a passage of it
+#[tauri::command]
+#[specta::specta]
+pub async fn create_note(
+    state: State<'_, AppState>,
+    node_id: String,
+    body: String,
+    anchor: Option<String>,
+) -> CommandResult<Note> {
+    if let Some(loader) = state.content_loader.lock()?.as_ref() {
+        if loader.get_node_by_id(&node_id).is_none() {
+            return Err(CommandError::not_found(format!("Node not found: {}", node_id)));
+        }
+    }
+    let mut note = Note::new(current_user(&state)?, node_id, non_empty(body)?);
+    if let Some(anchor) = anchor.map(|a| a.trim().to_string()).filter(|a| !a.is_empty()) {
+        note = note.with_anchor(anchor);
+    }
+
+    state
+        .run_db(move |conn| {
+            NoteRepository::create(conn, &note)?;
+            Ok(note)
+        })
+        .await
+}
+
+/// Replace the text of one of the current user's notes
+#[tauri::command]
+#[specta::specta]
+pub async fn update_note(state: State<'_, AppState>, note_id: String, body: String) -> CommandResult<Note> {
+    let user_id = current_user(&state)?;
+    let body = non_empty(body)?;
+
+    state
+        .run_db(move |conn| {
+            if !NoteRepository::update_body(conn, &user_id, &note_id, &body)? {
+                return Err(DbError::NotFound(format!("Note not found: {}", note_id)));
+            }
+            NoteRepository::get(conn, &user_id, &note_id)?
+                .ok_or_else(|| DbError::NotFound(format!("Note not found: {}", note_id)))
+        })
+        .await
+}
+
+/// Delete one of the current user's notes
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_note(state: State<'_, AppState>, note_id: String) -> CommandResult<()> {
+    let user_id = current_user(&state)?;
+
+    state
+        .run_db(move |conn| {
+            if !NoteRepository::delete(conn, &user_id, &note_id)? {
+                return Err(DbError::NotFound(format!("Note not found: {}", note_id)));
+            }
+            Ok(())
+        })
+        .await
+}
+
+/// Get the current user's notes on a node, newest first
+#[tauri::command]
+#[specta::specta]
+pub async fn get_notes_for_node(state: State<'_, AppState>, node_id: String) -> CommandResult<Vec<Note>> {
+    let user_id = current_user(&state)?;
+
+    state
+        .run_db(move |conn| NoteRepository::get_for_node(conn, &user_id, &node_id))
+        .await
+}
+
+/// All of the current user's notes, newest first, with the nodes they're on
+#[tauri::command]
+#[specta::specta]
+pub async fn list_notes(state: State<'_, AppState>) -> CommandResult<Vec<NoteListItem>> {
+    let user_id = current_user(&state)?;
+
+    let notes = state
+        .run_db(move |conn| NoteRepository::get_all_for_user(conn, &user_id))
+        .await?;
+
+    let loader = state.content_loader.lock()?;
+    Ok(notes
+        .into_iter()
+        .map(|note| {
+            let node = loader.as_ref().and_then(|l| l.get_node_by_id(&note.node_id));
+            NoteListItem {
+                node_title: node.map(|n| n.title.clone()),
+                node_type: node.map(|n| n.node_type.clone()),
+                note,
+            }
+        })
+        .collect())
+}
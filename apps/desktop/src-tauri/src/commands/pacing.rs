@@ -0,0 +1,82 @@
+use crate::error::{CommandError, CommandResult, ErrorCode};
+use crate::state::AppState;
+use chrono::{Local, NaiveDate};
+use glp_core::pacing::{self, PaceNode, PacePlan, PaceTarget};
+use tauri::State;
+
+/// The active curriculum's nodes in order, with their estimated minutes.
+/// Retired nodes aren't planned.
+fn curriculum_nodes(state: &AppState) -> CommandResult<Vec<PaceNode>> {
+    let loader = state.content_loader.lock()?;
+    let manifest = loader
+        .as_ref()
+        .ok_or_else(|| CommandError::new(ErrorCode::CurriculumNotLoaded, "Content not loaded"))?
+        .get_manifest();
+
+    Ok(manifest
+        .weeks
+        .iter()
+        .flat_map(|week| week.days.iter())
+        .flat_map(|day| day.nodes.iter())
+        .filter(|node| !node.is_deprecated())
+        .map(|node| PaceNode { node_id: node.id.clone(), estimated_minutes: node.estimated_minutes })
+        .collect())
+}
+
+/// Aim to finish the active curriculum by `target_date` (YYYY-MM-DD)
+#[tauri::command]
+#[specta::specta]
+pub async fn set_pace_target(state: State<'_, AppState>, target_date: String) -> CommandResult<PaceTarget> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    let date = NaiveDate::parse_from_str(&target_date, "%Y-%m-%d")
+        .map_err(|e| CommandError::validation(format!("Invalid target date {}: {}", target_date, e)))?;
+    let nodes = curriculum_nodes(&state)?;
+    let today = Local::now().date_naive();
+
+    let target = state
+        .run_db(move |conn| Ok(pacing::set_pace_target(conn, &user_id, Some(&curriculum_id), &nodes, date, today)))
+        .await??;
+    Ok(target)
+}
+
+/// The remaining nodes of the active curriculum spread over the study days
+/// left until its target date, or `None` without a target
+#[tauri::command]
+#[specta::specta]
+pub async fn get_pace_plan(state: State<'_, AppState>) -> CommandResult<Option<PacePlan>> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    let nodes = curriculum_nodes(&state)?;
+    let today = Local::now().date_naive();
+
+    let plan = state
+        .run_db(move |conn| Ok(pacing::pace_plan(conn, &user_id, Some(&curriculum_id), &nodes, today)))
+        .await??;
+    Ok(plan)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn clear_pace_target(state: State<'_, AppState>) -> CommandResult<()> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    state
+        .run_db(move |conn| pacing::clear_pace_target(conn, &user_id, Some(&curriculum_id)))
+        .await
+}
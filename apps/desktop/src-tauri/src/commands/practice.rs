@@ -0,0 +1,242 @@
+use crate::commands::challenge::{challenge_template_dir, connect_runner};
+use crate::commands::review::active_decay_policy;
+use crate::commands::{checkpoint, related, system};
+use crate::dto::practice::{PracticeResult, PracticeView};
+use crate::dto::review::{MasterySkillResponse, ReviewItemResponse};
+use crate::error::{CommandError, CommandResult, ErrorCode};
+use crate::state::AppState;
+use chrono::Utc;
+use content::ChallengeTemplate;
+use glp_core::db::error::DbError;
+use glp_core::db::repos::{MasteryRepository, PracticeRepository, ReviewRepository};
+use glp_core::embeddings;
+use glp_core::gamification::Difficulty;
+use glp_core::models::{Capability, PracticeChallenge, ReviewItem};
+use glp_core::quests::QuestActivity;
+use glp_core::services::GamificationService;
+use glp_core::spaced_repetition::{get_skills_for_practice, score_to_quality};
+use glp_grader::types::GraderConfig;
+use glp_grader::{GeneratedChallenge, GraderError, LLMGrader};
+use tauri::State;
+
+/// Mastery below this makes a skill a practice candidate
+const DEFAULT_PRACTICE_THRESHOLD: f64 = 0.6;
+
+/// Drafts asked for before giving up on one whose solution passes its tests
+const MAX_GENERATION_ATTEMPTS: usize = 2;
+
+/// Template crate shared by every practice challenge
+const PRACTICE_TEMPLATE: &str = "practice";
+
+/// The user's challenge, or not found if it belongs to someone else
+fn owned_challenge(
+    conn: &rusqlite::Connection,
+    user_id: &str,
+    practice_id: &str,
+) -> Result<PracticeChallenge, DbError> {
+    PracticeRepository::get(conn, practice_id)?
+        .filter(|challenge| challenge.user_id == user_id)
+        .ok_or_else(|| DbError::NotFound(format!("Practice challenge not found: {}", practice_id)))
+}
+
+/// The skill's display name and the text of the lecture that best covers it
+async fn skill_context(state: &AppState, curriculum_id: &str, skill_id: &str) -> CommandResult<(String, String)> {
+    let skill_name = {
+        let loader = state.content_loader.lock()?;
+        let loader = loader
+            .as_ref()
+            .ok_or_else(|| CommandError::new(ErrorCode::CurriculumNotLoaded, "Content not loaded"))?;
+        loader
+            .get_manifest()
+            .skills
+            .iter()
+            .find(|skill| skill.id == skill_id)
+            .map(|skill| skill.name.clone())
+            .ok_or_else(|| CommandError::not_found(format!("Skill not found: {}", skill_id)))?
+    };
+
+    related::ensure_indexed(state, curriculum_id).await?;
+    let (id, skill) = (curriculum_id.to_string(), skill_id.to_string());
+    let lectures = state
+        .run_db(move |conn| embeddings::find_content_for_skill(conn, &id, &skill, 1))
+        .await?;
+
+    let loader = state.content_loader.lock()?;
+    let excerpt = lectures
+        .first()
+        .and_then(|lecture| loader.as_ref()?.get_node_by_id(&lecture.node_id))
+        .and_then(|node| loader.as_ref()?.load_lecture(&node.content_path).ok())
+        .unwrap_or_default();
+    Ok((skill_name, excerpt))
+}
+
+/// Weak skills to practice in the active curriculum, decaying ones first
+#[tauri::command]
+#[specta::specta]
+pub async fn get_practice_skills(
+    state: State<'_, AppState>,
+    threshold: Option<f64>,
+) -> CommandResult<Vec<MasterySkillResponse>> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    let policy = active_decay_policy(&state)?;
+    let threshold = threshold.unwrap_or(DEFAULT_PRACTICE_THRESHOLD);
+
+    state
+        .run_db(move |conn| {
+            let masteries = MasteryRepository::get_all_in_curriculum(conn, &user_id, Some(&curriculum_id))?;
+            Ok(get_skills_for_practice(&masteries, Utc::now(), &policy, threshold)
+                .into_iter()
+                .map(|m| MasterySkillResponse {
+                    skill_id: m.skill_id.clone(),
+                    score: m.score,
+                    level: m.level_description().to_string(),
+                    last_updated: m.last_updated_at.to_rfc3339(),
+                })
+                .collect())
+        })
+        .await
+}
+
+/// Generate a practice challenge for a skill and add it to the review
+/// queue, due now. A draft is only kept once its solution passes its own
+/// tests in the sandbox.
+#[tauri::command]
+#[specta::specta]
+pub async fn generate_practice(
+    state: State<'_, AppState>,
+    skill_id: String,
+    difficulty: String,
+) -> CommandResult<PracticeView> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    let difficulty = difficulty.trim().to_ascii_lowercase();
+    difficulty.parse::<Difficulty>().map_err(CommandError::validation)?;
+
+    let (skill_name, excerpt) = skill_context(&state, &curriculum_id, &skill_id).await?;
+    let credentials = system::grading_credentials()
+        .ok_or_else(|| GraderError::MissingApiKey("the grading provider".to_string()))?;
+    let grader = LLMGrader::with_credentials(&credentials, GraderConfig::default());
+    let runner = connect_runner(&state).await?;
+    let template_dir = challenge_template_dir(state.app_data_dir(), PRACTICE_TEMPLATE, &ChallengeTemplate::default())?;
+
+    let mut verified: Option<GeneratedChallenge> = None;
+    for attempt in 1..=MAX_GENERATION_ATTEMPTS {
+        let generated = grader
+            .generate_practice(&skill_name, &difficulty, &excerpt)
+            .await
+            .map_err(|e| {
+                if checkpoint::is_unreachable(&e) {
+                    state.capabilities.mark_unavailable(Capability::Llm, e.to_string());
+                }
+                CommandError::from(e)
+            })?;
+
+        let source = format!("{}\n\n{}", generated.solution, generated.test_code);
+        let verification = runner.run_verification(&template_dir, &source).await?;
+        if verification.success && verification.tests_total > 0 {
+            verified = Some(generated);
+            break;
+        }
+        tracing::warn!(
+            skill = %skill_id,
+            attempt,
+            tests_passed = verification.tests_passed,
+            tests_total = verification.tests_total,
+            "Generated practice solution failed its tests"
+        );
+    }
+    let generated = verified.ok_or_else(|| {
+        CommandError::new(
+            ErrorCode::LlmError,
+            "Couldn't generate a working practice challenge for this skill. Try again.",
+        )
+    })?;
+
+    let challenge = PracticeChallenge {
+        title: generated.title,
+        instructions: generated.instructions,
+        starter_code: generated.starter_code,
+        test_code: generated.test_code,
+        solution: generated.solution,
+        hints: generated.hints,
+        ..PracticeChallenge::new(user_id.clone(), Some(curriculum_id.clone()), skill_id, difficulty)
+    };
+    let mut review = ReviewItem::new(user_id, challenge.review_id()).with_curriculum(curriculum_id);
+    review.due_date = Utc::now();
+
+    state
+        .run_db(move |conn| {
+            PracticeRepository::create(conn, &challenge)?;
+            ReviewRepository::create_or_update(conn, &review)?;
+            Ok(PracticeView::from(challenge))
+        })
+        .await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_practice_challenge(state: State<'_, AppState>, practice_id: String) -> CommandResult<PracticeView> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    state
+        .run_db(move |conn| Ok(PracticeView::from(owned_challenge(conn, &user_id, &practice_id)?)))
+        .await
+}
+
+/// Run a practice challenge's tests against the learner's code and grade
+/// its review item by the share of tests passed
+#[tauri::command]
+#[specta::specta]
+pub async fn submit_practice(
+    state: State<'_, AppState>,
+    practice_id: String,
+    code: String,
+) -> CommandResult<PracticeResult> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    let uid = user_id.clone();
+    let challenge = state
+        .run_db(move |conn| owned_challenge(conn, &uid, &practice_id))
+        .await?;
+    let runner = connect_runner(&state).await?;
+    let template_dir = challenge_template_dir(state.app_data_dir(), PRACTICE_TEMPLATE, &ChallengeTemplate::default())?;
+    let source = format!("{}\n\n{}", code, challenge.test_code);
+    let verification = runner.run_verification(&template_dir, &source).await?;
+
+    let score_percentage = if verification.tests_total > 0 {
+        verification.tests_passed as f64 / verification.tests_total as f64 * 100.0
+    } else {
+        0.0
+    };
+    let review_id = challenge.review_id();
+
+    state
+        .run_db(move |conn| {
+            let mut review = ReviewRepository::get(conn, &user_id, Some(&curriculum_id), &review_id)?
+                .ok_or_else(|| DbError::NotFound(format!("Review item not found: {}", review_id)))?;
+            review.update_after_review(score_to_quality(score_percentage) as i32);
+            ReviewRepository::create_or_update(conn, &review)?;
+            GamificationService::record_activity(conn, &user_id, QuestActivity::ReviewCompleted)?;
+
+            Ok(PracticeResult { verification, review: ReviewItemResponse::from(review) })
+        })
+        .await
+}
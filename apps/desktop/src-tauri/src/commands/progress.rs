@@ -1,105 +1,148 @@
+use crate::analytics;
+use crate::dto::progress::{BestAttemptData, ProgressData};
+use crate::error::{CommandError, CommandResult};
 use crate::state::AppState;
-use glp_core::db::repos::ProgressRepository;
-use glp_core::models::{NodeProgress, NodeStatus};
-use serde::Serialize;
+use chrono::Utc;
+use glp_core::db::repos::BestAttemptRepository;
+use glp_core::analytics::MAX_EVENT_MINUTES;
+use glp_core::models::{AnalyticsEvent, NodeTime, TimeHistogram};
+use glp_core::services::ProgressService;
 use tauri::State;
 
-#[derive(Serialize)]
-pub struct ProgressData {
-    pub node_id: String,
-    pub status: String,
-    pub attempts: i32,
-    pub time_spent_mins: i32,
-    pub completed: bool,
-}
-
-impl From<NodeProgress> for ProgressData {
-    fn from(progress: NodeProgress) -> Self {
-        Self {
-            node_id: progress.node_id,
-            status: progress.status.as_str().to_string(),
-            attempts: progress.attempts,
-            time_spent_mins: progress.time_spent_mins,
-            completed: progress.status == NodeStatus::Completed,
-        }
-    }
-}
-
 #[tauri::command]
-pub fn get_node_progress(state: State<AppState>, node_id: String) -> Result<Option<ProgressData>, String> {
+#[specta::specta]
+pub async fn get_node_progress(state: State<'_, AppState>, node_id: String) -> CommandResult<Option<ProgressData>> {
     let user_id = state
         .current_user_id
-        .lock()
-        .map_err(|e| e.to_string())?
+        .lock()?
         .clone()
-        .ok_or_else(|| "No user logged in".to_string())?;
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
 
     state
-        .db
-        .with_connection(|conn| {
-            let progress = ProgressRepository::get(conn, &user_id, &node_id)?;
+        .run_db(move |conn| {
+            let progress = ProgressService::get(conn, &user_id, Some(&curriculum_id), &node_id)?;
             Ok(progress.map(ProgressData::from))
         })
-        .map_err(|e| e.to_string())
+        .await
 }
 
 #[tauri::command]
-pub fn get_all_progress(state: State<AppState>) -> Result<Vec<ProgressData>, String> {
+#[specta::specta]
+pub async fn get_all_progress(state: State<'_, AppState>) -> CommandResult<Vec<ProgressData>> {
     let user_id = state
         .current_user_id
-        .lock()
-        .map_err(|e| e.to_string())?
+        .lock()?
         .clone()
-        .ok_or_else(|| "No user logged in".to_string())?;
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
 
     state
-        .db
-        .with_connection(|conn| {
-            let progress_list = ProgressRepository::get_all_for_user(conn, &user_id)?;
+        .run_db(move |conn| {
+            let progress_list = ProgressService::get_all(conn, &user_id, Some(&curriculum_id))?;
             Ok(progress_list.into_iter().map(ProgressData::from).collect())
         })
-        .map_err(|e| e.to_string())
+        .await
 }
 
 #[tauri::command]
-pub fn mark_node_complete(state: State<AppState>, node_id: String) -> Result<ProgressData, String> {
+#[specta::specta]
+pub async fn mark_node_complete(state: State<'_, AppState>, node_id: String) -> CommandResult<ProgressData> {
     let user_id = state
         .current_user_id
-        .lock()
-        .map_err(|e| e.to_string())?
+        .lock()?
         .clone()
-        .ok_or_else(|| "No user logged in".to_string())?;
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    let node_type = state
+        .content_loader
+        .lock()?
+        .as_ref()
+        .and_then(|loader| loader.get_node_by_id(&node_id))
+        .map(|node| node.node_type.clone());
 
-    state
-        .db
-        .with_connection(|conn| {
-            ProgressRepository::mark_completed(conn, &user_id, &node_id)?;
+    let (uid, cid, nid) = (user_id.clone(), curriculum_id.clone(), node_id.clone());
+    let progress = state
+        .run_db(move |conn| ProgressService::mark_complete(conn, &uid, Some(&cid), &nid))
+        .await?;
+    if let Some(node_type) = node_type {
+        let completed = AnalyticsEvent::NodeCompleted {
+            node_id,
+            node_type,
+            minutes: progress.time_spent_mins.clamp(0, MAX_EVENT_MINUTES),
+        };
+        analytics::record(&state, user_id, curriculum_id, completed).await;
+    }
+    Ok(ProgressData::from(progress))
+}
 
-            let progress = ProgressRepository::get(conn, &user_id, &node_id)?
-                .ok_or_else(|| glp_core::db::error::DbError::NotFound("Progress not found".to_string()))?;
+#[tauri::command]
+#[specta::specta]
+pub async fn start_node(state: State<'_, AppState>, node_id: String) -> CommandResult<ProgressData> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
 
+    state
+        .run_db(move |conn| {
+            let progress = ProgressService::start_node(conn, &user_id, Some(&curriculum_id), &node_id)?;
             Ok(ProgressData::from(progress))
         })
-        .map_err(|e| e.to_string())
+        .await
 }
 
+/// Best results at a node: one for a challenge, one per artifact for a
+/// checkpoint. Improving on one earns only the XP it adds.
 #[tauri::command]
-pub fn start_node(state: State<AppState>, node_id: String) -> Result<ProgressData, String> {
+#[specta::specta]
+pub async fn get_best_attempts(state: State<'_, AppState>, node_id: String) -> CommandResult<Vec<BestAttemptData>> {
     let user_id = state
         .current_user_id
-        .lock()
-        .map_err(|e| e.to_string())?
+        .lock()?
         .clone()
-        .ok_or_else(|| "No user logged in".to_string())?;
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
 
     state
-        .db
-        .with_connection(|conn| {
-            let mut progress = NodeProgress::new(user_id.clone(), node_id.clone());
-            progress.start();
-            ProgressRepository::create_or_update(conn, &progress)?;
+        .run_db(move |conn| {
+            let best = BestAttemptRepository::get_for_node(conn, &user_id, Some(&curriculum_id), &node_id)?;
+            Ok(best.into_iter().map(BestAttemptData::from).collect())
+        })
+        .await
+}
 
-            Ok(ProgressData::from(progress))
+/// Record that the learner is active on a lecture or challenge. The page
+/// sends one every `NODE_HEARTBEAT_INTERVAL_SECS` while it has focus and
+/// the learner has interacted recently; gaps over `NODE_IDLE_AFTER_SECS`
+/// aren't counted.
+#[tauri::command]
+#[specta::specta]
+pub async fn record_node_heartbeat(state: State<'_, AppState>, node_id: String) -> CommandResult<NodeTime> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    state
+        .run_db(move |conn| {
+            ProgressService::record_heartbeat(conn, &user_id, Some(&curriculum_id), &node_id, Utc::now())
         })
-        .map_err(|e| e.to_string())
+        .await
+}
+
+/// Active time every profile took on each node of the active curriculum
+/// they completed, bucketed by `bucket_minutes`
+#[tauri::command]
+#[specta::specta]
+pub async fn get_time_histograms(state: State<'_, AppState>, bucket_minutes: u32) -> CommandResult<Vec<TimeHistogram>> {
+    let curriculum_id = state.require_active_curriculum()?;
+
+    state
+        .run_db(move |conn| ProgressService::time_histograms(conn, Some(&curriculum_id), bucket_minutes))
+        .await
 }
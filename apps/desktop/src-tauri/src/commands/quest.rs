@@ -0,0 +1,40 @@
+use crate::dto::quest::{ClaimQuestResult, QuestResponse};
+use crate::error::CommandResult;
+use crate::notifications::emit_gamification_events;
+use crate::state::AppState;
+use glp_core::services::GamificationService;
+use tauri::{AppHandle, State};
+
+/// Get the active daily and weekly quests
+#[tauri::command]
+#[specta::specta]
+pub async fn get_active_quests(state: State<'_, AppState>) -> CommandResult<Vec<QuestResponse>> {
+    let user_id = state.get_current_user_id();
+
+    state.run_db(move |conn| {
+        let quests = GamificationService::active_quests(conn, &user_id)?;
+        Ok(quests.into_iter().map(QuestResponse::from).collect())
+    }).await
+}
+
+/// Claim the XP reward of a completed quest
+#[tauri::command]
+#[specta::specta]
+pub async fn claim_quest_reward(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    quest_id: String,
+) -> CommandResult<ClaimQuestResult> {
+    let user_id = state.get_current_user_id();
+
+    let award = state
+        .run_db(move |conn| GamificationService::claim_quest(conn, &user_id, &quest_id))
+        .await?;
+
+    emit_gamification_events(&app, &award.events);
+    Ok(ClaimQuestResult {
+        xp_earned: award.xp_earned,
+        new_total_xp: award.new_total_xp,
+        new_level: award.new_level,
+    })
+}
@@ -1,192 +1,417 @@
+use crate::analytics;
+use crate::commands::{checkpoint, events, system};
+use crate::dto::quiz::{QuestionReview, QuizAnalytics, QuizAttemptDetail, QuizView, SubmitQuizRequest};
+use crate::error::{CommandError, CommandResult, ErrorCode};
+use crate::notifications::emit_gamification_events;
 use crate::state::AppState;
-use glp_core::db::repos::{MasteryRepository, ProgressRepository, UserRepository};
-use glp_core::gamification::{
-    calculate_level, calculate_quiz_xp, get_retake_multiplier, update_mastery, Difficulty,
+use glp_core::analytics::{event_minutes, event_score};
+use glp_core::db::repos::QuizRepository;
+use glp_core::gamification::XpActivity;
+use glp_core::db::error::DbError;
+use glp_core::models::quiz::{Question, QuestionOption, Quiz};
+use glp_core::models::{
+    AnalyticsEvent, Capability, MistakeExplanation,
 };
-use glp_core::models::quiz::Quiz;
-use glp_core::models::NodeProgress;
-use serde::{Deserialize, Serialize};
+use glp_core::services::{QuizResult, QuizService, QuizStart, QuizSubmission};
+use glp_grader::types::GraderConfig;
+use glp_grader::{GraderError, LLMGrader};
+use chrono::Utc;
 use std::collections::HashMap;
-use tauri::State;
-
-#[derive(Serialize)]
-pub struct QuizResult {
-    pub score: i32,
-    pub total: i32,
-    pub score_percentage: f64,
-    pub passed: bool,
-    pub xp_earned: i32,
-    pub attempt_number: i32,
-    pub mastery_updates: HashMap<String, f64>,
-    pub feedback: Vec<QuestionFeedback>,
+use tauri::{AppHandle, State};
+
+/// Pass mark of content pack quizzes
+const PASSING_SCORE: i32 = 70;
+
+/// A quiz to answer, with its options in the order of the learner's next
+/// attempt. Correct answers and explanations stay here until it's submitted.
+/// Loading a timed quiz starts its clock if it isn't already running.
+#[tauri::command]
+#[specta::specta]
+pub async fn load_quiz(state: State<'_, AppState>, quiz_id: String) -> CommandResult<QuizView> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    let quiz = load_quiz_from_content(&state, &quiz_id)?;
+    let shown = state
+        .run_db(move |conn| Ok(QuizService::serve(conn, &user_id, Some(&curriculum_id), &quiz, Utc::now())))
+        .await??;
+    Ok(QuizView::from(&shown))
 }
 
-#[derive(Serialize)]
-pub struct QuestionFeedback {
-    pub question_id: String,
-    pub user_answer: Option<String>,
-    pub correct_answer: String,
-    pub is_correct: bool,
-    pub explanation: String,
+/// Start a quiz. For a timed quiz this starts the clock the submission is
+/// judged against, unless loading it already did; a running clock isn't
+/// restarted.
+#[tauri::command]
+#[specta::specta]
+pub async fn start_quiz(state: State<'_, AppState>, quiz_id: String) -> CommandResult<QuizStart> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    let quiz = load_quiz_from_content(&state, &quiz_id)?;
+    let start = state
+        .run_db(move |conn| Ok(QuizService::start(conn, &user_id, Some(&curriculum_id), &quiz, Utc::now())))
+        .await??;
+    Ok(start)
 }
 
-#[derive(Deserialize)]
-pub struct SubmitQuizRequest {
-    pub quiz_id: String,
-    pub answers: HashMap<String, String>,
-    pub time_spent_ms: i64,
+#[tauri::command]
+#[specta::specta]
+pub async fn submit_quiz(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    request: SubmitQuizRequest,
+) -> CommandResult<QuizResult> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    let event_multiplier = events::current_multiplier(&state, XpActivity::Quiz);
+    let submitted_at = Utc::now();
+
+    // Answers are given against the options as `load_quiz` showed them
+    let quiz = load_quiz_from_content(&state, &request.quiz_id)?;
+    let minutes = event_minutes(request.time_spent_ms);
+    let (uid, cid, node_id) = (user_id.clone(), curriculum_id.clone(), request.quiz_id.clone());
+    let (result, notifications) = state
+        .run_db(move |conn| {
+            let submission = QuizSubmission { answers: request.answers, time_spent_ms: request.time_spent_ms };
+            Ok(QuizService::submit_shown(
+                conn,
+                &uid,
+                Some(&cid),
+                &quiz,
+                &submission,
+                event_multiplier,
+                submitted_at,
+            ))
+        })
+        .await??;
+
+    emit_gamification_events(&app, &notifications);
+    let submitted = AnalyticsEvent::QuizSubmitted {
+        node_id,
+        score_percent: event_score(result.score_percentage),
+        passed: result.passed,
+        minutes,
+    };
+    analytics::record(&state, user_id, curriculum_id, submitted).await;
+    Ok(result)
 }
 
-pub fn grade_quiz(quiz: &Quiz, answers: &HashMap<String, String>) -> (i32, usize, usize) {
-    let mut score = 0;
-    let mut correct_count = 0;
-    let total = quiz.questions.len();
+/// A missed question, as the explanation prompt needs it
+struct Mistake {
+    quiz_id: String,
+    node_id: String,
+    question: String,
+    chosen_option: String,
+    chosen: String,
+    correct: String,
+}
 
-    for question in &quiz.questions {
-        let user_answer = answers.get(&question.id);
-        let is_correct = user_answer.map(|ans| ans == &question.correct_answer).unwrap_or(false);
+enum MistakeLookup {
+    Explained(MistakeExplanation),
+    Unexplained(Mistake),
+    AnsweredCorrectly,
+}
+
+/// Explain why the learner's answer to a missed question was wrong. The
+/// explanation is kept with the attempt, and reused for anyone who makes the
+/// same mistake on the same question.
+#[tauri::command]
+#[specta::specta]
+pub async fn explain_quiz_mistake(
+    state: State<'_, AppState>,
+    attempt_id: String,
+    question_id: String,
+) -> CommandResult<MistakeExplanation> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    let attempt = {
+        let attempt_id = attempt_id.clone();
+        state
+            .run_db(move |conn| {
+                QuizRepository::get_by_id(conn, &attempt_id)?
+                    .filter(|a| a.user_id == user_id)
+                    .ok_or_else(|| DbError::NotFound(format!("Quiz attempt not found: {}", attempt_id)))
+            })
+            .await?
+    };
+    let quiz = load_quiz_from_content(&state, &attempt.quiz_id)?;
+
+    let lookup = {
+        let question_id = question_id.clone();
+        state
+            .run_db(move |conn| {
+                if let Some(explained) = QuizRepository::get_explanations(conn, &attempt.id)?
+                    .into_iter()
+                    .find(|e| e.question_id == question_id)
+                {
+                    return Ok(MistakeLookup::Explained(explained));
+                }
+
+                let index = quiz
+                    .questions
+                    .iter()
+                    .position(|q| q.id == question_id)
+                    .ok_or_else(|| DbError::NotFound(format!("Question not found: {}", question_id)))?;
+                let question = &quiz.questions[index];
+                let chosen_option = attempt.answers.get(index).cloned().unwrap_or_default();
+                if chosen_option == question.correct_answer {
+                    return Ok(MistakeLookup::AnsweredCorrectly);
+                }
+
+                if let Some(cached) =
+                    QuizRepository::get_cached_explanation(conn, &attempt.quiz_id, &question_id, &chosen_option)?
+                {
+                    let explained = MistakeExplanation::new(attempt.id, question_id, chosen_option, cached).cached();
+                    QuizRepository::save_explanation(conn, &explained)?;
+                    return Ok(MistakeLookup::Explained(explained));
+                }
+
+                let option_text = |id: &str| {
+                    question.options.iter().find(|o| o.id == id).map(|o| o.text.clone()).unwrap_or_else(|| id.to_string())
+                };
+                let mut prompt = question.prompt.clone();
+                if let Some(snippet) = &question.code_snippet {
+                    prompt = format!("{}\n\n```rust\n{}\n```", prompt, snippet);
+                }
+                let mistake = Mistake {
+                    quiz_id: attempt.quiz_id.clone(),
+                    node_id: attempt.node_id.clone(),
+                    question: prompt,
+                    chosen: if chosen_option.is_empty() { "(no answer)".to_string() } else { option_text(&chosen_option) },
+                    correct: option_text(&question.correct_answer),
+                    chosen_option,
+                };
+                Ok(MistakeLookup::Unexplained(mistake))
+            })
+            .await?
+    };
+    let mistake = match lookup {
+        MistakeLookup::Explained(explained) => return Ok(explained),
+        MistakeLookup::Unexplained(mistake) => mistake,
+        MistakeLookup::AnsweredCorrectly => {
+            return Err(CommandError::validation(format!("Question {} was answered correctly", question_id)))
+        }
+    };
 
-        if is_correct {
-            score += question.points;
-            correct_count += 1;
+    let lecture_excerpt = lecture_for_quiz(&state, &mistake.node_id);
+    let explained = match system::grading_credentials() {
+        None => Err(GraderError::MissingApiKey("the grading provider".to_string())),
+        Some(credentials) => {
+            LLMGrader::with_credentials(&credentials, GraderConfig::default())
+                .explain_mistake(&mistake.question, &mistake.chosen, &mistake.correct, &lecture_excerpt)
+                .await
+        }
+    };
+    let explained = explained.map_err(|e| {
+        if checkpoint::is_unreachable(&e) {
+            state.capabilities.mark_unavailable(Capability::Llm, e.to_string());
         }
-    }
+        CommandError::from(e)
+    })?;
 
-    (score, correct_count, total)
+    let explanation = MistakeExplanation::new(attempt_id, question_id, mistake.chosen_option, explained.explanation);
+    state
+        .run_db(move |conn| {
+            QuizRepository::cache_explanation(
+                conn,
+                &mistake.quiz_id,
+                &explanation.question_id,
+                &explanation.chosen_option,
+                &explanation.explanation,
+            )?;
+            QuizRepository::save_explanation(conn, &explanation)?;
+            Ok(explanation)
+        })
+        .await
 }
 
-pub fn generate_feedback(quiz: &Quiz, answers: &HashMap<String, String>) -> Vec<QuestionFeedback> {
-    quiz.questions
-        .iter()
-        .map(|question| {
-            let user_answer = answers.get(&question.id).cloned();
-            let is_correct = user_answer.as_ref().map(|ans| ans == &question.correct_answer).unwrap_or(false);
-
-            QuestionFeedback {
-                question_id: question.id.clone(),
-                user_answer,
-                correct_answer: question.correct_answer.clone(),
-                is_correct,
-                explanation: question.explanation.clone(),
-            }
+/// Explanations kept with a quiz attempt
+#[tauri::command]
+#[specta::specta]
+pub async fn get_quiz_explanations(
+    state: State<'_, AppState>,
+    attempt_id: String,
+) -> CommandResult<Vec<MistakeExplanation>> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    state
+        .run_db(move |conn| {
+            let attempt = QuizRepository::get_by_id(conn, &attempt_id)?
+                .filter(|a| a.user_id == user_id)
+                .ok_or_else(|| DbError::NotFound(format!("Quiz attempt not found: {}", attempt_id)))?;
+            QuizRepository::get_explanations(conn, &attempt.id)
         })
-        .collect()
+        .await
 }
 
+/// A past attempt question by question, for reviewing what was missed
 #[tauri::command]
-pub fn submit_quiz(
-    state: State<AppState>,
-    request: SubmitQuizRequest,
-) -> Result<QuizResult, String> {
+#[specta::specta]
+pub async fn get_quiz_attempt_detail(
+    state: State<'_, AppState>,
+    attempt_id: String,
+) -> CommandResult<QuizAttemptDetail> {
     let user_id = state
         .current_user_id
-        .lock()
-        .map_err(|e| e.to_string())?
+        .lock()?
         .clone()
-        .ok_or_else(|| "No user logged in".to_string())?;
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    let attempt = state
+        .run_db(move |conn| {
+            QuizRepository::get_by_id(conn, &attempt_id)?
+                .filter(|a| a.user_id == user_id)
+                .ok_or_else(|| DbError::NotFound(format!("Quiz attempt not found: {}", attempt_id)))
+        })
+        .await?;
+    let quiz = load_quiz_from_content(&state, &attempt.quiz_id)?;
 
     state
-        .db
-        .with_connection(|conn| {
-            // Load quiz from content system
-            let quiz = load_quiz_from_content(&request.quiz_id)?;
-
-            // Get attempt count
-            let progress = ProgressRepository::get(conn, &user_id, &request.quiz_id)?;
-            let attempt_number = progress.as_ref().map(|p| p.attempts + 1).unwrap_or(1);
-
-            // Grade quiz
-            let (score, _correct_count, _total) = grade_quiz(&quiz, &request.answers);
-            let total_points: i32 = quiz.questions.iter().map(|q| q.points).sum();
-            let score_percentage = (score as f64 / total_points as f64) * 100.0;
-
-            // Parse difficulty
-            let difficulty = match quiz.difficulty.as_str() {
-                "Easy" => Difficulty::Easy,
-                "Medium" => Difficulty::Medium,
-                "Hard" => Difficulty::Hard,
-                "VeryHard" => Difficulty::VeryHard,
-                _ => Difficulty::Easy,
-            };
-
-            // Get user's current streak
-            let user = UserRepository::get_by_id(conn, &user_id)?
-                .ok_or_else(|| glp_core::db::error::DbError::NotFound("User not found".to_string()))?;
-
-            // Calculate XP with retake penalty
-            let base_xp = calculate_quiz_xp(difficulty, score_percentage, user.current_streak as u32);
-            let retake_multiplier = get_retake_multiplier(attempt_number as usize);
-            let xp_earned = (base_xp as f64 * retake_multiplier) as i32;
-
-            // Update mastery for all skills
-            let mut mastery_updates = HashMap::new();
-            for skill_id in &quiz.skills {
-                let current_mastery = MasteryRepository::get(conn, &user_id, skill_id)?
-                    .map(|m| m.score)
-                    .unwrap_or(0.0);
-
-                let performance_multiplier = get_mastery_retake_multiplier(attempt_number as usize);
-                let effective_performance = (score_percentage / 100.0) * performance_multiplier;
-                let new_mastery = update_mastery(current_mastery, effective_performance);
-
-                // Save to DB
-                let mut mastery_score = glp_core::models::MasteryScore::new(user_id.clone(), skill_id.clone());
-                mastery_score.score = new_mastery;
-                MasteryRepository::create_or_update(conn, &mastery_score)?;
-                mastery_updates.insert(skill_id.clone(), new_mastery);
+        .run_db(move |conn| {
+            // Attempts from before answers were kept per question are paired by position
+            let mut answers = QuizRepository::get_answers(conn, &attempt.id)?;
+            if answers.is_empty() {
+                answers = attempt.question_answers(&quiz);
             }
+            let explanations: HashMap<String, String> = QuizRepository::get_explanations(conn, &attempt.id)?
+                .into_iter()
+                .map(|e| (e.question_id, e.explanation))
+                .collect();
 
-            // Update progress
-            let mut progress = progress.unwrap_or_else(|| NodeProgress::new(user_id.clone(), request.quiz_id.clone()));
-            progress.add_time((request.time_spent_ms / 60000) as i32);
-            progress.attempts = attempt_number;
-            
-            let passed = score_percentage >= quiz.passing_score as f64;
-            if passed {
-                progress.complete();
-            } else {
-                progress.fail();
-            }
-            ProgressRepository::create_or_update(conn, &progress)?;
-
-            // Award XP and update level
-            UserRepository::update_xp(conn, &user_id, xp_earned)?;
-            let new_total_xp = user.total_xp + xp_earned;
-            let new_level = calculate_level(new_total_xp);
-            UserRepository::update_level(conn, &user_id, new_level as i32)?;
-
-            // Generate feedback
-            let feedback = generate_feedback(&quiz, &request.answers);
-
-            Ok(QuizResult {
-                score,
-                total: total_points,
-                score_percentage,
-                passed,
-                xp_earned,
-                attempt_number,
-                mastery_updates,
-                feedback,
+            let questions = quiz
+                .questions
+                .iter()
+                .map(|question| {
+                    let answer = answers.iter().find(|a| a.question_id == question.id);
+                    QuestionReview {
+                        question_id: question.id.clone(),
+                        prompt: question.prompt.clone(),
+                        code_snippet: question.code_snippet.clone(),
+                        options: question.options.clone(),
+                        answer: answer.map(|a| a.answer.clone()).filter(|a| !a.is_empty()),
+                        correct_answer: question.correct_answer.clone(),
+                        is_correct: answer.is_some_and(|a| a.is_correct),
+                        explanation: question.explanation.clone(),
+                        mistake_explanation: explanations.get(&question.id).cloned(),
+                    }
+                })
+                .collect();
+
+            Ok(QuizAttemptDetail {
+                quiz_title: quiz.title,
+                questions,
+                attempt,
             })
         })
-        .map_err(|e| e.to_string())
+        .await
 }
 
-fn load_quiz_from_content(quiz_id: &str) -> Result<Quiz, glp_core::db::error::DbError> {
-    // TODO: Load from content system
-    // For now, return a dummy quiz for compilation
-    Ok(Quiz {
-        id: quiz_id.to_string(),
-        title: "Sample Quiz".to_string(),
-        description: "A sample quiz".to_string(),
-        difficulty: "Easy".to_string(),
-        skills: vec!["rust".to_string()],
-        passing_score: 70,
-        time_limit_seconds: None,
-        questions: vec![],
-    })
+/// The questions missed most and accuracy per skill, across all attempts
+/// in the active curriculum
+#[tauri::command]
+#[specta::specta]
+pub async fn get_quiz_analytics(state: State<'_, AppState>, limit: Option<i32>) -> CommandResult<QuizAnalytics> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    state
+        .run_db(move |conn| {
+            Ok(QuizAnalytics {
+                most_missed: QuizRepository::get_most_missed(conn, &user_id, Some(&curriculum_id), limit.unwrap_or(10))?,
+                skill_accuracy: QuizRepository::get_skill_accuracy(conn, &user_id, Some(&curriculum_id))?,
+            })
+        })
+        .await
 }
 
-fn get_mastery_retake_multiplier(attempt_number: usize) -> f64 {
-    glp_core::gamification::get_mastery_retake_multiplier(attempt_number)
+/// Markdown of the lectures a quiz builds on, or nothing if they can't be
+/// loaded; the explanation is still worth having without them
+fn lecture_for_quiz(state: &AppState, node_id: &str) -> String {
+    let Ok(loader) = state.content_loader.lock() else {
+        return String::new();
+    };
+    let Some(loader) = loader.as_ref() else {
+        return String::new();
+    };
+    let Some(node) = loader.get_node_by_id(node_id) else {
+        return String::new();
+    };
+
+    node.prerequisites
+        .iter()
+        .filter_map(|id| loader.get_node_by_id(id))
+        .filter(|prereq| XpActivity::from_node_type(&prereq.node_type) == Some(XpActivity::Lecture))
+        .filter_map(|lecture| loader.load_lecture(&lecture.content_path).ok())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// The quiz of a quiz node in the active curriculum, ready to grade. Pack
+/// questions refer to options by position, so an option's id is its index.
+fn load_quiz_from_content(state: &AppState, quiz_id: &str) -> CommandResult<Quiz> {
+    let loader = state.content_loader.lock()?;
+    let loader = loader
+        .as_ref()
+        .ok_or_else(|| CommandError::new(ErrorCode::CurriculumNotLoaded, "Content not loaded"))?;
+    let node = loader
+        .get_node_by_id(quiz_id)
+        .filter(|node| node.node_type == "quiz")
+        .ok_or_else(|| CommandError::not_found(format!("Quiz not found: {}", quiz_id)))?;
+    let quiz = loader.load_quiz(&node.content_path)?;
+
+    Ok(Quiz {
+        id: node.id.clone(),
+        title: quiz.title,
+        description: node.description.clone(),
+        difficulty: node.difficulty.clone(),
+        skills: node.skills.clone(),
+        passing_score: PASSING_SCORE,
+        time_limit_seconds: quiz.time_limit_seconds.map(|s| s.min(i32::MAX as u32) as i32),
+        late_policy: Default::default(),
+        questions: quiz
+            .questions
+            .into_iter()
+            .map(|question| Question {
+                correct_answer: question.answer_key(),
+                id: question.id,
+                question_type: question.question_type,
+                prompt: question.question,
+                code_snippet: None,
+                options: question
+                    .options
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, text)| QuestionOption { id: index.to_string(), text })
+                    .collect(),
+                explanation: question.explanation,
+                points: 1,
+                skills: question.skills,
+            })
+            .collect(),
+    })
 }
@@ -0,0 +1,212 @@
+use crate::commands::{checkpoint, system};
+use crate::dto::related::RelatedNode;
+use crate::error::CommandResult;
+use crate::state::AppState;
+use content::{encryption, ContentLoader};
+use glp_core::db::repos::EmbeddingRepository;
+use glp_core::embeddings::{self, RelatedContent, LOCAL_MODEL};
+use glp_core::gamification::XpActivity;
+use glp_core::models::{Capability, ContentEmbedding, EmbeddedKind};
+use glp_grader::types::GraderConfig;
+use glp_grader::LLMGrader;
+use tauri::State;
+
+/// Suggestions returned when the caller doesn't say how many
+const DEFAULT_LIMIT: usize = 3;
+
+/// Lecture text beyond this isn't embedded; embedding models cap their input
+const MAX_EMBED_CHARS: usize = 8000;
+
+/// Texts sent to the provider per request
+const EMBED_BATCH_SIZE: usize = 100;
+
+/// A lecture or quiz question waiting to be embedded
+pub(crate) struct IndexItem {
+    item_id: String,
+    node_id: String,
+    kind: EmbeddedKind,
+    skills: Vec<String>,
+    text: String,
+}
+
+/// Everything in a curriculum worth embedding. Content that fails to load
+/// is skipped; validation has already reported it at import.
+pub(crate) fn collect_items(loader: &ContentLoader) -> Vec<IndexItem> {
+    let mut items = Vec::new();
+    let nodes = loader.get_manifest().weeks.iter().flat_map(|w| &w.days).flat_map(|d| &d.nodes);
+    for node in nodes {
+        // The index is kept in the clear, so sealed content stays out of it
+        if encryption::is_sealed(&node.content_path) {
+            continue;
+        }
+        match XpActivity::from_node_type(&node.node_type) {
+            Some(XpActivity::Lecture) => {
+                let Ok(markdown) = loader.load_lecture(&node.content_path) else {
+                    continue;
+                };
+                items.push(IndexItem {
+                    item_id: node.id.clone(),
+                    node_id: node.id.clone(),
+                    kind: EmbeddedKind::Lecture,
+                    skills: node.skills.clone(),
+                    text: format!("{}\n\n{}", node.title, markdown).chars().take(MAX_EMBED_CHARS).collect(),
+                });
+            }
+            Some(XpActivity::Quiz) => {
+                let Ok(quiz) = loader.load_quiz(&node.content_path) else {
+                    continue;
+                };
+                for question in quiz.questions {
+                    items.push(IndexItem {
+                        item_id: format!("{}/{}", node.id, question.id),
+                        node_id: node.id.clone(),
+                        kind: EmbeddedKind::QuizQuestion,
+                        skills: if question.skills.is_empty() { node.skills.clone() } else { question.skills },
+                        text: format!("{}\n{}\n{}", question.question, question.options.join("\n"), question.explanation),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    items
+}
+
+/// Embed with the grading provider where it offers embeddings, otherwise
+/// with the local model. Returns the model name with the vectors.
+async fn embed(state: &AppState, texts: &[String]) -> (String, Vec<Vec<f32>>) {
+    let remote = system::grading_credentials()
+        .and_then(|credentials| credentials.provider.embedding_model().map(|model| (credentials, model)));
+    if let Some((credentials, model)) = remote {
+        let grader = LLMGrader::with_credentials(&credentials, GraderConfig::default());
+        let mut vectors = Vec::with_capacity(texts.len());
+        let mut failed = None;
+        for batch in texts.chunks(EMBED_BATCH_SIZE) {
+            match grader.embed(model, batch).await {
+                Ok(batch_vectors) => vectors.extend(batch_vectors),
+                Err(e) => {
+                    failed = Some(e);
+                    break;
+                }
+            }
+        }
+        match failed {
+            None => return (model.to_string(), vectors),
+            Some(e) => {
+                if checkpoint::is_unreachable(&e) {
+                    state.capabilities.mark_unavailable(Capability::Llm, e.to_string());
+                }
+                tracing::warn!(error = %e, "Provider embeddings failed, using the local model");
+            }
+        }
+    }
+
+    (LOCAL_MODEL.to_string(), texts.iter().map(|text| embeddings::embed_local(text)).collect())
+}
+
+/// Embed a curriculum's content and replace its index
+pub(crate) async fn index_curriculum(
+    state: &AppState,
+    curriculum_id: String,
+    items: Vec<IndexItem>,
+) -> CommandResult<usize> {
+    let texts: Vec<String> = items.iter().map(|item| item.text.clone()).collect();
+    let (model, vectors) = embed(state, &texts).await;
+
+    let embeddings: Vec<ContentEmbedding> = items
+        .into_iter()
+        .zip(vectors)
+        .map(|(item, vector)| {
+            ContentEmbedding::new(
+                curriculum_id.clone(),
+                item.item_id,
+                item.node_id,
+                item.kind,
+                item.skills,
+                model.clone(),
+                vector,
+            )
+        })
+        .collect();
+    let count = embeddings.len();
+
+    tracing::info!(curriculum = %curriculum_id, items = count, model = %model, "Indexed content");
+    state
+        .run_db(move |conn| EmbeddingRepository::replace_for_curriculum(conn, &curriculum_id, &embeddings))
+        .await?;
+    Ok(count)
+}
+
+/// Index the active curriculum if it was imported before indexing existed
+/// or its index was lost, e.g. to an undone delete
+pub(crate) async fn ensure_indexed(state: &AppState, curriculum_id: &str) -> CommandResult<()> {
+    let id = curriculum_id.to_string();
+    let indexed = state
+        .run_db(move |conn| Ok(!EmbeddingRepository::get_for_curriculum(conn, &id)?.is_empty()))
+        .await?;
+    if indexed {
+        return Ok(());
+    }
+
+    let items = match state.content_loader.lock()?.as_ref() {
+        Some(loader) => collect_items(loader),
+        None => return Ok(()),
+    };
+    index_curriculum(state, curriculum_id.to_string(), items).await?;
+    Ok(())
+}
+
+fn with_titles(state: &AppState, related: Vec<RelatedContent>) -> CommandResult<Vec<RelatedNode>> {
+    let loader = state.content_loader.lock()?;
+    Ok(related
+        .into_iter()
+        .filter_map(|r| {
+            let node = loader.as_ref()?.get_node_by_id(&r.node_id)?;
+            Some(RelatedNode {
+                node_id: r.node_id,
+                title: node.title.clone(),
+                node_type: node.node_type.clone(),
+                kind: r.kind,
+                score: r.score,
+            })
+        })
+        .collect())
+}
+
+/// Lectures and quizzes most like a node
+#[tauri::command]
+#[specta::specta]
+pub async fn find_related(
+    state: State<'_, AppState>,
+    node_id: String,
+    limit: Option<usize>,
+) -> CommandResult<Vec<RelatedNode>> {
+    let curriculum_id = state.require_active_curriculum()?;
+    ensure_indexed(&state, &curriculum_id).await?;
+
+    let related = state
+        .run_db(move |conn| {
+            embeddings::find_related(conn, &curriculum_id, &node_id, limit.unwrap_or(DEFAULT_LIMIT))
+        })
+        .await?;
+    with_titles(&state, related)
+}
+
+/// Lectures to review for a skill, e.g. when its mastery drops
+#[tauri::command]
+#[specta::specta]
+pub async fn find_content_for_skill(
+    state: State<'_, AppState>,
+    skill_id: String,
+    limit: Option<usize>,
+) -> CommandResult<Vec<RelatedNode>> {
+    let curriculum_id = state.require_active_curriculum()?;
+    ensure_indexed(&state, &curriculum_id).await?;
+
+    let related = state
+        .run_db(move |conn| {
+            embeddings::find_content_for_skill(conn, &curriculum_id, &skill_id, limit.unwrap_or(DEFAULT_LIMIT))
+        })
+        .await?;
+    with_titles(&state, related)
+}
@@ -0,0 +1,42 @@
+use crate::dto::reminders::NotificationSchedule;
+use crate::error::CommandResult;
+use crate::state::AppState;
+use chrono::Local;
+use glp_core::db::repos::NotificationRepository;
+use glp_core::reminders::{self, NotificationPrefs};
+use tauri::State;
+
+async fn load_schedule(state: &AppState) -> CommandResult<NotificationSchedule> {
+    let user_id = state.get_current_user_id();
+    let curriculum_id = state.get_active_curriculum_id();
+
+    state
+        .run_db(move |conn| {
+            Ok(NotificationSchedule {
+                prefs: NotificationRepository::get_prefs(conn, &user_id)?,
+                upcoming: reminders::upcoming_reminders(conn, &user_id, curriculum_id.as_deref(), &Local::now())?,
+            })
+        })
+        .await
+}
+
+/// Reminder preferences and when the next reminders will be sent
+#[tauri::command]
+#[specta::specta]
+pub async fn get_notification_schedule(state: State<'_, AppState>) -> CommandResult<NotificationSchedule> {
+    load_schedule(&state).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn update_notification_prefs(
+    state: State<'_, AppState>,
+    prefs: NotificationPrefs,
+) -> CommandResult<NotificationSchedule> {
+    let user_id = state.get_current_user_id();
+    state
+        .run_db(move |conn| Ok(reminders::save_prefs(conn, &user_id, &prefs)))
+        .await??;
+
+    load_schedule(&state).await
+}
@@ -1,81 +1,124 @@
 use glp_core::{
-    db::repos::{ReviewRepository, MasteryRepository},
-    models::ReviewItem,
-    spaced_repetition::{apply_mastery_decay, score_to_quality},
+    anki::{anki_deck, import},
+    db::repos::{FlashcardRepository, MasteryHistoryRepository, MasteryRepository, ReviewRepository},
+    models::{DecayPolicy, Flashcard, MasteryChangeReason, MasteryHistoryEntry, ReviewItem},
+    quests::QuestActivity,
+    services::GamificationService,
+    spaced_repetition::{self, apply_mastery_decay_with_policy, review_session_seed, score_to_quality},
 };
-use chrono::Utc;
-use serde::{Deserialize, Serialize};
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
 use tauri::State;
+use crate::dto::review::{FlashcardImport, LeechItemResponse, MasteryHistoryPoint, MasterySkillResponse, ReviewItemResponse};
+use crate::error::{CommandError, CommandResult, ErrorCode};
 use crate::state::AppState;
 
-/// Review item for frontend
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ReviewItemResponse {
-    pub quiz_id: String,
-    pub due_date: String,
-    pub ease_factor: f64,
-    pub interval_days: i32,
-    pub repetitions: i32,
-    pub last_reviewed_at: Option<String>,
-}
-
-impl From<ReviewItem> for ReviewItemResponse {
-    fn from(item: ReviewItem) -> Self {
-        Self {
-            quiz_id: item.quiz_id,
-            due_date: item.due_date.to_rfc3339(),
-            ease_factor: item.ease_factor,
-            interval_days: item.interval_days,
-            repetitions: item.repetitions,
-            last_reviewed_at: item.last_reviewed_at.map(|d| d.to_rfc3339()),
-        }
-    }
-}
-
-/// Get all due reviews for the user
+/// Get all due reviews for the user in the active curriculum
 #[tauri::command]
-pub fn get_due_reviews(state: State<AppState>) -> Result<Vec<ReviewItemResponse>, String> {
+#[specta::specta]
+pub async fn get_due_reviews(state: State<'_, AppState>) -> CommandResult<Vec<ReviewItemResponse>> {
     let user_id = state.get_current_user_id();
+    let curriculum_id = state.require_active_curriculum()?;
 
-    state.db.with_connection(|conn| {
-        let due_reviews = ReviewRepository::get_due_reviews(conn, &user_id)?;
+    state.run_db(move |conn| {
+        let due_reviews = ReviewRepository::get_due_reviews(conn, &user_id, Some(&curriculum_id))?;
         Ok(due_reviews.into_iter().map(ReviewItemResponse::from).collect())
-    }).map_err(|e| e.to_string())
+    }).await
+}
+
+/// Build today's review session: at most `max_items` due reviews of the
+/// active curriculum, interleaved across skills and most pressing first
+#[tauri::command]
+#[specta::specta]
+pub async fn build_review_session(
+    state: State<'_, AppState>,
+    max_items: u32,
+) -> CommandResult<Vec<ReviewItemResponse>> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    let node_skills: HashMap<String, String> = {
+        let loader = state.content_loader.lock()?;
+        let loader = loader
+            .as_ref()
+            .ok_or_else(|| CommandError::new(ErrorCode::CurriculumNotLoaded, "Content not loaded"))?;
+        loader
+            .get_all_node_ids()
+            .into_iter()
+            .filter_map(|id| {
+                let skill = loader.get_node_by_id(&id)?.skills.first()?.clone();
+                Some((id, skill))
+            })
+            .collect()
+    };
+
+    state.run_db(move |conn| {
+        let now = Utc::now();
+        let due = ReviewRepository::get_due_reviews(conn, &user_id, Some(&curriculum_id))?;
+        let mut card_skills = HashMap::new();
+        for item in &due {
+            if let Some(id) = Flashcard::id_from_review(&item.quiz_id) {
+                if let Some(card) = FlashcardRepository::get(conn, id)? {
+                    card_skills.insert(item.quiz_id.clone(), card.skill_id);
+                }
+            }
+        }
+
+        let session = spaced_repetition::build_review_session(
+            &due,
+            |item| node_skills.get(&item.quiz_id).or_else(|| card_skills.get(&item.quiz_id)).cloned(),
+            now,
+            max_items as usize,
+            review_session_seed(&user_id, now),
+        );
+        Ok(session.into_iter().map(ReviewItemResponse::from).collect())
+    }).await
 }
 
 /// Get count of due reviews
 #[tauri::command]
-pub fn get_due_review_count(state: State<AppState>) -> Result<i32, String> {
+#[specta::specta]
+pub async fn get_due_review_count(state: State<'_, AppState>) -> CommandResult<i32> {
     let user_id = state.get_current_user_id();
+    let curriculum_id = state.require_active_curriculum()?;
 
-    state.db.with_connection(|conn| {
-        ReviewRepository::count_due_reviews(conn, &user_id)
-    }).map_err(|e| e.to_string())
+    state.run_db(move |conn| {
+        ReviewRepository::count_due_reviews(conn, &user_id, Some(&curriculum_id))
+    }).await
 }
 
-/// Get all reviews for the user (due and upcoming)
+/// Get all reviews for the user in the active curriculum (due and upcoming)
 #[tauri::command]
-pub fn get_all_reviews(state: State<AppState>) -> Result<Vec<ReviewItemResponse>, String> {
+#[specta::specta]
+pub async fn get_all_reviews(state: State<'_, AppState>) -> CommandResult<Vec<ReviewItemResponse>> {
     let user_id = state.get_current_user_id();
+    let curriculum_id = state.require_active_curriculum()?;
 
-    state.db.with_connection(|conn| {
-        let reviews = ReviewRepository::get_all_for_user(conn, &user_id)?;
+    state.run_db(move |conn| {
+        let reviews = ReviewRepository::get_all_in_curriculum(conn, &user_id, Some(&curriculum_id))?;
         Ok(reviews.into_iter().map(ReviewItemResponse::from).collect())
-    }).map_err(|e| e.to_string())
+    }).await
 }
 
 /// Submit a review result
 #[tauri::command]
-pub fn submit_review(
-    state: State<AppState>,
+#[specta::specta]
+pub async fn submit_review(
+    state: State<'_, AppState>,
     quiz_id: String,
     score_percentage: f64,
-) -> Result<ReviewItemResponse, String> {
+) -> CommandResult<ReviewItemResponse> {
     let user_id = state.get_current_user_id();
+    let curriculum_id = state.require_active_curriculum()?;
 
-    state.db.with_connection(|conn| {
+    state.run_db(move |conn| {
         // Get existing review item
-        let mut review = ReviewRepository::get(conn, &user_id, &quiz_id)?
+        let mut review = ReviewRepository::get(conn, &user_id, Some(&curriculum_id), &quiz_id)?
             .ok_or_else(|| glp_core::DbError::NotFound(format!("Review item not found: {}", quiz_id)))?;
 
         // Convert score to quality and update
@@ -84,64 +127,157 @@ pub fn submit_review(
 
         // Save updated review
         ReviewRepository::create_or_update(conn, &review)?;
+        GamificationService::record_activity(conn, &user_id, QuestActivity::ReviewCompleted)?;
 
         Ok(ReviewItemResponse::from(review))
-    }).map_err(|e| e.to_string())
+    }).await
 }
 
 /// Create a review item for a quiz (called after completing a quiz)
 #[tauri::command]
-pub fn create_review_item(
-    state: State<AppState>,
+#[specta::specta]
+pub async fn create_review_item(
+    state: State<'_, AppState>,
     quiz_id: String,
-) -> Result<ReviewItemResponse, String> {
+) -> CommandResult<ReviewItemResponse> {
     let user_id = state.get_current_user_id();
+    let curriculum_id = state.require_active_curriculum()?;
 
-    state.db.with_connection(|conn| {
+    state.run_db(move |conn| {
         // Check if already exists
-        if let Some(existing) = ReviewRepository::get(conn, &user_id, &quiz_id)? {
+        if let Some(existing) = ReviewRepository::get(conn, &user_id, Some(&curriculum_id), &quiz_id)? {
             return Ok(ReviewItemResponse::from(existing));
         }
 
         // Create new review item
-        let review = ReviewItem::new(user_id.clone(), quiz_id);
+        let review = ReviewItem::new(user_id.clone(), quiz_id).with_curriculum(curriculum_id);
+        ReviewRepository::create_or_update(conn, &review)?;
+
+        Ok(ReviewItemResponse::from(review))
+    }).await
+}
+
+/// Get review items suspended as leeches
+#[tauri::command]
+#[specta::specta]
+pub async fn get_leech_items(state: State<'_, AppState>) -> CommandResult<Vec<LeechItemResponse>> {
+    let user_id = state.get_current_user_id();
+    let curriculum_id = state.require_active_curriculum()?;
+
+    let leeches = state.run_db(move |conn| {
+        ReviewRepository::get_leech_items(conn, &user_id, Some(&curriculum_id))
+    }).await?;
+
+    let loader = state.content_loader.lock()?;
+
+    Ok(leeches
+        .into_iter()
+        .map(|item| {
+            // Point back at the lecture the quiz builds on
+            let lecture_node_id = loader.as_ref().and_then(|l| {
+                l.get_node_by_id(&item.quiz_id)?
+                    .prerequisites
+                    .iter()
+                    .find(|id| {
+                        l.get_node_by_id(id)
+                            .map(|n| n.node_type == "lecture")
+                            .unwrap_or(false)
+                    })
+                    .cloned()
+            });
+
+            LeechItemResponse {
+                review: ReviewItemResponse::from(item),
+                lecture_node_id,
+            }
+        })
+        .collect())
+}
+
+/// Return a suspended leech to the review queue
+#[tauri::command]
+#[specta::specta]
+pub async fn unsuspend_review(
+    state: State<'_, AppState>,
+    quiz_id: String,
+) -> CommandResult<ReviewItemResponse> {
+    let user_id = state.get_current_user_id();
+    let curriculum_id = state.require_active_curriculum()?;
+
+    state.run_db(move |conn| {
+        let mut review = ReviewRepository::get(conn, &user_id, Some(&curriculum_id), &quiz_id)?
+            .ok_or_else(|| glp_core::DbError::NotFound(format!("Review item not found: {}", quiz_id)))?;
+
+        review.unsuspend();
         ReviewRepository::create_or_update(conn, &review)?;
 
         Ok(ReviewItemResponse::from(review))
-    }).map_err(|e| e.to_string())
+    }).await
 }
 
-/// Apply mastery decay on app startup
+/// Apply mastery decay on app startup, across every curriculum
 #[tauri::command]
-pub fn apply_mastery_decay_on_startup(state: State<AppState>) -> Result<i32, String> {
+#[specta::specta]
+pub async fn apply_mastery_decay_on_startup(state: State<'_, AppState>) -> CommandResult<i32> {
+    apply_mastery_decay(&state).await
+}
+
+/// Decay the current user's mastery scores, recording each change in the
+/// mastery history. Returns how many skills decayed.
+pub(crate) async fn apply_mastery_decay(state: &AppState) -> CommandResult<i32> {
     let user_id = state.get_current_user_id();
+    let policy = active_decay_policy(state)?;
 
-    state.db.with_connection(|conn| {
+    state.run_db(move |conn| {
         // Get all masteries
         let mut masteries = MasteryRepository::get_all_for_user(conn, &user_id)?;
+        let previous_scores: Vec<f64> = masteries.iter().map(|m| m.score).collect();
 
         // Apply decay
-        let decayed_count = apply_mastery_decay(&mut masteries, Utc::now());
+        let decayed_count = apply_mastery_decay_with_policy(&mut masteries, Utc::now(), &policy);
 
         // Update database with decayed scores
-        for mastery in &masteries {
+        for (mastery, previous_score) in masteries.iter().zip(previous_scores) {
             MasteryRepository::create_or_update(conn, mastery)?;
+
+            if (mastery.score - previous_score).abs() > 0.001 {
+                let mut entry = MasteryHistoryEntry::from_change(mastery, previous_score, MasteryChangeReason::Decay);
+                entry.recorded_at = Utc::now();
+                MasteryHistoryRepository::record(conn, &entry)?;
+            }
         }
 
         Ok(decayed_count as i32)
-    }).map_err(|e| e.to_string())
+    }).await
+}
+
+/// Decay policy of the active curriculum, falling back to the platform defaults
+pub(crate) fn active_decay_policy(state: &AppState) -> CommandResult<DecayPolicy> {
+    let loader = state.content_loader.lock()?;
+
+    Ok(loader
+        .as_ref()
+        .and_then(|l| l.get_manifest().mastery_decay)
+        .map(|config| DecayPolicy {
+            grace_period_days: config.grace_period_days,
+            decay_rate: config.decay_rate,
+            minimum_score: config.minimum_score,
+        })
+        .unwrap_or_default())
 }
 
 /// Get mastery scores that need attention (below threshold)
 #[tauri::command]
-pub fn get_low_mastery_skills(
-    state: State<AppState>,
+#[specta::specta]
+pub async fn get_low_mastery_skills(
+    state: State<'_, AppState>,
     threshold: f64,
-) -> Result<Vec<MasterySkillResponse>, String> {
+) -> CommandResult<Vec<MasterySkillResponse>> {
     let user_id = state.get_current_user_id();
+    let curriculum_id = state.require_active_curriculum()?;
 
-    state.db.with_connection(|conn| {
-        let masteries = MasteryRepository::get_all_for_user(conn, &user_id)?;
+    state.run_db(move |conn| {
+        let masteries = MasteryRepository::get_all_in_curriculum(conn, &user_id, Some(&curriculum_id))?;
 
         let low_skills: Vec<MasterySkillResponse> = masteries
             .into_iter()
@@ -158,13 +294,180 @@ pub fn get_low_mastery_skills(
             .collect();
 
         Ok(low_skills)
-    }).map_err(|e| e.to_string())
+    }).await
+}
+
+/// Get a skill's mastery history over the last `days` days for charting
+#[tauri::command]
+#[specta::specta]
+pub async fn get_mastery_history(
+    state: State<'_, AppState>,
+    skill_id: String,
+    days: i64,
+) -> CommandResult<Vec<MasteryHistoryPoint>> {
+    let user_id = state.get_current_user_id();
+    let to = Utc::now();
+    let from = to - Duration::days(days.max(1));
+
+    state.run_db(move |conn| {
+        let history = MasteryHistoryRepository::get_mastery_history(conn, &user_id, &skill_id, from, to)?;
+
+        Ok(history
+            .into_iter()
+            .map(|entry| MasteryHistoryPoint {
+                previous_score: entry.previous_score,
+                score: entry.score,
+                reason: entry.reason.as_str().to_string(),
+                recorded_at: entry.recorded_at.to_rfc3339(),
+            })
+            .collect())
+    }).await
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MasterySkillResponse {
-    pub skill_id: String,
-    pub score: f64,
-    pub level: String,
-    pub last_updated: String,
+/// Export the active curriculum's review items and the user's notes as a
+/// file for Anki's "Import File", named after the curriculum
+#[tauri::command]
+#[specta::specta]
+pub async fn export_anki_deck(state: State<'_, AppState>, path: String) -> CommandResult<()> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    let mut deck = state
+        .run_db(move |conn| anki_deck(conn, &user_id, Some(&curriculum_id)))
+        .await?;
+    let deck_name = {
+        let loader = state.content_loader.lock()?;
+        let loader = loader
+            .as_ref()
+            .ok_or_else(|| CommandError::new(ErrorCode::CurriculumNotLoaded, "Content not loaded"))?;
+        deck.describe(
+            |quiz_id| {
+                let node = loader.get_node_by_id(quiz_id)?;
+                let quiz = loader.load_quiz(&node.content_path).ok()?;
+                Some(quiz_card_text(&quiz))
+            },
+            |node_id| loader.get_node_by_id(node_id).map(|node| node.title.clone()),
+        );
+        loader.get_manifest().title.clone()
+    };
+
+    fs::write(&path, deck.to_anki_text(&deck_name))?;
+    tracing::info!(cards = deck.cards.len(), "Exported Anki deck");
+    Ok(())
+}
+
+/// Import flashcards from a CSV file or an Anki text export into the review
+/// queue, under a skill of the active curriculum. A dry run checks the file
+/// and reports what would be imported without storing anything.
+#[tauri::command]
+#[specta::specta]
+pub async fn import_flashcards(
+    state: State<'_, AppState>,
+    path: String,
+    skill_id: String,
+    dry_run: bool,
+) -> CommandResult<FlashcardImport> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    {
+        let loader = state.content_loader.lock()?;
+        let loader = loader
+            .as_ref()
+            .ok_or_else(|| CommandError::new(ErrorCode::CurriculumNotLoaded, "Content not loaded"))?;
+        if !loader.get_manifest().skills.iter().any(|skill| skill.id == skill_id) {
+            return Err(CommandError::not_found(format!("Skill not found: {}", skill_id)));
+        }
+    }
+
+    let text = fs::read_to_string(&path)?;
+    let deck = import::parse_deck(&text)?;
+    let source = Path::new(&path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or(path);
+
+    let (cards, duplicates) = state
+        .run_db(move |conn| {
+            if dry_run {
+                return import::split_new(conn, &user_id, Some(&curriculum_id), &skill_id, &deck.cards);
+            }
+            let summary =
+                import::import_cards(conn, &user_id, Some(&curriculum_id), &skill_id, &source, &deck.cards, Utc::now())?;
+            let imported = deck
+                .cards
+                .into_iter()
+                .filter(|card| !summary.duplicates.contains(card))
+                .collect();
+            Ok((imported, summary.duplicates))
+        })
+        .await?;
+
+    if !dry_run {
+        tracing::info!(cards = cards.len(), duplicates = duplicates.len(), "Imported flashcards");
+    }
+    Ok(FlashcardImport { dry_run, cards, duplicates, issues: deck.issues })
+}
+
+/// An imported flashcard, by the id in its review item
+#[tauri::command]
+#[specta::specta]
+pub async fn get_flashcard(state: State<'_, AppState>, review_id: String) -> CommandResult<Flashcard> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    state
+        .run_db(move |conn| {
+            let card = match Flashcard::id_from_review(&review_id) {
+                Some(id) => FlashcardRepository::get(conn, id)?,
+                None => None,
+            };
+            card.filter(|card| card.user_id == user_id)
+                .ok_or_else(|| glp_core::DbError::NotFound(format!("Flashcard not found: {}", review_id)))
+        })
+        .await
+}
+
+/// A quiz as one card: its questions and options on the front, the answers
+/// and explanations on the back
+fn quiz_card_text(quiz: &content::Quiz) -> (String, String) {
+    let mut front = quiz.title.clone();
+    let mut back = String::new();
+    for (number, question) in quiz.questions.iter().enumerate() {
+        front.push_str(&format!("\n\n{}. {}", number + 1, question.question));
+        for (letter, option) in ('a'..='z').zip(&question.options) {
+            front.push_str(&format!("\n   {}) {}", letter, option));
+        }
+
+        let correct: Vec<usize> = question
+            .correct_answers
+            .clone()
+            .or_else(|| question.correct_answer.map(|answer| vec![answer]))
+            .unwrap_or_default();
+        let answers: Vec<String> = correct
+            .iter()
+            .filter_map(|&index| {
+                let letter = ('a'..='z').nth(index)?;
+                Some(format!("{}) {}", letter, question.options.get(index)?))
+            })
+            .collect();
+        if !back.is_empty() {
+            back.push_str("\n\n");
+        }
+        back.push_str(&format!("{}. {}", number + 1, answers.join(", ")));
+        if !question.explanation.is_empty() {
+            back.push_str(&format!("\n{}", question.explanation));
+        }
+    }
+    (front, back)
 }
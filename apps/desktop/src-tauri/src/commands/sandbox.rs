@@ -0,0 +1,233 @@
+use crate::commands::setup::SANDBOX_IMAGE;
+use crate::commands::system;
+use crate::dto::sandbox::SandboxImageStatus;
+use crate::error::{CommandError, CommandResult, ErrorCode};
+use chrono::Utc;
+use glp_core::sandbox::{self, ImageManifest, ImageRelease, SandboxImageAction, SandboxImageState};
+use std::fs;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Duration;
+
+const IMAGE_STATE_FILE: &str = "sandbox_image.json";
+
+/// Tag the image an update replaced is kept under, so pruning dangling
+/// images doesn't take away the rollback
+const PREVIOUS_IMAGE: &str = "gamified-rust-sandbox:previous";
+
+const SWAP_IMAGE: &str = "gamified-rust-sandbox:swap";
+
+const MANIFEST_TIMEOUT_SECS: u64 = 30;
+
+/// Held while the state file or the image tags change, since the background
+/// job and the commands can run at once
+static IMAGE_LOCK: Mutex<()> = Mutex::new(());
+
+fn load_state() -> SandboxImageState {
+    system::get_config_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join(IMAGE_STATE_FILE)).ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &SandboxImageState) -> CommandResult<()> {
+    let config_dir = system::get_config_dir()?;
+    fs::create_dir_all(&config_dir)?;
+    fs::write(config_dir.join(IMAGE_STATE_FILE), serde_json::to_string_pretty(state)?)?;
+    Ok(())
+}
+
+fn docker(args: &[&str]) -> CommandResult<String> {
+    let output = Command::new("docker")
+        .args(args)
+        .output()
+        .map_err(|e| CommandError::new(ErrorCode::DockerError, format!("Failed to run docker: {}", e)))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(CommandError::new(ErrorCode::DockerError, "Docker couldn't update the sandbox image")
+            .with_details(String::from_utf8_lossy(&output.stderr).trim().to_string()))
+    }
+}
+
+fn image_exists(image: &str) -> bool {
+    docker(&["image", "inspect", image]).is_ok()
+}
+
+fn status() -> SandboxImageStatus {
+    SandboxImageStatus {
+        image: SANDBOX_IMAGE.to_string(),
+        present: image_exists(SANDBOX_IMAGE),
+        can_roll_back: image_exists(PREVIOUS_IMAGE),
+        state: load_state(),
+    }
+}
+
+/// Pull a release by digest and check Docker has it under that digest
+fn download(release: &ImageRelease) -> CommandResult<()> {
+    let reference = release.reference();
+    docker(&["pull", &reference])?;
+    let digests = docker(&["image", "inspect", "--format", "{{json .RepoDigests}}", &reference])?;
+    let digests: Vec<String> = serde_json::from_str(&digests).unwrap_or_default();
+    if !digests.contains(&reference) {
+        return Err(CommandError::new(
+            ErrorCode::DockerError,
+            format!("Sandbox image {} doesn't match its published digest", release.version),
+        ));
+    }
+    Ok(())
+}
+
+/// Switch to the downloaded release, keeping the current image as the one
+/// to roll back to
+fn apply(state: &mut SandboxImageState) -> CommandResult<ImageRelease> {
+    let mut next = state.clone();
+    let release = next.apply()?;
+    if image_exists(SANDBOX_IMAGE) {
+        docker(&["tag", SANDBOX_IMAGE, PREVIOUS_IMAGE])?;
+    }
+    docker(&["tag", &release.reference(), SANDBOX_IMAGE])?;
+    *state = next;
+    Ok(release)
+}
+
+fn roll_back(state: &mut SandboxImageState) -> CommandResult<()> {
+    let mut next = state.clone();
+    next.roll_back()?;
+    if !image_exists(PREVIOUS_IMAGE) {
+        return Err(CommandError::new(ErrorCode::InvalidState, "The earlier sandbox image is no longer there"));
+    }
+    docker(&["tag", SANDBOX_IMAGE, SWAP_IMAGE])?;
+    docker(&["tag", PREVIOUS_IMAGE, SANDBOX_IMAGE])?;
+    docker(&["tag", SWAP_IMAGE, PREVIOUS_IMAGE])?;
+    docker(&["rmi", SWAP_IMAGE])?;
+    *state = next;
+    Ok(())
+}
+
+async fn fetch_manifest(url: &str) -> CommandResult<ImageManifest> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(MANIFEST_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| CommandError::internal(e.to_string()))?;
+    let unreachable = |e: reqwest::Error| {
+        CommandError::new(ErrorCode::IoError, "Couldn't fetch the sandbox image manifest").with_details(e.to_string())
+    };
+    let response = client.get(url).send().await.map_err(unreachable)?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(CommandError::new(
+            ErrorCode::IoError,
+            format!("Couldn't fetch the sandbox image manifest (HTTP {})", status.as_u16()),
+        ));
+    }
+    let json = response.text().await.map_err(unreachable)?;
+    Ok(ImageManifest::parse(&json)?)
+}
+
+/// Check the manifest and download a newer release, switching to it if
+/// updates apply automatically. Returns a summary for the job log.
+pub(crate) async fn check_for_update() -> CommandResult<String> {
+    let Some(url) = load_state().manifest_url else {
+        return Ok("No sandbox image manifest is configured".to_string());
+    };
+    let manifest = fetch_manifest(&url).await;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let _guard = IMAGE_LOCK.lock()?;
+        let mut state = load_state();
+        state.last_checked_at = Some(Utc::now());
+        let result = manifest.and_then(|manifest| update(&mut state, &manifest));
+        state.last_error = result.as_ref().err().map(|e| e.message.clone());
+        save_state(&state)?;
+        result
+    })
+    .await
+    .map_err(|e| CommandError::internal(e.to_string()))?
+}
+
+fn update(state: &mut SandboxImageState, manifest: &ImageManifest) -> CommandResult<String> {
+    let Some(release) = state.update_from(manifest)? else {
+        return Ok("The sandbox image is up to date".to_string());
+    };
+    if !system::check_docker_internal().running {
+        return Ok(format!("Sandbox image {} is available, but Docker is not running", release.version));
+    }
+    download(&release)?;
+    state.record_download(release.clone());
+    if state.auto_apply {
+        apply(state)?;
+        Ok(format!("Updated the sandbox image to {}", release.version))
+    } else {
+        Ok(format!("Downloaded sandbox image {}", release.version))
+    }
+}
+
+/// Which sandbox image is installed, and any update waiting to be applied
+#[tauri::command]
+#[specta::specta]
+pub async fn get_sandbox_image_status() -> CommandResult<SandboxImageStatus> {
+    tauri::async_runtime::spawn_blocking(status)
+        .await
+        .map_err(|e| CommandError::internal(e.to_string()))
+}
+
+/// Set where sandbox image updates come from. Digests in `pinned_digests`
+/// are the only ones accepted, unless it's empty.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_sandbox_image_source(
+    manifest_url: Option<String>,
+    pinned_digests: Vec<String>,
+    auto_apply: bool,
+) -> CommandResult<SandboxImageStatus> {
+    let manifest_url = manifest_url.map(|url| url.trim().to_string()).filter(|url| !url.is_empty());
+    if manifest_url.as_ref().is_some_and(|url| !url.starts_with("https://")) {
+        return Err(CommandError::validation("The manifest URL must be an https:// URL"));
+    }
+    if let Some(digest) = pinned_digests.iter().find(|digest| !sandbox::is_digest(digest)) {
+        return Err(CommandError::validation(format!("{} is not a sha256 digest", digest)));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let guard = IMAGE_LOCK.lock()?;
+        let mut state = load_state();
+        state.manifest_url = manifest_url;
+        state.pinned_digests = pinned_digests;
+        state.auto_apply = auto_apply;
+        save_state(&state)?;
+        drop(guard);
+        Ok(status())
+    })
+    .await
+    .map_err(|e| CommandError::internal(e.to_string()))?
+}
+
+/// Check for, apply or roll back a sandbox image update
+#[tauri::command]
+#[specta::specta]
+pub async fn update_sandbox_image(action: SandboxImageAction) -> CommandResult<SandboxImageStatus> {
+    match action {
+        SandboxImageAction::Check => {
+            if load_state().manifest_url.is_none() {
+                return Err(CommandError::validation("Set a sandbox image manifest URL first"));
+            }
+            check_for_update().await?;
+        }
+        SandboxImageAction::Apply | SandboxImageAction::RollBack => {
+            tauri::async_runtime::spawn_blocking(move || {
+                let _guard = IMAGE_LOCK.lock()?;
+                let mut state = load_state();
+                match action {
+                    SandboxImageAction::Apply => apply(&mut state).map(|_| ())?,
+                    _ => roll_back(&mut state)?,
+                }
+                save_state(&state)
+            })
+            .await
+            .map_err(|e| CommandError::internal(e.to_string()))??;
+        }
+    }
+    get_sandbox_image_status().await
+}
@@ -0,0 +1,27 @@
+use crate::error::{CommandError, CommandResult};
+use crate::state::AppState;
+use glp_core::db::repos::SearchRepository;
+use glp_core::models::SearchHit;
+use tauri::State;
+
+const DEFAULT_SEARCH_LIMIT: u32 = 50;
+
+/// Search the current user's notes, quiz answers and grade feedback
+#[tauri::command]
+#[specta::specta]
+pub async fn search_my_history(
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<u32>,
+) -> CommandResult<Vec<SearchHit>> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+
+    state
+        .run_db(move |conn| SearchRepository::search(conn, &user_id, &query, limit))
+        .await
+}
@@ -0,0 +1,39 @@
+use crate::commands::system;
+use crate::dto::settings::SettingsView;
+use crate::error::{CommandError, CommandResult};
+use crate::state::AppState;
+use glp_core::settings::{self};
+use tauri::State;
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_settings(state: State<'_, AppState>) -> CommandResult<SettingsView> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    let settings = state.run_db(move |conn| Ok(settings::load_settings(conn, &user_id))).await??;
+    Ok(SettingsView { settings, grading_provider: system::grading_provider() })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn update_settings(state: State<'_, AppState>, settings: SettingsView) -> CommandResult<SettingsView> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    let SettingsView { settings: updated, grading_provider } = settings;
+    state
+        .run_db(move |conn| Ok(settings::save_settings(conn, &user_id, &updated)))
+        .await??;
+    if grading_provider != system::grading_provider() {
+        system::set_grading_provider(grading_provider)?;
+    }
+
+    get_settings(state).await
+}
@@ -0,0 +1,223 @@
+use crate::commands::{curriculum, system};
+use crate::dto::setup::SetupProgress;
+use crate::error::{CommandError, CommandResult, ErrorCode};
+use crate::state::AppState;
+use chrono::Utc;
+use glp_core::db::repos::UserRepository;
+use glp_core::models::User;
+use glp_core::setup::{SetupAction, SetupError, SetupState, SetupStep, StepOutcome};
+use glp_grader::GraderError;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+use tauri::{AppHandle, Emitter, State};
+use uuid::Uuid;
+
+const SETUP_FILE: &str = "setup_state.json";
+
+/// Event name the frontend listens on for output from long-running steps
+pub const SETUP_PROGRESS_EVENT: &str = "setup://progress";
+
+/// Must match the runner's default `DockerConfig::image_name`
+pub(crate) const SANDBOX_IMAGE: &str = "gamified-rust-sandbox:latest";
+
+const SANDBOX_DOCKERFILE: &str = include_str!("../../../../../prototypes/docker-runner/Dockerfile");
+
+fn load_setup_state() -> SetupState {
+    let saved = system::get_config_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join(SETUP_FILE)).ok())
+        .and_then(|json| serde_json::from_str(&json).ok());
+
+    saved.unwrap_or_else(|| {
+        if system::is_onboarding_complete() {
+            SetupState::completed(Utc::now())
+        } else {
+            SetupState::new(Utc::now())
+        }
+    })
+}
+
+fn save_setup_state(setup: &SetupState) -> CommandResult<()> {
+    let config_dir = system::get_config_dir()?;
+    fs::create_dir_all(&config_dir)?;
+
+    let json = serde_json::to_string_pretty(setup)?;
+    fs::write(config_dir.join(SETUP_FILE), json)?;
+    Ok(())
+}
+
+/// Where first-run setup is up to
+#[tauri::command]
+#[specta::specta]
+pub fn get_setup_state() -> SetupState {
+    load_setup_state()
+}
+
+/// Run or skip the current setup step and return the updated state. A failed
+/// step stays current, so calling again with `Run` retries it.
+#[tauri::command]
+#[specta::specta]
+pub async fn advance_setup_step(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    action: SetupAction,
+) -> CommandResult<SetupState> {
+    let mut setup = load_setup_state();
+
+    match action {
+        SetupAction::Skip => {
+            setup.skip(Utc::now())?;
+        }
+        SetupAction::Run => {
+            let step = setup
+                .current_step()
+                .ok_or(SetupError::AlreadyComplete)?;
+            let outcome = match run_step(&state, &app, step).await {
+                Ok(message) => StepOutcome::Done(message),
+                Err(e) => StepOutcome::Failed(e.message),
+            };
+            setup.record(step, outcome, Utc::now())?;
+        }
+    }
+
+    save_setup_state(&setup)?;
+    if setup.is_complete() {
+        system::mark_onboarding_complete()?;
+    }
+
+    Ok(setup)
+}
+
+async fn run_step(state: &AppState, app: &AppHandle, step: SetupStep) -> CommandResult<Option<String>> {
+    match step {
+        SetupStep::CheckDocker => check_docker(),
+        SetupStep::PrepareSandbox => {
+            let app = app.clone();
+            tauri::async_runtime::spawn_blocking(move || prepare_sandbox(&app))
+                .await
+                .map_err(|e| CommandError::internal(e.to_string()))?
+        }
+        SetupStep::ValidateApiKey => validate_api_key().await,
+        SetupStep::CreateUser => create_user(state).await,
+        SetupStep::ImportCurriculum => import_default_curriculum(state).await,
+    }
+}
+
+fn check_docker() -> CommandResult<Option<String>> {
+    let docker = system::check_docker_internal();
+    if !docker.installed {
+        return Err(CommandError::new(ErrorCode::DockerNotInstalled, "Docker is not installed"));
+    }
+    if !docker.running {
+        return Err(CommandError::new(ErrorCode::DockerNotRunning, "Docker is installed but not running"));
+    }
+    Ok(docker.version.map(|v| format!("Docker {}", v)))
+}
+
+fn emit_progress(app: &AppHandle, step: SetupStep, line: &str) {
+    let progress = SetupProgress { step, line: line.to_string() };
+    if let Err(e) = app.emit(SETUP_PROGRESS_EVENT, progress) {
+        tracing::warn!(error = %e, "Failed to emit setup progress");
+    }
+}
+
+/// Build the sandbox image unless it is already present, streaming the
+/// build output to the frontend
+fn prepare_sandbox(app: &AppHandle) -> CommandResult<Option<String>> {
+    let exists = Command::new("docker")
+        .args(["image", "inspect", SANDBOX_IMAGE])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false);
+    if exists {
+        return Ok(Some(format!("{} is already present", SANDBOX_IMAGE)));
+    }
+
+    // The Dockerfile needs no build context, so it is piped in on stdin
+    let mut child = Command::new("docker")
+        .args(["build", "--progress=plain", "-t", SANDBOX_IMAGE, "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| CommandError::new(ErrorCode::DockerError, format!("Failed to start docker build: {}", e)))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(SANDBOX_DOCKERFILE.as_bytes())?;
+    }
+
+    let mut last_line = String::new();
+    if let Some(stderr) = child.stderr.take() {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            emit_progress(app, SetupStep::PrepareSandbox, &line);
+            last_line = line;
+        }
+    }
+
+    let status = child.wait()?;
+    if status.success() {
+        Ok(Some(format!("Built {}", SANDBOX_IMAGE)))
+    } else {
+        Err(CommandError::new(ErrorCode::DockerError, format!("Sandbox image build failed: {}", last_line)))
+    }
+}
+
+async fn validate_api_key() -> CommandResult<Option<String>> {
+    let credentials = system::grading_credentials()
+        .ok_or_else(|| GraderError::MissingApiKey("the grading provider".to_string()))?;
+    let check = glp_grader::check_credentials(&credentials).await?;
+
+    let provider = check.provider.display_name();
+    if check.model_available {
+        Ok(Some(format!("{} is ready to grade with {}", provider, check.model)))
+    } else {
+        Err(CommandError::new(
+            ErrorCode::LlmError,
+            format!("{} accepted the key, but {} is not available", provider, check.model),
+        ))
+    }
+}
+
+/// Create the learner, or keep the current profile when setup is resumed
+/// after one was created
+async fn create_user(state: &AppState) -> CommandResult<Option<String>> {
+    let current_user_id = state.current_user_id.lock()?.clone();
+
+    let (user, created) = state
+        .run_db(move |conn| {
+            if let Some(id) = current_user_id {
+                if let Some(user) = UserRepository::get_by_id(conn, &id)? {
+                    return Ok((user, false));
+                }
+            }
+            let user = User::new(Uuid::new_v4().to_string());
+            UserRepository::create(conn, &user)?;
+            Ok((user, true))
+        })
+        .await?;
+
+    *state.current_user_id.lock()? = Some(user.id.clone());
+
+    Ok(Some(if created {
+        format!("Created profile {}", user.profile_name())
+    } else {
+        format!("Using profile {}", user.profile_name())
+    }))
+}
+
+async fn import_default_curriculum(state: &AppState) -> CommandResult<Option<String>> {
+    if state.get_active_curriculum_id().is_some() {
+        return Ok(Some("A curriculum is already active".to_string()));
+    }
+
+    let response = curriculum::import_from_path(state, state.default_content_path.clone(), true).await?;
+    if response.success {
+        Ok(None)
+    } else {
+        Err(response.error.unwrap_or_else(|| "Failed to import the default curriculum".to_string()).into())
+    }
+}
@@ -0,0 +1,194 @@
+use crate::dto::stats::CurriculumCompletionStatus;
+use crate::error::{CommandError, CommandResult, ErrorCode};
+use crate::state::AppState;
+use chrono::Utc;
+use glp_core::db::error::DbError;
+use glp_core::db::repos::{ActivityRepository, CertificateRepository, CurriculumRepository, UserRepository};
+use glp_core::models::Certificate;
+use glp_core::stats::{
+    activity_csv, activity_heatmap, curriculum_completion, dashboard_stats, weekly_report, ActivityHeatmap,
+    CompletionWeighting, DashboardStats, NodeWeight, StatsRange,
+};
+use std::fs;
+use std::path::Path;
+use tauri::State;
+
+/// Everything the stats dashboard shows, for a range
+/// ("Week", "Month", "Quarter", "Year" or "AllTime")
+#[tauri::command]
+#[specta::specta]
+pub async fn get_dashboard_stats(state: State<'_, AppState>, range: String) -> CommandResult<DashboardStats> {
+    let range: StatsRange = range.parse()?;
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    state
+        .run_db(move |conn| dashboard_stats(conn, &user_id, Some(&curriculum_id), range, Utc::now()))
+        .await
+}
+
+/// Per-day activity for one calendar year, for the contributions calendar
+#[tauri::command]
+#[specta::specta]
+pub async fn get_activity_heatmap(state: State<'_, AppState>, year: i32) -> CommandResult<ActivityHeatmap> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    state.run_db(move |conn| activity_heatmap(conn, &user_id, year)).await
+}
+
+/// Export the user's whole activity history as CSV
+#[tauri::command]
+#[specta::specta]
+pub async fn export_activity_history(state: State<'_, AppState>, path: String) -> CommandResult<()> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    let days = state.run_db(move |conn| ActivityRepository::get_all(conn, &user_id)).await?;
+    fs::write(&path, activity_csv(&days))?;
+    Ok(())
+}
+
+/// Write the report for the past week to `path`, as HTML if it ends in
+/// .html or .htm and Markdown otherwise
+#[tauri::command]
+#[specta::specta]
+pub async fn export_weekly_report(state: State<'_, AppState>, path: String) -> CommandResult<()> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    let mut report = state
+        .run_db(move |conn| weekly_report(conn, &user_id, Some(&curriculum_id), Utc::now()))
+        .await?;
+    {
+        let loader = state.content_loader.lock()?;
+        let loader = loader
+            .as_ref()
+            .ok_or_else(|| CommandError::new(ErrorCode::CurriculumNotLoaded, "Content not loaded"))?;
+        report.name_nodes(|node_id| loader.get_node_by_id(node_id).map(|node| node.title.clone()));
+    }
+
+    let html = Path::new(&path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm"));
+    let rendered = if html { report.to_html() } else { report.to_markdown() };
+    fs::write(&path, rendered)?;
+    Ok(())
+}
+
+/// Every node and checkpoint of the loaded curriculum, with what it's worth
+fn curriculum_nodes(state: &AppState) -> CommandResult<Vec<NodeWeight>> {
+    let loader = state.content_loader.lock()?;
+    let loader = loader
+        .as_ref()
+        .ok_or_else(|| CommandError::new(ErrorCode::CurriculumNotLoaded, "Content not loaded"))?;
+    let manifest = loader.get_manifest();
+
+    let mut nodes: Vec<NodeWeight> = manifest
+        .weeks
+        .iter()
+        .flat_map(|week| &week.days)
+        .flat_map(|day| &day.nodes)
+        .map(|node| NodeWeight {
+            node_id: node.id.clone(),
+            xp_reward: node.xp_reward,
+            estimated_minutes: node.estimated_minutes,
+        })
+        .collect();
+    for checkpoint in &manifest.checkpoints {
+        if !nodes.iter().any(|node| node.node_id == checkpoint.id) {
+            nodes.push(NodeWeight {
+                node_id: checkpoint.id.clone(),
+                xp_reward: checkpoint.xp_reward,
+                estimated_minutes: checkpoint.estimated_hours * 60,
+            });
+        }
+    }
+    Ok(nodes)
+}
+
+/// How much of the active curriculum is done, weighted by "Xp" (the
+/// default) or "Minutes". Reaching 100% issues a completion certificate.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_curriculum_completion(
+    state: State<'_, AppState>,
+    weighting: Option<String>,
+) -> CommandResult<CurriculumCompletionStatus> {
+    let weighting: CompletionWeighting = weighting.as_deref().map(str::parse).transpose()?.unwrap_or_default();
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    let nodes = curriculum_nodes(&state)?;
+
+    state
+        .run_db(move |conn| {
+            let completion = curriculum_completion(conn, &user_id, Some(&curriculum_id), &nodes, weighting)?;
+
+            let mut certificate = CertificateRepository::get(conn, &user_id, &curriculum_id)?;
+            if let (None, Some(completed_at)) = (&certificate, completion.completed_at) {
+                let user = UserRepository::get_by_id(conn, &user_id)?
+                    .ok_or_else(|| DbError::NotFound("User not found".to_string()))?;
+                let curriculum = CurriculumRepository::get(conn, &curriculum_id)?
+                    .ok_or_else(|| DbError::NotFound(format!("Curriculum not found: {}", curriculum_id)))?;
+                let key = CertificateRepository::signing_key(conn)?;
+
+                let issued = Certificate::issue(
+                    user_id.clone(),
+                    user.display_name.unwrap_or_else(|| user_id.clone()),
+                    &curriculum,
+                    completed_at,
+                    key.as_bytes(),
+                );
+                CertificateRepository::create(conn, &issued)?;
+                certificate = Some(issued);
+            }
+
+            Ok(CurriculumCompletionStatus { completion, certificate })
+        })
+        .await
+}
+
+/// Write the active curriculum's completion certificate to `path`, as JSON
+/// if it ends in .json and SVG otherwise
+#[tauri::command]
+#[specta::specta]
+pub async fn export_certificate(state: State<'_, AppState>, path: String) -> CommandResult<()> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    let certificate = state
+        .run_db(move |conn| CertificateRepository::get(conn, &user_id, &curriculum_id))
+        .await?
+        .ok_or_else(|| CommandError::not_found("No certificate yet; finish the curriculum first"))?;
+
+    let json = Path::new(&path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let rendered = if json { serde_json::to_string_pretty(&certificate)? } else { certificate.to_svg() };
+    fs::write(&path, rendered)?;
+    Ok(())
+}
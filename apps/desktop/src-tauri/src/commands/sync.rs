@@ -0,0 +1,79 @@
+use crate::commands::system::get_config_dir;
+use crate::dto::sync::SyncStatus;
+use crate::error::{CommandError, CommandResult, ErrorCode};
+use crate::state::AppState;
+use glp_core::db::repos::SyncRepository;
+use glp_core::sync::{self, SyncReport};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+fn load_sync_folder() -> Option<PathBuf> {
+    get_config_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join("sync_folder")).ok())
+        .map(|s| PathBuf::from(s.trim()))
+        .filter(|p| !p.as_os_str().is_empty())
+}
+
+/// Set the folder, kept in step by a tool like Dropbox or Syncthing, that
+/// devices sync through. `None` turns sync off.
+#[tauri::command]
+#[specta::specta]
+pub fn configure_sync_folder(path: Option<String>) -> CommandResult<()> {
+    let config_dir = get_config_dir()?;
+    let config_file = config_dir.join("sync_folder");
+
+    match path {
+        Some(path) => {
+            if !Path::new(&path).is_dir() {
+                return Err(CommandError::validation(format!("Sync folder does not exist: {}", path)));
+            }
+            fs::create_dir_all(&config_dir)?;
+            fs::write(config_file, path)?;
+        }
+        None => {
+            if config_file.exists() {
+                fs::remove_file(config_file)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_sync_status(state: State<'_, AppState>) -> CommandResult<SyncStatus> {
+    let user_id = state.get_current_user_id();
+
+    let (device_id, last_synced_at) = state
+        .run_db(move |conn| {
+            Ok((SyncRepository::device_id(conn)?, SyncRepository::last_synced_at(conn, &user_id)?))
+        })
+        .await?;
+
+    Ok(SyncStatus {
+        folder: load_sync_folder().map(|p| p.display().to_string()),
+        device_id,
+        last_synced_at: last_synced_at.map(|t| t.to_rfc3339()),
+    })
+}
+
+/// Publish this device's progress, mastery and review changes to the sync
+/// folder and apply changes from the user's other devices
+#[tauri::command]
+#[specta::specta]
+pub async fn sync_now(state: State<'_, AppState>) -> CommandResult<SyncReport> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let folder = load_sync_folder().ok_or_else(|| CommandError::new(ErrorCode::InvalidState, "No sync folder configured"))?;
+
+    state
+        .run_db(move |conn| Ok(sync::sync_user(conn, &folder, &user_id)))
+        .await?
+        .map_err(CommandError::from)
+}
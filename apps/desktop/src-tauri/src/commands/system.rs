@@ -1,45 +1,35 @@
+use crate::commands::{events, trash};
+use crate::dto::system::{DockerStatus, ProviderStatus, SystemStatus};
+use crate::error::{CommandError, CommandResult};
 use crate::state::AppState;
-use glp_core::db::repos::{
-    BadgeRepository, MasteryRepository, ProgressRepository,
-    QuizRepository, ReviewRepository, UserRepository,
-};
+use glp_core::backup::{self, BackupData, BackupError, ImportOptions, ImportReport};
+use glp_core::db::repos::TrashRepository;
+use glp_core::DbError;
+use glp_core::gamification::{audit_xp, repair_xp, AuditedNode, Difficulty, XpActivity, XpAuditReport};
+use glp_grader::{LlmProvider, ProviderCheck, ProviderCredentials};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use tauri::State;
 
-#[derive(Debug, Serialize)]
-pub struct SystemStatus {
-    pub docker_installed: bool,
-    pub docker_running: bool,
-    pub api_key_set: bool,
-    pub database_ok: bool,
-}
-
-#[derive(Debug, Serialize)]
-pub struct DockerStatus {
-    pub installed: bool,
-    pub running: bool,
-    pub version: Option<String>,
-}
-
 /// Check system requirements
 #[tauri::command]
-pub fn check_system_status(state: State<AppState>) -> Result<SystemStatus, String> {
+#[specta::specta]
+pub async fn check_system_status(state: State<'_, AppState>) -> CommandResult<SystemStatus> {
     let docker = check_docker_internal();
 
-    // Check if API key is set
-    let api_key_set = std::env::var("OPENAI_API_KEY").is_ok()
-        || load_api_key_from_config().is_some();
+    // Check if the grading provider is set up
+    let api_key_set = grading_credentials().is_some();
 
     // Check database connection
     let database_ok = state
-        .db
-        .with_connection(|conn| {
+        .run_db(move |conn| {
             conn.execute("SELECT 1", [])?;
             Ok(())
         })
+        .await
         .is_ok();
 
     Ok(SystemStatus {
@@ -52,11 +42,12 @@ pub fn check_system_status(state: State<AppState>) -> Result<SystemStatus, Strin
 
 /// Check Docker status
 #[tauri::command]
+#[specta::specta]
 pub fn check_docker_status() -> DockerStatus {
     check_docker_internal()
 }
 
-fn check_docker_internal() -> DockerStatus {
+pub(crate) fn check_docker_internal() -> DockerStatus {
     // Check if Docker is installed
     let version_output = Command::new("docker").arg("--version").output();
 
@@ -91,51 +82,180 @@ fn check_docker_internal() -> DockerStatus {
     }
 }
 
-/// Save OpenAI API key
-#[tauri::command]
-pub fn save_api_key(api_key: String) -> Result<(), String> {
-    let config_dir = get_config_dir()?;
-    fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
+/// Provider settings, stored as `providers.json` in the config directory
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProviderConfig {
+    grading_provider: Option<LlmProvider>,
+    #[serde(default)]
+    providers: Vec<StoredProvider>,
+}
 
-    let key_path = config_dir.join("api_key");
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredProvider {
+    provider: LlmProvider,
+    /// Obfuscated with `obfuscate_key`
+    api_key: Option<String>,
+    endpoint: Option<String>,
+    model: Option<String>,
+}
 
-    // Simple obfuscation (not secure encryption, but better than plaintext)
-    let obfuscated = obfuscate_key(&api_key);
-    fs::write(&key_path, obfuscated).map_err(|e| e.to_string())?;
+fn load_provider_config() -> ProviderConfig {
+    get_config_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join("providers.json")).ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
 
-    // Also set as environment variable for current session
-    std::env::set_var("OPENAI_API_KEY", &api_key);
+fn save_provider_config(config: &ProviderConfig) -> CommandResult<()> {
+    let config_dir = get_config_dir()?;
+    fs::create_dir_all(&config_dir)?;
 
+    let json = serde_json::to_string_pretty(config)?;
+    fs::write(config_dir.join("providers.json"), json)?;
     Ok(())
 }
 
-/// Load API key from config
-#[tauri::command]
-pub fn get_api_key_status() -> bool {
-    std::env::var("OPENAI_API_KEY").is_ok() || load_api_key_from_config().is_some()
+/// Credentials for a provider. A saved key takes precedence over the
+/// provider's environment variable.
+fn load_credentials(config: &ProviderConfig, provider: LlmProvider) -> ProviderCredentials {
+    let stored = config.providers.iter().find(|p| p.provider == provider);
+
+    let api_key = stored
+        .and_then(|p| p.api_key.as_deref())
+        .map(deobfuscate_key)
+        .or_else(|| provider.api_key_env_var().and_then(|var| std::env::var(var).ok()))
+        .or_else(|| (provider == LlmProvider::OpenAi).then(load_legacy_api_key).flatten());
+
+    ProviderCredentials {
+        provider,
+        api_key,
+        endpoint: stored.and_then(|p| p.endpoint.clone()),
+        model: stored.and_then(|p| p.model.clone()),
+    }
 }
 
-fn load_api_key_from_config() -> Option<String> {
+/// OpenAI key saved by versions that only supported OpenAI
+fn load_legacy_api_key() -> Option<String> {
     let config_dir = get_config_dir().ok()?;
-    let key_path = config_dir.join("api_key");
+    let obfuscated = fs::read_to_string(config_dir.join("api_key")).ok()?;
+    Some(deobfuscate_key(&obfuscated))
+}
 
-    if !key_path.exists() {
-        return None;
-    }
+/// Credentials for the provider artifacts are graded with, if it is set up
+pub(crate) fn grading_credentials() -> Option<ProviderCredentials> {
+    let config = load_provider_config();
+    let provider = config.grading_provider.unwrap_or(LlmProvider::OpenAi);
+    Some(load_credentials(&config, provider)).filter(|c| c.is_configured())
+}
+
+/// Provider artifacts are graded with
+pub(crate) fn grading_provider() -> LlmProvider {
+    load_provider_config().grading_provider.unwrap_or(LlmProvider::OpenAi)
+}
+
+fn non_blank(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Save OpenAI API key
+#[tauri::command]
+#[specta::specta]
+pub fn save_api_key(api_key: String) -> CommandResult<()> {
+    save_provider_credentials(LlmProvider::OpenAi, Some(api_key), None, None)
+}
+
+/// Save a provider's API key, endpoint and model. A missing key keeps the
+/// saved one; a missing endpoint or model means the provider's default.
+#[tauri::command]
+#[specta::specta]
+pub fn save_provider_credentials(
+    provider: LlmProvider,
+    api_key: Option<String>,
+    endpoint: Option<String>,
+    model: Option<String>,
+) -> CommandResult<()> {
+    let mut config = load_provider_config();
+    let previous_key = config
+        .providers
+        .iter()
+        .find(|p| p.provider == provider)
+        .and_then(|p| p.api_key.clone());
+    config.providers.retain(|p| p.provider != provider);
+
+    config.providers.push(StoredProvider {
+        provider,
+        // Simple obfuscation (not secure encryption, but better than plaintext)
+        api_key: non_blank(api_key).map(|k| obfuscate_key(&k)).or(previous_key),
+        endpoint: non_blank(endpoint),
+        model: non_blank(model),
+    });
+
+    save_provider_config(&config)
+}
+
+/// Forget a provider's saved credentials
+#[tauri::command]
+#[specta::specta]
+pub fn remove_provider_credentials(provider: LlmProvider) -> CommandResult<()> {
+    let mut config = load_provider_config();
+    config.providers.retain(|p| p.provider != provider);
+    save_provider_config(&config)
+}
 
-    let obfuscated = fs::read_to_string(&key_path).ok()?;
-    let key = deobfuscate_key(&obfuscated);
+/// Choose the provider artifacts are graded with
+#[tauri::command]
+#[specta::specta]
+pub fn set_grading_provider(provider: LlmProvider) -> CommandResult<()> {
+    let mut config = load_provider_config();
+    config.grading_provider = Some(provider);
+    save_provider_config(&config)
+}
 
-    // Set as environment variable
-    std::env::set_var("OPENAI_API_KEY", &key);
+/// Configuration state of every supported provider
+#[tauri::command]
+#[specta::specta]
+pub fn get_provider_status() -> Vec<ProviderStatus> {
+    let config = load_provider_config();
+    let selected = config.grading_provider.unwrap_or(LlmProvider::OpenAi);
 
-    Some(key)
+    LlmProvider::ALL
+        .iter()
+        .map(|&provider| {
+            let credentials = load_credentials(&config, provider);
+            ProviderStatus {
+                provider,
+                name: provider.display_name().to_string(),
+                configured: credentials.is_configured(),
+                requires_api_key: provider.requires_api_key(),
+                endpoint: credentials.endpoint(),
+                model: credentials.model().to_string(),
+                selected: provider == selected,
+            }
+        })
+        .collect()
 }
 
-fn get_config_dir() -> Result<PathBuf, String> {
+/// Check a provider's saved credentials with a minimal request and report
+/// which models they can use
+#[tauri::command]
+#[specta::specta]
+pub async fn test_api_key(provider: LlmProvider) -> CommandResult<ProviderCheck> {
+    let credentials = load_credentials(&load_provider_config(), provider);
+    Ok(glp_grader::check_credentials(&credentials).await?)
+}
+
+/// Whether the grading provider is set up
+#[tauri::command]
+#[specta::specta]
+pub fn get_api_key_status() -> bool {
+    grading_credentials().is_some()
+}
+
+pub(crate) fn get_config_dir() -> CommandResult<PathBuf> {
     dirs::config_dir()
         .map(|p| p.join("gamified-learning-platform"))
-        .ok_or_else(|| "Could not find config directory".to_string())
+        .ok_or_else(|| CommandError::internal("Could not find config directory"))
 }
 
 // Simple XOR obfuscation (not secure, but prevents casual viewing)
@@ -164,224 +284,189 @@ fn deobfuscate_key(obfuscated: &str) -> String {
     String::from_utf8(deobfuscated).unwrap_or_default()
 }
 
-/// Backup data structure
-#[derive(Debug, Serialize, Deserialize)]
-pub struct BackupData {
-    pub version: String,
-    pub exported_at: String,
-    pub user: Option<serde_json::Value>,
-    pub node_progress: Vec<serde_json::Value>,
-    pub quiz_attempts: Vec<serde_json::Value>,
-    pub mastery_scores: Vec<serde_json::Value>,
-    pub badge_progress: Vec<serde_json::Value>,
-    pub review_items: Vec<serde_json::Value>,
-}
-
-/// Export all user data to JSON file
+/// Export all of the current user's data to a `.glpbackup` archive,
+/// encrypted when a passphrase is given
 #[tauri::command]
-pub fn export_user_data(state: State<AppState>, path: String) -> Result<(), String> {
+#[specta::specta]
+pub async fn export_user_data(
+    state: State<'_, AppState>,
+    path: String,
+    passphrase: Option<String>,
+) -> CommandResult<()> {
     // Get user ID
-    let user_id_guard = state.current_user_id.lock().map_err(|e| e.to_string())?;
-    let user_id = user_id_guard
-        .as_ref()
-        .ok_or_else(|| "No user logged in".to_string())?;
-    let user_id = user_id.clone();
-    drop(user_id_guard);
-
-    // Collect all data using with_connection
-    let user = state
-        .db
-        .with_connection(|conn| UserRepository::get_by_id(conn, &user_id))
-        .map_err(|e| e.to_string())?
-        .map(|u| serde_json::to_value(u).unwrap());
-
-    let node_progress: Vec<serde_json::Value> = state
-        .db
-        .with_connection(|conn| ProgressRepository::get_all_for_user(conn, &user_id))
-        .map_err(|e| e.to_string())?
-        .into_iter()
-        .map(|p| serde_json::to_value(p).unwrap())
-        .collect();
-
-    let quiz_attempts: Vec<serde_json::Value> = state
-        .db
-        .with_connection(|conn| QuizRepository::get_all_for_user(conn, &user_id))
-        .map_err(|e| e.to_string())?
-        .into_iter()
-        .map(|a| serde_json::to_value(a).unwrap())
-        .collect();
-
-    let mastery_scores: Vec<serde_json::Value> = state
-        .db
-        .with_connection(|conn| MasteryRepository::get_all_for_user(conn, &user_id))
-        .map_err(|e| e.to_string())?
-        .into_iter()
-        .map(|m| serde_json::to_value(m).unwrap())
-        .collect();
-
-    let badge_progress: Vec<serde_json::Value> = state
-        .db
-        .with_connection(|conn| BadgeRepository::get_all_for_user(conn, &user_id))
-        .map_err(|e| e.to_string())?
-        .into_iter()
-        .map(|b| serde_json::to_value(b).unwrap())
-        .collect();
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
 
-    let review_items: Vec<serde_json::Value> = state
-        .db
-        .with_connection(|conn| ReviewRepository::get_all_for_user(conn, &user_id))
-        .map_err(|e| e.to_string())?
-        .into_iter()
-        .map(|r| serde_json::to_value(r).unwrap())
-        .collect();
-
-    let backup = BackupData {
-        version: "1.0".to_string(),
-        exported_at: chrono::Utc::now().to_rfc3339(),
-        user,
-        node_progress,
-        quiz_attempts,
-        mastery_scores,
-        badge_progress,
-        review_items,
-    };
-
-    let json = serde_json::to_string_pretty(&backup).map_err(|e| e.to_string())?;
-    fs::write(&path, json).map_err(|e| e.to_string())?;
+    let data = state
+        .run_db(move |conn| backup::collect(conn, &user_id))
+        .await?;
 
+    backup::write_file(Path::new(&path), &data, non_empty(passphrase.as_deref()))?;
     Ok(())
 }
 
-/// Import user data from JSON file
+/// Import a backup, either merging it with local data using per-category
+/// conflict strategies or replacing the backed-up user's data with it.
+/// With `options.dry_run` only reports what would change; otherwise
+/// switches to the imported user.
 #[tauri::command]
-pub fn import_user_data(state: State<AppState>, path: String) -> Result<(), String> {
-    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-    let backup: BackupData = serde_json::from_str(&content).map_err(|e| e.to_string())?;
-
-    // Import user if present
-    if let Some(user_value) = backup.user {
-        let user: glp_core::models::User =
-            serde_json::from_value(user_value).map_err(|e| e.to_string())?;
-
-        // Check if user exists, create if not
-        let exists = state
-            .db
-            .with_connection(|conn| UserRepository::get_by_id(conn, &user.id))
-            .map_err(|e| e.to_string())?
-            .is_some();
-
-        if !exists {
-            state
-                .db
-                .with_connection(|conn| UserRepository::create(conn, &user))
-                .map_err(|e| e.to_string())?;
-        }
-
-        // Set as current user
-        *state.current_user_id.lock().map_err(|e| e.to_string())? = Some(user.id.clone());
+#[specta::specta]
+pub async fn import_user_data(
+    state: State<'_, AppState>,
+    path: String,
+    passphrase: Option<String>,
+    options: Option<ImportOptions>,
+) -> CommandResult<ImportReport> {
+    let data = read_backup(&path, passphrase.as_deref())?;
+    let options = options.unwrap_or_default();
+
+    let report = state
+        .run_db(move |conn| restore_backup(conn, &data, &options))
+        .await?;
+
+    // Set as current user
+    if let (false, Some(user_id)) = (report.dry_run, &report.user_id) {
+        *state.current_user_id.lock()? = Some(user_id.clone());
     }
 
-    // Import progress
-    for progress_value in backup.node_progress {
-        let progress: glp_core::models::NodeProgress =
-            serde_json::from_value(progress_value).map_err(|e| e.to_string())?;
-        state
-            .db
-            .with_connection(|conn| ProgressRepository::create_or_update(conn, &progress))
-            .map_err(|e| e.to_string())?;
-    }
+    Ok(report)
+}
 
-    // Import mastery scores
-    for mastery_value in backup.mastery_scores {
-        let mastery: glp_core::models::MasteryScore =
-            serde_json::from_value(mastery_value).map_err(|e| e.to_string())?;
-        state
-            .db
-            .with_connection(|conn| MasteryRepository::create_or_update(conn, &mastery))
-            .map_err(|e| e.to_string())?;
-    }
+/// Whether a backup file needs a passphrase to import
+#[tauri::command]
+#[specta::specta]
+pub fn is_backup_encrypted(path: String) -> CommandResult<bool> {
+    let bytes = fs::read(&path)?;
+    Ok(backup::is_encrypted(&bytes))
+}
 
-    // Import badge progress
-    for badge_value in backup.badge_progress {
-        let badge: glp_core::models::BadgeProgress =
-            serde_json::from_value(badge_value).map_err(|e| e.to_string())?;
-        state
-            .db
-            .with_connection(|conn| BadgeRepository::create_or_update(conn, &badge))
-            .map_err(|e| e.to_string())?;
-    }
+fn non_empty(passphrase: Option<&str>) -> Option<&str> {
+    passphrase.filter(|p| !p.is_empty())
+}
 
-    // Import review items
-    for review_value in backup.review_items {
-        let review: glp_core::models::ReviewItem =
-            serde_json::from_value(review_value).map_err(|e| e.to_string())?;
-        state
-            .db
-            .with_connection(|conn| ReviewRepository::create_or_update(conn, &review))
-            .map_err(|e| e.to_string())?;
-    }
+/// Read a backup file, decrypting it if it is encrypted
+pub fn read_backup(path: &str, passphrase: Option<&str>) -> CommandResult<BackupData> {
+    Ok(backup::read_file(Path::new(path), non_empty(passphrase))?)
+}
 
-    Ok(())
+/// Write backup contents to the database.
+/// Creates the backed-up user if missing.
+pub fn restore_backup(
+    conn: &rusqlite::Connection,
+    data: &BackupData,
+    options: &ImportOptions,
+) -> Result<ImportReport, DbError> {
+    backup::restore(conn, data, options).map_err(|e| match e {
+        BackupError::Db(e) => e,
+        e => DbError::InvalidData(e.to_string()),
+    })
 }
 
-/// Reset all user progress
+/// Reset all user progress. Can be undone with
+/// `undo_last_destructive_operation` until the undo window closes.
 #[tauri::command]
-pub fn reset_all_progress(state: State<AppState>) -> Result<(), String> {
-    let user_id_guard = state.current_user_id.lock().map_err(|e| e.to_string())?;
-    let user_id = user_id_guard
+#[specta::specta]
+pub async fn reset_all_progress(state: State<'_, AppState>) -> CommandResult<()> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    trash::purge_expired_trash(&state).await?;
+
+    // Delete all progress data, keeping a snapshot for undo
+    let undo_window = trash::undo_window();
+    state
+        .run_db(move |conn| TrashRepository::reset_user_progress(conn, &user_id, undo_window))
+        .await?;
+
+    Ok(())
+}
+
+/// XP-relevant facts about every node in the active curriculum
+fn audited_nodes(state: &AppState) -> HashMap<String, AuditedNode> {
+    let loader = state.content_loader.lock().ok();
+    loader
         .as_ref()
-        .ok_or_else(|| "No user logged in".to_string())?;
-    let user_id = user_id.clone();
-    drop(user_id_guard);
+        .and_then(|guard| guard.as_ref())
+        .map(|loader| {
+            loader
+                .get_manifest()
+                .weeks
+                .iter()
+                .flat_map(|w| &w.days)
+                .flat_map(|d| &d.nodes)
+                .filter_map(|node| {
+                    let activity = XpActivity::from_node_type(&node.node_type)?;
+                    let difficulty = node.difficulty.parse().unwrap_or(Difficulty::Easy);
+                    Some((node.id.clone(), AuditedNode { activity, difficulty }))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Recompute the current user's XP from their activity, optionally fixing the stored total
+#[tauri::command]
+#[specta::specta]
+pub async fn audit_user_xp(state: State<'_, AppState>, repair: bool) -> CommandResult<XpAuditReport> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    let nodes = audited_nodes(&state);
+    let calendar = events::discover_events(&state);
 
-    // Delete all progress data
     state
-        .db
-        .with_connection(|conn| {
-            conn.execute("DELETE FROM node_progress WHERE user_id = ?1", [&user_id])?;
-            conn.execute("DELETE FROM quiz_attempts WHERE user_id = ?1", [&user_id])?;
-            conn.execute("DELETE FROM challenge_attempts WHERE user_id = ?1", [&user_id])?;
-            conn.execute("DELETE FROM mastery_scores WHERE user_id = ?1", [&user_id])?;
-            conn.execute("DELETE FROM badge_progress WHERE user_id = ?1", [&user_id])?;
-            conn.execute("DELETE FROM review_items WHERE user_id = ?1", [&user_id])?;
-            conn.execute(
-                "UPDATE users SET total_xp = 0, current_level = 1, current_streak = 0 WHERE id = ?1",
-                [&user_id],
-            )?;
-            Ok(())
+        .run_db(move |conn| {
+            let report = audit_xp(conn, &user_id, &nodes, &calendar)?;
+            if repair {
+                repair_xp(conn, &report)?;
+            }
+            Ok(report)
         })
-        .map_err(|e| e.to_string())?;
-
-    Ok(())
+        .await
 }
 
 /// Check if this is first launch (no user exists)
 #[tauri::command]
-pub fn is_first_launch(state: State<AppState>) -> Result<bool, String> {
+#[specta::specta]
+pub async fn is_first_launch(state: State<'_, AppState>) -> CommandResult<bool> {
     state
-        .db
-        .with_connection(|conn| {
+        .run_db(move |conn| {
             let count: i64 = conn
                 .query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))?;
             Ok(count == 0)
         })
-        .map_err(|e| e.to_string())
+        .await
 }
 
 /// Mark onboarding as complete
 #[tauri::command]
-pub fn complete_onboarding(_state: State<AppState>) -> Result<(), String> {
+#[specta::specta]
+pub fn complete_onboarding(_state: State<AppState>) -> CommandResult<()> {
+    mark_onboarding_complete()
+}
+
+pub(crate) fn mark_onboarding_complete() -> CommandResult<()> {
     let config_dir = get_config_dir()?;
-    fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
+    fs::create_dir_all(&config_dir)?;
 
     let flag_path = config_dir.join("onboarding_complete");
-    fs::write(&flag_path, "true").map_err(|e| e.to_string())?;
+    fs::write(&flag_path, "true")?;
 
     Ok(())
 }
 
 /// Check if onboarding is complete
 #[tauri::command]
+#[specta::specta]
 pub fn is_onboarding_complete() -> bool {
     get_config_dir()
         .map(|d| d.join("onboarding_complete").exists())
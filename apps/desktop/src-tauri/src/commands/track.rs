@@ -0,0 +1,192 @@
+use crate::commands::challenge::{connect_runner, load_challenge_node, record_submission};
+use crate::dto::challenge::ChallengeResult;
+use crate::dto::track::{TrackDayData, TrackProgressData};
+use crate::error::{CommandError, CommandResult, ErrorCode};
+use crate::state::AppState;
+use content::ProjectTrack;
+use glp_core::db::repos::{ProgressRepository, TrackRepository};
+use glp_core::models::{NodeStatus, TrackRepo};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
+
+/// A track day as the manifest lists it
+struct TrackDay {
+    day: u32,
+    node_id: String,
+    title: String,
+}
+
+/// The active curriculum's tracks with their days, in manifest order
+fn track_days(state: &AppState) -> CommandResult<Vec<(ProjectTrack, Vec<TrackDay>)>> {
+    let loader = state.content_loader.lock()?;
+    let loader = loader
+        .as_ref()
+        .ok_or_else(|| CommandError::new(ErrorCode::CurriculumNotLoaded, "Content not loaded"))?;
+
+    Ok(loader
+        .get_manifest()
+        .tracks
+        .iter()
+        .map(|track| {
+            let days = loader
+                .get_track_days(&track.id)
+                .into_iter()
+                .filter_map(|node| {
+                    Some(TrackDay {
+                        day: node.track.as_ref()?.day,
+                        node_id: node.id.clone(),
+                        title: node.title.clone(),
+                    })
+                })
+                .collect();
+            (track.clone(), days)
+        })
+        .collect())
+}
+
+/// Progress through each project track in the active curriculum
+#[tauri::command]
+#[specta::specta]
+pub async fn get_track_progress(state: State<'_, AppState>) -> CommandResult<Vec<TrackProgressData>> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    let tracks = track_days(&state)?;
+
+    state
+        .run_db(move |conn| {
+            let statuses: HashMap<String, NodeStatus> =
+                ProgressRepository::get_all_in_curriculum(conn, &user_id, Some(&curriculum_id))?
+                    .into_iter()
+                    .map(|progress| (progress.node_id, progress.status))
+                    .collect();
+
+            let mut result = Vec::new();
+            for (track, days) in tracks {
+                let repo = TrackRepository::get_repo(conn, &user_id, Some(&curriculum_id), &track.id)?;
+                let days: Vec<TrackDayData> = days
+                    .into_iter()
+                    .map(|day| {
+                        let status = statuses.get(&day.node_id).cloned().unwrap_or(NodeStatus::NotStarted);
+                        TrackDayData {
+                            completed: status == NodeStatus::Completed,
+                            status: status.as_str().to_string(),
+                            node_id: day.node_id,
+                            day: day.day,
+                            title: day.title,
+                        }
+                    })
+                    .collect();
+
+                result.push(TrackProgressData {
+                    track_id: track.id,
+                    title: track.title,
+                    description: track.description,
+                    repo_dir: track.repo_dir,
+                    repo_path: repo.map(|r| r.repo_path),
+                    days_total: days.len(),
+                    days_completed: days.iter().filter(|d| d.completed).count(),
+                    next_node_id: days.iter().find(|d| !d.completed).map(|d| d.node_id.clone()),
+                    days,
+                });
+            }
+            Ok(result)
+        })
+        .await
+}
+
+/// Choose the repo a project track is built in. It must be a Cargo project.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_track_repo(
+    state: State<'_, AppState>,
+    track_id: String,
+    repo_path: String,
+) -> CommandResult<TrackRepo> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+
+    if !track_days(&state)?.iter().any(|(track, _)| track.id == track_id) {
+        return Err(CommandError::not_found(format!("Track not found: {}", track_id)));
+    }
+    let path = PathBuf::from(&repo_path);
+    if !path.join("Cargo.toml").is_file() {
+        return Err(CommandError::validation(format!("{} has no Cargo.toml", repo_path)));
+    }
+
+    let repo = TrackRepo::new(user_id, Some(curriculum_id), track_id, repo_path);
+    let saved = repo.clone();
+    state.run_db(move |conn| TrackRepository::set_repo(conn, &saved)).await?;
+    Ok(repo)
+}
+
+/// Verify one day of a project track against the learner's repo. Only
+/// that day's tests run, and earlier days must be completed first, since
+/// each day builds on the last.
+#[tauri::command]
+#[specta::specta]
+pub async fn submit_track_day(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    node_id: String,
+) -> CommandResult<ChallengeResult> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    let challenge = load_challenge_node(&state, &node_id)?;
+    let step = challenge
+        .track
+        .clone()
+        .ok_or_else(|| CommandError::validation(format!("{} is not part of a project track", node_id)))?;
+    let earlier: Vec<(u32, String)> = track_days(&state)?
+        .into_iter()
+        .filter(|(track, _)| track.id == step.track)
+        .flat_map(|(_, days)| days)
+        .filter(|day| day.day < step.day)
+        .map(|day| (day.day, day.node_id))
+        .collect();
+
+    let (uid, cid, track_id) = (user_id.clone(), curriculum_id.clone(), step.track.clone());
+    let (repo, unfinished) = state
+        .run_db(move |conn| {
+            let repo = TrackRepository::get_repo(conn, &uid, Some(&cid), &track_id)?;
+            let mut unfinished = None;
+            for (day, id) in earlier {
+                let done = ProgressRepository::get(conn, &uid, Some(&cid), &id)?
+                    .is_some_and(|p| p.status == NodeStatus::Completed);
+                if !done {
+                    unfinished = Some(day);
+                    break;
+                }
+            }
+            Ok((repo, unfinished))
+        })
+        .await?;
+    if let Some(day) = unfinished {
+        return Err(CommandError::new(ErrorCode::InvalidState, format!("Finish day {} of this track first", day)));
+    }
+    let repo = repo.ok_or_else(|| CommandError::new(ErrorCode::InvalidState, "Choose the repo for this track first"))?;
+
+    let repo_dir = PathBuf::from(&repo.repo_path);
+    let runner = connect_runner(&state).await?;
+    let test_name = format!("day{:02}", step.day);
+    let verification = runner
+        .run_project_verification(&repo_dir, &test_name, &challenge.test_code)
+        .await?;
+
+    // Kept with the attempt for solution feedback and code review
+    let code = fs::read_to_string(repo_dir.join("src").join("lib.rs")).unwrap_or_default();
+    record_submission(&app, &state, user_id, curriculum_id, node_id, challenge, code, verification, Vec::new()).await
+}
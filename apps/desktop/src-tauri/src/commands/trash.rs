@@ -0,0 +1,118 @@
+use crate::commands::system::get_config_dir;
+use crate::dto::trash::UndoableOperation;
+use crate::error::{CommandError, CommandResult, ErrorCode};
+use crate::state::AppState;
+use chrono::{Duration, Utc};
+use glp_core::db::repos::{CurriculumRepository, TrashRepository};
+use glp_core::models::{TrashEntry, TrashOperation};
+use std::fs;
+use tauri::State;
+
+/// Longest undo window a user can configure (one week)
+const MAX_UNDO_WINDOW_MINUTES: i64 = 7 * 24 * 60;
+
+fn load_undo_window_minutes() -> i64 {
+    get_config_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join("undo_window_minutes")).ok())
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(TrashEntry::DEFAULT_UNDO_WINDOW_MINUTES)
+}
+
+/// How long destructive operations stay undoable
+pub fn undo_window() -> Duration {
+    Duration::minutes(load_undo_window_minutes())
+}
+
+/// Drop trash whose undo window has closed, along with any trashed content
+pub async fn purge_expired_trash(state: &AppState) -> CommandResult<()> {
+    let purged = state
+        .run_db(|conn| TrashRepository::purge_expired(conn, Utc::now()))
+        .await?;
+
+    for entry in purged.iter().filter(|e| e.operation == TrashOperation::DeleteCurriculum) {
+        content::purge_trashed_content(state.app_data_dir(), &entry.id)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_undo_window_minutes() -> i64 {
+    load_undo_window_minutes()
+}
+
+/// Set how long reset and delete operations can be undone. Applies to
+/// operations run after the change.
+#[tauri::command]
+#[specta::specta]
+pub fn set_undo_window_minutes(minutes: i64) -> CommandResult<()> {
+    if !(1..=MAX_UNDO_WINDOW_MINUTES).contains(&minutes) {
+        return Err(CommandError::validation(format!(
+            "Undo window must be between 1 and {} minutes",
+            MAX_UNDO_WINDOW_MINUTES
+        )));
+    }
+
+    let config_dir = get_config_dir()?;
+    fs::create_dir_all(&config_dir)?;
+    fs::write(config_dir.join("undo_window_minutes"), minutes.to_string())?;
+
+    Ok(())
+}
+
+/// The operation `undo_last_destructive_operation` would undo, if any
+#[tauri::command]
+#[specta::specta]
+pub async fn get_undoable_operation(state: State<'_, AppState>) -> CommandResult<Option<UndoableOperation>> {
+    let user_id = state.get_current_user_id();
+
+    let entry = state
+        .run_db(move |conn| TrashRepository::get_latest(conn, &user_id, Utc::now()))
+        .await?;
+
+    Ok(entry.as_ref().map(UndoableOperation::from))
+}
+
+/// Restore what the most recent reset or curriculum deletion removed
+#[tauri::command]
+#[specta::specta]
+pub async fn undo_last_destructive_operation(state: State<'_, AppState>) -> CommandResult<UndoableOperation> {
+    let user_id = state.get_current_user_id();
+
+    let entry = state
+        .run_db(move |conn| TrashRepository::get_latest(conn, &user_id, Utc::now()))
+        .await?
+        .ok_or_else(|| CommandError::new(ErrorCode::InvalidState, "Nothing to undo"))?;
+
+    let restored = entry.clone();
+    state
+        .run_db(move |conn| TrashRepository::restore(conn, &restored))
+        .await?;
+
+    if entry.operation == TrashOperation::DeleteCurriculum {
+        if let Some(curriculum_id) = entry.subject_id.clone() {
+            content::restore_content_pack(state.app_data_dir(), &curriculum_id, &entry.id)?;
+
+            let id = curriculum_id.clone();
+            let curriculum = state
+                .run_db(move |conn| CurriculumRepository::get(conn, &id))
+                .await?;
+
+            // Reload the curriculum if it was active, unless the user has
+            // switched to another one since
+            if curriculum.map(|c| c.is_active).unwrap_or(false) {
+                match state.get_active_curriculum_id() {
+                    None => state.load_curriculum(&curriculum_id)?,
+                    Some(active_id) => {
+                        state
+                            .run_db(move |conn| CurriculumRepository::set_active(conn, &active_id))
+                            .await?
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(UndoableOperation::from(&entry))
+}
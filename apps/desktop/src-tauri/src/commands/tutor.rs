@@ -0,0 +1,182 @@
+use crate::commands::{checkpoint, system};
+use crate::dto::tutor::{TutorConversation, TutorExchange};
+use crate::error::{CommandError, CommandResult, ErrorCode};
+use crate::state::AppState;
+use glp_core::db::error::DbError;
+use glp_core::db::repos::{MasteryRepository, TutorRepository};
+use glp_core::gamification::XpActivity;
+use glp_core::models::{Capability, TutorMessage, TutorRole, TutorSession};
+use glp_grader::types::GraderConfig;
+use glp_grader::{ChatMessage, GraderError, LLMGrader, SkillMastery, TokenBudget, TutorContext};
+use std::collections::HashMap;
+use tauri::State;
+
+/// A lecture as the tutor needs it
+struct LectureNode {
+    title: String,
+    content: String,
+    skills: Vec<String>,
+    /// Display name of every skill in the curriculum, by id
+    skill_names: HashMap<String, String>,
+}
+
+fn load_lecture_node(state: &AppState, node_id: &str) -> CommandResult<LectureNode> {
+    let loader = state.content_loader.lock()?;
+    let loader = loader
+        .as_ref()
+        .ok_or_else(|| CommandError::new(ErrorCode::CurriculumNotLoaded, "Content not loaded"))?;
+
+    let node = loader
+        .get_node_by_id(node_id)
+        .ok_or_else(|| CommandError::not_found(format!("Node not found: {}", node_id)))?;
+    if XpActivity::from_node_type(&node.node_type) != Some(XpActivity::Lecture) {
+        return Err(CommandError::validation(format!("{} is not a lecture", node_id)));
+    }
+
+    Ok(LectureNode {
+        title: node.title.clone(),
+        content: loader.load_lecture(&node.content_path)?,
+        skills: node.skills.clone(),
+        skill_names: loader
+            .get_manifest()
+            .skills
+            .iter()
+            .map(|skill| (skill.id.clone(), skill.name.clone()))
+            .collect(),
+    })
+}
+
+/// The user's session, or not found if it belongs to someone else
+fn owned_session(conn: &rusqlite::Connection, user_id: &str, session_id: &str) -> Result<TutorSession, DbError> {
+    TutorRepository::get_session(conn, session_id)?
+        .filter(|session| session.user_id == user_id)
+        .ok_or_else(|| DbError::NotFound(format!("Tutor session not found: {}", session_id)))
+}
+
+/// Start a new tutor conversation about a lecture
+#[tauri::command]
+#[specta::specta]
+pub async fn start_tutor_session(state: State<'_, AppState>, node_id: String) -> CommandResult<TutorConversation> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    load_lecture_node(&state, &node_id)?;
+
+    let session = TutorSession::new(user_id, Some(curriculum_id), node_id);
+    state
+        .run_db(move |conn| {
+            TutorRepository::create_session(conn, &session)?;
+            Ok(TutorConversation { session, messages: Vec::new() })
+        })
+        .await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_tutor_session(state: State<'_, AppState>, session_id: String) -> CommandResult<TutorConversation> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    state
+        .run_db(move |conn| {
+            let session = owned_session(conn, &user_id, &session_id)?;
+            let messages = TutorRepository::get_messages(conn, &session.id)?;
+            Ok(TutorConversation { session, messages })
+        })
+        .await
+}
+
+/// Ask the tutor a question. The question and reply are stored only once
+/// the tutor has answered, so a failed call can simply be retried.
+#[tauri::command]
+#[specta::specta]
+pub async fn send_tutor_message(
+    state: State<'_, AppState>,
+    session_id: String,
+    message: String,
+) -> CommandResult<TutorExchange> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let message = message.trim().to_string();
+    if message.is_empty() {
+        return Err(CommandError::validation("Message is empty"));
+    }
+
+    let (session, stored, masteries) = state
+        .run_db(move |conn| {
+            let session = owned_session(conn, &user_id, &session_id)?;
+            let stored = TutorRepository::get_messages(conn, &session.id)?;
+            let masteries =
+                MasteryRepository::get_all_in_curriculum(conn, &user_id, session.curriculum_id.as_deref())?;
+            Ok((session, stored, masteries))
+        })
+        .await?;
+    let lecture = load_lecture_node(&state, &session.node_id)?;
+
+    let mut skills: Vec<SkillMastery> = masteries
+        .iter()
+        .map(|m| SkillMastery {
+            name: lecture.skill_names.get(&m.skill_id).cloned().unwrap_or_else(|| m.skill_id.clone()),
+            score: m.score,
+            in_lecture: lecture.skills.contains(&m.skill_id),
+        })
+        .collect();
+    for skill_id in &lecture.skills {
+        if !masteries.iter().any(|m| &m.skill_id == skill_id) {
+            skills.push(SkillMastery {
+                name: lecture.skill_names.get(skill_id).cloned().unwrap_or_else(|| skill_id.clone()),
+                score: 0.0,
+                in_lecture: true,
+            });
+        }
+    }
+    let context = TutorContext {
+        lecture_title: lecture.title,
+        lecture_content: lecture.content,
+        skills,
+    };
+
+    let mut history: Vec<ChatMessage> = stored
+        .iter()
+        .map(|m| match m.role {
+            TutorRole::User => ChatMessage::user(m.content.as_str()),
+            TutorRole::Assistant => ChatMessage::assistant(m.content.as_str()),
+        })
+        .collect();
+    history.push(ChatMessage::user(message.as_str()));
+
+    let replied = match system::grading_credentials() {
+        None => Err(GraderError::MissingApiKey("the grading provider".to_string())),
+        Some(credentials) => {
+            LLMGrader::with_credentials(&credentials, GraderConfig::default())
+                .tutor_reply(&context, &history, &TokenBudget::default())
+                .await
+        }
+    };
+    let reply = replied.map_err(|e| {
+        if checkpoint::is_unreachable(&e) {
+            state.capabilities.mark_unavailable(Capability::Llm, e.to_string());
+        }
+        CommandError::from(e)
+    })?;
+
+    let question = TutorMessage::new(session.id.clone(), TutorRole::User, message);
+    let answer = TutorMessage::new(session.id.clone(), TutorRole::Assistant, reply.content);
+    let dropped_messages = reply.dropped_messages;
+    state
+        .run_db(move |conn| {
+            TutorRepository::add_message(conn, &question)?;
+            TutorRepository::add_message(conn, &answer)?;
+            Ok(TutorExchange { question, reply: answer, dropped_messages })
+        })
+        .await
+}
@@ -1,20 +1,15 @@
-use serde::Serialize;
+use crate::dto::update::UpdateInfo;
+use crate::error::{CommandError, CommandResult, ErrorCode};
 use tauri::AppHandle;
 use tauri_plugin_updater::UpdaterExt;
 
-#[derive(Debug, Clone, Serialize)]
-pub struct UpdateInfo {
-    pub version: String,
-    pub current_version: String,
-    pub body: Option<String>,
-}
-
 // These commands are disabled until signing keys are configured.
 // See lib.rs for the commented-out handler registration.
 #[allow(dead_code)]
 #[tauri::command]
-pub async fn check_for_update(app: AppHandle) -> Result<Option<UpdateInfo>, String> {
-    let updater = app.updater().map_err(|e| e.to_string())?;
+#[specta::specta]
+pub async fn check_for_update(app: AppHandle) -> CommandResult<Option<UpdateInfo>> {
+    let updater = app.updater()?;
     
     match updater.check().await {
         Ok(Some(update)) => {
@@ -25,26 +20,25 @@ pub async fn check_for_update(app: AppHandle) -> Result<Option<UpdateInfo>, Stri
             }))
         }
         Ok(None) => Ok(None),
-        Err(e) => Err(e.to_string()),
+        Err(e) => Err(e.into()),
     }
 }
 
 #[allow(dead_code)]
 #[tauri::command]
-pub async fn download_and_install_update(app: AppHandle) -> Result<(), String> {
-    let updater = app.updater().map_err(|e| e.to_string())?;
+#[specta::specta]
+pub async fn download_and_install_update(app: AppHandle) -> CommandResult<()> {
+    let updater = app.updater()?;
     
     let update = updater
         .check()
-        .await
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "No update available".to_string())?;
+        .await?
+        .ok_or_else(|| CommandError::new(ErrorCode::InvalidState, "No update available"))?;
     
     // Download and install the update
     update
         .download_and_install(|_chunk_length, _content_length| {}, || {})
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
     
     Ok(())
 }
@@ -1,81 +1,58 @@
-use crate::state::AppState;
+use crate::dto::user::{ProfileSummary, UserData};
+use crate::error::{CommandError, CommandResult};
+use crate::state::{AppState, ACTIVE_CURRICULUM_SETTING};
 use glp_core::db::repos::UserRepository;
 use glp_core::models::User;
-use serde::Serialize;
+use std::collections::HashMap;
 use tauri::State;
 use uuid::Uuid;
 
-#[derive(Serialize)]
-pub struct UserData {
-    pub id: String,
-    pub total_xp: i32,
-    pub current_level: i32,
-    pub current_streak: i32,
-    pub xp_for_next_level: i32,
-    pub xp_progress_percentage: f64,
-}
-
-impl From<User> for UserData {
-    fn from(user: User) -> Self {
-        Self {
-            id: user.id.clone(),
-            total_xp: user.total_xp,
-            current_level: user.current_level,
-            current_streak: user.current_streak,
-            xp_for_next_level: user.xp_for_next_level(),
-            xp_progress_percentage: user.xp_progress_percentage(),
-        }
-    }
-}
-
 #[tauri::command]
-pub fn get_user_data(state: State<AppState>) -> Result<Option<UserData>, String> {
-    let user_id = state.current_user_id.lock().map_err(|e| e.to_string())?;
+#[specta::specta]
+pub async fn get_user_data(state: State<'_, AppState>) -> CommandResult<Option<UserData>> {
+    let user_id = state.current_user_id.lock()?.clone();
 
-    if let Some(ref uid) = *user_id {
+    if let Some(uid) = user_id {
         state
-            .db
-            .with_connection(|conn| {
-                let user = UserRepository::get_by_id(conn, uid)?;
+            .run_db(move |conn| {
+                let user = UserRepository::get_by_id(conn, &uid)?;
                 Ok(user.map(UserData::from))
             })
-            .map_err(|e| e.to_string())
+            .await
     } else {
         Ok(None)
     }
 }
 
 #[tauri::command]
-pub fn create_user(state: State<AppState>) -> Result<UserData, String> {
+#[specta::specta]
+pub async fn create_user(state: State<'_, AppState>) -> CommandResult<UserData> {
     let user_id = Uuid::new_v4().to_string();
     let user = User::new(user_id.clone());
 
-    state
-        .db
-        .with_connection(|conn| {
+    let user = state
+        .run_db(move |conn| {
             UserRepository::create(conn, &user)?;
-            Ok(())
+            Ok(user)
         })
-        .map_err(|e| e.to_string())?;
+        .await?;
 
-    // Set as current user
-    *state.current_user_id.lock().map_err(|e| e.to_string())? = Some(user_id);
+    enter_profile(&state, &user_id).await?;
 
     Ok(user.into())
 }
 
 #[tauri::command]
-pub fn update_user_xp(state: State<AppState>, xp_delta: i32) -> Result<UserData, String> {
+#[specta::specta]
+pub async fn update_user_xp(state: State<'_, AppState>, xp_delta: i32) -> CommandResult<UserData> {
     let user_id = state
         .current_user_id
-        .lock()
-        .map_err(|e| e.to_string())?
+        .lock()?
         .clone()
-        .ok_or_else(|| "No user logged in".to_string())?;
+        .ok_or_else(CommandError::not_logged_in)?;
 
     state
-        .db
-        .with_connection(|conn| {
+        .run_db(move |conn| {
             UserRepository::update_xp(conn, &user_id, xp_delta)?;
 
             // Check for level up
@@ -92,5 +69,174 @@ pub fn update_user_xp(state: State<AppState>, xp_delta: i32) -> Result<UserData,
 
             Ok(UserData::from(updated_user))
         })
-        .map_err(|e| e.to_string())
+        .await
+}
+
+/// List all local profiles, most recently used first
+#[tauri::command]
+#[specta::specta]
+pub async fn list_profiles(state: State<'_, AppState>) -> CommandResult<Vec<ProfileSummary>> {
+    let current_user_id = state.current_user_id.lock()?.clone();
+
+    state
+        .run_db(move |conn| {
+            let profiles = UserRepository::get_all(conn)?
+                .into_iter()
+                .map(|user| ProfileSummary {
+                    is_current: current_user_id.as_deref() == Some(user.id.as_str()),
+                    user: UserData::from(user),
+                })
+                .collect();
+            Ok(profiles)
+        })
+        .await
+}
+
+/// Create a named profile and switch to it
+#[tauri::command]
+#[specta::specta]
+pub async fn create_profile(state: State<'_, AppState>, display_name: String) -> CommandResult<UserData> {
+    let display_name = display_name.trim().to_string();
+    if display_name.is_empty() {
+        return Err(CommandError::validation("Profile name cannot be empty"));
+    }
+
+    let user = User::with_display_name(Uuid::new_v4().to_string(), display_name);
+
+    let user = state
+        .run_db(move |conn| {
+            UserRepository::create(conn, &user)?;
+            Ok(user)
+        })
+        .await?;
+
+    enter_profile(&state, &user.id).await?;
+
+    Ok(user.into())
+}
+
+/// Switch the active profile
+#[tauri::command]
+#[specta::specta]
+pub async fn switch_profile(state: State<'_, AppState>, user_id: String) -> CommandResult<UserData> {
+    let id = user_id.clone();
+    let user = state
+        .run_db(move |conn| {
+            UserRepository::touch(conn, &id)?;
+            UserRepository::get_by_id(conn, &id)
+        })
+        .await?
+        .ok_or_else(|| CommandError::not_found(format!("Profile not found: {}", user_id)))?;
+
+    enter_profile(&state, &user_id).await?;
+
+    Ok(user.into())
+}
+
+/// Rename a profile
+#[tauri::command]
+#[specta::specta]
+pub async fn rename_profile(state: State<'_, AppState>, user_id: String, display_name: String) -> CommandResult<()> {
+    let display_name = display_name.trim().to_string();
+    if display_name.is_empty() {
+        return Err(CommandError::validation("Profile name cannot be empty"));
+    }
+
+    state
+        .run_db(move |conn| UserRepository::update_display_name(conn, &user_id, &display_name))
+        .await
+}
+
+/// Make `user_id` the current profile. A guest profile being left is
+/// discarded, and the profile's own active curriculum is brought back.
+async fn enter_profile(state: &AppState, user_id: &str) -> CommandResult<()> {
+    let previous = state.current_user_id.lock()?.replace(user_id.to_string());
+    if let Some(previous) = previous.filter(|previous| previous != user_id) {
+        state
+            .run_db(move |conn| match UserRepository::get_by_id(conn, &previous)? {
+                Some(user) if user.is_guest => UserRepository::delete(conn, &user.id),
+                _ => Ok(()),
+            })
+            .await?;
+    }
+
+    let id = user_id.to_string();
+    let curriculum_id = state
+        .run_db(move |conn| UserRepository::get_setting(conn, &id, ACTIVE_CURRICULUM_SETTING))
+        .await?;
+    if let Some(curriculum_id) = curriculum_id {
+        if state.active_curriculum_id.lock()?.as_deref() != Some(curriculum_id.as_str()) {
+            // The curriculum may have been removed since; keep whatever is loaded
+            if let Err(e) = state.load_curriculum(&curriculum_id) {
+                tracing::warn!(curriculum = %curriculum_id, error = %e.message, "Couldn't restore the profile's curriculum");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Try the app in a throwaway guest profile. Its progress is discarded when
+/// switching to another profile, deleting it, or restarting the app.
+#[tauri::command]
+#[specta::specta]
+pub async fn start_guest_session(state: State<'_, AppState>) -> CommandResult<UserData> {
+    let user = User::guest(Uuid::new_v4().to_string());
+
+    let user = state
+        .run_db(move |conn| {
+            UserRepository::create(conn, &user)?;
+            Ok(user)
+        })
+        .await?;
+
+    enter_profile(&state, &user.id).await?;
+
+    Ok(user.into())
+}
+
+/// Delete a profile and everything recorded for it. Deleting the current
+/// profile leaves no one signed in.
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_profile(state: State<'_, AppState>, user_id: String) -> CommandResult<()> {
+    let id = user_id.clone();
+    state.run_db(move |conn| UserRepository::delete(conn, &id)).await?;
+
+    let mut current = state.current_user_id.lock()?;
+    if current.as_deref() == Some(user_id.as_str()) {
+        *current = None;
+    }
+    Ok(())
+}
+
+/// The current profile's settings
+#[tauri::command]
+#[specta::specta]
+pub async fn get_profile_settings(state: State<'_, AppState>) -> CommandResult<HashMap<String, String>> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    state.run_db(move |conn| UserRepository::get_settings(conn, &user_id)).await
+}
+
+/// Store a setting for the current profile only
+#[tauri::command]
+#[specta::specta]
+pub async fn set_profile_setting(state: State<'_, AppState>, key: String, value: String) -> CommandResult<()> {
+    let key = key.trim().to_string();
+    if key.is_empty() {
+        return Err(CommandError::validation("Setting name cannot be empty"));
+    }
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+
+    state
+        .run_db(move |conn| UserRepository::set_setting(conn, &user_id, &key, &value))
+        .await
 }
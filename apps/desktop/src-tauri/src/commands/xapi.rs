@@ -0,0 +1,119 @@
+use crate::error::{CommandError, CommandResult, ErrorCode};
+use crate::state::AppState;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use glp_core::xapi::{self, Statement, XAPI_VERSION};
+use std::fs;
+use std::time::Duration;
+use tauri::State;
+
+/// Statements sent to a Learning Record Store per request
+const LRS_BATCH_SIZE: usize = 50;
+const LRS_TIMEOUT_SECS: u64 = 30;
+
+/// Write the user's learning records in the active curriculum to `path` as
+/// a JSON array of xAPI statements. With `since` (RFC 3339), only records
+/// from after it.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_xapi_statements(
+    state: State<'_, AppState>,
+    path: String,
+    since: Option<String>,
+) -> CommandResult<u32> {
+    let statements = load_statements(&state, since).await?;
+    fs::write(&path, serde_json::to_string_pretty(&statements)?)?;
+    Ok(statements.len() as u32)
+}
+
+/// Send the user's learning records in the active curriculum to a Learning
+/// Record Store, authenticating with HTTP Basic auth. Statement ids are
+/// stable, so records the LRS already has aren't duplicated. Returns how
+/// many statements were sent.
+#[tauri::command]
+#[specta::specta]
+pub async fn push_xapi_statements(
+    state: State<'_, AppState>,
+    endpoint: String,
+    username: String,
+    password: String,
+    since: Option<String>,
+) -> CommandResult<u32> {
+    let endpoint = endpoint.trim().trim_end_matches('/').to_string();
+    let local = ["http://localhost", "http://127.0.0.1"]
+        .iter()
+        .any(|prefix| endpoint.strip_prefix(prefix).is_some_and(|rest| rest.is_empty() || rest.starts_with([':', '/'])));
+    if !endpoint.starts_with("https://") && !local {
+        return Err(CommandError::validation("The LRS endpoint must be an https:// URL"));
+    }
+
+    let statements = load_statements(&state, since).await?;
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(LRS_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| CommandError::internal(e.to_string()))?;
+    let credentials =
+        base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", username, password));
+
+    for batch in statements.chunks(LRS_BATCH_SIZE) {
+        let response = client
+            .post(format!("{}/statements", endpoint))
+            .header("X-Experience-API-Version", XAPI_VERSION)
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Basic {}", credentials))
+            .body(serde_json::to_string(batch)?)
+            .send()
+            .await
+            .map_err(|e| {
+                CommandError::new(ErrorCode::IoError, "Couldn't reach the Learning Record Store")
+                    .with_details(e.to_string())
+            })?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(CommandError::new(
+                ErrorCode::IoError,
+                format!("The Learning Record Store refused the statements (HTTP {})", status.as_u16()),
+            )
+            .with_details(body));
+        }
+    }
+
+    tracing::info!(statements = statements.len(), "Pushed xAPI statements");
+    Ok(statements.len() as u32)
+}
+
+/// The current user's statements, with activities named from the loaded
+/// curriculum
+async fn load_statements(state: &AppState, since: Option<String>) -> CommandResult<Vec<Statement>> {
+    let user_id = state
+        .current_user_id
+        .lock()?
+        .clone()
+        .ok_or_else(CommandError::not_logged_in)?;
+    let curriculum_id = state.require_active_curriculum()?;
+    let since = since
+        .map(|since| {
+            DateTime::parse_from_rfc3339(&since)
+                .map(|since| since.with_timezone(&Utc))
+                .map_err(|e| CommandError::validation(format!("Invalid date '{}': {}", since, e)))
+        })
+        .transpose()?;
+
+    let mut statements = state
+        .run_db(move |conn| xapi::statements(conn, &user_id, Some(&curriculum_id), since))
+        .await?;
+
+    let loader = state.content_loader.lock()?;
+    let loader = loader
+        .as_ref()
+        .ok_or_else(|| CommandError::new(ErrorCode::CurriculumNotLoaded, "Content not loaded"))?;
+    let manifest = loader.get_manifest();
+    xapi::name_activities(&mut statements, |id| {
+        loader
+            .get_node_by_id(id)
+            .map(|node| node.title.clone())
+            .or_else(|| manifest.checkpoints.iter().find(|c| c.id == id).map(|c| c.title.clone()))
+    });
+    Ok(statements)
+}
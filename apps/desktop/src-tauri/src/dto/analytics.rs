@@ -0,0 +1,10 @@
+use glp_core::models::AnalyticsSettings;
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Debug, Serialize, Type)]
+pub struct AnalyticsStatus {
+    pub settings: AnalyticsSettings,
+    /// Events waiting to be uploaded
+    pub queued_events: u32,
+}
@@ -0,0 +1,16 @@
+use glp_core::badges::NextTierProgress;
+use glp_core::models::{BadgeDefinition, BadgeTier};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Badge with user progress information
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct BadgeWithProgress {
+    pub definition: BadgeDefinition,
+    pub progress: f64,
+    pub current_value: f64,
+    pub is_earned: bool,
+    pub earned_at: Option<String>,
+    pub tier: Option<BadgeTier>,
+    pub next_tier: Option<NextTierProgress>,
+}
@@ -0,0 +1,14 @@
+use serde::Serialize;
+use specta::Type;
+
+/// A bookmarked node as the reading queue shows it
+#[derive(Serialize, Type)]
+pub struct BookmarkData {
+    pub node_id: String,
+    pub position: i64,
+    /// `None` if the node is no longer in the curriculum
+    pub title: Option<String>,
+    pub node_type: Option<String>,
+    pub estimated_minutes: Option<u32>,
+    pub created_at: String,
+}
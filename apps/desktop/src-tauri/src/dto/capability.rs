@@ -0,0 +1,12 @@
+use crate::capability::CapabilityState;
+use glp_core::models::PendingWork;
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Debug, Serialize, Type)]
+pub struct CapabilityStatus {
+    pub docker: CapabilityState,
+    pub llm: CapabilityState,
+    /// The current user's work waiting on a capability
+    pub pending_work: Vec<PendingWork>,
+}
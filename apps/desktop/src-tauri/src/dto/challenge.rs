@@ -0,0 +1,34 @@
+use glp_core::models::ChallengeAttempt;
+use glp_runner::{SolutionDiff, VerificationResult};
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashMap;
+
+#[derive(Serialize, Type)]
+pub struct ChallengeResult {
+    pub attempt_id: String,
+    pub passed: bool,
+    pub attempt_number: i32,
+    pub xp_earned: i32,
+    /// Skills whose mastery changed, with the new score. Empty when the
+    /// tests never ran, e.g. on a compile error.
+    pub mastery_updates: HashMap<String, f64>,
+    /// Share of the completion XP taken for revealed hints
+    pub hint_penalty_percent: u32,
+    pub verification: VerificationResult,
+}
+
+#[derive(Serialize, Type)]
+pub struct ChallengeAttemptSummary {
+    pub attempt: ChallengeAttempt,
+    /// Missing for attempts recorded before full results were kept
+    pub verification: Option<VerificationResult>,
+}
+
+#[derive(Serialize, Type)]
+pub struct SolutionFeedback {
+    /// The passing attempt compared
+    pub attempt_id: String,
+    pub solution: String,
+    pub diff: SolutionDiff,
+}
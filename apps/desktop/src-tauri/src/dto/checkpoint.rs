@@ -0,0 +1,47 @@
+use glp_core::models::{ArtifactSubmission, RepoSubmission};
+use glp_grader::GradeResult;
+use glp_runner::VerificationResult;
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Debug, Serialize, Type)]
+pub struct ArtifactResult {
+    pub submission: ArtifactSubmission,
+    pub grade: GradeResult,
+    pub xp_earned: i32,
+    /// Every artifact the checkpoint asks for has a passing grade
+    pub checkpoint_completed: bool,
+    /// Artifacts still without a passing grade
+    pub remaining_artifacts: Vec<String>,
+}
+
+/// Artifacts graded from a learner's repo
+#[derive(Debug, Serialize, Type)]
+pub struct CheckpointRepoResult {
+    /// Commit the artifacts were read from
+    pub commit_hash: String,
+    pub graded: Vec<ArtifactResult>,
+    /// Artifacts the checkpoint asks for that aren't committed
+    pub not_found: Vec<String>,
+}
+
+/// A checkpoint submitted as a GitHub repo
+#[derive(Debug, Serialize, Type)]
+pub struct GithubSubmissionResult {
+    pub submission: RepoSubmission,
+    /// The checkpoint's tests, when it has any
+    pub verification: Option<VerificationResult>,
+    /// Empty when the tests failed, as the artifacts aren't graded then
+    pub graded: Vec<ArtifactResult>,
+    /// Artifacts the checkpoint asks for that aren't committed
+    pub not_found: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct CheckpointSubmissionResult {
+    pub submission: ArtifactSubmission,
+    /// Grading was deferred until the grading provider is reachable
+    pub queued: bool,
+    /// Why grading was deferred
+    pub queued_reason: Option<String>,
+}
@@ -0,0 +1,45 @@
+use glp_core::models::{AttemptFlag, ChallengeAttempt};
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CohortMember {
+    pub user_id: String,
+    pub display_name: String,
+    pub total_xp: i32,
+    pub current_level: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CohortSummary {
+    pub id: String,
+    pub name: String,
+    pub created_at: String,
+    pub members: Vec<CohortMember>,
+}
+
+/// A challenge attempt flagged for an instructor to review
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct FlaggedAttempt {
+    pub attempt_id: String,
+    pub user_id: String,
+    pub display_name: String,
+    pub node_id: String,
+    pub passed: bool,
+    pub submitted_at: String,
+    pub flags: Vec<AttemptFlag>,
+}
+
+impl FlaggedAttempt {
+    pub fn new(attempt: ChallengeAttempt, display_name: String) -> Self {
+        Self {
+            passed: attempt.passed(),
+            attempt_id: attempt.id,
+            user_id: attempt.user_id,
+            display_name,
+            node_id: attempt.node_id,
+            submitted_at: attempt.submitted_at.to_rfc3339(),
+            flags: attempt.flags,
+        }
+    }
+}
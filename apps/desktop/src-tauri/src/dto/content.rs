@@ -0,0 +1,89 @@
+use content::{ContentNode, Manifest, TrackStep};
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Serialize, Type)]
+pub struct ContentTree {
+    pub title: String,
+    pub weeks: Vec<WeekData>,
+}
+
+#[derive(Serialize, Type)]
+pub struct WeekData {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub days: Vec<DayData>,
+}
+
+#[derive(Serialize, Type)]
+pub struct DayData {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub nodes: Vec<NodeData>,
+}
+
+#[derive(Serialize, Type)]
+pub struct NodeData {
+    pub id: String,
+    pub node_type: String,
+    pub title: String,
+    pub description: String,
+    pub difficulty: String,
+    pub estimated_minutes: u32,
+    pub xp_reward: u32,
+    pub prerequisites: Vec<String>,
+    pub skills: Vec<String>,
+    /// Set for project track days, which are listed apart from regular nodes
+    pub track: Option<TrackStep>,
+    /// Retired, and kept only for learners who already have progress on it
+    pub deprecated: bool,
+    pub superseded_by: Option<String>,
+}
+
+impl From<&ContentNode> for NodeData {
+    fn from(node: &ContentNode) -> Self {
+        Self {
+            id: node.id.clone(),
+            node_type: node.node_type.clone(),
+            title: node.title.clone(),
+            description: node.description.clone(),
+            difficulty: node.difficulty.clone(),
+            estimated_minutes: node.estimated_minutes,
+            xp_reward: node.xp_reward,
+            prerequisites: node.prerequisites.clone(),
+            skills: node.skills.clone(),
+            track: node.track.clone(),
+            deprecated: node.is_deprecated(),
+            superseded_by: node.superseded_by.clone(),
+        }
+    }
+}
+
+impl From<&Manifest> for ContentTree {
+    fn from(manifest: &Manifest) -> Self {
+        Self {
+            title: manifest.title.clone(),
+            weeks: manifest
+                .weeks
+                .iter()
+                .map(|w| WeekData {
+                    id: w.id.clone(),
+                    title: w.title.clone(),
+                    description: w.description.clone(),
+                    days: w
+                        .days
+                        .iter()
+                        .map(|d| DayData {
+                            id: d.id.clone(),
+                            title: d.title.clone(),
+                            description: d.description.clone(),
+                            nodes: d.nodes.iter().map(NodeData::from).collect(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use chrono::NaiveDate;
+use content::{ContentStats, License, LicenseStatus};
+use glp_core::models::Curriculum;
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Serialize, Type)]
+pub struct CurriculumInfo {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub imported_at: String,
+    pub is_active: bool,
+    pub stats: Option<ContentStats>,
+}
+
+impl From<Curriculum> for CurriculumInfo {
+    fn from(c: Curriculum) -> Self {
+        Self {
+            id: c.id,
+            name: c.name,
+            version: c.version,
+            description: c.description,
+            author: c.author,
+            imported_at: c.imported_at.to_rfc3339(),
+            is_active: c.is_active,
+            stats: None,
+        }
+    }
+}
+
+#[derive(Serialize, Type)]
+pub struct ValidationResponse {
+    pub is_valid: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub stats: Option<ContentStats>,
+}
+
+#[derive(Serialize, Type)]
+pub struct ImportResponse {
+    pub success: bool,
+    pub curriculum_id: Option<String>,
+    pub error: Option<String>,
+}
+
+/// The license a premium curriculum was activated with
+#[derive(Serialize, Type)]
+pub struct CurriculumLicense {
+    pub licensee: String,
+    pub issued_on: NaiveDate,
+    pub expires_on: Option<NaiveDate>,
+    pub status: LicenseStatus,
+}
+
+impl CurriculumLicense {
+    pub fn new(license: &License, today: NaiveDate) -> Self {
+        Self {
+            licensee: license.licensee.clone(),
+            issued_on: license.issued_on,
+            expires_on: license.expires_on,
+            status: license.status(today),
+        }
+    }
+}
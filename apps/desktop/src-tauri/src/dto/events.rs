@@ -0,0 +1,14 @@
+use glp_core::gamification::XpActivity;
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct XpEventResponse {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub multiplier: f64,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub activities: Vec<XpActivity>,
+}
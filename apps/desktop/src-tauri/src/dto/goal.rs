@@ -0,0 +1,10 @@
+use serde::Deserialize;
+use specta::Type;
+
+/// A goal's target as the frontend describes it
+#[derive(Debug, Deserialize, Type)]
+#[serde(tag = "kind")]
+pub enum GoalTargetInput {
+    CompleteWeek { week_id: String },
+    ReachMastery { skill_id: String, score: f64 },
+}
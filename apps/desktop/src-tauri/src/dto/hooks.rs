@@ -0,0 +1,12 @@
+use glp_core::models::Hook;
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Debug, Serialize, Type)]
+pub struct HookSettings {
+    pub hooks: Vec<Hook>,
+    /// Where command hooks' scripts must be placed
+    pub scripts_dir: String,
+    /// Hosts webhooks may post to over HTTPS, besides this machine
+    pub allowed_webhook_hosts: Vec<String>,
+}
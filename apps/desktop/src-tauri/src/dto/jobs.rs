@@ -0,0 +1,22 @@
+use serde::Serialize;
+use specta::Type;
+
+/// A background job's schedule and last run
+#[derive(Debug, Serialize, Type)]
+pub struct JobStatus {
+    pub id: String,
+    pub description: String,
+    pub schedule: String,
+    pub running: bool,
+    pub last_started_at: Option<String>,
+    pub last_duration_ms: Option<i64>,
+    /// Summary of the last successful run
+    pub last_result: Option<String>,
+    /// Error from the last run, if it failed
+    pub last_error: Option<String>,
+    pub last_success_at: Option<String>,
+    pub run_count: i64,
+    pub failure_count: i64,
+    /// None for a job that has never run, which runs on the next tick
+    pub next_run_at: Option<String>,
+}
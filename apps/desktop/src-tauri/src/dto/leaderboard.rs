@@ -0,0 +1,17 @@
+use glp_core::leaderboard::{LeaderboardEntry, LeaderboardPeriod};
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct LeaderboardEntryResponse {
+    #[serde(flatten)]
+    pub entry: LeaderboardEntry,
+    pub is_current_user: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct LeaderboardResponse {
+    pub period: LeaderboardPeriod,
+    pub generated_at: String,
+    pub entries: Vec<LeaderboardEntryResponse>,
+}
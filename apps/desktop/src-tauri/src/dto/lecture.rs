@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Serialize, Type)]
+pub struct CompletionResult {
+    pub xp_earned: i32,
+    pub new_total_xp: i32,
+    pub new_level: u32,
+    pub unlocked_nodes: Vec<String>,
+}
+
+/// Whether the answer to a lecture's inline question was right, and why
+#[derive(Serialize, Type)]
+pub struct InlineAnswerFeedback {
+    pub correct: bool,
+    pub correct_answer: u32,
+    pub explanation: String,
+}
+
+#[derive(Deserialize, Type)]
+pub struct CompleteLectureRequest {
+    pub lecture_id: String,
+    pub time_spent_ms: i64,
+    pub difficulty: String,
+    /// An option index for each comprehension check question; left out to
+    /// skip the check
+    #[serde(default)]
+    pub comprehension_answers: Option<Vec<u32>>,
+}
@@ -0,0 +1,41 @@
+//! Command payloads and results
+//!
+//! Everything a command takes from or returns to the webview that isn't a
+//! core type, one module per command module. Each derives `specta::Type` so
+//! the frontend's `bindings.ts` is generated from these definitions rather
+//! than kept in step by hand.
+
+pub mod analytics;
+pub mod badge;
+pub mod bookmark;
+pub mod capability;
+pub mod challenge;
+pub mod checkpoint;
+pub mod cohort;
+pub mod content;
+pub mod curriculum;
+pub mod events;
+pub mod goal;
+pub mod hooks;
+pub mod jobs;
+pub mod leaderboard;
+pub mod lecture;
+pub mod note;
+pub mod practice;
+pub mod progress;
+pub mod quest;
+pub mod quiz;
+pub mod related;
+pub mod reminders;
+pub mod review;
+pub mod sandbox;
+pub mod settings;
+pub mod setup;
+pub mod stats;
+pub mod sync;
+pub mod system;
+pub mod track;
+pub mod trash;
+pub mod tutor;
+pub mod update;
+pub mod user;
@@ -0,0 +1,12 @@
+use glp_core::models::Note;
+use serde::Serialize;
+use specta::Type;
+
+/// A note with the title of the node it's on, for the notes overview
+#[derive(Serialize, Type)]
+pub struct NoteListItem {
+    pub note: Note,
+    /// `None` if the node isn't in the active curriculum
+    pub node_title: Option<String>,
+    pub node_type: Option<String>,
+}
@@ -0,0 +1,44 @@
+use crate::dto::review::ReviewItemResponse;
+use glp_core::models::PracticeChallenge;
+use glp_runner::VerificationResult;
+use serde::Serialize;
+use specta::Type;
+
+/// A practice challenge as the learner sees it, without the solution
+#[derive(Serialize, Type)]
+pub struct PracticeView {
+    pub id: String,
+    /// Id of the challenge's item in the review queue
+    pub review_id: String,
+    pub skill_id: String,
+    pub difficulty: String,
+    pub title: String,
+    pub instructions: String,
+    pub starter_code: String,
+    pub test_code: String,
+    pub hints: Vec<String>,
+    pub created_at: String,
+}
+
+impl From<PracticeChallenge> for PracticeView {
+    fn from(challenge: PracticeChallenge) -> Self {
+        Self {
+            review_id: challenge.review_id(),
+            id: challenge.id,
+            skill_id: challenge.skill_id,
+            difficulty: challenge.difficulty,
+            title: challenge.title,
+            instructions: challenge.instructions,
+            starter_code: challenge.starter_code,
+            test_code: challenge.test_code,
+            hints: challenge.hints,
+            created_at: challenge.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Serialize, Type)]
+pub struct PracticeResult {
+    pub verification: VerificationResult,
+    pub review: ReviewItemResponse,
+}
@@ -0,0 +1,51 @@
+use glp_core::models::{BestAttempt, NodeProgress, NodeStatus};
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Serialize, Type)]
+pub struct ProgressData {
+    pub node_id: String,
+    pub status: String,
+    pub attempts: i32,
+    pub time_spent_mins: i32,
+    pub completed: bool,
+}
+
+impl From<NodeProgress> for ProgressData {
+    fn from(progress: NodeProgress) -> Self {
+        Self {
+            node_id: progress.node_id,
+            status: progress.status.as_str().to_string(),
+            attempts: progress.attempts,
+            time_spent_mins: progress.time_spent_mins,
+            completed: progress.status == NodeStatus::Completed,
+        }
+    }
+}
+
+/// The best result at a challenge, or at one artifact of a checkpoint
+#[derive(Serialize, Type)]
+pub struct BestAttemptData {
+    pub node_id: String,
+    /// The artifact type for a checkpoint artifact; empty for a challenge
+    pub part: String,
+    pub best_attempt_id: Option<String>,
+    pub best_verification_score: Option<f64>,
+    pub best_grade: Option<f64>,
+    pub xp_awarded: i32,
+    pub attempts: i32,
+}
+
+impl From<BestAttempt> for BestAttemptData {
+    fn from(best: BestAttempt) -> Self {
+        Self {
+            node_id: best.node_id,
+            part: best.part,
+            best_attempt_id: best.best_attempt_id,
+            best_verification_score: best.best_verification_score,
+            best_grade: best.best_grade,
+            xp_awarded: best.xp_awarded,
+            attempts: best.attempts,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use glp_core::models::Quest;
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct QuestResponse {
+    pub id: String,
+    pub period: String,
+    pub kind: String,
+    pub description: String,
+    pub target: i32,
+    pub progress: i32,
+    pub xp_reward: i32,
+    pub ends_at: String,
+    pub is_complete: bool,
+    pub is_claimed: bool,
+}
+
+impl From<Quest> for QuestResponse {
+    fn from(quest: Quest) -> Self {
+        Self {
+            is_complete: quest.is_complete(),
+            is_claimed: quest.is_claimed(),
+            id: quest.id,
+            period: quest.period.as_str().to_string(),
+            kind: quest.kind.as_str().to_string(),
+            description: quest.description,
+            target: quest.target,
+            progress: quest.progress,
+            xp_reward: quest.xp_reward,
+            ends_at: quest.ends_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ClaimQuestResult {
+    pub xp_earned: i32,
+    pub new_total_xp: i32,
+    pub new_level: u32,
+}
@@ -0,0 +1,69 @@
+use glp_core::models::quiz::{QuestionOption, Quiz, QuizAttempt};
+use glp_core::models::{QuestionStats, QuestionView, SkillAccuracy};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+
+/// A quiz as the learner sees it before answering: no correct answers and no
+/// explanations, which only come back once it's submitted
+#[derive(Serialize, Type)]
+pub struct QuizView {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub difficulty: String,
+    pub passing_score: i32,
+    pub time_limit_seconds: Option<i32>,
+    pub questions: Vec<QuestionView>,
+}
+
+impl From<&Quiz> for QuizView {
+    fn from(quiz: &Quiz) -> Self {
+        Self {
+            id: quiz.id.clone(),
+            title: quiz.title.clone(),
+            description: quiz.description.clone(),
+            difficulty: quiz.difficulty.clone(),
+            passing_score: quiz.passing_score,
+            time_limit_seconds: quiz.time_limit_seconds,
+            questions: quiz.questions.iter().map(QuestionView::from).collect(),
+        }
+    }
+}
+
+#[derive(Deserialize, Type)]
+pub struct SubmitQuizRequest {
+    pub quiz_id: String,
+    pub answers: HashMap<String, String>,
+    pub time_spent_ms: i64,
+}
+
+/// One question of a past attempt, with what was chosen and why the right
+/// answer is right
+#[derive(Serialize, Type)]
+pub struct QuestionReview {
+    pub question_id: String,
+    pub prompt: String,
+    pub code_snippet: Option<String>,
+    pub options: Vec<QuestionOption>,
+    /// None if the question was skipped
+    pub answer: Option<String>,
+    pub correct_answer: String,
+    pub is_correct: bool,
+    pub explanation: String,
+    /// Why the chosen answer was wrong, if one was asked for
+    pub mistake_explanation: Option<String>,
+}
+
+#[derive(Serialize, Type)]
+pub struct QuizAttemptDetail {
+    pub attempt: QuizAttempt,
+    pub quiz_title: String,
+    pub questions: Vec<QuestionReview>,
+}
+
+#[derive(Serialize, Type)]
+pub struct QuizAnalytics {
+    pub most_missed: Vec<QuestionStats>,
+    pub skill_accuracy: Vec<SkillAccuracy>,
+}
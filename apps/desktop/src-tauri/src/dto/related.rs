@@ -0,0 +1,12 @@
+use glp_core::models::EmbeddedKind;
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Serialize, Type)]
+pub struct RelatedNode {
+    pub node_id: String,
+    pub title: String,
+    pub node_type: String,
+    pub kind: EmbeddedKind,
+    pub score: f64,
+}
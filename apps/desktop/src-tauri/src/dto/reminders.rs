@@ -0,0 +1,10 @@
+use glp_core::reminders::{NotificationPrefs, ScheduledReminder};
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Debug, Serialize, Type)]
+pub struct NotificationSchedule {
+    pub prefs: NotificationPrefs,
+    /// Next reminder of each kind, soonest first, in local time
+    pub upcoming: Vec<ScheduledReminder>,
+}
@@ -0,0 +1,69 @@
+use glp_core::anki::import::{CardDraft, ImportIssue};
+use glp_core::models::ReviewItem;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Review item for frontend
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ReviewItemResponse {
+    pub quiz_id: String,
+    pub due_date: String,
+    pub ease_factor: f64,
+    pub interval_days: i32,
+    pub repetitions: i32,
+    pub last_reviewed_at: Option<String>,
+    pub lapses: i32,
+    pub is_suspended: bool,
+}
+
+impl From<ReviewItem> for ReviewItemResponse {
+    fn from(item: ReviewItem) -> Self {
+        Self {
+            quiz_id: item.quiz_id,
+            due_date: item.due_date.to_rfc3339(),
+            ease_factor: item.ease_factor,
+            interval_days: item.interval_days,
+            repetitions: item.repetitions,
+            last_reviewed_at: item.last_reviewed_at.map(|d| d.to_rfc3339()),
+            lapses: item.lapses,
+            is_suspended: item.is_suspended,
+        }
+    }
+}
+
+/// Leech review item with the lecture node the user should revisit
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct LeechItemResponse {
+    #[serde(flatten)]
+    pub review: ReviewItemResponse,
+    pub lecture_node_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct MasterySkillResponse {
+    pub skill_id: String,
+    pub score: f64,
+    pub level: String,
+    pub last_updated: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct MasteryHistoryPoint {
+    pub previous_score: f64,
+    pub score: f64,
+    pub reason: String,
+    pub recorded_at: String,
+}
+
+/// What importing a deck did, or on a dry run would do
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct FlashcardImport {
+    /// Nothing was stored
+    pub dry_run: bool,
+    /// Cards new to the skill
+    pub cards: Vec<CardDraft>,
+    /// Cards already imported for the skill, skipped
+    pub duplicates: Vec<CardDraft>,
+    /// Rows that can't be imported
+    pub issues: Vec<ImportIssue>,
+}
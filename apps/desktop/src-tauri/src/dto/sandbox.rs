@@ -0,0 +1,14 @@
+use glp_core::sandbox::SandboxImageState;
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SandboxImageStatus {
+    /// Tag verification runs in
+    pub image: String,
+    /// Whether Docker has the image
+    pub present: bool,
+    /// Whether the image the last switch replaced is still there
+    pub can_roll_back: bool,
+    pub state: SandboxImageState,
+}
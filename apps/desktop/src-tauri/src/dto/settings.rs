@@ -0,0 +1,13 @@
+use glp_core::settings::UserSettings;
+use glp_grader::LlmProvider;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// The current profile's settings. The grading provider is shared by every
+/// profile on this install, so it lives with the provider credentials.
+#[derive(Debug, Serialize, Deserialize, Type)]
+pub struct SettingsView {
+    #[serde(flatten)]
+    pub settings: UserSettings,
+    pub grading_provider: LlmProvider,
+}
@@ -0,0 +1,9 @@
+use glp_core::setup::SetupStep;
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SetupProgress {
+    pub step: SetupStep,
+    pub line: String,
+}
@@ -0,0 +1,12 @@
+use glp_core::models::Certificate;
+use glp_core::stats::CurriculumCompletion;
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Debug, Serialize, Type)]
+pub struct CurriculumCompletionStatus {
+    #[serde(flatten)]
+    pub completion: CurriculumCompletion,
+    /// Issued the first time the curriculum is found complete
+    pub certificate: Option<Certificate>,
+}
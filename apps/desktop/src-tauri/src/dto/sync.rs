@@ -0,0 +1,9 @@
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Debug, Serialize, Type)]
+pub struct SyncStatus {
+    pub folder: Option<String>,
+    pub device_id: String,
+    pub last_synced_at: Option<String>,
+}
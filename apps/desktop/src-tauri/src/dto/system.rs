@@ -0,0 +1,30 @@
+use glp_grader::LlmProvider;
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Debug, Serialize, Type)]
+pub struct SystemStatus {
+    pub docker_installed: bool,
+    pub docker_running: bool,
+    pub api_key_set: bool,
+    pub database_ok: bool,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct DockerStatus {
+    pub installed: bool,
+    pub running: bool,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct ProviderStatus {
+    pub provider: LlmProvider,
+    pub name: String,
+    pub configured: bool,
+    pub requires_api_key: bool,
+    pub endpoint: String,
+    pub model: String,
+    /// Whether artifacts are graded with this provider
+    pub selected: bool,
+}
@@ -0,0 +1,28 @@
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Serialize, Type)]
+pub struct TrackDayData {
+    pub node_id: String,
+    pub day: u32,
+    pub title: String,
+    pub status: String,
+    pub completed: bool,
+}
+
+/// A project track's progress, kept apart from the regular nodes
+#[derive(Serialize, Type)]
+pub struct TrackProgressData {
+    pub track_id: String,
+    pub title: String,
+    pub description: String,
+    /// Suggested name for the learner's repo directory
+    pub repo_dir: String,
+    /// The repo the learner chose, once they have
+    pub repo_path: Option<String>,
+    pub days_total: usize,
+    pub days_completed: usize,
+    /// First day not yet completed
+    pub next_node_id: Option<String>,
+    pub days: Vec<TrackDayData>,
+}
@@ -0,0 +1,27 @@
+use glp_core::models::TrashEntry;
+use serde::Serialize;
+use specta::Type;
+
+/// A destructive operation that can still be undone
+#[derive(Debug, Serialize, Type)]
+pub struct UndoableOperation {
+    pub id: String,
+    pub operation: String,
+    pub subject_id: Option<String>,
+    pub row_count: usize,
+    pub created_at: String,
+    pub expires_at: String,
+}
+
+impl From<&TrashEntry> for UndoableOperation {
+    fn from(entry: &TrashEntry) -> Self {
+        Self {
+            id: entry.id.clone(),
+            operation: entry.operation.as_str().to_string(),
+            subject_id: entry.subject_id.clone(),
+            row_count: entry.row_count(),
+            created_at: entry.created_at.to_rfc3339(),
+            expires_at: entry.expires_at.to_rfc3339(),
+        }
+    }
+}
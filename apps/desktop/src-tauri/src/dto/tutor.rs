@@ -0,0 +1,18 @@
+use glp_core::models::{TutorMessage, TutorSession};
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Serialize, Type)]
+pub struct TutorConversation {
+    pub session: TutorSession,
+    /// Oldest first
+    pub messages: Vec<TutorMessage>,
+}
+
+#[derive(Serialize, Type)]
+pub struct TutorExchange {
+    pub question: TutorMessage,
+    pub reply: TutorMessage,
+    /// Older messages the tutor didn't see, to stay within its token budget
+    pub dropped_messages: usize,
+}
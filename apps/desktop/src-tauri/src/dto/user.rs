@@ -0,0 +1,37 @@
+use glp_core::models::User;
+use serde::Serialize;
+use specta::Type;
+
+#[derive(Serialize, Type)]
+pub struct UserData {
+    pub id: String,
+    pub display_name: String,
+    pub total_xp: i32,
+    pub current_level: i32,
+    pub current_streak: i32,
+    pub xp_for_next_level: i32,
+    pub xp_progress_percentage: f64,
+    pub is_guest: bool,
+}
+
+impl From<User> for UserData {
+    fn from(user: User) -> Self {
+        Self {
+            id: user.id.clone(),
+            display_name: user.profile_name(),
+            total_xp: user.total_xp,
+            current_level: user.current_level,
+            current_streak: user.current_streak,
+            xp_for_next_level: user.xp_for_next_level(),
+            xp_progress_percentage: user.xp_progress_percentage(),
+            is_guest: user.is_guest,
+        }
+    }
+}
+
+#[derive(Serialize, Type)]
+pub struct ProfileSummary {
+    #[serde(flatten)]
+    pub user: UserData,
+    pub is_current: bool,
+}
@@ -0,0 +1,359 @@
+use content::ContentError;
+use glp_core::backup::BackupError;
+use glp_core::db::error::DbError;
+use glp_core::focus::FocusError;
+use glp_core::goals::GoalError;
+use glp_core::hints::HintError;
+use glp_core::anki::import::ImportError;
+use glp_core::analytics::AnalyticsError;
+use glp_core::hooks::HookError;
+use glp_core::pacing::PacingError;
+use glp_core::reminders::ReminderError;
+use glp_core::services::ServiceError;
+use glp_core::settings::SettingsError;
+use glp_core::sandbox::SandboxImageError;
+use glp_core::setup::SetupError;
+use glp_core::sync::SyncError;
+use glp_grader::GraderError;
+use glp_runner::RunnerError;
+use serde::Serialize;
+use std::fmt;
+use std::sync::PoisonError;
+
+/// What went wrong, for the frontend to branch on without parsing messages.
+/// The frontend's `ErrorCode` type in `src/bindings.ts` is generated from this.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    DockerNotInstalled,
+    DockerNotRunning,
+    DockerError,
+    CodeTimeout,
+    LlmApiTimeout,
+    LlmRateLimited,
+    LlmApiKeyInvalid,
+    LlmApiKeyMissing,
+    LlmError,
+    DatabaseLocked,
+    DatabaseCorrupted,
+    DatabaseError,
+    NotFound,
+    ContentNotFound,
+    CurriculumNotLoaded,
+    /// The curriculum is encrypted and hasn't been unlocked with its key
+    ContentLocked,
+    /// The curriculum needs a license key and hasn't been activated
+    LicenseRequired,
+    /// The curriculum's license ran out, grace period included
+    LicenseExpired,
+    NotLoggedIn,
+    ValidationError,
+    /// The action doesn't apply to the current state, e.g. pausing a timer
+    /// that isn't running
+    InvalidState,
+    IoError,
+    Unknown,
+}
+
+/// Error returned by every command
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct CommandError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// Underlying error, when `message` is written for the user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+}
+
+pub type CommandResult<T> = Result<T, CommandError>;
+
+impl CommandError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::ValidationError, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn not_logged_in() -> Self {
+        Self::new(ErrorCode::NotLoggedIn, "No user logged in")
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Unknown, message)
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// For background jobs and helpers that still report plain strings
+impl From<CommandError> for String {
+    fn from(e: CommandError) -> Self {
+        e.message
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::internal(message)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        Self::internal(message)
+    }
+}
+
+impl From<DbError> for CommandError {
+    fn from(e: DbError) -> Self {
+        let code = match &e {
+            DbError::NotFound(_) => ErrorCode::NotFound,
+            DbError::Sqlite(rusqlite::Error::QueryReturnedNoRows) => ErrorCode::NotFound,
+            DbError::Sqlite(rusqlite::Error::SqliteFailure(failure, _)) => match failure.code {
+                rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked => ErrorCode::DatabaseLocked,
+                rusqlite::ErrorCode::DatabaseCorrupt | rusqlite::ErrorCode::NotADatabase => {
+                    ErrorCode::DatabaseCorrupted
+                }
+                _ => ErrorCode::DatabaseError,
+            },
+            _ => ErrorCode::DatabaseError,
+        };
+        Self::new(code, e.to_string())
+    }
+}
+
+impl From<RunnerError> for CommandError {
+    fn from(e: RunnerError) -> Self {
+        let code = match &e {
+            RunnerError::DockerNotAvailable => ErrorCode::DockerNotRunning,
+            RunnerError::Timeout(_) => ErrorCode::CodeTimeout,
+            RunnerError::Io(_) => ErrorCode::IoError,
+            RunnerError::InvalidSource(_) | RunnerError::CrateNotAllowed(_) => ErrorCode::ValidationError,
+            _ => ErrorCode::DockerError,
+        };
+        Self::new(code, e.to_string())
+    }
+}
+
+impl From<GraderError> for CommandError {
+    fn from(e: GraderError) -> Self {
+        let code = match &e {
+            GraderError::MissingApiKey(_) => ErrorCode::LlmApiKeyMissing,
+            GraderError::InvalidApiKey(_) => ErrorCode::LlmApiKeyInvalid,
+            GraderError::RateLimit(_) => ErrorCode::LlmRateLimited,
+            GraderError::Timeout(_) => ErrorCode::LlmApiTimeout,
+            GraderError::InvalidArtifact(_) | GraderError::InvalidSelfAssessment(_) | GraderError::Git(_) => {
+                ErrorCode::ValidationError
+            }
+            GraderError::RubricNotFound(_) => ErrorCode::NotFound,
+            GraderError::Io(_) => ErrorCode::IoError,
+            GraderError::Database(_) => ErrorCode::DatabaseError,
+            _ => ErrorCode::LlmError,
+        };
+        Self::new(code, e.to_string())
+    }
+}
+
+impl From<ContentError> for CommandError {
+    fn from(e: ContentError) -> Self {
+        let code = match &e {
+            ContentError::NotFound(_) => ErrorCode::ContentNotFound,
+            ContentError::Io(io) if io.kind() == std::io::ErrorKind::NotFound => ErrorCode::ContentNotFound,
+            ContentError::Io(_) => ErrorCode::IoError,
+            ContentError::Json(_) | ContentError::Validation(_) => ErrorCode::ValidationError,
+            ContentError::KeyRequired(_) => ErrorCode::ContentLocked,
+            ContentError::InvalidKey(_) | ContentError::Decryption(_) => ErrorCode::ValidationError,
+            ContentError::LicenseRequired => ErrorCode::LicenseRequired,
+            ContentError::LicenseExpired(_) => ErrorCode::LicenseExpired,
+            ContentError::InvalidLicense(_) => ErrorCode::ValidationError,
+        };
+        Self::new(code, e.to_string())
+    }
+}
+
+impl From<FocusError> for CommandError {
+    fn from(e: FocusError) -> Self {
+        match e {
+            FocusError::Db(e) => e.into(),
+            FocusError::InvalidSettings(_) => Self::validation(e.to_string()),
+            e => Self::new(ErrorCode::InvalidState, e.to_string()),
+        }
+    }
+}
+
+impl From<GoalError> for CommandError {
+    fn from(e: GoalError) -> Self {
+        match e {
+            GoalError::Db(e) => e.into(),
+            GoalError::InvalidGoal(_) => Self::validation(e.to_string()),
+        }
+    }
+}
+
+impl From<PacingError> for CommandError {
+    fn from(e: PacingError) -> Self {
+        match e {
+            PacingError::Db(e) => e.into(),
+            PacingError::Settings(e) => e.into(),
+            PacingError::InvalidTarget(_) => Self::validation(e.to_string()),
+        }
+    }
+}
+
+impl From<HintError> for CommandError {
+    fn from(e: HintError) -> Self {
+        match e {
+            HintError::Db(e) => e.into(),
+            HintError::InvalidSettings(_) => Self::validation(e.to_string()),
+            e => Self::new(ErrorCode::InvalidState, e.to_string()),
+        }
+    }
+}
+
+impl From<ReminderError> for CommandError {
+    fn from(e: ReminderError) -> Self {
+        match e {
+            ReminderError::Db(e) => e.into(),
+            ReminderError::InvalidPrefs(_) => Self::validation(e.to_string()),
+        }
+    }
+}
+
+impl From<SyncError> for CommandError {
+    fn from(e: SyncError) -> Self {
+        match e {
+            SyncError::Db(e) => e.into(),
+            SyncError::Io(e) => e.into(),
+            SyncError::InvalidLog { .. } => Self::validation(e.to_string()),
+        }
+    }
+}
+
+impl From<BackupError> for CommandError {
+    fn from(e: BackupError) -> Self {
+        match e {
+            BackupError::Db(e) => e.into(),
+            BackupError::Io(e) => e.into(),
+            e => Self::validation(e.to_string()),
+        }
+    }
+}
+
+impl From<ServiceError> for CommandError {
+    fn from(e: ServiceError) -> Self {
+        match e {
+            ServiceError::Db(e) => e.into(),
+            ServiceError::InvalidState(message) => Self::new(ErrorCode::InvalidState, message),
+        }
+    }
+}
+
+impl From<SettingsError> for CommandError {
+    fn from(e: SettingsError) -> Self {
+        match e {
+            SettingsError::Db(e) => e.into(),
+            SettingsError::InvalidSettings(_) => Self::validation(e.to_string()),
+        }
+    }
+}
+
+impl From<HookError> for CommandError {
+    fn from(e: HookError) -> Self {
+        match e {
+            HookError::Db(e) => e.into(),
+            HookError::InvalidHook(_) => Self::validation(e.to_string()),
+        }
+    }
+}
+
+impl From<AnalyticsError> for CommandError {
+    fn from(e: AnalyticsError) -> Self {
+        match e {
+            AnalyticsError::Db(e) => e.into(),
+            AnalyticsError::InvalidSettings(_) | AnalyticsError::InvalidEvent(_) => Self::validation(e.to_string()),
+        }
+    }
+}
+
+impl From<ImportError> for CommandError {
+    fn from(e: ImportError) -> Self {
+        match e {
+            ImportError::Db(e) => e.into(),
+            ImportError::InvalidDeck(_) => Self::validation(e.to_string()),
+        }
+    }
+}
+
+impl From<SandboxImageError> for CommandError {
+    fn from(e: SandboxImageError) -> Self {
+        match e {
+            SandboxImageError::InvalidManifest(_) | SandboxImageError::NotPinned(_) => Self::validation(e.to_string()),
+            SandboxImageError::NothingDownloaded | SandboxImageError::NothingToRollBack => {
+                Self::new(ErrorCode::InvalidState, e.to_string())
+            }
+        }
+    }
+}
+
+impl From<SetupError> for CommandError {
+    fn from(e: SetupError) -> Self {
+        Self::new(ErrorCode::InvalidState, e.to_string())
+    }
+}
+
+impl From<std::io::Error> for CommandError {
+    fn from(e: std::io::Error) -> Self {
+        Self::new(ErrorCode::IoError, e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for CommandError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::internal(e.to_string())
+    }
+}
+
+impl<T> From<PoisonError<T>> for CommandError {
+    fn from(e: PoisonError<T>) -> Self {
+        Self::internal(e.to_string())
+    }
+}
+
+impl From<tauri::Error> for CommandError {
+    fn from(e: tauri::Error) -> Self {
+        Self::internal(e.to_string())
+    }
+}
+
+impl From<tauri_plugin_updater::Error> for CommandError {
+    fn from(e: tauri_plugin_updater::Error) -> Self {
+        Self::internal(e.to_string())
+    }
+}
+
+impl From<zip::result::ZipError> for CommandError {
+    fn from(e: zip::result::ZipError) -> Self {
+        Self::internal(e.to_string())
+    }
+}
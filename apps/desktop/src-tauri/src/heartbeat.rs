@@ -0,0 +1,75 @@
+use crate::state::AppState;
+use chrono::Utc;
+use glp_core::db::repos::SessionRepository;
+use glp_core::models::session::HEARTBEAT_INTERVAL_SECS;
+use glp_core::models::RecoveredSession;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event name the frontend listens on for sessions closed after a crash
+pub const SESSION_RECOVERED_EVENT: &str = "session://recovered";
+
+/// Close sessions left open by a crash, then keep the current user's open
+/// session heartbeating while the app runs
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        recover_sessions(&app, &state).await;
+
+        let period = std::time::Duration::from_secs(HEARTBEAT_INTERVAL_SECS as u64);
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            let Some(user_id) = state.current_user_id.lock().ok().and_then(|guard| guard.clone()) else {
+                continue;
+            };
+
+            let result = state
+                .run_db(move |conn| {
+                    match SessionRepository::get_active_session(conn, &user_id)? {
+                        Some(session) => SessionRepository::record_heartbeat(conn, &session.id, Utc::now()).map(|_| ()),
+                        None => Ok(()),
+                    }
+                })
+                .await;
+            if let Err(e) = result {
+                tracing::warn!(error = %e, "Failed to record session heartbeat");
+            }
+        }
+    });
+}
+
+async fn recover_sessions(app: &AppHandle, state: &AppState) {
+    let recovered = match state.run_db(|conn| SessionRepository::recover_stale(conn, Utc::now())).await {
+        Ok(recovered) => recovered,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to recover interrupted sessions");
+            return;
+        }
+    };
+
+    for session in &recovered {
+        tracing::info!(
+            session = %session.session_id,
+            active_seconds = session.active_seconds,
+            items_completed = session.items_completed,
+            "Recovered interrupted session"
+        );
+        if let Err(e) = app.emit(SESSION_RECOVERED_EVENT, session) {
+            tracing::warn!(error = %e, "Failed to emit session recovery");
+        }
+    }
+
+    // The window may not be listening yet, so keep them until it asks
+    if let Ok(mut pending) = state.recovered_sessions.lock() {
+        pending.extend(recovered);
+    }
+}
+
+/// Sessions recovered at startup that the frontend hasn't yet been shown
+pub fn take_recovered(state: &AppState) -> Vec<RecoveredSession> {
+    state
+        .recovered_sessions
+        .lock()
+        .map(|mut pending| std::mem::take(&mut *pending))
+        .unwrap_or_default()
+}
@@ -0,0 +1,212 @@
+use crate::state::AppState;
+use chrono::Utc;
+use glp_core::db::repos::{HookRepository, UserRepository};
+use glp_core::gamification::GamificationEvent;
+use glp_core::hooks;
+use glp_core::models::{HookAction, HookDelivery, HookEvent};
+use serde::Serialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+/// Directory under the app data folder that command hooks' scripts live in
+pub const HOOKS_DIR: &str = "hooks";
+
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+const SCRIPT_TIMEOUT_SECS: u64 = 30;
+
+/// What a webhook is sent. `content` is the message chat services such as
+/// Discord show; the event's own fields come alongside it.
+#[derive(Serialize)]
+struct WebhookBody<'a> {
+    content: String,
+    #[serde(flatten)]
+    event: &'a HookEvent,
+}
+
+pub fn scripts_dir(state: &AppState) -> PathBuf {
+    state.app_data_dir().join(HOOKS_DIR)
+}
+
+/// Run the current user's hooks for the badges among `events`, and for a
+/// streak milestone if one was just reached. Runs in the background, so a
+/// slow hook never holds up the command that earned the badge.
+pub fn on_gamification_events(app: &AppHandle, events: &[GamificationEvent]) {
+    let badges: Vec<HookEvent> = events
+        .iter()
+        .filter_map(|event| match event {
+            GamificationEvent::BadgeUnlocked { badge_id, name, .. } => Some(HookEvent::BadgeEarned {
+                badge_id: badge_id.clone(),
+                name: name.clone(),
+            }),
+            _ => None,
+        })
+        .collect();
+    if events.is_empty() {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let Some(user_id) = state.current_user_id.lock().ok().and_then(|guard| guard.clone()) else {
+            return;
+        };
+        let uid = user_id.clone();
+        let milestone = state
+            .run_db(move |conn| {
+                let streak = UserRepository::get_by_id(conn, &uid)?.map_or(0, |user| user.current_streak);
+                Ok(hooks::streak_milestone(conn, &uid, streak))
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|milestone| milestone.map_err(|e| e.to_string()));
+        let mut hook_events = badges;
+        match milestone {
+            Ok(Some(event)) => hook_events.push(event),
+            Ok(None) => {}
+            Err(e) => tracing::warn!(error = %e, "Failed to check streak milestone"),
+        }
+        fire(&state, user_id, hook_events).await;
+    });
+}
+
+/// Queue `events` for the user's hooks and try delivering them straight
+/// away. Failures are left in the queue for the scheduler to retry.
+pub async fn fire(state: &AppState, user_id: String, events: Vec<HookEvent>) {
+    if events.is_empty() {
+        return;
+    }
+    let queued = state
+        .run_db(move |conn| {
+            let configured = match hooks::load_hooks(conn, &user_id) {
+                Ok(configured) => configured,
+                Err(hooks::HookError::Db(e)) => return Err(e),
+                Err(e) => {
+                    tracing::warn!(error = %e, "Ignoring unreadable hooks");
+                    return Ok(Vec::new());
+                }
+            };
+            let mut queued = Vec::new();
+            for event in events {
+                for hook in hooks::hooks_for(&configured, event.kind()) {
+                    let delivery = HookDelivery::new(user_id.clone(), hook, event.clone());
+                    HookRepository::enqueue(conn, &delivery)?;
+                    queued.push(delivery);
+                }
+            }
+            Ok(queued)
+        })
+        .await;
+
+    match queued {
+        Ok(queued) => {
+            for delivery in queued {
+                attempt(state, delivery).await;
+            }
+        }
+        Err(e) => tracing::warn!(error = %e, "Failed to queue hook events"),
+    }
+}
+
+/// Retry queued deliveries that are due; run by the scheduler
+pub async fn run_due_deliveries(state: &AppState) -> Result<String, String> {
+    let due = state.run_db(|conn| HookRepository::get_due(conn, Utc::now())).await?;
+    let mut delivered = 0;
+    let count = due.len();
+    for delivery in due {
+        if attempt(state, delivery).await {
+            delivered += 1;
+        }
+    }
+    Ok(format!("Delivered {} of {} queued hook events", delivered, count))
+}
+
+/// Deliver once and record the outcome. Returns whether it was delivered.
+async fn attempt(state: &AppState, mut delivery: HookDelivery) -> bool {
+    let outcome = deliver(&scripts_dir(state), &delivery).await;
+    let delivered = outcome.is_ok();
+    match outcome {
+        Ok(()) => delivery.complete(Utc::now()),
+        Err(error) => {
+            tracing::warn!(delivery = %delivery.id, hook = %delivery.hook_id, error = %error, "Hook delivery failed");
+            delivery.record_failure(error, Utc::now());
+        }
+    }
+    if let Err(e) = state.run_db(move |conn| HookRepository::update(conn, &delivery)).await {
+        tracing::warn!(error = %e, "Failed to record hook delivery");
+    }
+    delivered
+}
+
+/// Run a delivery's action. Its target is checked against the allow-list
+/// again, since the queue may hold actions saved before a check existed,
+/// and webhooks don't follow redirects to hosts the check never saw.
+async fn deliver(scripts_dir: &Path, delivery: &HookDelivery) -> Result<(), String> {
+    hooks::validate(&delivery.action).map_err(|e| e.to_string())?;
+    let event_json = serde_json::to_string(&delivery.event).map_err(|e| e.to_string())?;
+    match &delivery.action {
+        HookAction::Webhook { url } => {
+            let body = WebhookBody { content: delivery.event.message(), event: &delivery.event };
+            let response = reqwest::Client::builder()
+                .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .map_err(|e| e.to_string())?
+                .post(url)
+                .header("Content-Type", "application/json")
+                .body(serde_json::to_string(&body).map_err(|e| e.to_string())?)
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            if response.status().is_success() {
+                Ok(())
+            } else {
+                Err(format!("Webhook returned HTTP {}", response.status()))
+            }
+        }
+        HookAction::Command { script, args } => {
+            let path = scripts_dir.join(script);
+            let args = args.clone();
+            tauri::async_runtime::spawn_blocking(move || run_script(&path, &args, &event_json))
+                .await
+                .map_err(|e| e.to_string())?
+        }
+    }
+}
+
+/// Run a hook script directly, without a shell, with the event as JSON on
+/// stdin and in `GLP_EVENT`
+fn run_script(path: &Path, args: &[String], event_json: &str) -> Result<(), String> {
+    if !path.is_file() {
+        return Err(format!("{} doesn't exist", path.display()));
+    }
+    let mut child = Command::new(path)
+        .args(args)
+        .current_dir(path.parent().unwrap_or(path))
+        .env("GLP_EVENT", event_json)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Couldn't run {}: {}", path.display(), e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A script that doesn't read its input closes the pipe early
+        let _ = stdin.write_all(event_json.as_bytes());
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(SCRIPT_TIMEOUT_SECS);
+    loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            return if status.success() { Ok(()) } else { Err(format!("Script exited with {}", status)) };
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!("Script took over {}s", SCRIPT_TIMEOUT_SECS));
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
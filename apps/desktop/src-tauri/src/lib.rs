@@ -1,8 +1,19 @@
+mod analytics;
+mod capability;
 mod commands;
+mod dto;
+mod error;
+mod heartbeat;
+mod hooks;
+mod logging;
+mod notifications;
+mod reminders;
+mod scheduler;
 mod state;
 
 use state::AppState;
 use std::path::PathBuf;
+use tauri_specta::{collect_commands, Builder};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -11,77 +22,289 @@ pub fn run() {
         .unwrap_or_else(|_| PathBuf::from("."))
         .join("content");
 
+    // Held until the app exits so buffered log lines are flushed
+    let _log_guard = logging::init(&state::default_app_data_dir());
+
     // Initialize app state
     let app_state = AppState::new(content_path).expect("Failed to initialize app state");
 
+    let builder = commands_builder();
+    #[cfg(debug_assertions)]
+    export_bindings(&builder).expect("Failed to export TypeScript bindings");
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
         // NOTE: Updater disabled until signing keys are configured
         // .plugin(tauri_plugin_updater::Builder::new().build())
         .manage(app_state)
-        .invoke_handler(tauri::generate_handler![
-            // User commands
-            commands::user::get_user_data,
-            commands::user::create_user,
-            commands::user::update_user_xp,
-            // Progress commands
-            commands::progress::get_node_progress,
-            commands::progress::get_all_progress,
-            commands::progress::mark_node_complete,
-            commands::progress::start_node,
-            // Content commands
-            commands::content::get_content_tree,
-            commands::content::get_node_by_id,
-            commands::content::load_lecture,
-            commands::content::load_quiz,
-            // Lecture commands
-            commands::lecture::start_lecture,
-            commands::lecture::update_lecture_time,
-            commands::lecture::complete_lecture,
-            // Quiz commands
-            commands::quiz::submit_quiz,
-            // Session commands
-            commands::session::create_daily_session,
-            commands::session::start_session,
-            commands::session::complete_session,
-            commands::session::get_interrupted_session,
-            // Badge commands
-            commands::badge::get_all_badges,
-            commands::badge::get_earned_badges,
-            commands::badge::check_and_unlock_badges,
-            commands::badge::update_badge_progress,
-            // Review commands
-            commands::review::get_due_reviews,
-            commands::review::get_due_review_count,
-            commands::review::get_all_reviews,
-            commands::review::submit_review,
-            commands::review::create_review_item,
-            commands::review::apply_mastery_decay_on_startup,
-            commands::review::get_low_mastery_skills,
-            // Curriculum commands
-            commands::curriculum::validate_curriculum,
-            commands::curriculum::import_curriculum,
-            commands::curriculum::list_curricula,
-            commands::curriculum::get_active_curriculum,
-            commands::curriculum::switch_curriculum,
-            commands::curriculum::delete_curriculum,
-            commands::curriculum::get_curriculum,
-            // System commands
-            commands::system::check_system_status,
-            commands::system::check_docker_status,
-            commands::system::save_api_key,
-            commands::system::get_api_key_status,
-            commands::system::export_user_data,
-            commands::system::import_user_data,
-            commands::system::reset_all_progress,
-            commands::system::is_first_launch,
-            commands::system::complete_onboarding,
-            commands::system::is_onboarding_complete,
-            // Update commands (disabled until signing keys configured)
-            // commands::update::check_for_update,
-            // commands::update::download_and_install_update,
-        ])
+        .setup(|app| {
+            scheduler::start(app.handle().clone());
+            heartbeat::start(app.handle().clone());
+            reminders::start(app.handle().clone());
+            Ok(())
+        })
+        .invoke_handler(builder.invoke_handler())
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+/// Every command the webview can invoke, with the types of what they take
+/// and return
+fn commands_builder() -> Builder<tauri::Wry> {
+    Builder::<tauri::Wry>::new().commands(collect_commands![
+        // User commands
+        commands::user::get_user_data,
+        commands::user::create_user,
+        commands::user::update_user_xp,
+        // Profile commands
+        commands::user::list_profiles,
+        commands::user::create_profile,
+        commands::user::switch_profile,
+        commands::user::rename_profile,
+        commands::user::delete_profile,
+        commands::user::start_guest_session,
+        commands::user::get_profile_settings,
+        commands::user::set_profile_setting,
+        // Progress commands
+        commands::progress::get_node_progress,
+        commands::progress::get_all_progress,
+        commands::progress::mark_node_complete,
+        commands::progress::start_node,
+        commands::progress::get_best_attempts,
+        commands::progress::record_node_heartbeat,
+        commands::progress::get_time_histograms,
+        // Content commands
+        commands::content::get_content_tree,
+        commands::content::get_node_by_id,
+        commands::content::load_lecture,
+        // Lecture commands
+        commands::lecture::start_lecture,
+        commands::lecture::update_lecture_time,
+        commands::lecture::complete_lecture,
+        commands::lecture::check_lecture_question,
+        // Note commands
+        commands::note::create_note,
+        commands::note::update_note,
+        commands::note::delete_note,
+        commands::note::get_notes_for_node,
+        commands::note::list_notes,
+        // Bookmark commands
+        commands::bookmark::toggle_bookmark,
+        commands::bookmark::get_bookmarks,
+        commands::bookmark::reorder_bookmarks,
+        // Search commands
+        commands::search::search_my_history,
+        // Quiz commands
+        commands::quiz::load_quiz,
+        commands::quiz::start_quiz,
+        commands::quiz::submit_quiz,
+        commands::quiz::explain_quiz_mistake,
+        commands::quiz::get_quiz_explanations,
+        commands::quiz::get_quiz_attempt_detail,
+        commands::quiz::get_quiz_analytics,
+        // Challenge commands
+        commands::challenge::submit_challenge,
+        commands::challenge::check_challenge,
+        commands::challenge::save_challenge_draft,
+        commands::challenge::get_challenge_draft,
+        commands::challenge::list_challenge_attempts,
+        commands::challenge::restore_challenge_attempt,
+        commands::challenge::get_solution_feedback,
+        commands::challenge::get_code_review,
+        commands::challenge::export_challenge_workspace,
+        commands::challenge::submit_challenge_workspace,
+        // Project track commands
+        commands::track::get_track_progress,
+        commands::track::set_track_repo,
+        commands::track::submit_track_day,
+        // Session commands
+        commands::session::create_daily_session,
+        commands::session::start_session,
+        commands::session::complete_session,
+        commands::session::get_interrupted_session,
+        commands::session::take_recovered_sessions,
+        // Badge commands
+        commands::badge::get_all_badges,
+        commands::badge::get_earned_badges,
+        commands::badge::check_and_unlock_badges,
+        commands::badge::update_badge_progress,
+        // Review commands
+        commands::review::get_due_reviews,
+        commands::review::build_review_session,
+        commands::review::get_due_review_count,
+        commands::review::get_all_reviews,
+        commands::review::submit_review,
+        commands::review::create_review_item,
+        commands::review::get_leech_items,
+        commands::review::unsuspend_review,
+        commands::review::apply_mastery_decay_on_startup,
+        commands::review::get_low_mastery_skills,
+        commands::review::get_mastery_history,
+        commands::review::export_anki_deck,
+        commands::review::import_flashcards,
+        commands::review::get_flashcard,
+        // Event commands
+        commands::events::get_active_xp_events,
+        // Leaderboard commands
+        commands::leaderboard::get_leaderboard,
+        // Stats commands
+        commands::stats::get_dashboard_stats,
+        commands::stats::get_activity_heatmap,
+        commands::stats::export_activity_history,
+        commands::stats::export_weekly_report,
+        commands::stats::get_curriculum_completion,
+        commands::stats::export_certificate,
+        // xAPI commands
+        commands::xapi::export_xapi_statements,
+        commands::xapi::push_xapi_statements,
+        // Cohort commands
+        commands::cohort::cohort_create,
+        commands::cohort::cohort_list,
+        commands::cohort::cohort_delete,
+        commands::cohort::cohort_enroll,
+        commands::cohort::cohort_unenroll,
+        commands::cohort::cohort_import_student,
+        commands::cohort::cohort_get_report,
+        commands::cohort::cohort_export_report,
+        commands::cohort::cohort_flagged_attempts,
+        // Quest commands
+        commands::quest::get_active_quests,
+        commands::quest::claim_quest_reward,
+        // Goal commands
+        commands::goal::create_goal,
+        commands::goal::get_goals_status,
+        commands::goal::delete_goal,
+        commands::pacing::set_pace_target,
+        commands::pacing::get_pace_plan,
+        commands::pacing::clear_pace_target,
+        // Curriculum commands
+        commands::curriculum::validate_curriculum,
+        commands::curriculum::import_curriculum,
+        commands::curriculum::list_curricula,
+        commands::curriculum::get_active_curriculum,
+        commands::curriculum::switch_curriculum,
+        commands::curriculum::unlock_curriculum,
+        commands::curriculum::activate_curriculum,
+        commands::curriculum::get_curriculum_license,
+        commands::curriculum::delete_curriculum,
+        commands::curriculum::get_curriculum,
+        // System commands
+        commands::system::check_system_status,
+        commands::system::check_docker_status,
+        commands::system::save_api_key,
+        commands::system::get_api_key_status,
+        commands::system::save_provider_credentials,
+        commands::system::remove_provider_credentials,
+        commands::system::set_grading_provider,
+        commands::system::get_provider_status,
+        commands::system::test_api_key,
+        commands::system::export_user_data,
+        commands::system::import_user_data,
+        commands::system::is_backup_encrypted,
+        commands::system::reset_all_progress,
+        commands::system::audit_user_xp,
+        commands::system::is_first_launch,
+        commands::system::complete_onboarding,
+        commands::system::is_onboarding_complete,
+        // Setup commands
+        commands::settings::get_settings,
+        commands::settings::update_settings,
+        commands::setup::get_setup_state,
+        commands::setup::advance_setup_step,
+        commands::sandbox::get_sandbox_image_status,
+        commands::sandbox::set_sandbox_image_source,
+        commands::sandbox::update_sandbox_image,
+        // Trash commands
+        commands::trash::get_undoable_operation,
+        commands::trash::undo_last_destructive_operation,
+        commands::trash::get_undo_window_minutes,
+        commands::trash::set_undo_window_minutes,
+        // Sync commands
+        commands::sync::configure_sync_folder,
+        commands::sync::get_sync_status,
+        commands::sync::sync_now,
+        // Focus commands
+        commands::focus::get_focus_state,
+        commands::focus::start_focus_block,
+        commands::focus::pause_focus_block,
+        commands::focus::resume_focus_block,
+        commands::focus::cancel_focus_block,
+        commands::focus::update_focus_settings,
+        // Hint commands
+        commands::hint::get_hint_state,
+        commands::hint::get_next_hint,
+        commands::hint::get_hint_settings,
+        commands::hint::update_hint_settings,
+        // Capability commands
+        commands::capability::get_capability_status,
+        // Checkpoint commands
+        commands::checkpoint::submit_checkpoint,
+        commands::checkpoint::submit_artifact,
+        commands::checkpoint::submit_checkpoint_repo,
+        commands::checkpoint::submit_checkpoint_github,
+        commands::checkpoint::get_checkpoint_rubric,
+        commands::checkpoint::get_checkpoint_readiness,
+        commands::checkpoint::self_assess_artifact,
+        // Related content commands
+        commands::related::find_related,
+        commands::related::find_content_for_skill,
+        // Practice commands
+        commands::practice::get_practice_skills,
+        commands::practice::generate_practice,
+        commands::practice::get_practice_challenge,
+        commands::practice::submit_practice,
+        // Tutor commands
+        commands::tutor::start_tutor_session,
+        commands::tutor::get_tutor_session,
+        commands::tutor::send_tutor_message,
+        // Reminder commands
+        commands::reminders::get_notification_schedule,
+        commands::reminders::update_notification_prefs,
+        // Hook commands
+        commands::hooks::get_hooks,
+        commands::hooks::configure_hooks,
+        // Analytics commands
+        commands::analytics::get_analytics_settings,
+        commands::analytics::set_analytics_settings,
+        commands::analytics::preview_analytics,
+        commands::analytics::purge_analytics,
+        // Job commands
+        commands::jobs::get_job_status,
+        // Diagnostics commands
+        commands::diagnostics::export_diagnostics,
+        // Update commands (disabled until signing keys configured)
+        // commands::update::check_for_update,
+        // commands::update::download_and_install_update,
+    ])
+}
+
+/// Write the frontend's `src/bindings.ts` from the commands' signatures, so
+/// its types can't drift from the Rust ones. Integers cross the IPC boundary
+/// as JSON numbers, including 64-bit ones.
+#[cfg(any(debug_assertions, test))]
+fn export_bindings(builder: &Builder<tauri::Wry>) -> Result<(), specta_typescript::ExportError> {
+    use specta_typescript::{BigIntExportBehavior, Typescript};
+
+    // The generated globals import helpers that not every command set uses,
+    // which `noUnusedLocals` would reject
+    builder.export(
+        Typescript::default()
+            .header("// @ts-nocheck")
+            .bigint(BigIntExportBehavior::Number),
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../src/bindings.ts"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `cargo test --lib export_bindings` regenerates the bindings without
+    /// starting the app
+    #[test]
+    fn export_bindings_for_every_command() {
+        export_bindings(&commands_builder()).unwrap();
+    }
+}
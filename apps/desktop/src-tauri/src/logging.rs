@@ -0,0 +1,73 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, EnvFilter};
+
+const LOG_FILE_PREFIX: &str = "glp";
+const LOG_FILE_SUFFIX: &str = "log";
+
+/// Daily log files kept before the oldest is deleted
+const LOG_FILES_KEPT: usize = 7;
+
+pub fn log_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("logs")
+}
+
+/// Log to stdout, and as JSON lines to a daily file in the app data dir.
+/// `RUST_LOG` overrides the default `info` level.
+///
+/// File writes happen on a background thread; the returned guard flushes
+/// them when dropped, so hold it for as long as the app runs.
+pub fn init(app_data_dir: &Path) -> Option<WorkerGuard> {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(LOG_FILES_KEPT)
+        .build(log_dir(app_data_dir));
+
+    let (file_layer, guard, file_error) = match appender {
+        Ok(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = fmt::layer().json().with_writer(writer);
+            (Some(layer), Some(guard), None)
+        }
+        Err(e) => (None, None, Some(e)),
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer())
+        .with(file_layer)
+        .init();
+
+    if let Some(e) = file_error {
+        tracing::warn!(error = %e, "Logging to stdout only; could not open the log file");
+    }
+    guard
+}
+
+/// Log files, newest first, up to `limit` of them
+pub fn recent_log_files(app_data_dir: &Path, limit: usize) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(log_dir(app_data_dir)) else {
+        return Vec::new();
+    };
+
+    // Daily files are suffixed with their date, so name order is age order
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX) && name.ends_with(LOG_FILE_SUFFIX))
+        })
+        .collect();
+    files.sort();
+    files.reverse();
+    files.truncate(limit);
+    files
+}
@@ -0,0 +1,17 @@
+use crate::hooks;
+use glp_core::gamification::GamificationEvent;
+use tauri::{AppHandle, Emitter};
+
+/// Event name the frontend listens on for gamification updates
+pub const GAMIFICATION_EVENT: &str = "gamification://event";
+
+/// Push gamification events to the frontend and run the user's hooks for them.
+/// Emission failures are logged rather than failing the command that produced them.
+pub fn emit_gamification_events(app: &AppHandle, events: &[GamificationEvent]) {
+    for event in events {
+        if let Err(e) = app.emit(GAMIFICATION_EVENT, event) {
+            tracing::warn!(error = %e, "Failed to emit gamification event");
+        }
+    }
+    hooks::on_gamification_events(app, events);
+}
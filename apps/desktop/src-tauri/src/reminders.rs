@@ -0,0 +1,53 @@
+use crate::state::AppState;
+use chrono::Local;
+use glp_core::db::repos::NotificationRepository;
+use glp_core::reminders::{self, ScheduledReminder};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// How often due reminders are checked for
+const TICK: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Deliver the current user's study reminders as system notifications while
+/// the app runs
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(TICK);
+        loop {
+            interval.tick().await;
+            let state = app.state::<AppState>();
+            let Some(user_id) = state.current_user_id.lock().ok().and_then(|guard| guard.clone()) else {
+                continue;
+            };
+            let curriculum_id = state.get_active_curriculum_id();
+
+            let due = state
+                .run_db(move |conn| {
+                    let due = reminders::due_reminders(conn, &user_id, curriculum_id.as_deref(), &Local::now())?;
+                    // Recorded before showing, so a failure can't repeat a reminder every minute
+                    for reminder in &due {
+                        NotificationRepository::record_sent(conn, &user_id, reminder.kind, reminder.for_date)?;
+                    }
+                    Ok(due)
+                })
+                .await;
+            match due {
+                Ok(due) => due.iter().for_each(|reminder| show(&app, reminder)),
+                Err(e) => tracing::warn!(error = %e, "Failed to check study reminders"),
+            }
+        }
+    });
+}
+
+fn show(app: &AppHandle, reminder: &ScheduledReminder) {
+    let shown = app
+        .notification()
+        .builder()
+        .title(&reminder.title)
+        .body(&reminder.body)
+        .show();
+    match shown {
+        Ok(()) => tracing::info!(kind = reminder.kind.as_str(), for_date = %reminder.for_date, "Sent study reminder"),
+        Err(e) => tracing::warn!(kind = reminder.kind.as_str(), error = %e, "Failed to show study reminder"),
+    }
+}
@@ -0,0 +1,266 @@
+use crate::analytics;
+use crate::capability;
+use crate::hooks;
+use crate::commands::{checkpoint, review, sandbox, system, trash};
+use crate::state::AppState;
+use chrono::{Duration, Utc};
+use glp_core::backup::{self, rotation};
+use glp_core::db::repos::{GradeCacheRepository, JobRepository, UserRepository};
+use glp_core::scheduler::Schedule;
+use glp_grader::GradeCache;
+use std::collections::HashSet;
+use std::process::Command;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+/// How often the scheduler wakes to check for due jobs
+const TICK: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Grades cached longer than this are dropped
+const GRADE_CACHE_MAX_AGE_DAYS: i64 = 30;
+
+/// Automatic backups kept per profile
+const BACKUPS_KEPT: usize = 7;
+
+/// A maintenance task run in the background while the app is open
+pub struct Job {
+    pub id: &'static str,
+    pub description: &'static str,
+    /// Spec understood by [`Schedule::parse`]
+    pub schedule: &'static str,
+}
+
+pub const JOBS: [Job; 9] = [
+    Job {
+        id: "mastery_decay",
+        description: "Decay mastery of skills that haven't been practised",
+        schedule: "@every 6h",
+    },
+    Job {
+        id: "trash_purge",
+        description: "Permanently delete trash whose undo window has closed",
+        schedule: "@every 10m",
+    },
+    Job {
+        id: "container_cleanup",
+        description: "Remove challenge containers left behind by crashed runs",
+        schedule: "@hourly",
+    },
+    Job {
+        id: "grade_cache_prune",
+        description: "Drop cached artifact grades older than 30 days",
+        schedule: "@daily",
+    },
+    Job {
+        id: "backup_rotation",
+        description: "Back up every profile, keeping the last 7 backups",
+        schedule: "@daily",
+    },
+    Job {
+        id: "pending_work",
+        description: "Run work queued while Docker or the grading provider was unreachable",
+        schedule: "@every 5m",
+    },
+    Job {
+        id: "hook_deliveries",
+        description: "Retry hook events that couldn't be delivered",
+        schedule: "@every 1m",
+    },
+    Job {
+        id: "analytics_upload",
+        description: "Upload analytics for profiles that opted in",
+        schedule: "@hourly",
+    },
+    Job {
+        id: "sandbox_image_update",
+        description: "Download sandbox image updates from the configured manifest",
+        schedule: "@daily",
+    },
+];
+
+impl Job {
+    pub fn parsed_schedule(&self) -> Schedule {
+        Schedule::parse(self.schedule).expect("built-in job schedules are valid")
+    }
+}
+
+/// Tracks which jobs are running, so status can be reported while they run
+#[derive(Default)]
+pub struct JobScheduler {
+    running: Mutex<HashSet<&'static str>>,
+}
+
+impl JobScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_running(&self, job_id: &str) -> bool {
+        self.running
+            .lock()
+            .map(|running| running.contains(job_id))
+            .unwrap_or(false)
+    }
+
+    fn set_running(&self, job_id: &'static str, running: bool) {
+        if let Ok(mut guard) = self.running.lock() {
+            if running {
+                guard.insert(job_id);
+            } else {
+                guard.remove(job_id);
+            }
+        }
+    }
+}
+
+/// Start the scheduler loop. Jobs missed while the app was closed run on the
+/// first tick.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(TICK);
+        loop {
+            interval.tick().await;
+            let state = app.state::<AppState>();
+            run_due_jobs(&state).await;
+        }
+    });
+}
+
+async fn run_due_jobs(state: &AppState) {
+    let runs = match state.run_db(JobRepository::get_runs).await {
+        Ok(runs) => runs,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to load job history");
+            return;
+        }
+    };
+
+    for job in &JOBS {
+        let last_run = runs.iter().find(|r| r.job_id == job.id).map(|r| r.last_started_at);
+        if job.parsed_schedule().is_due(last_run, Utc::now()) {
+            run_job(state, job).await;
+        }
+    }
+}
+
+async fn run_job(state: &AppState, job: &Job) {
+    let started_at = Utc::now();
+    state.scheduler.set_running(job.id, true);
+
+    let outcome = match job.id {
+        "mastery_decay" => decay_mastery(state).await,
+        "trash_purge" => trash::purge_expired_trash(state)
+            .await
+            .map(|_| "Expired trash purged".to_string())
+            .map_err(String::from),
+        "container_cleanup" => cleanup_containers().await,
+        "grade_cache_prune" => prune_grade_cache(state).await,
+        "backup_rotation" => rotate_backups(state).await,
+        "pending_work" => capability::run_pending_work(state).await,
+        "hook_deliveries" => hooks::run_due_deliveries(state).await,
+        "analytics_upload" => analytics::upload_queued(state).await,
+        "sandbox_image_update" => sandbox::check_for_update().await.map_err(String::from),
+        other => Err(format!("Unknown job {}", other)),
+    };
+
+    state.scheduler.set_running(job.id, false);
+    let job_id = job.id;
+    let duration_ms = (Utc::now() - started_at).num_milliseconds();
+    match &outcome {
+        Ok(summary) => tracing::info!(job = job_id, duration_ms, summary = %summary, "Job finished"),
+        Err(e) => tracing::error!(job = job_id, duration_ms, error = %e, "Job failed"),
+    }
+
+    let recorded = state
+        .run_db(move |conn| {
+            JobRepository::record_run(conn, job_id, started_at, duration_ms, outcome.as_deref().map_err(String::as_str))
+        })
+        .await;
+    if let Err(e) = recorded {
+        tracing::error!(job = job_id, error = %e, "Failed to record job run");
+    }
+}
+
+async fn decay_mastery(state: &AppState) -> Result<String, String> {
+    let decayed = review::apply_mastery_decay(state).await?;
+    Ok(format!("Decayed {} skills", decayed))
+}
+
+/// Remove stopped challenge containers, which a crash can leave behind
+async fn cleanup_containers() -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        if !system::check_docker_internal().running {
+            return Ok("Docker is not running".to_string());
+        }
+
+        let output = Command::new("docker")
+            .args([
+                "container",
+                "prune",
+                "--force",
+                "--filter",
+                "label=app=gamified-rust-challenge",
+                "--filter",
+                "until=1h",
+            ])
+            .output()
+            .map_err(|e| format!("Failed to run docker: {}", e))?;
+
+        if output.status.success() {
+            let summary = String::from_utf8_lossy(&output.stdout);
+            Ok(summary.lines().last().unwrap_or("").trim().to_string())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+async fn prune_grade_cache(state: &AppState) -> Result<String, String> {
+    let cutoff = Utc::now() - Duration::days(GRADE_CACHE_MAX_AGE_DAYS);
+    let removed = state
+        .run_db(move |conn| GradeCacheRepository::prune_older_than(conn, cutoff))
+        .await?;
+
+    let cache_path = state.app_data_dir().join(checkpoint::GRADE_CACHE_FILE);
+    let removed_artifact_grades = if cache_path.exists() {
+        tauri::async_runtime::spawn_blocking(move || {
+            GradeCache::new(&cache_path)?.cleanup_old_entries(GRADE_CACHE_MAX_AGE_DAYS as u32)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?
+    } else {
+        0
+    };
+    Ok(format!("Removed {} cached grades", removed + removed_artifact_grades))
+}
+
+/// Write a fresh backup of each profile into the app data folder, dropping
+/// the oldest ones
+async fn rotate_backups(state: &AppState) -> Result<String, String> {
+    let backups = state
+        .run_db(|conn| {
+            let mut backups = Vec::new();
+            for user in UserRepository::get_all(conn)? {
+                backups.push((user.id.clone(), backup::collect(conn, &user.id)?));
+            }
+            Ok(backups)
+        })
+        .await?;
+
+    let dir = state.app_data_dir().join("backups");
+    let count = backups.len();
+    tauri::async_runtime::spawn_blocking(move || {
+        for (user_id, data) in backups {
+            rotation::write_rotated(&dir, &format!("auto-{}", user_id), &data, BACKUPS_KEPT)
+                .map_err(|e| e.to_string())?;
+        }
+        Ok::<_, String>(())
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    Ok(format!("Backed up {} profiles", count))
+}
@@ -1,15 +1,38 @@
 use content::ContentLoader;
+use crate::capability::CapabilityService;
+use crate::error::{CommandError, CommandResult, ErrorCode};
+use crate::scheduler::JobScheduler;
 use glp_core::AppDatabase;
-use glp_core::db::repos::CurriculumRepository;
+use glp_core::db::error::DbResult;
+use glp_core::db::repos::{CurriculumRepository, UserRepository};
+use glp_core::models::RecoveredSession;
+use rusqlite::Connection;
 use std::path::PathBuf;
 use std::sync::Mutex;
 
+/// Profile setting holding the curriculum the profile last had active
+pub const ACTIVE_CURRICULUM_SETTING: &str = "active_curriculum_id";
+
+/// Where the database, curricula, backups and logs are stored
+pub fn default_app_data_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("gamified-learning-platform")
+}
+
 pub struct AppState {
     pub db: AppDatabase,
     pub content_loader: Mutex<Option<ContentLoader>>,
     pub current_user_id: Mutex<Option<String>>,
     pub app_data_dir: PathBuf,
     pub active_curriculum_id: Mutex<Option<String>>,
+    /// Content pack imported during first-run setup
+    pub default_content_path: PathBuf,
+    pub scheduler: JobScheduler,
+    /// Whether Docker and the grading provider are reachable
+    pub capabilities: CapabilityService,
+    /// Sessions closed after a crash, held until the frontend fetches them
+    pub recovered_sessions: Mutex<Vec<RecoveredSession>>,
 }
 
 impl AppState {
@@ -18,6 +41,20 @@ impl AppState {
         &self.db
     }
 
+    /// Run database work on a blocking thread with a pooled connection, so
+    /// long queries don't hold up the thread serving IPC
+    pub async fn run_db<F, T>(&self, f: F) -> CommandResult<T>
+    where
+        F: FnOnce(&Connection) -> DbResult<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let db = self.db.clone();
+        tauri::async_runtime::spawn_blocking(move || db.with_connection(f))
+            .await
+            .map_err(|e| CommandError::internal("Database task failed").with_details(e.to_string()))?
+            .map_err(CommandError::from)
+    }
+
     pub fn get_current_user_id(&self) -> String {
         self.current_user_id
             .lock()
@@ -33,26 +70,31 @@ impl AppState {
             .and_then(|guard| guard.clone())
     }
 
+    /// Active curriculum ID for commands that read or write progress, which
+    /// is always scoped to one curriculum
+    pub fn require_active_curriculum(&self) -> CommandResult<String> {
+        self.active_curriculum_id
+            .lock()?
+            .clone()
+            .ok_or_else(|| CommandError::new(ErrorCode::CurriculumNotLoaded, "No active curriculum"))
+    }
+
     pub fn app_data_dir(&self) -> &PathBuf {
         &self.app_data_dir
     }
 
-    pub fn new(_content_path: PathBuf) -> Result<Self, String> {
-        // Get app data directory for database and curricula
-        let app_data_dir = dirs::data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("gamified-learning-platform");
+    pub fn new(default_content_path: PathBuf) -> CommandResult<Self> {
+        let app_data_dir = default_app_data_dir();
 
-        std::fs::create_dir_all(&app_data_dir).map_err(|e| e.to_string())?;
-        std::fs::create_dir_all(app_data_dir.join("curricula")).map_err(|e| e.to_string())?;
+        std::fs::create_dir_all(&app_data_dir)?;
+        std::fs::create_dir_all(app_data_dir.join("curricula"))?;
 
         let db_path = app_data_dir.join("app.db");
 
-        println!("Database path: {:?}", db_path);
-        println!("App data dir: {:?}", app_data_dir);
+        tracing::info!(db_path = %db_path.display(), app_data_dir = %app_data_dir.display(), "Opening database");
 
         // Initialize database
-        let db = AppDatabase::new(db_path).map_err(|e| e.to_string())?;
+        let db = AppDatabase::new(db_path)?;
 
         // Try to load the active curriculum from database
         let (content_loader, active_curriculum_id) = db
@@ -61,74 +103,98 @@ impl AppState {
                     Some(curriculum) => {
                         let content_path = app_data_dir.join(&curriculum.content_path);
                         if content_path.join("manifest.json").exists() {
-                            match ContentLoader::new(content_path) {
+                            let content_key = CurriculumRepository::get_content_key(conn, &curriculum.id)?;
+                            let license_key = CurriculumRepository::get_license_key(conn, &curriculum.id)?;
+                            match ContentLoader::open(content_path, content_key.as_deref(), license_key.as_deref()) {
                                 Ok(loader) => {
-                                    println!("Loaded active curriculum: {}", curriculum.name);
+                                    tracing::info!(curriculum = %curriculum.name, "Loaded active curriculum");
                                     Ok((Some(loader), Some(curriculum.id)))
                                 }
                                 Err(e) => {
-                                    eprintln!("Warning: Failed to load curriculum content: {}", e);
+                                    tracing::warn!(error = %e, "Failed to load curriculum content");
                                     Ok((None, None))
                                 }
                             }
                         } else {
-                            eprintln!("Warning: Active curriculum content not found at {:?}", content_path);
+                            tracing::warn!(path = %content_path.display(), "Active curriculum content not found");
                             Ok((None, None))
                         }
                     }
                     None => {
-                        println!("No active curriculum set");
+                        tracing::info!("No active curriculum set");
                         Ok((None, None))
                     }
                 }
-            })
-            .map_err(|e| e.to_string())?;
+            })?;
+
+        // A guest session ends with the app; then resume the most recently
+        // used saved profile
+        let last_user_id = db.with_connection(|conn| {
+            let discarded = UserRepository::delete_guests(conn)?;
+            if discarded > 0 {
+                tracing::info!(discarded, "Discarded guest profiles");
+            }
+            Ok(UserRepository::get_all(conn)?.into_iter().next().map(|u| u.id))
+        })?;
 
         Ok(Self {
             db,
             content_loader: Mutex::new(content_loader),
-            current_user_id: Mutex::new(None),
+            current_user_id: Mutex::new(last_user_id),
             app_data_dir,
             active_curriculum_id: Mutex::new(active_curriculum_id),
+            default_content_path,
+            scheduler: JobScheduler::new(),
+            capabilities: CapabilityService::new(),
+            recovered_sessions: Mutex::new(Vec::new()),
         })
     }
 
     /// Load a curriculum by ID and set it as active
-    pub fn load_curriculum(&self, curriculum_id: &str) -> Result<(), String> {
+    pub fn load_curriculum(&self, curriculum_id: &str) -> CommandResult<()> {
         let curriculum = self.db
             .with_connection(|conn| {
                 CurriculumRepository::get(conn, curriculum_id)
-            })
-            .map_err(|e| e.to_string())?
-            .ok_or_else(|| format!("Curriculum not found: {}", curriculum_id))?;
-
+            })?
+            .ok_or_else(|| CommandError::not_found(format!("Curriculum not found: {}", curriculum_id)))?;
+
+        let (content_key, license_key) = self.db.with_connection(|conn| {
+            Ok((
+                CurriculumRepository::get_content_key(conn, curriculum_id)?,
+                CurriculumRepository::get_license_key(conn, curriculum_id)?,
+            ))
+        })?;
         let content_path = self.app_data_dir.join(&curriculum.content_path);
-        let loader = ContentLoader::new(content_path).map_err(|e| e.to_string())?;
+        let loader = ContentLoader::open(content_path, content_key.as_deref(), license_key.as_deref())?;
 
         // Update content loader
-        let mut content_guard = self.content_loader.lock().map_err(|e| e.to_string())?;
+        let mut content_guard = self.content_loader.lock()?;
         *content_guard = Some(loader);
 
         // Update active curriculum ID
-        let mut id_guard = self.active_curriculum_id.lock().map_err(|e| e.to_string())?;
+        let mut id_guard = self.active_curriculum_id.lock()?;
         *id_guard = Some(curriculum_id.to_string());
 
-        // Update database
+        // Update database, remembering the choice for the current profile
+        let user_id = self.current_user_id.lock()?.clone();
         self.db
             .with_connection(|conn| {
-                CurriculumRepository::set_active(conn, curriculum_id)
-            })
-            .map_err(|e| e.to_string())?;
+                CurriculumRepository::set_active(conn, curriculum_id)?;
+                match &user_id {
+                    Some(user_id) => UserRepository::set_setting(conn, user_id, ACTIVE_CURRICULUM_SETTING, curriculum_id),
+                    None => Ok(()),
+                }
+            })?;
 
         Ok(())
     }
 
     /// Unload the current curriculum
-    pub fn unload_curriculum(&self) -> Result<(), String> {
-        let mut content_guard = self.content_loader.lock().map_err(|e| e.to_string())?;
+    pub fn unload_curriculum(&self) -> CommandResult<()> {
+        let mut content_guard = self.content_loader.lock()?;
         *content_guard = None;
 
-        let mut id_guard = self.active_curriculum_id.lock().map_err(|e| e.to_string())?;
+        let mut id_guard = self.active_curriculum_id.lock()?;
         *id_guard = None;
 
         Ok(())
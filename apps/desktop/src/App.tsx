@@ -4,8 +4,10 @@ import { Navigation } from './components/layout/Navigation'
 import { StatusBar } from './components/layout/StatusBar'
 import { BadgeUnlockNotification } from './components/badges/BadgeUnlockNotification'
 import { KeyboardShortcutsModal } from './components/common/KeyboardShortcutsModal'
+import { SessionRecoveredNotice } from './components/common/SessionRecoveredNotice'
 import { UpdateChecker } from './components/UpdateChecker'
 import { useKeyboardShortcuts } from './hooks/useKeyboardShortcuts'
+import { useGamificationEvents } from './hooks/useGamificationEvents'
 import { useThemeStore } from './stores/themeStore'
 import { Home } from './pages/Home'
 import { Lecture } from './pages/Lecture'
@@ -24,6 +26,8 @@ function AppContent() {
   const { shortcuts, isHelpModalOpen, closeHelpModal } = useKeyboardShortcuts()
   const theme = useThemeStore((state) => state.theme)
 
+  useGamificationEvents()
+
   // Apply theme to document
   useEffect(() => {
     const root = document.documentElement
@@ -61,6 +65,7 @@ function AppContent() {
       {!isWelcomePage && <StatusBar />}
       {!isWelcomePage && <BadgeUnlockNotification />}
       {!isWelcomePage && <UpdateChecker />}
+      {!isWelcomePage && <SessionRecoveredNotice />}
 
       <KeyboardShortcutsModal
         isOpen={isHelpModalOpen}
@@ -0,0 +1,3778 @@
+// @ts-nocheck
+// This file was generated by [tauri-specta](https://github.com/oscartbeaumont/tauri-specta). Do not edit this file manually.
+
+/** user-defined commands **/
+
+
+export const commands = {
+async getUserData() : Promise<Result<UserData | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_user_data") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async createUser() : Promise<Result<UserData, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_user") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateUserXp(xpDelta: number) : Promise<Result<UserData, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_user_xp", { xpDelta }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List all local profiles, most recently used first
+ */
+async listProfiles() : Promise<Result<ProfileSummary[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_profiles") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Create a named profile and switch to it
+ */
+async createProfile(displayName: string) : Promise<Result<UserData, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_profile", { displayName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Switch the active profile
+ */
+async switchProfile(userId: string) : Promise<Result<UserData, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("switch_profile", { userId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Rename a profile
+ */
+async renameProfile(userId: string, displayName: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("rename_profile", { userId, displayName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete a profile and everything recorded for it. Deleting the current
+ * profile leaves no one signed in.
+ */
+async deleteProfile(userId: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_profile", { userId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Try the app in a throwaway guest profile. Its progress is discarded when
+ * switching to another profile, deleting it, or restarting the app.
+ */
+async startGuestSession() : Promise<Result<UserData, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_guest_session") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The current profile's settings
+ */
+async getProfileSettings() : Promise<Result<Partial<{ [key in string]: string }>, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_profile_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Store a setting for the current profile only
+ */
+async setProfileSetting(key: string, value: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_profile_setting", { key, value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getNodeProgress(nodeId: string) : Promise<Result<ProgressData | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_node_progress", { nodeId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getAllProgress() : Promise<Result<ProgressData[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_all_progress") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async markNodeComplete(nodeId: string) : Promise<Result<ProgressData, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mark_node_complete", { nodeId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async startNode(nodeId: string) : Promise<Result<ProgressData, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_node", { nodeId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Best results at a node: one for a challenge, one per artifact for a
+ * checkpoint. Improving on one earns only the XP it adds.
+ */
+async getBestAttempts(nodeId: string) : Promise<Result<BestAttemptData[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_best_attempts", { nodeId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Record that the learner is active on a lecture or challenge. The page
+ * sends one every `NODE_HEARTBEAT_INTERVAL_SECS` while it has focus and
+ * the learner has interacted recently; gaps over `NODE_IDLE_AFTER_SECS`
+ * aren't counted.
+ */
+async recordNodeHeartbeat(nodeId: string) : Promise<Result<NodeTime, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("record_node_heartbeat", { nodeId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Active time every profile took on each node of the active curriculum
+ * they completed, bucketed by `bucket_minutes`
+ */
+async getTimeHistograms(bucketMinutes: number) : Promise<Result<TimeHistogram[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_time_histograms", { bucketMinutes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getContentTree() : Promise<Result<ContentTree | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_content_tree") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getNodeById(nodeId: string) : Promise<Result<NodeData | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_node_by_id", { nodeId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async loadLecture(contentPath: string) : Promise<Result<LectureView, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("load_lecture", { contentPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async startLecture(lectureId: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_lecture", { lectureId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateLectureTime(lectureId: string, timeSpentMs: number) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_lecture_time", { lectureId, timeSpentMs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async completeLecture(request: CompleteLectureRequest) : Promise<Result<CompletionResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("complete_lecture", { request }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check the answer to one of a lecture's inline questions, given by its id
+ * in the lecture as `load_lecture` sent it. The right answer and the
+ * explanation only come back once an answer is given.
+ */
+async checkLectureQuestion(lectureId: string, questionId: string, answer: number) : Promise<Result<InlineAnswerFeedback, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_lecture_question", { lectureId, questionId, answer }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Add a note to a content node, optionally annotating a passage of it
+ */
+async createNote(nodeId: string, body: string, anchor: string | null) : Promise<Result<Note, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_note", { nodeId, body, anchor }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replace the text of one of the current user's notes
+ */
+async updateNote(noteId: string, body: string) : Promise<Result<Note, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_note", { noteId, body }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete one of the current user's notes
+ */
+async deleteNote(noteId: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_note", { noteId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the current user's notes on a node, newest first
+ */
+async getNotesForNode(nodeId: string) : Promise<Result<Note[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_notes_for_node", { nodeId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * All of the current user's notes, newest first, with the nodes they're on
+ */
+async listNotes() : Promise<Result<NoteListItem[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_notes") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save a node for later, or remove it if it's already saved. Returns
+ * whether the node is bookmarked afterwards.
+ */
+async toggleBookmark(nodeId: string) : Promise<Result<boolean, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("toggle_bookmark", { nodeId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The reading queue, front first
+ */
+async getBookmarks() : Promise<Result<BookmarkData[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_bookmarks") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reorder the reading queue. Bookmarks left out keep their order after
+ * the listed ones.
+ */
+async reorderBookmarks(nodeIds: string[]) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reorder_bookmarks", { nodeIds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Search the current user's notes, quiz answers and grade feedback
+ */
+async searchMyHistory(query: string, limit: number | null) : Promise<Result<SearchHit[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search_my_history", { query, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * A quiz to answer, with its options in the order of the learner's next
+ * attempt. Correct answers and explanations stay here until it's submitted.
+ * Loading a timed quiz starts its clock if it isn't already running.
+ */
+async loadQuiz(quizId: string) : Promise<Result<QuizView, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("load_quiz", { quizId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Start a quiz. For a timed quiz this starts the clock the submission is
+ * judged against, unless loading it already did; a running clock isn't
+ * restarted.
+ */
+async startQuiz(quizId: string) : Promise<Result<QuizStart, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_quiz", { quizId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async submitQuiz(request: SubmitQuizRequest) : Promise<Result<QuizResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("submit_quiz", { request }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Explain why the learner's answer to a missed question was wrong. The
+ * explanation is kept with the attempt, and reused for anyone who makes the
+ * same mistake on the same question.
+ */
+async explainQuizMistake(attemptId: string, questionId: string) : Promise<Result<MistakeExplanation, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("explain_quiz_mistake", { attemptId, questionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Explanations kept with a quiz attempt
+ */
+async getQuizExplanations(attemptId: string) : Promise<Result<MistakeExplanation[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_quiz_explanations", { attemptId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * A past attempt question by question, for reviewing what was missed
+ */
+async getQuizAttemptDetail(attemptId: string) : Promise<Result<QuizAttemptDetail, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_quiz_attempt_detail", { attemptId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The questions missed most and accuracy per skill, across all attempts
+ * in the active curriculum
+ */
+async getQuizAnalytics(limit: number | null) : Promise<Result<QuizAnalytics, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_quiz_analytics", { limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Run a mini-challenge's tests against the learner's code in the sandbox,
+ * then record the attempt. The first passing attempt completes the node
+ * and awards XP; every attempt whose tests ran updates mastery for the
+ * node's skills. Hints revealed before the first pass cost part of its XP.
+ */
+async submitChallenge(nodeId: string, code: string) : Promise<Result<ChallengeResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("submit_challenge", { nodeId, code }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Compile the learner's code with `cargo check`, without running tests or
+ * recording an attempt, for the editor's "Check" button. Runs under
+ * shorter limits than a submission, so it comes back quickly.
+ */
+async checkChallenge(nodeId: string, code: string) : Promise<Result<CheckResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_challenge", { nodeId, code }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Autosave the code in the editor, so closing the app doesn't lose it
+ */
+async saveChallengeDraft(nodeId: string, code: string) : Promise<Result<ChallengeDraft, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_challenge_draft", { nodeId, code }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The saved editor code for a challenge, if any
+ */
+async getChallengeDraft(nodeId: string) : Promise<Result<ChallengeDraft | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_challenge_draft", { nodeId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Previous attempts at a challenge with their test results, newest first
+ */
+async listChallengeAttempts(nodeId: string) : Promise<Result<ChallengeAttemptSummary[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_challenge_attempts", { nodeId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Put an earlier attempt's code back in the editor, replacing the draft
+ */
+async restoreChallengeAttempt(attemptId: string) : Promise<Result<ChallengeDraft, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_challenge_attempt", { attemptId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Compare the learner's latest passing code with the reference solution,
+ * for the recap after a challenge is solved
+ */
+async getSolutionFeedback(nodeId: string) : Promise<Result<SolutionFeedback, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_solution_feedback", { nodeId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Ask the grading provider to review the learner's latest passing code.
+ * The review is optional feedback and doesn't affect XP or progress.
+ */
+async getCodeReview(nodeId: string) : Promise<Result<CodeReview, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_code_review", { nodeId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Write a challenge out as a cargo project in `dir`, to solve in an
+ * external editor and submit with `submit_challenge_workspace`
+ */
+async exportChallengeWorkspace(nodeId: string, dir: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_challenge_workspace", { nodeId, dir }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Submit the solution in an exported challenge's `src/lib.rs`
+ */
+async submitChallengeWorkspace(dir: string) : Promise<Result<ChallengeResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("submit_challenge_workspace", { dir }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Progress through each project track in the active curriculum
+ */
+async getTrackProgress() : Promise<Result<TrackProgressData[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_track_progress") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Choose the repo a project track is built in. It must be a Cargo project.
+ */
+async setTrackRepo(trackId: string, repoPath: string) : Promise<Result<TrackRepo, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_track_repo", { trackId, repoPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Verify one day of a project track against the learner's repo. Only
+ * that day's tests run, and earlier days must be completed first, since
+ * each day builds on the last.
+ */
+async submitTrackDay(nodeId: string) : Promise<Result<ChallengeResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("submit_track_day", { nodeId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async createDailySession(targetMinutes: number) : Promise<Result<SessionPlan, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_daily_session", { targetMinutes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async startSession(sessionId: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_session", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async completeSession(sessionId: string, xpEarned: number) : Promise<Result<SessionSummary, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("complete_session", { sessionId, xpEarned }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getInterruptedSession() : Promise<Result<SessionPlan | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_interrupted_session") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sessions closed at startup because the app stopped while they were open.
+ * Each is returned once; they are also sent as `session://recovered` events.
+ */
+async takeRecoveredSessions() : Promise<RecoveredSession[]> {
+    return await TAURI_INVOKE("take_recovered_sessions");
+},
+/**
+ * Get all badges with user progress
+ */
+async getAllBadges() : Promise<Result<BadgeWithProgress[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_all_badges") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get only earned badges
+ */
+async getEarnedBadges() : Promise<Result<BadgeWithProgress[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_earned_badges") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check for newly unlocked badges and return them
+ */
+async checkAndUnlockBadges() : Promise<Result<BadgeDefinition[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_and_unlock_badges") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Update badge progress for a specific badge
+ */
+async updateBadgeProgress(badgeId: string) : Promise<Result<BadgeWithProgress, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_badge_progress", { badgeId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get all due reviews for the user in the active curriculum
+ */
+async getDueReviews() : Promise<Result<ReviewItemResponse[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_due_reviews") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Build today's review session: at most `max_items` due reviews of the
+ * active curriculum, interleaved across skills and most pressing first
+ */
+async buildReviewSession(maxItems: number) : Promise<Result<ReviewItemResponse[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("build_review_session", { maxItems }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get count of due reviews
+ */
+async getDueReviewCount() : Promise<Result<number, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_due_review_count") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get all reviews for the user in the active curriculum (due and upcoming)
+ */
+async getAllReviews() : Promise<Result<ReviewItemResponse[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_all_reviews") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Submit a review result
+ */
+async submitReview(quizId: string, scorePercentage: number) : Promise<Result<ReviewItemResponse, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("submit_review", { quizId, scorePercentage }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Create a review item for a quiz (called after completing a quiz)
+ */
+async createReviewItem(quizId: string) : Promise<Result<ReviewItemResponse, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_review_item", { quizId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get review items suspended as leeches
+ */
+async getLeechItems() : Promise<Result<LeechItemResponse[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_leech_items") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Return a suspended leech to the review queue
+ */
+async unsuspendReview(quizId: string) : Promise<Result<ReviewItemResponse, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unsuspend_review", { quizId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Apply mastery decay on app startup, across every curriculum
+ */
+async applyMasteryDecayOnStartup() : Promise<Result<number, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("apply_mastery_decay_on_startup") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get mastery scores that need attention (below threshold)
+ */
+async getLowMasterySkills(threshold: number) : Promise<Result<MasterySkillResponse[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_low_mastery_skills", { threshold }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get a skill's mastery history over the last `days` days for charting
+ */
+async getMasteryHistory(skillId: string, days: number) : Promise<Result<MasteryHistoryPoint[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_mastery_history", { skillId, days }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export the active curriculum's review items and the user's notes as a
+ * file for Anki's "Import File", named after the curriculum
+ */
+async exportAnkiDeck(path: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_anki_deck", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import flashcards from a CSV file or an Anki text export into the review
+ * queue, under a skill of the active curriculum. A dry run checks the file
+ * and reports what would be imported without storing anything.
+ */
+async importFlashcards(path: string, skillId: string, dryRun: boolean) : Promise<Result<FlashcardImport, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_flashcards", { path, skillId, dryRun }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * An imported flashcard, by the id in its review item
+ */
+async getFlashcard(reviewId: string) : Promise<Result<Flashcard, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_flashcard", { reviewId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get XP events that are currently running
+ */
+async getActiveXpEvents() : Promise<Result<XpEventResponse[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_active_xp_events") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the leaderboard across local profiles for a period ("Weekly" or "AllTime")
+ */
+async getLeaderboard(period: string) : Promise<Result<LeaderboardResponse, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_leaderboard", { period }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Everything the stats dashboard shows, for a range
+ * ("Week", "Month", "Quarter", "Year" or "AllTime")
+ */
+async getDashboardStats(range: string) : Promise<Result<DashboardStats, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_dashboard_stats", { range }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Per-day activity for one calendar year, for the contributions calendar
+ */
+async getActivityHeatmap(year: number) : Promise<Result<ActivityHeatmap, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_activity_heatmap", { year }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export the user's whole activity history as CSV
+ */
+async exportActivityHistory(path: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_activity_history", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Write the report for the past week to `path`, as HTML if it ends in
+ * .html or .htm and Markdown otherwise
+ */
+async exportWeeklyReport(path: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_weekly_report", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * How much of the active curriculum is done, weighted by "Xp" (the
+ * default) or "Minutes". Reaching 100% issues a completion certificate.
+ */
+async getCurriculumCompletion(weighting: string | null) : Promise<Result<CurriculumCompletionStatus, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_curriculum_completion", { weighting }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Write the active curriculum's completion certificate to `path`, as JSON
+ * if it ends in .json and SVG otherwise
+ */
+async exportCertificate(path: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_certificate", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Write the user's learning records in the active curriculum to `path` as
+ * a JSON array of xAPI statements. With `since` (RFC 3339), only records
+ * from after it.
+ */
+async exportXapiStatements(path: string, since: string | null) : Promise<Result<number, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_xapi_statements", { path, since }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Send the user's learning records in the active curriculum to a Learning
+ * Record Store, authenticating with HTTP Basic auth. Statement ids are
+ * stable, so records the LRS already has aren't duplicated. Returns how
+ * many statements were sent.
+ */
+async pushXapiStatements(endpoint: string, username: string, password: string, since: string | null) : Promise<Result<number, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("push_xapi_statements", { endpoint, username, password, since }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Create a new cohort
+ */
+async cohortCreate(name: string) : Promise<Result<CohortSummary, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cohort_create", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List cohorts with their members
+ */
+async cohortList() : Promise<Result<CohortSummary[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cohort_list") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete a cohort (enrolled profiles are kept)
+ */
+async cohortDelete(cohortId: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cohort_delete", { cohortId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Enroll an existing local profile in a cohort
+ */
+async cohortEnroll(cohortId: string, userId: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cohort_enroll", { cohortId, userId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove a profile from a cohort
+ */
+async cohortUnenroll(cohortId: string, userId: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cohort_unenroll", { cohortId, userId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import a student's exported data file and enroll them.
+ * Returns the imported profile ID; the current profile is not changed.
+ */
+async cohortImportStudent(cohortId: string, path: string, passphrase: string | null) : Promise<Result<string, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cohort_import_student", { cohortId, path, passphrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get aggregate progress and mastery for a cohort
+ */
+async cohortGetReport(cohortId: string) : Promise<Result<CohortReport, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cohort_get_report", { cohortId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export a cohort's per-node report as CSV
+ */
+async cohortExportReport(cohortId: string, path: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cohort_export_report", { cohortId, path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Challenge attempts by the cohort's members that were flagged for review,
+ * newest first: pasted reference solutions, suspiciously quick solves and
+ * edits to files learners aren't meant to change. Flags are heuristics;
+ * none of them stopped the submission.
+ */
+async cohortFlaggedAttempts(cohortId: string) : Promise<Result<FlaggedAttempt[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cohort_flagged_attempts", { cohortId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the active daily and weekly quests
+ */
+async getActiveQuests() : Promise<Result<QuestResponse[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_active_quests") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Claim the XP reward of a completed quest
+ */
+async claimQuestReward(questId: string) : Promise<Result<ClaimQuestResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("claim_quest_reward", { questId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set a goal in the active curriculum, due by the end of `deadline`
+ * (YYYY-MM-DD, local time)
+ */
+async createGoal(title: string, target: GoalTargetInput, deadline: string) : Promise<Result<Goal, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_goal", { title, target, deadline }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The active curriculum's goals with progress and whether each is on track
+ */
+async getGoalsStatus() : Promise<Result<GoalStatus[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_goals_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteGoal(goalId: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_goal", { goalId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Aim to finish the active curriculum by `target_date` (YYYY-MM-DD)
+ */
+async setPaceTarget(targetDate: string) : Promise<Result<PaceTarget, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_pace_target", { targetDate }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The remaining nodes of the active curriculum spread over the study days
+ * left until its target date, or `None` without a target
+ */
+async getPacePlan() : Promise<Result<PacePlan | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_pace_plan") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async clearPaceTarget() : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_pace_target") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Validate a content pack without importing it
+ */
+async validateCurriculum(sourcePath: string) : Promise<Result<ValidationResponse, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("validate_curriculum", { sourcePath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import a curriculum from a folder path
+ */
+async importCurriculum(sourcePath: string, setActive: boolean) : Promise<Result<ImportResponse, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_curriculum", { sourcePath, setActive }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List all imported curricula
+ */
+async listCurricula() : Promise<Result<CurriculumInfo[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_curricula") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the currently active curriculum
+ */
+async getActiveCurriculum() : Promise<Result<CurriculumInfo | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_active_curriculum") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Switch to a different curriculum
+ */
+async switchCurriculum(curriculumId: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("switch_curriculum", { curriculumId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Activate an encrypted curriculum with the content key that came with it.
+ * The key is checked against the pack and kept, so the curriculum opens
+ * without asking again.
+ */
+async unlockCurriculum(curriculumId: string, contentKey: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unlock_curriculum", { curriculumId, contentKey }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Activate a premium curriculum with its license key. The key is checked
+ * offline against the issuers pinned in the app and kept, and the
+ * curriculum made active.
+ */
+async activateCurriculum(curriculumId: string, licenseKey: string) : Promise<Result<CurriculumLicense, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("activate_curriculum", { curriculumId, licenseKey }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The license the active curriculum was activated with, or `None` if it
+ * doesn't need one or hasn't been activated
+ */
+async getCurriculumLicense() : Promise<Result<CurriculumLicense | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_curriculum_license") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete a curriculum. Can be undone with `undo_last_destructive_operation`
+ * until the undo window closes.
+ */
+async deleteCurriculum(curriculumId: string, deleteProgress: boolean) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_curriculum", { curriculumId, deleteProgress }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get a specific curriculum by ID
+ */
+async getCurriculum(curriculumId: string) : Promise<Result<CurriculumInfo | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_curriculum", { curriculumId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check system requirements
+ */
+async checkSystemStatus() : Promise<Result<SystemStatus, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_system_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check Docker status
+ */
+async checkDockerStatus() : Promise<DockerStatus> {
+    return await TAURI_INVOKE("check_docker_status");
+},
+/**
+ * Save OpenAI API key
+ */
+async saveApiKey(apiKey: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_api_key", { apiKey }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Whether the grading provider is set up
+ */
+async getApiKeyStatus() : Promise<boolean> {
+    return await TAURI_INVOKE("get_api_key_status");
+},
+/**
+ * Save a provider's API key, endpoint and model. A missing key keeps the
+ * saved one; a missing endpoint or model means the provider's default.
+ */
+async saveProviderCredentials(provider: LlmProvider, apiKey: string | null, endpoint: string | null, model: string | null) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_provider_credentials", { provider, apiKey, endpoint, model }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Forget a provider's saved credentials
+ */
+async removeProviderCredentials(provider: LlmProvider) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_provider_credentials", { provider }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Choose the provider artifacts are graded with
+ */
+async setGradingProvider(provider: LlmProvider) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_grading_provider", { provider }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Configuration state of every supported provider
+ */
+async getProviderStatus() : Promise<ProviderStatus[]> {
+    return await TAURI_INVOKE("get_provider_status");
+},
+/**
+ * Check a provider's saved credentials with a minimal request and report
+ * which models they can use
+ */
+async testApiKey(provider: LlmProvider) : Promise<Result<ProviderCheck, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("test_api_key", { provider }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export all of the current user's data to a `.glpbackup` archive,
+ * encrypted when a passphrase is given
+ */
+async exportUserData(path: string, passphrase: string | null) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_user_data", { path, passphrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import a backup, either merging it with local data using per-category
+ * conflict strategies or replacing the backed-up user's data with it.
+ * With `options.dry_run` only reports what would change; otherwise
+ * switches to the imported user.
+ */
+async importUserData(path: string, passphrase: string | null, options: ImportOptions | null) : Promise<Result<ImportReport, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_user_data", { path, passphrase, options }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Whether a backup file needs a passphrase to import
+ */
+async isBackupEncrypted(path: string) : Promise<Result<boolean, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("is_backup_encrypted", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reset all user progress. Can be undone with
+ * `undo_last_destructive_operation` until the undo window closes.
+ */
+async resetAllProgress() : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reset_all_progress") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Recompute the current user's XP from their activity, optionally fixing the stored total
+ */
+async auditUserXp(repair: boolean) : Promise<Result<XpAuditReport, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("audit_user_xp", { repair }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check if this is first launch (no user exists)
+ */
+async isFirstLaunch() : Promise<Result<boolean, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("is_first_launch") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Mark onboarding as complete
+ */
+async completeOnboarding() : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("complete_onboarding") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check if onboarding is complete
+ */
+async isOnboardingComplete() : Promise<boolean> {
+    return await TAURI_INVOKE("is_onboarding_complete");
+},
+async getSettings() : Promise<Result<SettingsView, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateSettings(settings: SettingsView) : Promise<Result<SettingsView, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Where first-run setup is up to
+ */
+async getSetupState() : Promise<SetupState> {
+    return await TAURI_INVOKE("get_setup_state");
+},
+/**
+ * Run or skip the current setup step and return the updated state. A failed
+ * step stays current, so calling again with `Run` retries it.
+ */
+async advanceSetupStep(action: SetupAction) : Promise<Result<SetupState, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("advance_setup_step", { action }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Which sandbox image is installed, and any update waiting to be applied
+ */
+async getSandboxImageStatus() : Promise<Result<SandboxImageStatus, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_sandbox_image_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set where sandbox image updates come from. Digests in `pinned_digests`
+ * are the only ones accepted, unless it's empty.
+ */
+async setSandboxImageSource(manifestUrl: string | null, pinnedDigests: string[], autoApply: boolean) : Promise<Result<SandboxImageStatus, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_sandbox_image_source", { manifestUrl, pinnedDigests, autoApply }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check for, apply or roll back a sandbox image update
+ */
+async updateSandboxImage(action: SandboxImageAction) : Promise<Result<SandboxImageStatus, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_sandbox_image", { action }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The operation `undo_last_destructive_operation` would undo, if any
+ */
+async getUndoableOperation() : Promise<Result<UndoableOperation | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_undoable_operation") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Restore what the most recent reset or curriculum deletion removed
+ */
+async undoLastDestructiveOperation() : Promise<Result<UndoableOperation, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("undo_last_destructive_operation") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getUndoWindowMinutes() : Promise<number> {
+    return await TAURI_INVOKE("get_undo_window_minutes");
+},
+/**
+ * Set how long reset and delete operations can be undone. Applies to
+ * operations run after the change.
+ */
+async setUndoWindowMinutes(minutes: number) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_undo_window_minutes", { minutes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set the folder, kept in step by a tool like Dropbox or Syncthing, that
+ * devices sync through. `None` turns sync off.
+ */
+async configureSyncFolder(path: string | null) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("configure_sync_folder", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSyncStatus() : Promise<Result<SyncStatus, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_sync_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Publish this device's progress, mastery and review changes to the sync
+ * folder and apply changes from the user's other devices
+ */
+async syncNow() : Promise<Result<SyncReport, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sync_now") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The focus timer, completing the current block if its time ran out
+ */
+async getFocusState() : Promise<Result<FocusState, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_focus_state") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Start a work or break block. Without a kind, starts whatever comes next
+ * in the work/break cycle.
+ */
+async startFocusBlock(kind: FocusBlockKind | null) : Promise<Result<FocusState, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_focus_block", { kind }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async pauseFocusBlock() : Promise<Result<FocusState, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("pause_focus_block") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async resumeFocusBlock() : Promise<Result<FocusState, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resume_focus_block") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async cancelFocusBlock() : Promise<Result<FocusState, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_focus_block") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Change work and break lengths for blocks started from now on
+ */
+async updateFocusSettings(settings: FocusSettings) : Promise<Result<FocusState, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_focus_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The hints revealed so far for a challenge
+ */
+async getHintState(nodeId: string) : Promise<Result<HintState, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_hint_state", { nodeId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reveal a challenge's next hint. Each hint revealed before the challenge
+ * is passed costs part of its completion XP.
+ */
+async getNextHint(nodeId: string) : Promise<Result<HintState, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_next_hint", { nodeId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getHintSettings() : Promise<Result<HintSettings, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_hint_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Change what revealing a hint costs, for challenges not yet passed
+ */
+async updateHintSettings(settings: HintSettings) : Promise<Result<HintSettings, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_hint_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Whether Docker and the grading provider are reachable, from the cache
+ * unless it's stale or `refresh` is set
+ */
+async getCapabilityStatus(refresh: boolean | null) : Promise<Result<CapabilityStatus, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_capability_status", { refresh }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Submit a checkpoint artifact for grading against the built-in rubric. If
+ * the grading provider is unreachable the submission is kept and graded
+ * once it's back, rather than failing. Unlike `submit_artifact`, this
+ * awards no XP.
+ */
+async submitCheckpoint(checkpointId: string, artifactType: string, content: string) : Promise<Result<CheckpointSubmissionResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("submit_checkpoint", { checkpointId, artifactType, content }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Grade one of a checkpoint's artifacts against the curriculum's rubric.
+ * The first passing grade for each artifact awards XP scaled by the grade,
+ * with the checkpoint's XP split across its artifacts; once every artifact
+ * has passed, the checkpoint is complete.
+ */
+async submitArtifact(nodeId: string, artifactType: string, content: string) : Promise<Result<ArtifactResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("submit_artifact", { nodeId, artifactType, content }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Grade a checkpoint's README and DESIGN artifacts as committed at the
+ * HEAD of the learner's repo, with the latest commits' diff as context.
+ * The repo must have the files the checkpoint's template lists, and each
+ * grade records the commit it was given for.
+ */
+async submitCheckpointRepo(nodeId: string, repoPath: string) : Promise<Result<CheckpointRepoResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("submit_checkpoint_repo", { nodeId, repoPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Submit a checkpoint as a public GitHub repo. The repo is cloned into a
+ * temporary directory, the checkpoint's tests run against it in the
+ * sandbox and, if they pass, its README and DESIGN artifacts are graded.
+ * Every submission is kept with its outcome, failed ones included, and at
+ * most `GITHUB_SUBMISSIONS_PER_HOUR` are accepted an hour.
+ */
+async submitCheckpointGithub(nodeId: string, url: string) : Promise<Result<GithubSubmissionResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("submit_checkpoint_github", { nodeId, url }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The rubric a checkpoint artifact is graded against, for scoring it by hand
+ */
+async getCheckpointRubric(nodeId: string, artifactType: string) : Promise<Result<Rubric, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_checkpoint_rubric", { nodeId, artifactType }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * How ready the learner is for a checkpoint, so they can be warned before a
+ * graded attempt. It covers the skills of its prerequisites or, when it has
+ * none, of its week's nodes.
+ */
+async getCheckpointReadiness(checkpointId: string) : Promise<Result<CheckpointReadiness, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_checkpoint_readiness", { checkpointId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Grade one of a checkpoint's artifacts with the learner's own scores
+ * against its rubric, for when there's no grading provider. Counts like a
+ * graded submission, but the first pass earns only
+ * `SELF_ASSESSED_XP_MULTIPLIER` of the usual XP.
+ */
+async selfAssessArtifact(nodeId: string, artifactType: string, content: string, scores: SelfScore[], reflection: string | null) : Promise<Result<ArtifactResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("self_assess_artifact", { nodeId, artifactType, content, scores, reflection }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lectures and quizzes most like a node
+ */
+async findRelated(nodeId: string, limit: number | null) : Promise<Result<RelatedNode[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("find_related", { nodeId, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lectures to review for a skill, e.g. when its mastery drops
+ */
+async findContentForSkill(skillId: string, limit: number | null) : Promise<Result<RelatedNode[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("find_content_for_skill", { skillId, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Weak skills to practice in the active curriculum, decaying ones first
+ */
+async getPracticeSkills(threshold: number | null) : Promise<Result<MasterySkillResponse[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_practice_skills", { threshold }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Generate a practice challenge for a skill and add it to the review
+ * queue, due now. A draft is only kept once its solution passes its own
+ * tests in the sandbox.
+ */
+async generatePractice(skillId: string, difficulty: string) : Promise<Result<PracticeView, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("generate_practice", { skillId, difficulty }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getPracticeChallenge(practiceId: string) : Promise<Result<PracticeView, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_practice_challenge", { practiceId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Run a practice challenge's tests against the learner's code and grade
+ * its review item by the share of tests passed
+ */
+async submitPractice(practiceId: string, code: string) : Promise<Result<PracticeResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("submit_practice", { practiceId, code }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Start a new tutor conversation about a lecture
+ */
+async startTutorSession(nodeId: string) : Promise<Result<TutorConversation, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_tutor_session", { nodeId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getTutorSession(sessionId: string) : Promise<Result<TutorConversation, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_tutor_session", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Ask the tutor a question. The question and reply are stored only once
+ * the tutor has answered, so a failed call can simply be retried.
+ */
+async sendTutorMessage(sessionId: string, message: string) : Promise<Result<TutorExchange, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("send_tutor_message", { sessionId, message }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reminder preferences and when the next reminders will be sent
+ */
+async getNotificationSchedule() : Promise<Result<NotificationSchedule, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_notification_schedule") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateNotificationPrefs(prefs: NotificationPrefs) : Promise<Result<NotificationSchedule, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_notification_prefs", { prefs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The current user's hooks and what they may target
+ */
+async getHooks() : Promise<Result<HookSettings, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_hooks") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replace the current user's hooks. Every hook's target must be on the
+ * allow-list; new hooks are given ids.
+ */
+async configureHooks(hooks: Hook[]) : Promise<Result<HookSettings, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("configure_hooks", { hooks }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Whether the current user shares analytics, and how much is waiting to go
+ */
+async getAnalyticsSettings() : Promise<Result<AnalyticsStatus, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_analytics_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Opt in to or out of analytics, or change where they're uploaded. Opting
+ * out drops anything not yet uploaded.
+ */
+async setAnalyticsSettings(settings: AnalyticsSettings) : Promise<Result<AnalyticsStatus, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_analytics_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Everything queued for upload, exactly as the endpoint will receive it
+ */
+async previewAnalytics() : Promise<Result<AnalyticsBatch, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_analytics") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Drop all of the current user's queued analytics and start uploads afresh
+ * under a new anonymous id. Returns how many events were dropped.
+ */
+async purgeAnalytics() : Promise<Result<number, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("purge_analytics") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Status of every background job
+ */
+async getJobStatus() : Promise<Result<JobStatus[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_job_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Write a zip of recent logs, system status, database integrity check
+ * results and version information, for attaching to bug reports. Contains
+ * no API keys or learner content.
+ */
+async exportDiagnostics(path: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_diagnostics", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+}
+}
+
+/** user-defined events **/
+
+
+
+/** user-defined constants **/
+
+
+
+/** user-defined types **/
+
+export type ActivityHeatmap = { year: number; days: DailyActivity[]; active_days: number; total_minutes: number; total_xp: number; total_nodes_completed: number }
+/**
+ * What's uploaded, and what the learner is shown before it is
+ */
+export type AnalyticsBatch = { schema_version: number; 
+/**
+ * Random id for this profile's uploads, replaced whenever its data is
+ * purged
+ */
+anonymous_id: string; events: RecordedEvent[] }
+/**
+ * What happened. Only ids from the curriculum and coarse numbers: never
+ * the learner's code, answers or anything they typed.
+ */
+export type AnalyticsEvent = { event: "node_completed"; node_id: string; node_type: string; minutes: number } | { event: "quiz_submitted"; node_id: string; score_percent: number; passed: boolean; minutes: number } | { event: "challenge_attempted"; node_id: string; passed: boolean; error: ErrorCategory | null }
+/**
+ * Whether a learner shares usage analytics, and where they go
+ */
+export type AnalyticsSettings = { 
+/**
+ * Off until the learner opts in
+ */
+enabled?: boolean; 
+/**
+ * Where batches are uploaded; events stay queued until one is set
+ */
+endpoint?: string | null }
+export type AnalyticsStatus = { settings: AnalyticsSettings; 
+/**
+ * Events waiting to be uploaded
+ */
+queued_events: number }
+export type ArtifactResult = { submission: ArtifactSubmission; grade: GradeResult; xp_earned: number; 
+/**
+ * Every artifact the checkpoint asks for has a passing grade
+ */
+checkpoint_completed: boolean; 
+/**
+ * Artifacts still without a passing grade
+ */
+remaining_artifacts: string[] }
+export type ArtifactSubmission = { id: string; user_id: string; checkpoint_id: string; artifact_type: ArtifactType; content_hash: string; grade_percentage: number | null; reasoning_json: string | null; xp_earned: number; submitted_at: string; graded_at: string | null; 
+/**
+ * Commit the artifact was read from, when it was collected from a git repo
+ */
+commit_hash?: string | null }
+export type ArtifactType = "Readme" | "Design" | "Bench" | "Runbook" | "Invariants"
+/**
+ * Something about a submission worth a second look. Flags are heuristics,
+ * so they're recorded for review rather than acted on.
+ */
+export type AttemptFlag = 
+/**
+ * The code is the bundled reference solution, give or take formatting
+ * and comments
+ */
+{ kind: "matches_reference" } | 
+/**
+ * Passed far sooner after starting the node than it's expected to take
+ */
+{ kind: "fast_solve"; seconds: number; expected_minutes: number } | 
+/**
+ * Files of an exported workspace that learners aren't meant to change
+ * were edited, added or removed
+ */
+{ kind: "edited_outside_allowed"; files: string[] }
+export type BadgeCategory = "Streak" | "Level" | "Xp" | "Completion" | "Mastery"
+/**
+ * A boolean expression over user stats
+ * 
+ * Serialized as its expression string so badge definitions stay readable.
+ */
+export type BadgeCriteria = { Compare: { stat: BadgeStat; op: Comparison; value: number } } | { And: BadgeCriteria[] } | { Or: BadgeCriteria[] }
+export type BadgeDefinition = { id: string; name: string; description: string; icon: string; threshold: number; category: BadgeCategory; 
+/**
+ * Composite criteria; when set it replaces the category threshold check
+ */
+criteria?: BadgeCriteria | null; 
+/**
+ * Tier thresholds in ascending order; `threshold` matches the lowest tier
+ */
+tiers: BadgeTierThreshold[] }
+/**
+ * A user stat that criteria can compare against
+ */
+export type BadgeStat = "Streak" | "Level" | "TotalXp" | "CompletedLectures" | "CompletedQuizzes" | "CompletedChallenges" | "TotalCompletions" | "PerfectQuizzes" | "MaxMastery"
+/**
+ * Tier of a tiered badge, ordered from lowest to highest
+ */
+export type BadgeTier = "Bronze" | "Silver" | "Gold"
+/**
+ * Threshold at which a tiered badge reaches a given tier
+ */
+export type BadgeTierThreshold = { tier: BadgeTier; threshold: number }
+/**
+ * Badge with user progress information
+ */
+export type BadgeWithProgress = { definition: BadgeDefinition; progress: number; current_value: number; is_earned: boolean; earned_at: string | null; tier: BadgeTier | null; next_tier: NextTierProgress | null }
+/**
+ * The best result at a challenge, or at one artifact of a checkpoint
+ */
+export type BestAttemptData = { node_id: string; 
+/**
+ * The artifact type for a checkpoint artifact; empty for a challenge
+ */
+part: string; best_attempt_id: string | null; best_verification_score: number | null; best_grade: number | null; xp_awarded: number; attempts: number }
+/**
+ * A bookmarked node as the reading queue shows it
+ */
+export type BookmarkData = { node_id: string; position: number; 
+/**
+ * `None` if the node is no longer in the curriculum
+ */
+title: string | null; node_type: string | null; estimated_minutes: number | null; created_at: string }
+/**
+ * Something outside the app that work can depend on
+ */
+export type Capability = 
+/**
+ * The Docker daemon, for running challenge code
+ */
+"Docker" | 
+/**
+ * The configured grading provider's API
+ */
+"Llm"
+/**
+ * Whether a capability was reachable when last checked
+ */
+export type CapabilityState = { available: boolean; 
+/**
+ * Version, model or the reason it's unavailable
+ */
+detail: string | null; 
+/**
+ * Never checked if missing
+ */
+checked_at: string | null }
+export type CapabilityStatus = { docker: CapabilityState; llm: CapabilityState; 
+/**
+ * The current user's work waiting on a capability
+ */
+pending_work: PendingWork[] }
+/**
+ * A card read from the file, not yet stored
+ */
+export type CardDraft = { 
+/**
+ * Line the card starts on
+ */
+line: number; front: string; back: string; tags: string[] }
+/**
+ * What an import did, or would do, to one category of records
+ */
+export type CategoryReport = { category: ImportCategory; 
+/**
+ * `None` for append-only records, which never conflict
+ */
+strategy: ConflictStrategy | null; 
+/**
+ * Records the database did not have
+ */
+added: number; 
+/**
+ * Records identical on both sides
+ */
+unchanged: number; 
+/**
+ * Conflicts settled by writing the backup's or a merged copy
+ */
+updated: number; 
+/**
+ * Conflicts settled by keeping the local copy
+ */
+kept_local: number; 
+/**
+ * Local records cleared by a replacing import
+ */
+removed: number }
+/**
+ * Score for a single category in the rubric
+ */
+export type CategoryScore = { 
+/**
+ * Category name
+ */
+category: string; 
+/**
+ * Score achieved
+ */
+score: number; 
+/**
+ * Maximum score for this category
+ */
+max_score: number; 
+/**
+ * Specific feedback for this category
+ */
+feedback: string }
+/**
+ * Proof that a learner finished a curriculum. The verification hash is an
+ * HMAC-SHA256 over the other fields, keyed with this install's signing key,
+ * so an edited certificate no longer verifies.
+ */
+export type Certificate = { id: string; user_id: string; curriculum_id: string; learner_name: string; curriculum_name: string; curriculum_version: string; completed_at: string; verification_hash: string }
+export type ChallengeAttempt = { id: string; user_id: string; curriculum_id: string | null; challenge_id: string; node_id: string; code_hash: string; 
+/**
+ * Missing for attempts recorded before code was kept
+ */
+code: string | null; tests_passed: number; tests_failed: number; stdout: string | null; stderr: string | null; xp_earned: number; 
+/**
+ * The runner's full result, as JSON
+ */
+verification_json: string | null; 
+/**
+ * Signals for an instructor to look into; they never block a submission
+ */
+flags?: AttemptFlag[]; submitted_at: string }
+export type ChallengeAttemptSummary = { attempt: ChallengeAttempt; 
+/**
+ * Missing for attempts recorded before full results were kept
+ */
+verification: VerificationResult | null }
+/**
+ * Code in the editor for a challenge, saved as the student types
+ */
+export type ChallengeDraft = { user_id: string; curriculum_id: string | null; node_id: string; code: string; 
+/**
+ * The attempt whose code was restored into the editor, if the draft
+ * hasn't been edited since
+ */
+restored_from: string | null; updated_at: string }
+export type ChallengeResult = { attempt_id: string; passed: boolean; attempt_number: number; xp_earned: number; 
+/**
+ * Skills whose mastery changed, with the new score. Empty when the
+ * tests never ran, e.g. on a compile error.
+ */
+mastery_updates: Partial<{ [key in string]: number }>; 
+/**
+ * Share of the completion XP taken for revealed hints
+ */
+hint_penalty_percent: number; verification: VerificationResult }
+/**
+ * Result of a check-only run: the code is compiled with `cargo check`,
+ * without running any tests
+ */
+export type CheckResult = { 
+/**
+ * Whether the code compiled without errors
+ */
+success: boolean; 
+/**
+ * Errors and warnings, in the order the compiler reported them
+ */
+diagnostics: Diagnostic[]; 
+/**
+ * Duration of the run in milliseconds
+ */
+duration_ms: number; 
+/**
+ * Whether the check was stopped at its time limit
+ */
+timed_out: boolean }
+export type CheckpointReadiness = { checkpoint_id: string; 
+/**
+ * 0.0-100.0
+ */
+percent: number; 
+/**
+ * `percent` is at least `READY_PERCENT`
+ */
+likely_ready: boolean; 
+/**
+ * Average mastery of the checkpoint's skills, 0.0-1.0; `None` when it
+ * covers no skills
+ */
+mastery: number | null; 
+/**
+ * Share of recent answers on the checkpoint's skills that were correct;
+ * `None` when there are none
+ */
+quiz_accuracy: number | null; recent_answers: number; prerequisites_completed: number; prerequisites_total: number; incomplete_prerequisites: string[]; 
+/**
+ * Skills mastered below `WEAK_SKILL_SCORE`, weakest first
+ */
+weak_skills: string[] }
+/**
+ * Artifacts graded from a learner's repo
+ */
+export type CheckpointRepoResult = { 
+/**
+ * Commit the artifacts were read from
+ */
+commit_hash: string; graded: ArtifactResult[]; 
+/**
+ * Artifacts the checkpoint asks for that aren't committed
+ */
+not_found: string[] }
+export type CheckpointSubmissionResult = { submission: ArtifactSubmission; 
+/**
+ * Grading was deferred until the grading provider is reachable
+ */
+queued: boolean; 
+/**
+ * Why grading was deferred
+ */
+queued_reason: string | null }
+export type ClaimQuestResult = { xp_earned: number; new_total_xp: number; new_level: number }
+export type CodeReview = { summary: string; 
+/**
+ * Ordered by line
+ */
+comments: ReviewComment[]; 
+/**
+ * Latency in milliseconds
+ */
+latency_ms: number }
+/**
+ * A group of local profiles tracked together by an instructor
+ */
+export type Cohort = { id: string; name: string; created_at: string }
+export type CohortMember = { user_id: string; display_name: string; total_xp: number; current_level: number }
+/**
+ * Aggregate progress for one content node across a cohort
+ */
+export type CohortNodeStats = { node_id: string; learners: number; started: number; completed: number; avg_attempts: number; avg_time_mins: number; 
+/**
+ * Mean cohort mastery over the node's skills, when the skills are known
+ */
+avg_mastery: number | null }
+export type CohortReport = { cohort: Cohort; generated_at: string; member_count: number; nodes: CohortNodeStats[]; skills: CohortSkillStats[] }
+/**
+ * Aggregate mastery for one skill across a cohort
+ */
+export type CohortSkillStats = { skill_id: string; learners: number; avg_score: number; min_score: number; max_score: number }
+export type CohortSummary = { id: string; name: string; created_at: string; members: CohortMember[] }
+/**
+ * Error returned by every command
+ */
+export type CommandError = { code: ErrorCode; message: string; 
+/**
+ * Underlying error, when `message` is written for the user
+ */
+details?: string | null }
+export type CommentSeverity = "praise" | "suggestion" | "issue"
+/**
+ * Comparison operator used in a criteria term
+ */
+export type Comparison = "GreaterOrEqual" | "Greater" | "LessOrEqual" | "Less" | "Equal"
+/**
+ * Compile error information
+ */
+export type CompileError = { 
+/**
+ * Error message
+ */
+message: string; 
+/**
+ * Line number where the error occurred
+ */
+line: number | null; 
+/**
+ * Column number where the error occurred
+ */
+column: number | null; 
+/**
+ * File where the error occurred
+ */
+file: string | null }
+export type CompleteLectureRequest = { lecture_id: string; time_spent_ms: number; difficulty: string; 
+/**
+ * An option index for each comprehension check question; left out to
+ * skip the check
+ */
+comprehension_answers?: number[] | null }
+export type CompletedActivitySummary = { title: string; xp_earned: number }
+export type CompletionResult = { xp_earned: number; new_total_xp: number; new_level: number; unlocked_nodes: string[] }
+/**
+ * What a node counts for towards completion
+ */
+export type CompletionWeighting = 
+/**
+ * The XP it awards
+ */
+"Xp" | 
+/**
+ * The minutes it's expected to take
+ */
+"Minutes"
+/**
+ * Strategy per record category
+ */
+export type ConflictStrategies = { user: ConflictStrategy; progress: ConflictStrategy; mastery: ConflictStrategy; badges: ConflictStrategy; reviews: ConflictStrategy; notes: ConflictStrategy }
+/**
+ * How to settle a record that exists both locally and in the backup
+ */
+export type ConflictStrategy = 
+/**
+ * Keep whichever copy was updated most recently
+ */
+"KeepNewest" | 
+/**
+ * Keep the local copy
+ */
+"KeepLocal" | 
+/**
+ * Overwrite the local copy with the backup's
+ */
+"KeepIncoming" | 
+/**
+ * Combine both copies, keeping the highest XP, scores and progress
+ */
+"MergeMax"
+/**
+ * Get statistics about a content pack
+ */
+export type ContentStats = { total_weeks: number; total_days: number; total_nodes: number; lectures: number; quizzes: number; challenges: number; checkpoints: number; total_xp: number; total_estimated_minutes: number }
+export type ContentTree = { title: string; weeks: WeekData[] }
+/**
+ * A specific criterion within a category
+ */
+export type Criterion = { 
+/**
+ * Description of what's being evaluated
+ */
+description: string; 
+/**
+ * Points for this criterion
+ */
+points: number; 
+/**
+ * Performance indicators
+ */
+indicators: Indicators }
+export type CurriculumCompletionStatus = ({ weighting: CompletionWeighting; completed_weight: number; total_weight: number; 
+/**
+ * 0.0-100.0
+ */
+percent: number; nodes_completed: number; nodes_total: number; 
+/**
+ * When the last node was completed, once every node is
+ */
+completed_at: string | null }) & { 
+/**
+ * Issued the first time the curriculum is found complete
+ */
+certificate: Certificate | null }
+export type CurriculumInfo = { id: string; name: string; version: string; description: string | null; author: string | null; imported_at: string; is_active: boolean; stats: ContentStats | null }
+/**
+ * The license a premium curriculum was activated with
+ */
+export type CurriculumLicense = { licensee: string; issued_on: string; expires_on: string | null; status: LicenseStatus }
+export type DailyAccuracy = { date: string; attempts: number; 
+/**
+ * Mean score of the day's quiz attempts, 0-100
+ */
+average_score: number }
+/**
+ * One day of a user's learning, rolled up from session time, XP and
+ * completed nodes. Days are UTC dates (YYYY-MM-DD).
+ */
+export type DailyActivity = { user_id: string; day: string; active_minutes: number; xp: number; nodes_completed: number }
+export type DailyXp = { date: string; xp: number }
+export type DashboardStats = { range: StatsRange; generated_at: string; weekly_time: WeeklyTime[]; xp_per_day: DailyXp[]; quiz_accuracy: DailyAccuracy[]; 
+/**
+ * Every skill with a mastery score, weakest first
+ */
+mastery: SkillMastery[]; current_streak: number; 
+/**
+ * Longest run of consecutive days with XP earned
+ */
+best_streak: number }
+export type DayData = { id: string; title: string; description: string; nodes: NodeData[] }
+/**
+ * A compiler diagnostic, as structured as rustc reports it, so an editor
+ * can underline exactly the code it's about
+ */
+export type Diagnostic = { 
+/**
+ * `error` or `warning` at the top level; `note` or `help` for children
+ */
+level: string; message: string; 
+/**
+ * Error code, e.g. `E0308`
+ */
+code: string | null; 
+/**
+ * Code the diagnostic points at; the primary spans are where it is
+ */
+spans: DiagnosticSpan[]; 
+/**
+ * Notes and help attached to the diagnostic
+ */
+children: Diagnostic[]; 
+/**
+ * The diagnostic as rustc prints it, with the code it points at
+ */
+rendered: string | null }
+/**
+ * A range of code a diagnostic points at. Lines and columns count from 1
+ * and columns are in characters; the end column is exclusive.
+ */
+export type DiagnosticSpan = { file: string; line_start: number; line_end: number; column_start: number; column_end: number; is_primary: boolean; 
+/**
+ * What rustc says about this span, e.g. "expected `i32`"
+ */
+label: string | null; 
+/**
+ * Code to replace the span with to apply a suggested fix
+ */
+suggested_replacement: string | null; 
+/**
+ * How safe the replacement is: `MachineApplicable`, `MaybeIncorrect`,
+ * `HasPlaceholders` or `Unspecified`
+ */
+suggestion_applicability: string | null }
+/**
+ * A difference worth explaining
+ */
+export type DiffNote = { topic: DiffTopic; message: string }
+export type DiffTopic = "cloning" | "error_handling" | "iteration" | "indexing" | "mutability" | "smart_pointers" | "unsafe" | "string_params"
+export type DockerStatus = { installed: boolean; running: boolean; version: string | null }
+export type EmbeddedKind = "Lecture" | "QuizQuestion"
+/**
+ * Why a challenge attempt didn't pass
+ */
+export type ErrorCategory = "compile_error" | "test_failure" | "timeout" | "panic" | "out_of_memory" | "resource_limit" | "policy_violation" | "runtime_error"
+/**
+ * What went wrong, for the frontend to branch on without parsing messages.
+ * The frontend's `ErrorCode` type in `src/bindings.ts` is generated from this.
+ */
+export type ErrorCode = "DOCKER_NOT_INSTALLED" | "DOCKER_NOT_RUNNING" | "DOCKER_ERROR" | "CODE_TIMEOUT" | "LLM_API_TIMEOUT" | "LLM_RATE_LIMITED" | "LLM_API_KEY_INVALID" | "LLM_API_KEY_MISSING" | "LLM_ERROR" | "DATABASE_LOCKED" | "DATABASE_CORRUPTED" | "DATABASE_ERROR" | "NOT_FOUND" | "CONTENT_NOT_FOUND" | "CURRICULUM_NOT_LOADED" | 
+/**
+ * The curriculum is encrypted and hasn't been unlocked with its key
+ */
+"CONTENT_LOCKED" | 
+/**
+ * The curriculum needs a license key and hasn't been activated
+ */
+"LICENSE_REQUIRED" | 
+/**
+ * The curriculum's license ran out, grace period included
+ */
+"LICENSE_EXPIRED" | "NOT_LOGGED_IN" | "VALIDATION_ERROR" | 
+/**
+ * The action doesn't apply to the current state, e.g. pausing a timer
+ * that isn't running
+ */
+"INVALID_STATE" | "IO_ERROR" | "UNKNOWN"
+/**
+ * A challenge attempt flagged for an instructor to review
+ */
+export type FlaggedAttempt = { attempt_id: string; user_id: string; display_name: string; node_id: string; passed: boolean; submitted_at: string; flags: AttemptFlag[] }
+/**
+ * A card imported from a CSV file or an Anki deck, reviewed under one skill
+ */
+export type Flashcard = { id: string; user_id: string; curriculum_id: string | null; skill_id: string; front: string; back: string; tags: string[]; 
+/**
+ * Name of the file the card was imported from
+ */
+source: string; created_at: string }
+/**
+ * What importing a deck did, or on a dry run would do
+ */
+export type FlashcardImport = { 
+/**
+ * Nothing was stored
+ */
+dry_run: boolean; 
+/**
+ * Cards new to the skill
+ */
+cards: CardDraft[]; 
+/**
+ * Cards already imported for the skill, skipped
+ */
+duplicates: CardDraft[]; 
+/**
+ * Rows that can't be imported
+ */
+issues: ImportIssue[] }
+/**
+ * One work or break interval. A running block keeps counting while the
+ * app is closed, like a kitchen timer; time spent paused doesn't count.
+ */
+export type FocusBlock = { id: string; user_id: string; 
+/**
+ * Study session the block was run in
+ */
+session_id: string | null; kind: FocusBlockKind; planned_seconds: number; started_at: string; paused_at: string | null; 
+/**
+ * Total time spent paused before the current pause, if any
+ */
+paused_seconds: number; ended_at: string | null; status: FocusStatus; xp_awarded: number }
+export type FocusBlockKind = "Work" | "ShortBreak" | "LongBreak"
+/**
+ * A work block that completed since the state was last checked
+ */
+export type FocusCompletion = { block: FocusBlock; xp_awarded: number; 
+/**
+ * The user's XP before the award, for level-up detection
+ */
+previous_total_xp: number }
+/**
+ * Work and break lengths, per user
+ */
+export type FocusSettings = { work_minutes: number; short_break_minutes: number; long_break_minutes: number; 
+/**
+ * Work blocks completed before a long break instead of a short one
+ */
+blocks_before_long_break: number }
+export type FocusState = { settings: FocusSettings; 
+/**
+ * The running or paused block, if any
+ */
+block: FocusBlock | null; remaining_seconds: number; 
+/**
+ * What starting a block without choosing a kind would run
+ */
+next_kind: FocusBlockKind; completed_work_blocks_today: number; completed: FocusCompletion | null }
+export type FocusStatus = "Running" | "Paused" | "Completed" | "Cancelled"
+/**
+ * A checkpoint submitted as a GitHub repo
+ */
+export type GithubSubmissionResult = { submission: RepoSubmission; 
+/**
+ * The checkpoint's tests, when it has any
+ */
+verification: VerificationResult | null; 
+/**
+ * Empty when the tests failed, as the artifacts aren't graded then
+ */
+graded: ArtifactResult[]; 
+/**
+ * Artifacts the checkpoint asks for that aren't committed
+ */
+not_found: string[] }
+/**
+ * A target the learner set themselves, to reach by a deadline
+ */
+export type Goal = { id: string; user_id: string; curriculum_id: string | null; title: string; target: GoalTarget; deadline: string; created_at: string; 
+/**
+ * Set the first time the target is reached; the goal stays achieved
+ * even if mastery later decays
+ */
+achieved_at: string | null }
+export type GoalState = "Achieved" | "OnTrack" | "Behind" | "Missed"
+/**
+ * A goal with its progress worked out
+ */
+export type GoalStatus = ({ id: string; user_id: string; curriculum_id: string | null; title: string; target: GoalTarget; deadline: string; created_at: string; 
+/**
+ * Set the first time the target is reached; the goal stays achieved
+ * even if mastery later decays
+ */
+achieved_at: string | null }) & { 
+/**
+ * How much of the target is done, 0.0-1.0
+ */
+progress: number; 
+/**
+ * How much should be done by now to finish on time, 0.0-1.0
+ */
+expected_progress: number; state: GoalState }
+/**
+ * What a goal asks for. Weeks carry their node ids, resolved from the
+ * curriculum when the goal is set, so progress can be read from the database
+ * alone.
+ */
+export type GoalTarget = 
+/**
+ * Complete every node in a week
+ */
+{ kind: "CompleteWeek"; week_id: string; node_ids: string[] } | 
+/**
+ * Reach a mastery score (0.0-1.0) in a skill
+ */
+{ kind: "ReachMastery"; skill_id: string; score: number }
+/**
+ * A goal's target as the frontend describes it
+ */
+export type GoalTargetInput = { kind: "CompleteWeek"; week_id: string } | { kind: "ReachMastery"; skill_id: string; score: number }
+/**
+ * Result of grading an artifact
+ */
+export type GradeResult = { 
+/**
+ * Total score (0-100)
+ */
+score: number; 
+/**
+ * Maximum possible score
+ */
+max_score: number; 
+/**
+ * Overall feedback
+ */
+overall_feedback: string; 
+/**
+ * Scores for each category
+ */
+category_scores: CategoryScore[]; 
+/**
+ * Whether this result came from cache
+ */
+from_cache: boolean; 
+/**
+ * Latency in milliseconds (0 if from cache)
+ */
+latency_ms: number; 
+/**
+ * Scored by the learner against the rubric rather than by a model
+ */
+self_assessed?: boolean }
+/**
+ * Grade range guidelines
+ */
+export type GradingGuidelines = { "A (90-100)"?: string; "B (80-89)"?: string; "C (70-79)"?: string; "D (60-69)"?: string; "F (0-59)"?: string }
+/**
+ * What revealing hints costs, per user
+ */
+export type HintSettings = { 
+/**
+ * Share of the completion XP each revealed hint takes
+ */
+penalty_percent_per_hint: number; 
+/**
+ * Most of the completion XP hints can take, however many are revealed
+ */
+max_penalty_percent: number }
+export type HintState = { node_id: string; 
+/**
+ * Hints revealed so far, in order
+ */
+revealed: string[]; total_hints: number; 
+/**
+ * Share of the completion XP the revealed hints cost
+ */
+penalty_percent: number; 
+/**
+ * What the cost would be with one more hint revealed
+ */
+next_penalty_percent: number }
+/**
+ * A user's hook: an action run whenever an event of one kind happens
+ */
+export type Hook = { 
+/**
+ * Assigned when the hook is first saved
+ */
+id?: string; event: HookEventKind; action: HookAction; enabled?: boolean }
+/**
+ * Where a hook sends its events
+ */
+export type HookAction = 
+/**
+ * POST the event as JSON to a URL
+ */
+{ type: "Webhook"; url: string } | 
+/**
+ * Run a script from the app's hooks directory with the event as JSON
+ * on stdin
+ */
+{ type: "Command"; script: string; args?: string[] }
+/**
+ * Learning events hooks can be run on
+ */
+export type HookEventKind = "BadgeEarned" | "CheckpointPassed" | "StreakMilestone"
+export type HookSettings = { hooks: Hook[]; 
+/**
+ * Where command hooks' scripts must be placed
+ */
+scripts_dir: string; 
+/**
+ * Hosts webhooks may post to over HTTPS, besides this machine
+ */
+allowed_webhook_hosts: string[] }
+/**
+ * A sandbox image published in the manifest
+ */
+export type ImageRelease = { 
+/**
+ * Filled in from the manifest's repository
+ */
+repository?: string; version: string; 
+/**
+ * Content digest, `sha256:` and 64 hex digits
+ */
+digest: string; published_at: string; notes?: string | null }
+export type ImportCategory = "User" | "Progress" | "Mastery" | "Badges" | "Reviews" | "QuizAttempts" | "Notes" | "GradeHistory"
+/**
+ * A row that can't be imported, and why
+ */
+export type ImportIssue = { line: number; message: string }
+/**
+ * How an import treats data already in the database
+ */
+export type ImportMode = 
+/**
+ * Keep local records, settling conflicts with the chosen strategies
+ */
+"Merge" | 
+/**
+ * Discard the user's local records and stats, leaving only the backup's
+ */
+"Replace"
+export type ImportOptions = { mode: ImportMode; 
+/**
+ * Only used when merging
+ */
+strategies: ConflictStrategies; 
+/**
+ * Report what the import would do without writing anything
+ */
+dry_run: boolean }
+/**
+ * Outcome of an import, per category
+ */
+export type ImportReport = { 
+/**
+ * The backed-up user, created if they were missing
+ */
+user_id: string | null; 
+/**
+ * True if nothing was written
+ */
+dry_run: boolean; categories: CategoryReport[] }
+export type ImportResponse = { success: boolean; curriculum_id: string | null; error: string | null }
+/**
+ * Performance indicators for excellent/good/poor
+ */
+export type Indicators = { 
+/**
+ * What excellent performance looks like
+ */
+excellent: string; 
+/**
+ * What good performance looks like
+ */
+good: string; 
+/**
+ * What poor performance looks like
+ */
+poor: string }
+/**
+ * Whether the answer to a lecture's inline question was right, and why
+ */
+export type InlineAnswerFeedback = { correct: boolean; correct_answer: number; explanation: string }
+/**
+ * The image the sandbox runs
+ */
+export type InstalledImage = 
+/**
+ * Built from the bundled Dockerfile during setup
+ */
+{ kind: "built" } | ({ kind: "release" } & ImageRelease)
+export type ItemChange = "same" | "changed" | 
+/**
+ * Only the submission has an item by this name
+ */
+"added" | 
+/**
+ * Only the solution has an item by this name
+ */
+"missing"
+/**
+ * How one top-level item compares
+ */
+export type ItemDiff = { 
+/**
+ * `fn parse`, `struct Point`, ...
+ */
+name: string; change: ItemChange; 
+/**
+ * Share of tokens the two versions have in common, 0-1
+ */
+similarity: number }
+/**
+ * A background job's schedule and last run
+ */
+export type JobStatus = { id: string; description: string; schedule: string; running: boolean; last_started_at: string | null; last_duration_ms: number | null; 
+/**
+ * Summary of the last successful run
+ */
+last_result: string | null; 
+/**
+ * Error from the last run, if it failed
+ */
+last_error: string | null; last_success_at: string | null; run_count: number; failure_count: number; 
+/**
+ * None for a job that has never run, which runs on the next tick
+ */
+next_run_at: string | null }
+export type LeaderboardEntryResponse = (({ user_id: string; display_name: string; 
+/**
+ * XP earned within the period
+ */
+xp: number; level: number; current_streak: number }) & { rank: number }) & { is_current_user: boolean }
+export type LeaderboardPeriod = "Weekly" | "AllTime"
+export type LeaderboardResponse = { period: LeaderboardPeriod; generated_at: string; entries: LeaderboardEntryResponse[] }
+export type LectureSegmentView = { kind: "markdown"; text: string } | 
+/**
+ * An inline question; its id is its position among the lecture's
+ * inline questions
+ */
+{ kind: "quiz_inline"; question: QuestionView } | { kind: "challenge_ref"; node_id: string } | { kind: "mermaid"; source: string }
+/**
+ * A lecture as the learner sees it. Its questions come without answers or
+ * explanations, and the raw Markdown, whose quiz blocks hold them, is left
+ * out; answers are only checked when they're given.
+ */
+export type LectureView = { segments: LectureSegmentView[]; 
+/**
+ * One message per unreadable block, with its line
+ */
+problems: string[]; 
+/**
+ * Questions to answer before the lecture counts as complete, with ids
+ * "0", "1", ... in the order answers are given
+ */
+comprehension_check: QuestionView[] }
+/**
+ * Leech review item with the lecture node the user should revisit
+ */
+export type LeechItemResponse = ({ quiz_id: string; due_date: string; ease_factor: number; interval_days: number; repetitions: number; last_reviewed_at: string | null; lapses: number; is_suspended: boolean }) & { lecture_node_id: string | null }
+/**
+ * Where a license stands on a given day
+ */
+export type LicenseStatus = { state: "active" } | 
+/**
+ * Expired, but content stays open until `ends_on`
+ */
+{ state: "grace"; ends_on: string } | { state: "expired" }
+/**
+ * A provider the grader can send artifacts to
+ */
+export type LlmProvider = "openai" | "anthropic" | 
+/**
+ * A local or self-hosted Ollama server
+ */
+"ollama"
+export type MasteryHistoryPoint = { previous_score: number; score: number; reason: string; recorded_at: string }
+export type MasterySkillResponse = { skill_id: string; score: number; level: string; last_updated: string }
+/**
+ * Why the answer chosen for a missed question was wrong, kept with the
+ * attempt so it can be reviewed later
+ */
+export type MistakeExplanation = { attempt_id: string; question_id: string; chosen_option: string; explanation: string; 
+/**
+ * Reused from an earlier explanation of the same mistake
+ */
+from_cache: boolean; created_at: string }
+/**
+ * Progress toward the next tier of a tiered badge
+ */
+export type NextTierProgress = { tier: BadgeTier; threshold: number; current_value: number; 
+/**
+ * Fraction of the next tier's threshold reached (0.0 to 1.0)
+ */
+progress: number }
+export type NodeData = { id: string; node_type: string; title: string; description: string; difficulty: string; estimated_minutes: number; xp_reward: number; prerequisites: string[]; skills: string[]; 
+/**
+ * Set for project track days, which are listed apart from regular nodes
+ */
+track: TrackStep | null; 
+/**
+ * Retired, and kept only for learners who already have progress on it
+ */
+deprecated: boolean; superseded_by: string | null }
+/**
+ * Time a learner has actively spent on a node, from the frontend's
+ * heartbeats
+ */
+export type NodeTime = { user_id: string; curriculum_id: string | null; node_id: string; 
+/**
+ * Time between heartbeats, without the idle gaps
+ */
+active_seconds: number; 
+/**
+ * Gaps between heartbeats too long to count
+ */
+idle_seconds: number; heartbeats: number; last_heartbeat_at: string | null }
+/**
+ * A learner's free-form note on a content node
+ */
+export type Note = { id: string; user_id: string; node_id: string; body: string; 
+/**
+ * Passage of the node's content the note annotates; `None` for a note
+ * on the whole node
+ */
+anchor?: string | null; created_at: string; updated_at: string }
+/**
+ * A note with the title of the node it's on, for the notes overview
+ */
+export type NoteListItem = { note: Note; 
+/**
+ * `None` if the node isn't in the active curriculum
+ */
+node_title: string | null; node_type: string | null }
+/**
+ * When and whether a user is reminded to study
+ */
+export type NotificationPrefs = { 
+/**
+ * Turns all reminders off when false
+ */
+enabled: boolean; review_reminders: boolean; 
+/**
+ * Time reviews due that day are announced
+ */
+review_time: string; streak_reminders: boolean; 
+/**
+ * Time the user is warned a streak will break without study today
+ */
+streak_time: string; quiet_hours: QuietHours | null; 
+/**
+ * Monday reminder, at `review_time`, to read the weekly report
+ */
+weekly_report_reminders?: boolean }
+export type NotificationSchedule = { prefs: NotificationPrefs; 
+/**
+ * Next reminder of each kind, soonest first, in local time
+ */
+upcoming: ScheduledReminder[] }
+/**
+ * A study day in a pace plan and the nodes to complete on it
+ */
+export type PaceDay = { date: string; node_ids: string[]; minutes: number }
+export type PacePlan = { target_date: string; set_on: string; remaining_nodes: number; remaining_minutes: number; 
+/**
+ * Study days from today to the target date, both included
+ */
+study_days_left: number; 
+/**
+ * Minutes a study day takes to finish on time
+ */
+minutes_per_day: number; daily_goal_minutes: number; 
+/**
+ * `minutes_per_day` is more than the daily goal
+ */
+over_daily_goal: boolean; 
+/**
+ * Minutes that would be left today at the pace set with the target
+ */
+expected_remaining_minutes: number; 
+/**
+ * More than `PACE_BEHIND_MARGIN` of the work past that pace
+ */
+behind: boolean; 
+/**
+ * Every study day left, in order. When none are left, today holds
+ * everything remaining.
+ */
+days: PaceDay[] }
+/**
+ * The date a learner wants to finish a curriculum by
+ */
+export type PaceTarget = { target_date: string; 
+/**
+ * The day the target was chosen
+ */
+set_on: string; 
+/**
+ * Estimated minutes of the nodes left when the target was chosen
+ */
+minutes_at_start: number }
+/**
+ * Work deferred until a capability it needs is available again, such as
+ * grading a checkpoint submitted while offline
+ */
+export type PendingWork = { id: string; user_id: string; kind: PendingWorkKind; capability: Capability; 
+/**
+ * Kind-specific JSON the work is run from
+ */
+payload: string; created_at: string; attempts: number; last_attempt_at: string | null; last_error: string | null; completed_at: string | null }
+export type PendingWorkKind = 
+/**
+ * Grade a submitted checkpoint artifact
+ */
+"GradeArtifact"
+export type PlannedActivity = { node_id: string; node_type: string; title: string; difficulty: string; xp_reward: number; estimated_minutes: number }
+/**
+ * Why a submission's dependencies weren't accepted
+ */
+export type PolicyViolation = { 
+/**
+ * The crates at fault
+ */
+crates: string[]; message: string }
+export type PracticeResult = { verification: VerificationResult; review: ReviewItemResponse }
+/**
+ * A practice challenge as the learner sees it, without the solution
+ */
+export type PracticeView = { id: string; 
+/**
+ * Id of the challenge's item in the review queue
+ */
+review_id: string; skill_id: string; difficulty: string; title: string; instructions: string; starter_code: string; test_code: string; hints: string[]; created_at: string }
+export type ProfileSummary = ({ id: string; display_name: string; total_xp: number; current_level: number; current_streak: number; xp_for_next_level: number; xp_progress_percentage: number; is_guest: boolean }) & { is_current: boolean }
+export type ProgressData = { node_id: string; status: string; attempts: number; time_spent_mins: number; completed: boolean }
+/**
+ * Result of checking a provider's credentials
+ */
+export type ProviderCheck = { provider: LlmProvider; 
+/**
+ * Model the grader will use
+ */
+model: string; 
+/**
+ * Whether that model is among the ones the credentials can use
+ */
+model_available: boolean; 
+/**
+ * Models the credentials can use
+ */
+available_models: string[]; latency_ms: number }
+export type ProviderStatus = { provider: LlmProvider; name: string; configured: boolean; requires_api_key: boolean; endpoint: string; model: string; 
+/**
+ * Whether artifacts are graded with this provider
+ */
+selected: boolean }
+export type QuestResponse = { id: string; period: string; kind: string; description: string; target: number; progress: number; xp_reward: number; ends_at: string; is_complete: boolean; is_claimed: boolean }
+export type QuestionFeedback = { question_id: string; user_answer: string | null; correct_answer: string; is_correct: boolean; explanation: string }
+export type QuestionOption = { id: string; text: string }
+/**
+ * One question of a past attempt, with what was chosen and why the right
+ * answer is right
+ */
+export type QuestionReview = { question_id: string; prompt: string; code_snippet: string | null; options: QuestionOption[]; 
+/**
+ * None if the question was skipped
+ */
+answer: string | null; correct_answer: string; is_correct: boolean; explanation: string; 
+/**
+ * Why the chosen answer was wrong, if one was asked for
+ */
+mistake_explanation: string | null }
+/**
+ * How often a question has been answered, and missed, across attempts
+ */
+export type QuestionStats = { quiz_id: string; question_id: string; answered: number; missed: number }
+/**
+ * A question as the learner sees it before answering: no correct answer and
+ * no explanation. Quizzes and lecture questions are all sent this way.
+ */
+export type QuestionView = { id: string; question_type: string; prompt: string; code_snippet: string | null; options: QuestionOption[]; points: number }
+/**
+ * Hours of the day reminders are held back. The window may cross midnight.
+ */
+export type QuietHours = { start: string; end: string }
+export type QuizAnalytics = { most_missed: QuestionStats[]; skill_accuracy: SkillAccuracy[] }
+export type QuizAttempt = { id: string; user_id: string; 
+/**
+ * Curriculum this belongs to; `None` for data recorded before curricula
+ */
+curriculum_id?: string | null; quiz_id: string; node_id: string; answers: string[]; score_percentage: number; xp_earned: number; submitted_at: string; 
+/**
+ * When a timed quiz was started; None for untimed quizzes
+ */
+started_at?: string | null; 
+/**
+ * How far past the time limit it was submitted, 0 if on time
+ */
+late_seconds?: number }
+export type QuizAttemptDetail = { attempt: QuizAttempt; quiz_title: string; questions: QuestionReview[] }
+export type QuizResult = { attempt_id: string; score: number; total: number; score_percentage: number; passed: boolean; xp_earned: number; attempt_number: number; mastery_updates: Partial<{ [key in string]: number }>; feedback: QuestionFeedback[]; 
+/**
+ * Seconds past a timed quiz's limit, 0 if on time or untimed
+ */
+late_seconds: number; 
+/**
+ * Points taken off `score_percentage` for lateness
+ */
+penalty_percent: number }
+/**
+ * The clock for a timed quiz, for the countdown
+ */
+export type QuizStart = { quiz_id: string; started_at: string; time_limit_seconds: number | null; deadline: string | null }
+/**
+ * A quiz as the learner sees it before answering: no correct answers and no
+ * explanations, which only come back once it's submitted
+ */
+export type QuizView = { id: string; title: string; description: string; difficulty: string; passing_score: number; time_limit_seconds: number | null; questions: QuestionView[] }
+/**
+ * An event with the curriculum it happened in and the day it was recorded.
+ * No user id and no time of day, so events can't be tied to a learner or
+ * lined up with their sessions.
+ */
+export type RecordedEvent = { recorded_on: string; 
+/**
+ * Curriculum title and version, as its manifest gives them
+ */
+curriculum: string; curriculum_version: string; event: AnalyticsEvent }
+/**
+ * What was kept from a session closed after the app stopped unexpectedly
+ */
+export type RecoveredSession = { session_id: string; user_id: string; started_at: string; 
+/**
+ * The last heartbeat, when the app was last known to be running
+ */
+ended_at: string; active_seconds: number; items_completed: number; total_xp_earned: number }
+export type RelatedNode = { node_id: string; title: string; node_type: string; kind: EmbeddedKind; score: number }
+export type ReminderKind = 
+/**
+ * Reviews are waiting
+ */
+"ReviewsDue" | 
+/**
+ * Nothing studied yet today and the streak will break
+ */
+"StreakAtRisk" | 
+/**
+ * A goal has fallen behind the pace needed to meet its deadline
+ */
+"GoalBehind" | 
+/**
+ * The weekly progress report is ready, sent on Mondays
+ */
+"WeeklyReport"
+/**
+ * A checkpoint submitted as a GitHub repo: the commit that was checked, how
+ * the checkpoint's tests fared on it and the artifact grades it produced.
+ * Submissions that failed before anything ran are kept too, with the
+ * error, since they count towards the submission rate limit.
+ */
+export type RepoSubmission = { id: string; user_id: string; curriculum_id: string | null; checkpoint_id: string; repo_url: string; commit_hash: string | null; 
+/**
+ * Unset when the checkpoint has no tests or they never ran
+ */
+tests_passed: number | null; tests_total: number | null; 
+/**
+ * The runner's full result
+ */
+verification_json: string | null; 
+/**
+ * Artifact grades recorded for this submission
+ */
+artifact_submission_ids: string[]; 
+/**
+ * The tests passed and every graded artifact passed
+ */
+passed: boolean; error: string | null; submitted_at: string }
+/**
+ * Resource limits that can be hit
+ */
+export type ResourceLimit = 
+/**
+ * Memory limit exceeded
+ */
+"Memory" | 
+/**
+ * CPU limit exceeded
+ */
+"Cpu" | 
+/**
+ * Disk space limit exceeded
+ */
+"DiskSpace" | 
+/**
+ * Process count limit exceeded (fork bomb protection)
+ */
+"ProcessCount"
+/**
+ * Resources a verification container used, sampled from Docker's stats
+ * while it ran. The figures are for the whole run, building the crate
+ * included, not just the tests.
+ */
+export type ResourceUsage = { 
+/**
+ * Highest memory use seen, in bytes
+ */
+peak_memory_bytes: number; 
+/**
+ * CPU time across all cores, in milliseconds
+ */
+cpu_time_ms: number; 
+/**
+ * Time the container ran, in milliseconds
+ */
+wall_time_ms: number; 
+/**
+ * Bytes written to disk
+ */
+bytes_written: number }
+/**
+ * A comment on one line of the submission
+ */
+export type ReviewComment = { 
+/**
+ * 1-based line in the submitted code
+ */
+line: number; severity: CommentSeverity; 
+/**
+ * Rubric category the comment falls under
+ */
+category: string; comment: string }
+/**
+ * Review item for frontend
+ */
+export type ReviewItemResponse = { quiz_id: string; due_date: string; ease_factor: number; interval_days: number; repetitions: number; last_reviewed_at: string | null; lapses: number; is_suspended: boolean }
+/**
+ * How reviews are spaced out
+ */
+export type ReviewScheduler = 
+/**
+ * SuperMemo 2, see `spaced_repetition`
+ */
+"Sm2"
+/**
+ * A grading rubric
+ */
+export type Rubric = { 
+/**
+ * Type of artifact (e.g., "DESIGN", "README")
+ */
+artifact_type: string; 
+/**
+ * Total possible points
+ */
+total_points: number; 
+/**
+ * Grading categories
+ */
+categories: RubricCategory[]; 
+/**
+ * Guidelines for letter grades
+ */
+grading_guidelines?: GradingGuidelines; 
+/**
+ * Required sections that must be present
+ */
+mandatory_sections?: string[] }
+/**
+ * A category within a rubric
+ */
+export type RubricCategory = { 
+/**
+ * Category name
+ */
+name: string; 
+/**
+ * Points for this category
+ */
+points: number; 
+/**
+ * Detailed criteria (optional)
+ */
+criteria?: Criterion[]; 
+/**
+ * Simple indicators (optional, alternative to criteria)
+ */
+indicators?: Indicators | null }
+/**
+ * Runtime error types
+ */
+export type RuntimeError = 
+/**
+ * Code timed out
+ */
+"Timeout" | 
+/**
+ * Code panicked
+ */
+{ Panic: { message: string } } | 
+/**
+ * Code ran out of memory
+ */
+"OutOfMemory" | 
+/**
+ * Unknown runtime error
+ */
+{ Unknown: { stderr: string } }
+/**
+ * What the learner asked to do with the sandbox image
+ */
+export type SandboxImageAction = 
+/**
+ * Check the manifest and download a newer release
+ */
+"Check" | 
+/**
+ * Switch to the downloaded release
+ */
+"Apply" | 
+/**
+ * Switch back to the image the last switch replaced
+ */
+"RollBack"
+/**
+ * Where sandbox images come from and which one is installed
+ */
+export type SandboxImageState = { manifest_url: string | null; 
+/**
+ * Digests accepted from the manifest; any are if none are pinned
+ */
+pinned_digests?: string[]; 
+/**
+ * Switch to updates once they're downloaded, rather than waiting
+ */
+auto_apply?: boolean; current: InstalledImage; previous: InstalledImage | null; 
+/**
+ * Downloaded and verified, waiting to be switched to
+ */
+downloaded: ImageRelease | null; last_checked_at: string | null; last_error: string | null }
+export type SandboxImageStatus = { 
+/**
+ * Tag verification runs in
+ */
+image: string; 
+/**
+ * Whether Docker has the image
+ */
+present: boolean; 
+/**
+ * Whether the image the last switch replaced is still there
+ */
+can_roll_back: boolean; state: SandboxImageState }
+export type ScheduledReminder = { kind: ReminderKind; 
+/**
+ * Day the reminder is about; each kind is sent at most once per day
+ */
+for_date: string; 
+/**
+ * Local time it's delivered, after moving it out of quiet hours
+ */
+at: string; title: string; body: string }
+/**
+ * One match from a user's own history
+ */
+export type SearchHit = { source: SearchSource; 
+/**
+ * ID of the note, quiz attempt or artifact submission
+ */
+record_id: string; 
+/**
+ * Node the record belongs to (the checkpoint for grade feedback)
+ */
+node_id: string; 
+/**
+ * Matching text with the search terms wrapped in `[` `]`
+ */
+snippet: string; occurred_at: string; 
+/**
+ * BM25 relevance; lower is a better match
+ */
+rank: number }
+/**
+ * Where a search hit came from
+ */
+export type SearchSource = "Note" | "QuizAnswer" | "GradeFeedback"
+/**
+ * The learner's score for one rubric category
+ */
+export type SelfScore = { 
+/**
+ * Category name, as in the rubric
+ */
+category: string; 
+/**
+ * Points given, at most the category's points
+ */
+score: number; 
+/**
+ * Why, in the learner's words
+ */
+note?: string }
+export type SessionPlan = { session_id: string; activities: PlannedActivity[]; estimated_minutes: number; total_xp_potential: number }
+export type SessionSummary = { session_id: string; duration_minutes: number; 
+/**
+ * Time the learner was active on lectures and challenges, without
+ * idle gaps
+ */
+active_minutes: number; total_xp_earned: number; activities_completed: CompletedActivitySummary[]; level_before: number; level_after: number; leveled_up: boolean; streak_days: number; streak_multiplier: number }
+/**
+ * The current profile's settings. The grading provider is shared by every
+ * profile on this install, so it lives with the provider credentials.
+ */
+export type SettingsView = ({ 
+/**
+ * Version of the document these settings were read from
+ */
+version: number; theme: Theme; 
+/**
+ * Minutes of study a day the learner aims for
+ */
+daily_goal_minutes: number; 
+/**
+ * Days pacing plans spread the remaining nodes over
+ */
+study_days: StudyDays; review_scheduler: ReviewScheduler; notifications: NotificationPrefs }) & { grading_provider: LlmProvider }
+/**
+ * What the learner asked to do with the current step
+ */
+export type SetupAction = 
+/**
+ * Run the step, or retry it after a failure
+ */
+"Run" | "Skip"
+/**
+ * Progress through first-run setup
+ */
+export type SetupState = { steps: StepState[]; started_at: string; completed_at: string | null }
+/**
+ * One step of first-run setup, in the order they run
+ */
+export type SetupStep = "CheckDocker" | "PrepareSandbox" | "ValidateApiKey" | "CreateUser" | "ImportCurriculum"
+/**
+ * Accuracy over every answered question testing a skill
+ */
+export type SkillAccuracy = { skill_id: string; answered: number; correct: number }
+export type SkillMastery = { skill_id: string; score: number; last_updated_at: string }
+export type SolutionDiff = { 
+/**
+ * Share of tokens the submission and solution have in common, 0-1
+ */
+similarity: number; items: ItemDiff[]; notes: DiffNote[] }
+export type SolutionFeedback = { 
+/**
+ * The passing attempt compared
+ */
+attempt_id: string; solution: string; diff: SolutionDiff }
+export type StatsRange = "Week" | "Month" | "Quarter" | "Year" | "AllTime"
+export type StepState = { step: SetupStep; status: StepStatus; 
+/**
+ * What happened on the last attempt, for display
+ */
+message: string | null; attempts: number; updated_at: string | null }
+export type StepStatus = "Pending" | "Done" | "Skipped" | 
+/**
+ * Ran and failed; stays the current step until it succeeds or is skipped
+ */
+"Failed"
+/**
+ * Days of the week the learner studies on
+ */
+export type StudyDays = { monday: boolean; tuesday: boolean; wednesday: boolean; thursday: boolean; friday: boolean; saturday: boolean; sunday: boolean }
+export type SubmitQuizRequest = { quiz_id: string; answers: Partial<{ [key in string]: string }>; time_spent_ms: number }
+/**
+ * Outcome of one sync
+ */
+export type SyncReport = { device_id: string; 
+/**
+ * Local changes written to this device's op-log
+ */
+ops_sent: number; 
+/**
+ * New ops read from other devices' logs
+ */
+ops_received: number; 
+/**
+ * Received ops that changed local data
+ */
+ops_applied: number; 
+/**
+ * Received ops that lost to a newer version of the same record
+ */
+conflicts: number; 
+/**
+ * Other devices with a log for this user
+ */
+devices: number }
+export type SyncStatus = { folder: string | null; device_id: string; last_synced_at: string | null }
+export type SystemStatus = { docker_installed: boolean; docker_running: boolean; api_key_set: boolean; database_ok: boolean }
+export type Theme = 
+/**
+ * Follow the operating system
+ */
+"System" | "Light" | "Dark"
+/**
+ * How much active time learners took to complete a node
+ */
+export type TimeHistogram = { node_id: string; bucket_minutes: number; 
+/**
+ * Learners per bucket; bucket `i` covers `i * bucket_minutes` up to
+ * `(i + 1) * bucket_minutes` minutes
+ */
+counts: number[]; learners: number; median_minutes: number }
+export type TrackDayData = { node_id: string; day: number; title: string; status: string; completed: boolean }
+/**
+ * A project track's progress, kept apart from the regular nodes
+ */
+export type TrackProgressData = { track_id: string; title: string; description: string; 
+/**
+ * Suggested name for the learner's repo directory
+ */
+repo_dir: string; 
+/**
+ * The repo the learner chose, once they have
+ */
+repo_path: string | null; days_total: number; days_completed: number; 
+/**
+ * First day not yet completed
+ */
+next_node_id: string | null; days: TrackDayData[] }
+/**
+ * Where a learner keeps the repo they build a project track in. Every
+ * day of the track is verified against this one directory.
+ */
+export type TrackRepo = { user_id: string; curriculum_id: string | null; track_id: string; repo_path: string; updated_at: string }
+/**
+ * Where a node sits in a project track
+ */
+export type TrackStep = { track: string; 
+/**
+ * 1-based; each day builds on the repo left by the day before
+ */
+day: number }
+export type TutorConversation = { session: TutorSession; 
+/**
+ * Oldest first
+ */
+messages: TutorMessage[] }
+export type TutorExchange = { question: TutorMessage; reply: TutorMessage; 
+/**
+ * Older messages the tutor didn't see, to stay within its token budget
+ */
+dropped_messages: number }
+export type TutorMessage = { id: string; session_id: string; role: TutorRole; content: string; created_at: string }
+export type TutorRole = "User" | "Assistant"
+/**
+ * A conversation with the tutor about one lecture
+ */
+export type TutorSession = { id: string; user_id: string; curriculum_id: string | null; node_id: string; created_at: string; updated_at: string }
+/**
+ * A destructive operation that can still be undone
+ */
+export type UndoableOperation = { id: string; operation: string; subject_id: string | null; row_count: number; created_at: string; expires_at: string }
+export type UserData = { id: string; display_name: string; total_xp: number; current_level: number; current_streak: number; xp_for_next_level: number; xp_progress_percentage: number; is_guest: boolean }
+export type ValidationResponse = { is_valid: boolean; errors: string[]; warnings: string[]; name: string | null; version: string | null; description: string | null; author: string | null; stats: ContentStats | null }
+/**
+ * Result of running a challenge verification
+ */
+export type VerificationResult = { 
+/**
+ * Whether all tests passed
+ */
+success: boolean; 
+/**
+ * Standard output from the test run
+ */
+stdout: string; 
+/**
+ * Standard error from the test run
+ */
+stderr: string; 
+/**
+ * Duration of the test run in milliseconds
+ */
+duration_ms: number; 
+/**
+ * Number of tests that passed
+ */
+tests_passed: number; 
+/**
+ * Number of tests that failed
+ */
+tests_failed: number; 
+/**
+ * Total number of tests
+ */
+tests_total: number; 
+/**
+ * Compile error if any
+ */
+compile_error: CompileError | null; 
+/**
+ * Runtime error if any
+ */
+runtime_error: RuntimeError | null; 
+/**
+ * Resource limit that was hit, if any
+ */
+resource_limit_hit: ResourceLimit | null; 
+/**
+ * Dependencies the challenge doesn't allow, in which case nothing ran
+ */
+policy_violation?: PolicyViolation | null; 
+/**
+ * What the container used, if Docker reported its stats
+ */
+resource_usage?: ResourceUsage | null; 
+/**
+ * Every compiler error and warning, with its spans
+ */
+diagnostics?: Diagnostic[]; 
+/**
+ * Names of the tests that failed
+ */
+failed_tests?: string[]; 
+/**
+ * Tests that failed, then passed when run again
+ */
+flaky_tests?: string[]; 
+/**
+ * Times the failed tests were run again
+ */
+reruns?: number }
+export type WeekData = { id: string; title: string; description: string; days: DayData[] }
+/**
+ * Active study time in the week starting on `week_start`, a Monday (YYYY-MM-DD)
+ */
+export type WeeklyTime = { week_start: string; active_minutes: number }
+/**
+ * Activity types that award XP
+ */
+export type XpActivity = "Lecture" | "Quiz" | "Challenge" | "Checkpoint"
+/**
+ * One contribution to the expected total
+ */
+export type XpAuditEntry = { source: XpAuditSource; node_id: string | null; recorded_xp: number | null; min_xp: number; max_xp: number }
+export type XpAuditReport = { user_id: string; recorded_xp: number; recorded_level: number; expected_min_xp: number; expected_max_xp: number; entries: XpAuditEntry[]; discrepancies: XpDiscrepancy[]; 
+/**
+ * Completed nodes that are not part of the curriculum and were not counted
+ */
+skipped_nodes: string[] }
+/**
+ * Where a line of expected XP comes from
+ */
+export type XpAuditSource = "lecture" | "quiz" | 
+/**
+ * A passed quiz with no recorded attempts (e.g. data from an older build)
+ */
+"untracked_quiz" | "quest" | "focus" | "challenge" | "checkpoint"
+export type XpDiscrepancy = 
+/**
+ * A quiz attempt recorded XP the formula could not have produced
+ */
+{ type: "attempt_out_of_range"; attempt_id: string; quiz_id: string; recorded_xp: number; min_xp: number; max_xp: number } | { type: "total_below_expected"; recorded_xp: number; min_xp: number } | { type: "total_above_expected"; recorded_xp: number; max_xp: number } | { type: "level_mismatch"; recorded_level: number; expected_level: number }
+export type XpEventResponse = { id: string; name: string; description: string; multiplier: number; starts_at: string; ends_at: string; activities: XpActivity[] }
+
+/** tauri-specta globals **/
+
+import {
+	invoke as TAURI_INVOKE,
+	Channel as TAURI_CHANNEL,
+} from "@tauri-apps/api/core";
+import * as TAURI_API_EVENT from "@tauri-apps/api/event";
+import { type WebviewWindow as __WebviewWindow__ } from "@tauri-apps/api/webviewWindow";
+
+type __EventObj__<T> = {
+	listen: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.listen<T>>;
+	once: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.once<T>>;
+	emit: null extends T
+		? (payload?: T) => ReturnType<typeof TAURI_API_EVENT.emit>
+		: (payload: T) => ReturnType<typeof TAURI_API_EVENT.emit>;
+};
+
+export type Result<T, E> =
+	| { status: "ok"; data: T }
+	| { status: "error"; error: E };
+
+function __makeEvents__<T extends Record<string, any>>(
+	mappings: Record<keyof T, string>,
+) {
+	return new Proxy(
+		{} as unknown as {
+			[K in keyof T]: __EventObj__<T[K]> & {
+				(handle: __WebviewWindow__): __EventObj__<T[K]>;
+			};
+		},
+		{
+			get: (_, event) => {
+				const name = mappings[event as keyof T];
+
+				return new Proxy((() => {}) as any, {
+					apply: (_, __, [window]: [__WebviewWindow__]) => ({
+						listen: (arg: any) => window.listen(name, arg),
+						once: (arg: any) => window.once(name, arg),
+						emit: (arg: any) => window.emit(name, arg),
+					}),
+					get: (_, command: keyof __EventObj__<any>) => {
+						switch (command) {
+							case "listen":
+								return (arg: any) => TAURI_API_EVENT.listen(name, arg);
+							case "once":
+								return (arg: any) => TAURI_API_EVENT.once(name, arg);
+							case "emit":
+								return (arg: any) => TAURI_API_EVENT.emit(name, arg);
+						}
+					},
+				});
+			},
+		},
+	);
+}
@@ -0,0 +1,41 @@
+import { History, X } from 'lucide-react'
+import { useSessionRecovery } from '../../hooks/useSessionRecovery'
+
+function formatMinutes(seconds: number) {
+  const minutes = Math.round(seconds / 60)
+  return minutes === 1 ? '1 minute' : `${minutes} minutes`
+}
+
+export function SessionRecoveredNotice() {
+  const { recovered, dismiss } = useSessionRecovery()
+
+  if (recovered.length === 0) return null
+
+  const seconds = recovered.reduce((sum, s) => sum + s.active_seconds, 0)
+  const items = recovered.reduce((sum, s) => sum + s.items_completed, 0)
+
+  return (
+    <div
+      className="fixed bottom-16 right-4 max-w-sm bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-lg shadow-lg p-4 z-50"
+      role="status"
+    >
+      <div className="flex items-start gap-3">
+        <History className="w-5 h-5 text-primary flex-shrink-0 mt-0.5" />
+        <div className="flex-1 min-w-0 text-sm">
+          <p className="font-medium dark:text-white">Your last session was interrupted</p>
+          <p className="text-gray-600 dark:text-gray-400 mt-1">
+            We saved {formatMinutes(seconds)} of study time
+            {items > 0 && ` and ${items} completed ${items === 1 ? 'activity' : 'activities'}`}.
+          </p>
+        </div>
+        <button
+          onClick={dismiss}
+          className="p-1 text-gray-400 hover:text-gray-600 dark:hover:text-gray-200 rounded"
+          aria-label="Dismiss"
+        >
+          <X className="w-4 h-4" />
+        </button>
+      </div>
+    </div>
+  )
+}
@@ -0,0 +1,96 @@
+import { useEffect, useState } from 'react'
+import { Pause, Play, Square, Timer } from 'lucide-react'
+import { useFocusStore, FocusBlockKind } from '@/stores/focusStore'
+
+const KIND_LABELS: Record<FocusBlockKind, string> = {
+  Work: 'Focus',
+  ShortBreak: 'Short break',
+  LongBreak: 'Long break',
+}
+
+function formatRemaining(seconds: number) {
+  const clamped = Math.max(0, seconds)
+  const minutes = Math.floor(clamped / 60)
+  return `${minutes}:${String(clamped % 60).padStart(2, '0')}`
+}
+
+export function FocusTimer() {
+  const { focus, error, fetchFocusState, startBlock, pauseBlock, resumeBlock, cancelBlock } = useFocusStore()
+  const [remaining, setRemaining] = useState(0)
+
+  useEffect(() => {
+    fetchFocusState()
+  }, [])
+
+  useEffect(() => {
+    setRemaining(focus?.remaining_seconds ?? 0)
+  }, [focus])
+
+  const running = focus?.block?.status === 'Running'
+
+  // Count down locally and let the backend settle the block when it runs out
+  useEffect(() => {
+    if (!running) return
+    const timer = setInterval(() => {
+      setRemaining((seconds) => {
+        if (seconds <= 1) {
+          fetchFocusState()
+          return 0
+        }
+        return seconds - 1
+      })
+    }, 1000)
+    return () => clearInterval(timer)
+  }, [running])
+
+  if (!focus) return null
+  const block = focus.block
+
+  return (
+    <div className="bg-white dark:bg-gray-800 rounded-lg shadow p-6 mb-6">
+      <div className="flex items-center justify-between mb-4">
+        <h2 className="text-lg font-semibold flex items-center gap-2 dark:text-white">
+          <Timer size={20} /> Focus Timer
+        </h2>
+        <span className="text-sm text-gray-500 dark:text-gray-400">
+          {focus.completed_work_blocks_today} completed today
+        </span>
+      </div>
+
+      {block ? (
+        <div className="flex items-center justify-between">
+          <div>
+            <p className="text-sm text-gray-500 dark:text-gray-400">
+              {KIND_LABELS[block.kind]}
+              {block.status === 'Paused' && ' · paused'}
+            </p>
+            <p className="text-3xl font-mono dark:text-white">{formatRemaining(remaining)}</p>
+          </div>
+          <div className="flex gap-2">
+            {running ? (
+              <button onClick={pauseBlock} className="p-2 rounded-lg bg-gray-100 dark:bg-gray-700" title="Pause">
+                <Pause size={18} />
+              </button>
+            ) : (
+              <button onClick={resumeBlock} className="p-2 rounded-lg bg-gray-100 dark:bg-gray-700" title="Resume">
+                <Play size={18} />
+              </button>
+            )}
+            <button onClick={cancelBlock} className="p-2 rounded-lg bg-gray-100 dark:bg-gray-700" title="Stop">
+              <Square size={18} />
+            </button>
+          </div>
+        </div>
+      ) : (
+        <button
+          onClick={() => startBlock()}
+          className="px-4 py-2 bg-primary text-white rounded-lg hover:bg-primary/90 transition-colors"
+        >
+          Start {KIND_LABELS[focus.next_kind].toLowerCase()}
+        </button>
+      )}
+
+      {error && <p className="mt-2 text-sm text-red-600 dark:text-red-400">{error}</p>}
+    </div>
+  )
+}
@@ -0,0 +1,47 @@
+import { useEffect } from 'react'
+import { useCapabilityStore, CapabilityState } from '@/stores/capabilityStore'
+import { Button } from '@/components/common/Button'
+
+function CapabilityRow({ name, state }: { name: string; state: CapabilityState }) {
+  return (
+    <li className="flex justify-between gap-4 p-3 border border-gray-200 dark:border-gray-700 rounded-lg text-sm">
+      <span className="font-medium dark:text-white">{name}</span>
+      <span className={state.available ? 'text-green-600 dark:text-green-400' : 'text-yellow-600 dark:text-yellow-400'}>
+        {state.available ? 'Available' : 'Unavailable'}
+        {state.detail && ` · ${state.detail}`}
+      </span>
+    </li>
+  )
+}
+
+export function CapabilityStatusList() {
+  const { status, isChecking, error, fetchCapabilityStatus } = useCapabilityStore()
+
+  useEffect(() => {
+    fetchCapabilityStatus()
+  }, [])
+
+  if (error) {
+    return <p className="text-sm text-red-600 dark:text-red-400">{error}</p>
+  }
+  if (!status) return null
+
+  const waiting = status.pending_work.length
+
+  return (
+    <div className="space-y-3">
+      <ul className="space-y-2">
+        <CapabilityRow name="Docker" state={status.docker} />
+        <CapabilityRow name="Grading provider" state={status.llm} />
+      </ul>
+      {waiting > 0 && (
+        <p className="text-sm text-gray-600 dark:text-gray-400">
+          {waiting} {waiting === 1 ? 'submission is' : 'submissions are'} waiting to be graded when online.
+        </p>
+      )}
+      <Button variant="outline" onClick={() => fetchCapabilityStatus(true)} disabled={isChecking}>
+        {isChecking ? 'Checking…' : 'Check Again'}
+      </Button>
+    </div>
+  )
+}
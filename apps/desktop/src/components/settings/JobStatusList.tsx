@@ -0,0 +1,45 @@
+import { useEffect } from 'react'
+import { useJobStore, JobStatus } from '@/stores/jobStore'
+
+function formatTime(time: string | null) {
+  return time ? new Date(time).toLocaleString() : 'Never'
+}
+
+function lastRunSummary(job: JobStatus) {
+  if (job.running) return 'Running…'
+  if (!job.last_started_at) return 'Not run yet'
+  if (job.last_error) return `Failed: ${job.last_error}`
+  return job.last_result || 'Succeeded'
+}
+
+export function JobStatusList() {
+  const { jobs, error, fetchJobStatus } = useJobStore()
+
+  useEffect(() => {
+    fetchJobStatus()
+  }, [])
+
+  if (error) {
+    return <p className="text-sm text-red-600 dark:text-red-400">{error}</p>
+  }
+
+  return (
+    <ul className="space-y-2">
+      {jobs.map((job) => (
+        <li key={job.id} className="p-3 border border-gray-200 dark:border-gray-700 rounded-lg text-sm">
+          <div className="flex justify-between gap-4">
+            <span className="font-medium dark:text-white">{job.description}</span>
+            <span className="text-gray-500 dark:text-gray-400 whitespace-nowrap">{job.schedule}</span>
+          </div>
+          <p className={job.last_error ? 'text-yellow-600 dark:text-yellow-400' : 'text-gray-500 dark:text-gray-400'}>
+            {lastRunSummary(job)}
+          </p>
+          <p className="text-xs text-gray-500 dark:text-gray-400">
+            Last run {formatTime(job.last_started_at)} · Next run{' '}
+            {job.next_run_at ? formatTime(job.next_run_at) : 'shortly'}
+          </p>
+        </li>
+      ))}
+    </ul>
+  )
+}
@@ -0,0 +1,167 @@
+import { useEffect, useState } from 'react'
+import { useSystemStore, LlmProvider, ProviderCheck, ProviderStatus } from '@/stores/systemStore'
+import { Button } from '@/components/common/Button'
+import { getErrorMessage } from '@/utils/errorHandler'
+
+interface Draft {
+  apiKey: string
+  endpoint: string
+  model: string
+}
+
+const inputClass =
+  'w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg dark:bg-gray-700 dark:text-white text-sm'
+
+export function ProviderSettings() {
+  const {
+    getProviderStatus,
+    saveProviderCredentials,
+    removeProviderCredentials,
+    setGradingProvider,
+    testApiKey,
+  } = useSystemStore()
+  const [providers, setProviders] = useState<ProviderStatus[]>([])
+  const [editing, setEditing] = useState<LlmProvider | null>(null)
+  const [draft, setDraft] = useState<Draft>({ apiKey: '', endpoint: '', model: '' })
+  const [testing, setTesting] = useState<LlmProvider | null>(null)
+  const [results, setResults] = useState<Partial<Record<LlmProvider, ProviderCheck | string>>>({})
+
+  const refresh = async () => setProviders(await getProviderStatus())
+
+  useEffect(() => {
+    refresh()
+  }, [])
+
+  const startEditing = (provider: ProviderStatus) => {
+    setEditing(provider.provider)
+    setDraft({ apiKey: '', endpoint: provider.endpoint, model: provider.model })
+  }
+
+  const handleSave = async (provider: LlmProvider) => {
+    await saveProviderCredentials(provider, draft)
+    setEditing(null)
+    await refresh()
+  }
+
+  const handleRemove = async (provider: LlmProvider) => {
+    await removeProviderCredentials(provider)
+    setResults((r) => ({ ...r, [provider]: undefined }))
+    await refresh()
+  }
+
+  const handleSelect = async (provider: LlmProvider) => {
+    await setGradingProvider(provider)
+    await refresh()
+  }
+
+  const handleTest = async (provider: LlmProvider) => {
+    setTesting(provider)
+    try {
+      const check = await testApiKey(provider)
+      setResults((r) => ({ ...r, [provider]: check }))
+    } catch (error) {
+      setResults((r) => ({ ...r, [provider]: getErrorMessage(error) }))
+    } finally {
+      setTesting(null)
+    }
+  }
+
+  return (
+    <div className="space-y-3">
+      {providers.map((p) => {
+        const result = results[p.provider]
+        return (
+          <div key={p.provider} className="p-4 border border-gray-200 dark:border-gray-700 rounded-lg">
+            <div className="flex items-center justify-between gap-4">
+              <label className="flex items-center gap-3">
+                <input
+                  type="radio"
+                  name="grading-provider"
+                  checked={p.selected}
+                  onChange={() => handleSelect(p.provider)}
+                />
+                <span>
+                  <span className="font-medium dark:text-white">{p.name}</span>
+                  <span className="block text-xs text-gray-500 dark:text-gray-400">
+                    {p.configured ? `${p.model} · ${p.endpoint}` : 'Not configured'}
+                  </span>
+                </span>
+              </label>
+              <div className="flex gap-2">
+                <Button
+                  variant="secondary"
+                  size="sm"
+                  onClick={() => handleTest(p.provider)}
+                  disabled={!p.configured || testing !== null}
+                >
+                  {testing === p.provider ? 'Testing…' : 'Test'}
+                </Button>
+                <Button variant="secondary" size="sm" onClick={() => startEditing(p)}>
+                  Edit
+                </Button>
+              </div>
+            </div>
+
+            {editing === p.provider && (
+              <div className="mt-4 space-y-2">
+                {p.requires_api_key && (
+                  <input
+                    type="password"
+                    value={draft.apiKey}
+                    onChange={(e) => setDraft({ ...draft, apiKey: e.target.value })}
+                    placeholder={p.configured ? 'Leave blank to keep the saved key' : 'API key'}
+                    className={inputClass}
+                  />
+                )}
+                <input
+                  type="text"
+                  value={draft.endpoint}
+                  onChange={(e) => setDraft({ ...draft, endpoint: e.target.value })}
+                  placeholder="Endpoint"
+                  className={inputClass}
+                />
+                <input
+                  type="text"
+                  value={draft.model}
+                  onChange={(e) => setDraft({ ...draft, model: e.target.value })}
+                  placeholder="Model"
+                  className={inputClass}
+                />
+                <div className="flex gap-2">
+                  <Button size="sm" onClick={() => handleSave(p.provider)}>
+                    Save
+                  </Button>
+                  <Button variant="secondary" size="sm" onClick={() => setEditing(null)}>
+                    Cancel
+                  </Button>
+                  {p.configured && (
+                    <Button variant="danger" size="sm" onClick={() => handleRemove(p.provider)}>
+                      Remove
+                    </Button>
+                  )}
+                </div>
+              </div>
+            )}
+
+            {typeof result === 'string' && (
+              <p className="mt-3 text-sm text-red-600 dark:text-red-400">{result}</p>
+            )}
+            {result && typeof result !== 'string' && (
+              <p
+                className={`mt-3 text-sm ${
+                  result.model_available
+                    ? 'text-green-600 dark:text-green-400'
+                    : 'text-yellow-600 dark:text-yellow-400'
+                }`}
+              >
+                {result.model_available
+                  ? `Connected in ${result.latency_ms} ms. ${result.model} is available.`
+                  : `Connected, but ${result.model} is not among the ${result.available_models.length} available models.`}
+              </p>
+            )}
+          </div>
+        )
+      })}
+    </div>
+  )
+}
@@ -0,0 +1,102 @@
+import { useEffect } from 'react'
+import { useReminderStore, NotificationPrefs } from '@/stores/reminderStore'
+
+const DEFAULT_QUIET_HOURS = { start: '22:00', end: '08:00' }
+
+export function ReminderSettings() {
+  const { schedule, error, fetchSchedule, updatePrefs } = useReminderStore()
+
+  useEffect(() => {
+    fetchSchedule()
+  }, [])
+
+  if (!schedule) {
+    return error ? <p className="text-sm text-red-600 dark:text-red-400">{error}</p> : null
+  }
+
+  const prefs = schedule.prefs
+  const update = (changes: Partial<NotificationPrefs>) => updatePrefs({ ...prefs, ...changes })
+  const inputClass = 'px-2 py-1 border border-gray-300 dark:border-gray-600 rounded dark:bg-gray-700 dark:text-white'
+
+  return (
+    <div className="space-y-3 text-sm">
+      <label className="flex items-center gap-2 dark:text-white">
+        <input type="checkbox" checked={prefs.enabled} onChange={(e) => update({ enabled: e.target.checked })} />
+        Send study reminders
+      </label>
+
+      <div className="flex items-center gap-2 dark:text-white">
+        <input
+          type="checkbox"
+          checked={prefs.review_reminders}
+          disabled={!prefs.enabled}
+          onChange={(e) => update({ review_reminders: e.target.checked })}
+        />
+        Reviews due at
+        <input
+          type="time"
+          className={inputClass}
+          value={prefs.review_time}
+          disabled={!prefs.enabled}
+          onChange={(e) => update({ review_time: e.target.value })}
+        />
+      </div>
+
+      <div className="flex items-center gap-2 dark:text-white">
+        <input
+          type="checkbox"
+          checked={prefs.streak_reminders}
+          disabled={!prefs.enabled}
+          onChange={(e) => update({ streak_reminders: e.target.checked })}
+        />
+        Streak about to break at
+        <input
+          type="time"
+          className={inputClass}
+          value={prefs.streak_time}
+          disabled={!prefs.enabled}
+          onChange={(e) => update({ streak_time: e.target.value })}
+        />
+      </div>
+
+      <div className="flex items-center gap-2 dark:text-white">
+        <input
+          type="checkbox"
+          checked={prefs.quiet_hours !== null}
+          disabled={!prefs.enabled}
+          onChange={(e) => update({ quiet_hours: e.target.checked ? DEFAULT_QUIET_HOURS : null })}
+        />
+        Quiet hours
+        {prefs.quiet_hours && (
+          <>
+            <input
+              type="time"
+              className={inputClass}
+              value={prefs.quiet_hours.start}
+              onChange={(e) => update({ quiet_hours: { ...prefs.quiet_hours!, start: e.target.value } })}
+            />
+            to
+            <input
+              type="time"
+              className={inputClass}
+              value={prefs.quiet_hours.end}
+              onChange={(e) => update({ quiet_hours: { ...prefs.quiet_hours!, end: e.target.value } })}
+            />
+          </>
+        )}
+      </div>
+
+      {schedule.upcoming.length > 0 && (
+        <ul className="text-gray-500 dark:text-gray-400">
+          {schedule.upcoming.map((reminder) => (
+            <li key={reminder.kind}>
+              Next: {reminder.title} — {new Date(reminder.at).toLocaleString()}
+            </li>
+          ))}
+        </ul>
+      )}
+
+      {error && <p className="text-red-600 dark:text-red-400">{error}</p>}
+    </div>
+  )
+}
@@ -0,0 +1,44 @@
+import { useEffect } from 'react'
+import { listen } from '@tauri-apps/api/event'
+import { useBadgeStore, BadgeTier } from '@/stores/badgeStore'
+import { useUserStore } from '@/stores/userStore'
+
+export const GAMIFICATION_EVENT = 'gamification://event'
+
+export type XpSource = 'Lecture' | 'Quiz' | 'Challenge' | 'Review' | 'Quest' | 'Focus' | 'Checkpoint'
+
+export type GamificationEvent =
+  | { type: 'xp_granted'; source: XpSource; amount: number; total_xp: number }
+  | { type: 'level_up'; previous_level: number; new_level: number }
+  | {
+      type: 'badge_unlocked'
+      badge_id: string
+      name: string
+      description: string
+      icon: string
+      tier: BadgeTier | null
+    }
+
+/**
+ * Subscribe to gamification events pushed by the backend after quiz, lecture
+ * and quest actions, so stores update without polling after each action.
+ */
+export function useGamificationEvents() {
+  useEffect(() => {
+    const unlisten = listen<GamificationEvent>(GAMIFICATION_EVENT, ({ payload }) => {
+      switch (payload.type) {
+        case 'xp_granted':
+        case 'level_up':
+          useUserStore.getState().fetchUser()
+          break
+        case 'badge_unlocked':
+          useBadgeStore.getState().pushUnlocked(payload)
+          break
+      }
+    })
+
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [])
+}
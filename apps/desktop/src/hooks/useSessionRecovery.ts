@@ -0,0 +1,44 @@
+import { useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+
+export const SESSION_RECOVERED_EVENT = 'session://recovered'
+
+export interface RecoveredSession {
+  session_id: string
+  user_id: string
+  started_at: string
+  ended_at: string
+  active_seconds: number
+  items_completed: number
+  total_xp_earned: number
+}
+
+/**
+ * Sessions the backend closed at startup because the app stopped while they
+ * were open. Picks up any recovered before this hook subscribed, then
+ * listens for more.
+ */
+export function useSessionRecovery() {
+  const [recovered, setRecovered] = useState<RecoveredSession[]>([])
+
+  useEffect(() => {
+    const add = (sessions: RecoveredSession[]) => {
+      setRecovered((current) => [
+        ...current,
+        ...sessions.filter((s) => !current.some((c) => c.session_id === s.session_id)),
+      ])
+    }
+
+    const unlisten = listen<RecoveredSession>(SESSION_RECOVERED_EVENT, ({ payload }) => add([payload]))
+    invoke<RecoveredSession[]>('take_recovered_sessions')
+      .then(add)
+      .catch((error) => console.error('Failed to fetch recovered sessions:', error))
+
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [])
+
+  return { recovered, dismiss: () => setRecovered([]) }
+}
@@ -1,6 +1,7 @@
 import { useEffect, useState } from 'react'
 import { useCurriculumStore, ValidationResponse } from '@/stores/curriculumStore'
 import { open } from '@tauri-apps/plugin-dialog'
+import { getErrorMessage } from '@/utils/errorHandler'
 
 export function CurriculumManager() {
   const {
@@ -43,7 +44,7 @@ export function CurriculumManager() {
         setValidation(result)
       }
     } catch (err) {
-      setImportError(String(err))
+      setImportError(getErrorMessage(err))
     }
   }
 
@@ -60,7 +61,7 @@ export function CurriculumManager() {
         setImportError(result.error ?? 'Import failed')
       }
     } catch (err) {
-      setImportError(String(err))
+      setImportError(getErrorMessage(err))
     }
   }
 
@@ -68,7 +69,7 @@ export function CurriculumManager() {
     try {
       await switchCurriculum(curriculumId)
     } catch (err) {
-      setImportError(String(err))
+      setImportError(getErrorMessage(err))
     }
   }
 
@@ -77,7 +78,7 @@ export function CurriculumManager() {
       await deleteCurriculum(curriculumId, deleteProgress)
       setDeleteConfirm(null)
     } catch (err) {
-      setImportError(String(err))
+      setImportError(getErrorMessage(err))
     }
   }
 
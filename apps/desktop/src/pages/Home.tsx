@@ -4,6 +4,7 @@ import { BookOpen, Code, FileText, ChevronRight } from 'lucide-react'
 import { useUserStore } from '@/stores/userStore'
 import { useContentStore } from '@/stores/contentStore'
 import { useProgressStore } from '@/stores/progressStore'
+import { FocusTimer } from '@/components/focus/FocusTimer'
 
 export function Home() {
   const { user, createUser } = useUserStore()
@@ -101,6 +102,8 @@ export function Home() {
         </p>
       </div>
 
+      <FocusTimer />
+
       {/* Continue Learning */}
       {next && (
         <div className="bg-white rounded-lg shadow p-6">
@@ -25,6 +25,7 @@ interface QuestionOption {
 }
 
 interface QuizResult {
+  attempt_id: string;
   score: number;
   total: number;
   score_percentage: number;
@@ -1,10 +1,39 @@
 import { useState } from 'react'
 import { save, open } from '@tauri-apps/plugin-dialog'
 import { useUserStore } from '@/stores/userStore'
-import { useSystemStore } from '@/stores/systemStore'
+import {
+  useSystemStore,
+  ConflictStrategy,
+  ImportMode,
+  ImportOptions,
+  ImportReport,
+} from '@/stores/systemStore'
 import { useThemeStore, Theme } from '@/stores/themeStore'
 import { Button } from '@/components/common/Button'
-import { Sun, Moon, Monitor, Download, Upload, Trash2 } from 'lucide-react'
+import { ProviderSettings } from '@/components/settings/ProviderSettings'
+import { JobStatusList } from '@/components/settings/JobStatusList'
+import { CapabilityStatusList } from '@/components/settings/CapabilityStatusList'
+import { ReminderSettings } from '@/components/settings/ReminderSettings'
+import { Sun, Moon, Monitor, Download, Upload, Trash2, FileArchive } from 'lucide-react'
+
+const strategyOptions: { value: ConflictStrategy | 'Default'; label: string }[] = [
+  { value: 'Default', label: 'Recommended for each kind of record' },
+  { value: 'KeepNewest', label: 'Keep the most recently updated copy' },
+  { value: 'KeepLocal', label: 'Keep what is on this device' },
+  { value: 'KeepIncoming', label: 'Use the backup' },
+  { value: 'MergeMax', label: 'Merge, keeping the highest XP and scores' },
+]
+
+const categoryLabels: Record<string, string> = {
+  User: 'Profile & XP',
+  Progress: 'Lesson progress',
+  Mastery: 'Skill mastery',
+  Badges: 'Badges',
+  Reviews: 'Review schedule',
+  QuizAttempts: 'Quiz attempts',
+  Notes: 'Notes',
+  GradeHistory: 'Graded artifacts',
+}
 
 const themeOptions: { value: Theme; label: string; icon: typeof Sun }[] = [
   { value: 'light', label: 'Light', icon: Sun },
@@ -14,20 +43,64 @@ const themeOptions: { value: Theme; label: string; icon: typeof Sun }[] = [
 
 export function Settings() {
   const { user } = useUserStore()
-  const { exportUserData, importUserData, resetAllProgress, loading } = useSystemStore()
+  const { exportUserData, exportDiagnostics, importUserData, isBackupEncrypted, resetAllProgress, loading } =
+    useSystemStore()
   const { theme, setTheme } = useThemeStore()
   const [showResetConfirm, setShowResetConfirm] = useState(false)
   const [message, setMessage] = useState<{ type: 'success' | 'error'; text: string } | null>(null)
+  const [passphrase, setPassphrase] = useState('')
+  const [importMode, setImportMode] = useState<ImportMode>('Merge')
+  const [strategy, setStrategy] = useState<ConflictStrategy | 'Default'>('Default')
+  const [pendingImport, setPendingImport] = useState<{ path: string; report: ImportReport } | null>(null)
+
+  const importOptions = (dryRun: boolean): ImportOptions => ({
+    mode: importMode,
+    strategies:
+      strategy === 'Default'
+        ? undefined
+        : {
+            user: strategy,
+            progress: strategy,
+            mastery: strategy,
+            badges: strategy,
+            reviews: strategy,
+            notes: strategy,
+          },
+    dry_run: dryRun,
+  })
+
+  const backupFilters = [
+    { name: 'GLP Backup', extensions: ['glpbackup'] },
+    { name: 'Legacy JSON Backup', extensions: ['json'] },
+  ]
+
+  const handleExportDiagnostics = async () => {
+    try {
+      const path = await save({
+        defaultPath: `glp-diagnostics-${new Date().toISOString().slice(0, 10)}.zip`,
+        filters: [{ name: 'Zip Archive', extensions: ['zip'] }],
+      })
+      if (path) {
+        await exportDiagnostics(path)
+        setMessage({ type: 'success', text: 'Diagnostics exported. Attach the zip to your bug report.' })
+      }
+    } catch (error) {
+      setMessage({ type: 'error', text: `Diagnostics export failed: ${error}` })
+    }
+  }
 
   const handleExport = async () => {
     try {
       const path = await save({
-        defaultPath: 'glp-backup.json',
-        filters: [{ name: 'JSON', extensions: ['json'] }],
+        defaultPath: 'progress.glpbackup',
+        filters: backupFilters.slice(0, 1),
       })
       if (path) {
-        await exportUserData(path)
-        setMessage({ type: 'success', text: 'Progress exported successfully!' })
+        await exportUserData(path, passphrase)
+        setMessage({
+          type: 'success',
+          text: passphrase ? 'Encrypted backup exported successfully!' : 'Progress exported successfully!',
+        })
       }
     } catch (error) {
       setMessage({ type: 'error', text: `Export failed: ${error}` })
@@ -36,18 +109,35 @@ export function Settings() {
 
   const handleImport = async () => {
     try {
-      const path = await open({
-        filters: [{ name: 'JSON', extensions: ['json'] }],
-      })
+      const path = await open({ filters: backupFilters })
       if (path && typeof path === 'string') {
-        await importUserData(path)
-        setMessage({ type: 'success', text: 'Progress imported successfully! Refresh to see changes.' })
+        if (!passphrase && (await isBackupEncrypted(path))) {
+          setMessage({ type: 'error', text: 'This backup is encrypted. Enter its passphrase and import again.' })
+          return
+        }
+        // Preview the import before anything is written
+        const report = await importUserData(path, passphrase, importOptions(true))
+        setPendingImport({ path, report })
       }
     } catch (error) {
       setMessage({ type: 'error', text: `Import failed: ${error}` })
     }
   }
 
+  const handleApplyImport = async () => {
+    if (!pendingImport) return
+    try {
+      const report = await importUserData(pendingImport.path, passphrase, importOptions(false))
+      const imported = report.categories
+        .filter((c) => c.category !== 'User')
+        .reduce((sum, c) => sum + c.added + c.updated, 0)
+      setPendingImport(null)
+      setMessage({ type: 'success', text: `Imported ${imported} records! Refresh to see changes.` })
+    } catch (error) {
+      setMessage({ type: 'error', text: `Import failed: ${error}` })
+    }
+  }
+
   const handleReset = async () => {
     try {
       await resetAllProgress()
@@ -130,6 +220,16 @@ export function Settings() {
           </div>
         </div>
 
+        {/* Grading Providers Section */}
+        <div className="p-6">
+          <h2 className="text-lg font-semibold mb-4 dark:text-white">Artifact Grading</h2>
+          <p className="text-sm text-gray-600 dark:text-gray-400 mb-4">
+            Choose the AI provider that grades your project artifacts. Ollama runs models locally
+            and needs no API key.
+          </p>
+          <ProviderSettings />
+        </div>
+
         {/* Data Management Section */}
         <div className="p-6">
           <h2 className="text-lg font-semibold mb-4 dark:text-white">Data Management</h2>
@@ -137,6 +237,46 @@ export function Settings() {
             All your progress is stored locally on your device. You can export your data for backup
             or import a previous backup.
           </p>
+          <div className="space-y-4 mb-4">
+            <div>
+              <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+                Backup passphrase (optional)
+              </label>
+              <input
+                type="password"
+                value={passphrase}
+                onChange={(e) => setPassphrase(e.target.value)}
+                placeholder="Encrypts exports and unlocks encrypted imports"
+                className="w-full px-4 py-2 bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 rounded-lg dark:text-white"
+              />
+            </div>
+            <label className="flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300">
+              <input
+                type="checkbox"
+                checked={importMode === 'Replace'}
+                onChange={(e) => setImportMode(e.target.checked ? 'Replace' : 'Merge')}
+              />
+              Replace existing progress when importing instead of merging
+            </label>
+            {importMode === 'Merge' && (
+              <div>
+                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+                  When a record exists on both sides
+                </label>
+                <select
+                  value={strategy}
+                  onChange={(e) => setStrategy(e.target.value as ConflictStrategy | 'Default')}
+                  className="w-full px-4 py-2 bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 rounded-lg dark:text-white"
+                >
+                  {strategyOptions.map((option) => (
+                    <option key={option.value} value={option.value}>
+                      {option.label}
+                    </option>
+                  ))}
+                </select>
+              </div>
+            )}
+          </div>
           <div className="flex flex-wrap gap-3">
             <Button
               variant="outline"
@@ -156,6 +296,68 @@ export function Settings() {
               Import Progress
             </Button>
           </div>
+          {pendingImport && (
+            <div className="mt-4 border border-gray-200 dark:border-gray-700 rounded-lg p-4">
+              <h3 className="font-medium mb-2 dark:text-white">Import preview</h3>
+              <table className="w-full text-sm text-gray-700 dark:text-gray-300 mb-4">
+                <thead>
+                  <tr className="text-left text-gray-500 dark:text-gray-400">
+                    <th className="font-normal">Records</th>
+                    <th className="font-normal">New</th>
+                    <th className="font-normal">Updated</th>
+                    <th className="font-normal">Kept local</th>
+                    <th className="font-normal">Removed</th>
+                  </tr>
+                </thead>
+                <tbody>
+                  {pendingImport.report.categories.map((c) => (
+                    <tr key={c.category}>
+                      <td>{categoryLabels[c.category] ?? c.category}</td>
+                      <td>{c.added}</td>
+                      <td>{c.updated}</td>
+                      <td>{c.kept_local}</td>
+                      <td>{c.removed}</td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+              <div className="flex gap-3">
+                <Button onClick={handleApplyImport} loading={loading}>
+                  Apply Import
+                </Button>
+                <Button variant="outline" onClick={() => setPendingImport(null)}>
+                  Cancel
+                </Button>
+              </div>
+            </div>
+          )}
+        </div>
+
+        {/* Reminders Section */}
+        <div className="p-6">
+          <h2 className="text-lg font-semibold mb-4 dark:text-white">Reminders</h2>
+          <p className="text-sm text-gray-600 dark:text-gray-400 mb-4">
+            System notifications while the app is open. Reminders due in quiet hours wait until they end.
+          </p>
+          <ReminderSettings />
+        </div>
+
+        {/* Connectivity Section */}
+        <div className="p-6">
+          <h2 className="text-lg font-semibold mb-4 dark:text-white">Connectivity</h2>
+          <p className="text-sm text-gray-600 dark:text-gray-400 mb-4">
+            Work that needs an unavailable service is queued and runs once it's reachable.
+          </p>
+          <CapabilityStatusList />
+        </div>
+
+        {/* Background Jobs Section */}
+        <div className="p-6">
+          <h2 className="text-lg font-semibold mb-4 dark:text-white">Background Jobs</h2>
+          <p className="text-sm text-gray-600 dark:text-gray-400 mb-4">
+            Maintenance that runs automatically while the app is open.
+          </p>
+          <JobStatusList />
         </div>
 
         {/* Danger Zone */}
@@ -206,6 +408,16 @@ export function Settings() {
               <span className="font-medium">Built with:</span> Tauri + React + Rust
             </p>
           </div>
+          <div className="mt-4">
+            <Button variant="secondary" onClick={handleExportDiagnostics}>
+              <FileArchive className="w-4 h-4 mr-2" />
+              Export Diagnostics
+            </Button>
+            <p className="text-xs text-gray-500 dark:text-gray-400 mt-2">
+              Bundles recent logs, system status and a database check into a zip for bug reports.
+              No API keys or notes are included.
+            </p>
+          </div>
         </div>
       </div>
     </div>
@@ -1,5 +1,20 @@
 import { create } from 'zustand'
 import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from '@/utils/errorHandler'
+
+export type BadgeTier = 'Bronze' | 'Silver' | 'Gold'
+
+export interface BadgeTierThreshold {
+  tier: BadgeTier
+  threshold: number
+}
+
+export interface NextTierProgress {
+  tier: BadgeTier
+  threshold: number
+  current_value: number
+  progress: number
+}
 
 export interface BadgeDefinition {
   id: string
@@ -8,6 +23,8 @@ export interface BadgeDefinition {
   icon: string
   threshold: number
   category: string
+  criteria?: string
+  tiers?: BadgeTierThreshold[]
 }
 
 export interface BadgeWithProgress {
@@ -16,6 +33,16 @@ export interface BadgeWithProgress {
   current_value: number
   is_earned: boolean
   earned_at: string | null
+  tier: BadgeTier | null
+  next_tier: NextTierProgress | null
+}
+
+export interface UnlockedBadge {
+  badge_id: string
+  name: string
+  description: string
+  icon: string
+  tier: BadgeTier | null
 }
 
 interface BadgeState {
@@ -25,6 +52,7 @@ interface BadgeState {
   error: string | null
   fetchAllBadges: () => Promise<void>
   checkAndUnlockBadges: () => Promise<BadgeDefinition[]>
+  pushUnlocked: (badge: UnlockedBadge) => void
   clearNewlyUnlocked: () => void
 }
 
@@ -40,7 +68,7 @@ export const useBadgeStore = create<BadgeState>((set, get) => ({
       const badges = await invoke<BadgeWithProgress[]>('get_all_badges')
       set({ badges, loading: false })
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
     }
   },
 
@@ -59,6 +87,21 @@ export const useBadgeStore = create<BadgeState>((set, get) => ({
     }
   },
 
+  pushUnlocked: (badge: UnlockedBadge) => {
+    const definition = get().badges.find((b) => b.definition.id === badge.badge_id)?.definition
+    const unlocked: BadgeDefinition = {
+      category: '',
+      threshold: 0,
+      ...definition,
+      id: badge.badge_id,
+      name: badge.tier ? `${badge.name} (${badge.tier})` : badge.name,
+      description: badge.description,
+      icon: badge.icon,
+    }
+    set({ newlyUnlocked: [...get().newlyUnlocked, unlocked] })
+    get().fetchAllBadges()
+  },
+
   clearNewlyUnlocked: () => {
     set({ newlyUnlocked: [] })
   },
@@ -0,0 +1,51 @@
+import { create } from 'zustand'
+import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from '@/utils/errorHandler'
+
+export interface CapabilityState {
+  available: boolean
+  detail: string | null
+  checked_at: string | null
+}
+
+export interface PendingWork {
+  id: string
+  user_id: string
+  kind: 'GradeArtifact'
+  capability: 'Docker' | 'Llm'
+  payload: string
+  created_at: string
+  attempts: number
+  last_attempt_at: string | null
+  last_error: string | null
+  completed_at: string | null
+}
+
+export interface CapabilityStatus {
+  docker: CapabilityState
+  llm: CapabilityState
+  pending_work: PendingWork[]
+}
+
+interface CapabilityStoreState {
+  status: CapabilityStatus | null
+  isChecking: boolean
+  error: string | null
+  fetchCapabilityStatus: (refresh?: boolean) => Promise<void>
+}
+
+export const useCapabilityStore = create<CapabilityStoreState>((set) => ({
+  status: null,
+  isChecking: false,
+  error: null,
+
+  fetchCapabilityStatus: async (refresh = false) => {
+    set({ isChecking: true })
+    try {
+      const status = await invoke<CapabilityStatus>('get_capability_status', { refresh })
+      set({ status, error: null, isChecking: false })
+    } catch (error) {
+      set({ error: getErrorMessage(error), isChecking: false })
+    }
+  },
+}))
@@ -0,0 +1,138 @@
+import { create } from 'zustand'
+import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from '@/utils/errorHandler'
+
+export interface CohortMember {
+  user_id: string
+  display_name: string
+  total_xp: number
+  current_level: number
+}
+
+export interface Cohort {
+  id: string
+  name: string
+  created_at: string
+  members: CohortMember[]
+}
+
+export interface CohortNodeStats {
+  node_id: string
+  learners: number
+  started: number
+  completed: number
+  avg_attempts: number
+  avg_time_mins: number
+  avg_mastery: number | null
+}
+
+export interface CohortSkillStats {
+  skill_id: string
+  learners: number
+  avg_score: number
+  min_score: number
+  max_score: number
+}
+
+export interface CohortReport {
+  generated_at: string
+  member_count: number
+  nodes: CohortNodeStats[]
+  skills: CohortSkillStats[]
+}
+
+interface CohortState {
+  cohorts: Cohort[]
+  report: CohortReport | null
+  loading: boolean
+  error: string | null
+  fetchCohorts: () => Promise<void>
+  createCohort: (name: string) => Promise<void>
+  deleteCohort: (cohortId: string) => Promise<void>
+  enroll: (cohortId: string, userId: string) => Promise<void>
+  unenroll: (cohortId: string, userId: string) => Promise<void>
+  importStudent: (cohortId: string, path: string) => Promise<void>
+  fetchReport: (cohortId: string) => Promise<void>
+  exportReport: (cohortId: string, path: string) => Promise<void>
+}
+
+export const useCohortStore = create<CohortState>((set, get) => ({
+  cohorts: [],
+  report: null,
+  loading: false,
+  error: null,
+
+  fetchCohorts: async () => {
+    set({ loading: true, error: null })
+    try {
+      const cohorts = await invoke<Cohort[]>('cohort_list')
+      set({ cohorts, loading: false })
+    } catch (error) {
+      set({ error: getErrorMessage(error), loading: false })
+    }
+  },
+
+  createCohort: async (name: string) => {
+    try {
+      await invoke('cohort_create', { name })
+      await get().fetchCohorts()
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+    }
+  },
+
+  deleteCohort: async (cohortId: string) => {
+    try {
+      await invoke('cohort_delete', { cohortId })
+      await get().fetchCohorts()
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+    }
+  },
+
+  enroll: async (cohortId: string, userId: string) => {
+    try {
+      await invoke('cohort_enroll', { cohortId, userId })
+      await get().fetchCohorts()
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+    }
+  },
+
+  unenroll: async (cohortId: string, userId: string) => {
+    try {
+      await invoke('cohort_unenroll', { cohortId, userId })
+      await get().fetchCohorts()
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+    }
+  },
+
+  importStudent: async (cohortId: string, path: string, passphrase?: string) => {
+    try {
+      await invoke<string>('cohort_import_student', { cohortId, path, passphrase: passphrase || null })
+      await get().fetchCohorts()
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+    }
+  },
+
+  fetchReport: async (cohortId: string) => {
+    set({ loading: true, error: null })
+    try {
+      const report = await invoke<CohortReport>('cohort_get_report', { cohortId })
+      set({ report, loading: false })
+    } catch (error) {
+      set({ error: getErrorMessage(error), loading: false })
+    }
+  },
+
+  exportReport: async (cohortId: string, path: string) => {
+    try {
+      await invoke('cohort_export_report', { cohortId, path })
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+      throw error
+    }
+  },
+}))
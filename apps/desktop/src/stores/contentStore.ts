@@ -1,5 +1,6 @@
 import { create } from 'zustand'
 import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from '@/utils/errorHandler'
 
 export interface ContentNode {
   id: string
@@ -54,7 +55,7 @@ export const useContentStore = create<ContentState>((set) => ({
       const tree = await invoke<ContentTree>('get_content_tree')
       set({ tree, loading: false })
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
     }
   },
 
@@ -64,7 +65,7 @@ export const useContentStore = create<ContentState>((set) => ({
       const node = await invoke<ContentNode>('get_node_by_id', { nodeId })
       set({ currentNode: node, loading: false })
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
     }
   },
 }))
@@ -1,5 +1,6 @@
 import { create } from 'zustand'
 import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from '@/utils/errorHandler'
 
 export interface ContentStats {
   total_weeks: number
@@ -68,7 +69,7 @@ export const useCurriculumStore = create<CurriculumState>((set, get) => ({
       const curricula = await invoke<CurriculumInfo[]>('list_curricula')
       set({ curricula, loading: false })
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
     }
   },
 
@@ -78,7 +79,7 @@ export const useCurriculumStore = create<CurriculumState>((set, get) => ({
       const activeCurriculum = await invoke<CurriculumInfo | null>('get_active_curriculum')
       set({ activeCurriculum, loading: false })
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
     }
   },
 
@@ -89,7 +90,7 @@ export const useCurriculumStore = create<CurriculumState>((set, get) => ({
       set({ loading: false })
       return result
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
       throw error
     }
   },
@@ -113,7 +114,7 @@ export const useCurriculumStore = create<CurriculumState>((set, get) => ({
       set({ loading: false })
       return result
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
       throw error
     }
   },
@@ -126,7 +127,7 @@ export const useCurriculumStore = create<CurriculumState>((set, get) => ({
       await get().fetchCurricula()
       set({ loading: false })
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
       throw error
     }
   },
@@ -145,7 +146,7 @@ export const useCurriculumStore = create<CurriculumState>((set, get) => ({
       
       set({ loading: false })
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
       throw error
     }
   },
@@ -0,0 +1,38 @@
+import { create } from 'zustand'
+import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from '@/utils/errorHandler'
+
+export type XpActivity = 'Lecture' | 'Quiz' | 'Challenge' | 'Checkpoint'
+
+export interface XpEvent {
+  id: string
+  name: string
+  description: string
+  multiplier: number
+  starts_at: string
+  ends_at: string
+  activities: XpActivity[]
+}
+
+interface EventState {
+  activeEvents: XpEvent[]
+  loading: boolean
+  error: string | null
+  fetchActiveEvents: () => Promise<void>
+}
+
+export const useEventStore = create<EventState>((set) => ({
+  activeEvents: [],
+  loading: false,
+  error: null,
+
+  fetchActiveEvents: async () => {
+    set({ loading: true, error: null })
+    try {
+      const activeEvents = await invoke<XpEvent[]>('get_active_xp_events')
+      set({ activeEvents, loading: false })
+    } catch (error) {
+      set({ error: getErrorMessage(error), loading: false })
+    }
+  },
+}))
@@ -0,0 +1,76 @@
+import { create } from 'zustand'
+import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from '@/utils/errorHandler'
+
+export type FocusBlockKind = 'Work' | 'ShortBreak' | 'LongBreak'
+export type FocusStatus = 'Running' | 'Paused' | 'Completed' | 'Cancelled'
+
+export interface FocusSettings {
+  work_minutes: number
+  short_break_minutes: number
+  long_break_minutes: number
+  blocks_before_long_break: number
+}
+
+export interface FocusBlock {
+  id: string
+  user_id: string
+  session_id: string | null
+  kind: FocusBlockKind
+  planned_seconds: number
+  started_at: string
+  paused_at: string | null
+  paused_seconds: number
+  ended_at: string | null
+  status: FocusStatus
+  xp_awarded: number
+}
+
+export interface FocusCompletion {
+  block: FocusBlock
+  xp_awarded: number
+  previous_total_xp: number
+}
+
+export interface FocusState {
+  settings: FocusSettings
+  block: FocusBlock | null
+  remaining_seconds: number
+  next_kind: FocusBlockKind
+  completed_work_blocks_today: number
+  completed: FocusCompletion | null
+}
+
+interface FocusStoreState {
+  focus: FocusState | null
+  error: string | null
+  fetchFocusState: () => Promise<void>
+  startBlock: (kind?: FocusBlockKind) => Promise<void>
+  pauseBlock: () => Promise<void>
+  resumeBlock: () => Promise<void>
+  cancelBlock: () => Promise<void>
+  updateSettings: (settings: FocusSettings) => Promise<void>
+}
+
+export const useFocusStore = create<FocusStoreState>((set) => {
+  const run = async (command: string, args?: Record<string, unknown>) => {
+    try {
+      const focus = await invoke<FocusState>(command, args)
+      set({ focus, error: null })
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+    }
+  }
+
+  return {
+    focus: null,
+    error: null,
+
+    fetchFocusState: () => run('get_focus_state'),
+    startBlock: (kind) => run('start_focus_block', { kind: kind ?? null }),
+    pauseBlock: () => run('pause_focus_block'),
+    resumeBlock: () => run('resume_focus_block'),
+    cancelBlock: () => run('cancel_focus_block'),
+    updateSettings: (settings) => run('update_focus_settings', { settings }),
+  }
+})
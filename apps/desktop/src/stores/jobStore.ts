@@ -0,0 +1,38 @@
+import { create } from 'zustand'
+import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from '@/utils/errorHandler'
+
+export interface JobStatus {
+  id: string
+  description: string
+  schedule: string
+  running: boolean
+  last_started_at: string | null
+  last_duration_ms: number | null
+  last_result: string | null
+  last_error: string | null
+  last_success_at: string | null
+  run_count: number
+  failure_count: number
+  next_run_at: string | null
+}
+
+interface JobState {
+  jobs: JobStatus[]
+  error: string | null
+  fetchJobStatus: () => Promise<void>
+}
+
+export const useJobStore = create<JobState>((set) => ({
+  jobs: [],
+  error: null,
+
+  fetchJobStatus: async () => {
+    try {
+      const jobs = await invoke<JobStatus[]>('get_job_status')
+      set({ jobs, error: null })
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+    }
+  },
+}))
@@ -0,0 +1,44 @@
+import { create } from 'zustand'
+import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from '@/utils/errorHandler'
+
+export type LeaderboardPeriod = 'Weekly' | 'AllTime'
+
+export interface LeaderboardEntry {
+  rank: number
+  user_id: string
+  display_name: string
+  xp: number
+  level: number
+  current_streak: number
+  is_current_user: boolean
+}
+
+export interface Leaderboard {
+  period: LeaderboardPeriod
+  generated_at: string
+  entries: LeaderboardEntry[]
+}
+
+interface LeaderboardState {
+  leaderboard: Leaderboard | null
+  loading: boolean
+  error: string | null
+  fetchLeaderboard: (period: LeaderboardPeriod) => Promise<void>
+}
+
+export const useLeaderboardStore = create<LeaderboardState>((set) => ({
+  leaderboard: null,
+  loading: false,
+  error: null,
+
+  fetchLeaderboard: async (period: LeaderboardPeriod) => {
+    set({ loading: true, error: null })
+    try {
+      const leaderboard = await invoke<Leaderboard>('get_leaderboard', { period })
+      set({ leaderboard, loading: false })
+    } catch (error) {
+      set({ error: getErrorMessage(error), loading: false })
+    }
+  },
+}))
@@ -0,0 +1,93 @@
+import { create } from 'zustand'
+import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from '@/utils/errorHandler'
+
+export interface Note {
+  id: string
+  user_id: string
+  node_id: string
+  body: string
+  created_at: string
+  updated_at: string
+}
+
+export type SearchSource = 'Note' | 'QuizAnswer' | 'GradeFeedback'
+
+export interface SearchHit {
+  source: SearchSource
+  record_id: string
+  node_id: string
+  snippet: string
+  occurred_at: string
+  rank: number
+}
+
+interface NoteState {
+  notes: Note[]
+  results: SearchHit[]
+  loading: boolean
+  error: string | null
+  fetchNotes: (nodeId: string) => Promise<void>
+  createNote: (nodeId: string, body: string) => Promise<void>
+  updateNote: (noteId: string, body: string) => Promise<void>
+  deleteNote: (noteId: string) => Promise<void>
+  searchHistory: (query: string) => Promise<void>
+}
+
+export const useNoteStore = create<NoteState>((set) => ({
+  notes: [],
+  results: [],
+  loading: false,
+  error: null,
+
+  fetchNotes: async (nodeId: string) => {
+    set({ loading: true, error: null })
+    try {
+      const notes = await invoke<Note[]>('get_notes_for_node', { nodeId })
+      set({ notes, loading: false })
+    } catch (error) {
+      set({ error: getErrorMessage(error), loading: false })
+    }
+  },
+
+  createNote: async (nodeId: string, body: string) => {
+    try {
+      const note = await invoke<Note>('create_note', { nodeId, body })
+      set((state) => ({ notes: [note, ...state.notes] }))
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+    }
+  },
+
+  updateNote: async (noteId: string, body: string) => {
+    try {
+      const note = await invoke<Note>('update_note', { noteId, body })
+      set((state) => ({ notes: state.notes.map((n) => (n.id === note.id ? note : n)) }))
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+    }
+  },
+
+  deleteNote: async (noteId: string) => {
+    try {
+      await invoke('delete_note', { noteId })
+      set((state) => ({ notes: state.notes.filter((n) => n.id !== noteId) }))
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+    }
+  },
+
+  searchHistory: async (query: string) => {
+    if (!query.trim()) {
+      set({ results: [] })
+      return
+    }
+    set({ loading: true, error: null })
+    try {
+      const results = await invoke<SearchHit[]>('search_my_history', { query })
+      set({ results, loading: false })
+    } catch (error) {
+      set({ error: getErrorMessage(error), loading: false })
+    }
+  },
+}))
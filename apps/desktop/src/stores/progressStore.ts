@@ -1,5 +1,6 @@
 import { create } from 'zustand'
 import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from '@/utils/errorHandler'
 
 export interface NodeProgress {
   id: number
@@ -36,7 +37,7 @@ export const useProgressStore = create<ProgressState>((set, get) => ({
       }
       set({ progress, loading: false })
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
     }
   },
 
@@ -50,7 +51,7 @@ export const useProgressStore = create<ProgressState>((set, get) => ({
         loading: false,
       })
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
     }
   },
 
@@ -67,7 +68,7 @@ export const useProgressStore = create<ProgressState>((set, get) => ({
         loading: false,
       })
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
     }
   },
 }))
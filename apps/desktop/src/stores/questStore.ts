@@ -0,0 +1,57 @@
+import { create } from 'zustand'
+import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from '@/utils/errorHandler'
+
+export interface Quest {
+  id: string
+  period: 'Daily' | 'Weekly'
+  kind: string
+  description: string
+  target: number
+  progress: number
+  xp_reward: number
+  ends_at: string
+  is_complete: boolean
+  is_claimed: boolean
+}
+
+export interface ClaimQuestResult {
+  xp_earned: number
+  new_total_xp: number
+  new_level: number
+}
+
+interface QuestState {
+  quests: Quest[]
+  loading: boolean
+  error: string | null
+  fetchActiveQuests: () => Promise<void>
+  claimQuestReward: (questId: string) => Promise<ClaimQuestResult>
+}
+
+export const useQuestStore = create<QuestState>((set, get) => ({
+  quests: [],
+  loading: false,
+  error: null,
+
+  fetchActiveQuests: async () => {
+    set({ loading: true, error: null })
+    try {
+      const quests = await invoke<Quest[]>('get_active_quests')
+      set({ quests, loading: false })
+    } catch (error) {
+      set({ error: getErrorMessage(error), loading: false })
+    }
+  },
+
+  claimQuestReward: async (questId: string) => {
+    try {
+      const result = await invoke<ClaimQuestResult>('claim_quest_reward', { questId })
+      await get().fetchActiveQuests()
+      return result
+    } catch (error) {
+      console.error('Failed to claim quest reward:', error)
+      throw error
+    }
+  },
+}))
@@ -0,0 +1,64 @@
+import { create } from 'zustand'
+import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from '@/utils/errorHandler'
+
+export type ReminderKind = 'ReviewsDue' | 'StreakAtRisk'
+
+export interface QuietHours {
+  /** Local time as HH:MM */
+  start: string
+  end: string
+}
+
+export interface NotificationPrefs {
+  enabled: boolean
+  review_reminders: boolean
+  review_time: string
+  streak_reminders: boolean
+  streak_time: string
+  quiet_hours: QuietHours | null
+}
+
+export interface ScheduledReminder {
+  kind: ReminderKind
+  for_date: string
+  /** Local date and time, without an offset */
+  at: string
+  title: string
+  body: string
+}
+
+export interface NotificationSchedule {
+  prefs: NotificationPrefs
+  upcoming: ScheduledReminder[]
+}
+
+interface ReminderState {
+  schedule: NotificationSchedule | null
+  error: string | null
+  fetchSchedule: () => Promise<void>
+  updatePrefs: (prefs: NotificationPrefs) => Promise<void>
+}
+
+export const useReminderStore = create<ReminderState>((set) => ({
+  schedule: null,
+  error: null,
+
+  fetchSchedule: async () => {
+    try {
+      const schedule = await invoke<NotificationSchedule>('get_notification_schedule')
+      set({ schedule, error: null })
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+    }
+  },
+
+  updatePrefs: async (prefs) => {
+    try {
+      const schedule = await invoke<NotificationSchedule>('update_notification_prefs', { prefs })
+      set({ schedule, error: null })
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+    }
+  },
+}))
@@ -1,5 +1,6 @@
 import { create } from 'zustand'
 import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from '@/utils/errorHandler'
 
 export interface ReviewItem {
   quiz_id: string
@@ -8,23 +9,41 @@ export interface ReviewItem {
   interval_days: number
   repetitions: number
   last_reviewed_at: string | null
+  lapses: number
+  is_suspended: boolean
+}
+
+export interface LeechItem extends ReviewItem {
+  lecture_node_id: string | null
+}
+
+export interface MasteryHistoryPoint {
+  previous_score: number
+  score: number
+  reason: 'Quiz' | 'Challenge' | 'Decay'
+  recorded_at: string
 }
 
 interface ReviewState {
   dueReviews: ReviewItem[]
   dueCount: number
+  leechItems: LeechItem[]
   loading: boolean
   error: string | null
   fetchDueReviews: () => Promise<void>
   fetchDueCount: () => Promise<void>
   submitReview: (quizId: string, scorePercentage: number) => Promise<ReviewItem>
   createReviewItem: (quizId: string) => Promise<ReviewItem>
+  fetchLeechItems: () => Promise<void>
+  unsuspendReview: (quizId: string) => Promise<ReviewItem>
   applyDecayOnStartup: () => Promise<number>
+  fetchMasteryHistory: (skillId: string, days: number) => Promise<MasteryHistoryPoint[]>
 }
 
 export const useReviewStore = create<ReviewState>((set, get) => ({
   dueReviews: [],
   dueCount: 0,
+  leechItems: [],
   loading: false,
   error: null,
 
@@ -34,7 +53,7 @@ export const useReviewStore = create<ReviewState>((set, get) => ({
       const dueReviews = await invoke<ReviewItem[]>('get_due_reviews')
       set({ dueReviews, dueCount: dueReviews.length, loading: false })
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
     }
   },
 
@@ -58,7 +77,7 @@ export const useReviewStore = create<ReviewState>((set, get) => ({
       await get().fetchDueReviews()
       return updated
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
       throw error
     }
   },
@@ -73,6 +92,27 @@ export const useReviewStore = create<ReviewState>((set, get) => ({
     }
   },
 
+  fetchLeechItems: async () => {
+    try {
+      const leechItems = await invoke<LeechItem[]>('get_leech_items')
+      set({ leechItems })
+    } catch (error) {
+      console.error('Failed to fetch leech items:', error)
+    }
+  },
+
+  unsuspendReview: async (quizId: string) => {
+    try {
+      const item = await invoke<ReviewItem>('unsuspend_review', { quizId })
+      await get().fetchLeechItems()
+      await get().fetchDueReviews()
+      return item
+    } catch (error) {
+      console.error('Failed to unsuspend review:', error)
+      throw error
+    }
+  },
+
   applyDecayOnStartup: async () => {
     try {
       const decayedCount = await invoke<number>('apply_mastery_decay_on_startup')
@@ -82,4 +122,13 @@ export const useReviewStore = create<ReviewState>((set, get) => ({
       return 0
     }
   },
+
+  fetchMasteryHistory: async (skillId: string, days: number) => {
+    try {
+      return await invoke<MasteryHistoryPoint[]>('get_mastery_history', { skillId, days })
+    } catch (error) {
+      console.error('Failed to fetch mastery history:', error)
+      return []
+    }
+  },
 }))
@@ -0,0 +1,88 @@
+import { create } from 'zustand'
+import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from '@/utils/errorHandler'
+
+export const SETUP_PROGRESS_EVENT = 'setup://progress'
+
+export type SetupStep =
+  | 'CheckDocker'
+  | 'PrepareSandbox'
+  | 'ValidateApiKey'
+  | 'CreateUser'
+  | 'ImportCurriculum'
+
+export type StepStatus = 'Pending' | 'Done' | 'Skipped' | 'Failed'
+
+export type SetupAction = 'Run' | 'Skip'
+
+export interface StepState {
+  step: SetupStep
+  status: StepStatus
+  message: string | null
+  attempts: number
+  updated_at: string | null
+}
+
+export interface SetupState {
+  steps: StepState[]
+  started_at: string
+  completed_at: string | null
+}
+
+export interface SetupProgress {
+  step: SetupStep
+  line: string
+}
+
+const OPTIONAL_STEPS: SetupStep[] = ['CheckDocker', 'PrepareSandbox', 'ValidateApiKey']
+
+/** The step to run next: the first one not yet done or skipped */
+export function currentStep(setup: SetupState | null): SetupStep | null {
+  const step = setup?.steps.find((s) => s.status === 'Pending' || s.status === 'Failed')
+  return step?.step ?? null
+}
+
+export function isOptional(step: SetupStep): boolean {
+  return OPTIONAL_STEPS.includes(step)
+}
+
+interface SetupStoreState {
+  setup: SetupState | null
+  running: boolean
+  progress: string[]
+  error: string | null
+  fetchSetupState: () => Promise<void>
+  advance: (action: SetupAction) => Promise<void>
+  appendProgress: (line: string) => void
+}
+
+export const useSetupStore = create<SetupStoreState>((set) => ({
+  setup: null,
+  running: false,
+  progress: [],
+  error: null,
+
+  fetchSetupState: async () => {
+    try {
+      const setup = await invoke<SetupState>('get_setup_state')
+      set({ setup })
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+    }
+  },
+
+  advance: async (action: SetupAction) => {
+    set({ running: true, progress: [], error: null })
+    try {
+      const setup = await invoke<SetupState>('advance_setup_step', { action })
+      set({ setup, running: false })
+    } catch (error) {
+      set({ error: getErrorMessage(error), running: false })
+    }
+  },
+
+  appendProgress: (line: string) => {
+    // Keep the tail of long build logs
+    set((state) => ({ progress: [...state.progress.slice(-199), line] }))
+  },
+}))
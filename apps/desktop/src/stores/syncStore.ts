@@ -0,0 +1,65 @@
+import { create } from 'zustand'
+import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from '@/utils/errorHandler'
+
+export interface SyncStatus {
+  folder: string | null
+  device_id: string
+  last_synced_at: string | null
+}
+
+export interface SyncReport {
+  device_id: string
+  ops_sent: number
+  ops_received: number
+  ops_applied: number
+  conflicts: number
+  devices: number
+}
+
+interface SyncState {
+  status: SyncStatus | null
+  lastReport: SyncReport | null
+  syncing: boolean
+  error: string | null
+  fetchStatus: () => Promise<void>
+  configureFolder: (path: string | null) => Promise<void>
+  syncNow: () => Promise<void>
+}
+
+export const useSyncStore = create<SyncState>((set, get) => ({
+  status: null,
+  lastReport: null,
+  syncing: false,
+  error: null,
+
+  fetchStatus: async () => {
+    try {
+      const status = await invoke<SyncStatus>('get_sync_status')
+      set({ status })
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+    }
+  },
+
+  configureFolder: async (path: string | null) => {
+    set({ error: null })
+    try {
+      await invoke('configure_sync_folder', { path })
+      await get().fetchStatus()
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+    }
+  },
+
+  syncNow: async () => {
+    set({ syncing: true, error: null })
+    try {
+      const lastReport = await invoke<SyncReport>('sync_now')
+      set({ lastReport, syncing: false })
+      await get().fetchStatus()
+    } catch (error) {
+      set({ error: getErrorMessage(error), syncing: false })
+    }
+  },
+}))
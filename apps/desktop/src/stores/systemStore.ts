@@ -1,5 +1,6 @@
 import { create } from 'zustand'
 import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from '@/utils/errorHandler'
 
 export interface SystemStatus {
   docker_installed: boolean
@@ -14,6 +15,76 @@ export interface DockerStatus {
   version: string | null
 }
 
+export interface UndoableOperation {
+  id: string
+  operation: 'ResetProgress' | 'DeleteCurriculum'
+  subject_id: string | null
+  row_count: number
+  created_at: string
+  expires_at: string
+}
+
+export type ImportMode = 'Merge' | 'Replace'
+
+export type ConflictStrategy = 'KeepNewest' | 'KeepLocal' | 'KeepIncoming' | 'MergeMax'
+
+export interface ConflictStrategies {
+  user: ConflictStrategy
+  progress: ConflictStrategy
+  mastery: ConflictStrategy
+  badges: ConflictStrategy
+  reviews: ConflictStrategy
+  notes: ConflictStrategy
+}
+
+export interface ImportOptions {
+  mode: ImportMode
+  strategies?: Partial<ConflictStrategies>
+  dry_run: boolean
+}
+
+export interface CategoryReport {
+  category: 'User' | 'Progress' | 'Mastery' | 'Badges' | 'Reviews' | 'QuizAttempts' | 'Notes' | 'GradeHistory'
+  strategy: ConflictStrategy | null
+  added: number
+  unchanged: number
+  updated: number
+  kept_local: number
+  removed: number
+}
+
+export interface ImportReport {
+  user_id: string | null
+  dry_run: boolean
+  categories: CategoryReport[]
+}
+
+export type LlmProvider = 'openai' | 'anthropic' | 'ollama'
+
+export interface ProviderStatus {
+  provider: LlmProvider
+  name: string
+  configured: boolean
+  requires_api_key: boolean
+  endpoint: string
+  model: string
+  selected: boolean
+}
+
+export interface ProviderCredentialsInput {
+  apiKey?: string
+  endpoint?: string
+  model?: string
+}
+
+export interface ProviderCheck {
+  provider: LlmProvider
+  model: string
+  model_available: boolean
+  available_models: string[]
+  latency_ms: number
+}
+
 interface SystemState {
   status: SystemStatus | null
   dockerStatus: DockerStatus | null
@@ -24,9 +95,20 @@ interface SystemState {
   checkDockerStatus: () => Promise<DockerStatus>
   saveApiKey: (apiKey: string) => Promise<void>
   getApiKeyStatus: () => Promise<boolean>
-  exportUserData: (path: string) => Promise<void>
-  importUserData: (path: string) => Promise<void>
+  getProviderStatus: () => Promise<ProviderStatus[]>
+  saveProviderCredentials: (provider: LlmProvider, credentials: ProviderCredentialsInput) => Promise<void>
+  removeProviderCredentials: (provider: LlmProvider) => Promise<void>
+  setGradingProvider: (provider: LlmProvider) => Promise<void>
+  testApiKey: (provider: LlmProvider) => Promise<ProviderCheck>
+  exportUserData: (path: string, passphrase?: string) => Promise<void>
+  exportDiagnostics: (path: string) => Promise<void>
+  importUserData: (path: string, passphrase?: string, options?: ImportOptions) => Promise<ImportReport>
+  isBackupEncrypted: (path: string) => Promise<boolean>
   resetAllProgress: () => Promise<void>
+  getUndoableOperation: () => Promise<UndoableOperation | null>
+  undoLastDestructiveOperation: () => Promise<UndoableOperation>
+  getUndoWindowMinutes: () => Promise<number>
+  setUndoWindowMinutes: (minutes: number) => Promise<void>
   isFirstLaunch: () => Promise<boolean>
   completeOnboarding: () => Promise<void>
   isOnboardingComplete: () => Promise<boolean>
@@ -44,7 +126,7 @@ export const useSystemStore = create<SystemState>((set) => ({
       const status = await invoke<SystemStatus>('check_system_status')
       set({ status, loading: false })
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
     }
   },
 
@@ -68,7 +150,7 @@ export const useSystemStore = create<SystemState>((set) => ({
       const status = await invoke<SystemStatus>('check_system_status')
       set({ status, loading: false })
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
       throw error
     }
   },
@@ -81,39 +163,129 @@ export const useSystemStore = create<SystemState>((set) => ({
     }
   },
 
-  exportUserData: async (path: string) => {
+  getProviderStatus: async () => {
+    try {
+      return await invoke<ProviderStatus[]>('get_provider_status')
+    } catch {
+      return []
+    }
+  },
+
+  saveProviderCredentials: async (provider: LlmProvider, credentials: ProviderCredentialsInput) => {
+    set({ error: null })
+    try {
+      await invoke('save_provider_credentials', {
+        provider,
+        apiKey: credentials.apiKey || null,
+        endpoint: credentials.endpoint || null,
+        model: credentials.model || null,
+      })
+      const status = await invoke<SystemStatus>('check_system_status')
+      set({ status })
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+      throw error
+    }
+  },
+
+  removeProviderCredentials: async (provider: LlmProvider) => {
+    try {
+      await invoke('remove_provider_credentials', { provider })
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+      throw error
+    }
+  },
+
+  setGradingProvider: async (provider: LlmProvider) => {
+    try {
+      await invoke('set_grading_provider', { provider })
+      const status = await invoke<SystemStatus>('check_system_status')
+      set({ status })
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+      throw error
+    }
+  },
+
+  testApiKey: async (provider: LlmProvider) => {
+    return await invoke<ProviderCheck>('test_api_key', { provider })
+  },
+
+  exportUserData: async (path: string, passphrase?: string) => {
     set({ loading: true, error: null })
     try {
-      await invoke('export_user_data', { path })
+      await invoke('export_user_data', { path, passphrase: passphrase || null })
       set({ loading: false })
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
       throw error
     }
   },
 
-  importUserData: async (path: string) => {
+  exportDiagnostics: async (path: string) => {
+    await invoke('export_diagnostics', { path })
+  },
+
+  importUserData: async (path: string, passphrase?: string, options?: ImportOptions) => {
     set({ loading: true, error: null })
     try {
-      await invoke('import_user_data', { path })
+      const report = await invoke<ImportReport>('import_user_data', {
+        path,
+        passphrase: passphrase || null,
+        options: options ?? null,
+      })
       set({ loading: false })
+      return report
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
       throw error
     }
   },
 
+  isBackupEncrypted: async (path: string) => {
+    return await invoke<boolean>('is_backup_encrypted', { path })
+  },
+
   resetAllProgress: async () => {
     set({ loading: true, error: null })
     try {
       await invoke('reset_all_progress')
       set({ loading: false })
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
       throw error
     }
   },
 
+  getUndoableOperation: async () => {
+    try {
+      return await invoke<UndoableOperation | null>('get_undoable_operation')
+    } catch {
+      return null
+    }
+  },
+
+  undoLastDestructiveOperation: async () => {
+    set({ loading: true, error: null })
+    try {
+      const undone = await invoke<UndoableOperation>('undo_last_destructive_operation')
+      set({ loading: false })
+      return undone
+    } catch (error) {
+      set({ error: getErrorMessage(error), loading: false })
+      throw error
+    }
+  },
+
+  getUndoWindowMinutes: async () => {
+    return await invoke<number>('get_undo_window_minutes')
+  },
+
+  setUndoWindowMinutes: async (minutes: number) => {
+    await invoke('set_undo_window_minutes', { minutes })
+  },
+
   isFirstLaunch: async () => {
     try {
       return await invoke<boolean>('is_first_launch')
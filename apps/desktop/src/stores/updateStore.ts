@@ -1,5 +1,6 @@
 import { create } from 'zustand'
 import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from '@/utils/errorHandler'
 
 interface UpdateInfo {
   version: string
@@ -39,7 +40,7 @@ export const useUpdateStore = create<UpdateState>((set, get) => ({
         lastChecked: new Date(),
       })
     } catch (error) {
-      set({ error: String(error) })
+      set({ error: getErrorMessage(error) })
     }
   },
 
@@ -53,7 +54,7 @@ export const useUpdateStore = create<UpdateState>((set, get) => ({
       // If we get here, the app will restart, so this won't execute
       set({ downloading: false, progress: 100 })
     } catch (error) {
-      set({ downloading: false, error: String(error) })
+      set({ downloading: false, error: getErrorMessage(error) })
     }
   },
 
@@ -1,26 +1,27 @@
 import { create } from 'zustand'
 import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from '@/utils/errorHandler'
+import type { UserData as User, ProfileSummary as Profile } from '@/bindings'
 
-export interface User {
-  id: string
-  total_xp: number
-  current_level: number
-  current_streak: number
-  xp_for_next_level: number
-  xp_progress_percentage: number
-}
+export type { User, Profile }
 
 interface UserState {
   user: User | null
+  profiles: Profile[]
   loading: boolean
   error: string | null
   fetchUser: () => Promise<void>
   createUser: () => Promise<void>
   updateXp: (xpDelta: number) => Promise<void>
+  fetchProfiles: () => Promise<void>
+  createProfile: (displayName: string) => Promise<void>
+  switchProfile: (userId: string) => Promise<void>
+  renameProfile: (userId: string, displayName: string) => Promise<void>
 }
 
 export const useUserStore = create<UserState>((set, get) => ({
   user: null,
+  profiles: [],
   loading: false,
   error: null,
 
@@ -30,7 +31,7 @@ export const useUserStore = create<UserState>((set, get) => ({
       const user = await invoke<User | null>('get_user_data')
       set({ user, loading: false })
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
     }
   },
 
@@ -40,7 +41,7 @@ export const useUserStore = create<UserState>((set, get) => ({
       const user = await invoke<User>('create_user')
       set({ user, loading: false })
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
     }
   },
 
@@ -53,7 +54,50 @@ export const useUserStore = create<UserState>((set, get) => ({
       const updatedUser = await invoke<User>('update_user_xp', { xpDelta })
       set({ user: updatedUser, loading: false })
     } catch (error) {
-      set({ error: String(error), loading: false })
+      set({ error: getErrorMessage(error), loading: false })
+    }
+  },
+
+  fetchProfiles: async () => {
+    try {
+      const profiles = await invoke<Profile[]>('list_profiles')
+      set({ profiles })
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
+    }
+  },
+
+  createProfile: async (displayName: string) => {
+    set({ loading: true, error: null })
+    try {
+      const user = await invoke<User>('create_profile', { displayName })
+      set({ user, loading: false })
+      await get().fetchProfiles()
+    } catch (error) {
+      set({ error: getErrorMessage(error), loading: false })
+    }
+  },
+
+  switchProfile: async (userId: string) => {
+    set({ loading: true, error: null })
+    try {
+      const user = await invoke<User>('switch_profile', { userId })
+      set({ user, loading: false })
+      await get().fetchProfiles()
+    } catch (error) {
+      set({ error: getErrorMessage(error), loading: false })
+    }
+  },
+
+  renameProfile: async (userId: string, displayName: string) => {
+    try {
+      await invoke('rename_profile', { userId, displayName })
+      await get().fetchProfiles()
+      if (get().user?.id === userId) {
+        await get().fetchUser()
+      }
+    } catch (error) {
+      set({ error: getErrorMessage(error) })
     }
   },
 }))
@@ -1,22 +1,11 @@
+import type { CommandError as BackendCommandError, ErrorCode as BackendErrorCode } from '@/bindings'
+
 /**
- * Error codes for categorizing application errors
+ * Error codes for categorizing application errors: the codes backend
+ * commands report (generated from `ErrorCode` in src-tauri/src/error.rs),
+ * plus those only the frontend detects.
  */
-export type ErrorCode =
-  | 'DOCKER_NOT_RUNNING'
-  | 'DOCKER_NOT_INSTALLED'
-  | 'LLM_API_TIMEOUT'
-  | 'LLM_RATE_LIMITED'
-  | 'LLM_API_KEY_INVALID'
-  | 'LLM_API_KEY_MISSING'
-  | 'DATABASE_LOCKED'
-  | 'DATABASE_CORRUPTED'
-  | 'CODE_TIMEOUT'
-  | 'CODE_MEMORY_EXCEEDED'
-  | 'CONTENT_NOT_FOUND'
-  | 'CURRICULUM_NOT_LOADED'
-  | 'NETWORK_ERROR'
-  | 'VALIDATION_ERROR'
-  | 'UNKNOWN'
+export type ErrorCode = BackendErrorCode | 'CODE_MEMORY_EXCEEDED' | 'NETWORK_ERROR'
 
 /**
  * Structured application error with user-friendly messaging
@@ -37,8 +26,4 @@ export interface AppError {
 /**
  * Error response from backend commands
  */
-export interface CommandError {
-  code: string
-  message: string
-  details?: string
-}
+export type CommandError = BackendCommandError
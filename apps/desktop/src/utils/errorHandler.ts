@@ -1,4 +1,4 @@
-import { AppError, ErrorCode } from '../types/errors'
+import { AppError, CommandError, ErrorCode } from '../types/errors'
 
 /**
  * Error pattern matching rules
@@ -55,14 +55,14 @@ const ERROR_PATTERNS: Array<{
   {
     pattern: /invalid.*api.*key|api.*key.*invalid|unauthorized/i,
     code: 'LLM_API_KEY_INVALID',
-    userMessage: 'Your OpenAI API key is invalid.',
+    userMessage: 'Your grading provider API key is invalid.',
     recoveryAction: 'Check your API key in Settings.',
     retryable: false,
   },
   {
     pattern: /api.*key.*missing|no.*api.*key/i,
     code: 'LLM_API_KEY_MISSING',
-    userMessage: 'OpenAI API key is not configured.',
+    userMessage: 'No API key is configured for the grading provider.',
     recoveryAction: 'Add your API key in Settings.',
     retryable: false,
   },
@@ -94,6 +94,13 @@ const ERROR_PATTERNS: Array<{
     recoveryAction: 'Import or select a curriculum first.',
     retryable: false,
   },
+  {
+    pattern: /no user logged in/i,
+    code: 'NOT_LOGGED_IN',
+    userMessage: 'No profile is selected.',
+    recoveryAction: 'Choose or create a profile first.',
+    retryable: false,
+  },
   {
     pattern: /network|connection.*refused|fetch.*failed/i,
     code: 'NETWORK_ERROR',
@@ -111,14 +118,29 @@ const ERROR_PATTERNS: Array<{
 ]
 
 /**
- * Parse a raw error message into a structured AppError
+ * Whether an error is a structured error returned by a backend command
+ */
+export function isCommandError(error: unknown): error is CommandError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    'code' in error &&
+    typeof error.code === 'string' &&
+    'message' in error &&
+    typeof error.message === 'string'
+  )
+}
+
+/**
+ * Parse a raw error into a structured AppError. Command errors carry their
+ * code; anything else is matched against known message patterns.
  */
 export function parseError(error: unknown): AppError {
   const message = getErrorMessage(error)
+  const code = isCommandError(error) ? error.code : undefined
 
-  // Try to match against known patterns
   for (const rule of ERROR_PATTERNS) {
-    if (rule.pattern.test(message)) {
+    if (code ? rule.code === code : rule.pattern.test(message)) {
       return {
         code: rule.code,
         message,
@@ -129,6 +151,16 @@ export function parseError(error: unknown): AppError {
     }
   }
 
+  // A command error without a friendlier message is already written for the user
+  if (code && code !== 'UNKNOWN') {
+    return {
+      code,
+      message,
+      userMessage: message,
+      retryable: false,
+    }
+  }
+
   // Unknown error
   return {
     code: 'UNKNOWN',
@@ -142,7 +174,7 @@ export function parseError(error: unknown): AppError {
 /**
  * Extract error message from various error types
  */
-function getErrorMessage(error: unknown): string {
+export function getErrorMessage(error: unknown): string {
   if (typeof error === 'string') {
     return error
   }
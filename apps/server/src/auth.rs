@@ -0,0 +1,36 @@
+use axum::extract::FromRequestParts;
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use chrono::Utc;
+use glp_core::db::repos::ApiTokenRepository;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::state::ServerState;
+
+/// The learner a request is made for, from its `Authorization: Bearer`
+/// token. Handlers that take a `Learner` reject requests without a valid one.
+pub struct Learner {
+    pub user_id: String,
+}
+
+impl FromRequestParts<Arc<ServerState>> for Learner {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<ServerState>) -> Result<Self, Self::Rejection> {
+        let secret = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(|secret| secret.trim().to_string())
+            .filter(|secret| !secret.is_empty())
+            .ok_or_else(ApiError::unauthorized)?;
+
+        let token = state
+            .run_db(move |conn| ApiTokenRepository::authenticate(conn, &secret, Utc::now()))
+            .await?
+            .ok_or_else(ApiError::unauthorized)?;
+        Ok(Learner { user_id: token.user_id })
+    }
+}
@@ -0,0 +1,80 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use content::ContentError;
+use glp_core::db::error::DbError;
+use glp_core::services::ServiceError;
+use serde::Serialize;
+
+/// An error response: the status, and a message for the client to show
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub message: String,
+}
+
+pub type ApiResult<T> = Result<T, ApiError>;
+
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    error: &'a str,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self { status, message: message.into() }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, message)
+    }
+
+    pub fn unauthorized() -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "A valid bearer token is required")
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        if self.status.is_server_error() {
+            tracing::error!(status = %self.status, error = %self.message, "Request failed");
+        }
+        (self.status, Json(ErrorBody { error: &self.message })).into_response()
+    }
+}
+
+impl From<DbError> for ApiError {
+    fn from(e: DbError) -> Self {
+        match e {
+            DbError::NotFound(message) => Self::not_found(message),
+            DbError::Sqlite(rusqlite::Error::QueryReturnedNoRows) => Self::not_found("Not found"),
+            DbError::InvalidData(message) => Self::new(StatusCode::UNPROCESSABLE_ENTITY, message),
+            e => Self::internal(e.to_string()),
+        }
+    }
+}
+
+impl From<ServiceError> for ApiError {
+    fn from(e: ServiceError) -> Self {
+        match e {
+            ServiceError::Db(e) => e.into(),
+            ServiceError::InvalidState(message) => Self::new(StatusCode::CONFLICT, message),
+        }
+    }
+}
+
+impl From<ContentError> for ApiError {
+    fn from(e: ContentError) -> Self {
+        match e {
+            ContentError::NotFound(message) => Self::not_found(message),
+            e @ (ContentError::LicenseRequired | ContentError::LicenseExpired(_) | ContentError::KeyRequired(_)) => {
+                Self::new(StatusCode::FORBIDDEN, e.to_string())
+            }
+            e => Self::internal(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! glp-server
+//!
+//! Runs one shared instance of the platform for a classroom: learners'
+//! progress, quizzes and reviews live in a single database, and lightweight
+//! clients reach them over a REST API authenticated with per-learner tokens.
+
+mod auth;
+mod error;
+mod quiz;
+mod routes;
+mod state;
+
+use chrono::Utc;
+use clap::{Parser, Subcommand};
+use glp_core::db::repos::{ApiTokenRepository, UserRepository};
+use glp_core::models::{ApiToken, User};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing_subscriber::EnvFilter;
+
+use crate::error::ApiResult;
+use crate::state::ServerState;
+
+#[derive(Parser)]
+#[command(name = "glp-server")]
+#[command(about = "Serve the learning platform to a classroom over HTTP", long_about = None)]
+struct Cli {
+    /// Data directory holding app.db and imported curricula (default: the desktop app's)
+    #[arg(short, long, global = true)]
+    data_dir: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Start the HTTP API
+    Serve {
+        /// Curriculum id to serve (default: the active curriculum)
+        #[arg(short, long)]
+        curriculum: Option<String>,
+        /// Address to listen on
+        #[arg(short, long, default_value = "127.0.0.1:8787")]
+        bind: SocketAddr,
+    },
+    /// Create an API token for a learner, creating the learner if needed
+    IssueToken {
+        /// Learner's user id
+        #[arg(short, long)]
+        user: String,
+        /// What the token is for, e.g. the learner's name or device
+        #[arg(short, long, default_value = "")]
+        label: String,
+    },
+    /// List issued API tokens
+    ListTokens,
+    /// Revoke an API token by id
+    RevokeToken {
+        /// Token id, as shown by list-tokens
+        id: String,
+    },
+}
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .init();
+
+    let cli = Cli::parse();
+    let data_dir = cli.data_dir.unwrap_or_else(state::default_data_dir);
+
+    let result = match cli.command {
+        Commands::Serve { curriculum, bind } => serve(data_dir, curriculum, bind).await,
+        Commands::IssueToken { user, label } => issue_token(data_dir, user, label),
+        Commands::ListTokens => list_tokens(data_dir),
+        Commands::RevokeToken { id } => revoke_token(data_dir, id),
+    };
+    if let Err(e) = result {
+        eprintln!("Error: {}", e.message);
+        std::process::exit(1);
+    }
+}
+
+async fn serve(data_dir: PathBuf, curriculum: Option<String>, bind: SocketAddr) -> ApiResult<()> {
+    let db = state::open_database(&data_dir)?;
+    let state = Arc::new(ServerState::open(&data_dir, db, curriculum.as_deref())?);
+    tracing::info!(curriculum = %state.curriculum_id, "Serving on http://{}", bind);
+
+    let listener = tokio::net::TcpListener::bind(bind)
+        .await
+        .map_err(|e| error::ApiError::internal(format!("Failed to bind {}: {}", bind, e)))?;
+    axum::serve(listener, routes::router(state))
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
+        .map_err(|e| error::ApiError::internal(e.to_string()))
+}
+
+fn issue_token(data_dir: PathBuf, user_id: String, label: String) -> ApiResult<()> {
+    let db = state::open_database(&data_dir)?;
+    let secret = db.with_connection(|conn| {
+        if UserRepository::get_by_id(conn, &user_id)?.is_none() {
+            UserRepository::create(conn, &User::new(user_id.clone()))?;
+        }
+        let (token, secret) = ApiToken::issue(user_id.clone(), label);
+        ApiTokenRepository::create(conn, &token)?;
+        Ok(secret)
+    })?;
+
+    println!("Token for {} (shown once, keep it safe):", user_id);
+    println!("{}", secret);
+    Ok(())
+}
+
+fn list_tokens(data_dir: PathBuf) -> ApiResult<()> {
+    let db = state::open_database(&data_dir)?;
+    let tokens = db.with_connection(ApiTokenRepository::get_all)?;
+    if tokens.is_empty() {
+        println!("No tokens issued");
+    }
+    for token in tokens {
+        let last_used = token
+            .last_used_at
+            .map(|at| format!("{} ago", humanize(Utc::now() - at)))
+            .unwrap_or_else(|| "never used".to_string());
+        println!("{}  {:<16} {:<24} {}", token.id, token.user_id, token.label, last_used);
+    }
+    Ok(())
+}
+
+fn revoke_token(data_dir: PathBuf, id: String) -> ApiResult<()> {
+    let db = state::open_database(&data_dir)?;
+    if !db.with_connection(|conn| ApiTokenRepository::revoke(conn, &id))? {
+        return Err(error::ApiError::not_found(format!("No token with id {}", id)));
+    }
+    println!("Revoked {}", id);
+    Ok(())
+}
+
+fn humanize(elapsed: chrono::Duration) -> String {
+    match elapsed.num_minutes() {
+        m if m < 60 => format!("{}m", m.max(0)),
+        m if m < 60 * 24 => format!("{}h", m / 60),
+        m => format!("{}d", m / (60 * 24)),
+    }
+}
@@ -0,0 +1,118 @@
+//! Quizzes as the content pack stores them, graded by the core quiz service
+//!
+//! Pack questions refer to their options by position, so an option's id is
+//! its index and a multiple-answer question's correct answer is its sorted
+//! indices joined with commas.
+
+use content::ContentNode;
+use glp_core::models::quiz::{Question, QuestionOption, QuestionView, Quiz};
+use serde::Serialize;
+
+const PASSING_SCORE: i32 = 70;
+
+/// The quiz for a manifest node, ready to grade
+pub fn to_core_quiz(node: &ContentNode, quiz: content::Quiz) -> Quiz {
+    Quiz {
+        id: node.id.clone(),
+        title: quiz.title,
+        description: node.description.clone(),
+        difficulty: node.difficulty.clone(),
+        skills: node.skills.clone(),
+        passing_score: PASSING_SCORE,
+        time_limit_seconds: quiz.time_limit_seconds.map(|s| s.min(i32::MAX as u32) as i32),
+        late_policy: Default::default(),
+        questions: quiz.questions.into_iter().map(to_core_question).collect(),
+    }
+}
+
+fn to_core_question(question: content::Question) -> Question {
+    let correct_answer = question.answer_key();
+
+    Question {
+        id: question.id,
+        question_type: question.question_type,
+        prompt: question.question,
+        code_snippet: None,
+        options: question
+            .options
+            .into_iter()
+            .enumerate()
+            .map(|(index, text)| QuestionOption { id: index.to_string(), text })
+            .collect(),
+        correct_answer,
+        explanation: question.explanation,
+        points: 1,
+        skills: question.skills,
+    }
+}
+
+/// A quiz as a client sees it before answering: no answers, no explanations.
+/// Routes build it from the quiz with options shuffled for the attempt.
+#[derive(Debug, Serialize)]
+pub struct QuizView {
+    pub id: String,
+    pub title: String,
+    pub passing_score: i32,
+    pub time_limit_seconds: Option<i32>,
+    pub questions: Vec<QuestionView>,
+}
+
+impl From<&Quiz> for QuizView {
+    fn from(quiz: &Quiz) -> Self {
+        Self {
+            id: quiz.id.clone(),
+            title: quiz.title.clone(),
+            passing_score: quiz.passing_score,
+            time_limit_seconds: quiz.time_limit_seconds,
+            questions: quiz.questions.iter().map(QuestionView::from).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node() -> ContentNode {
+        serde_json::from_value(serde_json::json!({
+            "id": "week1-day1-quiz",
+            "type": "quiz",
+            "title": "Ownership Quiz",
+            "description": "Check your understanding",
+            "difficulty": "easy",
+            "estimated_minutes": 10,
+            "xp_reward": 50,
+            "content_path": "week1/day1/quiz.json",
+            "skills": ["ownership"]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_pack_quiz_converts_with_index_answers() {
+        let quiz: content::Quiz = serde_json::from_value(serde_json::json!({
+            "id": "quiz",
+            "title": "Ownership",
+            "time_limit_seconds": 300,
+            "questions": [
+                {"id": "q1", "question": "One?", "type": "multiple_choice",
+                 "options": ["a", "b", "c"], "correct_answer": 2, "explanation": "c"},
+                {"id": "q2", "question": "Many?", "type": "multiple_select",
+                 "options": ["a", "b", "c"], "correct_answers": [2, 0], "explanation": "a and c"}
+            ]
+        }))
+        .unwrap();
+
+        let quiz = to_core_quiz(&node(), quiz);
+        assert_eq!(quiz.id, "week1-day1-quiz");
+        assert_eq!(quiz.time_limit_seconds, Some(300));
+        assert_eq!(quiz.skills, vec!["ownership".to_string()]);
+        assert_eq!(quiz.questions[0].correct_answer, "2");
+        assert_eq!(quiz.questions[0].options[2].id, "2");
+        assert_eq!(quiz.questions[1].correct_answer, "0,2");
+
+        let view = serde_json::to_string(&QuizView::from(&quiz)).unwrap();
+        assert!(!view.contains("correct_answer"));
+        assert!(!view.contains("explanation"));
+    }
+}
@@ -0,0 +1,376 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::Utc;
+use content::{ContentNode, Manifest};
+use glp_core::db::error::DbError;
+use glp_core::db::repos::{ReviewRepository, UserRepository};
+use glp_core::gamification::Difficulty;
+use glp_core::models::quiz::Quiz;
+use glp_core::models::{ComprehensionCheck, LectureView, NodeProgress, ReviewItem, User};
+use glp_core::services::{
+    LectureCompletion, ProgressService, QuizResult, QuizService, QuizStart, QuizSubmission, XpAward,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::auth::Learner;
+use crate::error::{ApiError, ApiResult};
+use crate::quiz::{to_core_quiz, QuizView};
+use crate::state::ServerState;
+
+type AppState = State<Arc<ServerState>>;
+
+pub fn router(state: Arc<ServerState>) -> Router {
+    Router::new()
+        .route("/api/health", get(health))
+        .route("/api/me", get(me))
+        .route("/api/content", get(manifest))
+        .route("/api/lectures/{id}", get(lecture))
+        .route("/api/lectures/{id}/complete", post(complete_lecture))
+        .route("/api/progress", get(all_progress))
+        .route("/api/progress/{id}", get(node_progress))
+        .route("/api/progress/{id}/start", post(start_node))
+        .route("/api/quizzes/{id}", get(quiz))
+        .route("/api/quizzes/{id}/start", post(start_quiz))
+        .route("/api/quizzes/{id}/submit", post(submit_quiz))
+        .route("/api/reviews/due", get(due_reviews))
+        .with_state(state)
+}
+
+#[derive(Serialize)]
+struct Health {
+    status: &'static str,
+    curriculum_id: String,
+}
+
+async fn health(State(state): AppState) -> Json<Health> {
+    Json(Health { status: "ok", curriculum_id: state.curriculum_id.clone() })
+}
+
+async fn me(State(state): AppState, learner: Learner) -> ApiResult<Json<User>> {
+    let user = state
+        .run_db(move |conn| {
+            UserRepository::get_by_id(conn, &learner.user_id)?
+                .ok_or_else(|| DbError::NotFound(format!("User not found: {}", learner.user_id)))
+        })
+        .await?;
+    Ok(Json(user))
+}
+
+async fn manifest(State(state): AppState, _learner: Learner) -> Json<Manifest> {
+    Json(state.content.get_manifest().clone())
+}
+
+/// The manifest node `id`, which must be of `node_type`
+fn node<'a>(state: &'a ServerState, id: &str, node_type: &str) -> ApiResult<&'a ContentNode> {
+    state
+        .content
+        .get_node_by_id(id)
+        .filter(|node| node.node_type == node_type)
+        .ok_or_else(|| ApiError::not_found(format!("No {} with id {}", node_type, id)))
+}
+
+/// The lecture's segments and questions, without frontmatter or answers
+async fn lecture(State(state): AppState, _learner: Learner, Path(id): Path<String>) -> ApiResult<Json<LectureView>> {
+    let node = node(&state, &id, "lecture")?;
+    Ok(Json(LectureView::from(&state.content.load_lecture_content(&node.content_path)?)))
+}
+
+#[derive(Deserialize)]
+struct CompleteLectureRequest {
+    time_spent_ms: i64,
+    /// An option index for each comprehension check question; left out to
+    /// skip the check
+    #[serde(default)]
+    comprehension_answers: Option<Vec<usize>>,
+}
+
+/// Completing a lecture with a comprehension check takes right answers to
+/// it, or records that it was skipped
+async fn complete_lecture(
+    State(state): AppState,
+    learner: Learner,
+    Path(id): Path<String>,
+    Json(request): Json<CompleteLectureRequest>,
+) -> ApiResult<Json<XpAward>> {
+    let node = node(&state, &id, "lecture")?;
+    let content = state.content.load_lecture_content(&node.content_path)?;
+    let comprehension_check = match request.comprehension_answers {
+        _ if content.comprehension_check.is_empty() => None,
+        None => Some(ComprehensionCheck::Skipped),
+        Some(answers) if content.passes_comprehension_check(&answers) => Some(ComprehensionCheck::Passed),
+        Some(_) => {
+            return Err(ApiError::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "The comprehension check answers aren't all right",
+            ))
+        }
+    };
+    let lecture = LectureCompletion {
+        difficulty: node.difficulty.parse().unwrap_or(Difficulty::Easy),
+        lecture_id: id,
+        time_spent_ms: request.time_spent_ms,
+        comprehension_check,
+    };
+    let curriculum_id = state.curriculum_id.clone();
+    let award = state
+        .run_db(move |conn| {
+            ProgressService::complete_lecture(conn, &learner.user_id, Some(&curriculum_id), &lecture, 1.0)
+        })
+        .await?;
+    Ok(Json(award))
+}
+
+async fn all_progress(State(state): AppState, learner: Learner) -> ApiResult<Json<Vec<NodeProgress>>> {
+    let curriculum_id = state.curriculum_id.clone();
+    let progress = state
+        .run_db(move |conn| ProgressService::get_all(conn, &learner.user_id, Some(&curriculum_id)))
+        .await?;
+    Ok(Json(progress))
+}
+
+async fn node_progress(
+    State(state): AppState,
+    learner: Learner,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Option<NodeProgress>>> {
+    let curriculum_id = state.curriculum_id.clone();
+    let progress = state
+        .run_db(move |conn| ProgressService::get(conn, &learner.user_id, Some(&curriculum_id), &id))
+        .await?;
+    Ok(Json(progress))
+}
+
+async fn start_node(State(state): AppState, learner: Learner, Path(id): Path<String>) -> ApiResult<Json<NodeProgress>> {
+    if state.content.get_node_by_id(&id).is_none() {
+        return Err(ApiError::not_found(format!("No node with id {}", id)));
+    }
+    let curriculum_id = state.curriculum_id.clone();
+    let progress = state
+        .run_db(move |conn| ProgressService::start_node(conn, &learner.user_id, Some(&curriculum_id), &id))
+        .await?;
+    Ok(Json(progress))
+}
+
+fn load_quiz(state: &ServerState, id: &str) -> ApiResult<Quiz> {
+    let node = node(state, id, "quiz")?;
+    let quiz = state.content.load_quiz(&node.content_path)?;
+    Ok(to_core_quiz(node, quiz))
+}
+
+/// The quiz with its options in the order of the learner's next attempt.
+/// Fetching a timed quiz starts its clock if it isn't already running.
+async fn quiz(State(state): AppState, learner: Learner, Path(id): Path<String>) -> ApiResult<Json<QuizView>> {
+    let quiz = load_quiz(&state, &id)?;
+    let curriculum_id = state.curriculum_id.clone();
+    let shown = state
+        .run_db(move |conn| {
+            Ok(QuizService::serve(conn, &learner.user_id, Some(&curriculum_id), &quiz, Utc::now()))
+        })
+        .await??;
+    Ok(Json(QuizView::from(&shown)))
+}
+
+async fn start_quiz(State(state): AppState, learner: Learner, Path(id): Path<String>) -> ApiResult<Json<QuizStart>> {
+    let quiz = load_quiz(&state, &id)?;
+    let curriculum_id = state.curriculum_id.clone();
+    let start = state
+        .run_db(move |conn| {
+            Ok(QuizService::start(conn, &learner.user_id, Some(&curriculum_id), &quiz, Utc::now()))
+        })
+        .await??;
+    Ok(Json(start))
+}
+
+async fn submit_quiz(
+    State(state): AppState,
+    learner: Learner,
+    Path(id): Path<String>,
+    Json(submission): Json<QuizSubmission>,
+) -> ApiResult<Json<QuizResult>> {
+    let quiz = load_quiz(&state, &id)?;
+    let curriculum_id = state.curriculum_id.clone();
+    let (result, _events) = state
+        .run_db(move |conn| {
+            Ok(QuizService::submit_shown(
+                conn,
+                &learner.user_id,
+                Some(&curriculum_id),
+                &quiz,
+                &submission,
+                1.0,
+                Utc::now(),
+            ))
+        })
+        .await??;
+    Ok(Json(result))
+}
+
+async fn due_reviews(State(state): AppState, learner: Learner) -> ApiResult<Json<Vec<ReviewItem>>> {
+    let curriculum_id = state.curriculum_id.clone();
+    let reviews = state
+        .run_db(move |conn| ReviewRepository::get_due_reviews(conn, &learner.user_id, Some(&curriculum_id)))
+        .await?;
+    Ok(Json(reviews))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use glp_core::db::repos::{ApiTokenRepository, CurriculumRepository};
+    use glp_core::gamification::{quiz_shuffle_seed, OptionShuffle};
+    use glp_core::models::{ApiToken, Curriculum};
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    fn request(method: &str, uri: &str, token: Option<&str>, body: Option<serde_json::Value>) -> Request<Body> {
+        let mut builder = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {}", token));
+        }
+        match body {
+            Some(body) => builder
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+            None => builder.body(Body::empty()).unwrap(),
+        }
+    }
+
+    async fn json(response: axum::response::Response) -> serde_json::Value {
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    /// A server for `content_dir` with one learner, and their token's secret
+    fn serve(data_dir: &std::path::Path, content_dir: &std::path::Path) -> (Arc<ServerState>, Router, String) {
+        let db = crate::state::open_database(data_dir).unwrap();
+        let curriculum = Curriculum::new(
+            "Rust Bootcamp".to_string(),
+            "1.0".to_string(),
+            content_dir.to_string_lossy().to_string(),
+        );
+        let secret = db
+            .with_connection(|conn| {
+                CurriculumRepository::create(conn, &curriculum)?;
+                UserRepository::create(conn, &User::new("learner".to_string()))?;
+                let (token, secret) = ApiToken::issue("learner".to_string(), "laptop".to_string());
+                ApiTokenRepository::create(conn, &token)?;
+                Ok(secret)
+            })
+            .unwrap();
+        let state = Arc::new(ServerState::open(data_dir, db, Some(&curriculum.id)).unwrap());
+        let app = router(state.clone());
+        (state, app, secret)
+    }
+
+    #[tokio::test]
+    async fn test_token_holder_can_take_a_quiz() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let content_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../content");
+        let (state, app, secret) = serve(data_dir.path(), &content_dir);
+
+        let health = app.clone().oneshot(request("GET", "/api/health", None, None)).await.unwrap();
+        assert_eq!(health.status(), StatusCode::OK);
+        let anonymous = app.clone().oneshot(request("GET", "/api/me", None, None)).await.unwrap();
+        assert_eq!(anonymous.status(), StatusCode::UNAUTHORIZED);
+        let forged = app.clone().oneshot(request("GET", "/api/me", Some("glp_forged"), None)).await.unwrap();
+        assert_eq!(forged.status(), StatusCode::UNAUTHORIZED);
+
+        let quiz = app
+            .clone()
+            .oneshot(request("GET", "/api/quizzes/week1-day1-quiz", Some(&secret), None))
+            .await
+            .unwrap();
+        assert_eq!(quiz.status(), StatusCode::OK);
+        let quiz = json(quiz).await;
+        assert!(!quiz.to_string().contains("correct_answer"));
+
+        // Options come in the order seeded for the learner's first attempt,
+        // and answers are given against that order
+        let stored = load_quiz(&state, "week1-day1-quiz").unwrap();
+        let shuffle = OptionShuffle::new(&stored, quiz_shuffle_seed("learner", "week1-day1-quiz", 1));
+        let shown = shuffle.apply(&stored);
+        let texts: Vec<&str> = quiz["questions"][0]["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|option| option["text"].as_str().unwrap())
+            .collect();
+        let shown_texts: Vec<&str> = shown.questions[0].options.iter().map(|option| option.text.as_str()).collect();
+        assert_eq!(texts, shown_texts);
+
+        let answers = serde_json::json!({
+            "q1": shuffle.to_shown("q1", "2"),
+            "q2": shuffle.to_shown("q2", "0,1,3"),
+        });
+        let submitted = app
+            .clone()
+            .oneshot(request(
+                "POST",
+                "/api/quizzes/week1-day1-quiz/submit",
+                Some(&secret),
+                Some(serde_json::json!({"answers": answers, "time_spent_ms": 60000})),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(submitted.status(), StatusCode::OK);
+        let result = json(submitted).await;
+        assert!(result["score"].as_i64().unwrap() >= 2);
+        assert_eq!(result["feedback"][0]["user_answer"], answers["q1"]);
+
+        let missing = app
+            .clone()
+            .oneshot(request("GET", "/api/lectures/week1-day1-quiz", Some(&secret), None))
+            .await
+            .unwrap();
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+
+        let progress = app.oneshot(request("GET", "/api/progress", Some(&secret), None)).await.unwrap();
+        assert_eq!(progress.status(), StatusCode::OK);
+    }
+    #[tokio::test]
+    async fn test_lecture_is_sent_without_frontmatter_or_answers() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let content_dir = tempfile::tempdir().unwrap();
+        let shipped = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../content");
+        std::fs::copy(shipped.join("manifest.json"), content_dir.path().join("manifest.json")).unwrap();
+        std::fs::create_dir_all(content_dir.path().join("week1/day1")).unwrap();
+        std::fs::write(
+            content_dir.path().join("week1/day1/lecture.md"),
+            "+++\n\
+             [[comprehension_check]]\n\
+             question = \"Who frees a value?\"\n\
+             options = [\"Its owner\", \"The garbage collector\"]\n\
+             correct_answer = 0\n\
+             explanation = \"Values are dropped when their owner goes out of scope\"\n\
+             +++\n\
+             # Introduction to Rust\n\n\
+             ```quiz-inline\n\
+             {\"question\": \"Is Rust garbage collected?\", \"options\": [\"Yes\", \"No\"], \
+             \"correct_answer\": 1, \"explanation\": \"Ownership frees memory\"}\n\
+             ```\n",
+        )
+        .unwrap();
+        let (_state, app, secret) = serve(data_dir.path(), content_dir.path());
+
+        let lecture = app
+            .oneshot(request("GET", "/api/lectures/week1-day1-lecture", Some(&secret), None))
+            .await
+            .unwrap();
+        assert_eq!(lecture.status(), StatusCode::OK);
+        let lecture = json(lecture).await;
+        assert_eq!(lecture["segments"][0]["text"], "# Introduction to Rust\n\n");
+        assert_eq!(lecture["segments"][1]["question"]["prompt"], "Is Rust garbage collected?");
+        assert_eq!(lecture["comprehension_check"][0]["options"][1]["text"], "The garbage collector");
+
+        let text = lecture.to_string();
+        assert!(!text.contains("+++"));
+        assert!(!text.contains("correct_answer"));
+        assert!(!text.contains("explanation"));
+    }
+}
@@ -0,0 +1,69 @@
+use content::ContentLoader;
+use glp_core::db::error::{DbError, DbResult};
+use glp_core::db::repos::CurriculumRepository;
+use glp_core::AppDatabase;
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+
+use crate::error::{ApiError, ApiResult};
+
+/// Where the desktop app keeps its data, so a server can run against a
+/// library of curricula imported there
+pub fn default_data_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("gamified-learning-platform")
+}
+
+pub fn open_database(data_dir: &Path) -> DbResult<AppDatabase> {
+    std::fs::create_dir_all(data_dir).map_err(|e| DbError::InvalidData(e.to_string()))?;
+    AppDatabase::new(data_dir.join("app.db"))
+}
+
+/// One database and one curriculum, shared by every connected learner
+pub struct ServerState {
+    pub db: AppDatabase,
+    pub content: ContentLoader,
+    pub curriculum_id: String,
+}
+
+impl ServerState {
+    /// Serve `curriculum_id`, or the curriculum last made active, from the
+    /// curricula imported into `data_dir`
+    pub fn open(data_dir: &Path, db: AppDatabase, curriculum_id: Option<&str>) -> ApiResult<Self> {
+        let curriculum = db.with_connection(|conn| match curriculum_id {
+            Some(id) => CurriculumRepository::get(conn, id),
+            None => CurriculumRepository::get_active(conn),
+        })?;
+        let curriculum = curriculum.ok_or_else(|| match curriculum_id {
+            Some(id) => ApiError::not_found(format!("Curriculum not found: {}", id)),
+            None => ApiError::not_found("No curriculum is active; pass --curriculum"),
+        })?;
+
+        let (content_key, license_key) = db.with_connection(|conn| {
+            Ok((
+                CurriculumRepository::get_content_key(conn, &curriculum.id)?,
+                CurriculumRepository::get_license_key(conn, &curriculum.id)?,
+            ))
+        })?;
+        let content = ContentLoader::open(
+            data_dir.join(&curriculum.content_path),
+            content_key.as_deref(),
+            license_key.as_deref(),
+        )?;
+        Ok(Self { db, content, curriculum_id: curriculum.id })
+    }
+
+    /// Run database work on a blocking thread with a pooled connection
+    pub async fn run_db<F, T>(&self, f: F) -> ApiResult<T>
+    where
+        F: FnOnce(&Connection) -> DbResult<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let db = self.db.clone();
+        tokio::task::spawn_blocking(move || db.with_connection(f))
+            .await
+            .map_err(|e| ApiError::internal(format!("Database task failed: {}", e)))?
+            .map_err(ApiError::from)
+    }
+}
@@ -0,0 +1,154 @@
+//! Sealed content payloads
+//!
+//! Paid packs can ship lecture, quiz and challenge files encrypted. A sealed
+//! file's content path ends in `.enc` and holds
+//!
+//! ```text
+//! "GLPSEALED" | format version: u8 | nonce: 12 | ciphertext
+//! ```
+//!
+//! sealed with AES-256-GCM under the pack's content key, with the header and
+//! the content path authenticated along with it so a sealed file can't be
+//! swapped for another. The key is handed to the learner when they activate
+//! the pack; the manifest only carries a check value to recognise it by.
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use sha2::{Digest, Sha256};
+
+use crate::error::{ContentError, ContentResult};
+use crate::hex;
+
+const MAGIC: &[u8] = b"GLPSEALED";
+const FORMAT_VERSION: u8 = 1;
+const HEADER_LEN: usize = MAGIC.len() + 1;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+/// Extension of a sealed content file
+pub const SEALED_EXTENSION: &str = ".enc";
+
+/// Whether the file at `content_path` is sealed
+pub fn is_sealed(content_path: &str) -> bool {
+    content_path.ends_with(SEALED_EXTENSION)
+}
+
+/// A pack's content key, written as 64 hex digits
+#[derive(Clone, PartialEq, Eq)]
+pub struct ContentKey([u8; KEY_LEN]);
+
+impl ContentKey {
+    /// A new random key, for pack authors
+    pub fn generate() -> Self {
+        let mut key = [0u8; KEY_LEN];
+        OsRng.fill_bytes(&mut key);
+        Self(key)
+    }
+
+    pub fn from_hex(hex: &str) -> ContentResult<Self> {
+        hex::decode(hex)
+            .map(Self)
+            .ok_or_else(|| ContentError::InvalidKey(format!("expected {} hex digits", KEY_LEN * 2)))
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(&self.0)
+    }
+
+    /// The value a manifest's `encryption.key_check` holds for this key.
+    /// Telling keys apart by it gives nothing away about them.
+    pub fn check(&self) -> String {
+        format!("{:x}", Sha256::digest(self.0))
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.0))
+    }
+}
+
+impl std::fmt::Debug for ContentKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ContentKey(..)")
+    }
+}
+
+fn aad(header: &[u8], content_path: &str) -> Vec<u8> {
+    [header, content_path.as_bytes()].concat()
+}
+
+/// Seal the file that will live at `content_path`
+pub fn seal(key: &ContentKey, content_path: &str, plaintext: &[u8]) -> ContentResult<Vec<u8>> {
+    let mut sealed = Vec::with_capacity(HEADER_LEN + NONCE_LEN + plaintext.len() + 16);
+    sealed.extend_from_slice(MAGIC);
+    sealed.push(FORMAT_VERSION);
+
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    let ciphertext = key
+        .cipher()
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: plaintext, aad: &aad(&sealed, content_path) })
+        .map_err(|_| ContentError::Decryption(content_path.to_string()))?;
+
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// The text of the sealed file at `content_path`
+pub fn open(key: &ContentKey, content_path: &str, sealed: &[u8]) -> ContentResult<String> {
+    if !sealed.starts_with(MAGIC) || sealed.len() < HEADER_LEN + NONCE_LEN {
+        return Err(ContentError::Validation(format!("{} is not a sealed content file", content_path)));
+    }
+    let (header, body) = sealed.split_at(HEADER_LEN);
+    if header[MAGIC.len()] != FORMAT_VERSION {
+        return Err(ContentError::Validation(format!(
+            "{} is sealed with unsupported format {}",
+            content_path,
+            header[MAGIC.len()]
+        )));
+    }
+    let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+
+    let plaintext = key
+        .cipher()
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: &aad(header, content_path) })
+        .map_err(|_| ContentError::Decryption(content_path.to_string()))?;
+    String::from_utf8(plaintext).map_err(|_| ContentError::Decryption(content_path.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_round_trip_and_wrong_key() {
+        let key = ContentKey::generate();
+        let sealed = seal(&key, "week1/day1/lecture.md.enc", b"# Ownership").unwrap();
+
+        assert!(!sealed.windows(b"Ownership".len()).any(|w| w == b"Ownership"));
+        assert_eq!(open(&key, "week1/day1/lecture.md.enc", &sealed).unwrap(), "# Ownership");
+        assert!(matches!(
+            open(&ContentKey::generate(), "week1/day1/lecture.md.enc", &sealed),
+            Err(ContentError::Decryption(_))
+        ));
+        // A sealed file moved to another path doesn't open
+        assert!(matches!(
+            open(&key, "week1/day2/lecture.md.enc", &sealed),
+            Err(ContentError::Decryption(_))
+        ));
+    }
+
+    #[test]
+    fn test_key_hex_round_trip() {
+        let key = ContentKey::generate();
+        let parsed = ContentKey::from_hex(&key.to_hex().to_uppercase()).unwrap();
+        assert_eq!(parsed, key);
+        assert_eq!(parsed.check(), key.check());
+        assert_ne!(key.check(), key.to_hex());
+
+        assert!(matches!(ContentKey::from_hex("abc"), Err(ContentError::InvalidKey(_))));
+        assert!(matches!(ContentKey::from_hex(&"zz".repeat(32)), Err(ContentError::InvalidKey(_))));
+        assert_eq!(format!("{:?}", key), "ContentKey(..)");
+    }
+}
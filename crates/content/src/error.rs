@@ -13,6 +13,24 @@ pub enum ContentError {
 
     #[error("Validation error: {0}")]
     Validation(String),
+
+    #[error("{0} is encrypted and no content key has been given")]
+    KeyRequired(String),
+
+    #[error("Invalid content key: {0}")]
+    InvalidKey(String),
+
+    #[error("Could not decrypt {0}: wrong key or corrupted file")]
+    Decryption(String),
+
+    #[error("This curriculum needs a license key; activate it to open its content")]
+    LicenseRequired,
+
+    #[error("Invalid license key: {0}")]
+    InvalidLicense(String),
+
+    #[error("The license for this curriculum expired on {0}; renew it to keep going")]
+    LicenseExpired(chrono::NaiveDate),
 }
 
 pub type ContentResult<T> = Result<T, ContentError>;
@@ -0,0 +1,20 @@
+//! Hex encoding for the keys packs and licenses are written with
+
+/// `N` bytes written as `2 * N` hex digits, in either case. Surrounding
+/// whitespace is ignored.
+pub(crate) fn decode<const N: usize>(hex: &str) -> Option<[u8; N]> {
+    let hex = hex.trim();
+    if hex.len() != N * 2 || !hex.is_ascii() {
+        return None;
+    }
+    let mut bytes = [0u8; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(bytes)
+}
+
+/// Lowercase hex digits for `bytes`
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
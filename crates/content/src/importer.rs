@@ -1,3 +1,4 @@
+use crate::encryption;
 use crate::error::{ContentError, ContentResult};
 use crate::manifest::Manifest;
 use std::fs;
@@ -81,6 +82,11 @@ pub fn validate_content_pack(source_path: &Path) -> ContentResult<ValidationResu
         errors.push("Manifest missing 'version' field".to_string());
     }
 
+    // Validate mastery decay overrides
+    if let Some(decay) = &manifest.mastery_decay {
+        errors.extend(decay.validate());
+    }
+
     // Validate content files exist
     for week in &manifest.weeks {
         for day in &week.days {
@@ -96,6 +102,30 @@ pub fn validate_content_pack(source_path: &Path) -> ContentResult<ValidationResu
         }
     }
 
+    if manifest.requires_license {
+        warnings.push("This curriculum needs a license key once imported".to_string());
+    }
+
+    // Sealed files need the manifest to say how they're unlocked
+    let sealed = manifest.weeks.iter()
+        .flat_map(|w| &w.days)
+        .flat_map(|d| &d.nodes)
+        .filter(|n| encryption::is_sealed(&n.content_path))
+        .count();
+    if sealed > 0 {
+        if manifest.encryption.is_none() {
+            errors.push(format!(
+                "{} node(s) have sealed content but the manifest has no encryption section",
+                sealed
+            ));
+        } else {
+            warnings.push(format!(
+                "{} node(s) are encrypted and need the curriculum's content key once imported",
+                sealed
+            ));
+        }
+    }
+
     // Validate node types
     let valid_types = ["lecture", "quiz", "mini-challenge", "checkpoint"];
     for week in &manifest.weeks {
@@ -230,8 +260,44 @@ pub fn delete_content_pack(app_data_dir: &Path, curriculum_id: &str) -> ContentR
     Ok(())
 }
 
+/// Move an imported curriculum's content into the trash under `trash_id`
+/// so a deletion can be undone
+pub fn trash_content_pack(app_data_dir: &Path, curriculum_id: &str, trash_id: &str) -> ContentResult<()> {
+    let content_dir = app_data_dir.join("curricula").join(curriculum_id);
+    if content_dir.exists() {
+        let trash_dir = app_data_dir.join("trash");
+        fs::create_dir_all(&trash_dir)?;
+        fs::rename(&content_dir, trash_dir.join(trash_id))?;
+    }
+    Ok(())
+}
+
+/// Move trashed content back to where it was imported
+pub fn restore_content_pack(app_data_dir: &Path, curriculum_id: &str, trash_id: &str) -> ContentResult<()> {
+    let trashed_dir = app_data_dir.join("trash").join(trash_id);
+    if trashed_dir.exists() {
+        let content_dir = app_data_dir.join("curricula").join(curriculum_id);
+        if content_dir.exists() {
+            fs::remove_dir_all(&content_dir)?;
+        }
+        fs::create_dir_all(app_data_dir.join("curricula"))?;
+        fs::rename(&trashed_dir, content_dir)?;
+    }
+    Ok(())
+}
+
+/// Permanently delete trashed content once it can no longer be restored
+pub fn purge_trashed_content(app_data_dir: &Path, trash_id: &str) -> ContentResult<()> {
+    let trashed_dir = app_data_dir.join("trash").join(trash_id);
+    if trashed_dir.exists() {
+        fs::remove_dir_all(&trashed_dir)?;
+    }
+    Ok(())
+}
+
 /// Get statistics about a content pack
 #[derive(Debug, Clone, serde::Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct ContentStats {
     pub total_weeks: usize,
     pub total_days: usize,
@@ -417,6 +483,25 @@ mod tests {
         assert!(dest.join("week1/day1/lecture.md").exists());
     }
 
+    #[test]
+    fn test_trash_and_restore_content_pack() {
+        let source = create_valid_content_pack();
+        let app_data = tempdir().unwrap();
+        import_content_pack(&source, app_data.path(), "test-curriculum").unwrap();
+
+        trash_content_pack(app_data.path(), "test-curriculum", "trash-1").unwrap();
+        assert!(!app_data.path().join("curricula/test-curriculum").exists());
+        assert!(app_data.path().join("trash/trash-1/manifest.json").exists());
+
+        restore_content_pack(app_data.path(), "test-curriculum", "trash-1").unwrap();
+        assert!(app_data.path().join("curricula/test-curriculum/week1/day1/lecture.md").exists());
+        assert!(!app_data.path().join("trash/trash-1").exists());
+
+        trash_content_pack(app_data.path(), "test-curriculum", "trash-2").unwrap();
+        purge_trashed_content(app_data.path(), "trash-2").unwrap();
+        assert!(!app_data.path().join("trash/trash-2").exists());
+    }
+
     #[test]
     fn test_get_content_stats() {
         let content_dir = create_valid_content_pack();
@@ -0,0 +1,365 @@
+//! Lecture preprocessing
+//!
+//! Lectures are Markdown with a few fenced blocks the app renders itself:
+//! ` ```quiz-inline ` holds a question as JSON, ` ```challenge-ref ` the id
+//! of a challenge node to link to and ` ```mermaid ` a diagram. A lecture is
+//! split into segments so the frontend gets those blocks as data and only
+//! renders the Markdown between them. A block that can't be read stays in
+//! the Markdown as code and is reported, so a typo doesn't hide a lecture;
+//! the validator turns those reports into errors at import.
+//!
+//! A lecture may start with TOML frontmatter between `+++` lines. Its
+//! `[[comprehension_check]]` tables are questions, in the same shape as a
+//! ` ```quiz-inline ` block, that the learner answers to complete it:
+//!
+//! ```text
+//! +++
+//! [[comprehension_check]]
+//! question = "Who frees a value?"
+//! options = ["Its owner, when it goes out of scope", "The garbage collector"]
+//! correct_answer = 0
+//! +++
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Most questions a lecture's comprehension check can have
+pub const MAX_COMPREHENSION_QUESTIONS: usize = 2;
+
+const FRONTMATTER_FENCE: &str = "+++";
+
+/// A multiple-choice question embedded in a lecture
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct InlineQuestion {
+    pub question: String,
+    pub options: Vec<String>,
+    /// Index into `options`
+    pub correct_answer: usize,
+    #[serde(default)]
+    pub explanation: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LectureSegment {
+    Markdown { text: String },
+    QuizInline { question: InlineQuestion },
+    ChallengeRef { node_id: String },
+    Mermaid { source: String },
+}
+
+/// A lecture split into segments, with the blocks that couldn't be read
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct Lecture {
+    pub markdown: String,
+    pub segments: Vec<LectureSegment>,
+    /// One message per unreadable block, with its line
+    pub problems: Vec<String>,
+    /// Questions to answer before the lecture counts as complete
+    #[serde(default)]
+    pub comprehension_check: Vec<InlineQuestion>,
+}
+
+impl Lecture {
+    /// Whether `answers`, an option index for each question in turn, pass
+    /// the comprehension check
+    pub fn passes_comprehension_check(&self, answers: &[usize]) -> bool {
+        answers.len() == self.comprehension_check.len()
+            && self
+                .comprehension_check
+                .iter()
+                .zip(answers)
+                .all(|(question, answer)| question.correct_answer == *answer)
+    }
+
+    /// The lecture's inline questions, in order
+    pub fn inline_questions(&self) -> impl Iterator<Item = &InlineQuestion> {
+        self.segments.iter().filter_map(|segment| match segment {
+            LectureSegment::QuizInline { question } => Some(question),
+            _ => None,
+        })
+    }
+
+    /// Ids of the challenge nodes the lecture links to
+    pub fn challenge_refs(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().filter_map(|segment| match segment {
+            LectureSegment::ChallengeRef { node_id } => Some(node_id.as_str()),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct Frontmatter {
+    #[serde(default)]
+    comprehension_check: Vec<InlineQuestion>,
+}
+
+/// Split frontmatter off the start of a lecture: the TOML, the rest and
+/// how many lines the frontmatter took up
+fn split_frontmatter(markdown: &str) -> Result<Option<(&str, &str, usize)>, String> {
+    let mut lines = markdown.split_inclusive('\n');
+    let Some(first) = lines.next().filter(|line| line.trim_end() == FRONTMATTER_FENCE) else {
+        return Ok(None);
+    };
+    let mut end = first.len();
+    for (n, line) in lines.enumerate() {
+        if line.trim_end() == FRONTMATTER_FENCE {
+            return Ok(Some((&markdown[first.len()..end], &markdown[end + line.len()..], n + 2)));
+        }
+        end += line.len();
+    }
+    Err("the frontmatter is never closed".to_string())
+}
+
+/// The comprehension check in a lecture's frontmatter, leaving out the
+/// questions that can't be used
+fn parse_frontmatter(toml_text: &str, problems: &mut Vec<String>) -> Vec<InlineQuestion> {
+    let frontmatter: Frontmatter = match toml::from_str(toml_text) {
+        Ok(frontmatter) => frontmatter,
+        Err(e) => {
+            problems.push(format!("line 1: the frontmatter isn't valid: {}", e.message()));
+            return Vec::new();
+        }
+    };
+
+    let mut questions = Vec::new();
+    for (number, question) in frontmatter.comprehension_check.into_iter().enumerate() {
+        if let Err(problem) = check_question(&question, "comprehension check") {
+            problems.push(format!("line 1: {} (question {})", problem, number + 1));
+        } else if questions.len() == MAX_COMPREHENSION_QUESTIONS {
+            problems.push(format!(
+                "line 1: a comprehension check has at most {} questions",
+                MAX_COMPREHENSION_QUESTIONS
+            ));
+            break;
+        } else {
+            questions.push(question);
+        }
+    }
+    questions
+}
+
+/// An open code fence: its character, length and info string
+struct Fence<'a> {
+    marker: char,
+    len: usize,
+    info: &'a str,
+}
+
+fn opening_fence(line: &str) -> Option<Fence<'_>> {
+    let trimmed = line.trim_start();
+    let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = trimmed.chars().take_while(|c| *c == marker).count();
+    let info = trimmed[len..].trim();
+    // Backtick fences can't have backticks in their info string
+    if len < 3 || (marker == '`' && info.contains('`')) {
+        return None;
+    }
+    Some(Fence { marker, len, info })
+}
+
+fn closes(fence: &Fence, line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.chars().all(|c| c == fence.marker) && trimmed.len() >= fence.len
+}
+
+/// Split a lecture into Markdown and the blocks the app renders itself
+pub fn parse_lecture(markdown: &str) -> Lecture {
+    let mut problems = Vec::new();
+    let (body, comprehension_check, offset) = match split_frontmatter(markdown) {
+        Ok(Some((frontmatter, body, lines))) => (body, parse_frontmatter(frontmatter, &mut problems), lines),
+        Ok(None) => (markdown, Vec::new(), 0),
+        Err(problem) => {
+            problems.push(format!("line 1: {}", problem));
+            (markdown, Vec::new(), 0)
+        }
+    };
+
+    let lines: Vec<&str> = body.lines().collect();
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let Some(fence) = opening_fence(lines[i]) else {
+            text.push_str(lines[i]);
+            text.push('\n');
+            i += 1;
+            continue;
+        };
+        let end = (i + 1..lines.len()).find(|&j| closes(&fence, lines[j]));
+        let body_end = end.unwrap_or(lines.len());
+        let block_end = end.map_or(lines.len(), |end| end + 1);
+        let body = lines[i + 1..body_end].join("\n");
+        let kind = fence.info.split_whitespace().next().unwrap_or_default();
+
+        let parsed = match kind {
+            "quiz-inline" | "challenge-ref" | "mermaid" if end.is_none() => {
+                Err(format!("the {} block is never closed", kind))
+            }
+            "quiz-inline" => parse_question(&body).map(|question| Some(LectureSegment::QuizInline { question })),
+            "challenge-ref" => parse_challenge_ref(&body).map(|node_id| Some(LectureSegment::ChallengeRef { node_id })),
+            "mermaid" if body.trim().is_empty() => Err("the mermaid block is empty".to_string()),
+            "mermaid" => Ok(Some(LectureSegment::Mermaid { source: body })),
+            _ => Ok(None),
+        };
+        match parsed {
+            Ok(Some(segment)) => {
+                if !text.trim().is_empty() {
+                    segments.push(LectureSegment::Markdown { text: std::mem::take(&mut text) });
+                }
+                text.clear();
+                segments.push(segment);
+            }
+            other => {
+                if let Err(problem) = other {
+                    problems.push(format!("line {}: {}", offset + i + 1, problem));
+                }
+                for line in &lines[i..block_end] {
+                    text.push_str(line);
+                    text.push('\n');
+                }
+            }
+        }
+        i = block_end;
+    }
+    if !text.trim().is_empty() {
+        segments.push(LectureSegment::Markdown { text });
+    }
+
+    Lecture { markdown: body.to_string(), segments, problems, comprehension_check }
+}
+
+fn parse_question(body: &str) -> Result<InlineQuestion, String> {
+    let question: InlineQuestion =
+        serde_json::from_str(body).map_err(|e| format!("the quiz-inline block isn't a valid question: {}", e))?;
+    check_question(&question, "quiz-inline")?;
+    Ok(question)
+}
+
+/// Check a question can be answered; `kind` names where it's from
+fn check_question(question: &InlineQuestion, kind: &str) -> Result<(), String> {
+    if question.question.trim().is_empty() {
+        return Err(format!("the {} question is empty", kind));
+    }
+    if question.options.len() < 2 {
+        return Err(format!("a {} question needs at least two options", kind));
+    }
+    if question.correct_answer >= question.options.len() {
+        return Err(format!(
+            "the {} answer {} isn't one of its {} options",
+            kind,
+            question.correct_answer,
+            question.options.len()
+        ));
+    }
+    Ok(())
+}
+
+fn parse_challenge_ref(body: &str) -> Result<String, String> {
+    let mut ids = body.split_whitespace();
+    match (ids.next(), ids.next()) {
+        (Some(id), None) => Ok(id.to_string()),
+        (None, _) => Err("the challenge-ref block is empty".to_string()),
+        (Some(_), Some(_)) => Err("a challenge-ref block holds a single node id".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_embedded_blocks() {
+        let markdown = "# Ownership\n\nEach value has one owner.\n\n\
+                        ```quiz-inline\n\
+                        {\"question\": \"How many owners?\", \"options\": [\"One\", \"Two\"], \"correct_answer\": 0}\n\
+                        ```\n\n\
+                        ```rust\nlet s = String::new();\n```\n\n\
+                        ```mermaid\ngraph LR\n  a --> b\n```\n\
+                        ```challenge-ref\nchallenge-ownership\n```\n";
+        let lecture = parse_lecture(markdown);
+
+        assert!(lecture.problems.is_empty(), "{:?}", lecture.problems);
+        assert_eq!(lecture.segments.len(), 5);
+        assert_eq!(
+            lecture.segments[0],
+            LectureSegment::Markdown { text: "# Ownership\n\nEach value has one owner.\n\n".to_string() }
+        );
+        assert!(matches!(&lecture.segments[1], LectureSegment::QuizInline { question } if question.options.len() == 2));
+        // Ordinary code blocks stay in the Markdown
+        assert!(matches!(&lecture.segments[2], LectureSegment::Markdown { text } if text.contains("```rust")));
+        assert_eq!(lecture.segments[3], LectureSegment::Mermaid { source: "graph LR\n  a --> b".to_string() });
+        assert_eq!(lecture.inline_questions().count(), 1);
+        assert_eq!(lecture.challenge_refs().collect::<Vec<_>>(), vec!["challenge-ownership"]);
+    }
+
+    #[test]
+    fn test_unreadable_blocks_stay_markdown() {
+        let markdown = "Intro\n\n\
+                        ````markdown\n```quiz-inline\nnot parsed here\n```\n````\n\n\
+                        ```quiz-inline\n{\"question\": \"Q\", \"options\": [\"A\", \"B\"], \"correct_answer\": 2}\n```\n\n\
+                        ```challenge-ref\n";
+        let lecture = parse_lecture(markdown);
+
+        assert_eq!(lecture.segments.len(), 1);
+        assert!(matches!(&lecture.segments[0], LectureSegment::Markdown { text } if text.contains("correct_answer")));
+        assert_eq!(
+            lecture.problems,
+            vec![
+                "line 9: the quiz-inline answer 2 isn't one of its 2 options".to_string(),
+                "line 13: the challenge-ref block is never closed".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_frontmatter_comprehension_check() {
+        let markdown = "+++\n\
+                        [[comprehension_check]]\n\
+                        question = \"Who frees a value?\"\n\
+                        options = [\"Its owner\", \"The garbage collector\"]\n\
+                        correct_answer = 0\n\
+                        +++\n\
+                        # Ownership\n\n\
+                        ```challenge-ref\n";
+        let lecture = parse_lecture(markdown);
+
+        assert_eq!(lecture.comprehension_check.len(), 1);
+        assert!(lecture.markdown.starts_with("# Ownership"));
+        // Lines are counted from the top of the file
+        assert_eq!(lecture.problems, vec!["line 9: the challenge-ref block is never closed".to_string()]);
+        assert!(lecture.passes_comprehension_check(&[0]));
+        assert!(!lecture.passes_comprehension_check(&[1]));
+        assert!(!lecture.passes_comprehension_check(&[]));
+    }
+
+    #[test]
+    fn test_unusable_comprehension_questions_are_reported() {
+        let question = |answer: usize| {
+            format!(
+                "[[comprehension_check]]\nquestion = \"Q\"\noptions = [\"A\", \"B\"]\ncorrect_answer = {}\n",
+                answer
+            )
+        };
+        let markdown = format!("+++\n{}{}{}{}+++\nBody\n", question(0), question(5), question(1), question(0));
+        let lecture = parse_lecture(&markdown);
+
+        assert_eq!(lecture.comprehension_check.len(), MAX_COMPREHENSION_QUESTIONS);
+        assert_eq!(
+            lecture.problems,
+            vec![
+                "line 1: the comprehension check answer 5 isn't one of its 2 options (question 2)".to_string(),
+                "line 1: a comprehension check has at most 2 questions".to_string(),
+            ]
+        );
+
+        let unclosed = parse_lecture("+++\ncomprehension_check = []\n# Body\n");
+        assert_eq!(unclosed.problems, vec!["line 1: the frontmatter is never closed".to_string()]);
+        assert!(unclosed.comprehension_check.is_empty());
+    }
+}
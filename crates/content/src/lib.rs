@@ -1,10 +1,26 @@
+pub mod encryption;
 pub mod error;
+mod hex;
+pub mod lecture;
+pub mod license;
 pub mod loader;
 pub mod manifest;
 pub mod validator;
 pub mod importer;
+pub mod template;
+pub mod workspace;
 
+pub use lecture::{parse_lecture, InlineQuestion, Lecture, LectureSegment};
 pub use loader::ContentLoader;
-pub use manifest::{Manifest, Week, Day, ContentNode, Checkpoint, Skill, Quiz, Question, Challenge};
+pub use manifest::{
+    Manifest, MasteryDecayConfig, PackEncryption, Week, Day, ContentNode, Checkpoint, Skill, Quiz, Question, Challenge, ProjectTrack,
+    TrackStep,
+};
 pub use error::ContentError;
-pub use importer::{validate_content_pack, import_content_pack, delete_content_pack, get_content_stats, ValidationResult, ContentStats};
+pub use encryption::ContentKey;
+pub use license::{License, LicenseStatus};
+pub use template::ChallengeTemplate;
+pub use importer::{
+    validate_content_pack, import_content_pack, delete_content_pack, trash_content_pack, restore_content_pack,
+    purge_trashed_content, get_content_stats, ValidationResult, ContentStats,
+};
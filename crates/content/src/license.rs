@@ -0,0 +1,254 @@
+//! License keys for premium curricula
+//!
+//! A pack with `requires_license` set only opens its content with a license
+//! key signed by one of the issuers pinned in the app when it's built (see
+//! [`pinned_issuers`]); a pack can't name its own. Keys are checked offline:
+//!
+//! ```text
+//! "GLP1." | base64url(license JSON) | "." | base64url(Ed25519 signature of the JSON)
+//! ```
+//!
+//! A license may expire; content stays open for [`GRACE_PERIOD_DAYS`] after
+//! that so a renewal can arrive before learners are locked out.
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{Duration, NaiveDate};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ContentError, ContentResult};
+use crate::hex;
+use crate::manifest::Manifest;
+
+const PREFIX: &str = "GLP1";
+
+/// Days content stays open after a license expires
+pub const GRACE_PERIOD_DAYS: i64 = 14;
+
+/// Issuer keys, as comma-separated hex, that the app is built to accept
+/// license keys from
+const PINNED_ISSUERS: Option<&str> = option_env!("GLP_LICENSE_ISSUERS");
+
+/// What a license key grants
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct License {
+    /// Title of the curriculum it's for
+    pub curriculum: String,
+    pub licensee: String,
+    pub issued_on: NaiveDate,
+    /// None for a license that doesn't expire
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_on: Option<NaiveDate>,
+}
+
+/// Where a license stands on a given day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum LicenseStatus {
+    Active,
+    /// Expired, but content stays open until `ends_on`
+    Grace { ends_on: NaiveDate },
+    Expired,
+}
+
+impl License {
+    pub fn status(&self, today: NaiveDate) -> LicenseStatus {
+        match self.expires_on {
+            Some(expires_on) if today > expires_on => {
+                let ends_on = expires_on + Duration::days(GRACE_PERIOD_DAYS);
+                if today > ends_on {
+                    LicenseStatus::Expired
+                } else {
+                    LicenseStatus::Grace { ends_on }
+                }
+            }
+            _ => LicenseStatus::Active,
+        }
+    }
+
+    /// The status on `today`, or why content can no longer be opened
+    pub fn check(&self, today: NaiveDate) -> ContentResult<LicenseStatus> {
+        match (self.status(today), self.expires_on) {
+            (LicenseStatus::Expired, Some(expires_on)) => Err(ContentError::LicenseExpired(expires_on)),
+            (status, _) => Ok(status),
+        }
+    }
+
+    /// The license key for this license, signed by its issuer
+    pub fn sign(&self, signing_key: &SigningKey) -> ContentResult<String> {
+        let payload = serde_json::to_vec(self)?;
+        let signature = signing_key.sign(&payload);
+        Ok(format!(
+            "{}.{}.{}",
+            PREFIX,
+            URL_SAFE_NO_PAD.encode(&payload),
+            URL_SAFE_NO_PAD.encode(signature.to_bytes())
+        ))
+    }
+}
+
+/// A new signing key for a license issuer
+pub fn generate_signing_key() -> SigningKey {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    SigningKey::from_bytes(&bytes)
+}
+
+/// A signing key written as 64 hex digits, for the issuer to keep
+pub fn signing_key_to_hex(signing_key: &SigningKey) -> String {
+    hex::encode(signing_key.as_bytes())
+}
+
+/// Parse an issuer's signing key, written as 64 hex digits
+pub fn signing_key_from_hex(hex: &str) -> ContentResult<SigningKey> {
+    Ok(SigningKey::from_bytes(&key_bytes(hex, "signing key")?))
+}
+
+/// An issuer key as the app is built with it: the public half of a signing
+/// key
+pub fn issuer_key(signing_key: &SigningKey) -> String {
+    hex::encode(signing_key.verifying_key().as_bytes())
+}
+
+/// Parse an issuer key, written as 64 hex digits
+pub fn issuer_from_hex(key: &str) -> ContentResult<VerifyingKey> {
+    VerifyingKey::from_bytes(&key_bytes(key, "issuer key")?)
+        .map_err(|_| ContentError::InvalidLicense("issuer key isn't a valid public key".to_string()))
+}
+
+/// The issuers this build accepts license keys from, set at build time in
+/// `GLP_LICENSE_ISSUERS`. A build without any accepts no license keys.
+pub fn pinned_issuers() -> ContentResult<Vec<VerifyingKey>> {
+    PINNED_ISSUERS
+        .unwrap_or_default()
+        .split(',')
+        .filter(|key| !key.trim().is_empty())
+        .map(issuer_from_hex)
+        .collect()
+}
+
+/// Check a license key against a pack: signed by one of `issuers` and for
+/// it. Whether it has expired is up to [`License::check`].
+pub fn verify(manifest: &Manifest, key: &str, issuers: &[VerifyingKey]) -> ContentResult<License> {
+    if issuers.is_empty() {
+        return Err(ContentError::InvalidLicense("this build of the app accepts no license keys".to_string()));
+    }
+
+    let malformed = || ContentError::InvalidLicense("not a license key".to_string());
+    let mut parts = key.trim().split('.');
+    let (Some(PREFIX), Some(payload), Some(signature), None) = (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(malformed());
+    };
+    let payload = URL_SAFE_NO_PAD.decode(payload).map_err(|_| malformed())?;
+    let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| malformed())?;
+    let signature = Signature::from_slice(&signature).map_err(|_| malformed())?;
+
+    if !issuers.iter().any(|issuer| issuer.verify(&payload, &signature).is_ok()) {
+        return Err(ContentError::InvalidLicense("it isn't signed by a trusted issuer".to_string()));
+    }
+    let license: License = serde_json::from_slice(&payload).map_err(|_| malformed())?;
+
+    if license.curriculum != manifest.title {
+        return Err(ContentError::InvalidLicense(format!("this key is for {}", license.curriculum)));
+    }
+    Ok(license)
+}
+
+fn key_bytes(key: &str, what: &str) -> ContentResult<[u8; 32]> {
+    hex::decode(key).ok_or_else(|| ContentError::InvalidLicense(format!("{} must be 64 hex digits", what)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn manifest() -> Manifest {
+        serde_json::from_value(serde_json::json!({
+            "version": "1.0",
+            "title": "Rust for Traders",
+            "description": "",
+            "author": "",
+            "created_at": "2024-01-01",
+            "weeks": [],
+            "requires_license": true
+        }))
+        .unwrap()
+    }
+
+    fn license(expires_on: Option<&str>) -> License {
+        License {
+            curriculum: "Rust for Traders".to_string(),
+            licensee: "ada@example.com".to_string(),
+            issued_on: date("2024-01-01"),
+            expires_on: expires_on.map(date),
+        }
+    }
+
+    #[test]
+    fn test_signed_license_verifies() {
+        let issuer = SigningKey::from_bytes(&[7u8; 32]);
+        let key = license(None).sign(&issuer).unwrap();
+
+        assert!(key.starts_with("GLP1."));
+        let verified = verify(&manifest(), &key, &[issuer.verifying_key()]).unwrap();
+        assert_eq!(verified, license(None));
+        assert_eq!(verified.check(date("2030-01-01")).unwrap(), LicenseStatus::Active);
+    }
+
+    #[test]
+    fn test_bad_keys_are_rejected() {
+        let issuer = SigningKey::from_bytes(&[7u8; 32]);
+        let issuers = [issuer.verifying_key()];
+        let manifest = manifest();
+
+        let forged = license(None).sign(&SigningKey::from_bytes(&[8u8; 32])).unwrap();
+        assert!(matches!(verify(&manifest, &forged, &issuers), Err(ContentError::InvalidLicense(_))));
+
+        // Nothing verifies without a trusted issuer
+        let key = license(None).sign(&issuer).unwrap();
+        assert!(matches!(verify(&manifest, &key, &[]), Err(ContentError::InvalidLicense(_))));
+
+        let mut other = license(None);
+        other.curriculum = "Another Course".to_string();
+        let other = other.sign(&issuer).unwrap();
+        assert!(matches!(verify(&manifest, &other, &issuers), Err(ContentError::InvalidLicense(m)) if m.contains("Another Course")));
+
+        // A payload edited after signing no longer matches the signature
+        let key = license(Some("2024-03-01")).sign(&issuer).unwrap();
+        let parts: Vec<&str> = key.split('.').collect();
+        let edited = URL_SAFE_NO_PAD.encode(
+            serde_json::to_vec(&license(Some("2099-01-01"))).unwrap(),
+        );
+        let tampered = format!("{}.{}.{}", parts[0], edited, parts[2]);
+        assert!(matches!(verify(&manifest, &tampered, &issuers), Err(ContentError::InvalidLicense(_))));
+
+        assert!(matches!(verify(&manifest, "GLP1.abc", &issuers), Err(ContentError::InvalidLicense(_))));
+    }
+
+    #[test]
+    fn test_expiry_and_grace_period() {
+        let issuer = SigningKey::from_bytes(&[7u8; 32]);
+        let key = license(Some("2024-03-01")).sign(&issuer).unwrap();
+
+        let status = |today: &str| license(Some("2024-03-01")).status(date(today));
+        assert_eq!(status("2024-03-01"), LicenseStatus::Active);
+        assert_eq!(status("2024-03-02"), LicenseStatus::Grace { ends_on: date("2024-03-15") });
+        assert_eq!(status("2024-03-16"), LicenseStatus::Expired);
+
+        let verified = verify(&manifest(), &key, &[issuer.verifying_key()]).unwrap();
+        assert!(verified.check(date("2024-03-15")).is_ok());
+        assert!(matches!(
+            verified.check(date("2024-03-16")),
+            Err(ContentError::LicenseExpired(d)) if d == date("2024-03-01")
+        ));
+    }
+}
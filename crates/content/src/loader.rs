@@ -1,11 +1,18 @@
+use crate::encryption::{self, ContentKey};
 use crate::error::{ContentError, ContentResult};
-use crate::manifest::{Challenge, Manifest, Quiz};
+use crate::license::{self, License, LicenseStatus};
+use crate::lecture::{parse_lecture, Lecture};
+use crate::template::ChallengeTemplate;
+use crate::manifest::{Challenge, ContentNode, Manifest, ProjectTrack, Quiz};
+use ed25519_dalek::VerifyingKey;
 use std::fs;
 use std::path::PathBuf;
 
 pub struct ContentLoader {
     content_dir: PathBuf,
     manifest: Manifest,
+    key: Option<ContentKey>,
+    license: Option<License>,
 }
 
 impl ContentLoader {
@@ -25,9 +32,81 @@ impl ContentLoader {
         Ok(Self {
             content_dir,
             manifest,
+            key: None,
+            license: None,
         })
     }
 
+    /// Load a pack, unlocking it with `content_key` (hex) if it's encrypted
+    /// and licensing it with `license_key` if it needs a license. Keys for a
+    /// pack that doesn't need them are ignored. A license key that doesn't
+    /// verify, e.g. after an update changed the issuer, is an
+    /// [`ContentError::InvalidLicense`] error.
+    pub fn open(content_dir: PathBuf, content_key: Option<&str>, license_key: Option<&str>) -> ContentResult<Self> {
+        let mut loader = Self::new(content_dir)?;
+        if let Some(key) = content_key.filter(|_| loader.is_encrypted()) {
+            loader = loader.with_key(ContentKey::from_hex(key)?)?;
+        }
+        if let Some(key) = license_key.filter(|_| loader.requires_license()) {
+            loader = loader.with_license(key)?;
+        }
+        Ok(loader)
+    }
+
+    /// License a pack that needs one with a key signed by an issuer pinned
+    /// in this build. An expired key is kept, so loads report when it expired.
+    pub fn with_license(self, license_key: &str) -> ContentResult<Self> {
+        self.with_license_from(license_key, &license::pinned_issuers()?)
+    }
+
+    /// License a pack with a key signed by one of `issuers`
+    pub fn with_license_from(mut self, license_key: &str, issuers: &[VerifyingKey]) -> ContentResult<Self> {
+        self.license = Some(license::verify(&self.manifest, license_key, issuers)?);
+        Ok(self)
+    }
+
+    /// Whether the pack's content only opens with a license
+    pub fn requires_license(&self) -> bool {
+        self.manifest.requires_license
+    }
+
+    /// The license the pack was opened with
+    pub fn license(&self) -> Option<&License> {
+        self.license.as_ref()
+    }
+
+    /// Whether content can be loaded today, as far as licensing goes
+    pub fn check_license(&self) -> ContentResult<Option<LicenseStatus>> {
+        if !self.requires_license() {
+            return Ok(None);
+        }
+        let license = self.license.as_ref().ok_or(ContentError::LicenseRequired)?;
+        license.check(chrono::Local::now().date_naive()).map(Some)
+    }
+
+    /// Unlock an encrypted pack's sealed files with its content key. Sealed
+    /// files are only ever decrypted in memory, as they're loaded.
+    pub fn with_key(mut self, key: ContentKey) -> ContentResult<Self> {
+        match &self.manifest.encryption {
+            Some(encryption) if encryption.accepts(&key) => {
+                self.key = Some(key);
+                Ok(self)
+            }
+            Some(_) => Err(ContentError::InvalidKey("not the key for this curriculum".to_string())),
+            None => Err(ContentError::InvalidKey("this curriculum isn't encrypted".to_string())),
+        }
+    }
+
+    /// Whether the pack has sealed files
+    pub fn is_encrypted(&self) -> bool {
+        self.manifest.encryption.is_some()
+    }
+
+    /// Whether the pack has sealed files and no key to open them
+    pub fn is_locked(&self) -> bool {
+        self.is_encrypted() && self.key.is_none()
+    }
+
     pub fn get_manifest(&self) -> &Manifest {
         &self.manifest
     }
@@ -37,47 +116,62 @@ impl ContentLoader {
     }
 
     pub fn load_lecture(&self, content_path: &str) -> ContentResult<String> {
-        let path = self.content_dir.join(content_path);
-
-        if !path.exists() {
-            return Err(ContentError::NotFound(format!(
-                "Lecture not found at {:?}",
-                path
-            )));
-        }
+        self.read_content(content_path, "Lecture")
+    }
 
-        let content = fs::read_to_string(&path)?;
-        Ok(content)
+    /// Load a lecture split into Markdown and its embedded blocks
+    pub fn load_lecture_content(&self, content_path: &str) -> ContentResult<Lecture> {
+        Ok(parse_lecture(&self.load_lecture(content_path)?))
     }
 
     pub fn load_quiz(&self, content_path: &str) -> ContentResult<Quiz> {
-        let path = self.content_dir.join(content_path);
-
-        if !path.exists() {
-            return Err(ContentError::NotFound(format!(
-                "Quiz not found at {:?}",
-                path
-            )));
-        }
-
-        let quiz_json = fs::read_to_string(&path)?;
+        let quiz_json = self.read_content(content_path, "Quiz")?;
         let quiz: Quiz = serde_json::from_str(&quiz_json)?;
         Ok(quiz)
     }
 
     pub fn load_challenge(&self, content_path: &str) -> ContentResult<Challenge> {
+        let challenge_json = self.read_content(content_path, "Challenge")?;
+        let challenge: Challenge = serde_json::from_str(&challenge_json)?;
+        Ok(challenge)
+    }
+
+    /// A file a checkpoint names, such as its tests or a rubric, read like
+    /// any other content: licensed, and opened if it's sealed
+    pub fn load_checkpoint_file(&self, content_path: &str) -> ContentResult<String> {
+        self.read_content(content_path, "Checkpoint file")
+    }
+
+    /// Read a content file, opening it if it's sealed. Packs that need a
+    /// license only give up their content with one that hasn't run out.
+    fn read_content(&self, content_path: &str, kind: &str) -> ContentResult<String> {
+        self.check_license()?;
         let path = self.content_dir.join(content_path);
 
         if !path.exists() {
             return Err(ContentError::NotFound(format!(
-                "Challenge not found at {:?}",
-                path
+                "{} not found at {:?}",
+                kind, path
             )));
         }
 
-        let challenge_json = fs::read_to_string(&path)?;
-        let challenge: Challenge = serde_json::from_str(&challenge_json)?;
-        Ok(challenge)
+        if !encryption::is_sealed(content_path) {
+            return Ok(fs::read_to_string(&path)?);
+        }
+        let key = self
+            .key
+            .as_ref()
+            .ok_or_else(|| ContentError::KeyRequired(content_path.to_string()))?;
+        encryption::open(key, content_path, &fs::read(&path)?)
+    }
+
+    /// The template a challenge's crate is built from, or an empty one if
+    /// it doesn't name one
+    pub fn load_template(&self, challenge: &Challenge) -> ContentResult<ChallengeTemplate> {
+        match &challenge.template {
+            Some(name) => ChallengeTemplate::load(&self.content_dir, name),
+            None => Ok(ChallengeTemplate::default()),
+        }
     }
 
     /// Get all node IDs in the manifest
@@ -100,6 +194,25 @@ impl ContentLoader {
             .flat_map(|d| &d.nodes)
             .find(|n| n.id == node_id)
     }
+
+    /// Get project track by ID
+    pub fn get_track(&self, track_id: &str) -> Option<&ProjectTrack> {
+        self.manifest.tracks.iter().find(|t| t.id == track_id)
+    }
+
+    /// Nodes of a project track, in day order
+    pub fn get_track_days(&self, track_id: &str) -> Vec<&ContentNode> {
+        let mut days: Vec<&ContentNode> = self
+            .manifest
+            .weeks
+            .iter()
+            .flat_map(|w| &w.days)
+            .flat_map(|d| &d.nodes)
+            .filter(|n| n.track.as_ref().is_some_and(|step| step.track == track_id))
+            .collect();
+        days.sort_by_key(|n| n.track.as_ref().map(|step| step.day));
+        days
+    }
 }
 
 #[cfg(test)]
@@ -142,6 +255,28 @@ mod tests {
                                     "content_path": "week1/day1/lecture.md",
                                     "skills": ["syntax"],
                                     "prerequisites": []
+                                },
+                                {
+                                    "id": "orderflow-day2",
+                                    "type": "mini-challenge",
+                                    "title": "Matching",
+                                    "description": "Match orders",
+                                    "difficulty": "medium",
+                                    "estimated_minutes": 90,
+                                    "xp_reward": 100,
+                                    "content_path": "week1/day1/orderflow-day2.json",
+                                    "track": { "track": "orderflow", "day": 2 }
+                                },
+                                {
+                                    "id": "orderflow-day1",
+                                    "type": "mini-challenge",
+                                    "title": "Order types",
+                                    "description": "Model orders",
+                                    "difficulty": "medium",
+                                    "estimated_minutes": 90,
+                                    "xp_reward": 100,
+                                    "content_path": "week1/day1/orderflow-day1.json",
+                                    "track": { "track": "orderflow", "day": 1 }
                                 }
                             ]
                         }
@@ -155,6 +290,14 @@ mod tests {
                     "name": "Rust Syntax",
                     "description": "Basic Rust syntax"
                 }
+            ],
+            "tracks": [
+                {
+                    "id": "orderflow",
+                    "title": "Order Flow Simulator",
+                    "description": "Build a deterministic simulator",
+                    "repo_dir": "orderflow-sim"
+                }
             ]
         }"#;
 
@@ -191,13 +334,97 @@ mod tests {
         assert!(lecture.contains("Test Lecture"));
     }
 
+    #[test]
+    fn test_sealed_lecture_needs_the_key() {
+        let content_dir = create_test_content();
+        let key = ContentKey::generate();
+
+        let manifest_path = content_dir.join("manifest.json");
+        let mut manifest: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&manifest_path).unwrap()).unwrap();
+        manifest["encryption"] = serde_json::json!({ "key_check": key.check() });
+        manifest["weeks"][0]["days"][0]["nodes"][0]["content_path"] = "week1/day1/lecture.md.enc".into();
+        fs::write(&manifest_path, manifest.to_string()).unwrap();
+        let sealed = encryption::seal(&key, "week1/day1/lecture.md.enc", b"# Sealed Lecture").unwrap();
+        fs::write(content_dir.join("week1/day1/lecture.md.enc"), sealed).unwrap();
+
+        let loader = ContentLoader::new(content_dir.clone()).unwrap();
+        assert!(loader.is_locked());
+        assert!(matches!(
+            loader.load_lecture("week1/day1/lecture.md.enc"),
+            Err(ContentError::KeyRequired(_))
+        ));
+        // Plain files in the same pack still load
+        assert!(loader.load_lecture("week1/day1/lecture.md").is_ok());
+
+        assert!(matches!(
+            ContentLoader::new(content_dir.clone()).unwrap().with_key(ContentKey::generate()),
+            Err(ContentError::InvalidKey(_))
+        ));
+        let loader = loader.with_key(key).unwrap();
+        assert!(!loader.is_locked());
+        assert_eq!(loader.load_lecture("week1/day1/lecture.md.enc").unwrap(), "# Sealed Lecture");
+    }
+
+    #[test]
+    fn test_licensed_pack_needs_a_current_license() {
+        let content_dir = create_test_content();
+        let issuer = ed25519_dalek::SigningKey::from_bytes(&[3u8; 32]);
+
+        let manifest_path = content_dir.join("manifest.json");
+        let mut manifest: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&manifest_path).unwrap()).unwrap();
+        manifest["requires_license"] = true.into();
+        fs::write(&manifest_path, manifest.to_string()).unwrap();
+
+        let license_for = |expires_on: Option<chrono::NaiveDate>, signer: &ed25519_dalek::SigningKey| {
+            License {
+                curriculum: "Test Course".to_string(),
+                licensee: "ada".to_string(),
+                issued_on: chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                expires_on,
+            }
+            .sign(signer)
+            .unwrap()
+        };
+
+        let loader = ContentLoader::open(content_dir.clone(), None, None).unwrap();
+        assert_eq!(loader.get_manifest().title, "Test Course");
+        assert!(matches!(loader.load_lecture("week1/day1/lecture.md"), Err(ContentError::LicenseRequired)));
+        assert!(matches!(loader.load_checkpoint_file("week1/day1/lecture.md"), Err(ContentError::LicenseRequired)));
+
+        let expired = chrono::NaiveDate::from_ymd_opt(2024, 2, 1).unwrap();
+        let loader = ContentLoader::new(content_dir.clone())
+            .unwrap()
+            .with_license_from(&license_for(Some(expired), &issuer), &[issuer.verifying_key()])
+            .unwrap();
+        assert!(matches!(
+            loader.load_lecture("week1/day1/lecture.md"),
+            Err(ContentError::LicenseExpired(d)) if d == expired
+        ));
+
+        // A stored key the build doesn't trust stops the pack loading
+        let forged = ed25519_dalek::SigningKey::from_bytes(&[4u8; 32]);
+        assert!(matches!(
+            ContentLoader::open(content_dir.clone(), None, Some(&license_for(None, &forged))),
+            Err(ContentError::InvalidLicense(_))
+        ));
+
+        let loader = ContentLoader::new(content_dir)
+            .unwrap()
+            .with_license_from(&license_for(None, &issuer), &[issuer.verifying_key()])
+            .unwrap();
+        assert_eq!(loader.check_license().unwrap(), Some(LicenseStatus::Active));
+        assert!(loader.load_lecture("week1/day1/lecture.md").is_ok());
+    }
+
     #[test]
     fn test_get_all_node_ids() {
         let content_dir = create_test_content();
         let loader = ContentLoader::new(content_dir).unwrap();
 
         let node_ids = loader.get_all_node_ids();
-        assert_eq!(node_ids.len(), 1);
+        assert_eq!(node_ids.len(), 3);
         assert_eq!(node_ids[0], "week1-day1-lecture");
     }
 
@@ -213,4 +440,15 @@ mod tests {
         let missing = loader.get_node_by_id("nonexistent");
         assert!(missing.is_none());
     }
+
+    #[test]
+    fn test_get_track_days_in_day_order() {
+        let content_dir = create_test_content();
+        let loader = ContentLoader::new(content_dir).unwrap();
+
+        assert_eq!(loader.get_track("orderflow").unwrap().repo_dir, "orderflow-sim");
+        let days: Vec<&str> = loader.get_track_days("orderflow").iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(days, vec!["orderflow-day1", "orderflow-day2"]);
+        assert!(loader.get_track_days("missing").is_empty());
+    }
 }
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+use crate::encryption::ContentKey;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Manifest {
@@ -13,6 +15,120 @@ pub struct Manifest {
     pub checkpoints: Vec<Checkpoint>,
     #[serde(default)]
     pub skills: Vec<Skill>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mastery_decay: Option<MasteryDecayConfig>,
+    #[serde(default)]
+    pub tracks: Vec<ProjectTrack>,
+    /// Content only opens with a license key; see [`crate::license`]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub requires_license: bool,
+    /// Set when some content files are sealed; see [`crate::encryption`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<PackEncryption>,
+}
+
+impl Manifest {
+    /// Nodes whose completion satisfies a prerequisite on `node_id`: the node
+    /// itself and every node linked to it by `superseded_by`, whichever way
+    pub fn satisfying_nodes(&self, node_id: &str) -> Vec<String> {
+        let links: Vec<(&str, &str)> = self
+            .weeks
+            .iter()
+            .flat_map(|w| &w.days)
+            .flat_map(|d| &d.nodes)
+            .filter_map(|n| n.superseded_by.as_deref().map(|by| (n.id.as_str(), by)))
+            .collect();
+
+        let mut found = vec![node_id.to_string()];
+        let mut next = 0;
+        while next < found.len() {
+            let id = found[next].clone();
+            for (old, new) in &links {
+                let other = if *old == id {
+                    new
+                } else if *new == id {
+                    old
+                } else {
+                    continue;
+                };
+                if !found.iter().any(|f| f == other) {
+                    found.push(other.to_string());
+                }
+            }
+            next += 1;
+        }
+        found
+    }
+
+    /// Whether a prerequisite on `node_id` is met by the `completed` nodes
+    pub fn prerequisite_met(&self, node_id: &str, completed: &HashSet<String>) -> bool {
+        self.satisfying_nodes(node_id).iter().any(|id| completed.contains(id))
+    }
+}
+
+/// Mastery decay parameters a curriculum can override.
+/// Fields left out of the manifest fall back to the platform defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MasteryDecayConfig {
+    pub grace_period_days: i64,
+    pub decay_rate: f64,
+    pub minimum_score: f64,
+}
+
+impl Default for MasteryDecayConfig {
+    fn default() -> Self {
+        Self {
+            grace_period_days: 3,
+            decay_rate: 0.05,
+            minimum_score: 0.30,
+        }
+    }
+}
+
+impl MasteryDecayConfig {
+    /// Check that the parameters are within sensible bounds
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if self.grace_period_days < 0 {
+            errors.push(format!(
+                "mastery_decay.grace_period_days must be >= 0, got {}",
+                self.grace_period_days
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.decay_rate) {
+            errors.push(format!(
+                "mastery_decay.decay_rate must be between 0.0 and 1.0, got {}",
+                self.decay_rate
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.minimum_score) {
+            errors.push(format!(
+                "mastery_decay.minimum_score must be between 0.0 and 1.0, got {}",
+                self.minimum_score
+            ));
+        }
+
+        errors
+    }
+}
+
+/// How a pack's sealed files are unlocked
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackEncryption {
+    /// SHA-256 of the content key, to recognise the right key by
+    pub key_check: String,
+    /// Where a learner gets the key, shown when it's asked for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_url: Option<String>,
+}
+
+impl PackEncryption {
+    /// Whether `key` is this pack's key
+    pub fn accepts(&self, key: &ContentKey) -> bool {
+        self.key_check.eq_ignore_ascii_case(&key.check())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +162,43 @@ pub struct ContentNode {
     pub skills: Vec<String>,
     #[serde(default)]
     pub prerequisites: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track: Option<TrackStep>,
+    /// Retired from the curriculum. The node stays in the manifest so
+    /// progress on it and prerequisites naming it still resolve.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    /// Node that replaces this one; completing either satisfies a
+    /// prerequisite on the other. Implies `deprecated`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub superseded_by: Option<String>,
+}
+
+impl ContentNode {
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated || self.superseded_by.is_some()
+    }
+}
+
+/// A multi-day project the learner builds up in one repo, e.g. the order
+/// flow simulator from day 1 to day 14. Each day is a challenge node whose
+/// tests cover only what that day adds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectTrack {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    /// Suggested name for the learner's repo directory
+    pub repo_dir: String,
+}
+
+/// Where a node sits in a project track
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct TrackStep {
+    pub track: String,
+    /// 1-based; each day builds on the repo left by the day before
+    pub day: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -63,6 +216,14 @@ pub struct Checkpoint {
     pub prerequisites: Vec<String>,
     #[serde(default)]
     pub rubrics: HashMap<String, String>,
+    /// Paths a repo submitted for the checkpoint must have committed; a
+    /// trailing `/` marks a directory
+    #[serde(default)]
+    pub template: Vec<String>,
+    /// Integration tests, relative to the content directory, run against a
+    /// repo submitted for the checkpoint
+    #[serde(default)]
+    pub tests: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -73,13 +234,18 @@ pub struct Skill {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct Quiz {
     pub id: String,
     pub title: String,
+    /// Countdown for a time-boxed quiz; untimed when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_limit_seconds: Option<u32>,
     pub questions: Vec<Question>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct Question {
     pub id: String,
     pub question: String,
@@ -95,6 +261,22 @@ pub struct Question {
     pub skills: Vec<String>,
 }
 
+impl Question {
+    /// The correct options as graded: the index of the right one, or the
+    /// indices of several sorted and joined with commas
+    pub fn answer_key(&self) -> String {
+        match (&self.correct_answers, self.correct_answer) {
+            (Some(answers), _) => {
+                let mut answers = answers.clone();
+                answers.sort_unstable();
+                answers.iter().map(usize::to_string).collect::<Vec<_>>().join(",")
+            }
+            (None, Some(answer)) => answer.to_string(),
+            (None, None) => String::new(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Challenge {
     pub id: String,
@@ -110,6 +292,15 @@ pub struct Challenge {
     pub difficulty: String,
     #[serde(default)]
     pub skills: Vec<String>,
+    /// Name of a template under `templates/` the challenge's crate is
+    /// built from; without one it has no dependencies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Crates learners may add to the `Cargo.toml` of an exported
+    /// workspace, on top of the template's. Without a list, edits to it
+    /// are ignored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dependencies_allowlist: Option<Vec<String>>,
 }
 
 #[cfg(test)]
@@ -152,6 +343,62 @@ mod tests {
         let node: ContentNode = serde_json::from_str(json).unwrap();
         assert_eq!(node.id, "test-node");
         assert_eq!(node.node_type, "lecture");
+        assert!(!node.is_deprecated());
+    }
+
+    #[test]
+    fn test_superseded_nodes_satisfy_each_other() {
+        let node = |id: &str, superseded_by: Option<&str>| {
+            serde_json::json!({
+                "id": id,
+                "type": "mini-challenge",
+                "title": id,
+                "description": "",
+                "difficulty": "easy",
+                "estimated_minutes": 20,
+                "xp_reward": 25,
+                "content_path": format!("{}.json", id),
+                "superseded_by": superseded_by,
+            })
+        };
+        let json = serde_json::json!({
+            "version": "1.0",
+            "title": "Test Course",
+            "description": "A test course",
+            "author": "Test Author",
+            "created_at": "2024-01-01",
+            "weeks": [{
+                "id": "week1",
+                "title": "Week 1",
+                "description": "",
+                "days": [{
+                    "id": "day1",
+                    "title": "Day 1",
+                    "description": "",
+                    "nodes": [
+                        node("parser-v1", Some("parser-v2")),
+                        node("parser-v2", Some("parser-v3")),
+                        node("parser-v3", None),
+                        node("lexer", None),
+                    ],
+                }],
+            }],
+        });
+        let manifest: Manifest = serde_json::from_value(json).unwrap();
+
+        let mut satisfying = manifest.satisfying_nodes("parser-v3");
+        satisfying.sort();
+        assert_eq!(satisfying, vec!["parser-v1", "parser-v2", "parser-v3"]);
+        assert_eq!(manifest.satisfying_nodes("lexer"), vec!["lexer"]);
+
+        let completed: HashSet<String> = ["parser-v1".to_string()].into();
+        assert!(manifest.prerequisite_met("parser-v3", &completed));
+        assert!(manifest.prerequisite_met("parser-v2", &completed));
+        assert!(!manifest.prerequisite_met("lexer", &completed));
+
+        let retired = &manifest.weeks[0].days[0].nodes[0];
+        assert!(retired.is_deprecated());
+        assert!(serde_json::to_value(retired).unwrap().get("deprecated").is_none());
     }
 
     #[test]
@@ -176,5 +423,78 @@ mod tests {
         assert_eq!(quiz.id, "test-quiz");
         assert_eq!(quiz.questions.len(), 1);
         assert_eq!(quiz.questions[0].correct_answer, Some(1));
+        assert_eq!(quiz.questions[0].answer_key(), "1");
+
+        let mut several = quiz.questions[0].clone();
+        several.correct_answers = Some(vec![2, 0]);
+        assert_eq!(several.answer_key(), "0,2");
+    }
+
+    #[test]
+    fn test_track_deserialization() {
+        let json = r#"{
+            "version": "1.0",
+            "title": "Bootcamp",
+            "description": "An intensive course",
+            "author": "Test Author",
+            "created_at": "2024-01-01",
+            "weeks": [],
+            "tracks": [
+                {
+                    "id": "orderflow",
+                    "title": "Order Flow Simulator",
+                    "description": "Build a deterministic simulator",
+                    "repo_dir": "orderflow-sim"
+                }
+            ]
+        }"#;
+
+        let manifest: Manifest = serde_json::from_str(json).unwrap();
+        assert_eq!(manifest.tracks[0].repo_dir, "orderflow-sim");
+
+        let node: ContentNode = serde_json::from_str(
+            r#"{
+                "id": "orderflow-day1",
+                "type": "mini-challenge",
+                "title": "Day 1",
+                "description": "Order types",
+                "difficulty": "medium",
+                "estimated_minutes": 90,
+                "xp_reward": 100,
+                "content_path": "week1/day1/orderflow.json",
+                "track": { "track": "orderflow", "day": 1 }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(node.track, Some(TrackStep { track: "orderflow".to_string(), day: 1 }));
+    }
+
+    #[test]
+    fn test_mastery_decay_partial_override() {
+        let json = r#"{
+            "version": "1.0",
+            "title": "Bootcamp",
+            "description": "An intensive course",
+            "author": "Test Author",
+            "created_at": "2024-01-01",
+            "weeks": [],
+            "mastery_decay": { "decay_rate": 0.15 }
+        }"#;
+
+        let manifest: Manifest = serde_json::from_str(json).unwrap();
+        let decay = manifest.mastery_decay.unwrap();
+        assert_eq!(decay.decay_rate, 0.15);
+        assert_eq!(decay.grace_period_days, 3);
+        assert!(decay.validate().is_empty());
+    }
+
+    #[test]
+    fn test_mastery_decay_validation() {
+        let decay = MasteryDecayConfig {
+            grace_period_days: -1,
+            decay_rate: 1.5,
+            minimum_score: 0.3,
+        };
+        assert_eq!(decay.validate().len(), 2);
     }
 }
@@ -0,0 +1,199 @@
+//! Challenge templates
+//!
+//! A content pack can keep the crate setup its challenges share under
+//! `templates/<name>/` instead of repeating it per challenge, and a
+//! challenge names the template it's built from. `template.json` holds the
+//! edition, the dependencies, as they'd be written in `Cargo.toml`, and
+//! optionally the sandbox image to verify in; a `Dockerfile` alongside it
+//! is how that image is built with the dependencies vendored, since the
+//! sandbox has no network. Anything under `files/` is copied into the crate
+//! as is, e.g. extra modules or a `build.rs`.
+//!
+//! ```json
+//! {
+//!   "edition": "2021",
+//!   "dependencies": { "serde": { "version": "1", "features": ["derive"] }, "rand": "0.8" },
+//!   "image": "glp-sandbox-serde:latest"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{ContentError, ContentResult};
+
+/// Directory of a content pack that templates live in
+pub const TEMPLATES_DIR: &str = "templates";
+
+const TEMPLATE_FILE: &str = "template.json";
+const FILES_DIR: &str = "files";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChallengeTemplate {
+    #[serde(skip)]
+    pub name: String,
+    #[serde(default = "default_edition")]
+    pub edition: String,
+    /// Crate name to its version, or to a table such as
+    /// `{ "version": "1", "features": ["derive"] }`
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, Value>,
+    /// Sandbox image to verify in instead of the default one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// Files copied into the crate, if the template has any
+    #[serde(skip)]
+    pub files_dir: Option<PathBuf>,
+}
+
+fn default_edition() -> String {
+    "2021".to_string()
+}
+
+impl Default for ChallengeTemplate {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            edition: default_edition(),
+            dependencies: BTreeMap::new(),
+            image: None,
+            files_dir: None,
+        }
+    }
+}
+
+impl ChallengeTemplate {
+    /// Read the template called `name` from a content pack
+    pub fn load(content_dir: &Path, name: &str) -> ContentResult<Self> {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(ContentError::Validation(format!("Invalid template name: {}", name)));
+        }
+        let dir = content_dir.join(TEMPLATES_DIR).join(name);
+        let path = dir.join(TEMPLATE_FILE);
+        if !path.exists() {
+            return Err(ContentError::NotFound(format!("Template not found at {:?}", path)));
+        }
+
+        let mut template: ChallengeTemplate = serde_json::from_str(&fs::read_to_string(&path)?)?;
+        template.name = name.to_string();
+        let files_dir = dir.join(FILES_DIR);
+        template.files_dir = files_dir.is_dir().then_some(files_dir);
+        Ok(template)
+    }
+
+    /// Names the dependencies are imported under in Rust code
+    pub fn crate_names(&self) -> Vec<String> {
+        self.dependencies.keys().map(|name| name.replace('-', "_")).collect()
+    }
+
+    pub fn cargo_toml(&self, package_name: &str) -> ContentResult<String> {
+        let mut toml = format!(
+            "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = {}\n\n[dependencies]\n",
+            package_name,
+            toml_string(&self.edition)
+        );
+        for (name, spec) in &self.dependencies {
+            if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                return Err(ContentError::Validation(format!("Invalid dependency name in template: {}", name)));
+            }
+            let spec = toml_value(spec).ok_or_else(|| {
+                ContentError::Validation(format!("Dependency {} must be a version or a table of settings", name))
+            })?;
+            toml.push_str(&format!("{} = {}\n", name, spec));
+        }
+        Ok(toml)
+    }
+
+    /// Write the crate into `dir`: the template's files, then `Cargo.toml`
+    pub fn write_crate(&self, dir: &Path, package_name: &str) -> ContentResult<()> {
+        let cargo_toml = self.cargo_toml(package_name)?;
+        fs::create_dir_all(dir)?;
+        if let Some(files_dir) = &self.files_dir {
+            copy_dir(files_dir, dir)?;
+        }
+        fs::write(dir.join("Cargo.toml"), cargo_toml)?;
+        Ok(())
+    }
+}
+
+fn toml_string(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+fn toml_key(key: &str) -> String {
+    if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        key.to_string()
+    } else {
+        toml_string(key)
+    }
+}
+
+/// A dependency spec as a TOML value. Only strings, booleans, numbers,
+/// arrays and tables of those have a place in `Cargo.toml`.
+fn toml_value(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(toml_string(text)),
+        Value::Bool(flag) => Some(flag.to_string()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Array(items) => {
+            let items: Option<Vec<String>> = items.iter().map(toml_value).collect();
+            Some(format!("[{}]", items?.join(", ")))
+        }
+        Value::Object(fields) => {
+            let fields: Option<Vec<String>> = fields
+                .iter()
+                .map(|(key, value)| Some(format!("{} = {}", toml_key(key), toml_value(value)?)))
+                .collect();
+            Some(format!("{{ {} }}", fields?.join(", ")))
+        }
+        Value::Null => None,
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> ContentResult<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_writes_crate() {
+        let content_dir = tempfile::tempdir().unwrap();
+        let template_dir = content_dir.path().join("templates").join("serde-json");
+        fs::create_dir_all(template_dir.join("files").join("src")).unwrap();
+        fs::write(
+            template_dir.join("template.json"),
+            r#"{"dependencies": {"serde-json": "1", "serde": {"version": "1", "features": ["derive"]}}}"#,
+        )
+        .unwrap();
+        fs::write(template_dir.join("files").join("src").join("data.rs"), "pub const N: u8 = 1;").unwrap();
+
+        let template = ChallengeTemplate::load(content_dir.path(), "serde-json").unwrap();
+        assert_eq!(template.crate_names(), vec!["serde", "serde_json"]);
+
+        let out = tempfile::tempdir().unwrap();
+        template.write_crate(out.path(), "challenge_demo").unwrap();
+        let cargo_toml = fs::read_to_string(out.path().join("Cargo.toml")).unwrap();
+        assert!(cargo_toml.contains("edition = \"2021\""));
+        assert!(cargo_toml.contains("serde = { features = [\"derive\"], version = \"1\" }\n"));
+        assert!(cargo_toml.contains("serde-json = \"1\"\n"));
+        assert!(out.path().join("src").join("data.rs").exists());
+
+        assert!(ChallengeTemplate::load(content_dir.path(), "../escape").is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::encryption;
 use crate::error::{ContentError, ContentResult};
 use crate::loader::ContentLoader;
 use crate::manifest::Manifest;
@@ -35,6 +36,36 @@ impl ContentValidator {
             }
         }
 
+        // Validate replacements: each names another node, and following them
+        // from any node ends rather than coming back round
+        let replacements: std::collections::HashMap<&str, &str> = manifest
+            .weeks
+            .iter()
+            .flat_map(|w| &w.days)
+            .flat_map(|d| &d.nodes)
+            .filter_map(|n| n.superseded_by.as_deref().map(|by| (n.id.as_str(), by)))
+            .collect();
+        for (&node_id, &replacement) in &replacements {
+            if !all_node_ids.contains(replacement) {
+                errors.push(format!(
+                    "Node '{}' is superseded by unknown node '{}'",
+                    node_id, replacement
+                ));
+                continue;
+            }
+            let mut current = replacement;
+            for _ in 0..replacements.len() {
+                if current == node_id {
+                    errors.push(format!("Node '{}' is superseded by itself through its replacements", node_id));
+                    break;
+                }
+                match replacements.get(current) {
+                    Some(next) => current = next,
+                    None => break,
+                }
+            }
+        }
+
         // Validate skill references
         let all_skill_ids: HashSet<String> =
             manifest.skills.iter().map(|s| s.id.clone()).collect();
@@ -84,6 +115,62 @@ impl ContentValidator {
             }
         }
 
+        // Validate project tracks: each day is a challenge in a known track,
+        // and a track's days run 1, 2, 3... with no gaps or repeats
+        let mut track_days: std::collections::HashMap<&str, Vec<u32>> =
+            manifest.tracks.iter().map(|t| (t.id.as_str(), Vec::new())).collect();
+        for week in &manifest.weeks {
+            for day in &week.days {
+                for node in &day.nodes {
+                    let Some(step) = &node.track else {
+                        continue;
+                    };
+                    match track_days.get_mut(step.track.as_str()) {
+                        Some(days) => days.push(step.day),
+                        None => errors.push(format!(
+                            "Node '{}' references unknown track '{}'",
+                            node.id, step.track
+                        )),
+                    }
+                    if node.node_type != "mini-challenge" {
+                        errors.push(format!(
+                            "Node '{}' is in track '{}' but is not a mini-challenge",
+                            node.id, step.track
+                        ));
+                    }
+                }
+            }
+        }
+        for track in &manifest.tracks {
+            let mut days = track_days.remove(track.id.as_str()).unwrap_or_default();
+            days.sort_unstable();
+            if days.iter().zip(1..).any(|(day, expected)| *day != expected) {
+                errors.push(format!(
+                    "Track '{}' days must run 1 to {} without gaps, got {:?}",
+                    track.id,
+                    days.len(),
+                    days
+                ));
+            }
+        }
+
+        // Validate mastery decay overrides
+        if let Some(decay) = &manifest.mastery_decay {
+            errors.extend(decay.validate());
+        }
+
+        // Sealed files can only be opened if the pack says how
+        if manifest.encryption.is_none() {
+            for node in manifest.weeks.iter().flat_map(|w| &w.days).flat_map(|d| &d.nodes) {
+                if encryption::is_sealed(&node.content_path) {
+                    errors.push(format!(
+                        "Node '{}' has sealed content {} but the manifest has no encryption section",
+                        node.id, node.content_path
+                    ));
+                }
+            }
+        }
+
         // Check for duplicate IDs
         let mut seen_ids = HashSet::new();
         for week in &manifest.weeks {
@@ -114,6 +201,39 @@ impl ContentValidator {
                     let path = loader.content_dir().join(&node.content_path);
                     if !path.exists() {
                         errors.push(format!("Missing content file: {}", node.content_path));
+                    } else if encryption::is_sealed(&node.content_path) && loader.is_locked() {
+                        // Can't be read without the key, which authors check with
+                    } else if node.node_type == "lecture" {
+                        let lecture = loader.load_lecture_content(&node.content_path)?;
+                        for problem in &lecture.problems {
+                            errors.push(format!("{}: {}", node.content_path, problem));
+                        }
+                        for node_id in lecture.challenge_refs() {
+                            if loader.get_node_by_id(node_id).is_none() {
+                                errors.push(format!(
+                                    "{}: challenge-ref to unknown node {}",
+                                    node.content_path, node_id
+                                ));
+                            }
+                        }
+                    } else if node.node_type == "mini-challenge" {
+                        let challenge = loader.load_challenge(&node.content_path)?;
+                        if let Err(e) = loader
+                            .load_template(&challenge)
+                            .and_then(|template| template.cargo_toml(&challenge.id))
+                        {
+                            errors.push(format!("{}: {}", node.content_path, e));
+                        }
+                        for name in challenge.dependencies_allowlist.iter().flatten() {
+                            if name.is_empty()
+                                || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+                            {
+                                errors.push(format!(
+                                    "{}: invalid crate name in dependencies_allowlist: {:?}",
+                                    node.content_path, name
+                                ));
+                            }
+                        }
                     }
                 }
             }
@@ -209,7 +329,7 @@ impl ContentValidator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::manifest::{ContentNode, Day, Skill, Week};
+    use crate::manifest::{ContentNode, Day, ProjectTrack, Skill, TrackStep, Week};
 
     fn create_test_manifest() -> Manifest {
         Manifest {
@@ -238,6 +358,9 @@ mod tests {
                             content_path: "test.md".to_string(),
                             skills: vec!["syntax".to_string()],
                             prerequisites: vec![],
+                            track: None,
+                            deprecated: false,
+                            superseded_by: None,
                         },
                         ContentNode {
                             id: "node2".to_string(),
@@ -250,6 +373,9 @@ mod tests {
                             content_path: "test.json".to_string(),
                             skills: vec!["syntax".to_string()],
                             prerequisites: vec!["node1".to_string()],
+                            track: None,
+                            deprecated: false,
+                            superseded_by: None,
                         },
                     ],
                 }],
@@ -260,6 +386,10 @@ mod tests {
                 name: "Syntax".to_string(),
                 description: "Test".to_string(),
             }],
+            mastery_decay: None,
+            tracks: vec![],
+            requires_license: false,
+            encryption: None,
         }
     }
 
@@ -281,6 +411,21 @@ mod tests {
         assert!(errors[0].contains("invalid prerequisite"));
     }
 
+    #[test]
+    fn test_sealed_content_needs_an_encryption_section() {
+        let mut manifest = create_test_manifest();
+        manifest.weeks[0].days[0].nodes[0].content_path = "test.md.enc".to_string();
+
+        let errors = ContentValidator::validate_manifest(&manifest).unwrap_err();
+        assert!(errors[0].contains("no encryption section"));
+
+        manifest.encryption = Some(crate::manifest::PackEncryption {
+            key_check: crate::ContentKey::generate().check(),
+            key_url: None,
+        });
+        assert!(ContentValidator::validate_manifest(&manifest).is_ok());
+    }
+
     #[test]
     fn test_validate_invalid_skill() {
         let mut manifest = create_test_manifest();
@@ -303,6 +448,46 @@ mod tests {
         assert!(errors[0].contains("invalid difficulty"));
     }
 
+    #[test]
+    fn test_validate_replacements() {
+        let mut manifest = create_test_manifest();
+        manifest.weeks[0].days[0].nodes[0].superseded_by = Some("node2".to_string());
+        assert!(ContentValidator::validate_manifest(&manifest).is_ok());
+
+        manifest.weeks[0].days[0].nodes[1].superseded_by = Some("node1".to_string());
+        let errors = ContentValidator::validate_manifest(&manifest).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|e| e.contains("superseded by itself")));
+
+        manifest.weeks[0].days[0].nodes[1].superseded_by = Some("node3".to_string());
+        let errors = ContentValidator::validate_manifest(&manifest).unwrap_err();
+        assert_eq!(errors, vec!["Node 'node2' is superseded by unknown node 'node3'"]);
+    }
+
+    #[test]
+    fn test_validate_track_days() {
+        let mut manifest = create_test_manifest();
+        manifest.tracks = vec![ProjectTrack {
+            id: "orderflow".to_string(),
+            title: "Order Flow".to_string(),
+            description: "Test".to_string(),
+            repo_dir: "orderflow-sim".to_string(),
+        }];
+        let node = &mut manifest.weeks[0].days[0].nodes[1];
+        node.node_type = "mini-challenge".to_string();
+        node.track = Some(TrackStep { track: "orderflow".to_string(), day: 1 });
+        assert!(ContentValidator::validate_manifest(&manifest).is_ok());
+
+        manifest.weeks[0].days[0].nodes[1].track = Some(TrackStep { track: "orderflow".to_string(), day: 2 });
+        let errors = ContentValidator::validate_manifest(&manifest).unwrap_err();
+        assert!(errors[0].contains("without gaps"));
+
+        manifest.weeks[0].days[0].nodes[0].track = Some(TrackStep { track: "other".to_string(), day: 1 });
+        let errors = ContentValidator::validate_manifest(&manifest).unwrap_err();
+        assert!(errors.iter().any(|e| e.contains("unknown track")));
+        assert!(errors.iter().any(|e| e.contains("not a mini-challenge")));
+    }
+
     #[test]
     fn test_check_no_circular_dependencies() {
         let manifest = create_test_manifest();
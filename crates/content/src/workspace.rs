@@ -0,0 +1,305 @@
+//! Challenge workspaces
+//!
+//! A challenge written out as a standalone cargo project, for learners who
+//! would rather work in their own editor. `src/lib.rs` holds the starter
+//! code followed by the challenge's tests, so `cargo test` works as is;
+//! submitting takes back only the code above the tests, since the
+//! curriculum's copy of the tests is what counts.
+//!
+//! The export records checksums of the files the learner isn't meant to
+//! change, so a submission can report what was edited outside the allowed
+//! set for an instructor to look at.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::encryption;
+use crate::error::{ContentError, ContentResult};
+use crate::manifest::{Challenge, ContentNode};
+use crate::template::ChallengeTemplate;
+
+/// Line separating the learner's code from the challenge's tests
+pub const TESTS_MARKER: &str =
+    "// ---- Challenge tests: submissions are verified with the curriculum's copy, so edits below are ignored ----";
+
+/// Checksum key for the tests at the end of `src/lib.rs`
+pub const TESTS_KEY: &str = "src/lib.rs (tests)";
+
+const WORKSPACE_FILE: &str = ".glp-challenge.json";
+
+/// Which challenge a workspace was exported for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceInfo {
+    pub node_id: String,
+    pub challenge_id: String,
+    /// SHA-256 of each file from the template, by path within the
+    /// workspace, and of the tests under [`TESTS_KEY`]. Empty for
+    /// workspaces exported before these were kept.
+    #[serde(default)]
+    pub checksums: BTreeMap<String, String>,
+}
+
+/// Package name for a node's crate, e.g. `week1-day2` becomes `week1_day2`
+pub fn crate_name(node_id: &str) -> String {
+    node_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect()
+}
+
+/// Write `challenge` into `dir` as a cargo project built from `template`.
+/// Refuses to overwrite an existing `src/lib.rs`, which may hold the
+/// learner's work. A sealed challenge's tests are left out, so an export
+/// never holds a paid pack's content in the clear beyond the starter code.
+pub fn export_workspace(
+    dir: &Path,
+    node: &ContentNode,
+    challenge: &Challenge,
+    template: &ChallengeTemplate,
+) -> ContentResult<()> {
+    let lib_rs = dir.join("src").join("lib.rs");
+    if lib_rs.exists() {
+        return Err(ContentError::Validation(format!(
+            "{} already exists; export into an empty directory",
+            lib_rs.display()
+        )));
+    }
+    template.write_crate(dir, &format!("challenge_{}", crate_name(&node.id)))?;
+    fs::create_dir_all(dir.join("src"))?;
+    let sealed = encryption::is_sealed(&node.content_path);
+    let tests = format!("{}\n\n{}\n", TESTS_MARKER, challenge.test_code.trim_end());
+    if sealed {
+        fs::write(&lib_rs, format!("{}\n", challenge.starter_code.trim_end()))?;
+    } else {
+        fs::write(&lib_rs, format!("{}\n\n{}", challenge.starter_code.trim_end(), tests))?;
+    }
+    fs::write(dir.join("README.md"), readme(challenge, sealed))?;
+    fs::write(dir.join(".gitignore"), "/target\n")?;
+
+    let mut template_files = vec!["Cargo.toml".to_string()];
+    if let Some(files_dir) = &template.files_dir {
+        collect_files(files_dir, "", &mut template_files)?;
+    }
+    let mut checksums = BTreeMap::new();
+    for path in template_files.into_iter().filter(|path| path != "src/lib.rs") {
+        checksums.insert(path.clone(), checksum(&fs::read(dir.join(&path))?));
+    }
+    if !sealed {
+        checksums.insert(TESTS_KEY.to_string(), checksum(tests.as_bytes()));
+    }
+
+    let info = WorkspaceInfo { node_id: node.id.clone(), challenge_id: challenge.id.clone(), checksums };
+    fs::write(dir.join(WORKSPACE_FILE), serde_json::to_string_pretty(&info)?)?;
+    Ok(())
+}
+
+/// The challenge an exported workspace is for, and the learner's code from
+/// its `src/lib.rs` without the tests
+pub fn read_workspace(dir: &Path) -> ContentResult<(WorkspaceInfo, String)> {
+    let info_path = dir.join(WORKSPACE_FILE);
+    if !info_path.exists() {
+        return Err(ContentError::NotFound(format!("{} is not an exported challenge", dir.display())));
+    }
+    let info: WorkspaceInfo = serde_json::from_str(&fs::read_to_string(info_path)?)?;
+
+    let lib_rs = fs::read_to_string(dir.join("src").join("lib.rs"))?;
+    let code = match lib_rs.find(TESTS_MARKER) {
+        Some(end) => lib_rs[..end].trim_end().to_string(),
+        None => lib_rs,
+    };
+    Ok((info, code))
+}
+
+/// Files of an exported workspace changed outside the learner's code: files
+/// from the template that were edited or removed, the tests below
+/// [`TESTS_MARKER`], and Rust files that weren't exported. `allowed` are
+/// paths the learner may change, such as `Cargo.toml` when the challenge
+/// lets them add dependencies.
+pub fn edited_outside(dir: &Path, info: &WorkspaceInfo, allowed: &[&str]) -> ContentResult<Vec<String>> {
+    if info.checksums.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut edited = Vec::new();
+    for (path, expected) in &info.checksums {
+        if allowed.contains(&path.as_str()) {
+            continue;
+        }
+        let actual = if path == TESTS_KEY {
+            let lib_rs = fs::read_to_string(dir.join("src").join("lib.rs"))?;
+            lib_rs.find(TESTS_MARKER).map(|start| checksum(&lib_rs.as_bytes()[start..]))
+        } else {
+            fs::read(dir.join(path)).ok().map(|bytes| checksum(&bytes))
+        };
+        if actual.as_ref() != Some(expected) {
+            edited.push(path.clone());
+        }
+    }
+
+    let mut files = Vec::new();
+    collect_files(dir, "", &mut files)?;
+    for path in files {
+        let added = path.ends_with(".rs") && path != "src/lib.rs" && !info.checksums.contains_key(&path);
+        if added && !allowed.contains(&path.as_str()) {
+            edited.push(path);
+        }
+    }
+    Ok(edited)
+}
+
+/// The `Cargo.toml` of an exported workspace, for challenges that let
+/// learners add dependencies
+pub fn read_cargo_toml(dir: &Path) -> ContentResult<String> {
+    Ok(fs::read_to_string(dir.join("Cargo.toml"))?)
+}
+
+fn checksum(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Paths of the files under `dir`, relative to it and separated by `/`.
+/// Build output and hidden entries, like `.git`, are skipped.
+fn collect_files(dir: &Path, prefix: &str, files: &mut Vec<String>) -> ContentResult<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') || (prefix.is_empty() && name == "target") {
+            continue;
+        }
+        let path = format!("{}{}", prefix, name);
+        if entry.file_type()?.is_dir() {
+            collect_files(&entry.path(), &format!("{}/", path), files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn readme(challenge: &Challenge, sealed: bool) -> String {
+    let dependencies = match &challenge.dependencies_allowlist {
+        Some(allowed) if !allowed.is_empty() => format!(
+            "\n## Dependencies\n\nYou may add these crates to `Cargo.toml`: {}.\n\
+             Other dependencies aren't accepted, and other changes to it are ignored.\n",
+            allowed.iter().map(|name| format!("`{}`", name)).collect::<Vec<_>>().join(", ")
+        ),
+        _ => String::new(),
+    };
+    let working_on_it = if sealed {
+        "Write your solution in `src/lib.rs`. This curriculum's tests aren't\n\
+         exported, so add your own to check it with `cargo test`. Submit it for XP\n\
+         by running\n\n\
+         ```sh\nglp submit\n```\n\n\
+         in this directory, or from the app, which runs the challenge's tests.\n"
+    } else {
+        "Write your solution in `src/lib.rs`, above the challenge's tests, and run\n\
+         them with `cargo test`. When they pass, submit it for XP by running\n\n\
+         ```sh\nglp submit\n```\n\n\
+         in this directory, or from the app. Only the code above the tests is\n\
+         submitted.\n"
+    };
+    format!(
+        "# {}\n\n{}\n\n## Requirements\n\n{}\n\n## Working on it\n\n{}{}",
+        challenge.title,
+        challenge.description.trim_end(),
+        challenge.instructions.trim_end(),
+        working_on_it,
+        dependencies,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node() -> ContentNode {
+        serde_json::from_value(serde_json::json!({
+            "id": "week1-day1-challenge",
+            "type": "mini-challenge",
+            "title": "Fibonacci",
+            "description": "",
+            "difficulty": "Easy",
+            "estimated_minutes": 30,
+            "xp_reward": 50,
+            "content_path": "week1/day1/challenge.json"
+        }))
+        .unwrap()
+    }
+
+    fn challenge() -> Challenge {
+        serde_json::from_value(serde_json::json!({
+            "id": "fibonacci",
+            "title": "Fibonacci",
+            "description": "Compute Fibonacci numbers.",
+            "instructions": "Implement `fibonacci`.",
+            "starter_code": "pub fn fibonacci(n: u32) -> u64 {\n    todo!()\n}\n",
+            "test_code": "#[cfg(test)]\nmod tests {\n    use super::*;\n}\n",
+            "difficulty": "Easy"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_exported_workspace_reads_back_without_tests() {
+        let dir = tempfile::tempdir().unwrap();
+        export_workspace(dir.path(), &node(), &challenge(), &ChallengeTemplate::default()).unwrap();
+
+        let lib_rs = fs::read_to_string(dir.path().join("src/lib.rs")).unwrap();
+        assert!(lib_rs.contains("mod tests"));
+        assert!(fs::read_to_string(dir.path().join("Cargo.toml"))
+            .unwrap()
+            .contains("challenge_week1_day1_challenge"));
+
+        fs::write(dir.path().join("src/lib.rs"), lib_rs.replace("todo!()", "0")).unwrap();
+        let (info, code) = read_workspace(dir.path()).unwrap();
+        assert_eq!(info.node_id, "week1-day1-challenge");
+        assert_eq!(code, "pub fn fibonacci(n: u32) -> u64 {\n    0\n}");
+
+        assert!(edited_outside(dir.path(), &info, &[]).unwrap().is_empty());
+
+        // Exporting again would clobber the solution
+        assert!(matches!(
+            export_workspace(dir.path(), &node(), &challenge(), &ChallengeTemplate::default()),
+            Err(ContentError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_sealed_challenge_exports_without_its_tests() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut node = node();
+        node.content_path = "week1/day1/challenge.json.enc".to_string();
+        export_workspace(dir.path(), &node, &challenge(), &ChallengeTemplate::default()).unwrap();
+
+        let lib_rs = fs::read_to_string(dir.path().join("src/lib.rs")).unwrap();
+        assert!(!lib_rs.contains("mod tests"));
+        assert!(!lib_rs.contains(TESTS_MARKER));
+
+        let (info, code) = read_workspace(dir.path()).unwrap();
+        assert!(!info.checksums.contains_key(TESTS_KEY));
+        assert_eq!(code, "pub fn fibonacci(n: u32) -> u64 {\n    todo!()\n}\n");
+        assert!(edited_outside(dir.path(), &info, &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_edits_outside_the_learners_code_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        export_workspace(dir.path(), &node(), &challenge(), &ChallengeTemplate::default()).unwrap();
+        let lib_rs = fs::read_to_string(dir.path().join("src/lib.rs")).unwrap();
+        fs::write(dir.path().join("src/lib.rs"), lib_rs.replace("use super::*;", "")).unwrap();
+        fs::write(dir.path().join("src/helper.rs"), "pub fn help() {}").unwrap();
+        let cargo_toml = fs::read_to_string(dir.path().join("Cargo.toml")).unwrap();
+        fs::write(dir.path().join("Cargo.toml"), format!("{}rand = \"0.8\"\n", cargo_toml)).unwrap();
+        fs::write(dir.path().join("README.md"), "notes").unwrap();
+
+        let (info, _) = read_workspace(dir.path()).unwrap();
+        assert_eq!(
+            edited_outside(dir.path(), &info, &[]).unwrap(),
+            vec!["Cargo.toml".to_string(), TESTS_KEY.to_string(), "src/helper.rs".to_string()]
+        );
+        assert_eq!(edited_outside(dir.path(), &info, &["Cargo.toml"]).unwrap().len(), 2);
+    }
+}
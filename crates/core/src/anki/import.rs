@@ -0,0 +1,412 @@
+//! Flashcard import
+//!
+//! Reads a deck from a CSV file, front and back in the first two columns,
+//! or from an Anki "Notes in Plain Text" export, whose header lines say how
+//! the file is laid out. Every card is checked before anything is stored, so
+//! a dry run can show what will come in and what's wrong with the rest.
+//! Imported cards are reviewed under one skill, each with its own review
+//! item, due straight away.
+
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+use crate::db::error::{DbError, DbResult};
+use crate::db::in_savepoint;
+use crate::db::repos::{FlashcardRepository, ReviewRepository};
+use crate::models::{Flashcard, ReviewItem};
+
+/// Most cards one file may hold
+pub const MAX_IMPORT_CARDS: usize = 5_000;
+
+/// Longest a card's front or back may be, in bytes
+pub const MAX_FIELD_LEN: usize = 10_000;
+
+#[derive(Error, Debug)]
+pub enum ImportError {
+    #[error(transparent)]
+    Db(#[from] DbError),
+
+    #[error("Invalid deck: {0}")]
+    InvalidDeck(String),
+}
+
+pub type ImportResult<T> = Result<T, ImportError>;
+
+/// A card read from the file, not yet stored
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct CardDraft {
+    /// Line the card starts on
+    pub line: u32,
+    pub front: String,
+    pub back: String,
+    pub tags: Vec<String>,
+}
+
+/// A row that can't be imported, and why
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ImportIssue {
+    pub line: u32,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ParsedDeck {
+    pub cards: Vec<CardDraft>,
+    pub issues: Vec<ImportIssue>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportSummary {
+    pub imported: Vec<Flashcard>,
+    /// Cards already imported for the skill, left alone
+    pub duplicates: Vec<CardDraft>,
+}
+
+/// How the file is laid out, from Anki's header lines or guessed for CSV
+struct Layout {
+    separator: char,
+    html: bool,
+    /// 0-based columns that aren't note fields
+    reserved: Vec<usize>,
+    tags: Option<usize>,
+}
+
+/// Read and check the cards in a CSV file or Anki text export
+pub fn parse_deck(text: &str) -> ImportResult<ParsedDeck> {
+    let text = text.trim_start_matches('\u{feff}');
+    let mut directives = HashMap::new();
+    let mut header_lines = 0;
+    for line in text.lines() {
+        let Some((key, value)) = line.strip_prefix('#').and_then(|line| line.split_once(':')) else {
+            break;
+        };
+        directives.insert(key.trim().to_ascii_lowercase(), value.trim().to_string());
+        header_lines += 1;
+    }
+    let body: String = text.lines().skip(header_lines).map(|line| format!("{}\n", line)).collect();
+    let layout = layout(&directives, &body)?;
+    let records = split_records(&body, layout.separator);
+
+    let mut deck = ParsedDeck::default();
+    let mut fronts: HashMap<String, u32> = HashMap::new();
+    for (index, (offset, fields)) in records.into_iter().enumerate() {
+        let line = (header_lines + offset + 1) as u32;
+        if fields.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        if index == 0 && directives.is_empty() && is_header_row(&fields) {
+            continue;
+        }
+        if deck.cards.len() + deck.issues.len() >= MAX_IMPORT_CARDS {
+            return Err(ImportError::InvalidDeck(format!("a file can hold at most {} cards", MAX_IMPORT_CARDS)));
+        }
+
+        let tags = layout
+            .tags
+            .and_then(|column| fields.get(column))
+            .map(|tags| tags.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default();
+        let mut note_fields = fields
+            .iter()
+            .enumerate()
+            .filter(|(column, _)| !layout.reserved.contains(column))
+            .map(|(_, field)| if layout.html { html_to_text(field) } else { field.trim().to_string() });
+        let front = note_fields.next().unwrap_or_default();
+        let back = note_fields.next().unwrap_or_default();
+
+        let problem = if front.is_empty() {
+            Some("the front is empty".to_string())
+        } else if back.is_empty() {
+            Some("the back is empty".to_string())
+        } else if front.len() > MAX_FIELD_LEN || back.len() > MAX_FIELD_LEN {
+            Some(format!("sides can be at most {} characters", MAX_FIELD_LEN))
+        } else {
+            fronts.get(&front).map(|first| format!("same front as line {}", first))
+        };
+        match problem {
+            Some(message) => deck.issues.push(ImportIssue { line, message }),
+            None => {
+                fronts.insert(front.clone(), line);
+                deck.cards.push(CardDraft { line, front, back, tags });
+            }
+        }
+    }
+
+    if deck.cards.is_empty() && deck.issues.is_empty() {
+        return Err(ImportError::InvalidDeck("no cards found".to_string()));
+    }
+    Ok(deck)
+}
+
+/// Split `cards` into those new to a skill and those whose front was
+/// imported for it before
+pub fn split_new(
+    conn: &Connection,
+    user_id: &str,
+    curriculum_id: Option<&str>,
+    skill_id: &str,
+    cards: &[CardDraft],
+) -> DbResult<(Vec<CardDraft>, Vec<CardDraft>)> {
+    let existing: HashSet<String> = FlashcardRepository::get_for_skill(conn, user_id, curriculum_id, skill_id)?
+        .into_iter()
+        .map(|card| card.front)
+        .collect();
+    Ok(cards.iter().cloned().partition(|card| !existing.contains(&card.front)))
+}
+
+/// Store `cards` under a skill, skipping any imported for it before, each
+/// with a review item due at `now`
+pub fn import_cards(
+    conn: &Connection,
+    user_id: &str,
+    curriculum_id: Option<&str>,
+    skill_id: &str,
+    source: &str,
+    cards: &[CardDraft],
+    now: DateTime<Utc>,
+) -> DbResult<ImportSummary> {
+    let (new, duplicates) = split_new(conn, user_id, curriculum_id, skill_id, cards)?;
+
+    let imported = in_savepoint(conn, "flashcard_import", || {
+        let mut imported = Vec::with_capacity(new.len());
+        for draft in new {
+            let card = Flashcard {
+                tags: draft.tags,
+                created_at: now,
+                ..Flashcard::new(
+                    user_id.to_string(),
+                    curriculum_id.map(str::to_string),
+                    skill_id.to_string(),
+                    draft.front,
+                    draft.back,
+                    source.to_string(),
+                )
+            };
+            let mut review = ReviewItem::new(user_id.to_string(), card.review_id());
+            review.curriculum_id = card.curriculum_id.clone();
+            review.due_date = now;
+
+            FlashcardRepository::create(conn, &card)?;
+            ReviewRepository::create_or_update(conn, &review)?;
+            imported.push(card);
+        }
+        Ok(imported)
+    })?;
+    Ok(ImportSummary { imported, duplicates })
+}
+
+fn detect_separator(directives: &HashMap<String, String>, body: &str) -> ImportResult<char> {
+    if let Some(separator) = directives.get("separator") {
+        return match separator.to_ascii_lowercase().as_str() {
+            "tab" => Ok('\t'),
+            "comma" => Ok(','),
+            "semicolon" => Ok(';'),
+            "pipe" => Ok('|'),
+            "colon" => Ok(':'),
+            "space" => Ok(' '),
+            other if other.chars().count() == 1 => Ok(other.chars().next().unwrap_or(',')),
+            other => Err(ImportError::InvalidDeck(format!("unknown separator '{}'", other))),
+        };
+    }
+    let first = body.lines().find(|line| !line.trim().is_empty()).unwrap_or_default();
+    Ok(if first.contains('\t') {
+        '\t'
+    } else if first.contains(';') && !first.contains(',') {
+        ';'
+    } else {
+        ','
+    })
+}
+
+fn layout(directives: &HashMap<String, String>, body: &str) -> ImportResult<Layout> {
+    let column = |key: &str| -> ImportResult<Option<usize>> {
+        match directives.get(key) {
+            None => Ok(None),
+            Some(value) => match value.parse::<usize>() {
+                Ok(column) if column > 0 => Ok(Some(column - 1)),
+                _ => Err(ImportError::InvalidDeck(format!("'{}' isn't a column number", value))),
+            },
+        }
+    };
+    let tags = column("tags column")?;
+    let mut reserved = Vec::new();
+    for key in ["guid column", "notetype column", "deck column", "tags column"] {
+        reserved.extend(column(key)?);
+    }
+    Ok(Layout {
+        separator: detect_separator(directives, body)?,
+        html: directives.get("html").is_some_and(|value| value.eq_ignore_ascii_case("true")),
+        reserved,
+        tags,
+    })
+}
+
+/// A first row naming the columns rather than holding a card
+fn is_header_row(fields: &[String]) -> bool {
+    let name = |index: usize| fields.get(index).map(|field| field.trim().to_ascii_lowercase()).unwrap_or_default();
+    matches!(name(0).as_str(), "front" | "question" | "term") && matches!(name(1).as_str(), "back" | "answer" | "definition")
+}
+
+/// Split into records of fields, each with the 0-based line it starts on.
+/// Quoted fields may hold separators, newlines and doubled quotes.
+fn split_records(body: &str, separator: char) -> Vec<(usize, Vec<String>)> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let (mut line, mut start) = (0, 0);
+    let (mut quoted, mut at_field_start) = (false, true);
+    let mut chars = body.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                '\n' => {
+                    line += 1;
+                    field.push('\n');
+                }
+                c => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if at_field_start => {
+                quoted = true;
+                at_field_start = false;
+            }
+            '\r' => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                records.push((start, std::mem::take(&mut fields)));
+                line += 1;
+                start = line;
+                at_field_start = true;
+            }
+            c if c == separator => {
+                fields.push(std::mem::take(&mut field));
+                at_field_start = true;
+            }
+            c => {
+                field.push(c);
+                at_field_start = false;
+            }
+        }
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((start, fields));
+    }
+    records
+}
+
+/// Anki's HTML fields as plain text: line breaks kept, other tags dropped
+fn html_to_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+    while let Some(open) = rest.find('<') {
+        text.push_str(&rest[..open]);
+        let Some(close) = rest[open..].find('>') else {
+            text.push_str(&rest[open..]);
+            rest = "";
+            break;
+        };
+        let tag = rest[open + 1..open + close].trim().to_ascii_lowercase();
+        if tag.starts_with("br") || tag == "/div" || tag == "/p" || tag == "/li" {
+            text.push('\n');
+        }
+        rest = &rest[open + close + 1..];
+    }
+    text.push_str(rest);
+
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::anki::anki_deck;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::User;
+
+    #[test]
+    fn test_parse_csv_with_header_and_quotes() {
+        let csv = "front,back\n\
+                   What is 'static?,The whole program\n\
+                   \"Vec<T>, or slice?\",\"Vec owns;\nslices borrow\"\n\
+                   ,missing front\n\
+                   What is 'static?,Duplicate\n";
+        let deck = parse_deck(csv).unwrap();
+
+        assert_eq!(deck.cards.len(), 2);
+        assert_eq!(deck.cards[1].front, "Vec<T>, or slice?");
+        assert_eq!(deck.cards[1].back, "Vec owns;\nslices borrow");
+        assert_eq!(deck.cards[1].line, 3);
+        assert_eq!(
+            deck.issues,
+            vec![
+                ImportIssue { line: 5, message: "the front is empty".to_string() },
+                ImportIssue { line: 6, message: "same front as line 2".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_anki_text_export() {
+        let export = "#separator:tab\n\
+                      #html:true\n\
+                      #guid column:1\n\
+                      #tags column:4\n\
+                      abc123\tWhat does <b>Box</b> do?\tHeap&nbsp;allocates<br>a value\trust::smart-pointers leech\n";
+        let deck = parse_deck(export).unwrap();
+
+        assert_eq!(
+            deck.cards,
+            vec![CardDraft {
+                line: 5,
+                front: "What does Box do?".to_string(),
+                back: "Heap allocates\na value".to_string(),
+                tags: vec!["rust::smart-pointers".to_string(), "leech".to_string()],
+            }]
+        );
+        assert!(matches!(parse_deck("#separator:tab\n"), Err(ImportError::InvalidDeck(_))));
+    }
+
+    #[test]
+    fn test_import_skips_cards_already_imported() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+        let now = Utc::now();
+
+        let deck = parse_deck("Ownership?,One owner\nBorrowing?,Shared or unique\n").unwrap();
+        let first = import_cards(conn, "test-user", Some("rust"), "ownership", "rust.csv", &deck.cards, now).unwrap();
+        assert_eq!(first.imported.len(), 2);
+        assert!(first.duplicates.is_empty());
+
+        let again = import_cards(conn, "test-user", Some("rust"), "ownership", "rust.csv", &deck.cards, now).unwrap();
+        assert!(again.imported.is_empty());
+        assert_eq!(again.duplicates.len(), 2);
+
+        let due = ReviewRepository::get_due_reviews(conn, "test-user", Some("rust")).unwrap();
+        assert_eq!(due.len(), 2);
+        let exported = anki_deck(conn, "test-user", Some("rust")).unwrap();
+        assert!(exported.cards.iter().any(|card| card.front == "Ownership?" && card.back == "One owner"));
+    }
+}
@@ -0,0 +1,240 @@
+//! Anki export
+//!
+//! Turns a learner's review items and notes into a file Anki's "Import
+//! File" reads as a deck, so their spaced-repetition history comes along if
+//! they move to Anki. The format is Anki's tab-separated text with header
+//! lines, not an `.apkg`: each quiz under review becomes one Basic note, and
+//! each of the learner's notes another, with the back taken from the note's
+//! body.
+//!
+//! Anki doesn't import scheduling from text files, so each review item's
+//! due date, interval, ease, reviews and lapses go in columns after the
+//! tags, to map onto fields of a custom note type or leave out. Suspended
+//! items and leeches are tagged, as Anki tags its own.
+//!
+//! Decks come the other way through `import`, from CSV files as well as
+//! Anki's own text exports.
+
+pub mod import;
+
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+
+use crate::db::error::DbResult;
+use crate::db::repos::{FlashcardRepository, NoteRepository, ReviewRepository};
+use crate::models::{Flashcard, Note, ReviewItem};
+
+/// Tag on every exported card, so the import is easy to find in Anki
+pub const DECK_TAG: &str = "glp";
+
+const COLUMNS: &[&str] = &["Front", "Back", "Tags", "Due", "Interval", "Ease", "Reviews", "Lapses"];
+
+/// Where a card came from
+#[derive(Debug, Clone)]
+pub enum CardSource {
+    Review(ReviewItem),
+    Note(Note),
+}
+
+#[derive(Debug, Clone)]
+pub struct AnkiCard {
+    /// The quiz or node the card is about
+    pub node_id: String,
+    /// Plain text; until `describe` is called, the quiz id or the note's
+    /// anchor or node id
+    pub front: String,
+    pub back: String,
+    pub tags: Vec<String>,
+    pub source: CardSource,
+}
+
+impl AnkiCard {
+    fn from_review(review: ReviewItem) -> Self {
+        let mut tags = vec![DECK_TAG.to_string(), format!("{}::quiz", DECK_TAG)];
+        if review.is_suspended {
+            tags.push(format!("{}::suspended", DECK_TAG));
+        }
+        if review.is_leech() {
+            tags.push("leech".to_string());
+        }
+        Self {
+            node_id: review.quiz_id.clone(),
+            front: review.quiz_id.clone(),
+            back: String::new(),
+            tags,
+            source: CardSource::Review(review),
+        }
+    }
+
+    fn from_note(note: Note) -> Self {
+        Self {
+            node_id: note.node_id.clone(),
+            front: note.anchor.clone().unwrap_or_else(|| note.node_id.clone()),
+            back: note.body.clone(),
+            tags: vec![DECK_TAG.to_string(), format!("{}::note", DECK_TAG)],
+            source: CardSource::Note(note),
+        }
+    }
+
+    pub fn review(&self) -> Option<&ReviewItem> {
+        match &self.source {
+            CardSource::Review(review) => Some(review),
+            CardSource::Note(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AnkiDeck {
+    pub cards: Vec<AnkiCard>,
+}
+
+/// A user's review items, in one curriculum, and all their notes as a deck
+pub fn anki_deck(conn: &Connection, user_id: &str, curriculum_id: Option<&str>) -> DbResult<AnkiDeck> {
+    let mut reviews = ReviewRepository::get_all_in_curriculum(conn, user_id, curriculum_id)?;
+    reviews.sort_by_key(|review| review.due_date);
+    let mut notes = NoteRepository::get_all_for_user(conn, user_id)?;
+    notes.reverse();
+
+    let mut cards = Vec::with_capacity(reviews.len() + notes.len());
+    for review in reviews {
+        let flashcard = match Flashcard::id_from_review(&review.quiz_id) {
+            Some(id) => FlashcardRepository::get(conn, id)?,
+            None => None,
+        };
+        let mut card = AnkiCard::from_review(review);
+        // Imported cards carry their own text
+        if let Some(flashcard) = flashcard {
+            card.front = flashcard.front;
+            card.back = flashcard.back;
+            card.tags.extend(flashcard.tags);
+        }
+        cards.push(card);
+    }
+    cards.extend(notes.into_iter().map(AnkiCard::from_note));
+    Ok(AnkiDeck { cards })
+}
+
+impl AnkiDeck {
+    /// Fill in the text of quiz cards from `quiz_text`, as (front, back), and
+    /// put node titles from `node_title` on the front of notes without an
+    /// anchor. Cards neither knows about keep their ids.
+    pub fn describe(
+        &mut self,
+        quiz_text: impl Fn(&str) -> Option<(String, String)>,
+        node_title: impl Fn(&str) -> Option<String>,
+    ) {
+        for card in &mut self.cards {
+            match &card.source {
+                CardSource::Review(_) if Flashcard::id_from_review(&card.node_id).is_none() => {
+                    if let Some((front, back)) = quiz_text(&card.node_id) {
+                        card.front = front;
+                        card.back = back;
+                    }
+                }
+                CardSource::Note(note) if note.anchor.is_none() => {
+                    if let Some(title) = node_title(&card.node_id) {
+                        card.front = title;
+                    }
+                }
+                CardSource::Review(_) | CardSource::Note(_) => {}
+            }
+        }
+    }
+
+    /// Render for Anki's "Import File", into a deck called `deck_name`
+    pub fn to_anki_text(&self, deck_name: &str) -> String {
+        let mut text = String::new();
+        text.push_str("#separator:tab\n");
+        text.push_str("#html:true\n");
+        text.push_str("#notetype:Basic\n");
+        text.push_str(&format!("#deck:{}\n", deck_name.replace(['\n', '\r', '\t'], " ")));
+        text.push_str("#tags column:3\n");
+        text.push_str(&format!("#columns:{}\n", COLUMNS.join("\t")));
+
+        for card in &self.cards {
+            let mut fields = vec![field(&card.front), field(&card.back), field(&card.tags.join(" "))];
+            match card.review() {
+                Some(review) => fields.extend([
+                    field(&due_day(review.due_date)),
+                    review.interval_days.to_string(),
+                    // Anki states ease as a percentage
+                    ((review.ease_factor * 100.0).round() as i64).to_string(),
+                    review.repetitions.to_string(),
+                    review.lapses.to_string(),
+                ]),
+                None => fields.resize(COLUMNS.len(), String::new()),
+            }
+            text.push_str(&fields.join("\t"));
+            text.push('\n');
+        }
+        text
+    }
+}
+
+fn due_day(due: DateTime<Utc>) -> String {
+    due.format("%Y-%m-%d").to_string()
+}
+
+/// Plain text as a quoted HTML field
+fn field(text: &str) -> String {
+    let html = text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace("\r\n", "\n")
+        .replace('\n', "<br>")
+        .replace('\t', "    ");
+    format!("\"{}\"", html.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::User;
+
+    #[test]
+    fn test_deck_keeps_review_history() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let mut review = ReviewItem::new("test-user".to_string(), "quiz-ownership".to_string())
+            .with_curriculum("rust".to_string());
+        review.interval_days = 6;
+        review.repetitions = 2;
+        review.lapses = 9;
+        review.is_suspended = true;
+        ReviewRepository::create_or_update(conn, &review).unwrap();
+        let other = ReviewItem::new("test-user".to_string(), "quiz-elsewhere".to_string())
+            .with_curriculum("go".to_string());
+        ReviewRepository::create_or_update(conn, &other).unwrap();
+        let note = Note::new("test-user".to_string(), "lecture-borrowing".to_string(), "&T is\nshared".to_string());
+        NoteRepository::create(conn, &note).unwrap();
+
+        let mut deck = anki_deck(conn, "test-user", Some("rust")).unwrap();
+        assert_eq!(deck.cards.len(), 2);
+        deck.describe(
+            |quiz_id| {
+                (quiz_id == "quiz-ownership").then(|| ("Who owns a \"String\"?".to_string(), "One owner".to_string()))
+            },
+            |node_id| (node_id == "lecture-borrowing").then(|| "Borrowing".to_string()),
+        );
+
+        let text = deck.to_anki_text("Rust");
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines.contains(&"#deck:Rust"));
+        assert!(lines.contains(&"#tags column:3"));
+
+        let quiz: Vec<&str> = lines.iter().find(|line| line.contains("One owner")).unwrap().split('\t').collect();
+        assert_eq!(quiz[0], "\"Who owns a \"\"String\"\"?\"");
+        assert_eq!(quiz[2], "\"glp glp::quiz glp::suspended leech\"");
+        assert_eq!(&quiz[4..], ["6", "250", "2", "9"]);
+
+        let note: Vec<&str> = lines.iter().find(|line| line.contains("Borrowing")).unwrap().split('\t').collect();
+        assert_eq!(note[1], "\"&amp;T is<br>shared\"");
+        assert_eq!(note.len(), COLUMNS.len());
+    }
+}
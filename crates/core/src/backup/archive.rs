@@ -0,0 +1,207 @@
+//! The `.glpbackup` file format
+//!
+//! ```text
+//! "GLPBACKUP" | format version: u8 | flags: u8 | [salt: 16 | nonce: 12] | payload
+//! ```
+//!
+//! The payload is gzip-compressed JSON of [`BackupData`]. When the encrypted
+//! flag is set it is sealed with AES-256-GCM under a key derived from the
+//! passphrase with Argon2id, and the header is authenticated along with it.
+//! Files without the magic prefix are read as v1 backups, which were plain
+//! JSON.
+
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use crate::backup::{BackupData, BackupError, BackupResult};
+
+const MAGIC: &[u8] = b"GLPBACKUP";
+const HEADER_LEN: usize = MAGIC.len() + 2;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Archive format written by this build
+pub const FORMAT_VERSION: u8 = 2;
+
+const FLAG_ENCRYPTED: u8 = 0b0000_0001;
+
+fn cipher(passphrase: &str, salt: &[u8]) -> BackupResult<Aes256Gcm> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| BackupError::Encryption(e.to_string()))?;
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))
+}
+
+/// Serialize a backup, encrypting it if a passphrase is given
+pub fn encode(backup: &BackupData, passphrase: Option<&str>) -> BackupResult<Vec<u8>> {
+    let json = serde_json::to_vec(backup).map_err(|e| BackupError::Invalid(e.to_string()))?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&json)?;
+    let payload = encoder.finish()?;
+
+    let mut archive = Vec::with_capacity(HEADER_LEN + SALT_LEN + NONCE_LEN + payload.len());
+    archive.extend_from_slice(MAGIC);
+    archive.push(FORMAT_VERSION);
+
+    match passphrase {
+        None => {
+            archive.push(0);
+            archive.extend_from_slice(&payload);
+        }
+        Some(passphrase) => {
+            archive.push(FLAG_ENCRYPTED);
+
+            let mut salt = [0u8; SALT_LEN];
+            let mut nonce = [0u8; NONCE_LEN];
+            OsRng.fill_bytes(&mut salt);
+            OsRng.fill_bytes(&mut nonce);
+
+            let sealed = cipher(passphrase, &salt)?
+                .encrypt(Nonce::from_slice(&nonce), Payload { msg: &payload, aad: &archive })
+                .map_err(|e| BackupError::Encryption(e.to_string()))?;
+            archive.extend_from_slice(&salt);
+            archive.extend_from_slice(&nonce);
+            archive.extend_from_slice(&sealed);
+        }
+    }
+
+    Ok(archive)
+}
+
+/// Parse a backup written by `encode`, or a v1 JSON backup
+pub fn decode(bytes: &[u8], passphrase: Option<&str>) -> BackupResult<BackupData> {
+    if !bytes.starts_with(MAGIC) {
+        return serde_json::from_slice(bytes).map_err(|e| BackupError::Invalid(e.to_string()));
+    }
+    if bytes.len() < HEADER_LEN {
+        return Err(BackupError::Invalid("truncated header".to_string()));
+    }
+
+    let (header, body) = bytes.split_at(HEADER_LEN);
+    let version = header[MAGIC.len()];
+    let flags = header[MAGIC.len() + 1];
+    if version != FORMAT_VERSION {
+        return Err(BackupError::UnsupportedFormat(version));
+    }
+    if flags & !FLAG_ENCRYPTED != 0 {
+        return Err(BackupError::Invalid(format!("unknown flags {:#04x}", flags)));
+    }
+
+    let payload = if flags & FLAG_ENCRYPTED != 0 {
+        let passphrase = passphrase.ok_or(BackupError::PassphraseRequired)?;
+        if body.len() < SALT_LEN + NONCE_LEN {
+            return Err(BackupError::Invalid("truncated encryption header".to_string()));
+        }
+        let (salt, rest) = body.split_at(SALT_LEN);
+        let (nonce, sealed) = rest.split_at(NONCE_LEN);
+
+        cipher(passphrase, salt)?
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: sealed, aad: header })
+            .map_err(|_| BackupError::Decryption)?
+    } else {
+        body.to_vec()
+    };
+
+    let mut json = Vec::new();
+    GzDecoder::new(payload.as_slice())
+        .read_to_end(&mut json)
+        .map_err(|e| BackupError::Invalid(format!("corrupted payload: {}", e)))?;
+    serde_json::from_slice(&json).map_err(|e| BackupError::Invalid(e.to_string()))
+}
+
+/// Whether importing these bytes needs a passphrase
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.len() >= HEADER_LEN && bytes.starts_with(MAGIC) && bytes[MAGIC.len() + 1] & FLAG_ENCRYPTED != 0
+}
+
+pub fn write_file(path: &Path, backup: &BackupData, passphrase: Option<&str>) -> BackupResult<()> {
+    fs::write(path, encode(backup, passphrase)?)?;
+    Ok(())
+}
+
+pub fn read_file(path: &Path, passphrase: Option<&str>) -> BackupResult<BackupData> {
+    decode(&fs::read(path)?, passphrase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backup::contents::CONTENTS_VERSION;
+    use crate::models::{Note, User};
+    use chrono::Utc;
+
+    fn sample_backup() -> BackupData {
+        BackupData {
+            version: CONTENTS_VERSION.to_string(),
+            schema_version: 11,
+            exported_at: Utc::now(),
+            user: Some(User::new("test-user".to_string())),
+            node_progress: Vec::new(),
+            quiz_attempts: Vec::new(),
+            mastery_scores: Vec::new(),
+            badge_progress: Vec::new(),
+            review_items: Vec::new(),
+            notes: vec![Note::new("test-user".to_string(), "week1-lecture".to_string(), "Lifetimes".to_string())],
+            artifact_submissions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_plain_round_trip() {
+        let archive = encode(&sample_backup(), None).unwrap();
+        assert!(archive.starts_with(MAGIC));
+        assert!(!is_encrypted(&archive));
+
+        let backup = decode(&archive, None).unwrap();
+        assert_eq!(backup.notes[0].body, "Lifetimes");
+    }
+
+    #[test]
+    fn test_encrypted_round_trip_and_wrong_passphrase() {
+        let archive = encode(&sample_backup(), Some("correct horse")).unwrap();
+        assert!(is_encrypted(&archive));
+        assert!(!archive.windows(9).any(|w| w == b"Lifetimes"));
+
+        assert_eq!(decode(&archive, Some("correct horse")).unwrap().notes.len(), 1);
+        assert!(matches!(decode(&archive, Some("battery staple")), Err(BackupError::Decryption)));
+        assert!(matches!(decode(&archive, None), Err(BackupError::PassphraseRequired)));
+    }
+
+    #[test]
+    fn test_tampered_header_is_rejected() {
+        let mut archive = encode(&sample_backup(), Some("pass")).unwrap();
+        archive[HEADER_LEN - 1] |= 0b1000_0000;
+        assert!(matches!(decode(&archive, Some("pass")), Err(BackupError::Invalid(_))));
+
+        let mut archive = encode(&sample_backup(), None).unwrap();
+        archive[MAGIC.len()] = 9;
+        assert!(matches!(decode(&archive, None), Err(BackupError::UnsupportedFormat(9))));
+    }
+
+    #[test]
+    fn test_reads_v1_json_backup() {
+        let json = r#"{
+            "version": "1.0",
+            "exported_at": "2024-01-01T00:00:00+00:00",
+            "user": null,
+            "node_progress": [],
+            "quiz_attempts": [],
+            "mastery_scores": [],
+            "badge_progress": [],
+            "review_items": []
+        }"#;
+
+        let backup = decode(json.as_bytes(), None).unwrap();
+        assert_eq!(backup.version, "1.0");
+        assert_eq!(backup.schema_version, 0);
+        assert!(backup.notes.is_empty());
+    }
+}
@@ -0,0 +1,424 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use crate::backup::planner::{resolve, CategoryReport, ConflictStrategies, ConflictStrategy, ImportCategory, ImportReport};
+use crate::backup::{BackupError, BackupResult};
+use crate::db::error::{DbError, DbResult};
+use crate::db::in_savepoint;
+use crate::db::migrations::{self, CURRENT_VERSION};
+use crate::db::repos::{
+    ArtifactRepository, BadgeRepository, CurriculumRepository, MasteryRepository, NoteRepository,
+    ProgressRepository, QuizRepository, ReviewRepository, UserRepository,
+};
+use crate::models::{
+    ArtifactSubmission, BadgeProgress, MasteryScore, NodeProgress, Note, QuizAttempt, ReviewItem, User,
+};
+
+/// Version of the backup contents written by this build
+pub const CONTENTS_VERSION: &str = "2.0";
+
+/// First schema version that namespaces progress by curriculum
+const CURRICULUM_NAMESPACE_SCHEMA: i32 = 10;
+
+/// Everything recorded about one user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupData {
+    pub version: String,
+    /// Database schema the backup was taken from. 0 for v1 backups, which
+    /// did not record it.
+    #[serde(default)]
+    pub schema_version: i32,
+    pub exported_at: DateTime<Utc>,
+    pub user: Option<User>,
+    pub node_progress: Vec<NodeProgress>,
+    pub quiz_attempts: Vec<QuizAttempt>,
+    pub mastery_scores: Vec<MasteryScore>,
+    /// Includes when each badge was earned
+    pub badge_progress: Vec<BadgeProgress>,
+    pub review_items: Vec<ReviewItem>,
+    /// Missing from backups made before notes existed
+    #[serde(default)]
+    pub notes: Vec<Note>,
+    /// Graded artifact submissions; missing from v1 backups
+    #[serde(default)]
+    pub artifact_submissions: Vec<ArtifactSubmission>,
+}
+
+impl BackupData {
+    /// Number of records the backup would restore, not counting the user
+    pub fn record_count(&self) -> usize {
+        self.node_progress.len()
+            + self.quiz_attempts.len()
+            + self.mastery_scores.len()
+            + self.badge_progress.len()
+            + self.review_items.len()
+            + self.notes.len()
+            + self.artifact_submissions.len()
+    }
+}
+
+/// How an import treats data already in the database
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum ImportMode {
+    /// Keep local records, settling conflicts with the chosen strategies
+    #[default]
+    Merge,
+    /// Discard the user's local records and stats, leaving only the backup's
+    Replace,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(default)]
+pub struct ImportOptions {
+    pub mode: ImportMode,
+    /// Only used when merging
+    pub strategies: ConflictStrategies,
+    /// Report what the import would do without writing anything
+    pub dry_run: bool,
+}
+
+/// Gather a user's data for export
+pub fn collect(conn: &Connection, user_id: &str) -> DbResult<BackupData> {
+    Ok(BackupData {
+        version: CONTENTS_VERSION.to_string(),
+        schema_version: migrations::schema_version(conn)?,
+        exported_at: Utc::now(),
+        user: UserRepository::get_by_id(conn, user_id)?,
+        node_progress: ProgressRepository::get_all_for_user(conn, user_id)?,
+        quiz_attempts: QuizRepository::get_all_for_user(conn, user_id)?,
+        mastery_scores: MasteryRepository::get_all_for_user(conn, user_id)?,
+        badge_progress: BadgeRepository::get_all_for_user(conn, user_id)?,
+        review_items: ReviewRepository::get_all_for_user(conn, user_id)?,
+        notes: NoteRepository::get_all_for_user(conn, user_id)?,
+        artifact_submissions: ArtifactRepository::get_all_for_user(conn, user_id)?,
+    })
+}
+
+/// Write a backup into the database, or with `dry_run` only report what
+/// would change. Nothing is written if any record fails.
+pub fn restore(conn: &Connection, backup: &BackupData, options: &ImportOptions) -> BackupResult<ImportReport> {
+    if backup.schema_version > CURRENT_VERSION {
+        return Err(BackupError::SchemaTooNew {
+            found: backup.schema_version,
+            supported: CURRENT_VERSION,
+        });
+    }
+    if options.mode == ImportMode::Replace && backup.user.is_none() {
+        return Err(BackupError::Invalid("backup has no user profile to replace".to_string()));
+    }
+
+    if options.dry_run {
+        conn.execute_batch("SAVEPOINT import_dry_run").map_err(DbError::from)?;
+        let result = restore_records(conn, backup, options);
+        conn.execute_batch("ROLLBACK TO import_dry_run; RELEASE import_dry_run").map_err(DbError::from)?;
+        return Ok(result?);
+    }
+
+    Ok(in_savepoint(conn, "backup_restore", || restore_records(conn, backup, options))?)
+}
+
+fn restore_records(conn: &Connection, backup: &BackupData, options: &ImportOptions) -> DbResult<ImportReport> {
+    let merging = options.mode == ImportMode::Merge;
+    let pick = |s: ConflictStrategy| if merging { s } else { ConflictStrategy::KeepIncoming };
+    let strategies = &options.strategies;
+
+    let mut user = CategoryReport::new(ImportCategory::User, Some(pick(strategies.user)));
+    let mut progress = CategoryReport::new(ImportCategory::Progress, Some(pick(strategies.progress)));
+    let mut mastery = CategoryReport::new(ImportCategory::Mastery, Some(pick(strategies.mastery)));
+    let mut badges = CategoryReport::new(ImportCategory::Badges, Some(pick(strategies.badges)));
+    let mut reviews = CategoryReport::new(ImportCategory::Reviews, Some(pick(strategies.reviews)));
+    let mut attempts = CategoryReport::new(ImportCategory::QuizAttempts, None);
+    let mut notes = CategoryReport::new(ImportCategory::Notes, Some(pick(strategies.notes)));
+    let mut grades = CategoryReport::new(ImportCategory::GradeHistory, None);
+
+    if let Some(incoming) = &backup.user {
+        let local = UserRepository::get_by_id(conn, &incoming.id)?;
+        let exists = local.is_some();
+        if let Some(resolved) = user.record(resolve(local, incoming, pick(strategies.user))) {
+            if exists {
+                UserRepository::update(conn, &resolved)?;
+            } else {
+                UserRepository::create(conn, &resolved)?;
+            }
+        }
+
+        if !merging {
+            for (table, report) in [
+                ("node_progress", &mut progress),
+                ("quiz_attempts", &mut attempts),
+                ("mastery_scores", &mut mastery),
+                ("badge_progress", &mut badges),
+                ("review_items", &mut reviews),
+                ("notes", &mut notes),
+                ("artifact_submissions", &mut grades),
+            ] {
+                report.removed = conn.execute(&format!("DELETE FROM {} WHERE user_id = ?1", table), params![incoming.id])?;
+            }
+        }
+    }
+
+    // Records from before progress was namespaced belong to the active
+    // curriculum, as they did when the v10 migration ran
+    let fallback_curriculum = if backup.schema_version < CURRICULUM_NAMESPACE_SCHEMA {
+        CurriculumRepository::get_active(conn)?.map(|c| c.id)
+    } else {
+        None
+    };
+    let curriculum_for = |id: &Option<String>| id.clone().or_else(|| fallback_curriculum.clone());
+
+    for incoming in &backup.node_progress {
+        let incoming = NodeProgress { curriculum_id: curriculum_for(&incoming.curriculum_id), ..incoming.clone() };
+        let local = ProgressRepository::get(conn, &incoming.user_id, incoming.curriculum_id.as_deref(), &incoming.node_id)?;
+        if let Some(resolved) = progress.record(resolve(local, &incoming, pick(strategies.progress))) {
+            ProgressRepository::create_or_update(conn, &resolved)?;
+        }
+    }
+
+    for incoming in &backup.mastery_scores {
+        let incoming = MasteryScore { curriculum_id: curriculum_for(&incoming.curriculum_id), ..incoming.clone() };
+        let local = MasteryRepository::get(conn, &incoming.user_id, incoming.curriculum_id.as_deref(), &incoming.skill_id)?;
+        if let Some(resolved) = mastery.record(resolve(local, &incoming, pick(strategies.mastery))) {
+            MasteryRepository::create_or_update(conn, &resolved)?;
+        }
+    }
+
+    // A badge already earned locally keeps its earned date
+    for incoming in &backup.badge_progress {
+        let local = BadgeRepository::get(conn, &incoming.user_id, &incoming.badge_id)?;
+        if let Some(resolved) = badges.record(resolve(local, incoming, pick(strategies.badges))) {
+            BadgeRepository::create_or_update(conn, &resolved)?;
+        }
+    }
+
+    for incoming in &backup.review_items {
+        let incoming = ReviewItem { curriculum_id: curriculum_for(&incoming.curriculum_id), ..incoming.clone() };
+        let local = ReviewRepository::get(conn, &incoming.user_id, incoming.curriculum_id.as_deref(), &incoming.quiz_id)?;
+        if let Some(resolved) = reviews.record(resolve(local, &incoming, pick(strategies.reviews))) {
+            ReviewRepository::create_or_update(conn, &resolved)?;
+        }
+    }
+
+    // Attempts and graded submissions never change once recorded, so only
+    // missing ones are added
+    for attempt in &backup.quiz_attempts {
+        if QuizRepository::get_by_id(conn, &attempt.id)?.is_some() {
+            attempts.unchanged += 1;
+            continue;
+        }
+        let mut attempt = QuizAttempt { curriculum_id: curriculum_for(&attempt.curriculum_id), ..attempt.clone() };
+        // Attempts still reference installed curricula; keep the score even
+        // when its curriculum is not installed here
+        if let Some(curriculum_id) = &attempt.curriculum_id {
+            if CurriculumRepository::get(conn, curriculum_id)?.is_none() {
+                attempt.curriculum_id = None;
+            }
+        }
+        QuizRepository::create(conn, &attempt)?;
+        attempts.added += 1;
+    }
+
+    for submission in &backup.artifact_submissions {
+        if ArtifactRepository::get(conn, &submission.id)?.is_some() {
+            grades.unchanged += 1;
+            continue;
+        }
+        ArtifactRepository::create(conn, submission)?;
+        grades.added += 1;
+    }
+
+    for incoming in &backup.notes {
+        let local = NoteRepository::get(conn, &incoming.user_id, &incoming.id)?;
+        let exists = local.is_some();
+        if let Some(resolved) = notes.record(resolve(local, incoming, pick(strategies.notes))) {
+            if exists {
+                NoteRepository::delete(conn, &resolved.user_id, &resolved.id)?;
+            }
+            NoteRepository::create(conn, &resolved)?;
+        }
+    }
+
+    Ok(ImportReport {
+        user_id: backup.user.as_ref().map(|u| u.id.clone()),
+        dry_run: options.dry_run,
+        categories: vec![user, progress, mastery, badges, reviews, attempts, notes, grades],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::models::{ArtifactType, Curriculum, NodeStatus};
+
+    fn setup_db() -> Database {
+        let db = Database::new_in_memory().unwrap();
+        UserRepository::create(db.connection(), &User::new("test-user".to_string())).unwrap();
+        db
+    }
+
+    fn seed_history(conn: &Connection) {
+        let mut progress = NodeProgress::new("test-user".to_string(), "week1-lecture".to_string());
+        progress.complete();
+        ProgressRepository::create_or_update(conn, &progress).unwrap();
+
+        let mut badge = BadgeProgress::new("test-user".to_string(), "first_steps".to_string());
+        badge.update_progress(1.0, 1.0);
+        BadgeRepository::create_or_update(conn, &badge).unwrap();
+
+        let mut submission = ArtifactSubmission::new(
+            "test-user".to_string(),
+            "week1-checkpoint".to_string(),
+            ArtifactType::Readme,
+            "# Project",
+        );
+        submission.set_grade(90, "{}".to_string(), 150);
+        ArtifactRepository::create(conn, &submission).unwrap();
+
+        let note = Note::new("test-user".to_string(), "week1-lecture".to_string(), "Borrowing".to_string());
+        NoteRepository::create(conn, &note).unwrap();
+        UserRepository::set_xp_and_level(conn, "test-user", 250, 2).unwrap();
+    }
+
+    #[test]
+    fn test_collect_and_restore_into_empty_database() {
+        let source = setup_db();
+        seed_history(source.connection());
+        let backup = collect(source.connection(), "test-user").unwrap();
+        assert_eq!(backup.schema_version, CURRENT_VERSION);
+
+        let target = Database::new_in_memory().unwrap();
+        let conn = target.connection();
+        let report = restore(conn, &backup, &ImportOptions::default()).unwrap();
+
+        assert_eq!(report.user_id.as_deref(), Some("test-user"));
+        assert_eq!(report.records_imported(), backup.record_count());
+        assert_eq!(report.conflicts(), 0);
+        assert_eq!(UserRepository::get_by_id(conn, "test-user").unwrap().unwrap().total_xp, 250);
+
+        let badge = BadgeRepository::get(conn, "test-user", "first_steps").unwrap().unwrap();
+        assert_eq!(badge.earned_at, backup.badge_progress[0].earned_at);
+        assert_eq!(ArtifactRepository::get_all_for_user(conn, "test-user").unwrap()[0].grade_percentage, Some(90));
+        assert_eq!(NoteRepository::get_all_for_user(conn, "test-user").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_merge_keeps_local_records_and_replace_drops_them() {
+        let db = setup_db();
+        let conn = db.connection();
+        seed_history(conn);
+        let backup = collect(conn, "test-user").unwrap();
+
+        ProgressRepository::create_or_update(conn, &NodeProgress::new("test-user".to_string(), "week2-lecture".to_string())).unwrap();
+        UserRepository::set_xp_and_level(conn, "test-user", 900, 4).unwrap();
+
+        restore(conn, &backup, &ImportOptions::default()).unwrap();
+        assert_eq!(ProgressRepository::get_all_for_user(conn, "test-user").unwrap().len(), 2);
+        assert_eq!(UserRepository::get_by_id(conn, "test-user").unwrap().unwrap().total_xp, 900);
+
+        let replace = ImportOptions { mode: ImportMode::Replace, ..Default::default() };
+        let report = restore(conn, &backup, &replace).unwrap();
+        assert_eq!(report.category(ImportCategory::Progress).unwrap().removed, 2);
+        let progress = ProgressRepository::get_all_for_user(conn, "test-user").unwrap();
+        assert_eq!(progress.len(), 1);
+        assert_eq!(progress[0].node_id, "week1-lecture");
+        assert_eq!(UserRepository::get_by_id(conn, "test-user").unwrap().unwrap().total_xp, 250);
+        assert_eq!(NoteRepository::get_all_for_user(conn, "test-user").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_rejects_backup_from_newer_schema() {
+        let db = setup_db();
+        let mut backup = collect(db.connection(), "test-user").unwrap();
+        backup.schema_version = CURRENT_VERSION + 1;
+
+        assert!(matches!(
+            restore(db.connection(), &backup, &ImportOptions::default()),
+            Err(BackupError::SchemaTooNew { found, .. }) if found == CURRENT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn test_pre_namespace_backup_joins_active_curriculum() {
+        let db = setup_db();
+        let conn = db.connection();
+        let rust = Curriculum::new("Rust".to_string(), "1.0".to_string(), "rust".to_string());
+        CurriculumRepository::create(conn, &rust).unwrap();
+        CurriculumRepository::set_active(conn, &rust.id).unwrap();
+
+        let mut backup = collect(conn, "test-user").unwrap();
+        backup.schema_version = 0;
+        backup.node_progress.push(NodeProgress::new("test-user".to_string(), "week1-lecture".to_string()));
+        backup.quiz_attempts.push(
+            QuizAttempt::new("test-user".to_string(), "quiz1".to_string(), "week1-quiz".to_string(), vec![], 80, 40)
+                .with_curriculum("not-installed".to_string()),
+        );
+        restore(conn, &backup, &ImportOptions::default()).unwrap();
+
+        assert!(ProgressRepository::get(conn, "test-user", Some(&rust.id), "week1-lecture").unwrap().is_some());
+        let attempts = QuizRepository::get_all_for_user(conn, "test-user").unwrap();
+        assert_eq!(attempts[0].curriculum_id, None);
+    }
+
+    #[test]
+    fn test_strategies_settle_progress_conflicts() {
+        let db = setup_db();
+        let conn = db.connection();
+        let mut backup = collect(conn, "test-user").unwrap();
+
+        let mut local = NodeProgress::new("test-user".to_string(), "week1-lecture".to_string());
+        local.complete();
+        local.last_updated_at = Utc::now() - chrono::Duration::days(1);
+        ProgressRepository::create_or_update(conn, &local).unwrap();
+
+        let mut incoming = NodeProgress::new("test-user".to_string(), "week1-lecture".to_string());
+        incoming.start();
+        incoming.attempts = 4;
+        backup.node_progress.push(incoming);
+
+        let keep_local = ImportOptions {
+            strategies: ConflictStrategies { progress: ConflictStrategy::KeepLocal, ..Default::default() },
+            ..Default::default()
+        };
+        let report = restore(conn, &backup, &keep_local).unwrap();
+        assert_eq!(report.category(ImportCategory::Progress).unwrap().kept_local, 1);
+        let stored = ProgressRepository::get(conn, "test-user", None, "week1-lecture").unwrap().unwrap();
+        assert_eq!(stored.status, NodeStatus::Completed);
+
+        let merge_max = ImportOptions {
+            strategies: ConflictStrategies { progress: ConflictStrategy::MergeMax, ..Default::default() },
+            ..Default::default()
+        };
+        restore(conn, &backup, &merge_max).unwrap();
+        let stored = ProgressRepository::get(conn, "test-user", None, "week1-lecture").unwrap().unwrap();
+        assert_eq!(stored.status, NodeStatus::Completed);
+        assert_eq!(stored.attempts, 4);
+
+        let keep_incoming = ImportOptions {
+            strategies: ConflictStrategies { progress: ConflictStrategy::KeepIncoming, ..Default::default() },
+            ..Default::default()
+        };
+        restore(conn, &backup, &keep_incoming).unwrap();
+        let stored = ProgressRepository::get(conn, "test-user", None, "week1-lecture").unwrap().unwrap();
+        assert_eq!(stored.status, NodeStatus::InProgress);
+    }
+
+    #[test]
+    fn test_dry_run_reports_without_writing() {
+        let source = setup_db();
+        seed_history(source.connection());
+        let backup = collect(source.connection(), "test-user").unwrap();
+
+        let db = setup_db();
+        let conn = db.connection();
+        let dry_run = ImportOptions { dry_run: true, ..Default::default() };
+        let report = restore(conn, &backup, &dry_run).unwrap();
+
+        assert!(report.dry_run);
+        assert_eq!(report.category(ImportCategory::Progress).unwrap().added, 1);
+        assert_eq!(report.category(ImportCategory::User).unwrap().updated, 1);
+        assert!(ProgressRepository::get_all_for_user(conn, "test-user").unwrap().is_empty());
+        assert_eq!(UserRepository::get_by_id(conn, "test-user").unwrap().unwrap().total_xp, 0);
+    }
+}
@@ -0,0 +1,51 @@
+//! Portable backups of a learner's data
+//!
+//! `contents` gathers everything recorded about one user (progress, quiz
+//! attempts, mastery, badges with their earned dates, reviews, notes and
+//! graded artifacts) and writes it back into a database, either merged with
+//! what is there or replacing it. `planner` settles conflicts between local
+//! and backed-up records when merging. `archive` reads and writes the `.glpbackup`
+//! file format, which compresses the contents and can encrypt them with a
+//! passphrase. `rotation` keeps a folder of automatic backups trimmed to the
+//! newest few.
+
+pub mod archive;
+pub mod contents;
+pub mod planner;
+pub mod rotation;
+
+pub use archive::{decode, encode, is_encrypted, read_file, write_file};
+pub use contents::{collect, restore, BackupData, ImportMode, ImportOptions};
+pub use planner::{CategoryReport, ConflictStrategies, ConflictStrategy, ImportCategory, ImportReport};
+
+use thiserror::Error;
+use crate::db::error::DbError;
+
+#[derive(Error, Debug)]
+pub enum BackupError {
+    #[error(transparent)]
+    Db(#[from] DbError),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid backup: {0}")]
+    Invalid(String),
+
+    #[error("Backup archive format v{0} is not supported")]
+    UnsupportedFormat(u8),
+
+    #[error("Backup is from database schema v{found}, newer than this build supports (v{supported}); please update the app")]
+    SchemaTooNew { found: i32, supported: i32 },
+
+    #[error("This backup is encrypted; enter its passphrase to import it")]
+    PassphraseRequired,
+
+    #[error("Wrong passphrase, or the backup is corrupted")]
+    Decryption,
+
+    #[error("Encryption failed: {0}")]
+    Encryption(String),
+}
+
+pub type BackupResult<T> = Result<T, BackupError>;
@@ -0,0 +1,377 @@
+//! Conflict resolution for merging imports
+//!
+//! Each incoming record is compared with the local record that has the same
+//! key. Records with no local counterpart are added, identical ones are left
+//! alone, and real conflicts are settled by the strategy chosen for that
+//! record's category. Every decision is tallied in an [`ImportReport`], which
+//! a dry run returns without writing anything.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::models::{BadgeProgress, ComprehensionCheck, MasteryScore, NodeProgress, NodeStatus, Note, ReviewItem, User};
+
+/// How to settle a record that exists both locally and in the backup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum ConflictStrategy {
+    /// Keep whichever copy was updated most recently
+    KeepNewest,
+    /// Keep the local copy
+    KeepLocal,
+    /// Overwrite the local copy with the backup's
+    KeepIncoming,
+    /// Combine both copies, keeping the highest XP, scores and progress
+    MergeMax,
+}
+
+/// Strategy per record category
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(default)]
+pub struct ConflictStrategies {
+    pub user: ConflictStrategy,
+    pub progress: ConflictStrategy,
+    pub mastery: ConflictStrategy,
+    pub badges: ConflictStrategy,
+    pub reviews: ConflictStrategy,
+    pub notes: ConflictStrategy,
+}
+
+impl Default for ConflictStrategies {
+    fn default() -> Self {
+        Self {
+            user: ConflictStrategy::MergeMax,
+            progress: ConflictStrategy::KeepNewest,
+            mastery: ConflictStrategy::KeepNewest,
+            badges: ConflictStrategy::MergeMax,
+            reviews: ConflictStrategy::KeepNewest,
+            notes: ConflictStrategy::KeepNewest,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum ImportCategory {
+    User,
+    Progress,
+    Mastery,
+    Badges,
+    Reviews,
+    QuizAttempts,
+    Notes,
+    GradeHistory,
+}
+
+/// What an import did, or would do, to one category of records
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct CategoryReport {
+    pub category: ImportCategory,
+    /// `None` for append-only records, which never conflict
+    pub strategy: Option<ConflictStrategy>,
+    /// Records the database did not have
+    pub added: usize,
+    /// Records identical on both sides
+    pub unchanged: usize,
+    /// Conflicts settled by writing the backup's or a merged copy
+    pub updated: usize,
+    /// Conflicts settled by keeping the local copy
+    pub kept_local: usize,
+    /// Local records cleared by a replacing import
+    pub removed: usize,
+}
+
+impl CategoryReport {
+    pub fn new(category: ImportCategory, strategy: Option<ConflictStrategy>) -> Self {
+        Self {
+            category,
+            strategy,
+            added: 0,
+            unchanged: 0,
+            updated: 0,
+            kept_local: 0,
+            removed: 0,
+        }
+    }
+
+    pub fn conflicts(&self) -> usize {
+        self.updated + self.kept_local
+    }
+
+    /// Tally a resolution, returning the record to write if any
+    pub(crate) fn record<T>(&mut self, resolution: Resolution<T>) -> Option<T> {
+        match resolution {
+            Resolution::Add(record) => {
+                self.added += 1;
+                Some(record)
+            }
+            Resolution::Update(record) => {
+                self.updated += 1;
+                Some(record)
+            }
+            Resolution::Unchanged => {
+                self.unchanged += 1;
+                None
+            }
+            Resolution::KeepLocal => {
+                self.kept_local += 1;
+                None
+            }
+        }
+    }
+}
+
+/// Outcome of an import, per category
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ImportReport {
+    /// The backed-up user, created if they were missing
+    pub user_id: Option<String>,
+    /// True if nothing was written
+    pub dry_run: bool,
+    pub categories: Vec<CategoryReport>,
+}
+
+impl ImportReport {
+    /// Records added or overwritten, not counting the user
+    pub fn records_imported(&self) -> usize {
+        self.categories
+            .iter()
+            .filter(|c| c.category != ImportCategory::User)
+            .map(|c| c.added + c.updated)
+            .sum()
+    }
+
+    pub fn conflicts(&self) -> usize {
+        self.categories.iter().map(CategoryReport::conflicts).sum()
+    }
+
+    pub fn category(&self, category: ImportCategory) -> Option<&CategoryReport> {
+        self.categories.iter().find(|c| c.category == category)
+    }
+}
+
+pub(crate) enum Resolution<T> {
+    Add(T),
+    Unchanged,
+    Update(T),
+    KeepLocal,
+}
+
+/// A record that can be reconciled with a local copy of itself
+pub(crate) trait Reconcile: Clone + Serialize {
+    /// When this copy last changed, if known
+    fn updated_at(&self) -> Option<DateTime<Utc>>;
+
+    /// Combine with another copy of the same record, keeping the larger values
+    fn merge_max(&self, other: &Self) -> Self;
+}
+
+fn same<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// Decide what to do with an incoming record given its local counterpart
+pub(crate) fn resolve<T: Reconcile>(local: Option<T>, incoming: &T, strategy: ConflictStrategy) -> Resolution<T> {
+    let Some(local) = local else {
+        return Resolution::Add(incoming.clone());
+    };
+    if same(&local, incoming) {
+        return Resolution::Unchanged;
+    }
+
+    let chosen = match strategy {
+        ConflictStrategy::KeepLocal => None,
+        ConflictStrategy::KeepIncoming => Some(incoming.clone()),
+        ConflictStrategy::KeepNewest => (incoming.updated_at() > local.updated_at()).then(|| incoming.clone()),
+        ConflictStrategy::MergeMax => Some(local.merge_max(incoming)),
+    };
+
+    match chosen {
+        Some(record) if !same(&record, &local) => Resolution::Update(record),
+        _ => Resolution::KeepLocal,
+    }
+}
+
+fn earliest(a: Option<DateTime<Utc>>, b: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+impl Reconcile for User {
+    fn updated_at(&self) -> Option<DateTime<Utc>> {
+        Some(self.last_activity)
+    }
+
+    fn merge_max(&self, other: &Self) -> Self {
+        let streak = if other.current_streak > self.current_streak { other } else { self };
+        Self {
+            created_at: self.created_at.min(other.created_at),
+            last_activity: self.last_activity.max(other.last_activity),
+            total_xp: self.total_xp.max(other.total_xp),
+            current_level: self.current_level.max(other.current_level),
+            current_streak: streak.current_streak,
+            last_streak_date: streak.last_streak_date,
+            display_name: self.display_name.clone().or_else(|| other.display_name.clone()),
+            ..self.clone()
+        }
+    }
+}
+
+/// How far along a status is, for keeping the furthest one
+fn status_rank(status: &NodeStatus) -> u8 {
+    match status {
+        NodeStatus::NotStarted => 0,
+        NodeStatus::Failed => 1,
+        NodeStatus::InProgress => 2,
+        NodeStatus::Completed => 3,
+    }
+}
+
+impl Reconcile for NodeProgress {
+    fn updated_at(&self) -> Option<DateTime<Utc>> {
+        Some(self.last_updated_at)
+    }
+
+    fn merge_max(&self, other: &Self) -> Self {
+        let status = if status_rank(&other.status) > status_rank(&self.status) {
+            other.status.clone()
+        } else {
+            self.status.clone()
+        };
+        Self {
+            status,
+            attempts: self.attempts.max(other.attempts),
+            time_spent_mins: self.time_spent_mins.max(other.time_spent_mins),
+            first_started_at: earliest(self.first_started_at, other.first_started_at),
+            completed_at: earliest(self.completed_at, other.completed_at),
+            last_updated_at: self.last_updated_at.max(other.last_updated_at),
+            comprehension_check: if other.comprehension_check == Some(ComprehensionCheck::Passed) {
+                other.comprehension_check
+            } else {
+                self.comprehension_check.or(other.comprehension_check)
+            },
+            ..self.clone()
+        }
+    }
+}
+
+impl Reconcile for MasteryScore {
+    fn updated_at(&self) -> Option<DateTime<Utc>> {
+        Some(self.last_updated_at)
+    }
+
+    fn merge_max(&self, other: &Self) -> Self {
+        Self {
+            score: self.score.max(other.score),
+            last_updated_at: self.last_updated_at.max(other.last_updated_at),
+            ..self.clone()
+        }
+    }
+}
+
+impl Reconcile for BadgeProgress {
+    fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.earned_at
+    }
+
+    fn merge_max(&self, other: &Self) -> Self {
+        Self {
+            current_value: self.current_value.max(other.current_value),
+            earned_at: earliest(self.earned_at, other.earned_at),
+            tier: self.tier.max(other.tier),
+            ..self.clone()
+        }
+    }
+}
+
+impl Reconcile for ReviewItem {
+    fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.last_reviewed_at
+    }
+
+    /// Scheduling state can't be combined field by field, so the most
+    /// recently reviewed copy wins, keeping the higher lapse count
+    fn merge_max(&self, other: &Self) -> Self {
+        let newest = if other.last_reviewed_at > self.last_reviewed_at { other } else { self };
+        Self {
+            lapses: self.lapses.max(other.lapses),
+            ..newest.clone()
+        }
+    }
+}
+
+impl Reconcile for Note {
+    fn updated_at(&self) -> Option<DateTime<Utc>> {
+        Some(self.updated_at)
+    }
+
+    /// Text can't be combined, so the most recently edited copy wins
+    fn merge_max(&self, other: &Self) -> Self {
+        if other.updated_at > self.updated_at { other.clone() } else { self.clone() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn progress(status: NodeStatus, attempts: i32, minutes_ago: i64) -> NodeProgress {
+        NodeProgress {
+            status,
+            attempts,
+            last_updated_at: Utc::now() - Duration::minutes(minutes_ago),
+            ..NodeProgress::new("test-user".to_string(), "week1-lecture".to_string())
+        }
+    }
+
+    #[test]
+    fn test_strategies_settle_conflicts() {
+        let local = progress(NodeStatus::Completed, 1, 60);
+        let incoming = progress(NodeStatus::InProgress, 3, 5);
+
+        assert!(matches!(resolve(Some(local.clone()), &incoming, ConflictStrategy::KeepLocal), Resolution::KeepLocal));
+        assert!(matches!(
+            resolve(Some(local.clone()), &incoming, ConflictStrategy::KeepNewest),
+            Resolution::Update(p) if p.status == NodeStatus::InProgress
+        ));
+        assert!(matches!(
+            resolve(Some(incoming.clone()), &local, ConflictStrategy::KeepNewest),
+            Resolution::KeepLocal
+        ));
+        assert!(matches!(
+            resolve(Some(local.clone()), &incoming, ConflictStrategy::MergeMax),
+            Resolution::Update(p) if p.status == NodeStatus::Completed && p.attempts == 3
+        ));
+        assert!(matches!(resolve(None, &incoming, ConflictStrategy::KeepLocal), Resolution::Add(_)));
+        assert!(matches!(resolve(Some(local.clone()), &local, ConflictStrategy::KeepIncoming), Resolution::Unchanged));
+    }
+
+    #[test]
+    fn test_merge_max_keeps_highest_xp() {
+        let mut local = User::new("test-user".to_string());
+        local.total_xp = 500;
+        local.current_level = 3;
+        let mut incoming = local.clone();
+        incoming.total_xp = 300;
+        incoming.current_streak = 9;
+
+        let merged = local.merge_max(&incoming);
+        assert_eq!(merged.total_xp, 500);
+        assert_eq!(merged.current_level, 3);
+        assert_eq!(merged.current_streak, 9);
+    }
+
+    #[test]
+    fn test_report_tallies() {
+        let mut report = CategoryReport::new(ImportCategory::Progress, Some(ConflictStrategy::KeepNewest));
+        assert_eq!(report.record(Resolution::Add(1)), Some(1));
+        assert_eq!(report.record(Resolution::<i32>::KeepLocal), None);
+        assert_eq!(report.record(Resolution::<i32>::Unchanged), None);
+        assert_eq!((report.added, report.kept_local, report.unchanged, report.conflicts()), (1, 1, 1, 1));
+    }
+}
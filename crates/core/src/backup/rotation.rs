@@ -0,0 +1,94 @@
+//! Automatic backups kept in a folder, with only the newest few retained
+//!
+//! Files are named `<prefix>-<UTC timestamp>.glpbackup` so that sorting by
+//! name sorts by age.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::backup::{write_file, BackupData, BackupResult};
+
+pub const EXTENSION: &str = "glpbackup";
+
+/// File name for a backup taken at `backup.exported_at`
+pub fn file_name(prefix: &str, backup: &BackupData) -> String {
+    format!("{}-{}.{}", prefix, backup.exported_at.format("%Y%m%dT%H%M%SZ"), EXTENSION)
+}
+
+/// Write `backup` into `dir`, then delete all but the newest `keep` backups
+/// with the same prefix. Returns the path written.
+pub fn write_rotated(dir: &Path, prefix: &str, backup: &BackupData, keep: usize) -> BackupResult<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(file_name(prefix, backup));
+    write_file(&path, backup, None)?;
+    rotate(dir, prefix, keep)?;
+    Ok(path)
+}
+
+/// Delete all but the newest `keep` backups in `dir` with the given prefix,
+/// returning the paths removed. Other files in the folder are left alone.
+pub fn rotate(dir: &Path, prefix: &str, keep: usize) -> BackupResult<Vec<PathBuf>> {
+    let name_prefix = format!("{}-", prefix);
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let matches = path.extension().is_some_and(|ext| ext == EXTENSION)
+            && path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(&name_prefix));
+        if matches {
+            backups.push(path);
+        }
+    }
+    backups.sort();
+
+    let excess = backups.len().saturating_sub(keep);
+    let mut removed = Vec::new();
+    for path in backups.into_iter().take(excess) {
+        fs::remove_file(&path)?;
+        removed.push(path);
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backup::contents::CONTENTS_VERSION;
+    use chrono::{Duration, Utc};
+    use tempfile::tempdir;
+
+    fn backup_at(days_ago: i64) -> BackupData {
+        BackupData {
+            version: CONTENTS_VERSION.to_string(),
+            schema_version: 13,
+            exported_at: Utc::now() - Duration::days(days_ago),
+            user: None,
+            node_progress: Vec::new(),
+            quiz_attempts: Vec::new(),
+            mastery_scores: Vec::new(),
+            badge_progress: Vec::new(),
+            review_items: Vec::new(),
+            notes: Vec::new(),
+            artifact_submissions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_rotation_keeps_newest_with_prefix() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("manual.glpbackup"), b"keep me").unwrap();
+
+        let mut written = Vec::new();
+        for days_ago in [3, 2, 1, 0] {
+            written.push(write_rotated(dir.path(), "auto", &backup_at(days_ago), 2).unwrap());
+        }
+
+        assert!(!written[0].exists());
+        assert!(!written[1].exists());
+        assert!(written[2].exists());
+        assert!(written[3].exists());
+        assert!(dir.path().join("manual.glpbackup").exists());
+        assert!(rotate(dir.path(), "auto", 2).unwrap().is_empty());
+    }
+}
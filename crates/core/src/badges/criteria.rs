@@ -0,0 +1,488 @@
+//! Composite badge criteria expressions
+//!
+//! Badges can carry a criteria expression such as
+//! `streak >= 7 AND completed_quizzes >= 5` instead of relying on a single
+//! category threshold. `AND` binds tighter than `OR`, and parentheses can be
+//! used to group terms.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use super::tracker::UserStats;
+
+/// A user stat that criteria can compare against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum BadgeStat {
+    Streak,
+    Level,
+    TotalXp,
+    CompletedLectures,
+    CompletedQuizzes,
+    CompletedChallenges,
+    TotalCompletions,
+    PerfectQuizzes,
+    MaxMastery,
+}
+
+impl BadgeStat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BadgeStat::Streak => "streak",
+            BadgeStat::Level => "level",
+            BadgeStat::TotalXp => "total_xp",
+            BadgeStat::CompletedLectures => "completed_lectures",
+            BadgeStat::CompletedQuizzes => "completed_quizzes",
+            BadgeStat::CompletedChallenges => "completed_challenges",
+            BadgeStat::TotalCompletions => "total_completions",
+            BadgeStat::PerfectQuizzes => "perfect_quizzes",
+            BadgeStat::MaxMastery => "max_mastery",
+        }
+    }
+}
+
+impl FromStr for BadgeStat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "streak" | "streak_days" => Ok(BadgeStat::Streak),
+            "level" => Ok(BadgeStat::Level),
+            "total_xp" | "xp" => Ok(BadgeStat::TotalXp),
+            "completed_lectures" => Ok(BadgeStat::CompletedLectures),
+            "completed_quizzes" => Ok(BadgeStat::CompletedQuizzes),
+            "completed_challenges" => Ok(BadgeStat::CompletedChallenges),
+            "total_completions" => Ok(BadgeStat::TotalCompletions),
+            "perfect_quizzes" | "perfect_quiz_count" => Ok(BadgeStat::PerfectQuizzes),
+            "max_mastery" | "max_mastery_score" => Ok(BadgeStat::MaxMastery),
+            _ => Err(format!("Unknown badge stat: {}", s)),
+        }
+    }
+}
+
+/// Comparison operator used in a criteria term
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum Comparison {
+    GreaterOrEqual,
+    Greater,
+    LessOrEqual,
+    Less,
+    Equal,
+}
+
+impl Comparison {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Comparison::GreaterOrEqual => ">=",
+            Comparison::Greater => ">",
+            Comparison::LessOrEqual => "<=",
+            Comparison::Less => "<",
+            Comparison::Equal => "==",
+        }
+    }
+
+    pub fn compare(&self, actual: f64, expected: f64) -> bool {
+        match self {
+            Comparison::GreaterOrEqual => actual >= expected,
+            Comparison::Greater => actual > expected,
+            Comparison::LessOrEqual => actual <= expected,
+            Comparison::Less => actual < expected,
+            Comparison::Equal => (actual - expected).abs() < f64::EPSILON,
+        }
+    }
+}
+
+/// A boolean expression over user stats
+///
+/// Serialized as its expression string so badge definitions stay readable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(try_from = "String", into = "String")]
+pub enum BadgeCriteria {
+    Compare {
+        stat: BadgeStat,
+        op: Comparison,
+        value: f64,
+    },
+    And(Vec<BadgeCriteria>),
+    Or(Vec<BadgeCriteria>),
+}
+
+impl BadgeCriteria {
+    /// Check whether the criteria holds for the given stats
+    pub fn evaluate(&self, stats: &UserStats) -> bool {
+        match self {
+            BadgeCriteria::Compare { stat, op, value } => op.compare(stats.get_stat(*stat), *value),
+            BadgeCriteria::And(terms) => terms.iter().all(|t| t.evaluate(stats)),
+            BadgeCriteria::Or(terms) => terms.iter().any(|t| t.evaluate(stats)),
+        }
+    }
+
+    /// Progress toward satisfying the criteria (0.0 to 1.0)
+    ///
+    /// `AND` averages its terms and `OR` takes the closest term. Only a
+    /// satisfied expression reports 1.0.
+    pub fn progress(&self, stats: &UserStats) -> f64 {
+        if self.evaluate(stats) {
+            return 1.0;
+        }
+
+        let partial = match self {
+            BadgeCriteria::Compare { stat, op, value } => match op {
+                Comparison::GreaterOrEqual | Comparison::Greater if *value > 0.0 => {
+                    (stats.get_stat(*stat) / value).max(0.0)
+                }
+                _ => 0.0,
+            },
+            BadgeCriteria::And(terms) => {
+                terms.iter().map(|t| t.progress(stats)).sum::<f64>() / terms.len() as f64
+            }
+            BadgeCriteria::Or(terms) => terms
+                .iter()
+                .map(|t| t.progress(stats))
+                .fold(0.0, f64::max),
+        };
+
+        partial.min(0.99)
+    }
+
+    fn fmt_grouped(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BadgeCriteria::Or(terms) if terms.len() > 1 => write!(f, "({})", self),
+            _ => write!(f, "{}", self),
+        }
+    }
+}
+
+impl fmt::Display for BadgeCriteria {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BadgeCriteria::Compare { stat, op, value } => {
+                write!(f, "{} {} {}", stat.as_str(), op.as_str(), value)
+            }
+            BadgeCriteria::And(terms) => {
+                for (i, term) in terms.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " AND ")?;
+                    }
+                    term.fmt_grouped(f)?;
+                }
+                Ok(())
+            }
+            BadgeCriteria::Or(terms) => {
+                for (i, term) in terms.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " OR ")?;
+                    }
+                    write!(f, "{}", term)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl FromStr for BadgeCriteria {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(s)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let criteria = parser.parse_or()?;
+
+        if let Some(token) = parser.peek() {
+            return Err(format!("Unexpected token in criteria: {:?}", token));
+        }
+
+        Ok(criteria)
+    }
+}
+
+impl TryFrom<String> for BadgeCriteria {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<BadgeCriteria> for String {
+    fn from(criteria: BadgeCriteria) -> Self {
+        criteria.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    Op(Comparison),
+    And,
+    Or,
+    LParen,
+    RParen,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '(' {
+            chars.next();
+            tokens.push(Token::LParen);
+        } else if c == ')' {
+            chars.next();
+            tokens.push(Token::RParen);
+        } else if matches!(c, '>' | '<' | '=') {
+            chars.next();
+            let has_eq = chars.peek() == Some(&'=');
+            if has_eq {
+                chars.next();
+            }
+            let op = match (c, has_eq) {
+                ('>', true) => Comparison::GreaterOrEqual,
+                ('>', false) => Comparison::Greater,
+                ('<', true) => Comparison::LessOrEqual,
+                ('<', false) => Comparison::Less,
+                _ => Comparison::Equal,
+            };
+            tokens.push(Token::Op(op));
+        } else if c == '&' || c == '|' {
+            chars.next();
+            if chars.next() != Some(c) {
+                return Err(format!("Expected '{}{}' in criteria", c, c));
+            }
+            tokens.push(if c == '&' { Token::And } else { Token::Or });
+        } else if c.is_ascii_digit() || c == '.' || c == '-' {
+            let mut number = String::new();
+            number.push(c);
+            chars.next();
+            while let Some(&d) = chars.peek() {
+                if d.is_ascii_digit() || d == '.' {
+                    number.push(d);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let value = number
+                .parse::<f64>()
+                .map_err(|_| format!("Invalid number in criteria: {}", number))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut ident = String::new();
+            while let Some(&d) = chars.peek() {
+                if d.is_ascii_alphanumeric() || d == '_' {
+                    ident.push(d);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            match ident.to_ascii_uppercase().as_str() {
+                "AND" => tokens.push(Token::And),
+                "OR" => tokens.push(Token::Or),
+                _ => tokens.push(Token::Ident(ident)),
+            }
+        } else {
+            return Err(format!("Unexpected character in criteria: '{}'", c));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<BadgeCriteria, String> {
+        let mut terms = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            terms.push(self.parse_and()?);
+        }
+        Ok(if terms.len() == 1 {
+            terms.remove(0)
+        } else {
+            BadgeCriteria::Or(terms)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<BadgeCriteria, String> {
+        let mut terms = vec![self.parse_primary()?];
+        while self.peek() == Some(&Token::And) {
+            self.next();
+            terms.push(self.parse_primary()?);
+        }
+        Ok(if terms.len() == 1 {
+            terms.remove(0)
+        } else {
+            BadgeCriteria::And(terms)
+        })
+    }
+
+    fn parse_primary(&mut self) -> Result<BadgeCriteria, String> {
+        match self.next() {
+            Some(Token::LParen) => {
+                let inner = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(inner),
+                    _ => Err("Expected ')' in criteria".to_string()),
+                }
+            }
+            Some(Token::Ident(name)) => {
+                let stat = name.parse::<BadgeStat>()?;
+                let op = match self.next() {
+                    Some(Token::Op(op)) => op,
+                    _ => return Err(format!("Expected comparison after '{}'", name)),
+                };
+                let value = match self.next() {
+                    Some(Token::Number(value)) => value,
+                    _ => return Err(format!("Expected number after '{} {}'", name, op.as_str())),
+                };
+                Ok(BadgeCriteria::Compare { stat, op, value })
+            }
+            Some(token) => Err(format!("Unexpected token in criteria: {:?}", token)),
+            None => Err("Unexpected end of criteria".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats() -> UserStats {
+        UserStats {
+            streak_days: 7,
+            level: 3,
+            total_xp: 450,
+            completed_quizzes: 5,
+            max_mastery_score: 0.5,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_single_comparison() {
+        let criteria: BadgeCriteria = "streak >= 7".parse().unwrap();
+        assert_eq!(
+            criteria,
+            BadgeCriteria::Compare {
+                stat: BadgeStat::Streak,
+                op: Comparison::GreaterOrEqual,
+                value: 7.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_and_binds_tighter_than_or() {
+        let criteria: BadgeCriteria = "level >= 10 OR streak >= 7 AND completed_quizzes >= 5"
+            .parse()
+            .unwrap();
+        match &criteria {
+            BadgeCriteria::Or(terms) => {
+                assert_eq!(terms.len(), 2);
+                assert!(matches!(terms[1], BadgeCriteria::And(_)));
+            }
+            other => panic!("Expected OR at the top level, got {:?}", other),
+        }
+        assert!(criteria.evaluate(&stats()));
+
+        // Grouping changes the meaning: level 3 fails the left side of the AND
+        let grouped: BadgeCriteria = "(level >= 10 OR streak >= 7) AND level >= 5"
+            .parse()
+            .unwrap();
+        assert!(!grouped.evaluate(&stats()));
+    }
+
+    #[test]
+    fn test_edge_values() {
+        let s = stats();
+        assert!("streak >= 7".parse::<BadgeCriteria>().unwrap().evaluate(&s));
+        assert!(!"streak > 7".parse::<BadgeCriteria>().unwrap().evaluate(&s));
+        assert!("streak <= 7".parse::<BadgeCriteria>().unwrap().evaluate(&s));
+        assert!(!"streak < 7".parse::<BadgeCriteria>().unwrap().evaluate(&s));
+        assert!("streak == 7".parse::<BadgeCriteria>().unwrap().evaluate(&s));
+        assert!("max_mastery >= 0.5".parse::<BadgeCriteria>().unwrap().evaluate(&s));
+        assert!("completed_challenges >= 0".parse::<BadgeCriteria>().unwrap().evaluate(&s));
+    }
+
+    #[test]
+    fn test_symbolic_operators_and_case() {
+        let criteria: BadgeCriteria = "streak >= 7 && (xp >= 1000 || completed_quizzes >= 5)"
+            .parse()
+            .unwrap();
+        assert!(criteria.evaluate(&stats()));
+
+        let lower: BadgeCriteria = "streak >= 7 and level >= 3".parse().unwrap();
+        assert!(lower.evaluate(&stats()));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("".parse::<BadgeCriteria>().is_err());
+        assert!("streak >=".parse::<BadgeCriteria>().is_err());
+        assert!("unknown_stat >= 1".parse::<BadgeCriteria>().is_err());
+        assert!("(streak >= 7".parse::<BadgeCriteria>().is_err());
+        assert!("streak >= 7 AND".parse::<BadgeCriteria>().is_err());
+        assert!("streak >= 7 level >= 3".parse::<BadgeCriteria>().is_err());
+        assert!("streak & 7".parse::<BadgeCriteria>().is_err());
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let source = "(level >= 10 OR streak >= 7) AND completed_quizzes >= 5";
+        let criteria: BadgeCriteria = source.parse().unwrap();
+        assert_eq!(criteria.to_string(), source);
+        assert_eq!(criteria.to_string().parse::<BadgeCriteria>().unwrap(), criteria);
+    }
+
+    #[test]
+    fn test_serde_as_string() {
+        let criteria: BadgeCriteria = "streak >= 7 AND completed_quizzes >= 5".parse().unwrap();
+        let json = serde_json::to_string(&criteria).unwrap();
+        assert_eq!(json, "\"streak >= 7 AND completed_quizzes >= 5\"");
+
+        let back: BadgeCriteria = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, criteria);
+
+        assert!(serde_json::from_str::<BadgeCriteria>("\"streak >>= 7\"").is_err());
+    }
+
+    #[test]
+    fn test_progress() {
+        let criteria: BadgeCriteria = "streak >= 14 AND completed_quizzes >= 10".parse().unwrap();
+        let progress = criteria.progress(&stats());
+        assert!((progress - 0.5).abs() < 0.001);
+
+        let either: BadgeCriteria = "streak >= 14 OR completed_quizzes >= 10".parse().unwrap();
+        assert!((either.progress(&stats()) - 0.5).abs() < 0.001);
+
+        // Strict comparison at the boundary is not yet satisfied
+        let strict: BadgeCriteria = "streak > 7".parse().unwrap();
+        assert!(strict.progress(&stats()) < 1.0);
+
+        let met: BadgeCriteria = "streak >= 7 AND completed_quizzes >= 5".parse().unwrap();
+        assert_eq!(met.progress(&stats()), 1.0);
+    }
+}
@@ -2,35 +2,25 @@
 //!
 //! This module defines all available badges and their unlock criteria.
 
-use crate::models::{BadgeCategory, BadgeDefinition};
+use crate::models::{BadgeCategory, BadgeDefinition, BadgeTier, BadgeTierThreshold};
 
 /// Returns all badge definitions for the platform
 pub fn get_all_badge_definitions() -> Vec<BadgeDefinition> {
     vec![
         // Streak badges
         BadgeDefinition {
-            id: "week_warrior".to_string(),
-            name: "Week Warrior".to_string(),
-            description: "Maintain a 7-day learning streak".to_string(),
+            id: "streak_keeper".to_string(),
+            name: "Streak Keeper".to_string(),
+            description: "Maintain a 7, 30 and 100-day learning streak".to_string(),
             icon: "🔥".to_string(),
             threshold: 7.0,
             category: BadgeCategory::Streak,
-        },
-        BadgeDefinition {
-            id: "streak_master".to_string(),
-            name: "Streak Master".to_string(),
-            description: "Maintain a 30-day learning streak".to_string(),
-            icon: "⚡".to_string(),
-            threshold: 30.0,
-            category: BadgeCategory::Streak,
-        },
-        BadgeDefinition {
-            id: "unstoppable".to_string(),
-            name: "Unstoppable".to_string(),
-            description: "Maintain a 100-day learning streak".to_string(),
-            icon: "💫".to_string(),
-            threshold: 100.0,
-            category: BadgeCategory::Streak,
+            criteria: None,
+            tiers: vec![
+                BadgeTierThreshold::new(BadgeTier::Bronze, 7.0),
+                BadgeTierThreshold::new(BadgeTier::Silver, 30.0),
+                BadgeTierThreshold::new(BadgeTier::Gold, 100.0),
+            ],
         },
         // Level badges
         BadgeDefinition {
@@ -40,6 +30,8 @@ pub fn get_all_badge_definitions() -> Vec<BadgeDefinition> {
             icon: "⭐".to_string(),
             threshold: 5.0,
             category: BadgeCategory::Level,
+            criteria: None,
+            tiers: Vec::new(),
         },
         BadgeDefinition {
             id: "apprentice".to_string(),
@@ -48,6 +40,8 @@ pub fn get_all_badge_definitions() -> Vec<BadgeDefinition> {
             icon: "🌟".to_string(),
             threshold: 10.0,
             category: BadgeCategory::Level,
+            criteria: None,
+            tiers: Vec::new(),
         },
         BadgeDefinition {
             id: "journeyman".to_string(),
@@ -56,31 +50,23 @@ pub fn get_all_badge_definitions() -> Vec<BadgeDefinition> {
             icon: "✨".to_string(),
             threshold: 20.0,
             category: BadgeCategory::Level,
+            criteria: None,
+            tiers: Vec::new(),
         },
         // XP badges
         BadgeDefinition {
-            id: "xp_hunter".to_string(),
-            name: "XP Hunter".to_string(),
-            description: "Earn 1,000 total XP".to_string(),
+            id: "xp_earner".to_string(),
+            name: "XP Earner".to_string(),
+            description: "Earn 1,000, 5,000 and 10,000 total XP".to_string(),
             icon: "💎".to_string(),
             threshold: 1000.0,
             category: BadgeCategory::Xp,
-        },
-        BadgeDefinition {
-            id: "xp_collector".to_string(),
-            name: "XP Collector".to_string(),
-            description: "Earn 5,000 total XP".to_string(),
-            icon: "💰".to_string(),
-            threshold: 5000.0,
-            category: BadgeCategory::Xp,
-        },
-        BadgeDefinition {
-            id: "xp_legend".to_string(),
-            name: "XP Legend".to_string(),
-            description: "Earn 10,000 total XP".to_string(),
-            icon: "👑".to_string(),
-            threshold: 10000.0,
-            category: BadgeCategory::Xp,
+            criteria: None,
+            tiers: vec![
+                BadgeTierThreshold::new(BadgeTier::Bronze, 1000.0),
+                BadgeTierThreshold::new(BadgeTier::Silver, 5000.0),
+                BadgeTierThreshold::new(BadgeTier::Gold, 10000.0),
+            ],
         },
         // Completion badges
         BadgeDefinition {
@@ -90,6 +76,8 @@ pub fn get_all_badge_definitions() -> Vec<BadgeDefinition> {
             icon: "👣".to_string(),
             threshold: 1.0,
             category: BadgeCategory::Completion,
+            criteria: None,
+            tiers: Vec::new(),
         },
         BadgeDefinition {
             id: "quiz_whiz".to_string(),
@@ -98,6 +86,8 @@ pub fn get_all_badge_definitions() -> Vec<BadgeDefinition> {
             icon: "📝".to_string(),
             threshold: 10.0,
             category: BadgeCategory::Completion,
+            criteria: None,
+            tiers: Vec::new(),
         },
         BadgeDefinition {
             id: "completionist".to_string(),
@@ -106,6 +96,8 @@ pub fn get_all_badge_definitions() -> Vec<BadgeDefinition> {
             icon: "🏆".to_string(),
             threshold: 50.0,
             category: BadgeCategory::Completion,
+            criteria: None,
+            tiers: Vec::new(),
         },
         BadgeDefinition {
             id: "perfect_score".to_string(),
@@ -114,6 +106,8 @@ pub fn get_all_badge_definitions() -> Vec<BadgeDefinition> {
             icon: "💯".to_string(),
             threshold: 1.0,
             category: BadgeCategory::Completion,
+            criteria: None,
+            tiers: Vec::new(),
         },
         // Mastery badges
         BadgeDefinition {
@@ -123,6 +117,8 @@ pub fn get_all_badge_definitions() -> Vec<BadgeDefinition> {
             icon: "🎯".to_string(),
             threshold: 0.5,
             category: BadgeCategory::Mastery,
+            criteria: None,
+            tiers: Vec::new(),
         },
         BadgeDefinition {
             id: "skill_master".to_string(),
@@ -131,6 +127,8 @@ pub fn get_all_badge_definitions() -> Vec<BadgeDefinition> {
             icon: "🏅".to_string(),
             threshold: 0.9,
             category: BadgeCategory::Mastery,
+            criteria: None,
+            tiers: Vec::new(),
         },
     ]
 }
@@ -174,9 +172,9 @@ mod tests {
 
     #[test]
     fn test_get_badge_by_id() {
-        let badge = get_badge_by_id("week_warrior");
+        let badge = get_badge_by_id("streak_keeper");
         assert!(badge.is_some());
-        assert_eq!(badge.unwrap().name, "Week Warrior");
+        assert_eq!(badge.unwrap().name, "Streak Keeper");
 
         let missing = get_badge_by_id("nonexistent");
         assert!(missing.is_none());
@@ -185,12 +183,24 @@ mod tests {
     #[test]
     fn test_get_badges_by_category() {
         let streak_badges = get_badges_by_category(BadgeCategory::Streak);
-        assert_eq!(streak_badges.len(), 3);
+        assert_eq!(streak_badges.len(), 1);
+        assert_eq!(streak_badges[0].tiers.len(), 3);
 
         let level_badges = get_badges_by_category(BadgeCategory::Level);
         assert_eq!(level_badges.len(), 3);
     }
 
+    #[test]
+    fn test_tiers_are_ascending_and_match_threshold() {
+        for badge in get_all_badge_definitions().iter().filter(|b| b.is_tiered()) {
+            assert_eq!(badge.tiers[0].threshold, badge.threshold, "{} threshold should match its lowest tier", badge.id);
+            for pair in badge.tiers.windows(2) {
+                assert!(pair[0].tier < pair[1].tier, "{} tiers out of order", badge.id);
+                assert!(pair[0].threshold < pair[1].threshold, "{} thresholds not ascending", badge.id);
+            }
+        }
+    }
+
     #[test]
     fn test_unique_badge_ids() {
         let badges = get_all_badge_definitions();
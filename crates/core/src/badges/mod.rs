@@ -2,8 +2,13 @@
 //!
 //! This module provides badge definitions, tracking, and unlock logic.
 
+pub mod criteria;
 pub mod definitions;
 pub mod tracker;
 
+pub use criteria::{BadgeCriteria, BadgeStat, Comparison};
 pub use definitions::{get_all_badge_definitions, get_badge_by_id, get_badges_by_category};
-pub use tracker::{check_badge_unlocks, check_single_badge, calculate_badge_progress, UserStats};
+pub use tracker::{
+    badge_value, calculate_badge_progress, check_badge_unlocks, check_single_badge,
+    check_tier_upgrades, next_tier_progress, NextTierProgress, UserStats,
+};
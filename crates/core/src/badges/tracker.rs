@@ -3,7 +3,9 @@
 //! This module provides functionality to check which badges a user has earned
 //! based on their current stats.
 
-use crate::models::{BadgeCategory, BadgeDefinition, BadgeProgress};
+use crate::models::{BadgeCategory, BadgeDefinition, BadgeProgress, BadgeTier};
+use serde::{Deserialize, Serialize};
+use super::criteria::BadgeStat;
 use super::definitions::get_all_badge_definitions;
 
 /// User stats used for badge evaluation
@@ -31,6 +33,21 @@ impl UserStats {
             BadgeCategory::Mastery => self.max_mastery_score,
         }
     }
+
+    /// Get the value of a single stat referenced by badge criteria
+    pub fn get_stat(&self, stat: BadgeStat) -> f64 {
+        match stat {
+            BadgeStat::Streak => self.streak_days as f64,
+            BadgeStat::Level => self.level as f64,
+            BadgeStat::TotalXp => self.total_xp as f64,
+            BadgeStat::CompletedLectures => self.completed_lectures as f64,
+            BadgeStat::CompletedQuizzes => self.completed_quizzes as f64,
+            BadgeStat::CompletedChallenges => self.completed_challenges as f64,
+            BadgeStat::TotalCompletions => self.total_completions as f64,
+            BadgeStat::PerfectQuizzes => self.perfect_quiz_count as f64,
+            BadgeStat::MaxMastery => self.max_mastery_score,
+        }
+    }
 }
 
 /// Check which badges should be unlocked based on user stats
@@ -59,6 +76,10 @@ pub fn check_badge_unlocks(
 
 /// Check if a single badge's criteria is met
 pub fn check_single_badge(badge: &BadgeDefinition, stats: &UserStats) -> bool {
+    if let Some(criteria) = &badge.criteria {
+        return criteria.evaluate(stats);
+    }
+
     match badge.category {
         BadgeCategory::Streak => stats.streak_days as f64 >= badge.threshold,
         BadgeCategory::Level => stats.level as f64 >= badge.threshold,
@@ -77,12 +98,9 @@ pub fn check_single_badge(badge: &BadgeDefinition, stats: &UserStats) -> bool {
     }
 }
 
-/// Calculate badge progress as a percentage (0.0 to 1.0)
-pub fn calculate_badge_progress(badge: &BadgeDefinition, stats: &UserStats) -> f64 {
-    let current_value = match badge.category {
-        BadgeCategory::Streak => stats.streak_days as f64,
-        BadgeCategory::Level => stats.level as f64,
-        BadgeCategory::Xp => stats.total_xp as f64,
+/// Current value of the stat a badge tracks
+pub fn badge_value(badge: &BadgeDefinition, stats: &UserStats) -> f64 {
+    match badge.category {
         BadgeCategory::Completion => {
             match badge.id.as_str() {
                 "first_steps" => stats.completed_lectures as f64,
@@ -92,15 +110,82 @@ pub fn calculate_badge_progress(badge: &BadgeDefinition, stats: &UserStats) -> f
                 _ => stats.total_completions as f64,
             }
         }
-        BadgeCategory::Mastery => stats.max_mastery_score,
-    };
+        _ => stats.get_value_for_category(&badge.category),
+    }
+}
+
+/// Calculate badge progress as a percentage (0.0 to 1.0)
+pub fn calculate_badge_progress(badge: &BadgeDefinition, stats: &UserStats) -> f64 {
+    if let Some(criteria) = &badge.criteria {
+        return criteria.progress(stats);
+    }
+
+    (badge_value(badge, stats) / badge.threshold).min(1.0)
+}
+
+/// Progress toward the next tier of a tiered badge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct NextTierProgress {
+    pub tier: BadgeTier,
+    pub threshold: f64,
+    pub current_value: f64,
+    /// Fraction of the next tier's threshold reached (0.0 to 1.0)
+    pub progress: f64,
+}
+
+/// Report progress toward the next unearned tier.
+/// Returns None for untiered badges or once the top tier is reached.
+pub fn next_tier_progress(
+    badge: &BadgeDefinition,
+    stats: &UserStats,
+    earned_tier: Option<BadgeTier>,
+) -> Option<NextTierProgress> {
+    let current_value = badge_value(badge, stats);
+    let reached = earned_tier.max(badge.tier_for_value(current_value));
+    let next = badge.next_tier(reached)?;
+
+    Some(NextTierProgress {
+        tier: next.tier,
+        threshold: next.threshold,
+        current_value,
+        progress: (current_value / next.threshold).min(1.0),
+    })
+}
+
+/// Check which already-earned tiered badges have reached a higher tier.
+/// Returns (badge ID, new tier) pairs; first-time unlocks come from `check_badge_unlocks`.
+pub fn check_tier_upgrades(
+    stats: &UserStats,
+    current_progress: &[BadgeProgress],
+) -> Vec<(String, BadgeTier)> {
+    let mut upgrades = Vec::new();
+
+    for badge_def in get_all_badge_definitions().into_iter().filter(|b| b.is_tiered()) {
+        let Some(progress) = current_progress
+            .iter()
+            .find(|p| p.badge_id == badge_def.id && p.is_earned())
+        else {
+            continue;
+        };
+
+        // Earned records without a tier predate tiers and count as the lowest one
+        let earned_tier = progress.tier.or_else(|| badge_def.tiers.first().map(|t| t.tier));
+
+        if let Some(reached) = badge_def.tier_for_value(badge_value(&badge_def, stats)) {
+            if earned_tier.is_none_or(|t| reached > t) {
+                upgrades.push((badge_def.id, reached));
+            }
+        }
+    }
 
-    (current_value / badge.threshold).min(1.0)
+    upgrades
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::badges::get_badge_by_id;
 
     #[test]
     fn test_streak_badge_unlock() {
@@ -115,6 +200,8 @@ mod tests {
             icon: "🔥".to_string(),
             threshold: 7.0,
             category: BadgeCategory::Streak,
+            criteria: None,
+            tiers: Vec::new(),
         };
         
         assert!(check_single_badge(&badge, &stats));
@@ -133,6 +220,8 @@ mod tests {
             icon: "⭐".to_string(),
             threshold: 5.0,
             category: BadgeCategory::Level,
+            criteria: None,
+            tiers: Vec::new(),
         };
         
         assert!(check_single_badge(&badge, &stats));
@@ -151,6 +240,8 @@ mod tests {
             icon: "💎".to_string(),
             threshold: 1000.0,
             category: BadgeCategory::Xp,
+            criteria: None,
+            tiers: Vec::new(),
         };
         
         assert!(check_single_badge(&badge, &stats));
@@ -170,6 +261,8 @@ mod tests {
             icon: "👣".to_string(),
             threshold: 1.0,
             category: BadgeCategory::Completion,
+            criteria: None,
+            tiers: Vec::new(),
         };
         
         assert!(check_single_badge(&badge, &stats));
@@ -188,6 +281,8 @@ mod tests {
             icon: "🏅".to_string(),
             threshold: 0.9,
             category: BadgeCategory::Mastery,
+            criteria: None,
+            tiers: Vec::new(),
         };
         
         assert!(check_single_badge(&badge, &stats));
@@ -201,13 +296,13 @@ mod tests {
         };
         
         // Already earned badge
-        let mut progress = BadgeProgress::new("user1".to_string(), "week_warrior".to_string());
+        let mut progress = BadgeProgress::new("user1".to_string(), "streak_keeper".to_string());
         progress.update_progress(7.0, 7.0); // This marks it as earned
         
         let newly_unlocked = check_badge_unlocks(&stats, &[progress]);
         
-        // week_warrior should not be in newly unlocked since it's already earned
-        assert!(!newly_unlocked.contains(&"week_warrior".to_string()));
+        // streak_keeper should not be in newly unlocked since it's already earned
+        assert!(!newly_unlocked.contains(&"streak_keeper".to_string()));
     }
 
     #[test]
@@ -224,9 +319,9 @@ mod tests {
         let newly_unlocked = check_badge_unlocks(&stats, &[]);
         
         // Should unlock multiple badges
-        assert!(newly_unlocked.contains(&"week_warrior".to_string()));
+        assert!(newly_unlocked.contains(&"streak_keeper".to_string()));
         assert!(newly_unlocked.contains(&"rising_star".to_string()));
-        assert!(newly_unlocked.contains(&"xp_hunter".to_string()));
+        assert!(newly_unlocked.contains(&"xp_earner".to_string()));
         assert!(newly_unlocked.contains(&"first_steps".to_string()));
     }
 
@@ -243,9 +338,92 @@ mod tests {
             icon: "🔥".to_string(),
             threshold: 7.0,
             category: BadgeCategory::Streak,
+            criteria: None,
+            tiers: Vec::new(),
         };
         
         let progress = calculate_badge_progress(&badge, &stats);
         assert!((progress - (3.0 / 7.0)).abs() < 0.01);
     }
+
+    #[test]
+    fn test_composite_criteria_overrides_threshold() {
+        let badge = BadgeDefinition {
+            id: "dedicated_quizzer".to_string(),
+            name: "Dedicated Quizzer".to_string(),
+            description: "7-day streak and 5 quizzes".to_string(),
+            icon: "🧠".to_string(),
+            threshold: 1.0,
+            category: BadgeCategory::Streak,
+            criteria: Some("streak >= 7 AND completed_quizzes >= 5".parse().unwrap()),
+            tiers: Vec::new(),
+        };
+
+        let streak_only = UserStats {
+            streak_days: 10,
+            ..Default::default()
+        };
+        assert!(!check_single_badge(&badge, &streak_only));
+
+        let both = UserStats {
+            streak_days: 7,
+            completed_quizzes: 5,
+            ..Default::default()
+        };
+        assert!(check_single_badge(&badge, &both));
+        assert_eq!(calculate_badge_progress(&badge, &both), 1.0);
+    }
+
+    #[test]
+    fn test_next_tier_progress() {
+        let badge = get_badge_by_id("streak_keeper").unwrap();
+
+        let stats = UserStats {
+            streak_days: 3,
+            ..Default::default()
+        };
+        let next = next_tier_progress(&badge, &stats, None).unwrap();
+        assert_eq!(next.tier, BadgeTier::Bronze);
+        assert!((next.progress - 3.0 / 7.0).abs() < 0.01);
+
+        // Progress is shared: the same streak counts toward silver once bronze is held
+        let stats = UserStats {
+            streak_days: 15,
+            ..Default::default()
+        };
+        let next = next_tier_progress(&badge, &stats, Some(BadgeTier::Bronze)).unwrap();
+        assert_eq!(next.tier, BadgeTier::Silver);
+        assert_eq!(next.threshold, 30.0);
+        assert!((next.progress - 0.5).abs() < 0.01);
+
+        // A reset streak still points past the tier already earned
+        let stats = UserStats::default();
+        let next = next_tier_progress(&badge, &stats, Some(BadgeTier::Silver)).unwrap();
+        assert_eq!(next.tier, BadgeTier::Gold);
+
+        assert!(next_tier_progress(&badge, &stats, Some(BadgeTier::Gold)).is_none());
+
+        let untiered = get_badge_by_id("rising_star").unwrap();
+        assert!(next_tier_progress(&untiered, &stats, None).is_none());
+    }
+
+    #[test]
+    fn test_check_tier_upgrades() {
+        let stats = UserStats {
+            streak_days: 30,
+            total_xp: 1500,
+            ..Default::default()
+        };
+
+        let mut streak = BadgeProgress::new("user1".to_string(), "streak_keeper".to_string());
+        streak.update_tiered_progress(7.0, &get_badge_by_id("streak_keeper").unwrap());
+        let mut xp = BadgeProgress::new("user1".to_string(), "xp_earner".to_string());
+        xp.update_progress(1000.0, 1000.0); // Earned before tiers existed
+
+        let upgrades = check_tier_upgrades(&stats, &[streak, xp]);
+        assert_eq!(upgrades, vec![("streak_keeper".to_string(), BadgeTier::Silver)]);
+
+        // Unearned badges are left to check_badge_unlocks
+        assert!(check_tier_upgrades(&stats, &[]).is_empty());
+    }
 }
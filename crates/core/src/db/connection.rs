@@ -1,8 +1,10 @@
-use rusqlite::Connection;
-use std::path::PathBuf;
-use std::sync::Mutex;
+use rusqlite::{Connection, OpenFlags};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::Duration;
 use crate::db::error::{DbError, DbResult};
-use crate::db::migrations;
+use crate::db::migrations::{self, MigrationReport};
 
 #[derive(Debug)]
 pub struct Database {
@@ -37,26 +39,181 @@ impl Database {
         Ok(Self { conn })
     }
 
+    /// Check which migrations a database file needs, without changing it.
+    /// Pending migrations are run inside a transaction that is rolled back,
+    /// so SQL errors surface here rather than on the next launch. A path
+    /// with no database is an error rather than a new, empty one.
+    pub fn dry_run_migrations(db_path: PathBuf) -> DbResult<MigrationReport> {
+        let flags = OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_URI | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+        let conn = Connection::open_with_flags(&db_path, flags)?;
+        conn.execute("PRAGMA foreign_keys = ON", [])?;
+        migrations::dry_run_migrations(&conn)
+    }
+
+    pub fn schema_version(&self) -> DbResult<i32> {
+        migrations::schema_version(&self.conn)
+    }
+
     pub fn connection(&self) -> &Connection {
         &self.conn
     }
 }
 
-/// Thread-safe wrapper for Tauri state
+/// Connection pool settings
+#[derive(Debug, Clone)]
+pub struct PoolConfig {
+    /// Most connections open at once. In-memory databases always use one.
+    pub max_connections: usize,
+    /// How long a connection waits on a locked database before failing
+    pub busy_timeout: Duration,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 4,
+            busy_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+struct PoolState {
+    idle: Vec<Connection>,
+    open: usize,
+}
+
+struct ConnectionPool {
+    /// `None` for an in-memory database, which can't be reopened
+    db_path: Option<PathBuf>,
+    config: PoolConfig,
+    state: Mutex<PoolState>,
+    returned: Condvar,
+}
+
+impl ConnectionPool {
+    fn new(db: Database, db_path: Option<PathBuf>, mut config: PoolConfig) -> DbResult<Self> {
+        db.conn.busy_timeout(config.busy_timeout)?;
+        if db_path.is_none() {
+            config.max_connections = 1;
+        }
+        config.max_connections = config.max_connections.max(1);
+
+        Ok(Self {
+            db_path,
+            config,
+            state: Mutex::new(PoolState {
+                idle: vec![db.conn],
+                open: 1,
+            }),
+            returned: Condvar::new(),
+        })
+    }
+
+    fn lock_state(&self) -> DbResult<MutexGuard<'_, PoolState>> {
+        self.state.lock().map_err(|e| DbError::InvalidData(e.to_string()))
+    }
+
+    /// Take an idle connection, open a new one if below the limit, or wait
+    /// for one to be returned
+    fn checkout(&self) -> DbResult<Connection> {
+        let mut state = self.lock_state()?;
+        loop {
+            if let Some(conn) = state.idle.pop() {
+                return Ok(conn);
+            }
+
+            if let (Some(path), true) = (&self.db_path, state.open < self.config.max_connections) {
+                state.open += 1;
+                drop(state);
+
+                return self.open_connection(path).inspect_err(|_| {
+                    if let Ok(mut state) = self.lock_state() {
+                        state.open -= 1;
+                    }
+                    self.returned.notify_one();
+                });
+            }
+
+            state = self
+                .returned
+                .wait(state)
+                .map_err(|e| DbError::InvalidData(e.to_string()))?;
+        }
+    }
+
+    /// Open another connection to an already migrated database
+    fn open_connection(&self, path: &Path) -> DbResult<Connection> {
+        let conn = Connection::open(path)?;
+        conn.execute("PRAGMA foreign_keys = ON", [])?;
+        conn.busy_timeout(self.config.busy_timeout)?;
+        Ok(conn)
+    }
+
+    fn checkin(&self, conn: Connection) {
+        if let Ok(mut state) = self.lock_state() {
+            state.idle.push(conn);
+        }
+        self.returned.notify_one();
+    }
+}
+
+/// A connection borrowed from the pool, returned when dropped
+pub struct PooledConnection<'a> {
+    pool: &'a ConnectionPool,
+    conn: Option<Connection>,
+}
+
+impl Deref for PooledConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("connection already returned")
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool.checkin(conn);
+        }
+    }
+}
+
+/// Pooled database handle for Tauri state.
+///
+/// Cheap to clone, so a command can move a handle onto a blocking thread and
+/// keep heavy queries off the thread that serves the UI. File databases open
+/// up to `PoolConfig::max_connections` connections; WAL mode lets readers run
+/// alongside a writer.
+#[derive(Clone)]
 pub struct AppDatabase {
-    pub db: Mutex<Database>,
+    pool: Arc<ConnectionPool>,
 }
 
 impl AppDatabase {
     pub fn new(db_path: PathBuf) -> DbResult<Self> {
+        Self::with_config(db_path, PoolConfig::default())
+    }
+
+    pub fn with_config(db_path: PathBuf, config: PoolConfig) -> DbResult<Self> {
+        let db = Database::new(db_path.clone())?;
         Ok(Self {
-            db: Mutex::new(Database::new(db_path)?),
+            pool: Arc::new(ConnectionPool::new(db, Some(db_path), config)?),
         })
     }
 
     pub fn new_in_memory() -> DbResult<Self> {
+        let db = Database::new_in_memory()?;
         Ok(Self {
-            db: Mutex::new(Database::new_in_memory()?),
+            pool: Arc::new(ConnectionPool::new(db, None, PoolConfig::default())?),
+        })
+    }
+
+    /// Borrow a connection, waiting if all of them are in use
+    pub fn connection(&self) -> DbResult<PooledConnection<'_>> {
+        Ok(PooledConnection {
+            pool: &self.pool,
+            conn: Some(self.pool.checkout()?),
         })
     }
 
@@ -64,8 +221,8 @@ impl AppDatabase {
     where
         F: FnOnce(&Connection) -> DbResult<T>,
     {
-        let db = self.db.lock().map_err(|e| DbError::InvalidData(e.to_string()))?;
-        f(db.connection())
+        let conn = self.connection()?;
+        f(&conn)
     }
 }
 
@@ -84,6 +241,15 @@ mod tests {
         assert!(db_path.exists());
     }
 
+    #[test]
+    fn test_dry_run_does_not_create_a_missing_database() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("missing.db");
+
+        assert!(Database::dry_run_migrations(db_path.clone()).is_err());
+        assert!(!db_path.exists());
+    }
+
     #[test]
     fn test_create_in_memory_database() {
         let db = Database::new_in_memory();
@@ -107,4 +273,62 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), 0);
     }
+
+    #[test]
+    fn test_pool_opens_connections_up_to_limit() {
+        let dir = tempdir().unwrap();
+        let config = PoolConfig { max_connections: 2, ..PoolConfig::default() };
+        let app_db = AppDatabase::with_config(dir.path().join("test.db"), config).unwrap();
+
+        let first = app_db.connection().unwrap();
+        let second = app_db.connection().unwrap();
+
+        // Both connections see the migrated schema
+        first.execute("INSERT INTO users (id, created_at, last_activity) VALUES ('u1', '2024-01-01T00:00:00+00:00', '2024-01-01T00:00:00+00:00')", []).unwrap();
+        let count: i32 = second.query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(app_db.pool.lock_state().unwrap().open, 2);
+    }
+
+    #[test]
+    fn test_pool_waits_for_returned_connection() {
+        let dir = tempdir().unwrap();
+        let config = PoolConfig { max_connections: 1, ..PoolConfig::default() };
+        let app_db = AppDatabase::with_config(dir.path().join("test.db"), config).unwrap();
+
+        let held = app_db.connection().unwrap();
+        let worker = {
+            let app_db = app_db.clone();
+            std::thread::spawn(move || {
+                app_db.with_connection(|conn| {
+                    let count: i32 = conn.query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))?;
+                    Ok(count)
+                })
+            })
+        };
+
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(!worker.is_finished());
+        drop(held);
+
+        assert_eq!(worker.join().unwrap().unwrap(), 0);
+        assert_eq!(app_db.pool.lock_state().unwrap().open, 1);
+    }
+
+    #[test]
+    fn test_in_memory_pool_shares_one_connection() {
+        let app_db = AppDatabase::new_in_memory().unwrap();
+        assert_eq!(app_db.pool.config.max_connections, 1);
+
+        app_db
+            .with_connection(|conn| {
+                conn.execute("INSERT INTO users (id, created_at, last_activity) VALUES ('u1', '2024-01-01T00:00:00+00:00', '2024-01-01T00:00:00+00:00')", [])?;
+                Ok(())
+            })
+            .unwrap();
+        let count: i32 = app_db
+            .with_connection(|conn| Ok(conn.query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))?))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
 }
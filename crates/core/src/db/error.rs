@@ -3,7 +3,7 @@ use thiserror::Error;
 #[derive(Error, Debug)]
 pub enum DbError {
     #[error("Database error: {0}")]
-    Sqlite(#[from] rusqlite::Error),
+    Sqlite(#[source] rusqlite::Error),
 
     #[error("Not found: {0}")]
     NotFound(String),
@@ -13,6 +13,20 @@ pub enum DbError {
 
     #[error("Migration error: {0}")]
     Migration(String),
+
+    #[error("Database schema v{found} is newer than this build supports (v{supported}); please update the app")]
+    SchemaTooNew { found: i32, supported: i32 },
 }
 
 pub type DbResult<T> = Result<T, DbError>;
+
+impl From<rusqlite::Error> for DbError {
+    fn from(e: rusqlite::Error) -> Self {
+        match e {
+            // A stored value that can't be read as its type is bad data, not
+            // a database failure
+            rusqlite::Error::FromSqlConversionFailure(..) => DbError::InvalidData(e.to_string()),
+            e => DbError::Sqlite(e),
+        }
+    }
+}
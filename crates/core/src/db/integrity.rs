@@ -0,0 +1,85 @@
+use rusqlite::Connection;
+use serde::Serialize;
+use crate::db::error::DbResult;
+use crate::db::migrations::{self, CURRENT_VERSION};
+
+/// Result of checking the database for corruption, for diagnostics
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityReport {
+    pub ok: bool,
+    pub schema_version: i32,
+    pub expected_schema_version: i32,
+    /// Problems reported by `PRAGMA integrity_check`
+    pub integrity_errors: Vec<String>,
+    /// Rows whose foreign key points at a missing parent
+    pub foreign_key_violations: Vec<String>,
+}
+
+/// Run SQLite's integrity and foreign key checks
+pub fn check_integrity(conn: &Connection) -> DbResult<IntegrityReport> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+    let mut integrity_errors = Vec::new();
+    for row in rows {
+        let message = row?;
+        if message != "ok" {
+            integrity_errors.push(message);
+        }
+    }
+
+    let mut stmt = conn.prepare("PRAGMA foreign_key_check")?;
+    let rows = stmt.query_map([], |row| {
+        let table: String = row.get(0)?;
+        let rowid: Option<i64> = row.get(1)?;
+        let parent: String = row.get(2)?;
+        Ok(match rowid {
+            Some(rowid) => format!("{} row {} references a missing {}", table, rowid, parent),
+            None => format!("{} references a missing {}", table, parent),
+        })
+    })?;
+
+    let mut foreign_key_violations = Vec::new();
+    for row in rows {
+        foreign_key_violations.push(row?);
+    }
+
+    let schema_version = migrations::schema_version(conn)?;
+    Ok(IntegrityReport {
+        ok: integrity_errors.is_empty() && foreign_key_violations.is_empty() && schema_version == CURRENT_VERSION,
+        schema_version,
+        expected_schema_version: CURRENT_VERSION,
+        integrity_errors,
+        foreign_key_violations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+
+    #[test]
+    fn test_fresh_database_is_ok() {
+        let db = Database::new_in_memory().unwrap();
+        let report = check_integrity(db.connection()).unwrap();
+        assert!(report.ok);
+        assert_eq!(report.schema_version, CURRENT_VERSION);
+    }
+
+    #[test]
+    fn test_reports_orphaned_rows() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        conn.execute_batch(
+            "PRAGMA foreign_keys = OFF;
+             INSERT INTO node_progress (user_id, node_id, curriculum_id, status)
+             VALUES ('ghost', 'week1-lecture', 'missing-curriculum', 'NotStarted');",
+        )
+        .unwrap();
+
+        let report = check_integrity(conn).unwrap();
+        assert!(!report.ok);
+        assert!(report.foreign_key_violations.iter().any(|v| v.starts_with("node_progress")));
+    }
+}
@@ -1,37 +1,220 @@
-use rusqlite::Connection;
+//! Versioned schema migrations
+//!
+//! Migrations are applied in order, each in its own transaction, and recorded
+//! in the `schema_version` table (mirrored in `PRAGMA user_version` for older
+//! builds). A database written by a newer build is refused rather than
+//! silently used, and a dry run applies pending migrations inside a
+//! transaction that is always rolled back.
+
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serde::Serialize;
 use crate::db::error::{DbError, DbResult};
+use crate::db::in_savepoint;
+
+pub const CURRENT_VERSION: i32 = 45;
+
+/// One schema change, identified by the version it upgrades to
+pub struct Migration {
+    pub version: i32,
+    pub description: &'static str,
+    apply: fn(&Connection) -> DbResult<()>,
+}
+
+/// All migrations, oldest first. Append new ones here and bump `CURRENT_VERSION`.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, description: "initial schema", apply: migrate_to_v1 },
+    Migration { version: 2, description: "curricula support", apply: migrate_to_v2 },
+    Migration { version: 3, description: "review leech tracking", apply: migrate_to_v3 },
+    Migration { version: 4, description: "mastery history", apply: migrate_to_v4 },
+    Migration { version: 5, description: "quests", apply: migrate_to_v5 },
+    Migration { version: 6, description: "badge tiers", apply: migrate_to_v6 },
+    Migration { version: 7, description: "profiles and XP log", apply: migrate_to_v7 },
+    Migration { version: 8, description: "cohorts", apply: migrate_to_v8 },
+    Migration { version: 9, description: "notes and full-text search", apply: migrate_to_v9 },
+    Migration { version: 10, description: "per-curriculum progress", apply: migrate_to_v10 },
+    Migration { version: 11, description: "undo trash", apply: migrate_to_v11 },
+    Migration { version: 12, description: "cross-device sync", apply: migrate_to_v12 },
+    Migration { version: 13, description: "background job runs", apply: migrate_to_v13 },
+    Migration { version: 14, description: "session heartbeats", apply: migrate_to_v14 },
+    Migration { version: 15, description: "focus timer", apply: migrate_to_v15 },
+    Migration { version: 16, description: "pending work queue", apply: migrate_to_v16 },
+    Migration { version: 17, description: "study reminders", apply: migrate_to_v17 },
+    Migration { version: 18, description: "challenge hints", apply: migrate_to_v18 },
+    Migration { version: 19, description: "challenge drafts and attempt code", apply: migrate_to_v19 },
+    Migration { version: 20, description: "tutor conversations", apply: migrate_to_v20 },
+    Migration { version: 21, description: "quiz mistake explanations", apply: migrate_to_v21 },
+    Migration { version: 22, description: "content embeddings", apply: migrate_to_v22 },
+    Migration { version: 23, description: "practice challenges", apply: migrate_to_v23 },
+    Migration { version: 24, description: "project track repos", apply: migrate_to_v24 },
+    Migration { version: 25, description: "note annotations", apply: migrate_to_v25 },
+    Migration { version: 26, description: "bookmarks", apply: migrate_to_v26 },
+    Migration { version: 27, description: "daily activity rollup", apply: migrate_to_v27 },
+    Migration { version: 28, description: "goals", apply: migrate_to_v28 },
+    Migration { version: 29, description: "weekly report reminders", apply: migrate_to_v29 },
+    Migration { version: 30, description: "per-question quiz answers", apply: migrate_to_v30 },
+    Migration { version: 31, description: "timed quizzes", apply: migrate_to_v31 },
+    Migration { version: 32, description: "completion certificates", apply: migrate_to_v32 },
+    Migration { version: 33, description: "guest profiles and profile settings", apply: migrate_to_v33 },
+    Migration { version: 34, description: "server API tokens", apply: migrate_to_v34 },
+    Migration { version: 35, description: "artifact commit hashes", apply: migrate_to_v35 },
+    Migration { version: 36, description: "GitHub checkpoint submissions", apply: migrate_to_v36 },
+    Migration { version: 37, description: "automation hook deliveries", apply: migrate_to_v37 },
+    Migration { version: 38, description: "imported flashcards", apply: migrate_to_v38 },
+    Migration { version: 39, description: "best attempts", apply: migrate_to_v39 },
+    Migration { version: 40, description: "challenge attempt review flags", apply: migrate_to_v40 },
+    Migration { version: 41, description: "per-node active time", apply: migrate_to_v41 },
+    Migration { version: 42, description: "lecture comprehension checks", apply: migrate_to_v42 },
+    Migration { version: 43, description: "curriculum content keys", apply: migrate_to_v43 },
+    Migration { version: 44, description: "curriculum license keys", apply: migrate_to_v44 },
+    Migration { version: 45, description: "queued analytics events", apply: migrate_to_v45 },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationMode {
+    Apply,
+    /// Run pending migrations, then roll everything back
+    DryRun,
+}
+
+/// What a migration run did (or, for a dry run, would do)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MigrationReport {
+    pub from_version: i32,
+    pub to_version: i32,
+    /// `(version, description)` of each migration run
+    pub migrations: Vec<(i32, String)>,
+    pub dry_run: bool,
+}
 
-pub const CURRENT_VERSION: i32 = 2;
+impl MigrationReport {
+    pub fn is_up_to_date(&self) -> bool {
+        self.migrations.is_empty()
+    }
+}
 
 pub fn run_migrations(conn: &Connection) -> DbResult<()> {
-    // Get current version
-    let version: i32 = conn
-        .pragma_query_value(None, "user_version", |row| row.get(0))
-        .unwrap_or(0);
+    migrate(conn, MIGRATIONS, MigrationMode::Apply)?;
+    Ok(())
+}
+
+/// Report pending migrations after checking that they apply cleanly
+pub fn dry_run_migrations(conn: &Connection) -> DbResult<MigrationReport> {
+    migrate(conn, MIGRATIONS, MigrationMode::DryRun)
+}
+
+/// Schema version recorded in the database (0 for a new database)
+pub fn schema_version(conn: &Connection) -> DbResult<i32> {
+    let has_table: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'schema_version'",
+        [],
+        |row| row.get(0),
+    )?;
+    let recorded: Option<i32> = if has_table {
+        conn.query_row("SELECT MAX(version) FROM schema_version", [], |row| row.get(0))?
+    } else {
+        None
+    };
+
+    match recorded {
+        Some(version) => Ok(version),
+        // Databases from before the schema_version table only have the pragma
+        None => Ok(conn.pragma_query_value(None, "user_version", |row| row.get(0))?),
+    }
+}
+
+fn record_version(conn: &Connection, version: i32, description: &str) -> DbResult<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO schema_version (version, description, applied_at) VALUES (?1, ?2, ?3)",
+        params![version, description, Utc::now().to_rfc3339()],
+    )?;
+    conn.pragma_update(None, "user_version", version)?;
+    Ok(())
+}
+
+fn migrate(conn: &Connection, migrations: &[Migration], mode: MigrationMode) -> DbResult<MigrationReport> {
+    let latest = migrations.last().map(|m| m.version).unwrap_or(0);
+    let version = schema_version(conn)?;
+
+    // Downgrade protection: never touch a schema this build doesn't know
+    if version > latest {
+        return Err(DbError::SchemaTooNew { found: version, supported: latest });
+    }
+
+    let mut report = MigrationReport {
+        from_version: version,
+        to_version: version,
+        migrations: Vec::new(),
+        dry_run: mode == MigrationMode::DryRun,
+    };
+    let has_pending = migrations.iter().any(|m| m.version > version);
+
+    if mode == MigrationMode::DryRun {
+        conn.execute_batch("SAVEPOINT dry_run")?;
+        let result = apply_pending(conn, migrations, version, mode, &mut report);
+        conn.execute_batch("ROLLBACK TO dry_run; RELEASE dry_run")?;
+        result?;
+        return Ok(report);
+    }
+
+    if has_pending {
+        tracing::info!(from = version, to = latest, "Running migrations");
+    }
+    apply_pending(conn, migrations, version, mode, &mut report)?;
+    if has_pending {
+        tracing::info!(version = report.to_version, "Database migrated");
+    }
+
+    Ok(report)
+}
 
-    if version < CURRENT_VERSION {
-        println!("Running migrations from v{} to v{}", version, CURRENT_VERSION);
+fn apply_pending(
+    conn: &Connection,
+    migrations: &[Migration],
+    version: i32,
+    mode: MigrationMode,
+    report: &mut MigrationReport,
+) -> DbResult<()> {
+    in_savepoint(conn, "schema_history", || {
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS schema_version (
+                version INTEGER PRIMARY KEY,
+                description TEXT NOT NULL,
+                applied_at TEXT NOT NULL
+            );
+            "#,
+        )
+        .map_err(|e| DbError::Migration(format!("Failed to create schema_version table: {}", e)))?;
 
-        // Run each migration in order
-        if version < 1 {
-            migrate_to_v1(conn)?;
+        // Backfill history for databases migrated before schema_version existed
+        for migration in migrations.iter().filter(|m| m.version <= version) {
+            conn.execute(
+                "INSERT OR IGNORE INTO schema_version (version, description, applied_at) VALUES (?1, ?2, ?3)",
+                params![migration.version, migration.description, Utc::now().to_rfc3339()],
+            )?;
         }
+        Ok(())
+    })?;
 
-        if version < 2 {
-            migrate_to_v2(conn)?;
+    for migration in migrations.iter().filter(|m| m.version > version) {
+        if mode == MigrationMode::Apply {
+            tracing::info!(version = migration.version, description = migration.description, "Running migration");
         }
 
-        // Update version
-        conn.pragma_update(None, "user_version", CURRENT_VERSION)?;
-        println!("Database now at version {}", CURRENT_VERSION);
+        in_savepoint(conn, "migration", || {
+            (migration.apply)(conn)?;
+            record_version(conn, migration.version, migration.description)
+        })?;
+
+        report.to_version = migration.version;
+        report.migrations.push((migration.version, migration.description.to_string()));
     }
 
     Ok(())
 }
 
 fn migrate_to_v1(conn: &Connection) -> DbResult<()> {
-    println!("  Running migration to v1 (initial schema)");
-
     // Read schema.sql and execute it
     let schema_sql = include_str!("schema.sql");
     conn.execute_batch(schema_sql)
@@ -41,8 +224,6 @@ fn migrate_to_v1(conn: &Connection) -> DbResult<()> {
 }
 
 fn migrate_to_v2(conn: &Connection) -> DbResult<()> {
-    println!("  Running migration to v2 (curricula support)");
-
     // Create curricula table
     conn.execute_batch(
         r#"
@@ -91,37 +272,1563 @@ fn migrate_to_v2(conn: &Connection) -> DbResult<()> {
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::tempdir;
+fn migrate_to_v3(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        -- Lapse counting and suspension for leech detection
+        ALTER TABLE review_items ADD COLUMN lapses INTEGER NOT NULL DEFAULT 0 CHECK (lapses >= 0);
+        ALTER TABLE review_items ADD COLUMN is_suspended INTEGER NOT NULL DEFAULT 0 CHECK (is_suspended IN (0, 1));
+        CREATE INDEX IF NOT EXISTS idx_review_suspended ON review_items(user_id, is_suspended);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add review leech tracking: {}", e)))?;
 
-    #[test]
-    fn test_migrations_run_successfully() {
-        let dir = tempdir().unwrap();
-        let db_path = dir.path().join("test.db");
-        let conn = Connection::open(&db_path).unwrap();
+    Ok(())
+}
 
-        // Run migrations
-        let result = run_migrations(&conn);
-        assert!(result.is_ok(), "Migrations failed: {:?}", result);
+fn migrate_to_v4(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        -- Every mastery score change, for charting skill progression
+        CREATE TABLE IF NOT EXISTS mastery_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id TEXT NOT NULL,
+            skill_id TEXT NOT NULL,
+            previous_score REAL NOT NULL,
+            score REAL NOT NULL,
+            reason TEXT NOT NULL,
+            recorded_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+            CHECK (previous_score >= 0.0 AND previous_score <= 1.0),
+            CHECK (score >= 0.0 AND score <= 1.0),
+            CHECK (reason IN ('Quiz', 'Challenge', 'Decay'))
+        );
 
-        // Check version was updated
-        let version: i32 = conn
-            .pragma_query_value(None, "user_version", |row| row.get(0))
-            .unwrap();
-        assert_eq!(version, CURRENT_VERSION);
-    }
+        CREATE INDEX IF NOT EXISTS idx_mastery_history_skill ON mastery_history(user_id, skill_id, recorded_at);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add mastery history: {}", e)))?;
 
-    #[test]
-    fn test_migrations_are_idempotent() {
-        let dir = tempdir().unwrap();
-        let db_path = dir.path().join("test.db");
-        let conn = Connection::open(&db_path).unwrap();
+    Ok(())
+}
 
-        // Run migrations twice
-        run_migrations(&conn).unwrap();
-        let result = run_migrations(&conn);
-        assert!(result.is_ok(), "Second migration run failed: {:?}", result);
+fn migrate_to_v5(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        -- Daily and weekly quest progress
+        CREATE TABLE IF NOT EXISTS quest_progress (
+            id TEXT NOT NULL,
+            user_id TEXT NOT NULL,
+            period TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            description TEXT NOT NULL,
+            target INTEGER NOT NULL,
+            progress INTEGER NOT NULL DEFAULT 0,
+            xp_reward INTEGER NOT NULL DEFAULT 0,
+            starts_at TEXT NOT NULL,
+            ends_at TEXT NOT NULL,
+            completed_at TEXT,
+            claimed_at TEXT,
+            PRIMARY KEY (user_id, id),
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+            CHECK (period IN ('Daily', 'Weekly')),
+            CHECK (target > 0),
+            CHECK (progress >= 0),
+            CHECK (xp_reward >= 0)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_quest_active ON quest_progress(user_id, ends_at);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add quests: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v6(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE badge_progress ADD COLUMN tier TEXT
+            CHECK (tier IS NULL OR tier IN ('Bronze', 'Silver', 'Gold'));
+
+        -- week_warrior / streak_master / unstoppable become streak_keeper tiers
+        INSERT INTO badge_progress (user_id, badge_id, current_value, earned_at, curriculum_id, tier)
+        SELECT user_id, 'streak_keeper', MAX(current_value), MIN(earned_at), MAX(curriculum_id),
+            CASE
+                WHEN SUM(badge_id = 'unstoppable' AND earned_at IS NOT NULL) > 0 THEN 'Gold'
+                WHEN SUM(badge_id = 'streak_master' AND earned_at IS NOT NULL) > 0 THEN 'Silver'
+                WHEN SUM(badge_id = 'week_warrior' AND earned_at IS NOT NULL) > 0 THEN 'Bronze'
+            END
+        FROM badge_progress
+        WHERE badge_id IN ('week_warrior', 'streak_master', 'unstoppable')
+        GROUP BY user_id
+        ON CONFLICT(user_id, badge_id) DO NOTHING;
+
+        -- xp_hunter / xp_collector / xp_legend become xp_earner tiers
+        INSERT INTO badge_progress (user_id, badge_id, current_value, earned_at, curriculum_id, tier)
+        SELECT user_id, 'xp_earner', MAX(current_value), MIN(earned_at), MAX(curriculum_id),
+            CASE
+                WHEN SUM(badge_id = 'xp_legend' AND earned_at IS NOT NULL) > 0 THEN 'Gold'
+                WHEN SUM(badge_id = 'xp_collector' AND earned_at IS NOT NULL) > 0 THEN 'Silver'
+                WHEN SUM(badge_id = 'xp_hunter' AND earned_at IS NOT NULL) > 0 THEN 'Bronze'
+            END
+        FROM badge_progress
+        WHERE badge_id IN ('xp_hunter', 'xp_collector', 'xp_legend')
+        GROUP BY user_id
+        ON CONFLICT(user_id, badge_id) DO NOTHING;
+
+        DELETE FROM badge_progress WHERE badge_id IN (
+            'week_warrior', 'streak_master', 'unstoppable',
+            'xp_hunter', 'xp_collector', 'xp_legend'
+        );
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add badge tiers: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v7(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE users ADD COLUMN display_name TEXT;
+
+        CREATE TABLE IF NOT EXISTS xp_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id TEXT NOT NULL,
+            amount INTEGER NOT NULL,
+            earned_at TEXT NOT NULL,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+            CHECK (amount > 0)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_xp_log_user_time ON xp_log(user_id, earned_at);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add profiles: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v8(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS cohorts (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS cohort_members (
+            cohort_id TEXT NOT NULL,
+            user_id TEXT NOT NULL,
+            enrolled_at TEXT NOT NULL,
+            PRIMARY KEY (cohort_id, user_id),
+            FOREIGN KEY (cohort_id) REFERENCES cohorts(id) ON DELETE CASCADE,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_cohort_members_user ON cohort_members(user_id);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add cohorts: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v9(conn: &Connection) -> DbResult<()> {
+    // Each searchable source gets its own FTS5 table keyed by the source row's
+    // ID and kept in sync by triggers. JSON columns are flattened to their text
+    // values so brackets and keys don't end up in search snippets.
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS notes (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            node_id TEXT NOT NULL,
+            body TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_notes_user_node ON notes(user_id, node_id);
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS notes_fts USING fts5(
+            note_id UNINDEXED,
+            body,
+            tokenize = 'porter unicode61'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS notes_fts_insert AFTER INSERT ON notes BEGIN
+            INSERT INTO notes_fts (note_id, body) VALUES (new.id, new.body);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS notes_fts_update AFTER UPDATE OF body ON notes BEGIN
+            UPDATE notes_fts SET body = new.body WHERE note_id = old.id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS notes_fts_delete AFTER DELETE ON notes BEGIN
+            DELETE FROM notes_fts WHERE note_id = old.id;
+        END;
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS quiz_answers_fts USING fts5(
+            attempt_id UNINDEXED,
+            answers,
+            tokenize = 'porter unicode61'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS quiz_answers_fts_insert AFTER INSERT ON quiz_attempts BEGIN
+            INSERT INTO quiz_answers_fts (attempt_id, answers) VALUES (
+                new.id,
+                CASE WHEN json_valid(new.answers_json)
+                    THEN (SELECT group_concat(value, ' ') FROM json_tree(new.answers_json) WHERE type = 'text')
+                    ELSE new.answers_json
+                END
+            );
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS quiz_answers_fts_delete AFTER DELETE ON quiz_attempts BEGIN
+            DELETE FROM quiz_answers_fts WHERE attempt_id = old.id;
+        END;
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS grade_feedback_fts USING fts5(
+            submission_id UNINDEXED,
+            feedback,
+            tokenize = 'porter unicode61'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS grade_feedback_fts_insert AFTER INSERT ON artifact_submissions
+        WHEN new.reasoning_json IS NOT NULL BEGIN
+            INSERT INTO grade_feedback_fts (submission_id, feedback) VALUES (
+                new.id,
+                CASE WHEN json_valid(new.reasoning_json)
+                    THEN (SELECT group_concat(value, ' ') FROM json_tree(new.reasoning_json) WHERE type = 'text')
+                    ELSE new.reasoning_json
+                END
+            );
+        END;
+
+        -- Submissions are usually graded after they are created
+        CREATE TRIGGER IF NOT EXISTS grade_feedback_fts_update AFTER UPDATE OF reasoning_json ON artifact_submissions BEGIN
+            DELETE FROM grade_feedback_fts WHERE submission_id = old.id;
+            INSERT INTO grade_feedback_fts (submission_id, feedback)
+            SELECT
+                new.id,
+                CASE WHEN json_valid(new.reasoning_json)
+                    THEN (SELECT group_concat(value, ' ') FROM json_tree(new.reasoning_json) WHERE type = 'text')
+                    ELSE new.reasoning_json
+                END
+            WHERE new.reasoning_json IS NOT NULL;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS grade_feedback_fts_delete AFTER DELETE ON artifact_submissions BEGIN
+            DELETE FROM grade_feedback_fts WHERE submission_id = old.id;
+        END;
+
+        -- Index rows written before this migration
+        INSERT INTO quiz_answers_fts (attempt_id, answers)
+        SELECT
+            id,
+            CASE WHEN json_valid(answers_json)
+                THEN (SELECT group_concat(value, ' ') FROM json_tree(answers_json) WHERE type = 'text')
+                ELSE answers_json
+            END
+        FROM quiz_attempts;
+
+        INSERT INTO grade_feedback_fts (submission_id, feedback)
+        SELECT
+            id,
+            CASE WHEN json_valid(reasoning_json)
+                THEN (SELECT group_concat(value, ' ') FROM json_tree(reasoning_json) WHERE type = 'text')
+                ELSE reasoning_json
+            END
+        FROM artifact_submissions
+        WHERE reasoning_json IS NOT NULL;
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add full-text search: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v10(conn: &Connection) -> DbResult<()> {
+    // Progress, mastery and reviews were keyed by user and node/skill/quiz
+    // only, so two curricula sharing IDs overwrote each other. Rebuild them
+    // with curriculum_id in the unique key and assign existing rows to the
+    // active curriculum. The key uses IFNULL so rows without a curriculum
+    // still conflict with each other. curriculum_id no longer references
+    // curricula so deleting a curriculum can keep its learners' history.
+    conn.execute_batch(
+        r#"
+        CREATE TABLE node_progress_v10 (
+            user_id TEXT NOT NULL,
+            curriculum_id TEXT,
+            node_id TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'NotStarted',
+            attempts INTEGER NOT NULL DEFAULT 0,
+            time_spent_mins INTEGER NOT NULL DEFAULT 0,
+            first_started_at TEXT,
+            completed_at TEXT,
+            last_updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+            CHECK (status IN ('NotStarted', 'InProgress', 'Completed', 'Failed')),
+            CHECK (attempts >= 0),
+            CHECK (time_spent_mins >= 0)
+        );
+
+        INSERT INTO node_progress_v10 (
+            user_id, curriculum_id, node_id, status, attempts, time_spent_mins,
+            first_started_at, completed_at, last_updated_at
+        )
+        SELECT
+            user_id,
+            COALESCE(curriculum_id, (SELECT id FROM curricula WHERE is_active = 1 LIMIT 1)),
+            node_id, status, attempts, time_spent_mins,
+            first_started_at, completed_at, last_updated_at
+        FROM node_progress;
+
+        DROP TABLE node_progress;
+        ALTER TABLE node_progress_v10 RENAME TO node_progress;
+
+        CREATE UNIQUE INDEX idx_node_progress_key ON node_progress(user_id, IFNULL(curriculum_id, ''), node_id);
+        CREATE INDEX idx_node_progress_user ON node_progress(user_id);
+        CREATE INDEX idx_node_progress_status ON node_progress(user_id, status);
+        CREATE INDEX idx_node_progress_completed ON node_progress(user_id, completed_at);
+        CREATE INDEX idx_node_progress_curriculum ON node_progress(curriculum_id);
+
+        CREATE TABLE mastery_scores_v10 (
+            user_id TEXT NOT NULL,
+            curriculum_id TEXT,
+            skill_id TEXT NOT NULL,
+            score REAL NOT NULL DEFAULT 0.0,
+            last_updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+            CHECK (score >= 0.0 AND score <= 1.0)
+        );
+
+        INSERT INTO mastery_scores_v10 (user_id, curriculum_id, skill_id, score, last_updated_at)
+        SELECT
+            user_id,
+            COALESCE(curriculum_id, (SELECT id FROM curricula WHERE is_active = 1 LIMIT 1)),
+            skill_id, score, last_updated_at
+        FROM mastery_scores;
+
+        DROP TABLE mastery_scores;
+        ALTER TABLE mastery_scores_v10 RENAME TO mastery_scores;
+
+        CREATE UNIQUE INDEX idx_mastery_key ON mastery_scores(user_id, IFNULL(curriculum_id, ''), skill_id);
+        CREATE INDEX idx_mastery_user ON mastery_scores(user_id);
+        CREATE INDEX idx_mastery_skill ON mastery_scores(skill_id);
+        CREATE INDEX idx_mastery_curriculum ON mastery_scores(curriculum_id);
+
+        CREATE TABLE review_items_v10 (
+            user_id TEXT NOT NULL,
+            curriculum_id TEXT,
+            quiz_id TEXT NOT NULL,
+            due_date TEXT NOT NULL,
+            ease_factor REAL NOT NULL DEFAULT 2.5,
+            interval_days INTEGER NOT NULL DEFAULT 1,
+            repetitions INTEGER NOT NULL DEFAULT 0,
+            last_reviewed_at TEXT,
+            lapses INTEGER NOT NULL DEFAULT 0,
+            is_suspended INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+            CHECK (ease_factor >= 1.3),
+            CHECK (interval_days >= 1),
+            CHECK (repetitions >= 0),
+            CHECK (lapses >= 0),
+            CHECK (is_suspended IN (0, 1))
+        );
+
+        INSERT INTO review_items_v10 (
+            user_id, curriculum_id, quiz_id, due_date, ease_factor, interval_days,
+            repetitions, last_reviewed_at, lapses, is_suspended
+        )
+        SELECT
+            user_id,
+            COALESCE(curriculum_id, (SELECT id FROM curricula WHERE is_active = 1 LIMIT 1)),
+            quiz_id, due_date, ease_factor, interval_days,
+            repetitions, last_reviewed_at, lapses, is_suspended
+        FROM review_items;
+
+        DROP TABLE review_items;
+        ALTER TABLE review_items_v10 RENAME TO review_items;
+
+        CREATE UNIQUE INDEX idx_review_key ON review_items(user_id, IFNULL(curriculum_id, ''), quiz_id);
+        CREATE INDEX idx_review_due ON review_items(user_id, due_date);
+        CREATE INDEX idx_review_curriculum ON review_items(curriculum_id);
+        CREATE INDEX idx_review_suspended ON review_items(user_id, is_suspended);
+
+        UPDATE quiz_attempts
+        SET curriculum_id = (SELECT id FROM curricula WHERE is_active = 1 LIMIT 1)
+        WHERE curriculum_id IS NULL;
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to namespace progress by curriculum: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v11(conn: &Connection) -> DbResult<()> {
+    // Rows removed by destructive operations are kept as a JSON snapshot
+    // until the undo window closes
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS trash (
+            id TEXT PRIMARY KEY,
+            user_id TEXT,
+            operation TEXT NOT NULL,
+            subject_id TEXT,
+            snapshot_json TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            expires_at TEXT NOT NULL,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+            CHECK (operation IN ('ResetProgress', 'DeleteCurriculum'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_trash_user_created ON trash(user_id, created_at);
+        CREATE INDEX IF NOT EXISTS idx_trash_expires ON trash(expires_at);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add trash: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v12(conn: &Connection) -> DbResult<()> {
+    // sync_records holds the last synced version of each record, so local
+    // edits can be found by hash and remote ones ordered by timestamp.
+    // sync_cursors counts how much of each device's op-log has been read.
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS sync_device (
+            id TEXT PRIMARY KEY,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS sync_records (
+            user_id TEXT NOT NULL,
+            record_key TEXT NOT NULL,
+            record_json TEXT NOT NULL,
+            content_hash TEXT NOT NULL,
+            deleted INTEGER NOT NULL DEFAULT 0,
+            updated_at TEXT NOT NULL,
+            device_id TEXT NOT NULL,
+            PRIMARY KEY (user_id, record_key),
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS sync_cursors (
+            user_id TEXT NOT NULL,
+            device_id TEXT NOT NULL,
+            ops_read INTEGER NOT NULL DEFAULT 0,
+            synced_at TEXT NOT NULL,
+            PRIMARY KEY (user_id, device_id),
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+            CHECK (ops_read >= 0)
+        );
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add sync tables: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v13(conn: &Connection) -> DbResult<()> {
+    // Last run of each background job, so schedules carry over restarts
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS job_runs (
+            job_id TEXT PRIMARY KEY,
+            last_started_at TEXT NOT NULL,
+            last_duration_ms INTEGER NOT NULL DEFAULT 0,
+            last_result TEXT,
+            last_error TEXT,
+            last_success_at TEXT,
+            run_count INTEGER NOT NULL DEFAULT 0,
+            failure_count INTEGER NOT NULL DEFAULT 0
+        );
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add job runs: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v14(conn: &Connection) -> DbResult<()> {
+    // Open sessions record a heartbeat, so time spent before a crash can be
+    // recovered
+    conn.execute_batch(
+        r#"
+        ALTER TABLE session_history ADD COLUMN last_heartbeat_at TEXT;
+        ALTER TABLE session_history ADD COLUMN active_seconds INTEGER NOT NULL DEFAULT 0;
+        CREATE INDEX IF NOT EXISTS idx_session_open ON session_history(ended_at) WHERE ended_at IS NULL;
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add session heartbeats: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v15(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS focus_settings (
+            user_id TEXT PRIMARY KEY,
+            work_minutes INTEGER NOT NULL,
+            short_break_minutes INTEGER NOT NULL,
+            long_break_minutes INTEGER NOT NULL,
+            blocks_before_long_break INTEGER NOT NULL,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS focus_blocks (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            session_id TEXT,
+            kind TEXT NOT NULL,
+            planned_seconds INTEGER NOT NULL,
+            started_at TEXT NOT NULL,
+            paused_at TEXT,
+            paused_seconds INTEGER NOT NULL DEFAULT 0,
+            ended_at TEXT,
+            status TEXT NOT NULL,
+            xp_awarded INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+            CHECK (kind IN ('Work', 'ShortBreak', 'LongBreak')),
+            CHECK (status IN ('Running', 'Paused', 'Completed', 'Cancelled'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_focus_blocks_user ON focus_blocks(user_id, started_at);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add focus timer: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v16(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS pending_work (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            capability TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            last_attempt_at TEXT,
+            last_error TEXT,
+            completed_at TEXT,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+            CHECK (capability IN ('Docker', 'Llm'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_pending_work_open ON pending_work(capability, completed_at, created_at);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add pending work queue: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v17(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS notification_prefs (
+            user_id TEXT PRIMARY KEY,
+            enabled INTEGER NOT NULL,
+            review_reminders INTEGER NOT NULL,
+            review_time TEXT NOT NULL,
+            streak_reminders INTEGER NOT NULL,
+            streak_time TEXT NOT NULL,
+            quiet_start TEXT,
+            quiet_end TEXT,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS notification_sent (
+            user_id TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            sent_for TEXT NOT NULL,
+            PRIMARY KEY (user_id, kind),
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add study reminders: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v18(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS hint_settings (
+            user_id TEXT PRIMARY KEY,
+            penalty_percent_per_hint INTEGER NOT NULL,
+            max_penalty_percent INTEGER NOT NULL,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS hint_reveals (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            curriculum_id TEXT,
+            node_id TEXT NOT NULL,
+            hint_index INTEGER NOT NULL,
+            attempt_number INTEGER NOT NULL,
+            revealed_at TEXT NOT NULL,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+            CHECK (hint_index >= 0)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_hint_reveals_node ON hint_reveals(user_id, node_id, hint_index);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add challenge hints: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v19(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE challenge_attempts ADD COLUMN code TEXT;
+        ALTER TABLE challenge_attempts ADD COLUMN verification_json TEXT;
+
+        CREATE TABLE IF NOT EXISTS challenge_drafts (
+            user_id TEXT NOT NULL,
+            curriculum_id TEXT,
+            node_id TEXT NOT NULL,
+            code TEXT NOT NULL,
+            restored_from TEXT,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_challenge_drafts_node ON challenge_drafts(user_id, node_id);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add challenge drafts: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v20(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS tutor_sessions (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            curriculum_id TEXT,
+            node_id TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS tutor_messages (
+            id TEXT PRIMARY KEY,
+            session_id TEXT NOT NULL,
+            role TEXT NOT NULL CHECK(role IN ('User', 'Assistant')),
+            content TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (session_id) REFERENCES tutor_sessions(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_tutor_sessions_node ON tutor_sessions(user_id, node_id);
+        CREATE INDEX IF NOT EXISTS idx_tutor_messages_session ON tutor_messages(session_id, created_at);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add tutor conversations: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v21(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS mistake_explanation_cache (
+            quiz_id TEXT NOT NULL,
+            question_id TEXT NOT NULL,
+            chosen_option TEXT NOT NULL,
+            explanation TEXT NOT NULL,
+            cached_at TEXT NOT NULL,
+            PRIMARY KEY (quiz_id, question_id, chosen_option)
+        );
+
+        CREATE TABLE IF NOT EXISTS quiz_mistake_explanations (
+            attempt_id TEXT NOT NULL,
+            question_id TEXT NOT NULL,
+            chosen_option TEXT NOT NULL,
+            explanation TEXT NOT NULL,
+            from_cache INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL,
+            PRIMARY KEY (attempt_id, question_id),
+            FOREIGN KEY (attempt_id) REFERENCES quiz_attempts(id) ON DELETE CASCADE
+        );
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add quiz mistake explanations: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v22(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS content_embeddings (
+            curriculum_id TEXT NOT NULL,
+            item_id TEXT NOT NULL,
+            node_id TEXT NOT NULL,
+            kind TEXT NOT NULL CHECK(kind IN ('Lecture', 'QuizQuestion')),
+            skills_json TEXT NOT NULL,
+            model TEXT NOT NULL,
+            vector BLOB NOT NULL,
+            indexed_at TEXT NOT NULL,
+            PRIMARY KEY (curriculum_id, item_id),
+            FOREIGN KEY (curriculum_id) REFERENCES curricula(id) ON DELETE CASCADE
+        );
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add content embeddings: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v23(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS practice_challenges (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            curriculum_id TEXT,
+            skill_id TEXT NOT NULL,
+            difficulty TEXT NOT NULL,
+            title TEXT NOT NULL,
+            instructions TEXT NOT NULL,
+            starter_code TEXT NOT NULL,
+            test_code TEXT NOT NULL,
+            solution TEXT NOT NULL,
+            hints_json TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_practice_challenges_skill ON practice_challenges(user_id, curriculum_id, skill_id);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add practice challenges: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v24(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS track_repos (
+            user_id TEXT NOT NULL,
+            curriculum_id TEXT,
+            track_id TEXT NOT NULL,
+            repo_path TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_track_repos_track ON track_repos(user_id, track_id);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add project track repos: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v25(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        -- Passage of the node's content a note annotates
+        ALTER TABLE notes ADD COLUMN anchor TEXT;
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add note annotations: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v26(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS bookmarks (
+            user_id TEXT NOT NULL,
+            curriculum_id TEXT,
+            node_id TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_bookmarks_queue ON bookmarks(user_id, curriculum_id, position);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add bookmarks: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v27(conn: &Connection) -> DbResult<()> {
+    // Kept up to date by triggers on the tables it rolls up, so every path
+    // that logs XP, completes a node or records session time counts
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS daily_activity (
+            user_id TEXT NOT NULL,
+            day TEXT NOT NULL,
+            active_seconds INTEGER NOT NULL DEFAULT 0,
+            xp INTEGER NOT NULL DEFAULT 0,
+            nodes_completed INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (user_id, day),
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE TRIGGER IF NOT EXISTS daily_activity_xp AFTER INSERT ON xp_log BEGIN
+            INSERT INTO daily_activity (user_id, day, xp) VALUES (new.user_id, substr(new.earned_at, 1, 10), new.amount)
+            ON CONFLICT (user_id, day) DO UPDATE SET xp = xp + excluded.xp;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS daily_activity_completed_insert AFTER INSERT ON node_progress
+        WHEN new.completed_at IS NOT NULL BEGIN
+            INSERT INTO daily_activity (user_id, day, nodes_completed) VALUES (new.user_id, substr(new.completed_at, 1, 10), 1)
+            ON CONFLICT (user_id, day) DO UPDATE SET nodes_completed = nodes_completed + 1;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS daily_activity_completed_update AFTER UPDATE OF completed_at ON node_progress
+        WHEN old.completed_at IS NULL AND new.completed_at IS NOT NULL BEGIN
+            INSERT INTO daily_activity (user_id, day, nodes_completed) VALUES (new.user_id, substr(new.completed_at, 1, 10), 1)
+            ON CONFLICT (user_id, day) DO UPDATE SET nodes_completed = nodes_completed + 1;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS daily_activity_time AFTER UPDATE OF active_seconds ON session_history
+        WHEN new.active_seconds > old.active_seconds BEGIN
+            INSERT INTO daily_activity (user_id, day, active_seconds)
+            VALUES (new.user_id, substr(COALESCE(new.last_heartbeat_at, new.started_at), 1, 10), new.active_seconds - old.active_seconds)
+            ON CONFLICT (user_id, day) DO UPDATE SET active_seconds = active_seconds + excluded.active_seconds;
+        END;
+
+        INSERT INTO daily_activity (user_id, day, active_seconds, xp, nodes_completed)
+        SELECT user_id, day, SUM(active_seconds), SUM(xp), SUM(nodes_completed) FROM (
+            SELECT user_id, substr(started_at, 1, 10) AS day, active_seconds, 0 AS xp, 0 AS nodes_completed
+            FROM session_history WHERE active_seconds > 0
+            UNION ALL
+            SELECT user_id, substr(earned_at, 1, 10), 0, amount, 0 FROM xp_log
+            UNION ALL
+            SELECT user_id, substr(completed_at, 1, 10), 0, 0, 1 FROM node_progress WHERE completed_at IS NOT NULL
+        )
+        GROUP BY user_id, day;
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add daily activity rollup: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v28(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS goals (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            curriculum_id TEXT,
+            title TEXT NOT NULL,
+            target_json TEXT NOT NULL,
+            deadline TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            achieved_at TEXT,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_goals_user ON goals(user_id, curriculum_id, deadline);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add goals: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v29(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE notification_prefs ADD COLUMN weekly_report_reminders INTEGER NOT NULL DEFAULT 0;
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add weekly report reminders: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v30(conn: &Connection) -> DbResult<()> {
+    // Earlier attempts stored answers without question ids, so they aren't backfilled
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS quiz_answers (
+            attempt_id TEXT NOT NULL,
+            question_id TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            answer TEXT NOT NULL,
+            is_correct INTEGER NOT NULL CHECK (is_correct IN (0, 1)),
+            skills_json TEXT NOT NULL DEFAULT '[]',
+            PRIMARY KEY (attempt_id, question_id),
+            FOREIGN KEY (attempt_id) REFERENCES quiz_attempts(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_quiz_answers_question ON quiz_answers(question_id);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add per-question quiz answers: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v31(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE quiz_attempts ADD COLUMN started_at TEXT;
+        ALTER TABLE quiz_attempts ADD COLUMN late_seconds INTEGER NOT NULL DEFAULT 0;
+
+        -- The clock for a quiz in progress, one per user and quiz
+        CREATE TABLE IF NOT EXISTS quiz_starts (
+            user_id TEXT NOT NULL,
+            curriculum_id TEXT,
+            quiz_id TEXT NOT NULL,
+            started_at TEXT NOT NULL,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_quiz_starts_key ON quiz_starts(user_id, IFNULL(curriculum_id, ''), quiz_id);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add timed quizzes: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v32(conn: &Connection) -> DbResult<()> {
+    // Certificates outlive the curriculum and progress they were issued for.
+    // certificate_key holds this install's signing key.
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS certificates (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            curriculum_id TEXT NOT NULL,
+            learner_name TEXT NOT NULL,
+            curriculum_name TEXT NOT NULL,
+            curriculum_version TEXT NOT NULL,
+            completed_at TEXT NOT NULL,
+            verification_hash TEXT NOT NULL,
+            UNIQUE (user_id, curriculum_id),
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS certificate_key (
+            key TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        );
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add completion certificates: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v33(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE users ADD COLUMN is_guest INTEGER NOT NULL DEFAULT 0;
+
+        CREATE TABLE IF NOT EXISTS profile_settings (
+            user_id TEXT NOT NULL,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            PRIMARY KEY (user_id, key),
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add guest profiles: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v34(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS api_tokens (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            label TEXT NOT NULL,
+            token_hash TEXT NOT NULL UNIQUE,
+            created_at TEXT NOT NULL,
+            last_used_at TEXT,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_api_tokens_user ON api_tokens(user_id);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add API tokens: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v35(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        -- Commit an artifact was graded at, when it was collected from a git repo
+        ALTER TABLE artifact_submissions ADD COLUMN commit_hash TEXT;
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add artifact commit hashes: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v36(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS repo_submissions (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            curriculum_id TEXT,
+            checkpoint_id TEXT NOT NULL,
+            repo_url TEXT NOT NULL,
+            commit_hash TEXT,
+            tests_passed INTEGER,
+            tests_total INTEGER,
+            verification_json TEXT,
+            artifact_submission_ids TEXT NOT NULL DEFAULT '[]',
+            passed INTEGER NOT NULL DEFAULT 0 CHECK (passed IN (0, 1)),
+            error TEXT,
+            submitted_at TEXT NOT NULL,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_repo_submissions_user ON repo_submissions(user_id, submitted_at);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add GitHub checkpoint submissions: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v37(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS hook_deliveries (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            hook_id TEXT NOT NULL,
+            action_json TEXT NOT NULL,
+            event_json TEXT NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            next_attempt_at TEXT NOT NULL,
+            last_error TEXT,
+            delivered_at TEXT,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_hook_deliveries_due ON hook_deliveries(delivered_at, next_attempt_at);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add automation hook deliveries: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v38(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS flashcards (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            curriculum_id TEXT,
+            skill_id TEXT NOT NULL,
+            front TEXT NOT NULL,
+            back TEXT NOT NULL,
+            tags_json TEXT NOT NULL,
+            source TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_flashcards_skill ON flashcards(user_id, curriculum_id, skill_id);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add imported flashcards: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v39(conn: &Connection) -> DbResult<()> {
+    // Seeded from the attempts so far, so improving on a result earned
+    // before the upgrade still earns only the difference. Artifact
+    // submissions aren't tagged with a curriculum; theirs go to the active
+    // one, as in v10.
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS best_attempts (
+            user_id TEXT NOT NULL,
+            curriculum_id TEXT,
+            node_id TEXT NOT NULL,
+            part TEXT NOT NULL DEFAULT '',
+            best_attempt_id TEXT,
+            best_verification_score REAL,
+            best_grade REAL,
+            xp_awarded INTEGER NOT NULL DEFAULT 0,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_best_attempts_key
+            ON best_attempts(user_id, IFNULL(curriculum_id, ''), node_id, part);
+
+        INSERT OR IGNORE INTO best_attempts (
+            user_id, curriculum_id, node_id, part, best_attempt_id, best_verification_score,
+            xp_awarded, attempts, updated_at
+        )
+        SELECT
+            a.user_id, a.curriculum_id, a.node_id, '',
+            (SELECT b.id FROM challenge_attempts b
+             WHERE b.user_id = a.user_id AND b.curriculum_id IS a.curriculum_id AND b.node_id = a.node_id
+               AND b.tests_passed + b.tests_failed > 0
+             ORDER BY b.tests_passed * 1.0 / (b.tests_passed + b.tests_failed) DESC, b.submitted_at
+             LIMIT 1),
+            MAX(CASE WHEN a.tests_passed + a.tests_failed > 0
+                THEN a.tests_passed * 100.0 / (a.tests_passed + a.tests_failed) END),
+            SUM(a.xp_earned), COUNT(*), MAX(a.submitted_at)
+        FROM challenge_attempts a
+        GROUP BY a.user_id, a.curriculum_id, a.node_id;
+
+        INSERT OR IGNORE INTO best_attempts (
+            user_id, curriculum_id, node_id, part, best_attempt_id, best_grade,
+            xp_awarded, attempts, updated_at
+        )
+        SELECT
+            a.user_id, (SELECT id FROM curricula WHERE is_active = 1 LIMIT 1), a.checkpoint_id, a.artifact_type,
+            (SELECT b.id FROM artifact_submissions b
+             WHERE b.user_id = a.user_id AND b.checkpoint_id = a.checkpoint_id
+               AND b.artifact_type = a.artifact_type AND b.grade_percentage IS NOT NULL
+             ORDER BY b.grade_percentage DESC, b.submitted_at
+             LIMIT 1),
+            MAX(a.grade_percentage), SUM(a.xp_earned), COUNT(*), MAX(a.submitted_at)
+        FROM artifact_submissions a
+        GROUP BY a.user_id, a.checkpoint_id, a.artifact_type;
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add best attempts: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v40(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        -- Signals an instructor may want to look at, as a JSON array
+        ALTER TABLE challenge_attempts ADD COLUMN flags_json TEXT;
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add challenge attempt flags: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v41(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS node_time (
+            user_id TEXT NOT NULL,
+            curriculum_id TEXT,
+            node_id TEXT NOT NULL,
+            active_seconds INTEGER NOT NULL DEFAULT 0,
+            idle_seconds INTEGER NOT NULL DEFAULT 0,
+            heartbeats INTEGER NOT NULL DEFAULT 0,
+            last_heartbeat_at TEXT,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_node_time_unique
+            ON node_time(user_id, IFNULL(curriculum_id, ''), node_id);
+        CREATE INDEX IF NOT EXISTS idx_node_time_last_heartbeat ON node_time(user_id, last_heartbeat_at);
+
+        -- Time the learner was active on nodes while the session was open
+        ALTER TABLE session_history ADD COLUMN learning_seconds INTEGER NOT NULL DEFAULT 0;
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add node active time: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v42(conn: &Connection) -> DbResult<()> {
+    // NULL for nodes without a check, and for lectures completed before checks existed
+    conn.execute_batch(
+        r#"
+        ALTER TABLE node_progress ADD COLUMN comprehension_check TEXT;
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add lecture comprehension checks: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v43(conn: &Connection) -> DbResult<()> {
+    // Key for an encrypted pack's sealed files, given when it's activated
+    conn.execute_batch(
+        r#"
+        ALTER TABLE curricula ADD COLUMN content_key TEXT;
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add curriculum content keys: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v44(conn: &Connection) -> DbResult<()> {
+    // Signed key a premium curriculum was activated with; checked again on every load
+    conn.execute_batch(
+        r#"
+        ALTER TABLE curricula ADD COLUMN license_key TEXT;
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add curriculum license keys: {}", e)))?;
+
+    Ok(())
+}
+
+fn migrate_to_v45(conn: &Connection) -> DbResult<()> {
+    // Events for learners who opted in to analytics, kept until they're uploaded
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS analytics_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id TEXT NOT NULL,
+            event_json TEXT NOT NULL,
+            curriculum TEXT NOT NULL,
+            curriculum_version TEXT NOT NULL,
+            recorded_on TEXT NOT NULL,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_analytics_events_user ON analytics_events(user_id, id);
+        "#,
+    )
+    .map_err(|e| DbError::Migration(format!("Failed to add analytics events: {}", e)))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_migrations_run_successfully() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let conn = Connection::open(&db_path).unwrap();
+
+        // Run migrations
+        let result = run_migrations(&conn);
+        assert!(result.is_ok(), "Migrations failed: {:?}", result);
+
+        // Check version was updated
+        let version: i32 = conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, CURRENT_VERSION);
+    }
+
+    #[test]
+    fn test_migrations_are_idempotent() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let conn = Connection::open(&db_path).unwrap();
+
+        // Run migrations twice
+        run_migrations(&conn).unwrap();
+        let result = run_migrations(&conn);
+        assert!(result.is_ok(), "Second migration run failed: {:?}", result);
+    }
+
+    #[test]
+    fn test_migrations_are_ordered() {
+        let versions: Vec<i32> = MIGRATIONS.iter().map(|m| m.version).collect();
+        let expected: Vec<i32> = (1..=CURRENT_VERSION).collect();
+        assert_eq!(versions, expected);
+    }
+
+    #[test]
+    fn test_schema_version_table_records_history() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+
+        let recorded: Vec<(i32, String)> = conn
+            .prepare("SELECT version, description FROM schema_version ORDER BY version")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(recorded.len(), CURRENT_VERSION as usize);
+        assert_eq!(recorded[0], (1, "initial schema".to_string()));
+        assert_eq!(schema_version(&conn).unwrap(), CURRENT_VERSION);
+    }
+
+    #[test]
+    fn test_legacy_database_history_is_backfilled() {
+        let conn = Connection::open_in_memory().unwrap();
+        for migration in &MIGRATIONS[..4] {
+            (migration.apply)(&conn).unwrap();
+        }
+        conn.pragma_update(None, "user_version", 4).unwrap();
+
+        let report = migrate(&conn, MIGRATIONS, MigrationMode::Apply).unwrap();
+        assert_eq!(report.from_version, 4);
+        assert_eq!(report.migrations.first().map(|m| m.0), Some(5));
+
+        let count: i32 = conn
+            .query_row("SELECT COUNT(*) FROM schema_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, CURRENT_VERSION);
+    }
+
+    #[test]
+    fn test_newer_schema_is_refused() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO schema_version (version, description, applied_at) VALUES (?1, 'future', 'now')",
+            [CURRENT_VERSION + 1],
+        )
+        .unwrap();
+
+        let result = run_migrations(&conn);
+        assert!(matches!(
+            result,
+            Err(DbError::SchemaTooNew { found, supported }) if found == CURRENT_VERSION + 1 && supported == CURRENT_VERSION
+        ));
+        assert!(dry_run_migrations(&conn).is_err());
+    }
+
+    #[test]
+    fn test_dry_run_leaves_database_untouched() {
+        let conn = Connection::open_in_memory().unwrap();
+
+        let report = dry_run_migrations(&conn).unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.to_version, CURRENT_VERSION);
+        assert_eq!(report.migrations.len(), MIGRATIONS.len());
+
+        let tables: i32 = conn
+            .query_row("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(tables, 0);
+        assert_eq!(schema_version(&conn).unwrap(), 0);
+
+        run_migrations(&conn).unwrap();
+        assert!(dry_run_migrations(&conn).unwrap().is_up_to_date());
+    }
+
+    #[test]
+    fn test_failed_migration_rolls_back() {
+        fn create_a(conn: &Connection) -> DbResult<()> {
+            conn.execute_batch("CREATE TABLE a (id INTEGER)")?;
+            Ok(())
+        }
+        fn half_applied(conn: &Connection) -> DbResult<()> {
+            conn.execute_batch("CREATE TABLE b (id INTEGER)")?;
+            conn.execute_batch("ALTER TABLE missing ADD COLUMN x INTEGER")?;
+            Ok(())
+        }
+        let migrations = [
+            Migration { version: 1, description: "a", apply: create_a },
+            Migration { version: 2, description: "b", apply: half_applied },
+        ];
+
+        let conn = Connection::open_in_memory().unwrap();
+        assert!(migrate(&conn, &migrations, MigrationMode::Apply).is_err());
+
+        let has_b: bool = conn
+            .query_row("SELECT COUNT(*) > 0 FROM sqlite_master WHERE name = 'b'", [], |row| row.get(0))
+            .unwrap();
+        assert!(!has_b);
+        assert_eq!(schema_version(&conn).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_v6_folds_badge_families_into_tiers() {
+        let dir = tempdir().unwrap();
+        let conn = Connection::open(dir.path().join("test.db")).unwrap();
+
+        for migrate in [migrate_to_v1, migrate_to_v2, migrate_to_v3, migrate_to_v4, migrate_to_v5] {
+            migrate(&conn).unwrap();
+        }
+        conn.pragma_update(None, "user_version", 5).unwrap();
+
+        conn.execute_batch(
+            r#"
+            INSERT INTO users (id, created_at, last_activity) VALUES ('u1', '2024-01-01T00:00:00+00:00', '2024-01-01T00:00:00+00:00');
+            INSERT INTO badge_progress (user_id, badge_id, current_value, earned_at) VALUES
+                ('u1', 'week_warrior', 35.0, '2024-01-07T00:00:00+00:00'),
+                ('u1', 'streak_master', 35.0, '2024-01-30T00:00:00+00:00'),
+                ('u1', 'unstoppable', 35.0, NULL),
+                ('u1', 'xp_hunter', 800.0, NULL);
+            "#,
+        )
+        .unwrap();
+
+        run_migrations(&conn).unwrap();
+
+        let (streak_tier, streak_earned): (Option<String>, Option<String>) = conn
+            .query_row(
+                "SELECT tier, earned_at FROM badge_progress WHERE user_id = 'u1' AND badge_id = 'streak_keeper'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(streak_tier.as_deref(), Some("Silver"));
+        assert_eq!(streak_earned.as_deref(), Some("2024-01-07T00:00:00+00:00"));
+
+        let (xp_tier, xp_earned): (Option<String>, Option<String>) = conn
+            .query_row(
+                "SELECT tier, earned_at FROM badge_progress WHERE user_id = 'u1' AND badge_id = 'xp_earner'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(xp_tier, None);
+        assert_eq!(xp_earned, None);
+
+        let legacy: i32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM badge_progress WHERE badge_id IN ('week_warrior', 'streak_master', 'unstoppable', 'xp_hunter')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(legacy, 0);
+    }
+
+    #[test]
+    fn test_v9_indexes_existing_quiz_answers() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn, &MIGRATIONS[..8], MigrationMode::Apply).unwrap();
+        conn.execute_batch(
+            r#"
+            INSERT INTO users (id, created_at, last_activity) VALUES ('u1', '2024-01-01T00:00:00+00:00', '2024-01-01T00:00:00+00:00');
+            INSERT INTO quiz_attempts (id, user_id, quiz_id, node_id, answers_json, score_percentage, xp_earned, submitted_at)
+            VALUES ('a1', 'u1', 'q1', 'n1', '["the borrow checker","b"]', 100, 50, '2024-01-02T00:00:00+00:00');
+            "#,
+        )
+        .unwrap();
+
+        run_migrations(&conn).unwrap();
+
+        let (attempt_id, answers): (String, String) = conn
+            .query_row(
+                "SELECT attempt_id, answers FROM quiz_answers_fts WHERE quiz_answers_fts MATCH 'checker'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(attempt_id, "a1");
+        assert_eq!(answers, "the borrow checker b");
+    }
+
+    #[test]
+    fn test_v10_assigns_existing_progress_to_active_curriculum() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn, &MIGRATIONS[..9], MigrationMode::Apply).unwrap();
+        conn.execute_batch(
+            r#"
+            INSERT INTO users (id, created_at, last_activity) VALUES ('u1', '2024-01-01T00:00:00+00:00', '2024-01-01T00:00:00+00:00');
+            INSERT INTO curricula (id, name, version, content_path, is_active) VALUES ('c1', 'Rust', '1.0', '/c1', 1);
+            INSERT INTO curricula (id, name, version, content_path, is_active) VALUES ('c2', 'Go', '1.0', '/c2', 0);
+            INSERT INTO node_progress (user_id, node_id, status) VALUES ('u1', 'n1', 'Completed');
+            INSERT INTO mastery_scores (user_id, skill_id, score) VALUES ('u1', 'ownership', 0.5);
+            INSERT INTO review_items (user_id, quiz_id, due_date, lapses) VALUES ('u1', 'q1', '2024-01-03T00:00:00+00:00', 2);
+            INSERT INTO quiz_attempts (id, user_id, quiz_id, node_id, answers_json, score_percentage, xp_earned, submitted_at)
+            VALUES ('a1', 'u1', 'q1', 'n1', '[]', 100, 50, '2024-01-02T00:00:00+00:00');
+            "#,
+        )
+        .unwrap();
+
+        run_migrations(&conn).unwrap();
+
+        for table in ["node_progress", "mastery_scores", "review_items", "quiz_attempts"] {
+            let curriculum: Option<String> = conn
+                .query_row(&format!("SELECT curriculum_id FROM {}", table), [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(curriculum.as_deref(), Some("c1"), "{}", table);
+        }
+        let lapses: i32 = conn.query_row("SELECT lapses FROM review_items", [], |row| row.get(0)).unwrap();
+        assert_eq!(lapses, 2);
+
+        // The same node can now be tracked separately in another curriculum
+        conn.execute(
+            "INSERT INTO node_progress (user_id, curriculum_id, node_id) VALUES ('u1', 'c2', 'n1')",
+            [],
+        )
+        .unwrap();
+        let duplicate = conn.execute(
+            "INSERT INTO node_progress (user_id, curriculum_id, node_id) VALUES ('u1', 'c1', 'n1')",
+            [],
+        );
+        assert!(duplicate.is_err());
+    }
+
+    #[test]
+    fn test_v39_seeds_best_attempts_from_history() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn, &MIGRATIONS[..38], MigrationMode::Apply).unwrap();
+        conn.execute_batch(
+            r#"
+            INSERT INTO users (id, created_at, last_activity) VALUES ('u1', '2024-01-01T00:00:00+00:00', '2024-01-01T00:00:00+00:00');
+            INSERT INTO curricula (id, name, version, content_path, is_active) VALUES ('c1', 'Rust', '1.0', '/c1', 1);
+            INSERT INTO challenge_attempts (id, user_id, curriculum_id, challenge_id, node_id, code_hash, tests_passed, tests_failed, xp_earned, submitted_at)
+            VALUES ('a1', 'u1', 'c1', 'ch1', 'n1', 'h1', 1, 2, 0, '2024-01-02T00:00:00+00:00'),
+                   ('a2', 'u1', 'c1', 'ch1', 'n1', 'h2', 3, 0, 120, '2024-01-03T00:00:00+00:00'),
+                   ('a3', 'u1', 'c1', 'ch1', 'n1', 'h3', 0, 0, 0, '2024-01-04T00:00:00+00:00');
+            INSERT INTO artifact_submissions (id, user_id, checkpoint_id, artifact_type, content_hash, grade_percentage, xp_earned, submitted_at)
+            VALUES ('s1', 'u1', 'cp1', 'README', 'h4', 80, 60, '2024-01-05T00:00:00+00:00');
+            "#,
+        )
+        .unwrap();
+
+        run_migrations(&conn).unwrap();
+
+        let challenge: (Option<String>, f64, i32, i32) = conn
+            .query_row(
+                "SELECT best_attempt_id, best_verification_score, xp_awarded, attempts FROM best_attempts WHERE node_id = 'n1'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(challenge, (Some("a2".to_string()), 100.0, 120, 3));
+
+        let artifact: (Option<String>, String, f64, i32) = conn
+            .query_row(
+                "SELECT curriculum_id, part, best_grade, xp_awarded FROM best_attempts WHERE node_id = 'cp1'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(artifact, (Some("c1".to_string()), "README".to_string(), 80.0, 60));
     }
 }
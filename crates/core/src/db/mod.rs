@@ -1,4 +1,24 @@
+use rusqlite::Connection;
+use crate::db::error::DbResult;
+
 pub mod connection;
 pub mod error;
+pub mod integrity;
 pub mod migrations;
 pub mod repos;
+
+/// Run `f` inside a savepoint, rolling it back if `f` fails. Savepoints nest,
+/// so this works both on its own and inside an outer transaction.
+pub(crate) fn in_savepoint<T>(conn: &Connection, name: &str, f: impl FnOnce() -> DbResult<T>) -> DbResult<T> {
+    conn.execute_batch(&format!("SAVEPOINT {}", name))?;
+    match f() {
+        Ok(value) => {
+            conn.execute_batch(&format!("RELEASE {}", name))?;
+            Ok(value)
+        }
+        Err(e) => {
+            conn.execute_batch(&format!("ROLLBACK TO {0}; RELEASE {0}", name))?;
+            Err(e)
+        }
+    }
+}
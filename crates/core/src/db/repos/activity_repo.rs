@@ -0,0 +1,96 @@
+use rusqlite::{params, Connection};
+use crate::db::error::DbResult;
+use crate::models::DailyActivity;
+
+pub struct ActivityRepository;
+
+const COLUMNS: &str = "user_id, day, active_seconds / 60, xp, nodes_completed";
+
+fn activity_from_row(row: &rusqlite::Row) -> rusqlite::Result<DailyActivity> {
+    Ok(DailyActivity {
+        user_id: row.get(0)?,
+        day: row.get(1)?,
+        active_minutes: row.get(2)?,
+        xp: row.get(3)?,
+        nodes_completed: row.get(4)?,
+    })
+}
+
+impl ActivityRepository {
+    /// Days with any activity in `[from, to]`, oldest first. Both bounds
+    /// are YYYY-MM-DD dates.
+    pub fn get_between(conn: &Connection, user_id: &str, from: &str, to: &str) -> DbResult<Vec<DailyActivity>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM daily_activity WHERE user_id = ?1 AND day >= ?2 AND day <= ?3 ORDER BY day",
+            COLUMNS
+        ))?;
+        let activity_iter = stmt.query_map(params![user_id, from, to], activity_from_row)?;
+
+        let mut results = Vec::new();
+        for activity in activity_iter {
+            results.push(activity?);
+        }
+        Ok(results)
+    }
+
+    /// Every day with any activity, oldest first
+    pub fn get_all(conn: &Connection, user_id: &str) -> DbResult<Vec<DailyActivity>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM daily_activity WHERE user_id = ?1 ORDER BY day",
+            COLUMNS
+        ))?;
+        let activity_iter = stmt.query_map(params![user_id], activity_from_row)?;
+
+        let mut results = Vec::new();
+        for activity in activity_iter {
+            results.push(activity?);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::{ProgressRepository, SessionRepository, UserRepository};
+    use crate::models::{SessionHistory, User};
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn test_rollup_follows_completion_paths() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        UserRepository::update_xp(conn, "test-user", 30).unwrap();
+        UserRepository::update_xp(conn, "test-user", 20).unwrap();
+        ProgressRepository::mark_completed(conn, "test-user", None, "node1").unwrap();
+        // Completing again doesn't count twice
+        ProgressRepository::mark_completed(conn, "test-user", None, "node1").unwrap();
+        ProgressRepository::mark_completed(conn, "test-user", None, "node2").unwrap();
+
+        let mut session = SessionHistory::new("test-user".to_string());
+        SessionRepository::create(conn, &session).unwrap();
+        session.active_seconds = 600;
+        session.last_heartbeat_at = Some(Utc::now());
+        SessionRepository::update(conn, &session).unwrap();
+        session.active_seconds = 900;
+        SessionRepository::update(conn, &session).unwrap();
+
+        let days = ActivityRepository::get_all(conn, "test-user").unwrap();
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].day, Utc::now().format("%Y-%m-%d").to_string());
+        assert_eq!(days[0].xp, 50);
+        assert_eq!(days[0].nodes_completed, 2);
+        assert_eq!(days[0].active_minutes, 15);
+
+        let today = Utc::now().date_naive();
+        let tomorrow = (today + Duration::days(1)).to_string();
+        assert!(ActivityRepository::get_between(conn, "test-user", &tomorrow, &tomorrow).unwrap().is_empty());
+        assert_eq!(
+            ActivityRepository::get_between(conn, "test-user", &today.to_string(), &tomorrow).unwrap().len(),
+            1
+        );
+    }
+}
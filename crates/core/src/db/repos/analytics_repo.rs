@@ -0,0 +1,149 @@
+use chrono::NaiveDate;
+use rusqlite::{params, params_from_iter, Connection};
+use crate::db::error::{DbError, DbResult};
+use crate::models::{QueuedEvent, RecordedEvent};
+
+pub struct AnalyticsRepository;
+
+fn queued_from_row(row: &rusqlite::Row) -> rusqlite::Result<QueuedEvent> {
+    let event_json: String = row.get(1)?;
+    let recorded_on: String = row.get(4)?;
+    Ok(QueuedEvent {
+        id: row.get(0)?,
+        recorded: RecordedEvent {
+            event: serde_json::from_str(&event_json)
+                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, Box::new(e)))?,
+            curriculum: row.get(2)?,
+            curriculum_version: row.get(3)?,
+            recorded_on: NaiveDate::parse_from_str(&recorded_on, "%Y-%m-%d")
+                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e)))?,
+        },
+    })
+}
+
+impl AnalyticsRepository {
+    pub fn enqueue(conn: &Connection, user_id: &str, recorded: &RecordedEvent) -> DbResult<()> {
+        let event_json = serde_json::to_string(&recorded.event).map_err(|e| DbError::InvalidData(e.to_string()))?;
+        conn.execute(
+            "INSERT INTO analytics_events (user_id, event_json, curriculum, curriculum_version, recorded_on)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                user_id,
+                event_json,
+                recorded.curriculum,
+                recorded.curriculum_version,
+                recorded.recorded_on.format("%Y-%m-%d").to_string(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// A user's queued events, oldest first, up to `limit` of them
+    pub fn get_queued(conn: &Connection, user_id: &str, limit: Option<usize>) -> DbResult<Vec<QueuedEvent>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, event_json, curriculum, curriculum_version, recorded_on FROM analytics_events
+             WHERE user_id = ?1 ORDER BY id LIMIT ?2",
+        )?;
+        let limit = limit.map_or(-1, |limit| limit as i64);
+        let events = stmt
+            .query_map(params![user_id, limit], queued_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(events)
+    }
+
+    pub fn count(conn: &Connection, user_id: &str) -> DbResult<usize> {
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM analytics_events WHERE user_id = ?1",
+            params![user_id],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// Users with events waiting to be uploaded
+    pub fn get_users_with_events(conn: &Connection) -> DbResult<Vec<String>> {
+        let mut stmt = conn.prepare("SELECT DISTINCT user_id FROM analytics_events ORDER BY user_id")?;
+        let users = stmt.query_map([], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
+        Ok(users)
+    }
+
+    /// Drop events once they've been uploaded
+    pub fn delete(conn: &Connection, ids: &[i64]) -> DbResult<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let placeholders = vec!["?"; ids.len()].join(", ");
+        conn.execute(
+            &format!("DELETE FROM analytics_events WHERE id IN ({})", placeholders),
+            params_from_iter(ids),
+        )?;
+        Ok(())
+    }
+
+    /// Drop a user's oldest events beyond the newest `keep`
+    pub fn trim(conn: &Connection, user_id: &str, keep: usize) -> DbResult<usize> {
+        Ok(conn.execute(
+            "DELETE FROM analytics_events WHERE user_id = ?1 AND id NOT IN (
+                SELECT id FROM analytics_events WHERE user_id = ?1 ORDER BY id DESC LIMIT ?2
+             )",
+            params![user_id, keep as i64],
+        )?)
+    }
+
+    /// Drop all of a user's events. Returns how many there were.
+    pub fn delete_all(conn: &Connection, user_id: &str) -> DbResult<usize> {
+        Ok(conn.execute("DELETE FROM analytics_events WHERE user_id = ?1", params![user_id])?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::{AnalyticsEvent, User};
+
+    fn recorded(node_id: &str) -> RecordedEvent {
+        RecordedEvent {
+            recorded_on: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            curriculum: "Rust Fundamentals".to_string(),
+            curriculum_version: "1.0.0".to_string(),
+            event: AnalyticsEvent::NodeCompleted {
+                node_id: node_id.to_string(),
+                node_type: "lecture".to_string(),
+                minutes: 12,
+            },
+        }
+    }
+
+    #[test]
+    fn test_queue_trim_and_delete() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+        UserRepository::create(conn, &User::new("other-user".to_string())).unwrap();
+
+        for node_id in ["a", "b", "c"] {
+            AnalyticsRepository::enqueue(conn, "test-user", &recorded(node_id)).unwrap();
+        }
+        AnalyticsRepository::enqueue(conn, "other-user", &recorded("d")).unwrap();
+
+        let queued = AnalyticsRepository::get_queued(conn, "test-user", None).unwrap();
+        assert_eq!(queued.iter().map(|q| q.recorded.clone()).collect::<Vec<_>>(), vec![
+            recorded("a"),
+            recorded("b"),
+            recorded("c")
+        ]);
+        assert_eq!(AnalyticsRepository::get_queued(conn, "test-user", Some(1)).unwrap().len(), 1);
+        assert_eq!(AnalyticsRepository::get_users_with_events(conn).unwrap(), vec!["other-user", "test-user"]);
+
+        assert_eq!(AnalyticsRepository::trim(conn, "test-user", 2).unwrap(), 1);
+        let queued = AnalyticsRepository::get_queued(conn, "test-user", None).unwrap();
+        assert_eq!(queued[0].recorded, recorded("b"));
+
+        AnalyticsRepository::delete(conn, &[queued[0].id]).unwrap();
+        assert_eq!(AnalyticsRepository::count(conn, "test-user").unwrap(), 1);
+        assert_eq!(AnalyticsRepository::delete_all(conn, "test-user").unwrap(), 1);
+        assert_eq!(AnalyticsRepository::count(conn, "other-user").unwrap(), 1);
+    }
+}
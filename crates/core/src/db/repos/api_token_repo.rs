@@ -0,0 +1,101 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use crate::db::error::DbResult;
+use crate::models::ApiToken;
+use crate::db::repos::parse_time;
+
+pub struct ApiTokenRepository;
+
+const COLUMNS: &str = "id, user_id, label, token_hash, created_at, last_used_at";
+
+fn token_from_row(row: &rusqlite::Row) -> rusqlite::Result<ApiToken> {
+    Ok(ApiToken {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        label: row.get(2)?,
+        token_hash: row.get(3)?,
+        created_at: parse_time(4, row.get(4)?)?,
+        last_used_at: row.get::<_, Option<String>>(5)?.map(|at| parse_time(5, at)).transpose()?,
+    })
+}
+
+impl ApiTokenRepository {
+    pub fn create(conn: &Connection, token: &ApiToken) -> DbResult<()> {
+        conn.execute(
+            &format!("INSERT INTO api_tokens ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6)", COLUMNS),
+            params![
+                token.id,
+                token.user_id,
+                token.label,
+                token.token_hash,
+                token.created_at.to_rfc3339(),
+                token.last_used_at.map(|at| at.to_rfc3339()),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// The token a secret belongs to, marking it used
+    pub fn authenticate(conn: &Connection, secret: &str, now: DateTime<Utc>) -> DbResult<Option<ApiToken>> {
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM api_tokens WHERE token_hash = ?1", COLUMNS))?;
+        let token = stmt.query_row(params![ApiToken::hash(secret)], token_from_row).optional()?;
+
+        match token {
+            Some(mut token) => {
+                conn.execute(
+                    "UPDATE api_tokens SET last_used_at = ?1 WHERE id = ?2",
+                    params![now.to_rfc3339(), token.id],
+                )?;
+                token.last_used_at = Some(now);
+                Ok(Some(token))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_all(conn: &Connection) -> DbResult<Vec<ApiToken>> {
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM api_tokens ORDER BY created_at", COLUMNS))?;
+        let token_iter = stmt.query_map([], token_from_row)?;
+
+        let mut results = Vec::new();
+        for token in token_iter {
+            results.push(token?);
+        }
+        Ok(results)
+    }
+
+    /// Returns whether a token was revoked
+    pub fn revoke(conn: &Connection, token_id: &str) -> DbResult<bool> {
+        let deleted = conn.execute("DELETE FROM api_tokens WHERE id = ?1", params![token_id])?;
+        Ok(deleted > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::User;
+
+    #[test]
+    fn test_tokens_authenticate_until_revoked() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let (token, secret) = ApiToken::issue("test-user".to_string(), "laptop".to_string());
+        ApiTokenRepository::create(conn, &token).unwrap();
+        assert_ne!(token.token_hash, secret);
+
+        let now = Utc::now();
+        let found = ApiTokenRepository::authenticate(conn, &secret, now).unwrap().unwrap();
+        assert_eq!(found.user_id, "test-user");
+        assert!(ApiTokenRepository::get_all(conn).unwrap()[0].last_used_at.is_some());
+        assert!(ApiTokenRepository::authenticate(conn, "glp_wrong", now).unwrap().is_none());
+
+        assert!(ApiTokenRepository::revoke(conn, &token.id).unwrap());
+        assert!(ApiTokenRepository::authenticate(conn, &secret, now).unwrap().is_none());
+        assert!(!ApiTokenRepository::revoke(conn, &token.id).unwrap());
+    }
+}
@@ -0,0 +1,206 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use crate::db::error::DbResult;
+use crate::models::{ArtifactSubmission, ArtifactType};
+use crate::db::repos::parse_time;
+
+pub struct ArtifactRepository;
+
+fn submission_from_row(row: &rusqlite::Row) -> rusqlite::Result<ArtifactSubmission> {
+    Ok(ArtifactSubmission {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        checkpoint_id: row.get(2)?,
+        artifact_type: ArtifactType::from_str(&row.get::<_, String>(3)?)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, e.into()))?,
+        content_hash: row.get(4)?,
+        grade_percentage: row.get(5)?,
+        reasoning_json: row.get(6)?,
+        xp_earned: row.get(7)?,
+        submitted_at: parse_time(8, row.get(8)?)?,
+        graded_at: row.get::<_, Option<String>>(9)?.map(|s| parse_time(9, s)).transpose()?,
+        commit_hash: row.get(10)?,
+    })
+}
+
+impl ArtifactRepository {
+    pub fn create(conn: &Connection, submission: &ArtifactSubmission) -> DbResult<()> {
+        conn.execute(
+            "INSERT INTO artifact_submissions (id, user_id, checkpoint_id, artifact_type, content_hash,
+                grade_percentage, reasoning_json, xp_earned, submitted_at, graded_at, commit_hash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                submission.id,
+                submission.user_id,
+                submission.checkpoint_id,
+                submission.artifact_type.as_str(),
+                submission.content_hash,
+                submission.grade_percentage,
+                submission.reasoning_json,
+                submission.xp_earned,
+                submission.submitted_at.to_rfc3339(),
+                submission.graded_at.map(|d| d.to_rfc3339()),
+                submission.commit_hash,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Record the grade of a submission graded after it was created
+    pub fn update_grade(conn: &Connection, submission: &ArtifactSubmission) -> DbResult<()> {
+        conn.execute(
+            "UPDATE artifact_submissions SET grade_percentage = ?1, reasoning_json = ?2, xp_earned = ?3, graded_at = ?4
+             WHERE id = ?5",
+            params![
+                submission.grade_percentage,
+                submission.reasoning_json,
+                submission.xp_earned,
+                submission.graded_at.map(|d| d.to_rfc3339()),
+                submission.id,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get(conn: &Connection, submission_id: &str) -> DbResult<Option<ArtifactSubmission>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, user_id, checkpoint_id, artifact_type, content_hash, grade_percentage,
+                    reasoning_json, xp_earned, submitted_at, graded_at, commit_hash
+             FROM artifact_submissions WHERE id = ?1"
+        )?;
+        let submission = stmt.query_row(params![submission_id], submission_from_row).optional()?;
+        Ok(submission)
+    }
+
+    /// A user's grade history, oldest first
+    pub fn get_all_for_user(conn: &Connection, user_id: &str) -> DbResult<Vec<ArtifactSubmission>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, user_id, checkpoint_id, artifact_type, content_hash, grade_percentage,
+                    reasoning_json, xp_earned, submitted_at, graded_at, commit_hash
+             FROM artifact_submissions WHERE user_id = ?1 ORDER BY submitted_at"
+        )?;
+        let submission_iter = stmt.query_map(params![user_id], submission_from_row)?;
+
+        let mut results = Vec::new();
+        for submission in submission_iter {
+            results.push(submission?);
+        }
+        Ok(results)
+    }
+
+    /// A user's submissions for one checkpoint, newest first
+    pub fn get_for_checkpoint(
+        conn: &Connection,
+        user_id: &str,
+        checkpoint_id: &str,
+    ) -> DbResult<Vec<ArtifactSubmission>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, user_id, checkpoint_id, artifact_type, content_hash, grade_percentage,
+                    reasoning_json, xp_earned, submitted_at, graded_at, commit_hash
+             FROM artifact_submissions WHERE user_id = ?1 AND checkpoint_id = ?2 ORDER BY submitted_at DESC"
+        )?;
+        let submission_iter = stmt.query_map(params![user_id, checkpoint_id], submission_from_row)?;
+
+        let mut results = Vec::new();
+        for submission in submission_iter {
+            results.push(submission?);
+        }
+        Ok(results)
+    }
+
+    pub fn get_awarded_xp_total(conn: &Connection, user_id: &str) -> DbResult<i32> {
+        let total: i32 = conn.query_row(
+            "SELECT COALESCE(SUM(xp_earned), 0) FROM artifact_submissions WHERE user_id = ?1",
+            params![user_id],
+            |row| row.get(0),
+        )?;
+        Ok(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::error::DbError;
+    use crate::db::repos::UserRepository;
+    use crate::models::User;
+
+    #[test]
+    fn test_create_and_list_graded_submission() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let mut submission = ArtifactSubmission::new(
+            "test-user".to_string(),
+            "week1-checkpoint".to_string(),
+            ArtifactType::Readme,
+            "# Project",
+        )
+        .with_commit("3f2a9c1");
+        submission.set_grade(85, r#"{"clarity": 90}"#.to_string(), 200);
+        ArtifactRepository::create(conn, &submission).unwrap();
+
+        let stored = ArtifactRepository::get(conn, &submission.id).unwrap().unwrap();
+        assert_eq!(stored.artifact_type, ArtifactType::Readme);
+        assert_eq!(stored.commit_hash.as_deref(), Some("3f2a9c1"));
+        assert_eq!(stored.grade_percentage, Some(85));
+        assert!(stored.graded_at.is_some());
+
+        let history = ArtifactRepository::get_all_for_user(conn, "test-user").unwrap();
+        assert_eq!(history.len(), 1);
+        assert!(ArtifactRepository::get_all_for_user(conn, "other-user").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_corrupt_graded_at_is_invalid_data() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let mut submission = ArtifactSubmission::new(
+            "test-user".to_string(),
+            "week1-checkpoint".to_string(),
+            ArtifactType::Readme,
+            "# Project",
+        );
+        submission.set_grade(85, "{}".to_string(), 200);
+        ArtifactRepository::create(conn, &submission).unwrap();
+        conn.execute("UPDATE artifact_submissions SET graded_at = 'not a time'", []).unwrap();
+
+        let err = ArtifactRepository::get(conn, &submission.id).unwrap_err();
+        assert!(matches!(err, DbError::InvalidData(_)), "got {err:?}");
+    }
+
+    #[test]
+    fn test_checkpoint_submissions_and_awarded_xp() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let mut design = ArtifactSubmission::new(
+            "test-user".to_string(),
+            "week1-checkpoint".to_string(),
+            ArtifactType::Design,
+            "# Design",
+        );
+        design.set_grade(80, "{}".to_string(), 120);
+        ArtifactRepository::create(conn, &design).unwrap();
+
+        let mut other = ArtifactSubmission::new(
+            "test-user".to_string(),
+            "week2-checkpoint".to_string(),
+            ArtifactType::Readme,
+            "# Readme",
+        );
+        other.set_grade(90, "{}".to_string(), 150);
+        ArtifactRepository::create(conn, &other).unwrap();
+
+        let submissions = ArtifactRepository::get_for_checkpoint(conn, "test-user", "week1-checkpoint").unwrap();
+        assert_eq!(submissions.len(), 1);
+        assert_eq!(submissions[0].artifact_type, ArtifactType::Design);
+
+        assert_eq!(ArtifactRepository::get_awarded_xp_total(conn, "test-user").unwrap(), 270);
+        assert_eq!(ArtifactRepository::get_awarded_xp_total(conn, "other-user").unwrap(), 0);
+    }
+}
@@ -1,23 +1,37 @@
-use chrono::{DateTime, Utc};
+use chrono::Utc;
 use rusqlite::{params, Connection, OptionalExtension};
 use crate::db::error::DbResult;
-use crate::models::BadgeProgress;
+use crate::models::{BadgeProgress, BadgeTier};
+use crate::db::repos::parse_time;
 
 pub struct BadgeRepository;
 
+fn badge_from_row(row: &rusqlite::Row) -> rusqlite::Result<BadgeProgress> {
+    Ok(BadgeProgress {
+        user_id: row.get(0)?,
+        badge_id: row.get(1)?,
+        current_value: row.get(2)?,
+        earned_at: row.get::<_, Option<String>>(3)?.map(|s| parse_time(3, s)).transpose()?,
+        tier: row.get::<_, Option<String>>(4)?
+            .and_then(|s| s.parse::<BadgeTier>().ok()),
+    })
+}
+
 impl BadgeRepository {
     pub fn create_or_update(conn: &Connection, badge: &BadgeProgress) -> DbResult<()> {
         conn.execute(
-            "INSERT INTO badge_progress (user_id, badge_id, current_value, earned_at)
-             VALUES (?1, ?2, ?3, ?4)
+            "INSERT INTO badge_progress (user_id, badge_id, current_value, earned_at, tier)
+             VALUES (?1, ?2, ?3, ?4, ?5)
              ON CONFLICT(user_id, badge_id) DO UPDATE SET
                 current_value = excluded.current_value,
-                earned_at = COALESCE(badge_progress.earned_at, excluded.earned_at)",
+                earned_at = COALESCE(badge_progress.earned_at, excluded.earned_at),
+                tier = COALESCE(excluded.tier, badge_progress.tier)",
             params![
                 badge.user_id,
                 badge.badge_id,
                 badge.current_value,
                 badge.earned_at.map(|d| d.to_rfc3339()),
+                badge.tier.map(|t| t.as_str()),
             ],
         )?;
         Ok(())
@@ -25,40 +39,22 @@ impl BadgeRepository {
 
     pub fn get(conn: &Connection, user_id: &str, badge_id: &str) -> DbResult<Option<BadgeProgress>> {
         let mut stmt = conn.prepare(
-            "SELECT user_id, badge_id, current_value, earned_at
+            "SELECT user_id, badge_id, current_value, earned_at, tier
              FROM badge_progress WHERE user_id = ?1 AND badge_id = ?2"
         )?;
 
-        let badge = stmt.query_row(params![user_id, badge_id], |row| {
-            Ok(BadgeProgress {
-                user_id: row.get(0)?,
-                badge_id: row.get(1)?,
-                current_value: row.get(2)?,
-                earned_at: row.get::<_, Option<String>>(3)?
-                    .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                    .map(|dt| dt.with_timezone(&Utc)),
-            })
-        }).optional()?;
+        let badge = stmt.query_row(params![user_id, badge_id], badge_from_row).optional()?;
 
         Ok(badge)
     }
 
     pub fn get_all_for_user(conn: &Connection, user_id: &str) -> DbResult<Vec<BadgeProgress>> {
         let mut stmt = conn.prepare(
-            "SELECT user_id, badge_id, current_value, earned_at
+            "SELECT user_id, badge_id, current_value, earned_at, tier
              FROM badge_progress WHERE user_id = ?1"
         )?;
 
-        let badge_iter = stmt.query_map(params![user_id], |row| {
-            Ok(BadgeProgress {
-                user_id: row.get(0)?,
-                badge_id: row.get(1)?,
-                current_value: row.get(2)?,
-                earned_at: row.get::<_, Option<String>>(3)?
-                    .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                    .map(|dt| dt.with_timezone(&Utc)),
-            })
-        })?;
+        let badge_iter = stmt.query_map(params![user_id], badge_from_row)?;
 
         let mut results = Vec::new();
         for badge in badge_iter {
@@ -69,20 +65,11 @@ impl BadgeRepository {
 
     pub fn get_earned(conn: &Connection, user_id: &str) -> DbResult<Vec<BadgeProgress>> {
         let mut stmt = conn.prepare(
-            "SELECT user_id, badge_id, current_value, earned_at
+            "SELECT user_id, badge_id, current_value, earned_at, tier
              FROM badge_progress WHERE user_id = ?1 AND earned_at IS NOT NULL"
         )?;
 
-        let badge_iter = stmt.query_map(params![user_id], |row| {
-            Ok(BadgeProgress {
-                user_id: row.get(0)?,
-                badge_id: row.get(1)?,
-                current_value: row.get(2)?,
-                earned_at: row.get::<_, Option<String>>(3)?
-                    .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                    .map(|dt| dt.with_timezone(&Utc)),
-            })
-        })?;
+        let badge_iter = stmt.query_map(params![user_id], badge_from_row)?;
 
         let mut results = Vec::new();
         for badge in badge_iter {
@@ -158,4 +145,25 @@ mod tests {
         assert_eq!(earned.len(), 1);
         assert_eq!(earned[0].badge_id, "badge1");
     }
+
+    #[test]
+    fn test_tier_persists_and_is_not_cleared() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        let mut badge = BadgeProgress::new("test-user".to_string(), "streak_keeper".to_string());
+        badge.earned_at = Some(Utc::now());
+        badge.tier = Some(BadgeTier::Silver);
+        BadgeRepository::create_or_update(conn, &badge).unwrap();
+
+        // Plain progress updates without a tier keep the earned tier
+        let mut update = BadgeProgress::new("test-user".to_string(), "streak_keeper".to_string());
+        update.current_value = 2.0;
+        BadgeRepository::create_or_update(conn, &update).unwrap();
+
+        let retrieved = BadgeRepository::get(conn, "test-user", "streak_keeper").unwrap().unwrap();
+        assert_eq!(retrieved.tier, Some(BadgeTier::Silver));
+        assert_eq!(retrieved.current_value, 2.0);
+        assert!(retrieved.is_earned());
+    }
 }
@@ -0,0 +1,151 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use crate::db::error::DbResult;
+use crate::models::BestAttempt;
+use crate::db::repos::parse_time;
+
+pub struct BestAttemptRepository;
+
+const COLUMNS: &str = "user_id, curriculum_id, node_id, part, best_attempt_id, best_verification_score, best_grade,
+                       xp_awarded, attempts, updated_at";
+
+fn best_from_row(row: &rusqlite::Row) -> rusqlite::Result<BestAttempt> {
+    Ok(BestAttempt {
+        user_id: row.get(0)?,
+        curriculum_id: row.get(1)?,
+        node_id: row.get(2)?,
+        part: row.get(3)?,
+        best_attempt_id: row.get(4)?,
+        best_verification_score: row.get(5)?,
+        best_grade: row.get(6)?,
+        xp_awarded: row.get(7)?,
+        attempts: row.get(8)?,
+        updated_at: parse_time(9, row.get(9)?)?,
+    })
+}
+
+impl BestAttemptRepository {
+    pub fn save(conn: &Connection, best: &BestAttempt) -> DbResult<()> {
+        conn.execute(
+            &format!(
+                "INSERT INTO best_attempts ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                 ON CONFLICT(user_id, IFNULL(curriculum_id, ''), node_id, part) DO UPDATE SET
+                    best_attempt_id = excluded.best_attempt_id,
+                    best_verification_score = excluded.best_verification_score,
+                    best_grade = excluded.best_grade,
+                    xp_awarded = excluded.xp_awarded,
+                    attempts = excluded.attempts,
+                    updated_at = excluded.updated_at",
+                COLUMNS
+            ),
+            params![
+                best.user_id,
+                best.curriculum_id,
+                best.node_id,
+                best.part,
+                best.best_attempt_id,
+                best.best_verification_score,
+                best.best_grade,
+                best.xp_awarded,
+                best.attempts,
+                best.updated_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        node_id: &str,
+        part: &str,
+    ) -> DbResult<Option<BestAttempt>> {
+        let best = conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM best_attempts
+                     WHERE user_id = ?1 AND curriculum_id IS ?2 AND node_id = ?3 AND part = ?4",
+                    COLUMNS
+                ),
+                params![user_id, curriculum_id, node_id, part],
+                best_from_row,
+            )
+            .optional()?;
+        Ok(best)
+    }
+
+    /// The tracker for a node, or a fresh one if nothing's been recorded
+    pub fn get_or_new(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        node_id: &str,
+        part: &str,
+    ) -> DbResult<BestAttempt> {
+        Ok(Self::get(conn, user_id, curriculum_id, node_id, part)?.unwrap_or_else(|| {
+            BestAttempt::new(
+                user_id.to_string(),
+                curriculum_id.map(str::to_string),
+                node_id.to_string(),
+                part.to_string(),
+            )
+        }))
+    }
+
+    /// Best results at a node: one for a challenge, one per artifact for a
+    /// checkpoint
+    pub fn get_for_node(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        node_id: &str,
+    ) -> DbResult<Vec<BestAttempt>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM best_attempts
+             WHERE user_id = ?1 AND curriculum_id IS ?2 AND node_id = ?3 ORDER BY part",
+            COLUMNS
+        ))?;
+
+        let best_iter = stmt.query_map(params![user_id, curriculum_id, node_id], best_from_row)?;
+
+        let mut results = Vec::new();
+        for best in best_iter {
+            results.push(best?);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::User;
+
+    #[test]
+    fn test_best_attempts_are_kept_per_part() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let mut readme = BestAttemptRepository::get_or_new(conn, "test-user", None, "checkpoint-1", "README").unwrap();
+        readme.record_grade("submission-1", 75.0, 40);
+        BestAttemptRepository::save(conn, &readme).unwrap();
+        readme.record_grade("submission-2", 90.0, 20);
+        BestAttemptRepository::save(conn, &readme).unwrap();
+
+        let mut design = BestAttemptRepository::get_or_new(conn, "test-user", None, "checkpoint-1", "DESIGN").unwrap();
+        design.record_grade("submission-3", 60.0, 0);
+        BestAttemptRepository::save(conn, &design).unwrap();
+
+        let stored = BestAttemptRepository::get_for_node(conn, "test-user", None, "checkpoint-1").unwrap();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[1].part, "README");
+        assert_eq!(stored[1].best_grade, Some(90.0));
+        assert_eq!((stored[1].xp_awarded, stored[1].attempts), (60, 2));
+        assert!(BestAttemptRepository::get_for_node(conn, "test-user", Some("other"), "checkpoint-1")
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+use rusqlite::{params, Connection};
+use crate::db::error::DbResult;
+use crate::db::in_savepoint;
+use crate::models::Bookmark;
+use crate::db::repos::parse_time;
+
+pub struct BookmarkRepository;
+
+const COLUMNS: &str = "user_id, curriculum_id, node_id, position, created_at";
+
+fn bookmark_from_row(row: &rusqlite::Row) -> rusqlite::Result<Bookmark> {
+    Ok(Bookmark {
+        user_id: row.get(0)?,
+        curriculum_id: row.get(1)?,
+        node_id: row.get(2)?,
+        position: row.get(3)?,
+        created_at: parse_time(4, row.get(4)?)?,
+    })
+}
+
+impl BookmarkRepository {
+    /// The user's reading queue, front first
+    pub fn get_all(conn: &Connection, user_id: &str, curriculum_id: Option<&str>) -> DbResult<Vec<Bookmark>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM bookmarks WHERE user_id = ?1 AND curriculum_id IS ?2 ORDER BY position, created_at",
+            COLUMNS
+        ))?;
+        let bookmark_iter = stmt.query_map(params![user_id, curriculum_id], bookmark_from_row)?;
+
+        let mut results = Vec::new();
+        for bookmark in bookmark_iter {
+            results.push(bookmark?);
+        }
+        Ok(results)
+    }
+
+    /// Bookmark a node at the back of the queue, or remove its bookmark.
+    /// Returns whether the node is bookmarked afterwards.
+    pub fn toggle(conn: &Connection, user_id: &str, curriculum_id: Option<&str>, node_id: &str) -> DbResult<bool> {
+        let deleted = conn.execute(
+            "DELETE FROM bookmarks WHERE user_id = ?1 AND curriculum_id IS ?2 AND node_id = ?3",
+            params![user_id, curriculum_id, node_id],
+        )?;
+        if deleted > 0 {
+            return Ok(false);
+        }
+
+        let position: i64 = conn.query_row(
+            "SELECT COALESCE(MAX(position) + 1, 0) FROM bookmarks WHERE user_id = ?1 AND curriculum_id IS ?2",
+            params![user_id, curriculum_id],
+            |row| row.get(0),
+        )?;
+        let bookmark = Bookmark::new(
+            user_id.to_string(),
+            curriculum_id.map(str::to_string),
+            node_id.to_string(),
+            position,
+        );
+        conn.execute(
+            &format!("INSERT INTO bookmarks ({}) VALUES (?1, ?2, ?3, ?4, ?5)", COLUMNS),
+            params![
+                bookmark.user_id,
+                bookmark.curriculum_id,
+                bookmark.node_id,
+                bookmark.position,
+                bookmark.created_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(true)
+    }
+
+    /// Put the queue in the given order. Bookmarked nodes left out of
+    /// `node_ids` keep their relative order behind the ones listed, and
+    /// unknown IDs are ignored.
+    pub fn reorder(conn: &Connection, user_id: &str, curriculum_id: Option<&str>, node_ids: &[String]) -> DbResult<()> {
+        in_savepoint(conn, "reorder_bookmarks", || {
+            let current = Self::get_all(conn, user_id, curriculum_id)?;
+            let listed = node_ids.iter().filter(|id| current.iter().any(|b| &b.node_id == *id));
+            let rest = current.iter().map(|b| &b.node_id).filter(|id| !node_ids.contains(id));
+
+            for (position, node_id) in listed.chain(rest).enumerate() {
+                conn.execute(
+                    "UPDATE bookmarks SET position = ?1 WHERE user_id = ?2 AND curriculum_id IS ?3 AND node_id = ?4",
+                    params![position as i64, user_id, curriculum_id, node_id],
+                )?;
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::User;
+
+    fn queue(conn: &Connection) -> Vec<String> {
+        BookmarkRepository::get_all(conn, "test-user", None)
+            .unwrap()
+            .into_iter()
+            .map(|b| b.node_id)
+            .collect()
+    }
+
+    #[test]
+    fn test_toggle_adds_to_back_and_removes() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        assert!(BookmarkRepository::toggle(conn, "test-user", None, "lecture-1").unwrap());
+        assert!(BookmarkRepository::toggle(conn, "test-user", None, "lecture-2").unwrap());
+        assert!(BookmarkRepository::toggle(conn, "test-user", None, "lecture-3").unwrap());
+        assert_eq!(queue(conn), vec!["lecture-1", "lecture-2", "lecture-3"]);
+
+        assert!(!BookmarkRepository::toggle(conn, "test-user", None, "lecture-2").unwrap());
+        assert_eq!(queue(conn), vec!["lecture-1", "lecture-3"]);
+        assert!(BookmarkRepository::get_all(conn, "test-user", Some("other-curriculum")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_reorder_keeps_unlisted_bookmarks_behind() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+        for node_id in ["lecture-1", "lecture-2", "lecture-3"] {
+            BookmarkRepository::toggle(conn, "test-user", None, node_id).unwrap();
+        }
+
+        let order = vec!["lecture-3".to_string(), "missing".to_string(), "lecture-2".to_string()];
+        BookmarkRepository::reorder(conn, "test-user", None, &order).unwrap();
+        assert_eq!(queue(conn), vec!["lecture-3", "lecture-2", "lecture-1"]);
+
+        BookmarkRepository::toggle(conn, "test-user", None, "lecture-4").unwrap();
+        assert_eq!(queue(conn).last().map(String::as_str), Some("lecture-4"));
+    }
+}
@@ -0,0 +1,114 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use uuid::Uuid;
+use crate::db::error::DbResult;
+use crate::models::Certificate;
+use crate::db::repos::parse_time;
+
+pub struct CertificateRepository;
+
+const COLUMNS: &str =
+    "id, user_id, curriculum_id, learner_name, curriculum_name, curriculum_version, completed_at, verification_hash";
+
+fn certificate_from_row(row: &rusqlite::Row) -> rusqlite::Result<Certificate> {
+    Ok(Certificate {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        curriculum_id: row.get(2)?,
+        learner_name: row.get(3)?,
+        curriculum_name: row.get(4)?,
+        curriculum_version: row.get(5)?,
+        completed_at: parse_time(6, row.get(6)?)?,
+        verification_hash: row.get(7)?,
+    })
+}
+
+impl CertificateRepository {
+    /// This install's certificate signing key, created on first use
+    pub fn signing_key(conn: &Connection) -> DbResult<String> {
+        let existing: Option<String> = conn
+            .query_row("SELECT key FROM certificate_key LIMIT 1", [], |row| row.get(0))
+            .optional()?;
+        if let Some(key) = existing {
+            return Ok(key);
+        }
+
+        let key = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        conn.execute(
+            "INSERT INTO certificate_key (key, created_at) VALUES (?1, ?2)",
+            params![key, Utc::now().to_rfc3339()],
+        )?;
+        Ok(key)
+    }
+
+    /// Store a certificate unless the user already has one for the curriculum
+    pub fn create(conn: &Connection, certificate: &Certificate) -> DbResult<()> {
+        conn.execute(
+            &format!("INSERT OR IGNORE INTO certificates ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)", COLUMNS),
+            params![
+                certificate.id,
+                certificate.user_id,
+                certificate.curriculum_id,
+                certificate.learner_name,
+                certificate.curriculum_name,
+                certificate.curriculum_version,
+                certificate.completed_at.to_rfc3339(),
+                certificate.verification_hash,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get(conn: &Connection, user_id: &str, curriculum_id: &str) -> DbResult<Option<Certificate>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM certificates WHERE user_id = ?1 AND curriculum_id = ?2",
+            COLUMNS
+        ))?;
+        let certificate = stmt.query_row(params![user_id, curriculum_id], certificate_from_row).optional()?;
+        Ok(certificate)
+    }
+
+    pub fn get_all(conn: &Connection, user_id: &str) -> DbResult<Vec<Certificate>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM certificates WHERE user_id = ?1 ORDER BY completed_at DESC",
+            COLUMNS
+        ))?;
+        let certificate_iter = stmt.query_map(params![user_id], certificate_from_row)?;
+
+        let mut results = Vec::new();
+        for certificate in certificate_iter {
+            results.push(certificate?);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::{Curriculum, User};
+
+    #[test]
+    fn test_signing_key_is_stable_and_certificates_are_kept_once() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let key = CertificateRepository::signing_key(conn).unwrap();
+        assert_eq!(key.len(), 64);
+        assert_eq!(CertificateRepository::signing_key(conn).unwrap(), key);
+
+        let curriculum = Curriculum::new("Rust".to_string(), "1.0".to_string(), "rust".to_string());
+        let first = Certificate::issue("test-user".to_string(), "Ada".to_string(), &curriculum, Utc::now(), key.as_bytes());
+        CertificateRepository::create(conn, &first).unwrap();
+        let again = Certificate::issue("test-user".to_string(), "Ada".to_string(), &curriculum, Utc::now(), key.as_bytes());
+        CertificateRepository::create(conn, &again).unwrap();
+
+        let stored = CertificateRepository::get(conn, "test-user", &curriculum.id).unwrap().unwrap();
+        assert_eq!(stored, first);
+        assert!(stored.verify(key.as_bytes()));
+        assert_eq!(CertificateRepository::get_all(conn, "test-user").unwrap().len(), 1);
+    }
+}
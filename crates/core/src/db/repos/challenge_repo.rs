@@ -0,0 +1,320 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use crate::db::error::{DbError, DbResult};
+use crate::models::{ChallengeAttempt, ChallengeDraft};
+use crate::db::repos::parse_time;
+
+pub struct ChallengeRepository;
+
+const COLUMNS: &str = "id, user_id, curriculum_id, challenge_id, node_id, code_hash, code, tests_passed, tests_failed,
+                       stdout, stderr, xp_earned, verification_json, flags_json, submitted_at";
+
+const DRAFT_COLUMNS: &str = "user_id, curriculum_id, node_id, code, restored_from, updated_at";
+
+fn attempt_from_row(row: &Row) -> rusqlite::Result<ChallengeAttempt> {
+    Ok(ChallengeAttempt {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        curriculum_id: row.get(2)?,
+        challenge_id: row.get(3)?,
+        node_id: row.get(4)?,
+        code_hash: row.get(5)?,
+        code: row.get(6)?,
+        tests_passed: row.get(7)?,
+        tests_failed: row.get(8)?,
+        stdout: row.get(9)?,
+        stderr: row.get(10)?,
+        xp_earned: row.get(11)?,
+        verification_json: row.get(12)?,
+        flags: row
+            .get::<_, Option<String>>(13)?
+            .map(|json| serde_json::from_str(&json))
+            .transpose()
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(13, rusqlite::types::Type::Text, Box::new(e)))?
+            .unwrap_or_default(),
+        submitted_at: parse_time(14, row.get(14)?)?,
+    })
+}
+
+fn draft_from_row(row: &Row) -> rusqlite::Result<ChallengeDraft> {
+    Ok(ChallengeDraft {
+        user_id: row.get(0)?,
+        curriculum_id: row.get(1)?,
+        node_id: row.get(2)?,
+        code: row.get(3)?,
+        restored_from: row.get(4)?,
+        updated_at: parse_time(5, row.get(5)?)?,
+    })
+}
+
+impl ChallengeRepository {
+    pub fn create(conn: &Connection, attempt: &ChallengeAttempt) -> DbResult<()> {
+        let flags_json = if attempt.flags.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(&attempt.flags).map_err(|e| DbError::InvalidData(e.to_string()))?)
+        };
+        conn.execute(
+            &format!(
+                "INSERT INTO challenge_attempts ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+                COLUMNS
+            ),
+            params![
+                attempt.id,
+                attempt.user_id,
+                attempt.curriculum_id,
+                attempt.challenge_id,
+                attempt.node_id,
+                attempt.code_hash,
+                attempt.code,
+                attempt.tests_passed,
+                attempt.tests_failed,
+                attempt.stdout,
+                attempt.stderr,
+                attempt.xp_earned,
+                attempt.verification_json,
+                flags_json,
+                attempt.submitted_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get(conn: &Connection, attempt_id: &str) -> DbResult<Option<ChallengeAttempt>> {
+        let attempt = conn
+            .query_row(
+                &format!("SELECT {} FROM challenge_attempts WHERE id = ?1", COLUMNS),
+                params![attempt_id],
+                attempt_from_row,
+            )
+            .optional()?;
+        Ok(attempt)
+    }
+
+    /// The user's attempts at a challenge node, newest first
+    pub fn get_for_node(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        node_id: &str,
+    ) -> DbResult<Vec<ChallengeAttempt>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM challenge_attempts
+             WHERE user_id = ?1 AND curriculum_id IS ?2 AND node_id = ?3 ORDER BY submitted_at DESC",
+            COLUMNS
+        ))?;
+
+        let attempt_iter = stmt.query_map(params![user_id, curriculum_id, node_id], attempt_from_row)?;
+
+        let mut results = Vec::new();
+        for attempt in attempt_iter {
+            results.push(attempt?);
+        }
+        Ok(results)
+    }
+
+    /// Attempts with the runner's full result, from every user and
+    /// curriculum, for content QA
+    pub fn get_verified(conn: &Connection) -> DbResult<Vec<ChallengeAttempt>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM challenge_attempts WHERE verification_json IS NOT NULL ORDER BY submitted_at DESC",
+            COLUMNS
+        ))?;
+
+        let attempt_iter = stmt.query_map([], attempt_from_row)?;
+
+        let mut results = Vec::new();
+        for attempt in attempt_iter {
+            results.push(attempt?);
+        }
+        Ok(results)
+    }
+
+    /// Flagged attempts by the members of a cohort, newest first, for an
+    /// instructor to review
+    pub fn get_flagged_in_cohort(conn: &Connection, cohort_id: &str) -> DbResult<Vec<ChallengeAttempt>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM challenge_attempts
+             WHERE flags_json IS NOT NULL
+               AND user_id IN (SELECT user_id FROM cohort_members WHERE cohort_id = ?1)
+             ORDER BY submitted_at DESC",
+            COLUMNS
+        ))?;
+
+        let attempt_iter = stmt.query_map(params![cohort_id], attempt_from_row)?;
+
+        let mut results = Vec::new();
+        for attempt in attempt_iter {
+            results.push(attempt?);
+        }
+        Ok(results)
+    }
+
+    /// Challenges the user has passed at least once, across every curriculum
+    pub fn count_passed(conn: &Connection, user_id: &str) -> DbResult<u32> {
+        let count: u32 = conn.query_row(
+            "SELECT COUNT(DISTINCT node_id) FROM challenge_attempts
+             WHERE user_id = ?1 AND tests_failed = 0 AND tests_passed > 0",
+            params![user_id],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    pub fn get_awarded_xp_total(conn: &Connection, user_id: &str) -> DbResult<i32> {
+        let total: i32 = conn.query_row(
+            "SELECT COALESCE(SUM(xp_earned), 0) FROM challenge_attempts WHERE user_id = ?1",
+            params![user_id],
+            |row| row.get(0),
+        )?;
+        Ok(total)
+    }
+
+    pub fn get_draft(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        node_id: &str,
+    ) -> DbResult<Option<ChallengeDraft>> {
+        let draft = conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM challenge_drafts WHERE user_id = ?1 AND curriculum_id IS ?2 AND node_id = ?3",
+                    DRAFT_COLUMNS
+                ),
+                params![user_id, curriculum_id, node_id],
+                draft_from_row,
+            )
+            .optional()?;
+        Ok(draft)
+    }
+
+    /// Replace the user's draft for the draft's node
+    pub fn save_draft(conn: &Connection, draft: &ChallengeDraft) -> DbResult<()> {
+        // curriculum_id may be NULL, which a unique key can't match on, so
+        // update first and insert only if there was nothing to update
+        let updated = conn.execute(
+            "UPDATE challenge_drafts SET code = ?1, restored_from = ?2, updated_at = ?3
+             WHERE user_id = ?4 AND curriculum_id IS ?5 AND node_id = ?6",
+            params![
+                draft.code,
+                draft.restored_from,
+                draft.updated_at.to_rfc3339(),
+                draft.user_id,
+                draft.curriculum_id,
+                draft.node_id,
+            ],
+        )?;
+        if updated == 0 {
+            conn.execute(
+                &format!("INSERT INTO challenge_drafts ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6)", DRAFT_COLUMNS),
+                params![
+                    draft.user_id,
+                    draft.curriculum_id,
+                    draft.node_id,
+                    draft.code,
+                    draft.restored_from,
+                    draft.updated_at.to_rfc3339(),
+                ],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::{CohortRepository, UserRepository};
+    use crate::models::{AttemptFlag, Cohort, User};
+
+    fn attempt(node_id: &str, tests_passed: i32, tests_failed: i32, xp_earned: i32) -> ChallengeAttempt {
+        ChallengeAttempt::new(
+            "test-user".to_string(),
+            node_id.to_string(),
+            node_id.to_string(),
+            "pub fn answer() -> u32 { 42 }",
+            tests_passed,
+            tests_failed,
+            Some("test result: ok".to_string()),
+            None,
+            xp_earned,
+        )
+    }
+
+    #[test]
+    fn test_attempts_round_trip_and_count_passed_nodes_once() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        ChallengeRepository::create(conn, &attempt("challenge-1", 1, 2, 0)).unwrap();
+        ChallengeRepository::create(conn, &attempt("challenge-1", 3, 0, 150)).unwrap();
+        ChallengeRepository::create(conn, &attempt("challenge-1", 3, 0, 0)).unwrap();
+        ChallengeRepository::create(conn, &attempt("challenge-2", 0, 0, 0)).unwrap();
+
+        let attempts = ChallengeRepository::get_for_node(conn, "test-user", None, "challenge-1").unwrap();
+        assert_eq!(attempts.len(), 3);
+        assert_eq!(attempts[0].stdout.as_deref(), Some("test result: ok"));
+
+        assert_eq!(ChallengeRepository::count_passed(conn, "test-user").unwrap(), 1);
+        assert_eq!(ChallengeRepository::get_awarded_xp_total(conn, "test-user").unwrap(), 150);
+    }
+
+    #[test]
+    fn test_drafts_are_replaced_and_restore_attempt_code() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let first = attempt("challenge-1", 1, 2, 0).with_verification(r#"{"success":false}"#.to_string());
+        ChallengeRepository::create(conn, &first).unwrap();
+        let stored = ChallengeRepository::get(conn, &first.id).unwrap().unwrap();
+        assert_eq!(stored.code.as_deref(), Some("pub fn answer() -> u32 { 42 }"));
+        assert_eq!(stored.verification_json.as_deref(), Some(r#"{"success":false}"#));
+        ChallengeRepository::create(conn, &attempt("challenge-1", 3, 0, 0)).unwrap();
+        let verified = ChallengeRepository::get_verified(conn).unwrap();
+        assert_eq!(verified.len(), 1);
+        assert_eq!(verified[0].id, first.id);
+
+        assert!(ChallengeRepository::get_draft(conn, "test-user", None, "challenge-1").unwrap().is_none());
+        let draft = ChallengeDraft::new("test-user".to_string(), None, "challenge-1".to_string(), "fn wip(".to_string());
+        ChallengeRepository::save_draft(conn, &draft).unwrap();
+        ChallengeRepository::save_draft(conn, &ChallengeDraft::restored(&stored).unwrap()).unwrap();
+
+        let draft = ChallengeRepository::get_draft(conn, "test-user", None, "challenge-1").unwrap().unwrap();
+        assert_eq!(draft.code, "pub fn answer() -> u32 { 42 }");
+        assert_eq!(draft.restored_from, Some(first.id));
+        let count: i32 = conn
+            .query_row("SELECT COUNT(*) FROM challenge_drafts", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_flagged_attempts_are_listed_for_cohort_members() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+        UserRepository::create(conn, &User::new("outsider".to_string())).unwrap();
+        CohortRepository::create(conn, &Cohort::new("c1".to_string(), "Spring".to_string())).unwrap();
+        CohortRepository::enroll(conn, "c1", "test-user").unwrap();
+
+        let flags = vec![
+            AttemptFlag::MatchesReference,
+            AttemptFlag::EditedOutsideAllowed { files: vec!["Cargo.toml".to_string()] },
+        ];
+        let flagged = attempt("challenge-1", 3, 0, 150).with_flags(flags.clone());
+        ChallengeRepository::create(conn, &flagged).unwrap();
+        ChallengeRepository::create(conn, &attempt("challenge-1", 1, 2, 0)).unwrap();
+        let mut outsider = attempt("challenge-1", 3, 0, 150).with_flags(vec![AttemptFlag::MatchesReference]);
+        outsider.user_id = "outsider".to_string();
+        ChallengeRepository::create(conn, &outsider).unwrap();
+
+        let stored = ChallengeRepository::get_flagged_in_cohort(conn, "c1").unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].id, flagged.id);
+        assert_eq!(stored[0].flags, flags);
+        assert_eq!(ChallengeRepository::get(conn, &outsider.id).unwrap().unwrap().flags.len(), 1);
+    }
+}
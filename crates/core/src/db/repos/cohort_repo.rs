@@ -0,0 +1,230 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use crate::db::error::{DbError, DbResult};
+use crate::models::{Cohort, CohortNodeStats, CohortSkillStats};
+use crate::db::repos::parse_time;
+
+pub struct CohortRepository;
+
+fn cohort_from_row(row: &rusqlite::Row) -> rusqlite::Result<Cohort> {
+    Ok(Cohort {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        created_at: parse_time(2, row.get(2)?)?,
+    })
+}
+
+impl CohortRepository {
+    pub fn create(conn: &Connection, cohort: &Cohort) -> DbResult<()> {
+        conn.execute(
+            "INSERT INTO cohorts (id, name, created_at) VALUES (?1, ?2, ?3)",
+            params![cohort.id, cohort.name, cohort.created_at.to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    pub fn get(conn: &Connection, cohort_id: &str) -> DbResult<Option<Cohort>> {
+        let mut stmt = conn.prepare("SELECT id, name, created_at FROM cohorts WHERE id = ?1")?;
+        let cohort = stmt.query_row(params![cohort_id], cohort_from_row).optional()?;
+        Ok(cohort)
+    }
+
+    pub fn list(conn: &Connection) -> DbResult<Vec<Cohort>> {
+        let mut stmt = conn.prepare("SELECT id, name, created_at FROM cohorts ORDER BY created_at DESC")?;
+        let cohort_iter = stmt.query_map([], cohort_from_row)?;
+
+        let mut results = Vec::new();
+        for cohort in cohort_iter {
+            results.push(cohort?);
+        }
+        Ok(results)
+    }
+
+    /// Delete a cohort and its enrollments (the profiles themselves are kept)
+    pub fn delete(conn: &Connection, cohort_id: &str) -> DbResult<()> {
+        conn.execute("DELETE FROM cohort_members WHERE cohort_id = ?1", params![cohort_id])?;
+        let rows = conn.execute("DELETE FROM cohorts WHERE id = ?1", params![cohort_id])?;
+
+        if rows == 0 {
+            return Err(DbError::NotFound(format!("Cohort not found: {}", cohort_id)));
+        }
+        Ok(())
+    }
+
+    /// Enroll a profile; enrolling twice is a no-op
+    pub fn enroll(conn: &Connection, cohort_id: &str, user_id: &str) -> DbResult<()> {
+        conn.execute(
+            "INSERT OR IGNORE INTO cohort_members (cohort_id, user_id, enrolled_at) VALUES (?1, ?2, ?3)",
+            params![cohort_id, user_id, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    pub fn unenroll(conn: &Connection, cohort_id: &str, user_id: &str) -> DbResult<()> {
+        conn.execute(
+            "DELETE FROM cohort_members WHERE cohort_id = ?1 AND user_id = ?2",
+            params![cohort_id, user_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_members(conn: &Connection, cohort_id: &str) -> DbResult<Vec<String>> {
+        let mut stmt = conn.prepare(
+            "SELECT user_id FROM cohort_members WHERE cohort_id = ?1 ORDER BY enrolled_at, user_id"
+        )?;
+        let member_iter = stmt.query_map(params![cohort_id], |row| row.get(0))?;
+
+        let mut results = Vec::new();
+        for member in member_iter {
+            results.push(member?);
+        }
+        Ok(results)
+    }
+
+    /// Progress aggregated per node over the cohort's members
+    pub fn get_node_stats(conn: &Connection, cohort_id: &str) -> DbResult<Vec<CohortNodeStats>> {
+        let learners = Self::get_members(conn, cohort_id)?.len() as u32;
+
+        let mut stmt = conn.prepare(
+            "SELECT np.node_id,
+                    SUM(CASE WHEN np.status != 'NotStarted' THEN 1 ELSE 0 END),
+                    SUM(CASE WHEN np.status = 'Completed' THEN 1 ELSE 0 END),
+                    AVG(np.attempts),
+                    AVG(np.time_spent_mins)
+             FROM node_progress np
+             JOIN cohort_members cm ON cm.user_id = np.user_id
+             WHERE cm.cohort_id = ?1
+             GROUP BY np.node_id
+             ORDER BY np.node_id"
+        )?;
+
+        let stats_iter = stmt.query_map(params![cohort_id], |row| {
+            Ok(CohortNodeStats {
+                node_id: row.get(0)?,
+                learners,
+                started: row.get(1)?,
+                completed: row.get(2)?,
+                avg_attempts: row.get(3)?,
+                avg_time_mins: row.get(4)?,
+                avg_mastery: None,
+            })
+        })?;
+
+        let mut results = Vec::new();
+        for stats in stats_iter {
+            results.push(stats?);
+        }
+        Ok(results)
+    }
+
+    /// Mastery aggregated per skill over the cohort's members
+    pub fn get_skill_stats(conn: &Connection, cohort_id: &str) -> DbResult<Vec<CohortSkillStats>> {
+        let mut stmt = conn.prepare(
+            "SELECT ms.skill_id, COUNT(*), AVG(ms.score), MIN(ms.score), MAX(ms.score)
+             FROM mastery_scores ms
+             JOIN cohort_members cm ON cm.user_id = ms.user_id
+             WHERE cm.cohort_id = ?1
+             GROUP BY ms.skill_id
+             ORDER BY ms.skill_id"
+        )?;
+
+        let stats_iter = stmt.query_map(params![cohort_id], |row| {
+            Ok(CohortSkillStats {
+                skill_id: row.get(0)?,
+                learners: row.get(1)?,
+                avg_score: row.get(2)?,
+                min_score: row.get(3)?,
+                max_score: row.get(4)?,
+            })
+        })?;
+
+        let mut results = Vec::new();
+        for stats in stats_iter {
+            results.push(stats?);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::{MasteryRepository, ProgressRepository, UserRepository};
+    use crate::models::{MasteryScore, NodeProgress, User};
+
+    fn setup_db() -> Database {
+        let db = Database::new_in_memory().unwrap();
+        for id in ["alice", "bob", "carol"] {
+            UserRepository::create(db.connection(), &User::new(id.to_string())).unwrap();
+        }
+        db
+    }
+
+    #[test]
+    fn test_create_enroll_and_delete() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        let cohort = Cohort::new("c1".to_string(), "Period 1".to_string());
+        CohortRepository::create(conn, &cohort).unwrap();
+        CohortRepository::enroll(conn, "c1", "alice").unwrap();
+        CohortRepository::enroll(conn, "c1", "alice").unwrap();
+        CohortRepository::enroll(conn, "c1", "bob").unwrap();
+
+        assert_eq!(CohortRepository::list(conn).unwrap().len(), 1);
+        assert_eq!(CohortRepository::get_members(conn, "c1").unwrap().len(), 2);
+
+        CohortRepository::unenroll(conn, "c1", "bob").unwrap();
+        assert_eq!(CohortRepository::get_members(conn, "c1").unwrap(), vec!["alice".to_string()]);
+
+        CohortRepository::delete(conn, "c1").unwrap();
+        assert!(CohortRepository::get(conn, "c1").unwrap().is_none());
+        assert!(UserRepository::get_by_id(conn, "alice").unwrap().is_some());
+        assert!(CohortRepository::delete(conn, "c1").is_err());
+    }
+
+    #[test]
+    fn test_stats_only_include_members() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        CohortRepository::create(conn, &Cohort::new("c1".to_string(), "Period 1".to_string())).unwrap();
+        CohortRepository::enroll(conn, "c1", "alice").unwrap();
+        CohortRepository::enroll(conn, "c1", "bob").unwrap();
+
+        let mut done = NodeProgress::new("alice".to_string(), "week1-lecture".to_string());
+        done.attempts = 2;
+        done.complete();
+        ProgressRepository::create_or_update(conn, &done).unwrap();
+
+        let mut started = NodeProgress::new("bob".to_string(), "week1-lecture".to_string());
+        started.start();
+        ProgressRepository::create_or_update(conn, &started).unwrap();
+
+        // carol is not enrolled and must not count
+        let mut outsider = NodeProgress::new("carol".to_string(), "week1-lecture".to_string());
+        outsider.complete();
+        ProgressRepository::create_or_update(conn, &outsider).unwrap();
+
+        for (user, score) in [("alice", 0.8), ("bob", 0.4), ("carol", 0.0)] {
+            let mut mastery = MasteryScore::new(user.to_string(), "ownership".to_string());
+            mastery.score = score;
+            MasteryRepository::create_or_update(conn, &mastery).unwrap();
+        }
+
+        let nodes = CohortRepository::get_node_stats(conn, "c1").unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].learners, 2);
+        assert_eq!(nodes[0].started, 2);
+        assert_eq!(nodes[0].completed, 1);
+        assert!((nodes[0].avg_attempts - 1.0).abs() < 1e-9);
+
+        let skills = CohortRepository::get_skill_stats(conn, "c1").unwrap();
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[0].learners, 2);
+        assert!((skills[0].avg_score - 0.6).abs() < 1e-9);
+        assert_eq!(skills[0].min_score, 0.4);
+        assert_eq!(skills[0].max_score, 0.8);
+    }
+}
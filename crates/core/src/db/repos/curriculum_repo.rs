@@ -1,7 +1,7 @@
-use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection, OptionalExtension};
 use crate::db::error::DbResult;
 use crate::models::Curriculum;
+use crate::db::repos::parse_time;
 
 pub struct CurriculumRepository;
 
@@ -39,9 +39,7 @@ impl CurriculumRepository {
                 version: row.get(2)?,
                 description: row.get(3)?,
                 author: row.get(4)?,
-                imported_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(5, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
+                imported_at: parse_time(5, row.get(5)?)?,
                 content_path: row.get(6)?,
                 is_active: row.get::<_, i32>(7)? != 0,
             })
@@ -64,9 +62,7 @@ impl CurriculumRepository {
                 version: row.get(2)?,
                 description: row.get(3)?,
                 author: row.get(4)?,
-                imported_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(5, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
+                imported_at: parse_time(5, row.get(5)?)?,
                 content_path: row.get(6)?,
                 is_active: row.get::<_, i32>(7)? != 0,
             })
@@ -93,9 +89,7 @@ impl CurriculumRepository {
                 version: row.get(2)?,
                 description: row.get(3)?,
                 author: row.get(4)?,
-                imported_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(5, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
+                imported_at: parse_time(5, row.get(5)?)?,
                 content_path: row.get(6)?,
                 is_active: row.get::<_, i32>(7)? != 0,
             })
@@ -118,8 +112,52 @@ impl CurriculumRepository {
         Ok(())
     }
 
+    /// Remember the content key an encrypted curriculum was unlocked with
+    pub fn set_content_key(conn: &Connection, id: &str, key: &str) -> DbResult<()> {
+        conn.execute(
+            "UPDATE curricula SET content_key = ?1 WHERE id = ?2",
+            params![key, id],
+        )?;
+        Ok(())
+    }
+
+    /// The content key a curriculum was unlocked with, if any
+    pub fn get_content_key(conn: &Connection, id: &str) -> DbResult<Option<String>> {
+        let key = conn
+            .query_row(
+                "SELECT content_key FROM curricula WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(key.flatten())
+    }
+
+    /// Remember the license key a premium curriculum was activated with
+    pub fn set_license_key(conn: &Connection, id: &str, key: &str) -> DbResult<()> {
+        conn.execute(
+            "UPDATE curricula SET license_key = ?1 WHERE id = ?2",
+            params![key, id],
+        )?;
+        Ok(())
+    }
+
+    /// The license key a curriculum was activated with, if any
+    pub fn get_license_key(conn: &Connection, id: &str) -> DbResult<Option<String>> {
+        let key = conn
+            .query_row(
+                "SELECT license_key FROM curricula WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(key.flatten())
+    }
+
     /// Delete a curriculum by ID
     pub fn delete(conn: &Connection, id: &str) -> DbResult<()> {
+        // Quiz attempts still reference curricula; keep them as unscoped history
+        conn.execute("UPDATE quiz_attempts SET curriculum_id = NULL WHERE curriculum_id = ?1", params![id])?;
         conn.execute("DELETE FROM curricula WHERE id = ?1", params![id])?;
         Ok(())
     }
@@ -133,6 +171,16 @@ impl CurriculumRepository {
         conn.execute("DELETE FROM mastery_scores WHERE curriculum_id = ?1", params![id])?;
         conn.execute("DELETE FROM badge_progress WHERE curriculum_id = ?1", params![id])?;
         conn.execute("DELETE FROM review_items WHERE curriculum_id = ?1", params![id])?;
+        conn.execute("DELETE FROM hint_reveals WHERE curriculum_id = ?1", params![id])?;
+        conn.execute("DELETE FROM challenge_drafts WHERE curriculum_id = ?1", params![id])?;
+        conn.execute("DELETE FROM best_attempts WHERE curriculum_id = ?1", params![id])?;
+        conn.execute("DELETE FROM node_time WHERE curriculum_id = ?1", params![id])?;
+        conn.execute("DELETE FROM track_repos WHERE curriculum_id = ?1", params![id])?;
+        conn.execute("DELETE FROM repo_submissions WHERE curriculum_id = ?1", params![id])?;
+        conn.execute("DELETE FROM flashcards WHERE curriculum_id = ?1", params![id])?;
+        conn.execute("DELETE FROM bookmarks WHERE curriculum_id = ?1", params![id])?;
+        conn.execute("DELETE FROM goals WHERE curriculum_id = ?1", params![id])?;
+        conn.execute("DELETE FROM quiz_starts WHERE curriculum_id = ?1", params![id])?;
         
         // Delete the curriculum itself
         conn.execute("DELETE FROM curricula WHERE id = ?1", params![id])?;
@@ -226,6 +274,34 @@ mod tests {
         assert!(!c1_updated.is_active);
     }
 
+    #[test]
+    fn test_content_and_license_keys() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        let curriculum = Curriculum::new("Paid Course".to_string(), "1.0".to_string(), "c1".to_string());
+        CurriculumRepository::create(conn, &curriculum).unwrap();
+        assert_eq!(CurriculumRepository::get_content_key(conn, &curriculum.id).unwrap(), None);
+
+        CurriculumRepository::set_content_key(conn, &curriculum.id, "ab12").unwrap();
+        assert_eq!(
+            CurriculumRepository::get_content_key(conn, &curriculum.id).unwrap(),
+            Some("ab12".to_string())
+        );
+        assert_eq!(CurriculumRepository::get_content_key(conn, "missing").unwrap(), None);
+
+        CurriculumRepository::set_license_key(conn, &curriculum.id, "GLP1.payload.signature").unwrap();
+        assert_eq!(
+            CurriculumRepository::get_license_key(conn, &curriculum.id).unwrap(),
+            Some("GLP1.payload.signature".to_string())
+        );
+        // Keys are kept apart
+        assert_eq!(
+            CurriculumRepository::get_content_key(conn, &curriculum.id).unwrap(),
+            Some("ab12".to_string())
+        );
+    }
+
     #[test]
     fn test_delete() {
         let db = setup_db();
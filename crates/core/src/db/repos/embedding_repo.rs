@@ -0,0 +1,134 @@
+use rusqlite::{params, Connection, Row};
+use crate::db::error::{DbError, DbResult};
+use crate::db::in_savepoint;
+use crate::models::ContentEmbedding;
+use crate::db::repos::parse_time;
+
+pub struct EmbeddingRepository;
+
+const COLUMNS: &str = "curriculum_id, item_id, node_id, kind, skills_json, model, vector, indexed_at";
+
+fn conversion_error(idx: usize, e: impl std::fmt::Display) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(
+        idx,
+        rusqlite::types::Type::Text,
+        Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string())),
+    )
+}
+
+/// Vectors are stored as little-endian f32s
+fn vector_to_blob(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn vector_from_blob(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .collect()
+}
+
+fn embedding_from_row(row: &Row) -> rusqlite::Result<ContentEmbedding> {
+    let kind: String = row.get(3)?;
+    let skills_json: String = row.get(4)?;
+    let vector: Vec<u8> = row.get(6)?;
+    let indexed_at: String = row.get(7)?;
+
+    Ok(ContentEmbedding {
+        curriculum_id: row.get(0)?,
+        item_id: row.get(1)?,
+        node_id: row.get(2)?,
+        kind: kind.parse().map_err(|e: String| conversion_error(3, e))?,
+        skills: serde_json::from_str(&skills_json).map_err(|e| conversion_error(4, e))?,
+        model: row.get(5)?,
+        vector: vector_from_blob(&vector),
+        indexed_at: parse_time(7, indexed_at)?,
+    })
+}
+
+impl EmbeddingRepository {
+    /// Replace a curriculum's whole index
+    pub fn replace_for_curriculum(
+        conn: &Connection,
+        curriculum_id: &str,
+        embeddings: &[ContentEmbedding],
+    ) -> DbResult<()> {
+        in_savepoint(conn, "embeddings", || {
+            conn.execute("DELETE FROM content_embeddings WHERE curriculum_id = ?1", params![curriculum_id])?;
+            for embedding in embeddings {
+                let skills_json = serde_json::to_string(&embedding.skills)
+                    .map_err(|e| DbError::InvalidData(e.to_string()))?;
+                conn.execute(
+                    &format!("INSERT INTO content_embeddings ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)", COLUMNS),
+                    params![
+                        curriculum_id,
+                        embedding.item_id,
+                        embedding.node_id,
+                        embedding.kind.as_str(),
+                        skills_json,
+                        embedding.model,
+                        vector_to_blob(&embedding.vector),
+                        embedding.indexed_at.to_rfc3339(),
+                    ],
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    pub fn get_for_curriculum(conn: &Connection, curriculum_id: &str) -> DbResult<Vec<ContentEmbedding>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM content_embeddings WHERE curriculum_id = ?1 ORDER BY item_id",
+            COLUMNS
+        ))?;
+
+        let embedding_iter = stmt.query_map(params![curriculum_id], embedding_from_row)?;
+
+        let mut results = Vec::new();
+        for embedding in embedding_iter {
+            results.push(embedding?);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::CurriculumRepository;
+    use crate::models::{Curriculum, EmbeddedKind};
+
+    fn embedding(curriculum_id: &str, item_id: &str, vector: Vec<f32>) -> ContentEmbedding {
+        ContentEmbedding::new(
+            curriculum_id.to_string(),
+            item_id.to_string(),
+            item_id.to_string(),
+            EmbeddedKind::Lecture,
+            vec!["ownership".to_string()],
+            "local".to_string(),
+            vector,
+        )
+    }
+
+    #[test]
+    fn test_index_is_replaced_and_vectors_round_trip() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        let curriculum = Curriculum::new("Rust".to_string(), "1.0".to_string(), "curricula/rust".to_string());
+        CurriculumRepository::create(conn, &curriculum).unwrap();
+
+        let first = vec![embedding(&curriculum.id, "lecture-1", vec![1.0, 0.0]), embedding(&curriculum.id, "lecture-2", vec![0.0, 1.0])];
+        EmbeddingRepository::replace_for_curriculum(conn, &curriculum.id, &first).unwrap();
+        let second = vec![embedding(&curriculum.id, "lecture-3", vec![0.6, -0.8])];
+        EmbeddingRepository::replace_for_curriculum(conn, &curriculum.id, &second).unwrap();
+
+        let stored = EmbeddingRepository::get_for_curriculum(conn, &curriculum.id).unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].item_id, "lecture-3");
+        assert_eq!(stored[0].vector, vec![0.6, -0.8]);
+        assert_eq!(stored[0].skills, vec!["ownership".to_string()]);
+
+        CurriculumRepository::delete(conn, &curriculum.id).unwrap();
+        assert!(EmbeddingRepository::get_for_curriculum(conn, &curriculum.id).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use crate::db::error::{DbError, DbResult};
+use crate::models::Flashcard;
+use crate::db::repos::parse_time;
+
+pub struct FlashcardRepository;
+
+const COLUMNS: &str = "id, user_id, curriculum_id, skill_id, front, back, tags_json, source, created_at";
+
+fn conversion_error(idx: usize, e: impl std::fmt::Display) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(
+        idx,
+        rusqlite::types::Type::Text,
+        Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string())),
+    )
+}
+
+fn flashcard_from_row(row: &Row) -> rusqlite::Result<Flashcard> {
+    let tags_json: String = row.get(6)?;
+    let created_at: String = row.get(8)?;
+
+    Ok(Flashcard {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        curriculum_id: row.get(2)?,
+        skill_id: row.get(3)?,
+        front: row.get(4)?,
+        back: row.get(5)?,
+        tags: serde_json::from_str(&tags_json).map_err(|e| conversion_error(6, e))?,
+        source: row.get(7)?,
+        created_at: parse_time(8, created_at)?,
+    })
+}
+
+impl FlashcardRepository {
+    pub fn create(conn: &Connection, card: &Flashcard) -> DbResult<()> {
+        let tags_json = serde_json::to_string(&card.tags).map_err(|e| DbError::InvalidData(e.to_string()))?;
+        conn.execute(
+            &format!("INSERT INTO flashcards ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)", COLUMNS),
+            params![
+                card.id,
+                card.user_id,
+                card.curriculum_id,
+                card.skill_id,
+                card.front,
+                card.back,
+                tags_json,
+                card.source,
+                card.created_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get(conn: &Connection, id: &str) -> DbResult<Option<Flashcard>> {
+        let card = conn
+            .query_row(
+                &format!("SELECT {} FROM flashcards WHERE id = ?1", COLUMNS),
+                params![id],
+                flashcard_from_row,
+            )
+            .optional()?;
+        Ok(card)
+    }
+
+    /// A user's cards for a skill, oldest first
+    pub fn get_for_skill(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        skill_id: &str,
+    ) -> DbResult<Vec<Flashcard>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM flashcards
+             WHERE user_id = ?1 AND curriculum_id IS ?2 AND skill_id = ?3
+             ORDER BY created_at",
+            COLUMNS
+        ))?;
+
+        let card_iter = stmt.query_map(params![user_id, curriculum_id, skill_id], flashcard_from_row)?;
+
+        let mut results = Vec::new();
+        for card in card_iter {
+            results.push(card?);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::User;
+
+    #[test]
+    fn test_flashcards_round_trip_by_skill() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let mut card = Flashcard::new(
+            "test-user".to_string(),
+            None,
+            "borrowing".to_string(),
+            "What does &mut guarantee?".to_string(),
+            "No other reference is live".to_string(),
+            "rust.csv".to_string(),
+        );
+        card.tags = vec!["refs".to_string()];
+        FlashcardRepository::create(conn, &card).unwrap();
+
+        let stored = FlashcardRepository::get(conn, &card.id).unwrap().unwrap();
+        assert_eq!(stored, card);
+        assert_eq!(Flashcard::id_from_review(&stored.review_id()), Some(card.id.as_str()));
+        assert!(Flashcard::id_from_review("quiz-1").is_none());
+
+        assert_eq!(FlashcardRepository::get_for_skill(conn, "test-user", None, "borrowing").unwrap().len(), 1);
+        assert!(FlashcardRepository::get_for_skill(conn, "test-user", Some("other"), "borrowing").unwrap().is_empty());
+    }
+}
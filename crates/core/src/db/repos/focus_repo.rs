@@ -0,0 +1,193 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use crate::db::error::DbResult;
+use crate::focus::{FocusBlock, FocusBlockKind, FocusSettings, FocusStatus};
+use crate::db::repos::parse_time;
+
+pub struct FocusRepository;
+
+const BLOCK_COLUMNS: &str = "id, user_id, session_id, kind, planned_seconds, started_at, paused_at, paused_seconds,
+                             ended_at, status, xp_awarded";
+
+fn invalid_text(idx: usize, e: String) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(
+        idx,
+        rusqlite::types::Type::Text,
+        Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+    )
+}
+
+fn block_from_row(row: &rusqlite::Row) -> rusqlite::Result<FocusBlock> {
+    Ok(FocusBlock {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        session_id: row.get(2)?,
+        kind: row.get::<_, String>(3)?.parse().map_err(|e| invalid_text(3, e))?,
+        planned_seconds: row.get(4)?,
+        started_at: parse_time(5, row.get(5)?)?,
+        paused_at: row.get::<_, Option<String>>(6)?.map(|s| parse_time(6, s)).transpose()?,
+        paused_seconds: row.get(7)?,
+        ended_at: row.get::<_, Option<String>>(8)?.map(|s| parse_time(8, s)).transpose()?,
+        status: row.get::<_, String>(9)?.parse().map_err(|e| invalid_text(9, e))?,
+        xp_awarded: row.get(10)?,
+    })
+}
+
+impl FocusRepository {
+    pub fn create_block(conn: &Connection, block: &FocusBlock) -> DbResult<()> {
+        conn.execute(
+            &format!("INSERT INTO focus_blocks ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)", BLOCK_COLUMNS),
+            params![
+                block.id,
+                block.user_id,
+                block.session_id,
+                block.kind.as_str(),
+                block.planned_seconds,
+                block.started_at.to_rfc3339(),
+                block.paused_at.map(|d| d.to_rfc3339()),
+                block.paused_seconds,
+                block.ended_at.map(|d| d.to_rfc3339()),
+                block.status.as_str(),
+                block.xp_awarded,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Persist pause, completion and award state
+    pub fn update_block(conn: &Connection, block: &FocusBlock) -> DbResult<()> {
+        conn.execute(
+            "UPDATE focus_blocks SET paused_at = ?1, paused_seconds = ?2, ended_at = ?3, status = ?4, xp_awarded = ?5
+             WHERE id = ?6",
+            params![
+                block.paused_at.map(|d| d.to_rfc3339()),
+                block.paused_seconds,
+                block.ended_at.map(|d| d.to_rfc3339()),
+                block.status.as_str(),
+                block.xp_awarded,
+                block.id,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// The user's running or paused block
+    pub fn get_open_block(conn: &Connection, user_id: &str) -> DbResult<Option<FocusBlock>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM focus_blocks WHERE user_id = ?1 AND status IN ('Running', 'Paused')
+             ORDER BY started_at DESC LIMIT 1",
+            BLOCK_COLUMNS
+        ))?;
+        let block = stmt.query_row(params![user_id], block_from_row).optional()?;
+        Ok(block)
+    }
+
+    /// The user's most recently finished block
+    pub fn get_last_finished(conn: &Connection, user_id: &str) -> DbResult<Option<FocusBlock>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM focus_blocks WHERE user_id = ?1 AND status IN ('Completed', 'Cancelled')
+             ORDER BY ended_at DESC LIMIT 1",
+            BLOCK_COLUMNS
+        ))?;
+        let block = stmt.query_row(params![user_id], block_from_row).optional()?;
+        Ok(block)
+    }
+
+    /// Completed work blocks that ended at or after `since`
+    pub fn count_completed_work_since(conn: &Connection, user_id: &str, since: DateTime<Utc>) -> DbResult<u32> {
+        let count: u32 = conn.query_row(
+            "SELECT COUNT(*) FROM focus_blocks
+             WHERE user_id = ?1 AND kind = ?2 AND status = ?3 AND ended_at >= ?4",
+            params![user_id, FocusBlockKind::Work.as_str(), FocusStatus::Completed.as_str(), since.to_rfc3339()],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    pub fn get_awarded_xp_total(conn: &Connection, user_id: &str) -> DbResult<i32> {
+        let total: i32 = conn.query_row(
+            "SELECT COALESCE(SUM(xp_awarded), 0) FROM focus_blocks WHERE user_id = ?1",
+            params![user_id],
+            |row| row.get(0),
+        )?;
+        Ok(total)
+    }
+
+    /// The user's timer settings, or the defaults if they haven't changed them
+    pub fn get_settings(conn: &Connection, user_id: &str) -> DbResult<FocusSettings> {
+        let settings = conn
+            .query_row(
+                "SELECT work_minutes, short_break_minutes, long_break_minutes, blocks_before_long_break
+                 FROM focus_settings WHERE user_id = ?1",
+                params![user_id],
+                |row| {
+                    Ok(FocusSettings {
+                        work_minutes: row.get(0)?,
+                        short_break_minutes: row.get(1)?,
+                        long_break_minutes: row.get(2)?,
+                        blocks_before_long_break: row.get(3)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(settings.unwrap_or_default())
+    }
+
+    pub fn save_settings(conn: &Connection, user_id: &str, settings: &FocusSettings) -> DbResult<()> {
+        conn.execute(
+            "INSERT INTO focus_settings (user_id, work_minutes, short_break_minutes, long_break_minutes, blocks_before_long_break)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(user_id) DO UPDATE SET
+                work_minutes = excluded.work_minutes,
+                short_break_minutes = excluded.short_break_minutes,
+                long_break_minutes = excluded.long_break_minutes,
+                blocks_before_long_break = excluded.blocks_before_long_break",
+            params![
+                user_id,
+                settings.work_minutes,
+                settings.short_break_minutes,
+                settings.long_break_minutes,
+                settings.blocks_before_long_break,
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::User;
+
+    #[test]
+    fn test_settings_default_until_saved() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        assert_eq!(FocusRepository::get_settings(conn, "test-user").unwrap(), FocusSettings::default());
+
+        let settings = FocusSettings { work_minutes: 50, short_break_minutes: 10, ..FocusSettings::default() };
+        FocusRepository::save_settings(conn, "test-user", &settings).unwrap();
+        assert_eq!(FocusRepository::get_settings(conn, "test-user").unwrap(), settings);
+    }
+
+    #[test]
+    fn test_block_round_trip() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let now = Utc::now();
+        let mut block = FocusBlock::new("test-user".to_string(), None, FocusBlockKind::Work, 1500, now);
+        FocusRepository::create_block(conn, &block).unwrap();
+        block.pause(now).unwrap();
+        FocusRepository::update_block(conn, &block).unwrap();
+
+        let open = FocusRepository::get_open_block(conn, "test-user").unwrap().unwrap();
+        assert_eq!(open, block);
+        assert!(FocusRepository::get_last_finished(conn, "test-user").unwrap().is_none());
+    }
+}
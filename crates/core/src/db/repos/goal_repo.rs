@@ -0,0 +1,115 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use crate::db::error::{DbError, DbResult};
+use crate::models::Goal;
+use crate::db::repos::parse_time;
+
+pub struct GoalRepository;
+
+const COLUMNS: &str = "id, user_id, curriculum_id, title, target_json, deadline, created_at, achieved_at";
+
+fn goal_from_row(row: &rusqlite::Row) -> rusqlite::Result<Goal> {
+    let target_json: String = row.get(4)?;
+
+    Ok(Goal {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        curriculum_id: row.get(2)?,
+        title: row.get(3)?,
+        target: serde_json::from_str(&target_json)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e)))?,
+        deadline: parse_time(5, row.get(5)?)?,
+        created_at: parse_time(6, row.get(6)?)?,
+        achieved_at: row.get::<_, Option<String>>(7)?.map(|at| parse_time(7, at)).transpose()?,
+    })
+}
+
+impl GoalRepository {
+    pub fn create(conn: &Connection, goal: &Goal) -> DbResult<()> {
+        let target_json = serde_json::to_string(&goal.target).map_err(|e| DbError::InvalidData(e.to_string()))?;
+
+        conn.execute(
+            &format!("INSERT INTO goals ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)", COLUMNS),
+            params![
+                goal.id,
+                goal.user_id,
+                goal.curriculum_id,
+                goal.title,
+                target_json,
+                goal.deadline.to_rfc3339(),
+                goal.created_at.to_rfc3339(),
+                goal.achieved_at.map(|at| at.to_rfc3339()),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_by_id(conn: &Connection, goal_id: &str) -> DbResult<Option<Goal>> {
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM goals WHERE id = ?1", COLUMNS))?;
+        let goal = stmt.query_row(params![goal_id], goal_from_row).optional()?;
+        Ok(goal)
+    }
+
+    /// The user's goals, soonest deadline first
+    pub fn get_all(conn: &Connection, user_id: &str, curriculum_id: Option<&str>) -> DbResult<Vec<Goal>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM goals WHERE user_id = ?1 AND curriculum_id IS ?2 ORDER BY deadline, created_at",
+            COLUMNS
+        ))?;
+        let goal_iter = stmt.query_map(params![user_id, curriculum_id], goal_from_row)?;
+
+        let mut results = Vec::new();
+        for goal in goal_iter {
+            results.push(goal?);
+        }
+        Ok(results)
+    }
+
+    pub fn mark_achieved(conn: &Connection, goal_id: &str, at: DateTime<Utc>) -> DbResult<()> {
+        conn.execute(
+            "UPDATE goals SET achieved_at = ?1 WHERE id = ?2 AND achieved_at IS NULL",
+            params![at.to_rfc3339(), goal_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete(conn: &Connection, goal_id: &str) -> DbResult<()> {
+        conn.execute("DELETE FROM goals WHERE id = ?1", params![goal_id])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::{GoalTarget, User};
+    use chrono::Duration;
+
+    #[test]
+    fn test_create_and_achieve() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let goal = Goal::new(
+            "test-user".to_string(),
+            None,
+            "Finish week 4".to_string(),
+            GoalTarget::CompleteWeek { week_id: "week4".to_string(), node_ids: vec!["a".to_string(), "b".to_string()] },
+            Utc::now() + Duration::days(14),
+        );
+        GoalRepository::create(conn, &goal).unwrap();
+
+        let goals = GoalRepository::get_all(conn, "test-user", None).unwrap();
+        assert_eq!(goals, vec![goal.clone()]);
+
+        GoalRepository::mark_achieved(conn, &goal.id, Utc::now()).unwrap();
+        let achieved = GoalRepository::get_by_id(conn, &goal.id).unwrap().unwrap();
+        assert!(achieved.achieved_at.is_some());
+
+        GoalRepository::delete(conn, &goal.id).unwrap();
+        assert!(GoalRepository::get_by_id(conn, &goal.id).unwrap().is_none());
+    }
+}
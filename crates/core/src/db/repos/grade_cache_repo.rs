@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use crate::db::error::DbResult;
+
+pub struct GradeCacheRepository;
+
+impl GradeCacheRepository {
+    /// Delete cached grades stored before `cutoff`. Returns how many were removed.
+    pub fn prune_older_than(conn: &Connection, cutoff: DateTime<Utc>) -> DbResult<usize> {
+        // cached_at defaults to SQLite's datetime('now') format, so compare
+        // through datetime() rather than as text
+        let deleted = conn.execute(
+            "DELETE FROM grade_cache WHERE datetime(cached_at) < datetime(?1)",
+            params![cutoff.to_rfc3339()],
+        )?;
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use chrono::Duration;
+
+    #[test]
+    fn test_prune_older_than() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        let old = (Utc::now() - Duration::days(45)).to_rfc3339();
+        conn.execute(
+            "INSERT INTO grade_cache (content_hash, artifact_type, grade_percentage, reasoning_json, cached_at)
+             VALUES ('old', 'README', 80, '{}', ?1)",
+            params![old],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO grade_cache (content_hash, artifact_type, grade_percentage, reasoning_json)
+             VALUES ('fresh', 'DESIGN', 90, '{}')",
+            [],
+        )
+        .unwrap();
+
+        let pruned = GradeCacheRepository::prune_older_than(conn, Utc::now() - Duration::days(30)).unwrap();
+        assert_eq!(pruned, 1);
+        let left: i64 = conn.query_row("SELECT COUNT(*) FROM grade_cache", [], |row| row.get(0)).unwrap();
+        assert_eq!(left, 1);
+    }
+}
@@ -0,0 +1,88 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use crate::db::error::DbResult;
+use crate::hints::{HintReveal, HintSettings};
+use crate::db::repos::parse_time;
+
+pub struct HintRepository;
+
+const REVEAL_COLUMNS: &str = "id, user_id, curriculum_id, node_id, hint_index, attempt_number, revealed_at";
+
+fn reveal_from_row(row: &rusqlite::Row) -> rusqlite::Result<HintReveal> {
+    Ok(HintReveal {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        curriculum_id: row.get(2)?,
+        node_id: row.get(3)?,
+        hint_index: row.get(4)?,
+        attempt_number: row.get(5)?,
+        revealed_at: parse_time(6, row.get(6)?)?,
+    })
+}
+
+impl HintRepository {
+    pub fn create_reveal(conn: &Connection, reveal: &HintReveal) -> DbResult<()> {
+        conn.execute(
+            &format!("INSERT INTO hint_reveals ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)", REVEAL_COLUMNS),
+            params![
+                reveal.id,
+                reveal.user_id,
+                reveal.curriculum_id,
+                reveal.node_id,
+                reveal.hint_index,
+                reveal.attempt_number,
+                reveal.revealed_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Hints the user has revealed for a challenge node, in hint order
+    pub fn get_reveals(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        node_id: &str,
+    ) -> DbResult<Vec<HintReveal>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM hint_reveals
+             WHERE user_id = ?1 AND curriculum_id IS ?2 AND node_id = ?3 ORDER BY hint_index",
+            REVEAL_COLUMNS
+        ))?;
+
+        let reveal_iter = stmt.query_map(params![user_id, curriculum_id, node_id], reveal_from_row)?;
+
+        let mut results = Vec::new();
+        for reveal in reveal_iter {
+            results.push(reveal?);
+        }
+        Ok(results)
+    }
+
+    pub fn get_settings(conn: &Connection, user_id: &str) -> DbResult<HintSettings> {
+        let settings = conn
+            .query_row(
+                "SELECT penalty_percent_per_hint, max_penalty_percent FROM hint_settings WHERE user_id = ?1",
+                params![user_id],
+                |row| {
+                    Ok(HintSettings {
+                        penalty_percent_per_hint: row.get(0)?,
+                        max_penalty_percent: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(settings.unwrap_or_default())
+    }
+
+    pub fn save_settings(conn: &Connection, user_id: &str, settings: &HintSettings) -> DbResult<()> {
+        conn.execute(
+            "INSERT INTO hint_settings (user_id, penalty_percent_per_hint, max_penalty_percent)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT(user_id) DO UPDATE SET
+                penalty_percent_per_hint = excluded.penalty_percent_per_hint,
+                max_penalty_percent = excluded.max_penalty_percent",
+            params![user_id, settings.penalty_percent_per_hint, settings.max_penalty_percent],
+        )?;
+        Ok(())
+    }
+}
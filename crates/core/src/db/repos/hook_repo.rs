@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use crate::db::error::{DbError, DbResult};
+use crate::models::{HookDelivery, MAX_HOOK_ATTEMPTS};
+use crate::db::repos::parse_time;
+
+pub struct HookRepository;
+
+const COLUMNS: &str =
+    "id, user_id, hook_id, action_json, event_json, attempts, next_attempt_at, last_error, delivered_at, created_at";
+
+fn parse_json<T: serde::de::DeserializeOwned>(idx: usize, value: String) -> rusqlite::Result<T> {
+    serde_json::from_str(&value)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e)))
+}
+
+fn delivery_from_row(row: &rusqlite::Row) -> rusqlite::Result<HookDelivery> {
+    Ok(HookDelivery {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        hook_id: row.get(2)?,
+        action: parse_json(3, row.get(3)?)?,
+        event: parse_json(4, row.get(4)?)?,
+        attempts: row.get(5)?,
+        next_attempt_at: parse_time(6, row.get(6)?)?,
+        last_error: row.get(7)?,
+        delivered_at: row.get::<_, Option<String>>(8)?.map(|s| parse_time(8, s)).transpose()?,
+        created_at: parse_time(9, row.get(9)?)?,
+    })
+}
+
+impl HookRepository {
+    pub fn enqueue(conn: &Connection, delivery: &HookDelivery) -> DbResult<()> {
+        let action_json = serde_json::to_string(&delivery.action).map_err(|e| DbError::InvalidData(e.to_string()))?;
+        let event_json = serde_json::to_string(&delivery.event).map_err(|e| DbError::InvalidData(e.to_string()))?;
+        conn.execute(
+            &format!("INSERT INTO hook_deliveries ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)", COLUMNS),
+            params![
+                delivery.id,
+                delivery.user_id,
+                delivery.hook_id,
+                action_json,
+                event_json,
+                delivery.attempts,
+                delivery.next_attempt_at.to_rfc3339(),
+                delivery.last_error,
+                delivery.delivered_at.map(|d| d.to_rfc3339()),
+                delivery.created_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Undelivered events due another attempt, oldest first
+    pub fn get_due(conn: &Connection, now: DateTime<Utc>) -> DbResult<Vec<HookDelivery>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM hook_deliveries
+             WHERE delivered_at IS NULL AND attempts < ?1 AND next_attempt_at <= ?2
+             ORDER BY created_at",
+            COLUMNS
+        ))?;
+        let deliveries = stmt
+            .query_map(params![MAX_HOOK_ATTEMPTS, now.to_rfc3339()], delivery_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(deliveries)
+    }
+
+    /// Record the outcome of a delivery attempt
+    pub fn update(conn: &Connection, delivery: &HookDelivery) -> DbResult<()> {
+        conn.execute(
+            "UPDATE hook_deliveries SET attempts = ?1, next_attempt_at = ?2, last_error = ?3, delivered_at = ?4
+             WHERE id = ?5",
+            params![
+                delivery.attempts,
+                delivery.next_attempt_at.to_rfc3339(),
+                delivery.last_error,
+                delivery.delivered_at.map(|d| d.to_rfc3339()),
+                delivery.id,
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::{Hook, HookAction, HookEvent, HookEventKind, User};
+    use chrono::Duration;
+
+    #[test]
+    fn test_only_due_deliveries_are_returned() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let hook = Hook {
+            id: "hook-1".to_string(),
+            event: HookEventKind::BadgeEarned,
+            action: HookAction::Command { script: "vault.sh".to_string(), args: vec!["--daily".to_string()] },
+            enabled: true,
+        };
+        let event = HookEvent::BadgeEarned { badge_id: "first-steps".to_string(), name: "First Steps".to_string() };
+        let mut failing = HookDelivery::new("test-user".to_string(), &hook, event.clone());
+        HookRepository::enqueue(conn, &failing).unwrap();
+        let mut delivered = HookDelivery::new("test-user".to_string(), &hook, event);
+        HookRepository::enqueue(conn, &delivered).unwrap();
+
+        let now = Utc::now();
+        assert_eq!(HookRepository::get_due(conn, now).unwrap(), vec![failing.clone(), delivered.clone()]);
+
+        failing.record_failure("exit status 1".to_string(), now);
+        HookRepository::update(conn, &failing).unwrap();
+        delivered.complete(now);
+        HookRepository::update(conn, &delivered).unwrap();
+        assert!(HookRepository::get_due(conn, now).unwrap().is_empty());
+
+        let due = HookRepository::get_due(conn, now + Duration::minutes(1)).unwrap();
+        assert_eq!(due, vec![failing]);
+    }
+}
@@ -0,0 +1,117 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use crate::db::error::DbResult;
+use crate::models::JobRun;
+use crate::db::repos::parse_time;
+
+pub struct JobRepository;
+
+fn job_run_from_row(row: &rusqlite::Row) -> rusqlite::Result<JobRun> {
+    Ok(JobRun {
+        job_id: row.get(0)?,
+        last_started_at: parse_time(1, row.get(1)?)?,
+        last_duration_ms: row.get(2)?,
+        last_result: row.get(3)?,
+        last_error: row.get(4)?,
+        last_success_at: row.get::<_, Option<String>>(5)?.map(|s| parse_time(5, s)).transpose()?,
+        run_count: row.get(6)?,
+        failure_count: row.get(7)?,
+    })
+}
+
+impl JobRepository {
+    pub fn get_run(conn: &Connection, job_id: &str) -> DbResult<Option<JobRun>> {
+        let mut stmt = conn.prepare(
+            "SELECT job_id, last_started_at, last_duration_ms, last_result, last_error,
+                    last_success_at, run_count, failure_count
+             FROM job_runs WHERE job_id = ?1"
+        )?;
+        let run = stmt.query_row(params![job_id], job_run_from_row).optional()?;
+        Ok(run)
+    }
+
+    pub fn get_runs(conn: &Connection) -> DbResult<Vec<JobRun>> {
+        let mut stmt = conn.prepare(
+            "SELECT job_id, last_started_at, last_duration_ms, last_result, last_error,
+                    last_success_at, run_count, failure_count
+             FROM job_runs ORDER BY job_id"
+        )?;
+        let run_iter = stmt.query_map([], job_run_from_row)?;
+
+        let mut results = Vec::new();
+        for run in run_iter {
+            results.push(run?);
+        }
+        Ok(results)
+    }
+
+    /// Record a finished run. `outcome` is the run's summary, or its error.
+    pub fn record_run(
+        conn: &Connection,
+        job_id: &str,
+        started_at: DateTime<Utc>,
+        duration_ms: i64,
+        outcome: Result<&str, &str>,
+    ) -> DbResult<()> {
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        let started = started_at.to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO job_runs (job_id, last_started_at, last_duration_ms, last_result, last_error,
+                                   last_success_at, run_count, failure_count)
+             VALUES (?1, ?2, ?3, ?4, ?5, CASE WHEN ?5 IS NULL THEN ?2 END, 1, CASE WHEN ?5 IS NULL THEN 0 ELSE 1 END)
+             ON CONFLICT(job_id) DO UPDATE SET
+                last_started_at = excluded.last_started_at,
+                last_duration_ms = excluded.last_duration_ms,
+                last_result = excluded.last_result,
+                last_error = excluded.last_error,
+                last_success_at = COALESCE(excluded.last_success_at, job_runs.last_success_at),
+                run_count = job_runs.run_count + 1,
+                failure_count = job_runs.failure_count + excluded.failure_count",
+            params![job_id, started, duration_ms, result, error],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::error::DbError;
+    use chrono::Duration;
+
+    #[test]
+    fn test_record_run_keeps_last_success() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        let first = Utc::now() - Duration::hours(1);
+
+        assert!(JobRepository::get_run(conn, "trash_purge").unwrap().is_none());
+
+        JobRepository::record_run(conn, "trash_purge", first, 12, Ok("Purged 2 snapshots")).unwrap();
+        JobRepository::record_run(conn, "trash_purge", Utc::now(), 5, Err("database is locked")).unwrap();
+
+        let run = JobRepository::get_run(conn, "trash_purge").unwrap().unwrap();
+        assert!(!run.succeeded());
+        assert_eq!((run.run_count, run.failure_count), (2, 1));
+        assert_eq!(run.last_result, None);
+        assert_eq!(run.last_success_at.map(|t| t.timestamp()), Some(first.timestamp()));
+        assert_eq!(JobRepository::get_runs(conn).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_corrupt_timestamp_is_invalid_data() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+
+        JobRepository::record_run(conn, "trash_purge", Utc::now(), 12, Ok("Purged 2 snapshots")).unwrap();
+        conn.execute("UPDATE job_runs SET last_success_at = 'yesterday'", []).unwrap();
+
+        let err = JobRepository::get_run(conn, "trash_purge").unwrap_err();
+        assert!(matches!(err, DbError::InvalidData(_)), "got {err:?}");
+    }
+}
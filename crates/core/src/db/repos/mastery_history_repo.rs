@@ -0,0 +1,152 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use crate::db::error::DbResult;
+use crate::models::{MasteryChangeReason, MasteryHistoryEntry};
+use crate::db::repos::parse_time;
+
+pub struct MasteryHistoryRepository;
+
+impl MasteryHistoryRepository {
+    /// Append a mastery change to the skill's history
+    pub fn record(conn: &Connection, entry: &MasteryHistoryEntry) -> DbResult<()> {
+        conn.execute(
+            "INSERT INTO mastery_history (user_id, skill_id, previous_score, score, reason, recorded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                entry.user_id,
+                entry.skill_id,
+                entry.previous_score,
+                entry.score,
+                entry.reason.as_str(),
+                entry.recorded_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get the mastery history for a skill within a time range, oldest first
+    pub fn get_mastery_history(
+        conn: &Connection,
+        user_id: &str,
+        skill_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> DbResult<Vec<MasteryHistoryEntry>> {
+        let mut stmt = conn.prepare(
+            "SELECT user_id, skill_id, previous_score, score, reason, recorded_at
+             FROM mastery_history
+             WHERE user_id = ?1 AND skill_id = ?2 AND recorded_at >= ?3 AND recorded_at <= ?4
+             ORDER BY recorded_at ASC, id ASC"
+        )?;
+
+        let history_iter = stmt.query_map(
+            params![user_id, skill_id, from.to_rfc3339(), to.to_rfc3339()],
+            |row| {
+                Ok(MasteryHistoryEntry {
+                    user_id: row.get(0)?,
+                    skill_id: row.get(1)?,
+                    previous_score: row.get(2)?,
+                    score: row.get(3)?,
+                    reason: row.get::<_, String>(4)?
+                        .parse::<MasteryChangeReason>()
+                        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, e.into()))?,
+                    recorded_at: parse_time(5, row.get(5)?)?,
+                })
+            },
+        )?;
+
+        let mut results = Vec::new();
+        for entry in history_iter {
+            results.push(entry?);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::User;
+    use chrono::Duration;
+
+    fn setup_db() -> Database {
+        let db = Database::new_in_memory().unwrap();
+        let user = User::new("test-user".to_string());
+        UserRepository::create(db.connection(), &user).unwrap();
+        db
+    }
+
+    #[test]
+    fn test_record_and_get_history() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        let first = MasteryHistoryEntry::new(
+            "test-user".to_string(),
+            "ownership".to_string(),
+            0.0,
+            0.25,
+            MasteryChangeReason::Quiz,
+        );
+        let second = MasteryHistoryEntry::new(
+            "test-user".to_string(),
+            "ownership".to_string(),
+            0.25,
+            0.2,
+            MasteryChangeReason::Decay,
+        );
+        MasteryHistoryRepository::record(conn, &first).unwrap();
+        MasteryHistoryRepository::record(conn, &second).unwrap();
+
+        let history = MasteryHistoryRepository::get_mastery_history(
+            conn,
+            "test-user",
+            "ownership",
+            Utc::now() - Duration::days(7),
+            Utc::now(),
+        )
+        .unwrap();
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].reason, MasteryChangeReason::Quiz);
+        assert_eq!(history[1].reason, MasteryChangeReason::Decay);
+        assert!(history[1].delta() < 0.0);
+    }
+
+    #[test]
+    fn test_history_respects_range() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        let mut old = MasteryHistoryEntry::new(
+            "test-user".to_string(),
+            "ownership".to_string(),
+            0.0,
+            0.25,
+            MasteryChangeReason::Quiz,
+        );
+        old.recorded_at = Utc::now() - Duration::days(30);
+        MasteryHistoryRepository::record(conn, &old).unwrap();
+
+        let other_skill = MasteryHistoryEntry::new(
+            "test-user".to_string(),
+            "lifetimes".to_string(),
+            0.0,
+            0.25,
+            MasteryChangeReason::Challenge,
+        );
+        MasteryHistoryRepository::record(conn, &other_skill).unwrap();
+
+        let history = MasteryHistoryRepository::get_mastery_history(
+            conn,
+            "test-user",
+            "ownership",
+            Utc::now() - Duration::days(7),
+            Utc::now(),
+        )
+        .unwrap();
+        assert!(history.is_empty());
+    }
+}
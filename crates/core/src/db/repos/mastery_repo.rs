@@ -1,20 +1,22 @@
-use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection, OptionalExtension};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Row};
 use crate::db::error::DbResult;
 use crate::models::MasteryScore;
+use crate::db::repos::parse_time;
 
 pub struct MasteryRepository;
 
 impl MasteryRepository {
     pub fn create_or_update(conn: &Connection, mastery: &MasteryScore) -> DbResult<()> {
         conn.execute(
-            "INSERT INTO mastery_scores (user_id, skill_id, score, last_updated_at)
-             VALUES (?1, ?2, ?3, ?4)
-             ON CONFLICT(user_id, skill_id) DO UPDATE SET
+            "INSERT INTO mastery_scores (user_id, curriculum_id, skill_id, score, last_updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(user_id, IFNULL(curriculum_id, ''), skill_id) DO UPDATE SET
                 score = excluded.score,
                 last_updated_at = excluded.last_updated_at",
             params![
                 mastery.user_id,
+                mastery.curriculum_id,
                 mastery.skill_id,
                 mastery.score,
                 mastery.last_updated_at.to_rfc3339(),
@@ -23,42 +25,32 @@ impl MasteryRepository {
         Ok(())
     }
 
-    pub fn get(conn: &Connection, user_id: &str, skill_id: &str) -> DbResult<Option<MasteryScore>> {
+    pub fn get(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        skill_id: &str,
+    ) -> DbResult<Option<MasteryScore>> {
         let mut stmt = conn.prepare(
-            "SELECT user_id, skill_id, score, last_updated_at
-             FROM mastery_scores WHERE user_id = ?1 AND skill_id = ?2"
+            "SELECT user_id, curriculum_id, skill_id, score, last_updated_at
+             FROM mastery_scores WHERE user_id = ?1 AND curriculum_id IS ?2 AND skill_id = ?3"
         )?;
 
-        let mastery = stmt.query_row(params![user_id, skill_id], |row| {
-            Ok(MasteryScore {
-                user_id: row.get(0)?,
-                skill_id: row.get(1)?,
-                score: row.get(2)?,
-                last_updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
-            })
-        }).optional()?;
+        let mastery = stmt
+            .query_row(params![user_id, curriculum_id, skill_id], Self::mastery_from_row)
+            .optional()?;
 
         Ok(mastery)
     }
 
+    /// Mastery across every curriculum the user has studied
     pub fn get_all_for_user(conn: &Connection, user_id: &str) -> DbResult<Vec<MasteryScore>> {
         let mut stmt = conn.prepare(
-            "SELECT user_id, skill_id, score, last_updated_at
+            "SELECT user_id, curriculum_id, skill_id, score, last_updated_at
              FROM mastery_scores WHERE user_id = ?1"
         )?;
 
-        let mastery_iter = stmt.query_map(params![user_id], |row| {
-            Ok(MasteryScore {
-                user_id: row.get(0)?,
-                skill_id: row.get(1)?,
-                score: row.get(2)?,
-                last_updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
-            })
-        })?;
+        let mastery_iter = stmt.query_map(params![user_id], Self::mastery_from_row)?;
 
         let mut results = Vec::new();
         for mastery in mastery_iter {
@@ -67,15 +59,58 @@ impl MasteryRepository {
         Ok(results)
     }
 
-    pub fn update_score(conn: &Connection, user_id: &str, skill_id: &str, new_score: f64) -> DbResult<()> {
+    pub fn get_all_in_curriculum(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+    ) -> DbResult<Vec<MasteryScore>> {
+        let mut stmt = conn.prepare(
+            "SELECT user_id, curriculum_id, skill_id, score, last_updated_at
+             FROM mastery_scores WHERE user_id = ?1 AND curriculum_id IS ?2"
+        )?;
+
+        let mastery_iter = stmt.query_map(params![user_id, curriculum_id], Self::mastery_from_row)?;
+
+        let mut results = Vec::new();
+        for mastery in mastery_iter {
+            results.push(mastery?);
+        }
+        Ok(results)
+    }
+
+    pub fn update_score(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        skill_id: &str,
+        new_score: f64,
+    ) -> DbResult<()> {
         let now = Utc::now().to_rfc3339();
         conn.execute(
             "UPDATE mastery_scores SET score = ?1, last_updated_at = ?2
-             WHERE user_id = ?3 AND skill_id = ?4",
-            params![new_score, now, user_id, skill_id],
+             WHERE user_id = ?3 AND curriculum_id IS ?4 AND skill_id = ?5",
+            params![new_score, now, user_id, curriculum_id, skill_id],
         )?;
         Ok(())
     }
+
+    pub fn delete(conn: &Connection, user_id: &str, curriculum_id: Option<&str>, skill_id: &str) -> DbResult<()> {
+        conn.execute(
+            "DELETE FROM mastery_scores WHERE user_id = ?1 AND curriculum_id IS ?2 AND skill_id = ?3",
+            params![user_id, curriculum_id, skill_id],
+        )?;
+        Ok(())
+    }
+
+    fn mastery_from_row(row: &Row) -> rusqlite::Result<MasteryScore> {
+        Ok(MasteryScore {
+            user_id: row.get(0)?,
+            curriculum_id: row.get(1)?,
+            skill_id: row.get(2)?,
+            score: row.get(3)?,
+            last_updated_at: parse_time(4, row.get(4)?)?,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -101,7 +136,7 @@ mod tests {
         mastery.score = 0.75;
         MasteryRepository::create_or_update(conn, &mastery).unwrap();
 
-        let retrieved = MasteryRepository::get(conn, "test-user", "ownership").unwrap();
+        let retrieved = MasteryRepository::get(conn, "test-user", None, "ownership").unwrap();
         assert!(retrieved.is_some());
         let retrieved = retrieved.unwrap();
         assert!((retrieved.score - 0.75).abs() < 0.01);
@@ -129,9 +164,29 @@ mod tests {
         let mastery = MasteryScore::new("test-user".to_string(), "ownership".to_string());
         MasteryRepository::create_or_update(conn, &mastery).unwrap();
 
-        MasteryRepository::update_score(conn, "test-user", "ownership", 0.9).unwrap();
+        MasteryRepository::update_score(conn, "test-user", None, "ownership", 0.9).unwrap();
 
-        let updated = MasteryRepository::get(conn, "test-user", "ownership").unwrap().unwrap();
+        let updated = MasteryRepository::get(conn, "test-user", None, "ownership").unwrap().unwrap();
         assert!((updated.score - 0.9).abs() < 0.01);
     }
+
+    #[test]
+    fn test_update_score_stays_in_curriculum() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        for curriculum in ["rust", "go"] {
+            let mastery = MasteryScore::new("test-user".to_string(), "concurrency".to_string())
+                .with_curriculum(curriculum.to_string());
+            MasteryRepository::create_or_update(conn, &mastery).unwrap();
+        }
+
+        MasteryRepository::update_score(conn, "test-user", Some("go"), "concurrency", 0.8).unwrap();
+
+        let rust = MasteryRepository::get(conn, "test-user", Some("rust"), "concurrency").unwrap().unwrap();
+        let go = MasteryRepository::get(conn, "test-user", Some("go"), "concurrency").unwrap().unwrap();
+        assert_eq!(rust.score, 0.0);
+        assert!((go.score - 0.8).abs() < 0.01);
+        assert_eq!(MasteryRepository::get_all_in_curriculum(conn, "test-user", Some("rust")).unwrap().len(), 1);
+    }
 }
@@ -1,17 +1,85 @@
 pub mod user_repo;
 pub mod progress_repo;
 pub mod mastery_repo;
+pub mod mastery_history_repo;
 pub mod badge_repo;
 pub mod quiz_repo;
 pub mod session_repo;
 pub mod review_repo;
 pub mod curriculum_repo;
+pub mod quest_repo;
+pub mod cohort_repo;
+pub mod note_repo;
+pub mod search_repo;
+pub mod trash_repo;
+pub mod artifact_repo;
+pub mod sync_repo;
+pub mod job_repo;
+pub mod grade_cache_repo;
+pub mod focus_repo;
+pub mod pending_work_repo;
+pub mod notification_repo;
+pub mod challenge_repo;
+pub mod hint_repo;
+pub mod tutor_repo;
+pub mod embedding_repo;
+pub mod practice_repo;
+pub mod track_repo;
+pub mod bookmark_repo;
+pub mod activity_repo;
+pub mod goal_repo;
+pub mod certificate_repo;
+pub mod api_token_repo;
+pub mod repo_submission_repo;
+pub mod hook_repo;
+pub mod flashcard_repo;
+pub mod best_attempt_repo;
+pub mod node_time_repo;
+pub mod analytics_repo;
 
 pub use user_repo::UserRepository;
 pub use progress_repo::ProgressRepository;
 pub use mastery_repo::MasteryRepository;
+pub use mastery_history_repo::MasteryHistoryRepository;
 pub use badge_repo::BadgeRepository;
 pub use quiz_repo::QuizRepository;
 pub use session_repo::SessionRepository;
 pub use review_repo::ReviewRepository;
 pub use curriculum_repo::CurriculumRepository;
+pub use quest_repo::QuestRepository;
+pub use cohort_repo::CohortRepository;
+pub use note_repo::NoteRepository;
+pub use search_repo::SearchRepository;
+pub use trash_repo::TrashRepository;
+pub use artifact_repo::ArtifactRepository;
+pub use sync_repo::SyncRepository;
+pub use job_repo::JobRepository;
+pub use grade_cache_repo::GradeCacheRepository;
+pub use focus_repo::FocusRepository;
+pub use pending_work_repo::PendingWorkRepository;
+pub use notification_repo::NotificationRepository;
+pub use challenge_repo::ChallengeRepository;
+pub use hint_repo::HintRepository;
+pub use tutor_repo::TutorRepository;
+pub use embedding_repo::EmbeddingRepository;
+pub use practice_repo::PracticeRepository;
+pub use track_repo::TrackRepository;
+pub use bookmark_repo::BookmarkRepository;
+pub use activity_repo::ActivityRepository;
+pub use goal_repo::GoalRepository;
+pub use certificate_repo::CertificateRepository;
+pub use api_token_repo::ApiTokenRepository;
+pub use repo_submission_repo::RepoSubmissionRepository;
+pub use hook_repo::HookRepository;
+pub use flashcard_repo::FlashcardRepository;
+pub use best_attempt_repo::BestAttemptRepository;
+pub use node_time_repo::NodeTimeRepository;
+pub use analytics_repo::AnalyticsRepository;
+
+/// Read an RFC 3339 timestamp stored in column `idx`. One that can't be read
+/// comes back as [`DbError::InvalidData`](crate::db::error::DbError::InvalidData).
+pub(crate) fn parse_time(idx: usize, value: String) -> rusqlite::Result<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_rfc3339(&value)
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e)))
+}
@@ -0,0 +1,151 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use crate::db::error::DbResult;
+use crate::models::NodeTime;
+use crate::db::repos::parse_time;
+
+pub struct NodeTimeRepository;
+
+const COLUMNS: &str = "user_id, curriculum_id, node_id, active_seconds, idle_seconds, heartbeats, last_heartbeat_at";
+
+fn node_time_from_row(row: &rusqlite::Row) -> rusqlite::Result<NodeTime> {
+    Ok(NodeTime {
+        user_id: row.get(0)?,
+        curriculum_id: row.get(1)?,
+        node_id: row.get(2)?,
+        active_seconds: row.get(3)?,
+        idle_seconds: row.get(4)?,
+        heartbeats: row.get(5)?,
+        last_heartbeat_at: row
+            .get::<_, Option<String>>(6)?
+            .map(|s| parse_time(6, s))
+            .transpose()?,
+    })
+}
+
+impl NodeTimeRepository {
+    pub fn save(conn: &Connection, time: &NodeTime) -> DbResult<()> {
+        conn.execute(
+            &format!(
+                "INSERT INTO node_time ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT(user_id, IFNULL(curriculum_id, ''), node_id) DO UPDATE SET
+                    active_seconds = excluded.active_seconds,
+                    idle_seconds = excluded.idle_seconds,
+                    heartbeats = excluded.heartbeats,
+                    last_heartbeat_at = excluded.last_heartbeat_at",
+                COLUMNS
+            ),
+            params![
+                time.user_id,
+                time.curriculum_id,
+                time.node_id,
+                time.active_seconds,
+                time.idle_seconds,
+                time.heartbeats,
+                time.last_heartbeat_at.map(|d| d.to_rfc3339()),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        node_id: &str,
+    ) -> DbResult<Option<NodeTime>> {
+        let time = conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM node_time WHERE user_id = ?1 AND curriculum_id IS ?2 AND node_id = ?3",
+                    COLUMNS
+                ),
+                params![user_id, curriculum_id, node_id],
+                node_time_from_row,
+            )
+            .optional()?;
+        Ok(time)
+    }
+
+    /// The node the user last sent a heartbeat from, in any curriculum
+    pub fn get_latest(conn: &Connection, user_id: &str) -> DbResult<Option<NodeTime>> {
+        let time = conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM node_time WHERE user_id = ?1 AND last_heartbeat_at IS NOT NULL
+                     ORDER BY last_heartbeat_at DESC LIMIT 1",
+                    COLUMNS
+                ),
+                params![user_id],
+                node_time_from_row,
+            )
+            .optional()?;
+        Ok(time)
+    }
+
+    /// Active seconds each learner took on the nodes they've completed, by
+    /// node, from every profile
+    pub fn get_completed_seconds(
+        conn: &Connection,
+        curriculum_id: Option<&str>,
+    ) -> DbResult<HashMap<String, Vec<i64>>> {
+        let mut stmt = conn.prepare(
+            "SELECT t.node_id, t.active_seconds FROM node_time t
+             JOIN node_progress p ON p.user_id = t.user_id AND p.curriculum_id IS t.curriculum_id
+                                 AND p.node_id = t.node_id
+             WHERE p.status = 'Completed' AND t.active_seconds > 0 AND (?1 IS NULL OR t.curriculum_id = ?1)
+             ORDER BY t.node_id",
+        )?;
+        let rows = stmt.query_map(params![curriculum_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?;
+
+        let mut seconds: HashMap<String, Vec<i64>> = HashMap::new();
+        for row in rows {
+            let (node_id, active) = row?;
+            seconds.entry(node_id).or_default().push(active);
+        }
+        Ok(seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::{ProgressRepository, UserRepository};
+    use crate::models::{NodeProgress, User};
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn test_completed_seconds_only_count_finished_nodes() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+        let start = Utc::now();
+
+        for (node_id, seconds, completed) in [("lecture-1", 300, true), ("lecture-2", 120, false)] {
+            let mut time = NodeTime::new("test-user".to_string(), Some("c1".to_string()), node_id.to_string());
+            time.record_heartbeat(start, false);
+            time.active_seconds = seconds;
+            NodeTimeRepository::save(conn, &time).unwrap();
+
+            let mut progress =
+                NodeProgress::new("test-user".to_string(), node_id.to_string()).with_curriculum("c1".to_string());
+            progress.start();
+            if completed {
+                progress.complete();
+            }
+            ProgressRepository::create_or_update(conn, &progress).unwrap();
+        }
+        let mut later = NodeTimeRepository::get(conn, "test-user", Some("c1"), "lecture-2").unwrap().unwrap();
+        later.record_heartbeat(start + Duration::seconds(15), true);
+        NodeTimeRepository::save(conn, &later).unwrap();
+
+        let latest = NodeTimeRepository::get_latest(conn, "test-user").unwrap().unwrap();
+        assert_eq!((latest.node_id.as_str(), latest.active_seconds), ("lecture-2", 135));
+
+        let completed = NodeTimeRepository::get_completed_seconds(conn, Some("c1")).unwrap();
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed["lecture-1"], vec![300]);
+        assert!(NodeTimeRepository::get_completed_seconds(conn, Some("c2")).unwrap().is_empty());
+    }
+}
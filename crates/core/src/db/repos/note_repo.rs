@@ -0,0 +1,156 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use crate::db::error::DbResult;
+use crate::models::Note;
+use crate::db::repos::parse_time;
+
+pub struct NoteRepository;
+
+const COLUMNS: &str = "id, user_id, node_id, body, anchor, created_at, updated_at";
+
+fn note_from_row(row: &rusqlite::Row) -> rusqlite::Result<Note> {
+    Ok(Note {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        node_id: row.get(2)?,
+        body: row.get(3)?,
+        anchor: row.get(4)?,
+        created_at: parse_time(5, row.get(5)?)?,
+        updated_at: parse_time(6, row.get(6)?)?,
+    })
+}
+
+impl NoteRepository {
+    pub fn create(conn: &Connection, note: &Note) -> DbResult<()> {
+        conn.execute(
+            &format!("INSERT INTO notes ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)", COLUMNS),
+            params![
+                note.id,
+                note.user_id,
+                note.node_id,
+                note.body,
+                note.anchor,
+                note.created_at.to_rfc3339(),
+                note.updated_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get(conn: &Connection, user_id: &str, note_id: &str) -> DbResult<Option<Note>> {
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM notes WHERE user_id = ?1 AND id = ?2", COLUMNS))?;
+        let note = stmt.query_row(params![user_id, note_id], note_from_row).optional()?;
+        Ok(note)
+    }
+
+    /// Replace a note's text. Returns false if the user has no such note.
+    pub fn update_body(conn: &Connection, user_id: &str, note_id: &str, body: &str) -> DbResult<bool> {
+        let updated = conn.execute(
+            "UPDATE notes SET body = ?1, updated_at = ?2 WHERE user_id = ?3 AND id = ?4",
+            params![body, Utc::now().to_rfc3339(), user_id, note_id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Returns false if the user has no such note
+    pub fn delete(conn: &Connection, user_id: &str, note_id: &str) -> DbResult<bool> {
+        let deleted = conn.execute(
+            "DELETE FROM notes WHERE user_id = ?1 AND id = ?2",
+            params![user_id, note_id],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// Notes on a node, newest first
+    pub fn get_for_node(conn: &Connection, user_id: &str, node_id: &str) -> DbResult<Vec<Note>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM notes WHERE user_id = ?1 AND node_id = ?2 ORDER BY created_at DESC",
+            COLUMNS
+        ))?;
+        let note_iter = stmt.query_map(params![user_id, node_id], note_from_row)?;
+
+        let mut results = Vec::new();
+        for note in note_iter {
+            results.push(note?);
+        }
+        Ok(results)
+    }
+
+    pub fn get_all_for_user(conn: &Connection, user_id: &str) -> DbResult<Vec<Note>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM notes WHERE user_id = ?1 ORDER BY created_at DESC",
+            COLUMNS
+        ))?;
+        let note_iter = stmt.query_map(params![user_id], note_from_row)?;
+
+        let mut results = Vec::new();
+        for note in note_iter {
+            results.push(note?);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::User;
+
+    fn setup_db() -> Database {
+        let db = Database::new_in_memory().unwrap();
+        UserRepository::create(db.connection(), &User::new("test-user".to_string())).unwrap();
+        UserRepository::create(db.connection(), &User::new("other-user".to_string())).unwrap();
+        db
+    }
+
+    #[test]
+    fn test_create_update_and_delete_note() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        let note = Note::new("test-user".to_string(), "week1-lecture".to_string(), "Moves transfer ownership".to_string());
+        NoteRepository::create(conn, &note).unwrap();
+
+        assert!(NoteRepository::update_body(conn, "test-user", &note.id, "Moves transfer ownership, copies don't").unwrap());
+        let stored = NoteRepository::get(conn, "test-user", &note.id).unwrap().unwrap();
+        assert_eq!(stored.body, "Moves transfer ownership, copies don't");
+        assert!(stored.updated_at >= stored.created_at);
+
+        assert!(NoteRepository::delete(conn, "test-user", &note.id).unwrap());
+        assert!(NoteRepository::get(conn, "test-user", &note.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_annotation_keeps_its_anchor() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        let note = Note::new("test-user".to_string(), "week1-lecture".to_string(), "Think of it as a lease".to_string())
+            .with_anchor("A borrow lasts until its last use".to_string());
+        NoteRepository::create(conn, &note).unwrap();
+        NoteRepository::create(conn, &Note::new("test-user".to_string(), "week1-lecture".to_string(), "Summary".to_string()))
+            .unwrap();
+
+        let notes = NoteRepository::get_for_node(conn, "test-user", "week1-lecture").unwrap();
+        let anchored = notes.iter().find(|n| n.id == note.id).unwrap();
+        assert_eq!(anchored.anchor.as_deref(), Some("A borrow lasts until its last use"));
+        assert_eq!(notes.iter().filter(|n| n.anchor.is_none()).count(), 1);
+    }
+
+    #[test]
+    fn test_notes_are_scoped_to_user() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        let note = Note::new("test-user".to_string(), "week1-lecture".to_string(), "Mine".to_string());
+        NoteRepository::create(conn, &note).unwrap();
+
+        assert!(NoteRepository::get(conn, "other-user", &note.id).unwrap().is_none());
+        assert!(!NoteRepository::update_body(conn, "other-user", &note.id, "Theirs").unwrap());
+        assert!(!NoteRepository::delete(conn, "other-user", &note.id).unwrap());
+        assert!(NoteRepository::get_for_node(conn, "other-user", "week1-lecture").unwrap().is_empty());
+        assert_eq!(NoteRepository::get_for_node(conn, "test-user", "week1-lecture").unwrap().len(), 1);
+    }
+}
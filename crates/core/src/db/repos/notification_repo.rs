@@ -0,0 +1,128 @@
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, OptionalExtension};
+use crate::db::error::{DbError, DbResult};
+use crate::reminders::prefs::{parse_time, TIME_FORMAT};
+use crate::reminders::{NotificationPrefs, QuietHours, ReminderKind};
+
+pub struct NotificationRepository;
+
+fn invalid_text(idx: usize, e: String) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(
+        idx,
+        rusqlite::types::Type::Text,
+        Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+    )
+}
+
+fn time_at(row: &rusqlite::Row, idx: usize) -> rusqlite::Result<chrono::NaiveTime> {
+    parse_time(&row.get::<_, String>(idx)?).map_err(|e| invalid_text(idx, e.to_string()))
+}
+
+fn prefs_from_row(row: &rusqlite::Row) -> rusqlite::Result<NotificationPrefs> {
+    let quiet_hours = match (row.get::<_, Option<String>>(5)?, row.get::<_, Option<String>>(6)?) {
+        (Some(_), Some(_)) => Some(QuietHours { start: time_at(row, 5)?, end: time_at(row, 6)? }),
+        _ => None,
+    };
+    Ok(NotificationPrefs {
+        enabled: row.get(0)?,
+        review_reminders: row.get(1)?,
+        review_time: time_at(row, 2)?,
+        streak_reminders: row.get(3)?,
+        streak_time: time_at(row, 4)?,
+        quiet_hours,
+        weekly_report_reminders: row.get(7)?,
+    })
+}
+
+impl NotificationRepository {
+    /// The user's reminder preferences, or the defaults if they haven't changed them
+    pub fn get_prefs(conn: &Connection, user_id: &str) -> DbResult<NotificationPrefs> {
+        let prefs = conn
+            .query_row(
+                "SELECT enabled, review_reminders, review_time, streak_reminders, streak_time, quiet_start, quiet_end,
+                        weekly_report_reminders
+                 FROM notification_prefs WHERE user_id = ?1",
+                params![user_id],
+                prefs_from_row,
+            )
+            .optional()?;
+        Ok(prefs.unwrap_or_default())
+    }
+
+    pub fn save_prefs(conn: &Connection, user_id: &str, prefs: &NotificationPrefs) -> DbResult<()> {
+        let format = |time: chrono::NaiveTime| time.format(TIME_FORMAT).to_string();
+        conn.execute(
+            "INSERT INTO notification_prefs (user_id, enabled, review_reminders, review_time, streak_reminders,
+                streak_time, quiet_start, quiet_end, weekly_report_reminders)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+             ON CONFLICT(user_id) DO UPDATE SET
+                enabled = excluded.enabled,
+                review_reminders = excluded.review_reminders,
+                review_time = excluded.review_time,
+                streak_reminders = excluded.streak_reminders,
+                streak_time = excluded.streak_time,
+                quiet_start = excluded.quiet_start,
+                quiet_end = excluded.quiet_end,
+                weekly_report_reminders = excluded.weekly_report_reminders",
+            params![
+                user_id,
+                prefs.enabled,
+                prefs.review_reminders,
+                format(prefs.review_time),
+                prefs.streak_reminders,
+                format(prefs.streak_time),
+                prefs.quiet_hours.map(|q| format(q.start)),
+                prefs.quiet_hours.map(|q| format(q.end)),
+                prefs.weekly_report_reminders,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// The day each kind of reminder was last sent for
+    pub fn get_last_sent(conn: &Connection, user_id: &str) -> DbResult<Vec<(ReminderKind, NaiveDate)>> {
+        let mut stmt = conn.prepare("SELECT kind, sent_for FROM notification_sent WHERE user_id = ?1")?;
+        let sent_iter = stmt.query_map(params![user_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut results = Vec::new();
+        for sent in sent_iter {
+            let (kind, date) = sent?;
+            let kind = kind.parse().map_err(DbError::InvalidData)?;
+            let date = date.parse().map_err(|e: chrono::ParseError| DbError::InvalidData(e.to_string()))?;
+            results.push((kind, date));
+        }
+        Ok(results)
+    }
+
+    pub fn record_sent(conn: &Connection, user_id: &str, kind: ReminderKind, for_date: NaiveDate) -> DbResult<()> {
+        conn.execute(
+            "INSERT INTO notification_sent (user_id, kind, sent_for) VALUES (?1, ?2, ?3)
+             ON CONFLICT(user_id, kind) DO UPDATE SET sent_for = excluded.sent_for",
+            params![user_id, kind.as_str(), for_date.to_string()],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::User;
+
+    #[test]
+    fn test_prefs_default_until_saved() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        assert_eq!(NotificationRepository::get_prefs(conn, "test-user").unwrap(), NotificationPrefs::default());
+
+        let prefs = NotificationPrefs { streak_reminders: false, quiet_hours: None, ..NotificationPrefs::default() };
+        NotificationRepository::save_prefs(conn, "test-user", &prefs).unwrap();
+        assert_eq!(NotificationRepository::get_prefs(conn, "test-user").unwrap(), prefs);
+    }
+}
@@ -0,0 +1,152 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use crate::db::error::DbResult;
+use crate::models::pending_work::MAX_PENDING_WORK_ATTEMPTS;
+use crate::models::{Capability, PendingWork};
+use crate::db::repos::parse_time;
+
+pub struct PendingWorkRepository;
+
+const PENDING_WORK_COLUMNS: &str = "id, user_id, kind, capability, payload, created_at, attempts, last_attempt_at,
+                                    last_error, completed_at";
+
+fn invalid_text(idx: usize, e: String) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(
+        idx,
+        rusqlite::types::Type::Text,
+        Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+    )
+}
+
+fn pending_work_from_row(row: &rusqlite::Row) -> rusqlite::Result<PendingWork> {
+    Ok(PendingWork {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        kind: row.get::<_, String>(2)?.parse().map_err(|e| invalid_text(2, e))?,
+        capability: row.get::<_, String>(3)?.parse().map_err(|e| invalid_text(3, e))?,
+        payload: row.get(4)?,
+        created_at: parse_time(5, row.get(5)?)?,
+        attempts: row.get(6)?,
+        last_attempt_at: row.get::<_, Option<String>>(7)?.map(|s| parse_time(7, s)).transpose()?,
+        last_error: row.get(8)?,
+        completed_at: row.get::<_, Option<String>>(9)?.map(|s| parse_time(9, s)).transpose()?,
+    })
+}
+
+impl PendingWorkRepository {
+    pub fn enqueue(conn: &Connection, work: &PendingWork) -> DbResult<()> {
+        conn.execute(
+            &format!(
+                "INSERT INTO pending_work ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                PENDING_WORK_COLUMNS
+            ),
+            params![
+                work.id,
+                work.user_id,
+                work.kind.as_str(),
+                work.capability.as_str(),
+                work.payload,
+                work.created_at.to_rfc3339(),
+                work.attempts,
+                work.last_attempt_at.map(|d| d.to_rfc3339()),
+                work.last_error,
+                work.completed_at.map(|d| d.to_rfc3339()),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get(conn: &Connection, id: &str) -> DbResult<Option<PendingWork>> {
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM pending_work WHERE id = ?1", PENDING_WORK_COLUMNS))?;
+        let work = stmt.query_row(params![id], pending_work_from_row).optional()?;
+        Ok(work)
+    }
+
+    /// Record the outcome of an attempt
+    pub fn update(conn: &Connection, work: &PendingWork) -> DbResult<()> {
+        conn.execute(
+            "UPDATE pending_work SET attempts = ?1, last_attempt_at = ?2, last_error = ?3, completed_at = ?4
+             WHERE id = ?5",
+            params![
+                work.attempts,
+                work.last_attempt_at.map(|d| d.to_rfc3339()),
+                work.last_error,
+                work.completed_at.map(|d| d.to_rfc3339()),
+                work.id,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Unfinished work needing `capability` that hasn't been given up on,
+    /// oldest first
+    pub fn get_runnable(conn: &Connection, capability: Capability) -> DbResult<Vec<PendingWork>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM pending_work
+             WHERE capability = ?1 AND completed_at IS NULL AND attempts < ?2
+             ORDER BY created_at",
+            PENDING_WORK_COLUMNS
+        ))?;
+        let work_iter = stmt.query_map(params![capability.as_str(), MAX_PENDING_WORK_ATTEMPTS], pending_work_from_row)?;
+
+        let mut results = Vec::new();
+        for work in work_iter {
+            results.push(work?);
+        }
+        Ok(results)
+    }
+
+    /// A user's unfinished work, including any that has been given up on
+    pub fn get_unfinished_for_user(conn: &Connection, user_id: &str) -> DbResult<Vec<PendingWork>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM pending_work WHERE user_id = ?1 AND completed_at IS NULL ORDER BY created_at",
+            PENDING_WORK_COLUMNS
+        ))?;
+        let work_iter = stmt.query_map(params![user_id], pending_work_from_row)?;
+
+        let mut results = Vec::new();
+        for work in work_iter {
+            results.push(work?);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::{PendingWorkKind, User};
+    use chrono::Utc;
+
+    #[test]
+    fn test_runnable_excludes_completed_and_exhausted_work() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let queued = PendingWork::new("test-user".to_string(), PendingWorkKind::GradeArtifact, "{}".to_string());
+        let mut done = PendingWork::new("test-user".to_string(), PendingWorkKind::GradeArtifact, "{}".to_string());
+        let mut failing = PendingWork::new("test-user".to_string(), PendingWorkKind::GradeArtifact, "{}".to_string());
+        for work in [&queued, &done, &failing] {
+            PendingWorkRepository::enqueue(conn, work).unwrap();
+        }
+
+        done.complete(Utc::now());
+        PendingWorkRepository::update(conn, &done).unwrap();
+        for _ in 0..MAX_PENDING_WORK_ATTEMPTS {
+            failing.record_failure("HTTP 500".to_string(), Utc::now());
+        }
+        PendingWorkRepository::update(conn, &failing).unwrap();
+
+        let runnable = PendingWorkRepository::get_runnable(conn, Capability::Llm).unwrap();
+        assert_eq!(runnable.iter().map(|w| w.id.as_str()).collect::<Vec<_>>(), vec![queued.id.as_str()]);
+        assert!(PendingWorkRepository::get_runnable(conn, Capability::Docker).unwrap().is_empty());
+
+        let unfinished = PendingWorkRepository::get_unfinished_for_user(conn, "test-user").unwrap();
+        assert_eq!(unfinished.len(), 2);
+        let reloaded = PendingWorkRepository::get(conn, &failing.id).unwrap().unwrap();
+        assert!(reloaded.is_exhausted());
+        assert_eq!(reloaded.last_error.as_deref(), Some("HTTP 500"));
+    }
+}
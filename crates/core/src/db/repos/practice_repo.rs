@@ -0,0 +1,133 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use crate::db::error::{DbError, DbResult};
+use crate::models::PracticeChallenge;
+use crate::db::repos::parse_time;
+
+pub struct PracticeRepository;
+
+const COLUMNS: &str = "id, user_id, curriculum_id, skill_id, difficulty, title, instructions, starter_code, \
+                       test_code, solution, hints_json, created_at";
+
+fn conversion_error(idx: usize, e: impl std::fmt::Display) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(
+        idx,
+        rusqlite::types::Type::Text,
+        Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string())),
+    )
+}
+
+fn challenge_from_row(row: &Row) -> rusqlite::Result<PracticeChallenge> {
+    let hints_json: String = row.get(10)?;
+    let created_at: String = row.get(11)?;
+
+    Ok(PracticeChallenge {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        curriculum_id: row.get(2)?,
+        skill_id: row.get(3)?,
+        difficulty: row.get(4)?,
+        title: row.get(5)?,
+        instructions: row.get(6)?,
+        starter_code: row.get(7)?,
+        test_code: row.get(8)?,
+        solution: row.get(9)?,
+        hints: serde_json::from_str(&hints_json).map_err(|e| conversion_error(10, e))?,
+        created_at: parse_time(11, created_at)?,
+    })
+}
+
+impl PracticeRepository {
+    pub fn create(conn: &Connection, challenge: &PracticeChallenge) -> DbResult<()> {
+        let hints_json = serde_json::to_string(&challenge.hints).map_err(|e| DbError::InvalidData(e.to_string()))?;
+        conn.execute(
+            &format!(
+                "INSERT INTO practice_challenges ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                COLUMNS
+            ),
+            params![
+                challenge.id,
+                challenge.user_id,
+                challenge.curriculum_id,
+                challenge.skill_id,
+                challenge.difficulty,
+                challenge.title,
+                challenge.instructions,
+                challenge.starter_code,
+                challenge.test_code,
+                challenge.solution,
+                hints_json,
+                challenge.created_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get(conn: &Connection, id: &str) -> DbResult<Option<PracticeChallenge>> {
+        let challenge = conn
+            .query_row(
+                &format!("SELECT {} FROM practice_challenges WHERE id = ?1", COLUMNS),
+                params![id],
+                challenge_from_row,
+            )
+            .optional()?;
+        Ok(challenge)
+    }
+
+    /// A user's practice challenges for a skill, newest first
+    pub fn get_for_skill(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        skill_id: &str,
+    ) -> DbResult<Vec<PracticeChallenge>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM practice_challenges
+             WHERE user_id = ?1 AND curriculum_id IS ?2 AND skill_id = ?3
+             ORDER BY created_at DESC",
+            COLUMNS
+        ))?;
+
+        let challenge_iter = stmt.query_map(params![user_id, curriculum_id, skill_id], challenge_from_row)?;
+
+        let mut results = Vec::new();
+        for challenge in challenge_iter {
+            results.push(challenge?);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::User;
+
+    #[test]
+    fn test_challenges_round_trip_by_skill() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let challenge = PracticeChallenge {
+            title: "Longest word".to_string(),
+            test_code: "#[cfg(test)] mod tests {}".to_string(),
+            solution: "pub fn longest() {}".to_string(),
+            hints: vec!["Split on whitespace".to_string()],
+            ..PracticeChallenge::new("test-user".to_string(), None, "borrowing".to_string(), "easy".to_string())
+        };
+        PracticeRepository::create(conn, &challenge).unwrap();
+
+        let stored = PracticeRepository::get(conn, &challenge.id).unwrap().unwrap();
+        assert_eq!(stored.title, "Longest word");
+        assert_eq!(stored.hints, vec!["Split on whitespace".to_string()]);
+        assert_eq!(stored.review_id(), format!("practice:{}", challenge.id));
+        assert_eq!(PracticeChallenge::id_from_review(&stored.review_id()), Some(challenge.id.as_str()));
+        assert!(PracticeChallenge::id_from_review("quiz-1").is_none());
+
+        assert_eq!(PracticeRepository::get_for_skill(conn, "test-user", None, "borrowing").unwrap().len(), 1);
+        assert!(PracticeRepository::get_for_skill(conn, "test-user", Some("other"), "borrowing").unwrap().is_empty());
+        assert!(PracticeRepository::get(conn, "missing").unwrap().is_none());
+    }
+}
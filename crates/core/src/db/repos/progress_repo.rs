@@ -1,24 +1,31 @@
-use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection, OptionalExtension};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Row};
 use crate::db::error::DbResult;
 use crate::models::{NodeProgress, NodeStatus};
+use crate::db::repos::parse_time;
+
+const PROGRESS_COLUMNS: &str =
+    "user_id, curriculum_id, node_id, status, attempts, time_spent_mins, first_started_at, completed_at, last_updated_at,
+     comprehension_check";
 
 pub struct ProgressRepository;
 
 impl ProgressRepository {
     pub fn create_or_update(conn: &Connection, progress: &NodeProgress) -> DbResult<()> {
         conn.execute(
-            "INSERT INTO node_progress (user_id, node_id, status, attempts, time_spent_mins, first_started_at, completed_at, last_updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
-             ON CONFLICT(user_id, node_id) DO UPDATE SET
+            "INSERT INTO node_progress (user_id, curriculum_id, node_id, status, attempts, time_spent_mins, first_started_at, completed_at, last_updated_at, comprehension_check)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+             ON CONFLICT(user_id, IFNULL(curriculum_id, ''), node_id) DO UPDATE SET
                 status = excluded.status,
                 attempts = excluded.attempts,
                 time_spent_mins = excluded.time_spent_mins,
                 first_started_at = COALESCE(node_progress.first_started_at, excluded.first_started_at),
                 completed_at = excluded.completed_at,
-                last_updated_at = excluded.last_updated_at",
+                last_updated_at = excluded.last_updated_at,
+                comprehension_check = excluded.comprehension_check",
             params![
                 progress.user_id,
+                progress.curriculum_id,
                 progress.node_id,
                 progress.status.as_str(),
                 progress.attempts,
@@ -26,65 +33,57 @@ impl ProgressRepository {
                 progress.first_started_at.map(|d| d.to_rfc3339()),
                 progress.completed_at.map(|d| d.to_rfc3339()),
                 progress.last_updated_at.to_rfc3339(),
+                progress.comprehension_check.map(|check| check.as_str()),
             ],
         )?;
         Ok(())
     }
 
-    pub fn get(conn: &Connection, user_id: &str, node_id: &str) -> DbResult<Option<NodeProgress>> {
-        let mut stmt = conn.prepare(
-            "SELECT user_id, node_id, status, attempts, time_spent_mins, first_started_at, completed_at, last_updated_at
-             FROM node_progress WHERE user_id = ?1 AND node_id = ?2"
-        )?;
+    pub fn get(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        node_id: &str,
+    ) -> DbResult<Option<NodeProgress>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM node_progress WHERE user_id = ?1 AND curriculum_id IS ?2 AND node_id = ?3",
+            PROGRESS_COLUMNS
+        ))?;
 
-        let progress = stmt.query_row(params![user_id, node_id], |row| {
-            Ok(NodeProgress {
-                user_id: row.get(0)?,
-                node_id: row.get(1)?,
-                status: NodeStatus::from_str(&row.get::<_, String>(2)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, e))))?,
-                attempts: row.get(3)?,
-                time_spent_mins: row.get(4)?,
-                first_started_at: row.get::<_, Option<String>>(5)?
-                    .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                    .map(|dt| dt.with_timezone(&Utc)),
-                completed_at: row.get::<_, Option<String>>(6)?
-                    .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                    .map(|dt| dt.with_timezone(&Utc)),
-                last_updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(7, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
-            })
-        }).optional()?;
+        let progress = stmt
+            .query_row(params![user_id, curriculum_id, node_id], Self::progress_from_row)
+            .optional()?;
 
         Ok(progress)
     }
 
+    /// Progress across every curriculum the user has studied
     pub fn get_all_for_user(conn: &Connection, user_id: &str) -> DbResult<Vec<NodeProgress>> {
-        let mut stmt = conn.prepare(
-            "SELECT user_id, node_id, status, attempts, time_spent_mins, first_started_at, completed_at, last_updated_at
-             FROM node_progress WHERE user_id = ?1"
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM node_progress WHERE user_id = ?1",
+            PROGRESS_COLUMNS
+        ))?;
 
-        let progress_iter = stmt.query_map(params![user_id], |row| {
-            Ok(NodeProgress {
-                user_id: row.get(0)?,
-                node_id: row.get(1)?,
-                status: NodeStatus::from_str(&row.get::<_, String>(2)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, e))))?,
-                attempts: row.get(3)?,
-                time_spent_mins: row.get(4)?,
-                first_started_at: row.get::<_, Option<String>>(5)?
-                    .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                    .map(|dt| dt.with_timezone(&Utc)),
-                completed_at: row.get::<_, Option<String>>(6)?
-                    .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                    .map(|dt| dt.with_timezone(&Utc)),
-                last_updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(7, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
-            })
-        })?;
+        let progress_iter = stmt.query_map(params![user_id], Self::progress_from_row)?;
+
+        let mut results = Vec::new();
+        for progress in progress_iter {
+            results.push(progress?);
+        }
+        Ok(results)
+    }
+
+    pub fn get_all_in_curriculum(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+    ) -> DbResult<Vec<NodeProgress>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM node_progress WHERE user_id = ?1 AND curriculum_id IS ?2",
+            PROGRESS_COLUMNS
+        ))?;
+
+        let progress_iter = stmt.query_map(params![user_id, curriculum_id], Self::progress_from_row)?;
 
         let mut results = Vec::new();
         for progress in progress_iter {
@@ -94,30 +93,12 @@ impl ProgressRepository {
     }
 
     pub fn get_by_status(conn: &Connection, user_id: &str, status: &NodeStatus) -> DbResult<Vec<NodeProgress>> {
-        let mut stmt = conn.prepare(
-            "SELECT user_id, node_id, status, attempts, time_spent_mins, first_started_at, completed_at, last_updated_at
-             FROM node_progress WHERE user_id = ?1 AND status = ?2"
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM node_progress WHERE user_id = ?1 AND status = ?2",
+            PROGRESS_COLUMNS
+        ))?;
 
-        let progress_iter = stmt.query_map(params![user_id, status.as_str()], |row| {
-            Ok(NodeProgress {
-                user_id: row.get(0)?,
-                node_id: row.get(1)?,
-                status: NodeStatus::from_str(&row.get::<_, String>(2)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, e))))?,
-                attempts: row.get(3)?,
-                time_spent_mins: row.get(4)?,
-                first_started_at: row.get::<_, Option<String>>(5)?
-                    .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                    .map(|dt| dt.with_timezone(&Utc)),
-                completed_at: row.get::<_, Option<String>>(6)?
-                    .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                    .map(|dt| dt.with_timezone(&Utc)),
-                last_updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(7, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
-            })
-        })?;
+        let progress_iter = stmt.query_map(params![user_id, status.as_str()], Self::progress_from_row)?;
 
         let mut results = Vec::new();
         for progress in progress_iter {
@@ -126,32 +107,79 @@ impl ProgressRepository {
         Ok(results)
     }
 
-    pub fn mark_completed(conn: &Connection, user_id: &str, node_id: &str) -> DbResult<()> {
+    pub fn mark_completed(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        node_id: &str,
+    ) -> DbResult<()> {
         let now = Utc::now().to_rfc3339();
         let rows = conn.execute(
             "UPDATE node_progress SET status = 'Completed', completed_at = ?1, last_updated_at = ?1
-             WHERE user_id = ?2 AND node_id = ?3",
-            params![now, user_id, node_id],
+             WHERE user_id = ?2 AND curriculum_id IS ?3 AND node_id = ?4",
+            params![now, user_id, curriculum_id, node_id],
         )?;
 
         if rows == 0 {
             // Create new progress entry if it doesn't exist
             let mut progress = NodeProgress::new(user_id.to_string(), node_id.to_string());
+            progress.curriculum_id = curriculum_id.map(str::to_string);
             progress.complete();
             Self::create_or_update(conn, &progress)?;
         }
         Ok(())
     }
 
-    pub fn increment_time(conn: &Connection, user_id: &str, node_id: &str, mins: i32) -> DbResult<()> {
+    pub fn increment_time(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        node_id: &str,
+        mins: i32,
+    ) -> DbResult<()> {
         let now = Utc::now().to_rfc3339();
         conn.execute(
             "UPDATE node_progress SET time_spent_mins = time_spent_mins + ?1, last_updated_at = ?2
-             WHERE user_id = ?3 AND node_id = ?4",
-            params![mins, now, user_id, node_id],
+             WHERE user_id = ?3 AND curriculum_id IS ?4 AND node_id = ?5",
+            params![mins, now, user_id, curriculum_id, node_id],
         )?;
         Ok(())
     }
+
+    pub fn delete(conn: &Connection, user_id: &str, curriculum_id: Option<&str>, node_id: &str) -> DbResult<()> {
+        conn.execute(
+            "DELETE FROM node_progress WHERE user_id = ?1 AND curriculum_id IS ?2 AND node_id = ?3",
+            params![user_id, curriculum_id, node_id],
+        )?;
+        Ok(())
+    }
+
+    fn progress_from_row(row: &Row) -> rusqlite::Result<NodeProgress> {
+        Ok(NodeProgress {
+            user_id: row.get(0)?,
+            curriculum_id: row.get(1)?,
+            node_id: row.get(2)?,
+            status: NodeStatus::from_str(&row.get::<_, String>(3)?)
+                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, e))))?,
+            attempts: row.get(4)?,
+            time_spent_mins: row.get(5)?,
+            first_started_at: row.get::<_, Option<String>>(6)?.map(|s| parse_time(6, s)).transpose()?,
+            completed_at: row.get::<_, Option<String>>(7)?.map(|s| parse_time(7, s)).transpose()?,
+            last_updated_at: parse_time(8, row.get(8)?)?,
+            comprehension_check: row
+                .get::<_, Option<String>>(9)?
+                .map(|s| {
+                    s.parse().map_err(|e| {
+                        rusqlite::Error::FromSqlConversionFailure(
+                            9,
+                            rusqlite::types::Type::Text,
+                            Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+                        )
+                    })
+                })
+                .transpose()?,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -178,7 +206,7 @@ mod tests {
         progress.start();
         ProgressRepository::create_or_update(conn, &progress).unwrap();
 
-        let retrieved = ProgressRepository::get(conn, "test-user", "node1").unwrap();
+        let retrieved = ProgressRepository::get(conn, "test-user", None, "node1").unwrap();
         assert!(retrieved.is_some());
         let retrieved = retrieved.unwrap();
         assert_eq!(retrieved.status, NodeStatus::InProgress);
@@ -206,9 +234,9 @@ mod tests {
         let progress = NodeProgress::new("test-user".to_string(), "node1".to_string());
         ProgressRepository::create_or_update(conn, &progress).unwrap();
 
-        ProgressRepository::mark_completed(conn, "test-user", "node1").unwrap();
+        ProgressRepository::mark_completed(conn, "test-user", None, "node1").unwrap();
 
-        let updated = ProgressRepository::get(conn, "test-user", "node1").unwrap().unwrap();
+        let updated = ProgressRepository::get(conn, "test-user", None, "node1").unwrap().unwrap();
         assert_eq!(updated.status, NodeStatus::Completed);
         assert!(updated.completed_at.is_some());
     }
@@ -228,4 +256,29 @@ mod tests {
         assert_eq!(completed.len(), 1);
         assert_eq!(completed[0].node_id, "node1");
     }
+
+    #[test]
+    fn test_progress_is_separate_per_curriculum() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        let rust = NodeProgress::new("test-user".to_string(), "week1-day1".to_string())
+            .with_curriculum("rust".to_string());
+        let go = NodeProgress::new("test-user".to_string(), "week1-day1".to_string())
+            .with_curriculum("go".to_string());
+        ProgressRepository::create_or_update(conn, &rust).unwrap();
+        ProgressRepository::create_or_update(conn, &go).unwrap();
+
+        ProgressRepository::mark_completed(conn, "test-user", Some("rust"), "week1-day1").unwrap();
+
+        let rust = ProgressRepository::get(conn, "test-user", Some("rust"), "week1-day1").unwrap().unwrap();
+        let go = ProgressRepository::get(conn, "test-user", Some("go"), "week1-day1").unwrap().unwrap();
+        assert_eq!(rust.status, NodeStatus::Completed);
+        assert_eq!(go.status, NodeStatus::NotStarted);
+        assert!(ProgressRepository::get(conn, "test-user", None, "week1-day1").unwrap().is_none());
+
+        let in_go = ProgressRepository::get_all_in_curriculum(conn, "test-user", Some("go")).unwrap();
+        assert_eq!(in_go.len(), 1);
+        assert_eq!(ProgressRepository::get_all_for_user(conn, "test-user").unwrap().len(), 2);
+    }
 }
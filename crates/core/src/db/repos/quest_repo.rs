@@ -0,0 +1,165 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use crate::db::error::DbResult;
+use crate::models::{Quest, QuestKind, QuestPeriod};
+use crate::db::repos::parse_time;
+
+pub struct QuestRepository;
+
+impl QuestRepository {
+    /// Insert a generated quest, keeping existing progress if it was already stored
+    pub fn create_if_missing(conn: &Connection, quest: &Quest) -> DbResult<()> {
+        conn.execute(
+            "INSERT OR IGNORE INTO quest_progress
+                (id, user_id, period, kind, description, target, progress, xp_reward, starts_at, ends_at, completed_at, claimed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                quest.id,
+                quest.user_id,
+                quest.period.as_str(),
+                quest.kind.as_str(),
+                quest.description,
+                quest.target,
+                quest.progress,
+                quest.xp_reward,
+                quest.starts_at.to_rfc3339(),
+                quest.ends_at.to_rfc3339(),
+                quest.completed_at.map(|d| d.to_rfc3339()),
+                quest.claimed_at.map(|d| d.to_rfc3339()),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Persist progress, completion and claim state
+    pub fn update(conn: &Connection, quest: &Quest) -> DbResult<()> {
+        conn.execute(
+            "UPDATE quest_progress SET progress = ?1, completed_at = ?2, claimed_at = ?3
+             WHERE user_id = ?4 AND id = ?5",
+            params![
+                quest.progress,
+                quest.completed_at.map(|d| d.to_rfc3339()),
+                quest.claimed_at.map(|d| d.to_rfc3339()),
+                quest.user_id,
+                quest.id,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get(conn: &Connection, user_id: &str, quest_id: &str) -> DbResult<Option<Quest>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, user_id, period, kind, description, target, progress, xp_reward, starts_at, ends_at, completed_at, claimed_at
+             FROM quest_progress WHERE user_id = ?1 AND id = ?2"
+        )?;
+
+        let quest = stmt.query_row(params![user_id, quest_id], quest_from_row).optional()?;
+
+        Ok(quest)
+    }
+
+    /// Get quests whose period contains `now`
+    pub fn get_active(conn: &Connection, user_id: &str, now: DateTime<Utc>) -> DbResult<Vec<Quest>> {
+        let now = now.to_rfc3339();
+        let mut stmt = conn.prepare(
+            "SELECT id, user_id, period, kind, description, target, progress, xp_reward, starts_at, ends_at, completed_at, claimed_at
+             FROM quest_progress WHERE user_id = ?1 AND starts_at <= ?2 AND ends_at > ?2
+             ORDER BY period ASC, id ASC"
+        )?;
+
+        let quest_iter = stmt.query_map(params![user_id, now], quest_from_row)?;
+
+        let mut results = Vec::new();
+        for quest in quest_iter {
+            results.push(quest?);
+        }
+        Ok(results)
+    }
+
+    /// Total XP paid out by claimed quests
+    pub fn get_claimed_xp_total(conn: &Connection, user_id: &str) -> DbResult<i32> {
+        let total: i32 = conn.query_row(
+            "SELECT COALESCE(SUM(xp_reward), 0) FROM quest_progress
+             WHERE user_id = ?1 AND claimed_at IS NOT NULL",
+            params![user_id],
+            |row| row.get(0),
+        )?;
+        Ok(total)
+    }
+}
+
+fn quest_from_row(row: &rusqlite::Row) -> rusqlite::Result<Quest> {
+    Ok(Quest {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        period: row.get::<_, String>(2)?
+            .parse::<QuestPeriod>()
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, e.into()))?,
+        kind: row.get::<_, String>(3)?
+            .parse::<QuestKind>()
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, e.into()))?,
+        description: row.get(4)?,
+        target: row.get(5)?,
+        progress: row.get(6)?,
+        xp_reward: row.get(7)?,
+        starts_at: parse_time(8, row.get(8)?)?,
+        ends_at: parse_time(9, row.get(9)?)?,
+        completed_at: row.get::<_, Option<String>>(10)?.map(|s| parse_time(10, s)).transpose()?,
+        claimed_at: row.get::<_, Option<String>>(11)?.map(|s| parse_time(11, s)).transpose()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::User;
+    use crate::quests::generate_quests;
+    use chrono::Duration;
+
+    fn setup_db() -> Database {
+        let db = Database::new_in_memory().unwrap();
+        let user = User::new("test-user".to_string());
+        UserRepository::create(db.connection(), &user).unwrap();
+        db
+    }
+
+    #[test]
+    fn test_create_if_missing_keeps_progress() {
+        let db = setup_db();
+        let conn = db.connection();
+        let now = Utc::now();
+
+        let quests = generate_quests("test-user", QuestPeriod::Daily, now);
+        for quest in &quests {
+            QuestRepository::create_if_missing(conn, quest).unwrap();
+        }
+
+        let mut first = quests[0].clone();
+        first.record_progress(1, now);
+        QuestRepository::update(conn, &first).unwrap();
+
+        // Regenerating the same quests must not reset progress
+        for quest in &generate_quests("test-user", QuestPeriod::Daily, now) {
+            QuestRepository::create_if_missing(conn, quest).unwrap();
+        }
+
+        let stored = QuestRepository::get(conn, "test-user", &first.id).unwrap().unwrap();
+        assert_eq!(stored.progress, 1);
+        assert_eq!(QuestRepository::get_active(conn, "test-user", now).unwrap().len(), quests.len());
+    }
+
+    #[test]
+    fn test_get_active_excludes_expired() {
+        let db = setup_db();
+        let conn = db.connection();
+        let yesterday = Utc::now() - Duration::days(1);
+
+        for quest in &generate_quests("test-user", QuestPeriod::Daily, yesterday) {
+            QuestRepository::create_if_missing(conn, quest).unwrap();
+        }
+
+        assert!(QuestRepository::get_active(conn, "test-user", Utc::now()).unwrap().is_empty());
+    }
+}
@@ -1,27 +1,32 @@
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection, OptionalExtension};
-use crate::db::error::DbResult;
-use crate::models::QuizAttempt;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use crate::db::error::{DbError, DbResult};
+use crate::models::{MistakeExplanation, QuestionAnswer, QuestionStats, QuizAttempt, SkillAccuracy};
+use crate::db::repos::parse_time;
 
 pub struct QuizRepository;
 
 impl QuizRepository {
     pub fn create(conn: &Connection, attempt: &QuizAttempt) -> DbResult<()> {
         let answers_json = serde_json::to_string(&attempt.answers)
-            .map_err(|e| crate::db::error::DbError::InvalidData(e.to_string()))?;
+            .map_err(|e| DbError::InvalidData(e.to_string()))?;
 
         conn.execute(
-            "INSERT INTO quiz_attempts (id, user_id, quiz_id, node_id, answers_json, score_percentage, xp_earned, submitted_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT INTO quiz_attempts (id, user_id, curriculum_id, quiz_id, node_id, answers_json, score_percentage, xp_earned, submitted_at,
+                                        started_at, late_seconds)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
             params![
                 attempt.id,
                 attempt.user_id,
+                attempt.curriculum_id,
                 attempt.quiz_id,
                 attempt.node_id,
                 answers_json,
                 attempt.score_percentage,
                 attempt.xp_earned,
                 attempt.submitted_at.to_rfc3339(),
+                attempt.started_at.map(|at| at.to_rfc3339()),
+                attempt.late_seconds,
             ],
         )?;
         Ok(())
@@ -29,56 +34,29 @@ impl QuizRepository {
 
     pub fn get_by_id(conn: &Connection, attempt_id: &str) -> DbResult<Option<QuizAttempt>> {
         let mut stmt = conn.prepare(
-            "SELECT id, user_id, quiz_id, node_id, answers_json, score_percentage, xp_earned, submitted_at
+            "SELECT id, user_id, curriculum_id, quiz_id, node_id, answers_json, score_percentage, xp_earned, submitted_at,
+                    started_at, late_seconds
              FROM quiz_attempts WHERE id = ?1"
         )?;
 
-        let attempt = stmt.query_row(params![attempt_id], |row| {
-            let answers_json: String = row.get(4)?;
-            let answers: Vec<String> = serde_json::from_str(&answers_json)
-                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e)))?;
-
-            Ok(QuizAttempt {
-                id: row.get(0)?,
-                user_id: row.get(1)?,
-                quiz_id: row.get(2)?,
-                node_id: row.get(3)?,
-                answers,
-                score_percentage: row.get(5)?,
-                xp_earned: row.get(6)?,
-                submitted_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(7, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
-            })
-        }).optional()?;
+        let attempt = stmt.query_row(params![attempt_id], Self::attempt_from_row).optional()?;
 
         Ok(attempt)
     }
 
-    pub fn get_for_quiz(conn: &Connection, user_id: &str, quiz_id: &str) -> DbResult<Vec<QuizAttempt>> {
+    pub fn get_for_quiz(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        quiz_id: &str,
+    ) -> DbResult<Vec<QuizAttempt>> {
         let mut stmt = conn.prepare(
-            "SELECT id, user_id, quiz_id, node_id, answers_json, score_percentage, xp_earned, submitted_at
-             FROM quiz_attempts WHERE user_id = ?1 AND quiz_id = ?2 ORDER BY submitted_at DESC"
-        )?;
-
-        let attempt_iter = stmt.query_map(params![user_id, quiz_id], |row| {
-            let answers_json: String = row.get(4)?;
-            let answers: Vec<String> = serde_json::from_str(&answers_json)
-                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e)))?;
-
-            Ok(QuizAttempt {
-                id: row.get(0)?,
-                user_id: row.get(1)?,
-                quiz_id: row.get(2)?,
-                node_id: row.get(3)?,
-                answers,
-                score_percentage: row.get(5)?,
-                xp_earned: row.get(6)?,
-                submitted_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(7, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
-            })
-        })?;
+            "SELECT id, user_id, curriculum_id, quiz_id, node_id, answers_json, score_percentage, xp_earned, submitted_at,
+                    started_at, late_seconds
+             FROM quiz_attempts WHERE user_id = ?1 AND curriculum_id IS ?2 AND quiz_id = ?3 ORDER BY submitted_at DESC"
+        )?;
+
+        let attempt_iter = stmt.query_map(params![user_id, curriculum_id, quiz_id], Self::attempt_from_row)?;
 
         let mut results = Vec::new();
         for attempt in attempt_iter {
@@ -87,30 +65,35 @@ impl QuizRepository {
         Ok(results)
     }
 
+    /// Attempts across every curriculum the user has studied
     pub fn get_all_for_user(conn: &Connection, user_id: &str) -> DbResult<Vec<QuizAttempt>> {
         let mut stmt = conn.prepare(
-            "SELECT id, user_id, quiz_id, node_id, answers_json, score_percentage, xp_earned, submitted_at
+            "SELECT id, user_id, curriculum_id, quiz_id, node_id, answers_json, score_percentage, xp_earned, submitted_at,
+                    started_at, late_seconds
              FROM quiz_attempts WHERE user_id = ?1 ORDER BY submitted_at DESC"
         )?;
 
-        let attempt_iter = stmt.query_map(params![user_id], |row| {
-            let answers_json: String = row.get(4)?;
-            let answers: Vec<String> = serde_json::from_str(&answers_json)
-                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e)))?;
-
-            Ok(QuizAttempt {
-                id: row.get(0)?,
-                user_id: row.get(1)?,
-                quiz_id: row.get(2)?,
-                node_id: row.get(3)?,
-                answers,
-                score_percentage: row.get(5)?,
-                xp_earned: row.get(6)?,
-                submitted_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(7, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
-            })
-        })?;
+        let attempt_iter = stmt.query_map(params![user_id], Self::attempt_from_row)?;
+
+        let mut results = Vec::new();
+        for attempt in attempt_iter {
+            results.push(attempt?);
+        }
+        Ok(results)
+    }
+
+    pub fn get_all_in_curriculum(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+    ) -> DbResult<Vec<QuizAttempt>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, user_id, curriculum_id, quiz_id, node_id, answers_json, score_percentage, xp_earned, submitted_at,
+                    started_at, late_seconds
+             FROM quiz_attempts WHERE user_id = ?1 AND curriculum_id IS ?2 ORDER BY submitted_at DESC"
+        )?;
+
+        let attempt_iter = stmt.query_map(params![user_id, curriculum_id], Self::attempt_from_row)?;
 
         let mut results = Vec::new();
         for attempt in attempt_iter {
@@ -121,28 +104,12 @@ impl QuizRepository {
 
     pub fn get_recent(conn: &Connection, user_id: &str, limit: i32) -> DbResult<Vec<QuizAttempt>> {
         let mut stmt = conn.prepare(
-            "SELECT id, user_id, quiz_id, node_id, answers_json, score_percentage, xp_earned, submitted_at
+            "SELECT id, user_id, curriculum_id, quiz_id, node_id, answers_json, score_percentage, xp_earned, submitted_at,
+                    started_at, late_seconds
              FROM quiz_attempts WHERE user_id = ?1 ORDER BY submitted_at DESC LIMIT ?2"
         )?;
 
-        let attempt_iter = stmt.query_map(params![user_id, limit], |row| {
-            let answers_json: String = row.get(4)?;
-            let answers: Vec<String> = serde_json::from_str(&answers_json)
-                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e)))?;
-
-            Ok(QuizAttempt {
-                id: row.get(0)?,
-                user_id: row.get(1)?,
-                quiz_id: row.get(2)?,
-                node_id: row.get(3)?,
-                answers,
-                score_percentage: row.get(5)?,
-                xp_earned: row.get(6)?,
-                submitted_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(7, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
-            })
-        })?;
+        let attempt_iter = stmt.query_map(params![user_id, limit], Self::attempt_from_row)?;
 
         let mut results = Vec::new();
         for attempt in attempt_iter {
@@ -150,14 +117,291 @@ impl QuizRepository {
         }
         Ok(results)
     }
+
+    /// Timed attempts, from every user and curriculum, for estimating how
+    /// long quizzes take
+    pub fn get_timed(conn: &Connection) -> DbResult<Vec<QuizAttempt>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, user_id, curriculum_id, quiz_id, node_id, answers_json, score_percentage, xp_earned, submitted_at,
+                    started_at, late_seconds
+             FROM quiz_attempts WHERE started_at IS NOT NULL ORDER BY submitted_at DESC"
+        )?;
+
+        let attempt_iter = stmt.query_map([], Self::attempt_from_row)?;
+
+        let mut results = Vec::new();
+        for attempt in attempt_iter {
+            results.push(attempt?);
+        }
+        Ok(results)
+    }
+
+    /// An explanation already generated for choosing `chosen_option` on a
+    /// quiz question, by any user
+    pub fn get_cached_explanation(
+        conn: &Connection,
+        quiz_id: &str,
+        question_id: &str,
+        chosen_option: &str,
+    ) -> DbResult<Option<String>> {
+        let explanation = conn
+            .query_row(
+                "SELECT explanation FROM mistake_explanation_cache
+                 WHERE quiz_id = ?1 AND question_id = ?2 AND chosen_option = ?3",
+                params![quiz_id, question_id, chosen_option],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(explanation)
+    }
+
+    pub fn cache_explanation(
+        conn: &Connection,
+        quiz_id: &str,
+        question_id: &str,
+        chosen_option: &str,
+        explanation: &str,
+    ) -> DbResult<()> {
+        conn.execute(
+            "INSERT OR REPLACE INTO mistake_explanation_cache (quiz_id, question_id, chosen_option, explanation, cached_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![quiz_id, question_id, chosen_option, explanation, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    pub fn save_explanation(conn: &Connection, explanation: &MistakeExplanation) -> DbResult<()> {
+        conn.execute(
+            "INSERT OR REPLACE INTO quiz_mistake_explanations
+             (attempt_id, question_id, chosen_option, explanation, from_cache, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                explanation.attempt_id,
+                explanation.question_id,
+                explanation.chosen_option,
+                explanation.explanation,
+                explanation.from_cache,
+                explanation.created_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Explanations stored with an attempt, in the order they were asked for
+    pub fn get_explanations(conn: &Connection, attempt_id: &str) -> DbResult<Vec<MistakeExplanation>> {
+        let mut stmt = conn.prepare(
+            "SELECT attempt_id, question_id, chosen_option, explanation, from_cache, created_at
+             FROM quiz_mistake_explanations WHERE attempt_id = ?1 ORDER BY created_at"
+        )?;
+
+        let explanation_iter = stmt.query_map(params![attempt_id], |row| {
+            Ok(MistakeExplanation {
+                attempt_id: row.get(0)?,
+                question_id: row.get(1)?,
+                chosen_option: row.get(2)?,
+                explanation: row.get(3)?,
+                from_cache: row.get(4)?,
+                created_at: parse_time(5, row.get(5)?)?,
+            })
+        })?;
+
+        let mut results = Vec::new();
+        for explanation in explanation_iter {
+            results.push(explanation?);
+        }
+        Ok(results)
+    }
+
+    /// Store each question's answer for an attempt
+    pub fn save_answers(conn: &Connection, answers: &[QuestionAnswer]) -> DbResult<()> {
+        let mut stmt = conn.prepare(
+            "INSERT OR REPLACE INTO quiz_answers (attempt_id, question_id, position, answer, is_correct, skills_json)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+        )?;
+        for answer in answers {
+            let skills_json = serde_json::to_string(&answer.skills)
+                .map_err(|e| DbError::InvalidData(e.to_string()))?;
+            stmt.execute(params![
+                answer.attempt_id,
+                answer.question_id,
+                answer.position,
+                answer.answer,
+                answer.is_correct,
+                skills_json,
+            ])?;
+        }
+        Ok(())
+    }
+
+    /// An attempt's answers in question order. Empty for attempts made
+    /// before answers were kept per question.
+    pub fn get_answers(conn: &Connection, attempt_id: &str) -> DbResult<Vec<QuestionAnswer>> {
+        let mut stmt = conn.prepare(
+            "SELECT attempt_id, question_id, position, answer, is_correct, skills_json
+             FROM quiz_answers WHERE attempt_id = ?1 ORDER BY position"
+        )?;
+
+        let answer_iter = stmt.query_map(params![attempt_id], |row| {
+            let skills_json: String = row.get(5)?;
+            Ok(QuestionAnswer {
+                attempt_id: row.get(0)?,
+                question_id: row.get(1)?,
+                position: row.get(2)?,
+                answer: row.get(3)?,
+                is_correct: row.get(4)?,
+                skills: serde_json::from_str(&skills_json)
+                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(5, rusqlite::types::Type::Text, Box::new(e)))?,
+            })
+        })?;
+
+        let mut results = Vec::new();
+        for answer in answer_iter {
+            results.push(answer?);
+        }
+        Ok(results)
+    }
+
+    /// The questions the user misses most, worst first
+    pub fn get_most_missed(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        limit: i32,
+    ) -> DbResult<Vec<QuestionStats>> {
+        let mut stmt = conn.prepare(
+            "SELECT a.quiz_id, qa.question_id, COUNT(*), SUM(1 - qa.is_correct) AS missed
+             FROM quiz_answers qa JOIN quiz_attempts a ON a.id = qa.attempt_id
+             WHERE a.user_id = ?1 AND a.curriculum_id IS ?2
+             GROUP BY a.quiz_id, qa.question_id
+             HAVING missed > 0
+             ORDER BY missed DESC, CAST(missed AS REAL) / COUNT(*) DESC, a.quiz_id, qa.question_id
+             LIMIT ?3"
+        )?;
+
+        let stats_iter = stmt.query_map(params![user_id, curriculum_id, limit], |row| {
+            Ok(QuestionStats {
+                quiz_id: row.get(0)?,
+                question_id: row.get(1)?,
+                answered: row.get(2)?,
+                missed: row.get(3)?,
+            })
+        })?;
+
+        let mut results = Vec::new();
+        for stats in stats_iter {
+            results.push(stats?);
+        }
+        Ok(results)
+    }
+
+    /// Per-skill accuracy over every answered question tagged with the skill,
+    /// weakest first
+    pub fn get_skill_accuracy(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+    ) -> DbResult<Vec<SkillAccuracy>> {
+        let mut stmt = conn.prepare(
+            "SELECT skill.value, COUNT(*), SUM(qa.is_correct)
+             FROM quiz_answers qa
+             JOIN quiz_attempts a ON a.id = qa.attempt_id
+             JOIN json_each(qa.skills_json) skill
+             WHERE a.user_id = ?1 AND a.curriculum_id IS ?2
+             GROUP BY skill.value
+             ORDER BY CAST(SUM(qa.is_correct) AS REAL) / COUNT(*), skill.value"
+        )?;
+
+        let accuracy_iter = stmt.query_map(params![user_id, curriculum_id], |row| {
+            Ok(SkillAccuracy {
+                skill_id: row.get(0)?,
+                answered: row.get(1)?,
+                correct: row.get(2)?,
+            })
+        })?;
+
+        let mut results = Vec::new();
+        for accuracy in accuracy_iter {
+            results.push(accuracy?);
+        }
+        Ok(results)
+    }
+
+    /// Start the clock on a timed quiz. A start that's already recorded is
+    /// kept, so the clock can't be restarted; returns when the quiz started.
+    pub fn record_start(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        quiz_id: &str,
+        started_at: DateTime<Utc>,
+    ) -> DbResult<DateTime<Utc>> {
+        conn.execute(
+            "INSERT OR IGNORE INTO quiz_starts (user_id, curriculum_id, quiz_id, started_at) VALUES (?1, ?2, ?3, ?4)",
+            params![user_id, curriculum_id, quiz_id, started_at.to_rfc3339()],
+        )?;
+        Self::get_start(conn, user_id, curriculum_id, quiz_id)?
+            .ok_or_else(|| DbError::InvalidData(format!("Start of quiz {} not recorded", quiz_id)))
+    }
+
+    /// When a timed quiz was started, if its clock is running
+    pub fn get_start(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        quiz_id: &str,
+    ) -> DbResult<Option<DateTime<Utc>>> {
+        Ok(conn
+            .query_row(
+                "SELECT started_at FROM quiz_starts WHERE user_id = ?1 AND curriculum_id IS ?2 AND quiz_id = ?3",
+                params![user_id, curriculum_id, quiz_id],
+                |row| parse_time(0, row.get(0)?),
+            )
+            .optional()?)
+    }
+
+    /// Remove and return when a timed quiz was started, so each start
+    /// covers exactly one submission
+    pub fn take_start(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        quiz_id: &str,
+    ) -> DbResult<Option<DateTime<Utc>>> {
+        let started_at = Self::get_start(conn, user_id, curriculum_id, quiz_id)?;
+        conn.execute(
+            "DELETE FROM quiz_starts WHERE user_id = ?1 AND curriculum_id IS ?2 AND quiz_id = ?3",
+            params![user_id, curriculum_id, quiz_id],
+        )?;
+        Ok(started_at)
+    }
+
+    fn attempt_from_row(row: &Row) -> rusqlite::Result<QuizAttempt> {
+        let answers_json: String = row.get(5)?;
+        let answers: Vec<String> = serde_json::from_str(&answers_json)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(5, rusqlite::types::Type::Text, Box::new(e)))?;
+
+        Ok(QuizAttempt {
+            id: row.get(0)?,
+            user_id: row.get(1)?,
+            curriculum_id: row.get(2)?,
+            quiz_id: row.get(3)?,
+            node_id: row.get(4)?,
+            answers,
+            score_percentage: row.get(6)?,
+            xp_earned: row.get(7)?,
+            submitted_at: parse_time(8, row.get(8)?)?,
+            started_at: row.get::<_, Option<String>>(9)?.map(|s| parse_time(9, s)).transpose()?,
+            late_seconds: row.get(10)?,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::db::connection::Database;
-    use crate::db::repos::UserRepository;
-    use crate::models::User;
+    use crate::db::repos::{CurriculumRepository, UserRepository};
+    use crate::models::{Curriculum, User};
 
     fn setup_db() -> Database {
         let db = Database::new_in_memory().unwrap();
@@ -214,8 +458,26 @@ mod tests {
         QuizRepository::create(conn, &attempt1).unwrap();
         QuizRepository::create(conn, &attempt2).unwrap();
 
-        let attempts = QuizRepository::get_for_quiz(conn, "test-user", "quiz1").unwrap();
+        let attempts = QuizRepository::get_for_quiz(conn, "test-user", None, "quiz1").unwrap();
         assert_eq!(attempts.len(), 2);
+
+        let go = Curriculum::new("Go".to_string(), "1.0".to_string(), "go".to_string());
+        CurriculumRepository::create(conn, &go).unwrap();
+        let other = QuizAttempt::new(
+            "test-user".to_string(),
+            "quiz1".to_string(),
+            "node1".to_string(),
+            vec!["c".to_string()],
+            100,
+            60,
+        )
+        .with_curriculum(go.id.clone());
+        QuizRepository::create(conn, &other).unwrap();
+
+        assert_eq!(QuizRepository::get_for_quiz(conn, "test-user", None, "quiz1").unwrap().len(), 2);
+        let in_go = QuizRepository::get_for_quiz(conn, "test-user", Some(&go.id), "quiz1").unwrap();
+        assert_eq!(in_go.len(), 1);
+        assert_eq!(in_go[0].curriculum_id, Some(go.id));
     }
 
     #[test]
@@ -238,4 +500,138 @@ mod tests {
         let recent = QuizRepository::get_recent(conn, "test-user", 3).unwrap();
         assert_eq!(recent.len(), 3);
     }
+
+    #[test]
+    fn test_mistake_explanations_are_cached_and_kept_with_attempt() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        let attempt = QuizAttempt::new(
+            "test-user".to_string(),
+            "quiz1".to_string(),
+            "node1".to_string(),
+            vec!["b".to_string()],
+            0,
+            10,
+        );
+        QuizRepository::create(conn, &attempt).unwrap();
+
+        assert!(QuizRepository::get_cached_explanation(conn, "quiz1", "q1", "b").unwrap().is_none());
+        QuizRepository::cache_explanation(conn, "quiz1", "q1", "b", "b copies; a moves").unwrap();
+        let cached = QuizRepository::get_cached_explanation(conn, "quiz1", "q1", "b").unwrap();
+        assert_eq!(cached.as_deref(), Some("b copies; a moves"));
+        assert!(QuizRepository::get_cached_explanation(conn, "quiz1", "q1", "c").unwrap().is_none());
+
+        let explanation = MistakeExplanation::new(
+            attempt.id.clone(),
+            "q1".to_string(),
+            "b".to_string(),
+            cached.unwrap(),
+        )
+        .cached();
+        QuizRepository::save_explanation(conn, &explanation).unwrap();
+
+        let stored = QuizRepository::get_explanations(conn, &attempt.id).unwrap();
+        assert_eq!(stored.len(), 1);
+        assert!(stored[0].from_cache);
+        assert_eq!(stored[0].explanation, "b copies; a moves");
+    }
+
+    #[test]
+    fn test_per_question_answers_and_analytics() {
+        use crate::models::quiz::{Question, Quiz};
+
+        let db = setup_db();
+        let conn = db.connection();
+
+        let question = |id: &str, skills: &[&str]| Question {
+            id: id.to_string(),
+            question_type: "multiple_choice".to_string(),
+            prompt: format!("Prompt {}", id),
+            code_snippet: None,
+            options: vec![],
+            correct_answer: "a".to_string(),
+            explanation: String::new(),
+            points: 10,
+            skills: skills.iter().map(|s| s.to_string()).collect(),
+        };
+        let quiz = Quiz {
+            id: "quiz1".to_string(),
+            title: "Ownership".to_string(),
+            description: String::new(),
+            difficulty: "Easy".to_string(),
+            skills: vec![],
+            passing_score: 70,
+            time_limit_seconds: None,
+            late_policy: Default::default(),
+            questions: vec![question("q1", &["ownership"]), question("q2", &["ownership", "borrowing"])],
+        };
+
+        for answers in [vec!["a", "b"], vec!["a", ""], vec!["b", "a"]] {
+            let attempt = QuizAttempt::new(
+                "test-user".to_string(),
+                "quiz1".to_string(),
+                "node1".to_string(),
+                answers.iter().map(|a| a.to_string()).collect(),
+                50,
+                10,
+            );
+            QuizRepository::create(conn, &attempt).unwrap();
+            QuizRepository::save_answers(conn, &attempt.question_answers(&quiz)).unwrap();
+        }
+
+        let attempt = QuizRepository::get_recent(conn, "test-user", 1).unwrap().remove(0);
+        let answers = QuizRepository::get_answers(conn, &attempt.id).unwrap();
+        assert_eq!(answers.len(), 2);
+        assert_eq!(answers[1].skills, vec!["ownership".to_string(), "borrowing".to_string()]);
+
+        let missed = QuizRepository::get_most_missed(conn, "test-user", None, 10).unwrap();
+        assert_eq!(missed.len(), 2);
+        assert_eq!(missed[0].question_id, "q2");
+        assert_eq!((missed[0].answered, missed[0].missed), (3, 2));
+        assert_eq!(missed[1].accuracy(), 2.0 / 3.0);
+
+        let skills = QuizRepository::get_skill_accuracy(conn, "test-user", None).unwrap();
+        assert_eq!(skills[0], SkillAccuracy { skill_id: "borrowing".to_string(), answered: 3, correct: 1 });
+        assert_eq!(skills[1], SkillAccuracy { skill_id: "ownership".to_string(), answered: 6, correct: 3 });
+    }
+
+    #[test]
+    fn test_timed_attempt_start_is_taken_once() {
+        let db = setup_db();
+        let conn = db.connection();
+        let started = DateTime::parse_from_rfc3339("2024-05-06T10:00:00Z").unwrap().with_timezone(&Utc);
+
+        assert!(QuizRepository::take_start(conn, "test-user", None, "quiz1").unwrap().is_none());
+
+        // Starting again keeps the clock running from the first start
+        let first = QuizRepository::record_start(conn, "test-user", None, "quiz1", started).unwrap();
+        let again =
+            QuizRepository::record_start(conn, "test-user", None, "quiz1", started + chrono::Duration::minutes(5)).unwrap();
+        assert_eq!((first, again), (started, started));
+        assert_eq!(QuizRepository::take_start(conn, "test-user", None, "quiz1").unwrap(), Some(started));
+        assert!(QuizRepository::take_start(conn, "test-user", None, "quiz1").unwrap().is_none());
+
+        let attempt = QuizAttempt::new(
+            "test-user".to_string(),
+            "quiz1".to_string(),
+            "node1".to_string(),
+            vec!["a".to_string()],
+            60,
+            20,
+        )
+        .with_timing(started, 42);
+        QuizRepository::create(conn, &attempt).unwrap();
+
+        let stored = QuizRepository::get_by_id(conn, &attempt.id).unwrap().unwrap();
+        assert_eq!(stored.started_at, Some(started));
+        assert_eq!(stored.late_seconds, 42);
+        assert!(stored.is_late());
+
+        let untimed = QuizAttempt::new("test-user".to_string(), "quiz1".to_string(), "node1".to_string(), vec![], 80, 20);
+        QuizRepository::create(conn, &untimed).unwrap();
+        let timed = QuizRepository::get_timed(conn).unwrap();
+        assert_eq!(timed.len(), 1);
+        assert_eq!(timed[0].id, attempt.id);
+    }
 }
@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use crate::db::error::{DbError, DbResult};
+use crate::models::RepoSubmission;
+use crate::db::repos::parse_time;
+
+pub struct RepoSubmissionRepository;
+
+const COLUMNS: &str = "id, user_id, curriculum_id, checkpoint_id, repo_url, commit_hash, tests_passed, tests_total,
+    verification_json, artifact_submission_ids, passed, error, submitted_at";
+
+fn submission_from_row(row: &rusqlite::Row) -> rusqlite::Result<RepoSubmission> {
+    Ok(RepoSubmission {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        curriculum_id: row.get(2)?,
+        checkpoint_id: row.get(3)?,
+        repo_url: row.get(4)?,
+        commit_hash: row.get(5)?,
+        tests_passed: row.get(6)?,
+        tests_total: row.get(7)?,
+        verification_json: row.get(8)?,
+        artifact_submission_ids: serde_json::from_str(&row.get::<_, String>(9)?)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(9, rusqlite::types::Type::Text, Box::new(e)))?,
+        passed: row.get(10)?,
+        error: row.get(11)?,
+        submitted_at: parse_time(12, row.get(12)?)?,
+    })
+}
+
+impl RepoSubmissionRepository {
+    pub fn create(conn: &Connection, submission: &RepoSubmission) -> DbResult<()> {
+        let artifact_ids_json = serde_json::to_string(&submission.artifact_submission_ids)
+            .map_err(|e| DbError::InvalidData(e.to_string()))?;
+        conn.execute(
+            &format!(
+                "INSERT INTO repo_submissions ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                COLUMNS
+            ),
+            params![
+                submission.id,
+                submission.user_id,
+                submission.curriculum_id,
+                submission.checkpoint_id,
+                submission.repo_url,
+                submission.commit_hash,
+                submission.tests_passed,
+                submission.tests_total,
+                submission.verification_json,
+                artifact_ids_json,
+                submission.passed,
+                submission.error,
+                submission.submitted_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// A user's submissions for one checkpoint, newest first
+    pub fn get_for_checkpoint(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        checkpoint_id: &str,
+    ) -> DbResult<Vec<RepoSubmission>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM repo_submissions
+             WHERE user_id = ?1 AND curriculum_id IS ?2 AND checkpoint_id = ?3 ORDER BY submitted_at DESC",
+            COLUMNS
+        ))?;
+        let submissions = stmt
+            .query_map(params![user_id, curriculum_id, checkpoint_id], submission_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(submissions)
+    }
+
+    /// How many repos a user has submitted since `since`, failed ones included
+    pub fn count_since(conn: &Connection, user_id: &str, since: DateTime<Utc>) -> DbResult<u32> {
+        let count: u32 = conn.query_row(
+            "SELECT COUNT(*) FROM repo_submissions WHERE user_id = ?1 AND submitted_at >= ?2",
+            params![user_id, since.to_rfc3339()],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::User;
+    use chrono::Duration;
+
+    #[test]
+    fn test_submissions_are_listed_and_counted() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let url = "https://github.com/ferris/kv-store".to_string();
+        let mut graded = RepoSubmission::new("test-user".to_string(), None, "capstone".to_string(), url.clone());
+        graded.commit_hash = Some("3f2a9c1".to_string());
+        graded.tests_passed = Some(4);
+        graded.tests_total = Some(4);
+        graded.artifact_submission_ids = vec!["artifact-1".to_string()];
+        graded.passed = true;
+        RepoSubmissionRepository::create(conn, &graded).unwrap();
+
+        let mut private = RepoSubmission::new("test-user".to_string(), None, "capstone".to_string(), url)
+            .failed("github.com/ferris/kv-store wasn't found");
+        private.submitted_at = Utc::now() - Duration::hours(2);
+        RepoSubmissionRepository::create(conn, &private).unwrap();
+
+        let submissions = RepoSubmissionRepository::get_for_checkpoint(conn, "test-user", None, "capstone").unwrap();
+        assert_eq!(submissions, vec![graded, private]);
+
+        let hour_ago = Utc::now() - Duration::hours(1);
+        assert_eq!(RepoSubmissionRepository::count_since(conn, "test-user", hour_ago).unwrap(), 1);
+        assert_eq!(RepoSubmissionRepository::count_since(conn, "other-user", hour_ago).unwrap(), 0);
+    }
+}
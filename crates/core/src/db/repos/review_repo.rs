@@ -2,79 +2,63 @@ use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection, OptionalExtension};
 use crate::db::error::DbResult;
 use crate::models::ReviewItem;
+use crate::db::repos::parse_time;
 
 pub struct ReviewRepository;
 
 impl ReviewRepository {
     pub fn create_or_update(conn: &Connection, review: &ReviewItem) -> DbResult<()> {
         conn.execute(
-            "INSERT INTO review_items (user_id, quiz_id, due_date, ease_factor, interval_days, repetitions, last_reviewed_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
-             ON CONFLICT(user_id, quiz_id) DO UPDATE SET
+            "INSERT INTO review_items (user_id, curriculum_id, quiz_id, due_date, ease_factor, interval_days, repetitions, last_reviewed_at, lapses, is_suspended)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+             ON CONFLICT(user_id, IFNULL(curriculum_id, ''), quiz_id) DO UPDATE SET
                 due_date = excluded.due_date,
                 ease_factor = excluded.ease_factor,
                 interval_days = excluded.interval_days,
                 repetitions = excluded.repetitions,
-                last_reviewed_at = excluded.last_reviewed_at",
+                last_reviewed_at = excluded.last_reviewed_at,
+                lapses = excluded.lapses,
+                is_suspended = excluded.is_suspended",
             params![
                 review.user_id,
+                review.curriculum_id,
                 review.quiz_id,
                 review.due_date.to_rfc3339(),
                 review.ease_factor,
                 review.interval_days,
                 review.repetitions,
                 review.last_reviewed_at.map(|d| d.to_rfc3339()),
+                review.lapses,
+                review.is_suspended as i32,
             ],
         )?;
         Ok(())
     }
 
-    pub fn get(conn: &Connection, user_id: &str, quiz_id: &str) -> DbResult<Option<ReviewItem>> {
+    pub fn get(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        quiz_id: &str,
+    ) -> DbResult<Option<ReviewItem>> {
         let mut stmt = conn.prepare(
-            "SELECT user_id, quiz_id, due_date, ease_factor, interval_days, repetitions, last_reviewed_at
-             FROM review_items WHERE user_id = ?1 AND quiz_id = ?2"
+            "SELECT user_id, curriculum_id, quiz_id, due_date, ease_factor, interval_days, repetitions, last_reviewed_at, lapses, is_suspended
+             FROM review_items WHERE user_id = ?1 AND curriculum_id IS ?2 AND quiz_id = ?3"
         )?;
 
-        let review = stmt.query_row(params![user_id, quiz_id], |row| {
-            Ok(ReviewItem {
-                user_id: row.get(0)?,
-                quiz_id: row.get(1)?,
-                due_date: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
-                ease_factor: row.get(3)?,
-                interval_days: row.get(4)?,
-                repetitions: row.get(5)?,
-                last_reviewed_at: row.get::<_, Option<String>>(6)?
-                    .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                    .map(|dt| dt.with_timezone(&Utc)),
-            })
-        }).optional()?;
+        let review = stmt.query_row(params![user_id, curriculum_id, quiz_id], review_from_row).optional()?;
 
         Ok(review)
     }
 
+    /// Review items across every curriculum the user has studied
     pub fn get_all_for_user(conn: &Connection, user_id: &str) -> DbResult<Vec<ReviewItem>> {
         let mut stmt = conn.prepare(
-            "SELECT user_id, quiz_id, due_date, ease_factor, interval_days, repetitions, last_reviewed_at
+            "SELECT user_id, curriculum_id, quiz_id, due_date, ease_factor, interval_days, repetitions, last_reviewed_at, lapses, is_suspended
              FROM review_items WHERE user_id = ?1"
         )?;
 
-        let review_iter = stmt.query_map(params![user_id], |row| {
-            Ok(ReviewItem {
-                user_id: row.get(0)?,
-                quiz_id: row.get(1)?,
-                due_date: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
-                ease_factor: row.get(3)?,
-                interval_days: row.get(4)?,
-                repetitions: row.get(5)?,
-                last_reviewed_at: row.get::<_, Option<String>>(6)?
-                    .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                    .map(|dt| dt.with_timezone(&Utc)),
-            })
-        })?;
+        let review_iter = stmt.query_map(params![user_id], review_from_row)?;
 
         let mut results = Vec::new();
         for review in review_iter {
@@ -83,29 +67,38 @@ impl ReviewRepository {
         Ok(results)
     }
 
-    pub fn get_due_reviews(conn: &Connection, user_id: &str) -> DbResult<Vec<ReviewItem>> {
+    pub fn get_all_in_curriculum(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+    ) -> DbResult<Vec<ReviewItem>> {
+        let mut stmt = conn.prepare(
+            "SELECT user_id, curriculum_id, quiz_id, due_date, ease_factor, interval_days, repetitions, last_reviewed_at, lapses, is_suspended
+             FROM review_items WHERE user_id = ?1 AND curriculum_id IS ?2"
+        )?;
+
+        let review_iter = stmt.query_map(params![user_id, curriculum_id], review_from_row)?;
+
+        let mut results = Vec::new();
+        for review in review_iter {
+            results.push(review?);
+        }
+        Ok(results)
+    }
+
+    pub fn get_due_reviews(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+    ) -> DbResult<Vec<ReviewItem>> {
         let now = Utc::now().to_rfc3339();
         let mut stmt = conn.prepare(
-            "SELECT user_id, quiz_id, due_date, ease_factor, interval_days, repetitions, last_reviewed_at
-             FROM review_items WHERE user_id = ?1 AND due_date <= ?2
+            "SELECT user_id, curriculum_id, quiz_id, due_date, ease_factor, interval_days, repetitions, last_reviewed_at, lapses, is_suspended
+             FROM review_items WHERE user_id = ?1 AND curriculum_id IS ?2 AND due_date <= ?3 AND is_suspended = 0
              ORDER BY due_date ASC"
         )?;
 
-        let review_iter = stmt.query_map(params![user_id, now], |row| {
-            Ok(ReviewItem {
-                user_id: row.get(0)?,
-                quiz_id: row.get(1)?,
-                due_date: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
-                ease_factor: row.get(3)?,
-                interval_days: row.get(4)?,
-                repetitions: row.get(5)?,
-                last_reviewed_at: row.get::<_, Option<String>>(6)?
-                    .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                    .map(|dt| dt.with_timezone(&Utc)),
-            })
-        })?;
+        let review_iter = stmt.query_map(params![user_id, curriculum_id, now], review_from_row)?;
 
         let mut results = Vec::new();
         for review in review_iter {
@@ -114,25 +107,71 @@ impl ReviewRepository {
         Ok(results)
     }
 
-    pub fn count_due_reviews(conn: &Connection, user_id: &str) -> DbResult<i32> {
-        let now = Utc::now().to_rfc3339();
+    pub fn count_due_reviews(conn: &Connection, user_id: &str, curriculum_id: Option<&str>) -> DbResult<i32> {
+        Self::count_due_reviews_by(conn, user_id, curriculum_id, Utc::now())
+    }
+
+    /// Reviews that will be due by `at`
+    pub fn count_due_reviews_by(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        at: DateTime<Utc>,
+    ) -> DbResult<i32> {
         let count: i32 = conn.query_row(
-            "SELECT COUNT(*) FROM review_items WHERE user_id = ?1 AND due_date <= ?2",
-            params![user_id, now],
+            "SELECT COUNT(*) FROM review_items
+             WHERE user_id = ?1 AND curriculum_id IS ?2 AND due_date <= ?3 AND is_suspended = 0",
+            params![user_id, curriculum_id, at.to_rfc3339()],
             |row| row.get(0),
         )?;
         Ok(count)
     }
 
-    pub fn delete(conn: &Connection, user_id: &str, quiz_id: &str) -> DbResult<()> {
+    /// Get review items that have been suspended as leeches
+    pub fn get_leech_items(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+    ) -> DbResult<Vec<ReviewItem>> {
+        let mut stmt = conn.prepare(
+            "SELECT user_id, curriculum_id, quiz_id, due_date, ease_factor, interval_days, repetitions, last_reviewed_at, lapses, is_suspended
+             FROM review_items WHERE user_id = ?1 AND curriculum_id IS ?2 AND is_suspended = 1
+             ORDER BY lapses DESC"
+        )?;
+
+        let review_iter = stmt.query_map(params![user_id, curriculum_id], review_from_row)?;
+
+        let mut results = Vec::new();
+        for review in review_iter {
+            results.push(review?);
+        }
+        Ok(results)
+    }
+
+    pub fn delete(conn: &Connection, user_id: &str, curriculum_id: Option<&str>, quiz_id: &str) -> DbResult<()> {
         conn.execute(
-            "DELETE FROM review_items WHERE user_id = ?1 AND quiz_id = ?2",
-            params![user_id, quiz_id],
+            "DELETE FROM review_items WHERE user_id = ?1 AND curriculum_id IS ?2 AND quiz_id = ?3",
+            params![user_id, curriculum_id, quiz_id],
         )?;
         Ok(())
     }
 }
 
+fn review_from_row(row: &rusqlite::Row) -> rusqlite::Result<ReviewItem> {
+    Ok(ReviewItem {
+        user_id: row.get(0)?,
+        curriculum_id: row.get(1)?,
+        quiz_id: row.get(2)?,
+        due_date: parse_time(3, row.get(3)?)?,
+        ease_factor: row.get(4)?,
+        interval_days: row.get(5)?,
+        repetitions: row.get(6)?,
+        last_reviewed_at: row.get::<_, Option<String>>(7)?.map(|s| parse_time(7, s)).transpose()?,
+        lapses: row.get(8)?,
+        is_suspended: row.get::<_, i32>(9)? != 0,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -156,7 +195,7 @@ mod tests {
         let review = ReviewItem::new("test-user".to_string(), "quiz1".to_string());
         ReviewRepository::create_or_update(conn, &review).unwrap();
 
-        let retrieved = ReviewRepository::get(conn, "test-user", "quiz1").unwrap();
+        let retrieved = ReviewRepository::get(conn, "test-user", None, "quiz1").unwrap();
         assert!(retrieved.is_some());
         let retrieved = retrieved.unwrap();
         assert_eq!(retrieved.quiz_id, "quiz1");
@@ -177,7 +216,7 @@ mod tests {
         let future_review = ReviewItem::new("test-user".to_string(), "quiz2".to_string());
         ReviewRepository::create_or_update(conn, &future_review).unwrap();
 
-        let due = ReviewRepository::get_due_reviews(conn, "test-user").unwrap();
+        let due = ReviewRepository::get_due_reviews(conn, "test-user", None).unwrap();
         assert_eq!(due.len(), 1);
         assert_eq!(due[0].quiz_id, "quiz1");
     }
@@ -196,7 +235,7 @@ mod tests {
         ReviewRepository::create_or_update(conn, &review1).unwrap();
         ReviewRepository::create_or_update(conn, &review2).unwrap();
 
-        let count = ReviewRepository::count_due_reviews(conn, "test-user").unwrap();
+        let count = ReviewRepository::count_due_reviews(conn, "test-user", None).unwrap();
         assert_eq!(count, 2);
     }
 
@@ -212,8 +251,52 @@ mod tests {
         review.update_after_review(4); // Good
         ReviewRepository::create_or_update(conn, &review).unwrap();
 
-        let updated = ReviewRepository::get(conn, "test-user", "quiz1").unwrap().unwrap();
+        let updated = ReviewRepository::get(conn, "test-user", None, "quiz1").unwrap().unwrap();
         assert_eq!(updated.repetitions, 1);
         assert!(updated.last_reviewed_at.is_some());
     }
+
+    #[test]
+    fn test_leech_items_excluded_from_due_queue() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        let mut leech = ReviewItem::new("test-user".to_string(), "quiz1".to_string());
+        leech.due_date = Utc::now() - Duration::hours(1);
+        leech.lapses = ReviewItem::LEECH_THRESHOLD + 1;
+        leech.is_suspended = true;
+        ReviewRepository::create_or_update(conn, &leech).unwrap();
+
+        let mut due = ReviewItem::new("test-user".to_string(), "quiz2".to_string());
+        due.due_date = Utc::now() - Duration::hours(1);
+        ReviewRepository::create_or_update(conn, &due).unwrap();
+
+        assert_eq!(ReviewRepository::count_due_reviews(conn, "test-user", None).unwrap(), 1);
+        assert_eq!(ReviewRepository::get_due_reviews(conn, "test-user", None).unwrap().len(), 1);
+
+        let leeches = ReviewRepository::get_leech_items(conn, "test-user", None).unwrap();
+        assert_eq!(leeches.len(), 1);
+        assert_eq!(leeches[0].quiz_id, "quiz1");
+        assert_eq!(leeches[0].lapses, ReviewItem::LEECH_THRESHOLD + 1);
+    }
+
+    #[test]
+    fn test_due_reviews_scoped_to_curriculum() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        for curriculum in ["rust", "go"] {
+            let mut review = ReviewItem::new("test-user".to_string(), "quiz1".to_string())
+                .with_curriculum(curriculum.to_string());
+            review.due_date = Utc::now() - Duration::hours(1);
+            ReviewRepository::create_or_update(conn, &review).unwrap();
+        }
+
+        assert_eq!(ReviewRepository::count_due_reviews(conn, "test-user", Some("rust")).unwrap(), 1);
+        assert_eq!(ReviewRepository::count_due_reviews(conn, "test-user", None).unwrap(), 0);
+
+        ReviewRepository::delete(conn, "test-user", Some("rust"), "quiz1").unwrap();
+        assert!(ReviewRepository::get(conn, "test-user", Some("rust"), "quiz1").unwrap().is_none());
+        assert_eq!(ReviewRepository::get_due_reviews(conn, "test-user", Some("go")).unwrap().len(), 1);
+    }
 }
@@ -0,0 +1,214 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rusqlite::{params, Connection};
+use crate::db::error::DbResult;
+use crate::models::{SearchHit, SearchSource};
+
+pub struct SearchRepository;
+
+/// Turn free text into an FTS5 query. Every word must match, as a prefix, and
+/// FTS5 operators typed by the user are treated as plain text.
+fn fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|term| term.replace('"', ""))
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("\"{}\"*", term))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Accepts RFC3339 and SQLite's `datetime('now')` format, which
+/// artifact submissions use by default
+fn parse_timestamp(idx: usize, value: &str) -> rusqlite::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|d| d.with_timezone(&Utc))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").map(|d| d.and_utc()))
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e)))
+}
+
+fn hit_from_row(row: &rusqlite::Row) -> rusqlite::Result<SearchHit> {
+    Ok(SearchHit {
+        source: row.get::<_, String>(0)?
+            .parse::<SearchSource>()
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, e.into()))?,
+        record_id: row.get(1)?,
+        node_id: row.get(2)?,
+        snippet: row.get(3)?,
+        occurred_at: parse_timestamp(4, &row.get::<_, String>(4)?)?,
+        rank: row.get(5)?,
+    })
+}
+
+impl SearchRepository {
+    /// Search a user's notes, quiz answers and grade feedback, best matches first
+    pub fn search(conn: &Connection, user_id: &str, query: &str, limit: u32) -> DbResult<Vec<SearchHit>> {
+        let Some(fts_query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let mut stmt = conn.prepare(
+            "SELECT 'Note', n.id, n.node_id, snippet(notes_fts, 1, '[', ']', '…', 12),
+                    n.updated_at, bm25(notes_fts)
+             FROM notes_fts JOIN notes n ON n.id = notes_fts.note_id
+             WHERE notes_fts MATCH ?1 AND n.user_id = ?2
+             UNION ALL
+             SELECT 'QuizAnswer', q.id, q.node_id, snippet(quiz_answers_fts, 1, '[', ']', '…', 12),
+                    q.submitted_at, bm25(quiz_answers_fts)
+             FROM quiz_answers_fts JOIN quiz_attempts q ON q.id = quiz_answers_fts.attempt_id
+             WHERE quiz_answers_fts MATCH ?1 AND q.user_id = ?2
+             UNION ALL
+             SELECT 'GradeFeedback', a.id, a.checkpoint_id, snippet(grade_feedback_fts, 1, '[', ']', '…', 12),
+                    COALESCE(a.graded_at, a.submitted_at), bm25(grade_feedback_fts)
+             FROM grade_feedback_fts JOIN artifact_submissions a ON a.id = grade_feedback_fts.submission_id
+             WHERE grade_feedback_fts MATCH ?1 AND a.user_id = ?2
+             ORDER BY 6 ASC, 5 DESC
+             LIMIT ?3"
+        )?;
+        let hit_iter = stmt.query_map(params![fts_query, user_id, limit], hit_from_row)?;
+
+        let mut results = Vec::new();
+        for hit in hit_iter {
+            results.push(hit?);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::{NoteRepository, QuizRepository, UserRepository};
+    use crate::models::{Note, QuizAttempt, User};
+
+    fn setup_db() -> Database {
+        let db = Database::new_in_memory().unwrap();
+        UserRepository::create(db.connection(), &User::new("test-user".to_string())).unwrap();
+        UserRepository::create(db.connection(), &User::new("other-user".to_string())).unwrap();
+        db
+    }
+
+    fn add_note(conn: &Connection, user_id: &str, body: &str) -> Note {
+        let note = Note::new(user_id.to_string(), "week1-lecture".to_string(), body.to_string());
+        NoteRepository::create(conn, &note).unwrap();
+        note
+    }
+
+    fn add_graded_submission(conn: &Connection, id: &str, user_id: &str, reasoning_json: &str) {
+        conn.execute(
+            "INSERT INTO artifact_submissions (id, user_id, checkpoint_id, artifact_type, content_hash)
+             VALUES (?1, ?2, 'week1-checkpoint', 'DESIGN', 'hash')",
+            params![id, user_id],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE artifact_submissions SET reasoning_json = ?1, grade_percentage = 80 WHERE id = ?2",
+            params![reasoning_json, id],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_fts_query_quotes_terms() {
+        assert_eq!(fts_query("borrow checker").unwrap(), "\"borrow\"* \"checker\"*");
+        assert_eq!(fts_query("\"NEAR\" OR").unwrap(), "\"NEAR\"* \"OR\"*");
+        assert!(fts_query("   ").is_none());
+    }
+
+    #[test]
+    fn test_search_across_sources() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        let note = add_note(conn, "test-user", "The borrow checker rejects two mutable references");
+        let attempt = QuizAttempt::new(
+            "test-user".to_string(),
+            "quiz1".to_string(),
+            "week1-quiz".to_string(),
+            vec!["The borrow checker".to_string(), "b".to_string()],
+            100,
+            50,
+        );
+        QuizRepository::create(conn, &attempt).unwrap();
+        add_graded_submission(
+            conn,
+            "sub1",
+            "test-user",
+            r#"{"overall_feedback":"Explain how the borrow checker shapes the API","category_scores":[]}"#,
+        );
+
+        let hits = SearchRepository::search(conn, "test-user", "borrow checker", 10).unwrap();
+        assert_eq!(hits.len(), 3);
+
+        let sources: Vec<SearchSource> = hits.iter().map(|h| h.source).collect();
+        assert!(sources.contains(&SearchSource::Note));
+        assert!(sources.contains(&SearchSource::QuizAnswer));
+        assert!(sources.contains(&SearchSource::GradeFeedback));
+
+        let note_hit = hits.iter().find(|h| h.source == SearchSource::Note).unwrap();
+        assert_eq!(note_hit.record_id, note.id);
+        assert!(note_hit.snippet.contains("[borrow]"));
+
+        // JSON structure is not indexed
+        let feedback_hit = hits.iter().find(|h| h.source == SearchSource::GradeFeedback).unwrap();
+        assert_eq!(feedback_hit.node_id, "week1-checkpoint");
+        assert!(!feedback_hit.snippet.contains("overall_feedback"));
+        assert!(SearchRepository::search(conn, "test-user", "category_scores", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_is_scoped_to_user() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        add_note(conn, "other-user", "Lifetimes tie references to their owners");
+
+        assert!(SearchRepository::search(conn, "test-user", "lifetimes", 10).unwrap().is_empty());
+        assert_eq!(SearchRepository::search(conn, "other-user", "lifetimes", 10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_search_follows_note_edits_and_deletes() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        let note = add_note(conn, "test-user", "Traits are like interfaces");
+        assert_eq!(SearchRepository::search(conn, "test-user", "interfaces", 10).unwrap().len(), 1);
+
+        NoteRepository::update_body(conn, "test-user", &note.id, "Traits define shared behaviour").unwrap();
+        assert!(SearchRepository::search(conn, "test-user", "interfaces", 10).unwrap().is_empty());
+        assert_eq!(SearchRepository::search(conn, "test-user", "behaviour", 10).unwrap().len(), 1);
+
+        NoteRepository::delete(conn, "test-user", &note.id).unwrap();
+        assert!(SearchRepository::search(conn, "test-user", "behaviour", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_matches_prefixes_and_stems() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        add_note(conn, "test-user", "Borrowing rules prevent data races");
+
+        assert_eq!(SearchRepository::search(conn, "test-user", "borrow", 10).unwrap().len(), 1);
+        assert_eq!(SearchRepository::search(conn, "test-user", "rac", 10).unwrap().len(), 1);
+        assert!(SearchRepository::search(conn, "test-user", "borrow threads", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_respects_limit() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        for i in 0..5 {
+            add_note(conn, "test-user", &format!("Closure note {}", i));
+        }
+
+        assert_eq!(SearchRepository::search(conn, "test-user", "closure", 3).unwrap().len(), 3);
+    }
+}
@@ -1,15 +1,36 @@
 use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection, OptionalExtension};
 use crate::db::error::DbResult;
-use crate::models::SessionHistory;
+use crate::db::in_savepoint;
+use crate::models::{RecoveredSession, SessionHistory};
+use crate::db::repos::parse_time;
 
 pub struct SessionRepository;
 
+const SESSION_COLUMNS: &str =
+    "id, user_id, started_at, ended_at, total_xp_earned, items_completed, last_heartbeat_at, active_seconds,
+     learning_seconds";
+
+fn session_from_row(row: &rusqlite::Row) -> rusqlite::Result<SessionHistory> {
+    Ok(SessionHistory {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        started_at: parse_time(2, row.get(2)?)?,
+        ended_at: row.get::<_, Option<String>>(3)?.map(|s| parse_time(3, s)).transpose()?,
+        total_xp_earned: row.get(4)?,
+        items_completed: row.get(5)?,
+        last_heartbeat_at: row.get::<_, Option<String>>(6)?.map(|s| parse_time(6, s)).transpose()?,
+        active_seconds: row.get(7)?,
+        learning_seconds: row.get(8)?,
+    })
+}
+
 impl SessionRepository {
     pub fn create(conn: &Connection, session: &SessionHistory) -> DbResult<()> {
         conn.execute(
-            "INSERT INTO session_history (id, user_id, started_at, ended_at, total_xp_earned, items_completed)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            "INSERT INTO session_history (id, user_id, started_at, ended_at, total_xp_earned, items_completed,
+                                          last_heartbeat_at, active_seconds, learning_seconds)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             params![
                 session.id,
                 session.user_id,
@@ -17,43 +38,37 @@ impl SessionRepository {
                 session.ended_at.map(|d| d.to_rfc3339()),
                 session.total_xp_earned,
                 session.items_completed,
+                session.last_heartbeat_at.map(|d| d.to_rfc3339()),
+                session.active_seconds,
+                session.learning_seconds,
             ],
         )?;
         Ok(())
     }
 
     pub fn get_by_id(conn: &Connection, session_id: &str) -> DbResult<Option<SessionHistory>> {
-        let mut stmt = conn.prepare(
-            "SELECT id, user_id, started_at, ended_at, total_xp_earned, items_completed
-             FROM session_history WHERE id = ?1"
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM session_history WHERE id = ?1",
+            SESSION_COLUMNS
+        ))?;
 
-        let session = stmt.query_row(params![session_id], |row| {
-            Ok(SessionHistory {
-                id: row.get(0)?,
-                user_id: row.get(1)?,
-                started_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
-                ended_at: row.get::<_, Option<String>>(3)?
-                    .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                    .map(|dt| dt.with_timezone(&Utc)),
-                total_xp_earned: row.get(4)?,
-                items_completed: row.get(5)?,
-            })
-        }).optional()?;
+        let session = stmt.query_row(params![session_id], session_from_row).optional()?;
 
         Ok(session)
     }
 
     pub fn update(conn: &Connection, session: &SessionHistory) -> DbResult<()> {
         conn.execute(
-            "UPDATE session_history SET ended_at = ?1, total_xp_earned = ?2, items_completed = ?3
-             WHERE id = ?4",
+            "UPDATE session_history SET ended_at = ?1, total_xp_earned = ?2, items_completed = ?3,
+                                        last_heartbeat_at = ?4, active_seconds = ?5, learning_seconds = ?6
+             WHERE id = ?7",
             params![
                 session.ended_at.map(|d| d.to_rfc3339()),
                 session.total_xp_earned,
                 session.items_completed,
+                session.last_heartbeat_at.map(|d| d.to_rfc3339()),
+                session.active_seconds,
+                session.learning_seconds,
                 session.id,
             ],
         )?;
@@ -61,50 +76,24 @@ impl SessionRepository {
     }
 
     pub fn get_active_session(conn: &Connection, user_id: &str) -> DbResult<Option<SessionHistory>> {
-        let mut stmt = conn.prepare(
-            "SELECT id, user_id, started_at, ended_at, total_xp_earned, items_completed
-             FROM session_history WHERE user_id = ?1 AND ended_at IS NULL
-             ORDER BY started_at DESC LIMIT 1"
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM session_history WHERE user_id = ?1 AND ended_at IS NULL
+             ORDER BY started_at DESC LIMIT 1",
+            SESSION_COLUMNS
+        ))?;
 
-        let session = stmt.query_row(params![user_id], |row| {
-            Ok(SessionHistory {
-                id: row.get(0)?,
-                user_id: row.get(1)?,
-                started_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
-                ended_at: row.get::<_, Option<String>>(3)?
-                    .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                    .map(|dt| dt.with_timezone(&Utc)),
-                total_xp_earned: row.get(4)?,
-                items_completed: row.get(5)?,
-            })
-        }).optional()?;
+        let session = stmt.query_row(params![user_id], session_from_row).optional()?;
 
         Ok(session)
     }
 
     pub fn get_recent(conn: &Connection, user_id: &str, limit: i32) -> DbResult<Vec<SessionHistory>> {
-        let mut stmt = conn.prepare(
-            "SELECT id, user_id, started_at, ended_at, total_xp_earned, items_completed
-             FROM session_history WHERE user_id = ?1 ORDER BY started_at DESC LIMIT ?2"
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM session_history WHERE user_id = ?1 ORDER BY started_at DESC LIMIT ?2",
+            SESSION_COLUMNS
+        ))?;
 
-        let session_iter = stmt.query_map(params![user_id, limit], |row| {
-            Ok(SessionHistory {
-                id: row.get(0)?,
-                user_id: row.get(1)?,
-                started_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
-                ended_at: row.get::<_, Option<String>>(3)?
-                    .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                    .map(|dt| dt.with_timezone(&Utc)),
-                total_xp_earned: row.get(4)?,
-                items_completed: row.get(5)?,
-            })
-        })?;
+        let session_iter = stmt.query_map(params![user_id, limit], session_from_row)?;
 
         let mut results = Vec::new();
         for session in session_iter {
@@ -112,6 +101,57 @@ impl SessionRepository {
         }
         Ok(results)
     }
+
+    /// Record that the app is still running an open session. Returns the
+    /// updated session, or None if it has ended or doesn't exist.
+    pub fn record_heartbeat(conn: &Connection, session_id: &str, now: DateTime<Utc>) -> DbResult<Option<SessionHistory>> {
+        let Some(mut session) = Self::get_by_id(conn, session_id)? else {
+            return Ok(None);
+        };
+        if !session.is_active() {
+            return Ok(None);
+        }
+
+        session.record_heartbeat(now);
+        Self::update(conn, &session)?;
+        Ok(Some(session))
+    }
+
+    /// Add time the learner was active on a node to their open session, if
+    /// they have one
+    pub fn add_learning_time(conn: &Connection, user_id: &str, seconds: i64) -> DbResult<()> {
+        conn.execute(
+            "UPDATE session_history SET learning_seconds = learning_seconds + ?1
+             WHERE id = (SELECT id FROM session_history WHERE user_id = ?2 AND ended_at IS NULL
+                         ORDER BY started_at DESC LIMIT 1)",
+            params![seconds, user_id],
+        )?;
+        Ok(())
+    }
+
+    /// Close every open session, for any user, that stopped heartbeating,
+    /// ending each at its last heartbeat
+    pub fn recover_stale(conn: &Connection, now: DateTime<Utc>) -> DbResult<Vec<RecoveredSession>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM session_history WHERE ended_at IS NULL ORDER BY started_at",
+            SESSION_COLUMNS
+        ))?;
+        let session_iter = stmt.query_map([], session_from_row)?;
+
+        let mut open = Vec::new();
+        for session in session_iter {
+            open.push(session?);
+        }
+
+        in_savepoint(conn, "recover_sessions", || {
+            let mut recovered = Vec::new();
+            for mut session in open.into_iter().filter(|s| s.is_stale(now)) {
+                recovered.push(session.recover());
+                Self::update(conn, &session)?;
+            }
+            Ok(recovered)
+        })
+    }
 }
 
 #[cfg(test)]
@@ -120,6 +160,7 @@ mod tests {
     use crate::db::connection::Database;
     use crate::db::repos::UserRepository;
     use crate::models::User;
+    use chrono::Duration;
 
     fn setup_db() -> Database {
         let db = Database::new_in_memory().unwrap();
@@ -172,4 +213,32 @@ mod tests {
         assert_eq!(updated.total_xp_earned, 100);
         assert_eq!(updated.items_completed, 1);
     }
+
+    #[test]
+    fn test_recover_stale_closes_only_sessions_that_stopped_beating() {
+        let db = setup_db();
+        let conn = db.connection();
+        let now = Utc::now();
+
+        let mut crashed = SessionHistory::new("test-user".to_string());
+        crashed.started_at = now - Duration::minutes(30);
+        SessionRepository::create(conn, &crashed).unwrap();
+        SessionRepository::record_heartbeat(conn, &crashed.id, now - Duration::minutes(29)).unwrap();
+        SessionRepository::record_heartbeat(conn, &crashed.id, now - Duration::minutes(28)).unwrap();
+
+        let live = SessionHistory::new("test-user".to_string());
+        SessionRepository::create(conn, &live).unwrap();
+        SessionRepository::record_heartbeat(conn, &live.id, now).unwrap();
+
+        let recovered = SessionRepository::recover_stale(conn, now).unwrap();
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(recovered[0].session_id, crashed.id);
+        assert_eq!(recovered[0].active_seconds, 120);
+
+        let closed = SessionRepository::get_by_id(conn, &crashed.id).unwrap().unwrap();
+        assert_eq!(closed.ended_at, closed.last_heartbeat_at);
+        assert!(SessionRepository::get_by_id(conn, &live.id).unwrap().unwrap().is_active());
+        assert!(SessionRepository::recover_stale(conn, now).unwrap().is_empty());
+        assert!(SessionRepository::record_heartbeat(conn, &crashed.id, now).unwrap().is_none());
+    }
 }
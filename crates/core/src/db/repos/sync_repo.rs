@@ -0,0 +1,163 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use uuid::Uuid;
+use crate::db::error::{DbError, DbResult};
+use crate::models::{SyncRecord, SyncedRecord};
+use crate::db::repos::parse_time;
+
+pub struct SyncRepository;
+
+fn synced_record_from_row(row: &rusqlite::Row) -> rusqlite::Result<SyncedRecord> {
+    let record: SyncRecord = serde_json::from_str(&row.get::<_, String>(2)?)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e)))?;
+
+    Ok(SyncedRecord {
+        user_id: row.get(0)?,
+        record_key: row.get(1)?,
+        record,
+        content_hash: row.get(3)?,
+        deleted: row.get(4)?,
+        updated_at: parse_time(5, row.get(5)?)?,
+        device_id: row.get(6)?,
+    })
+}
+
+impl SyncRepository {
+    /// This database's device ID, created on first use
+    pub fn device_id(conn: &Connection) -> DbResult<String> {
+        let existing: Option<String> = conn
+            .query_row("SELECT id FROM sync_device LIMIT 1", [], |row| row.get(0))
+            .optional()?;
+        if let Some(id) = existing {
+            return Ok(id);
+        }
+
+        let id = Uuid::new_v4().to_string();
+        conn.execute(
+            "INSERT INTO sync_device (id, created_at) VALUES (?1, ?2)",
+            params![id, Utc::now().to_rfc3339()],
+        )?;
+        Ok(id)
+    }
+
+    pub fn save_record(conn: &Connection, synced: &SyncedRecord) -> DbResult<()> {
+        let record_json = serde_json::to_string(&synced.record)
+            .map_err(|e| DbError::InvalidData(e.to_string()))?;
+
+        conn.execute(
+            "INSERT INTO sync_records (user_id, record_key, record_json, content_hash, deleted, updated_at, device_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(user_id, record_key) DO UPDATE SET
+                record_json = excluded.record_json,
+                content_hash = excluded.content_hash,
+                deleted = excluded.deleted,
+                updated_at = excluded.updated_at,
+                device_id = excluded.device_id",
+            params![
+                synced.user_id,
+                synced.record_key,
+                record_json,
+                synced.content_hash,
+                synced.deleted,
+                synced.updated_at.to_rfc3339(),
+                synced.device_id,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_record(conn: &Connection, user_id: &str, record_key: &str) -> DbResult<Option<SyncedRecord>> {
+        let mut stmt = conn.prepare(
+            "SELECT user_id, record_key, record_json, content_hash, deleted, updated_at, device_id
+             FROM sync_records WHERE user_id = ?1 AND record_key = ?2"
+        )?;
+        let synced = stmt.query_row(params![user_id, record_key], synced_record_from_row).optional()?;
+        Ok(synced)
+    }
+
+    pub fn get_records(conn: &Connection, user_id: &str) -> DbResult<Vec<SyncedRecord>> {
+        let mut stmt = conn.prepare(
+            "SELECT user_id, record_key, record_json, content_hash, deleted, updated_at, device_id
+             FROM sync_records WHERE user_id = ?1"
+        )?;
+        let record_iter = stmt.query_map(params![user_id], synced_record_from_row)?;
+
+        let mut results = Vec::new();
+        for record in record_iter {
+            results.push(record?);
+        }
+        Ok(results)
+    }
+
+    /// How many ops of a device's log have been read for a user
+    pub fn get_cursor(conn: &Connection, user_id: &str, device_id: &str) -> DbResult<usize> {
+        let ops_read: Option<i64> = conn
+            .query_row(
+                "SELECT ops_read FROM sync_cursors WHERE user_id = ?1 AND device_id = ?2",
+                params![user_id, device_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(ops_read.unwrap_or(0) as usize)
+    }
+
+    pub fn set_cursor(conn: &Connection, user_id: &str, device_id: &str, ops_read: usize) -> DbResult<()> {
+        conn.execute(
+            "INSERT INTO sync_cursors (user_id, device_id, ops_read, synced_at) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(user_id, device_id) DO UPDATE SET ops_read = excluded.ops_read, synced_at = excluded.synced_at",
+            params![user_id, device_id, ops_read as i64, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// When the user's data was last synced from another device
+    pub fn last_synced_at(conn: &Connection, user_id: &str) -> DbResult<Option<DateTime<Utc>>> {
+        let synced_at: Option<String> = conn.query_row(
+            "SELECT MAX(synced_at) FROM sync_cursors WHERE user_id = ?1",
+            params![user_id],
+            |row| row.get(0),
+        )?;
+        synced_at
+            .map(|s| parse_time(0, s))
+            .transpose()
+            .map_err(DbError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::{NodeProgress, SyncOp, User};
+
+    #[test]
+    fn test_device_id_is_stable() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        assert_eq!(SyncRepository::device_id(conn).unwrap(), SyncRepository::device_id(conn).unwrap());
+    }
+
+    #[test]
+    fn test_save_record_and_cursor() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let record = SyncRecord::Progress(NodeProgress::new("test-user".to_string(), "week1-lecture".to_string()));
+        let op = SyncOp::new("device-a".to_string(), Utc::now(), false, record);
+        SyncRepository::save_record(conn, &SyncedRecord::from(&op)).unwrap();
+        SyncRepository::save_record(conn, &SyncedRecord { deleted: true, ..SyncedRecord::from(&op) }).unwrap();
+
+        let stored = SyncRepository::get_record(conn, "test-user", &op.record.key()).unwrap().unwrap();
+        assert!(stored.deleted);
+        assert_eq!(stored.content_hash, op.record.content_hash());
+        assert_eq!(SyncRepository::get_records(conn, "test-user").unwrap().len(), 1);
+
+        assert_eq!(SyncRepository::get_cursor(conn, "test-user", "device-a").unwrap(), 0);
+        assert!(SyncRepository::last_synced_at(conn, "test-user").unwrap().is_none());
+        SyncRepository::set_cursor(conn, "test-user", "device-a", 5).unwrap();
+        assert_eq!(SyncRepository::get_cursor(conn, "test-user", "device-a").unwrap(), 5);
+        assert!(SyncRepository::last_synced_at(conn, "test-user").unwrap().is_some());
+    }
+}
@@ -0,0 +1,97 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use crate::db::error::DbResult;
+use crate::models::TrackRepo;
+use crate::db::repos::parse_time;
+
+pub struct TrackRepository;
+
+const COLUMNS: &str = "user_id, curriculum_id, track_id, repo_path, updated_at";
+
+fn repo_from_row(row: &rusqlite::Row) -> rusqlite::Result<TrackRepo> {
+    Ok(TrackRepo {
+        user_id: row.get(0)?,
+        curriculum_id: row.get(1)?,
+        track_id: row.get(2)?,
+        repo_path: row.get(3)?,
+        updated_at: parse_time(4, row.get(4)?)?,
+    })
+}
+
+impl TrackRepository {
+    pub fn get_repo(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        track_id: &str,
+    ) -> DbResult<Option<TrackRepo>> {
+        let repo = conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM track_repos WHERE user_id = ?1 AND curriculum_id IS ?2 AND track_id = ?3",
+                    COLUMNS
+                ),
+                params![user_id, curriculum_id, track_id],
+                repo_from_row,
+            )
+            .optional()?;
+        Ok(repo)
+    }
+
+    /// Point the user's track at a repo, replacing any earlier one
+    pub fn set_repo(conn: &Connection, repo: &TrackRepo) -> DbResult<()> {
+        // curriculum_id may be NULL, which a unique key can't match on, so
+        // update first and insert only if there was nothing to update
+        let updated = conn.execute(
+            "UPDATE track_repos SET repo_path = ?1, updated_at = ?2
+             WHERE user_id = ?3 AND curriculum_id IS ?4 AND track_id = ?5",
+            params![
+                repo.repo_path,
+                repo.updated_at.to_rfc3339(),
+                repo.user_id,
+                repo.curriculum_id,
+                repo.track_id,
+            ],
+        )?;
+        if updated == 0 {
+            conn.execute(
+                &format!("INSERT INTO track_repos ({}) VALUES (?1, ?2, ?3, ?4, ?5)", COLUMNS),
+                params![
+                    repo.user_id,
+                    repo.curriculum_id,
+                    repo.track_id,
+                    repo.repo_path,
+                    repo.updated_at.to_rfc3339(),
+                ],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::User;
+
+    #[test]
+    fn test_track_repo_is_replaced() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        assert!(TrackRepository::get_repo(conn, "test-user", None, "orderflow").unwrap().is_none());
+        let first = TrackRepo::new("test-user".to_string(), None, "orderflow".to_string(), "/src/sim".to_string());
+        TrackRepository::set_repo(conn, &first).unwrap();
+        let moved = TrackRepo::new("test-user".to_string(), None, "orderflow".to_string(), "/src/orderflow".to_string());
+        TrackRepository::set_repo(conn, &moved).unwrap();
+
+        let repo = TrackRepository::get_repo(conn, "test-user", None, "orderflow").unwrap().unwrap();
+        assert_eq!(repo.repo_path, "/src/orderflow");
+        let count: i32 = conn
+            .query_row("SELECT COUNT(*) FROM track_repos", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+}
@@ -0,0 +1,368 @@
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Params};
+use crate::db::error::{DbError, DbResult};
+use crate::db::in_savepoint;
+use crate::db::repos::CurriculumRepository;
+use crate::models::{TableSnapshot, TrashEntry, TrashOperation};
+use crate::db::repos::parse_time;
+
+/// Tables cleared when a user resets their progress
+const USER_PROGRESS_TABLES: &[&str] = &[
+    "node_progress",
+    "quiz_attempts",
+    "challenge_attempts",
+    "mastery_scores",
+    "mastery_history",
+    "badge_progress",
+    "review_items",
+    "quest_progress",
+    "xp_log",
+    "focus_blocks",
+    "hint_reveals",
+    "challenge_drafts",
+    "best_attempts",
+    "node_time",
+    // Last, so restoring it overwrites what the triggers on the tables above re-added
+    "daily_activity",
+];
+
+/// Tables holding progress tagged with a curriculum
+const CURRICULUM_PROGRESS_TABLES: &[&str] = &[
+    "node_progress",
+    "quiz_attempts",
+    "challenge_attempts",
+    "mastery_scores",
+    "badge_progress",
+    "review_items",
+    "hint_reveals",
+    "challenge_drafts",
+    "best_attempts",
+    "node_time",
+    "track_repos",
+    "repo_submissions",
+    "flashcards",
+    "bookmarks",
+    "goals",
+];
+
+pub struct TrashRepository;
+
+fn trash_from_row(row: &rusqlite::Row) -> rusqlite::Result<TrashEntry> {
+    let operation: String = row.get(2)?;
+    let snapshot_json: String = row.get(4)?;
+
+    Ok(TrashEntry {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        operation: operation.parse()
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, e))))?,
+        subject_id: row.get(3)?,
+        tables: serde_json::from_str(&snapshot_json)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e)))?,
+        created_at: parse_time(5, row.get(5)?)?,
+        expires_at: parse_time(6, row.get(6)?)?,
+    })
+}
+
+fn to_json(value: ValueRef) -> serde_json::Value {
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(i) => i.into(),
+        ValueRef::Real(f) => f.into(),
+        ValueRef::Text(t) => String::from_utf8_lossy(t).into_owned().into(),
+        ValueRef::Blob(b) => b.to_vec().into(),
+    }
+}
+
+fn from_json(value: &serde_json::Value) -> DbResult<Value> {
+    match value {
+        serde_json::Value::Null => Ok(Value::Null),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Ok(Value::Integer(i)),
+            None => Ok(Value::Real(n.as_f64().unwrap_or_default())),
+        },
+        serde_json::Value::String(s) => Ok(Value::Text(s.clone())),
+        serde_json::Value::Array(bytes) => bytes
+            .iter()
+            .map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+            .collect::<Option<Vec<u8>>>()
+            .map(Value::Blob)
+            .ok_or_else(|| DbError::InvalidData("Invalid blob in trash snapshot".to_string())),
+        other => Err(DbError::InvalidData(format!("Unexpected value in trash snapshot: {}", other))),
+    }
+}
+
+impl TrashRepository {
+    /// Copy the rows of `table` matching `filter` so they can be put back later
+    pub fn snapshot<P: Params>(conn: &Connection, table: &str, filter: &str, params: P) -> DbResult<TableSnapshot> {
+        let mut stmt = conn.prepare(&format!("SELECT * FROM {} WHERE {}", table, filter))?;
+        let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+
+        let mut rows = Vec::new();
+        let mut query = stmt.query(params)?;
+        while let Some(row) = query.next()? {
+            let mut values = Vec::with_capacity(columns.len());
+            for i in 0..columns.len() {
+                values.push(to_json(row.get_ref(i)?));
+            }
+            rows.push(values);
+        }
+
+        Ok(TableSnapshot {
+            table: table.to_string(),
+            columns,
+            rows,
+        })
+    }
+
+    pub fn create(conn: &Connection, entry: &TrashEntry) -> DbResult<()> {
+        let snapshot_json = serde_json::to_string(&entry.tables)
+            .map_err(|e| DbError::InvalidData(e.to_string()))?;
+
+        conn.execute(
+            "INSERT INTO trash (id, user_id, operation, subject_id, snapshot_json, created_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                entry.id,
+                entry.user_id,
+                entry.operation.as_str(),
+                entry.subject_id,
+                snapshot_json,
+                entry.created_at.to_rfc3339(),
+                entry.expires_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Most recent entry the user can still undo, including shared ones
+    pub fn get_latest(conn: &Connection, user_id: &str, now: DateTime<Utc>) -> DbResult<Option<TrashEntry>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, user_id, operation, subject_id, snapshot_json, created_at, expires_at
+             FROM trash WHERE (user_id = ?1 OR user_id IS NULL) AND expires_at > ?2
+             ORDER BY created_at DESC LIMIT 1"
+        )?;
+        let entry = stmt.query_row(params![user_id, now.to_rfc3339()], trash_from_row).optional()?;
+        Ok(entry)
+    }
+
+    /// Put the snapshotted rows back and drop the entry. Rows recreated since
+    /// the operation are overwritten with their snapshotted values.
+    pub fn restore(conn: &Connection, entry: &TrashEntry) -> DbResult<()> {
+        in_savepoint(conn, "trash", || {
+            for snapshot in &entry.tables {
+                if snapshot.rows.is_empty() {
+                    continue;
+                }
+
+                // Updating in place on conflict avoids REPLACE, which would
+                // delete the existing row and cascade to its children
+                let placeholders: Vec<String> = (1..=snapshot.columns.len()).map(|i| format!("?{}", i)).collect();
+                let updates: Vec<String> = snapshot.columns.iter().map(|c| format!("{0} = excluded.{0}", c)).collect();
+                let sql = format!(
+                    "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT DO UPDATE SET {}",
+                    snapshot.table,
+                    snapshot.columns.join(", "),
+                    placeholders.join(", "),
+                    updates.join(", "),
+                );
+
+                let mut stmt = conn.prepare(&sql)?;
+                for row in &snapshot.rows {
+                    let values = row.iter().map(from_json).collect::<DbResult<Vec<Value>>>()?;
+                    stmt.execute(params_from_iter(values))?;
+                }
+            }
+
+            conn.execute("DELETE FROM trash WHERE id = ?1", params![entry.id])?;
+            Ok(())
+        })
+    }
+
+    /// Drop entries whose undo window has closed and return them
+    pub fn purge_expired(conn: &Connection, now: DateTime<Utc>) -> DbResult<Vec<TrashEntry>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, user_id, operation, subject_id, snapshot_json, created_at, expires_at
+             FROM trash WHERE expires_at <= ?1"
+        )?;
+        let entry_iter = stmt.query_map(params![now.to_rfc3339()], trash_from_row)?;
+
+        let mut results = Vec::new();
+        for entry in entry_iter {
+            results.push(entry?);
+        }
+
+        conn.execute("DELETE FROM trash WHERE expires_at <= ?1", params![now.to_rfc3339()])?;
+        Ok(results)
+    }
+
+    /// Clear a user's progress and reset their XP, level and streak, keeping
+    /// a snapshot for `undo_window`
+    pub fn reset_user_progress(conn: &Connection, user_id: &str, undo_window: Duration) -> DbResult<TrashEntry> {
+        in_savepoint(conn, "trash", || {
+            let mut entry = TrashEntry::new(Some(user_id.to_string()), TrashOperation::ResetProgress, undo_window);
+            entry.tables.push(Self::snapshot(conn, "users", "id = ?1", params![user_id])?);
+
+            for table in USER_PROGRESS_TABLES {
+                entry.tables.push(Self::snapshot(conn, table, "user_id = ?1", params![user_id])?);
+                conn.execute(&format!("DELETE FROM {} WHERE user_id = ?1", table), params![user_id])?;
+            }
+            conn.execute(
+                "UPDATE users SET total_xp = 0, current_level = 1, current_streak = 0 WHERE id = ?1",
+                params![user_id],
+            )?;
+
+            Self::create(conn, &entry)?;
+            Ok(entry)
+        })
+    }
+
+    /// Delete a curriculum, and optionally its progress, keeping a snapshot
+    /// for `undo_window`
+    pub fn delete_curriculum(
+        conn: &Connection,
+        curriculum_id: &str,
+        delete_progress: bool,
+        undo_window: Duration,
+    ) -> DbResult<TrashEntry> {
+        in_savepoint(conn, "trash", || {
+            let mut entry = TrashEntry::new(None, TrashOperation::DeleteCurriculum, undo_window)
+                .with_subject(curriculum_id.to_string());
+            entry.tables.push(Self::snapshot(conn, "curricula", "id = ?1", params![curriculum_id])?);
+
+            if delete_progress {
+                for table in CURRICULUM_PROGRESS_TABLES {
+                    entry.tables.push(Self::snapshot(conn, table, "curriculum_id = ?1", params![curriculum_id])?);
+                }
+                CurriculumRepository::delete_with_progress(conn, curriculum_id)?;
+            } else {
+                // Attempts keep their rows but lose the curriculum tag
+                entry.tables.push(Self::snapshot(conn, "quiz_attempts", "curriculum_id = ?1", params![curriculum_id])?);
+                CurriculumRepository::delete(conn, curriculum_id)?;
+            }
+
+            Self::create(conn, &entry)?;
+            Ok(entry)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::{ProgressRepository, QuizRepository, UserRepository};
+    use crate::models::{Curriculum, NodeProgress, QuizAttempt, User};
+
+    fn setup_db() -> Database {
+        let db = Database::new_in_memory().unwrap();
+        let mut user = User::new("test-user".to_string());
+        user.total_xp = 500;
+        user.current_level = 3;
+        UserRepository::create(db.connection(), &user).unwrap();
+        db
+    }
+
+    fn window() -> Duration {
+        Duration::minutes(TrashEntry::DEFAULT_UNDO_WINDOW_MINUTES)
+    }
+
+    #[test]
+    fn test_reset_progress_can_be_undone() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        ProgressRepository::mark_completed(conn, "test-user", None, "node1").unwrap();
+        let attempt = QuizAttempt::new(
+            "test-user".to_string(),
+            "quiz1".to_string(),
+            "node1".to_string(),
+            vec!["ownership".to_string()],
+            90,
+            50,
+        );
+        QuizRepository::create(conn, &attempt).unwrap();
+
+        TrashRepository::reset_user_progress(conn, "test-user", window()).unwrap();
+        assert!(ProgressRepository::get_all_for_user(conn, "test-user").unwrap().is_empty());
+        assert_eq!(UserRepository::get_by_id(conn, "test-user").unwrap().unwrap().total_xp, 0);
+
+        let entry = TrashRepository::get_latest(conn, "test-user", Utc::now()).unwrap().unwrap();
+        assert_eq!(entry.operation, TrashOperation::ResetProgress);
+        TrashRepository::restore(conn, &entry).unwrap();
+
+        let user = UserRepository::get_by_id(conn, "test-user").unwrap().unwrap();
+        assert_eq!(user.total_xp, 500);
+        assert_eq!(user.current_level, 3);
+        assert_eq!(ProgressRepository::get_all_for_user(conn, "test-user").unwrap().len(), 1);
+        assert_eq!(QuizRepository::get_by_id(conn, &attempt.id).unwrap().unwrap().answers, vec!["ownership"]);
+        assert!(TrashRepository::get_latest(conn, "test-user", Utc::now()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_undo_overwrites_progress_made_after_reset() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        ProgressRepository::mark_completed(conn, "test-user", None, "node1").unwrap();
+        let entry = TrashRepository::reset_user_progress(conn, "test-user", window()).unwrap();
+
+        let progress = NodeProgress::new("test-user".to_string(), "node1".to_string());
+        ProgressRepository::create_or_update(conn, &progress).unwrap();
+
+        TrashRepository::restore(conn, &entry).unwrap();
+        let all = ProgressRepository::get_all_for_user(conn, "test-user").unwrap();
+        assert_eq!(all.len(), 1);
+        assert!(all[0].completed_at.is_some());
+    }
+
+    #[test]
+    fn test_delete_curriculum_can_be_undone() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        let curriculum = Curriculum::new("Rust".to_string(), "1.0".to_string(), "curricula/rust".to_string());
+        CurriculumRepository::create(conn, &curriculum).unwrap();
+        let attempt = QuizAttempt::new(
+            "test-user".to_string(),
+            "quiz1".to_string(),
+            "node1".to_string(),
+            vec![],
+            90,
+            50,
+        )
+        .with_curriculum(curriculum.id.clone());
+        QuizRepository::create(conn, &attempt).unwrap();
+
+        TrashRepository::delete_curriculum(conn, &curriculum.id, false, window()).unwrap();
+        assert!(CurriculumRepository::get(conn, &curriculum.id).unwrap().is_none());
+        assert_eq!(QuizRepository::get_by_id(conn, &attempt.id).unwrap().unwrap().curriculum_id, None);
+
+        // Curriculum deletions are shared, so any user can undo them
+        let entry = TrashRepository::get_latest(conn, "someone-else", Utc::now()).unwrap().unwrap();
+        assert_eq!(entry.subject_id.as_deref(), Some(curriculum.id.as_str()));
+        TrashRepository::restore(conn, &entry).unwrap();
+
+        assert_eq!(CurriculumRepository::get(conn, &curriculum.id).unwrap().unwrap().name, "Rust");
+        assert_eq!(
+            QuizRepository::get_by_id(conn, &attempt.id).unwrap().unwrap().curriculum_id,
+            Some(curriculum.id)
+        );
+    }
+
+    #[test]
+    fn test_expired_entries_are_purged() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        let entry = TrashRepository::reset_user_progress(conn, "test-user", Duration::minutes(5)).unwrap();
+        let later = entry.created_at + Duration::minutes(6);
+
+        assert!(TrashRepository::get_latest(conn, "test-user", later).unwrap().is_none());
+        let purged = TrashRepository::purge_expired(conn, later).unwrap();
+        assert_eq!(purged.len(), 1);
+        assert_eq!(purged[0].id, entry.id);
+        assert!(TrashRepository::get_latest(conn, "test-user", Utc::now()).unwrap().is_none());
+    }
+}
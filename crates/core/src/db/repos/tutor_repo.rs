@@ -0,0 +1,134 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use crate::db::error::DbResult;
+use crate::models::{TutorMessage, TutorSession};
+use crate::db::repos::parse_time;
+
+pub struct TutorRepository;
+
+const SESSION_COLUMNS: &str = "id, user_id, curriculum_id, node_id, created_at, updated_at";
+
+const MESSAGE_COLUMNS: &str = "id, session_id, role, content, created_at";
+
+fn session_from_row(row: &Row) -> rusqlite::Result<TutorSession> {
+    Ok(TutorSession {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        curriculum_id: row.get(2)?,
+        node_id: row.get(3)?,
+        created_at: parse_time(4, row.get(4)?)?,
+        updated_at: parse_time(5, row.get(5)?)?,
+    })
+}
+
+fn message_from_row(row: &Row) -> rusqlite::Result<TutorMessage> {
+    let role: String = row.get(2)?;
+    Ok(TutorMessage {
+        id: row.get(0)?,
+        session_id: row.get(1)?,
+        role: role.parse().map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(
+                2,
+                rusqlite::types::Type::Text,
+                Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+            )
+        })?,
+        content: row.get(3)?,
+        created_at: parse_time(4, row.get(4)?)?,
+    })
+}
+
+impl TutorRepository {
+    pub fn create_session(conn: &Connection, session: &TutorSession) -> DbResult<()> {
+        conn.execute(
+            &format!("INSERT INTO tutor_sessions ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6)", SESSION_COLUMNS),
+            params![
+                session.id,
+                session.user_id,
+                session.curriculum_id,
+                session.node_id,
+                session.created_at.to_rfc3339(),
+                session.updated_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_session(conn: &Connection, session_id: &str) -> DbResult<Option<TutorSession>> {
+        let session = conn
+            .query_row(
+                &format!("SELECT {} FROM tutor_sessions WHERE id = ?1", SESSION_COLUMNS),
+                params![session_id],
+                session_from_row,
+            )
+            .optional()?;
+        Ok(session)
+    }
+
+    /// Store a message and mark its session as updated
+    pub fn add_message(conn: &Connection, message: &TutorMessage) -> DbResult<()> {
+        conn.execute(
+            &format!("INSERT INTO tutor_messages ({}) VALUES (?1, ?2, ?3, ?4, ?5)", MESSAGE_COLUMNS),
+            params![
+                message.id,
+                message.session_id,
+                message.role.as_str(),
+                message.content,
+                message.created_at.to_rfc3339(),
+            ],
+        )?;
+        conn.execute(
+            "UPDATE tutor_sessions SET updated_at = ?1 WHERE id = ?2",
+            params![message.created_at.to_rfc3339(), message.session_id],
+        )?;
+        Ok(())
+    }
+
+    /// A session's messages, oldest first
+    pub fn get_messages(conn: &Connection, session_id: &str) -> DbResult<Vec<TutorMessage>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM tutor_messages WHERE session_id = ?1 ORDER BY created_at, rowid",
+            MESSAGE_COLUMNS
+        ))?;
+
+        let message_iter = stmt.query_map(params![session_id], message_from_row)?;
+
+        let mut results = Vec::new();
+        for message in message_iter {
+            results.push(message?);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::{TutorRole, User};
+
+    #[test]
+    fn test_messages_round_trip_in_order() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let session = TutorSession::new("test-user".to_string(), None, "lecture-1".to_string());
+        TutorRepository::create_session(conn, &session).unwrap();
+        assert!(TutorRepository::get_messages(conn, &session.id).unwrap().is_empty());
+
+        let question = TutorMessage::new(session.id.clone(), TutorRole::User, "Why can't I use a moved value?".to_string());
+        let answer = TutorMessage::new(session.id.clone(), TutorRole::Assistant, "Who owns it after the move?".to_string());
+        TutorRepository::add_message(conn, &question).unwrap();
+        TutorRepository::add_message(conn, &answer).unwrap();
+
+        let messages = TutorRepository::get_messages(conn, &session.id).unwrap();
+        let roles: Vec<TutorRole> = messages.iter().map(|m| m.role).collect();
+        assert_eq!(roles, vec![TutorRole::User, TutorRole::Assistant]);
+        assert_eq!(messages[0].content, "Why can't I use a moved value?");
+
+        let stored = TutorRepository::get_session(conn, &session.id).unwrap().unwrap();
+        assert_eq!(stored.updated_at, answer.created_at);
+        assert!(TutorRepository::get_session(conn, "missing").unwrap().is_none());
+    }
+}
@@ -1,15 +1,31 @@
 use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
 use crate::db::error::{DbError, DbResult};
 use crate::models::User;
+use crate::db::repos::parse_time;
 
 pub struct UserRepository;
 
+fn user_from_row(row: &rusqlite::Row) -> rusqlite::Result<User> {
+    Ok(User {
+        id: row.get(0)?,
+        created_at: parse_time(1, row.get(1)?)?,
+        last_activity: parse_time(2, row.get(2)?)?,
+        total_xp: row.get(3)?,
+        current_level: row.get(4)?,
+        current_streak: row.get(5)?,
+        last_streak_date: row.get::<_, Option<String>>(6)?.map(|s| parse_time(6, s)).transpose()?,
+        display_name: row.get(7)?,
+        is_guest: row.get(8)?,
+    })
+}
+
 impl UserRepository {
     pub fn create(conn: &Connection, user: &User) -> DbResult<()> {
         conn.execute(
-            "INSERT INTO users (id, created_at, last_activity, total_xp, current_level, current_streak, last_streak_date)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO users (id, created_at, last_activity, total_xp, current_level, current_streak, last_streak_date, display_name, is_guest)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             params![
                 user.id,
                 user.created_at.to_rfc3339(),
@@ -18,50 +34,127 @@ impl UserRepository {
                 user.current_level,
                 user.current_streak,
                 user.last_streak_date.map(|d| d.to_rfc3339()),
+                user.display_name,
+                user.is_guest,
             ],
         )?;
         Ok(())
     }
 
+    /// Overwrite every stored field of an existing user
+    pub fn update(conn: &Connection, user: &User) -> DbResult<()> {
+        let rows = conn.execute(
+            "UPDATE users SET created_at = ?1, last_activity = ?2, total_xp = ?3, current_level = ?4,
+                current_streak = ?5, last_streak_date = ?6, display_name = ?7
+             WHERE id = ?8",
+            params![
+                user.created_at.to_rfc3339(),
+                user.last_activity.to_rfc3339(),
+                user.total_xp,
+                user.current_level,
+                user.current_streak,
+                user.last_streak_date.map(|d| d.to_rfc3339()),
+                user.display_name,
+                user.id,
+            ],
+        )?;
+
+        if rows == 0 {
+            return Err(DbError::NotFound(format!("User not found: {}", user.id)));
+        }
+        Ok(())
+    }
+
     pub fn get_by_id(conn: &Connection, user_id: &str) -> DbResult<Option<User>> {
         let mut stmt = conn.prepare(
-            "SELECT id, created_at, last_activity, total_xp, current_level, current_streak, last_streak_date
+            "SELECT id, created_at, last_activity, total_xp, current_level, current_streak, last_streak_date, display_name, is_guest
              FROM users WHERE id = ?1"
         )?;
 
-        let user = stmt.query_row(params![user_id], |row| {
-            Ok(User {
-                id: row.get(0)?,
-                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(1)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
-                last_activity: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e)))?
-                    .with_timezone(&Utc),
-                total_xp: row.get(3)?,
-                current_level: row.get(4)?,
-                current_streak: row.get(5)?,
-                last_streak_date: row.get::<_, Option<String>>(6)?
-                    .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                    .map(|dt| dt.with_timezone(&Utc)),
-            })
-        }).optional()?;
+        let user = stmt.query_row(params![user_id], user_from_row).optional()?;
 
         Ok(user)
     }
 
+    /// Get all saved local profiles, most recently active first. Guest
+    /// profiles are left out.
+    pub fn get_all(conn: &Connection) -> DbResult<Vec<User>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, created_at, last_activity, total_xp, current_level, current_streak, last_streak_date, display_name, is_guest
+             FROM users WHERE is_guest = 0 ORDER BY last_activity DESC"
+        )?;
+
+        let user_iter = stmt.query_map([], user_from_row)?;
+
+        let mut results = Vec::new();
+        for user in user_iter {
+            results.push(user?);
+        }
+        Ok(results)
+    }
+
+    pub fn update_display_name(conn: &Connection, user_id: &str, display_name: &str) -> DbResult<()> {
+        let rows = conn.execute(
+            "UPDATE users SET display_name = ?1 WHERE id = ?2",
+            params![display_name, user_id],
+        )?;
+
+        if rows == 0 {
+            return Err(DbError::NotFound(format!("User not found: {}", user_id)));
+        }
+        Ok(())
+    }
+
+    /// Mark a profile as the most recently used one
+    pub fn touch(conn: &Connection, user_id: &str) -> DbResult<()> {
+        let rows = conn.execute(
+            "UPDATE users SET last_activity = ?1 WHERE id = ?2",
+            params![Utc::now().to_rfc3339(), user_id],
+        )?;
+
+        if rows == 0 {
+            return Err(DbError::NotFound(format!("User not found: {}", user_id)));
+        }
+        Ok(())
+    }
+
     pub fn update_xp(conn: &Connection, user_id: &str, xp_delta: i32) -> DbResult<()> {
+        let now = Utc::now().to_rfc3339();
         let rows = conn.execute(
             "UPDATE users SET total_xp = total_xp + ?1, last_activity = ?2 WHERE id = ?3",
-            params![xp_delta, Utc::now().to_rfc3339(), user_id],
+            params![xp_delta, now, user_id],
         )?;
 
         if rows == 0 {
             return Err(DbError::NotFound(format!("User not found: {}", user_id)));
         }
+
+        // Log gains so XP can be totalled over a time window
+        if xp_delta > 0 {
+            conn.execute(
+                "INSERT INTO xp_log (user_id, amount, earned_at) VALUES (?1, ?2, ?3)",
+                params![user_id, xp_delta, now],
+            )?;
+        }
         Ok(())
     }
 
+    /// Total XP gained in `[from, to)`
+    pub fn get_xp_earned_between(
+        conn: &Connection,
+        user_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> DbResult<i32> {
+        let total: i32 = conn.query_row(
+            "SELECT COALESCE(SUM(amount), 0) FROM xp_log
+             WHERE user_id = ?1 AND earned_at >= ?2 AND earned_at < ?3",
+            params![user_id, from.to_rfc3339(), to.to_rfc3339()],
+            |row| row.get(0),
+        )?;
+        Ok(total)
+    }
+
     pub fn update_level(conn: &Connection, user_id: &str, new_level: i32) -> DbResult<()> {
         let rows = conn.execute(
             "UPDATE users SET current_level = ?1, last_activity = ?2 WHERE id = ?3",
@@ -74,6 +167,19 @@ impl UserRepository {
         Ok(())
     }
 
+    /// Overwrite XP and level directly, bypassing the XP log (used for corrections)
+    pub fn set_xp_and_level(conn: &Connection, user_id: &str, total_xp: i32, level: i32) -> DbResult<()> {
+        let rows = conn.execute(
+            "UPDATE users SET total_xp = ?1, current_level = ?2 WHERE id = ?3",
+            params![total_xp, level, user_id],
+        )?;
+
+        if rows == 0 {
+            return Err(DbError::NotFound(format!("User not found: {}", user_id)));
+        }
+        Ok(())
+    }
+
     pub fn update_streak(conn: &Connection, user_id: &str, new_streak: i32, streak_date: DateTime<Utc>) -> DbResult<()> {
         let rows = conn.execute(
             "UPDATE users SET current_streak = ?1, last_streak_date = ?2, last_activity = ?3 WHERE id = ?4",
@@ -94,6 +200,53 @@ impl UserRepository {
         }
         Ok(())
     }
+
+    /// Discard every guest profile along with its data. Returns how many
+    /// were removed.
+    pub fn delete_guests(conn: &Connection) -> DbResult<usize> {
+        Ok(conn.execute("DELETE FROM users WHERE is_guest = 1", [])?)
+    }
+
+    pub fn get_setting(conn: &Connection, user_id: &str, key: &str) -> DbResult<Option<String>> {
+        let value = conn
+            .query_row(
+                "SELECT value FROM profile_settings WHERE user_id = ?1 AND key = ?2",
+                params![user_id, key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value)
+    }
+
+    /// Every setting stored for a profile
+    pub fn get_settings(conn: &Connection, user_id: &str) -> DbResult<HashMap<String, String>> {
+        let mut stmt = conn.prepare("SELECT key, value FROM profile_settings WHERE user_id = ?1")?;
+        let setting_iter = stmt.query_map(params![user_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        let mut results = HashMap::new();
+        for setting in setting_iter {
+            let (key, value) = setting?;
+            results.insert(key, value);
+        }
+        Ok(results)
+    }
+
+    pub fn set_setting(conn: &Connection, user_id: &str, key: &str, value: &str) -> DbResult<()> {
+        conn.execute(
+            "INSERT INTO profile_settings (user_id, key, value) VALUES (?1, ?2, ?3)
+             ON CONFLICT(user_id, key) DO UPDATE SET value = excluded.value",
+            params![user_id, key, value],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_setting(conn: &Connection, user_id: &str, key: &str) -> DbResult<()> {
+        conn.execute(
+            "DELETE FROM profile_settings WHERE user_id = ?1 AND key = ?2",
+            params![user_id, key],
+        )?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -192,4 +345,82 @@ mod tests {
         let result = UserRepository::get_by_id(conn, "test-user").unwrap();
         assert!(result.is_none());
     }
+
+    #[test]
+    fn test_profiles_and_display_name() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        UserRepository::create(conn, &User::with_display_name("a".to_string(), "Ada".to_string())).unwrap();
+        UserRepository::create(conn, &User::new("b".to_string())).unwrap();
+        UserRepository::touch(conn, "b").unwrap();
+
+        let profiles = UserRepository::get_all(conn).unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].id, "b");
+        assert_eq!(profiles[1].display_name.as_deref(), Some("Ada"));
+
+        UserRepository::update_display_name(conn, "b", "Grace").unwrap();
+        let renamed = UserRepository::get_by_id(conn, "b").unwrap().unwrap();
+        assert_eq!(renamed.display_name.as_deref(), Some("Grace"));
+    }
+
+    #[test]
+    fn test_xp_earned_between() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+        let before = Utc::now() - chrono::Duration::seconds(1);
+
+        UserRepository::update_xp(conn, "test-user", 40).unwrap();
+        UserRepository::update_xp(conn, "test-user", 60).unwrap();
+        // Deductions are not counted as earned XP
+        UserRepository::update_xp(conn, "test-user", -10).unwrap();
+
+        let after = Utc::now() + chrono::Duration::seconds(1);
+        assert_eq!(UserRepository::get_xp_earned_between(conn, "test-user", before, after).unwrap(), 100);
+        assert_eq!(UserRepository::get_xp_earned_between(conn, "test-user", after, after + chrono::Duration::days(1)).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_guests_are_not_saved_profiles() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        UserRepository::create(conn, &User::with_display_name("a".to_string(), "Ada".to_string())).unwrap();
+        UserRepository::create(conn, &User::guest("guest".to_string())).unwrap();
+        UserRepository::update_xp(conn, "guest", 30).unwrap();
+
+        let guest = UserRepository::get_by_id(conn, "guest").unwrap().unwrap();
+        assert!(guest.is_guest);
+        assert_eq!(guest.profile_name(), "Guest");
+        let profiles = UserRepository::get_all(conn).unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].id, "a");
+
+        assert_eq!(UserRepository::delete_guests(conn).unwrap(), 1);
+        assert!(UserRepository::get_by_id(conn, "guest").unwrap().is_none());
+        let xp_rows: i32 = conn
+            .query_row("SELECT COUNT(*) FROM xp_log WHERE user_id = 'guest'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(xp_rows, 0);
+    }
+
+    #[test]
+    fn test_profile_settings() {
+        let db = setup_db();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("a".to_string())).unwrap();
+        UserRepository::create(conn, &User::new("b".to_string())).unwrap();
+
+        assert!(UserRepository::get_setting(conn, "a", "theme").unwrap().is_none());
+        UserRepository::set_setting(conn, "a", "theme", "dark").unwrap();
+        UserRepository::set_setting(conn, "a", "theme", "light").unwrap();
+        UserRepository::set_setting(conn, "b", "theme", "dark").unwrap();
+
+        assert_eq!(UserRepository::get_setting(conn, "a", "theme").unwrap().as_deref(), Some("light"));
+        let settings = UserRepository::get_settings(conn, "b").unwrap();
+        assert_eq!(settings.get("theme").map(String::as_str), Some("dark"));
+    }
 }
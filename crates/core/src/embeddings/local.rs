@@ -0,0 +1,71 @@
+//! Offline embedding model
+//!
+//! Words and word pairs are hashed into a fixed number of buckets and the
+//! counts normalised, so texts that share vocabulary point the same way.
+//! It knows nothing of synonyms, but it is deterministic and good enough to
+//! match a quiz question to the lecture it was written from.
+
+/// Name stored with vectors from this model
+pub const LOCAL_MODEL: &str = "local-hashed-512";
+
+const DIMENSIONS: usize = 512;
+
+/// Words too common to say anything about a text
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "are", "but", "not", "you", "your", "with", "this", "that", "from", "have", "has", "was",
+    "can", "will", "what", "which", "when", "how", "its", "into", "use", "used", "using", "there", "their", "they",
+    "then", "than", "also", "each", "all", "any", "one", "does", "let",
+];
+
+/// FNV-1a, so vectors stay the same across Rust releases
+fn hash(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .map(str::to_lowercase)
+        .filter(|w| w.len() > 2 && !STOP_WORDS.contains(&w.as_str()))
+        .collect()
+}
+
+pub fn embed_local(text: &str) -> Vec<f32> {
+    let mut vector = vec![0f32; DIMENSIONS];
+    let words = words(text);
+
+    let mut add = |feature: &str, weight: f32| {
+        let h = hash(feature);
+        // The sign bit spreads collisions around zero instead of piling up
+        let sign = if h >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[(h % DIMENSIONS as u64) as usize] += sign * weight;
+    };
+    for word in &words {
+        add(word, 1.0);
+    }
+    for pair in words.windows(2) {
+        add(&format!("{} {}", pair[0], pair[1]), 0.5);
+    }
+
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embeddings::cosine_similarity;
+
+    #[test]
+    fn test_shared_vocabulary_is_closer() {
+        let borrowing = embed_local("Borrowing lets a function use a value without taking ownership of it.");
+        let question = embed_local("What does borrowing a value avoid taking?");
+        let traits = embed_local("Traits describe shared behaviour that types implement.");
+
+        assert!(cosine_similarity(&borrowing, &question) > cosine_similarity(&borrowing, &traits));
+        assert_eq!(embed_local("Ownership rules"), embed_local("ownership RULES"));
+        assert_eq!(embed_local("").len(), DIMENSIONS);
+    }
+}
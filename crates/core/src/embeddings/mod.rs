@@ -0,0 +1,13 @@
+//! Related-content suggestions
+//!
+//! Lectures and quiz questions are embedded when a curriculum is imported,
+//! either by the LLM provider or by `local`, a hashed bag-of-words model that
+//! needs no network. `related` ranks a curriculum's nodes by cosine
+//! similarity: to another node, or to everything tagged with a skill, so the
+//! app can point a learner whose mastery slips back at the right lectures.
+
+pub mod local;
+pub mod related;
+
+pub use local::{embed_local, LOCAL_MODEL};
+pub use related::{cosine_similarity, find_content_for_skill, find_related, RelatedContent};
@@ -0,0 +1,174 @@
+//! Similarity search over a curriculum's embeddings
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::db::error::DbResult;
+use crate::db::repos::EmbeddingRepository;
+use crate::models::{ContentEmbedding, EmbeddedKind};
+
+/// A node suggested for review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedContent {
+    pub node_id: String,
+    pub kind: EmbeddedKind,
+    /// Cosine similarity of the node's closest item, -1 to 1
+    pub score: f64,
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0f64, 0f64, 0f64);
+    for (x, y) in a.iter().zip(b) {
+        dot += (*x as f64) * (*y as f64);
+        norm_a += (*x as f64) * (*x as f64);
+        norm_b += (*y as f64) * (*y as f64);
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Mean of the vectors, or `None` if there are none
+fn centroid<'a>(vectors: impl Iterator<Item = &'a [f32]>) -> Option<Vec<f32>> {
+    let mut sum: Option<Vec<f32>> = None;
+    let mut count = 0;
+    for vector in vectors {
+        let sum = sum.get_or_insert_with(|| vec![0.0; vector.len()]);
+        if sum.len() != vector.len() {
+            continue;
+        }
+        sum.iter_mut().zip(vector).for_each(|(s, v)| *s += v);
+        count += 1;
+    }
+    sum.map(|s| s.into_iter().map(|v| v / count as f32).collect())
+}
+
+/// Score each node by its item closest to `query`, best first. Items
+/// embedded by another model than the query's aren't comparable and are left
+/// out.
+fn rank_nodes<'a>(
+    items: impl Iterator<Item = &'a ContentEmbedding>,
+    query: &[f32],
+    model: &str,
+    limit: usize,
+) -> Vec<RelatedContent> {
+    let mut best: HashMap<&str, RelatedContent> = HashMap::new();
+    for item in items.filter(|item| item.model == model) {
+        let score = cosine_similarity(&item.vector, query);
+        let entry = best.entry(item.node_id.as_str()).or_insert_with(|| RelatedContent {
+            node_id: item.node_id.clone(),
+            kind: item.kind,
+            score,
+        });
+        if score > entry.score {
+            entry.score = score;
+        }
+    }
+
+    let mut ranked: Vec<RelatedContent> = best.into_values().collect();
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.node_id.cmp(&b.node_id)));
+    ranked.truncate(limit);
+    ranked
+}
+
+/// Other lectures and quizzes most like a node. Empty if the node isn't
+/// indexed.
+pub fn find_related(
+    conn: &Connection,
+    curriculum_id: &str,
+    node_id: &str,
+    limit: usize,
+) -> DbResult<Vec<RelatedContent>> {
+    let index = EmbeddingRepository::get_for_curriculum(conn, curriculum_id)?;
+    let own: Vec<&ContentEmbedding> = index.iter().filter(|item| item.node_id == node_id).collect();
+    let Some(query) = centroid(own.iter().map(|item| item.vector.as_slice())) else {
+        return Ok(Vec::new());
+    };
+
+    let others = index.iter().filter(|item| item.node_id != node_id);
+    Ok(rank_nodes(others, &query, &own[0].model, limit))
+}
+
+/// Lectures that best cover a skill, judged against everything tagged with
+/// it. Empty if nothing is tagged with the skill.
+pub fn find_content_for_skill(
+    conn: &Connection,
+    curriculum_id: &str,
+    skill_id: &str,
+    limit: usize,
+) -> DbResult<Vec<RelatedContent>> {
+    let index = EmbeddingRepository::get_for_curriculum(conn, curriculum_id)?;
+    let tagged: Vec<&ContentEmbedding> = index.iter().filter(|item| item.skills.iter().any(|s| s == skill_id)).collect();
+    let Some(query) = centroid(tagged.iter().map(|item| item.vector.as_slice())) else {
+        return Ok(Vec::new());
+    };
+
+    let lectures = index.iter().filter(|item| item.kind == EmbeddedKind::Lecture);
+    Ok(rank_nodes(lectures, &query, &tagged[0].model, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::CurriculumRepository;
+    use crate::embeddings::{embed_local, LOCAL_MODEL};
+    use crate::models::Curriculum;
+
+    fn item(curriculum_id: &str, item_id: &str, kind: EmbeddedKind, skill: &str, text: &str) -> ContentEmbedding {
+        let node_id = item_id.split('/').next().unwrap();
+        ContentEmbedding::new(
+            curriculum_id.to_string(),
+            item_id.to_string(),
+            node_id.to_string(),
+            kind,
+            vec![skill.to_string()],
+            LOCAL_MODEL.to_string(),
+            embed_local(text),
+        )
+    }
+
+    fn indexed_db() -> (Database, String) {
+        let db = Database::new_in_memory().unwrap();
+        let curriculum = Curriculum::new("Rust".to_string(), "1.0".to_string(), "curricula/rust".to_string());
+        CurriculumRepository::create(db.connection(), &curriculum).unwrap();
+
+        let id = curriculum.id.clone();
+        let index = vec![
+            item(&id, "ownership", EmbeddedKind::Lecture, "ownership", "Ownership moves values; a moved value can't be used again."),
+            item(&id, "borrowing", EmbeddedKind::Lecture, "borrowing", "Borrowing a value with references avoids moving ownership."),
+            item(&id, "traits", EmbeddedKind::Lecture, "traits", "Traits define shared behaviour; types implement trait methods."),
+            item(&id, "quiz-1/q1", EmbeddedKind::QuizQuestion, "ownership", "Why can't a moved value be used again?"),
+            item(&id, "quiz-1/q2", EmbeddedKind::QuizQuestion, "ownership", "Who has ownership after a value moves?"),
+        ];
+        EmbeddingRepository::replace_for_curriculum(db.connection(), &id, &index).unwrap();
+        (db, id)
+    }
+
+    #[test]
+    fn test_related_nodes_rank_by_similarity() {
+        let (db, curriculum_id) = indexed_db();
+        let related = find_related(db.connection(), &curriculum_id, "quiz-1", 2).unwrap();
+
+        assert_eq!(related.len(), 2);
+        assert_eq!(related[0].node_id, "ownership");
+        assert!(related[0].score > related[1].score);
+        assert!(related.iter().all(|r| r.node_id != "quiz-1"));
+        assert!(find_related(db.connection(), &curriculum_id, "missing", 2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_content_for_skill_suggests_lectures() {
+        let (db, curriculum_id) = indexed_db();
+        let lectures = find_content_for_skill(db.connection(), &curriculum_id, "ownership", 2).unwrap();
+
+        assert_eq!(lectures[0].node_id, "ownership");
+        assert!(lectures.iter().all(|r| r.kind == EmbeddedKind::Lecture));
+        assert!(find_content_for_skill(db.connection(), &curriculum_id, "macros", 2).unwrap().is_empty());
+    }
+}
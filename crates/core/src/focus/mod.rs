@@ -0,0 +1,41 @@
+//! Focus timer
+//!
+//! `timer` models work and break blocks: pausing, resuming and completing
+//! them against the wall clock, so a block started before the app closed is
+//! still tracked after it reopens. `tracker` runs blocks for a user against
+//! the database, opening a study session for work blocks and awarding XP when
+//! one completes.
+
+pub mod timer;
+pub mod tracker;
+
+pub use timer::{FocusBlock, FocusBlockKind, FocusSettings, FocusStatus, FOCUS_XP_PER_MINUTE};
+pub use tracker::{
+    cancel_block, get_state, pause_block, resume_block, save_settings, start_block, FocusCompletion, FocusState,
+};
+
+use thiserror::Error;
+use crate::db::error::DbError;
+
+#[derive(Error, Debug)]
+pub enum FocusError {
+    #[error(transparent)]
+    Db(#[from] DbError),
+
+    #[error("A focus block is already running")]
+    AlreadyRunning,
+
+    #[error("No focus block is running")]
+    NoActiveBlock,
+
+    #[error("The focus block is not running")]
+    NotRunning,
+
+    #[error("The focus block is not paused")]
+    NotPaused,
+
+    #[error("Invalid focus settings: {0}")]
+    InvalidSettings(String),
+}
+
+pub type FocusResult<T> = Result<T, FocusError>;
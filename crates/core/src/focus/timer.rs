@@ -0,0 +1,297 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use uuid::Uuid;
+use crate::focus::{FocusError, FocusResult};
+
+/// XP for each minute of a completed work block
+pub const FOCUS_XP_PER_MINUTE: i32 = 1;
+
+/// Work and break lengths, per user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct FocusSettings {
+    pub work_minutes: u32,
+    pub short_break_minutes: u32,
+    pub long_break_minutes: u32,
+    /// Work blocks completed before a long break instead of a short one
+    pub blocks_before_long_break: u32,
+}
+
+impl Default for FocusSettings {
+    fn default() -> Self {
+        Self {
+            work_minutes: 25,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            blocks_before_long_break: 4,
+        }
+    }
+}
+
+impl FocusSettings {
+    pub fn validate(&self) -> FocusResult<()> {
+        let lengths = [
+            ("work", self.work_minutes),
+            ("short break", self.short_break_minutes),
+            ("long break", self.long_break_minutes),
+        ];
+        for (name, minutes) in lengths {
+            if !(1..=180).contains(&minutes) {
+                return Err(FocusError::InvalidSettings(format!("{} length must be 1-180 minutes", name)));
+            }
+        }
+        if self.blocks_before_long_break == 0 {
+            return Err(FocusError::InvalidSettings("blocks before a long break must be at least 1".to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn planned_seconds(&self, kind: FocusBlockKind) -> i64 {
+        let minutes = match kind {
+            FocusBlockKind::Work => self.work_minutes,
+            FocusBlockKind::ShortBreak => self.short_break_minutes,
+            FocusBlockKind::LongBreak => self.long_break_minutes,
+        };
+        i64::from(minutes) * 60
+    }
+
+    /// What to run after `last`, given how many work blocks were completed
+    /// today including it
+    pub fn next_kind(&self, last: Option<FocusBlockKind>, completed_work_blocks: u32) -> FocusBlockKind {
+        match last {
+            Some(FocusBlockKind::Work) if completed_work_blocks.is_multiple_of(self.blocks_before_long_break) => {
+                FocusBlockKind::LongBreak
+            }
+            Some(FocusBlockKind::Work) => FocusBlockKind::ShortBreak,
+            _ => FocusBlockKind::Work,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum FocusBlockKind {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+impl FocusBlockKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FocusBlockKind::Work => "Work",
+            FocusBlockKind::ShortBreak => "ShortBreak",
+            FocusBlockKind::LongBreak => "LongBreak",
+        }
+    }
+}
+
+impl FromStr for FocusBlockKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Work" => Ok(FocusBlockKind::Work),
+            "ShortBreak" => Ok(FocusBlockKind::ShortBreak),
+            "LongBreak" => Ok(FocusBlockKind::LongBreak),
+            other => Err(format!("Unknown focus block kind: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum FocusStatus {
+    Running,
+    Paused,
+    Completed,
+    Cancelled,
+}
+
+impl FocusStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FocusStatus::Running => "Running",
+            FocusStatus::Paused => "Paused",
+            FocusStatus::Completed => "Completed",
+            FocusStatus::Cancelled => "Cancelled",
+        }
+    }
+}
+
+impl FromStr for FocusStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Running" => Ok(FocusStatus::Running),
+            "Paused" => Ok(FocusStatus::Paused),
+            "Completed" => Ok(FocusStatus::Completed),
+            "Cancelled" => Ok(FocusStatus::Cancelled),
+            other => Err(format!("Unknown focus status: {}", other)),
+        }
+    }
+}
+
+/// One work or break interval. A running block keeps counting while the
+/// app is closed, like a kitchen timer; time spent paused doesn't count.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct FocusBlock {
+    pub id: String,
+    pub user_id: String,
+    /// Study session the block was run in
+    pub session_id: Option<String>,
+    pub kind: FocusBlockKind,
+    pub planned_seconds: i64,
+    pub started_at: DateTime<Utc>,
+    pub paused_at: Option<DateTime<Utc>>,
+    /// Total time spent paused before the current pause, if any
+    pub paused_seconds: i64,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub status: FocusStatus,
+    pub xp_awarded: i32,
+}
+
+impl FocusBlock {
+    pub fn new(
+        user_id: String,
+        session_id: Option<String>,
+        kind: FocusBlockKind,
+        planned_seconds: i64,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            session_id,
+            kind,
+            planned_seconds,
+            started_at: now,
+            paused_at: None,
+            paused_seconds: 0,
+            ended_at: None,
+            status: FocusStatus::Running,
+            xp_awarded: 0,
+        }
+    }
+
+    /// Running or paused
+    pub fn is_open(&self) -> bool {
+        matches!(self.status, FocusStatus::Running | FocusStatus::Paused)
+    }
+
+    /// Time counted towards the block so far, capped at its planned length
+    pub fn elapsed_seconds(&self, now: DateTime<Utc>) -> i64 {
+        let until = self.ended_at.or(self.paused_at).unwrap_or(now);
+        let elapsed = (until - self.started_at).num_seconds() - self.paused_seconds;
+        elapsed.clamp(0, self.planned_seconds)
+    }
+
+    pub fn remaining_seconds(&self, now: DateTime<Utc>) -> i64 {
+        self.planned_seconds - self.elapsed_seconds(now)
+    }
+
+    pub fn pause(&mut self, now: DateTime<Utc>) -> FocusResult<()> {
+        if self.status != FocusStatus::Running {
+            return Err(FocusError::NotRunning);
+        }
+        self.status = FocusStatus::Paused;
+        self.paused_at = Some(now);
+        Ok(())
+    }
+
+    pub fn resume(&mut self, now: DateTime<Utc>) -> FocusResult<()> {
+        let paused_at = match (self.status, self.paused_at) {
+            (FocusStatus::Paused, Some(paused_at)) => paused_at,
+            _ => return Err(FocusError::NotPaused),
+        };
+        self.paused_seconds += (now - paused_at).num_seconds().max(0);
+        self.paused_at = None;
+        self.status = FocusStatus::Running;
+        Ok(())
+    }
+
+    pub fn cancel(&mut self, now: DateTime<Utc>) -> FocusResult<()> {
+        if !self.is_open() {
+            return Err(FocusError::NoActiveBlock);
+        }
+        // A paused block stopped counting when it was paused
+        self.ended_at = Some(self.paused_at.unwrap_or(now));
+        self.status = FocusStatus::Cancelled;
+        Ok(())
+    }
+
+    /// Complete a running block whose time is up, ending it when the time
+    /// ran out rather than now. Returns whether it completed.
+    pub fn complete_if_due(&mut self, now: DateTime<Utc>) -> bool {
+        if self.status != FocusStatus::Running || self.remaining_seconds(now) > 0 {
+            return false;
+        }
+        self.ended_at = Some(self.started_at + Duration::seconds(self.planned_seconds + self.paused_seconds));
+        self.status = FocusStatus::Completed;
+        true
+    }
+
+    /// XP a completed block earns. Breaks earn nothing.
+    pub fn earned_xp(&self) -> i32 {
+        match (self.status, self.kind) {
+            (FocusStatus::Completed, FocusBlockKind::Work) => (self.planned_seconds / 60) as i32 * FOCUS_XP_PER_MINUTE,
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn work_block(now: DateTime<Utc>) -> FocusBlock {
+        FocusBlock::new("user1".to_string(), None, FocusBlockKind::Work, 25 * 60, now)
+    }
+
+    #[test]
+    fn test_pause_time_is_excluded() {
+        let start = Utc::now();
+        let mut block = work_block(start);
+
+        block.pause(start + Duration::minutes(10)).unwrap();
+        assert_eq!(block.elapsed_seconds(start + Duration::minutes(40)), 10 * 60);
+        assert!(matches!(block.pause(start + Duration::minutes(40)), Err(FocusError::NotRunning)));
+
+        block.resume(start + Duration::minutes(40)).unwrap();
+        assert_eq!(block.remaining_seconds(start + Duration::minutes(45)), 10 * 60);
+        assert!(!block.complete_if_due(start + Duration::minutes(54)));
+
+        // Completion is dated when the time ran out, not when it was noticed
+        assert!(block.complete_if_due(start + Duration::hours(3)));
+        assert_eq!(block.ended_at, Some(start + Duration::minutes(55)));
+        assert_eq!(block.earned_xp(), 25 * FOCUS_XP_PER_MINUTE);
+    }
+
+    #[test]
+    fn test_cancelled_and_break_blocks_earn_nothing() {
+        let start = Utc::now();
+        let mut block = work_block(start);
+        block.cancel(start + Duration::minutes(20)).unwrap();
+        assert_eq!(block.earned_xp(), 0);
+        assert!(!block.complete_if_due(start + Duration::hours(1)));
+
+        let mut rest = FocusBlock::new("user1".to_string(), None, FocusBlockKind::ShortBreak, 300, start);
+        assert!(rest.complete_if_due(start + Duration::minutes(5)));
+        assert_eq!(rest.earned_xp(), 0);
+    }
+
+    #[test]
+    fn test_long_break_after_every_fourth_work_block() {
+        let settings = FocusSettings::default();
+        assert_eq!(settings.next_kind(None, 0), FocusBlockKind::Work);
+        assert_eq!(settings.next_kind(Some(FocusBlockKind::Work), 3), FocusBlockKind::ShortBreak);
+        assert_eq!(settings.next_kind(Some(FocusBlockKind::Work), 4), FocusBlockKind::LongBreak);
+        assert_eq!(settings.next_kind(Some(FocusBlockKind::LongBreak), 4), FocusBlockKind::Work);
+
+        let invalid = FocusSettings { work_minutes: 0, ..settings };
+        assert!(matches!(invalid.validate(), Err(FocusError::InvalidSettings(_))));
+    }
+}
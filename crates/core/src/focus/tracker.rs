@@ -0,0 +1,265 @@
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use crate::db::error::{DbError, DbResult};
+use crate::db::in_savepoint;
+use crate::db::repos::{FocusRepository, SessionRepository, UserRepository};
+use crate::focus::{FocusBlock, FocusBlockKind, FocusError, FocusResult, FocusSettings};
+use crate::gamification::calculate_level;
+use crate::models::SessionHistory;
+
+/// A work block that completed since the state was last checked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct FocusCompletion {
+    pub block: FocusBlock,
+    pub xp_awarded: i32,
+    /// The user's XP before the award, for level-up detection
+    pub previous_total_xp: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct FocusState {
+    pub settings: FocusSettings,
+    /// The running or paused block, if any
+    pub block: Option<FocusBlock>,
+    pub remaining_seconds: i64,
+    /// What starting a block without choosing a kind would run
+    pub next_kind: FocusBlockKind,
+    pub completed_work_blocks_today: u32,
+    pub completed: Option<FocusCompletion>,
+}
+
+fn start_of_day(now: DateTime<Utc>) -> DateTime<Utc> {
+    now.date_naive().and_hms_opt(0, 0, 0).expect("midnight is a valid time").and_utc()
+}
+
+/// Complete the user's open block if its time ran out, awarding XP for a
+/// work block. Returns the block if it's still open.
+fn settle(
+    conn: &Connection,
+    user_id: &str,
+    now: DateTime<Utc>,
+) -> DbResult<(Option<FocusBlock>, Option<FocusCompletion>)> {
+    let Some(mut block) = FocusRepository::get_open_block(conn, user_id)? else {
+        return Ok((None, None));
+    };
+    if !block.complete_if_due(now) {
+        return Ok((Some(block), None));
+    }
+
+    let completion = in_savepoint(conn, "focus_complete", || {
+        block.xp_awarded = block.earned_xp();
+        FocusRepository::update_block(conn, &block)?;
+
+        let user = UserRepository::get_by_id(conn, user_id)?
+            .ok_or_else(|| DbError::NotFound("User not found".to_string()))?;
+        if block.xp_awarded > 0 {
+            UserRepository::update_xp(conn, user_id, block.xp_awarded)?;
+            let new_level = calculate_level(user.total_xp + block.xp_awarded);
+            UserRepository::update_level(conn, user_id, new_level as i32)?;
+
+            if let Some(session_id) = &block.session_id {
+                if let Some(mut session) = SessionRepository::get_by_id(conn, session_id)? {
+                    session.total_xp_earned += block.xp_awarded;
+                    SessionRepository::update(conn, &session)?;
+                }
+            }
+        }
+
+        Ok(FocusCompletion {
+            xp_awarded: block.xp_awarded,
+            previous_total_xp: user.total_xp,
+            block: block.clone(),
+        })
+    })?;
+
+    Ok((None, Some(completion)))
+}
+
+fn build_state(
+    conn: &Connection,
+    user_id: &str,
+    block: Option<FocusBlock>,
+    completed: Option<FocusCompletion>,
+    now: DateTime<Utc>,
+) -> DbResult<FocusState> {
+    let settings = FocusRepository::get_settings(conn, user_id)?;
+    let completed_work_blocks_today = FocusRepository::count_completed_work_since(conn, user_id, start_of_day(now))?;
+    let last = FocusRepository::get_last_finished(conn, user_id)?;
+    // A cancelled work block doesn't earn a break
+    let last_kind = last
+        .filter(|b| b.status == crate::focus::FocusStatus::Completed)
+        .map(|b| b.kind);
+
+    Ok(FocusState {
+        settings,
+        remaining_seconds: block.as_ref().map(|b| b.remaining_seconds(now)).unwrap_or(0),
+        next_kind: settings.next_kind(last_kind, completed_work_blocks_today),
+        completed_work_blocks_today,
+        block,
+        completed,
+    })
+}
+
+/// The user's timer, completing the open block first if its time ran out
+pub fn get_state(conn: &Connection, user_id: &str, now: DateTime<Utc>) -> FocusResult<FocusState> {
+    let (block, completed) = settle(conn, user_id, now)?;
+    Ok(build_state(conn, user_id, block, completed, now)?)
+}
+
+/// Start a block of `kind`, or of whatever comes next in the cycle. A work
+/// block runs inside the user's open study session, starting one if needed.
+pub fn start_block(
+    conn: &Connection,
+    user_id: &str,
+    kind: Option<FocusBlockKind>,
+    now: DateTime<Utc>,
+) -> FocusResult<FocusState> {
+    let (open, completed) = settle(conn, user_id, now)?;
+    if open.is_some() {
+        return Err(FocusError::AlreadyRunning);
+    }
+
+    let kind = match kind {
+        Some(kind) => kind,
+        None => build_state(conn, user_id, None, None, now)?.next_kind,
+    };
+    let settings = FocusRepository::get_settings(conn, user_id)?;
+
+    let block = in_savepoint(conn, "focus_start", || {
+        let session_id = if kind == FocusBlockKind::Work {
+            let session = match SessionRepository::get_active_session(conn, user_id)? {
+                Some(session) => session,
+                None => {
+                    let session = SessionHistory::new(user_id.to_string());
+                    SessionRepository::create(conn, &session)?;
+                    session
+                }
+            };
+            Some(session.id)
+        } else {
+            None
+        };
+
+        let block = FocusBlock::new(user_id.to_string(), session_id, kind, settings.planned_seconds(kind), now);
+        FocusRepository::create_block(conn, &block)?;
+        Ok(block)
+    })?;
+
+    Ok(build_state(conn, user_id, Some(block), completed, now)?)
+}
+
+/// Apply `change` to the open block. If the block's time had already run
+/// out it completes instead, and the returned state reports that.
+fn change_open_block(
+    conn: &Connection,
+    user_id: &str,
+    now: DateTime<Utc>,
+    change: impl FnOnce(&mut FocusBlock) -> FocusResult<()>,
+) -> FocusResult<FocusState> {
+    let (open, completed) = settle(conn, user_id, now)?;
+    if completed.is_some() {
+        return Ok(build_state(conn, user_id, None, completed, now)?);
+    }
+
+    let mut block = open.ok_or(FocusError::NoActiveBlock)?;
+    change(&mut block)?;
+    FocusRepository::update_block(conn, &block)?;
+
+    let block = Some(block).filter(FocusBlock::is_open);
+    Ok(build_state(conn, user_id, block, None, now)?)
+}
+
+pub fn pause_block(conn: &Connection, user_id: &str, now: DateTime<Utc>) -> FocusResult<FocusState> {
+    change_open_block(conn, user_id, now, |block| block.pause(now))
+}
+
+pub fn resume_block(conn: &Connection, user_id: &str, now: DateTime<Utc>) -> FocusResult<FocusState> {
+    change_open_block(conn, user_id, now, |block| block.resume(now))
+}
+
+/// Stop the open block early. Cancelled work blocks earn no XP.
+pub fn cancel_block(conn: &Connection, user_id: &str, now: DateTime<Utc>) -> FocusResult<FocusState> {
+    change_open_block(conn, user_id, now, |block| block.cancel(now))
+}
+
+/// Change the user's work and break lengths. Blocks already running keep
+/// the length they started with.
+pub fn save_settings(conn: &Connection, user_id: &str, settings: &FocusSettings) -> FocusResult<()> {
+    settings.validate()?;
+    FocusRepository::save_settings(conn, user_id, settings)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::models::User;
+    use chrono::Duration;
+
+    fn setup_db() -> Database {
+        let db = Database::new_in_memory().unwrap();
+        UserRepository::create(db.connection(), &User::new("test-user".to_string())).unwrap();
+        db
+    }
+
+    #[test]
+    fn test_completed_work_block_awards_xp_once_and_suggests_a_break() {
+        let db = setup_db();
+        let conn = db.connection();
+        let start = Utc::now();
+
+        let state = start_block(conn, "test-user", None, start).unwrap();
+        let block = state.block.unwrap();
+        assert_eq!(block.kind, FocusBlockKind::Work);
+        let session_id = block.session_id.clone().unwrap();
+        assert!(matches!(start_block(conn, "test-user", None, start), Err(FocusError::AlreadyRunning)));
+
+        // The app was closed while the block ran out
+        let later = start + Duration::minutes(40);
+        let state = get_state(conn, "test-user", later).unwrap();
+        let completion = state.completed.unwrap();
+        assert_eq!(completion.xp_awarded, 25);
+        assert!(state.block.is_none());
+        assert_eq!(state.next_kind, FocusBlockKind::ShortBreak);
+        assert_eq!(state.completed_work_blocks_today, 1);
+
+        let user = UserRepository::get_by_id(conn, "test-user").unwrap().unwrap();
+        assert_eq!(user.total_xp, 25);
+        let session = SessionRepository::get_by_id(conn, &session_id).unwrap().unwrap();
+        assert_eq!(session.total_xp_earned, 25);
+        assert_eq!(FocusRepository::get_awarded_xp_total(conn, "test-user").unwrap(), 25);
+
+        assert!(get_state(conn, "test-user", later).unwrap().completed.is_none());
+    }
+
+    #[test]
+    fn test_pause_resume_and_cancel() {
+        let db = setup_db();
+        let conn = db.connection();
+        let start = Utc::now();
+
+        start_block(conn, "test-user", Some(FocusBlockKind::Work), start).unwrap();
+        pause_block(conn, "test-user", start + Duration::minutes(5)).unwrap();
+
+        // Paused blocks don't run out
+        let state = get_state(conn, "test-user", start + Duration::hours(2)).unwrap();
+        assert!(state.completed.is_none());
+        assert_eq!(state.remaining_seconds, 20 * 60);
+
+        let state = resume_block(conn, "test-user", start + Duration::hours(2)).unwrap();
+        assert_eq!(state.remaining_seconds, 20 * 60);
+
+        let state = cancel_block(conn, "test-user", start + Duration::hours(2) + Duration::minutes(1)).unwrap();
+        assert!(state.block.is_none());
+        assert_eq!(state.next_kind, FocusBlockKind::Work);
+        assert!(matches!(
+            cancel_block(conn, "test-user", start + Duration::hours(3)),
+            Err(FocusError::NoActiveBlock)
+        ));
+        assert_eq!(UserRepository::get_by_id(conn, "test-user").unwrap().unwrap().total_xp, 0);
+    }
+}
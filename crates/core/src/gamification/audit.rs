@@ -0,0 +1,531 @@
+//! XP integrity audit
+//!
+//! Recomputes the XP a user should have from their recorded activity (node
+//! completions, quiz and challenge attempts, graded checkpoint artifacts,
+//! claimed quest rewards and focus blocks) using the canonical formulas, and compares it with the stored
+//! total. The streak a user had at the time of each award is not recorded,
+//! so expected XP is a range bounded by the lowest and highest streak
+//! multipliers; event multipliers are re-derived from the calendar at the time
+//! of each activity.
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::db::error::{DbError, DbResult};
+use crate::db::repos::{
+    ArtifactRepository, ChallengeRepository, FocusRepository, ProgressRepository, QuestRepository, QuizRepository,
+    UserRepository,
+};
+use crate::models::{NodeStatus, QuizAttempt};
+use super::events::EventCalendar;
+use super::formulas::{calculate_level, Difficulty, XpActivity, XpCalculator};
+
+/// Streak length that earns the highest streak multiplier
+const MAX_STREAK_DAYS: u32 = 31;
+
+/// What the auditor needs to know about a curriculum node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditedNode {
+    pub activity: XpActivity,
+    pub difficulty: Difficulty,
+}
+
+/// Where a line of expected XP comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "snake_case")]
+pub enum XpAuditSource {
+    Lecture,
+    Quiz,
+    /// A passed quiz with no recorded attempts (e.g. data from an older build)
+    UntrackedQuiz,
+    Quest,
+    Focus,
+    Challenge,
+    Checkpoint,
+}
+
+/// One contribution to the expected total
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct XpAuditEntry {
+    pub source: XpAuditSource,
+    pub node_id: Option<String>,
+    pub recorded_xp: Option<i32>,
+    pub min_xp: i32,
+    pub max_xp: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum XpDiscrepancy {
+    /// A quiz attempt recorded XP the formula could not have produced
+    AttemptOutOfRange {
+        attempt_id: String,
+        quiz_id: String,
+        recorded_xp: i32,
+        min_xp: i32,
+        max_xp: i32,
+    },
+    TotalBelowExpected { recorded_xp: i32, min_xp: i32 },
+    TotalAboveExpected { recorded_xp: i32, max_xp: i32 },
+    LevelMismatch { recorded_level: i32, expected_level: i32 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct XpAuditReport {
+    pub user_id: String,
+    pub recorded_xp: i32,
+    pub recorded_level: i32,
+    pub expected_min_xp: i32,
+    pub expected_max_xp: i32,
+    pub entries: Vec<XpAuditEntry>,
+    pub discrepancies: Vec<XpDiscrepancy>,
+    /// Completed nodes that are not part of the curriculum and were not counted
+    pub skipped_nodes: Vec<String>,
+}
+
+impl XpAuditReport {
+    pub fn is_consistent(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+
+    /// The closest total to the recorded one that the audit considers valid
+    pub fn corrected_xp(&self) -> i32 {
+        self.recorded_xp.clamp(self.expected_min_xp, self.expected_max_xp)
+    }
+}
+
+/// XP bounds for an award whose streak is unknown
+fn award_bounds(calculator: XpCalculator) -> (i32, i32) {
+    (
+        calculator.with_streak(0).calculate(),
+        calculator.with_streak(MAX_STREAK_DAYS).calculate(),
+    )
+}
+
+/// Recompute a user's expected XP and compare it with their stored total
+pub fn audit_xp(
+    conn: &Connection,
+    user_id: &str,
+    nodes: &HashMap<String, AuditedNode>,
+    calendar: &EventCalendar,
+) -> DbResult<XpAuditReport> {
+    let user = UserRepository::get_by_id(conn, user_id)?
+        .ok_or_else(|| DbError::NotFound(format!("User not found: {}", user_id)))?;
+
+    let mut entries = Vec::new();
+    let mut discrepancies = Vec::new();
+    let mut skipped_nodes = Vec::new();
+    let mut expected_min_xp = 0;
+    let mut expected_max_xp = 0;
+
+    // Group quiz attempts per quiz, oldest first, to recover attempt numbers
+    let mut attempts_by_quiz: HashMap<String, Vec<QuizAttempt>> = HashMap::new();
+    for attempt in QuizRepository::get_all_for_user(conn, user_id)? {
+        attempts_by_quiz.entry(attempt.quiz_id.clone()).or_default().push(attempt);
+    }
+    let mut quiz_ids: Vec<&String> = attempts_by_quiz.keys().collect();
+    quiz_ids.sort();
+
+    for quiz_id in quiz_ids {
+        let mut attempts = attempts_by_quiz[quiz_id].clone();
+        attempts.sort_by_key(|a| a.submitted_at);
+
+        let Some(node) = nodes.get(&attempts[0].node_id) else {
+            skipped_nodes.push(attempts[0].node_id.clone());
+            continue;
+        };
+
+        for (index, attempt) in attempts.iter().enumerate() {
+            let event_multiplier = calendar.multiplier_for(XpActivity::Quiz, attempt.submitted_at);
+            let (min_xp, max_xp) = award_bounds(
+                XpCalculator::new(XpActivity::Quiz, node.difficulty)
+                    .with_accuracy(attempt.score_percentage as f64)
+                    .with_attempt(index + 1)
+                    .with_event_multiplier(event_multiplier),
+            );
+
+            if attempt.xp_earned < min_xp || attempt.xp_earned > max_xp {
+                discrepancies.push(XpDiscrepancy::AttemptOutOfRange {
+                    attempt_id: attempt.id.clone(),
+                    quiz_id: attempt.quiz_id.clone(),
+                    recorded_xp: attempt.xp_earned,
+                    min_xp,
+                    max_xp,
+                });
+            }
+
+            // An in-range recorded award is exact; only the streak was unknown
+            let counted = attempt.xp_earned.clamp(min_xp, max_xp);
+            expected_min_xp += counted;
+            expected_max_xp += counted;
+            entries.push(XpAuditEntry {
+                source: XpAuditSource::Quiz,
+                node_id: Some(attempt.node_id.clone()),
+                recorded_xp: Some(attempt.xp_earned),
+                min_xp,
+                max_xp,
+            });
+        }
+    }
+
+    for progress in ProgressRepository::get_by_status(conn, user_id, &NodeStatus::Completed)? {
+        let Some(node) = nodes.get(&progress.node_id) else {
+            skipped_nodes.push(progress.node_id.clone());
+            continue;
+        };
+        let completed_at = progress.completed_at.unwrap_or(progress.last_updated_at);
+        let event_multiplier = calendar.multiplier_for(node.activity, completed_at);
+
+        let (source, min_xp, max_xp) = match node.activity {
+            XpActivity::Lecture => {
+                let (min_xp, max_xp) = award_bounds(
+                    XpCalculator::new(XpActivity::Lecture, node.difficulty)
+                        .with_event_multiplier(event_multiplier),
+                );
+                (XpAuditSource::Lecture, min_xp, max_xp)
+            }
+            XpActivity::Quiz if !attempts_by_quiz.contains_key(&progress.node_id) => {
+                // Score is unknown, so anything from nothing up to a perfect first attempt
+                let (_, max_xp) = award_bounds(
+                    XpCalculator::new(XpActivity::Quiz, node.difficulty)
+                        .with_accuracy(100.0)
+                        .with_event_multiplier(event_multiplier),
+                );
+                (XpAuditSource::UntrackedQuiz, 0, max_xp)
+            }
+            // Tracked quizzes, challenges and checkpoints are counted per submission
+            _ => continue,
+        };
+
+        expected_min_xp += min_xp;
+        expected_max_xp += max_xp;
+        entries.push(XpAuditEntry {
+            source,
+            node_id: Some(progress.node_id.clone()),
+            recorded_xp: None,
+            min_xp,
+            max_xp,
+        });
+    }
+
+    let quest_xp = QuestRepository::get_claimed_xp_total(conn, user_id)?;
+    if quest_xp > 0 {
+        expected_min_xp += quest_xp;
+        expected_max_xp += quest_xp;
+        entries.push(XpAuditEntry {
+            source: XpAuditSource::Quest,
+            node_id: None,
+            recorded_xp: Some(quest_xp),
+            min_xp: quest_xp,
+            max_xp: quest_xp,
+        });
+    }
+
+    let focus_xp = FocusRepository::get_awarded_xp_total(conn, user_id)?;
+    if focus_xp > 0 {
+        expected_min_xp += focus_xp;
+        expected_max_xp += focus_xp;
+        entries.push(XpAuditEntry {
+            source: XpAuditSource::Focus,
+            node_id: None,
+            recorded_xp: Some(focus_xp),
+            min_xp: focus_xp,
+            max_xp: focus_xp,
+        });
+    }
+
+    let challenge_xp = ChallengeRepository::get_awarded_xp_total(conn, user_id)?;
+    if challenge_xp > 0 {
+        expected_min_xp += challenge_xp;
+        expected_max_xp += challenge_xp;
+        entries.push(XpAuditEntry {
+            source: XpAuditSource::Challenge,
+            node_id: None,
+            recorded_xp: Some(challenge_xp),
+            min_xp: challenge_xp,
+            max_xp: challenge_xp,
+        });
+    }
+
+    let checkpoint_xp = ArtifactRepository::get_awarded_xp_total(conn, user_id)?;
+    if checkpoint_xp > 0 {
+        expected_min_xp += checkpoint_xp;
+        expected_max_xp += checkpoint_xp;
+        entries.push(XpAuditEntry {
+            source: XpAuditSource::Checkpoint,
+            node_id: None,
+            recorded_xp: Some(checkpoint_xp),
+            min_xp: checkpoint_xp,
+            max_xp: checkpoint_xp,
+        });
+    }
+
+    if user.total_xp < expected_min_xp {
+        discrepancies.push(XpDiscrepancy::TotalBelowExpected {
+            recorded_xp: user.total_xp,
+            min_xp: expected_min_xp,
+        });
+    } else if user.total_xp > expected_max_xp {
+        discrepancies.push(XpDiscrepancy::TotalAboveExpected {
+            recorded_xp: user.total_xp,
+            max_xp: expected_max_xp,
+        });
+    }
+
+    let expected_level = calculate_level(user.total_xp) as i32;
+    if user.current_level != expected_level {
+        discrepancies.push(XpDiscrepancy::LevelMismatch {
+            recorded_level: user.current_level,
+            expected_level,
+        });
+    }
+
+    skipped_nodes.sort();
+    skipped_nodes.dedup();
+
+    Ok(XpAuditReport {
+        user_id: user.id,
+        recorded_xp: user.total_xp,
+        recorded_level: user.current_level,
+        expected_min_xp,
+        expected_max_xp,
+        entries,
+        discrepancies,
+        skipped_nodes,
+    })
+}
+
+/// Bring the user record in line with an audit. Returns false if nothing changed.
+pub fn repair_xp(conn: &Connection, report: &XpAuditReport) -> DbResult<bool> {
+    if report.is_consistent() {
+        return Ok(false);
+    }
+
+    let total_xp = report.corrected_xp();
+    let level = calculate_level(total_xp) as i32;
+    UserRepository::set_xp_and_level(conn, &report.user_id, total_xp, level)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::gamification::{calculate_lecture_xp, calculate_quiz_xp, XpEvent};
+    use crate::models::{NodeProgress, Quest, QuestKind, QuestPeriod, User};
+    use chrono::{Duration, Utc};
+
+    fn setup_db() -> Database {
+        let db = Database::new_in_memory().unwrap();
+        let user = User::new("audit-user".to_string());
+        UserRepository::create(db.connection(), &user).unwrap();
+        db
+    }
+
+    fn curriculum() -> HashMap<String, AuditedNode> {
+        let mut nodes = HashMap::new();
+        nodes.insert(
+            "lecture-1".to_string(),
+            AuditedNode { activity: XpActivity::Lecture, difficulty: Difficulty::Easy },
+        );
+        nodes.insert(
+            "quiz-1".to_string(),
+            AuditedNode { activity: XpActivity::Quiz, difficulty: Difficulty::Medium },
+        );
+        nodes
+    }
+
+    fn complete(conn: &Connection, node_id: &str) {
+        let mut progress = NodeProgress::new("audit-user".to_string(), node_id.to_string());
+        progress.complete();
+        ProgressRepository::create_or_update(conn, &progress).unwrap();
+    }
+
+    fn record_attempt(conn: &Connection, score: i32, xp: i32, offset_mins: i64) {
+        let mut attempt = QuizAttempt::new(
+            "audit-user".to_string(),
+            "quiz-1".to_string(),
+            "quiz-1".to_string(),
+            vec![],
+            score,
+            xp,
+        );
+        attempt.submitted_at = Utc::now() - Duration::minutes(60 - offset_mins);
+        QuizRepository::create(conn, &attempt).unwrap();
+    }
+
+    fn set_xp(conn: &Connection, xp: i32) {
+        UserRepository::set_xp_and_level(conn, "audit-user", xp, calculate_level(xp) as i32).unwrap();
+    }
+
+    #[test]
+    fn test_consistent_user_passes() {
+        let db = setup_db();
+        let conn = db.connection();
+        let lecture_xp = calculate_lecture_xp(Difficulty::Easy, 0);
+        let quiz_xp = calculate_quiz_xp(Difficulty::Medium, 80.0, 0);
+
+        complete(conn, "lecture-1");
+        complete(conn, "quiz-1");
+        record_attempt(conn, 80, quiz_xp, 0);
+        set_xp(conn, lecture_xp + quiz_xp);
+
+        let report = audit_xp(conn, "audit-user", &curriculum(), &EventCalendar::default()).unwrap();
+        assert!(report.is_consistent(), "{:?}", report.discrepancies);
+        assert_eq!(report.expected_min_xp, lecture_xp + quiz_xp);
+        assert_eq!(report.expected_max_xp, calculate_lecture_xp(Difficulty::Easy, 31) + quiz_xp);
+        assert!(!repair_xp(conn, &report).unwrap());
+    }
+
+    #[test]
+    fn test_missing_xp_is_reported_and_repaired() {
+        let db = setup_db();
+        let conn = db.connection();
+        complete(conn, "lecture-1");
+
+        let report = audit_xp(conn, "audit-user", &curriculum(), &EventCalendar::default()).unwrap();
+        let min_xp = calculate_lecture_xp(Difficulty::Easy, 0);
+        assert_eq!(
+            report.discrepancies,
+            vec![XpDiscrepancy::TotalBelowExpected { recorded_xp: 0, min_xp }]
+        );
+
+        assert!(repair_xp(conn, &report).unwrap());
+        let user = UserRepository::get_by_id(conn, "audit-user").unwrap().unwrap();
+        assert_eq!(user.total_xp, min_xp);
+        assert!(audit_xp(conn, "audit-user", &curriculum(), &EventCalendar::default())
+            .unwrap()
+            .is_consistent());
+    }
+
+    #[test]
+    fn test_inflated_xp_is_capped() {
+        let db = setup_db();
+        let conn = db.connection();
+        complete(conn, "lecture-1");
+        set_xp(conn, 5000);
+
+        let report = audit_xp(conn, "audit-user", &curriculum(), &EventCalendar::default()).unwrap();
+        let max_xp = calculate_lecture_xp(Difficulty::Easy, 31);
+        assert!(report
+            .discrepancies
+            .contains(&XpDiscrepancy::TotalAboveExpected { recorded_xp: 5000, max_xp }));
+
+        repair_xp(conn, &report).unwrap();
+        let user = UserRepository::get_by_id(conn, "audit-user").unwrap().unwrap();
+        assert_eq!(user.total_xp, max_xp);
+        assert_eq!(user.current_level, calculate_level(max_xp) as i32);
+    }
+
+    #[test]
+    fn test_retakes_use_attempt_order() {
+        let db = setup_db();
+        let conn = db.connection();
+        let first = calculate_quiz_xp(Difficulty::Medium, 50.0, 0);
+        let second = (calculate_quiz_xp(Difficulty::Medium, 90.0, 0) as f64 * 0.5) as i32;
+
+        complete(conn, "quiz-1");
+        record_attempt(conn, 50, first, 0);
+        record_attempt(conn, 90, second, 10);
+        set_xp(conn, first + second);
+
+        let report = audit_xp(conn, "audit-user", &curriculum(), &EventCalendar::default()).unwrap();
+        assert!(report.is_consistent(), "{:?}", report.discrepancies);
+        assert_eq!(report.expected_min_xp, first + second);
+    }
+
+    #[test]
+    fn test_tampered_attempt_is_flagged() {
+        let db = setup_db();
+        let conn = db.connection();
+        complete(conn, "quiz-1");
+        record_attempt(conn, 80, 9999, 0);
+        set_xp(conn, 9999);
+
+        let report = audit_xp(conn, "audit-user", &curriculum(), &EventCalendar::default()).unwrap();
+        assert!(matches!(
+            report.discrepancies[0],
+            XpDiscrepancy::AttemptOutOfRange { recorded_xp: 9999, .. }
+        ));
+        let max_xp = match &report.discrepancies[0] {
+            XpDiscrepancy::AttemptOutOfRange { max_xp, .. } => *max_xp,
+            _ => unreachable!(),
+        };
+        assert_eq!(report.corrected_xp(), max_xp);
+    }
+
+    #[test]
+    fn test_event_multiplier_widens_expected_range() {
+        let db = setup_db();
+        let conn = db.connection();
+        complete(conn, "lecture-1");
+        let calendar = EventCalendar {
+            events: vec![XpEvent {
+                id: "double".to_string(),
+                name: "Double XP".to_string(),
+                description: String::new(),
+                multiplier: 2.0,
+                starts_at: Utc::now() - Duration::days(1),
+                ends_at: Utc::now() + Duration::days(1),
+                activities: vec![],
+            }],
+        };
+        set_xp(conn, calculate_lecture_xp(Difficulty::Easy, 0) * 2);
+
+        let report = audit_xp(conn, "audit-user", &curriculum(), &calendar).unwrap();
+        assert!(report.is_consistent(), "{:?}", report.discrepancies);
+    }
+
+    #[test]
+    fn test_quest_rewards_and_unknown_nodes() {
+        let db = setup_db();
+        let conn = db.connection();
+        complete(conn, "retired-node");
+        let now = Utc::now();
+        let (starts_at, ends_at) = QuestPeriod::Daily.bounds(now);
+        let mut quest = Quest {
+            id: "daily-quest".to_string(),
+            user_id: "audit-user".to_string(),
+            period: QuestPeriod::Daily,
+            kind: QuestKind::CompleteLectures,
+            description: "Complete a lecture".to_string(),
+            target: 1,
+            progress: 0,
+            xp_reward: 30,
+            starts_at,
+            ends_at,
+            completed_at: None,
+            claimed_at: None,
+        };
+        QuestRepository::create_if_missing(conn, &quest).unwrap();
+        quest.record_progress(1, starts_at);
+        quest.claim();
+        QuestRepository::update(conn, &quest).unwrap();
+        set_xp(conn, 30);
+
+        let report = audit_xp(conn, "audit-user", &curriculum(), &EventCalendar::default()).unwrap();
+        assert!(report.is_consistent(), "{:?}", report.discrepancies);
+        assert_eq!(report.skipped_nodes, vec!["retired-node".to_string()]);
+        assert_eq!(report.expected_min_xp, 30);
+    }
+
+    #[test]
+    fn test_level_mismatch_detected() {
+        let db = setup_db();
+        let conn = db.connection();
+        UserRepository::set_xp_and_level(conn, "audit-user", 0, 4).unwrap();
+
+        let report = audit_xp(conn, "audit-user", &curriculum(), &EventCalendar::default()).unwrap();
+        assert_eq!(
+            report.discrepancies,
+            vec![XpDiscrepancy::LevelMismatch { recorded_level: 4, expected_level: 1 }]
+        );
+        repair_xp(conn, &report).unwrap();
+        let user = UserRepository::get_by_id(conn, "audit-user").unwrap().unwrap();
+        assert_eq!(user.current_level, 1);
+    }
+}
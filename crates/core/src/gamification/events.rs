@@ -0,0 +1,247 @@
+//! Seasonal XP events
+//!
+//! Time-bounded events that multiply XP earned for some or all activity types.
+//! Events are loaded from JSON (an app-level `events.json` or one shipped in a
+//! content pack) and stack multiplicatively with the other XP bonuses.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use super::formulas::{Difficulty, XpActivity, XpCalculator};
+
+pub const MAX_EVENT_MULTIPLIER: f64 = 5.0;
+/// Ceiling on the product of all overlapping events
+pub const MAX_COMBINED_EVENT_MULTIPLIER: f64 = 5.0;
+
+/// A time-bounded XP multiplier
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XpEvent {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub multiplier: f64,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    /// Activities boosted by this event; empty means all activities
+    #[serde(default)]
+    pub activities: Vec<XpActivity>,
+}
+
+impl XpEvent {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        now >= self.starts_at && now < self.ends_at
+    }
+
+    pub fn applies_to(&self, activity: XpActivity) -> bool {
+        self.activities.is_empty() || self.activities.contains(&activity)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.ends_at <= self.starts_at {
+            return Err(format!("Event '{}' ends before it starts", self.id));
+        }
+        if !(self.multiplier > 0.0 && self.multiplier <= MAX_EVENT_MULTIPLIER) {
+            return Err(format!(
+                "Event '{}' multiplier must be in (0, {}], got {}",
+                self.id, MAX_EVENT_MULTIPLIER, self.multiplier
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// All known XP events
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventCalendar {
+    pub events: Vec<XpEvent>,
+}
+
+impl EventCalendar {
+    /// Parse and validate an events JSON document
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let calendar: EventCalendar =
+            serde_json::from_str(json).map_err(|e| format!("Invalid events file: {}", e))?;
+        for event in &calendar.events {
+            event.validate()?;
+        }
+        Ok(calendar)
+    }
+
+    /// Add events from another source, skipping IDs that are already known
+    pub fn merge(&mut self, other: EventCalendar) {
+        for event in other.events {
+            if !self.events.iter().any(|e| e.id == event.id) {
+                self.events.push(event);
+            }
+        }
+    }
+
+    /// Events running at `now`, ordered by ID
+    pub fn active_events(&self, now: DateTime<Utc>) -> Vec<&XpEvent> {
+        let mut active: Vec<&XpEvent> = self.events.iter().filter(|e| e.is_active(now)).collect();
+        active.sort_by(|a, b| a.id.cmp(&b.id));
+        active
+    }
+
+    /// Combined multiplier of all active events for an activity.
+    /// Events stack multiplicatively in ID order so the result is deterministic,
+    /// up to `MAX_COMBINED_EVENT_MULTIPLIER`.
+    pub fn multiplier_for(&self, activity: XpActivity, now: DateTime<Utc>) -> f64 {
+        self.active_events(now)
+            .into_iter()
+            .filter(|e| e.applies_to(activity))
+            .fold(1.0, |acc, e| acc * e.multiplier)
+            .min(MAX_COMBINED_EVENT_MULTIPLIER)
+    }
+}
+
+/// Calculate lecture XP including seasonal event bonuses
+pub fn calculate_lecture_xp_with_events(
+    difficulty: Difficulty,
+    streak_days: u32,
+    event_multiplier: f64,
+) -> i32 {
+    XpCalculator::new(XpActivity::Lecture, difficulty)
+        .with_streak(streak_days)
+        .with_event_multiplier(event_multiplier)
+        .calculate()
+}
+
+/// Calculate quiz XP including seasonal event bonuses
+pub fn calculate_quiz_xp_with_events(
+    difficulty: Difficulty,
+    score_percentage: f64,
+    streak_days: u32,
+    event_multiplier: f64,
+) -> i32 {
+    XpCalculator::new(XpActivity::Quiz, difficulty)
+        .with_accuracy(score_percentage)
+        .with_streak(streak_days)
+        .with_event_multiplier(event_multiplier)
+        .calculate()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gamification::{calculate_lecture_xp, calculate_quiz_xp};
+    use chrono::{Duration, TimeZone};
+
+    fn event(id: &str, multiplier: f64, activities: Vec<XpActivity>) -> XpEvent {
+        XpEvent {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            multiplier,
+            starts_at: Utc.with_ymd_and_hms(2024, 12, 1, 0, 0, 0).unwrap(),
+            ends_at: Utc.with_ymd_and_hms(2024, 12, 8, 0, 0, 0).unwrap(),
+            activities,
+        }
+    }
+
+    fn during() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 12, 3, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_no_events_matches_base_formulas() {
+        let calendar = EventCalendar::default();
+        let mult = calendar.multiplier_for(XpActivity::Quiz, during());
+        assert_eq!(mult, 1.0);
+        assert_eq!(
+            calculate_quiz_xp_with_events(Difficulty::Medium, 90.0, 10, mult),
+            calculate_quiz_xp(Difficulty::Medium, 90.0, 10)
+        );
+        assert_eq!(
+            calculate_lecture_xp_with_events(Difficulty::Hard, 31, mult),
+            calculate_lecture_xp(Difficulty::Hard, 31)
+        );
+    }
+
+    #[test]
+    fn test_event_stacks_with_streak_and_accuracy() {
+        let calendar = EventCalendar { events: vec![event("double-xp", 2.0, vec![])] };
+        let mult = calendar.multiplier_for(XpActivity::Quiz, during());
+
+        // 50 * 1.5 (medium) * 1.2 (10-day streak) * 1.3 (90%) * 2.0 = 234
+        assert_eq!(calculate_quiz_xp_with_events(Difficulty::Medium, 90.0, 10, mult), 234);
+    }
+
+    #[test]
+    fn test_multiple_events_stack_deterministically() {
+        let a = event("a-winter", 1.5, vec![]);
+        let b = event("b-quiz-week", 1.2, vec![XpActivity::Quiz]);
+
+        let forward = EventCalendar { events: vec![a.clone(), b.clone()] };
+        let reverse = EventCalendar { events: vec![b, a] };
+
+        let m1 = forward.multiplier_for(XpActivity::Quiz, during());
+        let m2 = reverse.multiplier_for(XpActivity::Quiz, during());
+        assert_eq!(m1.to_bits(), m2.to_bits());
+        assert!((m1 - 1.8).abs() < 1e-9);
+
+        // Quiz-only event does not boost lectures
+        assert_eq!(forward.multiplier_for(XpActivity::Lecture, during()), 1.5);
+    }
+
+    #[test]
+    fn test_overlapping_events_are_capped() {
+        let calendar = EventCalendar {
+            events: vec![
+                event("a-triple", 3.0, vec![]),
+                event("b-triple", 3.0, vec![]),
+                event("c-quiz-double", 2.0, vec![XpActivity::Quiz]),
+            ],
+        };
+
+        // 3.0 * 3.0 * 2.0 = 18.0 uncapped
+        assert_eq!(calendar.multiplier_for(XpActivity::Quiz, during()), MAX_COMBINED_EVENT_MULTIPLIER);
+        assert_eq!(calendar.multiplier_for(XpActivity::Lecture, during()), MAX_COMBINED_EVENT_MULTIPLIER);
+        assert_eq!(
+            calculate_quiz_xp_with_events(Difficulty::Medium, 90.0, 10, calendar.multiplier_for(XpActivity::Quiz, during())),
+            calculate_quiz_xp_with_events(Difficulty::Medium, 90.0, 10, MAX_COMBINED_EVENT_MULTIPLIER)
+        );
+    }
+
+    #[test]
+    fn test_events_outside_window_are_inactive() {
+        let calendar = EventCalendar { events: vec![event("double-xp", 2.0, vec![])] };
+        let before = Utc.with_ymd_and_hms(2024, 11, 30, 23, 59, 59).unwrap();
+        let at_end = Utc.with_ymd_and_hms(2024, 12, 8, 0, 0, 0).unwrap();
+
+        assert_eq!(calendar.multiplier_for(XpActivity::Quiz, before), 1.0);
+        assert_eq!(calendar.multiplier_for(XpActivity::Quiz, at_end), 1.0);
+        assert_eq!(calendar.multiplier_for(XpActivity::Quiz, at_end - Duration::seconds(1)), 2.0);
+    }
+
+    #[test]
+    fn test_from_json_validates_events() {
+        let json = r#"{
+            "events": [{
+                "id": "winter",
+                "name": "Winter Sprint",
+                "multiplier": 1.5,
+                "starts_at": "2024-12-01T00:00:00Z",
+                "ends_at": "2024-12-08T00:00:00Z",
+                "activities": ["Quiz", "Challenge"]
+            }]
+        }"#;
+        let calendar = EventCalendar::from_json(json).unwrap();
+        assert_eq!(calendar.events.len(), 1);
+        assert!(!calendar.events[0].applies_to(XpActivity::Lecture));
+
+        let bad = json.replace("1.5", "50.0");
+        assert!(EventCalendar::from_json(&bad).is_err());
+    }
+
+    #[test]
+    fn test_merge_skips_duplicate_ids() {
+        let mut calendar = EventCalendar { events: vec![event("winter", 1.5, vec![])] };
+        calendar.merge(EventCalendar {
+            events: vec![event("winter", 3.0, vec![]), event("spring", 1.2, vec![])],
+        });
+
+        assert_eq!(calendar.events.len(), 2);
+        assert_eq!(calendar.events[0].multiplier, 1.5);
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Difficulty {
@@ -8,12 +9,30 @@ pub enum Difficulty {
     VeryHard,
 }
 
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Content files use lowercase names, the frontend uses the variant names
+        match s.to_ascii_lowercase().replace(['_', '-'], "").as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            "veryhard" => Ok(Difficulty::VeryHard),
+            _ => Err(format!("Invalid difficulty: {}", s)),
+        }
+    }
+}
+
 // Base XP values per content type
 pub const LECTURE_BASE_XP: i32 = 25;
 pub const QUIZ_BASE_XP: i32 = 50;
 pub const CHALLENGE_BASE_XP: i32 = 100;
 pub const CHECKPOINT_BASE_XP: i32 = 200;
 
+/// Share of the usual XP for a checkpoint graded by the learner themselves
+pub const SELF_ASSESSED_XP_MULTIPLIER: f64 = 0.5;
+
 // Mastery learning rate
 pub const LEARNING_RATE: f64 = 0.25;
 pub const MASTERY_FLOOR: f64 = 0.30;
@@ -51,13 +70,106 @@ pub fn get_accuracy_multiplier(accuracy_pct: f64) -> f64 {
     }
 }
 
+/// Activity types that award XP
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum XpActivity {
+    Lecture,
+    Quiz,
+    Challenge,
+    Checkpoint,
+}
+
+impl XpActivity {
+    /// Map a curriculum node type (`lecture`, `quiz`, `mini-challenge`, `checkpoint`)
+    pub fn from_node_type(node_type: &str) -> Option<Self> {
+        match node_type {
+            "lecture" => Some(XpActivity::Lecture),
+            "quiz" => Some(XpActivity::Quiz),
+            "mini-challenge" | "mini_challenge" => Some(XpActivity::Challenge),
+            "checkpoint" => Some(XpActivity::Checkpoint),
+            _ => None,
+        }
+    }
+
+    pub fn base_xp(&self) -> i32 {
+        match self {
+            XpActivity::Lecture => LECTURE_BASE_XP,
+            XpActivity::Quiz => QUIZ_BASE_XP,
+            XpActivity::Challenge => CHALLENGE_BASE_XP,
+            XpActivity::Checkpoint => CHECKPOINT_BASE_XP,
+        }
+    }
+}
+
+/// Single source of truth for XP awards.
+///
+/// Formula: base_xp × difficulty × streak × accuracy × events, rounded, then
+/// scaled down for retakes. Accuracy only applies when a score is given.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct XpCalculator {
+    pub activity: XpActivity,
+    pub difficulty: Difficulty,
+    pub streak_days: u32,
+    pub accuracy_pct: Option<f64>,
+    pub attempt_number: usize,
+    pub event_multiplier: f64,
+}
+
+impl XpCalculator {
+    pub fn new(activity: XpActivity, difficulty: Difficulty) -> Self {
+        Self {
+            activity,
+            difficulty,
+            streak_days: 0,
+            accuracy_pct: None,
+            attempt_number: 1,
+            event_multiplier: 1.0,
+        }
+    }
+
+    pub fn with_streak(mut self, streak_days: u32) -> Self {
+        self.streak_days = streak_days;
+        self
+    }
+
+    /// Score as a percentage (0-100)
+    pub fn with_accuracy(mut self, accuracy_pct: f64) -> Self {
+        self.accuracy_pct = Some(accuracy_pct);
+        self
+    }
+
+    /// 1-based attempt number; later attempts earn less (see `get_retake_multiplier`)
+    pub fn with_attempt(mut self, attempt_number: usize) -> Self {
+        self.attempt_number = attempt_number;
+        self
+    }
+
+    pub fn with_event_multiplier(mut self, event_multiplier: f64) -> Self {
+        self.event_multiplier = event_multiplier;
+        self
+    }
+
+    /// XP before the retake penalty
+    pub fn base_award(&self) -> i32 {
+        let base = self.activity.base_xp() as f64;
+        let diff_mult = get_difficulty_multiplier(self.difficulty);
+        let streak_mult = get_streak_multiplier(self.streak_days);
+        let accuracy_mult = self.accuracy_pct.map(get_accuracy_multiplier).unwrap_or(1.0);
+
+        (base * diff_mult * streak_mult * accuracy_mult * self.event_multiplier).round() as i32
+    }
+
+    pub fn calculate(&self) -> i32 {
+        (self.base_award() as f64 * get_retake_multiplier(self.attempt_number)) as i32
+    }
+}
+
 /// Calculate XP for lecture completion
 pub fn calculate_lecture_xp(difficulty: Difficulty, streak_days: u32) -> i32 {
-    let base = LECTURE_BASE_XP as f64;
-    let diff_mult = get_difficulty_multiplier(difficulty);
-    let streak_mult = get_streak_multiplier(streak_days);
-
-    (base * diff_mult * streak_mult).round() as i32
+    XpCalculator::new(XpActivity::Lecture, difficulty)
+        .with_streak(streak_days)
+        .calculate()
 }
 
 /// Calculate XP for quiz completion
@@ -66,12 +178,22 @@ pub fn calculate_quiz_xp(
     score_percentage: f64,
     streak_days: u32,
 ) -> i32 {
-    let base = QUIZ_BASE_XP as f64;
-    let diff_mult = get_difficulty_multiplier(difficulty);
-    let streak_mult = get_streak_multiplier(streak_days);
-    let accuracy_mult = get_accuracy_multiplier(score_percentage);
+    XpCalculator::new(XpActivity::Quiz, difficulty)
+        .with_accuracy(score_percentage)
+        .with_streak(streak_days)
+        .calculate()
+}
 
-    (base * diff_mult * streak_mult * accuracy_mult).round() as i32
+/// Calculate XP for a mini-challenge, where accuracy is the share of tests passed
+pub fn calculate_challenge_xp(
+    difficulty: Difficulty,
+    tests_passed_pct: f64,
+    streak_days: u32,
+) -> i32 {
+    XpCalculator::new(XpActivity::Challenge, difficulty)
+        .with_accuracy(tests_passed_pct)
+        .with_streak(streak_days)
+        .calculate()
 }
 
 /// Calculate level from total XP
@@ -141,6 +263,15 @@ pub fn get_mastery_retake_multiplier(attempt_number: usize) -> f64 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_difficulty_parsing() {
+        assert_eq!("Medium".parse::<Difficulty>(), Ok(Difficulty::Medium));
+        assert_eq!("hard".parse::<Difficulty>(), Ok(Difficulty::Hard));
+        assert_eq!("very_hard".parse::<Difficulty>(), Ok(Difficulty::VeryHard));
+        assert_eq!("VeryHard".parse::<Difficulty>(), Ok(Difficulty::VeryHard));
+        assert!("trivial".parse::<Difficulty>().is_err());
+    }
+
     #[test]
     fn test_difficulty_multipliers() {
         assert_eq!(get_difficulty_multiplier(Difficulty::Easy), 1.0);
@@ -195,6 +326,54 @@ mod tests {
         assert_eq!(calculate_quiz_xp(Difficulty::Hard, 75.0, 0), 100); // 50 * 2.0 * 1.0 * 1.0
     }
 
+    // Golden values from prototypes/gamification/formulas.rs (XPCalculator).
+    // The prototype takes accuracy as 0.0-1.0; core takes a percentage.
+    #[test]
+    fn test_calculator_matches_prototype() {
+        let golden = [
+            (XpActivity::Quiz, Difficulty::Medium, 0, 100.0, 113),
+            (XpActivity::Challenge, Difficulty::Hard, 10, 90.0, 312),
+            (XpActivity::Lecture, Difficulty::Easy, 0, 100.0, 38),
+            (XpActivity::Checkpoint, Difficulty::VeryHard, 31, 75.0, 900),
+            (XpActivity::Quiz, Difficulty::Easy, 5, 65.0, 44),
+            (XpActivity::Challenge, Difficulty::Medium, 20, 40.0, 98),
+        ];
+
+        for (activity, difficulty, streak, accuracy, expected) in golden {
+            let xp = XpCalculator::new(activity, difficulty)
+                .with_streak(streak)
+                .with_accuracy(accuracy)
+                .calculate();
+            assert_eq!(xp, expected, "{:?} {:?} streak={} accuracy={}", activity, difficulty, streak, accuracy);
+        }
+    }
+
+    #[test]
+    fn test_calculator_retakes_and_events() {
+        let first = XpCalculator::new(XpActivity::Quiz, Difficulty::Hard).with_accuracy(90.0);
+        assert_eq!(first.calculate(), 130);
+        assert_eq!(first.with_attempt(2).calculate(), 65);
+        assert_eq!(first.with_attempt(5).calculate(), 13);
+        assert_eq!(first.with_event_multiplier(2.0).calculate(), 260);
+    }
+
+    #[test]
+    fn test_lecture_ignores_accuracy_by_default() {
+        assert_eq!(
+            XpCalculator::new(XpActivity::Lecture, Difficulty::Medium).calculate(),
+            calculate_lecture_xp(Difficulty::Medium, 0)
+        );
+        assert_eq!(calculate_challenge_xp(Difficulty::Easy, 100.0, 0), 150);
+    }
+
+    #[test]
+    fn test_activity_from_node_type() {
+        assert_eq!(XpActivity::from_node_type("mini-challenge"), Some(XpActivity::Challenge));
+        assert_eq!(XpActivity::from_node_type("mini_challenge"), Some(XpActivity::Challenge));
+        assert_eq!(XpActivity::from_node_type("lecture"), Some(XpActivity::Lecture));
+        assert_eq!(XpActivity::from_node_type("video"), None);
+    }
+
     #[test]
     fn test_level_calculation() {
         assert_eq!(calculate_level(0), 1);
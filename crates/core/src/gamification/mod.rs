@@ -1,7 +1,17 @@
+pub mod audit;
+pub mod events;
 pub mod formulas;
+pub mod notifications;
 pub mod quiz_grading;
+pub mod quiz_shuffle;
+pub mod quiz_timing;
 pub mod streak;
 
+pub use audit::*;
+pub use events::*;
 pub use formulas::*;
+pub use notifications::*;
 pub use quiz_grading::*;
+pub use quiz_shuffle::*;
+pub use quiz_timing::*;
 pub use streak::*;
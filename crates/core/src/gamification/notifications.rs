@@ -0,0 +1,127 @@
+//! Gamification notifications
+//!
+//! Structured events describing what changed after an action (XP granted,
+//! level-ups, badge unlocks) so the UI can react without polling.
+
+use serde::{Deserialize, Serialize};
+use super::formulas::calculate_level;
+use crate::models::{BadgeDefinition, BadgeTier};
+
+/// What an XP grant was awarded for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum XpSource {
+    Lecture,
+    Quiz,
+    Challenge,
+    Review,
+    Quest,
+    Focus,
+    Checkpoint,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GamificationEvent {
+    XpGranted {
+        source: XpSource,
+        amount: i32,
+        total_xp: i32,
+    },
+    LevelUp {
+        previous_level: u32,
+        new_level: u32,
+    },
+    BadgeUnlocked {
+        badge_id: String,
+        name: String,
+        description: String,
+        icon: String,
+        tier: Option<BadgeTier>,
+    },
+}
+
+impl GamificationEvent {
+    pub fn badge_unlocked(badge: &BadgeDefinition, tier: Option<BadgeTier>) -> Self {
+        GamificationEvent::BadgeUnlocked {
+            badge_id: badge.id.clone(),
+            name: badge.name.clone(),
+            description: badge.description.clone(),
+            icon: badge.icon.clone(),
+            tier,
+        }
+    }
+}
+
+/// Events for an XP grant on top of `previous_total_xp`, including a level-up if one happened
+pub fn xp_award_events(source: XpSource, amount: i32, previous_total_xp: i32) -> Vec<GamificationEvent> {
+    if amount <= 0 {
+        return Vec::new();
+    }
+
+    let total_xp = previous_total_xp + amount;
+    let mut events = vec![GamificationEvent::XpGranted {
+        source,
+        amount,
+        total_xp,
+    }];
+
+    let previous_level = calculate_level(previous_total_xp);
+    let new_level = calculate_level(total_xp);
+    if new_level > previous_level {
+        events.push(GamificationEvent::LevelUp {
+            previous_level,
+            new_level,
+        });
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gamification::xp_required_for_level;
+
+    #[test]
+    fn test_xp_grant_without_level_up() {
+        let events = xp_award_events(XpSource::Lecture, 25, 0);
+        assert_eq!(
+            events,
+            vec![GamificationEvent::XpGranted {
+                source: XpSource::Lecture,
+                amount: 25,
+                total_xp: 25,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_xp_grant_crossing_level_boundary() {
+        let threshold = xp_required_for_level(2);
+        let events = xp_award_events(XpSource::Quiz, 10, threshold - 5);
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[1],
+            GamificationEvent::LevelUp {
+                previous_level: 1,
+                new_level: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_zero_xp_emits_nothing() {
+        assert!(xp_award_events(XpSource::Quiz, 0, 100).is_empty());
+    }
+
+    #[test]
+    fn test_event_serialization_is_tagged() {
+        let event = GamificationEvent::LevelUp {
+            previous_level: 2,
+            new_level: 3,
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "level_up");
+        assert_eq!(json["new_level"], 3);
+    }
+}
@@ -36,6 +36,7 @@ mod tests {
             skills: vec!["rust".to_string()],
             passing_score: 70,
             time_limit_seconds: None,
+            late_policy: Default::default(),
             questions: vec![
                 Question {
                     id: "q1".to_string(),
@@ -59,6 +60,7 @@ mod tests {
                     correct_answer: "b".to_string(),
                     explanation: "2+2=4".to_string(),
                     points: 10,
+                    skills: vec![],
                 },
                 Question {
                     id: "q2".to_string(),
@@ -78,6 +80,7 @@ mod tests {
                     correct_answer: "true".to_string(),
                     explanation: "Rust is indeed a systems programming language".to_string(),
                     points: 10,
+                    skills: vec![],
                 },
             ],
         }
@@ -0,0 +1,208 @@
+//! Seeded option shuffling
+//!
+//! Each attempt at a quiz shows every question's options in an order seeded
+//! by the learner, the quiz and the attempt number, so reloading an attempt
+//! shows the same order and a retake a new one. Shown options are numbered
+//! by where they appear, so nothing a client is sent gives away the stored
+//! order; answers given against the shown options are mapped back to the
+//! stored ones before grading.
+
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+use crate::models::quiz::{QuestionOption, Quiz};
+use crate::quests::generator::SplitMix64;
+
+/// Seed for the option order of a user's `attempt_number`th attempt at a quiz
+pub fn quiz_shuffle_seed(user_id: &str, quiz_id: &str, attempt_number: i32) -> u64 {
+    let mut hasher = Sha256::new();
+    hasher.update(user_id.as_bytes());
+    hasher.update(quiz_id.as_bytes());
+    hasher.update(attempt_number.to_le_bytes());
+    let digest = hasher.finalize();
+
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    u64::from_le_bytes(bytes)
+}
+
+/// The order a quiz's options are shown in for one attempt
+#[derive(Debug, Clone)]
+pub struct OptionShuffle {
+    /// Stored option id at each shown position, by question id
+    orders: HashMap<String, Vec<String>>,
+}
+
+impl OptionShuffle {
+    pub fn new(quiz: &Quiz, seed: u64) -> Self {
+        let mut rng = SplitMix64::new(seed);
+        let orders = quiz
+            .questions
+            .iter()
+            .map(|question| {
+                let mut ids: Vec<String> = question.options.iter().map(|option| option.id.clone()).collect();
+                for i in (1..ids.len()).rev() {
+                    let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+                    ids.swap(i, j);
+                }
+                (question.id.clone(), ids)
+            })
+            .collect();
+        Self { orders }
+    }
+
+    /// The quiz as shown: options in shown order, numbered from 0, and
+    /// correct answers to match
+    pub fn apply(&self, quiz: &Quiz) -> Quiz {
+        let mut shown = quiz.clone();
+        for (question, stored) in shown.questions.iter_mut().zip(&quiz.questions) {
+            let Some(order) = self.orders.get(&question.id) else {
+                continue;
+            };
+            let texts: HashMap<&str, &str> =
+                stored.options.iter().map(|o| (o.id.as_str(), o.text.as_str())).collect();
+            question.options = order
+                .iter()
+                .enumerate()
+                .map(|(position, id)| QuestionOption {
+                    id: position.to_string(),
+                    text: texts.get(id.as_str()).copied().unwrap_or_default().to_string(),
+                })
+                .collect();
+            question.correct_answer = self.to_shown(&question.id, &question.correct_answer);
+        }
+        shown
+    }
+
+    /// An answer in stored option ids, in terms of the shown options
+    pub fn to_shown(&self, question_id: &str, answer: &str) -> String {
+        let Some(order) = self.orders.get(question_id) else {
+            return answer.to_string();
+        };
+        map_answer(answer, |id| order.iter().position(|stored| stored == id).map(|position| position.to_string()))
+    }
+
+    /// An answer to the shown options, in terms of the stored option ids
+    pub fn to_stored(&self, question_id: &str, answer: &str) -> String {
+        let Some(order) = self.orders.get(question_id) else {
+            return answer.to_string();
+        };
+        map_answer(answer, |id| id.parse::<usize>().ok().and_then(|position| order.get(position)).cloned())
+    }
+
+    /// Answers by question id, given against the shown options, in terms of
+    /// the stored ones
+    pub fn stored_answers(&self, answers: &HashMap<String, String>) -> HashMap<String, String> {
+        answers
+            .iter()
+            .map(|(question_id, answer)| (question_id.clone(), self.to_stored(question_id, answer)))
+            .collect()
+    }
+}
+
+/// Map each option id in a single or comma-separated answer. Ids with no
+/// match are kept, so they still grade as wrong. Several ids are sorted the
+/// way stored multiple answers are, numerically where they're numbers.
+fn map_answer(answer: &str, map: impl Fn(&str) -> Option<String>) -> String {
+    if answer.is_empty() {
+        return String::new();
+    }
+    let mut ids: Vec<String> = answer.split(',').map(|id| map(id).unwrap_or_else(|| id.to_string())).collect();
+    ids.sort_by(|a, b| match (a.parse::<usize>(), b.parse::<usize>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    });
+    ids.dedup();
+    ids.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gamification::grade_quiz;
+    use crate::models::quiz::Question;
+
+    fn question(id: &str, options: usize, correct_answer: &str) -> Question {
+        Question {
+            id: id.to_string(),
+            question_type: "multiple-choice".to_string(),
+            prompt: format!("{}?", id),
+            code_snippet: None,
+            options: (0..options)
+                .map(|i| QuestionOption { id: i.to_string(), text: format!("{}-option-{}", id, i) })
+                .collect(),
+            correct_answer: correct_answer.to_string(),
+            explanation: String::new(),
+            points: 1,
+            skills: Vec::new(),
+        }
+    }
+
+    fn quiz() -> Quiz {
+        Quiz {
+            id: "quiz-1".to_string(),
+            title: "Quiz".to_string(),
+            description: String::new(),
+            difficulty: "easy".to_string(),
+            skills: Vec::new(),
+            passing_score: 70,
+            time_limit_seconds: None,
+            late_policy: Default::default(),
+            questions: vec![question("q1", 4, "2"), question("q2", 5, "0,3")],
+        }
+    }
+
+    #[test]
+    fn test_shown_correct_answers_grade_as_stored() {
+        let quiz = quiz();
+        let shuffle = OptionShuffle::new(&quiz, quiz_shuffle_seed("user1", &quiz.id, 1));
+        let shown = shuffle.apply(&quiz);
+
+        for (stored, shown) in quiz.questions.iter().zip(&shown.questions) {
+            let ids: Vec<&str> = shown.options.iter().map(|o| o.id.as_str()).collect();
+            let expected: Vec<String> = (0..stored.options.len()).map(|i| i.to_string()).collect();
+            assert_eq!(ids, expected);
+
+            // The right options are still right wherever they're shown
+            let correct_texts = |q: &Question| {
+                let mut texts: Vec<String> = q
+                    .correct_answer
+                    .split(',')
+                    .filter_map(|id| q.options.iter().find(|o| o.id == id))
+                    .map(|o| o.text.clone())
+                    .collect();
+                texts.sort();
+                texts
+            };
+            assert_eq!(correct_texts(stored), correct_texts(shown));
+        }
+
+        let answers: HashMap<String, String> =
+            shown.questions.iter().map(|q| (q.id.clone(), q.correct_answer.clone())).collect();
+        assert_eq!(grade_quiz(&quiz, &shuffle.stored_answers(&answers)).1, 2);
+        assert_eq!(shuffle.to_stored("q2", &shown.questions[1].correct_answer), "0,3");
+    }
+
+    #[test]
+    fn test_order_follows_the_attempt() {
+        let quiz = quiz();
+        let order = |user: &str, attempt: i32| {
+            let shown = OptionShuffle::new(&quiz, quiz_shuffle_seed(user, &quiz.id, attempt)).apply(&quiz);
+            shown.questions.iter().flat_map(|q| q.options.iter().map(|o| o.text.clone())).collect::<Vec<_>>()
+        };
+
+        assert_eq!(order("user1", 1), order("user1", 1));
+        assert!((2..6).any(|attempt| order("user1", attempt) != order("user1", 1)));
+        assert!(["user2", "user3", "user4"].iter().any(|user| order(user, 1) != order("user1", 1)));
+    }
+
+    #[test]
+    fn test_unknown_answers_stay_wrong() {
+        let quiz = quiz();
+        let shuffle = OptionShuffle::new(&quiz, 7);
+
+        assert_eq!(shuffle.to_stored("q1", ""), "");
+        assert_eq!(shuffle.to_stored("q1", "9"), "9");
+        assert_eq!(shuffle.to_stored("missing", "1"), "1");
+    }
+}
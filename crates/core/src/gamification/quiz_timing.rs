@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::quiz::LatePolicy;
+
+/// Seconds past the limit still accepted as on time, covering the trip from
+/// the learner pressing submit to the submission being recorded
+pub const LATE_GRACE_SECONDS: i64 = 5;
+
+/// How a timed submission is treated
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum QuizTiming {
+    OnTime,
+    /// Graded, with `penalty_percent` points taken off the score
+    Late { late_seconds: i64, penalty_percent: f64 },
+    Rejected { late_seconds: i64 },
+}
+
+impl QuizTiming {
+    pub fn late_seconds(&self) -> i64 {
+        match self {
+            QuizTiming::OnTime => 0,
+            QuizTiming::Late { late_seconds, .. } | QuizTiming::Rejected { late_seconds } => *late_seconds,
+        }
+    }
+
+    pub fn penalty_percent(&self) -> f64 {
+        match self {
+            QuizTiming::Late { penalty_percent, .. } => *penalty_percent,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Judge a submission against the quiz's time limit. Both times come from
+/// the app's clock, never the learner's; a start that appears to be in the
+/// future (the clock was set back mid-quiz) counts as no time elapsed.
+pub fn assess_quiz_timing(
+    started_at: DateTime<Utc>,
+    submitted_at: DateTime<Utc>,
+    time_limit_seconds: i32,
+    policy: LatePolicy,
+) -> QuizTiming {
+    let elapsed = (submitted_at - started_at).num_seconds().max(0);
+    let late_seconds = elapsed - time_limit_seconds as i64;
+    if late_seconds <= LATE_GRACE_SECONDS {
+        return QuizTiming::OnTime;
+    }
+
+    match policy {
+        LatePolicy::Flag => QuizTiming::Late { late_seconds, penalty_percent: 0.0 },
+        LatePolicy::Penalize { percent_per_minute, max_percent } => {
+            let minutes = (late_seconds + 59) / 60;
+            QuizTiming::Late {
+                late_seconds,
+                penalty_percent: (minutes as f64 * percent_per_minute).min(max_percent).max(0.0),
+            }
+        }
+        LatePolicy::Reject => QuizTiming::Rejected { late_seconds },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn start() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-05-06T10:00:00Z").unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_on_time_within_limit_and_grace() {
+        let policy = LatePolicy::Reject;
+        assert_eq!(assess_quiz_timing(start(), start() + Duration::seconds(300), 300, policy), QuizTiming::OnTime);
+        assert_eq!(
+            assess_quiz_timing(start(), start() + Duration::seconds(300 + LATE_GRACE_SECONDS), 300, policy),
+            QuizTiming::OnTime
+        );
+        assert_eq!(
+            assess_quiz_timing(start(), start() + Duration::seconds(306), 300, policy),
+            QuizTiming::Rejected { late_seconds: 6 }
+        );
+    }
+
+    #[test]
+    fn test_clock_set_back_counts_as_no_time() {
+        let submitted = start() - Duration::hours(1);
+        assert_eq!(assess_quiz_timing(start(), submitted, 60, LatePolicy::Reject), QuizTiming::OnTime);
+    }
+
+    #[test]
+    fn test_clock_jumped_forward_is_late() {
+        let submitted = start() + Duration::days(1);
+        let timing = assess_quiz_timing(start(), submitted, 60, LatePolicy::Flag);
+        assert_eq!(timing, QuizTiming::Late { late_seconds: 86_340, penalty_percent: 0.0 });
+    }
+
+    #[test]
+    fn test_penalty_per_started_minute_is_capped() {
+        let policy = LatePolicy::Penalize { percent_per_minute: 10.0, max_percent: 25.0 };
+        let timing = assess_quiz_timing(start(), start() + Duration::seconds(300 + 61), 300, policy);
+        assert_eq!(timing.late_seconds(), 61);
+        assert_eq!(timing.penalty_percent(), 20.0);
+
+        let timing = assess_quiz_timing(start(), start() + Duration::seconds(300 + 600), 300, policy);
+        assert_eq!(timing.penalty_percent(), 25.0);
+    }
+}
@@ -0,0 +1,23 @@
+//! Learner goals
+//!
+//! A goal is a target the learner sets with a deadline, like finishing a week
+//! or reaching a mastery score in a skill. `tracker` stores goals and works
+//! out their progress from node progress and mastery, comparing it with the
+//! pace needed to finish on time so reminders can nudge goals that fall behind.
+
+pub mod tracker;
+
+pub use tracker::{behind_goals, create_goal, goal_progress, goals_status};
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum GoalError {
+    #[error(transparent)]
+    Db(#[from] crate::db::error::DbError),
+
+    #[error("Invalid goal: {0}")]
+    InvalidGoal(String),
+}
+
+pub type GoalResult<T> = Result<T, GoalError>;
@@ -0,0 +1,189 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+
+use crate::db::error::{DbError, DbResult};
+use crate::db::repos::{GoalRepository, MasteryRepository};
+use crate::goals::{GoalError, GoalResult};
+use crate::models::{Goal, GoalState, GoalStatus, GoalTarget};
+
+/// Check and store a new goal
+pub fn create_goal(conn: &Connection, goal: &Goal, now: DateTime<Utc>) -> GoalResult<()> {
+    if goal.title.trim().is_empty() {
+        return Err(GoalError::InvalidGoal("title is empty".to_string()));
+    }
+    if goal.deadline <= now {
+        return Err(GoalError::InvalidGoal("deadline has already passed".to_string()));
+    }
+    match &goal.target {
+        GoalTarget::CompleteWeek { week_id, node_ids } if node_ids.is_empty() => {
+            return Err(GoalError::InvalidGoal(format!("week {} has no nodes", week_id)));
+        }
+        GoalTarget::ReachMastery { score, .. } if !(*score > 0.0 && *score <= 1.0) => {
+            return Err(GoalError::InvalidGoal("mastery target must be above 0 and at most 1".to_string()));
+        }
+        _ => {}
+    }
+
+    GoalRepository::create(conn, goal)?;
+    Ok(())
+}
+
+/// How much of a goal's target is done, 0.0-1.0
+pub fn goal_progress(conn: &Connection, goal: &Goal) -> DbResult<f64> {
+    let progress = match &goal.target {
+        GoalTarget::CompleteWeek { node_ids, .. } => {
+            let ids = serde_json::to_string(node_ids).map_err(|e| DbError::InvalidData(e.to_string()))?;
+            let completed: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM node_progress
+                 WHERE user_id = ?1 AND curriculum_id IS ?2 AND status = 'Completed'
+                   AND node_id IN (SELECT value FROM json_each(?3))",
+                params![goal.user_id, goal.curriculum_id, ids],
+                |row| row.get(0),
+            )?;
+            if node_ids.is_empty() {
+                1.0
+            } else {
+                completed as f64 / node_ids.len() as f64
+            }
+        }
+        GoalTarget::ReachMastery { skill_id, score } => {
+            let current = MasteryRepository::get(conn, &goal.user_id, goal.curriculum_id.as_deref(), skill_id)?
+                .map(|m| m.score)
+                .unwrap_or(0.0);
+            current / score
+        }
+    };
+    Ok(progress.clamp(0.0, 1.0))
+}
+
+/// The user's goals with their progress, recording any newly achieved
+pub fn goals_status(
+    conn: &Connection,
+    user_id: &str,
+    curriculum_id: Option<&str>,
+    now: DateTime<Utc>,
+) -> DbResult<Vec<GoalStatus>> {
+    let mut statuses = Vec::new();
+
+    for mut goal in GoalRepository::get_all(conn, user_id, curriculum_id)? {
+        let progress = goal_progress(conn, &goal)?;
+        let state = goal.state(progress, now);
+        if state == GoalState::Achieved && goal.achieved_at.is_none() {
+            GoalRepository::mark_achieved(conn, &goal.id, now)?;
+            goal.achieved_at = Some(now);
+        }
+
+        statuses.push(GoalStatus {
+            expected_progress: goal.expected_progress(now),
+            progress,
+            state,
+            goal,
+        });
+    }
+
+    Ok(statuses)
+}
+
+/// Goals that have fallen behind the pace needed to finish on time
+pub fn behind_goals(
+    conn: &Connection,
+    user_id: &str,
+    curriculum_id: Option<&str>,
+    now: DateTime<Utc>,
+) -> DbResult<Vec<GoalStatus>> {
+    Ok(goals_status(conn, user_id, curriculum_id, now)?
+        .into_iter()
+        .filter(|status| status.state == GoalState::Behind)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::{ProgressRepository, UserRepository};
+    use crate::models::{MasteryScore, User};
+    use chrono::Duration;
+
+    fn setup() -> Database {
+        let db = Database::new_in_memory().unwrap();
+        UserRepository::create(db.connection(), &User::new("test-user".to_string())).unwrap();
+        db
+    }
+
+    fn week_goal(days: i64) -> Goal {
+        Goal::new(
+            "test-user".to_string(),
+            None,
+            "Finish week 4".to_string(),
+            GoalTarget::CompleteWeek {
+                week_id: "week4".to_string(),
+                node_ids: vec!["a".to_string(), "b".to_string(), "c".to_string(), "d".to_string()],
+            },
+            Utc::now() + Duration::days(days),
+        )
+    }
+
+    #[test]
+    fn test_create_goal_validates() {
+        let db = setup();
+        let conn = db.connection();
+        let now = Utc::now();
+
+        assert!(matches!(create_goal(conn, &week_goal(-1), now), Err(GoalError::InvalidGoal(_))));
+
+        let mut empty = week_goal(7);
+        empty.target = GoalTarget::CompleteWeek { week_id: "week4".to_string(), node_ids: vec![] };
+        assert!(matches!(create_goal(conn, &empty, now), Err(GoalError::InvalidGoal(_))));
+
+        let mut mastery = week_goal(7);
+        mastery.target = GoalTarget::ReachMastery { skill_id: "ownership".to_string(), score: 1.5 };
+        assert!(matches!(create_goal(conn, &mastery, now), Err(GoalError::InvalidGoal(_))));
+
+        create_goal(conn, &week_goal(7), now).unwrap();
+        assert_eq!(GoalRepository::get_all(conn, "test-user", None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_week_progress_and_pace() {
+        let db = setup();
+        let conn = db.connection();
+        let goal = week_goal(10);
+        create_goal(conn, &goal, Utc::now()).unwrap();
+        ProgressRepository::mark_completed(conn, "test-user", None, "a").unwrap();
+        ProgressRepository::mark_completed(conn, "test-user", None, "elsewhere").unwrap();
+
+        assert_eq!(goal_progress(conn, &goal).unwrap(), 0.25);
+
+        // A quarter done with most of the time gone
+        let late = goal.created_at + Duration::days(8);
+        let behind = behind_goals(conn, "test-user", None, late).unwrap();
+        assert_eq!(behind.len(), 1);
+        assert_eq!(behind[0].goal.id, goal.id);
+
+        let early = goal.created_at + Duration::days(1);
+        assert!(behind_goals(conn, "test-user", None, early).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_mastery_goal_is_recorded_when_achieved() {
+        let db = setup();
+        let conn = db.connection();
+        let mut goal = week_goal(10);
+        goal.target = GoalTarget::ReachMastery { skill_id: "ownership".to_string(), score: 0.8 };
+        create_goal(conn, &goal, Utc::now()).unwrap();
+
+        let mut mastery = MasteryScore::new("test-user".to_string(), "ownership".to_string());
+        mastery.score = 0.4;
+        MasteryRepository::create_or_update(conn, &mastery).unwrap();
+        let status = goals_status(conn, "test-user", None, Utc::now()).unwrap();
+        assert_eq!(status[0].progress, 0.5);
+        assert_eq!(status[0].state, GoalState::OnTrack);
+
+        mastery.score = 0.85;
+        MasteryRepository::create_or_update(conn, &mastery).unwrap();
+        let status = goals_status(conn, "test-user", None, Utc::now()).unwrap();
+        assert_eq!(status[0].state, GoalState::Achieved);
+        assert!(GoalRepository::get_by_id(conn, &goal.id).unwrap().unwrap().achieved_at.is_some());
+    }
+}
@@ -0,0 +1,31 @@
+//! Challenge hints
+//!
+//! `penalty` holds how much of a challenge's completion XP each revealed hint
+//! costs. `tracker` hands a challenge's hints out one at a time, recording
+//! which attempt each was revealed in, and applies the cost when the
+//! challenge is first passed.
+
+pub mod penalty;
+pub mod tracker;
+
+pub use penalty::HintSettings;
+pub use tracker::{apply_penalty, get_state, reveal_next, save_settings, HintReveal, HintState};
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum HintError {
+    #[error(transparent)]
+    Db(#[from] crate::db::error::DbError),
+
+    #[error("This challenge has no hints")]
+    NoHints,
+
+    #[error("Every hint has already been revealed")]
+    AllRevealed,
+
+    #[error("Invalid hint settings: {0}")]
+    InvalidSettings(String),
+}
+
+pub type HintResult<T> = Result<T, HintError>;
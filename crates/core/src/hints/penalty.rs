@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use crate::hints::{HintError, HintResult};
+
+/// What revealing hints costs, per user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct HintSettings {
+    /// Share of the completion XP each revealed hint takes
+    pub penalty_percent_per_hint: u32,
+    /// Most of the completion XP hints can take, however many are revealed
+    pub max_penalty_percent: u32,
+}
+
+impl Default for HintSettings {
+    fn default() -> Self {
+        Self {
+            penalty_percent_per_hint: 10,
+            max_penalty_percent: 50,
+        }
+    }
+}
+
+impl HintSettings {
+    pub fn validate(&self) -> HintResult<()> {
+        if self.penalty_percent_per_hint > 100 {
+            return Err(HintError::InvalidSettings("penalty per hint must be 0-100%".to_string()));
+        }
+        if self.max_penalty_percent > 100 {
+            return Err(HintError::InvalidSettings("maximum penalty must be 0-100%".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Share of the completion XP lost to `revealed` hints
+    pub fn penalty_percent(&self, revealed: usize) -> u32 {
+        let revealed = u32::try_from(revealed).unwrap_or(u32::MAX);
+        self.penalty_percent_per_hint
+            .saturating_mul(revealed)
+            .min(self.max_penalty_percent)
+    }
+
+    /// `xp` less the penalty for `revealed` hints
+    pub fn apply(&self, xp: i32, revealed: usize) -> i32 {
+        let kept = 100 - self.penalty_percent(revealed).min(100) as i32;
+        xp * kept / 100
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_penalty_grows_per_hint_up_to_the_cap() {
+        let settings = HintSettings::default();
+        assert_eq!(settings.penalty_percent(0), 0);
+        assert_eq!(settings.penalty_percent(2), 20);
+        assert_eq!(settings.penalty_percent(9), 50);
+
+        assert_eq!(settings.apply(150, 0), 150);
+        assert_eq!(settings.apply(150, 2), 120);
+        assert_eq!(settings.apply(150, 9), 75);
+    }
+
+    #[test]
+    fn test_settings_are_percentages() {
+        assert!(HintSettings::default().validate().is_ok());
+        let settings = HintSettings {
+            penalty_percent_per_hint: 101,
+            max_penalty_percent: 50,
+        };
+        assert!(matches!(settings.validate(), Err(HintError::InvalidSettings(_))));
+    }
+}
@@ -0,0 +1,225 @@
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::db::error::DbResult;
+use crate::db::repos::{ChallengeRepository, HintRepository};
+use crate::hints::{HintError, HintResult, HintSettings};
+
+/// One hint shown to a user
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HintReveal {
+    pub id: String,
+    pub user_id: String,
+    pub curriculum_id: Option<String>,
+    pub node_id: String,
+    /// Position of the hint in the challenge's list
+    pub hint_index: u32,
+    /// The challenge attempt the hint was revealed during, 1-based
+    pub attempt_number: i32,
+    pub revealed_at: DateTime<Utc>,
+}
+
+impl HintReveal {
+    pub fn new(
+        user_id: String,
+        curriculum_id: Option<String>,
+        node_id: String,
+        hint_index: u32,
+        attempt_number: i32,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            curriculum_id,
+            node_id,
+            hint_index,
+            attempt_number,
+            revealed_at: now,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct HintState {
+    pub node_id: String,
+    /// Hints revealed so far, in order
+    pub revealed: Vec<String>,
+    pub total_hints: usize,
+    /// Share of the completion XP the revealed hints cost
+    pub penalty_percent: u32,
+    /// What the cost would be with one more hint revealed
+    pub next_penalty_percent: u32,
+}
+
+fn build_state(
+    conn: &Connection,
+    user_id: &str,
+    curriculum_id: Option<&str>,
+    node_id: &str,
+    hints: &[String],
+) -> DbResult<HintState> {
+    let settings = HintRepository::get_settings(conn, user_id)?;
+    // A hint removed from the curriculum since it was revealed no longer shows or costs anything
+    let revealed: Vec<String> = HintRepository::get_reveals(conn, user_id, curriculum_id, node_id)?
+        .iter()
+        .filter_map(|reveal| hints.get(reveal.hint_index as usize).cloned())
+        .collect();
+
+    Ok(HintState {
+        node_id: node_id.to_string(),
+        penalty_percent: settings.penalty_percent(revealed.len()),
+        next_penalty_percent: settings.penalty_percent(revealed.len() + 1),
+        total_hints: hints.len(),
+        revealed,
+    })
+}
+
+/// The hints a user has revealed for a challenge, given the challenge's hints
+pub fn get_state(
+    conn: &Connection,
+    user_id: &str,
+    curriculum_id: Option<&str>,
+    node_id: &str,
+    hints: &[String],
+) -> HintResult<HintState> {
+    Ok(build_state(conn, user_id, curriculum_id, node_id, hints)?)
+}
+
+/// Reveal the first hint the user hasn't seen, recording it against the
+/// attempt the user is working on
+pub fn reveal_next(
+    conn: &Connection,
+    user_id: &str,
+    curriculum_id: Option<&str>,
+    node_id: &str,
+    hints: &[String],
+    now: DateTime<Utc>,
+) -> HintResult<HintState> {
+    if hints.is_empty() {
+        return Err(HintError::NoHints);
+    }
+
+    let revealed = HintRepository::get_reveals(conn, user_id, curriculum_id, node_id)?;
+    let Some(hint_index) = (0..hints.len() as u32).find(|i| !revealed.iter().any(|r| r.hint_index == *i)) else {
+        return Err(HintError::AllRevealed);
+    };
+
+    let attempts = ChallengeRepository::get_for_node(conn, user_id, curriculum_id, node_id)?;
+    let reveal = HintReveal::new(
+        user_id.to_string(),
+        curriculum_id.map(str::to_string),
+        node_id.to_string(),
+        hint_index,
+        attempts.len() as i32 + 1,
+        now,
+    );
+    HintRepository::create_reveal(conn, &reveal)?;
+
+    Ok(build_state(conn, user_id, curriculum_id, node_id, hints)?)
+}
+
+/// Take the user's hint penalty out of a challenge's completion XP. Returns
+/// the XP left and the share taken.
+pub fn apply_penalty(
+    conn: &Connection,
+    user_id: &str,
+    curriculum_id: Option<&str>,
+    node_id: &str,
+    xp: i32,
+) -> DbResult<(i32, u32)> {
+    let settings = HintRepository::get_settings(conn, user_id)?;
+    let revealed = HintRepository::get_reveals(conn, user_id, curriculum_id, node_id)?.len();
+    Ok((settings.apply(xp, revealed), settings.penalty_percent(revealed)))
+}
+
+/// Change what revealing a hint costs. Challenges already completed keep
+/// the XP they earned.
+pub fn save_settings(conn: &Connection, user_id: &str, settings: &HintSettings) -> HintResult<()> {
+    settings.validate()?;
+    HintRepository::save_settings(conn, user_id, settings)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::{ChallengeAttempt, User};
+
+    fn setup_db() -> Database {
+        let db = Database::new_in_memory().unwrap();
+        UserRepository::create(db.connection(), &User::new("test-user".to_string())).unwrap();
+        db
+    }
+
+    fn hints() -> Vec<String> {
+        vec!["Read the error".to_string(), "Borrow it".to_string()]
+    }
+
+    #[test]
+    fn test_hints_reveal_in_order_per_attempt() {
+        let db = setup_db();
+        let conn = db.connection();
+        let now = Utc::now();
+
+        let state = get_state(conn, "test-user", None, "challenge-1", &hints()).unwrap();
+        assert!(state.revealed.is_empty());
+        assert_eq!(state.next_penalty_percent, 10);
+
+        let state = reveal_next(conn, "test-user", None, "challenge-1", &hints(), now).unwrap();
+        assert_eq!(state.revealed, vec!["Read the error".to_string()]);
+        assert_eq!(state.penalty_percent, 10);
+
+        let failed = ChallengeAttempt::new(
+            "test-user".to_string(),
+            "challenge-1".to_string(),
+            "challenge-1".to_string(),
+            "fn main() {}",
+            0,
+            2,
+            None,
+            None,
+            0,
+        );
+        ChallengeRepository::create(conn, &failed).unwrap();
+
+        let state = reveal_next(conn, "test-user", None, "challenge-1", &hints(), now).unwrap();
+        assert_eq!(state.revealed, hints());
+        assert!(matches!(
+            reveal_next(conn, "test-user", None, "challenge-1", &hints(), now),
+            Err(HintError::AllRevealed)
+        ));
+        assert!(matches!(reveal_next(conn, "test-user", None, "challenge-2", &[], now), Err(HintError::NoHints)));
+
+        let attempts: Vec<i32> = HintRepository::get_reveals(conn, "test-user", None, "challenge-1")
+            .unwrap()
+            .iter()
+            .map(|r| r.attempt_number)
+            .collect();
+        assert_eq!(attempts, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_penalty_follows_settings() {
+        let db = setup_db();
+        let conn = db.connection();
+
+        assert_eq!(apply_penalty(conn, "test-user", None, "challenge-1", 150).unwrap(), (150, 0));
+
+        reveal_next(conn, "test-user", None, "challenge-1", &hints(), Utc::now()).unwrap();
+        assert_eq!(apply_penalty(conn, "test-user", None, "challenge-1", 150).unwrap(), (135, 10));
+
+        let settings = HintSettings {
+            penalty_percent_per_hint: 25,
+            max_penalty_percent: 50,
+        };
+        save_settings(conn, "test-user", &settings).unwrap();
+        assert_eq!(apply_penalty(conn, "test-user", None, "challenge-1", 150).unwrap(), (112, 25));
+        // Other curricula's reveals don't count
+        assert_eq!(apply_penalty(conn, "test-user", Some("other"), "challenge-1", 150).unwrap(), (150, 0));
+    }
+}
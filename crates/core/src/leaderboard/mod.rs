@@ -0,0 +1,11 @@
+//! Leaderboard for local profiles
+//!
+//! Standings are gathered from one or more `LeaderboardSource`s and ranked
+//! together. The local database is the only source today; a remote sync
+//! backend can be added as another source without changing the ranking.
+
+pub mod ranking;
+pub mod source;
+
+pub use ranking::{rank_standings, Leaderboard, LeaderboardEntry, LeaderboardPeriod, ProfileStanding};
+pub use source::{build_leaderboard, LeaderboardSource, LocalLeaderboardSource};
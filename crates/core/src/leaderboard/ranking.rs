@@ -0,0 +1,181 @@
+//! Leaderboard ranking
+//!
+//! Profiles are ranked by XP earned in the period, then by current streak.
+//! Tied profiles share a rank.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use crate::models::QuestPeriod;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum LeaderboardPeriod {
+    Weekly,
+    AllTime,
+}
+
+impl LeaderboardPeriod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaderboardPeriod::Weekly => "Weekly",
+            LeaderboardPeriod::AllTime => "AllTime",
+        }
+    }
+
+    /// Start of the window XP is counted from, or None for all time.
+    /// Weeks start on Monday (UTC), matching weekly quests.
+    pub fn window_start(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            LeaderboardPeriod::Weekly => Some(QuestPeriod::Weekly.bounds(now).0),
+            LeaderboardPeriod::AllTime => None,
+        }
+    }
+}
+
+impl FromStr for LeaderboardPeriod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Weekly" => Ok(LeaderboardPeriod::Weekly),
+            "AllTime" => Ok(LeaderboardPeriod::AllTime),
+            _ => Err(format!("Invalid leaderboard period: {}", s)),
+        }
+    }
+}
+
+/// A profile's numbers for one period, as reported by a source
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ProfileStanding {
+    pub user_id: String,
+    pub display_name: String,
+    /// XP earned within the period
+    pub xp: i32,
+    pub level: i32,
+    pub current_streak: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct LeaderboardEntry {
+    pub rank: u32,
+    #[serde(flatten)]
+    pub standing: ProfileStanding,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub period: LeaderboardPeriod,
+    pub generated_at: DateTime<Utc>,
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+impl Leaderboard {
+    pub fn entry_for(&self, user_id: &str) -> Option<&LeaderboardEntry> {
+        self.entries.iter().find(|e| e.standing.user_id == user_id)
+    }
+}
+
+fn compare_standings(a: &ProfileStanding, b: &ProfileStanding) -> Ordering {
+    b.xp.cmp(&a.xp)
+        .then(b.current_streak.cmp(&a.current_streak))
+}
+
+/// Rank standings into a leaderboard.
+/// Order is deterministic: ties are listed by display name, then user ID.
+pub fn rank_standings(
+    period: LeaderboardPeriod,
+    mut standings: Vec<ProfileStanding>,
+    now: DateTime<Utc>,
+) -> Leaderboard {
+    standings.sort_by(|a, b| {
+        compare_standings(a, b)
+            .then_with(|| a.display_name.cmp(&b.display_name))
+            .then_with(|| a.user_id.cmp(&b.user_id))
+    });
+
+    let mut entries: Vec<LeaderboardEntry> = Vec::with_capacity(standings.len());
+    for (i, standing) in standings.into_iter().enumerate() {
+        let rank = match entries.last() {
+            Some(prev) if compare_standings(&prev.standing, &standing) == Ordering::Equal => prev.rank,
+            _ => i as u32 + 1,
+        };
+        entries.push(LeaderboardEntry { rank, standing });
+    }
+
+    Leaderboard {
+        period,
+        generated_at: now,
+        entries,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn standing(id: &str, xp: i32, streak: i32) -> ProfileStanding {
+        ProfileStanding {
+            user_id: id.to_string(),
+            display_name: id.to_uppercase(),
+            xp,
+            level: 1,
+            current_streak: streak,
+        }
+    }
+
+    #[test]
+    fn test_ranks_by_xp_then_streak() {
+        let board = rank_standings(
+            LeaderboardPeriod::Weekly,
+            vec![standing("a", 100, 2), standing("b", 250, 0), standing("c", 100, 5)],
+            Utc::now(),
+        );
+
+        let order: Vec<&str> = board.entries.iter().map(|e| e.standing.user_id.as_str()).collect();
+        assert_eq!(order, vec!["b", "c", "a"]);
+        let ranks: Vec<u32> = board.entries.iter().map(|e| e.rank).collect();
+        assert_eq!(ranks, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_ties_share_rank() {
+        let board = rank_standings(
+            LeaderboardPeriod::AllTime,
+            vec![standing("b", 50, 1), standing("a", 50, 1), standing("c", 10, 0)],
+            Utc::now(),
+        );
+
+        let ranks: Vec<(String, u32)> = board
+            .entries
+            .iter()
+            .map(|e| (e.standing.user_id.clone(), e.rank))
+            .collect();
+        assert_eq!(
+            ranks,
+            vec![("a".to_string(), 1), ("b".to_string(), 1), ("c".to_string(), 3)]
+        );
+        assert_eq!(board.entry_for("c").unwrap().rank, 3);
+    }
+
+    #[test]
+    fn test_weekly_window_starts_monday() {
+        // Thursday 2024-01-11
+        let now = Utc.with_ymd_and_hms(2024, 1, 11, 15, 0, 0).unwrap();
+        let start = LeaderboardPeriod::Weekly.window_start(now).unwrap();
+        assert_eq!(start, Utc.with_ymd_and_hms(2024, 1, 8, 0, 0, 0).unwrap());
+        assert!(LeaderboardPeriod::AllTime.window_start(now).is_none());
+    }
+
+    #[test]
+    fn test_period_parsing() {
+        assert_eq!("Weekly".parse::<LeaderboardPeriod>().unwrap(), LeaderboardPeriod::Weekly);
+        assert_eq!(LeaderboardPeriod::AllTime.as_str(), "AllTime");
+        assert!("Monthly".parse::<LeaderboardPeriod>().is_err());
+    }
+}
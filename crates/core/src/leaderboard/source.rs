@@ -0,0 +1,142 @@
+//! Leaderboard sources
+//!
+//! A source reports standings for a period. `LocalLeaderboardSource` reads the
+//! profiles on this machine; a remote sync backend would implement the same
+//! trait and be passed alongside it to `build_leaderboard`.
+
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use std::collections::HashSet;
+
+use super::ranking::{rank_standings, Leaderboard, LeaderboardPeriod, ProfileStanding};
+use crate::db::error::DbResult;
+use crate::db::repos::UserRepository;
+
+pub trait LeaderboardSource {
+    fn standings(&self, period: LeaderboardPeriod, now: DateTime<Utc>) -> DbResult<Vec<ProfileStanding>>;
+}
+
+/// Standings for every profile in the local database
+pub struct LocalLeaderboardSource<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> LocalLeaderboardSource<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+}
+
+impl LeaderboardSource for LocalLeaderboardSource<'_> {
+    fn standings(&self, period: LeaderboardPeriod, now: DateTime<Utc>) -> DbResult<Vec<ProfileStanding>> {
+        let mut standings = Vec::new();
+
+        for user in UserRepository::get_all(self.conn)? {
+            let xp = match period.window_start(now) {
+                Some(start) => UserRepository::get_xp_earned_between(self.conn, &user.id, start, now)?,
+                None => user.total_xp,
+            };
+
+            standings.push(ProfileStanding {
+                display_name: user.profile_name(),
+                user_id: user.id,
+                xp,
+                level: user.current_level,
+                current_streak: user.current_streak,
+            });
+        }
+
+        Ok(standings)
+    }
+}
+
+/// Build a leaderboard from several sources.
+/// When sources report the same profile, the first source wins.
+pub fn build_leaderboard(
+    sources: &[&dyn LeaderboardSource],
+    period: LeaderboardPeriod,
+    now: DateTime<Utc>,
+) -> DbResult<Leaderboard> {
+    let mut seen = HashSet::new();
+    let mut standings = Vec::new();
+
+    for source in sources {
+        for standing in source.standings(period, now)? {
+            if seen.insert(standing.user_id.clone()) {
+                standings.push(standing);
+            }
+        }
+    }
+
+    Ok(rank_standings(period, standings, now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::models::User;
+
+    struct FixedSource(Vec<ProfileStanding>);
+
+    impl LeaderboardSource for FixedSource {
+        fn standings(&self, _period: LeaderboardPeriod, _now: DateTime<Utc>) -> DbResult<Vec<ProfileStanding>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn test_local_weekly_uses_xp_log() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+
+        let mut veteran = User::with_display_name("veteran".to_string(), "Veteran".to_string());
+        veteran.total_xp = 5000;
+        UserRepository::create(conn, &veteran).unwrap();
+        UserRepository::create(conn, &User::with_display_name("newcomer".to_string(), "Newcomer".to_string())).unwrap();
+        UserRepository::update_xp(conn, "newcomer", 120).unwrap();
+
+        let local = LocalLeaderboardSource::new(conn);
+        let now = Utc::now() + chrono::Duration::seconds(1);
+
+        let weekly = build_leaderboard(&[&local], LeaderboardPeriod::Weekly, now).unwrap();
+        assert_eq!(weekly.entries[0].standing.user_id, "newcomer");
+        assert_eq!(weekly.entries[0].standing.xp, 120);
+        assert_eq!(weekly.entry_for("veteran").unwrap().standing.xp, 0);
+
+        let all_time = build_leaderboard(&[&local], LeaderboardPeriod::AllTime, now).unwrap();
+        assert_eq!(all_time.entries[0].standing.user_id, "veteran");
+    }
+
+    #[test]
+    fn test_first_source_wins_on_duplicates() {
+        let local = FixedSource(vec![ProfileStanding {
+            user_id: "u1".to_string(),
+            display_name: "Local".to_string(),
+            xp: 10,
+            level: 1,
+            current_streak: 0,
+        }]);
+        let remote = FixedSource(vec![
+            ProfileStanding {
+                user_id: "u1".to_string(),
+                display_name: "Stale".to_string(),
+                xp: 5,
+                level: 1,
+                current_streak: 0,
+            },
+            ProfileStanding {
+                user_id: "u2".to_string(),
+                display_name: "Remote".to_string(),
+                xp: 20,
+                level: 2,
+                current_streak: 3,
+            },
+        ]);
+
+        let board = build_leaderboard(&[&local, &remote], LeaderboardPeriod::Weekly, Utc::now()).unwrap();
+        assert_eq!(board.entries.len(), 2);
+        assert_eq!(board.entry_for("u1").unwrap().standing.display_name, "Local");
+        assert_eq!(board.entries[0].standing.user_id, "u2");
+    }
+}
@@ -1,11 +1,35 @@
+pub mod analytics;
+pub mod anki;
+pub mod backup;
 pub mod badges;
 pub mod db;
+pub mod embeddings;
+pub mod focus;
 pub mod gamification;
+pub mod goals;
+pub mod hints;
+pub mod hooks;
+pub mod leaderboard;
 pub mod models;
+pub mod pacing;
+pub mod quests;
+pub mod reminders;
+pub mod sandbox;
+pub mod scheduler;
+pub mod services;
+pub mod settings;
+pub mod setup;
+pub mod simulation;
 pub mod spaced_repetition;
+pub mod stats;
+pub mod sync;
+pub mod xapi;
 
 pub use badges::*;
-pub use db::connection::{AppDatabase, Database};
+pub use db::connection::{AppDatabase, Database, PoolConfig};
 pub use db::error::DbError;
 pub use gamification::*;
+pub use leaderboard::*;
+pub use quests::*;
+pub use simulation::*;
 pub use spaced_repetition::*;
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// One day of a user's learning, rolled up from session time, XP and
+/// completed nodes. Days are UTC dates (YYYY-MM-DD).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct DailyActivity {
+    pub user_id: String,
+    pub day: String,
+    pub active_minutes: i64,
+    pub xp: i64,
+    pub nodes_completed: i64,
+}
@@ -0,0 +1,109 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Version of the event schema batches are uploaded in. Bump it whenever an
+/// event gains, loses or changes a field.
+pub const ANALYTICS_SCHEMA_VERSION: u32 = 1;
+
+/// Whether a learner shares usage analytics, and where they go
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct AnalyticsSettings {
+    /// Off until the learner opts in
+    #[serde(default)]
+    pub enabled: bool,
+    /// Where batches are uploaded; events stay queued until one is set
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+/// Why a challenge attempt didn't pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    CompileError,
+    TestFailure,
+    Timeout,
+    Panic,
+    OutOfMemory,
+    ResourceLimit,
+    PolicyViolation,
+    RuntimeError,
+}
+
+/// What happened. Only ids from the curriculum and coarse numbers: never
+/// the learner's code, answers or anything they typed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AnalyticsEvent {
+    NodeCompleted { node_id: String, node_type: String, minutes: i32 },
+    QuizSubmitted { node_id: String, score_percent: i32, passed: bool, minutes: i32 },
+    ChallengeAttempted { node_id: String, passed: bool, error: Option<ErrorCategory> },
+}
+
+impl AnalyticsEvent {
+    pub fn node_id(&self) -> &str {
+        match self {
+            AnalyticsEvent::NodeCompleted { node_id, .. }
+            | AnalyticsEvent::QuizSubmitted { node_id, .. }
+            | AnalyticsEvent::ChallengeAttempted { node_id, .. } => node_id,
+        }
+    }
+}
+
+/// An event with the curriculum it happened in and the day it was recorded.
+/// No user id and no time of day, so events can't be tied to a learner or
+/// lined up with their sessions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct RecordedEvent {
+    pub recorded_on: NaiveDate,
+    /// Curriculum title and version, as its manifest gives them
+    pub curriculum: String,
+    pub curriculum_version: String,
+    pub event: AnalyticsEvent,
+}
+
+/// An event waiting to be uploaded
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuedEvent {
+    pub id: i64,
+    pub recorded: RecordedEvent,
+}
+
+/// What's uploaded, and what the learner is shown before it is
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct AnalyticsBatch {
+    pub schema_version: u32,
+    /// Random id for this profile's uploads, replaced whenever its data is
+    /// purged
+    pub anonymous_id: String,
+    pub events: Vec<RecordedEvent>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_json_shape() {
+        let event = AnalyticsEvent::ChallengeAttempted {
+            node_id: "week1-day2-challenge".to_string(),
+            passed: false,
+            error: Some(ErrorCategory::CompileError),
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({
+                "event": "challenge_attempted",
+                "node_id": "week1-day2-challenge",
+                "passed": false,
+                "error": "compile_error"
+            })
+        );
+        assert_eq!(event.node_id(), "week1-day2-challenge");
+    }
+}
@@ -0,0 +1,38 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// A bearer token a learner's client uses to reach a shared server. Only the
+/// token's hash is stored; the token itself is shown once, when issued.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: String,
+    pub user_id: String,
+    /// What the token is for, e.g. the device it was given to
+    pub label: String,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl ApiToken {
+    /// A new token for `user_id`, with the secret to hand out
+    pub fn issue(user_id: String, label: String) -> (Self, String) {
+        let secret = format!("glp_{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        let token = Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            label,
+            token_hash: Self::hash(&secret),
+            created_at: Utc::now(),
+            last_used_at: None,
+        };
+        (token, secret)
+    }
+
+    pub fn hash(secret: &str) -> String {
+        Sha256::digest(secret.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
@@ -4,6 +4,7 @@ use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub enum ArtifactType {
     Readme,
     Design,
@@ -36,6 +37,7 @@ impl ArtifactType {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct ArtifactSubmission {
     pub id: String,
     pub user_id: String,
@@ -47,6 +49,9 @@ pub struct ArtifactSubmission {
     pub xp_earned: i32,
     pub submitted_at: DateTime<Utc>,
     pub graded_at: Option<DateTime<Utc>>,
+    /// Commit the artifact was read from, when it was collected from a git repo
+    #[serde(default)]
+    pub commit_hash: Option<String>,
 }
 
 impl ArtifactSubmission {
@@ -67,9 +72,15 @@ impl ArtifactSubmission {
             xp_earned: 0,
             submitted_at: Utc::now(),
             graded_at: None,
+            commit_hash: None,
         }
     }
 
+    pub fn with_commit(mut self, commit_hash: impl Into<String>) -> Self {
+        self.commit_hash = Some(commit_hash.into());
+        self
+    }
+
     pub fn hash_content(content: &str) -> String {
         let mut hasher = Sha256::new();
         hasher.update(content.as_bytes());
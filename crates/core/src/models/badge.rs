@@ -1,7 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::badges::BadgeCriteria;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub enum BadgeCategory {
     Streak,
     Level,
@@ -33,7 +37,54 @@ impl BadgeCategory {
     }
 }
 
+/// Tier of a tiered badge, ordered from lowest to highest
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum BadgeTier {
+    Bronze,
+    Silver,
+    Gold,
+}
+
+impl BadgeTier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BadgeTier::Bronze => "Bronze",
+            BadgeTier::Silver => "Silver",
+            BadgeTier::Gold => "Gold",
+        }
+    }
+}
+
+impl FromStr for BadgeTier {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Bronze" => Ok(BadgeTier::Bronze),
+            "Silver" => Ok(BadgeTier::Silver),
+            "Gold" => Ok(BadgeTier::Gold),
+            _ => Err(format!("Invalid badge tier: {}", s)),
+        }
+    }
+}
+
+/// Threshold at which a tiered badge reaches a given tier
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct BadgeTierThreshold {
+    pub tier: BadgeTier,
+    pub threshold: f64,
+}
+
+impl BadgeTierThreshold {
+    pub fn new(tier: BadgeTier, threshold: f64) -> Self {
+        Self { tier, threshold }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct BadgeDefinition {
     pub id: String,
     pub name: String,
@@ -41,6 +92,35 @@ pub struct BadgeDefinition {
     pub icon: String,
     pub threshold: f64,
     pub category: BadgeCategory,
+    /// Composite criteria; when set it replaces the category threshold check
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub criteria: Option<BadgeCriteria>,
+    /// Tier thresholds in ascending order; `threshold` matches the lowest tier
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tiers: Vec<BadgeTierThreshold>,
+}
+
+impl BadgeDefinition {
+    pub fn is_tiered(&self) -> bool {
+        !self.tiers.is_empty()
+    }
+
+    /// Highest tier reached by a value, if any
+    pub fn tier_for_value(&self, value: f64) -> Option<BadgeTier> {
+        self.tiers
+            .iter()
+            .filter(|t| value >= t.threshold)
+            .map(|t| t.tier)
+            .max()
+    }
+
+    /// The first tier above `current`, or the lowest tier when nothing is earned
+    pub fn next_tier(&self, current: Option<BadgeTier>) -> Option<&BadgeTierThreshold> {
+        self.tiers
+            .iter()
+            .filter(|t| current.is_none_or(|c| t.tier > c))
+            .min_by_key(|t| t.tier)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,6 +129,9 @@ pub struct BadgeProgress {
     pub badge_id: String,
     pub current_value: f64,
     pub earned_at: Option<DateTime<Utc>>,
+    /// Highest tier earned for tiered badges
+    #[serde(default)]
+    pub tier: Option<BadgeTier>,
 }
 
 impl BadgeProgress {
@@ -58,6 +141,7 @@ impl BadgeProgress {
             badge_id,
             current_value: 0.0,
             earned_at: None,
+            tier: None,
         }
     }
 
@@ -72,6 +156,23 @@ impl BadgeProgress {
         }
     }
 
+    /// Update shared progress for a tiered badge.
+    /// Returns the new tier if the value reached a higher tier than before.
+    pub fn update_tiered_progress(&mut self, value: f64, badge: &BadgeDefinition) -> Option<BadgeTier> {
+        self.current_value = value;
+
+        let reached = badge.tier_for_value(value)?;
+        if self.tier.is_some_and(|t| t >= reached) {
+            return None;
+        }
+
+        self.tier = Some(reached);
+        if self.earned_at.is_none() {
+            self.earned_at = Some(Utc::now());
+        }
+        Some(reached)
+    }
+
     pub fn progress_percentage(&self, threshold: f64) -> f64 {
         if threshold == 0.0 {
             return 100.0;
@@ -102,4 +203,58 @@ mod tests {
         progress.update_progress(7.0, 7.0);
         assert!(progress.is_earned());
     }
+
+    fn tiered_badge() -> BadgeDefinition {
+        BadgeDefinition {
+            id: "streak_keeper".to_string(),
+            name: "Streak Keeper".to_string(),
+            description: "Keep a learning streak".to_string(),
+            icon: "🔥".to_string(),
+            threshold: 7.0,
+            category: BadgeCategory::Streak,
+            criteria: None,
+            tiers: vec![
+                BadgeTierThreshold::new(BadgeTier::Bronze, 7.0),
+                BadgeTierThreshold::new(BadgeTier::Silver, 30.0),
+                BadgeTierThreshold::new(BadgeTier::Gold, 100.0),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_tier_for_value() {
+        let badge = tiered_badge();
+        assert_eq!(badge.tier_for_value(6.0), None);
+        assert_eq!(badge.tier_for_value(7.0), Some(BadgeTier::Bronze));
+        assert_eq!(badge.tier_for_value(99.0), Some(BadgeTier::Silver));
+        assert_eq!(badge.tier_for_value(100.0), Some(BadgeTier::Gold));
+
+        assert_eq!(badge.next_tier(None).unwrap().tier, BadgeTier::Bronze);
+        assert_eq!(badge.next_tier(Some(BadgeTier::Bronze)).unwrap().tier, BadgeTier::Silver);
+        assert!(badge.next_tier(Some(BadgeTier::Gold)).is_none());
+    }
+
+    #[test]
+    fn test_tiered_progress_only_upgrades() {
+        let badge = tiered_badge();
+        let mut progress = BadgeProgress::new("user1".to_string(), badge.id.clone());
+
+        assert_eq!(progress.update_tiered_progress(3.0, &badge), None);
+        assert!(!progress.is_earned());
+
+        assert_eq!(progress.update_tiered_progress(30.0, &badge), Some(BadgeTier::Silver));
+        assert!(progress.is_earned());
+        let earned_at = progress.earned_at;
+
+        // Same tier again is not an upgrade
+        assert_eq!(progress.update_tiered_progress(45.0, &badge), None);
+
+        // A broken streak never downgrades the earned tier
+        assert_eq!(progress.update_tiered_progress(1.0, &badge), None);
+        assert_eq!(progress.tier, Some(BadgeTier::Silver));
+        assert_eq!(progress.current_value, 1.0);
+
+        assert_eq!(progress.update_tiered_progress(100.0, &badge), Some(BadgeTier::Gold));
+        assert_eq!(progress.earned_at, earned_at);
+    }
 }
@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The best result a learner has had at a challenge, or at one artifact of
+/// a checkpoint, and the XP it has earned so far. An attempt that improves
+/// on the best earns only what its XP adds to what was already awarded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BestAttempt {
+    pub user_id: String,
+    pub curriculum_id: Option<String>,
+    pub node_id: String,
+    /// The artifact type for a checkpoint artifact; empty for a challenge
+    pub part: String,
+    /// The attempt or submission with the best result
+    pub best_attempt_id: Option<String>,
+    /// Highest share of tests passed, as a percentage, once tests have run
+    pub best_verification_score: Option<f64>,
+    /// Highest grade, as a percentage
+    pub best_grade: Option<f64>,
+    pub xp_awarded: i32,
+    pub attempts: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl BestAttempt {
+    pub fn new(user_id: String, curriculum_id: Option<String>, node_id: String, part: String) -> Self {
+        Self {
+            user_id,
+            curriculum_id,
+            node_id,
+            part,
+            best_attempt_id: None,
+            best_verification_score: None,
+            best_grade: None,
+            xp_awarded: 0,
+            attempts: 0,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Whether a verification score beats the best so far
+    pub fn improves_verification(&self, score: f64) -> bool {
+        self.best_verification_score.is_none_or(|best| score > best)
+    }
+
+    /// Whether a grade beats the best so far
+    pub fn improves_grade(&self, grade: f64) -> bool {
+        self.best_grade.is_none_or(|best| grade > best)
+    }
+
+    /// What an attempt worth `xp` earns on top of what's been awarded
+    pub fn xp_delta(&self, xp: i32) -> i32 {
+        (xp - self.xp_awarded).max(0)
+    }
+
+    /// Record a verified attempt and the XP it earned
+    pub fn record_verification(&mut self, attempt_id: &str, score: Option<f64>, xp_earned: i32) {
+        if let Some(score) = score.filter(|score| self.improves_verification(*score)) {
+            self.best_verification_score = Some(score);
+            self.best_attempt_id = Some(attempt_id.to_string());
+        }
+        self.record(xp_earned);
+    }
+
+    /// Record a graded submission and the XP it earned
+    pub fn record_grade(&mut self, submission_id: &str, grade: f64, xp_earned: i32) {
+        if self.improves_grade(grade) {
+            self.best_grade = Some(grade);
+            self.best_attempt_id = Some(submission_id.to_string());
+        }
+        self.record(xp_earned);
+    }
+
+    fn record(&mut self, xp_earned: i32) {
+        self.xp_awarded += xp_earned;
+        self.attempts += 1;
+        self.updated_at = Utc::now();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_improvements_earn_the_difference() {
+        let mut best = BestAttempt::new("user".to_string(), None, "checkpoint-1".to_string(), "README".to_string());
+        assert!(best.improves_grade(70.0));
+        assert_eq!(best.xp_delta(80), 80);
+        best.record_grade("first", 70.0, 80);
+
+        assert!(!best.improves_grade(65.0));
+        assert!(best.improves_grade(90.0));
+        assert_eq!(best.xp_delta(110), 30);
+        assert_eq!(best.xp_delta(60), 0);
+        best.record_grade("second", 90.0, 30);
+        best.record_grade("third", 65.0, 0);
+
+        assert_eq!(best.best_grade, Some(90.0));
+        assert_eq!(best.best_attempt_id.as_deref(), Some("second"));
+        assert_eq!((best.xp_awarded, best.attempts), (110, 3));
+    }
+}
@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A node the learner saved to come back to. Bookmarks form a reading
+/// queue ordered by `position`, lowest first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub user_id: String,
+    pub curriculum_id: Option<String>,
+    pub node_id: String,
+    pub position: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Bookmark {
+    pub fn new(user_id: String, curriculum_id: Option<String>, node_id: String, position: i64) -> Self {
+        Self {
+            user_id,
+            curriculum_id,
+            node_id,
+            position,
+            created_at: Utc::now(),
+        }
+    }
+}
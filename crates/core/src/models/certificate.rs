@@ -0,0 +1,149 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use crate::models::Curriculum;
+
+/// Proof that a learner finished a curriculum. The verification hash is an
+/// HMAC-SHA256 over the other fields, keyed with this install's signing key,
+/// so an edited certificate no longer verifies.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct Certificate {
+    pub id: String,
+    pub user_id: String,
+    pub curriculum_id: String,
+    pub learner_name: String,
+    pub curriculum_name: String,
+    pub curriculum_version: String,
+    pub completed_at: DateTime<Utc>,
+    pub verification_hash: String,
+}
+
+impl Certificate {
+    pub fn issue(
+        user_id: String,
+        learner_name: String,
+        curriculum: &Curriculum,
+        completed_at: DateTime<Utc>,
+        key: &[u8],
+    ) -> Self {
+        let mut certificate = Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            curriculum_id: curriculum.id.clone(),
+            learner_name,
+            curriculum_name: curriculum.name.clone(),
+            curriculum_version: curriculum.version.clone(),
+            completed_at,
+            verification_hash: String::new(),
+        };
+        certificate.verification_hash = certificate.sign(key);
+        certificate
+    }
+
+    fn sign(&self, key: &[u8]) -> String {
+        let fields = serde_json::to_vec(&(
+            &self.id,
+            &self.user_id,
+            &self.curriculum_id,
+            &self.learner_name,
+            &self.curriculum_name,
+            &self.curriculum_version,
+            self.completed_at.to_rfc3339(),
+        ))
+        .unwrap_or_default();
+        hex(&hmac_sha256(key, &fields))
+    }
+
+    /// The certificate is unchanged since it was issued with `key`
+    pub fn verify(&self, key: &[u8]) -> bool {
+        self.sign(key) == self.verification_hash
+    }
+
+    pub fn to_svg(&self) -> String {
+        format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="1000" height="700" viewBox="0 0 1000 700">
+  <rect width="1000" height="700" fill="#fdfaf3"/>
+  <rect x="30" y="30" width="940" height="640" fill="none" stroke="#b8860b" stroke-width="6"/>
+  <text x="500" y="150" text-anchor="middle" font-family="Georgia, serif" font-size="48" fill="#333">Certificate of Completion</text>
+  <text x="500" y="240" text-anchor="middle" font-family="Georgia, serif" font-size="22" fill="#555">This certifies that</text>
+  <text x="500" y="310" text-anchor="middle" font-family="Georgia, serif" font-size="42" fill="#222">{}</text>
+  <text x="500" y="380" text-anchor="middle" font-family="Georgia, serif" font-size="22" fill="#555">has completed</text>
+  <text x="500" y="440" text-anchor="middle" font-family="Georgia, serif" font-size="32" fill="#222">{} (v{})</text>
+  <text x="500" y="510" text-anchor="middle" font-family="Georgia, serif" font-size="20" fill="#555">on {}</text>
+  <text x="500" y="620" text-anchor="middle" font-family="monospace" font-size="12" fill="#888">Certificate {} · verification {}</text>
+</svg>
+"##,
+            escape_xml(&self.learner_name),
+            escape_xml(&self.curriculum_name),
+            escape_xml(&self.curriculum_version),
+            self.completed_at.format("%B %-d, %Y"),
+            self.id,
+            self.verification_hash,
+        )
+    }
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK_SIZE: usize = 64;
+
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hmac_sha256_matches_rfc_4231() {
+        // Test case 2
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(hex(&mac), "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+    }
+
+    #[test]
+    fn test_certificate_verifies_until_edited() {
+        let curriculum = Curriculum::new("Rust Bootcamp".to_string(), "1.0".to_string(), "rust".to_string());
+        let certificate = Certificate::issue(
+            "user".to_string(),
+            "Ada <Lovelace>".to_string(),
+            &curriculum,
+            Utc::now(),
+            b"install key",
+        );
+        assert!(certificate.verify(b"install key"));
+        assert!(!certificate.verify(b"another key"));
+
+        let mut edited = certificate.clone();
+        edited.learner_name = "Someone Else".to_string();
+        assert!(!edited.verify(b"install key"));
+
+        assert!(certificate.to_svg().contains("Ada &lt;Lovelace&gt;"));
+    }
+}
@@ -1,23 +1,63 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct ChallengeAttempt {
     pub id: String,
     pub user_id: String,
+    pub curriculum_id: Option<String>,
     pub challenge_id: String,
     pub node_id: String,
     pub code_hash: String,
+    /// Missing for attempts recorded before code was kept
+    pub code: Option<String>,
     pub tests_passed: i32,
     pub tests_failed: i32,
     pub stdout: Option<String>,
     pub stderr: Option<String>,
     pub xp_earned: i32,
+    /// The runner's full result, as JSON
+    pub verification_json: Option<String>,
+    /// Signals for an instructor to look into; they never block a submission
+    #[serde(default)]
+    pub flags: Vec<AttemptFlag>,
     pub submitted_at: DateTime<Utc>,
 }
 
+/// Share of a node's estimated time below which a first pass is flagged
+pub const FAST_SOLVE_SHARE: f64 = 0.1;
+
+/// Something about a submission worth a second look. Flags are heuristics,
+/// so they're recorded for review rather than acted on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AttemptFlag {
+    /// The code is the bundled reference solution, give or take formatting
+    /// and comments
+    MatchesReference,
+    /// Passed far sooner after starting the node than it's expected to take
+    FastSolve { seconds: i64, expected_minutes: u32 },
+    /// Files of an exported workspace that learners aren't meant to change
+    /// were edited, added or removed
+    EditedOutsideAllowed { files: Vec<String> },
+}
+
+impl AttemptFlag {
+    /// A fast-solve flag if `elapsed` is under [`FAST_SOLVE_SHARE`] of the
+    /// node's estimated time
+    pub fn fast_solve(elapsed: Duration, expected_minutes: u32) -> Option<Self> {
+        let threshold = expected_minutes as f64 * 60.0 * FAST_SOLVE_SHARE;
+        (expected_minutes > 0 && (elapsed.num_seconds() as f64) < threshold).then(|| AttemptFlag::FastSolve {
+            seconds: elapsed.num_seconds().max(0),
+            expected_minutes,
+        })
+    }
+}
+
 impl ChallengeAttempt {
     pub fn new(
         user_id: String,
@@ -33,18 +73,37 @@ impl ChallengeAttempt {
         Self {
             id: Uuid::new_v4().to_string(),
             user_id,
+            curriculum_id: None,
             challenge_id,
             node_id,
             code_hash: Self::hash_code(code),
+            code: Some(code.to_string()),
             tests_passed,
             tests_failed,
             stdout,
             stderr,
             xp_earned,
+            verification_json: None,
+            flags: Vec::new(),
             submitted_at: Utc::now(),
         }
     }
 
+    pub fn with_curriculum(mut self, curriculum_id: String) -> Self {
+        self.curriculum_id = Some(curriculum_id);
+        self
+    }
+
+    pub fn with_verification(mut self, verification_json: String) -> Self {
+        self.verification_json = Some(verification_json);
+        self
+    }
+
+    pub fn with_flags(mut self, flags: Vec<AttemptFlag>) -> Self {
+        self.flags = flags;
+        self
+    }
+
     pub fn hash_code(code: &str) -> String {
         let mut hasher = Sha256::new();
         hasher.update(code.as_bytes());
@@ -64,6 +123,42 @@ impl ChallengeAttempt {
     }
 }
 
+/// Code in the editor for a challenge, saved as the student types
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ChallengeDraft {
+    pub user_id: String,
+    pub curriculum_id: Option<String>,
+    pub node_id: String,
+    pub code: String,
+    /// The attempt whose code was restored into the editor, if the draft
+    /// hasn't been edited since
+    pub restored_from: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ChallengeDraft {
+    pub fn new(user_id: String, curriculum_id: Option<String>, node_id: String, code: String) -> Self {
+        Self {
+            user_id,
+            curriculum_id,
+            node_id,
+            code,
+            restored_from: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// A draft holding an earlier attempt's code, if its code was kept
+    pub fn restored(attempt: &ChallengeAttempt) -> Option<Self> {
+        let code = attempt.code.clone()?;
+        Some(Self {
+            restored_from: Some(attempt.id.clone()),
+            ..Self::new(attempt.user_id.clone(), attempt.curriculum_id.clone(), attempt.node_id.clone(), code)
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,4 +190,15 @@ mod tests {
         assert_eq!(hash1, hash2);
         assert_ne!(hash1, hash3);
     }
+
+    #[test]
+    fn test_fast_solve_threshold() {
+        // 10% of 30 minutes is three
+        assert_eq!(
+            AttemptFlag::fast_solve(Duration::seconds(90), 30),
+            Some(AttemptFlag::FastSolve { seconds: 90, expected_minutes: 30 })
+        );
+        assert_eq!(AttemptFlag::fast_solve(Duration::minutes(5), 30), None);
+        assert_eq!(AttemptFlag::fast_solve(Duration::seconds(10), 0), None);
+    }
 }
@@ -0,0 +1,201 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A group of local profiles tracked together by an instructor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct Cohort {
+    pub id: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Cohort {
+    pub fn new(id: String, name: String) -> Self {
+        Self {
+            id,
+            name,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Aggregate progress for one content node across a cohort
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct CohortNodeStats {
+    pub node_id: String,
+    pub learners: u32,
+    pub started: u32,
+    pub completed: u32,
+    pub avg_attempts: f64,
+    pub avg_time_mins: f64,
+    /// Mean cohort mastery over the node's skills, when the skills are known
+    pub avg_mastery: Option<f64>,
+}
+
+impl CohortNodeStats {
+    pub fn completion_rate(&self) -> f64 {
+        if self.learners == 0 {
+            return 0.0;
+        }
+        self.completed as f64 / self.learners as f64
+    }
+}
+
+/// Aggregate mastery for one skill across a cohort
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct CohortSkillStats {
+    pub skill_id: String,
+    pub learners: u32,
+    pub avg_score: f64,
+    pub min_score: f64,
+    pub max_score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct CohortReport {
+    pub cohort: Cohort,
+    pub generated_at: DateTime<Utc>,
+    pub member_count: u32,
+    pub nodes: Vec<CohortNodeStats>,
+    pub skills: Vec<CohortSkillStats>,
+}
+
+impl CohortReport {
+    /// Assemble a report, filling each node's mastery from the skills it teaches
+    pub fn new(
+        cohort: Cohort,
+        member_count: u32,
+        mut nodes: Vec<CohortNodeStats>,
+        skills: Vec<CohortSkillStats>,
+        node_skills: &HashMap<String, Vec<String>>,
+    ) -> Self {
+        let skill_avg: HashMap<&str, f64> = skills
+            .iter()
+            .map(|s| (s.skill_id.as_str(), s.avg_score))
+            .collect();
+
+        for node in &mut nodes {
+            let scores: Vec<f64> = node_skills
+                .get(&node.node_id)
+                .map(|ids| ids.iter().filter_map(|id| skill_avg.get(id.as_str()).copied()).collect())
+                .unwrap_or_default();
+
+            if !scores.is_empty() {
+                node.avg_mastery = Some(scores.iter().sum::<f64>() / scores.len() as f64);
+            }
+        }
+
+        Self {
+            cohort,
+            generated_at: Utc::now(),
+            member_count,
+            nodes,
+            skills,
+        }
+    }
+
+    /// Render the per-node report as CSV
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "node_id,learners,started,completed,completion_rate,avg_attempts,avg_time_mins,avg_mastery\n",
+        );
+
+        for node in &self.nodes {
+            csv.push_str(&format!(
+                "{},{},{},{},{:.2},{:.2},{:.1},{}\n",
+                csv_field(&node.node_id),
+                node.learners,
+                node.started,
+                node.completed,
+                node.completion_rate(),
+                node.avg_attempts,
+                node.avg_time_mins,
+                node.avg_mastery.map(|m| format!("{:.2}", m)).unwrap_or_default(),
+            ));
+        }
+
+        csv
+    }
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or newline
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str, completed: u32) -> CohortNodeStats {
+        CohortNodeStats {
+            node_id: id.to_string(),
+            learners: 4,
+            started: 4,
+            completed,
+            avg_attempts: 1.5,
+            avg_time_mins: 12.0,
+            avg_mastery: None,
+        }
+    }
+
+    fn skill(id: &str, avg: f64) -> CohortSkillStats {
+        CohortSkillStats {
+            skill_id: id.to_string(),
+            learners: 4,
+            avg_score: avg,
+            min_score: 0.0,
+            max_score: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_completion_rate() {
+        assert_eq!(node("n1", 3).completion_rate(), 0.75);
+        let empty = CohortNodeStats { learners: 0, ..node("n1", 0) };
+        assert_eq!(empty.completion_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_report_fills_node_mastery_from_skills() {
+        let mut node_skills = HashMap::new();
+        node_skills.insert("n1".to_string(), vec!["ownership".to_string(), "borrowing".to_string()]);
+        node_skills.insert("n2".to_string(), vec!["unknown".to_string()]);
+
+        let report = CohortReport::new(
+            Cohort::new("c1".to_string(), "Period 1".to_string()),
+            4,
+            vec![node("n1", 2), node("n2", 1)],
+            vec![skill("ownership", 0.6), skill("borrowing", 0.4)],
+            &node_skills,
+        );
+
+        assert!((report.nodes[0].avg_mastery.unwrap() - 0.5).abs() < 1e-9);
+        assert_eq!(report.nodes[1].avg_mastery, None);
+    }
+
+    #[test]
+    fn test_csv_output() {
+        let report = CohortReport::new(
+            Cohort::new("c1".to_string(), "Period 1".to_string()),
+            4,
+            vec![node("week1,day1", 3)],
+            vec![],
+            &HashMap::new(),
+        );
+
+        let csv = report.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "node_id,learners,started,completed,completion_rate,avg_attempts,avg_time_mins,avg_mastery");
+        assert_eq!(lines[1], "\"week1,day1\",4,4,3,0.75,1.50,12.0,");
+    }
+}
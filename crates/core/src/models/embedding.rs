@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum EmbeddedKind {
+    Lecture,
+    QuizQuestion,
+}
+
+impl EmbeddedKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmbeddedKind::Lecture => "Lecture",
+            EmbeddedKind::QuizQuestion => "QuizQuestion",
+        }
+    }
+}
+
+impl FromStr for EmbeddedKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Lecture" => Ok(EmbeddedKind::Lecture),
+            "QuizQuestion" => Ok(EmbeddedKind::QuizQuestion),
+            _ => Err(format!("Invalid embedded kind: {}", s)),
+        }
+    }
+}
+
+/// A lecture or quiz question of a curriculum, embedded for similarity search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentEmbedding {
+    pub curriculum_id: String,
+    /// The node id for a lecture, `node_id/question_id` for a question
+    pub item_id: String,
+    pub node_id: String,
+    pub kind: EmbeddedKind,
+    pub skills: Vec<String>,
+    /// Vectors are only comparable within one model
+    pub model: String,
+    pub vector: Vec<f32>,
+    pub indexed_at: DateTime<Utc>,
+}
+
+impl ContentEmbedding {
+    pub fn new(
+        curriculum_id: String,
+        item_id: String,
+        node_id: String,
+        kind: EmbeddedKind,
+        skills: Vec<String>,
+        model: String,
+        vector: Vec<f32>,
+    ) -> Self {
+        Self {
+            curriculum_id,
+            item_id,
+            node_id,
+            kind,
+            skills,
+            model,
+            vector,
+            indexed_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Review items for imported flashcards are keyed by this prefix and the
+/// card id, alongside the quiz ids of ordinary review items
+pub const FLASHCARD_REVIEW_PREFIX: &str = "card:";
+
+/// A card imported from a CSV file or an Anki deck, reviewed under one skill
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct Flashcard {
+    pub id: String,
+    pub user_id: String,
+    pub curriculum_id: Option<String>,
+    pub skill_id: String,
+    pub front: String,
+    pub back: String,
+    pub tags: Vec<String>,
+    /// Name of the file the card was imported from
+    pub source: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Flashcard {
+    pub fn new(
+        user_id: String,
+        curriculum_id: Option<String>,
+        skill_id: String,
+        front: String,
+        back: String,
+        source: String,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            curriculum_id,
+            skill_id,
+            front,
+            back,
+            tags: Vec::new(),
+            source,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Id of the card's review item
+    pub fn review_id(&self) -> String {
+        format!("{}{}", FLASHCARD_REVIEW_PREFIX, self.id)
+    }
+
+    /// Card id from a review item id, if the item is a flashcard
+    pub fn id_from_review(review_id: &str) -> Option<&str> {
+        review_id.strip_prefix(FLASHCARD_REVIEW_PREFIX)
+    }
+}
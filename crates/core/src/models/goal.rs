@@ -0,0 +1,137 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// How far behind the expected pace a goal can fall before it counts as
+/// behind, as a fraction of the goal
+pub const GOAL_BEHIND_MARGIN: f64 = 0.1;
+
+/// What a goal asks for. Weeks carry their node ids, resolved from the
+/// curriculum when the goal is set, so progress can be read from the database
+/// alone.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(tag = "kind")]
+pub enum GoalTarget {
+    /// Complete every node in a week
+    CompleteWeek { week_id: String, node_ids: Vec<String> },
+    /// Reach a mastery score (0.0-1.0) in a skill
+    ReachMastery { skill_id: String, score: f64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum GoalState {
+    Achieved,
+    OnTrack,
+    Behind,
+    Missed,
+}
+
+/// A target the learner set themselves, to reach by a deadline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct Goal {
+    pub id: String,
+    pub user_id: String,
+    pub curriculum_id: Option<String>,
+    pub title: String,
+    pub target: GoalTarget,
+    pub deadline: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    /// Set the first time the target is reached; the goal stays achieved
+    /// even if mastery later decays
+    pub achieved_at: Option<DateTime<Utc>>,
+}
+
+impl Goal {
+    pub fn new(
+        user_id: String,
+        curriculum_id: Option<String>,
+        title: String,
+        target: GoalTarget,
+        deadline: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            curriculum_id,
+            title,
+            target,
+            deadline,
+            created_at: Utc::now(),
+            achieved_at: None,
+        }
+    }
+
+    /// Share of the time between setting the goal and its deadline that has passed
+    pub fn expected_progress(&self, now: DateTime<Utc>) -> f64 {
+        let total = (self.deadline - self.created_at).num_seconds();
+        if total <= 0 {
+            return 1.0;
+        }
+        ((now - self.created_at).num_seconds() as f64 / total as f64).clamp(0.0, 1.0)
+    }
+
+    /// Where the goal stands given its current progress (0.0-1.0)
+    pub fn state(&self, progress: f64, now: DateTime<Utc>) -> GoalState {
+        if self.achieved_at.is_some() || progress >= 1.0 {
+            GoalState::Achieved
+        } else if now >= self.deadline {
+            GoalState::Missed
+        } else if progress + GOAL_BEHIND_MARGIN < self.expected_progress(now) {
+            GoalState::Behind
+        } else {
+            GoalState::OnTrack
+        }
+    }
+}
+
+/// A goal with its progress worked out
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct GoalStatus {
+    #[serde(flatten)]
+    pub goal: Goal,
+    /// How much of the target is done, 0.0-1.0
+    pub progress: f64,
+    /// How much should be done by now to finish on time, 0.0-1.0
+    pub expected_progress: f64,
+    pub state: GoalState,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn goal(days: i64) -> Goal {
+        Goal::new(
+            "user".to_string(),
+            None,
+            "Master ownership".to_string(),
+            GoalTarget::ReachMastery { skill_id: "ownership".to_string(), score: 0.8 },
+            Utc::now() + Duration::days(days),
+        )
+    }
+
+    #[test]
+    fn test_goal_state() {
+        let mut goal = goal(10);
+        goal.created_at = goal.deadline - Duration::days(10);
+        let halfway = goal.created_at + Duration::days(5);
+
+        assert!((goal.expected_progress(halfway) - 0.5).abs() < 1e-9);
+        assert_eq!(goal.state(0.45, halfway), GoalState::OnTrack);
+        assert_eq!(goal.state(0.3, halfway), GoalState::Behind);
+        assert_eq!(goal.state(1.0, halfway), GoalState::Achieved);
+        assert_eq!(goal.state(0.9, goal.deadline), GoalState::Missed);
+    }
+
+    #[test]
+    fn test_achieved_goal_stays_achieved() {
+        let mut goal = goal(10);
+        goal.achieved_at = Some(Utc::now());
+        assert_eq!(goal.state(0.2, goal.deadline + Duration::days(1)), GoalState::Achieved);
+    }
+}
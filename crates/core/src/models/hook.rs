@@ -0,0 +1,160 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Failed deliveries before a hook event is given up on
+pub const MAX_HOOK_ATTEMPTS: i32 = 5;
+
+/// Learning events hooks can be run on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum HookEventKind {
+    BadgeEarned,
+    CheckpointPassed,
+    StreakMilestone,
+}
+
+/// What happened, as sent to a hook
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum HookEvent {
+    BadgeEarned { badge_id: String, name: String },
+    CheckpointPassed { checkpoint_id: String, title: String },
+    StreakMilestone { days: i32 },
+}
+
+impl HookEvent {
+    pub fn kind(&self) -> HookEventKind {
+        match self {
+            HookEvent::BadgeEarned { .. } => HookEventKind::BadgeEarned,
+            HookEvent::CheckpointPassed { .. } => HookEventKind::CheckpointPassed,
+            HookEvent::StreakMilestone { .. } => HookEventKind::StreakMilestone,
+        }
+    }
+
+    /// One line for chat services, which show a message rather than fields
+    pub fn message(&self) -> String {
+        match self {
+            HookEvent::BadgeEarned { name, .. } => format!("🏅 Earned the {} badge", name),
+            HookEvent::CheckpointPassed { title, .. } => format!("✅ Passed the {} checkpoint", title),
+            HookEvent::StreakMilestone { days } => format!("🔥 Reached a {}-day study streak", days),
+        }
+    }
+}
+
+/// Where a hook sends its events
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(tag = "type")]
+pub enum HookAction {
+    /// POST the event as JSON to a URL
+    Webhook { url: String },
+    /// Run a script from the app's hooks directory with the event as JSON
+    /// on stdin
+    Command {
+        script: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+/// A user's hook: an action run whenever an event of one kind happens
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct Hook {
+    /// Assigned when the hook is first saved
+    #[serde(default)]
+    pub id: String,
+    pub event: HookEventKind,
+    pub action: HookAction,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+/// One event queued for one hook, kept until it's delivered or given up on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct HookDelivery {
+    pub id: String,
+    pub user_id: String,
+    pub hook_id: String,
+    /// The hook's action when the event happened, so editing the hook
+    /// doesn't redirect events already queued
+    pub action: HookAction,
+    pub event: HookEvent,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl HookDelivery {
+    pub fn new(user_id: String, hook: &Hook, event: HookEvent) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            hook_id: hook.id.clone(),
+            action: hook.action.clone(),
+            event,
+            attempts: 0,
+            next_attempt_at: now,
+            last_error: None,
+            delivered_at: None,
+            created_at: now,
+        }
+    }
+
+    /// Failed too often to be retried
+    pub fn is_exhausted(&self) -> bool {
+        self.attempts >= MAX_HOOK_ATTEMPTS
+    }
+
+    /// Retry after a minute, then two, four and so on
+    pub fn record_failure(&mut self, error: String, now: DateTime<Utc>) {
+        self.attempts += 1;
+        self.last_error = Some(error);
+        self.next_attempt_at = now + Duration::minutes(1 << (self.attempts - 1).min(10));
+    }
+
+    pub fn complete(&mut self, now: DateTime<Utc>) {
+        self.attempts += 1;
+        self.last_error = None;
+        self.delivered_at = Some(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retries_back_off_until_exhausted() {
+        let hook = Hook {
+            id: "hook-1".to_string(),
+            event: HookEventKind::StreakMilestone,
+            action: HookAction::Webhook { url: "http://localhost:8080/glp".to_string() },
+            enabled: true,
+        };
+        let mut delivery = HookDelivery::new("user1".to_string(), &hook, HookEvent::StreakMilestone { days: 7 });
+
+        let now = Utc::now();
+        delivery.record_failure("connection refused".to_string(), now);
+        assert_eq!(delivery.next_attempt_at, now + Duration::minutes(1));
+        delivery.record_failure("connection refused".to_string(), now);
+        assert_eq!(delivery.next_attempt_at, now + Duration::minutes(2));
+
+        for _ in 2..MAX_HOOK_ATTEMPTS {
+            assert!(!delivery.is_exhausted());
+            delivery.record_failure("connection refused".to_string(), now);
+        }
+        assert!(delivery.is_exhausted());
+        assert!(delivery.delivered_at.is_none());
+    }
+}
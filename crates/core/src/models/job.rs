@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The last run of a background job, kept so schedules survive restarts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRun {
+    pub job_id: String,
+    pub last_started_at: DateTime<Utc>,
+    pub last_duration_ms: i64,
+    /// Summary of what the last successful run did
+    pub last_result: Option<String>,
+    /// Error from the last run, if it failed
+    pub last_error: Option<String>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub run_count: i64,
+    pub failure_count: i64,
+}
+
+impl JobRun {
+    pub fn succeeded(&self) -> bool {
+        self.last_error.is_none()
+    }
+}
@@ -0,0 +1,121 @@
+use content::{InlineQuestion, Lecture, LectureSegment};
+use serde::Serialize;
+
+use crate::models::quiz::{QuestionOption, QuestionView};
+
+/// Question type lecture questions are shown as; they have one right option
+const LECTURE_QUESTION_TYPE: &str = "multiple-choice";
+
+/// A lecture as the learner sees it. Its questions come without answers or
+/// explanations, and the raw Markdown, whose quiz blocks hold them, is left
+/// out; answers are only checked when they're given.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct LectureView {
+    pub segments: Vec<LectureSegmentView>,
+    /// One message per unreadable block, with its line
+    pub problems: Vec<String>,
+    /// Questions to answer before the lecture counts as complete, with ids
+    /// "0", "1", ... in the order answers are given
+    pub comprehension_check: Vec<QuestionView>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LectureSegmentView {
+    Markdown { text: String },
+    /// An inline question; its id is its position among the lecture's
+    /// inline questions
+    QuizInline { question: QuestionView },
+    ChallengeRef { node_id: String },
+    Mermaid { source: String },
+}
+
+fn question_view(index: usize, question: &InlineQuestion) -> QuestionView {
+    QuestionView {
+        id: index.to_string(),
+        question_type: LECTURE_QUESTION_TYPE.to_string(),
+        prompt: question.question.clone(),
+        code_snippet: None,
+        options: question
+            .options
+            .iter()
+            .enumerate()
+            .map(|(index, text)| QuestionOption { id: index.to_string(), text: text.clone() })
+            .collect(),
+        points: 1,
+    }
+}
+
+impl From<&Lecture> for LectureView {
+    fn from(lecture: &Lecture) -> Self {
+        let mut inline = 0;
+        let segments = lecture
+            .segments
+            .iter()
+            .map(|segment| match segment {
+                LectureSegment::Markdown { text } => LectureSegmentView::Markdown { text: text.clone() },
+                LectureSegment::QuizInline { question } => {
+                    inline += 1;
+                    LectureSegmentView::QuizInline { question: question_view(inline - 1, question) }
+                }
+                LectureSegment::ChallengeRef { node_id } => LectureSegmentView::ChallengeRef { node_id: node_id.clone() },
+                LectureSegment::Mermaid { source } => LectureSegmentView::Mermaid { source: source.clone() },
+            })
+            .collect();
+
+        Self {
+            segments,
+            problems: lecture.problems.clone(),
+            comprehension_check: lecture
+                .comprehension_check
+                .iter()
+                .enumerate()
+                .map(|(index, question)| question_view(index, question))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view_carries_no_answers() {
+        let markdown = "+++\n\
+                        [[comprehension_check]]\n\
+                        question = \"Who frees a value?\"\n\
+                        options = [\"Its owner\", \"The garbage collector\"]\n\
+                        correct_answer = 0\n\
+                        explanation = \"Values are dropped when their owner goes out of scope\"\n\
+                        +++\n\
+                        # Ownership\n\n\
+                        ```quiz-inline\n\
+                        {\"question\": \"How many owners?\", \"options\": [\"One\", \"Two\"], \"correct_answer\": 0, \
+                        \"explanation\": \"Exactly one\"}\n\
+                        ```\n";
+        let lecture = content::parse_lecture(markdown);
+        assert!(lecture.problems.is_empty(), "{:?}", lecture.problems);
+
+        let view = LectureView::from(&lecture);
+        assert_eq!(view.comprehension_check[0].prompt, "Who frees a value?");
+        assert!(matches!(
+            &view.segments[1],
+            LectureSegmentView::QuizInline { question } if question.id == "0" && question.options[1].text == "Two"
+        ));
+
+        fn has_key(value: &serde_json::Value, key: &str) -> bool {
+            match value {
+                serde_json::Value::Object(map) => map.iter().any(|(k, v)| k == key || has_key(v, key)),
+                serde_json::Value::Array(items) => items.iter().any(|v| has_key(v, key)),
+                _ => false,
+            }
+        }
+        let json = serde_json::to_value(&view).unwrap();
+        assert!(!has_key(&json, "correct_answer"));
+        assert!(!has_key(&json, "explanation"));
+        assert!(!json.to_string().contains("Exactly one"));
+    }
+}
@@ -1,9 +1,31 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Parameters controlling how mastery decays during inactivity
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DecayPolicy {
+    pub grace_period_days: i64,
+    pub decay_rate: f64,
+    pub minimum_score: f64,
+}
+
+impl Default for DecayPolicy {
+    fn default() -> Self {
+        Self {
+            grace_period_days: MasteryScore::GRACE_PERIOD_DAYS,
+            decay_rate: MasteryScore::DECAY_RATE,
+            minimum_score: MasteryScore::MINIMUM_SCORE,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MasteryScore {
     pub user_id: String,
+    /// Curriculum this belongs to; `None` for data recorded before curricula
+    #[serde(default)]
+    pub curriculum_id: Option<String>,
     pub skill_id: String,
     pub score: f64,
     pub last_updated_at: DateTime<Utc>,
@@ -18,12 +40,18 @@ impl MasteryScore {
     pub fn new(user_id: String, skill_id: String) -> Self {
         Self {
             user_id,
+            curriculum_id: None,
             skill_id,
             score: 0.0,
             last_updated_at: Utc::now(),
         }
     }
 
+    pub fn with_curriculum(mut self, curriculum_id: String) -> Self {
+        self.curriculum_id = Some(curriculum_id);
+        self
+    }
+
     /// Update mastery score based on performance (0.0 to 1.0)
     /// Uses exponential moving average: new = old + learning_rate × (performance - old)
     pub fn update_with_performance(&mut self, performance: f64) {
@@ -36,13 +64,18 @@ impl MasteryScore {
     /// Apply decay based on days since last activity
     /// Formula: score = score × e^(-decay_rate × days_inactive)
     pub fn apply_decay(&mut self, days_inactive: i64) {
-        if days_inactive <= Self::GRACE_PERIOD_DAYS {
+        self.apply_decay_with_policy(days_inactive, &DecayPolicy::default());
+    }
+
+    /// Apply decay using curriculum-specific parameters
+    pub fn apply_decay_with_policy(&mut self, days_inactive: i64, policy: &DecayPolicy) {
+        if days_inactive <= policy.grace_period_days {
             return;
         }
 
-        let decay_days = days_inactive - Self::GRACE_PERIOD_DAYS;
-        let decay_factor = (-Self::DECAY_RATE * decay_days as f64).exp();
-        self.score = (self.score * decay_factor).max(Self::MINIMUM_SCORE);
+        let decay_days = days_inactive - policy.grace_period_days;
+        let decay_factor = (-policy.decay_rate * decay_days as f64).exp();
+        self.score = (self.score * decay_factor).max(policy.minimum_score);
     }
 
     /// Get mastery level description
@@ -57,6 +90,83 @@ impl MasteryScore {
     }
 }
 
+/// Why a mastery score changed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MasteryChangeReason {
+    Quiz,
+    Challenge,
+    Decay,
+}
+
+impl MasteryChangeReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MasteryChangeReason::Quiz => "Quiz",
+            MasteryChangeReason::Challenge => "Challenge",
+            MasteryChangeReason::Decay => "Decay",
+        }
+    }
+}
+
+impl FromStr for MasteryChangeReason {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Quiz" => Ok(MasteryChangeReason::Quiz),
+            "Challenge" => Ok(MasteryChangeReason::Challenge),
+            "Decay" => Ok(MasteryChangeReason::Decay),
+            _ => Err(format!("Invalid mastery change reason: {}", s)),
+        }
+    }
+}
+
+/// A single point in a skill's mastery time series
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MasteryHistoryEntry {
+    pub user_id: String,
+    pub skill_id: String,
+    pub previous_score: f64,
+    pub score: f64,
+    pub reason: MasteryChangeReason,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl MasteryHistoryEntry {
+    pub fn new(
+        user_id: String,
+        skill_id: String,
+        previous_score: f64,
+        score: f64,
+        reason: MasteryChangeReason,
+    ) -> Self {
+        Self {
+            user_id,
+            skill_id,
+            previous_score,
+            score,
+            reason,
+            recorded_at: Utc::now(),
+        }
+    }
+
+    /// Record the transition of an updated mastery score
+    pub fn from_change(mastery: &MasteryScore, previous_score: f64, reason: MasteryChangeReason) -> Self {
+        Self {
+            user_id: mastery.user_id.clone(),
+            skill_id: mastery.skill_id.clone(),
+            previous_score,
+            score: mastery.score,
+            reason,
+            recorded_at: mastery.last_updated_at,
+        }
+    }
+
+    pub fn delta(&self) -> f64 {
+        self.score - self.previous_score
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,6 +207,26 @@ mod tests {
         assert!(mastery.score >= MasteryScore::MINIMUM_SCORE);
     }
 
+    #[test]
+    fn test_decay_with_custom_policy() {
+        let harsh = DecayPolicy {
+            grace_period_days: 1,
+            decay_rate: 0.2,
+            minimum_score: 0.1,
+        };
+
+        let mut default_mastery = MasteryScore::new("user1".to_string(), "ownership".to_string());
+        default_mastery.score = 0.8;
+        default_mastery.apply_decay(5);
+
+        let mut harsh_mastery = MasteryScore::new("user1".to_string(), "ownership".to_string());
+        harsh_mastery.score = 0.8;
+        harsh_mastery.apply_decay_with_policy(5, &harsh);
+
+        assert!(harsh_mastery.score < default_mastery.score);
+        assert!(harsh_mastery.score >= 0.1);
+    }
+
     #[test]
     fn test_level_description() {
         let mut mastery = MasteryScore::new("user1".to_string(), "ownership".to_string());
@@ -107,4 +237,12 @@ mod tests {
         mastery.score = 0.1;
         assert_eq!(mastery.level_description(), "Novice");
     }
+
+    #[test]
+    fn test_change_reason_round_trip() {
+        for reason in [MasteryChangeReason::Quiz, MasteryChangeReason::Challenge, MasteryChangeReason::Decay] {
+            assert_eq!(reason.as_str().parse::<MasteryChangeReason>().unwrap(), reason);
+        }
+        assert!("Unknown".parse::<MasteryChangeReason>().is_err());
+    }
 }
@@ -8,14 +8,66 @@ pub mod artifact;
 pub mod review;
 pub mod session;
 pub mod curriculum;
+pub mod quest;
+pub mod cohort;
+pub mod note;
+pub mod search;
+pub mod trash;
+pub mod sync;
+pub mod job;
+pub mod pending_work;
+pub mod tutor;
+pub mod embedding;
+pub mod practice;
+pub mod track;
+pub mod bookmark;
+pub mod activity;
+pub mod goal;
+pub mod certificate;
+pub mod api_token;
+pub mod repo_submission;
+pub mod hook;
+pub mod flashcard;
+pub mod best_attempt;
+pub mod node_time;
+pub mod analytics;
+pub mod lecture;
 
 pub use user::User;
-pub use progress::{NodeProgress, NodeStatus};
-pub use mastery::MasteryScore;
-pub use badge::{BadgeProgress, BadgeDefinition, BadgeCategory};
-pub use quiz::QuizAttempt;
-pub use challenge::ChallengeAttempt;
-pub use artifact::ArtifactSubmission;
+pub use progress::{ComprehensionCheck, NodeProgress, NodeStatus};
+pub use mastery::{MasteryScore, MasteryHistoryEntry, MasteryChangeReason, DecayPolicy};
+pub use badge::{BadgeProgress, BadgeDefinition, BadgeCategory, BadgeTier, BadgeTierThreshold};
+pub use quiz::{
+    LatePolicy, MistakeExplanation, QuestionAnswer, QuestionStats, QuestionView, QuizAttempt, SkillAccuracy,
+};
+pub use challenge::{AttemptFlag, ChallengeAttempt, ChallengeDraft, FAST_SOLVE_SHARE};
+pub use artifact::{ArtifactSubmission, ArtifactType};
 pub use review::ReviewItem;
-pub use session::SessionHistory;
+pub use session::{RecoveredSession, SessionHistory};
 pub use curriculum::{Curriculum, CurriculumSummary};
+pub use quest::{Quest, QuestKind, QuestPeriod};
+pub use cohort::{Cohort, CohortNodeStats, CohortReport, CohortSkillStats};
+pub use note::Note;
+pub use search::{SearchHit, SearchSource};
+pub use trash::{TableSnapshot, TrashEntry, TrashOperation};
+pub use sync::{SyncOp, SyncRecord, SyncedRecord};
+pub use job::JobRun;
+pub use pending_work::{Capability, PendingWork, PendingWorkKind};
+pub use tutor::{TutorMessage, TutorRole, TutorSession};
+pub use embedding::{ContentEmbedding, EmbeddedKind};
+pub use practice::{PracticeChallenge, PRACTICE_REVIEW_PREFIX};
+pub use track::TrackRepo;
+pub use bookmark::Bookmark;
+pub use activity::DailyActivity;
+pub use goal::{Goal, GoalState, GoalStatus, GoalTarget, GOAL_BEHIND_MARGIN};
+pub use certificate::Certificate;
+pub use api_token::ApiToken;
+pub use repo_submission::RepoSubmission;
+pub use hook::{Hook, HookAction, HookDelivery, HookEvent, HookEventKind, MAX_HOOK_ATTEMPTS};
+pub use flashcard::{Flashcard, FLASHCARD_REVIEW_PREFIX};
+pub use best_attempt::BestAttempt;
+pub use node_time::{NodeTime, TimeHistogram, NODE_HEARTBEAT_INTERVAL_SECS, NODE_IDLE_AFTER_SECS};
+pub use lecture::{LectureSegmentView, LectureView};
+pub use analytics::{
+    AnalyticsBatch, AnalyticsEvent, AnalyticsSettings, ErrorCategory, QueuedEvent, RecordedEvent, ANALYTICS_SCHEMA_VERSION,
+};
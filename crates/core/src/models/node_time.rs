@@ -0,0 +1,141 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How often the frontend sends a heartbeat while the learner is active on
+/// a lecture or challenge
+pub const NODE_HEARTBEAT_INTERVAL_SECS: i64 = 15;
+
+/// A gap between heartbeats longer than this means the learner stepped away
+/// or switched to something else, so it isn't counted as time on the node
+pub const NODE_IDLE_AFTER_SECS: i64 = 120;
+
+/// Time a learner has actively spent on a node, from the frontend's
+/// heartbeats
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct NodeTime {
+    pub user_id: String,
+    pub curriculum_id: Option<String>,
+    pub node_id: String,
+    /// Time between heartbeats, without the idle gaps
+    pub active_seconds: i64,
+    /// Gaps between heartbeats too long to count
+    pub idle_seconds: i64,
+    pub heartbeats: i32,
+    pub last_heartbeat_at: Option<DateTime<Utc>>,
+}
+
+impl NodeTime {
+    pub fn new(user_id: String, curriculum_id: Option<String>, node_id: String) -> Self {
+        Self {
+            user_id,
+            curriculum_id,
+            node_id,
+            active_seconds: 0,
+            idle_seconds: 0,
+            heartbeats: 0,
+            last_heartbeat_at: None,
+        }
+    }
+
+    /// Record a heartbeat at `now`. `continues` is whether the learner's
+    /// previous heartbeat, on any node, was on this one; if not, this one
+    /// starts a new stretch and the time before it isn't this node's.
+    /// Returns the seconds counted as active.
+    pub fn record_heartbeat(&mut self, now: DateTime<Utc>, continues: bool) -> i64 {
+        let gap = self.last_heartbeat_at.map(|last| (now - last).num_seconds());
+        let counted = match gap {
+            Some(gap) if continues && (0..=NODE_IDLE_AFTER_SECS).contains(&gap) => gap,
+            Some(gap) if continues && gap > NODE_IDLE_AFTER_SECS => {
+                self.idle_seconds += gap;
+                0
+            }
+            _ => 0,
+        };
+        self.active_seconds += counted;
+        self.heartbeats += 1;
+        self.last_heartbeat_at = Some(now);
+        counted
+    }
+
+    pub fn active_minutes(&self) -> i32 {
+        (self.active_seconds / 60) as i32
+    }
+}
+
+/// How much active time learners took to complete a node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct TimeHistogram {
+    pub node_id: String,
+    pub bucket_minutes: u32,
+    /// Learners per bucket; bucket `i` covers `i * bucket_minutes` up to
+    /// `(i + 1) * bucket_minutes` minutes
+    pub counts: Vec<u32>,
+    pub learners: u32,
+    pub median_minutes: f64,
+}
+
+impl TimeHistogram {
+    /// Bucket each learner's active seconds on a node
+    pub fn from_seconds(node_id: String, seconds: &[i64], bucket_minutes: u32) -> Self {
+        let bucket_seconds = bucket_minutes.max(1) as i64 * 60;
+        let mut counts = Vec::new();
+        for &s in seconds {
+            let bucket = (s.max(0) / bucket_seconds) as usize;
+            if counts.len() <= bucket {
+                counts.resize(bucket + 1, 0);
+            }
+            counts[bucket] += 1;
+        }
+
+        let mut sorted = seconds.to_vec();
+        sorted.sort_unstable();
+        let middle = sorted.len() / 2;
+        let median_seconds = match sorted.len() {
+            0 => 0.0,
+            n if n.is_multiple_of(2) => (sorted[middle - 1] + sorted[middle]) as f64 / 2.0,
+            _ => sorted[middle] as f64,
+        };
+
+        Self {
+            node_id,
+            bucket_minutes: bucket_minutes.max(1),
+            counts,
+            learners: seconds.len() as u32,
+            median_minutes: median_seconds / 60.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_idle_gaps_and_other_nodes_are_not_counted() {
+        let start = Utc::now();
+        let mut time = NodeTime::new("user".to_string(), None, "lecture-1".to_string());
+        assert_eq!(time.record_heartbeat(start, false), 0);
+        assert_eq!(time.record_heartbeat(start + Duration::seconds(15), true), 15);
+        assert_eq!(time.record_heartbeat(start + Duration::seconds(30), true), 15);
+        // Away for ten minutes
+        assert_eq!(time.record_heartbeat(start + Duration::seconds(630), true), 0);
+        // Back from another node
+        assert_eq!(time.record_heartbeat(start + Duration::seconds(700), false), 0);
+        assert_eq!(time.record_heartbeat(start + Duration::seconds(715), true), 15);
+
+        assert_eq!(time.active_seconds, 45);
+        assert_eq!(time.idle_seconds, 600);
+        assert_eq!(time.heartbeats, 6);
+    }
+
+    #[test]
+    fn test_histogram_buckets_and_median() {
+        let histogram = TimeHistogram::from_seconds("quiz-1".to_string(), &[4 * 60, 6 * 60, 7 * 60, 21 * 60], 5);
+        assert_eq!(histogram.counts, vec![1, 2, 0, 0, 1]);
+        assert_eq!(histogram.learners, 4);
+        assert_eq!(histogram.median_minutes, 6.5);
+    }
+}
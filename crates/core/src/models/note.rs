@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A learner's free-form note on a content node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct Note {
+    pub id: String,
+    pub user_id: String,
+    pub node_id: String,
+    pub body: String,
+    /// Passage of the node's content the note annotates; `None` for a note
+    /// on the whole node
+    #[serde(default)]
+    pub anchor: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Note {
+    pub fn new(user_id: String, node_id: String, body: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            node_id,
+            body,
+            anchor: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn with_anchor(mut self, anchor: String) -> Self {
+        self.anchor = Some(anchor);
+        self
+    }
+}
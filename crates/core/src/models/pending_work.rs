@@ -0,0 +1,146 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Failed runs before queued work is given up on
+pub const MAX_PENDING_WORK_ATTEMPTS: i32 = 5;
+
+/// Something outside the app that work can depend on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum Capability {
+    /// The Docker daemon, for running challenge code
+    Docker,
+    /// The configured grading provider's API
+    Llm,
+}
+
+impl Capability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Capability::Docker => "Docker",
+            Capability::Llm => "Llm",
+        }
+    }
+}
+
+impl FromStr for Capability {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Docker" => Ok(Capability::Docker),
+            "Llm" => Ok(Capability::Llm),
+            other => Err(format!("Unknown capability: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum PendingWorkKind {
+    /// Grade a submitted checkpoint artifact
+    GradeArtifact,
+}
+
+impl PendingWorkKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PendingWorkKind::GradeArtifact => "GradeArtifact",
+        }
+    }
+
+    /// What the work can't run without
+    pub fn requires(&self) -> Capability {
+        match self {
+            PendingWorkKind::GradeArtifact => Capability::Llm,
+        }
+    }
+}
+
+impl FromStr for PendingWorkKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "GradeArtifact" => Ok(PendingWorkKind::GradeArtifact),
+            other => Err(format!("Unknown pending work kind: {}", other)),
+        }
+    }
+}
+
+/// Work deferred until a capability it needs is available again, such as
+/// grading a checkpoint submitted while offline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct PendingWork {
+    pub id: String,
+    pub user_id: String,
+    pub kind: PendingWorkKind,
+    pub capability: Capability,
+    /// Kind-specific JSON the work is run from
+    pub payload: String,
+    pub created_at: DateTime<Utc>,
+    pub attempts: i32,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl PendingWork {
+    pub fn new(user_id: String, kind: PendingWorkKind, payload: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            kind,
+            capability: kind.requires(),
+            payload,
+            created_at: Utc::now(),
+            attempts: 0,
+            last_attempt_at: None,
+            last_error: None,
+            completed_at: None,
+        }
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.completed_at.is_some()
+    }
+
+    /// Failed too often to be retried
+    pub fn is_exhausted(&self) -> bool {
+        self.attempts >= MAX_PENDING_WORK_ATTEMPTS
+    }
+
+    pub fn record_failure(&mut self, error: String, now: DateTime<Utc>) {
+        self.attempts += 1;
+        self.last_attempt_at = Some(now);
+        self.last_error = Some(error);
+    }
+
+    pub fn complete(&mut self, now: DateTime<Utc>) {
+        self.attempts += 1;
+        self.last_attempt_at = Some(now);
+        self.last_error = None;
+        self.completed_at = Some(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gives_up_after_max_attempts() {
+        let mut work = PendingWork::new("user1".to_string(), PendingWorkKind::GradeArtifact, "{}".to_string());
+        assert_eq!(work.capability, Capability::Llm);
+
+        for _ in 0..MAX_PENDING_WORK_ATTEMPTS {
+            assert!(!work.is_exhausted());
+            work.record_failure("timeout".to_string(), Utc::now());
+        }
+        assert!(work.is_exhausted());
+        assert!(!work.is_completed());
+    }
+}
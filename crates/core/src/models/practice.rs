@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Review items for practice challenges are keyed by this prefix and the
+/// challenge id, alongside the quiz ids of ordinary review items
+pub const PRACTICE_REVIEW_PREFIX: &str = "practice:";
+
+/// A generated challenge drilling one skill, in the runner's format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PracticeChallenge {
+    pub id: String,
+    pub user_id: String,
+    pub curriculum_id: Option<String>,
+    pub skill_id: String,
+    pub difficulty: String,
+    pub title: String,
+    pub instructions: String,
+    pub starter_code: String,
+    pub test_code: String,
+    /// Passed `test_code` in the sandbox before the challenge was stored
+    pub solution: String,
+    pub hints: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PracticeChallenge {
+    /// An empty challenge; fill in the generated content with struct update syntax
+    pub fn new(user_id: String, curriculum_id: Option<String>, skill_id: String, difficulty: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            curriculum_id,
+            skill_id,
+            difficulty,
+            title: String::new(),
+            instructions: String::new(),
+            starter_code: String::new(),
+            test_code: String::new(),
+            solution: String::new(),
+            hints: Vec::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Id of the challenge's review item
+    pub fn review_id(&self) -> String {
+        format!("{}{}", PRACTICE_REVIEW_PREFIX, self.id)
+    }
+
+    /// Challenge id from a review item id, if the item is a practice challenge
+    pub fn id_from_review(review_id: &str) -> Option<&str> {
+        review_id.strip_prefix(PRACTICE_REVIEW_PREFIX)
+    }
+}
@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum NodeStatus {
@@ -30,9 +31,41 @@ impl NodeStatus {
     }
 }
 
+/// How a lecture with a comprehension check was completed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ComprehensionCheck {
+    Passed,
+    /// Completed without answering the check
+    Skipped,
+}
+
+impl ComprehensionCheck {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ComprehensionCheck::Passed => "Passed",
+            ComprehensionCheck::Skipped => "Skipped",
+        }
+    }
+}
+
+impl FromStr for ComprehensionCheck {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Passed" => Ok(ComprehensionCheck::Passed),
+            "Skipped" => Ok(ComprehensionCheck::Skipped),
+            _ => Err(format!("Invalid comprehension check: {}", s)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeProgress {
     pub user_id: String,
+    /// Curriculum this belongs to; `None` for data recorded before curricula
+    #[serde(default)]
+    pub curriculum_id: Option<String>,
     pub node_id: String,
     pub status: NodeStatus,
     pub attempts: i32,
@@ -40,12 +73,17 @@ pub struct NodeProgress {
     pub first_started_at: Option<DateTime<Utc>>,
     pub completed_at: Option<DateTime<Utc>>,
     pub last_updated_at: DateTime<Utc>,
+    /// For a lecture with a comprehension check, whether it was passed;
+    /// `None` for other nodes
+    #[serde(default)]
+    pub comprehension_check: Option<ComprehensionCheck>,
 }
 
 impl NodeProgress {
     pub fn new(user_id: String, node_id: String) -> Self {
         Self {
             user_id,
+            curriculum_id: None,
             node_id,
             status: NodeStatus::NotStarted,
             attempts: 0,
@@ -53,9 +91,15 @@ impl NodeProgress {
             first_started_at: None,
             completed_at: None,
             last_updated_at: Utc::now(),
+            comprehension_check: None,
         }
     }
 
+    pub fn with_curriculum(mut self, curriculum_id: String) -> Self {
+        self.curriculum_id = Some(curriculum_id);
+        self
+    }
+
     pub fn start(&mut self) {
         if self.first_started_at.is_none() {
             self.first_started_at = Some(Utc::now());
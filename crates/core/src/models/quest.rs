@@ -0,0 +1,196 @@
+use chrono::{DateTime, Datelike, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// How long a quest stays active before it rotates out
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum QuestPeriod {
+    Daily,
+    Weekly,
+}
+
+impl QuestPeriod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuestPeriod::Daily => "Daily",
+            QuestPeriod::Weekly => "Weekly",
+        }
+    }
+
+    /// Start and end of the period containing `now` (UTC days, weeks start on Monday)
+    pub fn bounds(&self, now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        let midnight = now
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .expect("midnight is always valid")
+            .and_utc();
+
+        match self {
+            QuestPeriod::Daily => (midnight, midnight + Duration::days(1)),
+            QuestPeriod::Weekly => {
+                let start = midnight - Duration::days(now.weekday().num_days_from_monday() as i64);
+                (start, start + Duration::days(7))
+            }
+        }
+    }
+}
+
+impl FromStr for QuestPeriod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Daily" => Ok(QuestPeriod::Daily),
+            "Weekly" => Ok(QuestPeriod::Weekly),
+            _ => Err(format!("Invalid quest period: {}", s)),
+        }
+    }
+}
+
+/// The activity a quest counts
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum QuestKind {
+    CompleteLectures,
+    CompleteQuizzes,
+    CompleteReviews,
+    EarnXp,
+}
+
+impl QuestKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuestKind::CompleteLectures => "CompleteLectures",
+            QuestKind::CompleteQuizzes => "CompleteQuizzes",
+            QuestKind::CompleteReviews => "CompleteReviews",
+            QuestKind::EarnXp => "EarnXp",
+        }
+    }
+}
+
+impl FromStr for QuestKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "CompleteLectures" => Ok(QuestKind::CompleteLectures),
+            "CompleteQuizzes" => Ok(QuestKind::CompleteQuizzes),
+            "CompleteReviews" => Ok(QuestKind::CompleteReviews),
+            "EarnXp" => Ok(QuestKind::EarnXp),
+            _ => Err(format!("Invalid quest kind: {}", s)),
+        }
+    }
+}
+
+/// A rotating goal and the user's progress toward it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Quest {
+    pub id: String,
+    pub user_id: String,
+    pub period: QuestPeriod,
+    pub kind: QuestKind,
+    pub description: String,
+    pub target: i32,
+    pub progress: i32,
+    pub xp_reward: i32,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub claimed_at: Option<DateTime<Utc>>,
+}
+
+impl Quest {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        now >= self.starts_at && now < self.ends_at
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.completed_at.is_some()
+    }
+
+    pub fn is_claimed(&self) -> bool {
+        self.claimed_at.is_some()
+    }
+
+    /// Completed quests can be claimed once
+    pub fn can_claim(&self) -> bool {
+        self.is_complete() && !self.is_claimed()
+    }
+
+    /// Add progress at `now`, marking the quest complete when the target is
+    /// reached. Returns true if this call completed the quest.
+    pub fn record_progress(&mut self, amount: i32, now: DateTime<Utc>) -> bool {
+        if self.is_complete() || amount <= 0 {
+            return false;
+        }
+
+        self.progress = (self.progress + amount).min(self.target);
+        if self.progress >= self.target {
+            self.completed_at = Some(now);
+            return true;
+        }
+        false
+    }
+
+    /// Mark the reward as claimed, returning the XP to award
+    pub fn claim(&mut self) -> Option<i32> {
+        if !self.can_claim() {
+            return None;
+        }
+        self.claimed_at = Some(Utc::now());
+        Some(self.xp_reward)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn sample_quest() -> Quest {
+        let (starts_at, ends_at) = QuestPeriod::Daily.bounds(Utc::now());
+        Quest {
+            id: "daily-quest".to_string(),
+            user_id: "user1".to_string(),
+            period: QuestPeriod::Daily,
+            kind: QuestKind::CompleteQuizzes,
+            description: "Complete 2 quizzes".to_string(),
+            target: 2,
+            progress: 0,
+            xp_reward: 30,
+            starts_at,
+            ends_at,
+            completed_at: None,
+            claimed_at: None,
+        }
+    }
+
+    #[test]
+    fn test_period_bounds() {
+        // Wednesday afternoon
+        let now = Utc.with_ymd_and_hms(2024, 1, 10, 15, 30, 0).unwrap();
+
+        let (start, end) = QuestPeriod::Daily.bounds(now);
+        assert_eq!(start, Utc.with_ymd_and_hms(2024, 1, 10, 0, 0, 0).unwrap());
+        assert_eq!(end, Utc.with_ymd_and_hms(2024, 1, 11, 0, 0, 0).unwrap());
+
+        let (start, end) = QuestPeriod::Weekly.bounds(now);
+        assert_eq!(start, Utc.with_ymd_and_hms(2024, 1, 8, 0, 0, 0).unwrap());
+        assert_eq!(end, Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_record_progress_and_claim() {
+        let mut quest = sample_quest();
+        let now = quest.starts_at + chrono::Duration::hours(1);
+        assert!(!quest.can_claim());
+
+        assert!(!quest.record_progress(1, now));
+        assert!(quest.record_progress(5, now));
+        assert_eq!(quest.progress, 2);
+        assert_eq!(quest.completed_at, Some(now));
+        assert!(!quest.record_progress(1, now)); // Already complete
+
+        assert_eq!(quest.claim(), Some(30));
+        assert_eq!(quest.claim(), None); // Only once
+    }
+}
@@ -11,9 +11,26 @@ pub struct Quiz {
     pub skills: Vec<String>,
     pub passing_score: i32,
     pub time_limit_seconds: Option<i32>,
+    /// What happens when a timed quiz is submitted after its limit
+    #[serde(default)]
+    pub late_policy: LatePolicy,
     pub questions: Vec<Question>,
 }
 
+/// What happens to a timed quiz submitted after its limit
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(tag = "kind")]
+pub enum LatePolicy {
+    /// Accept and grade as usual, but mark the attempt late
+    #[default]
+    Flag,
+    /// Take `percent_per_minute` points off the score for each started minute
+    /// late, up to `max_percent`
+    Penalize { percent_per_minute: f64, max_percent: f64 },
+    /// Refuse the submission
+    Reject,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Question {
     pub id: String,
@@ -24,24 +41,64 @@ pub struct Question {
     pub correct_answer: String,
     pub explanation: String,
     pub points: i32,
+    /// Skills this question tests, for per-skill accuracy
+    #[serde(default)]
+    pub skills: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct QuestionOption {
     pub id: String,
     pub text: String,
 }
 
+/// A question as the learner sees it before answering: no correct answer and
+/// no explanation. Quizzes and lecture questions are all sent this way.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct QuestionView {
+    pub id: String,
+    pub question_type: String,
+    pub prompt: String,
+    pub code_snippet: Option<String>,
+    pub options: Vec<QuestionOption>,
+    pub points: i32,
+}
+
+impl From<&Question> for QuestionView {
+    fn from(question: &Question) -> Self {
+        Self {
+            id: question.id.clone(),
+            question_type: question.question_type.clone(),
+            prompt: question.prompt.clone(),
+            code_snippet: question.code_snippet.clone(),
+            options: question.options.clone(),
+            points: question.points,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct QuizAttempt {
     pub id: String,
     pub user_id: String,
+    /// Curriculum this belongs to; `None` for data recorded before curricula
+    #[serde(default)]
+    pub curriculum_id: Option<String>,
     pub quiz_id: String,
     pub node_id: String,
     pub answers: Vec<String>,
     pub score_percentage: i32,
     pub xp_earned: i32,
     pub submitted_at: DateTime<Utc>,
+    /// When a timed quiz was started; None for untimed quizzes
+    #[serde(default)]
+    pub started_at: Option<DateTime<Utc>>,
+    /// How far past the time limit it was submitted, 0 if on time
+    #[serde(default)]
+    pub late_seconds: i64,
 }
 
 impl QuizAttempt {
@@ -56,18 +113,128 @@ impl QuizAttempt {
         Self {
             id: Uuid::new_v4().to_string(),
             user_id,
+            curriculum_id: None,
             quiz_id,
             node_id,
             answers,
             score_percentage,
             xp_earned,
             submitted_at: Utc::now(),
+            started_at: None,
+            late_seconds: 0,
         }
     }
 
+    pub fn with_curriculum(mut self, curriculum_id: String) -> Self {
+        self.curriculum_id = Some(curriculum_id);
+        self
+    }
+
+    pub fn with_timing(mut self, started_at: DateTime<Utc>, late_seconds: i64) -> Self {
+        self.started_at = Some(started_at);
+        self.late_seconds = late_seconds;
+        self
+    }
+
+    pub fn is_late(&self) -> bool {
+        self.late_seconds > 0
+    }
+
     pub fn passed(&self) -> bool {
         self.score_percentage >= 70
     }
+
+    /// Grade each question on its own, pairing `answers` with the quiz's
+    /// questions by position
+    pub fn question_answers(&self, quiz: &Quiz) -> Vec<QuestionAnswer> {
+        quiz.questions
+            .iter()
+            .enumerate()
+            .map(|(position, question)| {
+                let answer = self.answers.get(position).cloned().unwrap_or_default();
+                QuestionAnswer {
+                    attempt_id: self.id.clone(),
+                    question_id: question.id.clone(),
+                    position: position as i32,
+                    is_correct: answer == question.correct_answer,
+                    answer,
+                    skills: question.skills.clone(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// One question's answer within a quiz attempt. An empty answer means the
+/// question was skipped.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuestionAnswer {
+    pub attempt_id: String,
+    pub question_id: String,
+    pub position: i32,
+    pub answer: String,
+    pub is_correct: bool,
+    pub skills: Vec<String>,
+}
+
+/// How often a question has been answered, and missed, across attempts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct QuestionStats {
+    pub quiz_id: String,
+    pub question_id: String,
+    pub answered: i64,
+    pub missed: i64,
+}
+
+impl QuestionStats {
+    pub fn accuracy(&self) -> f64 {
+        if self.answered == 0 {
+            return 0.0;
+        }
+        (self.answered - self.missed) as f64 / self.answered as f64
+    }
+}
+
+/// Accuracy over every answered question testing a skill
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct SkillAccuracy {
+    pub skill_id: String,
+    pub answered: i64,
+    pub correct: i64,
+}
+
+/// Why the answer chosen for a missed question was wrong, kept with the
+/// attempt so it can be reviewed later
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct MistakeExplanation {
+    pub attempt_id: String,
+    pub question_id: String,
+    pub chosen_option: String,
+    pub explanation: String,
+    /// Reused from an earlier explanation of the same mistake
+    pub from_cache: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl MistakeExplanation {
+    pub fn new(attempt_id: String, question_id: String, chosen_option: String, explanation: String) -> Self {
+        Self {
+            attempt_id,
+            question_id,
+            chosen_option,
+            explanation,
+            from_cache: false,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn cached(mut self) -> Self {
+        self.from_cache = true;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A checkpoint submitted as a GitHub repo: the commit that was checked, how
+/// the checkpoint's tests fared on it and the artifact grades it produced.
+/// Submissions that failed before anything ran are kept too, with the
+/// error, since they count towards the submission rate limit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct RepoSubmission {
+    pub id: String,
+    pub user_id: String,
+    pub curriculum_id: Option<String>,
+    pub checkpoint_id: String,
+    pub repo_url: String,
+    pub commit_hash: Option<String>,
+    /// Unset when the checkpoint has no tests or they never ran
+    pub tests_passed: Option<i32>,
+    pub tests_total: Option<i32>,
+    /// The runner's full result
+    pub verification_json: Option<String>,
+    /// Artifact grades recorded for this submission
+    pub artifact_submission_ids: Vec<String>,
+    /// The tests passed and every graded artifact passed
+    pub passed: bool,
+    pub error: Option<String>,
+    pub submitted_at: DateTime<Utc>,
+}
+
+impl RepoSubmission {
+    pub fn new(user_id: String, curriculum_id: Option<String>, checkpoint_id: String, repo_url: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            curriculum_id,
+            checkpoint_id,
+            repo_url,
+            commit_hash: None,
+            tests_passed: None,
+            tests_total: None,
+            verification_json: None,
+            artifact_submission_ids: Vec::new(),
+            passed: false,
+            error: None,
+            submitted_at: Utc::now(),
+        }
+    }
+
+    pub fn failed(mut self, error: impl Into<String>) -> Self {
+        self.error = Some(error.into());
+        self.passed = false;
+        self
+    }
+}
@@ -5,37 +5,60 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReviewItem {
     pub user_id: String,
+    /// Curriculum this belongs to; `None` for data recorded before curricula
+    #[serde(default)]
+    pub curriculum_id: Option<String>,
     pub quiz_id: String,
     pub due_date: DateTime<Utc>,
     pub ease_factor: f64,
     pub interval_days: i32,
     pub repetitions: i32,
     pub last_reviewed_at: Option<DateTime<Utc>>,
+    /// Number of times this item has been failed
+    #[serde(default)]
+    pub lapses: i32,
+    /// Suspended items are kept out of the review queue
+    #[serde(default)]
+    pub is_suspended: bool,
 }
 
 impl ReviewItem {
-    const MIN_EASE_FACTOR: f64 = 1.3;
-    const INITIAL_EASE_FACTOR: f64 = 2.5;
+    pub const MIN_EASE_FACTOR: f64 = 1.3;
+    pub const INITIAL_EASE_FACTOR: f64 = 2.5;
+    /// Items failed more than this many times are flagged as leeches
+    pub const LEECH_THRESHOLD: i32 = 8;
 
     pub fn new(user_id: String, quiz_id: String) -> Self {
         Self {
             user_id,
+            curriculum_id: None,
             quiz_id,
             due_date: Utc::now() + Duration::days(1),
             ease_factor: Self::INITIAL_EASE_FACTOR,
             interval_days: 1,
             repetitions: 0,
             last_reviewed_at: None,
+            lapses: 0,
+            is_suspended: false,
         }
     }
 
+    pub fn with_curriculum(mut self, curriculum_id: String) -> Self {
+        self.curriculum_id = Some(curriculum_id);
+        self
+    }
+
     /// Update review item based on quality of response (0-5 scale)
     /// 0-2: Again (failed), 3: Hard, 4: Good, 5: Easy
     pub fn update_after_review(&mut self, quality: i32) {
         let quality = quality.clamp(0, 5);
 
         if quality < 3 {
-            // Failed - reset
+            // Failed - count a lapse and reset
+            self.lapses += 1;
+            if self.is_leech() {
+                self.is_suspended = true;
+            }
             self.repetitions = 0;
             self.interval_days = 1;
         } else {
@@ -61,7 +84,19 @@ impl ReviewItem {
     }
 
     pub fn is_due(&self) -> bool {
-        Utc::now() >= self.due_date
+        !self.is_suspended && Utc::now() >= self.due_date
+    }
+
+    /// An item is a leech once it has lapsed more than `LEECH_THRESHOLD` times
+    pub fn is_leech(&self) -> bool {
+        self.lapses > Self::LEECH_THRESHOLD
+    }
+
+    /// Return a suspended leech to the queue with a fresh lapse count
+    pub fn unsuspend(&mut self) {
+        self.is_suspended = false;
+        self.lapses = 0;
+        self.due_date = Utc::now();
     }
 }
 
@@ -99,5 +134,28 @@ mod tests {
         item.update_after_review(2); // Failed
         assert_eq!(item.repetitions, 0);
         assert_eq!(item.interval_days, 1);
+        assert_eq!(item.lapses, 1);
+    }
+
+    #[test]
+    fn test_leech_suspended_after_threshold() {
+        let mut item = ReviewItem::new("user1".to_string(), "quiz1".to_string());
+
+        for _ in 0..ReviewItem::LEECH_THRESHOLD {
+            item.update_after_review(1);
+        }
+        assert!(!item.is_leech());
+        assert!(!item.is_suspended);
+
+        item.update_after_review(1);
+        assert!(item.is_leech());
+        assert!(item.is_suspended);
+
+        item.due_date = Utc::now() - Duration::hours(1);
+        assert!(!item.is_due());
+
+        item.unsuspend();
+        assert!(!item.is_leech());
+        assert!(item.is_due());
     }
 }
@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Where a search hit came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum SearchSource {
+    Note,
+    QuizAnswer,
+    GradeFeedback,
+}
+
+impl SearchSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchSource::Note => "Note",
+            SearchSource::QuizAnswer => "QuizAnswer",
+            SearchSource::GradeFeedback => "GradeFeedback",
+        }
+    }
+}
+
+impl FromStr for SearchSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Note" => Ok(SearchSource::Note),
+            "QuizAnswer" => Ok(SearchSource::QuizAnswer),
+            "GradeFeedback" => Ok(SearchSource::GradeFeedback),
+            _ => Err(format!("Invalid search source: {}", s)),
+        }
+    }
+}
+
+/// One match from a user's own history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct SearchHit {
+    pub source: SearchSource,
+    /// ID of the note, quiz attempt or artifact submission
+    pub record_id: String,
+    /// Node the record belongs to (the checkpoint for grade feedback)
+    pub node_id: String,
+    /// Matching text with the search terms wrapped in `[` `]`
+    pub snippet: String,
+    pub occurred_at: DateTime<Utc>,
+    /// BM25 relevance; lower is a better match
+    pub rank: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_source_round_trip() {
+        for source in [SearchSource::Note, SearchSource::QuizAnswer, SearchSource::GradeFeedback] {
+            assert_eq!(source.as_str().parse::<SearchSource>().unwrap(), source);
+        }
+        assert!("Unknown".parse::<SearchSource>().is_err());
+    }
+}
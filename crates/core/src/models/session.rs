@@ -2,6 +2,14 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// How often an open session records that the app is still running
+pub const HEARTBEAT_INTERVAL_SECS: i64 = 15;
+
+/// An open session with no heartbeat for this long was cut off, usually by
+/// a crash. Gaps this long between heartbeats (the machine sleeping) don't
+/// count as study time either.
+pub const STALE_AFTER_SECS: i64 = HEARTBEAT_INTERVAL_SECS * 4;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionHistory {
     pub id: String,
@@ -10,6 +18,26 @@ pub struct SessionHistory {
     pub ended_at: Option<DateTime<Utc>>,
     pub total_xp_earned: i32,
     pub items_completed: i32,
+    pub last_heartbeat_at: Option<DateTime<Utc>>,
+    /// Time between heartbeats, excluding gaps where the app wasn't running
+    pub active_seconds: i64,
+    /// Time the learner was active on lectures and challenges, from their
+    /// node heartbeats
+    pub learning_seconds: i64,
+}
+
+/// What was kept from a session closed after the app stopped unexpectedly
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct RecoveredSession {
+    pub session_id: String,
+    pub user_id: String,
+    pub started_at: DateTime<Utc>,
+    /// The last heartbeat, when the app was last known to be running
+    pub ended_at: DateTime<Utc>,
+    pub active_seconds: i64,
+    pub items_completed: i32,
+    pub total_xp_earned: i32,
 }
 
 impl SessionHistory {
@@ -21,6 +49,9 @@ impl SessionHistory {
             ended_at: None,
             total_xp_earned: 0,
             items_completed: 0,
+            last_heartbeat_at: None,
+            active_seconds: 0,
+            learning_seconds: 0,
         }
     }
 
@@ -41,11 +72,46 @@ impl SessionHistory {
     pub fn is_active(&self) -> bool {
         self.ended_at.is_none()
     }
+
+    /// When the app was last known to be running this session
+    pub fn last_alive_at(&self) -> DateTime<Utc> {
+        self.last_heartbeat_at.unwrap_or(self.started_at)
+    }
+
+    pub fn record_heartbeat(&mut self, now: DateTime<Utc>) {
+        let gap = (now - self.last_alive_at()).num_seconds();
+        if (0..=STALE_AFTER_SECS).contains(&gap) {
+            self.active_seconds += gap;
+        }
+        self.last_heartbeat_at = Some(now);
+    }
+
+    /// Open, but without a heartbeat recently enough to still be running
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        self.is_active() && (now - self.last_alive_at()).num_seconds() > STALE_AFTER_SECS
+    }
+
+    /// Close a stale session at its last heartbeat
+    pub fn recover(&mut self) -> RecoveredSession {
+        let ended_at = self.last_alive_at();
+        self.ended_at = Some(ended_at);
+
+        RecoveredSession {
+            session_id: self.id.clone(),
+            user_id: self.user_id.clone(),
+            started_at: self.started_at,
+            ended_at,
+            active_seconds: self.active_seconds,
+            items_completed: self.items_completed,
+            total_xp_earned: self.total_xp_earned,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Duration;
 
     #[test]
     fn test_session_lifecycle() {
@@ -61,4 +127,26 @@ mod tests {
         session.end_session();
         assert!(!session.is_active());
     }
+
+    #[test]
+    fn test_heartbeats_skip_gaps_and_recover_at_last_beat() {
+        let mut session = SessionHistory::new("user1".to_string());
+        let start = session.started_at;
+
+        session.record_heartbeat(start + Duration::seconds(15));
+        session.record_heartbeat(start + Duration::seconds(30));
+        // The laptop slept for an hour
+        session.record_heartbeat(start + Duration::seconds(3630));
+        session.record_heartbeat(start + Duration::seconds(3645));
+        assert_eq!(session.active_seconds, 45);
+
+        let last_beat = start + Duration::seconds(3645);
+        assert!(!session.is_stale(last_beat + Duration::seconds(STALE_AFTER_SECS)));
+        assert!(session.is_stale(last_beat + Duration::seconds(STALE_AFTER_SECS + 1)));
+
+        let recovered = session.recover();
+        assert_eq!(recovered.ended_at, last_beat);
+        assert_eq!(recovered.active_seconds, 45);
+        assert!(!session.is_active());
+    }
 }
@@ -0,0 +1,141 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use crate::models::{MasteryScore, NodeProgress, ReviewItem};
+
+/// Learner state that is kept in step across devices
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data")]
+pub enum SyncRecord {
+    Progress(NodeProgress),
+    Mastery(MasteryScore),
+    Review(ReviewItem),
+}
+
+impl SyncRecord {
+    /// Identifies the record regardless of its contents
+    pub fn key(&self) -> String {
+        let parts = match self {
+            SyncRecord::Progress(p) => ("progress", &p.curriculum_id, &p.node_id),
+            SyncRecord::Mastery(m) => ("mastery", &m.curriculum_id, &m.skill_id),
+            SyncRecord::Review(r) => ("review", &r.curriculum_id, &r.quiz_id),
+        };
+        serde_json::to_string(&parts).unwrap_or_default()
+    }
+
+    pub fn user_id(&self) -> &str {
+        match self {
+            SyncRecord::Progress(p) => &p.user_id,
+            SyncRecord::Mastery(m) => &m.user_id,
+            SyncRecord::Review(r) => &r.user_id,
+        }
+    }
+
+    /// When the record last changed, if it tracks that
+    pub fn updated_at(&self) -> Option<DateTime<Utc>> {
+        match self {
+            SyncRecord::Progress(p) => Some(p.last_updated_at),
+            SyncRecord::Mastery(m) => Some(m.last_updated_at),
+            SyncRecord::Review(r) => r.last_reviewed_at,
+        }
+    }
+
+    pub fn content_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(serde_json::to_vec(self).unwrap_or_default());
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+/// One change in a device's op-log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncOp {
+    pub id: String,
+    /// Device that made the change
+    pub device_id: String,
+    pub user_id: String,
+    pub timestamp: DateTime<Utc>,
+    /// The record was removed; `record` holds its last contents
+    #[serde(default)]
+    pub deleted: bool,
+    pub record: SyncRecord,
+}
+
+impl SyncOp {
+    pub fn new(device_id: String, timestamp: DateTime<Utc>, deleted: bool, record: SyncRecord) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            device_id,
+            user_id: record.user_id().to_string(),
+            timestamp,
+            deleted,
+            record,
+        }
+    }
+
+    /// Last writer wins, with the device ID breaking timestamp ties so every
+    /// device settles on the same version
+    pub fn supersedes(&self, synced: &SyncedRecord) -> bool {
+        (self.timestamp, self.device_id.as_str()) > (synced.updated_at, synced.device_id.as_str())
+    }
+}
+
+/// The last synced version of a record on this device
+#[derive(Debug, Clone)]
+pub struct SyncedRecord {
+    pub user_id: String,
+    pub record_key: String,
+    pub record: SyncRecord,
+    pub content_hash: String,
+    pub deleted: bool,
+    pub updated_at: DateTime<Utc>,
+    pub device_id: String,
+}
+
+impl From<&SyncOp> for SyncedRecord {
+    fn from(op: &SyncOp) -> Self {
+        Self {
+            user_id: op.user_id.clone(),
+            record_key: op.record.key(),
+            record: op.record.clone(),
+            content_hash: op.record.content_hash(),
+            deleted: op.deleted,
+            updated_at: op.timestamp,
+            device_id: op.device_id.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_key_ignores_contents() {
+        let mut progress = NodeProgress::new("test-user".to_string(), "week1-lecture".to_string());
+        let before = SyncRecord::Progress(progress.clone());
+        progress.complete();
+        let after = SyncRecord::Progress(progress.clone());
+
+        assert_eq!(before.key(), after.key());
+        assert_ne!(before.content_hash(), after.content_hash());
+        assert_ne!(
+            before.key(),
+            SyncRecord::Progress(progress.with_curriculum("rust".to_string())).key()
+        );
+    }
+
+    #[test]
+    fn test_later_op_supersedes_and_ties_break_by_device() {
+        let record = SyncRecord::Mastery(MasteryScore::new("test-user".to_string(), "ownership".to_string()));
+        let now = Utc::now();
+        let synced = SyncedRecord::from(&SyncOp::new("device-b".to_string(), now, false, record.clone()));
+
+        assert!(SyncOp::new("device-a".to_string(), now + Duration::seconds(1), false, record.clone()).supersedes(&synced));
+        assert!(!SyncOp::new("device-c".to_string(), now - Duration::seconds(1), false, record.clone()).supersedes(&synced));
+        assert!(SyncOp::new("device-c".to_string(), now, false, record.clone()).supersedes(&synced));
+        assert!(!SyncOp::new("device-a".to_string(), now, false, record).supersedes(&synced));
+    }
+}
@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Where a learner keeps the repo they build a project track in. Every
+/// day of the track is verified against this one directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct TrackRepo {
+    pub user_id: String,
+    pub curriculum_id: Option<String>,
+    pub track_id: String,
+    pub repo_path: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TrackRepo {
+    pub fn new(user_id: String, curriculum_id: Option<String>, track_id: String, repo_path: String) -> Self {
+        Self {
+            user_id,
+            curriculum_id,
+            track_id,
+            repo_path,
+            updated_at: Utc::now(),
+        }
+    }
+}
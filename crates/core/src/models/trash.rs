@@ -0,0 +1,106 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// A destructive operation that can be undone for a while after it runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrashOperation {
+    ResetProgress,
+    DeleteCurriculum,
+}
+
+impl TrashOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrashOperation::ResetProgress => "ResetProgress",
+            TrashOperation::DeleteCurriculum => "DeleteCurriculum",
+        }
+    }
+}
+
+impl FromStr for TrashOperation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ResetProgress" => Ok(TrashOperation::ResetProgress),
+            "DeleteCurriculum" => Ok(TrashOperation::DeleteCurriculum),
+            _ => Err(format!("Invalid trash operation: {}", s)),
+        }
+    }
+}
+
+/// Rows copied from one table before they were deleted or overwritten
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableSnapshot {
+    pub table: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
+
+/// Everything a destructive operation removed, kept until `expires_at`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub id: String,
+    /// User whose data was removed; `None` for shared data such as curricula
+    pub user_id: Option<String>,
+    pub operation: TrashOperation,
+    /// What the operation acted on, e.g. the deleted curriculum's ID
+    pub subject_id: Option<String>,
+    /// Restored in order, so parent tables come first
+    pub tables: Vec<TableSnapshot>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl TrashEntry {
+    pub const DEFAULT_UNDO_WINDOW_MINUTES: i64 = 30;
+
+    pub fn new(user_id: Option<String>, operation: TrashOperation, undo_window: Duration) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            operation,
+            subject_id: None,
+            tables: Vec::new(),
+            created_at: now,
+            expires_at: now + undo_window,
+        }
+    }
+
+    pub fn with_subject(mut self, subject_id: String) -> Self {
+        self.subject_id = Some(subject_id);
+        self
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+
+    /// Number of rows that undoing would restore
+    pub fn row_count(&self) -> usize {
+        self.tables.iter().map(|t| t.rows.len()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_expires_after_window() {
+        let entry = TrashEntry::new(None, TrashOperation::DeleteCurriculum, Duration::minutes(10));
+        assert!(!entry.is_expired(entry.created_at + Duration::minutes(9)));
+        assert!(entry.is_expired(entry.created_at + Duration::minutes(10)));
+    }
+
+    #[test]
+    fn test_operation_round_trip() {
+        for op in [TrashOperation::ResetProgress, TrashOperation::DeleteCurriculum] {
+            assert_eq!(op.as_str().parse::<TrashOperation>().unwrap(), op);
+        }
+        assert!("Nope".parse::<TrashOperation>().is_err());
+    }
+}
@@ -0,0 +1,80 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// A conversation with the tutor about one lecture
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct TutorSession {
+    pub id: String,
+    pub user_id: String,
+    pub curriculum_id: Option<String>,
+    pub node_id: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TutorSession {
+    pub fn new(user_id: String, curriculum_id: Option<String>, node_id: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            curriculum_id,
+            node_id,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum TutorRole {
+    User,
+    Assistant,
+}
+
+impl TutorRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TutorRole::User => "User",
+            TutorRole::Assistant => "Assistant",
+        }
+    }
+}
+
+impl FromStr for TutorRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "User" => Ok(TutorRole::User),
+            "Assistant" => Ok(TutorRole::Assistant),
+            _ => Err(format!("Invalid tutor role: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct TutorMessage {
+    pub id: String,
+    pub session_id: String,
+    pub role: TutorRole,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TutorMessage {
+    pub fn new(session_id: String, role: TutorRole, content: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            session_id,
+            role,
+            content,
+            created_at: Utc::now(),
+        }
+    }
+}
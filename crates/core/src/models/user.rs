@@ -10,6 +10,12 @@ pub struct User {
     pub current_level: i32,
     pub current_streak: i32,
     pub last_streak_date: Option<DateTime<Utc>>,
+    /// Profile name shown when several people share the machine
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// A throwaway profile whose data is discarded when the learner leaves it
+    #[serde(default)]
+    pub is_guest: bool,
 }
 
 impl User {
@@ -23,6 +29,33 @@ impl User {
             current_level: 1,
             current_streak: 0,
             last_streak_date: None,
+            display_name: None,
+            is_guest: false,
+        }
+    }
+
+    /// Create a named local profile
+    pub fn with_display_name(id: String, display_name: String) -> Self {
+        Self {
+            display_name: Some(display_name),
+            ..Self::new(id)
+        }
+    }
+
+    /// Create a guest profile for trying the app without keeping anything
+    pub fn guest(id: String) -> Self {
+        Self {
+            is_guest: true,
+            ..Self::new(id)
+        }
+    }
+
+    /// Name to show for this profile, falling back to a short form of the ID
+    pub fn profile_name(&self) -> String {
+        match &self.display_name {
+            Some(name) => name.clone(),
+            None if self.is_guest => "Guest".to_string(),
+            None => format!("Learner {}", self.id.chars().take(8).collect::<String>()),
         }
     }
 
@@ -77,6 +110,15 @@ mod tests {
         assert_eq!(user.current_streak, 0);
     }
 
+    #[test]
+    fn test_profile_name() {
+        let named = User::with_display_name("id-1".to_string(), "Ada".to_string());
+        assert_eq!(named.profile_name(), "Ada");
+
+        let unnamed = User::new("0123456789abcdef".to_string());
+        assert_eq!(unnamed.profile_name(), "Learner 01234567");
+    }
+
     #[test]
     fn test_xp_for_level() {
         assert_eq!(User::xp_for_level(1), 100);
@@ -0,0 +1,33 @@
+//! Curriculum pacing
+//!
+//! The learner picks a date to finish the curriculum by, and `planner`
+//! spreads the nodes they haven't completed over the study days left until
+//! then, following the weekly study days in their settings. The plan is
+//! worked out again from what's left each time it's asked for, so falling
+//! behind moves the remaining nodes onto the days still available, and it is
+//! compared with the pace set when the target was chosen to say so.
+
+pub mod planner;
+
+pub use planner::{
+    clear_pace_target, pace_plan, plan_pace, set_pace_target, PaceDay, PaceNode, PacePlan, PaceTarget,
+    PACE_TARGET_KEY,
+};
+
+use thiserror::Error;
+
+use crate::settings::SettingsError;
+
+#[derive(Error, Debug)]
+pub enum PacingError {
+    #[error(transparent)]
+    Db(#[from] crate::db::error::DbError),
+
+    #[error(transparent)]
+    Settings(#[from] SettingsError),
+
+    #[error("Invalid target: {0}")]
+    InvalidTarget(String),
+}
+
+pub type PacingResult<T> = Result<T, PacingError>;
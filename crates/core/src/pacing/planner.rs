@@ -0,0 +1,317 @@
+use chrono::{Datelike, NaiveDate};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::db::error::{DbError, DbResult};
+use crate::db::repos::UserRepository;
+use crate::pacing::{PacingError, PacingResult};
+use crate::settings::{load_settings, StudyDays};
+
+/// Profile setting a curriculum's pace target is stored under, followed by
+/// `:` and the curriculum id
+pub const PACE_TARGET_KEY: &str = "pace_target";
+
+/// How far, as a fraction of the minutes the target started with, the
+/// learner can trail the pace before they're behind
+pub const PACE_BEHIND_MARGIN: f64 = 0.1;
+
+/// The date a learner wants to finish a curriculum by
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct PaceTarget {
+    pub target_date: NaiveDate,
+    /// The day the target was chosen
+    pub set_on: NaiveDate,
+    /// Estimated minutes of the nodes left when the target was chosen
+    pub minutes_at_start: u32,
+}
+
+/// A node still to complete, in curriculum order
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaceNode {
+    pub node_id: String,
+    pub estimated_minutes: u32,
+}
+
+/// A study day in a pace plan and the nodes to complete on it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct PaceDay {
+    pub date: NaiveDate,
+    pub node_ids: Vec<String>,
+    pub minutes: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct PacePlan {
+    pub target_date: NaiveDate,
+    pub set_on: NaiveDate,
+    pub remaining_nodes: u32,
+    pub remaining_minutes: u32,
+    /// Study days from today to the target date, both included
+    pub study_days_left: u32,
+    /// Minutes a study day takes to finish on time
+    pub minutes_per_day: u32,
+    pub daily_goal_minutes: u32,
+    /// `minutes_per_day` is more than the daily goal
+    pub over_daily_goal: bool,
+    /// Minutes that would be left today at the pace set with the target
+    pub expected_remaining_minutes: u32,
+    /// More than `PACE_BEHIND_MARGIN` of the work past that pace
+    pub behind: bool,
+    /// Every study day left, in order. When none are left, today holds
+    /// everything remaining.
+    pub days: Vec<PaceDay>,
+}
+
+fn target_key(curriculum_id: Option<&str>) -> String {
+    match curriculum_id {
+        Some(curriculum_id) => format!("{}:{}", PACE_TARGET_KEY, curriculum_id),
+        None => PACE_TARGET_KEY.to_string(),
+    }
+}
+
+/// Study days from `from` to `to`, both included
+fn study_dates(from: NaiveDate, to: NaiveDate, study_days: &StudyDays) -> Vec<NaiveDate> {
+    from.iter_days()
+        .take_while(|date| *date <= to)
+        .filter(|date| study_days.includes(date.weekday()))
+        .collect()
+}
+
+/// The nodes among `nodes` the user hasn't completed
+fn remaining_nodes(
+    conn: &Connection,
+    user_id: &str,
+    curriculum_id: Option<&str>,
+    nodes: &[PaceNode],
+) -> DbResult<Vec<PaceNode>> {
+    let mut stmt = conn.prepare(
+        "SELECT node_id FROM node_progress
+         WHERE user_id = ?1 AND curriculum_id IS ?2 AND status = 'Completed'",
+    )?;
+    let completed = stmt
+        .query_map(params![user_id, curriculum_id], |row| row.get::<_, String>(0))?
+        .collect::<Result<HashSet<_>, _>>()?;
+    Ok(nodes.iter().filter(|node| !completed.contains(&node.node_id)).cloned().collect())
+}
+
+/// Choose the date to finish a curriculum made up of `nodes` by
+pub fn set_pace_target(
+    conn: &Connection,
+    user_id: &str,
+    curriculum_id: Option<&str>,
+    nodes: &[PaceNode],
+    target_date: NaiveDate,
+    today: NaiveDate,
+) -> PacingResult<PaceTarget> {
+    if target_date < today {
+        return Err(PacingError::InvalidTarget("target date has already passed".to_string()));
+    }
+    let settings = load_settings(conn, user_id)?;
+    if study_dates(today, target_date, &settings.study_days).is_empty() {
+        return Err(PacingError::InvalidTarget("no study days before the target date".to_string()));
+    }
+
+    let remaining = remaining_nodes(conn, user_id, curriculum_id, nodes)?;
+    let target = PaceTarget {
+        target_date,
+        set_on: today,
+        minutes_at_start: remaining.iter().map(|node| node.estimated_minutes).sum(),
+    };
+    let json = serde_json::to_string(&target).map_err(|e| DbError::InvalidData(e.to_string()))?;
+    UserRepository::set_setting(conn, user_id, &target_key(curriculum_id), &json)?;
+    Ok(target)
+}
+
+pub fn clear_pace_target(conn: &Connection, user_id: &str, curriculum_id: Option<&str>) -> DbResult<()> {
+    UserRepository::delete_setting(conn, user_id, &target_key(curriculum_id))
+}
+
+/// The user's pace plan for a curriculum made up of `nodes`, or `None` when
+/// they haven't chosen a target date
+pub fn pace_plan(
+    conn: &Connection,
+    user_id: &str,
+    curriculum_id: Option<&str>,
+    nodes: &[PaceNode],
+    today: NaiveDate,
+) -> PacingResult<Option<PacePlan>> {
+    let Some(json) = UserRepository::get_setting(conn, user_id, &target_key(curriculum_id))? else {
+        return Ok(None);
+    };
+    let target: PaceTarget = serde_json::from_str(&json).map_err(|e| DbError::InvalidData(e.to_string()))?;
+    let settings = load_settings(conn, user_id)?;
+    let remaining = remaining_nodes(conn, user_id, curriculum_id, nodes)?;
+    Ok(Some(plan_pace(
+        &remaining,
+        &settings.study_days,
+        settings.daily_goal_minutes,
+        &target,
+        today,
+    )))
+}
+
+/// Spread `remaining` over the study days from `today` to the target date.
+/// Nodes keep their order and each day gets about the same minutes, a node
+/// going on the day its first minute falls in.
+pub fn plan_pace(
+    remaining: &[PaceNode],
+    study_days: &StudyDays,
+    daily_goal_minutes: u32,
+    target: &PaceTarget,
+    today: NaiveDate,
+) -> PacePlan {
+    let dates = study_dates(today, target.target_date, study_days);
+    let study_days_left = dates.len() as u32;
+    let mut days: Vec<PaceDay> = if dates.is_empty() { vec![today] } else { dates }
+        .into_iter()
+        .map(|date| PaceDay { date, node_ids: Vec::new(), minutes: 0 })
+        .collect();
+
+    // Nodes without an estimate still take up a minute, so they're spread too
+    let total: u64 = remaining.iter().map(|node| node.estimated_minutes.max(1) as u64).sum();
+    let day_count = days.len();
+    let mut before = 0u64;
+    for node in remaining {
+        let index = (before * day_count as u64 / total.max(1)) as usize;
+        let day = &mut days[index.min(day_count - 1)];
+        day.node_ids.push(node.node_id.clone());
+        day.minutes += node.estimated_minutes;
+        before += node.estimated_minutes.max(1) as u64;
+    }
+
+    let remaining_minutes: u32 = remaining.iter().map(|node| node.estimated_minutes).sum();
+    let minutes_per_day = remaining_minutes.div_ceil(study_days_left.max(1));
+
+    // The pace set with the target: the minutes it started with, worked off
+    // evenly over its study days
+    let planned_days = study_dates(target.set_on, target.target_date, study_days).len() as u64;
+    let expected_remaining_minutes = (target.minutes_at_start as u64 * study_days_left as u64)
+        .checked_div(planned_days)
+        .unwrap_or(0) as u32;
+    let margin = (target.minutes_at_start as f64 * PACE_BEHIND_MARGIN) as u32;
+
+    PacePlan {
+        target_date: target.target_date,
+        set_on: target.set_on,
+        remaining_nodes: remaining.len() as u32,
+        remaining_minutes,
+        study_days_left,
+        minutes_per_day,
+        daily_goal_minutes,
+        over_daily_goal: minutes_per_day > daily_goal_minutes,
+        expected_remaining_minutes,
+        behind: remaining_minutes > expected_remaining_minutes + margin,
+        days,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::ProgressRepository;
+    use crate::models::User;
+    use crate::settings::{save_settings, UserSettings};
+
+    fn date(day: u32) -> NaiveDate {
+        // 2024-03-04 is a Monday
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn nodes(minutes: &[u32]) -> Vec<PaceNode> {
+        minutes
+            .iter()
+            .enumerate()
+            .map(|(i, &estimated_minutes)| PaceNode { node_id: format!("node-{}", i), estimated_minutes })
+            .collect()
+    }
+
+    fn node_ids(day: &PaceDay) -> Vec<&str> {
+        day.node_ids.iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn test_nodes_are_spread_over_study_days() {
+        let weekdays = StudyDays { saturday: false, sunday: false, ..Default::default() };
+        let target = PaceTarget { target_date: date(10), set_on: date(4), minutes_at_start: 200 };
+
+        let plan = plan_pace(&nodes(&[30, 30, 20, 20, 40, 20, 20, 20]), &weekdays, 30, &target, date(4));
+        assert_eq!(plan.study_days_left, 5);
+        assert_eq!(plan.days.iter().map(|day| day.date).collect::<Vec<_>>(), (4..=8).map(date).collect::<Vec<_>>());
+        assert_eq!(node_ids(&plan.days[0]), vec!["node-0", "node-1"]);
+        assert_eq!(node_ids(&plan.days[1]), vec!["node-2"]);
+        assert_eq!(node_ids(&plan.days[2]), vec!["node-3", "node-4"]);
+        assert_eq!(node_ids(&plan.days[3]), vec!["node-5"]);
+        assert_eq!(node_ids(&plan.days[4]), vec!["node-6", "node-7"]);
+        assert_eq!(plan.minutes_per_day, 40);
+        assert!(plan.over_daily_goal);
+        assert!(!plan.behind);
+    }
+
+    #[test]
+    fn test_falling_behind_recalculates_the_plan() {
+        let target = PaceTarget { target_date: date(13), set_on: date(4), minutes_at_start: 100 };
+        let remaining = nodes(&[10; 10]);
+
+        // Five of ten days gone and nothing done
+        let plan = plan_pace(&remaining, &StudyDays::default(), 30, &target, date(9));
+        assert_eq!(plan.study_days_left, 5);
+        assert_eq!(plan.expected_remaining_minutes, 50);
+        assert!(plan.behind);
+        assert_eq!(plan.minutes_per_day, 20);
+        assert!(plan.days.iter().all(|day| day.node_ids.len() == 2));
+
+        // Past the target, everything left is due today
+        let late = plan_pace(&remaining, &StudyDays::default(), 30, &target, date(14));
+        assert_eq!(late.study_days_left, 0);
+        assert_eq!(late.days.len(), 1);
+        assert_eq!(late.days[0].date, date(14));
+        assert_eq!(late.days[0].node_ids.len(), 10);
+        assert!(late.behind);
+    }
+
+    #[test]
+    fn test_pace_target_follows_completed_nodes() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+        let curriculum = nodes(&[20, 20, 20, 20]);
+
+        assert_eq!(pace_plan(conn, "test-user", None, &curriculum, date(4)).unwrap(), None);
+        assert!(matches!(
+            set_pace_target(conn, "test-user", None, &curriculum, date(3), date(4)),
+            Err(PacingError::InvalidTarget(_))
+        ));
+
+        let weekends = StudyDays {
+            monday: false,
+            tuesday: false,
+            wednesday: false,
+            thursday: false,
+            friday: false,
+            ..Default::default()
+        };
+        save_settings(conn, "test-user", &UserSettings { study_days: weekends, ..Default::default() }).unwrap();
+        assert!(matches!(
+            set_pace_target(conn, "test-user", None, &curriculum, date(8), date(4)),
+            Err(PacingError::InvalidTarget(_))
+        ));
+
+        ProgressRepository::mark_completed(conn, "test-user", None, "node-0").unwrap();
+        let target = set_pace_target(conn, "test-user", None, &curriculum, date(17), date(4)).unwrap();
+        assert_eq!(target.minutes_at_start, 60);
+
+        ProgressRepository::mark_completed(conn, "test-user", None, "node-1").unwrap();
+        let plan = pace_plan(conn, "test-user", None, &curriculum, date(4)).unwrap().unwrap();
+        assert_eq!(plan.remaining_nodes, 2);
+        assert_eq!(plan.days.iter().map(|day| day.date).collect::<Vec<_>>(), vec![date(9), date(10), date(16), date(17)]);
+        assert!(!plan.behind);
+
+        clear_pace_target(conn, "test-user", None).unwrap();
+        assert_eq!(pace_plan(conn, "test-user", None, &curriculum, date(4)).unwrap(), None);
+    }
+}
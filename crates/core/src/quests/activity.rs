@@ -0,0 +1,79 @@
+//! Quest activity - applies learning activity to active quests
+
+use chrono::{DateTime, Utc};
+use crate::models::{Quest, QuestKind};
+
+/// Learning activity that can advance quests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuestActivity {
+    LectureCompleted,
+    QuizCompleted,
+    ReviewCompleted,
+    XpEarned(i32),
+}
+
+impl QuestActivity {
+    fn applies_to(&self, kind: QuestKind) -> Option<i32> {
+        match (self, kind) {
+            (QuestActivity::LectureCompleted, QuestKind::CompleteLectures) => Some(1),
+            (QuestActivity::QuizCompleted, QuestKind::CompleteQuizzes) => Some(1),
+            (QuestActivity::ReviewCompleted, QuestKind::CompleteReviews) => Some(1),
+            (QuestActivity::XpEarned(xp), QuestKind::EarnXp) => Some(*xp),
+            _ => None,
+        }
+    }
+}
+
+/// Apply an activity to all active quests it counts toward
+/// Returns the IDs of quests that were completed by this activity
+pub fn record_quest_activity(
+    quests: &mut [Quest],
+    activity: QuestActivity,
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    let mut completed = Vec::new();
+
+    for quest in quests.iter_mut().filter(|q| q.is_active(now)) {
+        if let Some(amount) = activity.applies_to(quest.kind) {
+            if quest.record_progress(amount, now) {
+                completed.push(quest.id.clone());
+            }
+        }
+    }
+
+    completed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::QuestPeriod;
+    use crate::quests::generate_quests;
+
+    #[test]
+    fn test_activity_only_advances_matching_quests() {
+        let now = Utc::now();
+        let mut quests = generate_quests("user1", QuestPeriod::Daily, now);
+        quests.extend(generate_quests("user1", QuestPeriod::Weekly, now));
+
+        record_quest_activity(&mut quests, QuestActivity::XpEarned(10_000), now);
+
+        for quest in &quests {
+            if quest.kind == QuestKind::EarnXp {
+                assert!(quest.is_complete());
+            } else {
+                assert_eq!(quest.progress, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_expired_quests_are_ignored() {
+        let now = Utc::now();
+        let mut quests = generate_quests("user1", QuestPeriod::Daily, now);
+        let tomorrow = now + chrono::Duration::days(1);
+
+        let completed = record_quest_activity(&mut quests, QuestActivity::XpEarned(10_000), tomorrow);
+        assert!(completed.is_empty());
+    }
+}
@@ -0,0 +1,194 @@
+//! Quest generator - picks the active quests for a period
+//!
+//! Quests are chosen from a fixed template pool using a generator seeded by
+//! the user and period start, so the same user always sees the same quests
+//! for a given day or week.
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use crate::models::{Quest, QuestKind, QuestPeriod};
+
+pub const DAILY_QUEST_COUNT: usize = 3;
+pub const WEEKLY_QUEST_COUNT: usize = 2;
+
+/// A quest shape that can be rolled for a period
+#[derive(Debug, Clone, Copy)]
+pub struct QuestTemplate {
+    pub period: QuestPeriod,
+    pub kind: QuestKind,
+    pub target: i32,
+    pub xp_reward: i32,
+}
+
+impl QuestTemplate {
+    pub fn description(&self) -> String {
+        let when = match self.period {
+            QuestPeriod::Daily => "today",
+            QuestPeriod::Weekly => "this week",
+        };
+
+        match self.kind {
+            QuestKind::CompleteLectures => format!("Complete {} lectures {}", self.target, when),
+            QuestKind::CompleteQuizzes => format!("Complete {} quizzes {}", self.target, when),
+            QuestKind::CompleteReviews => format!("Review {} items {}", self.target, when),
+            QuestKind::EarnXp => format!("Earn {} XP {}", self.target, when),
+        }
+    }
+}
+
+/// All quest templates available to the generator
+pub fn quest_templates() -> Vec<QuestTemplate> {
+    vec![
+        // Daily quests
+        QuestTemplate { period: QuestPeriod::Daily, kind: QuestKind::CompleteLectures, target: 1, xp_reward: 20 },
+        QuestTemplate { period: QuestPeriod::Daily, kind: QuestKind::CompleteLectures, target: 2, xp_reward: 35 },
+        QuestTemplate { period: QuestPeriod::Daily, kind: QuestKind::CompleteQuizzes, target: 1, xp_reward: 25 },
+        QuestTemplate { period: QuestPeriod::Daily, kind: QuestKind::CompleteQuizzes, target: 2, xp_reward: 40 },
+        QuestTemplate { period: QuestPeriod::Daily, kind: QuestKind::CompleteReviews, target: 5, xp_reward: 25 },
+        QuestTemplate { period: QuestPeriod::Daily, kind: QuestKind::EarnXp, target: 100, xp_reward: 30 },
+        // Weekly quests
+        QuestTemplate { period: QuestPeriod::Weekly, kind: QuestKind::CompleteLectures, target: 5, xp_reward: 100 },
+        QuestTemplate { period: QuestPeriod::Weekly, kind: QuestKind::CompleteQuizzes, target: 5, xp_reward: 120 },
+        QuestTemplate { period: QuestPeriod::Weekly, kind: QuestKind::CompleteReviews, target: 10, xp_reward: 80 },
+        QuestTemplate { period: QuestPeriod::Weekly, kind: QuestKind::EarnXp, target: 750, xp_reward: 150 },
+    ]
+}
+
+/// Generate the quests for the period containing `now`
+pub fn generate_quests(user_id: &str, period: QuestPeriod, now: DateTime<Utc>) -> Vec<Quest> {
+    let (starts_at, ends_at) = period.bounds(now);
+    let count = match period {
+        QuestPeriod::Daily => DAILY_QUEST_COUNT,
+        QuestPeriod::Weekly => WEEKLY_QUEST_COUNT,
+    };
+
+    let mut pool: Vec<QuestTemplate> = quest_templates()
+        .into_iter()
+        .filter(|t| t.period == period)
+        .collect();
+
+    // Seeded Fisher-Yates shuffle
+    let mut rng = SplitMix64::new(seed_for(user_id, period, starts_at));
+    for i in (1..pool.len()).rev() {
+        let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+        pool.swap(i, j);
+    }
+
+    // At most one quest per activity kind
+    let mut chosen: Vec<QuestTemplate> = Vec::new();
+    for template in pool {
+        if chosen.len() == count {
+            break;
+        }
+        if !chosen.iter().any(|c| c.kind == template.kind) {
+            chosen.push(template);
+        }
+    }
+
+    chosen
+        .into_iter()
+        .map(|template| Quest {
+            id: format!(
+                "{}-{}-{}",
+                period.as_str().to_lowercase(),
+                starts_at.format("%Y-%m-%d"),
+                template.kind.as_str()
+            ),
+            user_id: user_id.to_string(),
+            period,
+            kind: template.kind,
+            description: template.description(),
+            target: template.target,
+            progress: 0,
+            xp_reward: template.xp_reward,
+            starts_at,
+            ends_at,
+            completed_at: None,
+            claimed_at: None,
+        })
+        .collect()
+}
+
+fn seed_for(user_id: &str, period: QuestPeriod, starts_at: DateTime<Utc>) -> u64 {
+    let mut hasher = Sha256::new();
+    hasher.update(user_id.as_bytes());
+    hasher.update(period.as_str().as_bytes());
+    hasher.update(starts_at.to_rfc3339().as_bytes());
+    let digest = hasher.finalize();
+
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    u64::from_le_bytes(bytes)
+}
+
+/// Small deterministic PRNG so quest rolls never depend on platform randomness
+pub(crate) struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_generation_is_deterministic() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 5, 9, 0, 0).unwrap();
+        let later_same_day = now + Duration::hours(10);
+
+        let first = generate_quests("user1", QuestPeriod::Daily, now);
+        let second = generate_quests("user1", QuestPeriod::Daily, later_same_day);
+
+        let first_ids: Vec<_> = first.iter().map(|q| q.id.clone()).collect();
+        let second_ids: Vec<_> = second.iter().map(|q| q.id.clone()).collect();
+        assert_eq!(first_ids, second_ids);
+    }
+
+    #[test]
+    fn test_quest_counts_and_unique_kinds() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 5, 9, 0, 0).unwrap();
+
+        let daily = generate_quests("user1", QuestPeriod::Daily, now);
+        assert_eq!(daily.len(), DAILY_QUEST_COUNT);
+
+        let weekly = generate_quests("user1", QuestPeriod::Weekly, now);
+        assert_eq!(weekly.len(), WEEKLY_QUEST_COUNT);
+
+        for quests in [&daily, &weekly] {
+            for (i, quest) in quests.iter().enumerate() {
+                assert!(quests[i + 1..].iter().all(|q| q.kind != quest.kind));
+                assert!(quest.is_active(now));
+            }
+        }
+    }
+
+    #[test]
+    fn test_quests_rotate_between_days() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let rolls: Vec<Vec<QuestKind>> = (0..14)
+            .map(|d| {
+                generate_quests("user1", QuestPeriod::Daily, start + Duration::days(d))
+                    .iter()
+                    .map(|q| q.kind)
+                    .collect()
+            })
+            .collect();
+
+        // Over two weeks the selection should not be constant
+        assert!(rolls.iter().any(|r| r != &rolls[0]));
+    }
+}
@@ -0,0 +1,10 @@
+//! Daily and weekly quest system
+//!
+//! This module generates rotating quests from a seeded deterministic generator
+//! and applies learning activity to quest progress.
+
+pub mod generator;
+pub mod activity;
+
+pub use generator::{generate_quests, quest_templates, QuestTemplate, DAILY_QUEST_COUNT, WEEKLY_QUEST_COUNT};
+pub use activity::{record_quest_activity, QuestActivity};
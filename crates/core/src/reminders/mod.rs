@@ -0,0 +1,26 @@
+//! Study reminders
+//!
+//! `prefs` holds when a user wants to be reminded and their quiet hours.
+//! `schedule` works out the next review, streak, goal and weekly report
+//! reminders from those preferences and the user's progress. All times here
+//! are local wall-clock times; the app converts to and from UTC and delivers
+//! the notifications.
+
+pub mod prefs;
+pub mod schedule;
+
+pub use prefs::{save_prefs, NotificationPrefs, QuietHours};
+pub use schedule::{due_reminders, upcoming_reminders, ReminderKind, ScheduledReminder};
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ReminderError {
+    #[error(transparent)]
+    Db(#[from] crate::db::error::DbError),
+
+    #[error("Invalid notification preferences: {0}")]
+    InvalidPrefs(String),
+}
+
+pub type ReminderResult<T> = Result<T, ReminderError>;
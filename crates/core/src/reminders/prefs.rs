@@ -0,0 +1,150 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use crate::db::repos::NotificationRepository;
+use crate::reminders::{ReminderError, ReminderResult};
+
+/// Format times are stored and exchanged in
+pub const TIME_FORMAT: &str = "%H:%M";
+
+fn time(hour: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, 0, 0).expect("whole hours are valid times")
+}
+
+/// Hours of the day reminders are held back. The window may cross midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct QuietHours {
+    #[serde(with = "hh_mm")]
+    pub start: NaiveTime,
+    #[serde(with = "hh_mm")]
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    pub fn contains(&self, at: NaiveTime) -> bool {
+        if self.start <= self.end {
+            at >= self.start && at < self.end
+        } else {
+            at >= self.start || at < self.end
+        }
+    }
+
+    /// When the quiet window containing `at` began
+    pub fn started_before(&self, at: NaiveDateTime) -> NaiveDateTime {
+        let date = if at.time() >= self.start { at.date() } else { previous_day(at.date()) };
+        date.and_time(self.start)
+    }
+
+    /// When the quiet window containing `at` ends
+    pub fn ends_after(&self, at: NaiveDateTime) -> NaiveDateTime {
+        let date = if at.time() < self.end { at.date() } else { next_day(at.date()) };
+        date.and_time(self.end)
+    }
+}
+
+fn previous_day(date: NaiveDate) -> NaiveDate {
+    date.pred_opt().expect("dates in use are far from chrono's limits")
+}
+
+fn next_day(date: NaiveDate) -> NaiveDate {
+    date.succ_opt().expect("dates in use are far from chrono's limits")
+}
+
+/// When and whether a user is reminded to study
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct NotificationPrefs {
+    /// Turns all reminders off when false
+    pub enabled: bool,
+    pub review_reminders: bool,
+    /// Time reviews due that day are announced
+    #[serde(with = "hh_mm")]
+    pub review_time: NaiveTime,
+    pub streak_reminders: bool,
+    /// Time the user is warned a streak will break without study today
+    #[serde(with = "hh_mm")]
+    pub streak_time: NaiveTime,
+    pub quiet_hours: Option<QuietHours>,
+    /// Monday reminder, at `review_time`, to read the weekly report
+    #[serde(default)]
+    pub weekly_report_reminders: bool,
+}
+
+impl Default for NotificationPrefs {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            review_reminders: true,
+            review_time: time(18),
+            streak_reminders: true,
+            streak_time: time(21),
+            quiet_hours: Some(QuietHours { start: time(22), end: time(8) }),
+            weekly_report_reminders: false,
+        }
+    }
+}
+
+impl NotificationPrefs {
+    pub fn validate(&self) -> ReminderResult<()> {
+        if let Some(quiet) = self.quiet_hours {
+            if quiet.start == quiet.end {
+                return Err(ReminderError::InvalidPrefs("quiet hours must start and end at different times".to_string()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Validate and store a user's preferences
+pub fn save_prefs(conn: &Connection, user_id: &str, prefs: &NotificationPrefs) -> ReminderResult<()> {
+    prefs.validate()?;
+    NotificationRepository::save_prefs(conn, user_id, prefs)?;
+    Ok(())
+}
+
+pub fn parse_time(value: &str) -> Result<NaiveTime, chrono::ParseError> {
+    NaiveTime::parse_from_str(value, TIME_FORMAT).or_else(|_| NaiveTime::parse_from_str(value, "%H:%M:%S"))
+}
+
+/// Times as `HH:MM`, the form an HTML time input produces
+mod hh_mm {
+    use chrono::NaiveTime;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(time: &NaiveTime, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&time.format(super::TIME_FORMAT).to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveTime, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        super::parse_time(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quiet_hours_across_midnight() {
+        let quiet = QuietHours { start: time(22), end: time(8) };
+        assert!(quiet.contains(time(23)));
+        assert!(quiet.contains(time(3)));
+        assert!(!quiet.contains(time(8)));
+        assert!(!quiet.contains(time(21)));
+
+        let late = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap().and_time(time(23));
+        assert_eq!(quiet.started_before(late), late.date().and_time(time(22)));
+        assert_eq!(quiet.ends_after(late), next_day(late.date()).and_time(time(8)));
+    }
+
+    #[test]
+    fn test_prefs_round_trip_as_hh_mm() {
+        let prefs = NotificationPrefs::default();
+        let json = serde_json::to_value(prefs).unwrap();
+        assert_eq!(json["review_time"], "18:00");
+        assert_eq!(json["quiet_hours"]["start"], "22:00");
+        assert_eq!(serde_json::from_value::<NotificationPrefs>(json).unwrap(), prefs);
+    }
+}
@@ -0,0 +1,341 @@
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use crate::db::error::{DbError, DbResult};
+use crate::db::repos::{NotificationRepository, ReviewRepository, UserRepository};
+use crate::goals;
+use crate::reminders::NotificationPrefs;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum ReminderKind {
+    /// Reviews are waiting
+    ReviewsDue,
+    /// Nothing studied yet today and the streak will break
+    StreakAtRisk,
+    /// A goal has fallen behind the pace needed to meet its deadline
+    GoalBehind,
+    /// The weekly progress report is ready, sent on Mondays
+    WeeklyReport,
+}
+
+impl ReminderKind {
+    pub const ALL: [ReminderKind; 4] = [
+        ReminderKind::ReviewsDue,
+        ReminderKind::StreakAtRisk,
+        ReminderKind::GoalBehind,
+        ReminderKind::WeeklyReport,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReminderKind::ReviewsDue => "ReviewsDue",
+            ReminderKind::StreakAtRisk => "StreakAtRisk",
+            ReminderKind::GoalBehind => "GoalBehind",
+            ReminderKind::WeeklyReport => "WeeklyReport",
+        }
+    }
+}
+
+impl FromStr for ReminderKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ReviewsDue" => Ok(ReminderKind::ReviewsDue),
+            "StreakAtRisk" => Ok(ReminderKind::StreakAtRisk),
+            "GoalBehind" => Ok(ReminderKind::GoalBehind),
+            "WeeklyReport" => Ok(ReminderKind::WeeklyReport),
+            other => Err(format!("Unknown reminder kind: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ScheduledReminder {
+    pub kind: ReminderKind,
+    /// Day the reminder is about; each kind is sent at most once per day
+    pub for_date: NaiveDate,
+    /// Local time it's delivered, after moving it out of quiet hours
+    pub at: NaiveDateTime,
+    pub title: String,
+    pub body: String,
+}
+
+fn utc_at<Tz: TimeZone>(tz: &Tz, local: NaiveDateTime) -> DateTime<Utc> {
+    // A time skipped by a DST change is read as UTC, which is close enough
+    tz.from_local_datetime(&local)
+        .earliest()
+        .map(|at| at.with_timezone(&Utc))
+        .unwrap_or_else(|| local.and_utc())
+}
+
+/// Move a delivery out of quiet hours: to when they end, or for a streak
+/// reminder that would then be too late, to when they start
+fn deliver_at(prefs: &NotificationPrefs, kind: ReminderKind, at: NaiveDateTime) -> Option<NaiveDateTime> {
+    let Some(quiet) = prefs.quiet_hours.filter(|quiet| quiet.contains(at.time())) else {
+        return Some(at);
+    };
+    let deferred = quiet.ends_after(at);
+    if kind == ReminderKind::StreakAtRisk && deferred.date() != at.date() {
+        let earlier = quiet.started_before(at);
+        return (earlier.date() == at.date()).then_some(earlier);
+    }
+    Some(deferred)
+}
+
+/// The next reminder of each enabled kind, given the user's progress at
+/// `now`. Whether a reminder is still needed is checked again when it's due,
+/// so tomorrow's entries are a forecast.
+pub fn upcoming_reminders<Tz: TimeZone>(
+    conn: &Connection,
+    user_id: &str,
+    curriculum_id: Option<&str>,
+    now: &DateTime<Tz>,
+) -> DbResult<Vec<ScheduledReminder>> {
+    let prefs = NotificationRepository::get_prefs(conn, user_id)?;
+    if !prefs.enabled {
+        return Ok(Vec::new());
+    }
+
+    let user = UserRepository::get_by_id(conn, user_id)?
+        .ok_or_else(|| DbError::NotFound("User not found".to_string()))?;
+    let tz = now.timezone();
+    let local_now = now.naive_local();
+    let today = local_now.date();
+    let studied_today = user
+        .last_streak_date
+        .map(|date| date.with_timezone(&tz).date_naive() == today)
+        .unwrap_or(false);
+    let last_sent = NotificationRepository::get_last_sent(conn, user_id)?;
+
+    let tomorrow = today.succ_opt().expect("dates in use are far from chrono's limits");
+    let plan = |kind: ReminderKind, for_date: NaiveDate| -> DbResult<Option<ScheduledReminder>> {
+        // Goal nudges and the weekly report go out with the day's review reminder
+        let time = match kind {
+            ReminderKind::ReviewsDue | ReminderKind::GoalBehind | ReminderKind::WeeklyReport => prefs.review_time,
+            ReminderKind::StreakAtRisk => prefs.streak_time,
+        };
+        let target = for_date.and_time(time);
+
+        let (title, body) = match kind {
+            ReminderKind::ReviewsDue => {
+                let due = ReviewRepository::count_due_reviews_by(conn, user_id, curriculum_id, utc_at(&tz, target))?;
+                if due == 0 {
+                    return Ok(None);
+                }
+                let noun = if due == 1 { "review" } else { "reviews" };
+                ("Reviews due".to_string(), format!("You have {} {} waiting.", due, noun))
+            }
+            ReminderKind::StreakAtRisk => {
+                let at_risk = if for_date == today {
+                    user.current_streak > 0 && !studied_today
+                } else {
+                    user.current_streak > 0 || studied_today
+                };
+                if !at_risk {
+                    return Ok(None);
+                }
+                let streak = user.current_streak.max(1);
+                (
+                    "Keep your streak going".to_string(),
+                    format!("Study today to keep your {}-day streak.", streak),
+                )
+            }
+            ReminderKind::GoalBehind => {
+                let behind = goals::behind_goals(conn, user_id, curriculum_id, utc_at(&tz, target))?;
+                let Some(first) = behind.first() else {
+                    return Ok(None);
+                };
+                let body = match behind.len() {
+                    1 => format!("\"{}\" is falling behind. A session today will catch it up.", first.goal.title),
+                    n => format!("\"{}\" and {} other goals are falling behind.", first.goal.title, n - 1),
+                };
+                ("Goal falling behind".to_string(), body)
+            }
+            ReminderKind::WeeklyReport => {
+                if for_date.weekday() != Weekday::Mon {
+                    return Ok(None);
+                }
+                (
+                    "Your weekly report is ready".to_string(),
+                    "See what you learned last week and what's coming up.".to_string(),
+                )
+            }
+        };
+
+        let Some(mut at) = deliver_at(&prefs, kind, target) else {
+            return Ok(None);
+        };
+        if at < local_now {
+            // Missed while the app was closed: send now, unless that's in quiet hours
+            at = match prefs.quiet_hours.filter(|quiet| quiet.contains(local_now.time())) {
+                Some(quiet) if kind != ReminderKind::StreakAtRisk => quiet.ends_after(local_now),
+                Some(_) => return Ok(None),
+                None => local_now,
+            };
+        }
+        Ok(Some(ScheduledReminder { kind, for_date, at, title, body }))
+    };
+
+    let mut reminders = Vec::new();
+    for kind in ReminderKind::ALL {
+        let enabled = match kind {
+            ReminderKind::ReviewsDue => prefs.review_reminders,
+            ReminderKind::StreakAtRisk => prefs.streak_reminders,
+            ReminderKind::GoalBehind => true,
+            ReminderKind::WeeklyReport => prefs.weekly_report_reminders,
+        };
+        if !enabled {
+            continue;
+        }
+
+        // Today's reminder unless it was sent or isn't needed, else tomorrow's
+        let sent_today = last_sent.iter().any(|(sent_kind, date)| *sent_kind == kind && *date == today);
+        let mut next = if sent_today { None } else { plan(kind, today)? };
+        if next.is_none() {
+            next = plan(kind, tomorrow)?;
+        }
+        reminders.extend(next);
+    }
+
+    reminders.sort_by_key(|r| r.at);
+    Ok(reminders)
+}
+
+/// Reminders that should be delivered now
+pub fn due_reminders<Tz: TimeZone>(
+    conn: &Connection,
+    user_id: &str,
+    curriculum_id: Option<&str>,
+    now: &DateTime<Tz>,
+) -> DbResult<Vec<ScheduledReminder>> {
+    let local_now = now.naive_local();
+    Ok(upcoming_reminders(conn, user_id, curriculum_id, now)?
+        .into_iter()
+        .filter(|r| r.at <= local_now)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::{GoalRepository, ProgressRepository};
+    use crate::models::{Goal, GoalTarget, ReviewItem, User};
+    use crate::reminders::QuietHours;
+    use chrono::{Duration, FixedOffset, NaiveTime};
+
+    fn local(date: NaiveDate, hour: u32, minute: u32) -> DateTime<FixedOffset> {
+        let tz = FixedOffset::east_opt(2 * 3600).unwrap();
+        tz.from_local_datetime(&date.and_hms_opt(hour, minute, 0).unwrap()).unwrap()
+    }
+
+    fn setup(streak: i32, last_studied: Option<DateTime<Utc>>) -> Database {
+        let db = Database::new_in_memory().unwrap();
+        let mut user = User::new("test-user".to_string());
+        user.current_streak = streak;
+        user.last_streak_date = last_studied;
+        UserRepository::create(db.connection(), &user).unwrap();
+        db
+    }
+
+    #[test]
+    fn test_streak_reminder_only_when_not_studied_today() {
+        let day = NaiveDate::from_ymd_opt(2024, 5, 6).unwrap();
+        let db = setup(4, Some(local(day, 0, 0).with_timezone(&Utc) - Duration::hours(6)));
+        let conn = db.connection();
+
+        let upcoming = upcoming_reminders(conn, "test-user", None, &local(day, 12, 0)).unwrap();
+        assert_eq!(upcoming.len(), 1);
+        assert_eq!(upcoming[0].kind, ReminderKind::StreakAtRisk);
+        assert_eq!(upcoming[0].at, day.and_hms_opt(21, 0, 0).unwrap());
+        assert!(due_reminders(conn, "test-user", None, &local(day, 12, 0)).unwrap().is_empty());
+
+        let due = due_reminders(conn, "test-user", None, &local(day, 21, 5)).unwrap();
+        assert_eq!(due.len(), 1);
+        NotificationRepository::record_sent(conn, "test-user", ReminderKind::StreakAtRisk, due[0].for_date).unwrap();
+        assert!(due_reminders(conn, "test-user", None, &local(day, 21, 6)).unwrap().is_empty());
+
+        // Studying today means no reminder until tomorrow
+        let studied = setup(4, Some(local(day, 9, 0).with_timezone(&Utc)));
+        let upcoming = upcoming_reminders(studied.connection(), "test-user", None, &local(day, 12, 0)).unwrap();
+        assert_eq!(upcoming[0].for_date, day.succ_opt().unwrap());
+    }
+
+    #[test]
+    fn test_reviews_reminder_respects_quiet_hours() {
+        let day = NaiveDate::from_ymd_opt(2024, 5, 6).unwrap();
+        let db = setup(0, None);
+        let conn = db.connection();
+
+        let mut review = ReviewItem::new("test-user".to_string(), "quiz-1".to_string());
+        review.due_date = local(day, 10, 0).with_timezone(&Utc);
+        ReviewRepository::create_or_update(conn, &review).unwrap();
+
+        let upcoming = upcoming_reminders(conn, "test-user", None, &local(day, 12, 0)).unwrap();
+        assert_eq!(upcoming.len(), 1);
+        assert_eq!(upcoming[0].kind, ReminderKind::ReviewsDue);
+        assert_eq!(upcoming[0].body, "You have 1 review waiting.");
+
+        let prefs = NotificationPrefs {
+            review_time: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+            quiet_hours: Some(QuietHours {
+                start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+                end: NaiveTime::from_hms_opt(7, 30, 0).unwrap(),
+            }),
+            ..NotificationPrefs::default()
+        };
+        NotificationRepository::save_prefs(conn, "test-user", &prefs).unwrap();
+        let upcoming = upcoming_reminders(conn, "test-user", None, &local(day, 12, 0)).unwrap();
+        assert_eq!(upcoming[0].at, day.succ_opt().unwrap().and_hms_opt(7, 30, 0).unwrap());
+        assert_eq!(upcoming[0].for_date, day);
+    }
+
+    #[test]
+    fn test_goal_reminder_when_behind() {
+        let day = NaiveDate::from_ymd_opt(2024, 5, 6).unwrap();
+        let db = setup(0, None);
+        let conn = db.connection();
+        let now = local(day, 12, 0);
+
+        let mut goal = Goal::new(
+            "test-user".to_string(),
+            None,
+            "Finish week 4".to_string(),
+            GoalTarget::CompleteWeek { week_id: "week4".to_string(), node_ids: vec!["a".to_string()] },
+            now.with_timezone(&Utc) + Duration::days(1),
+        );
+        goal.created_at = now.with_timezone(&Utc) - Duration::days(9);
+        GoalRepository::create(conn, &goal).unwrap();
+
+        let upcoming = upcoming_reminders(conn, "test-user", None, &now).unwrap();
+        assert_eq!(upcoming.len(), 1);
+        assert_eq!(upcoming[0].kind, ReminderKind::GoalBehind);
+        assert_eq!(upcoming[0].at, day.and_hms_opt(18, 0, 0).unwrap());
+        assert!(upcoming[0].body.contains("Finish week 4"));
+
+        ProgressRepository::mark_completed(conn, "test-user", None, "a").unwrap();
+        assert!(upcoming_reminders(conn, "test-user", None, &now).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_weekly_report_reminder_on_mondays_when_enabled() {
+        let monday = NaiveDate::from_ymd_opt(2024, 5, 6).unwrap();
+        let db = setup(0, None);
+        let conn = db.connection();
+        assert!(upcoming_reminders(conn, "test-user", None, &local(monday, 12, 0)).unwrap().is_empty());
+
+        let prefs = NotificationPrefs { weekly_report_reminders: true, ..NotificationPrefs::default() };
+        NotificationRepository::save_prefs(conn, "test-user", &prefs).unwrap();
+        let upcoming = upcoming_reminders(conn, "test-user", None, &local(monday, 12, 0)).unwrap();
+        assert_eq!(upcoming.len(), 1);
+        assert_eq!(upcoming[0].kind, ReminderKind::WeeklyReport);
+        assert_eq!(upcoming[0].at, monday.and_hms_opt(18, 0, 0).unwrap());
+
+        let tuesday = monday.succ_opt().unwrap();
+        assert!(upcoming_reminders(conn, "test-user", None, &local(tuesday, 12, 0)).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,307 @@
+//! Sandbox image updates
+//!
+//! Instead of only the image built during setup, the code sandbox can be
+//! kept up to date from a manifest of published images. An update is
+//! downloaded by digest, so what runs is exactly what was published, and if
+//! any digests are pinned only those are accepted, so a compromised manifest
+//! can't push an arbitrary image. The image an update replaces is kept, and
+//! rolling back returns to it; rolling back again returns to the update.
+//! Talking to the manifest URL and to Docker is left to the app; this module
+//! only decides which release to take and tracks what's installed.
+//!
+//! ```json
+//! {
+//!   "repository": "ghcr.io/example/glp-sandbox",
+//!   "releases": [
+//!     { "version": "2025.1", "digest": "sha256:…", "published_at": "2025-01-06T00:00:00Z" }
+//!   ]
+//! }
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// A sandbox image published in the manifest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ImageRelease {
+    /// Filled in from the manifest's repository
+    #[serde(default)]
+    pub repository: String,
+    pub version: String,
+    /// Content digest, `sha256:` and 64 hex digits
+    pub digest: String,
+    pub published_at: DateTime<Utc>,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+impl ImageRelease {
+    /// Reference that pulls exactly this image, e.g. `repo@sha256:…`
+    pub fn reference(&self) -> String {
+        format!("{}@{}", self.repository, self.digest)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImageManifest {
+    pub repository: String,
+    pub releases: Vec<ImageRelease>,
+}
+
+impl ImageManifest {
+    pub fn parse(json: &str) -> Result<Self, SandboxImageError> {
+        let mut manifest: ImageManifest =
+            serde_json::from_str(json).map_err(|e| SandboxImageError::InvalidManifest(e.to_string()))?;
+        let repository_ok = !manifest.repository.is_empty()
+            && manifest
+                .repository
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "._-/:".contains(c));
+        if !repository_ok {
+            return Err(SandboxImageError::InvalidManifest(format!(
+                "invalid repository {:?}",
+                manifest.repository
+            )));
+        }
+        for release in &mut manifest.releases {
+            if !is_digest(&release.digest) {
+                return Err(SandboxImageError::InvalidManifest(format!(
+                    "release {} has an invalid digest",
+                    release.version
+                )));
+            }
+            release.repository = manifest.repository.clone();
+        }
+        Ok(manifest)
+    }
+
+    /// The most recently published release
+    pub fn latest(&self) -> Option<&ImageRelease> {
+        self.releases.iter().max_by_key(|release| release.published_at)
+    }
+}
+
+/// Whether `digest` is a content digest, `sha256:` and 64 hex digits
+pub fn is_digest(digest: &str) -> bool {
+    digest
+        .strip_prefix("sha256:")
+        .is_some_and(|hex| hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// The image the sandbox runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum InstalledImage {
+    /// Built from the bundled Dockerfile during setup
+    Built,
+    Release(ImageRelease),
+}
+
+fn release_of(image: &InstalledImage) -> Option<&ImageRelease> {
+    match image {
+        InstalledImage::Release(release) => Some(release),
+        InstalledImage::Built => None,
+    }
+}
+
+/// What the learner asked to do with the sandbox image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum SandboxImageAction {
+    /// Check the manifest and download a newer release
+    Check,
+    /// Switch to the downloaded release
+    Apply,
+    /// Switch back to the image the last switch replaced
+    RollBack,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum SandboxImageError {
+    #[error("Invalid sandbox image manifest: {0}")]
+    InvalidManifest(String),
+
+    #[error("Sandbox image {0} isn't one of the pinned digests")]
+    NotPinned(String),
+
+    #[error("No sandbox image update has been downloaded")]
+    NothingDownloaded,
+
+    #[error("There's no earlier sandbox image to roll back to")]
+    NothingToRollBack,
+}
+
+/// Where sandbox images come from and which one is installed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct SandboxImageState {
+    pub manifest_url: Option<String>,
+    /// Digests accepted from the manifest; any are if none are pinned
+    #[serde(default)]
+    pub pinned_digests: Vec<String>,
+    /// Switch to updates once they're downloaded, rather than waiting
+    #[serde(default = "default_auto_apply")]
+    pub auto_apply: bool,
+    pub current: InstalledImage,
+    pub previous: Option<InstalledImage>,
+    /// Downloaded and verified, waiting to be switched to
+    pub downloaded: Option<ImageRelease>,
+    pub last_checked_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+fn default_auto_apply() -> bool {
+    true
+}
+
+impl Default for SandboxImageState {
+    fn default() -> Self {
+        Self {
+            manifest_url: None,
+            pinned_digests: Vec::new(),
+            auto_apply: default_auto_apply(),
+            current: InstalledImage::Built,
+            previous: None,
+            downloaded: None,
+            last_checked_at: None,
+            last_error: None,
+        }
+    }
+}
+
+impl SandboxImageState {
+    pub fn is_pinned(&self, digest: &str) -> bool {
+        self.pinned_digests.is_empty() || self.pinned_digests.iter().any(|pinned| pinned == digest)
+    }
+
+    /// The manifest's latest release, if it's newer than what's installed,
+    /// downloaded, or was rolled back from. A newer release that isn't
+    /// pinned is an error rather than skipped, so a bad manifest gets
+    /// noticed.
+    pub fn update_from(&self, manifest: &ImageManifest) -> Result<Option<ImageRelease>, SandboxImageError> {
+        let Some(latest) = manifest.latest() else {
+            return Ok(None);
+        };
+        let known = [
+            self.downloaded.as_ref(),
+            release_of(&self.current),
+            self.previous.as_ref().and_then(release_of),
+        ];
+        if known
+            .iter()
+            .flatten()
+            .any(|release| release.digest == latest.digest || release.published_at >= latest.published_at)
+        {
+            return Ok(None);
+        }
+        if !self.is_pinned(&latest.digest) {
+            return Err(SandboxImageError::NotPinned(latest.digest.clone()));
+        }
+        Ok(Some(latest.clone()))
+    }
+
+    pub fn record_download(&mut self, release: ImageRelease) {
+        self.downloaded = Some(release);
+    }
+
+    /// Switch to the downloaded release, keeping the current image to roll
+    /// back to. The pins are checked again, in case they changed since the
+    /// download.
+    pub fn apply(&mut self) -> Result<ImageRelease, SandboxImageError> {
+        let release = self.downloaded.clone().ok_or(SandboxImageError::NothingDownloaded)?;
+        if !self.is_pinned(&release.digest) {
+            return Err(SandboxImageError::NotPinned(release.digest));
+        }
+        self.downloaded = None;
+        let replaced = std::mem::replace(&mut self.current, InstalledImage::Release(release.clone()));
+        self.previous = Some(replaced);
+        Ok(release)
+    }
+
+    /// Swap back to the image the last switch replaced
+    pub fn roll_back(&mut self) -> Result<&InstalledImage, SandboxImageError> {
+        let previous = self.previous.take().ok_or(SandboxImageError::NothingToRollBack)?;
+        self.previous = Some(std::mem::replace(&mut self.current, previous));
+        Ok(&self.current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn digest(n: u8) -> String {
+        format!("sha256:{}", format!("{:02x}", n).repeat(32))
+    }
+
+    fn manifest(releases: &[(&str, u8, u32)]) -> ImageManifest {
+        let releases: Vec<_> = releases
+            .iter()
+            .map(|(version, n, day)| {
+                serde_json::json!({
+                    "version": version,
+                    "digest": digest(*n),
+                    "published_at": Utc.with_ymd_and_hms(2025, 1, *day, 0, 0, 0).unwrap(),
+                })
+            })
+            .collect();
+        let json = serde_json::json!({ "repository": "ghcr.io/example/glp-sandbox", "releases": releases });
+        ImageManifest::parse(&json.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_manifest_is_validated() {
+        let manifest = manifest(&[("2025.1", 1, 1), ("2025.2", 2, 8)]);
+        let latest = manifest.latest().unwrap();
+        assert_eq!(latest.version, "2025.2");
+        assert_eq!(latest.reference(), format!("ghcr.io/example/glp-sandbox@{}", digest(2)));
+
+        let bad_digest = r#"{"repository": "glp", "releases": [{"version": "1", "digest": "latest", "published_at": "2025-01-01T00:00:00Z"}]}"#;
+        assert!(matches!(ImageManifest::parse(bad_digest), Err(SandboxImageError::InvalidManifest(_))));
+        let bad_repository = r#"{"repository": "glp; rm -rf /", "releases": []}"#;
+        assert!(matches!(ImageManifest::parse(bad_repository), Err(SandboxImageError::InvalidManifest(_))));
+    }
+
+    #[test]
+    fn test_only_newer_pinned_releases_are_taken() {
+        let mut state = SandboxImageState::default();
+        let first = manifest(&[("2025.1", 1, 1)]);
+        let update = state.update_from(&first).unwrap().unwrap();
+        assert_eq!(update.version, "2025.1");
+
+        state.record_download(update);
+        assert_eq!(state.update_from(&first), Ok(None));
+        state.apply().unwrap();
+        assert_eq!(state.update_from(&first), Ok(None));
+
+        state.pinned_digests = vec![digest(1)];
+        let second = manifest(&[("2025.1", 1, 1), ("2025.2", 2, 8)]);
+        assert_eq!(state.update_from(&second), Err(SandboxImageError::NotPinned(digest(2))));
+        state.pinned_digests.push(digest(2));
+        assert_eq!(state.update_from(&second).unwrap().unwrap().version, "2025.2");
+    }
+
+    #[test]
+    fn test_apply_and_roll_back() {
+        let mut state = SandboxImageState::default();
+        assert_eq!(state.apply(), Err(SandboxImageError::NothingDownloaded));
+        assert_eq!(state.roll_back(), Err(SandboxImageError::NothingToRollBack));
+
+        let release = manifest(&[("2025.1", 1, 1)]).latest().unwrap().clone();
+        state.record_download(release.clone());
+        state.apply().unwrap();
+        assert_eq!(state.current, InstalledImage::Release(release.clone()));
+        assert_eq!(state.previous, Some(InstalledImage::Built));
+        assert!(state.downloaded.is_none());
+
+        assert_eq!(state.roll_back(), Ok(&InstalledImage::Built));
+        assert_eq!(state.previous, Some(InstalledImage::Release(release.clone())));
+        // The release rolled back from isn't offered again
+        assert_eq!(state.update_from(&manifest(&[("2025.1", 1, 1)])), Ok(None));
+        assert_eq!(state.roll_back(), Ok(&InstalledImage::Release(release)));
+    }
+}
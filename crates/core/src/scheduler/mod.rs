@@ -0,0 +1,10 @@
+//! Background job scheduling
+//!
+//! Schedules decide when recurring maintenance jobs are due. The app owns
+//! the timer and the jobs themselves; last-run times are stored with
+//! `JobRepository` so a job missed while the app was closed runs on the
+//! next start.
+
+pub mod schedule;
+
+pub use schedule::{CronSpec, Schedule, ScheduleError};
@@ -0,0 +1,280 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc};
+use thiserror::Error;
+
+/// How far ahead to look for a cron match before deciding there is none
+const SEARCH_LIMIT_DAYS: i64 = 366 * 5;
+
+#[derive(Error, Debug, PartialEq)]
+#[error("Invalid schedule '{spec}': {reason}")]
+pub struct ScheduleError {
+    pub spec: String,
+    pub reason: String,
+}
+
+/// When a job runs.
+///
+/// Specs are either five-field cron expressions evaluated in UTC
+/// (`minute hour day-of-month month day-of-week`, each a `*`, value, range
+/// or list, optionally with a `/step`), one of `@hourly`, `@daily`,
+/// `@weekly` or `@monthly`, or `@every <n><s|m|h|d>` for a fixed interval
+/// since the last run.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schedule {
+    Every(Duration),
+    Cron(CronSpec),
+}
+
+/// Parsed cron fields, one bit per allowed value
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSpec {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// Cron matches either day field when both are restricted
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl Schedule {
+    pub fn parse(spec: &str) -> Result<Schedule, ScheduleError> {
+        let invalid = |reason: String| ScheduleError { spec: spec.to_string(), reason };
+        let trimmed = spec.trim();
+
+        if let Some(interval) = trimmed.strip_prefix("@every") {
+            return parse_interval(interval.trim()).map(Schedule::Every).map_err(invalid);
+        }
+
+        let expression = match trimmed {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        CronSpec::parse(expression).map(Schedule::Cron).map_err(invalid)
+    }
+
+    /// The first run time strictly after `after`, if there is one
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Schedule::Every(interval) => Some(after + *interval),
+            Schedule::Cron(cron) => cron.next_after(after),
+        }
+    }
+
+    /// Whether a job last run at `last_run` should run now. A job that has
+    /// never run is due, as is one whose run was missed while the app was
+    /// closed.
+    pub fn is_due(&self, last_run: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+        match last_run {
+            None => true,
+            Some(last_run) => self.next_after(last_run).is_some_and(|next| next <= now),
+        }
+    }
+}
+
+fn parse_interval(interval: &str) -> Result<Duration, String> {
+    if interval.len() < 2 {
+        return Err("expected an interval like 15m".to_string());
+    }
+    let (amount, unit) = interval.split_at(interval.len() - 1);
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| format!("invalid interval amount '{}'", amount))?;
+    if amount <= 0 {
+        return Err("interval must be positive".to_string());
+    }
+
+    match unit {
+        "s" => Ok(Duration::seconds(amount)),
+        "m" => Ok(Duration::minutes(amount)),
+        "h" => Ok(Duration::hours(amount)),
+        "d" => Ok(Duration::days(amount)),
+        other => Err(format!("unknown interval unit '{}'", other)),
+    }
+}
+
+/// Bits for one cron field, e.g. `*/15`, `1-5` or `0,30`
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut bits = 0u64;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| format!("invalid step '{}'", step))?;
+                if step == 0 {
+                    return Err("step must be positive".to_string());
+                }
+                (range, Some(step))
+            }
+            None => (part, None),
+        };
+
+        let parse_value = |s: &str| -> Result<u32, String> {
+            let value: u32 = s.parse().map_err(|_| format!("invalid value '{}'", s))?;
+            if value < min || value > max {
+                return Err(format!("{} is outside {}-{}", value, min, max));
+            }
+            Ok(value)
+        };
+
+        let (low, high) = if range == "*" {
+            (min, max)
+        } else if let Some((low, high)) = range.split_once('-') {
+            (parse_value(low)?, parse_value(high)?)
+        } else {
+            let value = parse_value(range)?;
+            // `5/10` means every 10 starting at 5
+            (value, if step.is_some() { max } else { value })
+        };
+        if low > high {
+            return Err(format!("range {}-{} is backwards", low, high));
+        }
+
+        for value in (low..=high).step_by(step.unwrap_or(1) as usize) {
+            bits |= 1 << value;
+        }
+    }
+
+    Ok(bits)
+}
+
+fn has(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+fn midnight(date: NaiveDate) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).expect("midnight is a valid time"))
+}
+
+impl CronSpec {
+    fn parse(expression: &str) -> Result<CronSpec, String> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(format!("expected 5 fields, found {}", fields.len()));
+        };
+
+        // Sunday may be written as 0 or 7
+        let mut days_of_week = parse_field(day_of_week, 0, 7)?;
+        if has(days_of_week, 7) {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+
+        Ok(CronSpec {
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days_of_month: parse_field(day_of_month, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            days_of_week,
+            day_of_month_restricted: day_of_month != "*",
+            day_of_week_restricted: day_of_week != "*",
+        })
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day_of_month = has(self.days_of_month, date.day());
+        let day_of_week = has(self.days_of_week, date.weekday().num_days_from_sunday());
+
+        if self.day_of_month_restricted && self.day_of_week_restricted {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+
+    fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = after + Duration::days(SEARCH_LIMIT_DAYS);
+        let mut t = start;
+
+        // Skip whole months, days and hours that can't match before
+        // stepping through minutes
+        while t <= limit {
+            let date = t.date_naive();
+            if !has(self.months, t.month()) {
+                let (year, month) = if t.month() == 12 { (t.year() + 1, 1) } else { (t.year(), t.month() + 1) };
+                t = midnight(NaiveDate::from_ymd_opt(year, month, 1)?);
+            } else if !self.matches_day(date) {
+                t = midnight(date.succ_opt()?);
+            } else if !has(self.hours, t.hour()) {
+                t = t.with_minute(0)? + Duration::hours(1);
+            } else if !has(self.minutes, t.minute()) {
+                t += Duration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_specs() {
+        assert!(Schedule::parse("* * * *").is_err());
+        assert!(Schedule::parse("60 * * * *").is_err());
+        assert!(Schedule::parse("*/0 * * * *").is_err());
+        assert!(Schedule::parse("5-1 * * * *").is_err());
+        assert!(Schedule::parse("@every 10x").is_err());
+        assert!(Schedule::parse("@every -5m").is_err());
+    }
+
+    #[test]
+    fn test_interval_schedule() {
+        let schedule = Schedule::parse("@every 15m").unwrap();
+        let now = at("2024-03-01T10:00:00Z");
+        assert_eq!(schedule.next_after(now), Some(at("2024-03-01T10:15:00Z")));
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        let after = at("2024-03-01T10:07:30Z");
+
+        let hourly = Schedule::parse("@hourly").unwrap();
+        assert_eq!(hourly.next_after(after), Some(at("2024-03-01T11:00:00Z")));
+
+        let quarter_hours = Schedule::parse("*/15 * * * *").unwrap();
+        assert_eq!(quarter_hours.next_after(after), Some(at("2024-03-01T10:15:00Z")));
+
+        // 2024-03-01 is a Friday; next weekday 03:30 is the Monday
+        let weekdays = Schedule::parse("30 3 * * 1-5").unwrap();
+        assert_eq!(weekdays.next_after(after), Some(at("2024-03-04T03:30:00Z")));
+
+        let new_year = Schedule::parse("0 0 1 1 *").unwrap();
+        assert_eq!(new_year.next_after(after), Some(at("2025-01-01T00:00:00Z")));
+    }
+
+    #[test]
+    fn test_cron_day_fields_match_either_when_both_set() {
+        // The 15th, or any Sunday (written as 7)
+        let schedule = Schedule::parse("0 0 15 * 7").unwrap();
+        assert_eq!(schedule.next_after(at("2024-03-01T12:00:00Z")), Some(at("2024-03-03T00:00:00Z")));
+        assert_eq!(schedule.next_after(at("2024-03-10T12:00:00Z")), Some(at("2024-03-15T00:00:00Z")));
+    }
+
+    #[test]
+    fn test_impossible_cron_never_runs() {
+        let schedule = Schedule::parse("0 0 30 2 *").unwrap();
+        assert_eq!(schedule.next_after(at("2024-01-01T00:00:00Z")), None);
+        assert!(!schedule.is_due(Some(at("2024-01-01T00:00:00Z")), at("2030-01-01T00:00:00Z")));
+    }
+
+    #[test]
+    fn test_is_due_catches_up_on_missed_runs() {
+        let schedule = Schedule::parse("@daily").unwrap();
+        let now = at("2024-03-05T09:00:00Z");
+
+        assert!(schedule.is_due(None, now));
+        assert!(schedule.is_due(Some(at("2024-03-03T08:00:00Z")), now));
+        assert!(!schedule.is_due(Some(at("2024-03-05T00:00:00Z")), now));
+    }
+}
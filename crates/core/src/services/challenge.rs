@@ -0,0 +1,286 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::db::error::{DbError, DbResult};
+use crate::db::repos::{
+    BestAttemptRepository, ChallengeRepository, MasteryHistoryRepository, MasteryRepository, ProgressRepository,
+    UserRepository,
+};
+use crate::gamification::{
+    get_mastery_retake_multiplier, update_mastery, Difficulty, GamificationEvent, XpActivity, XpCalculator, XpSource,
+};
+use crate::hints;
+use crate::models::{
+    AttemptFlag, ChallengeAttempt, MasteryChangeReason, MasteryHistoryEntry, MasteryScore, NodeProgress, NodeStatus,
+};
+use crate::quests::QuestActivity;
+use crate::services::GamificationService;
+
+/// What recording a submission needs from the curriculum
+#[derive(Debug, Clone)]
+pub struct ChallengeSpec {
+    pub challenge_id: String,
+    pub difficulty: Difficulty,
+    pub skills: Vec<String>,
+    /// The node's estimated time, which a first pass much sooner than is
+    /// flagged for review
+    pub estimated_minutes: u32,
+}
+
+/// How the submission fared when its tests were run
+#[derive(Debug, Clone)]
+pub struct ChallengeRun {
+    pub success: bool,
+    pub tests_passed: u32,
+    pub tests_failed: u32,
+    pub tests_total: u32,
+    pub stdout: String,
+    pub stderr: String,
+    /// The runner's full result, kept with the attempt
+    pub verification_json: String,
+    /// Signals the caller noticed about the code, kept for review
+    pub flags: Vec<AttemptFlag>,
+}
+
+impl ChallengeRun {
+    pub fn passed(&self) -> bool {
+        self.success && self.tests_passed > 0
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChallengeOutcome {
+    pub attempt_id: String,
+    pub passed: bool,
+    pub attempt_number: i32,
+    pub xp_earned: i32,
+    /// Skills whose mastery changed, with the new score. Empty when the
+    /// tests never ran, e.g. on a compile error.
+    pub mastery_updates: HashMap<String, f64>,
+    /// Share of the completion XP taken for revealed hints
+    pub hint_penalty_percent: u32,
+}
+
+pub struct ChallengeService;
+
+impl ChallengeService {
+    /// Record a verified submission. The first passing attempt completes the
+    /// node and awards XP, less the penalty for hints revealed before it;
+    /// a pass that beats the best result so far earns only what its XP adds
+    /// to what was already awarded. Every attempt whose tests ran updates
+    /// mastery for the node's skills. A first pass that comes much sooner
+    /// after starting the node than it's expected to take is flagged, along
+    /// with `run.flags`, for an instructor to review; flags don't affect XP.
+    #[allow(clippy::too_many_arguments)]
+    pub fn submit(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        node_id: &str,
+        challenge: &ChallengeSpec,
+        code: &str,
+        run: &ChallengeRun,
+        event_multiplier: f64,
+    ) -> DbResult<(ChallengeOutcome, Vec<GamificationEvent>)> {
+        let progress = ProgressRepository::get(conn, user_id, curriculum_id, node_id)?;
+        let mut best = BestAttemptRepository::get_or_new(conn, user_id, curriculum_id, node_id, "")?;
+        let attempt_number = progress.as_ref().map(|p| p.attempts + 1).unwrap_or(1);
+        let already_completed = progress.as_ref().is_some_and(|p| p.status == NodeStatus::Completed);
+
+        let passed = run.passed();
+        let tests_ran = run.tests_total > 0;
+        let pass_pct = if tests_ran {
+            run.tests_passed as f64 / run.tests_total as f64 * 100.0
+        } else {
+            0.0
+        };
+
+        let user = UserRepository::get_by_id(conn, user_id)?
+            .ok_or_else(|| DbError::NotFound("User not found".to_string()))?;
+
+        // Only a pass that beats the best so far earns XP, and only the
+        // difference, so resubmitting a solved challenge can't farm it
+        let (xp_earned, hint_penalty_percent) = if passed && best.improves_verification(pass_pct) {
+            let xp = XpCalculator::new(XpActivity::Challenge, challenge.difficulty)
+                .with_accuracy(pass_pct)
+                .with_streak(user.current_streak as u32)
+                .with_attempt(attempt_number as usize)
+                .with_event_multiplier(event_multiplier)
+                .calculate();
+            let (xp, hint_penalty_percent) = hints::apply_penalty(conn, user_id, curriculum_id, node_id, xp)?;
+            (best.xp_delta(xp), hint_penalty_percent)
+        } else {
+            (0, 0)
+        };
+
+        // A compile error says nothing about the skills being tested
+        let mut mastery_updates = HashMap::new();
+        if tests_ran {
+            let performance = pass_pct / 100.0 * get_mastery_retake_multiplier(attempt_number as usize);
+            for skill_id in &challenge.skills {
+                let current_mastery = MasteryRepository::get(conn, user_id, curriculum_id, skill_id)?
+                    .map(|m| m.score)
+                    .unwrap_or(0.0);
+                let new_mastery = update_mastery(current_mastery, performance);
+
+                let mut mastery_score = MasteryScore::new(user_id.to_string(), skill_id.clone());
+                mastery_score.curriculum_id = curriculum_id.map(str::to_string);
+                mastery_score.score = new_mastery;
+                MasteryRepository::create_or_update(conn, &mastery_score)?;
+                MasteryHistoryRepository::record(
+                    conn,
+                    &MasteryHistoryEntry::from_change(&mastery_score, current_mastery, MasteryChangeReason::Challenge),
+                )?;
+                mastery_updates.insert(skill_id.clone(), new_mastery);
+            }
+        }
+
+        let mut progress = progress.unwrap_or_else(|| {
+            let mut progress = NodeProgress::new(user_id.to_string(), node_id.to_string());
+            progress.curriculum_id = curriculum_id.map(str::to_string);
+            progress
+        });
+        progress.attempts = attempt_number;
+        if passed {
+            progress.complete();
+        } else if !already_completed {
+            progress.fail();
+        }
+        ProgressRepository::create_or_update(conn, &progress)?;
+
+        let mut flags = run.flags.clone();
+        if passed && !already_completed {
+            let fast_solve = progress
+                .first_started_at
+                .and_then(|started| AttemptFlag::fast_solve(Utc::now() - started, challenge.estimated_minutes));
+            flags.extend(fast_solve);
+        }
+
+        let mut attempt = ChallengeAttempt::new(
+            user_id.to_string(),
+            challenge.challenge_id.clone(),
+            node_id.to_string(),
+            code,
+            run.tests_passed as i32,
+            run.tests_failed as i32,
+            Some(run.stdout.clone()).filter(|s| !s.is_empty()),
+            Some(run.stderr.clone()).filter(|s| !s.is_empty()),
+            xp_earned,
+        )
+        .with_verification(run.verification_json.clone())
+        .with_flags(flags);
+        attempt.curriculum_id = curriculum_id.map(str::to_string);
+        ChallengeRepository::create(conn, &attempt)?;
+        best.record_verification(&attempt.id, tests_ran.then_some(pass_pct), xp_earned);
+        BestAttemptRepository::save(conn, &best)?;
+
+        GamificationService::record_activity(conn, user_id, QuestActivity::XpEarned(xp_earned))?;
+
+        let events = GamificationService::award_xp(conn, user_id, XpSource::Challenge, xp_earned)?.events;
+
+        Ok((
+            ChallengeOutcome {
+                attempt_id: attempt.id,
+                passed,
+                attempt_number,
+                xp_earned,
+                mastery_updates,
+                hint_penalty_percent,
+            },
+            events,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::models::User;
+
+    fn spec() -> ChallengeSpec {
+        ChallengeSpec {
+            challenge_id: "challenge-1".to_string(),
+            difficulty: Difficulty::Easy,
+            skills: vec!["ownership".to_string()],
+            estimated_minutes: 30,
+        }
+    }
+
+    fn run(tests_passed: u32, tests_total: u32) -> ChallengeRun {
+        ChallengeRun {
+            success: tests_passed == tests_total,
+            tests_passed,
+            tests_failed: tests_total - tests_passed,
+            tests_total,
+            stdout: String::new(),
+            stderr: String::new(),
+            verification_json: "{}".to_string(),
+            flags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_only_the_first_pass_earns_xp() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let (failed, _) =
+            ChallengeService::submit(conn, "test-user", None, "node-1", &spec(), "fn a() {}", &run(1, 3), 1.0)
+                .unwrap();
+        assert!(!failed.passed);
+        assert_eq!(failed.xp_earned, 0);
+        assert_eq!(failed.mastery_updates.len(), 1);
+
+        let (passed, events) =
+            ChallengeService::submit(conn, "test-user", None, "node-1", &spec(), "fn b() {}", &run(3, 3), 1.0)
+                .unwrap();
+        assert!(passed.passed);
+        assert!(passed.xp_earned > 0);
+        assert!(!events.is_empty());
+
+        let (again, _) =
+            ChallengeService::submit(conn, "test-user", None, "node-1", &spec(), "fn b() {}", &run(3, 3), 1.0)
+                .unwrap();
+        assert_eq!(again.xp_earned, 0);
+
+        let progress = ProgressRepository::get(conn, "test-user", None, "node-1").unwrap().unwrap();
+        assert_eq!(progress.status, NodeStatus::Completed);
+        let best = BestAttemptRepository::get(conn, "test-user", None, "node-1", "").unwrap().unwrap();
+        assert_eq!(best.best_verification_score, Some(100.0));
+        assert_eq!(best.best_attempt_id, Some(passed.attempt_id));
+        assert_eq!((best.xp_awarded, best.attempts), (passed.xp_earned, 3));
+        assert_eq!(ChallengeRepository::get_for_node(conn, "test-user", None, "node-1").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_quick_first_pass_is_flagged_without_blocking() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+        let mut progress = NodeProgress::new("test-user".to_string(), "node-1".to_string());
+        progress.start();
+        ProgressRepository::create_or_update(conn, &progress).unwrap();
+
+        let mut pasted = run(3, 3);
+        pasted.flags.push(AttemptFlag::MatchesReference);
+        let (outcome, _) =
+            ChallengeService::submit(conn, "test-user", None, "node-1", &spec(), "fn b() {}", &pasted, 1.0).unwrap();
+        assert!(outcome.passed);
+        assert!(outcome.xp_earned > 0);
+
+        let attempt = ChallengeRepository::get(conn, &outcome.attempt_id).unwrap().unwrap();
+        assert_eq!(attempt.flags.len(), 2);
+        assert_eq!(attempt.flags[0], AttemptFlag::MatchesReference);
+        assert!(matches!(attempt.flags[1], AttemptFlag::FastSolve { expected_minutes: 30, .. }));
+
+        // Only the first pass is timed
+        let (again, _) =
+            ChallengeService::submit(conn, "test-user", None, "node-1", &spec(), "fn b() {}", &run(3, 3), 1.0)
+                .unwrap();
+        assert!(ChallengeRepository::get(conn, &again.attempt_id).unwrap().unwrap().flags.is_empty());
+    }
+}
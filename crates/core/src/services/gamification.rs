@@ -0,0 +1,226 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::badges::{
+    badge_value, check_badge_unlocks, check_tier_upgrades, get_all_badge_definitions, UserStats,
+};
+use crate::db::error::{DbError, DbResult};
+use crate::db::repos::{
+    BadgeRepository, ChallengeRepository, MasteryRepository, ProgressRepository, QuestRepository, QuizRepository,
+    UserRepository,
+};
+use crate::gamification::{calculate_level, xp_award_events, GamificationEvent, XpSource};
+use crate::models::{BadgeDefinition, BadgeProgress, BadgeTier, NodeStatus, Quest, QuestPeriod, User};
+use crate::quests::{generate_quests, record_quest_activity, QuestActivity};
+
+/// XP added to a user's total and where it left them
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct XpAward {
+    pub xp_earned: i32,
+    pub previous_total_xp: i32,
+    pub new_total_xp: i32,
+    pub previous_level: u32,
+    pub new_level: u32,
+    /// XP, level-up and badge events to show the learner
+    pub events: Vec<GamificationEvent>,
+}
+
+impl XpAward {
+    pub fn leveled_up(&self) -> bool {
+        self.new_level > self.previous_level
+    }
+}
+
+pub struct GamificationService;
+
+impl GamificationService {
+    /// Add XP to a user's total and bring their level up to date, without
+    /// raising any events
+    pub fn add_xp(conn: &Connection, user_id: &str, xp: i32) -> DbResult<XpAward> {
+        let user = UserRepository::get_by_id(conn, user_id)?
+            .ok_or_else(|| DbError::NotFound("User not found".to_string()))?;
+
+        UserRepository::update_xp(conn, user_id, xp)?;
+        let new_total_xp = user.total_xp + xp;
+        let new_level = calculate_level(new_total_xp);
+        UserRepository::update_level(conn, user_id, new_level as i32)?;
+
+        Ok(XpAward {
+            xp_earned: xp,
+            previous_total_xp: user.total_xp,
+            new_total_xp,
+            previous_level: user.current_level.max(0) as u32,
+            new_level,
+            events: Vec::new(),
+        })
+    }
+
+    /// Award XP earned from `source`, then unlock any badges it qualifies
+    /// the user for
+    pub fn award_xp(conn: &Connection, user_id: &str, source: XpSource, xp: i32) -> DbResult<XpAward> {
+        let mut award = Self::add_xp(conn, user_id, xp)?;
+        award.events = xp_award_events(source, xp, award.previous_total_xp);
+        award.events.extend(Self::unlock_badge_events(conn, user_id)?);
+        Ok(award)
+    }
+
+    /// Make sure this period's generated quests exist and return the active ones
+    pub fn active_quests(conn: &Connection, user_id: &str) -> DbResult<Vec<Quest>> {
+        let now = Utc::now();
+        for period in [QuestPeriod::Daily, QuestPeriod::Weekly] {
+            for quest in generate_quests(user_id, period, now) {
+                QuestRepository::create_if_missing(conn, &quest)?;
+            }
+        }
+        QuestRepository::get_active(conn, user_id, now)
+    }
+
+    /// Advance active quests for a learning activity.
+    /// Returns the IDs of quests completed by this activity.
+    pub fn record_activity(conn: &Connection, user_id: &str, activity: QuestActivity) -> DbResult<Vec<String>> {
+        let mut quests = Self::active_quests(conn, user_id)?;
+        let completed = record_quest_activity(&mut quests, activity, Utc::now());
+
+        for quest in &quests {
+            QuestRepository::update(conn, quest)?;
+        }
+
+        Ok(completed)
+    }
+
+    /// Claim the XP reward of a completed quest
+    pub fn claim_quest(conn: &Connection, user_id: &str, quest_id: &str) -> DbResult<XpAward> {
+        let mut quest = QuestRepository::get(conn, user_id, quest_id)?
+            .ok_or_else(|| DbError::NotFound(format!("Quest not found: {}", quest_id)))?;
+
+        let xp_earned = quest
+            .claim()
+            .ok_or_else(|| DbError::InvalidData(format!("Quest cannot be claimed: {}", quest_id)))?;
+        QuestRepository::update(conn, &quest)?;
+
+        Self::award_xp(conn, user_id, XpSource::Quest, xp_earned)
+    }
+
+    /// Everything badges are judged on
+    pub fn user_stats(conn: &Connection, user_id: &str) -> DbResult<UserStats> {
+        let user = UserRepository::get_by_id(conn, user_id)?.unwrap_or_else(|| User::new(user_id.to_string()));
+
+        let all_progress = ProgressRepository::get_all_for_user(conn, user_id)?;
+        let completed_lectures = all_progress
+            .iter()
+            .filter(|p| p.status == NodeStatus::Completed && p.node_id.contains("lecture"))
+            .count() as u32;
+        let total_completions = all_progress.iter().filter(|p| p.status == NodeStatus::Completed).count() as u32;
+
+        let quiz_attempts = QuizRepository::get_all_for_user(conn, user_id)?;
+        let completed_quizzes = quiz_attempts.len() as u32;
+        let perfect_quiz_count = quiz_attempts.iter().filter(|q| q.score_percentage >= 100).count() as u32;
+
+        let masteries = MasteryRepository::get_all_for_user(conn, user_id)?;
+        let max_mastery = masteries.iter().map(|m| m.score).fold(0.0_f64, f64::max);
+
+        Ok(UserStats {
+            streak_days: user.current_streak as u32,
+            level: user.current_level as u32,
+            total_xp: user.total_xp,
+            completed_lectures,
+            completed_quizzes,
+            completed_challenges: ChallengeRepository::count_passed(conn, user_id)?,
+            total_completions,
+            perfect_quiz_count,
+            max_mastery_score: max_mastery,
+        })
+    }
+
+    /// Persist newly unlocked badges and tier upgrades for a user.
+    /// Returns each unlocked badge with the tier it reached, if tiered.
+    pub fn unlock_badges(conn: &Connection, user_id: &str) -> DbResult<Vec<(BadgeDefinition, Option<BadgeTier>)>> {
+        let stats = Self::user_stats(conn, user_id)?;
+        let current_progress = BadgeRepository::get_all_for_user(conn, user_id)?;
+
+        let mut newly_unlocked = Vec::new();
+        for badge_id in check_badge_unlocks(&stats, &current_progress) {
+            if let Some(def) = get_all_badge_definitions().into_iter().find(|d| d.id == badge_id) {
+                let mut progress = BadgeProgress::new(user_id.to_string(), badge_id.clone());
+                if def.is_tiered() {
+                    progress.update_tiered_progress(badge_value(&def, &stats), &def);
+                } else {
+                    progress.update_progress(def.threshold, def.threshold);
+                }
+
+                BadgeRepository::create_or_update(conn, &progress)?;
+
+                newly_unlocked.push((def, progress.tier));
+            }
+        }
+
+        // Tier upgrades on already earned badges are reported like new unlocks
+        for (badge_id, tier) in check_tier_upgrades(&stats, &current_progress) {
+            if let Some(def) = get_all_badge_definitions().into_iter().find(|d| d.id == badge_id) {
+                if let Some(mut progress) = current_progress.iter().find(|p| p.badge_id == badge_id).cloned() {
+                    progress.current_value = badge_value(&def, &stats);
+                    progress.tier = Some(tier);
+                    BadgeRepository::create_or_update(conn, &progress)?;
+                    newly_unlocked.push((def, Some(tier)));
+                }
+            }
+        }
+
+        Ok(newly_unlocked)
+    }
+
+    /// Unlock badges and describe them as gamification events
+    pub fn unlock_badge_events(conn: &Connection, user_id: &str) -> DbResult<Vec<GamificationEvent>> {
+        Ok(Self::unlock_badges(conn, user_id)?
+            .iter()
+            .map(|(def, tier)| GamificationEvent::badge_unlocked(def, *tier))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+
+    #[test]
+    fn test_award_xp_levels_up_and_reports_it() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let quiet = GamificationService::add_xp(conn, "test-user", 10).unwrap();
+        assert_eq!((quiet.previous_total_xp, quiet.new_total_xp), (0, 10));
+        assert!(quiet.events.is_empty());
+
+        let award = GamificationService::award_xp(conn, "test-user", XpSource::Quiz, 500).unwrap();
+        assert_eq!(award.new_total_xp, 510);
+        assert!(award.leveled_up());
+        assert!(award.events.iter().any(|e| matches!(e, GamificationEvent::LevelUp { .. })));
+
+        let user = UserRepository::get_by_id(conn, "test-user").unwrap().unwrap();
+        assert_eq!(user.total_xp, 510);
+        assert_eq!(user.current_level as u32, award.new_level);
+    }
+
+    #[test]
+    fn test_quests_are_generated_once_and_claimed_once() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let quests = GamificationService::active_quests(conn, "test-user").unwrap();
+        assert!(!quests.is_empty());
+        assert_eq!(GamificationService::active_quests(conn, "test-user").unwrap().len(), quests.len());
+
+        let mut quest = quests[0].clone();
+        assert!(GamificationService::claim_quest(conn, "test-user", &quest.id).is_err());
+
+        quest.record_progress(quest.target, Utc::now());
+        QuestRepository::update(conn, &quest).unwrap();
+        let award = GamificationService::claim_quest(conn, "test-user", &quest.id).unwrap();
+        assert_eq!(award.xp_earned, quest.xp_reward);
+        assert!(GamificationService::claim_quest(conn, "test-user", &quest.id).is_err());
+    }
+}
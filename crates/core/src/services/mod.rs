@@ -0,0 +1,38 @@
+//! Application services
+//!
+//! The flows behind the app's commands, written against a connection so any
+//! front end (the desktop app, a CLI, a server) runs exactly the same steps.
+//! `ProgressService` tracks nodes and completes lectures, `QuizService`
+//! grades quizzes, `ChallengeService` records verified challenge
+//! submissions, `SessionService` runs study sessions and
+//! `GamificationService` awards XP and levels, advances quests and unlocks
+//! badges. Front ends work out who the user is and which curriculum is
+//! active, call a service, and deliver the `GamificationEvent`s it returns
+//! however they notify the learner.
+
+pub mod challenge;
+pub mod gamification;
+pub mod progress;
+pub mod quiz;
+pub mod session;
+
+pub use challenge::{ChallengeOutcome, ChallengeRun, ChallengeService, ChallengeSpec};
+pub use gamification::{GamificationService, XpAward};
+pub use progress::{LectureCompletion, ProgressService};
+pub use quiz::{QuestionFeedback, QuizResult, QuizService, QuizStart, QuizSubmission};
+pub use session::{CompletedActivitySummary, PlannedActivity, SessionPlan, SessionService, SessionSummary};
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ServiceError {
+    #[error(transparent)]
+    Db(#[from] crate::db::error::DbError),
+
+    /// The request can't be carried out as things stand, e.g. a timed quiz
+    /// submitted without being started
+    #[error("{0}")]
+    InvalidState(String),
+}
+
+pub type ServiceResult<T> = Result<T, ServiceError>;
@@ -0,0 +1,309 @@
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+
+use crate::db::error::{DbError, DbResult};
+use crate::db::repos::{NodeTimeRepository, ProgressRepository, SessionRepository, UserRepository};
+use crate::gamification::{Difficulty, XpActivity, XpCalculator, XpSource};
+use crate::models::{ComprehensionCheck, NodeProgress, NodeStatus, NodeTime, TimeHistogram};
+use crate::quests::QuestActivity;
+use crate::services::{GamificationService, XpAward};
+
+/// A lecture the learner has finished reading
+#[derive(Debug, Clone)]
+pub struct LectureCompletion {
+    pub lecture_id: String,
+    pub time_spent_ms: i64,
+    pub difficulty: Difficulty,
+    /// How its comprehension check went; `None` if it has none
+    pub comprehension_check: Option<ComprehensionCheck>,
+}
+
+pub struct ProgressService;
+
+impl ProgressService {
+    pub fn get(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        node_id: &str,
+    ) -> DbResult<Option<NodeProgress>> {
+        ProgressRepository::get(conn, user_id, curriculum_id, node_id)
+    }
+
+    pub fn get_all(conn: &Connection, user_id: &str, curriculum_id: Option<&str>) -> DbResult<Vec<NodeProgress>> {
+        ProgressRepository::get_all_in_curriculum(conn, user_id, curriculum_id)
+    }
+
+    /// Mark a node as started
+    pub fn start_node(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        node_id: &str,
+    ) -> DbResult<NodeProgress> {
+        let mut progress = NodeProgress::new(user_id.to_string(), node_id.to_string());
+        progress.curriculum_id = curriculum_id.map(str::to_string);
+        progress.start();
+        ProgressRepository::create_or_update(conn, &progress)?;
+        Ok(progress)
+    }
+
+    /// Mark a node as completed, without awarding anything for it
+    pub fn mark_complete(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        node_id: &str,
+    ) -> DbResult<NodeProgress> {
+        ProgressRepository::mark_completed(conn, user_id, curriculum_id, node_id)?;
+        ProgressRepository::get(conn, user_id, curriculum_id, node_id)?
+            .ok_or_else(|| DbError::NotFound("Progress not found".to_string()))
+    }
+
+    /// Add study time to a started node. Nodes timed by heartbeats keep
+    /// the time those counted instead, which leaves out idle gaps.
+    pub fn add_time(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        node_id: &str,
+        time_spent_ms: i64,
+    ) -> DbResult<NodeProgress> {
+        let mut progress = ProgressRepository::get(conn, user_id, curriculum_id, node_id)?
+            .ok_or_else(|| DbError::NotFound("Progress not found".to_string()))?;
+
+        if !Self::timed_by_heartbeats(conn, user_id, curriculum_id, node_id)? {
+            progress.add_time((time_spent_ms / 60000) as i32);
+            ProgressRepository::create_or_update(conn, &progress)?;
+        }
+        Ok(progress)
+    }
+
+    /// Record a heartbeat from the frontend while the learner is active on a
+    /// node. The time since their previous heartbeat counts towards the
+    /// node, their open session and the node's progress, unless it's over
+    /// the idle threshold or the previous heartbeat was on another node.
+    pub fn record_heartbeat(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        node_id: &str,
+        now: DateTime<Utc>,
+    ) -> DbResult<NodeTime> {
+        let continues = NodeTimeRepository::get_latest(conn, user_id)?
+            .is_some_and(|latest| latest.curriculum_id.as_deref() == curriculum_id && latest.node_id == node_id);
+        let mut time = NodeTimeRepository::get(conn, user_id, curriculum_id, node_id)?.unwrap_or_else(|| {
+            NodeTime::new(user_id.to_string(), curriculum_id.map(str::to_string), node_id.to_string())
+        });
+
+        let minutes_before = time.active_minutes();
+        let counted = time.record_heartbeat(now, continues);
+        NodeTimeRepository::save(conn, &time)?;
+
+        if counted > 0 {
+            SessionRepository::add_learning_time(conn, user_id, counted)?;
+            let minutes = time.active_minutes() - minutes_before;
+            if minutes > 0 {
+                ProgressRepository::increment_time(conn, user_id, curriculum_id, node_id, minutes)?;
+            }
+        }
+        Ok(time)
+    }
+
+    /// How much active time learners took on each node they completed, in
+    /// buckets of `bucket_minutes`
+    pub fn time_histograms(
+        conn: &Connection,
+        curriculum_id: Option<&str>,
+        bucket_minutes: u32,
+    ) -> DbResult<Vec<TimeHistogram>> {
+        let mut histograms: Vec<TimeHistogram> = NodeTimeRepository::get_completed_seconds(conn, curriculum_id)?
+            .into_iter()
+            .map(|(node_id, seconds)| TimeHistogram::from_seconds(node_id, &seconds, bucket_minutes))
+            .collect();
+        histograms.sort_by(|a, b| a.node_id.cmp(&b.node_id));
+        Ok(histograms)
+    }
+
+    fn timed_by_heartbeats(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        node_id: &str,
+    ) -> DbResult<bool> {
+        Ok(NodeTimeRepository::get(conn, user_id, curriculum_id, node_id)?.is_some())
+    }
+
+    /// Complete a lecture and award its XP, scaled by the learner's streak
+    /// and any running XP event. The reported time is only added if the
+    /// lecture wasn't timed by heartbeats. XP and quest progress only come
+    /// the first time; completing it again just records the time and check.
+    pub fn complete_lecture(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        lecture: &LectureCompletion,
+        event_multiplier: f64,
+    ) -> DbResult<XpAward> {
+        let user = UserRepository::get_by_id(conn, user_id)?
+            .ok_or_else(|| DbError::NotFound("User not found".to_string()))?;
+
+        let xp_earned = XpCalculator::new(XpActivity::Lecture, lecture.difficulty)
+            .with_streak(user.current_streak as u32)
+            .with_event_multiplier(event_multiplier)
+            .calculate();
+
+        let mut progress = ProgressRepository::get(conn, user_id, curriculum_id, &lecture.lecture_id)?
+            .unwrap_or_else(|| {
+                let mut progress = NodeProgress::new(user_id.to_string(), lecture.lecture_id.clone());
+                progress.curriculum_id = curriculum_id.map(str::to_string);
+                progress
+            });
+        let completed_before = progress.status == NodeStatus::Completed;
+
+        if !Self::timed_by_heartbeats(conn, user_id, curriculum_id, &lecture.lecture_id)? {
+            progress.add_time((lecture.time_spent_ms / 60000) as i32);
+        }
+        // A check passed before stays passed if the lecture is completed again
+        if progress.comprehension_check != Some(ComprehensionCheck::Passed) {
+            progress.comprehension_check = lecture.comprehension_check;
+        }
+        progress.complete();
+        ProgressRepository::create_or_update(conn, &progress)?;
+
+        if completed_before {
+            return GamificationService::add_xp(conn, user_id, 0);
+        }
+        GamificationService::record_activity(conn, user_id, QuestActivity::LectureCompleted)?;
+        GamificationService::record_activity(conn, user_id, QuestActivity::XpEarned(xp_earned))?;
+
+        GamificationService::award_xp(conn, user_id, XpSource::Lecture, xp_earned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::models::{NodeStatus, User};
+    use chrono::Duration;
+
+    #[test]
+    fn test_complete_lecture_records_progress_and_xp() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        ProgressService::start_node(conn, "test-user", Some("rust"), "lecture-1").unwrap();
+        let lecture = LectureCompletion {
+            lecture_id: "lecture-1".to_string(),
+            time_spent_ms: 5 * 60_000,
+            difficulty: Difficulty::Easy,
+            comprehension_check: None,
+        };
+        let award = ProgressService::complete_lecture(conn, "test-user", Some("rust"), &lecture, 1.0).unwrap();
+        assert!(award.xp_earned > 0);
+        assert_eq!(award.new_total_xp, award.xp_earned);
+
+        let progress = ProgressService::get(conn, "test-user", Some("rust"), "lecture-1").unwrap().unwrap();
+        assert_eq!(progress.status, NodeStatus::Completed);
+        assert_eq!(progress.time_spent_mins, 5);
+        assert!(ProgressService::get(conn, "test-user", None, "lecture-1").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_passed_comprehension_check_is_kept() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let complete = |comprehension_check| {
+            let lecture = LectureCompletion {
+                lecture_id: "lecture-1".to_string(),
+                time_spent_ms: 60_000,
+                difficulty: Difficulty::Easy,
+                comprehension_check,
+            };
+            ProgressService::complete_lecture(conn, "test-user", None, &lecture, 1.0).unwrap();
+            ProgressService::get(conn, "test-user", None, "lecture-1").unwrap().unwrap().comprehension_check
+        };
+        assert_eq!(complete(Some(ComprehensionCheck::Skipped)), Some(ComprehensionCheck::Skipped));
+        assert_eq!(complete(Some(ComprehensionCheck::Passed)), Some(ComprehensionCheck::Passed));
+        assert_eq!(complete(Some(ComprehensionCheck::Skipped)), Some(ComprehensionCheck::Passed));
+    }
+
+    #[test]
+    fn test_completing_a_lecture_again_awards_nothing() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let lecture = LectureCompletion {
+            lecture_id: "lecture-1".to_string(),
+            time_spent_ms: 3 * 60_000,
+            difficulty: Difficulty::Easy,
+            comprehension_check: Some(ComprehensionCheck::Skipped),
+        };
+        let first = ProgressService::complete_lecture(conn, "test-user", None, &lecture, 1.0).unwrap();
+        assert!(first.xp_earned > 0);
+        let quests = GamificationService::active_quests(conn, "test-user").unwrap();
+
+        let again = LectureCompletion { comprehension_check: Some(ComprehensionCheck::Passed), ..lecture };
+        let second = ProgressService::complete_lecture(conn, "test-user", None, &again, 1.0).unwrap();
+        assert_eq!(second.xp_earned, 0);
+        assert!(second.events.is_empty());
+        assert_eq!(second.new_total_xp, first.new_total_xp);
+        let unchanged = GamificationService::active_quests(conn, "test-user").unwrap();
+        assert_eq!(
+            unchanged.iter().map(|q| q.progress).collect::<Vec<_>>(),
+            quests.iter().map(|q| q.progress).collect::<Vec<_>>()
+        );
+
+        // Time and the check are still recorded
+        let progress = ProgressService::get(conn, "test-user", None, "lecture-1").unwrap().unwrap();
+        assert_eq!(progress.time_spent_mins, 6);
+        assert_eq!(progress.comprehension_check, Some(ComprehensionCheck::Passed));
+    }
+
+    #[test]
+    fn test_heartbeats_time_nodes_without_idle_gaps() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+        let session = crate::models::SessionHistory::new("test-user".to_string());
+        SessionRepository::create(conn, &session).unwrap();
+        ProgressService::start_node(conn, "test-user", Some("rust"), "lecture-1").unwrap();
+
+        let start = Utc::now();
+        let beat = |node_id: &str, seconds: i64| {
+            ProgressService::record_heartbeat(conn, "test-user", Some("rust"), node_id, start + Duration::seconds(seconds))
+                .unwrap()
+        };
+        for seconds in (0..=180).step_by(15) {
+            beat("lecture-1", seconds);
+        }
+        // Ten minutes away, then a minute on another node and back
+        beat("lecture-1", 780);
+        beat("quiz-1", 795);
+        let time = beat("lecture-1", 855);
+        assert_eq!(time.active_seconds, 180);
+        assert_eq!(time.idle_seconds, 600);
+
+        // Reported time is left out once heartbeats time the lecture
+        let lecture = LectureCompletion {
+            lecture_id: "lecture-1".to_string(),
+            time_spent_ms: 20 * 60_000,
+            difficulty: Difficulty::Easy,
+            comprehension_check: None,
+        };
+        ProgressService::complete_lecture(conn, "test-user", Some("rust"), &lecture, 1.0).unwrap();
+        let progress = ProgressService::get(conn, "test-user", Some("rust"), "lecture-1").unwrap().unwrap();
+        assert_eq!(progress.time_spent_mins, 3);
+        let session = SessionRepository::get_by_id(conn, &session.id).unwrap().unwrap();
+        assert_eq!(session.learning_seconds, 180);
+
+        let histograms = ProgressService::time_histograms(conn, Some("rust"), 5).unwrap();
+        assert_eq!(histograms.len(), 1);
+        assert_eq!((histograms[0].counts.clone(), histograms[0].median_minutes), (vec![1], 3.0));
+    }
+}
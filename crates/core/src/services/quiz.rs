@@ -0,0 +1,445 @@
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::db::error::DbError;
+use crate::db::repos::{MasteryHistoryRepository, MasteryRepository, ProgressRepository, QuizRepository, UserRepository};
+use crate::gamification::{
+    assess_quiz_timing, get_mastery_retake_multiplier, grade_quiz, quiz_shuffle_seed, update_mastery, Difficulty,
+    GamificationEvent, OptionShuffle, QuizTiming, XpActivity, XpCalculator, XpSource,
+};
+use crate::models::quiz::{Quiz, QuizAttempt};
+use crate::models::{MasteryChangeReason, MasteryHistoryEntry, MasteryScore, NodeProgress};
+use crate::quests::QuestActivity;
+use crate::services::{GamificationService, ServiceError, ServiceResult};
+
+/// The clock for a timed quiz, for the countdown
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct QuizStart {
+    pub quiz_id: String,
+    pub started_at: DateTime<Utc>,
+    pub time_limit_seconds: Option<i32>,
+    pub deadline: Option<DateTime<Utc>>,
+}
+
+/// A learner's answers to a quiz, by question id
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuizSubmission {
+    pub answers: HashMap<String, String>,
+    pub time_spent_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct QuizResult {
+    pub attempt_id: String,
+    pub score: i32,
+    pub total: i32,
+    pub score_percentage: f64,
+    pub passed: bool,
+    pub xp_earned: i32,
+    pub attempt_number: i32,
+    pub mastery_updates: HashMap<String, f64>,
+    pub feedback: Vec<QuestionFeedback>,
+    /// Seconds past a timed quiz's limit, 0 if on time or untimed
+    pub late_seconds: i64,
+    /// Points taken off `score_percentage` for lateness
+    pub penalty_percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct QuestionFeedback {
+    pub question_id: String,
+    pub user_answer: Option<String>,
+    pub correct_answer: String,
+    pub is_correct: bool,
+    pub explanation: String,
+}
+
+pub fn generate_feedback(quiz: &Quiz, answers: &HashMap<String, String>) -> Vec<QuestionFeedback> {
+    quiz.questions
+        .iter()
+        .map(|question| {
+            let user_answer = answers.get(&question.id).cloned();
+            let is_correct = user_answer.as_ref().map(|ans| ans == &question.correct_answer).unwrap_or(false);
+
+            QuestionFeedback {
+                question_id: question.id.clone(),
+                user_answer,
+                correct_answer: question.correct_answer.clone(),
+                is_correct,
+                explanation: question.explanation.clone(),
+            }
+        })
+        .collect()
+}
+
+/// A timed quiz's start and how its submission stands against the limit
+#[derive(Clone, Copy)]
+struct TimedSubmission {
+    started_at: DateTime<Utc>,
+    timing: QuizTiming,
+}
+
+pub struct QuizService;
+
+impl QuizService {
+    /// Start a quiz. For a timed quiz this starts the clock the submission is
+    /// judged against. A clock that's already running keeps running from
+    /// when it started; only one whose submission would be refused as too
+    /// late starts over.
+    pub fn start(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        quiz: &Quiz,
+        now: DateTime<Utc>,
+    ) -> ServiceResult<QuizStart> {
+        let mut started_at = now;
+        if let Some(limit) = quiz.time_limit_seconds {
+            let running = QuizRepository::get_start(conn, user_id, curriculum_id, &quiz.id)?;
+            if let Some(running) = running {
+                if let QuizTiming::Rejected { .. } = assess_quiz_timing(running, now, limit, quiz.late_policy) {
+                    QuizRepository::take_start(conn, user_id, curriculum_id, &quiz.id)?;
+                }
+            }
+            started_at = QuizRepository::record_start(conn, user_id, curriculum_id, &quiz.id, now)?;
+        }
+
+        Ok(QuizStart {
+            quiz_id: quiz.id.clone(),
+            started_at,
+            time_limit_seconds: quiz.time_limit_seconds,
+            deadline: quiz.time_limit_seconds.map(|limit| started_at + Duration::seconds(limit as i64)),
+        })
+    }
+
+    /// Number the user's next attempt at a quiz will have
+    pub fn next_attempt_number(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        quiz_id: &str,
+    ) -> ServiceResult<i32> {
+        let progress = ProgressRepository::get(conn, user_id, curriculum_id, quiz_id)?;
+        Ok(progress.map(|p| p.attempts + 1).unwrap_or(1))
+    }
+
+    /// The option order of the user's next attempt at a quiz. It stays the
+    /// same until that attempt is submitted.
+    pub fn option_shuffle(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        quiz: &Quiz,
+    ) -> ServiceResult<OptionShuffle> {
+        let attempt_number = Self::next_attempt_number(conn, user_id, curriculum_id, &quiz.id)?;
+        Ok(OptionShuffle::new(quiz, quiz_shuffle_seed(user_id, &quiz.id, attempt_number)))
+    }
+
+    /// The quiz to answer, with its options in the order of the user's next
+    /// attempt. Serving a timed quiz starts its clock if it isn't running, so
+    /// its questions can't be read before the time counts.
+    pub fn serve(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        quiz: &Quiz,
+        served_at: DateTime<Utc>,
+    ) -> ServiceResult<Quiz> {
+        Self::start(conn, user_id, curriculum_id, quiz, served_at)?;
+        Ok(Self::option_shuffle(conn, user_id, curriculum_id, quiz)?.apply(quiz))
+    }
+
+    /// Like `submit`, for answers given against the options as
+    /// `option_shuffle` shows them. They're graded and stored against the
+    /// quiz's own options, and the feedback is put back in the shown order.
+    pub fn submit_shown(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        quiz: &Quiz,
+        submission: &QuizSubmission,
+        event_multiplier: f64,
+        submitted_at: DateTime<Utc>,
+    ) -> ServiceResult<(QuizResult, Vec<GamificationEvent>)> {
+        let shuffle = Self::option_shuffle(conn, user_id, curriculum_id, quiz)?;
+        let stored = QuizSubmission {
+            answers: shuffle.stored_answers(&submission.answers),
+            time_spent_ms: submission.time_spent_ms,
+        };
+        let (mut result, events) =
+            Self::submit(conn, user_id, curriculum_id, quiz, &stored, event_multiplier, submitted_at)?;
+        for feedback in &mut result.feedback {
+            feedback.user_answer = feedback.user_answer.as_ref().map(|answer| shuffle.to_shown(&feedback.question_id, answer));
+            feedback.correct_answer = shuffle.to_shown(&feedback.question_id, &feedback.correct_answer);
+        }
+        Ok((result, events))
+    }
+
+    /// How a submission stands against the quiz's time limit, or why it
+    /// can't be accepted. `None` for untimed quizzes.
+    fn timed_submission(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        quiz: &Quiz,
+        submitted_at: DateTime<Utc>,
+    ) -> ServiceResult<Option<TimedSubmission>> {
+        let Some(limit) = quiz.time_limit_seconds else {
+            return Ok(None);
+        };
+        let Some(started_at) = QuizRepository::take_start(conn, user_id, curriculum_id, &quiz.id)? else {
+            return Err(ServiceError::InvalidState("Start the quiz before submitting it".to_string()));
+        };
+
+        match assess_quiz_timing(started_at, submitted_at, limit, quiz.late_policy) {
+            QuizTiming::Rejected { late_seconds } => Err(ServiceError::InvalidState(format!(
+                "Time ran out {} seconds before this was submitted",
+                late_seconds
+            ))),
+            timing => Ok(Some(TimedSubmission { started_at, timing })),
+        }
+    }
+
+    /// Grade a submission and record everything that follows from it:
+    /// mastery, progress, the attempt itself, XP, quests and badges. Timed
+    /// quizzes are judged on `submitted_at`, the server's clock, never the
+    /// learner's.
+    pub fn submit(
+        conn: &Connection,
+        user_id: &str,
+        curriculum_id: Option<&str>,
+        quiz: &Quiz,
+        submission: &QuizSubmission,
+        event_multiplier: f64,
+        submitted_at: DateTime<Utc>,
+    ) -> ServiceResult<(QuizResult, Vec<GamificationEvent>)> {
+        let timing = Self::timed_submission(conn, user_id, curriculum_id, quiz, submitted_at)?;
+        let late_seconds = timing.map(|t| t.timing.late_seconds()).unwrap_or(0);
+        let penalty_percent = timing.map(|t| t.timing.penalty_percent()).unwrap_or(0.0);
+
+        // Get attempt count
+        let progress = ProgressRepository::get(conn, user_id, curriculum_id, &quiz.id)?;
+        let attempt_number = progress.as_ref().map(|p| p.attempts + 1).unwrap_or(1);
+
+        // Grade quiz
+        let (score, _correct_count, _total) = grade_quiz(quiz, &submission.answers);
+        let total_points: i32 = quiz.questions.iter().map(|q| q.points).sum();
+        let score_percentage = ((score as f64 / total_points as f64) * 100.0 - penalty_percent).max(0.0);
+
+        let difficulty = quiz.difficulty.parse().unwrap_or(Difficulty::Easy);
+
+        // Get user's current streak
+        let user = UserRepository::get_by_id(conn, user_id)?
+            .ok_or_else(|| DbError::NotFound("User not found".to_string()))?;
+
+        // Calculate XP with retake penalty
+        let xp_earned = XpCalculator::new(XpActivity::Quiz, difficulty)
+            .with_accuracy(score_percentage)
+            .with_streak(user.current_streak as u32)
+            .with_attempt(attempt_number as usize)
+            .with_event_multiplier(event_multiplier)
+            .calculate();
+
+        // Update mastery for all skills
+        let mut mastery_updates = HashMap::new();
+        for skill_id in &quiz.skills {
+            let current_mastery = MasteryRepository::get(conn, user_id, curriculum_id, skill_id)?
+                .map(|m| m.score)
+                .unwrap_or(0.0);
+
+            let performance_multiplier = get_mastery_retake_multiplier(attempt_number as usize);
+            let effective_performance = (score_percentage / 100.0) * performance_multiplier;
+            let new_mastery = update_mastery(current_mastery, effective_performance);
+
+            let mut mastery_score = MasteryScore::new(user_id.to_string(), skill_id.clone());
+            mastery_score.curriculum_id = curriculum_id.map(str::to_string);
+            mastery_score.score = new_mastery;
+            MasteryRepository::create_or_update(conn, &mastery_score)?;
+            MasteryHistoryRepository::record(
+                conn,
+                &MasteryHistoryEntry::from_change(&mastery_score, current_mastery, MasteryChangeReason::Quiz),
+            )?;
+            mastery_updates.insert(skill_id.clone(), new_mastery);
+        }
+
+        // Update progress
+        let mut progress = progress.unwrap_or_else(|| {
+            let mut progress = NodeProgress::new(user_id.to_string(), quiz.id.clone());
+            progress.curriculum_id = curriculum_id.map(str::to_string);
+            progress
+        });
+        progress.add_time((submission.time_spent_ms / 60000) as i32);
+        progress.attempts = attempt_number;
+
+        let passed = score_percentage >= quiz.passing_score as f64;
+        if passed {
+            progress.complete();
+        } else {
+            progress.fail();
+        }
+        ProgressRepository::create_or_update(conn, &progress)?;
+
+        // Keep the attempt so XP can be audited later. Truncating the score
+        // keeps it in the same accuracy band it was graded in.
+        let attempt = QuizAttempt::new(
+            user_id.to_string(),
+            quiz.id.clone(),
+            quiz.id.clone(),
+            quiz.questions
+                .iter()
+                .map(|q| submission.answers.get(&q.id).cloned().unwrap_or_default())
+                .collect(),
+            score_percentage as i32,
+            xp_earned,
+        );
+        let mut attempt = match timing {
+            Some(timed) => attempt.with_timing(timed.started_at, late_seconds),
+            None => attempt,
+        };
+        attempt.curriculum_id = curriculum_id.map(str::to_string);
+        QuizRepository::create(conn, &attempt)?;
+        QuizRepository::save_answers(conn, &attempt.question_answers(quiz))?;
+
+        // Advance quests
+        if passed {
+            GamificationService::record_activity(conn, user_id, QuestActivity::QuizCompleted)?;
+        }
+        GamificationService::record_activity(conn, user_id, QuestActivity::XpEarned(xp_earned))?;
+
+        let events = GamificationService::award_xp(conn, user_id, XpSource::Quiz, xp_earned)?.events;
+
+        Ok((
+            QuizResult {
+                attempt_id: attempt.id,
+                score,
+                total: total_points,
+                score_percentage,
+                passed,
+                xp_earned,
+                attempt_number,
+                mastery_updates,
+                feedback: generate_feedback(quiz, &submission.answers),
+                late_seconds,
+                penalty_percent,
+            },
+            events,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::models::quiz::{LatePolicy, Question, QuestionOption};
+    use crate::models::{NodeStatus, User};
+
+    fn quiz(time_limit_seconds: Option<i32>) -> Quiz {
+        Quiz {
+            id: "quiz-1".to_string(),
+            title: "Ownership".to_string(),
+            description: String::new(),
+            difficulty: "Easy".to_string(),
+            skills: vec!["ownership".to_string()],
+            passing_score: 70,
+            time_limit_seconds,
+            late_policy: Default::default(),
+            questions: vec![Question {
+                id: "q1".to_string(),
+                question_type: "multiple_choice".to_string(),
+                prompt: "Who owns a moved value?".to_string(),
+                code_snippet: None,
+                options: vec![
+                    QuestionOption { id: "a".to_string(), text: "The new binding".to_string() },
+                    QuestionOption { id: "b".to_string(), text: "Both".to_string() },
+                ],
+                correct_answer: "a".to_string(),
+                explanation: String::new(),
+                points: 10,
+                skills: vec![],
+            }],
+        }
+    }
+
+    fn submission(answer: &str) -> QuizSubmission {
+        QuizSubmission {
+            answers: HashMap::from([("q1".to_string(), answer.to_string())]),
+            time_spent_ms: 60_000,
+        }
+    }
+
+    #[test]
+    fn test_submit_grades_and_records_the_attempt() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let (result, events) =
+            QuizService::submit(conn, "test-user", None, &quiz(None), &submission("a"), 1.0, Utc::now())
+                .unwrap();
+        assert!(result.passed);
+        assert!(result.xp_earned > 0);
+        assert!(!events.is_empty());
+        assert_eq!(result.mastery_updates.len(), 1);
+
+        let progress = ProgressRepository::get(conn, "test-user", None, "quiz-1").unwrap().unwrap();
+        assert_eq!(progress.status, NodeStatus::Completed);
+        assert_eq!(QuizRepository::get_all_for_user(conn, "test-user").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_timed_quiz_must_be_started() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+        let timed = quiz(Some(60));
+
+        let unstarted = QuizService::submit(conn, "test-user", None, &timed, &submission("a"), 1.0, Utc::now());
+        assert!(matches!(unstarted, Err(ServiceError::InvalidState(_))));
+
+        let start = QuizService::start(conn, "test-user", None, &timed, Utc::now()).unwrap();
+        assert_eq!(start.deadline, Some(start.started_at + Duration::seconds(60)));
+        let (result, _) =
+            QuizService::submit(conn, "test-user", None, &timed, &submission("b"), 1.0, Utc::now()).unwrap();
+        assert!(!result.passed);
+        assert_eq!(result.late_seconds, 0);
+    }
+    #[test]
+    fn test_timed_quiz_counts_from_when_it_was_served() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+        let timed = quiz(Some(60));
+        let served_at = Utc::now() - Duration::seconds(120);
+
+        // Reading the questions, then starting, doesn't reset the clock
+        let shown = QuizService::serve(conn, "test-user", None, &timed, served_at).unwrap();
+        assert_eq!(shown.questions.len(), 1);
+        let start = QuizService::start(conn, "test-user", None, &timed, Utc::now() - Duration::seconds(1)).unwrap();
+        assert_eq!(start.started_at, QuizRepository::get_start(conn, "test-user", None, "quiz-1").unwrap().unwrap());
+        assert!((start.started_at - served_at).num_seconds().abs() < 1);
+
+        let (result, _) =
+            QuizService::submit(conn, "test-user", None, &timed, &submission("a"), 1.0, Utc::now()).unwrap();
+        assert!(result.late_seconds >= 55);
+    }
+
+    #[test]
+    fn test_start_past_a_rejecting_limit_starts_over() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+        let mut timed = quiz(Some(60));
+        timed.late_policy = LatePolicy::Reject;
+        let now = Utc::now();
+
+        QuizService::start(conn, "test-user", None, &timed, now - Duration::minutes(10)).unwrap();
+        let start = QuizService::start(conn, "test-user", None, &timed, now).unwrap();
+        assert_eq!(start.started_at.timestamp(), now.timestamp());
+    }
+}
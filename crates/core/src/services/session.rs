@@ -0,0 +1,177 @@
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::db::error::{DbError, DbResult};
+use crate::db::repos::{ProgressRepository, SessionRepository, UserRepository};
+use crate::gamification::get_streak_multiplier;
+use crate::models::{NodeStatus, SessionHistory};
+use crate::services::GamificationService;
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct SessionPlan {
+    pub session_id: String,
+    pub activities: Vec<PlannedActivity>,
+    pub estimated_minutes: u32,
+    pub total_xp_potential: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct PlannedActivity {
+    pub node_id: String,
+    pub node_type: String,
+    pub title: String,
+    pub difficulty: String,
+    pub xp_reward: i32,
+    pub estimated_minutes: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct SessionSummary {
+    pub session_id: String,
+    pub duration_minutes: u32,
+    /// Time the learner was active on lectures and challenges, without
+    /// idle gaps
+    pub active_minutes: u32,
+    pub total_xp_earned: i32,
+    pub activities_completed: Vec<CompletedActivitySummary>,
+    pub level_before: u32,
+    pub level_after: u32,
+    pub leveled_up: bool,
+    pub streak_days: i32,
+    pub streak_multiplier: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct CompletedActivitySummary {
+    pub title: String,
+    pub xp_earned: i32,
+}
+
+pub struct SessionService;
+
+impl SessionService {
+    /// Open a session and plan what to study in it
+    pub fn create_daily_session(conn: &Connection, user_id: &str, curriculum_id: Option<&str>) -> DbResult<SessionPlan> {
+        // Get user's progress to find available content
+        let all_progress = ProgressRepository::get_all_in_curriculum(conn, user_id, curriculum_id)?;
+        let _completed_ids: Vec<String> = all_progress
+            .iter()
+            .filter(|p| p.status == NodeStatus::Completed)
+            .map(|p| p.node_id.clone())
+            .collect();
+
+        // For now, create a simple session with mock activities
+        // In production, this would query the content system
+        let activities = vec![
+            PlannedActivity {
+                node_id: "lecture-intro".to_string(),
+                node_type: "lecture".to_string(),
+                title: "Introduction to Rust".to_string(),
+                difficulty: "Easy".to_string(),
+                xp_reward: 25,
+                estimated_minutes: 10,
+            },
+            PlannedActivity {
+                node_id: "quiz-basics".to_string(),
+                node_type: "quiz".to_string(),
+                title: "Rust Basics Quiz".to_string(),
+                difficulty: "Easy".to_string(),
+                xp_reward: 50,
+                estimated_minutes: 10,
+            },
+        ];
+
+        let total_xp: i32 = activities.iter().map(|a| a.xp_reward).sum();
+        let total_minutes: u32 = activities.iter().map(|a| a.estimated_minutes).sum();
+
+        let session = SessionHistory::new(user_id.to_string());
+        SessionRepository::create(conn, &session)?;
+
+        Ok(SessionPlan {
+            session_id: session.id.clone(),
+            activities,
+            estimated_minutes: total_minutes,
+            total_xp_potential: total_xp,
+        })
+    }
+
+    pub fn start_session(conn: &Connection, session_id: &str) -> DbResult<()> {
+        let session = SessionRepository::get_by_id(conn, session_id)?
+            .ok_or_else(|| DbError::NotFound("Session not found".to_string()))?;
+
+        // Session is already started when created
+        SessionRepository::update(conn, &session)
+    }
+
+    /// Close a session and add the XP earned in it to the user's total
+    pub fn complete_session(
+        conn: &Connection,
+        user_id: &str,
+        session_id: &str,
+        xp_earned: i32,
+    ) -> DbResult<SessionSummary> {
+        let mut session = SessionRepository::get_by_id(conn, session_id)?
+            .ok_or_else(|| DbError::NotFound("Session not found".to_string()))?;
+        let user = UserRepository::get_by_id(conn, user_id)?
+            .ok_or_else(|| DbError::NotFound("User not found".to_string()))?;
+
+        session.add_completion(xp_earned);
+        session.end_session();
+        SessionRepository::update(conn, &session)?;
+
+        let award = GamificationService::add_xp(conn, user_id, xp_earned)?;
+
+        Ok(SessionSummary {
+            session_id: session.id.clone(),
+            duration_minutes: session.duration_minutes() as u32,
+            active_minutes: (session.learning_seconds / 60) as u32,
+            total_xp_earned: xp_earned,
+            activities_completed: vec![], // Would be populated from session activities
+            level_before: award.previous_level,
+            level_after: award.new_level,
+            leveled_up: award.leveled_up(),
+            streak_days: user.current_streak,
+            streak_multiplier: get_streak_multiplier(user.current_streak as u32),
+        })
+    }
+
+    /// The user's session still open, if any
+    pub fn interrupted_session(conn: &Connection, user_id: &str) -> DbResult<Option<SessionPlan>> {
+        let session = SessionRepository::get_active_session(conn, user_id)?;
+        Ok(session.map(|session| SessionPlan {
+            session_id: session.id,
+            activities: vec![], // Would be populated from session data
+            estimated_minutes: 0,
+            total_xp_potential: 0,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::models::User;
+
+    #[test]
+    fn test_session_round_trip() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let plan = SessionService::create_daily_session(conn, "test-user", None).unwrap();
+        let interrupted = SessionService::interrupted_session(conn, "test-user").unwrap().unwrap();
+        assert_eq!(interrupted.session_id, plan.session_id);
+
+        SessionService::start_session(conn, &plan.session_id).unwrap();
+        let summary = SessionService::complete_session(conn, "test-user", &plan.session_id, 40).unwrap();
+        assert_eq!(summary.total_xp_earned, 40);
+        assert_eq!(UserRepository::get_by_id(conn, "test-user").unwrap().unwrap().total_xp, 40);
+        assert!(SessionService::interrupted_session(conn, "test-user").unwrap().is_none());
+        assert!(SessionService::complete_session(conn, "test-user", "missing", 10).is_err());
+    }
+}
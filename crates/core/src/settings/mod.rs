@@ -0,0 +1,37 @@
+//! User settings
+//!
+//! `preferences` holds each profile's typed, versioned `UserSettings`: theme,
+//! daily study goal, study days, review scheduler and notification preferences. They are
+//! stored as one JSON document in the profile's settings, apart from the
+//! notification preferences, which keep the table the reminder scheduler
+//! reads and are loaded and saved alongside.
+
+pub mod preferences;
+
+pub use preferences::{
+    load_settings, save_settings, ReviewScheduler, StudyDays, Theme, UserSettings, SETTINGS_VERSION, USER_SETTINGS_KEY,
+};
+
+use thiserror::Error;
+
+use crate::reminders::ReminderError;
+
+#[derive(Error, Debug)]
+pub enum SettingsError {
+    #[error(transparent)]
+    Db(#[from] crate::db::error::DbError),
+
+    #[error("Invalid settings: {0}")]
+    InvalidSettings(String),
+}
+
+pub type SettingsResult<T> = Result<T, SettingsError>;
+
+impl From<ReminderError> for SettingsError {
+    fn from(e: ReminderError) -> Self {
+        match e {
+            ReminderError::Db(e) => SettingsError::Db(e),
+            ReminderError::InvalidPrefs(message) => SettingsError::InvalidSettings(message),
+        }
+    }
+}
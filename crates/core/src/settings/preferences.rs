@@ -0,0 +1,225 @@
+use chrono::Weekday;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use crate::db::error::DbError;
+use crate::db::repos::{NotificationRepository, UserRepository};
+use crate::reminders::{self, NotificationPrefs};
+use crate::settings::{SettingsError, SettingsResult};
+
+/// Version of the stored settings document. Bump it, and upgrade older
+/// documents in `upgrade`, when a field changes meaning or shape.
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// Profile setting the settings document is stored under
+pub const USER_SETTINGS_KEY: &str = "user_settings";
+
+/// Longest daily goal accepted, a whole day
+const MAX_DAILY_GOAL_MINUTES: u32 = 24 * 60;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum Theme {
+    /// Follow the operating system
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+/// How reviews are spaced out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum ReviewScheduler {
+    /// SuperMemo 2, see `spaced_repetition`
+    #[default]
+    Sm2,
+}
+
+/// Days of the week the learner studies on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(default)]
+pub struct StudyDays {
+    pub monday: bool,
+    pub tuesday: bool,
+    pub wednesday: bool,
+    pub thursday: bool,
+    pub friday: bool,
+    pub saturday: bool,
+    pub sunday: bool,
+}
+
+impl Default for StudyDays {
+    fn default() -> Self {
+        Self {
+            monday: true,
+            tuesday: true,
+            wednesday: true,
+            thursday: true,
+            friday: true,
+            saturday: true,
+            sunday: true,
+        }
+    }
+}
+
+impl StudyDays {
+    pub fn includes(&self, day: Weekday) -> bool {
+        match day {
+            Weekday::Mon => self.monday,
+            Weekday::Tue => self.tuesday,
+            Weekday::Wed => self.wednesday,
+            Weekday::Thu => self.thursday,
+            Weekday::Fri => self.friday,
+            Weekday::Sat => self.saturday,
+            Weekday::Sun => self.sunday,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !(self.monday || self.tuesday || self.wednesday || self.thursday || self.friday || self.saturday || self.sunday)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(default)]
+pub struct UserSettings {
+    /// Version of the document these settings were read from
+    pub version: u32,
+    pub theme: Theme,
+    /// Minutes of study a day the learner aims for
+    pub daily_goal_minutes: u32,
+    /// Days pacing plans spread the remaining nodes over
+    pub study_days: StudyDays,
+    pub review_scheduler: ReviewScheduler,
+    pub notifications: NotificationPrefs,
+}
+
+impl Default for UserSettings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            theme: Theme::default(),
+            daily_goal_minutes: 30,
+            study_days: StudyDays::default(),
+            review_scheduler: ReviewScheduler::default(),
+            notifications: NotificationPrefs::default(),
+        }
+    }
+}
+
+impl UserSettings {
+    pub fn validate(&self) -> SettingsResult<()> {
+        if self.daily_goal_minutes == 0 || self.daily_goal_minutes > MAX_DAILY_GOAL_MINUTES {
+            return Err(SettingsError::InvalidSettings(format!(
+                "daily goal must be between 1 and {} minutes",
+                MAX_DAILY_GOAL_MINUTES
+            )));
+        }
+        if self.study_days.is_empty() {
+            return Err(SettingsError::InvalidSettings("at least one study day is needed".to_string()));
+        }
+        Ok(self.notifications.validate()?)
+    }
+}
+
+/// Bring a stored document up to `SETTINGS_VERSION`. Fields added since it
+/// was written take their defaults when it's read.
+fn upgrade(document: serde_json::Value, _from: u32) -> serde_json::Value {
+    document
+}
+
+/// A user's settings, with defaults for anything never saved
+pub fn load_settings(conn: &Connection, user_id: &str) -> SettingsResult<UserSettings> {
+    let mut settings = match UserRepository::get_setting(conn, user_id, USER_SETTINGS_KEY)? {
+        Some(json) => {
+            let document: serde_json::Value =
+                serde_json::from_str(&json).map_err(|e| DbError::InvalidData(e.to_string()))?;
+            let version = document.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+            serde_json::from_value(upgrade(document, version)).map_err(|e| DbError::InvalidData(e.to_string()))?
+        }
+        None => UserSettings::default(),
+    };
+    settings.version = SETTINGS_VERSION;
+    settings.notifications = NotificationRepository::get_prefs(conn, user_id)?;
+    Ok(settings)
+}
+
+/// Validate and store a user's settings
+pub fn save_settings(conn: &Connection, user_id: &str, settings: &UserSettings) -> SettingsResult<()> {
+    settings.validate()?;
+
+    let mut document = serde_json::to_value(settings).map_err(|e| DbError::InvalidData(e.to_string()))?;
+    if let Some(fields) = document.as_object_mut() {
+        fields.remove("notifications");
+        fields.insert("version".to_string(), SETTINGS_VERSION.into());
+    }
+    UserRepository::set_setting(conn, user_id, USER_SETTINGS_KEY, &document.to_string())?;
+    reminders::save_prefs(conn, user_id, &settings.notifications)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::models::User;
+
+    fn setup() -> Database {
+        let db = Database::new_in_memory().unwrap();
+        UserRepository::create(db.connection(), &User::new("test-user".to_string())).unwrap();
+        db
+    }
+
+    #[test]
+    fn test_defaults_until_saved() {
+        let db = setup();
+        let conn = db.connection();
+
+        assert_eq!(load_settings(conn, "test-user").unwrap(), UserSettings::default());
+
+        let mut settings = UserSettings { theme: Theme::Dark, daily_goal_minutes: 45, ..Default::default() };
+        settings.notifications.streak_reminders = false;
+        save_settings(conn, "test-user", &settings).unwrap();
+
+        assert_eq!(load_settings(conn, "test-user").unwrap(), settings);
+        // Notification preferences stay where the reminder scheduler reads them
+        assert!(!NotificationRepository::get_prefs(conn, "test-user").unwrap().streak_reminders);
+        let stored = UserRepository::get_setting(conn, "test-user", USER_SETTINGS_KEY).unwrap().unwrap();
+        assert!(!stored.contains("notifications"));
+    }
+
+    #[test]
+    fn test_older_documents_take_defaults() {
+        let db = setup();
+        let conn = db.connection();
+
+        UserRepository::set_setting(conn, "test-user", USER_SETTINGS_KEY, r#"{"theme":"Light"}"#).unwrap();
+        let settings = load_settings(conn, "test-user").unwrap();
+        assert_eq!(settings.theme, Theme::Light);
+        assert_eq!(settings.daily_goal_minutes, 30);
+        assert_eq!(settings.version, SETTINGS_VERSION);
+    }
+
+    #[test]
+    fn test_invalid_settings_are_rejected() {
+        let db = setup();
+        let conn = db.connection();
+
+        let settings = UserSettings { daily_goal_minutes: 0, ..Default::default() };
+        assert!(matches!(save_settings(conn, "test-user", &settings), Err(SettingsError::InvalidSettings(_))));
+        let no_days = StudyDays {
+            monday: false,
+            tuesday: false,
+            wednesday: false,
+            thursday: false,
+            friday: false,
+            saturday: false,
+            sunday: false,
+        };
+        let settings = UserSettings { study_days: no_days, ..Default::default() };
+        assert!(matches!(save_settings(conn, "test-user", &settings), Err(SettingsError::InvalidSettings(_))));
+        assert!(UserRepository::get_setting(conn, "test-user", USER_SETTINGS_KEY).unwrap().is_none());
+    }
+}
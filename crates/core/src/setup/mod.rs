@@ -0,0 +1,12 @@
+//! First-run setup
+//!
+//! Onboarding is a fixed sequence of steps: check Docker, prepare the code
+//! sandbox image, validate the grading API key, create the learner and import
+//! the default curriculum. The sequence is kept as a serializable state
+//! machine so an interrupted setup resumes at the step it stopped on. Running
+//! the steps is left to the app; this module only decides which step is
+//! next and which outcomes are allowed.
+
+pub mod state;
+
+pub use state::{SetupAction, SetupError, SetupState, SetupStep, StepOutcome, StepState, StepStatus};
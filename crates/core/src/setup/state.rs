@@ -0,0 +1,274 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// One step of first-run setup, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum SetupStep {
+    CheckDocker,
+    PrepareSandbox,
+    ValidateApiKey,
+    CreateUser,
+    ImportCurriculum,
+}
+
+impl SetupStep {
+    pub const ALL: [SetupStep; 5] = [
+        SetupStep::CheckDocker,
+        SetupStep::PrepareSandbox,
+        SetupStep::ValidateApiKey,
+        SetupStep::CreateUser,
+        SetupStep::ImportCurriculum,
+    ];
+
+    /// Code challenges and AI grading are optional; the app works without them
+    pub fn is_optional(&self) -> bool {
+        matches!(self, SetupStep::CheckDocker | SetupStep::PrepareSandbox | SetupStep::ValidateApiKey)
+    }
+
+    /// Later steps that can't run once this one is skipped
+    pub fn dependents(&self) -> &'static [SetupStep] {
+        match self {
+            SetupStep::CheckDocker => &[SetupStep::PrepareSandbox],
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum StepStatus {
+    Pending,
+    Done,
+    Skipped,
+    /// Ran and failed; stays the current step until it succeeds or is skipped
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct StepState {
+    pub step: SetupStep,
+    pub status: StepStatus,
+    /// What happened on the last attempt, for display
+    pub message: Option<String>,
+    pub attempts: u32,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// What the learner asked to do with the current step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum SetupAction {
+    /// Run the step, or retry it after a failure
+    Run,
+    Skip,
+}
+
+/// Result of running a step
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepOutcome {
+    Done(Option<String>),
+    Failed(String),
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum SetupError {
+    #[error("Setup is already complete")]
+    AlreadyComplete,
+
+    #[error("Setup step {0:?} is required and cannot be skipped")]
+    NotSkippable(SetupStep),
+
+    #[error("Setup step {found:?} is not the current step")]
+    OutOfOrder { found: SetupStep },
+}
+
+/// Progress through first-run setup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct SetupState {
+    pub steps: Vec<StepState>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl SetupState {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            steps: SetupStep::ALL
+                .iter()
+                .map(|&step| StepState {
+                    step,
+                    status: StepStatus::Pending,
+                    message: None,
+                    attempts: 0,
+                    updated_at: None,
+                })
+                .collect(),
+            started_at: now,
+            completed_at: None,
+        }
+    }
+
+    /// State for an install that finished onboarding before guided setup
+    /// existed, so it isn't asked to set up again
+    pub fn completed(now: DateTime<Utc>) -> Self {
+        let mut state = Self::new(now);
+        for step in &mut state.steps {
+            step.status = StepStatus::Done;
+            step.updated_at = Some(now);
+        }
+        state.completed_at = Some(now);
+        state
+    }
+
+    /// The step to run next: the first one not yet done or skipped
+    pub fn current_step(&self) -> Option<SetupStep> {
+        self.steps
+            .iter()
+            .find(|s| matches!(s.status, StepStatus::Pending | StepStatus::Failed))
+            .map(|s| s.step)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.current_step().is_none()
+    }
+
+    pub fn step(&self, step: SetupStep) -> Option<&StepState> {
+        self.steps.iter().find(|s| s.step == step)
+    }
+
+    fn step_mut(&mut self, step: SetupStep) -> Option<&mut StepState> {
+        self.steps.iter_mut().find(|s| s.step == step)
+    }
+
+    /// Record the outcome of running the current step
+    pub fn record(&mut self, step: SetupStep, outcome: StepOutcome, now: DateTime<Utc>) -> Result<(), SetupError> {
+        match self.current_step() {
+            None => return Err(SetupError::AlreadyComplete),
+            Some(current) if current != step => return Err(SetupError::OutOfOrder { found: step }),
+            Some(_) => {}
+        }
+
+        if let Some(state) = self.step_mut(step) {
+            let (status, message) = match outcome {
+                StepOutcome::Done(message) => (StepStatus::Done, message),
+                StepOutcome::Failed(message) => (StepStatus::Failed, Some(message)),
+            };
+            state.status = status;
+            state.message = message;
+            state.attempts += 1;
+            state.updated_at = Some(now);
+        }
+        self.finish_if_complete(now);
+        Ok(())
+    }
+
+    /// Skip the current step, along with the steps that depend on it
+    pub fn skip(&mut self, now: DateTime<Utc>) -> Result<SetupStep, SetupError> {
+        let step = self.current_step().ok_or(SetupError::AlreadyComplete)?;
+        if !step.is_optional() {
+            return Err(SetupError::NotSkippable(step));
+        }
+
+        let mut skipped = vec![(step, None)];
+        for &dependent in step.dependents() {
+            skipped.push((dependent, Some(format!("Skipped because {:?} was skipped", step))));
+        }
+        for (step, message) in skipped {
+            if let Some(state) = self.step_mut(step) {
+                if state.status != StepStatus::Done {
+                    state.status = StepStatus::Skipped;
+                    state.message = message;
+                    state.updated_at = Some(now);
+                }
+            }
+        }
+
+        self.finish_if_complete(now);
+        Ok(step)
+    }
+
+    fn finish_if_complete(&mut self, now: DateTime<Utc>) {
+        if self.is_complete() && self.completed_at.is_none() {
+            self.completed_at = Some(now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps_run_in_order_to_completion() {
+        let now = Utc::now();
+        let mut state = SetupState::new(now);
+
+        for step in SetupStep::ALL {
+            assert_eq!(state.current_step(), Some(step));
+            state.record(step, StepOutcome::Done(None), now).unwrap();
+        }
+
+        assert!(state.is_complete());
+        assert_eq!(state.completed_at, Some(now));
+        assert_eq!(
+            state.record(SetupStep::CreateUser, StepOutcome::Done(None), now),
+            Err(SetupError::AlreadyComplete)
+        );
+    }
+
+    #[test]
+    fn test_failed_step_stays_current_until_retried() {
+        let now = Utc::now();
+        let mut state = SetupState::new(now);
+
+        state
+            .record(SetupStep::CheckDocker, StepOutcome::Failed("Docker is not running".to_string()), now)
+            .unwrap();
+        assert_eq!(state.current_step(), Some(SetupStep::CheckDocker));
+
+        state.record(SetupStep::CheckDocker, StepOutcome::Done(None), now).unwrap();
+        let docker = state.step(SetupStep::CheckDocker).unwrap();
+        assert_eq!((docker.status, docker.attempts, docker.message.as_deref()), (StepStatus::Done, 2, None));
+        assert_eq!(state.current_step(), Some(SetupStep::PrepareSandbox));
+    }
+
+    #[test]
+    fn test_skipping_docker_skips_the_sandbox() {
+        let now = Utc::now();
+        let mut state = SetupState::new(now);
+
+        assert_eq!(state.skip(now), Ok(SetupStep::CheckDocker));
+        assert_eq!(state.step(SetupStep::PrepareSandbox).unwrap().status, StepStatus::Skipped);
+        assert_eq!(state.current_step(), Some(SetupStep::ValidateApiKey));
+
+        state.skip(now).unwrap();
+        assert_eq!(state.skip(now), Err(SetupError::NotSkippable(SetupStep::CreateUser)));
+    }
+
+    #[test]
+    fn test_out_of_order_outcome_is_rejected() {
+        let now = Utc::now();
+        let mut state = SetupState::new(now);
+
+        assert_eq!(
+            state.record(SetupStep::CreateUser, StepOutcome::Done(None), now),
+            Err(SetupError::OutOfOrder { found: SetupStep::CreateUser })
+        );
+    }
+
+    #[test]
+    fn test_state_resumes_after_round_trip() {
+        let now = Utc::now();
+        let mut state = SetupState::new(now);
+        state.record(SetupStep::CheckDocker, StepOutcome::Done(Some("24.0.7".to_string())), now).unwrap();
+
+        let json = serde_json::to_string(&state).unwrap();
+        let resumed: SetupState = serde_json::from_str(&json).unwrap();
+        assert_eq!(resumed.current_step(), Some(SetupStep::PrepareSandbox));
+        assert!(SetupState::completed(now).is_complete());
+    }
+}
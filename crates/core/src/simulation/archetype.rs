@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+/// A learner's study habits
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Archetype {
+    pub name: String,
+    pub minutes_per_session: u32,
+    /// Study days per week (1-7), spread evenly across the week
+    pub sessions_per_week: u32,
+    /// How long the learner keeps going before giving up
+    pub max_weeks: u32,
+    /// Typical score on quizzes, challenges and checkpoints (0-100)
+    pub accuracy_pct: f64,
+    /// Whether a balanced curriculum should let this learner finish
+    #[serde(default)]
+    pub expect_completion: bool,
+}
+
+impl Archetype {
+    /// 30 minutes every day for 20 weeks
+    pub fn daily() -> Self {
+        Self {
+            name: "daily".to_string(),
+            minutes_per_session: 30,
+            sessions_per_week: 7,
+            max_weeks: 20,
+            accuracy_pct: 85.0,
+            expect_completion: true,
+        }
+    }
+
+    /// 8 hours a day for 4 weeks
+    pub fn binge() -> Self {
+        Self {
+            name: "binge".to_string(),
+            minutes_per_session: 480,
+            sessions_per_week: 7,
+            max_weeks: 4,
+            accuracy_pct: 80.0,
+            expect_completion: false,
+        }
+    }
+
+    /// 2 hours once a week for 40 weeks
+    pub fn casual() -> Self {
+        Self {
+            name: "casual".to_string(),
+            minutes_per_session: 120,
+            sessions_per_week: 1,
+            max_weeks: 40,
+            accuracy_pct: 75.0,
+            expect_completion: false,
+        }
+    }
+
+    /// The archetypes from the original balance study
+    pub fn presets() -> Vec<Self> {
+        vec![Self::daily(), Self::binge(), Self::casual()]
+    }
+
+    /// Whether the learner studies on a given day (0-based day of the week)
+    pub fn is_active_on(&self, day_of_week: u32) -> bool {
+        let sessions = self.sessions_per_week.clamp(1, 7);
+        // Spread sessions evenly: a day is active when the running share ticks over
+        (day_of_week + 1) * sessions / 7 != day_of_week * sessions / 7
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn active_days(archetype: &Archetype) -> Vec<u32> {
+        (0..7).filter(|d| archetype.is_active_on(*d)).collect()
+    }
+
+    #[test]
+    fn test_active_days_spread_evenly() {
+        assert_eq!(active_days(&Archetype::daily()), vec![0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(active_days(&Archetype::casual()).len(), 1);
+
+        let three = Archetype { sessions_per_week: 3, ..Archetype::daily() };
+        assert_eq!(active_days(&three), vec![2, 4, 6]);
+    }
+
+    #[test]
+    fn test_archetype_deserializes_without_optional_fields() {
+        let json = r#"{"name":"weekend","minutes_per_session":90,"sessions_per_week":2,"max_weeks":16,"accuracy_pct":90.0}"#;
+        let archetype: Archetype = serde_json::from_str(json).unwrap();
+        assert_eq!(archetype.sessions_per_week, 2);
+        assert!(!archetype.expect_completion);
+    }
+}
@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+use super::archetype::Archetype;
+use super::engine::{simulate, SimCurriculum, SimulationResult};
+
+/// What a balanced curriculum should feel like for every archetype
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BalanceTargets {
+    /// Study hours needed per level gained
+    pub min_hours_per_level: f64,
+    pub max_hours_per_level: f64,
+    /// Badge unlocks per study hour
+    pub min_badges_per_hour: f64,
+    pub max_badges_per_hour: f64,
+    /// Average mastery at the end of the run (0.0-1.0)
+    pub min_average_mastery: f64,
+    /// Largest allowed ratio between the XP of learners who finish. Keeps
+    /// streak bonuses from making one study style far more rewarding.
+    pub max_finisher_xp_ratio: f64,
+}
+
+impl Default for BalanceTargets {
+    fn default() -> Self {
+        Self {
+            min_hours_per_level: 0.5,
+            max_hours_per_level: 4.0,
+            min_badges_per_hour: 0.05,
+            max_badges_per_hour: 1.0,
+            min_average_mastery: 0.3,
+            max_finisher_xp_ratio: 1.75,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceCheck {
+    /// Archetype the check applies to, or `None` for cross-archetype checks
+    pub archetype: Option<String>,
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceReport {
+    pub results: Vec<SimulationResult>,
+    pub checks: Vec<BalanceCheck>,
+}
+
+impl BalanceReport {
+    pub fn is_balanced(&self) -> bool {
+        self.checks.iter().all(|c| c.passed)
+    }
+
+    pub fn failures(&self) -> Vec<&BalanceCheck> {
+        self.checks.iter().filter(|c| !c.passed).collect()
+    }
+}
+
+fn range_check(
+    archetype: &str,
+    name: &str,
+    value: f64,
+    min: f64,
+    max: f64,
+    unit: &str,
+) -> BalanceCheck {
+    BalanceCheck {
+        archetype: Some(archetype.to_string()),
+        name: name.to_string(),
+        passed: value >= min && value <= max,
+        detail: format!("{:.2} {} (target {:.2}-{:.2})", value, unit, min, max),
+    }
+}
+
+/// Simulate each archetype through a curriculum and check the pacing
+pub fn evaluate_balance(
+    curriculum: &SimCurriculum,
+    archetypes: &[Archetype],
+    targets: &BalanceTargets,
+) -> BalanceReport {
+    let mut results = Vec::new();
+    let mut checks = Vec::new();
+
+    for archetype in archetypes {
+        let result = simulate(archetype, curriculum);
+        let hours = result.hours_studied.max(f64::EPSILON);
+        let name = archetype.name.as_str();
+
+        if archetype.expect_completion {
+            checks.push(BalanceCheck {
+                archetype: Some(name.to_string()),
+                name: "completion".to_string(),
+                passed: result.finished(),
+                detail: match result.weeks_to_complete {
+                    Some(w) => format!("finished in {} weeks", w),
+                    None => format!(
+                        "completed {}/{} nodes in {} weeks",
+                        result.nodes_completed, result.total_nodes, archetype.max_weeks
+                    ),
+                },
+            });
+        }
+
+        checks.push(match result.hours_per_level() {
+            Some(hours_per_level) => range_check(
+                name,
+                "level pacing",
+                hours_per_level,
+                targets.min_hours_per_level,
+                targets.max_hours_per_level,
+                "hours/level",
+            ),
+            None => BalanceCheck {
+                archetype: Some(name.to_string()),
+                name: "level pacing".to_string(),
+                passed: false,
+                detail: "never levelled up".to_string(),
+            },
+        });
+        checks.push(range_check(
+            name,
+            "badge frequency",
+            result.badges_earned as f64 / hours,
+            targets.min_badges_per_hour,
+            targets.max_badges_per_hour,
+            "badges/hour",
+        ));
+        checks.push(BalanceCheck {
+            archetype: Some(name.to_string()),
+            name: "mastery retention".to_string(),
+            passed: result.average_mastery >= targets.min_average_mastery,
+            detail: format!(
+                "{:.0}% average mastery (target >= {:.0}%)",
+                result.average_mastery * 100.0,
+                targets.min_average_mastery * 100.0
+            ),
+        });
+
+        results.push(result);
+    }
+
+    let finisher_xp: Vec<i32> = results.iter().filter(|r| r.finished()).map(|r| r.total_xp).collect();
+    if let (Some(&min), Some(&max)) = (finisher_xp.iter().min(), finisher_xp.iter().max()) {
+        if finisher_xp.len() > 1 && min > 0 {
+            let ratio = max as f64 / min as f64;
+            checks.push(BalanceCheck {
+                archetype: None,
+                name: "finisher XP spread".to_string(),
+                passed: ratio <= targets.max_finisher_xp_ratio,
+                detail: format!(
+                    "{:.2}x between {} and {} XP (target <= {:.2}x)",
+                    ratio, min, max, targets.max_finisher_xp_ratio
+                ),
+            });
+        }
+    }
+
+    BalanceReport { results, checks }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bootcamp_is_balanced_for_presets() {
+        let report = evaluate_balance(
+            &SimCurriculum::bootcamp(),
+            &Archetype::presets(),
+            &BalanceTargets::default(),
+        );
+        assert_eq!(report.results.len(), 3);
+        assert!(report.is_balanced(), "{:#?}", report.failures());
+    }
+
+    #[test]
+    fn test_empty_curriculum_is_unbalanced() {
+        let report = evaluate_balance(
+            &SimCurriculum::default(),
+            &[Archetype::daily()],
+            &BalanceTargets::default(),
+        );
+        assert!(!report.is_balanced());
+        assert!(report.failures().iter().any(|c| c.name == "level pacing"));
+    }
+
+    #[test]
+    fn test_completion_checked_only_when_expected() {
+        let report = evaluate_balance(
+            &SimCurriculum::bootcamp(),
+            &[Archetype::casual()],
+            &BalanceTargets::default(),
+        );
+        assert!(report.checks.iter().all(|c| c.name != "completion"));
+    }
+}
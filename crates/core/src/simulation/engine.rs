@@ -0,0 +1,360 @@
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::badges::{badge_value, check_single_badge, get_all_badge_definitions, UserStats};
+use crate::gamification::{calculate_level, calculate_streak_info, Difficulty, XpActivity, XpCalculator};
+use crate::models::MasteryScore;
+use super::archetype::Archetype;
+
+/// One piece of content in simulation order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimNode {
+    pub id: String,
+    pub activity: XpActivity,
+    pub difficulty: Difficulty,
+    pub estimated_minutes: u32,
+    #[serde(default)]
+    pub skills: Vec<String>,
+}
+
+/// Content to play through, in the order a learner meets it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SimCurriculum {
+    pub nodes: Vec<SimNode>,
+}
+
+impl SimCurriculum {
+    pub fn new(nodes: Vec<SimNode>) -> Self {
+        Self { nodes }
+    }
+
+    /// The 14-week bootcamp shape used by the original balance study: each week
+    /// has 5 lectures, 5 quizzes, 3 mini-challenges and a checkpoint.
+    pub fn bootcamp() -> Self {
+        let mut nodes = Vec::new();
+        for week in 1..=14 {
+            let mut add = |kind: &str, count: u32, activity, difficulty, minutes, skill: &str| {
+                for i in 1..=count {
+                    nodes.push(SimNode {
+                        id: format!("week{}-{}-{}", week, kind, i),
+                        activity,
+                        difficulty,
+                        estimated_minutes: minutes,
+                        skills: vec![format!("week{}_{}", week, skill)],
+                    });
+                }
+            };
+            add("lecture", 5, XpActivity::Lecture, Difficulty::Medium, 5, "concept");
+            add("quiz", 5, XpActivity::Quiz, Difficulty::Medium, 10, "concept");
+            add("challenge", 3, XpActivity::Challenge, Difficulty::Hard, 30, "coding");
+            add("checkpoint", 1, XpActivity::Checkpoint, Difficulty::VeryHard, 60, "project");
+        }
+        Self { nodes }
+    }
+
+    pub fn total_minutes(&self) -> u32 {
+        self.nodes.iter().map(|n| n.estimated_minutes).sum()
+    }
+}
+
+/// Outcome of playing one archetype through a curriculum
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationResult {
+    pub archetype: String,
+    pub total_xp: i32,
+    pub final_level: u32,
+    pub max_streak: u32,
+    pub average_mastery: f64,
+    pub nodes_completed: u32,
+    pub total_nodes: u32,
+    /// Badge unlocks, counting each tier of a tiered badge
+    pub badges_earned: u32,
+    pub days_active: u32,
+    /// Estimated study time of the completed nodes
+    pub hours_studied: f64,
+    /// Weeks until the last node was completed, if the learner finished
+    pub weeks_to_complete: Option<u32>,
+    /// XP earned in each simulated week
+    pub weekly_xp: Vec<i32>,
+}
+
+impl SimulationResult {
+    pub fn finished(&self) -> bool {
+        self.weeks_to_complete.is_some()
+    }
+
+    /// Study hours per level gained, or `None` if the learner never levelled up
+    pub fn hours_per_level(&self) -> Option<f64> {
+        let levels_gained = self.final_level.saturating_sub(1);
+        (levels_gained > 0).then(|| self.hours_studied / levels_gained as f64)
+    }
+}
+
+struct LearnerState {
+    total_xp: i32,
+    streak: u32,
+    max_streak: u32,
+    last_active_day: Option<u32>,
+    days_active: u32,
+    mastery: HashMap<String, (MasteryScore, u32)>,
+    stats: UserStats,
+    badges: HashMap<String, u32>,
+}
+
+impl LearnerState {
+    fn new() -> Self {
+        Self {
+            total_xp: 0,
+            streak: 0,
+            max_streak: 0,
+            last_active_day: None,
+            days_active: 0,
+            mastery: HashMap::new(),
+            stats: UserStats {
+                streak_days: 0,
+                level: 1,
+                total_xp: 0,
+                completed_lectures: 0,
+                completed_quizzes: 0,
+                completed_challenges: 0,
+                total_completions: 0,
+                perfect_quiz_count: 0,
+                max_mastery_score: 0.0,
+            },
+            badges: HashMap::new(),
+        }
+    }
+
+    /// Advance the streak with the same rules the app applies on login
+    fn visit(&mut self, day: u32) {
+        self.streak = match self.last_active_day {
+            None => 1,
+            Some(last) => {
+                let last_activity = Utc::now() - Duration::days((day - last) as i64);
+                calculate_streak_info(last_activity, self.streak).current_streak
+            }
+        };
+        self.max_streak = self.max_streak.max(self.streak);
+        self.last_active_day = Some(day);
+        self.days_active += 1;
+    }
+
+    fn complete(&mut self, node: &SimNode, archetype: &Archetype, day: u32) -> i32 {
+        let scored = node.activity != XpActivity::Lecture;
+        let mut calculator = XpCalculator::new(node.activity, node.difficulty).with_streak(self.streak);
+        if scored {
+            calculator = calculator.with_accuracy(archetype.accuracy_pct);
+        }
+        let xp = calculator.calculate();
+        self.total_xp += xp;
+
+        let performance = if scored { archetype.accuracy_pct / 100.0 } else { 1.0 };
+        for skill in &node.skills {
+            let (score, last_practiced) = self
+                .mastery
+                .entry(skill.clone())
+                .or_insert_with(|| (MasteryScore::new(String::new(), skill.clone()), day));
+            score.apply_decay((day - *last_practiced) as i64);
+            score.update_with_performance(performance);
+            *last_practiced = day;
+        }
+
+        match node.activity {
+            XpActivity::Lecture => self.stats.completed_lectures += 1,
+            XpActivity::Quiz => {
+                self.stats.completed_quizzes += 1;
+                if archetype.accuracy_pct >= 100.0 {
+                    self.stats.perfect_quiz_count += 1;
+                }
+            }
+            XpActivity::Challenge | XpActivity::Checkpoint => self.stats.completed_challenges += 1,
+        }
+        self.stats.total_completions += 1;
+        xp
+    }
+
+    /// Refresh badge stats and record any unlocks. Unlocks are permanent, so a
+    /// badge keeps the best tier it ever reached.
+    fn check_badges(&mut self) {
+        self.stats.streak_days = self.streak;
+        self.stats.total_xp = self.total_xp;
+        self.stats.level = calculate_level(self.total_xp);
+        self.stats.max_mastery_score = self
+            .mastery
+            .values()
+            .map(|(m, _)| m.score)
+            .fold(self.stats.max_mastery_score, f64::max);
+
+        for badge in get_all_badge_definitions() {
+            let earned = if badge.is_tiered() {
+                let value = badge_value(&badge, &self.stats);
+                badge.tiers.iter().filter(|t| value >= t.threshold).count() as u32
+            } else {
+                check_single_badge(&badge, &self.stats) as u32
+            };
+            let best = self.badges.entry(badge.id).or_insert(0);
+            *best = (*best).max(earned);
+        }
+    }
+
+    fn average_mastery(&self, final_day: u32) -> f64 {
+        if self.mastery.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = self
+            .mastery
+            .values()
+            .map(|(score, last_practiced)| {
+                let mut score = score.clone();
+                score.apply_decay(final_day.saturating_sub(*last_practiced) as i64);
+                score.score
+            })
+            .sum();
+        total / self.mastery.len() as f64
+    }
+}
+
+/// Play an archetype through a curriculum, one simulated day at a time.
+///
+/// Each study session works through nodes in order until the session's time is
+/// spent; a node longer than a whole session still fits in a fresh one.
+pub fn simulate(archetype: &Archetype, curriculum: &SimCurriculum) -> SimulationResult {
+    let mut learner = LearnerState::new();
+    let mut next_node = 0;
+    let mut weekly_xp = Vec::new();
+    let mut weeks_to_complete = None;
+    let mut final_day = 0;
+    let mut minutes_studied = 0;
+
+    'weeks: for week in 0..archetype.max_weeks {
+        let mut week_xp = 0;
+        for day_of_week in 0..7 {
+            let day = week * 7 + day_of_week;
+            final_day = day;
+            if !archetype.is_active_on(day_of_week) {
+                continue;
+            }
+
+            learner.visit(day);
+            let mut remaining = archetype.minutes_per_session;
+            while let Some(node) = curriculum.nodes.get(next_node) {
+                let fresh_session = remaining == archetype.minutes_per_session;
+                if node.estimated_minutes > remaining && !fresh_session {
+                    break;
+                }
+                week_xp += learner.complete(node, archetype, day);
+                minutes_studied += node.estimated_minutes;
+                remaining = remaining.saturating_sub(node.estimated_minutes);
+                next_node += 1;
+            }
+            learner.check_badges();
+
+            if next_node == curriculum.nodes.len() {
+                weekly_xp.push(week_xp);
+                weeks_to_complete = Some(week + 1);
+                break 'weeks;
+            }
+        }
+        weekly_xp.push(week_xp);
+    }
+
+    SimulationResult {
+        archetype: archetype.name.clone(),
+        total_xp: learner.total_xp,
+        final_level: calculate_level(learner.total_xp),
+        max_streak: learner.max_streak,
+        average_mastery: learner.average_mastery(final_day),
+        nodes_completed: next_node as u32,
+        total_nodes: curriculum.nodes.len() as u32,
+        badges_earned: learner.badges.values().sum(),
+        days_active: learner.days_active,
+        hours_studied: minutes_studied as f64 / 60.0,
+        weeks_to_complete,
+        weekly_xp,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_daily_learner_finishes_bootcamp() {
+        let result = simulate(&Archetype::daily(), &SimCurriculum::bootcamp());
+        assert!(result.finished());
+        let weeks = result.weeks_to_complete.unwrap();
+        assert!((14..=20).contains(&weeks), "took {} weeks", weeks);
+        assert!(result.total_xp > 5000);
+        assert!(result.final_level >= 10);
+    }
+
+    #[test]
+    fn test_binge_learner_finishes_first() {
+        let curriculum = SimCurriculum::bootcamp();
+        let binge = simulate(&Archetype::binge(), &curriculum);
+        let daily = simulate(&Archetype::daily(), &curriculum);
+        // 8 hour days cover the whole bootcamp inside a week
+        assert_eq!(binge.weeks_to_complete, Some(1));
+        assert_eq!(binge.hours_studied, curriculum.total_minutes() as f64 / 60.0);
+        // ...but never build the streak bonus a daily learner does
+        assert!(binge.total_xp < daily.total_xp);
+    }
+
+    #[test]
+    fn test_casual_learner_progresses_slowly() {
+        let curriculum = SimCurriculum::bootcamp();
+        let result = simulate(&Archetype::casual(), &curriculum);
+        let weeks = result.weeks_to_complete.unwrap();
+        assert!(weeks > 20, "took {} weeks", weeks);
+        assert_eq!(result.days_active, weeks);
+        // Weekly sessions never build a streak
+        assert_eq!(result.max_streak, 1);
+
+        let short = Archetype { max_weeks: 10, ..Archetype::casual() };
+        let result = simulate(&short, &curriculum);
+        assert!(!result.finished());
+        assert_eq!(result.weekly_xp.len(), 10);
+        assert!(result.nodes_completed < result.total_nodes);
+    }
+
+    #[test]
+    fn test_xp_matches_calculator() {
+        let curriculum = SimCurriculum::new(vec![
+            SimNode {
+                id: "l".to_string(),
+                activity: XpActivity::Lecture,
+                difficulty: Difficulty::Easy,
+                estimated_minutes: 10,
+                skills: vec!["syntax".to_string()],
+            },
+            SimNode {
+                id: "q".to_string(),
+                activity: XpActivity::Quiz,
+                difficulty: Difficulty::Medium,
+                estimated_minutes: 10,
+                skills: vec!["syntax".to_string()],
+            },
+        ]);
+        let result = simulate(&Archetype::daily(), &curriculum);
+
+        let expected = XpCalculator::new(XpActivity::Lecture, Difficulty::Easy).calculate()
+            + XpCalculator::new(XpActivity::Quiz, Difficulty::Medium).with_accuracy(85.0).calculate();
+        assert_eq!(result.total_xp, expected);
+        assert_eq!(result.weeks_to_complete, Some(1));
+        assert_eq!(result.weekly_xp, vec![expected]);
+        assert!(result.badges_earned >= 1); // first_steps
+    }
+
+    #[test]
+    fn test_long_node_fits_in_fresh_session() {
+        let curriculum = SimCurriculum::new(vec![SimNode {
+            id: "project".to_string(),
+            activity: XpActivity::Checkpoint,
+            difficulty: Difficulty::Hard,
+            estimated_minutes: 240,
+            skills: vec![],
+        }]);
+        let result = simulate(&Archetype::daily(), &curriculum);
+        assert_eq!(result.nodes_completed, 1);
+    }
+}
@@ -0,0 +1,14 @@
+//! Gamification balance simulation
+//!
+//! Plays learner archetypes through a curriculum day by day using the real XP,
+//! streak, mastery, level and badge rules, then checks the resulting pacing
+//! against balance targets. Used by `content-builder simulate` to vet a
+//! curriculum before it ships.
+
+pub mod archetype;
+pub mod balance;
+pub mod engine;
+
+pub use archetype::Archetype;
+pub use balance::{evaluate_balance, BalanceCheck, BalanceReport, BalanceTargets};
+pub use engine::{simulate, SimCurriculum, SimNode, SimulationResult};
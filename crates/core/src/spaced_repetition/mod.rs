@@ -1,8 +1,10 @@
 //! Spaced repetition system for the learning platform
 //!
-//! This module provides SM-2 based spaced repetition scheduling and mastery decay.
+//! This module provides SM-2 based spaced repetition scheduling, mastery decay
+//! and review session batching.
 
 pub mod scheduler;
+pub mod session;
 
 pub use scheduler::{
     ReviewQuality,
@@ -13,5 +15,9 @@ pub use scheduler::{
     calculate_next_review_date,
     score_to_quality,
     apply_mastery_decay,
+    apply_mastery_decay_with_policy,
     get_skills_needing_review,
+    get_skills_for_practice,
 };
+
+pub use session::{build_review_session, review_difficulty, review_session_seed, review_urgency};
@@ -3,7 +3,7 @@
 //! This module provides scheduling logic for review items using the SM-2 algorithm.
 
 use chrono::{DateTime, Duration, Utc};
-use crate::models::{MasteryScore, ReviewItem};
+use crate::models::{DecayPolicy, MasteryScore, ReviewItem};
 
 /// Quality of response for SM-2 algorithm
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -95,15 +95,25 @@ pub fn score_to_quality(score_percentage: f64) -> ReviewQuality {
 pub fn apply_mastery_decay(
     masteries: &mut [MasteryScore],
     current_time: DateTime<Utc>,
+) -> usize {
+    apply_mastery_decay_with_policy(masteries, current_time, &DecayPolicy::default())
+}
+
+/// Apply mastery decay to all stale skills using a curriculum's decay policy
+/// Returns the number of skills that were decayed
+pub fn apply_mastery_decay_with_policy(
+    masteries: &mut [MasteryScore],
+    current_time: DateTime<Utc>,
+    policy: &DecayPolicy,
 ) -> usize {
     let mut decayed_count = 0;
     
     for mastery in masteries.iter_mut() {
         let days_since_update = (current_time - mastery.last_updated_at).num_days();
         
-        if days_since_update > 3 {  // Beyond grace period
+        if days_since_update > policy.grace_period_days {
             let original_score = mastery.score;
-            mastery.apply_decay(days_since_update);
+            mastery.apply_decay_with_policy(days_since_update, policy);
             
             if (mastery.score - original_score).abs() > 0.001 {
                 decayed_count += 1;
@@ -119,6 +129,25 @@ pub fn get_skills_needing_review(masteries: &[MasteryScore], threshold: f64) ->
     masteries.iter().filter(|m| m.score < threshold).collect()
 }
 
+/// Get weak skills worth a practice challenge: those below `threshold`,
+/// with skills already decaying under `policy` first, then the weakest
+pub fn get_skills_for_practice<'a>(
+    masteries: &'a [MasteryScore],
+    current_time: DateTime<Utc>,
+    policy: &DecayPolicy,
+    threshold: f64,
+) -> Vec<&'a MasteryScore> {
+    let is_decaying = |m: &MasteryScore| (current_time - m.last_updated_at).num_days() > policy.grace_period_days;
+
+    let mut skills = get_skills_needing_review(masteries, threshold);
+    skills.sort_by(|a, b| {
+        is_decaying(b)
+            .cmp(&is_decaying(a))
+            .then(a.score.partial_cmp(&b.score).unwrap_or(std::cmp::Ordering::Equal))
+    });
+    skills
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,12 +201,14 @@ mod tests {
         let mut masteries = vec![
             MasteryScore {
                 user_id: "user1".to_string(),
+                curriculum_id: None,
                 skill_id: "skill1".to_string(),
                 score: 0.8,
                 last_updated_at: Utc::now() - Duration::days(10), // Stale
             },
             MasteryScore {
                 user_id: "user1".to_string(),
+                curriculum_id: None,
                 skill_id: "skill2".to_string(),
                 score: 0.8,
                 last_updated_at: Utc::now() - Duration::days(2), // Fresh
@@ -191,6 +222,26 @@ mod tests {
         assert_eq!(masteries[1].score, 0.8); // Should not have decayed
     }
 
+    #[test]
+    fn test_apply_mastery_decay_with_policy() {
+        let mut masteries = vec![MasteryScore {
+            user_id: "user1".to_string(),
+            curriculum_id: None,
+            skill_id: "skill1".to_string(),
+            score: 0.8,
+            last_updated_at: Utc::now() - Duration::days(2),
+        }];
+
+        // Fresh under the default policy, stale under a one-day grace period
+        let strict = DecayPolicy {
+            grace_period_days: 1,
+            ..DecayPolicy::default()
+        };
+        assert_eq!(apply_mastery_decay(&mut masteries, Utc::now()), 0);
+        assert_eq!(apply_mastery_decay_with_policy(&mut masteries, Utc::now(), &strict), 1);
+        assert!(masteries[0].score < 0.8);
+    }
+
     #[test]
     fn test_calculate_next_review_date() {
         // First review
@@ -201,4 +252,24 @@ mod tests {
         let date2 = calculate_next_review_date(1, 2.5, 1);
         assert!((date2 - Utc::now()).num_days() >= 5); // About 6 days
     }
+
+    #[test]
+    fn test_get_skills_for_practice_puts_decaying_skills_first() {
+        let mastery = |skill_id: &str, score: f64, days_idle: i64| MasteryScore {
+            user_id: "user1".to_string(),
+            curriculum_id: None,
+            skill_id: skill_id.to_string(),
+            score,
+            last_updated_at: Utc::now() - Duration::days(days_idle),
+        };
+        let masteries = vec![
+            mastery("weak-fresh", 0.2, 0),
+            mastery("strong-stale", 0.9, 30),
+            mastery("weak-stale", 0.5, 30),
+        ];
+
+        let skills = get_skills_for_practice(&masteries, Utc::now(), &DecayPolicy::default(), 0.6);
+        let ids: Vec<&str> = skills.iter().map(|m| m.skill_id.as_str()).collect();
+        assert_eq!(ids, vec!["weak-stale", "weak-fresh"]);
+    }
 }
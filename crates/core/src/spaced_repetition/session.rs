@@ -0,0 +1,168 @@
+//! Review session builder
+//!
+//! A review session is a capped batch of due items, interleaved across skills
+//! rather than working through one skill's items before the next. Within a
+//! skill, items come in order of urgency × difficulty; equal items are put in
+//! order by a seeded shuffle, so the same seed always builds the same session.
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use crate::models::ReviewItem;
+use crate::quests::generator::SplitMix64;
+
+/// How overdue an item is: 1 when it has just come due, plus one for every
+/// interval's worth of time it has been waiting since
+pub fn review_urgency(item: &ReviewItem, now: DateTime<Utc>) -> f64 {
+    let overdue_days = (now - item.due_date).num_seconds().max(0) as f64 / 86_400.0;
+    1.0 + overdue_days / item.interval_days.max(1) as f64
+}
+
+/// How hard the learner finds an item: 1 at the starting ease factor, up to
+/// about 1.9 at the lowest
+pub fn review_difficulty(item: &ReviewItem) -> f64 {
+    ReviewItem::INITIAL_EASE_FACTOR / item.ease_factor.max(ReviewItem::MIN_EASE_FACTOR)
+}
+
+/// Build a session of at most `max_items` of the items due at `now`.
+/// `skill_of` gives the skill an item practices; items without one are
+/// treated as a skill of their own.
+pub fn build_review_session(
+    items: &[ReviewItem],
+    skill_of: impl Fn(&ReviewItem) -> Option<String>,
+    now: DateTime<Utc>,
+    max_items: usize,
+    seed: u64,
+) -> Vec<ReviewItem> {
+    let mut due: Vec<&ReviewItem> = items
+        .iter()
+        .filter(|item| !item.is_suspended && item.due_date <= now)
+        .collect();
+
+    // Seeded Fisher-Yates shuffle, then a stable sort, so ties keep the
+    // shuffled order
+    let mut rng = SplitMix64::new(seed);
+    for i in (1..due.len()).rev() {
+        let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+        due.swap(i, j);
+    }
+    let priority = |item: &ReviewItem| review_urgency(item, now) * review_difficulty(item);
+    due.sort_by(|a, b| priority(b).total_cmp(&priority(a)));
+
+    // One queue per skill, in order of each skill's most pressing item
+    let mut queues: Vec<(String, VecDeque<&ReviewItem>)> = Vec::new();
+    for item in due {
+        let skill = skill_of(item).unwrap_or_else(|| item.quiz_id.clone());
+        match queues.iter_mut().find(|(id, _)| *id == skill) {
+            Some((_, queue)) => queue.push_back(item),
+            None => queues.push((skill, VecDeque::from([item]))),
+        }
+    }
+
+    // Take the next item of each skill in turn
+    let mut session = Vec::new();
+    while session.len() < max_items && !queues.is_empty() {
+        for (_, queue) in queues.iter_mut() {
+            if session.len() == max_items {
+                break;
+            }
+            if let Some(item) = queue.pop_front() {
+                session.push(item.clone());
+            }
+        }
+        queues.retain(|(_, queue)| !queue.is_empty());
+    }
+    session
+}
+
+/// Seed for a user's review session on the day of `now`, so reopening the
+/// session the same day gives the same order
+pub fn review_session_seed(user_id: &str, now: DateTime<Utc>) -> u64 {
+    let mut hasher = Sha256::new();
+    hasher.update(user_id.as_bytes());
+    hasher.update(now.date_naive().to_string().as_bytes());
+    let digest = hasher.finalize();
+
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    u64::from_le_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 5, 9, 0, 0).unwrap()
+    }
+
+    /// Due item `id`, `overdue_days` past its one-day interval
+    fn due(id: &str, overdue_days: i64, ease_factor: f64) -> ReviewItem {
+        let mut item = ReviewItem::new("user1".to_string(), id.to_string());
+        item.due_date = now() - Duration::days(overdue_days);
+        item.ease_factor = ease_factor;
+        item
+    }
+
+    /// Skill of a test item, from its id's prefix
+    fn skill(item: &ReviewItem) -> Option<String> {
+        item.quiz_id.split('-').next().map(str::to_string)
+    }
+
+    fn ids(session: &[ReviewItem]) -> Vec<&str> {
+        session.iter().map(|item| item.quiz_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_session_interleaves_skills_by_priority() {
+        let items = vec![
+            due("loops-1", 1, 2.5),
+            due("loops-2", 4, 2.5),
+            due("loops-3", 0, 1.3),
+            due("traits-1", 2, 2.5),
+            due("traits-2", 0, 2.5),
+            due("errors-1", 0, 2.5),
+        ];
+
+        let session = build_review_session(&items, skill, now(), 10, 7);
+        assert_eq!(
+            ids(&session),
+            vec!["loops-2", "traits-1", "errors-1", "loops-1", "traits-2", "loops-3"]
+        );
+    }
+
+    #[test]
+    fn test_session_is_capped_and_skips_items_not_due() {
+        let mut later = due("loops-4", 0, 2.5);
+        later.due_date = now() + Duration::hours(1);
+        let mut suspended = due("loops-5", 10, 1.3);
+        suspended.is_suspended = true;
+        let items = vec![due("loops-1", 3, 2.5), due("traits-1", 2, 2.5), due("loops-2", 1, 2.5), later, suspended];
+
+        assert_eq!(ids(&build_review_session(&items, skill, now(), 2, 7)), vec!["loops-1", "traits-1"]);
+        assert_eq!(build_review_session(&items, skill, now(), 10, 7).len(), 3);
+        assert!(build_review_session(&items, skill, now(), 0, 7).is_empty());
+    }
+
+    #[test]
+    fn test_ties_follow_the_seed() {
+        let items: Vec<ReviewItem> = (0..8).map(|i| due(&format!("loops-{}", i), 1, 2.5)).collect();
+
+        let first = build_review_session(&items, skill, now(), 8, 42);
+        let again = build_review_session(&items, skill, now(), 8, 42);
+        assert_eq!(ids(&first), ids(&again));
+
+        let reseeded: Vec<Vec<ReviewItem>> =
+            (0..5).map(|seed| build_review_session(&items, skill, now(), 8, seed)).collect();
+        assert!(reseeded.iter().any(|session| ids(session) != ids(&first)));
+    }
+
+    #[test]
+    fn test_session_seed_is_stable_within_a_day() {
+        let seed = review_session_seed("user1", now());
+        assert_eq!(seed, review_session_seed("user1", now() + Duration::hours(10)));
+        assert_ne!(seed, review_session_seed("user1", now() + Duration::days(1)));
+        assert_ne!(seed, review_session_seed("user2", now()));
+    }
+}
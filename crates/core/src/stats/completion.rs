@@ -0,0 +1,164 @@
+//! Curriculum completion
+//!
+//! How much of a curriculum is done, with each node counted by how much it
+//! is worth rather than one apiece, so a day-long checkpoint outweighs a
+//! five-minute lecture.
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::db::error::DbResult;
+
+/// What a node counts for towards completion
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum CompletionWeighting {
+    /// The XP it awards
+    #[default]
+    Xp,
+    /// The minutes it's expected to take
+    Minutes,
+}
+
+impl FromStr for CompletionWeighting {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Xp" => Ok(CompletionWeighting::Xp),
+            "Minutes" => Ok(CompletionWeighting::Minutes),
+            _ => Err(format!("Invalid completion weighting: {}", s)),
+        }
+    }
+}
+
+/// A node of the curriculum and what it's worth
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeWeight {
+    pub node_id: String,
+    pub xp_reward: u32,
+    pub estimated_minutes: u32,
+}
+
+impl NodeWeight {
+    /// Never zero, so a node worth nothing still has to be done
+    fn weight(&self, weighting: CompletionWeighting) -> u64 {
+        let weight = match weighting {
+            CompletionWeighting::Xp => self.xp_reward,
+            CompletionWeighting::Minutes => self.estimated_minutes,
+        };
+        weight.max(1) as u64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct CurriculumCompletion {
+    pub weighting: CompletionWeighting,
+    pub completed_weight: u64,
+    pub total_weight: u64,
+    /// 0.0-100.0
+    pub percent: f64,
+    pub nodes_completed: usize,
+    pub nodes_total: usize,
+    /// When the last node was completed, once every node is
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl CurriculumCompletion {
+    pub fn is_complete(&self) -> bool {
+        self.nodes_total > 0 && self.nodes_completed == self.nodes_total
+    }
+}
+
+/// How much of `nodes` the user has completed in a curriculum
+pub fn curriculum_completion(
+    conn: &Connection,
+    user_id: &str,
+    curriculum_id: Option<&str>,
+    nodes: &[NodeWeight],
+    weighting: CompletionWeighting,
+) -> DbResult<CurriculumCompletion> {
+    let mut stmt = conn.prepare(
+        "SELECT node_id, completed_at FROM node_progress
+         WHERE user_id = ?1 AND curriculum_id IS ?2 AND status = 'Completed'",
+    )?;
+    let completed = stmt
+        .query_map(params![user_id, curriculum_id], |row| {
+            let completed_at = row
+                .get::<_, Option<String>>(1)?
+                .and_then(|at| DateTime::parse_from_rfc3339(&at).ok())
+                .map(|at| at.with_timezone(&Utc));
+            Ok((row.get::<_, String>(0)?, completed_at))
+        })?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+
+    let mut completion = CurriculumCompletion {
+        weighting,
+        completed_weight: 0,
+        total_weight: 0,
+        percent: 0.0,
+        nodes_completed: 0,
+        nodes_total: nodes.len(),
+        completed_at: None,
+    };
+    let mut last_completed = None;
+    for node in nodes {
+        let weight = node.weight(weighting);
+        completion.total_weight += weight;
+        if let Some(completed_at) = completed.get(&node.node_id) {
+            completion.completed_weight += weight;
+            completion.nodes_completed += 1;
+            last_completed = last_completed.max(*completed_at);
+        }
+    }
+
+    if completion.total_weight > 0 {
+        completion.percent = completion.completed_weight as f64 / completion.total_weight as f64 * 100.0;
+    }
+    if completion.is_complete() {
+        completion.completed_at = last_completed;
+    }
+    Ok(completion)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::{ProgressRepository, UserRepository};
+    use crate::models::User;
+
+    fn node(id: &str, xp_reward: u32, estimated_minutes: u32) -> NodeWeight {
+        NodeWeight { node_id: id.to_string(), xp_reward, estimated_minutes }
+    }
+
+    #[test]
+    fn test_completion_is_weighted() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let nodes = vec![node("lecture", 25, 10), node("checkpoint", 75, 90), node("free", 0, 0)];
+        ProgressRepository::mark_completed(conn, "test-user", None, "lecture").unwrap();
+
+        let by_xp = curriculum_completion(conn, "test-user", None, &nodes, CompletionWeighting::Xp).unwrap();
+        assert_eq!((by_xp.completed_weight, by_xp.total_weight), (25, 101));
+        assert_eq!(by_xp.nodes_completed, 1);
+        assert!(!by_xp.is_complete());
+        assert!(by_xp.completed_at.is_none());
+
+        let by_minutes = curriculum_completion(conn, "test-user", None, &nodes, CompletionWeighting::Minutes).unwrap();
+        assert_eq!((by_minutes.completed_weight, by_minutes.total_weight), (10, 101));
+
+        ProgressRepository::mark_completed(conn, "test-user", None, "checkpoint").unwrap();
+        ProgressRepository::mark_completed(conn, "test-user", None, "free").unwrap();
+        let done = curriculum_completion(conn, "test-user", None, &nodes, CompletionWeighting::Xp).unwrap();
+        assert_eq!(done.percent, 100.0);
+        assert!(done.is_complete());
+        assert!(done.completed_at.is_some());
+    }
+}
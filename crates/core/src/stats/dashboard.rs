@@ -0,0 +1,294 @@
+//! Dashboard aggregates
+//!
+//! Each series is one grouped query. Time-on-task, XP and quiz accuracy are
+//! limited to the requested range; mastery and the best streak always cover
+//! the learner's whole history.
+
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::db::error::DbResult;
+use crate::models::QuestPeriod;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum StatsRange {
+    Week,
+    Month,
+    Quarter,
+    Year,
+    AllTime,
+}
+
+impl StatsRange {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatsRange::Week => "Week",
+            StatsRange::Month => "Month",
+            StatsRange::Quarter => "Quarter",
+            StatsRange::Year => "Year",
+            StatsRange::AllTime => "AllTime",
+        }
+    }
+
+    /// Start of the range, or None for all time. Ranges other than Week are
+    /// trailing windows ending today; Week starts on Monday (UTC).
+    pub fn start(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let today = QuestPeriod::Daily.bounds(now).0;
+        match self {
+            StatsRange::Week => Some(QuestPeriod::Weekly.bounds(now).0),
+            StatsRange::Month => Some(today - Duration::days(29)),
+            StatsRange::Quarter => Some(today - Duration::days(89)),
+            StatsRange::Year => Some(today - Duration::days(364)),
+            StatsRange::AllTime => None,
+        }
+    }
+}
+
+impl FromStr for StatsRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Week" => Ok(StatsRange::Week),
+            "Month" => Ok(StatsRange::Month),
+            "Quarter" => Ok(StatsRange::Quarter),
+            "Year" => Ok(StatsRange::Year),
+            "AllTime" => Ok(StatsRange::AllTime),
+            _ => Err(format!("Invalid stats range: {}", s)),
+        }
+    }
+}
+
+/// Active study time in the week starting on `week_start`, a Monday (YYYY-MM-DD)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct WeeklyTime {
+    pub week_start: String,
+    pub active_minutes: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct DailyXp {
+    pub date: String,
+    pub xp: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct DailyAccuracy {
+    pub date: String,
+    pub attempts: i64,
+    /// Mean score of the day's quiz attempts, 0-100
+    pub average_score: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct SkillMastery {
+    pub skill_id: String,
+    pub score: f64,
+    pub last_updated_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct DashboardStats {
+    pub range: StatsRange,
+    pub generated_at: DateTime<Utc>,
+    pub weekly_time: Vec<WeeklyTime>,
+    pub xp_per_day: Vec<DailyXp>,
+    pub quiz_accuracy: Vec<DailyAccuracy>,
+    /// Every skill with a mastery score, weakest first
+    pub mastery: Vec<SkillMastery>,
+    pub current_streak: i32,
+    /// Longest run of consecutive days with XP earned
+    pub best_streak: i64,
+}
+
+/// Everything the stats dashboard shows for a user
+pub fn dashboard_stats(
+    conn: &Connection,
+    user_id: &str,
+    curriculum_id: Option<&str>,
+    range: StatsRange,
+    now: DateTime<Utc>,
+) -> DbResult<DashboardStats> {
+    // RFC 3339 timestamps compare correctly as text
+    let since = range.start(now).map(|s| s.to_rfc3339()).unwrap_or_default();
+
+    let mut stmt = conn.prepare(
+        "SELECT date(substr(started_at, 1, 10), '-6 days', 'weekday 1') AS week, SUM(active_seconds)
+         FROM session_history
+         WHERE user_id = ?1 AND started_at >= ?2
+         GROUP BY week ORDER BY week",
+    )?;
+    let weekly_time = stmt
+        .query_map(params![user_id, since], |row| {
+            Ok(WeeklyTime {
+                week_start: row.get(0)?,
+                active_minutes: row.get::<_, i64>(1)? / 60,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT substr(earned_at, 1, 10) AS day, SUM(amount)
+         FROM xp_log
+         WHERE user_id = ?1 AND earned_at >= ?2
+         GROUP BY day ORDER BY day",
+    )?;
+    let xp_per_day = stmt
+        .query_map(params![user_id, since], |row| {
+            Ok(DailyXp {
+                date: row.get(0)?,
+                xp: row.get(1)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT substr(submitted_at, 1, 10) AS day, COUNT(*), AVG(score_percentage)
+         FROM quiz_attempts
+         WHERE user_id = ?1 AND curriculum_id IS ?2 AND submitted_at >= ?3
+         GROUP BY day ORDER BY day",
+    )?;
+    let quiz_accuracy = stmt
+        .query_map(params![user_id, curriculum_id, since], |row| {
+            Ok(DailyAccuracy {
+                date: row.get(0)?,
+                attempts: row.get(1)?,
+                average_score: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT skill_id, score, last_updated_at
+         FROM mastery_scores
+         WHERE user_id = ?1 AND curriculum_id IS ?2
+         ORDER BY score, skill_id",
+    )?;
+    let mastery = stmt
+        .query_map(params![user_id, curriculum_id], |row| {
+            Ok(SkillMastery {
+                skill_id: row.get(0)?,
+                score: row.get(1)?,
+                last_updated_at: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let current_streak: i32 = conn.query_row(
+        "SELECT current_streak FROM users WHERE id = ?1",
+        params![user_id],
+        |row| row.get(0),
+    )?;
+
+    // Gaps-and-islands: consecutive days share the same day-minus-row-number
+    let best_streak: i64 = conn.query_row(
+        "SELECT COALESCE(MAX(days), 0) FROM (
+             SELECT COUNT(*) AS days FROM (
+                 SELECT day, julianday(day) - ROW_NUMBER() OVER (ORDER BY day) AS island
+                 FROM (SELECT DISTINCT substr(earned_at, 1, 10) AS day FROM xp_log WHERE user_id = ?1)
+             )
+             GROUP BY island
+         )",
+        params![user_id],
+        |row| row.get(0),
+    )?;
+
+    Ok(DashboardStats {
+        range,
+        generated_at: now,
+        weekly_time,
+        xp_per_day,
+        quiz_accuracy,
+        mastery,
+        current_streak,
+        best_streak,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::User;
+    use chrono::TimeZone;
+
+    fn log_xp(conn: &Connection, amount: i32, earned_at: &str) {
+        conn.execute(
+            "INSERT INTO xp_log (user_id, amount, earned_at) VALUES ('test-user', ?1, ?2)",
+            params![amount, earned_at],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_range_start() {
+        // A Wednesday
+        let now = Utc.with_ymd_and_hms(2024, 5, 15, 13, 0, 0).unwrap();
+        assert_eq!(StatsRange::Week.start(now), Some(Utc.with_ymd_and_hms(2024, 5, 13, 0, 0, 0).unwrap()));
+        assert_eq!(StatsRange::Month.start(now), Some(Utc.with_ymd_and_hms(2024, 4, 16, 0, 0, 0).unwrap()));
+        assert_eq!(StatsRange::AllTime.start(now), None);
+        assert_eq!("Quarter".parse::<StatsRange>().unwrap(), StatsRange::Quarter);
+        assert!("Decade".parse::<StatsRange>().is_err());
+    }
+
+    #[test]
+    fn test_dashboard_stats() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        log_xp(conn, 10, "2024-05-01T09:00:00+00:00");
+        log_xp(conn, 20, "2024-05-02T09:00:00+00:00");
+        log_xp(conn, 5, "2024-05-02T18:00:00+00:00");
+        log_xp(conn, 30, "2024-05-03T09:00:00+00:00");
+        log_xp(conn, 40, "2024-05-14T09:00:00+00:00");
+
+        conn.execute_batch(
+            "INSERT INTO session_history (id, user_id, started_at, active_seconds) VALUES
+                 ('s1', 'test-user', '2024-05-13T09:00:00+00:00', 1200),
+                 ('s2', 'test-user', '2024-05-15T09:00:00+00:00', 600),
+                 ('s3', 'test-user', '2024-05-06T09:00:00+00:00', 3000);
+             INSERT INTO quiz_attempts (id, user_id, quiz_id, node_id, answers_json, score_percentage, xp_earned, submitted_at) VALUES
+                 ('a1', 'test-user', 'q1', 'n1', '[]', 50, 0, '2024-05-14T10:00:00+00:00'),
+                 ('a2', 'test-user', 'q1', 'n1', '[]', 100, 0, '2024-05-14T11:00:00+00:00'),
+                 ('a3', 'test-user', 'q2', 'n2', '[]', 80, 0, '2024-05-15T10:00:00+00:00');
+             INSERT INTO mastery_scores (user_id, skill_id, score) VALUES
+                 ('test-user', 'ownership', 0.8),
+                 ('test-user', 'traits', 0.3);",
+        )
+        .unwrap();
+
+        let now = Utc.with_ymd_and_hms(2024, 5, 15, 13, 0, 0).unwrap();
+        let stats = dashboard_stats(conn, "test-user", None, StatsRange::Week, now).unwrap();
+
+        assert_eq!(
+            stats.weekly_time,
+            vec![WeeklyTime {
+                week_start: "2024-05-13".to_string(),
+                active_minutes: 30,
+            }]
+        );
+        assert_eq!(stats.xp_per_day.len(), 1);
+        assert_eq!(stats.xp_per_day[0].xp, 40);
+        assert_eq!(stats.quiz_accuracy.len(), 2);
+        assert_eq!(stats.quiz_accuracy[0].attempts, 2);
+        assert_eq!(stats.quiz_accuracy[0].average_score, 75.0);
+        assert_eq!(stats.mastery[0].skill_id, "traits");
+        assert_eq!(stats.best_streak, 3);
+
+        let all = dashboard_stats(conn, "test-user", None, StatsRange::AllTime, now).unwrap();
+        assert_eq!(all.weekly_time.len(), 2);
+        assert_eq!(all.weekly_time[0].week_start, "2024-05-06");
+        assert_eq!(all.xp_per_day.len(), 4);
+        assert_eq!(all.xp_per_day[1].xp, 25);
+    }
+}
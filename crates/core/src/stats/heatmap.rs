@@ -0,0 +1,87 @@
+//! Activity heatmap
+//!
+//! A contributions-style calendar read from the `daily_activity` rollup.
+//! Only days with activity are listed; the calendar fills in the rest.
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::db::error::DbResult;
+use crate::db::repos::ActivityRepository;
+use crate::models::DailyActivity;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ActivityHeatmap {
+    pub year: i32,
+    pub days: Vec<DailyActivity>,
+    pub active_days: usize,
+    pub total_minutes: i64,
+    pub total_xp: i64,
+    pub total_nodes_completed: i64,
+}
+
+/// A user's activity for one calendar year (UTC)
+pub fn activity_heatmap(conn: &Connection, user_id: &str, year: i32) -> DbResult<ActivityHeatmap> {
+    let days = ActivityRepository::get_between(conn, user_id, &format!("{:04}-01-01", year), &format!("{:04}-12-31", year))?;
+
+    Ok(ActivityHeatmap {
+        year,
+        active_days: days.len(),
+        total_minutes: days.iter().map(|d| d.active_minutes).sum(),
+        total_xp: days.iter().map(|d| d.xp).sum(),
+        total_nodes_completed: days.iter().map(|d| d.nodes_completed).sum(),
+        days,
+    })
+}
+
+/// Render activity history as CSV, one row per day
+pub fn activity_csv(days: &[DailyActivity]) -> String {
+    let mut csv = String::from("date,active_minutes,xp,nodes_completed\n");
+
+    for day in days {
+        csv.push_str(&format!("{},{},{},{}\n", day.day, day.active_minutes, day.xp, day.nodes_completed));
+    }
+
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::User;
+    use rusqlite::params;
+
+    #[test]
+    fn test_heatmap_for_year() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        for (amount, earned_at) in [
+            (10, "2023-12-31T23:00:00+00:00"),
+            (20, "2024-01-01T08:00:00+00:00"),
+            (5, "2024-01-01T09:00:00+00:00"),
+            (15, "2024-06-10T09:00:00+00:00"),
+        ] {
+            conn.execute(
+                "INSERT INTO xp_log (user_id, amount, earned_at) VALUES ('test-user', ?1, ?2)",
+                params![amount, earned_at],
+            )
+            .unwrap();
+        }
+
+        let heatmap = activity_heatmap(conn, "test-user", 2024).unwrap();
+        assert_eq!(heatmap.active_days, 2);
+        assert_eq!(heatmap.total_xp, 40);
+        assert_eq!(heatmap.days[0].day, "2024-01-01");
+        assert_eq!(heatmap.days[0].xp, 25);
+
+        assert_eq!(
+            activity_csv(&heatmap.days),
+            "date,active_minutes,xp,nodes_completed\n2024-01-01,0,25,0\n2024-06-10,0,15,0\n"
+        );
+    }
+}
@@ -0,0 +1,19 @@
+//! Study statistics
+//!
+//! Aggregates for the stats dashboard, activity heatmap, weekly report,
+//! curriculum completion and checkpoint readiness, computed in SQL so the
+//! frontend gets each view in one call.
+
+pub mod completion;
+pub mod dashboard;
+pub mod heatmap;
+pub mod readiness;
+pub mod report;
+
+pub use completion::{curriculum_completion, CompletionWeighting, CurriculumCompletion, NodeWeight};
+pub use dashboard::{
+    dashboard_stats, DailyAccuracy, DailyXp, DashboardStats, SkillMastery, StatsRange, WeeklyTime,
+};
+pub use heatmap::{activity_csv, activity_heatmap, ActivityHeatmap};
+pub use readiness::{checkpoint_readiness, CheckpointReadiness, READY_PERCENT};
+pub use report::{weekly_report, CompletedNode, MasteryDelta, WeeklyReport};
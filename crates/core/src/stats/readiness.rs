@@ -0,0 +1,259 @@
+//! Checkpoint readiness
+//!
+//! How likely a learner is to pass a checkpoint, from their mastery of the
+//! skills it covers, how well they've answered recent quiz questions on those
+//! skills and how many of its prerequisites they've completed, so they can be
+//! warned before spending a graded attempt on it.
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::db::error::{DbError, DbResult};
+use crate::db::repos::MasteryRepository;
+
+/// How much each part counts towards readiness. A part with nothing to go on
+/// is left out and the others scaled up to make up for it.
+pub const READINESS_MASTERY_WEIGHT: f64 = 0.4;
+pub const READINESS_ACCURACY_WEIGHT: f64 = 0.3;
+pub const READINESS_PREREQUISITE_WEIGHT: f64 = 0.3;
+
+/// Most recent answers on a checkpoint's skills that accuracy is taken over
+pub const READINESS_RECENT_ANSWERS: usize = 30;
+
+/// Readiness below this means the learner is likely not ready
+pub const READY_PERCENT: f64 = 70.0;
+
+/// Skills mastered below this are listed as ones to work on
+pub const WEAK_SKILL_SCORE: f64 = 0.6;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct CheckpointReadiness {
+    pub checkpoint_id: String,
+    /// 0.0-100.0
+    pub percent: f64,
+    /// `percent` is at least `READY_PERCENT`
+    pub likely_ready: bool,
+    /// Average mastery of the checkpoint's skills, 0.0-1.0; `None` when it
+    /// covers no skills
+    pub mastery: Option<f64>,
+    /// Share of recent answers on the checkpoint's skills that were correct;
+    /// `None` when there are none
+    pub quiz_accuracy: Option<f64>,
+    pub recent_answers: u32,
+    pub prerequisites_completed: u32,
+    pub prerequisites_total: u32,
+    pub incomplete_prerequisites: Vec<String>,
+    /// Skills mastered below `WEAK_SKILL_SCORE`, weakest first
+    pub weak_skills: Vec<String>,
+}
+
+/// How ready the user is for a checkpoint covering `skills`, with
+/// `prerequisites` to complete first. `satisfied_by` gives the nodes whose
+/// completion meets a prerequisite, the prerequisite itself among them.
+pub fn checkpoint_readiness(
+    conn: &Connection,
+    user_id: &str,
+    curriculum_id: Option<&str>,
+    checkpoint_id: &str,
+    skills: &[String],
+    prerequisites: &[String],
+    satisfied_by: impl Fn(&str) -> Vec<String>,
+) -> DbResult<CheckpointReadiness> {
+    let scores: HashMap<String, f64> = MasteryRepository::get_all_in_curriculum(conn, user_id, curriculum_id)?
+        .into_iter()
+        .map(|mastery| (mastery.skill_id, mastery.score))
+        .collect();
+    let mut skill_scores: Vec<(&String, f64)> = skills
+        .iter()
+        .map(|skill| (skill, scores.get(skill).copied().unwrap_or(0.0)))
+        .collect();
+    let mastery = (!skill_scores.is_empty())
+        .then(|| skill_scores.iter().map(|(_, score)| score).sum::<f64>() / skill_scores.len() as f64);
+    skill_scores.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(b.0)));
+    let weak_skills = skill_scores
+        .into_iter()
+        .filter(|(_, score)| *score < WEAK_SKILL_SCORE)
+        .map(|(skill, _)| skill.clone())
+        .collect();
+
+    let (correct, recent_answers) = recent_accuracy(conn, user_id, curriculum_id, skills)?;
+    let quiz_accuracy = (recent_answers > 0).then(|| correct as f64 / recent_answers as f64);
+
+    let mut stmt = conn.prepare(
+        "SELECT node_id FROM node_progress
+         WHERE user_id = ?1 AND curriculum_id IS ?2 AND status = 'Completed'",
+    )?;
+    let completed = stmt
+        .query_map(params![user_id, curriculum_id], |row| row.get::<_, String>(0))?
+        .collect::<Result<HashSet<_>, _>>()?;
+    let incomplete_prerequisites: Vec<String> = prerequisites
+        .iter()
+        .filter(|id| !satisfied_by(id).iter().any(|node| completed.contains(node)))
+        .cloned()
+        .collect();
+    let prerequisites_completed = prerequisites.len() - incomplete_prerequisites.len();
+    let prerequisite_share =
+        (!prerequisites.is_empty()).then(|| prerequisites_completed as f64 / prerequisites.len() as f64);
+
+    let parts = [
+        (READINESS_MASTERY_WEIGHT, mastery),
+        (READINESS_ACCURACY_WEIGHT, quiz_accuracy),
+        (READINESS_PREREQUISITE_WEIGHT, prerequisite_share),
+    ];
+    let weight: f64 = parts.iter().filter(|(_, value)| value.is_some()).map(|(weight, _)| weight).sum();
+    let percent = if weight > 0.0 {
+        parts.iter().filter_map(|(weight, value)| value.map(|value| weight * value)).sum::<f64>() / weight * 100.0
+    } else {
+        100.0
+    };
+
+    Ok(CheckpointReadiness {
+        checkpoint_id: checkpoint_id.to_string(),
+        percent,
+        likely_ready: percent >= READY_PERCENT,
+        mastery,
+        quiz_accuracy,
+        recent_answers,
+        prerequisites_completed: prerequisites_completed as u32,
+        prerequisites_total: prerequisites.len() as u32,
+        incomplete_prerequisites,
+        weak_skills,
+    })
+}
+
+/// Correct answers among the user's latest `READINESS_RECENT_ANSWERS`
+/// answers to questions tagged with any of `skills`, and how many that was
+fn recent_accuracy(
+    conn: &Connection,
+    user_id: &str,
+    curriculum_id: Option<&str>,
+    skills: &[String],
+) -> DbResult<(u32, u32)> {
+    let mut stmt = conn.prepare(
+        "SELECT qa.is_correct FROM quiz_answers qa
+         JOIN quiz_attempts a ON a.id = qa.attempt_id
+         WHERE a.user_id = ?1 AND a.curriculum_id IS ?2
+           AND EXISTS (SELECT 1 FROM json_each(qa.skills_json) skill
+                       WHERE skill.value IN (SELECT value FROM json_each(?3)))
+         ORDER BY a.submitted_at DESC, qa.position
+         LIMIT ?4",
+    )?;
+    let skills_json = serde_json::to_string(skills).map_err(|e| DbError::InvalidData(e.to_string()))?;
+    let answers = stmt
+        .query_map(
+            params![user_id, curriculum_id, skills_json, READINESS_RECENT_ANSWERS as i64],
+            |row| row.get::<_, bool>(0),
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    let correct = answers.iter().filter(|correct| **correct).count();
+    Ok((correct as u32, answers.len() as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::{ProgressRepository, QuizRepository, UserRepository};
+    use crate::models::{MasteryScore, QuestionAnswer, QuizAttempt, User};
+
+    fn same_node(id: &str) -> Vec<String> {
+        vec![id.to_string()]
+    }
+
+    #[test]
+    fn test_readiness_combines_mastery_accuracy_and_prerequisites() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+        let skills = vec!["ownership".to_string(), "traits".to_string()];
+        let prerequisites = vec!["ownership-quiz".to_string(), "traits-quiz".to_string()];
+
+        // Nothing done yet
+        let fresh = checkpoint_readiness(conn, "test-user", None, "cp-1", &skills, &prerequisites, same_node).unwrap();
+        assert_eq!(fresh.percent, 0.0);
+        assert!(!fresh.likely_ready);
+        assert_eq!(fresh.quiz_accuracy, None);
+        assert_eq!(fresh.weak_skills, vec!["ownership", "traits"]);
+
+        for (skill, score) in [("ownership", 0.9), ("traits", 0.5)] {
+            let mut mastery = MasteryScore::new("test-user".to_string(), skill.to_string());
+            mastery.score = score;
+            MasteryRepository::create_or_update(conn, &mastery).unwrap();
+        }
+        let attempt = QuizAttempt::new(
+            "test-user".to_string(),
+            "ownership-quiz".to_string(),
+            "ownership-quiz".to_string(),
+            Vec::new(),
+            75,
+            0,
+        );
+        QuizRepository::create(conn, &attempt).unwrap();
+        let answer = |position: i32, is_correct: bool, skill: &str| QuestionAnswer {
+            attempt_id: attempt.id.clone(),
+            question_id: format!("q{}", position),
+            position,
+            answer: "a".to_string(),
+            is_correct,
+            skills: vec![skill.to_string()],
+        };
+        let answers = [
+            answer(0, true, "ownership"),
+            answer(1, true, "ownership"),
+            answer(2, true, "traits"),
+            answer(3, false, "traits"),
+            answer(4, false, "closures"),
+        ];
+        QuizRepository::save_answers(conn, &answers).unwrap();
+        ProgressRepository::mark_completed(conn, "test-user", None, "ownership-quiz").unwrap();
+
+        let readiness = checkpoint_readiness(conn, "test-user", None, "cp-1", &skills, &prerequisites, same_node).unwrap();
+        assert!((readiness.mastery.unwrap() - 0.7).abs() < 1e-9);
+        assert_eq!(readiness.recent_answers, 4);
+        assert_eq!(readiness.quiz_accuracy, Some(0.75));
+        assert_eq!((readiness.prerequisites_completed, readiness.prerequisites_total), (1, 2));
+        assert_eq!(readiness.incomplete_prerequisites, vec!["traits-quiz"]);
+        assert_eq!(readiness.weak_skills, vec!["traits"]);
+        // 0.4 × 0.7 + 0.3 × 0.75 + 0.3 × 0.5
+        assert!((readiness.percent - 65.5).abs() < 1e-9);
+        assert!(!readiness.likely_ready);
+
+        ProgressRepository::mark_completed(conn, "test-user", None, "traits-quiz").unwrap();
+        let ready = checkpoint_readiness(conn, "test-user", None, "cp-1", &skills, &prerequisites, same_node).unwrap();
+        assert!(ready.likely_ready);
+    }
+
+    #[test]
+    fn test_readiness_without_anything_to_go_on() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        let readiness = checkpoint_readiness(conn, "test-user", None, "cp-1", &[], &[], same_node).unwrap();
+        assert_eq!(readiness.percent, 100.0);
+        assert!(readiness.likely_ready);
+        assert_eq!(readiness.mastery, None);
+    }
+
+    #[test]
+    fn test_superseded_prerequisite_is_met_by_its_replacement() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+        let prerequisites = vec!["parser-v1".to_string()];
+        let satisfied_by = |id: &str| match id {
+            "parser-v1" | "parser-v2" => vec!["parser-v1".to_string(), "parser-v2".to_string()],
+            _ => vec![id.to_string()],
+        };
+
+        ProgressRepository::mark_completed(conn, "test-user", None, "parser-v2").unwrap();
+        let readiness = checkpoint_readiness(conn, "test-user", None, "cp-1", &[], &prerequisites, satisfied_by).unwrap();
+        assert_eq!(readiness.prerequisites_completed, 1);
+        assert!(readiness.incomplete_prerequisites.is_empty());
+
+        let strict = checkpoint_readiness(conn, "test-user", None, "cp-1", &[], &prerequisites, same_node).unwrap();
+        assert_eq!(strict.incomplete_prerequisites, vec!["parser-v1"]);
+    }
+}
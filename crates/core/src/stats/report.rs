@@ -0,0 +1,273 @@
+//! Weekly progress report
+//!
+//! Sums up the seven days before a moment: XP, nodes completed, study time
+//! and how each practised skill's mastery moved, plus the reviews coming due
+//! in the seven days after. Renders to Markdown or HTML for export.
+
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use crate::db::error::DbResult;
+use crate::db::repos::ReviewRepository;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompletedNode {
+    pub node_id: String,
+    /// The node's title, or its id until `name_nodes` is called
+    pub title: String,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// How a skill's mastery moved over the week
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MasteryDelta {
+    pub skill_id: String,
+    pub from: f64,
+    pub to: f64,
+}
+
+impl MasteryDelta {
+    pub fn change(&self) -> f64 {
+        self.to - self.from
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeeklyReport {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub xp: i64,
+    pub active_minutes: i64,
+    pub completed: Vec<CompletedNode>,
+    /// Skills whose mastery changed, biggest gain first
+    pub mastery: Vec<MasteryDelta>,
+    /// Reviews due within a week of `to`, including any already overdue
+    pub reviews_due_next_week: i32,
+}
+
+/// The report for the week before `now`
+pub fn weekly_report(
+    conn: &Connection,
+    user_id: &str,
+    curriculum_id: Option<&str>,
+    now: DateTime<Utc>,
+) -> DbResult<WeeklyReport> {
+    let from = now - Duration::days(7);
+    let (start, end) = (from.to_rfc3339(), now.to_rfc3339());
+
+    let xp: i64 = conn.query_row(
+        "SELECT COALESCE(SUM(amount), 0) FROM xp_log WHERE user_id = ?1 AND earned_at >= ?2 AND earned_at < ?3",
+        params![user_id, start, end],
+        |row| row.get(0),
+    )?;
+
+    let active_seconds: i64 = conn.query_row(
+        "SELECT COALESCE(SUM(active_seconds), 0) FROM session_history
+         WHERE user_id = ?1 AND started_at >= ?2 AND started_at < ?3",
+        params![user_id, start, end],
+        |row| row.get(0),
+    )?;
+
+    let mut stmt = conn.prepare(
+        "SELECT node_id, completed_at FROM node_progress
+         WHERE user_id = ?1 AND curriculum_id IS ?2 AND status = 'Completed'
+           AND completed_at >= ?3 AND completed_at < ?4
+         ORDER BY completed_at",
+    )?;
+    let completed = stmt
+        .query_map(params![user_id, curriculum_id, start, end], |row| {
+            let node_id: String = row.get(0)?;
+            Ok(CompletedNode {
+                title: node_id.clone(),
+                node_id,
+                completed_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(1)?)
+                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, Box::new(e)))?
+                    .with_timezone(&Utc),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Score before the week's first change and after its last
+    let mut stmt = conn.prepare(
+        "SELECT skill_id,
+                (SELECT previous_score FROM mastery_history f
+                 WHERE f.user_id = h.user_id AND f.skill_id = h.skill_id AND f.recorded_at >= ?2 AND f.recorded_at < ?3
+                 ORDER BY f.recorded_at, f.id LIMIT 1),
+                (SELECT score FROM mastery_history l
+                 WHERE l.user_id = h.user_id AND l.skill_id = h.skill_id AND l.recorded_at >= ?2 AND l.recorded_at < ?3
+                 ORDER BY l.recorded_at DESC, l.id DESC LIMIT 1)
+         FROM mastery_history h
+         WHERE h.user_id = ?1 AND h.recorded_at >= ?2 AND h.recorded_at < ?3
+         GROUP BY skill_id",
+    )?;
+    let mut mastery = stmt
+        .query_map(params![user_id, start, end], |row| {
+            Ok(MasteryDelta {
+                skill_id: row.get(0)?,
+                from: row.get(1)?,
+                to: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    mastery.sort_by(|a, b| b.change().total_cmp(&a.change()).then_with(|| a.skill_id.cmp(&b.skill_id)));
+
+    let reviews_due_next_week =
+        ReviewRepository::count_due_reviews_by(conn, user_id, curriculum_id, now + Duration::days(7))?;
+
+    Ok(WeeklyReport {
+        from,
+        to: now,
+        xp,
+        active_minutes: active_seconds / 60,
+        completed,
+        mastery,
+        reviews_due_next_week,
+    })
+}
+
+impl WeeklyReport {
+    /// Replace node ids with titles where `title_of` knows them
+    pub fn name_nodes(&mut self, title_of: impl Fn(&str) -> Option<String>) {
+        for node in &mut self.completed {
+            if let Some(title) = title_of(&node.node_id) {
+                node.title = title;
+            }
+        }
+    }
+
+    fn heading(&self) -> String {
+        format!("Week of {} to {}", self.from.format("%b %-d"), self.to.format("%b %-d, %Y"))
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("# {}\n\n", self.heading());
+        md.push_str(&format!("- **XP earned:** {}\n", self.xp));
+        md.push_str(&format!("- **Time studied:** {} min\n", self.active_minutes));
+        md.push_str(&format!("- **Nodes completed:** {}\n", self.completed.len()));
+        md.push_str(&format!("- **Reviews due next week:** {}\n", self.reviews_due_next_week));
+
+        if !self.completed.is_empty() {
+            md.push_str("\n## Completed\n\n");
+            for node in &self.completed {
+                md.push_str(&format!("- {}\n", node.title));
+            }
+        }
+
+        if !self.mastery.is_empty() {
+            md.push_str("\n## Mastery\n\n| Skill | From | To | Change |\n|---|---|---|---|\n");
+            for delta in &self.mastery {
+                md.push_str(&format!(
+                    "| {} | {:.0}% | {:.0}% | {:+.0} |\n",
+                    delta.skill_id.replace('|', "\\|"),
+                    delta.from * 100.0,
+                    delta.to * 100.0,
+                    delta.change() * 100.0,
+                ));
+            }
+        }
+
+        md
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{0}</title></head>\n<body>\n<h1>{0}</h1>\n<ul>\n",
+            escape_html(&self.heading())
+        );
+        html.push_str(&format!("<li><strong>XP earned:</strong> {}</li>\n", self.xp));
+        html.push_str(&format!("<li><strong>Time studied:</strong> {} min</li>\n", self.active_minutes));
+        html.push_str(&format!("<li><strong>Nodes completed:</strong> {}</li>\n", self.completed.len()));
+        html.push_str(&format!("<li><strong>Reviews due next week:</strong> {}</li>\n", self.reviews_due_next_week));
+        html.push_str("</ul>\n");
+
+        if !self.completed.is_empty() {
+            html.push_str("<h2>Completed</h2>\n<ul>\n");
+            for node in &self.completed {
+                html.push_str(&format!("<li>{}</li>\n", escape_html(&node.title)));
+            }
+            html.push_str("</ul>\n");
+        }
+
+        if !self.mastery.is_empty() {
+            html.push_str("<h2>Mastery</h2>\n<table>\n<tr><th>Skill</th><th>From</th><th>To</th><th>Change</th></tr>\n");
+            for delta in &self.mastery {
+                html.push_str(&format!(
+                    "<tr><td>{}</td><td>{:.0}%</td><td>{:.0}%</td><td>{:+.0}</td></tr>\n",
+                    escape_html(&delta.skill_id),
+                    delta.from * 100.0,
+                    delta.to * 100.0,
+                    delta.change() * 100.0,
+                ));
+            }
+            html.push_str("</table>\n");
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::{MasteryHistoryRepository, ProgressRepository, UserRepository};
+    use crate::models::{MasteryChangeReason, MasteryHistoryEntry, ReviewItem, User};
+
+    #[test]
+    fn test_weekly_report() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::new("test-user".to_string())).unwrap();
+
+        UserRepository::update_xp(conn, "test-user", 75).unwrap();
+        ProgressRepository::mark_completed(conn, "test-user", None, "ownership-lecture").unwrap();
+
+        let now = Utc::now();
+        for (previous, score, ago) in [(0.2, 0.4, 3), (0.4, 0.7, 1)] {
+            let mut entry = MasteryHistoryEntry::new(
+                "test-user".to_string(),
+                "ownership".to_string(),
+                previous,
+                score,
+                MasteryChangeReason::Quiz,
+            );
+            entry.recorded_at = now - Duration::days(ago);
+            MasteryHistoryRepository::record(conn, &entry).unwrap();
+        }
+
+        let mut review = ReviewItem::new("test-user".to_string(), "quiz-1".to_string());
+        review.due_date = now + Duration::days(3);
+        ReviewRepository::create_or_update(conn, &review).unwrap();
+
+        let mut report = weekly_report(conn, "test-user", None, now + Duration::seconds(1)).unwrap();
+        assert_eq!(report.xp, 75);
+        assert_eq!(report.completed.len(), 1);
+        assert_eq!(report.reviews_due_next_week, 1);
+        assert_eq!(report.mastery.len(), 1);
+        assert_eq!(report.mastery[0].from, 0.2);
+        assert_eq!(report.mastery[0].to, 0.7);
+
+        report.name_nodes(|id| (id == "ownership-lecture").then(|| "Ownership <basics>".to_string()));
+        let md = report.to_markdown();
+        assert!(md.contains("- Ownership <basics>\n"));
+        assert!(md.contains("| ownership | 20% | 70% | +50 |"));
+        let html = report.to_html();
+        assert!(html.contains("<li>Ownership &lt;basics&gt;</li>"));
+
+        // A week later none of it counts
+        let later = weekly_report(conn, "test-user", None, now + Duration::days(8)).unwrap();
+        assert_eq!(later.xp, 0);
+        assert!(later.completed.is_empty());
+        assert!(later.mastery.is_empty());
+    }
+}
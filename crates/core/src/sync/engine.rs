@@ -0,0 +1,222 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::Serialize;
+use crate::db::error::DbResult;
+use crate::db::in_savepoint;
+use crate::db::repos::{MasteryRepository, ProgressRepository, ReviewRepository, SyncRepository};
+use crate::models::{SyncOp, SyncRecord, SyncedRecord};
+use crate::sync::{oplog, SyncResult};
+
+/// Outcome of one sync
+#[derive(Debug, Clone, Default, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct SyncReport {
+    pub device_id: String,
+    /// Local changes written to this device's op-log
+    pub ops_sent: usize,
+    /// New ops read from other devices' logs
+    pub ops_received: usize,
+    /// Received ops that changed local data
+    pub ops_applied: usize,
+    /// Received ops that lost to a newer version of the same record
+    pub conflicts: usize,
+    /// Other devices with a log for this user
+    pub devices: usize,
+}
+
+/// Publish local changes to the sync folder, then apply other devices' changes
+pub fn sync_user(conn: &Connection, folder: &Path, user_id: &str) -> SyncResult<SyncReport> {
+    let device_id = SyncRepository::device_id(conn)?;
+    let mut report = SyncReport { device_id: device_id.clone(), ..Default::default() };
+
+    // Write the log before recording the ops as synced, so a failed write
+    // leaves the changes to be found again next time
+    let local_ops = local_changes(conn, user_id, &device_id, Utc::now())?;
+    oplog::append(&oplog::log_path(folder, user_id, &device_id), &local_ops)?;
+    in_savepoint(conn, "sync", || {
+        for op in &local_ops {
+            SyncRepository::save_record(conn, &SyncedRecord::from(op))?;
+        }
+        Ok(())
+    })?;
+    report.ops_sent = local_ops.len();
+
+    for (remote_device, path) in oplog::remote_logs(folder, user_id, &device_id)? {
+        let ops_read = SyncRepository::get_cursor(conn, user_id, &remote_device)?;
+        let ops = oplog::read_from(&path, ops_read)?;
+
+        in_savepoint(conn, "sync", || {
+            for op in ops.iter().filter(|op| op.user_id == user_id && op.record.user_id() == user_id) {
+                apply_remote(conn, op, &mut report)?;
+            }
+            SyncRepository::set_cursor(conn, user_id, &remote_device, ops_read + ops.len())
+        })?;
+
+        report.ops_received += ops.len();
+        report.devices += 1;
+    }
+
+    Ok(report)
+}
+
+fn current_records(conn: &Connection, user_id: &str) -> DbResult<Vec<SyncRecord>> {
+    let mut records = Vec::new();
+    for progress in ProgressRepository::get_all_for_user(conn, user_id)? {
+        records.push(SyncRecord::Progress(progress));
+    }
+    for mastery in MasteryRepository::get_all_for_user(conn, user_id)? {
+        records.push(SyncRecord::Mastery(mastery));
+    }
+    for review in ReviewRepository::get_all_for_user(conn, user_id)? {
+        records.push(SyncRecord::Review(review));
+    }
+    Ok(records)
+}
+
+/// Ops for records that changed or disappeared since they were last synced
+fn local_changes(conn: &Connection, user_id: &str, device_id: &str, now: DateTime<Utc>) -> DbResult<Vec<SyncOp>> {
+    let synced: HashMap<String, SyncedRecord> = SyncRepository::get_records(conn, user_id)?
+        .into_iter()
+        .map(|r| (r.record_key.clone(), r))
+        .collect();
+
+    let mut ops = Vec::new();
+    let mut present = HashSet::new();
+
+    for record in current_records(conn, user_id)? {
+        let key = record.key();
+        let previous = synced.get(&key);
+        present.insert(key);
+
+        if previous.is_some_and(|p| !p.deleted && p.content_hash == record.content_hash()) {
+            continue;
+        }
+
+        // Prefer the record's own change time so an edit made before the last
+        // sync doesn't beat a later edit on another device, unless that time
+        // would not order it after the version it replaces
+        let timestamp = record
+            .updated_at()
+            .filter(|t| previous.is_none_or(|p| *t > p.updated_at))
+            .unwrap_or(now);
+        ops.push(SyncOp::new(device_id.to_string(), timestamp, false, record));
+    }
+
+    for (key, previous) in &synced {
+        if !previous.deleted && !present.contains(key) {
+            ops.push(SyncOp::new(device_id.to_string(), now.max(previous.updated_at), true, previous.record.clone()));
+        }
+    }
+
+    Ok(ops)
+}
+
+fn apply_remote(conn: &Connection, op: &SyncOp, report: &mut SyncReport) -> DbResult<()> {
+    if let Some(current) = SyncRepository::get_record(conn, &op.user_id, &op.record.key())? {
+        if !op.supersedes(&current) {
+            if current.deleted != op.deleted || current.content_hash != op.record.content_hash() {
+                report.conflicts += 1;
+            }
+            return Ok(());
+        }
+    }
+
+    match (&op.record, op.deleted) {
+        (SyncRecord::Progress(p), false) => ProgressRepository::create_or_update(conn, p)?,
+        (SyncRecord::Progress(p), true) => ProgressRepository::delete(conn, &p.user_id, p.curriculum_id.as_deref(), &p.node_id)?,
+        (SyncRecord::Mastery(m), false) => MasteryRepository::create_or_update(conn, m)?,
+        (SyncRecord::Mastery(m), true) => MasteryRepository::delete(conn, &m.user_id, m.curriculum_id.as_deref(), &m.skill_id)?,
+        (SyncRecord::Review(r), false) => ReviewRepository::create_or_update(conn, r)?,
+        (SyncRecord::Review(r), true) => ReviewRepository::delete(conn, &r.user_id, r.curriculum_id.as_deref(), &r.quiz_id)?,
+    }
+    SyncRepository::save_record(conn, &SyncedRecord::from(op))?;
+    report.ops_applied += 1;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::{MasteryScore, NodeProgress, NodeStatus, User};
+    use chrono::Duration;
+    use tempfile::tempdir;
+
+    fn device() -> Database {
+        let db = Database::new_in_memory().unwrap();
+        UserRepository::create(db.connection(), &User::new("test-user".to_string())).unwrap();
+        db
+    }
+
+    fn progress(status: NodeStatus, updated_at: DateTime<Utc>) -> NodeProgress {
+        NodeProgress {
+            status,
+            last_updated_at: updated_at,
+            ..NodeProgress::new("test-user".to_string(), "week1-lecture".to_string())
+        }
+    }
+
+    fn stored_status(db: &Database) -> Option<NodeStatus> {
+        ProgressRepository::get(db.connection(), "test-user", None, "week1-lecture")
+            .unwrap()
+            .map(|p| p.status)
+    }
+
+    #[test]
+    fn test_changes_flow_between_devices() {
+        let folder = tempdir().unwrap();
+        let (laptop, desktop) = (device(), device());
+
+        ProgressRepository::create_or_update(laptop.connection(), &progress(NodeStatus::Completed, Utc::now())).unwrap();
+        let mastery = MasteryScore { score: 0.8, ..MasteryScore::new("test-user".to_string(), "ownership".to_string()) };
+        MasteryRepository::create_or_update(laptop.connection(), &mastery).unwrap();
+
+        let sent = sync_user(laptop.connection(), folder.path(), "test-user").unwrap();
+        assert_eq!(sent.ops_sent, 2);
+
+        let received = sync_user(desktop.connection(), folder.path(), "test-user").unwrap();
+        assert_eq!((received.ops_received, received.ops_applied, received.devices), (2, 2, 1));
+        assert_eq!(stored_status(&desktop), Some(NodeStatus::Completed));
+
+        // Applied ops are not echoed back, and nothing is re-read
+        let again = sync_user(desktop.connection(), folder.path(), "test-user").unwrap();
+        assert_eq!((again.ops_sent, again.ops_received), (0, 0));
+    }
+
+    #[test]
+    fn test_last_writer_wins_on_conflict() {
+        let folder = tempdir().unwrap();
+        let (laptop, desktop) = (device(), device());
+        let now = Utc::now();
+
+        ProgressRepository::create_or_update(laptop.connection(), &progress(NodeStatus::InProgress, now - Duration::hours(2))).unwrap();
+        ProgressRepository::create_or_update(desktop.connection(), &progress(NodeStatus::Completed, now - Duration::hours(1))).unwrap();
+
+        sync_user(laptop.connection(), folder.path(), "test-user").unwrap();
+        let report = sync_user(desktop.connection(), folder.path(), "test-user").unwrap();
+        assert_eq!(report.conflicts, 1);
+        sync_user(laptop.connection(), folder.path(), "test-user").unwrap();
+
+        assert_eq!(stored_status(&laptop), Some(NodeStatus::Completed));
+        assert_eq!(stored_status(&desktop), Some(NodeStatus::Completed));
+    }
+
+    #[test]
+    fn test_deletions_propagate() {
+        let folder = tempdir().unwrap();
+        let (laptop, desktop) = (device(), device());
+
+        ProgressRepository::create_or_update(laptop.connection(), &progress(NodeStatus::Completed, Utc::now())).unwrap();
+        sync_user(laptop.connection(), folder.path(), "test-user").unwrap();
+        sync_user(desktop.connection(), folder.path(), "test-user").unwrap();
+
+        ProgressRepository::delete(laptop.connection(), "test-user", None, "week1-lecture").unwrap();
+        assert_eq!(sync_user(laptop.connection(), folder.path(), "test-user").unwrap().ops_sent, 1);
+        sync_user(desktop.connection(), folder.path(), "test-user").unwrap();
+
+        assert_eq!(stored_status(&desktop), None);
+    }
+}
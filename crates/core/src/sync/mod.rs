@@ -0,0 +1,30 @@
+//! Cross-device sync through a shared folder
+//!
+//! Each device appends the changes it makes to progress, mastery and review
+//! state to its own op-log in a folder kept in step by a file sync tool such
+//! as Dropbox or Syncthing. Because no two devices write the same file, the
+//! sync tool never has to merge anything. Syncing reads the other devices'
+//! logs and applies each op unless the record has a newer version, so every
+//! device converges on the last write to each record.
+
+pub mod engine;
+pub mod oplog;
+
+pub use engine::{sync_user, SyncReport};
+
+use thiserror::Error;
+use crate::db::error::DbError;
+
+#[derive(Error, Debug)]
+pub enum SyncError {
+    #[error(transparent)]
+    Db(#[from] DbError),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid op-log {path}: {reason}")]
+    InvalidLog { path: String, reason: String },
+}
+
+pub type SyncResult<T> = Result<T, SyncError>;
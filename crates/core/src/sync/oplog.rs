@@ -0,0 +1,136 @@
+//! Op-log files
+//!
+//! Logs live at `<folder>/<user_id>/<device_id>.jsonl`, one JSON op per line.
+//! A final line without a newline is treated as still being written by the
+//! sync tool and left for the next sync.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use crate::models::SyncOp;
+use crate::sync::{SyncError, SyncResult};
+
+const LOG_EXTENSION: &str = "jsonl";
+
+pub fn log_path(folder: &Path, user_id: &str, device_id: &str) -> PathBuf {
+    folder.join(user_id).join(format!("{}.{}", device_id, LOG_EXTENSION))
+}
+
+/// Append ops to a log, creating it if needed
+pub fn append(path: &Path, ops: &[SyncOp]) -> SyncResult<()> {
+    if ops.is_empty() {
+        return Ok(());
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut lines = String::new();
+    for op in ops {
+        let line = serde_json::to_string(op).map_err(|e| SyncError::InvalidLog {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })?;
+        lines.push_str(&line);
+        lines.push('\n');
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(lines.as_bytes())?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Complete ops in a log after the first `skip`
+pub fn read_from(path: &Path, skip: usize) -> SyncResult<Vec<SyncOp>> {
+    let content = fs::read_to_string(path)?;
+    let complete = match content.rfind('\n') {
+        Some(end) => &content[..end],
+        None => return Ok(Vec::new()),
+    };
+
+    let mut ops = Vec::new();
+    for (line_number, line) in complete.lines().enumerate().skip(skip) {
+        let op = serde_json::from_str(line).map_err(|e| SyncError::InvalidLog {
+            path: path.display().to_string(),
+            reason: format!("line {}: {}", line_number + 1, e),
+        })?;
+        ops.push(op);
+    }
+    Ok(ops)
+}
+
+/// Logs written by other devices for a user, as (device ID, path)
+pub fn remote_logs(folder: &Path, user_id: &str, local_device_id: &str) -> SyncResult<Vec<(String, PathBuf)>> {
+    let dir = folder.join(user_id);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut logs = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(LOG_EXTENSION) {
+            continue;
+        }
+        let Some(device_id) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if device_id != local_device_id {
+            logs.push((device_id.to_string(), path));
+        }
+    }
+    logs.sort();
+    Ok(logs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NodeProgress, SyncRecord};
+    use chrono::Utc;
+    use tempfile::tempdir;
+
+    fn op() -> SyncOp {
+        let record = SyncRecord::Progress(NodeProgress::new("test-user".to_string(), "week1-lecture".to_string()));
+        SyncOp::new("device-a".to_string(), Utc::now(), false, record)
+    }
+
+    #[test]
+    fn test_append_and_read_from_cursor() {
+        let dir = tempdir().unwrap();
+        let path = log_path(dir.path(), "test-user", "device-a");
+
+        append(&path, &[op(), op()]).unwrap();
+        append(&path, &[op()]).unwrap();
+
+        assert_eq!(read_from(&path, 0).unwrap().len(), 3);
+        assert_eq!(read_from(&path, 2).unwrap().len(), 1);
+        assert!(read_from(&path, 3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_partial_line_is_left_for_later() {
+        let dir = tempdir().unwrap();
+        let path = log_path(dir.path(), "test-user", "device-a");
+        append(&path, &[op()]).unwrap();
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"id\":\"half").unwrap();
+
+        assert_eq!(read_from(&path, 0).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_remote_logs_skip_own_device() {
+        let dir = tempdir().unwrap();
+        append(&log_path(dir.path(), "test-user", "device-a"), &[op()]).unwrap();
+        append(&log_path(dir.path(), "test-user", "device-b"), &[op()]).unwrap();
+        fs::write(dir.path().join("test-user").join("notes.txt"), "not a log").unwrap();
+
+        let logs = remote_logs(dir.path(), "test-user", "device-a").unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].0, "device-b");
+        assert!(remote_logs(dir.path(), "other-user", "device-a").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,335 @@
+//! xAPI learning records
+//!
+//! Turns a learner's completed nodes, quiz attempts and graded checkpoint
+//! artifacts into xAPI statements, so a school's Learning Record Store and
+//! the LMS analytics behind it can take them in. Each statement's id is
+//! derived from the record it describes, so sending the same history twice
+//! doesn't duplicate it in the LRS.
+
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Builder;
+
+use crate::db::error::{DbError, DbResult};
+use crate::db::repos::{ArtifactRepository, ProgressRepository, QuizRepository, UserRepository};
+use crate::models::NodeStatus;
+
+/// xAPI version statements are written for
+pub const XAPI_VERSION: &str = "1.0.3";
+
+/// Home of the platform, under which activity ids and accounts are minted
+pub const PLATFORM_IRI: &str = "https://github.com/Aa-thomas/gamified-learning-platform";
+
+const VERB_COMPLETED: &str = "http://adlnet.gov/expapi/verbs/completed";
+const VERB_PASSED: &str = "http://adlnet.gov/expapi/verbs/passed";
+const VERB_FAILED: &str = "http://adlnet.gov/expapi/verbs/failed";
+const ACTIVITY_LESSON: &str = "http://adlnet.gov/expapi/activities/lesson";
+const ACTIVITY_ASSESSMENT: &str = "http://adlnet.gov/expapi/activities/assessment";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanguageMap {
+    #[serde(rename = "en-US")]
+    pub en_us: String,
+}
+
+impl LanguageMap {
+    fn new(text: impl Into<String>) -> Self {
+        Self { en_us: text.into() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Account {
+    pub home_page: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Actor {
+    pub object_type: String,
+    pub name: String,
+    pub account: Account,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Verb {
+    pub id: String,
+    pub display: LanguageMap,
+}
+
+impl Verb {
+    fn new(id: &str) -> Self {
+        let display = id.rsplit('/').next().unwrap_or(id);
+        Self { id: id.to_string(), display: LanguageMap::new(display) }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityDefinition {
+    pub name: LanguageMap,
+    #[serde(rename = "type")]
+    pub activity_type: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Activity {
+    pub object_type: String,
+    pub id: String,
+    pub definition: ActivityDefinition,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Score {
+    /// Between 0 and 1
+    pub scaled: f64,
+    pub raw: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Score {
+    fn percentage(percent: i32) -> Self {
+        let raw = percent.clamp(0, 100) as f64;
+        Self { scaled: raw / 100.0, raw, min: 0.0, max: 100.0 }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatementResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<Score>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion: Option<bool>,
+    /// ISO 8601 duration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Statement {
+    pub id: String,
+    pub actor: Actor,
+    pub verb: Verb,
+    pub object: Activity,
+    pub result: StatementResult,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Statement {
+    /// The node, quiz or checkpoint the statement is about
+    pub fn content_id(&self) -> &str {
+        self.activity_path().split('/').next().unwrap_or_default()
+    }
+
+    /// Activity id after the platform and curriculum: a content id, then
+    /// for artifacts `/` and the artifact type
+    fn activity_path(&self) -> &str {
+        self.object
+            .id
+            .strip_prefix(&format!("{}/xapi/", PLATFORM_IRI))
+            .and_then(|rest| rest.split_once('/'))
+            .map_or("", |(_, path)| path)
+    }
+}
+
+/// Statements for a user's learning records in a curriculum, oldest first.
+/// Graded artifacts aren't tied to a curriculum, so all of them are
+/// included. With `since`, only records from after it.
+pub fn statements(
+    conn: &Connection,
+    user_id: &str,
+    curriculum_id: Option<&str>,
+    since: Option<DateTime<Utc>>,
+) -> DbResult<Vec<Statement>> {
+    let user = UserRepository::get_by_id(conn, user_id)?
+        .ok_or_else(|| DbError::NotFound("User not found".to_string()))?;
+    let actor = Actor {
+        object_type: "Agent".to_string(),
+        name: user.display_name.clone().unwrap_or_else(|| user.id.clone()),
+        account: Account { home_page: PLATFORM_IRI.to_string(), name: user.id.clone() },
+    };
+    let scope = curriculum_id.unwrap_or("default");
+    let activity = |path: String, activity_type: &str, name: &str| Activity {
+        object_type: "Activity".to_string(),
+        id: format!("{}/xapi/{}/{}", PLATFORM_IRI, scope, path),
+        definition: ActivityDefinition { name: LanguageMap::new(name), activity_type: activity_type.to_string() },
+    };
+    let is_new = |at: &DateTime<Utc>| since.is_none_or(|since| *at > since);
+    let mut statements = Vec::new();
+
+    for progress in ProgressRepository::get_all_in_curriculum(conn, user_id, curriculum_id)? {
+        let Some(completed_at) = progress.completed_at.filter(|at| is_new(at)) else {
+            continue;
+        };
+        if progress.status != NodeStatus::Completed {
+            continue;
+        }
+        statements.push(Statement {
+            id: statement_id(&["completed", scope, user_id, &progress.node_id]),
+            actor: actor.clone(),
+            verb: Verb::new(VERB_COMPLETED),
+            object: activity(progress.node_id.clone(), ACTIVITY_LESSON, &progress.node_id),
+            result: StatementResult {
+                completion: Some(true),
+                duration: (progress.time_spent_mins > 0).then(|| format!("PT{}M", progress.time_spent_mins)),
+                ..Default::default()
+            },
+            timestamp: completed_at,
+        });
+    }
+
+    for attempt in QuizRepository::get_all_in_curriculum(conn, user_id, curriculum_id)? {
+        if !is_new(&attempt.submitted_at) {
+            continue;
+        }
+        let passed = attempt.passed();
+        statements.push(Statement {
+            id: statement_id(&["quiz", &attempt.id]),
+            actor: actor.clone(),
+            verb: Verb::new(if passed { VERB_PASSED } else { VERB_FAILED }),
+            object: activity(attempt.quiz_id.clone(), ACTIVITY_ASSESSMENT, &attempt.quiz_id),
+            result: StatementResult {
+                score: Some(Score::percentage(attempt.score_percentage)),
+                success: Some(passed),
+                completion: Some(true),
+                duration: attempt
+                    .started_at
+                    .map(|started| format!("PT{}S", (attempt.submitted_at - started).num_seconds().max(0))),
+            },
+            timestamp: attempt.submitted_at,
+        });
+    }
+
+    for submission in ArtifactRepository::get_all_for_user(conn, user_id)? {
+        let (Some(grade), Some(graded_at)) = (submission.grade_percentage, submission.graded_at) else {
+            continue;
+        };
+        if !is_new(&graded_at) {
+            continue;
+        }
+        let passed = submission.passed();
+        let artifact = submission.artifact_type.as_str();
+        statements.push(Statement {
+            id: statement_id(&["artifact", &submission.id]),
+            actor: actor.clone(),
+            verb: Verb::new(if passed { VERB_PASSED } else { VERB_FAILED }),
+            object: activity(
+                format!("{}/{}", submission.checkpoint_id, artifact),
+                ACTIVITY_ASSESSMENT,
+                &format!("{} {}", submission.checkpoint_id, artifact),
+            ),
+            result: StatementResult {
+                score: Some(Score::percentage(grade)),
+                success: Some(passed),
+                completion: Some(true),
+                duration: None,
+            },
+            timestamp: graded_at,
+        });
+    }
+
+    statements.sort_by_key(|statement| statement.timestamp);
+    Ok(statements)
+}
+
+/// Give activities readable names where `title_of` knows the node, quiz or
+/// checkpoint they're about
+pub fn name_activities(statements: &mut [Statement], title_of: impl Fn(&str) -> Option<String>) {
+    for statement in statements {
+        let Some(title) = title_of(statement.content_id()) else {
+            continue;
+        };
+        let name = match statement.activity_path().split_once('/') {
+            Some((_, artifact)) => format!("{} {}", title, artifact),
+            None => title,
+        };
+        statement.object.definition.name = LanguageMap::new(name);
+    }
+}
+
+/// A stable UUID for a record, shaped as a name-based (version 5) UUID so
+/// LRSs that check the version accept it
+fn statement_id(parts: &[&str]) -> String {
+    let digest = Sha256::digest(parts.join("\u{1f}").as_bytes());
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    Builder::from_sha1_bytes(bytes).into_uuid().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::Database;
+    use crate::db::repos::UserRepository;
+    use crate::models::{ArtifactSubmission, ArtifactType, NodeProgress, QuizAttempt, User};
+
+    #[test]
+    fn test_statements_for_learning_records() {
+        let db = Database::new_in_memory().unwrap();
+        let conn = db.connection();
+        UserRepository::create(conn, &User::with_display_name("test-user".to_string(), "Ferris".to_string())).unwrap();
+
+        let mut progress = NodeProgress::new("test-user".to_string(), "lecture-1".to_string());
+        progress.time_spent_mins = 12;
+        progress.complete();
+        ProgressRepository::create_or_update(conn, &progress).unwrap();
+
+        let attempt = QuizAttempt::new(
+            "test-user".to_string(),
+            "quiz-1".to_string(),
+            "quiz-1".to_string(),
+            vec![],
+            85,
+            10,
+        );
+        QuizRepository::create(conn, &attempt).unwrap();
+
+        let mut submission = ArtifactSubmission::new(
+            "test-user".to_string(),
+            "checkpoint-1".to_string(),
+            ArtifactType::Readme,
+            "# KV store",
+        );
+        submission.set_grade(55, "{}".to_string(), 0);
+        ArtifactRepository::create(conn, &submission).unwrap();
+
+        let mut all = statements(conn, "test-user", None, None).unwrap();
+        assert_eq!(all.len(), 3);
+        let completed = all.iter().find(|s| s.verb.id == VERB_COMPLETED).unwrap();
+        assert_eq!(completed.actor.name, "Ferris");
+        assert_eq!(completed.result.duration.as_deref(), Some("PT12M"));
+        let quiz = all.iter().find(|s| s.content_id() == "quiz-1").unwrap();
+        assert_eq!(quiz.verb.display.en_us, "passed");
+        assert_eq!(quiz.result.score.as_ref().unwrap().scaled, 0.85);
+        let artifact = all.iter().find(|s| s.object.id.ends_with("checkpoint-1/README")).unwrap();
+        assert_eq!(artifact.result.success, Some(false));
+
+        // Ids are stable, so re-sending doesn't duplicate statements
+        let again = statements(conn, "test-user", None, None).unwrap();
+        assert_eq!(again.iter().map(|s| &s.id).collect::<Vec<_>>(), all.iter().map(|s| &s.id).collect::<Vec<_>>());
+        assert!(statements(conn, "test-user", None, Some(Utc::now())).unwrap().is_empty());
+
+        name_activities(&mut all, |id| match id {
+            "lecture-1" => Some("Ownership".to_string()),
+            "checkpoint-1" => Some("KV store".to_string()),
+            _ => None,
+        });
+        let names: Vec<&str> = all.iter().map(|s| s.object.definition.name.en_us.as_str()).collect();
+        assert!(names.contains(&"Ownership"));
+        assert!(names.contains(&"KV store README"));
+
+        let json = serde_json::to_value(&all[0]).unwrap();
+        assert!(json["actor"]["account"]["homePage"].is_string());
+        assert!(json["object"]["definition"]["name"]["en-US"].is_string());
+    }
+}
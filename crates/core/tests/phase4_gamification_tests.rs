@@ -7,9 +7,9 @@ use chrono::{Duration, Utc};
 use glp_core::{
     badges::{
         check_badge_unlocks, check_single_badge, calculate_badge_progress,
-        get_all_badge_definitions, get_badge_by_id, UserStats,
+        get_all_badge_definitions, get_badge_by_id, next_tier_progress, UserStats,
     },
-    models::{BadgeCategory, BadgeDefinition, BadgeProgress, MasteryScore, ReviewItem},
+    models::{BadgeCategory, BadgeDefinition, BadgeProgress, BadgeTier, MasteryScore, ReviewItem},
     spaced_repetition::{
         apply_mastery_decay, score_to_quality, ReviewQuality,
     },
@@ -38,63 +38,56 @@ fn test_badge_definitions_comprehensive() {
 
 #[test]
 fn test_badge_unlock_streak_progression() {
-    // Test the 3 streak badges unlock at correct thresholds
-    let week_warrior = get_badge_by_id("week_warrior").unwrap();
-    let streak_master = get_badge_by_id("streak_master").unwrap();
-    let unstoppable = get_badge_by_id("unstoppable").unwrap();
+    // The streak badge family is one tiered badge with shared progress
+    let streak_keeper = get_badge_by_id("streak_keeper").unwrap();
     
-    assert_eq!(week_warrior.threshold, 7.0);
-    assert_eq!(streak_master.threshold, 30.0);
-    assert_eq!(unstoppable.threshold, 100.0);
+    assert_eq!(streak_keeper.threshold, 7.0);
+    let thresholds: Vec<f64> = streak_keeper.tiers.iter().map(|t| t.threshold).collect();
+    assert_eq!(thresholds, vec![7.0, 30.0, 100.0]);
     
-    // 6-day streak should not unlock week_warrior
+    // 6-day streak should not unlock any tier
     let stats_6_days = UserStats {
         streak_days: 6,
         ..Default::default()
     };
-    assert!(!check_single_badge(&week_warrior, &stats_6_days));
+    assert!(!check_single_badge(&streak_keeper, &stats_6_days));
+    assert_eq!(streak_keeper.tier_for_value(6.0), None);
     
-    // 7-day streak should unlock week_warrior but not streak_master
+    // 7-day streak should unlock bronze but not silver
     let stats_7_days = UserStats {
         streak_days: 7,
         ..Default::default()
     };
-    assert!(check_single_badge(&week_warrior, &stats_7_days));
-    assert!(!check_single_badge(&streak_master, &stats_7_days));
+    assert!(check_single_badge(&streak_keeper, &stats_7_days));
+    assert_eq!(streak_keeper.tier_for_value(7.0), Some(BadgeTier::Bronze));
     
-    // 30-day streak should unlock both week_warrior and streak_master
-    let stats_30_days = UserStats {
-        streak_days: 30,
-        ..Default::default()
-    };
-    assert!(check_single_badge(&week_warrior, &stats_30_days));
-    assert!(check_single_badge(&streak_master, &stats_30_days));
-    assert!(!check_single_badge(&unstoppable, &stats_30_days));
+    // 30-day streak should reach silver but not gold
+    assert_eq!(streak_keeper.tier_for_value(30.0), Some(BadgeTier::Silver));
+    let next = next_tier_progress(&streak_keeper, &UserStats { streak_days: 30, ..Default::default() }, None).unwrap();
+    assert_eq!(next.tier, BadgeTier::Gold);
 }
 
 #[test]
 fn test_badge_unlock_xp_progression() {
-    let xp_hunter = get_badge_by_id("xp_hunter").unwrap();
-    let xp_collector = get_badge_by_id("xp_collector").unwrap();
-    let xp_legend = get_badge_by_id("xp_legend").unwrap();
+    let xp_earner = get_badge_by_id("xp_earner").unwrap();
     
-    assert_eq!(xp_hunter.threshold, 1000.0);
-    assert_eq!(xp_collector.threshold, 5000.0);
-    assert_eq!(xp_legend.threshold, 10000.0);
+    let thresholds: Vec<f64> = xp_earner.tiers.iter().map(|t| t.threshold).collect();
+    assert_eq!(thresholds, vec![1000.0, 5000.0, 10000.0]);
     
     // Test boundary conditions
     let stats_999 = UserStats { total_xp: 999, ..Default::default() };
     let stats_1000 = UserStats { total_xp: 1000, ..Default::default() };
-    let stats_5000 = UserStats { total_xp: 5000, ..Default::default() };
     
-    assert!(!check_single_badge(&xp_hunter, &stats_999));
-    assert!(check_single_badge(&xp_hunter, &stats_1000));
-    assert!(check_single_badge(&xp_collector, &stats_5000));
+    assert!(!check_single_badge(&xp_earner, &stats_999));
+    assert!(check_single_badge(&xp_earner, &stats_1000));
+    assert_eq!(xp_earner.tier_for_value(4999.0), Some(BadgeTier::Bronze));
+    assert_eq!(xp_earner.tier_for_value(5000.0), Some(BadgeTier::Silver));
+    assert_eq!(xp_earner.tier_for_value(10000.0), Some(BadgeTier::Gold));
 }
 
 #[test]
 fn test_badge_progress_calculation() {
-    let badge = get_badge_by_id("week_warrior").unwrap();
+    let badge = get_badge_by_id("streak_keeper").unwrap();
     
     let stats_0 = UserStats { streak_days: 0, ..Default::default() };
     let stats_3 = UserStats { streak_days: 3, ..Default::default() };
@@ -275,12 +268,14 @@ fn test_mastery_decay_grace_period() {
     let mut masteries = vec![
         MasteryScore {
             user_id: "user1".to_string(),
+            curriculum_id: None,
             skill_id: "skill1".to_string(),
             score: 0.8,
             last_updated_at: Utc::now() - Duration::days(2),
         },
         MasteryScore {
             user_id: "user1".to_string(),
+            curriculum_id: None,
             skill_id: "skill2".to_string(),
             score: 0.8,
             last_updated_at: Utc::now() - Duration::days(3),
@@ -300,6 +295,7 @@ fn test_mastery_decay_after_grace_period() {
     let mut masteries = vec![
         MasteryScore {
             user_id: "user1".to_string(),
+            curriculum_id: None,
             skill_id: "skill1".to_string(),
             score: 0.8,
             last_updated_at: Utc::now() - Duration::days(10),
@@ -323,6 +319,7 @@ fn test_mastery_minimum_floor() {
     let mut masteries = vec![
         MasteryScore {
             user_id: "user1".to_string(),
+            curriculum_id: None,
             skill_id: "skill1".to_string(),
             score: 0.4,
             last_updated_at: Utc::now() - Duration::days(100), // Very old
@@ -340,18 +337,21 @@ fn test_mastery_decay_mixed_skills() {
     let mut masteries = vec![
         MasteryScore {
             user_id: "user1".to_string(),
+            curriculum_id: None,
             skill_id: "fresh".to_string(),
             score: 0.9,
             last_updated_at: Utc::now() - Duration::days(1),
         },
         MasteryScore {
             user_id: "user1".to_string(),
+            curriculum_id: None,
             skill_id: "medium".to_string(),
             score: 0.8,
             last_updated_at: Utc::now() - Duration::days(7),
         },
         MasteryScore {
             user_id: "user1".to_string(),
+            curriculum_id: None,
             skill_id: "stale".to_string(),
             score: 0.7,
             last_updated_at: Utc::now() - Duration::days(30),
@@ -422,6 +422,7 @@ fn test_decay_formula_matches_prototype() {
         let mut masteries = vec![
             MasteryScore {
                 user_id: "user1".to_string(),
+                curriculum_id: None,
                 skill_id: "test".to_string(),
                 score: initial_score,
                 last_updated_at: Utc::now() - Duration::days(days_inactive),
@@ -86,6 +86,7 @@ impl GradeCache {
                 category_scores,
                 from_cache: true,
                 latency_ms: 0,
+                self_assessed: false,
             })
         });
 
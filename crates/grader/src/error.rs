@@ -5,9 +5,15 @@ use thiserror::Error;
 /// Errors that can occur during LLM-based grading
 #[derive(Debug, Error)]
 pub enum GraderError {
-    #[error("OpenAI API error: {0}")]
+    #[error("LLM API error: {0}")]
     ApiError(String),
 
+    #[error("No API key configured for {0}")]
+    MissingApiKey(String),
+
+    #[error("Invalid API key for {0}")]
+    InvalidApiKey(String),
+
     #[error("Rate limit exceeded. Retry after {0}s")]
     RateLimit(u64),
 
@@ -23,6 +29,12 @@ pub enum GraderError {
     #[error("Rubric not found: {0}")]
     RubricNotFound(String),
 
+    #[error("Invalid self-assessment: {0}")]
+    InvalidSelfAssessment(String),
+
+    #[error("Git error: {0}")]
+    Git(String),
+
     #[error("Cache error: {0}")]
     CacheError(String),
 
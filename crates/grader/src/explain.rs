@@ -0,0 +1,68 @@
+//! Explanations of wrong quiz answers
+//!
+//! A quiz question's stock explanation covers why the right answer is right.
+//! This asks the model why the option the learner actually chose is wrong,
+//! grounded in an excerpt of the lecture the quiz covers.
+
+use serde::{Deserialize, Serialize};
+
+/// Lecture text beyond this is cut from the prompt
+const MAX_EXCERPT_CHARS: usize = 6000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplainedMistake {
+    pub explanation: String,
+    /// Latency in milliseconds
+    pub latency_ms: u64,
+}
+
+pub(crate) fn build_system_message() -> String {
+    r#"You are a patient Rust instructor in a Rust bootcamp. A learner chose a wrong answer on a quiz question.
+
+Explain their mistake:
+1. Start from the misconception that makes the chosen answer look right
+2. Show why it is wrong and why the correct answer holds, using the lecture where it helps
+3. Keep it to one short paragraph of plain text, with inline code only where needed
+4. Be encouraging without being patronising"#
+        .to_string()
+}
+
+pub(crate) fn build_user_message(question: &str, chosen: &str, correct: &str, lecture_excerpt: &str) -> String {
+    let excerpt: String = lecture_excerpt.chars().take(MAX_EXCERPT_CHARS).collect();
+    let excerpt = if excerpt.trim().is_empty() { "(no lecture available)".to_string() } else { excerpt };
+
+    format!(
+        r#"## Question
+{}
+
+## Learner's Answer
+{}
+
+## Correct Answer
+{}
+
+## Lecture Excerpt
+{}"#,
+        question, chosen, correct, excerpt
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_message_includes_answers_and_excerpt() {
+        let msg = build_user_message("Which keyword makes a binding mutable?", "var", "let mut", "Bindings are immutable by default.");
+        assert!(msg.contains("## Learner's Answer\nvar"));
+        assert!(msg.contains("## Correct Answer\nlet mut"));
+        assert!(msg.contains("immutable by default"));
+    }
+
+    #[test]
+    fn test_long_excerpt_is_cut() {
+        let msg = build_user_message("Q", "a", "b", &"x".repeat(MAX_EXCERPT_CHARS * 2));
+        assert!(msg.len() < MAX_EXCERPT_CHARS + 100);
+        assert!(build_user_message("Q", "a", "b", "  ").contains("(no lecture available)"));
+    }
+}
@@ -0,0 +1,198 @@
+//! Checkpoint artifacts from a git repo
+//!
+//! Rather than pasting DESIGN.md or README.md into the app, a learner can
+//! point a checkpoint at the repo they built it in. Artifacts are read as
+//! committed at HEAD, not from the working tree, so a grade always refers
+//! to a commit that can be checked out again. The grader also sees a
+//! bounded diff of the latest commits, to judge the docs against the work.
+
+use std::path::Path;
+use std::process::Command;
+
+use crate::error::GraderError;
+
+/// How much of the recent history's diff goes to the grader by default
+pub const DEFAULT_DIFF_LIMIT: usize = 12_000;
+
+/// How many commits back the diff reaches by default
+pub const DEFAULT_DIFF_COMMITS: usize = 5;
+
+/// A repo as committed at HEAD
+#[derive(Debug, Clone)]
+pub struct RepoSnapshot {
+    /// Full hash of HEAD
+    pub commit_hash: String,
+    pub readme: Option<String>,
+    pub design: Option<String>,
+    /// Patches of the latest commits, newest first, cut to the diff limit
+    pub recent_diff: String,
+    /// The diff was longer than the limit
+    pub diff_truncated: bool,
+}
+
+impl RepoSnapshot {
+    /// Read a repo's HEAD, with the diff of up to `commits` commits cut to
+    /// `diff_limit` bytes
+    pub fn read(repo: &Path, commits: usize, diff_limit: usize) -> Result<Self, GraderError> {
+        let commit_hash = git(repo, &["rev-parse", "--verify", "HEAD"])?.trim().to_string();
+        let log = git(
+            repo,
+            &["log", "-p", "--no-color", "--no-ext-diff", &format!("-n{}", commits.max(1)), "HEAD"],
+        )?;
+        let (recent_diff, diff_truncated) = truncate(&log, diff_limit);
+
+        Ok(Self {
+            readme: committed_file(repo, "README.md")?,
+            design: committed_file(repo, "DESIGN.md")?,
+            commit_hash,
+            recent_diff: recent_diff.to_string(),
+            diff_truncated,
+        })
+    }
+
+    /// Short form of the commit hash, for display
+    pub fn short_hash(&self) -> &str {
+        &self.commit_hash[..self.commit_hash.len().min(7)]
+    }
+
+    /// The committed artifact for a type name such as `README` or `DESIGN`
+    pub fn artifact(&self, artifact_type: &str) -> Option<&str> {
+        match artifact_type {
+            "README" => self.readme.as_deref(),
+            "DESIGN" => self.design.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// What the grader is given for an artifact: the document, followed by
+    /// the recent commits as context
+    pub fn grading_content(&self, artifact: &str) -> String {
+        if self.recent_diff.is_empty() {
+            return artifact.to_string();
+        }
+        let omitted = if self.diff_truncated { "\n[Older changes are omitted]" } else { "" };
+        format!(
+            "{}\n\n---\n\n## Recent commits (context for grading, not part of the artifact)\n\n```diff\n{}\n```{}\n",
+            artifact.trim_end(),
+            self.recent_diff.trim_end(),
+            omitted
+        )
+    }
+}
+
+/// Paths of `template` missing from the repo's HEAD. An entry ending in `/`
+/// is a directory, present when any committed file is under it.
+pub fn missing_template_files(repo: &Path, template: &[String]) -> Result<Vec<String>, GraderError> {
+    let tracked = git(repo, &["ls-tree", "-r", "--name-only", "HEAD"])?;
+    let tracked: Vec<&str> = tracked.lines().collect();
+
+    Ok(template
+        .iter()
+        .filter(|expected| {
+            let present = match expected.strip_suffix('/') {
+                Some(dir) => tracked.iter().any(|path| path.starts_with(&format!("{}/", dir))),
+                None => tracked.contains(&expected.as_str()),
+            };
+            !present
+        })
+        .cloned()
+        .collect())
+}
+
+/// A file's contents at HEAD, or `None` if it isn't committed
+fn committed_file(repo: &Path, path: &str) -> Result<Option<String>, GraderError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["show", &format!("HEAD:{}", path)])
+        .output()?;
+    if output.status.success() {
+        Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+    } else {
+        Ok(None)
+    }
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<String, GraderError> {
+    if !repo.is_dir() {
+        return Err(GraderError::Git(format!("{} is not a directory", repo.display())));
+    }
+    let output = Command::new("git").arg("-C").arg(repo).args(args).output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = if stderr.contains("not a git repository") {
+            format!("{} is not a git repository", repo.display())
+        } else if stderr.contains("unknown revision") || stderr.contains("Needed a single revision") {
+            format!("{} has no commits yet", repo.display())
+        } else {
+            stderr.trim().to_string()
+        };
+        return Err(GraderError::Git(message));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn truncate(text: &str, limit: usize) -> (&str, bool) {
+    if text.len() <= limit {
+        return (text, false);
+    }
+    let mut end = limit;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    (&text[..end], true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn commit(repo: &Path, path: &str, contents: &str) {
+        let file = repo.join(path);
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(file, contents).unwrap();
+        for args in [
+            vec!["add", "-A"],
+            vec!["-c", "user.name=Test", "-c", "user.email=test@example.com", "commit", "-qm", path],
+        ] {
+            assert!(Command::new("git").arg("-C").arg(repo).args(args).status().unwrap().success());
+        }
+    }
+
+    #[test]
+    fn test_snapshot_reads_committed_artifacts() {
+        let dir = tempfile::tempdir().unwrap();
+        if Command::new("git").args(["init", "-q"]).arg(dir.path()).status().is_err() {
+            return; // git isn't installed
+        }
+        assert!(matches!(RepoSnapshot::read(dir.path(), 5, 1000), Err(GraderError::Git(_))));
+
+        commit(dir.path(), "Cargo.toml", "[package]\nname = \"kv\"\n");
+        commit(dir.path(), "src/lib.rs", "pub fn get() {}\n");
+        commit(dir.path(), "README.md", "# KV store\n");
+        // Uncommitted edits aren't graded
+        fs::write(dir.path().join("README.md"), "# Draft\n").unwrap();
+
+        let snapshot = RepoSnapshot::read(dir.path(), 2, 100_000).unwrap();
+        assert_eq!(snapshot.commit_hash.len(), 40);
+        assert_eq!(snapshot.artifact("README"), Some("# KV store\n"));
+        assert_eq!(snapshot.artifact("DESIGN"), None);
+        assert!(snapshot.recent_diff.contains("+pub fn get() {}"));
+        assert!(!snapshot.recent_diff.contains("name = \"kv\""));
+        assert!(snapshot.grading_content("# KV store\n").contains("## Recent commits"));
+
+        let bounded = RepoSnapshot::read(dir.path(), 5, 40).unwrap();
+        assert!(bounded.diff_truncated);
+        assert_eq!(bounded.recent_diff.len(), 40);
+
+        let template = vec!["Cargo.toml".to_string(), "src/".to_string(), "tests/".to_string()];
+        assert_eq!(missing_template_files(dir.path(), &template).unwrap(), vec!["tests/".to_string()]);
+    }
+
+    #[test]
+    fn test_truncate_keeps_char_boundaries() {
+        assert_eq!(truncate("héllo", 2), ("h", true));
+        assert_eq!(truncate("hello", 10), ("hello", false));
+    }
+}
@@ -0,0 +1,237 @@
+//! Checkpoint submissions from GitHub
+//!
+//! A capstone checkpoint can be submitted as a public GitHub repo. It's
+//! cloned shallowly into a temporary directory that goes away with the
+//! clone, with symlinks checked out as plain files, no submodules and no
+//! credential prompts, so a repo can't point outside its directory or hang
+//! waiting for a password. Repos over the size cap are refused before
+//! cloning when GitHub reports their size, and after cloning otherwise.
+
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use tempfile::TempDir;
+
+use crate::error::GraderError;
+
+/// Largest repo accepted, in bytes
+pub const MAX_REPO_BYTES: u64 = 50 * 1024 * 1024;
+
+const CLONE_TIMEOUT_SECS: u64 = 120;
+const API_TIMEOUT_SECS: u64 = 10;
+
+/// A repository on github.com
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GithubRepo {
+    pub owner: String,
+    pub name: String,
+}
+
+impl GithubRepo {
+    /// Parse `https://github.com/owner/repo`, with or without the scheme, a
+    /// `.git` suffix or a trailing path such as `/tree/main`
+    pub fn parse(url: &str) -> Result<Self, GraderError> {
+        let invalid = || GraderError::Git(format!("{} isn't a GitHub repository URL", url));
+        let rest = url.trim().trim_start_matches("https://").trim_start_matches("http://");
+        let rest = rest.strip_prefix("www.").unwrap_or(rest);
+        let rest = rest.strip_prefix("github.com/").ok_or_else(invalid)?;
+
+        let mut parts = rest.split('/');
+        let owner = parts.next().unwrap_or_default();
+        let name = parts.next().unwrap_or_default();
+        let name = name.strip_suffix(".git").unwrap_or(name);
+
+        let valid = |part: &str| {
+            !part.is_empty()
+                && !part.starts_with('.')
+                && !part.starts_with('-')
+                && part.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        };
+        if !valid(owner) || !valid(name) {
+            return Err(invalid());
+        }
+        Ok(Self { owner: owner.to_string(), name: name.to_string() })
+    }
+
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.owner, self.name)
+    }
+
+    pub fn clone_url(&self) -> String {
+        format!("https://github.com/{}.git", self.full_name())
+    }
+
+    fn not_found(&self) -> GraderError {
+        GraderError::Git(format!(
+            "github.com/{} wasn't found. Private repos can't be submitted; make it public or check the URL",
+            self.full_name()
+        ))
+    }
+}
+
+#[derive(Deserialize)]
+struct RepoMetadata {
+    /// Size in KiB, as GitHub reports it
+    size: u64,
+    #[serde(default)]
+    private: bool,
+}
+
+/// Check with the GitHub API that a repo is public and under the size cap.
+/// GitHub's own rate limit on anonymous requests only skips the check, as
+/// the clone is capped too.
+pub async fn check_repo(repo: &GithubRepo) -> Result<(), GraderError> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(API_TIMEOUT_SECS))
+        .user_agent("gamified-learning-platform")
+        .build()
+        .map_err(|e| GraderError::Git(e.to_string()))?;
+
+    let response = client
+        .get(format!("https://api.github.com/repos/{}", repo.full_name()))
+        .send()
+        .await
+        .map_err(|e| GraderError::Git(format!("Could not reach GitHub: {}", e)))?;
+    match response.status().as_u16() {
+        200..=299 => {}
+        404 => return Err(repo.not_found()),
+        code => {
+            tracing::warn!(repo = %repo.full_name(), status = code, "Skipped GitHub repo check");
+            return Ok(());
+        }
+    }
+
+    let body = response.text().await.map_err(|e| GraderError::Git(e.to_string()))?;
+    let metadata: RepoMetadata = serde_json::from_str(&body)
+        .map_err(|e| GraderError::ParseError(format!("Invalid GitHub response: {}", e)))?;
+    if metadata.private {
+        return Err(repo.not_found());
+    }
+    check_size(repo, metadata.size * 1024)
+}
+
+/// A shallow clone, deleted when dropped
+#[derive(Debug)]
+pub struct ClonedRepo {
+    dir: TempDir,
+    pub repo: GithubRepo,
+}
+
+impl ClonedRepo {
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
+/// Clone the last `depth` commits of a repo's default branch. Blocks until
+/// the clone finishes or times out.
+pub fn shallow_clone(repo: &GithubRepo, depth: usize) -> Result<ClonedRepo, GraderError> {
+    let dir = tempfile::tempdir()?;
+    let mut child = Command::new("git")
+        .args(["-c", "core.symlinks=false", "-c", "protocol.file.allow=never", "clone", "-q"])
+        .args(["--depth", &depth.max(1).to_string(), "--single-branch", "--no-tags"])
+        .arg(repo.clone_url())
+        .arg(dir.path())
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_LFS_SKIP_SMUDGE", "1")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let deadline = Instant::now() + Duration::from_secs(CLONE_TIMEOUT_SECS);
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(GraderError::Git(format!(
+                "Cloning {} took over {}s",
+                repo.full_name(),
+                CLONE_TIMEOUT_SECS
+            )));
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    };
+
+    if !status.success() {
+        let mut stderr = String::new();
+        if let Some(mut pipe) = child.stderr.take() {
+            pipe.read_to_string(&mut stderr)?;
+        }
+        // Without credentials GitHub answers for a private repo as for a missing one
+        if stderr.contains("could not read Username")
+            || stderr.contains("Repository not found")
+            || stderr.contains("Authentication failed")
+        {
+            return Err(repo.not_found());
+        }
+        return Err(GraderError::Git(format!("Couldn't clone {}: {}", repo.full_name(), stderr.trim())));
+    }
+
+    check_size(repo, dir_size(dir.path())?)?;
+    Ok(ClonedRepo { dir, repo: repo.clone() })
+}
+
+fn check_size(repo: &GithubRepo, bytes: u64) -> Result<(), GraderError> {
+    if bytes > MAX_REPO_BYTES {
+        return Err(GraderError::Git(format!(
+            "{} is {} MB; submitted repos can be at most {} MB",
+            repo.full_name(),
+            bytes / (1024 * 1024),
+            MAX_REPO_BYTES / (1024 * 1024)
+        )));
+    }
+    Ok(())
+}
+
+fn dir_size(dir: &Path) -> Result<u64, GraderError> {
+    let mut total = 0;
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let metadata = fs::symlink_metadata(&path)?;
+        total += if metadata.is_dir() { dir_size(&path)? } else { metadata.len() };
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_github_urls() {
+        let expected = GithubRepo { owner: "ferris".to_string(), name: "kv-store".to_string() };
+        for url in [
+            "https://github.com/ferris/kv-store",
+            "https://github.com/ferris/kv-store.git",
+            "github.com/ferris/kv-store/tree/main",
+            " https://www.github.com/ferris/kv-store/ ",
+        ] {
+            assert_eq!(GithubRepo::parse(url).unwrap(), expected, "{}", url);
+        }
+        assert_eq!(expected.clone_url(), "https://github.com/ferris/kv-store.git");
+
+        for url in [
+            "https://gitlab.com/ferris/kv-store",
+            "https://github.com/ferris",
+            "https://github.com/ferris/--upload-pack=x",
+            "https://github.com/../etc",
+        ] {
+            assert!(matches!(GithubRepo::parse(url), Err(GraderError::Git(_))), "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_size_cap() {
+        let repo = GithubRepo::parse("github.com/ferris/kv-store").unwrap();
+        assert!(check_size(&repo, MAX_REPO_BYTES).is_ok());
+        assert!(check_size(&repo, MAX_REPO_BYTES + 1).is_err());
+    }
+}
@@ -1,16 +1,39 @@
 //! LLM-based artifact grading
 //!
 //! This crate provides functionality to grade student artifacts
-//! (DESIGN.md, README.md, etc.) using GPT-4 with caching.
+//! (DESIGN.md, README.md, etc.), pasted in or collected from a git or GitHub
+//! repo, using OpenAI, Anthropic or a local Ollama model, with caching, to
+//! review challenge code, to tutor learners, to explain wrong quiz answers,
+//! to draft quiz questions and practice challenges and to embed content for
+//! related-content suggestions. Learners without a provider can score an
+//! artifact against its rubric themselves.
 
 pub mod error;
 pub mod cache;
+pub mod explain;
+pub mod git;
+pub mod github;
 pub mod rubrics;
 pub mod llm;
+pub mod practice;
+pub mod provider;
+pub mod quiz_gen;
+pub mod review;
+pub mod self_assessment;
+pub mod tutor;
 pub mod types;
 
 pub use error::GraderError;
 pub use cache::GradeCache;
+pub use explain::ExplainedMistake;
+pub use git::RepoSnapshot;
+pub use github::{ClonedRepo, GithubRepo};
 pub use rubrics::Rubric;
 pub use llm::LLMGrader;
+pub use practice::GeneratedChallenge;
+pub use provider::{check_credentials, LlmProvider, ProviderCheck, ProviderCredentials};
+pub use quiz_gen::{GeneratedQuiz, QuizQuestion};
+pub use review::{CodeReview, CommentSeverity, ReviewComment};
+pub use self_assessment::{self_assess, SelfScore};
+pub use tutor::{ChatMessage, ChatRole, SkillMastery, TokenBudget, TutorContext, TutorReply};
 pub use types::{GradeResult, CategoryScore};
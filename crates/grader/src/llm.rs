@@ -1,12 +1,13 @@
-//! LLM-based artifact grading using OpenAI
+//! LLM-based artifact grading using OpenAI-compatible chat APIs
 //!
 //! Provides grading functionality using GPT-4 with retry logic and caching.
 
 use async_openai::{
     config::OpenAIConfig,
     types::{
-        ChatCompletionRequestMessage, ChatCompletionRequestSystemMessageArgs,
-        ChatCompletionRequestUserMessageArgs, CreateChatCompletionRequestArgs,
+        ChatCompletionRequestAssistantMessageArgs, ChatCompletionRequestMessage,
+        ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+        CreateChatCompletionRequestArgs, CreateEmbeddingRequestArgs,
     },
     Client,
 };
@@ -14,10 +15,16 @@ use std::time::Instant;
 
 use crate::cache::GradeCache;
 use crate::error::GraderError;
-use crate::rubrics::Rubric;
+use crate::practice::{self, GeneratedChallenge};
+use crate::provider::ProviderCredentials;
+use crate::review::{self, CodeReview};
+use crate::explain::{self, ExplainedMistake};
+use crate::quiz_gen::{self, GeneratedQuiz};
+use crate::rubrics::{BuiltInRubrics, Rubric};
+use crate::tutor::{self, ChatMessage, ChatRole, TokenBudget, TutorContext, TutorReply};
 use crate::types::{CategoryScore, GradeResult, GraderConfig};
 
-/// LLM-based grader using OpenAI
+/// LLM-based grader using an OpenAI-compatible chat API
 pub struct LLMGrader {
     client: Client<OpenAIConfig>,
     config: GraderConfig,
@@ -43,6 +50,25 @@ impl LLMGrader {
         Self { client, config }
     }
 
+    /// Create a grader for any supported provider, using the credentials'
+    /// model in place of the configured one
+    pub fn with_credentials(credentials: &ProviderCredentials, config: GraderConfig) -> Self {
+        // Ollama ignores the key but the client always sends one
+        let api_key = credentials.api_key.as_deref().unwrap_or("ollama");
+        let openai_config = OpenAIConfig::new()
+            .with_api_base(credentials.api_base())
+            .with_api_key(api_key);
+        let client = Client::with_config(openai_config);
+
+        Self {
+            client,
+            config: GraderConfig {
+                model: credentials.model().to_string(),
+                ..config
+            },
+        }
+    }
+
     /// Grade an artifact using the provided rubric
     pub async fn grade(
         &self,
@@ -60,7 +86,18 @@ impl LLMGrader {
 
         // Parse the response
         let latency_ms = start.elapsed().as_millis() as u64;
-        self.parse_response(&response, latency_ms)
+        let result = self.parse_response(&response, latency_ms);
+        match &result {
+            Ok(grade) => tracing::info!(
+                model = %self.config.model,
+                artifact_type = %rubric.artifact_type,
+                score = grade.score,
+                latency_ms,
+                "Graded artifact"
+            ),
+            Err(e) => tracing::warn!(model = %self.config.model, error = %e, "Failed to parse grading response"),
+        }
+        result
     }
 
     /// Grade an artifact with caching
@@ -72,6 +109,7 @@ impl LLMGrader {
     ) -> Result<GradeResult, GraderError> {
         // Check cache first
         if let Some(cached) = cache.get(artifact_content, &rubric.artifact_type)? {
+            tracing::debug!(artifact_type = %rubric.artifact_type, "Using cached grade");
             return Ok(cached);
         }
 
@@ -84,6 +122,184 @@ impl LLMGrader {
         Ok(result)
     }
 
+    /// Review a passing challenge submission against the code-quality
+    /// rubric, returning comments keyed to lines of `code`
+    pub async fn review_code(
+        &self,
+        code: &str,
+        tests_output: &str,
+        skill_tags: &[String],
+    ) -> Result<CodeReview, GraderError> {
+        let start = Instant::now();
+
+        let rubric = BuiltInRubrics::code_quality();
+        let system_message = review::build_system_message();
+        let user_message = review::build_user_message(code, tests_output, skill_tags, &rubric);
+        let response = self.call_api(&system_message, &user_message).await?;
+
+        let latency_ms = start.elapsed().as_millis() as u64;
+        let result = extract_json(&response).and_then(|json| review::parse_review(&json, code, latency_ms));
+        match &result {
+            Ok(review) => tracing::info!(
+                model = %self.config.model,
+                comments = review.comments.len(),
+                latency_ms,
+                "Reviewed code"
+            ),
+            Err(e) => tracing::warn!(model = %self.config.model, error = %e, "Failed to parse review response"),
+        }
+        result
+    }
+
+    /// Draft `n_questions` quiz questions on a lecture, tagged with `skills`.
+    /// The questions still need validating and an author's review.
+    pub async fn generate_quiz(
+        &self,
+        lecture_md: &str,
+        n_questions: usize,
+        skills: &[String],
+    ) -> Result<GeneratedQuiz, GraderError> {
+        let start = Instant::now();
+
+        let system_message = quiz_gen::build_system_message();
+        let user_message = quiz_gen::build_user_message(lecture_md, n_questions, skills);
+        let response = self.call_api(&system_message, &user_message).await?;
+
+        let latency_ms = start.elapsed().as_millis() as u64;
+        let result = extract_json(&response).and_then(|json| quiz_gen::parse_questions(&json, skills, latency_ms));
+        match &result {
+            Ok(quiz) => tracing::info!(
+                model = %self.config.model,
+                questions = quiz.questions.len(),
+                latency_ms,
+                "Generated quiz"
+            ),
+            Err(e) => tracing::warn!(model = %self.config.model, error = %e, "Failed to parse quiz response"),
+        }
+        result
+    }
+
+    /// Embed each text with an embedding `model`, in order
+    pub async fn embed(&self, model: &str, texts: &[String]) -> Result<Vec<Vec<f32>>, GraderError> {
+        let start = Instant::now();
+
+        let request = CreateEmbeddingRequestArgs::default()
+            .model(model)
+            .input(texts.to_vec())
+            .build()
+            .map_err(|e| GraderError::ApiError(e.to_string()))?;
+        let response = self.client.embeddings().create(request).await?;
+
+        let mut data = response.data;
+        data.sort_by_key(|e| e.index);
+        if data.len() != texts.len() {
+            return Err(GraderError::ParseError(format!(
+                "Expected {} embeddings, got {}",
+                texts.len(),
+                data.len()
+            )));
+        }
+
+        let latency_ms = start.elapsed().as_millis() as u64;
+        tracing::info!(model, texts = texts.len(), latency_ms, "Embedded texts");
+        Ok(data.into_iter().map(|e| e.embedding).collect())
+    }
+
+    /// Draft a practice challenge on one skill. The solution is untested.
+    pub async fn generate_practice(
+        &self,
+        skill: &str,
+        difficulty: &str,
+        lecture_excerpt: &str,
+    ) -> Result<GeneratedChallenge, GraderError> {
+        let start = Instant::now();
+
+        let system_message = practice::build_system_message();
+        let user_message = practice::build_user_message(skill, difficulty, lecture_excerpt);
+        let response = self.call_api(&system_message, &user_message).await?;
+
+        let latency_ms = start.elapsed().as_millis() as u64;
+        let result = extract_json(&response).and_then(|json| practice::parse_challenge(&json, latency_ms));
+        match &result {
+            Ok(challenge) => tracing::info!(
+                model = %self.config.model,
+                title = %challenge.title,
+                latency_ms,
+                "Generated practice challenge"
+            ),
+            Err(e) => tracing::warn!(model = %self.config.model, error = %e, "Failed to parse practice response"),
+        }
+        result
+    }
+
+    /// Explain why `chosen` is the wrong answer to a quiz question
+    pub async fn explain_mistake(
+        &self,
+        question: &str,
+        chosen: &str,
+        correct: &str,
+        lecture_excerpt: &str,
+    ) -> Result<ExplainedMistake, GraderError> {
+        let start = Instant::now();
+
+        let system_message = explain::build_system_message();
+        let user_message = explain::build_user_message(question, chosen, correct, lecture_excerpt);
+        let response = self.call_api(&system_message, &user_message).await?;
+
+        let latency_ms = start.elapsed().as_millis() as u64;
+        let explanation = response.trim().to_string();
+        if explanation.is_empty() {
+            return Err(GraderError::ParseError("Empty explanation".to_string()));
+        }
+        tracing::info!(model = %self.config.model, latency_ms, "Explained quiz mistake");
+        Ok(ExplainedMistake { explanation, latency_ms })
+    }
+
+    /// Answer the latest message in a tutor conversation. `history` ends
+    /// with the learner's new message; older messages that don't fit the
+    /// budget are left out.
+    pub async fn tutor_reply(
+        &self,
+        context: &TutorContext,
+        history: &[ChatMessage],
+        budget: &TokenBudget,
+    ) -> Result<TutorReply, GraderError> {
+        let start = Instant::now();
+
+        let system_message = tutor::build_system_prompt(context, budget);
+        let kept = tutor::fit_history(history, budget);
+        let prompt_tokens = tutor::estimate_tokens(&system_message)
+            + kept.iter().map(|m| tutor::estimate_tokens(&m.content)).sum::<usize>();
+
+        let mut messages = vec![system_request(&system_message)?];
+        for message in kept {
+            messages.push(match message.role {
+                ChatRole::User => ChatCompletionRequestMessage::User(
+                    ChatCompletionRequestUserMessageArgs::default()
+                        .content(message.content.as_str())
+                        .build()
+                        .map_err(|e| GraderError::ApiError(e.to_string()))?,
+                ),
+                ChatRole::Assistant => ChatCompletionRequestMessage::Assistant(
+                    ChatCompletionRequestAssistantMessageArgs::default()
+                        .content(message.content.as_str())
+                        .build()
+                        .map_err(|e| GraderError::ApiError(e.to_string()))?,
+                ),
+            });
+        }
+        let content = self.call_chat(messages).await?;
+
+        let latency_ms = start.elapsed().as_millis() as u64;
+        tracing::info!(model = %self.config.model, prompt_tokens, latency_ms, "Tutor replied");
+        Ok(TutorReply {
+            content,
+            prompt_tokens,
+            dropped_messages: history.len() - kept.len(),
+            latency_ms,
+        })
+    }
+
     /// Build the system message for the LLM
     fn build_system_message(&self) -> String {
         r#"You are an expert code reviewer and educator grading student project artifacts for a Rust bootcamp.
@@ -147,19 +363,14 @@ Be specific in your feedback. Quote or reference specific parts of the artifact.
         )
     }
 
-    /// Call the OpenAI API
+    /// Call the chat completions API
     async fn call_api(
         &self,
         system_message: &str,
         user_message: &str,
     ) -> Result<String, GraderError> {
         let messages = vec![
-            ChatCompletionRequestMessage::System(
-                ChatCompletionRequestSystemMessageArgs::default()
-                    .content(system_message)
-                    .build()
-                    .map_err(|e| GraderError::ApiError(e.to_string()))?,
-            ),
+            system_request(system_message)?,
             ChatCompletionRequestMessage::User(
                 ChatCompletionRequestUserMessageArgs::default()
                     .content(user_message)
@@ -167,7 +378,11 @@ Be specific in your feedback. Quote or reference specific parts of the artifact.
                     .map_err(|e| GraderError::ApiError(e.to_string()))?,
             ),
         ];
+        self.call_chat(messages).await
+    }
 
+    /// Send a conversation and return the reply's text
+    async fn call_chat(&self, messages: Vec<ChatCompletionRequestMessage>) -> Result<String, GraderError> {
         let request = CreateChatCompletionRequestArgs::default()
             .model(&self.config.model)
             .temperature(self.config.temperature)
@@ -217,10 +432,20 @@ Be specific in your feedback. Quote or reference specific parts of the artifact.
             category_scores,
             from_cache: false,
             latency_ms,
+            self_assessed: false,
         })
     }
 }
 
+fn system_request(content: &str) -> Result<ChatCompletionRequestMessage, GraderError> {
+    Ok(ChatCompletionRequestMessage::System(
+        ChatCompletionRequestSystemMessageArgs::default()
+            .content(content)
+            .build()
+            .map_err(|e| GraderError::ApiError(e.to_string()))?,
+    ))
+}
+
 /// Extract JSON from a potentially wrapped response
 fn extract_json(response: &str) -> Result<String, GraderError> {
     let trimmed = response.trim();
@@ -0,0 +1,136 @@
+//! Practice challenge generation
+//!
+//! Asks the model for a small challenge on one skill in the runner's
+//! format: starter code, a reference solution and a `#[cfg(test)]` module
+//! that is appended to whichever of the two is being tested. Nothing here
+//! checks that the solution passes; callers run it in the sandbox first.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::GraderError;
+
+/// Lecture text beyond this is cut from the prompt
+const MAX_EXCERPT_CHARS: usize = 6000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedChallenge {
+    pub title: String,
+    pub instructions: String,
+    pub starter_code: String,
+    pub test_code: String,
+    pub solution: String,
+    pub hints: Vec<String>,
+    /// Latency in milliseconds
+    pub latency_ms: u64,
+}
+
+pub(crate) fn build_system_message() -> String {
+    r#"You are an experienced Rust instructor writing short practice exercises for a Rust bootcamp.
+
+Write one exercise that drills a single skill:
+1. Keep it small: one or two functions a learner can finish in under fifteen minutes
+2. Use only the standard library
+3. Give the starter code the final signatures, with `todo!()` bodies
+4. Write tests that pass against the solution and fail against the starter code
+5. Put the tests in a `#[cfg(test)] mod tests` block that starts with `use super::*;`; it is appended to the learner's code"#
+        .to_string()
+}
+
+pub(crate) fn build_user_message(skill: &str, difficulty: &str, lecture_excerpt: &str) -> String {
+    let excerpt: String = lecture_excerpt.chars().take(MAX_EXCERPT_CHARS).collect();
+    let excerpt = if excerpt.trim().is_empty() { "(no lecture available)".to_string() } else { excerpt };
+
+    format!(
+        r#"# PRACTICE EXERCISE TASK
+
+## Skill
+{}
+
+## Difficulty
+{}
+
+## Lecture Excerpt
+{}
+
+## Output Format
+Respond with ONLY valid JSON in this exact format (no markdown, no code blocks):
+
+{{
+  "title": "<short title>",
+  "instructions": "<what to implement, in markdown>",
+  "starter_code": "<Rust code with todo!() bodies>",
+  "test_code": "<#[cfg(test)] mod tests {{ ... }}>",
+  "solution": "<the starter code, completed>",
+  "hints": ["<hint>", "<hint>"]
+}}"#,
+        skill, difficulty, excerpt
+    )
+}
+
+#[derive(Deserialize)]
+struct LLMChallenge {
+    title: String,
+    #[serde(default)]
+    instructions: String,
+    starter_code: String,
+    test_code: String,
+    solution: String,
+    #[serde(default)]
+    hints: Vec<String>,
+}
+
+pub(crate) fn parse_challenge(json: &str, latency_ms: u64) -> Result<GeneratedChallenge, GraderError> {
+    let parsed: LLMChallenge = serde_json::from_str(json)
+        .map_err(|e| GraderError::ParseError(format!("Failed to parse challenge JSON: {}", e)))?;
+
+    if parsed.test_code.trim().is_empty() || parsed.solution.trim().is_empty() {
+        return Err(GraderError::ParseError("Challenge is missing its tests or solution".to_string()));
+    }
+
+    Ok(GeneratedChallenge {
+        title: parsed.title,
+        instructions: parsed.instructions,
+        starter_code: parsed.starter_code,
+        test_code: parsed.test_code,
+        solution: parsed.solution,
+        hints: parsed.hints,
+        latency_ms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_message_includes_skill_difficulty_and_excerpt() {
+        let msg = build_user_message("Borrowing", "medium", "References borrow without taking ownership.");
+        assert!(msg.contains("## Skill\nBorrowing"));
+        assert!(msg.contains("## Difficulty\nmedium"));
+        assert!(msg.contains("without taking ownership"));
+        assert!(build_user_message("Borrowing", "easy", "").contains("(no lecture available)"));
+    }
+
+    #[test]
+    fn test_parse_challenge() {
+        let json = r##"{
+            "title": "Longest word",
+            "instructions": "Return the longest word.",
+            "starter_code": "pub fn longest(s: &str) -> &str { todo!() }",
+            "test_code": "#[cfg(test)] mod tests { use super::*; #[test] fn t() { assert_eq!(longest(\"a bb\"), \"bb\"); } }",
+            "solution": "pub fn longest(s: &str) -> &str { s.split(' ').max_by_key(|w| w.len()).unwrap_or(\"\") }"
+        }"##;
+
+        let challenge = parse_challenge(json, 120).unwrap();
+        assert_eq!(challenge.title, "Longest word");
+        assert!(challenge.hints.is_empty());
+        assert_eq!(challenge.latency_ms, 120);
+    }
+
+    #[test]
+    fn test_parse_challenge_requires_tests_and_solution() {
+        let json = r#"{"title": "T", "starter_code": "", "test_code": " ", "solution": "fn f() {}"}"#;
+        assert!(matches!(parse_challenge(json, 0), Err(GraderError::ParseError(_))));
+        assert!(matches!(parse_challenge("{}", 0), Err(GraderError::ParseError(_))));
+    }
+}
@@ -0,0 +1,285 @@
+//! LLM providers and credential checks
+//!
+//! Grading talks to every provider through an OpenAI-compatible chat API.
+//! Credential checks use each provider's own model listing endpoint, so a
+//! check both validates the key and reports which models it can use.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::error::GraderError;
+
+/// How long a credential check waits for the provider
+const CHECK_TIMEOUT_SECS: u64 = 10;
+
+/// API version sent with Anthropic requests
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// A provider the grader can send artifacts to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "lowercase")]
+pub enum LlmProvider {
+    OpenAi,
+    Anthropic,
+    /// A local or self-hosted Ollama server
+    Ollama,
+}
+
+impl LlmProvider {
+    pub const ALL: [LlmProvider; 3] = [LlmProvider::OpenAi, LlmProvider::Anthropic, LlmProvider::Ollama];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            LlmProvider::OpenAi => "OpenAI",
+            LlmProvider::Anthropic => "Anthropic",
+            LlmProvider::Ollama => "Ollama",
+        }
+    }
+
+    pub fn default_endpoint(&self) -> &'static str {
+        match self {
+            LlmProvider::OpenAi => "https://api.openai.com/v1",
+            LlmProvider::Anthropic => "https://api.anthropic.com/v1",
+            LlmProvider::Ollama => "http://localhost:11434",
+        }
+    }
+
+    pub fn default_model(&self) -> &'static str {
+        match self {
+            LlmProvider::OpenAi => "gpt-4",
+            LlmProvider::Anthropic => "claude-sonnet-4-5",
+            LlmProvider::Ollama => "llama3.1",
+        }
+    }
+
+    /// Model used to embed content, if the provider offers embeddings
+    pub fn embedding_model(&self) -> Option<&'static str> {
+        match self {
+            LlmProvider::OpenAi => Some("text-embedding-3-small"),
+            LlmProvider::Anthropic => None,
+            LlmProvider::Ollama => Some("nomic-embed-text"),
+        }
+    }
+
+    /// Local servers run without an API key
+    pub fn requires_api_key(&self) -> bool {
+        !matches!(self, LlmProvider::Ollama)
+    }
+
+    /// Environment variable a key can be supplied through
+    pub fn api_key_env_var(&self) -> Option<&'static str> {
+        match self {
+            LlmProvider::OpenAi => Some("OPENAI_API_KEY"),
+            LlmProvider::Anthropic => Some("ANTHROPIC_API_KEY"),
+            LlmProvider::Ollama => None,
+        }
+    }
+}
+
+/// Everything needed to call a provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderCredentials {
+    pub provider: LlmProvider,
+    pub api_key: Option<String>,
+    /// Overrides the provider's default endpoint
+    pub endpoint: Option<String>,
+    /// Overrides the provider's default model
+    pub model: Option<String>,
+}
+
+impl ProviderCredentials {
+    pub fn new(provider: LlmProvider) -> Self {
+        Self {
+            provider,
+            api_key: None,
+            endpoint: None,
+            model: None,
+        }
+    }
+
+    pub fn with_api_key(mut self, api_key: &str) -> Self {
+        self.api_key = Some(api_key.to_string());
+        self
+    }
+
+    pub fn endpoint(&self) -> String {
+        self.endpoint
+            .as_deref()
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+            .unwrap_or(self.provider.default_endpoint())
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    pub fn model(&self) -> &str {
+        self.model
+            .as_deref()
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .unwrap_or(self.provider.default_model())
+    }
+
+    /// Whether the credentials are complete enough to try
+    pub fn is_configured(&self) -> bool {
+        !self.provider.requires_api_key() || self.api_key.as_deref().is_some_and(|k| !k.trim().is_empty())
+    }
+
+    /// Base URL of the provider's OpenAI-compatible chat API
+    pub fn api_base(&self) -> String {
+        match self.provider {
+            LlmProvider::OpenAi | LlmProvider::Anthropic => self.endpoint(),
+            LlmProvider::Ollama => format!("{}/v1", self.endpoint()),
+        }
+    }
+}
+
+/// Result of checking a provider's credentials
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ProviderCheck {
+    pub provider: LlmProvider,
+    /// Model the grader will use
+    pub model: String,
+    /// Whether that model is among the ones the credentials can use
+    pub model_available: bool,
+    /// Models the credentials can use
+    pub available_models: Vec<String>,
+    pub latency_ms: u64,
+}
+
+/// Validate credentials with a minimal request that lists the provider's models
+pub async fn check_credentials(credentials: &ProviderCredentials) -> Result<ProviderCheck, GraderError> {
+    let provider = credentials.provider;
+    if !credentials.is_configured() {
+        return Err(GraderError::MissingApiKey(provider.display_name().to_string()));
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(CHECK_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| GraderError::ApiError(e.to_string()))?;
+
+    let endpoint = credentials.endpoint();
+    let api_key = credentials.api_key.as_deref().unwrap_or_default().trim();
+    let request = match provider {
+        LlmProvider::OpenAi => client.get(format!("{}/models", endpoint)).bearer_auth(api_key),
+        LlmProvider::Anthropic => client
+            .get(format!("{}/models", endpoint))
+            .header("x-api-key", api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION),
+        LlmProvider::Ollama => client.get(format!("{}/api/tags", endpoint)),
+    };
+
+    let start = std::time::Instant::now();
+    let response = request.send().await.map_err(|e| {
+        if e.is_timeout() {
+            GraderError::Timeout(CHECK_TIMEOUT_SECS)
+        } else {
+            GraderError::ApiError(format!("Could not reach {}: {}", provider.display_name(), e))
+        }
+    })?;
+    let latency_ms = start.elapsed().as_millis() as u64;
+
+    let status = response.status();
+    let body = response.text().await.map_err(|e| GraderError::ApiError(e.to_string()))?;
+    match status.as_u16() {
+        200..=299 => {}
+        401 | 403 => return Err(GraderError::InvalidApiKey(provider.display_name().to_string())),
+        429 => return Err(GraderError::RateLimit(60)),
+        code => return Err(GraderError::ApiError(format!("{} returned HTTP {}: {}", provider.display_name(), code, body))),
+    }
+
+    let available_models = parse_model_ids(provider, &body)?;
+    let model = credentials.model().to_string();
+    tracing::info!(
+        provider = provider.display_name(),
+        model = %model,
+        models = available_models.len(),
+        latency_ms,
+        "Checked provider credentials"
+    );
+    Ok(ProviderCheck {
+        provider,
+        model_available: has_model(&available_models, &model),
+        model,
+        available_models,
+        latency_ms,
+    })
+}
+
+/// Model IDs from a provider's model listing response
+fn parse_model_ids(provider: LlmProvider, body: &str) -> Result<Vec<String>, GraderError> {
+    let value: serde_json::Value = serde_json::from_str(body)
+        .map_err(|e| GraderError::ParseError(format!("Invalid model list: {}", e)))?;
+
+    let (list, id_field) = match provider {
+        LlmProvider::OpenAi | LlmProvider::Anthropic => ("data", "id"),
+        LlmProvider::Ollama => ("models", "name"),
+    };
+    let entries = value
+        .get(list)
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| GraderError::ParseError(format!("Model list has no `{}` array", list)))?;
+
+    let mut models: Vec<String> = entries
+        .iter()
+        .filter_map(|m| m.get(id_field).and_then(|id| id.as_str()))
+        .map(str::to_string)
+        .collect();
+    models.sort();
+    Ok(models)
+}
+
+/// Whether a model is in a list, treating Ollama's `:latest` tag as implied
+fn has_model(models: &[String], model: &str) -> bool {
+    models
+        .iter()
+        .any(|m| m == model || m.strip_suffix(":latest") == Some(model))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_serde_names() {
+        assert_eq!(serde_json::to_string(&LlmProvider::OpenAi).unwrap(), "\"openai\"");
+        assert_eq!(
+            serde_json::from_str::<LlmProvider>("\"ollama\"").unwrap(),
+            LlmProvider::Ollama
+        );
+    }
+
+    #[test]
+    fn test_credentials_defaults_and_overrides() {
+        let openai = ProviderCredentials::new(LlmProvider::OpenAi);
+        assert!(!openai.is_configured());
+        assert_eq!(openai.model(), "gpt-4");
+        assert!(openai.with_api_key("sk-test").is_configured());
+
+        let ollama = ProviderCredentials {
+            endpoint: Some("http://gpu-box:11434/".to_string()),
+            model: Some("qwen2.5-coder".to_string()),
+            ..ProviderCredentials::new(LlmProvider::Ollama)
+        };
+        assert!(ollama.is_configured());
+        assert_eq!(ollama.endpoint(), "http://gpu-box:11434");
+        assert_eq!(ollama.api_base(), "http://gpu-box:11434/v1");
+        assert_eq!(ollama.model(), "qwen2.5-coder");
+    }
+
+    #[test]
+    fn test_parse_model_ids() {
+        let openai = r#"{"object": "list", "data": [{"id": "gpt-4o"}, {"id": "gpt-4"}]}"#;
+        assert_eq!(parse_model_ids(LlmProvider::OpenAi, openai).unwrap(), vec!["gpt-4", "gpt-4o"]);
+
+        let ollama = r#"{"models": [{"name": "llama3.1:latest", "size": 1}]}"#;
+        let models = parse_model_ids(LlmProvider::Ollama, ollama).unwrap();
+        assert!(has_model(&models, "llama3.1"));
+        assert!(!has_model(&models, "llama3"));
+
+        assert!(parse_model_ids(LlmProvider::Anthropic, r#"{"error": "nope"}"#).is_err());
+    }
+}
@@ -0,0 +1,173 @@
+//! Quiz generation from lecture markdown
+//!
+//! Asks the model for questions in the platform's quiz JSON schema. The
+//! questions come back unchecked apart from their shape; authors are
+//! expected to run them through the content validator and review each one
+//! before it ships.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::GraderError;
+
+/// Lecture text beyond this is cut from the prompt
+const MAX_LECTURE_CHARS: usize = 16000;
+
+/// A question in the quiz file schema
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuizQuestion {
+    pub id: String,
+    pub question: String,
+    /// `multiple-choice` or `multiple-select`
+    #[serde(rename = "type")]
+    pub question_type: String,
+    pub options: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correct_answer: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correct_answers: Option<Vec<usize>>,
+    pub explanation: String,
+    #[serde(default)]
+    pub skills: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedQuiz {
+    pub questions: Vec<QuizQuestion>,
+    /// Latency in milliseconds
+    pub latency_ms: u64,
+}
+
+pub(crate) fn build_system_message() -> String {
+    r#"You are an experienced Rust instructor writing quiz questions for a Rust bootcamp.
+
+Write questions that check understanding of the lecture, not recall of its wording:
+1. Only ask about material the lecture covers
+2. Make every wrong option plausible to a learner who misunderstood the material
+3. Have exactly one defensible answer for multiple-choice questions
+4. Explain in the explanation why the answer is right and the common wrong answer is wrong"#
+        .to_string()
+}
+
+pub(crate) fn build_user_message(lecture_md: &str, n_questions: usize, skills: &[String]) -> String {
+    let lecture: String = lecture_md.chars().take(MAX_LECTURE_CHARS).collect();
+    let skills = if skills.is_empty() { "(none listed)".to_string() } else { skills.join(", ") };
+
+    format!(
+        r#"# QUIZ GENERATION TASK
+
+## Skills To Tag
+{}
+
+## Lecture
+{}
+
+## Output Format
+Respond with ONLY valid JSON in this exact format (no markdown, no code blocks):
+
+{{
+  "questions": [
+    {{
+      "question": "<the question>",
+      "type": "<multiple-choice|multiple-select>",
+      "options": ["<option>", "<option>", "<option>", "<option>"],
+      "correct_answer": <0-based index, for multiple-choice only>,
+      "correct_answers": [<0-based indices, for multiple-select only>],
+      "explanation": "<why the answer is right>",
+      "skills": ["<skill from the list above>"]
+    }}
+  ]
+}}
+
+Write exactly {} questions, with four options each."#,
+        skills, lecture, n_questions
+    )
+}
+
+#[derive(Deserialize)]
+struct LLMQuestion {
+    question: String,
+    #[serde(rename = "type")]
+    question_type: String,
+    options: Vec<String>,
+    #[serde(default)]
+    correct_answer: Option<usize>,
+    #[serde(default)]
+    correct_answers: Option<Vec<usize>>,
+    #[serde(default)]
+    explanation: String,
+    #[serde(default)]
+    skills: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct LLMQuiz {
+    questions: Vec<LLMQuestion>,
+}
+
+/// Parse the model's reply. Questions are numbered `q1`, `q2`, ... and
+/// tagged with the requested skills when the model left them out.
+pub(crate) fn parse_questions(json: &str, skills: &[String], latency_ms: u64) -> Result<GeneratedQuiz, GraderError> {
+    let parsed: LLMQuiz = serde_json::from_str(json)
+        .map_err(|e| GraderError::ParseError(format!("Failed to parse quiz JSON: {}", e)))?;
+
+    let questions = parsed
+        .questions
+        .into_iter()
+        .enumerate()
+        .map(|(i, q)| QuizQuestion {
+            id: format!("q{}", i + 1),
+            question: q.question,
+            question_type: q.question_type,
+            options: q.options,
+            correct_answer: q.correct_answer,
+            correct_answers: q.correct_answers,
+            explanation: q.explanation,
+            skills: if q.skills.is_empty() { skills.to_vec() } else { q.skills },
+        })
+        .collect();
+
+    Ok(GeneratedQuiz { questions, latency_ms })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_message_includes_lecture_skills_and_count() {
+        let msg = build_user_message("# Ownership\n\nEach value has one owner.", 5, &["ownership".to_string()]);
+        assert!(msg.contains("Each value has one owner."));
+        assert!(msg.contains("ownership"));
+        assert!(msg.contains("Write exactly 5 questions"));
+    }
+
+    #[test]
+    fn test_parse_questions_numbers_and_tags_questions() {
+        let json = r#"{
+            "questions": [
+                {"question": "Who owns a moved value?", "type": "multiple-choice",
+                 "options": ["The caller", "The callee", "Both", "Nobody"], "correct_answer": 1,
+                 "explanation": "Moving transfers ownership."},
+                {"question": "Which types are Copy?", "type": "multiple-select",
+                 "options": ["i32", "String", "bool", "Vec<u8>"], "correct_answers": [0, 2],
+                 "explanation": "Scalars are Copy.", "skills": ["traits"]}
+            ]
+        }"#;
+
+        let quiz = parse_questions(json, &["ownership".to_string()], 300).unwrap();
+        assert_eq!(quiz.questions.len(), 2);
+        assert_eq!(quiz.questions[0].id, "q1");
+        assert_eq!(quiz.questions[0].skills, vec!["ownership".to_string()]);
+        assert_eq!(quiz.questions[1].id, "q2");
+        assert_eq!(quiz.questions[1].skills, vec!["traits".to_string()]);
+
+        let value = serde_json::to_value(&quiz.questions[0]).unwrap();
+        assert_eq!(value["type"], "multiple-choice");
+        assert!(value.get("correct_answers").is_none());
+    }
+
+    #[test]
+    fn test_parse_questions_rejects_malformed_json() {
+        assert!(matches!(parse_questions("{\"items\": []}", &[], 0), Err(GraderError::ParseError(_))));
+    }
+}
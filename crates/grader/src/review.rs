@@ -0,0 +1,173 @@
+//! Code review of challenge submissions
+//!
+//! Builds the review prompt from the learner's code, test output and the
+//! skills the challenge exercises, and parses the model's reply into
+//! comments keyed to lines of the submitted code.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::GraderError;
+use crate::rubrics::Rubric;
+
+/// Test output beyond this is cut from the prompt
+const MAX_TEST_OUTPUT_CHARS: usize = 4000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "lowercase")]
+pub enum CommentSeverity {
+    Praise,
+    Suggestion,
+    Issue,
+}
+
+/// A comment on one line of the submission
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ReviewComment {
+    /// 1-based line in the submitted code
+    pub line: u32,
+    pub severity: CommentSeverity,
+    /// Rubric category the comment falls under
+    pub category: String,
+    pub comment: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct CodeReview {
+    pub summary: String,
+    /// Ordered by line
+    pub comments: Vec<ReviewComment>,
+    /// Latency in milliseconds
+    pub latency_ms: u64,
+}
+
+pub(crate) fn build_system_message() -> String {
+    r#"You are an experienced Rust engineer reviewing a learner's solution to a coding exercise in a Rust bootcamp.
+
+The solution already passes its tests, so focus on code quality rather than correctness:
+1. Point out what is done well as well as what could improve
+2. Tie every comment to a specific line
+3. Explain why a change is better, in one or two sentences
+4. Prefer the few comments that matter most over exhaustive nitpicks"#
+        .to_string()
+}
+
+pub(crate) fn build_user_message(code: &str, tests_output: &str, skill_tags: &[String], rubric: &Rubric) -> String {
+    let numbered: String = code
+        .lines()
+        .enumerate()
+        .map(|(i, line)| format!("{:>4} | {}\n", i + 1, line))
+        .collect();
+    let tests_output: String = tests_output.chars().take(MAX_TEST_OUTPUT_CHARS).collect();
+    let skills = if skill_tags.is_empty() { "(none listed)".to_string() } else { skill_tags.join(", ") };
+
+    format!(
+        r#"# CODE REVIEW TASK
+
+## Skills Practised
+{}
+
+## Rubric
+{}
+
+## Submission (line numbers added)
+```
+{}```
+
+## Test Output
+```
+{}
+```
+
+## Output Format
+Respond with ONLY valid JSON in this exact format (no markdown, no code blocks):
+
+{{
+  "summary": "<2-3 sentences on the overall quality of the code>",
+  "comments": [
+    {{
+      "line": <line number from the submission>,
+      "severity": "<praise|suggestion|issue>",
+      "category": "<rubric category name>",
+      "comment": "<the comment>"
+    }}
+  ]
+}}
+
+Give at most 8 comments."#,
+        skills,
+        rubric.to_prompt_string(),
+        numbered,
+        tests_output
+    )
+}
+
+#[derive(Deserialize)]
+struct LLMReview {
+    summary: String,
+    #[serde(default)]
+    comments: Vec<ReviewComment>,
+}
+
+/// Parse the model's reply, dropping comments on lines the code doesn't have
+pub(crate) fn parse_review(json: &str, code: &str, latency_ms: u64) -> Result<CodeReview, GraderError> {
+    let parsed: LLMReview = serde_json::from_str(json)
+        .map_err(|e| GraderError::ParseError(format!("Failed to parse review JSON: {}", e)))?;
+
+    let line_count = code.lines().count() as u32;
+    let mut comments: Vec<ReviewComment> = parsed
+        .comments
+        .into_iter()
+        .filter(|c| (1..=line_count).contains(&c.line))
+        .collect();
+    comments.sort_by_key(|c| c.line);
+
+    Ok(CodeReview {
+        summary: parsed.summary,
+        comments,
+        latency_ms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rubrics::BuiltInRubrics;
+
+    const CODE: &str = "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}";
+
+    #[test]
+    fn test_user_message_numbers_lines_and_lists_skills() {
+        let msg = build_user_message(CODE, "test result: ok", &["functions".to_string()], &BuiltInRubrics::code_quality());
+        assert!(msg.contains("   1 | pub fn add"));
+        assert!(msg.contains("   3 | }"));
+        assert!(msg.contains("functions"));
+        assert!(msg.contains("Idiomatic Rust"));
+        assert!(msg.contains("test result: ok"));
+    }
+
+    #[test]
+    fn test_parse_review_keeps_comments_on_real_lines() {
+        let json = r#"{
+            "summary": "Clean and simple.",
+            "comments": [
+                {"line": 2, "severity": "praise", "category": "Readability", "comment": "Nice and direct"},
+                {"line": 1, "severity": "suggestion", "category": "Idiomatic Rust", "comment": "Consider a doc comment"},
+                {"line": 40, "severity": "issue", "category": "Readability", "comment": "Not a real line"}
+            ]
+        }"#;
+
+        let review = parse_review(json, CODE, 120).unwrap();
+        assert_eq!(review.summary, "Clean and simple.");
+        let lines: Vec<u32> = review.comments.iter().map(|c| c.line).collect();
+        assert_eq!(lines, vec![1, 2]);
+        assert_eq!(review.comments[1].severity, CommentSeverity::Praise);
+    }
+
+    #[test]
+    fn test_parse_review_rejects_malformed_json() {
+        assert!(matches!(parse_review("{\"comments\": []}", CODE, 0), Err(GraderError::ParseError(_))));
+    }
+}
@@ -9,6 +9,7 @@ use crate::error::GraderError;
 
 /// A grading rubric
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct Rubric {
     /// Type of artifact (e.g., "DESIGN", "README")
     pub artifact_type: String,
@@ -69,6 +70,7 @@ impl Rubric {
 
 /// A category within a rubric
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct RubricCategory {
     /// Category name
     pub name: String,
@@ -84,6 +86,7 @@ pub struct RubricCategory {
 
 /// A specific criterion within a category
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct Criterion {
     /// Description of what's being evaluated
     pub description: String,
@@ -95,6 +98,7 @@ pub struct Criterion {
 
 /// Performance indicators for excellent/good/poor
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct Indicators {
     /// What excellent performance looks like
     pub excellent: String,
@@ -106,6 +110,7 @@ pub struct Indicators {
 
 /// Grade range guidelines
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct GradingGuidelines {
     #[serde(rename = "A (90-100)", default)]
     pub a_grade: String,
@@ -133,6 +138,11 @@ impl BuiltInRubrics {
         serde_json::from_str(README_RUBRIC_JSON).unwrap()
     }
 
+    /// Get the rubric for reviewing challenge code
+    pub fn code_quality() -> Rubric {
+        serde_json::from_str(CODE_QUALITY_RUBRIC_JSON).unwrap()
+    }
+
     /// Get rubric by artifact type
     pub fn get(artifact_type: &str) -> Option<Rubric> {
         match artifact_type.to_uppercase().as_str() {
@@ -382,6 +392,64 @@ const README_RUBRIC_JSON: &str = r#"{
     ]
 }"#;
 
+const CODE_QUALITY_RUBRIC_JSON: &str = r#"{
+    "artifact_type": "Rust code",
+    "total_points": 100,
+    "categories": [
+        {
+            "name": "Idiomatic Rust",
+            "points": 30,
+            "criteria": [
+                {
+                    "description": "Ownership and borrowing",
+                    "points": 15,
+                    "indicators": {
+                        "excellent": "Borrows where ownership isn't needed; no needless clones or allocations",
+                        "good": "Mostly borrows, with a few avoidable clones",
+                        "poor": "Clones or takes ownership to get past the borrow checker"
+                    }
+                },
+                {
+                    "description": "Standard library and iterators",
+                    "points": 15,
+                    "indicators": {
+                        "excellent": "Uses iterator adapters, pattern matching and std types where they fit",
+                        "good": "Some hand-written code where std offers a clearer tool",
+                        "poor": "Reimplements std functionality or fights the type system"
+                    }
+                }
+            ]
+        },
+        {
+            "name": "Error Handling",
+            "points": 25,
+            "indicators": {
+                "excellent": "Errors are returned with Result/Option and ?; no panics on bad input",
+                "good": "Mostly handles errors, with an unwrap on a case that can't fail",
+                "poor": "unwrap/expect on input that can fail, or errors silently ignored"
+            }
+        },
+        {
+            "name": "Readability",
+            "points": 25,
+            "indicators": {
+                "excellent": "Clear names, small functions, comments only where the code can't speak",
+                "good": "Readable with a few unclear names or long functions",
+                "poor": "Hard to follow; cryptic names, deep nesting or dead code"
+            }
+        },
+        {
+            "name": "Efficiency",
+            "points": 20,
+            "indicators": {
+                "excellent": "Appropriate data structures and algorithmic complexity",
+                "good": "Reasonable, with minor wasted work",
+                "poor": "Needless quadratic work, repeated allocation or redundant passes"
+            }
+        }
+    ]
+}"#;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -435,6 +503,13 @@ mod tests {
         assert!(BuiltInRubrics::get("unknown").is_none());
     }
 
+    #[test]
+    fn test_code_quality_rubric_is_valid() {
+        let rubric = BuiltInRubrics::code_quality();
+        assert!(rubric.validate().is_ok());
+        assert!(BuiltInRubrics::get(&rubric.artifact_type).is_none());
+    }
+
     #[test]
     fn test_category_points_sum() {
         let rubric = BuiltInRubrics::design();
@@ -0,0 +1,122 @@
+//! Rubric self-assessment
+//!
+//! Without a grading provider a learner can still finish a checkpoint by
+//! scoring their artifact against the rubric themselves, one category at a
+//! time. The result has the same shape as a model's grade, flagged
+//! `self_assessed` so it can be told apart and rewarded less.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::GraderError;
+use crate::rubrics::Rubric;
+use crate::types::{CategoryScore, GradeResult};
+
+/// The learner's score for one rubric category
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct SelfScore {
+    /// Category name, as in the rubric
+    pub category: String,
+    /// Points given, at most the category's points
+    pub score: u32,
+    /// Why, in the learner's words
+    #[serde(default)]
+    pub note: String,
+}
+
+/// Turn per-category self-scores into a grade out of 100. Every rubric
+/// category must be scored exactly once.
+pub fn self_assess(rubric: &Rubric, scores: &[SelfScore], reflection: &str) -> Result<GradeResult, GraderError> {
+    if let Some(unknown) = scores.iter().find(|s| !rubric.categories.iter().any(|c| c.name == s.category)) {
+        return Err(GraderError::InvalidSelfAssessment(format!(
+            "'{}' isn't a category of the {} rubric",
+            unknown.category, rubric.artifact_type
+        )));
+    }
+
+    let mut category_scores = Vec::new();
+    for category in &rubric.categories {
+        let mut given = scores.iter().filter(|s| s.category == category.name);
+        let score = match (given.next(), given.next()) {
+            (Some(score), None) => score,
+            (None, _) => {
+                return Err(GraderError::InvalidSelfAssessment(format!("'{}' wasn't scored", category.name)))
+            }
+            (Some(_), Some(_)) => {
+                return Err(GraderError::InvalidSelfAssessment(format!("'{}' was scored twice", category.name)))
+            }
+        };
+        if score.score > category.points {
+            return Err(GraderError::InvalidSelfAssessment(format!(
+                "'{}' is out of {} points, not {}",
+                category.name, category.points, score.score
+            )));
+        }
+        category_scores.push(CategoryScore::new(
+            category.name.clone(),
+            score.score,
+            category.points,
+            score.note.trim().to_string(),
+        ));
+    }
+
+    let earned: u32 = category_scores.iter().map(|c| c.score).sum();
+    let total = rubric.total_points.max(1);
+    let score = ((earned as f64 / total as f64) * 100.0).round().min(100.0) as u32;
+
+    let mut result = GradeResult::new(score, reflection.trim().to_string(), category_scores, 0);
+    result.self_assessed = true;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rubrics::BuiltInRubrics;
+
+    fn full_marks(rubric: &Rubric) -> Vec<SelfScore> {
+        rubric
+            .categories
+            .iter()
+            .map(|c| SelfScore { category: c.name.clone(), score: c.points, note: String::new() })
+            .collect()
+    }
+
+    #[test]
+    fn test_self_assessment_scales_to_100() {
+        let rubric = BuiltInRubrics::readme();
+        let result = self_assess(&rubric, &full_marks(&rubric), "  Happy with it  ").unwrap();
+        assert_eq!(result.score, 100);
+        assert!(result.self_assessed);
+        assert_eq!(result.overall_feedback, "Happy with it");
+        assert_eq!(result.category_scores.len(), rubric.categories.len());
+
+        let mut scores = full_marks(&rubric);
+        for score in &mut scores {
+            score.score /= 2;
+        }
+        let half = self_assess(&rubric, &scores, "").unwrap();
+        assert!((45..=55).contains(&half.score));
+    }
+
+    #[test]
+    fn test_self_assessment_must_cover_the_rubric() {
+        let rubric = BuiltInRubrics::readme();
+
+        let mut missing = full_marks(&rubric);
+        missing.pop();
+        assert!(matches!(self_assess(&rubric, &missing, ""), Err(GraderError::InvalidSelfAssessment(_))));
+
+        let mut twice = full_marks(&rubric);
+        twice.push(twice[0].clone());
+        assert!(matches!(self_assess(&rubric, &twice, ""), Err(GraderError::InvalidSelfAssessment(_))));
+
+        let mut over = full_marks(&rubric);
+        over[0].score += 1;
+        assert!(matches!(self_assess(&rubric, &over, ""), Err(GraderError::InvalidSelfAssessment(_))));
+
+        let mut unknown = full_marks(&rubric);
+        unknown.push(SelfScore { category: "Vibes".to_string(), score: 0, note: String::new() });
+        assert!(matches!(self_assess(&rubric, &unknown, ""), Err(GraderError::InvalidSelfAssessment(_))));
+    }
+}
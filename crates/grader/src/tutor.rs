@@ -0,0 +1,206 @@
+//! Socratic tutor chat
+//!
+//! The tutor answers questions about the lecture the learner is on. Its
+//! system prompt embeds the lecture and the learner's mastery of each skill,
+//! and asks it to guide with questions rather than hand out answers. Tokens
+//! are estimated rather than counted, and the prompt is fitted to a budget:
+//! the lecture is cut to its share and only the newest messages that fit
+//! are sent.
+
+use serde::{Deserialize, Serialize};
+
+/// Rough characters per token for English prose and code
+const CHARS_PER_TOKEN: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatRole {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn user(content: impl Into<String>) -> Self {
+        Self { role: ChatRole::User, content: content.into() }
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self { role: ChatRole::Assistant, content: content.into() }
+    }
+}
+
+/// How well the learner knows a skill
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillMastery {
+    pub name: String,
+    /// 0-1
+    pub score: f64,
+    /// The lecture teaches this skill
+    pub in_lecture: bool,
+}
+
+/// What the tutor is grounded in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TutorContext {
+    pub lecture_title: String,
+    pub lecture_content: String,
+    pub skills: Vec<SkillMastery>,
+}
+
+/// Estimated tokens each part of the prompt may use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenBudget {
+    pub lecture_tokens: usize,
+    pub history_tokens: usize,
+}
+
+impl Default for TokenBudget {
+    fn default() -> Self {
+        Self {
+            lecture_tokens: 3000,
+            history_tokens: 2000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TutorReply {
+    pub content: String,
+    /// Estimated tokens sent, system prompt included
+    pub prompt_tokens: usize,
+    /// Older messages left out to stay within the budget
+    pub dropped_messages: usize,
+    pub latency_ms: u64,
+}
+
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+fn truncate_to_tokens(text: &str, tokens: usize) -> (&str, bool) {
+    match text.char_indices().nth(tokens * CHARS_PER_TOKEN) {
+        Some((end, _)) => (&text[..end], true),
+        None => (text, false),
+    }
+}
+
+pub(crate) fn build_system_prompt(context: &TutorContext, budget: &TokenBudget) -> String {
+    let (lecture, truncated) = truncate_to_tokens(&context.lecture_content, budget.lecture_tokens);
+    let lecture = if truncated { format!("{}\n\n[The rest of the lecture is omitted]", lecture) } else { lecture.to_string() };
+
+    let mut skills: Vec<&SkillMastery> = context.skills.iter().collect();
+    skills.sort_by_key(|s| !s.in_lecture);
+    let profile = if skills.is_empty() {
+        "No mastery recorded yet.".to_string()
+    } else {
+        skills
+            .iter()
+            .map(|s| {
+                let marker = if s.in_lecture { " (taught in this lecture)" } else { "" };
+                format!("- {}: {:.0}%{}", s.name, s.score * 100.0, marker)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    format!(
+        r#"You are a Socratic tutor in a Rust bootcamp, helping a learner with the lecture "{}".
+
+How to tutor:
+1. Guide with questions that lead the learner to the answer; don't hand out full solutions
+2. Ground explanations in the lecture below, and say so when a question goes beyond it
+3. Pitch explanations to the learner's mastery: build on strong skills, slow down on weak ones
+4. Keep replies short, and end with a question when the learner is working something out
+
+## Learner's Mastery
+{}
+
+## Lecture
+{}"#,
+        context.lecture_title, profile, lecture
+    )
+}
+
+/// The newest messages that fit the history budget. The latest message is
+/// always kept, and the kept history starts with a learner message.
+pub(crate) fn fit_history<'a>(history: &'a [ChatMessage], budget: &TokenBudget) -> &'a [ChatMessage] {
+    if history.is_empty() {
+        return history;
+    }
+
+    let mut start = history.len();
+    let mut used = 0;
+    while start > 0 {
+        let tokens = estimate_tokens(&history[start - 1].content);
+        if start < history.len() && used + tokens > budget.history_tokens {
+            break;
+        }
+        used += tokens;
+        start -= 1;
+    }
+    while start < history.len() - 1 && history[start].role != ChatRole::User {
+        start += 1;
+    }
+    &history[start..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(lecture_content: &str) -> TutorContext {
+        TutorContext {
+            lecture_title: "Ownership".to_string(),
+            lecture_content: lecture_content.to_string(),
+            skills: vec![
+                SkillMastery { name: "Traits".to_string(), score: 0.9, in_lecture: false },
+                SkillMastery { name: "Borrowing".to_string(), score: 0.25, in_lecture: true },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_system_prompt_embeds_lecture_and_mastery() {
+        let prompt = build_system_prompt(&context("Each value has one owner."), &TokenBudget::default());
+        assert!(prompt.contains("Each value has one owner."));
+        assert!(prompt.contains("- Borrowing: 25% (taught in this lecture)\n- Traits: 90%"));
+        assert!(!prompt.contains("omitted"));
+    }
+
+    #[test]
+    fn test_long_lecture_is_cut_to_budget() {
+        let budget = TokenBudget { lecture_tokens: 10, history_tokens: 100 };
+        let prompt = build_system_prompt(&context(&"ownership ".repeat(100)), &budget);
+        assert!(prompt.contains("[The rest of the lecture is omitted]"));
+        assert!(estimate_tokens(&prompt) < 300);
+    }
+
+    #[test]
+    fn test_history_keeps_newest_messages_within_budget() {
+        let history = vec![
+            ChatMessage::user("a".repeat(40)),
+            ChatMessage::assistant("b".repeat(40)),
+            ChatMessage::user("c".repeat(40)),
+            ChatMessage::assistant("d".repeat(40)),
+            ChatMessage::user("e".repeat(40)),
+        ];
+        let budget = TokenBudget { lecture_tokens: 100, history_tokens: 25 };
+
+        // Room for two messages, but history can't open with the tutor
+        let kept = fit_history(&history, &budget);
+        assert_eq!(kept, &history[4..]);
+
+        let budget = TokenBudget { lecture_tokens: 100, history_tokens: 30 };
+        assert_eq!(fit_history(&history, &budget), &history[2..]);
+
+        // The new message is sent even if it's over budget on its own
+        let budget = TokenBudget { lecture_tokens: 100, history_tokens: 1 };
+        assert_eq!(fit_history(&history, &budget).len(), 1);
+    }
+}
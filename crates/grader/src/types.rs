@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 
 /// Result of grading an artifact
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct GradeResult {
     /// Total score (0-100)
     pub score: u32,
@@ -17,6 +18,9 @@ pub struct GradeResult {
     pub from_cache: bool,
     /// Latency in milliseconds (0 if from cache)
     pub latency_ms: u64,
+    /// Scored by the learner against the rubric rather than by a model
+    #[serde(default)]
+    pub self_assessed: bool,
 }
 
 impl GradeResult {
@@ -34,6 +38,7 @@ impl GradeResult {
             category_scores,
             from_cache: false,
             latency_ms,
+            self_assessed: false,
         }
     }
 
@@ -63,6 +68,7 @@ impl GradeResult {
 
 /// Score for a single category in the rubric
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct CategoryScore {
     /// Category name
     pub category: String,
@@ -97,7 +103,7 @@ impl CategoryScore {
 /// Configuration for the grader
 #[derive(Debug, Clone)]
 pub struct GraderConfig {
-    /// OpenAI model to use
+    /// Model to use
     pub model: String,
     /// Temperature for LLM (lower = more consistent)
     pub temperature: f32,
@@ -0,0 +1,515 @@
+//! Structural comparison of a submission with the reference solution
+//!
+//! Both sides are parsed with `syn`, so formatting, comments and whitespace
+//! don't count as differences. Top-level items are matched by name and
+//! compared as token streams; a visitor tallies the idioms each side uses
+//! (cloning, unwrapping, loops versus iterator adapters, ...) and turns the
+//! notable differences into short notes for the learner's recap.
+//!
+//! The same normalization gives a fingerprint of a whole file, so a
+//! submission that is the reference solution reformatted can be spotted.
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use syn::visit::{self, Visit};
+
+use crate::error::RunnerError;
+
+/// Iterator adapters that replace a hand-written loop
+const ITERATOR_ADAPTERS: &[&str] = &[
+    "map", "filter", "filter_map", "fold", "sum", "product", "any", "all", "find", "position", "collect", "for_each",
+    "flat_map", "zip", "enumerate", "count", "max", "min", "max_by_key", "min_by_key",
+];
+
+/// Types that share or move data to the heap, and so are worth pointing out
+/// when the solution manages without them
+const SMART_POINTERS: &[&str] = &["Box", "Rc", "Arc", "RefCell", "Cell", "Mutex"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "snake_case")]
+pub enum ItemChange {
+    Same,
+    Changed,
+    /// Only the submission has an item by this name
+    Added,
+    /// Only the solution has an item by this name
+    Missing,
+}
+
+/// How one top-level item compares
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ItemDiff {
+    /// `fn parse`, `struct Point`, ...
+    pub name: String,
+    pub change: ItemChange,
+    /// Share of tokens the two versions have in common, 0-1
+    pub similarity: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "snake_case")]
+pub enum DiffTopic {
+    Cloning,
+    ErrorHandling,
+    Iteration,
+    Indexing,
+    Mutability,
+    SmartPointers,
+    Unsafe,
+    StringParams,
+}
+
+/// A difference worth explaining
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct DiffNote {
+    pub topic: DiffTopic,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct SolutionDiff {
+    /// Share of tokens the submission and solution have in common, 0-1
+    pub similarity: f64,
+    pub items: Vec<ItemDiff>,
+    pub notes: Vec<DiffNote>,
+}
+
+/// Compare a submission with the reference solution. Test modules are left
+/// out of both sides.
+pub fn compare_with_solution(submission: &str, solution: &str) -> Result<SolutionDiff, RunnerError> {
+    let submission = parse(submission, "submission")?;
+    let solution = parse(solution, "solution")?;
+
+    let submitted_items = named_items(&submission);
+    let solution_items = named_items(&solution);
+
+    let mut items = Vec::new();
+    for (name, tokens) in &solution_items {
+        match submitted_items.iter().find(|(n, _)| n == name) {
+            Some((_, submitted)) => {
+                let similarity = similarity(submitted, tokens);
+                let change = if submitted == tokens { ItemChange::Same } else { ItemChange::Changed };
+                items.push(ItemDiff { name: name.clone(), change, similarity });
+            }
+            None => items.push(ItemDiff { name: name.clone(), change: ItemChange::Missing, similarity: 0.0 }),
+        }
+    }
+    for (name, _) in &submitted_items {
+        if !solution_items.iter().any(|(n, _)| n == name) {
+            items.push(ItemDiff { name: name.clone(), change: ItemChange::Added, similarity: 0.0 });
+        }
+    }
+
+    let all_submitted: Vec<String> = submitted_items.into_iter().flat_map(|(_, tokens)| tokens).collect();
+    let all_solution: Vec<String> = solution_items.into_iter().flat_map(|(_, tokens)| tokens).collect();
+
+    Ok(SolutionDiff {
+        similarity: similarity(&all_submitted, &all_solution),
+        items,
+        notes: notes(&Idioms::of(&submission), &Idioms::of(&solution)),
+    })
+}
+
+/// Hash of a file's tokens outside test modules, with doc comments left
+/// out, so formatting, whitespace and comments don't change it
+pub fn normalized_hash(source: &str) -> Result<String, RunnerError> {
+    let file = parse(source, "source")?;
+    let tokens = strip_doc_comments(named_items(&file).into_iter().flat_map(|(_, tokens)| tokens).collect());
+
+    let mut hasher = Sha256::new();
+    hasher.update(tokens.join(" ").as_bytes());
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Whether a submission is the reference solution apart from formatting and
+/// comments. Either side failing to parse counts as no match.
+pub fn matches_solution(submission: &str, solution: &str) -> bool {
+    match (normalized_hash(submission), normalized_hash(solution)) {
+        (Ok(submitted), Ok(reference)) => submitted == reference,
+        _ => false,
+    }
+}
+
+fn parse(source: &str, side: &str) -> Result<syn::File, RunnerError> {
+    syn::parse_file(source).map_err(|e| RunnerError::InvalidSource(format!("{}: {}", side, e)))
+}
+
+fn is_test_module(item: &syn::Item) -> bool {
+    let syn::Item::Mod(module) = item else {
+        return false;
+    };
+    module.attrs.iter().any(|attr| {
+        attr.path().is_ident("cfg") && attr.meta.to_token_stream().to_string().replace(' ', "") == "cfg(test)"
+    })
+}
+
+/// Top-level items in source order, each with its flattened tokens
+fn named_items(file: &syn::File) -> Vec<(String, Vec<String>)> {
+    file.items
+        .iter()
+        .filter(|item| !is_test_module(item))
+        .map(|item| (item_name(item), flatten(item.to_token_stream())))
+        .collect()
+}
+
+fn item_name(item: &syn::Item) -> String {
+    match item {
+        syn::Item::Fn(f) => format!("fn {}", f.sig.ident),
+        syn::Item::Struct(s) => format!("struct {}", s.ident),
+        syn::Item::Enum(e) => format!("enum {}", e.ident),
+        syn::Item::Trait(t) => format!("trait {}", t.ident),
+        syn::Item::Type(t) => format!("type {}", t.ident),
+        syn::Item::Const(c) => format!("const {}", c.ident),
+        syn::Item::Static(s) => format!("static {}", s.ident),
+        syn::Item::Mod(m) => format!("mod {}", m.ident),
+        syn::Item::Impl(i) => {
+            let self_ty = i.self_ty.to_token_stream().to_string();
+            match &i.trait_ {
+                Some((_, path, _)) => format!("impl {} for {}", path.to_token_stream(), self_ty),
+                None => format!("impl {}", self_ty),
+            }
+        }
+        syn::Item::Use(u) => format!("use {}", u.tree.to_token_stream()),
+        other => other.to_token_stream().to_string(),
+    }
+}
+
+/// Tokens as strings, with groups opened and closed by their delimiters so
+/// nesting still counts
+fn flatten(stream: TokenStream) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for tree in stream {
+        match tree {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    proc_macro2::Delimiter::Parenthesis => ("(", ")"),
+                    proc_macro2::Delimiter::Brace => ("{", "}"),
+                    proc_macro2::Delimiter::Bracket => ("[", "]"),
+                    proc_macro2::Delimiter::None => ("", ""),
+                };
+                tokens.push(open.to_string());
+                tokens.extend(flatten(group.stream()));
+                tokens.push(close.to_string());
+            }
+            other => tokens.push(other.to_string()),
+        }
+    }
+    tokens
+}
+
+/// Drop `#[doc = "..."]` and `#![doc = "..."]`, which is what doc comments
+/// turn into, from flattened tokens
+fn strip_doc_comments(tokens: Vec<String>) -> Vec<String> {
+    let mut kept = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+    while i < tokens.len() {
+        let start = if tokens.get(i + 1).map(String::as_str) == Some("!") { i + 2 } else { i + 1 };
+        let is_doc = tokens[i] == "#"
+            && tokens.get(start).map(String::as_str) == Some("[")
+            && tokens.get(start + 1).map(String::as_str) == Some("doc")
+            && tokens.get(start + 2).map(String::as_str) == Some("=")
+            && tokens.get(start + 4).map(String::as_str) == Some("]");
+        if is_doc {
+            i = start + 5;
+        } else {
+            kept.push(tokens[i].clone());
+            i += 1;
+        }
+    }
+    kept
+}
+
+/// Longest common subsequence over the longer side's length
+fn similarity(a: &[String], b: &[String]) -> f64 {
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    let mut previous = vec![0usize; b.len() + 1];
+    let mut current = vec![0usize; b.len() + 1];
+    for token in a {
+        for (j, other) in b.iter().enumerate() {
+            current[j + 1] = if token == other { previous[j] + 1 } else { current[j].max(previous[j + 1]) };
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()] as f64 / longest as f64
+}
+
+/// How often one side uses each idiom the notes talk about
+#[derive(Debug, Default)]
+struct Idioms {
+    method_calls: HashMap<String, usize>,
+    try_operators: usize,
+    borrows: usize,
+    for_loops: usize,
+    while_loops: usize,
+    indexing: usize,
+    mutable_bindings: usize,
+    unsafe_blocks: usize,
+    string_params: usize,
+    str_params: usize,
+    types: HashMap<String, usize>,
+}
+
+impl Idioms {
+    fn of(file: &syn::File) -> Self {
+        let mut idioms = Self::default();
+        for item in file.items.iter().filter(|item| !is_test_module(item)) {
+            idioms.visit_item(item);
+        }
+        idioms
+    }
+
+    fn calls(&self, method: &str) -> usize {
+        self.method_calls.get(method).copied().unwrap_or(0)
+    }
+
+    fn adapter_calls(&self) -> usize {
+        ITERATOR_ADAPTERS.iter().map(|m| self.calls(m)).sum()
+    }
+
+    fn uses_type(&self, name: &str) -> bool {
+        self.types.contains_key(name)
+    }
+}
+
+impl<'ast> Visit<'ast> for Idioms {
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        *self.method_calls.entry(call.method.to_string()).or_default() += 1;
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_expr_try(&mut self, expr: &'ast syn::ExprTry) {
+        self.try_operators += 1;
+        visit::visit_expr_try(self, expr);
+    }
+
+    fn visit_expr_reference(&mut self, expr: &'ast syn::ExprReference) {
+        self.borrows += 1;
+        visit::visit_expr_reference(self, expr);
+    }
+
+    fn visit_expr_for_loop(&mut self, expr: &'ast syn::ExprForLoop) {
+        self.for_loops += 1;
+        visit::visit_expr_for_loop(self, expr);
+    }
+
+    fn visit_expr_while(&mut self, expr: &'ast syn::ExprWhile) {
+        self.while_loops += 1;
+        visit::visit_expr_while(self, expr);
+    }
+
+    fn visit_expr_index(&mut self, expr: &'ast syn::ExprIndex) {
+        self.indexing += 1;
+        visit::visit_expr_index(self, expr);
+    }
+
+    fn visit_pat_ident(&mut self, pat: &'ast syn::PatIdent) {
+        if pat.mutability.is_some() {
+            self.mutable_bindings += 1;
+        }
+        visit::visit_pat_ident(self, pat);
+    }
+
+    fn visit_expr_unsafe(&mut self, expr: &'ast syn::ExprUnsafe) {
+        self.unsafe_blocks += 1;
+        visit::visit_expr_unsafe(self, expr);
+    }
+
+    fn visit_fn_arg(&mut self, arg: &'ast syn::FnArg) {
+        if let syn::FnArg::Typed(arg) = arg {
+            match &*arg.ty {
+                syn::Type::Path(path) if path.path.is_ident("String") => self.string_params += 1,
+                syn::Type::Reference(reference) => {
+                    if let syn::Type::Path(path) = &*reference.elem {
+                        if path.path.is_ident("str") {
+                            self.str_params += 1;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        visit::visit_fn_arg(self, arg);
+    }
+
+    fn visit_path_segment(&mut self, segment: &'ast syn::PathSegment) {
+        *self.types.entry(segment.ident.to_string()).or_default() += 1;
+        visit::visit_path_segment(self, segment);
+    }
+}
+
+fn times(n: usize) -> String {
+    if n == 1 { "once".to_string() } else { format!("{} times", n) }
+}
+
+fn notes(submission: &Idioms, solution: &Idioms) -> Vec<DiffNote> {
+    let mut notes = Vec::new();
+    let mut note = |topic, message: String| notes.push(DiffNote { topic, message });
+
+    let (clones, solution_clones) = (submission.calls("clone"), solution.calls("clone"));
+    if clones > solution_clones {
+        let how = if solution.borrows > submission.borrows { ", borrowing instead" } else { "" };
+        note(
+            DiffTopic::Cloning,
+            format!(
+                "You call clone() {}; the solution does {}{}",
+                times(clones),
+                if solution_clones == 0 { "without it".to_string() } else { format!("it {}", times(solution_clones)) },
+                how
+            ),
+        );
+    }
+
+    let unwraps = submission.calls("unwrap") + submission.calls("expect");
+    let solution_unwraps = solution.calls("unwrap") + solution.calls("expect");
+    if unwraps > solution_unwraps {
+        let message = if solution.try_operators > submission.try_operators {
+            format!("You unwrap {}; the solution passes errors on with ?", times(unwraps))
+        } else {
+            format!("You unwrap {}; the solution handles the missing case instead of panicking", times(unwraps))
+        };
+        note(DiffTopic::ErrorHandling, message);
+    }
+
+    let loops = submission.for_loops + submission.while_loops;
+    let solution_loops = solution.for_loops + solution.while_loops;
+    if loops > solution_loops && solution.adapter_calls() > submission.adapter_calls() {
+        note(
+            DiffTopic::Iteration,
+            "You wrote a loop by hand; the solution uses iterator adapters like map, filter and sum".to_string(),
+        );
+    } else if solution_loops > loops && submission.adapter_calls() > solution.adapter_calls() {
+        note(
+            DiffTopic::Iteration,
+            "You used iterator adapters where the solution writes a plain loop; both are fine".to_string(),
+        );
+    }
+
+    if submission.indexing > solution.indexing && solution.calls("get") > submission.calls("get") {
+        note(
+            DiffTopic::Indexing,
+            "You index with [], which panics when out of bounds; the solution uses get()".to_string(),
+        );
+    }
+
+    if submission.mutable_bindings > solution.mutable_bindings {
+        note(
+            DiffTopic::Mutability,
+            format!(
+                "You declare {} mutable binding(s); the solution needs {}",
+                submission.mutable_bindings, solution.mutable_bindings
+            ),
+        );
+    }
+
+    for pointer in SMART_POINTERS {
+        if submission.uses_type(pointer) && !solution.uses_type(pointer) {
+            note(DiffTopic::SmartPointers, format!("You reached for {}; the solution doesn't need it", pointer));
+        }
+    }
+
+    if submission.unsafe_blocks > 0 && solution.unsafe_blocks == 0 {
+        note(DiffTopic::Unsafe, "You used unsafe; the solution manages in safe Rust".to_string());
+    }
+
+    if submission.string_params > solution.string_params && solution.str_params > submission.str_params {
+        note(
+            DiffTopic::StringParams,
+            "You take String parameters; the solution borrows &str, so callers needn't give up ownership".to_string(),
+        );
+    }
+
+    notes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOLUTION: &str = r#"
+        pub fn total_length(words: &[&str]) -> usize {
+            words.iter().map(|w| w.len()).sum()
+        }
+
+        pub fn first_word(text: &str) -> Option<&str> {
+            text.split_whitespace().next()
+        }
+
+        #[cfg(test)]
+        mod tests {
+            #[test]
+            fn it_works() {}
+        }
+    "#;
+
+    #[test]
+    fn test_formatting_is_not_a_difference() {
+        let submission = "pub fn total_length(words:&[&str])->usize{words.iter().map(|w|w.len()).sum()}
+                          // first word
+                          pub fn first_word(text: &str) -> Option<&str> { text.split_whitespace().next() }";
+        let diff = compare_with_solution(submission, SOLUTION).unwrap();
+
+        assert!(diff.items.iter().all(|item| item.change == ItemChange::Same));
+        assert_eq!(diff.similarity, 1.0);
+        assert!(diff.notes.is_empty());
+    }
+
+    #[test]
+    fn test_notes_explain_notable_differences() {
+        let submission = r#"
+            pub fn total_length(words: Vec<String>) -> usize {
+                let mut total = 0;
+                for word in words.clone() {
+                    total += word.clone().len();
+                }
+                total
+            }
+
+            pub fn helper() {}
+        "#;
+        let diff = compare_with_solution(submission, SOLUTION).unwrap();
+
+        let topics: Vec<DiffTopic> = diff.notes.iter().map(|n| n.topic).collect();
+        assert!(topics.contains(&DiffTopic::Cloning));
+        assert!(topics.contains(&DiffTopic::Iteration));
+        assert!(topics.contains(&DiffTopic::Mutability));
+        assert!(diff.notes[0].message.contains("clone() 2 times"));
+
+        let change = |name: &str| diff.items.iter().find(|i| i.name == name).map(|i| i.change);
+        assert_eq!(change("fn total_length"), Some(ItemChange::Changed));
+        assert_eq!(change("fn first_word"), Some(ItemChange::Missing));
+        assert_eq!(change("fn helper"), Some(ItemChange::Added));
+        assert_eq!(change("mod tests"), None);
+        assert!(diff.similarity < 1.0);
+    }
+
+    #[test]
+    fn test_reformatted_solution_matches_its_hash() {
+        let pasted = "/// Sums the lengths\npub fn total_length(words:&[&str])->usize{words.iter().map(|w|w.len()).sum()}
+                      pub fn first_word(text: &str) -> Option<&str> {
+                          // the first one
+                          text.split_whitespace().next()
+                      }";
+        assert!(matches_solution(pasted, SOLUTION));
+
+        let edited = pasted.replace("next()", "last()");
+        assert!(!matches_solution(&edited, SOLUTION));
+        assert!(!matches_solution("fn (", SOLUTION));
+    }
+
+    #[test]
+    fn test_unparseable_code_is_rejected() {
+        assert!(matches!(compare_with_solution("fn (", SOLUTION), Err(RunnerError::InvalidSource(_))));
+    }
+}
@@ -4,7 +4,7 @@
 
 use bollard::container::{
     Config, CreateContainerOptions, LogOutput, LogsOptions, RemoveContainerOptions,
-    StartContainerOptions, WaitContainerOptions,
+    StartContainerOptions, Stats, StatsOptions, WaitContainerOptions,
 };
 use bollard::models::{HostConfig, Mount, MountTypeEnum};
 use bollard::Docker;
@@ -16,8 +16,9 @@ use tokio::time::timeout;
 use uuid::Uuid;
 
 use crate::error::RunnerError;
-use crate::parser::parse_cargo_output;
-use crate::types::{DockerConfig, RuntimeError, VerificationResult};
+use crate::parser::{parse_cargo_output, parse_check_output};
+use crate::policy::apply_dependency_policy;
+use crate::types::{CheckResult, DockerConfig, ResourceUsage, RuntimeError, VerificationResult};
 
 /// Docker-based code runner
 pub struct DockerRunner {
@@ -42,6 +43,13 @@ impl DockerRunner {
         Ok(Self { docker, config })
     }
 
+    /// Verify in `image` instead of the configured image, e.g. one a
+    /// challenge template built with its dependencies
+    pub fn with_image(mut self, image: impl Into<String>) -> Self {
+        self.config.image_name = image.into();
+        self
+    }
+
     /// Check if Docker is available
     pub async fn check_available() -> Result<bool, RunnerError> {
         let docker = Docker::connect_with_local_defaults()
@@ -76,16 +84,186 @@ impl DockerRunner {
         // Copy challenge files and write student code
         self.prepare_challenge_dir(challenge_dir, work_dir, student_code)?;
 
+        self.verify_in_container(work_dir, Vec::new(), start).await
+    }
+
+    /// Run verification with the learner's own `Cargo.toml`, for challenges
+    /// that let them add dependencies from `allowlist`. Submissions breaking
+    /// the policy come back as a policy violation without being run.
+    pub async fn run_verification_with_dependencies(
+        &self,
+        challenge_dir: &Path,
+        student_code: &str,
+        cargo_toml: &str,
+        allowlist: &[String],
+    ) -> Result<VerificationResult, RunnerError> {
+        let base_toml = std::fs::read_to_string(challenge_dir.join("Cargo.toml"))?;
+        let merged = match apply_dependency_policy(&base_toml, cargo_toml, allowlist)? {
+            Ok(merged) => merged,
+            Err(violation) => {
+                tracing::info!(crates = ?violation.crates, "Submission breaks the dependency policy");
+                return Ok(VerificationResult::policy_violation(violation));
+            }
+        };
+        let start = Instant::now();
+
+        let temp_dir = tempfile::tempdir()?;
+        let work_dir = temp_dir.path();
+        self.prepare_challenge_dir(challenge_dir, work_dir, student_code)?;
+        std::fs::write(work_dir.join("Cargo.toml"), merged)?;
+
+        self.verify_in_container(work_dir, Vec::new(), start).await
+    }
+
+    /// Run one day of a project track against the learner's own repo. The
+    /// repo is copied as it is and the day's tests are added as the
+    /// integration test `tests/<test_name>.rs`, which is the only test
+    /// target run, so earlier days' tests don't count against this one.
+    pub async fn run_project_verification(
+        &self,
+        repo_dir: &Path,
+        test_name: &str,
+        test_code: &str,
+    ) -> Result<VerificationResult, RunnerError> {
+        if test_name.is_empty() || !test_name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(RunnerError::InvalidSource(format!("invalid test target name: {}", test_name)));
+        }
+        if !repo_dir.join("Cargo.toml").exists() {
+            return Err(RunnerError::InvalidSource(format!(
+                "{} is not a Cargo project",
+                repo_dir.display()
+            )));
+        }
+        let start = Instant::now();
+
+        let temp_dir = tempfile::tempdir()?;
+        let work_dir = temp_dir.path();
+        prepare_project_dir(repo_dir, work_dir, test_name, test_code)?;
+
+        self.verify_in_container(
+            work_dir,
+            vec!["--test".to_string(), test_name.to_string()],
+            start,
+        )
+        .await
+    }
+
+    /// Run `cargo test` with extra arguments in a fresh container and clean
+    /// it up afterwards, running failed tests again up to `max_reruns` times
+    async fn verify_in_container(
+        &self,
+        work_dir: &Path,
+        test_args: Vec<String>,
+        start: Instant,
+    ) -> Result<VerificationResult, RunnerError> {
+        let mut result = self.test_in_container(work_dir, test_args.clone(), start).await;
+
+        if let Ok(verification) = &mut result {
+            while verification.reruns < self.config.max_reruns && verification.can_rerun() {
+                tracing::info!(tests = ?verification.failed_tests, "Running failed tests again");
+                let rerun_args = test_args
+                    .iter()
+                    .cloned()
+                    .chain(["--".to_string(), "--exact".to_string()])
+                    .chain(verification.failed_tests.iter().cloned())
+                    .collect();
+                match self.test_in_container(work_dir, rerun_args, start).await {
+                    Ok(rerun) => {
+                        verification.merge_rerun(&rerun);
+                        verification.duration_ms = rerun.duration_ms;
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Rerun failed");
+                        break;
+                    }
+                }
+            }
+            if !verification.flaky_tests.is_empty() {
+                tracing::warn!(tests = ?verification.flaky_tests, "Tests passed only when run again");
+            }
+        }
+
+        match &result {
+            Ok(verification) => tracing::info!(
+                success = verification.success,
+                tests_passed = verification.tests_passed,
+                tests_total = verification.tests_total,
+                reruns = verification.reruns,
+                duration_ms = verification.duration_ms,
+                "Verification finished"
+            ),
+            Err(e) => tracing::error!(error = %e, "Verification failed"),
+        }
+        result
+    }
+
+    /// One `cargo test` run
+    async fn test_in_container(
+        &self,
+        work_dir: &Path,
+        test_args: Vec<String>,
+        start: Instant,
+    ) -> Result<VerificationResult, RunnerError> {
+        let run = self.run_and_clean_up(work_dir, Mode::Test(test_args), start).await?;
+        let mut result = match run.output {
+            Some((stdout, stderr, exit_code)) => {
+                let mut result = parse_cargo_output(&stdout, &stderr, run.duration_ms);
+
+                // Check for OOM kill (exit code 137)
+                if exit_code == 137 {
+                    result.runtime_error = Some(RuntimeError::OutOfMemory);
+                    result.success = false;
+                }
+                result
+            }
+            None => VerificationResult::runtime_error(RuntimeError::Timeout, run.duration_ms),
+        };
+        result.resource_usage = run.usage;
+        Ok(result)
+    }
+
+    /// Compile a challenge with `cargo check`, without running its tests,
+    /// under the shorter check-only limits. Much faster than verifying, for
+    /// a quick look at compile errors before submitting.
+    pub async fn run_check_only(&self, challenge_dir: &Path, student_code: &str) -> Result<CheckResult, RunnerError> {
+        let start = Instant::now();
+
+        let temp_dir = tempfile::tempdir()?;
+        let work_dir = temp_dir.path();
+        self.prepare_challenge_dir(challenge_dir, work_dir, student_code)?;
+
+        let run = self.run_and_clean_up(work_dir, Mode::Check, start).await?;
+        let result = match run.output {
+            Some((stdout, _, _)) => parse_check_output(&stdout, run.duration_ms),
+            None => CheckResult {
+                success: false,
+                diagnostics: Vec::new(),
+                duration_ms: run.duration_ms,
+                timed_out: true,
+            },
+        };
+        tracing::info!(
+            success = result.success,
+            diagnostics = result.diagnostics.len(),
+            duration_ms = result.duration_ms,
+            "Check finished"
+        );
+        Ok(result)
+    }
+
+    /// Run a fresh container and clean it up afterwards
+    async fn run_and_clean_up(&self, work_dir: &Path, mode: Mode, start: Instant) -> Result<ContainerRun, RunnerError> {
         // Generate unique container name
         let container_name = format!("challenge-{}", Uuid::new_v4());
 
         // Create and run container
-        let result = self
-            .run_container(&container_name, work_dir, start)
-            .await;
+        tracing::info!(container = %container_name, image = %self.config.image_name, mode = mode.name(), "Running container");
+        let result = self.run_container(&container_name, work_dir, mode, start).await;
 
         // Cleanup container (best effort)
-        let _ = self.cleanup_container(&container_name).await;
+        if let Err(e) = self.cleanup_container(&container_name).await {
+            tracing::warn!(container = %container_name, error = %e, "Failed to remove container");
+        }
 
         result
     }
@@ -110,16 +288,22 @@ impl DockerRunner {
         Ok(())
     }
 
-    /// Run the container and collect results
+    /// Run the container and collect its output
     async fn run_container(
         &self,
         container_name: &str,
         work_dir: &Path,
+        mode: Mode,
         start: Instant,
-    ) -> Result<VerificationResult, RunnerError> {
+    ) -> Result<ContainerRun, RunnerError> {
+        let (memory_limit, time_limit) = match mode {
+            Mode::Test(_) => (self.config.memory_limit, self.config.timeout),
+            Mode::Check => (self.config.check_memory_limit, self.config.check_timeout),
+        };
+
         // Container configuration
         let host_config = HostConfig {
-            memory: Some(self.config.memory_limit as i64),
+            memory: Some(memory_limit as i64),
             nano_cpus: Some((self.config.cpu_limit * 1_000_000_000.0) as i64),
             network_mode: Some(self.config.network_mode.as_str().to_string()),
             pids_limit: Some(100), // Prevent fork bombs
@@ -138,11 +322,7 @@ impl DockerRunner {
 
         let config = Config {
             image: Some(self.config.image_name.clone()),
-            cmd: Some(vec![
-                "cargo".to_string(),
-                "test".to_string(),
-                "--message-format=json".to_string(),
-            ]),
+            cmd: Some(mode.command()),
             working_dir: Some("/challenge".to_string()),
             host_config: Some(host_config),
             labels: Some({
@@ -170,33 +350,44 @@ impl DockerRunner {
             .await
             .map_err(|e| RunnerError::ExecutionFailed(e.to_string()))?;
 
-        // Wait for container with timeout
-        let wait_result = timeout(self.config.timeout, self.wait_for_container(container_name)).await;
+        // Wait for container with timeout, sampling its stats meanwhile
+        let started = Instant::now();
+        let wait = timeout(time_limit, self.wait_for_container(container_name));
+        tokio::pin!(wait);
+        let mut stats = self.docker.stats(
+            container_name,
+            Some(StatsOptions {
+                stream: true,
+                one_shot: false,
+            }),
+        );
+        let mut usage: Option<ResourceUsage> = None;
+        let wait_result = loop {
+            tokio::select! {
+                result = &mut wait => break result,
+                Some(Ok(sample)) = stats.next() => record_stats(usage.get_or_insert_with(Default::default), &sample),
+            }
+        };
+        if let Some(usage) = &mut usage {
+            usage.wall_time_ms = started.elapsed().as_millis() as u64;
+        }
 
         let duration_ms = start.elapsed().as_millis() as u64;
 
         match wait_result {
             Ok(Ok((stdout, stderr, exit_code))) => {
-                // Parse the output
-                let mut result = parse_cargo_output(&stdout, &stderr, duration_ms);
-                
-                // Check for OOM kill (exit code 137)
                 if exit_code == 137 {
-                    result.runtime_error = Some(RuntimeError::OutOfMemory);
-                    result.success = false;
+                    tracing::warn!(container = %container_name, "Container ran out of memory");
                 }
-
-                Ok(result)
+                Ok(ContainerRun { output: Some((stdout, stderr, exit_code)), usage, duration_ms })
             }
             Ok(Err(e)) => Err(e),
             Err(_) => {
                 // Timeout - kill container
+                tracing::warn!(container = %container_name, timeout_secs = time_limit.as_secs(), "Container timed out");
                 let _ = self.docker.kill_container(container_name, None::<bollard::container::KillContainerOptions<String>>).await;
-                
-                Ok(VerificationResult::runtime_error(
-                    RuntimeError::Timeout,
-                    duration_ms,
-                ))
+
+                Ok(ContainerRun { output: None, usage, duration_ms })
             }
         }
     }
@@ -294,10 +485,98 @@ impl DockerRunner {
             }
         }
 
+        if cleaned > 0 {
+            tracing::info!(cleaned, "Removed orphaned challenge containers");
+        }
         Ok(cleaned)
     }
 }
 
+/// What a container is run for
+enum Mode {
+    /// `cargo test`, with extra arguments
+    Test(Vec<String>),
+    /// `cargo check`, with the check-only limits
+    Check,
+}
+
+impl Mode {
+    fn name(&self) -> &'static str {
+        match self {
+            Mode::Test(_) => "test",
+            Mode::Check => "check",
+        }
+    }
+
+    fn command(self) -> Vec<String> {
+        let (subcommand, extra) = match self {
+            Mode::Test(args) => ("test", args),
+            Mode::Check => ("check", Vec::new()),
+        };
+        ["cargo", subcommand, "--message-format=json"]
+            .into_iter()
+            .map(String::from)
+            .chain(extra)
+            .collect()
+    }
+}
+
+/// A finished container run
+struct ContainerRun {
+    /// Stdout, stderr and exit code, or `None` if it timed out
+    output: Option<(String, String, i64)>,
+    usage: Option<ResourceUsage>,
+    duration_ms: u64,
+}
+
+/// Fold one stats sample into `usage`. Docker's counters are cumulative, so
+/// the highest of each is kept; cgroup v2 has no peak memory figure, so
+/// there the peak is the highest usage sampled.
+fn record_stats(usage: &mut ResourceUsage, stats: &Stats) {
+    let memory = stats.memory_stats.max_usage.max(stats.memory_stats.usage).unwrap_or(0);
+    usage.peak_memory_bytes = usage.peak_memory_bytes.max(memory);
+    usage.cpu_time_ms = usage.cpu_time_ms.max(stats.cpu_stats.cpu_usage.total_usage / 1_000_000);
+
+    let written: u64 = stats
+        .blkio_stats
+        .io_service_bytes_recursive
+        .iter()
+        .flatten()
+        .filter(|entry| entry.op.eq_ignore_ascii_case("write"))
+        .map(|entry| entry.value)
+        .sum();
+    let written = written.max(stats.storage_stats.write_size_bytes.unwrap_or(0));
+    usage.bytes_written = usage.bytes_written.max(written);
+}
+
+/// Copy the learner's repo without build output and add the day's tests
+fn prepare_project_dir(
+    repo_dir: &Path,
+    work_dir: &Path,
+    test_name: &str,
+    test_code: &str,
+) -> Result<(), std::io::Error> {
+    for entry in std::fs::read_dir(repo_dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if name == "target" || name == ".git" {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            copy_dir_recursive(&path, &work_dir.join(&name))?;
+        } else {
+            std::fs::copy(&path, work_dir.join(&name))?;
+        }
+    }
+
+    let tests_dir = work_dir.join("tests");
+    std::fs::create_dir_all(&tests_dir)?;
+    std::fs::write(tests_dir.join(format!("{}.rs", test_name)), test_code)?;
+
+    Ok(())
+}
+
 /// Recursively copy a directory
 fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), std::io::Error> {
     if !dst.exists() {
@@ -336,6 +615,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_record_stats_keeps_highest() {
+        let sample = |usage: u64, cpu_ns: u64, written: u64| -> Stats {
+            serde_json::from_value(serde_json::json!({
+                "read": "2024-01-01T00:00:00Z",
+                "preread": "2024-01-01T00:00:00Z",
+                "num_procs": 0,
+                "pids_stats": {},
+                "memory_stats": { "usage": usage },
+                "blkio_stats": {
+                    "io_service_bytes_recursive": [
+                        { "major": 8, "minor": 0, "op": "read", "value": 999 },
+                        { "major": 8, "minor": 0, "op": "write", "value": written }
+                    ]
+                },
+                "cpu_stats": {
+                    "cpu_usage": { "total_usage": cpu_ns, "usage_in_usermode": 0, "usage_in_kernelmode": 0 },
+                    "throttling_data": { "periods": 0, "throttled_periods": 0, "throttled_time": 0 }
+                },
+                "precpu_stats": {
+                    "cpu_usage": { "total_usage": 0, "usage_in_usermode": 0, "usage_in_kernelmode": 0 },
+                    "throttling_data": { "periods": 0, "throttled_periods": 0, "throttled_time": 0 }
+                },
+                "storage_stats": {}
+            }))
+            .unwrap()
+        };
+
+        let mut usage = ResourceUsage::default();
+        record_stats(&mut usage, &sample(42 * 1024 * 1024, 1_500_000_000, 4096));
+        record_stats(&mut usage, &sample(10 * 1024 * 1024, 2_000_000_000, 8192));
+
+        assert_eq!(usage.peak_memory_bytes, 42 * 1024 * 1024);
+        assert_eq!(usage.cpu_time_ms, 2000);
+        assert_eq!(usage.bytes_written, 8192);
+    }
+
     #[test]
     fn test_copy_dir_recursive() {
         let temp_src = tempfile::tempdir().unwrap();
@@ -357,4 +673,24 @@ mod tests {
             "hello"
         );
     }
+
+    #[test]
+    fn test_prepare_project_dir_skips_build_output() {
+        let repo = tempfile::tempdir().unwrap();
+        let work = tempfile::tempdir().unwrap();
+        std::fs::write(repo.path().join("Cargo.toml"), "[package]").unwrap();
+        std::fs::create_dir_all(repo.path().join("src")).unwrap();
+        std::fs::write(repo.path().join("src/lib.rs"), "pub fn day1() {}").unwrap();
+        std::fs::create_dir_all(repo.path().join("target/debug")).unwrap();
+        std::fs::write(repo.path().join("target/debug/junk"), "x").unwrap();
+
+        prepare_project_dir(repo.path(), work.path(), "day2", "#[test] fn t() {}").unwrap();
+
+        assert!(work.path().join("src/lib.rs").exists());
+        assert!(!work.path().join("target").exists());
+        assert_eq!(
+            std::fs::read_to_string(work.path().join("tests/day2.rs")).unwrap(),
+            "#[test] fn t() {}"
+        );
+    }
 }
@@ -31,6 +31,12 @@ pub enum RunnerError {
 
     #[error("Failed to parse output: {0}")]
     ParseError(String),
+
+    #[error("Not valid Rust: {0}")]
+    InvalidSource(String),
+
+    #[error("Crate not available in this challenge: {0}")]
+    CrateNotAllowed(String),
 }
 
 impl From<bollard::errors::Error> for RunnerError {
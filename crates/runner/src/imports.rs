@@ -0,0 +1,91 @@
+//! Checking which crates a submission uses
+//!
+//! A challenge crate only has the dependencies its template lists, and the
+//! sandbox can't fetch others, so a submission reaching for another crate
+//! is turned away up front with a clear message rather than a build error
+//! from inside the container. Only top-level `use` and `extern crate` items
+//! are checked; anything subtler still fails to compile.
+
+use std::collections::HashSet;
+use syn::{Item, UseTree};
+
+use crate::error::RunnerError;
+
+/// Crates every Rust program can use, and path roots that aren't crates
+const BUILTIN_ROOTS: &[&str] = &["std", "core", "alloc", "test", "proc_macro", "crate", "self", "super"];
+
+/// Check `source` only uses crates from `allowed`, given as they're
+/// imported (`serde_json`, not `serde-json`). Code that doesn't parse is
+/// left for the compiler to report.
+pub fn check_crates(source: &str, allowed: &[String]) -> Result<(), RunnerError> {
+    let Ok(file) = syn::parse_file(source) else {
+        return Ok(());
+    };
+
+    // With uniform paths, `use` can also start at one of the file's own items
+    let mut known: HashSet<String> = BUILTIN_ROOTS.iter().map(|root| root.to_string()).collect();
+    known.extend(allowed.iter().cloned());
+    known.extend(file.items.iter().filter_map(item_name));
+
+    let mut unknown = Vec::new();
+    for item in &file.items {
+        match item {
+            Item::ExternCrate(extern_crate) => unknown.push(extern_crate.ident.to_string()),
+            Item::Use(item_use) => unknown.extend(use_roots(&item_use.tree)),
+            _ => {}
+        }
+    }
+    unknown.retain(|root| !known.contains(root.trim_start_matches("r#")));
+    unknown.sort();
+    unknown.dedup();
+
+    if unknown.is_empty() {
+        Ok(())
+    } else {
+        let available = if allowed.is_empty() { "none".to_string() } else { allowed.join(", ") };
+        Err(RunnerError::CrateNotAllowed(format!("{} (available: {})", unknown.join(", "), available)))
+    }
+}
+
+fn item_name(item: &Item) -> Option<String> {
+    let ident = match item {
+        Item::Const(item) => &item.ident,
+        Item::Enum(item) => &item.ident,
+        Item::Fn(item) => &item.sig.ident,
+        Item::Macro(item) => item.ident.as_ref()?,
+        Item::Mod(item) => &item.ident,
+        Item::Static(item) => &item.ident,
+        Item::Struct(item) => &item.ident,
+        Item::Trait(item) => &item.ident,
+        Item::Type(item) => &item.ident,
+        Item::Union(item) => &item.ident,
+        _ => return None,
+    };
+    Some(ident.to_string())
+}
+
+fn use_roots(tree: &UseTree) -> Vec<String> {
+    match tree {
+        UseTree::Path(path) => vec![path.ident.to_string()],
+        UseTree::Name(name) => vec![name.ident.to_string()],
+        UseTree::Rename(rename) => vec![rename.ident.to_string()],
+        UseTree::Glob(_) => Vec::new(),
+        UseTree::Group(group) => group.items.iter().flat_map(use_roots).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_crates() {
+        let allowed = vec!["serde".to_string()];
+        let source = "use std::collections::HashMap;\nuse serde::Serialize;\nmod util { pub fn f() {} }\nuse util::f;\n";
+        assert!(check_crates(source, &allowed).is_ok());
+
+        let source = "use rand::Rng;\nextern crate regex;\nuse {std::fmt, rand::random};\n";
+        let err = check_crates(source, &allowed).unwrap_err();
+        assert_eq!(err.to_string(), "Crate not available in this challenge: rand, regex (available: serde)");
+    }
+}
@@ -8,8 +8,16 @@ pub mod parser;
 pub mod types;
 pub mod docker;
 pub mod pool;
+pub mod diff;
+pub mod imports;
+pub mod policy;
 
 pub use error::RunnerError;
-pub use types::{DockerConfig, VerificationResult, CompileError, RuntimeError, ResourceLimit};
+pub use types::{
+    CheckResult, CompileError, Diagnostic, DiagnosticSpan, DockerConfig, ResourceLimit, ResourceUsage, RuntimeError, VerificationResult,
+};
 pub use docker::DockerRunner;
 pub use pool::ContainerPool;
+pub use diff::{compare_with_solution, matches_solution, normalized_hash, SolutionDiff};
+pub use imports::check_crates;
+pub use policy::{apply_dependency_policy, PolicyViolation};
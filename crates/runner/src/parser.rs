@@ -4,7 +4,7 @@
 //! to extract test results, compile errors, and other information.
 
 use serde::Deserialize;
-use crate::types::{VerificationResult, CompileError, RuntimeError, ResourceLimit};
+use crate::types::{CheckResult, Diagnostic, DiagnosticSpan, VerificationResult, CompileError, RuntimeError, ResourceLimit};
 
 /// Parse cargo test output and return a VerificationResult
 pub fn parse_cargo_output(output: &str, stderr: &str, duration_ms: u64) -> VerificationResult {
@@ -13,6 +13,11 @@ pub fn parse_cargo_output(output: &str, stderr: &str, duration_ms: u64) -> Verif
     let mut compile_error: Option<CompileError> = None;
     let mut build_success = true;
     let mut stdout_lines = Vec::new();
+    let mut diagnostics = Vec::new();
+    let mut failed_tests: Vec<String> = Vec::new();
+    // Tests counted from libtest's plain output, used if it gave no JSON
+    let mut plain_passed = 0u32;
+    let mut plain_failed = 0u32;
 
     // Parse each line of JSON output
     for line in output.lines() {
@@ -20,6 +25,17 @@ pub fn parse_cargo_output(output: &str, stderr: &str, duration_ms: u64) -> Verif
         if line.is_empty() || !line.starts_with('{') {
             // Collect non-JSON output for stdout
             if !line.is_empty() {
+                // libtest's plain output, e.g. "test tests::add ... FAILED"
+                if let Some(test) = line.strip_prefix("test ") {
+                    if test.ends_with(" ... ok") {
+                        plain_passed += 1;
+                    } else if let Some(name) = test.strip_suffix(" ... FAILED") {
+                        plain_failed += 1;
+                        if !failed_tests.iter().any(|failed| failed == name) {
+                            failed_tests.push(name.to_string());
+                        }
+                    }
+                }
                 stdout_lines.push(line.to_string());
             }
             continue;
@@ -38,14 +54,22 @@ pub fn parse_cargo_output(output: &str, stderr: &str, duration_ms: u64) -> Verif
                         };
                         compile_error = Some(error);
                     }
+                    if let Some(diagnostic) = to_diagnostic(message) {
+                        diagnostics.push(diagnostic);
+                    }
                 }
                 CargoMessage::BuildFinished { success } => {
                     build_success = success;
                 }
-                CargoMessage::Test { event, .. } => {
+                CargoMessage::Test { name, event } => {
                     match event.as_str() {
                         "ok" => tests_passed += 1,
-                        "failed" => tests_failed += 1,
+                        "failed" => {
+                            tests_failed += 1;
+                            if !failed_tests.contains(&name) {
+                                failed_tests.push(name);
+                            }
+                        }
                         _ => {}
                     }
                 }
@@ -74,13 +98,20 @@ pub fn parse_cargo_output(output: &str, stderr: &str, duration_ms: u64) -> Verif
     let runtime_error = detect_runtime_error(stderr);
     let resource_limit = detect_resource_limit(stderr);
 
+    if tests_passed + tests_failed == 0 {
+        tests_passed = plain_passed;
+        tests_failed = plain_failed;
+    }
+
     // Calculate total tests
     let tests_total = tests_passed + tests_failed;
 
     // Handle compile error case
     if let Some(error) = compile_error {
-        return VerificationResult::compile_error(error)
+        let mut result = VerificationResult::compile_error(error)
             .with_output(stdout_lines.join("\n"), stderr.to_string());
+        result.diagnostics = diagnostics;
+        return result;
     }
 
     // Handle runtime error case
@@ -88,6 +119,7 @@ pub fn parse_cargo_output(output: &str, stderr: &str, duration_ms: u64) -> Verif
         let mut result = VerificationResult::runtime_error(error, duration_ms)
             .with_output(stdout_lines.join("\n"), stderr.to_string());
         result.resource_limit_hit = resource_limit;
+        result.diagnostics = diagnostics;
         return result;
     }
 
@@ -103,10 +135,74 @@ pub fn parse_cargo_output(output: &str, stderr: &str, duration_ms: u64) -> Verif
     result.stdout = stdout_lines.join("\n");
     result.stderr = stderr.to_string();
     result.resource_limit_hit = resource_limit;
+    result.diagnostics = diagnostics;
+    result.failed_tests = failed_tests;
 
     result
 }
 
+/// Parse `cargo check --message-format=json` output into its diagnostics
+pub fn parse_check_output(output: &str, duration_ms: u64) -> CheckResult {
+    let mut diagnostics = Vec::new();
+    let mut build_success = false;
+
+    for line in output.lines().map(str::trim).filter(|line| line.starts_with('{')) {
+        match serde_json::from_str::<CargoMessage>(line) {
+            Ok(CargoMessage::CompilerMessage { message }) => diagnostics.extend(to_diagnostic(message)),
+            Ok(CargoMessage::BuildFinished { success }) => build_success = success,
+            _ => {}
+        }
+    }
+
+    CheckResult {
+        success: build_success && !diagnostics.iter().any(|d| d.level == "error"),
+        diagnostics,
+        duration_ms,
+        timed_out: false,
+    }
+}
+
+/// A compiler error or warning as a [`Diagnostic`], leaving out rustc's
+/// closing tallies and anything else that isn't about the code
+fn to_diagnostic(message: CompilerDiagnostic) -> Option<Diagnostic> {
+    if !matches!(message.level.as_str(), "error" | "warning") || is_summary(&message.message) {
+        return None;
+    }
+    Some(convert_diagnostic(message))
+}
+
+fn convert_diagnostic(message: CompilerDiagnostic) -> Diagnostic {
+    Diagnostic {
+        level: message.level,
+        message: message.message,
+        code: message.code.map(|code| code.code),
+        spans: message.spans.into_iter().filter_map(convert_span).collect(),
+        children: message.children.into_iter().map(convert_diagnostic).collect(),
+        rendered: message.rendered,
+    }
+}
+
+/// Spans without a location, which rustc only gives for internal
+/// diagnostics, are dropped
+fn convert_span(span: CompilerSpan) -> Option<DiagnosticSpan> {
+    Some(DiagnosticSpan {
+        file: span.file_name?,
+        line_start: span.line_start?,
+        line_end: span.line_end?,
+        column_start: span.column_start?,
+        column_end: span.column_end?,
+        is_primary: span.is_primary,
+        label: span.label,
+        suggested_replacement: span.suggested_replacement,
+        suggestion_applicability: span.suggestion_applicability,
+    })
+}
+
+/// rustc's closing tallies, e.g. "aborting due to 2 previous errors"
+fn is_summary(message: &str) -> bool {
+    message.starts_with("aborting due to") || message.ends_with("warnings emitted") || message.ends_with("warning emitted")
+}
+
 /// Detect runtime errors from stderr content
 fn detect_runtime_error(stderr: &str) -> Option<RuntimeError> {
     // Check for panic
@@ -175,7 +271,6 @@ enum CargoMessage {
 
 #[serde(rename = "test")]
     Test { 
-        #[allow(dead_code)]
         name: String,
         event: String,
     },
@@ -201,14 +296,37 @@ struct CompilerDiagnostic {
     message: String,
     level: String,
     #[serde(default)]
-    spans: Vec<DiagnosticSpan>,
+    code: Option<CompilerCode>,
+    #[serde(default)]
+    spans: Vec<CompilerSpan>,
+    #[serde(default)]
+    children: Vec<CompilerDiagnostic>,
+    #[serde(default)]
+    rendered: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompilerCode {
+    code: String,
 }
 
 #[derive(Debug, Deserialize)]
-struct DiagnosticSpan {
+struct CompilerSpan {
     file_name: Option<String>,
     line_start: Option<u32>,
+    #[serde(default)]
+    line_end: Option<u32>,
     column_start: Option<u32>,
+    #[serde(default)]
+    column_end: Option<u32>,
+    #[serde(default)]
+    is_primary: bool,
+    #[serde(default)]
+    label: Option<String>,
+    #[serde(default)]
+    suggested_replacement: Option<String>,
+    #[serde(default)]
+    suggestion_applicability: Option<String>,
 }
 
 #[cfg(test)]
@@ -250,6 +368,59 @@ mod tests {
         assert!(!result.success);
         assert_eq!(result.tests_passed, 2);
         assert_eq!(result.tests_failed, 1);
+        assert_eq!(result.failed_tests, vec!["test_sub"]);
+
+        let plain = "running 2 tests\ntest tests::add ... ok\ntest tests::slow ... FAILED\n{\"reason\":\"build-finished\",\"success\":true}";
+        let result = parse_cargo_output(plain, "", 1000);
+        assert_eq!((result.tests_passed, result.tests_failed), (1, 1));
+        assert_eq!(result.failed_tests, vec!["tests::slow"]);
+    }
+
+    #[test]
+    fn test_parse_check_output() {
+        let output = r#"{"reason":"compiler-message","message":{"message":"unused variable: `x`","level":"warning","spans":[{"file_name":"src/lib.rs","line_start":2,"line_end":2,"column_start":9,"column_end":10,"is_primary":true}],"rendered":"warning: unused variable"}}
+{"reason":"compiler-message","message":{"message":"mismatched types","level":"error","spans":[{"file_name":"src/lib.rs","line_start":3,"line_end":3,"column_start":5,"column_end":6,"is_primary":true}]}}
+{"reason":"compiler-message","message":{"message":"aborting due to 1 previous error; 1 warning emitted","level":"error","spans":[]}}
+{"reason":"build-finished","success":false}"#;
+
+        let result = parse_check_output(output, 800);
+        assert!(!result.success);
+        assert_eq!(result.diagnostics.len(), 2);
+        assert_eq!(result.diagnostics[0].level, "warning");
+        assert_eq!(result.diagnostics[0].rendered.as_deref(), Some("warning: unused variable"));
+        assert_eq!(result.diagnostics[1].primary_span().map(|s| s.line_start), Some(3));
+
+        let clean = parse_check_output(r#"{"reason":"build-finished","success":true}"#, 500);
+        assert!(clean.success);
+        assert!(clean.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_diagnostic_spans_and_suggestions() {
+        let output = r#"{"reason":"compiler-message","message":{"message":"mismatched types","code":{"code":"E0308","explanation":"..."},"level":"error","spans":[{"file_name":"src/lib.rs","byte_start":40,"byte_end":47,"line_start":2,"line_end":2,"column_start":18,"column_end":25,"is_primary":true,"text":[],"label":"expected `i32`, found `&str`","suggested_replacement":null,"suggestion_applicability":null,"expansion":null},{"file_name":"src/lib.rs","byte_start":30,"byte_end":33,"line_start":2,"line_end":2,"column_start":8,"column_end":11,"is_primary":false,"text":[],"label":"expected due to this","suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[{"message":"try using a conversion method","code":null,"level":"help","spans":[{"file_name":"src/lib.rs","byte_start":40,"byte_end":47,"line_start":2,"line_end":2,"column_start":18,"column_end":25,"is_primary":true,"text":[],"label":null,"suggested_replacement":"\"5\".parse().unwrap()","suggestion_applicability":"MaybeIncorrect","expansion":null}],"children":[],"rendered":null}],"rendered":"error[E0308]: mismatched types"}}
+{"reason":"compiler-message","message":{"message":"unused import: `std::fmt`","code":{"code":"unused_imports","explanation":null},"level":"warning","spans":[{"file_name":"src/lib.rs","line_start":1,"line_end":1,"column_start":5,"column_end":13,"is_primary":true,"label":null}],"children":[],"rendered":"warning: unused import"}}
+{"reason":"build-finished","success":false}"#;
+
+        let result = parse_cargo_output(output, "", 0);
+        assert!(result.compile_error.is_some());
+        assert_eq!(result.diagnostics.len(), 2);
+
+        let error = &result.diagnostics[0];
+        assert_eq!(error.code.as_deref(), Some("E0308"));
+        assert_eq!(error.spans.len(), 2);
+        let primary = error.primary_span().unwrap();
+        assert_eq!((primary.line_start, primary.column_start, primary.column_end), (2, 18, 25));
+        assert_eq!(primary.label.as_deref(), Some("expected `i32`, found `&str`"));
+        assert_eq!(error.children[0].level, "help");
+
+        let suggestions: Vec<_> = error.suggestions().collect();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].0, "try using a conversion method");
+        assert_eq!(suggestions[0].1.suggested_replacement.as_deref(), Some("\"5\".parse().unwrap()"));
+        assert_eq!(suggestions[0].1.suggestion_applicability.as_deref(), Some("MaybeIncorrect"));
+
+        assert_eq!(result.diagnostics[1].level, "warning");
+        assert_eq!(result.diagnostics[1].code.as_deref(), Some("unused_imports"));
     }
 
     #[test]
@@ -0,0 +1,155 @@
+//! Dependency policy for challenges whose `Cargo.toml` learners may edit
+//!
+//! A challenge can list crates learners are allowed to add on top of its
+//! template's. The learner's manifest is only read for its dependencies:
+//! each one must be on the list, or already in the template, and come from
+//! crates.io, since the sandbox can't reach anywhere else. Anything else is
+//! reported as a `PolicyViolation` in place of a test run. The dependencies
+//! that pass are merged into the template's manifest, so the rest of the
+//! learner's file is never used.
+
+use serde::{Deserialize, Serialize};
+use toml::{Table, Value};
+
+use crate::error::RunnerError;
+
+/// Manifest sections dependencies can be added to
+pub const DEPENDENCY_SECTIONS: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Dependency keys pointing somewhere other than crates.io
+const SOURCE_KEYS: &[&str] = &["path", "git", "branch", "tag", "rev", "registry", "registry-index"];
+
+/// Why a submission's dependencies weren't accepted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct PolicyViolation {
+    /// The crates at fault
+    pub crates: Vec<String>,
+    pub message: String,
+}
+
+fn normalize(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+/// Merge the dependencies a learner added in `student_toml` into the
+/// template manifest `base_toml`, if `allowlist` allows them. Returns the
+/// merged manifest, or why the learner's dependencies can't be used.
+pub fn apply_dependency_policy(
+    base_toml: &str,
+    student_toml: &str,
+    allowlist: &[String],
+) -> Result<Result<String, PolicyViolation>, RunnerError> {
+    let mut base: Table = base_toml
+        .parse()
+        .map_err(|e| RunnerError::ParseError(format!("template Cargo.toml: {}", e)))?;
+    let student: Table = match student_toml.parse() {
+        Ok(student) => student,
+        Err(e) => {
+            return Ok(Err(PolicyViolation {
+                crates: Vec::new(),
+                message: format!("Cargo.toml isn't valid TOML: {}", e),
+            }))
+        }
+    };
+    if student.get("target").and_then(Value::as_table).is_some_and(|targets| {
+        targets
+            .values()
+            .filter_map(Value::as_table)
+            .any(|target| DEPENDENCY_SECTIONS.iter().any(|section| target.contains_key(*section)))
+    }) {
+        return Ok(Err(PolicyViolation {
+            crates: Vec::new(),
+            message: "Target-specific dependencies aren't allowed in challenges".to_string(),
+        }));
+    }
+
+    let allowed: Vec<String> = allowlist.iter().map(|name| normalize(name)).collect();
+    let mut disallowed = Vec::new();
+    let mut wrong_source = Vec::new();
+    for section in DEPENDENCY_SECTIONS {
+        let Some(dependencies) = student.get(*section).and_then(Value::as_table) else {
+            continue;
+        };
+        let template_section = base.get(*section).and_then(Value::as_table).cloned().unwrap_or_default();
+        let mut merged = template_section.clone();
+        for (name, spec) in dependencies {
+            // The template's own dependencies stay as the template has them
+            if template_section.contains_key(name) {
+                continue;
+            }
+            // A renamed dependency is allowed by the crate it really is
+            let package = spec.get("package").and_then(Value::as_str).unwrap_or(name);
+            if !allowed.contains(&normalize(package)) {
+                disallowed.push(package.to_string());
+                continue;
+            }
+            if spec.as_table().is_some_and(|spec| SOURCE_KEYS.iter().any(|key| spec.contains_key(*key))) {
+                wrong_source.push(package.to_string());
+                continue;
+            }
+            merged.insert(name.clone(), spec.clone());
+        }
+        if !merged.is_empty() {
+            base.insert(section.to_string(), Value::Table(merged));
+        }
+    }
+
+    disallowed.sort();
+    disallowed.dedup();
+    wrong_source.sort();
+    wrong_source.dedup();
+    if !disallowed.is_empty() {
+        let allowed = if allowlist.is_empty() { "none".to_string() } else { allowlist.join(", ") };
+        return Ok(Err(PolicyViolation {
+            message: format!(
+                "{} not allowed in this challenge (allowed: {})",
+                disallowed.join(", "),
+                allowed
+            ),
+            crates: disallowed,
+        }));
+    }
+    if !wrong_source.is_empty() {
+        return Ok(Err(PolicyViolation {
+            message: format!("{} must come from crates.io, not a path, git or other registry", wrong_source.join(", ")),
+            crates: wrong_source,
+        }));
+    }
+
+    toml::to_string(&base)
+        .map(Ok)
+        .map_err(|e| RunnerError::ParseError(format!("merged Cargo.toml: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "[package]\nname = \"challenge_demo\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\nserde = \"1\"\n";
+
+    #[test]
+    fn test_allowed_dependencies_are_merged() {
+        let student = "[package]\nname = \"renamed\"\nbuild = \"evil.rs\"\n\n[dependencies]\nserde = \"0.1\"\nitertools = \"0.12\"\n";
+        let merged = apply_dependency_policy(BASE, student, &["itertools".to_string()]).unwrap().unwrap();
+        let merged: Table = merged.parse().unwrap();
+
+        assert_eq!(merged["package"]["name"].as_str(), Some("challenge_demo"));
+        assert!(merged["package"].get("build").is_none());
+        assert_eq!(merged["dependencies"]["serde"].as_str(), Some("1"));
+        assert_eq!(merged["dependencies"]["itertools"].as_str(), Some("0.12"));
+    }
+
+    #[test]
+    fn test_disallowed_dependencies_are_violations() {
+        let allowlist = vec!["itertools".to_string()];
+
+        let student = "[dependencies]\nrand = \"0.8\"\nits = { package = \"regex\", version = \"1\" }\n";
+        let violation = apply_dependency_policy(BASE, student, &allowlist).unwrap().unwrap_err();
+        assert_eq!(violation.crates, vec!["rand", "regex"]);
+
+        let student = "[dev-dependencies]\nitertools = { path = \"/etc\" }\n";
+        let violation = apply_dependency_policy(BASE, student, &allowlist).unwrap().unwrap_err();
+        assert_eq!(violation.crates, vec!["itertools"]);
+    }
+}
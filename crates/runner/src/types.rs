@@ -3,6 +3,8 @@
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
+use crate::policy::PolicyViolation;
+
 /// Configuration for the Docker runner
 #[derive(Debug, Clone)]
 pub struct DockerConfig {
@@ -18,6 +20,13 @@ pub struct DockerConfig {
     pub network_mode: NetworkMode,
     /// Number of pre-warmed containers to keep in pool
     pub pre_warm_pool_size: usize,
+    /// Memory limit in bytes for check-only runs, which don't run tests
+    pub check_memory_limit: u64,
+    /// Maximum time for check-only runs
+    pub check_timeout: Duration,
+    /// Times failed tests are run again on their own before they count as
+    /// failed, since timing-sensitive tests can fail in a busy container
+    pub max_reruns: u32,
 }
 
 impl Default for DockerConfig {
@@ -29,6 +38,9 @@ impl Default for DockerConfig {
             timeout: Duration::from_secs(30),
             network_mode: NetworkMode::None,
             pre_warm_pool_size: 2,
+            check_memory_limit: 192 * 1024 * 1024, // 192MB
+            check_timeout: Duration::from_secs(15),
+            max_reruns: 2,
         }
     }
 }
@@ -53,6 +65,7 @@ impl NetworkMode {
 
 /// Result of running a challenge verification
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct VerificationResult {
     /// Whether all tests passed
     pub success: bool,
@@ -74,6 +87,24 @@ pub struct VerificationResult {
     pub runtime_error: Option<RuntimeError>,
     /// Resource limit that was hit, if any
     pub resource_limit_hit: Option<ResourceLimit>,
+    /// Dependencies the challenge doesn't allow, in which case nothing ran
+    #[serde(default)]
+    pub policy_violation: Option<PolicyViolation>,
+    /// What the container used, if Docker reported its stats
+    #[serde(default)]
+    pub resource_usage: Option<ResourceUsage>,
+    /// Every compiler error and warning, with its spans
+    #[serde(default)]
+    pub diagnostics: Vec<Diagnostic>,
+    /// Names of the tests that failed
+    #[serde(default)]
+    pub failed_tests: Vec<String>,
+    /// Tests that failed, then passed when run again
+    #[serde(default)]
+    pub flaky_tests: Vec<String>,
+    /// Times the failed tests were run again
+    #[serde(default)]
+    pub reruns: u32,
 }
 
 impl VerificationResult {
@@ -90,6 +121,12 @@ impl VerificationResult {
             compile_error: None,
             runtime_error: None,
             resource_limit_hit: None,
+            policy_violation: None,
+            resource_usage: None,
+            diagnostics: Vec::new(),
+            failed_tests: Vec::new(),
+            flaky_tests: Vec::new(),
+            reruns: 0,
         }
     }
 
@@ -106,6 +143,12 @@ impl VerificationResult {
             compile_error: None,
             runtime_error: None,
             resource_limit_hit: None,
+            policy_violation: None,
+            resource_usage: None,
+            diagnostics: Vec::new(),
+            failed_tests: Vec::new(),
+            flaky_tests: Vec::new(),
+            reruns: 0,
         }
     }
 
@@ -122,6 +165,12 @@ impl VerificationResult {
             compile_error: Some(error),
             runtime_error: None,
             resource_limit_hit: None,
+            policy_violation: None,
+            resource_usage: None,
+            diagnostics: Vec::new(),
+            failed_tests: Vec::new(),
+            flaky_tests: Vec::new(),
+            reruns: 0,
         }
     }
 
@@ -138,6 +187,35 @@ impl VerificationResult {
             compile_error: None,
             runtime_error: Some(error),
             resource_limit_hit: None,
+            policy_violation: None,
+            resource_usage: None,
+            diagnostics: Vec::new(),
+            failed_tests: Vec::new(),
+            flaky_tests: Vec::new(),
+            reruns: 0,
+        }
+    }
+
+    /// Create a result for a submission whose dependencies break the
+    /// challenge's policy
+    pub fn policy_violation(violation: PolicyViolation) -> Self {
+        Self {
+            success: false,
+            stdout: String::new(),
+            stderr: String::new(),
+            duration_ms: 0,
+            tests_passed: 0,
+            tests_failed: 0,
+            tests_total: 0,
+            compile_error: None,
+            runtime_error: None,
+            resource_limit_hit: None,
+            policy_violation: Some(violation),
+            resource_usage: None,
+            diagnostics: Vec::new(),
+            failed_tests: Vec::new(),
+            flaky_tests: Vec::new(),
+            reruns: 0,
         }
     }
 
@@ -147,10 +225,121 @@ impl VerificationResult {
         self.stderr = stderr;
         self
     }
+
+    /// Whether some tests failed in a run that otherwise went fine, so
+    /// running them again could pass
+    pub fn can_rerun(&self) -> bool {
+        !self.failed_tests.is_empty() && self.compile_error.is_none() && self.runtime_error.is_none()
+    }
+
+    /// Fold in a run of just the failed tests. Those that passed this time
+    /// count as passed and are marked flaky. A rerun that didn't run every
+    /// failed test, or broke some other way, is only counted.
+    pub fn merge_rerun(&mut self, rerun: &VerificationResult) {
+        self.reruns += 1;
+        let broke = rerun.compile_error.is_some() || rerun.runtime_error.is_some();
+        if broke || rerun.tests_total as usize != self.failed_tests.len() {
+            return;
+        }
+        let (still_failing, passed): (Vec<_>, Vec<_>) = std::mem::take(&mut self.failed_tests)
+            .into_iter()
+            .partition(|name| rerun.failed_tests.contains(name));
+        self.tests_passed += passed.len() as u32;
+        self.tests_failed = still_failing.len() as u32;
+        self.failed_tests = still_failing;
+        self.flaky_tests.extend(passed);
+        self.success = self.tests_failed == 0 && self.tests_passed > 0;
+    }
+}
+
+/// Resources a verification container used, sampled from Docker's stats
+/// while it ran. The figures are for the whole run, building the crate
+/// included, not just the tests.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ResourceUsage {
+    /// Highest memory use seen, in bytes
+    pub peak_memory_bytes: u64,
+    /// CPU time across all cores, in milliseconds
+    pub cpu_time_ms: u64,
+    /// Time the container ran, in milliseconds
+    pub wall_time_ms: u64,
+    /// Bytes written to disk
+    pub bytes_written: u64,
+}
+
+/// Result of a check-only run: the code is compiled with `cargo check`,
+/// without running any tests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct CheckResult {
+    /// Whether the code compiled without errors
+    pub success: bool,
+    /// Errors and warnings, in the order the compiler reported them
+    pub diagnostics: Vec<Diagnostic>,
+    /// Duration of the run in milliseconds
+    pub duration_ms: u64,
+    /// Whether the check was stopped at its time limit
+    pub timed_out: bool,
+}
+
+/// A compiler diagnostic, as structured as rustc reports it, so an editor
+/// can underline exactly the code it's about
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct Diagnostic {
+    /// `error` or `warning` at the top level; `note` or `help` for children
+    pub level: String,
+    pub message: String,
+    /// Error code, e.g. `E0308`
+    pub code: Option<String>,
+    /// Code the diagnostic points at; the primary spans are where it is
+    pub spans: Vec<DiagnosticSpan>,
+    /// Notes and help attached to the diagnostic
+    pub children: Vec<Diagnostic>,
+    /// The diagnostic as rustc prints it, with the code it points at
+    pub rendered: Option<String>,
+}
+
+impl Diagnostic {
+    /// The first primary span, where the diagnostic is reported
+    pub fn primary_span(&self) -> Option<&DiagnosticSpan> {
+        self.spans.iter().find(|span| span.is_primary)
+    }
+
+    /// Spans rustc suggests replacing, here and in the children, with
+    /// the suggestion's message
+    pub fn suggestions(&self) -> impl Iterator<Item = (&str, &DiagnosticSpan)> {
+        std::iter::once(self)
+            .chain(&self.children)
+            .flat_map(|d| d.spans.iter().map(move |span| (d.message.as_str(), span)))
+            .filter(|(_, span)| span.suggested_replacement.is_some())
+    }
+}
+
+/// A range of code a diagnostic points at. Lines and columns count from 1
+/// and columns are in characters; the end column is exclusive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct DiagnosticSpan {
+    pub file: String,
+    pub line_start: u32,
+    pub line_end: u32,
+    pub column_start: u32,
+    pub column_end: u32,
+    pub is_primary: bool,
+    /// What rustc says about this span, e.g. "expected `i32`"
+    pub label: Option<String>,
+    /// Code to replace the span with to apply a suggested fix
+    pub suggested_replacement: Option<String>,
+    /// How safe the replacement is: `MachineApplicable`, `MaybeIncorrect`,
+    /// `HasPlaceholders` or `Unspecified`
+    pub suggestion_applicability: Option<String>,
 }
 
 /// Compile error information
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct CompileError {
     /// Error message
     pub message: String,
@@ -182,6 +371,7 @@ impl CompileError {
 
 /// Runtime error types
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub enum RuntimeError {
     /// Code timed out
     Timeout,
@@ -195,6 +385,7 @@ pub enum RuntimeError {
 
 /// Resource limits that can be hit
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub enum ResourceLimit {
     /// Memory limit exceeded
     Memory,
@@ -236,6 +427,30 @@ mod tests {
         assert_eq!(result.tests_failed, 2);
     }
 
+    #[test]
+    fn test_merge_rerun() {
+        let mut result = VerificationResult::failure(1, 2, 3, 1000);
+        result.failed_tests = vec!["tests::slow".to_string(), "tests::wrong".to_string()];
+        assert!(result.can_rerun());
+
+        let mut rerun = VerificationResult::failure(1, 1, 2, 500);
+        rerun.failed_tests = vec!["tests::wrong".to_string()];
+        result.merge_rerun(&rerun);
+        assert!(!result.success);
+        assert_eq!((result.tests_passed, result.tests_failed, result.reruns), (2, 1, 1));
+        assert_eq!(result.flaky_tests, vec!["tests::slow"]);
+        assert_eq!(result.failed_tests, vec!["tests::wrong"]);
+
+        // A rerun that broke doesn't change the outcome
+        result.merge_rerun(&VerificationResult::runtime_error(RuntimeError::Timeout, 30000));
+        assert_eq!((result.tests_failed, result.reruns), (1, 2));
+
+        result.merge_rerun(&VerificationResult::success(1, 1, 500));
+        assert!(result.success);
+        assert_eq!((result.tests_passed, result.tests_failed), (3, 0));
+        assert_eq!(result.flaky_tests, vec!["tests::slow", "tests::wrong"]);
+    }
+
     #[test]
     fn test_compile_error_with_location() {
         let error = CompileError::new("expected `;`".to_string())
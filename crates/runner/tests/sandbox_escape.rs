@@ -0,0 +1,110 @@
+//! Sandbox escape regression tests
+//!
+//! Runs adversarial submissions from `tests/sandbox_escape/` through the
+//! Docker runner with its default configuration. Each one has a single test
+//! that only passes if its attack worked, so every attack must come back
+//! without a passing test, and without a compile error, which would mean
+//! the attack was never tried. A harmless control submission must pass, to
+//! show passing tests are recognised at all.
+//!
+//! These need Docker and the sandbox image, so they only build with the
+//! `docker-integration` feature:
+//!
+//! ```sh
+//! docker build -t gamified-rust-sandbox:latest prototypes/docker-runner
+//! cargo test -p glp_runner --features docker-integration --test sandbox_escape
+//! ```
+
+#![cfg(feature = "docker-integration")]
+
+use glp_runner::{DockerConfig, DockerRunner, RuntimeError, VerificationResult};
+use std::path::Path;
+use std::time::Duration;
+
+const CARGO_TOML: &str = "[package]\nname = \"sandbox_escape\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n";
+
+/// Run `source` as a submission, failing the test if Docker or the sandbox
+/// image isn't there rather than skipping it
+async fn run(source: &str) -> VerificationResult {
+    let runner = DockerRunner::new()
+        .await
+        .expect("docker-integration tests need a running Docker daemon");
+    assert!(
+        runner.check_image_exists().await,
+        "sandbox image {} is missing; see the module docs",
+        DockerConfig::default().image_name
+    );
+
+    let challenge_dir = tempfile::tempdir().unwrap();
+    std::fs::write(challenge_dir.path().join("Cargo.toml"), CARGO_TOML).unwrap();
+    runner.run_verification(challenge_dir.path(), source).await.unwrap()
+}
+
+/// Whether the submission's test passed. libtest's summary isn't part of
+/// cargo's JSON messages, so it's also looked for in the plain output.
+fn passed(result: &VerificationResult) -> bool {
+    result.success || result.stdout.contains("test result: ok")
+}
+
+fn assert_contained(name: &str, result: &VerificationResult) {
+    assert!(
+        result.compile_error.is_none(),
+        "{} didn't compile, so it proves nothing: {:?}",
+        name,
+        result.compile_error
+    );
+    assert!(
+        !passed(result),
+        "{} escaped the sandbox\nstdout:\n{}\nstderr:\n{}",
+        name,
+        result.stdout,
+        result.stderr
+    );
+}
+
+#[tokio::test]
+async fn test_control_passes() {
+    let result = run(include_str!("sandbox_escape/control.rs")).await;
+    assert!(passed(&result), "control submission failed\nstdout:\n{}\nstderr:\n{}", result.stdout, result.stderr);
+}
+
+#[tokio::test]
+async fn test_fork_bomb_is_contained() {
+    let result = run(include_str!("sandbox_escape/fork_bomb.rs")).await;
+    assert_contained("fork bomb", &result);
+}
+
+#[tokio::test]
+async fn test_network_is_unreachable() {
+    let result = run(include_str!("sandbox_escape/network.rs")).await;
+    assert_contained("network call", &result);
+}
+
+#[tokio::test]
+async fn test_huge_allocation_is_contained() {
+    let result = run(include_str!("sandbox_escape/huge_allocation.rs")).await;
+    assert_contained("huge allocation", &result);
+}
+
+#[tokio::test]
+async fn test_host_files_are_out_of_reach() {
+    // A file on the host, outside the directory mounted into the container
+    let host_dir = tempfile::tempdir().unwrap();
+    let canary = host_dir.path().join("canary");
+    std::fs::write(&canary, "host").unwrap();
+
+    let source = include_str!("sandbox_escape/etc_read.rs").replace("{{CANARY}}", &canary.to_string_lossy());
+    let result = run(&source).await;
+    assert_contained("/etc read", &result);
+    assert!(!Path::new("/etc/glp-escape").exists());
+}
+
+#[tokio::test]
+async fn test_infinite_loop_times_out() {
+    let result = run(include_str!("sandbox_escape/infinite_loop.rs")).await;
+    assert_contained("infinite loop", &result);
+
+    let timeout = DockerConfig::default().timeout;
+    assert!(matches!(result.runtime_error, Some(RuntimeError::Timeout)), "{:?}", result.runtime_error);
+    assert!(Duration::from_millis(result.duration_ms) < timeout + Duration::from_secs(10));
+}
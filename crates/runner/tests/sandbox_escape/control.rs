@@ -0,0 +1,16 @@
+//! A harmless submission whose test passes, so the harness knows a passing
+//! test is recognised and the sandbox image works at all
+
+pub fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        assert_eq!(add(2, 2), 4);
+    }
+}
@@ -0,0 +1,23 @@
+//! Looks for a file the harness left on the host, outside the challenge
+//! directory, and tries to write to `/etc`; the container only sees its own
+//! read-only filesystem and the challenge mount
+
+use std::fs;
+
+pub const CANARY: &str = "{{CANARY}}";
+
+pub fn escaped() -> bool {
+    let read_host = fs::read_to_string(CANARY).is_ok();
+    let wrote_etc = fs::write("/etc/glp-escape", "escaped").is_ok();
+    read_host || wrote_etc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert!(escaped());
+    }
+}
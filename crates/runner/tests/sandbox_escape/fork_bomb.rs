@@ -0,0 +1,32 @@
+//! Spawns processes until it can't; the container's pids limit should stop
+//! it well short of the target
+
+use std::process::{Child, Command};
+
+pub const TARGET: usize = 1000;
+
+pub fn spawn_all() -> usize {
+    let mut children: Vec<Child> = Vec::new();
+    while children.len() < TARGET {
+        match Command::new("sleep").arg("60").spawn() {
+            Ok(child) => children.push(child),
+            Err(_) => break,
+        }
+    }
+    let spawned = children.len();
+    for mut child in children {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+    spawned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(spawn_all(), TARGET);
+    }
+}
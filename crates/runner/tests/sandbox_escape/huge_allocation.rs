@@ -0,0 +1,25 @@
+//! Allocates and touches far more memory than the container is given, so
+//! the kernel can't just hand out pages it never backs
+
+pub const BYTES: usize = 2 * 1024 * 1024 * 1024;
+
+const BLOCK: usize = 16 * 1024 * 1024;
+
+pub fn allocate() -> usize {
+    // Filled with non-zero bytes, so every page is written
+    let mut blocks: Vec<Vec<u8>> = Vec::new();
+    while blocks.len() * BLOCK < BYTES {
+        blocks.push(vec![1; BLOCK]);
+    }
+    std::hint::black_box(&blocks).iter().map(Vec::len).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(allocate(), BYTES);
+    }
+}
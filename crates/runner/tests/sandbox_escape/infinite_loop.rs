@@ -0,0 +1,18 @@
+//! Never finishes; the runner should kill it at its timeout
+
+pub fn spin() -> u64 {
+    let mut n: u64 = 0;
+    loop {
+        n = std::hint::black_box(n.wrapping_add(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(spin(), 0);
+    }
+}
@@ -0,0 +1,25 @@
+//! Tries to reach the outside world, by IP and by name; the container has
+//! no network
+
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+pub fn reached_outside() -> bool {
+    let addr: SocketAddr = "1.1.1.1:443".parse().unwrap();
+    let by_ip = TcpStream::connect_timeout(&addr, Duration::from_secs(5)).is_ok();
+    let by_name = ("crates.io", 443)
+        .to_socket_addrs()
+        .map(|mut addrs| addrs.next().is_some())
+        .unwrap_or(false);
+    by_ip || by_name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert!(reached_outside());
+    }
+}
@@ -0,0 +1,72 @@
+//! Replay and cancel throughput of the order book
+//!
+//! `replay_1m` replays a logged run of a million events; `deep_cancels`
+//! fills one price level with resting orders and cancels them oldest-last,
+//! the case a linear search of the level makes quadratic.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use glp_simulator::{replay, AgentSpec, Order, OrderBook, RegimeSchedule, ReplayLog, Side, SimConfig};
+
+const REPLAY_EVENTS: usize = 1_000_000;
+const DEEP_ORDERS: u64 = 20_000;
+
+/// A log of at least `REPLAY_EVENTS` events from a busy run
+fn million_event_log() -> ReplayLog {
+    let mut agents = Vec::new();
+    for id in 1..=12 {
+        agents.push(AgentSpec::NoiseTrader { id, reference: 100, spread: 4 });
+    }
+    for id in 13..=16 {
+        agents.push(AgentSpec::CancelBot { id, reference: 100 });
+    }
+    let mut config = SimConfig {
+        seed: 7,
+        ticks: 10_000,
+        schedule: RegimeSchedule::cycle(50),
+        regimes: None,
+        agents,
+    };
+
+    loop {
+        let log = ReplayLog::record(&config).expect("stock agents build");
+        let events: usize = log.ticks.iter().map(|record| record.events.len()).sum();
+        if events >= REPLAY_EVENTS {
+            return log;
+        }
+        config.ticks = config.ticks * REPLAY_EVENTS as u64 / events.max(1) as u64 + 1;
+    }
+}
+
+fn bench_replay(c: &mut Criterion) {
+    let log = million_event_log();
+    let mut group = c.benchmark_group("replay_1m");
+    group.sample_size(10);
+    group.bench_function("replay", |b| b.iter(|| replay(&log).expect("log replays")));
+    group.finish();
+}
+
+fn bench_deep_cancels(c: &mut Criterion) {
+    let mut book = OrderBook::new();
+    for id in 1..=DEEP_ORDERS {
+        book.submit(Order::new(id, 1, Side::Buy, 100, 1)).expect("orders rest");
+    }
+
+    let mut group = c.benchmark_group("deep_cancels");
+    group.sample_size(10);
+    group.bench_function("newest_first", |b| {
+        b.iter_batched(
+            || book.clone(),
+            |mut book| {
+                for id in (1..=DEEP_ORDERS).rev() {
+                    book.cancel(id).expect("order rests");
+                }
+                book
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_replay, bench_deep_cancels);
+criterion_main!(benches);
@@ -0,0 +1,46 @@
+//! The agent abstraction
+//!
+//! Agents only propose actions; the engine assigns order ids, applies the
+//! actions to the book and records what happened. An agent's randomness
+//! comes solely from the stream it is handed, never from global state.
+
+use serde::{Deserialize, Serialize};
+
+use crate::book::OrderBook;
+use crate::regime::Regime;
+use crate::rng::Rng;
+use crate::types::{AgentId, Order, OrderId, Price, Qty, Side, Tick};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    Place { side: Side, price: Price, qty: Qty },
+    Cancel(OrderId),
+}
+
+/// What an agent sees when it acts
+pub struct Ctx<'a> {
+    pub tick: Tick,
+    pub regime: Regime,
+    /// The agent being stepped
+    pub agent: AgentId,
+    /// The book as left by the agents stepped before this one
+    pub book: &'a OrderBook,
+}
+
+impl Ctx<'_> {
+    /// The agent's resting orders, oldest first
+    pub fn open_orders(&self) -> Vec<&Order> {
+        self.book.open_orders(self.agent)
+    }
+}
+
+pub trait Agent {
+    fn id(&self) -> AgentId;
+
+    /// Act every `interval` ticks, starting at tick 0
+    fn interval(&self) -> Tick {
+        1
+    }
+
+    fn step(&mut self, ctx: &Ctx, rng: &mut Rng) -> Vec<Action>;
+}
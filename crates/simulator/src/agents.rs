@@ -0,0 +1,156 @@
+//! Stock agents
+//!
+//! The `NoiseTrader` and `CancelBot` from the day 3 challenges, moved onto
+//! the real order book.
+
+use crate::agent::{Action, Agent, Ctx};
+use crate::regime::Regime;
+use crate::rng::Rng;
+use crate::types::{AgentId, Price, Side};
+
+/// Places small orders on a random side around a reference price, often
+/// crossing the spread. Places more during a burst and less in a cancel
+/// storm.
+pub struct NoiseTrader {
+    id: AgentId,
+    reference: Price,
+    /// Widest distance from `reference`, in ticks
+    spread: u32,
+}
+
+impl NoiseTrader {
+    pub fn new(id: AgentId, reference: Price, spread: u32) -> Self {
+        Self { id, reference, spread }
+    }
+
+    fn random_order(&self, rng: &mut Rng) -> Action {
+        let side = if rng.next_bool() { Side::Buy } else { Side::Sell };
+        let offset = Price::from(rng.next_below(self.spread + 1));
+        let price = if rng.next_bool() {
+            self.reference + offset
+        } else {
+            self.reference.saturating_sub(offset).max(1)
+        };
+        let qty = u64::from(rng.next_below(5)) + 1;
+        Action::Place { side, price, qty }
+    }
+}
+
+impl Agent for NoiseTrader {
+    fn id(&self) -> AgentId {
+        self.id
+    }
+
+    fn step(&mut self, ctx: &Ctx, rng: &mut Rng) -> Vec<Action> {
+        let count = match ctx.regime {
+            Regime::Calm => usize::from(rng.next_bool()),
+            Regime::Burst => 3,
+            Regime::CancelStorm => usize::from(rng.next_below(4) == 0),
+        };
+        (0..count).map(|_| self.random_order(rng)).collect()
+    }
+}
+
+/// Quotes one passive order a tick just outside the spread and pulls its
+/// own resting orders: one at a time normally, up to three at once in a
+/// cancel storm
+pub struct CancelBot {
+    id: AgentId,
+    reference: Price,
+}
+
+impl CancelBot {
+    pub fn new(id: AgentId, reference: Price) -> Self {
+        Self { id, reference }
+    }
+}
+
+impl Agent for CancelBot {
+    fn id(&self) -> AgentId {
+        self.id
+    }
+
+    fn step(&mut self, ctx: &Ctx, rng: &mut Rng) -> Vec<Action> {
+        let mut actions = Vec::new();
+        let open: Vec<_> = ctx.open_orders().iter().map(|order| order.id).collect();
+
+        let cancels = match ctx.regime {
+            Regime::CancelStorm => rng.next_below(4) as usize,
+            _ => usize::from(rng.next_bool()),
+        };
+        let mut candidates = open;
+        for _ in 0..cancels {
+            let Some(&id) = rng.pick(&candidates) else {
+                break;
+            };
+            candidates.retain(|candidate| *candidate != id);
+            actions.push(Action::Cancel(id));
+        }
+
+        let side = if rng.next_bool() { Side::Buy } else { Side::Sell };
+        let price = match side {
+            Side::Buy => ctx.book.best_bid().unwrap_or(self.reference).min(self.reference).saturating_sub(1).max(1),
+            Side::Sell => ctx.book.best_ask().unwrap_or(self.reference).max(self.reference) + 1,
+        };
+        actions.push(Action::Place { side, price, qty: 1 });
+        actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::book::OrderBook;
+    use crate::types::Order;
+
+    fn count_over_window(agent: &mut dyn Agent, regime: Regime, book: &OrderBook, cancels: bool) -> usize {
+        let mut rng = Rng::new(7);
+        (0..50)
+            .map(|tick| {
+                let ctx = Ctx { tick, regime, agent: agent.id(), book };
+                agent
+                    .step(&ctx, &mut rng)
+                    .iter()
+                    .filter(|action| matches!(action, Action::Cancel(_)) == cancels)
+                    .count()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_noise_trader_places_more_in_a_burst() {
+        let book = OrderBook::new();
+        let mut trader = NoiseTrader::new(1, 100, 3);
+        let calm = count_over_window(&mut trader, Regime::Calm, &book, false);
+        let burst = count_over_window(&mut trader, Regime::Burst, &book, false);
+        assert!(burst > calm, "burst={burst}, calm={calm}");
+    }
+
+    #[test]
+    fn test_cancel_bot_cancels_more_in_a_storm() {
+        let mut book = OrderBook::new();
+        for id in 1..=5 {
+            book.submit(Order::new(id, 2, Side::Buy, 90 + id, 1)).unwrap();
+        }
+        let mut bot = CancelBot::new(2, 100);
+        let calm = count_over_window(&mut bot, Regime::Calm, &book, true);
+        let storm = count_over_window(&mut bot, Regime::CancelStorm, &book, true);
+        assert!(storm > calm, "storm={storm}, calm={calm}");
+    }
+
+    #[test]
+    fn test_cancel_bot_only_cancels_its_own_orders() {
+        let mut book = OrderBook::new();
+        book.submit(Order::new(1, 9, Side::Buy, 95, 1)).unwrap();
+        book.submit(Order::new(2, 2, Side::Buy, 96, 1)).unwrap();
+        let mut bot = CancelBot::new(2, 100);
+        let mut rng = Rng::new(1);
+
+        for tick in 0..20 {
+            let ctx = Ctx { tick, regime: Regime::CancelStorm, agent: 2, book: &book };
+            for action in bot.step(&ctx, &mut rng) {
+                assert_ne!(action, Action::Cancel(1));
+            }
+        }
+    }
+}
@@ -0,0 +1,424 @@
+//! Limit order book
+//!
+//! Orders match by price-time priority: an incoming order trades against
+//! the best opposite price first and, within a price, the oldest order
+//! first. Whatever is left rests in the book. Levels are kept in `BTreeMap`s
+//! and each level queues its orders by arrival, so every walk over the book
+//! happens in the same order on every run.
+//!
+//! Resting orders are looked up by id in a hash map, which is never walked.
+//! Cancelling leaves the order's queue entry behind to be skipped when it
+//! reaches the front, so neither lookups nor cancels search a level.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+use crate::error::SimError;
+use crate::types::{AgentId, Order, OrderId, Price, Qty, Side};
+
+/// A trade between a resting (maker) order and an incoming (taker) order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fill {
+    pub maker_order: OrderId,
+    pub maker_agent: AgentId,
+    pub taker_order: OrderId,
+    pub taker_agent: AgentId,
+    /// Trades happen at the resting order's price
+    pub price: Price,
+    pub qty: Qty,
+}
+
+/// The resting orders of a book in priority order: best price first, then
+/// oldest first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookSnapshot {
+    pub bids: Vec<Order>,
+    pub asks: Vec<Order>,
+}
+
+/// A resting order and when it arrived
+#[derive(Debug, Clone, Copy)]
+struct Resting {
+    order: Order,
+    arrival: u64,
+}
+
+/// The orders at one price as `(arrival, id)`, oldest first. Entries of
+/// cancelled orders stay until they reach the front or the level is
+/// compacted; the arrival tells them apart from a later order that reuses
+/// the id.
+#[derive(Debug, Clone, Default)]
+struct Level {
+    queue: VecDeque<(u64, OrderId)>,
+    /// Entries in `queue` still resting
+    live: usize,
+    /// Open quantity of those entries
+    qty: Qty,
+}
+
+fn is_live(orders: &HashMap<OrderId, Resting>, (arrival, id): (u64, OrderId)) -> bool {
+    orders.get(&id).is_some_and(|resting| resting.arrival == arrival)
+}
+
+impl Level {
+    /// Drop entries of cancelled orders once they outnumber live ones, so
+    /// a level's queue stays within twice its size
+    fn compact(&mut self, orders: &HashMap<OrderId, Resting>) {
+        if self.queue.len() > 2 * self.live {
+            self.queue.retain(|entry| is_live(orders, *entry));
+        }
+    }
+
+    /// Live orders, oldest first
+    fn orders<'a>(&'a self, orders: &'a HashMap<OrderId, Resting>) -> impl Iterator<Item = &'a Order> + 'a {
+        self.queue.iter().filter_map(|(arrival, id)| {
+            orders.get(id).filter(|resting| resting.arrival == *arrival).map(|resting| &resting.order)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OrderBook {
+    bids: BTreeMap<Price, Level>,
+    asks: BTreeMap<Price, Level>,
+    /// Every resting order, by id
+    orders: HashMap<OrderId, Resting>,
+    /// Resting order ids by agent
+    by_agent: BTreeMap<AgentId, BTreeSet<OrderId>>,
+    next_arrival: u64,
+}
+
+impl OrderBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn levels(&self, side: Side) -> &BTreeMap<Price, Level> {
+        match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        }
+    }
+
+    /// Best price a resting order of `side` is offering
+    fn best(&self, side: Side) -> Option<Price> {
+        match side {
+            Side::Buy => self.bids.keys().next_back().copied(),
+            Side::Sell => self.asks.keys().next().copied(),
+        }
+    }
+
+    pub fn best_bid(&self) -> Option<Price> {
+        self.best(Side::Buy)
+    }
+
+    pub fn best_ask(&self) -> Option<Price> {
+        self.best(Side::Sell)
+    }
+
+    /// Midpoint of the best bid and ask, rounded down
+    pub fn mid(&self) -> Option<Price> {
+        Some((self.best_bid()? + self.best_ask()?) / 2)
+    }
+
+    /// Open quantity resting on one side
+    pub fn depth(&self, side: Side) -> Qty {
+        self.levels(side).values().map(|level| level.qty).sum()
+    }
+
+    /// Number of resting orders
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    pub fn get(&self, id: OrderId) -> Option<&Order> {
+        self.orders.get(&id).map(|resting| &resting.order)
+    }
+
+    /// An agent's resting orders, oldest id first
+    pub fn open_orders(&self, agent: AgentId) -> Vec<&Order> {
+        self.by_agent
+            .get(&agent)
+            .map(|ids| ids.iter().filter_map(|id| self.get(*id)).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn snapshot(&self) -> BookSnapshot {
+        BookSnapshot {
+            bids: self.bids.values().rev().flat_map(|level| level.orders(&self.orders)).copied().collect(),
+            asks: self.asks.values().flat_map(|level| level.orders(&self.orders)).copied().collect(),
+        }
+    }
+
+    fn forget(by_agent: &mut BTreeMap<AgentId, BTreeSet<OrderId>>, order: &Order) {
+        if let Some(ids) = by_agent.get_mut(&order.agent) {
+            ids.remove(&order.id);
+            if ids.is_empty() {
+                by_agent.remove(&order.agent);
+            }
+        }
+    }
+
+    /// Match an order against the book and rest what's left of it
+    pub fn submit(&mut self, order: Order) -> Result<Vec<Fill>, SimError> {
+        if order.qty == 0 {
+            return Err(SimError::ZeroQuantity(order.id));
+        }
+        if self.orders.contains_key(&order.id) {
+            return Err(SimError::DuplicateOrder(order.id));
+        }
+
+        let mut incoming = order;
+        let mut fills = Vec::new();
+        let opposite = incoming.side.opposite();
+        while incoming.qty > 0 {
+            let Some(price) = self.best(opposite).filter(|price| incoming.crosses(*price)) else {
+                break;
+            };
+            let levels = match opposite {
+                Side::Buy => &mut self.bids,
+                Side::Sell => &mut self.asks,
+            };
+            let Some(level) = levels.get_mut(&price) else {
+                break;
+            };
+
+            while incoming.qty > 0 {
+                let Some(&entry) = level.queue.front() else {
+                    break;
+                };
+                if !is_live(&self.orders, entry) {
+                    level.queue.pop_front();
+                    continue;
+                }
+                let Some(resting) = self.orders.get_mut(&entry.1) else {
+                    break;
+                };
+                let qty = incoming.qty.min(resting.order.qty);
+                incoming.qty -= qty;
+                resting.order.qty -= qty;
+                level.qty -= qty;
+                fills.push(Fill {
+                    maker_order: resting.order.id,
+                    maker_agent: resting.order.agent,
+                    taker_order: incoming.id,
+                    taker_agent: incoming.agent,
+                    price,
+                    qty,
+                });
+                if resting.order.qty == 0 {
+                    let filled = resting.order;
+                    self.orders.remove(&filled.id);
+                    Self::forget(&mut self.by_agent, &filled);
+                    level.queue.pop_front();
+                    level.live -= 1;
+                }
+            }
+            if level.live == 0 {
+                levels.remove(&price);
+            }
+        }
+
+        if incoming.qty > 0 {
+            let arrival = self.next_arrival;
+            self.next_arrival += 1;
+            let levels = match incoming.side {
+                Side::Buy => &mut self.bids,
+                Side::Sell => &mut self.asks,
+            };
+            let level = levels.entry(incoming.price).or_default();
+            level.queue.push_back((arrival, incoming.id));
+            level.live += 1;
+            level.qty += incoming.qty;
+            self.orders.insert(incoming.id, Resting { order: incoming, arrival });
+            self.by_agent.entry(incoming.agent).or_default().insert(incoming.id);
+        }
+        Ok(fills)
+    }
+
+    /// Remove a resting order
+    pub fn cancel(&mut self, id: OrderId) -> Result<Order, SimError> {
+        let order = self.orders.remove(&id).ok_or(SimError::UnknownOrder(id))?.order;
+        Self::forget(&mut self.by_agent, &order);
+        let levels = match order.side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        let level = levels
+            .get_mut(&order.price)
+            .ok_or_else(|| SimError::Invariant(format!("order {} rests at missing level {}", id, order.price)))?;
+        level.live -= 1;
+        level.qty -= order.qty;
+        if level.live == 0 {
+            levels.remove(&order.price);
+        } else {
+            level.compact(&self.orders);
+        }
+        Ok(order)
+    }
+
+    /// Check the book's invariants: it isn't crossed, holds no empty levels
+    /// or empty orders, every resting order is queued exactly once at its
+    /// own price, and the levels' counts and the agent index match
+    pub fn check_invariants(&self) -> Result<(), SimError> {
+        if let (Some(bid), Some(ask)) = (self.best_bid(), self.best_ask()) {
+            if bid >= ask {
+                return Err(SimError::Invariant(format!("book is crossed: bid {} >= ask {}", bid, ask)));
+            }
+        }
+
+        let mut resting = 0;
+        for side in [Side::Buy, Side::Sell] {
+            for (price, level) in self.levels(side) {
+                let orders: Vec<&Order> = level.orders(&self.orders).collect();
+                if orders.is_empty() {
+                    return Err(SimError::Invariant(format!("empty {} level at {}", side, price)));
+                }
+                if orders.len() != level.live || orders.iter().map(|order| order.qty).sum::<Qty>() != level.qty {
+                    return Err(SimError::Invariant(format!("{} level at {} miscounts its orders", side, price)));
+                }
+                for order in orders {
+                    if order.qty == 0 || order.side != side || order.price != *price {
+                        return Err(SimError::Invariant(format!("order {} is misplaced or empty", order.id)));
+                    }
+                    if !self.by_agent.get(&order.agent).is_some_and(|ids| ids.contains(&order.id)) {
+                        return Err(SimError::Invariant(format!("order {} is missing from its agent", order.id)));
+                    }
+                    resting += 1;
+                }
+            }
+        }
+        let indexed: usize = self.by_agent.values().map(BTreeSet::len).sum();
+        if resting != self.orders.len() || indexed != self.orders.len() {
+            return Err(SimError::Invariant(format!(
+                "{} orders rest, levels queue {} and agents hold {}",
+                self.orders.len(),
+                resting,
+                indexed
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(id: OrderId, agent: AgentId, side: Side, price: Price, qty: Qty) -> Order {
+        Order::new(id, agent, side, price, qty)
+    }
+
+    #[test]
+    fn test_non_crossing_orders_rest() {
+        let mut book = OrderBook::new();
+        assert!(book.submit(order(1, 1, Side::Buy, 99, 5)).unwrap().is_empty());
+        assert!(book.submit(order(2, 2, Side::Sell, 101, 3)).unwrap().is_empty());
+
+        assert_eq!(book.best_bid(), Some(99));
+        assert_eq!(book.best_ask(), Some(101));
+        assert_eq!(book.mid(), Some(100));
+        assert_eq!(book.depth(Side::Buy), 5);
+        assert_eq!(book.len(), 2);
+        book.check_invariants().unwrap();
+
+        book.submit(order(3, 3, Side::Buy, 100, 1)).unwrap();
+        book.submit(order(4, 4, Side::Buy, 100, 1)).unwrap();
+        let bids: Vec<OrderId> = book.snapshot().bids.iter().map(|o| o.id).collect();
+        assert_eq!(bids, vec![3, 4, 1]);
+    }
+
+    #[test]
+    fn test_matching_follows_price_then_time_priority() {
+        let mut book = OrderBook::new();
+        book.submit(order(1, 1, Side::Sell, 101, 2)).unwrap();
+        book.submit(order(2, 2, Side::Sell, 100, 2)).unwrap();
+        book.submit(order(3, 3, Side::Sell, 100, 2)).unwrap();
+
+        let fills = book.submit(order(4, 4, Side::Buy, 101, 5)).unwrap();
+        let matched: Vec<(OrderId, Price, Qty)> = fills.iter().map(|f| (f.maker_order, f.price, f.qty)).collect();
+        assert_eq!(matched, vec![(2, 100, 2), (3, 100, 2), (1, 101, 1)]);
+
+        // The taker is fully filled and the last maker keeps its remainder
+        assert!(book.get(4).is_none());
+        assert_eq!(book.get(1).map(|o| o.qty), Some(1));
+        assert_eq!(book.best_ask(), Some(101));
+        assert_eq!(book.best_bid(), None);
+        book.check_invariants().unwrap();
+    }
+
+    #[test]
+    fn test_unfilled_remainder_rests_at_its_limit() {
+        let mut book = OrderBook::new();
+        book.submit(order(1, 1, Side::Buy, 100, 2)).unwrap();
+        let fills = book.submit(order(2, 2, Side::Sell, 99, 5)).unwrap();
+
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].price, 100);
+        assert_eq!(book.get(2).map(|o| (o.price, o.qty)), Some((99, 3)));
+        assert_eq!(book.best_bid(), None);
+        book.check_invariants().unwrap();
+    }
+
+    #[test]
+    fn test_cancel() {
+        let mut book = OrderBook::new();
+        book.submit(order(1, 1, Side::Buy, 100, 2)).unwrap();
+        book.submit(order(2, 1, Side::Buy, 100, 3)).unwrap();
+
+        assert_eq!(book.cancel(1).unwrap().qty, 2);
+        assert_eq!(book.cancel(1), Err(SimError::UnknownOrder(1)));
+        assert_eq!(book.open_orders(1).iter().map(|o| o.id).collect::<Vec<_>>(), vec![2]);
+        book.cancel(2).unwrap();
+        assert!(book.is_empty());
+        assert_eq!(book.best_bid(), None);
+        book.check_invariants().unwrap();
+    }
+
+    #[test]
+    fn test_rejects_empty_and_duplicate_orders() {
+        let mut book = OrderBook::new();
+        assert_eq!(book.submit(order(1, 1, Side::Buy, 100, 0)), Err(SimError::ZeroQuantity(1)));
+        book.submit(order(1, 1, Side::Buy, 100, 1)).unwrap();
+        assert_eq!(book.submit(order(1, 1, Side::Buy, 98, 1)), Err(SimError::DuplicateOrder(1)));
+    }
+
+    #[test]
+    fn test_cancelled_entries_are_skipped_and_compacted() {
+        let mut book = OrderBook::new();
+        for id in 1..=10 {
+            book.submit(order(id, 1, Side::Sell, 100, 1)).unwrap();
+        }
+        for id in 1..=8 {
+            book.cancel(id).unwrap();
+            book.check_invariants().unwrap();
+        }
+        assert!(book.asks[&100].queue.len() <= 2 * book.asks[&100].live);
+        assert_eq!(book.depth(Side::Sell), 2);
+
+        let fills = book.submit(order(11, 2, Side::Buy, 100, 2)).unwrap();
+        assert_eq!(fills.iter().map(|f| f.maker_order).collect::<Vec<_>>(), vec![9, 10]);
+        assert!(book.is_empty());
+        book.check_invariants().unwrap();
+    }
+
+    #[test]
+    fn test_reused_id_queues_behind_older_orders() {
+        let mut book = OrderBook::new();
+        book.submit(order(1, 1, Side::Sell, 100, 1)).unwrap();
+        book.submit(order(2, 1, Side::Sell, 100, 1)).unwrap();
+        book.submit(order(3, 1, Side::Sell, 100, 1)).unwrap();
+        book.cancel(1).unwrap();
+        book.submit(order(1, 1, Side::Sell, 100, 1)).unwrap();
+
+        let asks: Vec<OrderId> = book.snapshot().asks.iter().map(|o| o.id).collect();
+        assert_eq!(asks, vec![2, 3, 1]);
+        let fills = book.submit(order(4, 2, Side::Buy, 100, 1)).unwrap();
+        assert_eq!(fills[0].maker_order, 2);
+        book.check_invariants().unwrap();
+    }
+}
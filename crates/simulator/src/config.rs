@@ -0,0 +1,127 @@
+//! Run configuration
+//!
+//! A serializable description of a run built from the stock agents. Runs
+//! with custom agents are assembled on a `Simulation` directly and can't be
+//! described here.
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent::Agent;
+use crate::agents::{CancelBot, NoiseTrader};
+use crate::engine::Simulation;
+use crate::error::SimError;
+use crate::regime::{RegimeSchedule, RegimeScheduler};
+use crate::types::{AgentId, Price, Tick};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AgentSpec {
+    NoiseTrader { id: AgentId, reference: Price, spread: u32 },
+    CancelBot { id: AgentId, reference: Price },
+}
+
+impl AgentSpec {
+    pub fn build(&self) -> Box<dyn Agent> {
+        match *self {
+            AgentSpec::NoiseTrader { id, reference, spread } => Box::new(NoiseTrader::new(id, reference, spread)),
+            AgentSpec::CancelBot { id, reference } => Box::new(CancelBot::new(id, reference)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimConfig {
+    pub seed: u64,
+    pub ticks: Tick,
+    #[serde(default)]
+    pub schedule: RegimeSchedule,
+    /// Draws the schedule from the seed instead; `schedule` is then ignored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regimes: Option<RegimeScheduler>,
+    pub agents: Vec<AgentSpec>,
+}
+
+impl SimConfig {
+    /// The regime schedule the run follows
+    pub fn regime_schedule(&self) -> RegimeSchedule {
+        match &self.regimes {
+            Some(scheduler) => scheduler.schedule(self.seed, self.ticks),
+            None => self.schedule.clone(),
+        }
+    }
+
+    /// A simulation with the configured agents, ready to run
+    pub fn build(&self) -> Result<Simulation, SimError> {
+        let mut sim = Simulation::new(self.seed, self.regime_schedule());
+        for spec in &self.agents {
+            sim.add_agent(spec.build())?;
+        }
+        Ok(sim)
+    }
+
+    /// Build the simulation and run it for the configured ticks
+    pub fn run(&self) -> Result<Simulation, SimError> {
+        let mut sim = self.build()?;
+        sim.run(self.ticks);
+        Ok(sim)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_round_trips_through_json() {
+        let config = SimConfig {
+            seed: 42,
+            ticks: 10,
+            schedule: RegimeSchedule::cycle(5),
+            regimes: None,
+            agents: vec![
+                AgentSpec::NoiseTrader { id: 1, reference: 100, spread: 3 },
+                AgentSpec::CancelBot { id: 2, reference: 100 },
+            ],
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains(r#""type":"noise_trader""#));
+        assert_eq!(serde_json::from_str::<SimConfig>(&json).unwrap(), config);
+
+        let sim = config.run().unwrap();
+        assert_eq!(sim.tick(), 10);
+        assert_eq!(sim.agents().ids(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_duplicate_agents_are_rejected() {
+        let config = SimConfig {
+            seed: 1,
+            ticks: 1,
+            schedule: RegimeSchedule::calm(),
+            regimes: None,
+            agents: vec![
+                AgentSpec::CancelBot { id: 1, reference: 100 },
+                AgentSpec::CancelBot { id: 1, reference: 90 },
+            ],
+        };
+        assert_eq!(config.build().err(), Some(SimError::DuplicateAgent(1)));
+    }
+
+    #[test]
+    fn test_seeded_regimes_replace_the_schedule() {
+        let scheduler = RegimeScheduler::sticky(10).unwrap();
+        let config = SimConfig {
+            seed: 42,
+            ticks: 200,
+            schedule: RegimeSchedule::calm(),
+            regimes: Some(scheduler.clone()),
+            agents: vec![AgentSpec::NoiseTrader { id: 1, reference: 100, spread: 2 }],
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(serde_json::from_str::<SimConfig>(&json).unwrap(), config);
+
+        let expected = scheduler.schedule(42, 200);
+        assert_ne!(expected, RegimeSchedule::calm());
+        assert_eq!(config.build().unwrap().schedule(), &expected);
+    }
+}
@@ -0,0 +1,367 @@
+//! The tick loop
+//!
+//! Each tick, every due agent is stepped in id order against the book as
+//! the previous agents left it, and its actions are applied before the next
+//! agent runs. Order ids are assigned from one counter in application
+//! order, so the whole trace follows from the seed and the agents.
+
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+use crate::agent::{Action, Agent, Ctx};
+use crate::book::{BookSnapshot, OrderBook};
+use crate::error::SimError;
+use crate::event::{self, Event, EventKind};
+use crate::metrics::{Metrics, TickMetrics};
+use crate::regime::{Regime, RegimeSchedule};
+use crate::registry::AgentRegistry;
+use crate::types::{AgentId, Order, OrderId, Tick};
+
+/// Everything a run leaves behind apart from its trace. Replaying a run's
+/// log reproduces it exactly.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimState {
+    /// Ticks run
+    pub tick: Tick,
+    pub next_order_id: OrderId,
+    pub book: BookSnapshot,
+    pub metrics: Metrics,
+}
+
+pub struct Simulation {
+    seed: u64,
+    tick: Tick,
+    next_order_id: OrderId,
+    book: OrderBook,
+    agents: AgentRegistry,
+    schedule: RegimeSchedule,
+    trace: Vec<Event>,
+    /// Actions proposed in the last tick, in application order
+    last_actions: Vec<(AgentId, Action)>,
+    metrics: Metrics,
+    tick_metrics: TickMetrics,
+}
+
+impl Simulation {
+    pub fn new(seed: u64, schedule: RegimeSchedule) -> Self {
+        Self {
+            seed,
+            tick: 0,
+            next_order_id: 1,
+            book: OrderBook::new(),
+            agents: AgentRegistry::new(seed),
+            schedule,
+            trace: Vec::new(),
+            last_actions: Vec::new(),
+            metrics: Metrics::default(),
+            tick_metrics: TickMetrics::new(),
+        }
+    }
+
+    pub fn add_agent(&mut self, agent: Box<dyn Agent>) -> Result<(), SimError> {
+        self.agents.register(agent)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn schedule(&self) -> &RegimeSchedule {
+        &self.schedule
+    }
+
+    /// The next tick to run
+    pub fn tick(&self) -> Tick {
+        self.tick
+    }
+
+    pub fn regime(&self) -> Regime {
+        self.schedule.regime_at(self.tick)
+    }
+
+    pub fn book(&self) -> &OrderBook {
+        &self.book
+    }
+
+    pub fn agents(&self) -> &AgentRegistry {
+        &self.agents
+    }
+
+    pub fn agents_mut(&mut self) -> &mut AgentRegistry {
+        &mut self.agents
+    }
+
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Counts for every tick run and how long actions took to apply
+    pub fn tick_metrics(&self) -> &TickMetrics {
+        &self.tick_metrics
+    }
+
+    pub fn trace(&self) -> &[Event] {
+        &self.trace
+    }
+
+    /// Actions the agents proposed in the last tick run, in the order they
+    /// were applied. See [`crate::schedule::fingerprint`] to compare them.
+    pub fn last_actions(&self) -> &[(AgentId, Action)] {
+        &self.last_actions
+    }
+
+    pub fn state(&self) -> SimState {
+        SimState {
+            tick: self.tick,
+            next_order_id: self.next_order_id,
+            book: self.book.snapshot(),
+            metrics: self.metrics,
+        }
+    }
+
+    /// Hash of the trace so far; equal fingerprints mean equal traces
+    pub fn fingerprint(&self) -> String {
+        event::fingerprint(&self.trace)
+    }
+
+    fn record(&mut self, agent: AgentId, kind: EventKind) {
+        let seq = self.trace.len() as u64;
+        self.metrics.count(&kind);
+        self.tick_metrics.count(agent, &kind);
+        self.trace.push(Event { seq, tick: self.tick, kind });
+    }
+
+    fn apply(&mut self, agent: AgentId, action: Action) {
+        match action {
+            Action::Place { side, price, qty } => {
+                let order = Order::new(self.next_order_id, agent, side, price, qty);
+                match self.book.submit(order) {
+                    Ok(fills) => {
+                        self.next_order_id += 1;
+                        self.record(agent, EventKind::NewOrder(order));
+                        for fill in fills {
+                            self.record(agent, EventKind::Fill(fill));
+                        }
+                    }
+                    Err(e) => self.record(agent, EventKind::rejected(agent, &e)),
+                }
+            }
+            Action::Cancel(id) => {
+                let owner = self.book.get(id).map(|order| order.agent);
+                let result = match owner {
+                    None => Err(SimError::UnknownOrder(id)),
+                    Some(owner) if owner != agent => Err(SimError::NotOwner { agent, order: id }),
+                    Some(_) => self.book.cancel(id),
+                };
+                match result {
+                    Ok(_) => self.record(agent, EventKind::Cancel { agent, order: id }),
+                    Err(e) => self.record(agent, EventKind::rejected(agent, &e)),
+                }
+            }
+        }
+    }
+
+    /// Run one tick and return the events it produced
+    pub fn step(&mut self) -> &[Event] {
+        let start = self.trace.len();
+        let regime = self.regime();
+        self.last_actions.clear();
+        self.tick_metrics.begin_tick(self.tick, regime);
+
+        for id in self.agents.due(self.tick) {
+            let Some((agent, rng)) = self.agents.get_mut(id) else {
+                continue;
+            };
+            let ctx = Ctx { tick: self.tick, regime, agent: id, book: &self.book };
+            let actions = agent.step(&ctx, rng);
+            for action in actions {
+                self.last_actions.push((id, action));
+                let started = Instant::now();
+                self.apply(id, action);
+                self.tick_metrics.record_apply(started.elapsed());
+            }
+        }
+
+        self.tick_metrics.end_tick(self.book.len());
+        self.tick += 1;
+        self.metrics.ticks += 1;
+        &self.trace[start..]
+    }
+
+    /// Run `ticks` more ticks
+    pub fn run(&mut self, ticks: u64) {
+        for _ in 0..ticks {
+            self.step();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::{CancelBot, NoiseTrader};
+    use crate::rng::Rng;
+    use crate::schedule;
+    use crate::types::Side;
+    use proptest::prelude::*;
+
+    fn simulation(seed: u64) -> Simulation {
+        let mut sim = Simulation::new(seed, RegimeSchedule::cycle(20));
+        sim.add_agent(Box::new(NoiseTrader::new(1, 100, 3))).unwrap();
+        sim.add_agent(Box::new(CancelBot::new(2, 100))).unwrap();
+        sim.add_agent(Box::new(NoiseTrader::new(3, 100, 5))).unwrap();
+        sim
+    }
+
+    #[test]
+    fn test_same_seed_same_trace() {
+        let mut a = simulation(42);
+        let mut b = simulation(42);
+        a.run(100);
+        b.run(100);
+
+        assert_eq!(a.trace(), b.trace());
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        assert_eq!(a.metrics(), b.metrics());
+
+        let mut c = simulation(43);
+        c.run(100);
+        assert_ne!(a.fingerprint(), c.fingerprint());
+    }
+
+    #[test]
+    fn test_registration_order_does_not_matter() {
+        let mut a = simulation(42);
+        let mut b = Simulation::new(42, RegimeSchedule::cycle(20));
+        b.add_agent(Box::new(NoiseTrader::new(3, 100, 5))).unwrap();
+        b.add_agent(Box::new(CancelBot::new(2, 100))).unwrap();
+        b.add_agent(Box::new(NoiseTrader::new(1, 100, 3))).unwrap();
+        a.run(60);
+        b.run(60);
+        assert_eq!(a.fingerprint(), b.fingerprint());
+    }
+
+    #[test]
+    fn test_book_invariants_hold_every_tick() {
+        let mut sim = simulation(7);
+        for _ in 0..200 {
+            sim.step();
+            sim.book().check_invariants().unwrap();
+        }
+
+        let metrics = sim.metrics();
+        assert_eq!(metrics.ticks, 200);
+        assert!(metrics.orders > 0 && metrics.fills > 0 && metrics.cancels > 0);
+        assert_eq!(metrics.orders + metrics.cancels + metrics.fills + metrics.rejected, sim.trace().len() as u64);
+    }
+
+    #[test]
+    fn test_golden_trace() {
+        // Changes to the RNG, matching, agents or trace format all move this;
+        // update it only when the change is meant to alter every recorded run
+        let mut sim = simulation(42);
+        sim.run(100);
+        assert_eq!(sim.fingerprint(), "7ee560471ae20df4");
+    }
+
+    struct Script {
+        id: AgentId,
+        actions: Vec<Vec<Action>>,
+    }
+
+    impl Agent for Script {
+        fn id(&self) -> AgentId {
+            self.id
+        }
+
+        fn step(&mut self, ctx: &Ctx, _rng: &mut Rng) -> Vec<Action> {
+            self.actions.get(ctx.tick as usize).cloned().unwrap_or_default()
+        }
+    }
+
+    #[test]
+    fn test_scripted_trace() {
+        let mut sim = Simulation::new(0, RegimeSchedule::calm());
+        sim.add_agent(Box::new(Script {
+            id: 1,
+            actions: vec![
+                vec![Action::Place { side: Side::Sell, price: 101, qty: 2 }],
+                vec![],
+                vec![Action::Cancel(1)],
+            ],
+        }))
+        .unwrap();
+        sim.add_agent(Box::new(Script {
+            id: 2,
+            actions: vec![
+                vec![Action::Cancel(1)],
+                vec![Action::Place { side: Side::Buy, price: 102, qty: 3 }],
+            ],
+        }))
+        .unwrap();
+        sim.run(3);
+
+        let lines: Vec<String> = sim.trace().iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "0 t0 new #1 a1 sell 2@101",
+                "1 t0 reject a2: Agent 2 doesn't own order 1",
+                "2 t1 new #2 a2 buy 3@102",
+                "3 t1 fill #1<-#2 2@101",
+                "4 t2 reject a1: Unknown order: 1",
+            ]
+        );
+        assert_eq!(sim.book().get(2).map(|o| o.qty), Some(1));
+    }
+
+    #[test]
+    fn test_golden_schedule() {
+        // Tick 20 opens the first burst
+        let mut sim = simulation(42);
+        sim.run(21);
+        assert_eq!(sim.regime(), Regime::Burst);
+        assert_eq!(
+            schedule::fingerprint(sim.last_actions()),
+            "a1:Place { side: Buy, price: 98, qty: 2 },a1:Place { side: Buy, price: 97, qty: 4 },\
+             a1:Place { side: Buy, price: 97, qty: 5 },a2:Cancel(16),a2:Place { side: Buy, price: 99, qty: 1 },\
+             a3:Place { side: Sell, price: 103, qty: 1 },a3:Place { side: Buy, price: 97, qty: 5 },\
+             a3:Place { side: Sell, price: 100, qty: 5 }"
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn schedules_are_stable_across_runs(seed in any::<u64>(), ticks in 1u64..60) {
+            let mut a = simulation(seed);
+            let mut b = simulation(seed);
+            for _ in 0..ticks {
+                a.step();
+                b.step();
+                prop_assert_eq!(schedule::fingerprint(a.last_actions()), schedule::fingerprint(b.last_actions()));
+            }
+            prop_assert_eq!(a.fingerprint(), b.fingerprint());
+        }
+    }
+
+    #[test]
+    fn test_tick_metrics_add_up_to_the_totals() {
+        let mut sim = simulation(42);
+        sim.run(100);
+        let ticks = sim.tick_metrics().ticks();
+        assert_eq!(ticks.len(), 100);
+        assert_eq!(ticks.iter().map(|t| t.places).sum::<u64>(), sim.metrics().orders);
+        assert_eq!(ticks.iter().map(|t| t.total_cancels()).sum::<u64>(), sim.metrics().cancels);
+        assert_eq!(ticks.iter().map(|t| t.fills).sum::<u64>(), sim.metrics().fills);
+        assert_eq!(ticks[25].regime, Regime::Burst);
+        assert_eq!(ticks[99].open_orders, sim.book().len());
+
+        let summary = sim.tick_metrics().latency_summary().unwrap();
+        assert_eq!(summary.samples, sim.trace().iter().filter(|e| !matches!(e.kind, EventKind::Fill(_))).count());
+        assert!(summary.p50 <= summary.p99 && summary.p99 <= summary.max);
+
+        let mut again = simulation(42);
+        again.run(100);
+        assert_eq!(again.tick_metrics().fingerprint(), sim.tick_metrics().fingerprint());
+    }
+}
@@ -0,0 +1,38 @@
+//! Error types for the simulation engine
+
+use thiserror::Error;
+
+use crate::types::{AgentId, OrderId};
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SimError {
+    #[error("Unknown order: {0}")]
+    UnknownOrder(OrderId),
+
+    #[error("Order {0} is already in the book")]
+    DuplicateOrder(OrderId),
+
+    #[error("Order {0} has zero quantity")]
+    ZeroQuantity(OrderId),
+
+    #[error("Agent {agent} doesn't own order {order}")]
+    NotOwner { agent: AgentId, order: OrderId },
+
+    #[error("Agent {0} is already registered")]
+    DuplicateAgent(AgentId),
+
+    #[error("Unknown agent: {0}")]
+    UnknownAgent(AgentId),
+
+    #[error("Order book invariant violated: {0}")]
+    Invariant(String),
+
+    #[error("Invalid configuration: {0}")]
+    Config(String),
+
+    #[error("Invalid replay log: {0}")]
+    Log(String),
+
+    #[error("Replay diverged: {0}")]
+    Replay(String),
+}
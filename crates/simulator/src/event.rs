@@ -0,0 +1,103 @@
+//! Trace events
+//!
+//! Everything that changes the book is recorded as an event. An event's
+//! `Display` form is one line of the trace; the run's fingerprint is a hash
+//! of those lines, so changing the format changes every golden fingerprint.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::book::Fill;
+use crate::error::SimError;
+use crate::types::{AgentId, Order, OrderId, Tick};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EventKind {
+    NewOrder(Order),
+    Cancel { agent: AgentId, order: OrderId },
+    Fill(Fill),
+    /// An action the book refused, e.g. cancelling an order that has
+    /// already filled
+    Rejected { agent: AgentId, reason: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Event {
+    /// Position in the trace, from zero
+    pub seq: u64,
+    pub tick: Tick,
+    pub kind: EventKind,
+}
+
+impl EventKind {
+    pub(crate) fn rejected(agent: AgentId, error: &SimError) -> Self {
+        EventKind::Rejected { agent, reason: error.to_string() }
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} t{} ", self.seq, self.tick)?;
+        match &self.kind {
+            EventKind::NewOrder(order) => write!(
+                f,
+                "new #{} a{} {} {}@{}",
+                order.id, order.agent, order.side, order.qty, order.price
+            ),
+            EventKind::Cancel { agent, order } => write!(f, "cancel #{} a{}", order, agent),
+            EventKind::Fill(fill) => write!(
+                f,
+                "fill #{}<-#{} {}@{}",
+                fill.maker_order, fill.taker_order, fill.qty, fill.price
+            ),
+            EventKind::Rejected { agent, reason } => write!(f, "reject a{}: {}", agent, reason),
+        }
+    }
+}
+
+/// FNV-1a over the trace lines, as 16 hex digits
+pub(crate) fn fingerprint(events: &[Event]) -> String {
+    fnv1a_lines(events.iter().map(|event| event.to_string()))
+}
+
+/// FNV-1a over lines, each followed by a newline, as 16 hex digits
+pub(crate) fn fnv1a_lines<S: AsRef<str>>(lines: impl IntoIterator<Item = S>) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let mut hash = OFFSET_BASIS;
+    for line in lines {
+        for byte in line.as_ref().bytes().chain(std::iter::once(b'\n')) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Side;
+
+    #[test]
+    fn test_trace_lines() {
+        let new = Event { seq: 0, tick: 3, kind: EventKind::NewOrder(Order::new(5, 1, Side::Buy, 100, 2)) };
+        let cancel = Event { seq: 1, tick: 3, kind: EventKind::Cancel { agent: 1, order: 5 } };
+        let rejected = Event { seq: 2, tick: 4, kind: EventKind::rejected(2, &SimError::UnknownOrder(9)) };
+
+        assert_eq!(new.to_string(), "0 t3 new #5 a1 buy 2@100");
+        assert_eq!(cancel.to_string(), "1 t3 cancel #5 a1");
+        assert_eq!(rejected.to_string(), "2 t4 reject a2: Unknown order: 9");
+    }
+
+    #[test]
+    fn test_fingerprint_depends_on_every_event() {
+        let a = Event { seq: 0, tick: 0, kind: EventKind::Cancel { agent: 1, order: 1 } };
+        let b = Event { seq: 0, tick: 0, kind: EventKind::Cancel { agent: 1, order: 2 } };
+
+        assert_eq!(fingerprint(&[]), "cbf29ce484222325");
+        assert_ne!(fingerprint(std::slice::from_ref(&a)), fingerprint(std::slice::from_ref(&b)));
+        assert_ne!(fingerprint(&[a.clone(), b.clone()]), fingerprint(&[b, a]));
+    }
+}
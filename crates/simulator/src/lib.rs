@@ -0,0 +1,38 @@
+//! Deterministic order flow simulation engine
+//!
+//! The engine the orderflow curriculum builds on: a price-time priority
+//! order book, a registry of agents that each draw from their own seeded
+//! RNG stream, and a tick loop that turns the agents' actions into a trace
+//! of events. The same seed and agents always produce the same trace, down
+//! to its fingerprint, and a run's replay log rebuilds its end state.
+
+pub mod error;
+pub mod types;
+pub mod rng;
+pub mod book;
+pub mod event;
+pub mod regime;
+pub mod agent;
+pub mod agents;
+pub mod registry;
+pub mod metrics;
+pub mod config;
+pub mod schedule;
+pub mod engine;
+pub mod replay;
+#[cfg(any(test, feature = "testkit"))]
+pub mod testkit;
+
+pub use error::SimError;
+pub use types::{AgentId, Order, OrderId, Price, Qty, Side, Tick};
+pub use rng::Rng;
+pub use book::{BookSnapshot, Fill, OrderBook};
+pub use event::{Event, EventKind};
+pub use regime::{Regime, RegimeSchedule, RegimeScheduler};
+pub use agent::{Action, Agent, Ctx};
+pub use agents::{CancelBot, NoiseTrader};
+pub use registry::AgentRegistry;
+pub use config::{AgentSpec, SimConfig};
+pub use metrics::{LatencySummary, Metrics, TickMetrics, TickStats};
+pub use engine::{SimState, Simulation};
+pub use replay::{replay, ReplayHeader, ReplayLog, TickRecord, REPLAY_VERSION};
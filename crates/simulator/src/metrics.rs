@@ -0,0 +1,272 @@
+//! Simulation metrics
+//!
+//! Running totals over a run, a row of counts for every tick and the time
+//! the engine spent applying each action. The counts follow from the trace,
+//! so their CSV and fingerprint are as stable as the trace is. Latencies
+//! depend on the machine running the simulation and are kept out of both.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use crate::event::{self, EventKind};
+use crate::regime::Regime;
+use crate::types::{AgentId, Tick};
+
+/// Running counts over a simulation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metrics {
+    pub ticks: u64,
+    pub orders: u64,
+    pub cancels: u64,
+    pub fills: u64,
+    pub filled_qty: u64,
+    pub rejected: u64,
+}
+
+impl Metrics {
+    /// Count one event
+    pub fn count(&mut self, kind: &EventKind) {
+        match kind {
+            EventKind::NewOrder(_) => self.orders += 1,
+            EventKind::Cancel { .. } => self.cancels += 1,
+            EventKind::Fill(fill) => {
+                self.fills += 1;
+                self.filled_qty += fill.qty;
+            }
+            EventKind::Rejected { .. } => self.rejected += 1,
+        }
+    }
+
+    /// Share of placed orders later cancelled
+    pub fn cancel_rate(&self) -> f64 {
+        if self.orders == 0 {
+            return 0.0;
+        }
+        self.cancels as f64 / self.orders as f64
+    }
+}
+
+/// What happened in one tick
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickStats {
+    pub tick: Tick,
+    pub regime: Regime,
+    /// Orders accepted into the book, filled or not
+    pub places: u64,
+    /// Cancels by agent; agents that cancelled nothing are left out
+    pub cancels: BTreeMap<AgentId, u64>,
+    pub fills: u64,
+    pub rejected: u64,
+    /// Orders resting in the book once the tick was over
+    pub open_orders: usize,
+}
+
+impl TickStats {
+    fn new(tick: Tick, regime: Regime) -> Self {
+        Self {
+            tick,
+            regime,
+            places: 0,
+            cancels: BTreeMap::new(),
+            fills: 0,
+            rejected: 0,
+            open_orders: 0,
+        }
+    }
+
+    pub fn total_cancels(&self) -> u64 {
+        self.cancels.values().sum()
+    }
+}
+
+/// Percentiles of the time spent applying actions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct LatencySummary {
+    pub samples: usize,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+/// Per-tick counts and apply latencies of a run
+#[derive(Debug, Clone, Default)]
+pub struct TickMetrics {
+    ticks: Vec<TickStats>,
+    /// Time each action took to apply, in application order
+    apply_latencies: Vec<Duration>,
+}
+
+impl TickMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stats of every tick run, in tick order
+    pub fn ticks(&self) -> &[TickStats] {
+        &self.ticks
+    }
+
+    pub(crate) fn begin_tick(&mut self, tick: Tick, regime: Regime) {
+        self.ticks.push(TickStats::new(tick, regime));
+    }
+
+    pub(crate) fn count(&mut self, agent: AgentId, kind: &EventKind) {
+        let Some(stats) = self.ticks.last_mut() else {
+            return;
+        };
+        match kind {
+            EventKind::NewOrder(_) => stats.places += 1,
+            EventKind::Cancel { .. } => *stats.cancels.entry(agent).or_default() += 1,
+            EventKind::Fill(_) => stats.fills += 1,
+            EventKind::Rejected { .. } => stats.rejected += 1,
+        }
+    }
+
+    pub(crate) fn end_tick(&mut self, open_orders: usize) {
+        if let Some(stats) = self.ticks.last_mut() {
+            stats.open_orders = open_orders;
+        }
+    }
+
+    pub(crate) fn record_apply(&mut self, elapsed: Duration) {
+        self.apply_latencies.push(elapsed);
+    }
+
+    /// Nearest-rank percentile of the apply latencies, `percentile` being
+    /// between 0 and 100. `None` before anything was applied.
+    pub fn latency_percentile(&self, percentile: f64) -> Option<Duration> {
+        let mut sorted = self.apply_latencies.clone();
+        sorted.sort_unstable();
+        nearest_rank(&sorted, percentile)
+    }
+
+    pub fn latency_summary(&self) -> Option<LatencySummary> {
+        let mut sorted = self.apply_latencies.clone();
+        sorted.sort_unstable();
+        Some(LatencySummary {
+            samples: sorted.len(),
+            p50: nearest_rank(&sorted, 50.0)?,
+            p90: nearest_rank(&sorted, 90.0)?,
+            p99: nearest_rank(&sorted, 99.0)?,
+            max: *sorted.last()?,
+        })
+    }
+
+    /// One row per tick. Besides the totals there is a `cancels_a{id}`
+    /// column for every agent that cancelled anything during the run.
+    pub fn to_csv(&self) -> String {
+        let agents: BTreeSet<AgentId> = self.ticks.iter().flat_map(|stats| stats.cancels.keys().copied()).collect();
+
+        let mut out = "tick,regime,places,cancels,fills,rejected,open_orders".to_string();
+        for agent in &agents {
+            out.push_str(&format!(",cancels_a{}", agent));
+        }
+        out.push('\n');
+
+        for stats in &self.ticks {
+            out.push_str(&format!(
+                "{},{:?},{},{},{},{},{}",
+                stats.tick,
+                stats.regime,
+                stats.places,
+                stats.total_cancels(),
+                stats.fills,
+                stats.rejected,
+                stats.open_orders
+            ));
+            for agent in &agents {
+                out.push_str(&format!(",{}", stats.cancels.get(agent).copied().unwrap_or(0)));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Hash of the CSV, as 16 hex digits; equal fingerprints mean equal
+    /// per-tick counts
+    pub fn fingerprint(&self) -> String {
+        event::fnv1a_lines(self.to_csv().lines())
+    }
+}
+
+fn nearest_rank(sorted: &[Duration], percentile: f64) -> Option<Duration> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (percentile.clamp(0.0, 100.0) / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.clamp(1, sorted.len()) - 1).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::book::Fill;
+    use crate::error::SimError;
+    use crate::types::{Order, Side};
+
+    fn two_ticks() -> TickMetrics {
+        let mut metrics = TickMetrics::new();
+        metrics.begin_tick(0, Regime::Calm);
+        metrics.count(1, &EventKind::NewOrder(Order::new(1, 1, Side::Buy, 100, 2)));
+        metrics.count(2, &EventKind::NewOrder(Order::new(2, 2, Side::Sell, 101, 1)));
+        metrics.end_tick(2);
+
+        metrics.begin_tick(1, Regime::CancelStorm);
+        metrics.count(2, &EventKind::Cancel { agent: 2, order: 2 });
+        metrics.count(3, &EventKind::rejected(3, &SimError::UnknownOrder(9)));
+        let fill = Fill { maker_order: 1, maker_agent: 1, taker_order: 3, taker_agent: 3, price: 100, qty: 2 };
+        metrics.count(3, &EventKind::NewOrder(Order::new(3, 3, Side::Sell, 100, 2)));
+        metrics.count(3, &EventKind::Fill(fill));
+        metrics.end_tick(0);
+        metrics
+    }
+
+    #[test]
+    fn test_counts_per_tick() {
+        let metrics = two_ticks();
+        let ticks = metrics.ticks();
+        assert_eq!(ticks.len(), 2);
+        assert_eq!((ticks[0].places, ticks[0].total_cancels(), ticks[0].open_orders), (2, 0, 2));
+        assert_eq!(ticks[1].cancels, BTreeMap::from([(2, 1)]));
+        assert_eq!((ticks[1].fills, ticks[1].rejected, ticks[1].regime), (1, 1, Regime::CancelStorm));
+    }
+
+    #[test]
+    fn test_csv_and_fingerprint() {
+        let metrics = two_ticks();
+        assert_eq!(
+            metrics.to_csv(),
+            "tick,regime,places,cancels,fills,rejected,open_orders,cancels_a2\n\
+             0,Calm,2,0,0,0,2,0\n\
+             1,CancelStorm,1,1,1,1,0,1\n"
+        );
+
+        // Latencies don't enter the fingerprint
+        let mut timed = two_ticks();
+        timed.record_apply(Duration::from_micros(3));
+        assert_eq!(timed.fingerprint(), metrics.fingerprint());
+        assert_eq!(metrics.fingerprint(), "3cb9f60cc1d0c307");
+    }
+
+    #[test]
+    fn test_latency_percentiles() {
+        let mut metrics = TickMetrics::new();
+        assert_eq!(metrics.latency_percentile(50.0), None);
+        assert_eq!(metrics.latency_summary(), None);
+
+        for micros in (1..=100).rev() {
+            metrics.record_apply(Duration::from_micros(micros));
+        }
+        assert_eq!(metrics.latency_percentile(0.0), Some(Duration::from_micros(1)));
+        assert_eq!(metrics.latency_percentile(50.0), Some(Duration::from_micros(50)));
+        assert_eq!(metrics.latency_percentile(99.5), Some(Duration::from_micros(100)));
+
+        let summary = metrics.latency_summary().unwrap();
+        assert_eq!(summary.samples, 100);
+        assert_eq!(summary.p90, Duration::from_micros(90));
+        assert_eq!(summary.p99, Duration::from_micros(99));
+        assert_eq!(summary.max, Duration::from_micros(100));
+    }
+}
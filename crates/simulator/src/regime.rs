@@ -0,0 +1,229 @@
+//! Market regimes
+//!
+//! Agents change how they behave with the regime: quiet in `Calm`, placing
+//! bursts of orders in `Burst` and pulling orders in `CancelStorm`. A
+//! schedule fixes which regime holds at each tick, either written by hand
+//! or drawn by a `RegimeScheduler` from a seeded Markov chain.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::SimError;
+use crate::rng::Rng;
+use crate::types::Tick;
+
+/// Stream the scheduler forks off a run's seed. Agent streams are forked
+/// by agent id, which never reaches this high.
+const REGIME_STREAM: u64 = 1 << 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Regime {
+    Calm,
+    Burst,
+    CancelStorm,
+}
+
+impl Regime {
+    /// Every regime, in the order transition matrices index them
+    pub const ALL: [Regime; 3] = [Regime::Calm, Regime::Burst, Regime::CancelStorm];
+
+    fn index(self) -> usize {
+        match self {
+            Regime::Calm => 0,
+            Regime::Burst => 1,
+            Regime::CancelStorm => 2,
+        }
+    }
+}
+
+/// Regime changes by tick. Before the first change the regime is `Calm`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegimeSchedule {
+    /// Start tick and regime, in tick order
+    changes: Vec<(Tick, Regime)>,
+}
+
+impl RegimeSchedule {
+    /// Calm for the whole run
+    pub fn calm() -> Self {
+        Self::default()
+    }
+
+    /// Switch to `regime` from tick `start`. A later change at the same tick
+    /// replaces an earlier one.
+    pub fn then_at(mut self, start: Tick, regime: Regime) -> Self {
+        self.changes.retain(|(tick, _)| *tick != start);
+        let position = self.changes.partition_point(|(tick, _)| *tick < start);
+        self.changes.insert(position, (start, regime));
+        self
+    }
+
+    /// Calm, then a burst, then a cancel storm, then calm again, each
+    /// phase lasting `phase` ticks
+    pub fn cycle(phase: Tick) -> Self {
+        Self::calm()
+            .then_at(phase, Regime::Burst)
+            .then_at(phase * 2, Regime::CancelStorm)
+            .then_at(phase * 3, Regime::Calm)
+    }
+
+    pub fn regime_at(&self, tick: Tick) -> Regime {
+        let position = self.changes.partition_point(|(start, _)| *start <= tick);
+        match position {
+            0 => Regime::Calm,
+            n => self.changes[n - 1].1,
+        }
+    }
+}
+
+/// Draws a regime schedule from a Markov chain. The run starts `Calm` and,
+/// at the start of every window of `window` ticks, moves to the next regime
+/// with odds given by the current regime's row of `weights`. Rows and
+/// columns are in [`Regime::ALL`] order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegimeScheduler {
+    window: Tick,
+    weights: [[u32; 3]; 3],
+}
+
+impl RegimeScheduler {
+    pub fn new(window: Tick, weights: [[u32; 3]; 3]) -> Result<Self, SimError> {
+        if window == 0 {
+            return Err(SimError::Config("regime window must be at least one tick".to_string()));
+        }
+        for (regime, row) in Regime::ALL.iter().zip(&weights) {
+            if row.iter().all(|weight| *weight == 0) {
+                return Err(SimError::Config(format!("{:?} has no transitions", regime)));
+            }
+        }
+        Ok(Self { window, weights })
+    }
+
+    /// Mostly calm, with bursts and cancel storms that tend to last a few
+    /// windows before things settle
+    pub fn sticky(window: Tick) -> Result<Self, SimError> {
+        Self::new(window, [[8, 1, 1], [3, 6, 1], [4, 1, 5]])
+    }
+
+    pub fn window(&self) -> Tick {
+        self.window
+    }
+
+    fn next(&self, from: Regime, rng: &mut Rng) -> Regime {
+        let row = &self.weights[from.index()];
+        let total: u32 = row.iter().sum();
+        if total == 0 {
+            // Only reachable through a deserialized scheduler
+            return from;
+        }
+        let mut draw = rng.next_below(total);
+        for (regime, weight) in Regime::ALL.iter().zip(row) {
+            if draw < *weight {
+                return *regime;
+            }
+            draw -= weight;
+        }
+        from
+    }
+
+    /// The regime of each of the first `windows` windows
+    pub fn sequence(&self, seed: u64, windows: usize) -> Vec<Regime> {
+        let mut rng = Rng::new(seed).fork(REGIME_STREAM);
+        let mut regime = Regime::Calm;
+        let mut sequence = Vec::with_capacity(windows);
+        for window in 0..windows {
+            if window > 0 {
+                regime = self.next(regime, &mut rng);
+            }
+            sequence.push(regime);
+        }
+        sequence
+    }
+
+    /// The schedule for a run of `ticks` ticks from `seed`
+    pub fn schedule(&self, seed: u64, ticks: Tick) -> RegimeSchedule {
+        let windows = ticks.div_ceil(self.window) as usize;
+        let mut schedule = RegimeSchedule::calm();
+        let mut current = Regime::Calm;
+        for (window, regime) in self.sequence(seed, windows).into_iter().enumerate() {
+            if regime != current {
+                schedule = schedule.then_at(window as Tick * self.window, regime);
+                current = regime;
+            }
+        }
+        schedule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regime_at() {
+        let schedule = RegimeSchedule::cycle(10);
+        assert_eq!(schedule.regime_at(0), Regime::Calm);
+        assert_eq!(schedule.regime_at(10), Regime::Burst);
+        assert_eq!(schedule.regime_at(19), Regime::Burst);
+        assert_eq!(schedule.regime_at(25), Regime::CancelStorm);
+        assert_eq!(schedule.regime_at(1000), Regime::Calm);
+    }
+
+    #[test]
+    fn test_changes_keep_tick_order() {
+        let schedule = RegimeSchedule::calm()
+            .then_at(20, Regime::CancelStorm)
+            .then_at(5, Regime::Burst)
+            .then_at(20, Regime::Burst);
+        assert_eq!(schedule.regime_at(4), Regime::Calm);
+        assert_eq!(schedule.regime_at(5), Regime::Burst);
+        assert_eq!(schedule.regime_at(30), Regime::Burst);
+    }
+
+    fn letters(sequence: &[Regime]) -> String {
+        sequence
+            .iter()
+            .map(|regime| match regime {
+                Regime::Calm => 'c',
+                Regime::Burst => 'b',
+                Regime::CancelStorm => 's',
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_golden_regime_sequence() {
+        // Moves with the RNG or the sampling; update only on purpose
+        let scheduler = RegimeScheduler::sticky(10).unwrap();
+        assert_eq!(letters(&scheduler.sequence(42, 40)), "ccssssscsssscccccsccccccsccsssscbbccccbs");
+        assert_eq!(letters(&scheduler.sequence(7, 40)), "cscccccccbbbbbbbbbccbbbscssssscbbscssssc");
+    }
+
+    #[test]
+    fn test_schedule_follows_the_sequence() {
+        let scheduler = RegimeScheduler::sticky(5).unwrap();
+        let sequence = scheduler.sequence(3, 20);
+        let schedule = scheduler.schedule(3, 100);
+        for tick in 0..100 {
+            assert_eq!(schedule.regime_at(tick), sequence[(tick / 5) as usize], "tick {}", tick);
+        }
+        assert_eq!(scheduler.schedule(3, 98), schedule);
+        assert_eq!(scheduler.schedule(3, 100), schedule);
+    }
+
+    #[test]
+    fn test_forced_transitions() {
+        // Calm always bursts, a burst always storms, a storm always calms
+        let cycle = RegimeScheduler::new(4, [[0, 1, 0], [0, 0, 1], [1, 0, 0]]).unwrap();
+        assert_eq!(letters(&cycle.sequence(99, 7)), "cbscbsc");
+        assert_eq!(cycle.schedule(99, 13), RegimeSchedule::cycle(4));
+
+        let frozen = RegimeScheduler::new(4, [[1, 0, 0], [0, 1, 0], [0, 0, 1]]).unwrap();
+        assert_eq!(frozen.schedule(5, 1000), RegimeSchedule::calm());
+    }
+
+    #[test]
+    fn test_rejects_bad_matrices() {
+        assert!(matches!(RegimeScheduler::new(0, [[1; 3]; 3]), Err(SimError::Config(_))));
+        assert!(matches!(RegimeScheduler::new(5, [[1, 0, 0], [0, 0, 0], [0, 0, 1]]), Err(SimError::Config(_))));
+    }
+}
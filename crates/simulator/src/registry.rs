@@ -0,0 +1,155 @@
+//! Agent registry
+//!
+//! Holds the agents of a run, each with its own RNG stream forked from the
+//! run's seed by agent id, and steps them in ascending id order whatever
+//! order they were registered in.
+
+use std::collections::BTreeMap;
+
+use crate::agent::Agent;
+use crate::error::SimError;
+use crate::rng::Rng;
+use crate::types::{AgentId, Tick};
+
+struct Entry {
+    agent: Box<dyn Agent>,
+    rng: Rng,
+}
+
+pub struct AgentRegistry {
+    /// Never drawn from, only forked
+    root: Rng,
+    entries: BTreeMap<AgentId, Entry>,
+}
+
+impl AgentRegistry {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            root: Rng::new(seed),
+            entries: BTreeMap::new(),
+        }
+    }
+
+    pub fn register(&mut self, agent: Box<dyn Agent>) -> Result<(), SimError> {
+        let id = agent.id();
+        if self.entries.contains_key(&id) {
+            return Err(SimError::DuplicateAgent(id));
+        }
+        let rng = self.root.fork(u64::from(id));
+        self.entries.insert(id, Entry { agent, rng });
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn ids(&self) -> Vec<AgentId> {
+        self.entries.keys().copied().collect()
+    }
+
+    /// Ids of the agents that act at `tick`, in stepping order
+    pub fn due(&self, tick: Tick) -> Vec<AgentId> {
+        self.entries
+            .iter()
+            .filter(|(_, entry)| tick.is_multiple_of(entry.agent.interval().max(1)))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// An agent's RNG stream as it stands, e.g. to save it mid-run
+    pub fn rng(&self, id: AgentId) -> Option<&Rng> {
+        self.entries.get(&id).map(|entry| &entry.rng)
+    }
+
+    /// Put back a stream saved with [`AgentRegistry::rng`]
+    pub fn restore_rng(&mut self, id: AgentId, rng: Rng) -> Result<(), SimError> {
+        let entry = self.entries.get_mut(&id).ok_or(SimError::UnknownAgent(id))?;
+        entry.rng = rng;
+        Ok(())
+    }
+
+    /// An agent with its RNG stream, to step it
+    pub(crate) fn get_mut(&mut self, id: AgentId) -> Option<(&mut Box<dyn Agent>, &mut Rng)> {
+        self.entries.get_mut(&id).map(|entry| (&mut entry.agent, &mut entry.rng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::{Action, Ctx};
+
+    struct Every {
+        id: AgentId,
+        interval: Tick,
+    }
+
+    impl Agent for Every {
+        fn id(&self) -> AgentId {
+            self.id
+        }
+
+        fn interval(&self) -> Tick {
+            self.interval
+        }
+
+        fn step(&mut self, _ctx: &Ctx, _rng: &mut Rng) -> Vec<Action> {
+            Vec::new()
+        }
+    }
+
+    #[test]
+    fn test_due_agents_in_id_order() {
+        let mut registry = AgentRegistry::new(1);
+        registry.register(Box::new(Every { id: 3, interval: 2 })).unwrap();
+        registry.register(Box::new(Every { id: 1, interval: 1 })).unwrap();
+        registry.register(Box::new(Every { id: 2, interval: 3 })).unwrap();
+
+        assert_eq!(registry.due(0), vec![1, 2, 3]);
+        assert_eq!(registry.due(1), vec![1]);
+        assert_eq!(registry.due(4), vec![1, 3]);
+        assert_eq!(registry.due(6), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_rejects_duplicate_ids() {
+        let mut registry = AgentRegistry::new(1);
+        registry.register(Box::new(Every { id: 1, interval: 1 })).unwrap();
+        let duplicate = registry.register(Box::new(Every { id: 1, interval: 5 }));
+        assert_eq!(duplicate, Err(SimError::DuplicateAgent(1)));
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[test]
+    fn test_streams_depend_only_on_seed_and_id() {
+        let mut alone = AgentRegistry::new(9);
+        alone.register(Box::new(Every { id: 1, interval: 1 })).unwrap();
+        let mut crowded = AgentRegistry::new(9);
+        crowded.register(Box::new(Every { id: 2, interval: 1 })).unwrap();
+        crowded.register(Box::new(Every { id: 1, interval: 1 })).unwrap();
+
+        assert_eq!(alone.rng(1), crowded.rng(1));
+        assert_ne!(crowded.rng(1), crowded.rng(2));
+        assert_eq!(alone.rng(2), None);
+    }
+
+    #[test]
+    fn test_restores_a_saved_stream() {
+        let mut registry = AgentRegistry::new(9);
+        registry.register(Box::new(Every { id: 1, interval: 1 })).unwrap();
+        let saved = registry.rng(1).cloned().unwrap();
+
+        let (_, rng) = registry.get_mut(1).unwrap();
+        let first = rng.next_u64();
+        registry.restore_rng(1, saved).unwrap();
+        let (_, rng) = registry.get_mut(1).unwrap();
+        assert_eq!(rng.next_u64(), first);
+
+        assert_eq!(registry.restore_rng(5, Rng::new(0)), Err(SimError::UnknownAgent(5)));
+    }
+}
@@ -0,0 +1,261 @@
+//! Replay logs
+//!
+//! A run is logged as JSON lines: a header with the format version and the
+//! run's configuration, then one line per tick with that tick's events,
+//! empty ticks included. Replaying a log rebuilds the book from the events
+//! alone, without the agents, and checks every fill the book produces
+//! against the logged ones, so a log that no longer matches the engine is
+//! reported rather than silently replayed.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::book::{Fill, OrderBook};
+use crate::config::SimConfig;
+use crate::engine::{SimState, Simulation};
+use crate::error::SimError;
+use crate::event::{Event, EventKind};
+use crate::metrics::Metrics;
+use crate::types::Tick;
+
+/// Bumped whenever the log format changes incompatibly
+pub const REPLAY_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayHeader {
+    pub version: u32,
+    pub config: SimConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickRecord {
+    pub tick: Tick,
+    pub events: Vec<Event>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayLog {
+    pub header: ReplayHeader,
+    pub ticks: Vec<TickRecord>,
+}
+
+impl ReplayLog {
+    /// Log a simulation run so far, grouping its trace by tick
+    pub fn capture(config: SimConfig, sim: &Simulation) -> Self {
+        let mut ticks: Vec<TickRecord> = (0..sim.tick()).map(|tick| TickRecord { tick, events: Vec::new() }).collect();
+        for event in sim.trace() {
+            if let Some(record) = ticks.get_mut(event.tick as usize) {
+                record.events.push(event.clone());
+            }
+        }
+        Self {
+            header: ReplayHeader { version: REPLAY_VERSION, config },
+            ticks,
+        }
+    }
+
+    /// Run a configuration and log it
+    pub fn record(config: &SimConfig) -> Result<Self, SimError> {
+        let sim = config.run()?;
+        Ok(Self::capture(config.clone(), &sim))
+    }
+
+    pub fn to_jsonl(&self) -> Result<String, SimError> {
+        let mut out = to_line(&self.header)?;
+        for record in &self.ticks {
+            out.push_str(&to_line(record)?);
+        }
+        Ok(out)
+    }
+
+    pub fn from_jsonl(jsonl: &str) -> Result<Self, SimError> {
+        let mut lines = jsonl.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+        let (_, first) = lines.next().ok_or_else(|| SimError::Log("log is empty".to_string()))?;
+        let header: ReplayHeader = from_line(1, first)?;
+        if header.version != REPLAY_VERSION {
+            return Err(SimError::Log(format!(
+                "log is version {}, expected {}",
+                header.version, REPLAY_VERSION
+            )));
+        }
+
+        let ticks = lines
+            .map(|(idx, line)| from_line(idx + 1, line))
+            .collect::<Result<Vec<TickRecord>, _>>()?;
+        Ok(Self { header, ticks })
+    }
+}
+
+fn to_line<T: Serialize>(value: &T) -> Result<String, SimError> {
+    let mut line = serde_json::to_string(value).map_err(|e| SimError::Log(e.to_string()))?;
+    line.push('\n');
+    Ok(line)
+}
+
+fn from_line<T: for<'de> Deserialize<'de>>(line_number: usize, line: &str) -> Result<T, SimError> {
+    serde_json::from_str(line).map_err(|e| SimError::Log(format!("line {}: {}", line_number, e)))
+}
+
+fn diverged(event: &Event, message: impl std::fmt::Display) -> SimError {
+    SimError::Replay(format!("event {} at tick {}: {}", event.seq, event.tick, message))
+}
+
+/// Rebuild the end state of a logged run from its events
+pub fn replay(log: &ReplayLog) -> Result<SimState, SimError> {
+    let mut book = OrderBook::new();
+    let mut metrics = Metrics::default();
+    let mut next_order_id = 1;
+    let mut seq = 0;
+
+    for (expected_tick, record) in log.ticks.iter().enumerate() {
+        if record.tick != expected_tick as Tick {
+            return Err(SimError::Replay(format!("expected tick {}, found tick {}", expected_tick, record.tick)));
+        }
+
+        // Fills the book produced that the log has yet to show
+        let mut pending: VecDeque<Fill> = VecDeque::new();
+        for event in &record.events {
+            if event.seq != seq || event.tick != record.tick {
+                return Err(diverged(event, "out of sequence"));
+            }
+            seq += 1;
+
+            match &event.kind {
+                EventKind::Fill(fill) => {
+                    if pending.pop_front().as_ref() != Some(fill) {
+                        return Err(diverged(event, "fill doesn't match the book"));
+                    }
+                }
+                _ if !pending.is_empty() => return Err(diverged(event, "fills are missing")),
+                EventKind::NewOrder(order) => {
+                    if order.id != next_order_id {
+                        return Err(diverged(event, format!("expected order id {}", next_order_id)));
+                    }
+                    pending = book.submit(*order).map_err(|e| diverged(event, e))?.into();
+                    next_order_id += 1;
+                }
+                EventKind::Cancel { agent, order } => {
+                    let cancelled = book.cancel(*order).map_err(|e| diverged(event, e))?;
+                    if cancelled.agent != *agent {
+                        return Err(diverged(event, format!("order {} belongs to agent {}", order, cancelled.agent)));
+                    }
+                }
+                EventKind::Rejected { .. } => {}
+            }
+            metrics.count(&event.kind);
+        }
+        if !pending.is_empty() {
+            return Err(SimError::Replay(format!("tick {} is missing fills", record.tick)));
+        }
+        metrics.ticks += 1;
+    }
+
+    Ok(SimState {
+        tick: log.ticks.len() as Tick,
+        next_order_id,
+        book: book.snapshot(),
+        metrics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AgentSpec;
+    use crate::regime::RegimeSchedule;
+    use std::path::PathBuf;
+
+    fn config(seed: u64, ticks: Tick) -> SimConfig {
+        SimConfig {
+            seed,
+            ticks,
+            schedule: RegimeSchedule::cycle(20),
+            regimes: None,
+            agents: vec![
+                AgentSpec::NoiseTrader { id: 1, reference: 100, spread: 3 },
+                AgentSpec::CancelBot { id: 2, reference: 100 },
+                AgentSpec::NoiseTrader { id: 3, reference: 100, spread: 5 },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_replay_reproduces_the_end_state() {
+        let config = config(42, 120);
+        let sim = config.run().unwrap();
+        let log = ReplayLog::capture(config, &sim);
+
+        let parsed = ReplayLog::from_jsonl(&log.to_jsonl().unwrap()).unwrap();
+        assert_eq!(parsed, log);
+        assert_eq!(parsed.ticks.len(), 120);
+
+        let state = replay(&parsed).unwrap();
+        assert_eq!(state, sim.state());
+        assert_eq!(serde_json::to_string(&state).unwrap(), serde_json::to_string(&sim.state()).unwrap());
+    }
+
+    #[test]
+    fn test_replay_detects_tampering() {
+        let log = ReplayLog::record(&config(7, 60)).unwrap();
+
+        let mut dropped = log.clone();
+        let record = dropped.ticks.iter_mut().find(|r| r.events.iter().any(|e| matches!(e.kind, EventKind::Fill(_))));
+        let record = record.expect("run has fills");
+        let position = record.events.iter().position(|e| matches!(e.kind, EventKind::Fill(_))).unwrap();
+        record.events.remove(position);
+        assert!(matches!(replay(&dropped), Err(SimError::Replay(_))));
+
+        let mut reordered = log.clone();
+        reordered.ticks.swap(0, 1);
+        assert!(matches!(replay(&reordered), Err(SimError::Replay(_))));
+    }
+
+    #[test]
+    fn test_rejects_other_versions_and_garbage() {
+        let mut log = ReplayLog::record(&config(1, 2)).unwrap();
+        log.header.version = REPLAY_VERSION + 1;
+        assert!(matches!(ReplayLog::from_jsonl(&log.to_jsonl().unwrap()), Err(SimError::Log(_))));
+        assert!(matches!(ReplayLog::from_jsonl(""), Err(SimError::Log(_))));
+        assert!(matches!(ReplayLog::from_jsonl("{}\n"), Err(SimError::Log(_))));
+    }
+
+    fn corpus_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata").join("replays")
+    }
+
+    /// Every committed log must still be what its configuration produces,
+    /// and must replay to its committed end state. Set `UPDATE_REPLAYS=1`
+    /// to rewrite the corpus after an intended change to the engine.
+    #[test]
+    fn test_golden_corpus() {
+        let update = std::env::var_os("UPDATE_REPLAYS").is_some();
+        let mut checked = 0;
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(corpus_dir())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+            .collect();
+        entries.sort();
+
+        for path in entries {
+            let state_path = path.with_extension("state.json");
+            let committed = std::fs::read_to_string(&path).unwrap();
+            let log = ReplayLog::from_jsonl(&committed).unwrap();
+            let rerun = ReplayLog::record(&log.header.config).unwrap();
+            let state = serde_json::to_string_pretty(&replay(&rerun).unwrap()).unwrap() + "\n";
+            let rerun = rerun.to_jsonl().unwrap();
+
+            if update {
+                std::fs::write(&path, &rerun).unwrap();
+                std::fs::write(&state_path, &state).unwrap();
+            } else {
+                assert_eq!(rerun, committed, "{} no longer matches its configuration", path.display());
+                assert_eq!(replay(&log).unwrap(), replay(&ReplayLog::from_jsonl(&rerun).unwrap()).unwrap());
+                let expected = std::fs::read_to_string(&state_path).unwrap();
+                assert_eq!(state, expected, "{} replays to a different state", path.display());
+            }
+            checked += 1;
+        }
+        assert!(checked >= 2, "replay corpus is missing");
+    }
+}
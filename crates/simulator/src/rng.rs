@@ -0,0 +1,119 @@
+//! Seeded random number streams
+//!
+//! `Rng` is SplitMix64: one `u64` of state, every output bit well mixed,
+//! and cheap to fork. The challenges' LCG had weak low bits, which made
+//! `next_u32() % n` and `next_bool()` visibly patterned. Each agent draws
+//! from a stream forked off the run's root by its id, so adding or removing
+//! one agent never shifts the numbers another draws. The state serializes,
+//! so a stream can be saved mid-run and resumed exactly.
+
+use serde::{Deserialize, Serialize};
+
+/// SplitMix64's increment, the golden ratio in 64-bit fixed point
+const GOLDEN_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// SplitMix64's output function
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        mix(self.state)
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    pub fn next_bool(&mut self) -> bool {
+        self.next_u64() >> 63 == 1
+    }
+
+    /// A number in `0..bound`; zero when `bound` is zero. Scales rather
+    /// than taking a remainder, so every bit of the draw counts.
+    pub fn next_below(&mut self, bound: u32) -> u32 {
+        ((u64::from(self.next_u32()) * u64::from(bound)) >> 32) as u32
+    }
+
+    /// A uniformly chosen item, or `None` from an empty slice
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        let bound = u32::try_from(items.len()).unwrap_or(u32::MAX);
+        if bound == 0 {
+            return None;
+        }
+        items.get(self.next_below(bound) as usize)
+    }
+
+    /// An independent stream derived from this one's current state and
+    /// `stream_id`. Forking doesn't advance this stream, so the same parent
+    /// state and id always give the same child.
+    pub fn fork(&self, stream_id: u64) -> Rng {
+        Rng::new(mix(self.state ^ mix(stream_id.wrapping_add(1).wrapping_mul(GOLDEN_GAMMA))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_the_splitmix64_reference_values() {
+        let mut rng = Rng::new(0);
+        assert_eq!(rng.next_u64(), 0xe220_a839_7b1d_cdaf);
+        assert_eq!(rng.next_u64(), 0x6e78_9e6a_a1b9_65f4);
+        assert_eq!(rng.next_u64(), 0x06c4_5d18_8009_454f);
+    }
+
+    #[test]
+    fn test_forks_are_reproducible_and_independent() {
+        let root = Rng::new(7);
+        let draw = |mut rng: Rng| -> Vec<u64> { (0..5).map(|_| rng.next_u64()).collect() };
+
+        assert_eq!(draw(root.fork(1)), draw(root.fork(1)));
+        assert_ne!(draw(root.fork(1)), draw(root.fork(2)));
+        assert_ne!(draw(root.fork(1)), draw(Rng::new(8).fork(1)));
+        assert_ne!(draw(root.fork(1)), draw(root.clone()));
+        // Forking leaves the parent where it was
+        assert_eq!(root, Rng::new(7));
+    }
+
+    #[test]
+    fn test_state_survives_serialization_mid_stream() {
+        let mut rng = Rng::new(42);
+        for _ in 0..10 {
+            rng.next_u64();
+        }
+        let saved = serde_json::to_string(&rng).unwrap();
+        let mut resumed: Rng = serde_json::from_str(&saved).unwrap();
+
+        let expected: Vec<u64> = (0..5).map(|_| rng.next_u64()).collect();
+        let got: Vec<u64> = (0..5).map(|_| resumed.next_u64()).collect();
+        assert_eq!(got, expected);
+    }
+
+    #[test]
+    fn test_bounded_draws() {
+        let mut rng = Rng::new(3);
+        assert!((0..100).all(|_| rng.next_below(4) < 4));
+        assert_eq!(rng.next_below(0), 0);
+        assert_eq!(rng.pick::<u32>(&[]), None);
+        assert!([5, 6, 7].contains(rng.pick(&[5, 6, 7]).unwrap()));
+
+        // Low bits are as good as high ones: even and odd draws split evenly
+        let odd = (0..10_000).filter(|_| rng.next_u32() & 1 == 1).count();
+        assert!((4_500..5_500).contains(&odd), "odd={odd}");
+    }
+}
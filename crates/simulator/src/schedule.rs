@@ -0,0 +1,67 @@
+//! Tick schedules
+//!
+//! The actions agents proposed in one tick, in the order the engine
+//! applied them, and a stable text fingerprint of them for golden tests.
+
+use crate::agent::Action;
+use crate::types::AgentId;
+
+/// `a{id}:{action:?}` for each action, joined with commas, e.g.
+/// `a1:Cancel(6),a2:Place { side: Buy, price: 100, qty: 1 }`. Nothing in it
+/// depends on addresses, hashing or the clock, so equal schedules always
+/// give equal fingerprints.
+pub fn fingerprint(actions: &[(AgentId, Action)]) -> String {
+    actions
+        .iter()
+        .map(|(agent, action)| format!("a{}:{:?}", agent, action))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Side;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_golden_fingerprint() {
+        let actions = vec![
+            (1, Action::Place { side: Side::Buy, price: 100, qty: 3 }),
+            (2, Action::Cancel(6)),
+        ];
+        assert_eq!(fingerprint(&actions), "a1:Place { side: Buy, price: 100, qty: 3 },a2:Cancel(6)");
+    }
+
+    #[test]
+    fn test_empty_schedule() {
+        assert_eq!(fingerprint(&[]), "");
+    }
+
+    fn action() -> impl Strategy<Value = Action> {
+        prop_oneof![
+            (any::<bool>(), any::<u64>(), any::<u64>()).prop_map(|(buy, price, qty)| Action::Place {
+                side: if buy { Side::Buy } else { Side::Sell },
+                price,
+                qty,
+            }),
+            any::<u64>().prop_map(Action::Cancel),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn fingerprint_is_stable(actions in prop::collection::vec((any::<u32>(), action()), 0..20)) {
+            let copy = actions.clone();
+            prop_assert_eq!(fingerprint(&actions), fingerprint(&copy));
+        }
+
+        #[test]
+        fn fingerprint_tells_schedules_apart(
+            a in prop::collection::vec((any::<u32>(), action()), 0..8),
+            b in prop::collection::vec((any::<u32>(), action()), 0..8),
+        ) {
+            prop_assert_eq!(fingerprint(&a) == fingerprint(&b), a == b);
+        }
+    }
+}
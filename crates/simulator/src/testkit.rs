@@ -0,0 +1,264 @@
+//! Property testing kit
+//!
+//! Generators and invariant checks for challenges that ask for property
+//! tests, behind the `testkit` feature. The generators cover seeds, agent
+//! line-ups, whole run configurations and event sequences; the checks
+//! return a `SimError::Invariant` naming the first event that breaks a
+//! rule, so they work both in `prop_assert!` and with `?`.
+//!
+//! A challenge's property test then reads:
+//!
+//! ```text
+//! proptest! {
+//!     #[test]
+//!     fn traces_are_consistent(trace in testkit::traces()) {
+//!         prop_assert_eq!(testkit::check_trace(&trace), Ok(()));
+//!     }
+//! }
+//! ```
+
+use proptest::prelude::*;
+use std::collections::BTreeMap;
+
+use crate::config::{AgentSpec, SimConfig};
+use crate::engine::Simulation;
+use crate::error::SimError;
+use crate::event::{Event, EventKind};
+use crate::regime::RegimeSchedule;
+use crate::types::{AgentId, Order, OrderId, Price, Qty, Side, Tick};
+
+/// Any seed at all
+pub fn seeds() -> impl Strategy<Value = u64> {
+    any::<u64>()
+}
+
+pub fn sides() -> impl Strategy<Value = Side> {
+    prop_oneof![Just(Side::Buy), Just(Side::Sell)]
+}
+
+/// Prices an agent's orders may take around `reference`, `spread` either way
+pub fn prices(reference: Price, spread: Price) -> impl Strategy<Value = Price> {
+    reference.saturating_sub(spread)..=reference.saturating_add(spread)
+}
+
+/// A stock agent around a reference price between 50 and 150, id unset
+fn agent_kinds() -> impl Strategy<Value = AgentSpec> {
+    prop_oneof![
+        (50..150u64, 1..6u32).prop_map(|(reference, spread)| AgentSpec::NoiseTrader { id: 0, reference, spread }),
+        (50..150u64).prop_map(|reference| AgentSpec::CancelBot { id: 0, reference }),
+    ]
+}
+
+/// One to `max` stock agents with ids 1, 2, 3 and so on
+pub fn agent_specs(max: usize) -> impl Strategy<Value = Vec<AgentSpec>> {
+    prop::collection::vec(agent_kinds(), 1..=max.max(1)).prop_map(|specs| {
+        specs
+            .into_iter()
+            .zip(1..)
+            .map(|(spec, id)| match spec {
+                AgentSpec::NoiseTrader { reference, spread, .. } => AgentSpec::NoiseTrader { id, reference, spread },
+                AgentSpec::CancelBot { reference, .. } => AgentSpec::CancelBot { id, reference },
+            })
+            .collect()
+    })
+}
+
+/// Calm throughout, or the calm/burst/storm cycle with a random phase
+pub fn schedules() -> impl Strategy<Value = RegimeSchedule> {
+    prop_oneof![Just(RegimeSchedule::calm()), (1..40u64).prop_map(RegimeSchedule::cycle)]
+}
+
+/// A run of up to `max_ticks` ticks with up to four stock agents
+pub fn sim_configs(max_ticks: Tick) -> impl Strategy<Value = SimConfig> {
+    (seeds(), 1..=max_ticks.max(1), schedules(), agent_specs(4)).prop_map(|(seed, ticks, schedule, agents)| {
+        SimConfig { seed, ticks, schedule, regimes: None, agents }
+    })
+}
+
+/// Traces of real runs, so every one of them is consistent
+pub fn traces() -> impl Strategy<Value = Vec<Event>> {
+    sim_configs(80).prop_map(|config| match config.run() {
+        Ok(sim) => sim.trace().to_vec(),
+        Err(_) => Vec::new(),
+    })
+}
+
+/// One event's kind with ids, agents and prices drawn from small ranges,
+/// so cancels and fills often name orders that never existed
+fn arbitrary_kinds() -> impl Strategy<Value = EventKind> {
+    let order = (1..20u64, 1..4u32, sides(), 95..105u64, 1..6u64)
+        .prop_map(|(id, agent, side, price, qty)| Order::new(id, agent, side, price, qty));
+    prop_oneof![
+        4 => order.prop_map(EventKind::NewOrder),
+        2 => (1..4u32, 1..20u64).prop_map(|(agent, order)| EventKind::Cancel { agent, order }),
+        1 => (1..4u32, "[a-z ]{1,20}").prop_map(|(agent, reason)| EventKind::Rejected { agent, reason }),
+    ]
+}
+
+/// Sequences of up to `max_len` events, correctly numbered and ticked but
+/// otherwise unchecked: the input to test that a consumer rejects what it
+/// should
+pub fn arbitrary_events(max_len: usize) -> impl Strategy<Value = Vec<Event>> {
+    prop::collection::vec((arbitrary_kinds(), 0..3u64), 0..=max_len).prop_map(|kinds| {
+        let mut tick = 0;
+        kinds
+            .into_iter()
+            .zip(0..)
+            .map(|((kind, advance), seq)| {
+                tick += advance;
+                Event { seq, tick, kind }
+            })
+            .collect()
+    })
+}
+
+fn broken(event: &Event, message: impl std::fmt::Display) -> SimError {
+    SimError::Invariant(format!("event {} at tick {}: {}", event.seq, event.tick, message))
+}
+
+/// Take a fill out of an open order, which must have that much left
+fn take(open: &mut BTreeMap<OrderId, (AgentId, Qty)>, event: &Event, id: OrderId, qty: Qty) -> Result<(), SimError> {
+    let (_, left) = open
+        .get_mut(&id)
+        .ok_or_else(|| broken(event, format!("fill of order {} which isn't open", id)))?;
+    *left = left
+        .checked_sub(qty)
+        .ok_or_else(|| broken(event, format!("fill of {} leaves order {} negative", qty, id)))?;
+    if *left == 0 {
+        open.remove(&id);
+    }
+    Ok(())
+}
+
+/// Replay a trace's bookkeeping and return the orders still open, with
+/// their owner and remaining quantity. Fails on the first event that
+/// numbers or ticks out of order, reuses an order id, places an empty
+/// order, cancels an order that isn't open or isn't the agent's, or fills
+/// more than an order has left.
+pub fn open_orders(events: &[Event]) -> Result<BTreeMap<OrderId, (AgentId, Qty)>, SimError> {
+    let mut open: BTreeMap<OrderId, (AgentId, Qty)> = BTreeMap::new();
+    let mut last_id = 0;
+    let mut last_tick = 0;
+
+    for (seq, event) in events.iter().enumerate() {
+        if event.seq != seq as u64 {
+            return Err(broken(event, format!("expected seq {}", seq)));
+        }
+        if event.tick < last_tick {
+            return Err(broken(event, format!("goes back from tick {}", last_tick)));
+        }
+        last_tick = event.tick;
+
+        match &event.kind {
+            EventKind::NewOrder(order) => {
+                if order.id <= last_id {
+                    return Err(broken(event, format!("order id {} isn't above {}", order.id, last_id)));
+                }
+                if order.qty == 0 {
+                    return Err(broken(event, format!("order {} has zero quantity", order.id)));
+                }
+                last_id = order.id;
+                open.insert(order.id, (order.agent, order.qty));
+            }
+            EventKind::Cancel { agent, order } => match open.remove(order) {
+                Some((owner, _)) if owner == *agent => {}
+                Some((owner, _)) => {
+                    return Err(broken(event, format!("agent {} cancels order {} of agent {}", agent, order, owner)));
+                }
+                None => return Err(broken(event, format!("cancel of order {} which isn't open", order))),
+            },
+            EventKind::Fill(fill) => {
+                take(&mut open, event, fill.maker_order, fill.qty)?;
+                take(&mut open, event, fill.taker_order, fill.qty)?;
+            }
+            EventKind::Rejected { .. } => {}
+        }
+    }
+    Ok(open)
+}
+
+/// Check a trace's bookkeeping; see [`open_orders`] for the rules
+pub fn check_trace(events: &[Event]) -> Result<(), SimError> {
+    open_orders(events).map(|_| ())
+}
+
+/// Check a simulation: its book's own invariants, its trace's, and that
+/// the orders resting in the book are exactly those the trace leaves open
+pub fn check_sim(sim: &Simulation) -> Result<(), SimError> {
+    sim.book().check_invariants()?;
+    let open = open_orders(sim.trace())?;
+
+    let snapshot = sim.book().snapshot();
+    let mut resting: BTreeMap<OrderId, (AgentId, Qty)> = BTreeMap::new();
+    for order in snapshot.bids.iter().chain(&snapshot.asks) {
+        resting.insert(order.id, (order.agent, order.qty));
+    }
+    if resting != open {
+        return Err(SimError::Invariant(format!(
+            "book holds {} orders, trace leaves {} open",
+            resting.len(),
+            open.len()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn real_runs_pass_every_check(config in sim_configs(60)) {
+            let sim = config.run().unwrap();
+            prop_assert_eq!(check_sim(&sim), Ok(()));
+        }
+
+        #[test]
+        fn agent_ids_are_unique(specs in agent_specs(6)) {
+            let ids: Vec<AgentId> = specs.iter().map(|spec| match spec {
+                AgentSpec::NoiseTrader { id, .. } | AgentSpec::CancelBot { id, .. } => *id,
+            }).collect();
+            prop_assert_eq!(ids, (1..=specs.len() as AgentId).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn arbitrary_events_are_numbered(events in arbitrary_events(30)) {
+            for (seq, event) in events.iter().enumerate() {
+                prop_assert_eq!(event.seq, seq as u64);
+            }
+            // Checking never panics, whatever it's given
+            let _ = check_trace(&events);
+        }
+
+        #[test]
+        fn prices_stay_in_range(price in prices(3, 5)) {
+            prop_assert!(price <= 8);
+        }
+    }
+
+    fn event(seq: u64, kind: EventKind) -> Event {
+        Event { seq, tick: 0, kind }
+    }
+
+    #[test]
+    fn test_catches_broken_traces() {
+        let place = |seq, id, agent, qty| event(seq, EventKind::NewOrder(Order::new(id, agent, Side::Buy, 100, qty)));
+
+        let unknown = vec![place(0, 1, 1, 2), event(1, EventKind::Cancel { agent: 1, order: 2 })];
+        assert!(matches!(check_trace(&unknown), Err(SimError::Invariant(_))));
+
+        let not_theirs = vec![place(0, 1, 1, 2), event(1, EventKind::Cancel { agent: 2, order: 1 })];
+        assert!(matches!(check_trace(&not_theirs), Err(SimError::Invariant(_))));
+
+        let reused = vec![place(0, 1, 1, 2), place(1, 1, 1, 2)];
+        assert!(matches!(check_trace(&reused), Err(SimError::Invariant(_))));
+
+        let fill = crate::book::Fill { maker_order: 1, maker_agent: 1, taker_order: 2, taker_agent: 2, price: 100, qty: 3 };
+        let overfilled = vec![place(0, 1, 1, 2), place(1, 2, 2, 5), event(2, EventKind::Fill(fill))];
+        assert!(matches!(check_trace(&overfilled), Err(SimError::Invariant(_))));
+
+        let cancelled = vec![place(0, 1, 1, 2), place(1, 2, 1, 1), event(2, EventKind::Cancel { agent: 1, order: 1 })];
+        assert_eq!(open_orders(&cancelled).unwrap(), BTreeMap::from([(2, (1, 1))]));
+    }
+}
@@ -0,0 +1,65 @@
+//! Identifiers and orders shared across the engine
+//!
+//! Prices are integer ticks rather than floats so that matching and
+//! fingerprints never depend on rounding.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub type AgentId = u32;
+pub type OrderId = u64;
+pub type Price = u64;
+pub type Qty = u64;
+pub type Tick = u64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl Side {
+    pub fn opposite(&self) -> Side {
+        match self {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Side::Buy => "buy",
+            Side::Sell => "sell",
+        }
+    }
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A limit order. `qty` is what is still open once the order rests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Order {
+    pub id: OrderId,
+    pub agent: AgentId,
+    pub side: Side,
+    pub price: Price,
+    pub qty: Qty,
+}
+
+impl Order {
+    pub fn new(id: OrderId, agent: AgentId, side: Side, price: Price, qty: Qty) -> Self {
+        Self { id, agent, side, price, qty }
+    }
+
+    /// Whether this order would trade against a resting order at `price`
+    pub fn crosses(&self, price: Price) -> bool {
+        match self.side {
+            Side::Buy => self.price >= price,
+            Side::Sell => self.price <= price,
+        }
+    }
+}
@@ -0,0 +1,244 @@
+//! Curriculum diff module
+//!
+//! Compares two versions of a content pack for review before publishing:
+//! nodes and checkpoints added, removed or renamed, XP changes, changed
+//! prerequisites and changed rubrics. A removed and an added item are taken
+//! as one renamed item when they're the same kind and a node keeps its
+//! content file (by path or by contents) or a checkpoint keeps its title.
+
+use crate::validator::{load_manifest, Manifest};
+use anyhow::{Context, Result};
+use colored::*;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+/// A node or checkpoint, flattened for comparison
+struct Item<'a> {
+    id: &'a str,
+    /// Node type, or "checkpoint"
+    kind: &'a str,
+    title: &'a str,
+    week: &'a str,
+    xp: u32,
+    prerequisites: &'a [String],
+    content_path: Option<&'a str>,
+    rubrics: Option<&'a HashMap<String, String>>,
+}
+
+fn items(manifest: &Manifest) -> Vec<Item<'_>> {
+    let mut items = Vec::new();
+    for week in &manifest.weeks {
+        for day in &week.days {
+            for node in &day.nodes {
+                items.push(Item {
+                    id: &node.id,
+                    kind: &node.node_type,
+                    title: &node.title,
+                    week: &week.id,
+                    xp: node.xp_reward,
+                    prerequisites: &node.prerequisites,
+                    content_path: Some(&node.content_path),
+                    rubrics: None,
+                });
+            }
+        }
+    }
+    for checkpoint in &manifest.checkpoints {
+        items.push(Item {
+            id: &checkpoint.id,
+            kind: "checkpoint",
+            title: &checkpoint.title,
+            week: &checkpoint.week,
+            xp: checkpoint.xp_reward,
+            prerequisites: &checkpoint.prerequisites,
+            content_path: None,
+            rubrics: Some(&checkpoint.rubrics),
+        });
+    }
+    items
+}
+
+pub struct CurriculumDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub renamed: Vec<String>,
+    pub xp: Vec<String>,
+    pub prerequisites: Vec<String>,
+    pub rubrics: Vec<String>,
+    pub old_xp: u32,
+    pub new_xp: u32,
+}
+
+impl CurriculumDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.xp.is_empty()
+            && self.prerequisites.is_empty()
+            && self.rubrics.is_empty()
+    }
+}
+
+impl std::fmt::Display for CurriculumDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sections = [
+            ("Added:", &self.added),
+            ("Removed:", &self.removed),
+            ("Renamed and moved:", &self.renamed),
+            ("XP:", &self.xp),
+            ("Prerequisites:", &self.prerequisites),
+            ("Rubrics:", &self.rubrics),
+        ];
+        for (heading, lines) in sections {
+            if lines.is_empty() {
+                continue;
+            }
+            writeln!(f, "\n{}", heading.cyan().bold())?;
+            for line in lines {
+                writeln!(f, "  {}", line)?;
+            }
+        }
+
+        if self.is_empty() {
+            writeln!(f, "\n{}", "✓ No changes".green().bold())?;
+        } else {
+            let delta = self.new_xp as i64 - self.old_xp as i64;
+            writeln!(f, "\nTotal XP: {} → {} ({:+})", self.old_xp, self.new_xp, delta)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn diff_content(old_path: &Path, new_path: &Path) -> Result<CurriculumDiff> {
+    let old_manifest = load_manifest(old_path).with_context(|| format!("In {}", old_path.display()))?;
+    let new_manifest = load_manifest(new_path).with_context(|| format!("In {}", new_path.display()))?;
+    let old_items = items(&old_manifest);
+    let new_items = items(&new_manifest);
+
+    let mut diff = CurriculumDiff {
+        added: Vec::new(),
+        removed: Vec::new(),
+        renamed: Vec::new(),
+        xp: Vec::new(),
+        prerequisites: Vec::new(),
+        rubrics: Vec::new(),
+        old_xp: old_items.iter().map(|item| item.xp).sum(),
+        new_xp: new_items.iter().map(|item| item.xp).sum(),
+    };
+
+    let find = |items: &'_ [Item<'_>], id: &str| items.iter().position(|item| item.id == id);
+    let mut removed: Vec<usize> = (0..old_items.len()).filter(|&i| find(&new_items, old_items[i].id).is_none()).collect();
+    let mut added: Vec<usize> = (0..new_items.len()).filter(|&i| find(&old_items, new_items[i].id).is_none()).collect();
+
+    // Old id → new id for renamed items
+    let mut renames: HashMap<&str, &str> = HashMap::new();
+    removed.retain(|&old| {
+        let matched = added
+            .iter()
+            .position(|&new| same_item(old_path, &old_items[old], new_path, &new_items[new]));
+        match matched {
+            Some(position) => {
+                let new = added.remove(position);
+                renames.insert(old_items[old].id, new_items[new].id);
+                false
+            }
+            None => true,
+        }
+    });
+
+    for &i in &added {
+        let item = &new_items[i];
+        diff.added.push(format!("{} {} ({}, {} XP) in {}", "+".green(), item.id, item.kind, item.xp, item.week));
+    }
+    for &i in &removed {
+        let item = &old_items[i];
+        diff.removed.push(format!("{} {} ({}, {} XP) in {}", "-".red(), item.id, item.kind, item.xp, item.week));
+    }
+
+    for old in &old_items {
+        let new_id = renames.get(old.id).copied().unwrap_or(old.id);
+        let Some(new) = find(&new_items, new_id).map(|i| &new_items[i]) else {
+            continue;
+        };
+        if new_id != old.id {
+            diff.renamed.push(format!("{} → {}", old.id, new_id));
+        }
+        if old.week != new.week {
+            diff.renamed.push(format!("{} moved from {} to {}", new_id, old.week, new.week));
+        }
+
+        if old.xp != new.xp {
+            diff.xp.push(format!("{}: {} → {} ({:+})", new_id, old.xp, new.xp, new.xp as i64 - old.xp as i64));
+        }
+
+        // Compare prerequisites under their new ids, so renames alone don't show
+        let old_prereqs: BTreeSet<&str> = old
+            .prerequisites
+            .iter()
+            .map(|id| renames.get(id.as_str()).copied().unwrap_or(id))
+            .collect();
+        let new_prereqs: BTreeSet<&str> = new.prerequisites.iter().map(String::as_str).collect();
+        for id in new_prereqs.difference(&old_prereqs) {
+            diff.prerequisites.push(format!("{}: {} {}", new_id, "+".green(), id));
+        }
+        for id in old_prereqs.difference(&new_prereqs) {
+            diff.prerequisites.push(format!("{}: {} {}", new_id, "-".red(), id));
+        }
+
+        if let (Some(old_rubrics), Some(new_rubrics)) = (old.rubrics, new.rubrics) {
+            diff_rubrics(new_id, old_path, old_rubrics, new_path, new_rubrics, &mut diff.rubrics);
+        }
+    }
+
+    Ok(diff)
+}
+
+fn same_item(old_path: &Path, old: &Item, new_path: &Path, new: &Item) -> bool {
+    if old.kind != new.kind {
+        return false;
+    }
+    match (old.content_path, new.content_path) {
+        (Some(old_file), Some(new_file)) => {
+            old_file == new_file
+                || matches!(
+                    (std::fs::read(old_path.join(old_file)), std::fs::read(new_path.join(new_file))),
+                    (Ok(a), Ok(b)) if a == b
+                )
+        }
+        _ => old.title == new.title,
+    }
+}
+
+fn diff_rubrics(
+    checkpoint_id: &str,
+    old_path: &Path,
+    old: &HashMap<String, String>,
+    new_path: &Path,
+    new: &HashMap<String, String>,
+    out: &mut Vec<String>,
+) {
+    let artifact_types: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    for artifact_type in artifact_types {
+        match (old.get(artifact_type), new.get(artifact_type)) {
+            (None, Some(file)) => {
+                out.push(format!("{}: {} {} rubric ({})", checkpoint_id, "+".green(), artifact_type, file))
+            }
+            (Some(file), None) => {
+                out.push(format!("{}: {} {} rubric ({})", checkpoint_id, "-".red(), artifact_type, file))
+            }
+            (Some(old_file), Some(new_file)) => {
+                let old_text = std::fs::read_to_string(old_path.join(old_file)).ok();
+                let new_text = std::fs::read_to_string(new_path.join(new_file)).ok();
+                if old_file != new_file {
+                    out.push(format!("{}: {} rubric moved from {} to {}", checkpoint_id, artifact_type, old_file, new_file));
+                }
+                if old_text != new_text {
+                    out.push(format!("{}: {} rubric changed", checkpoint_id, artifact_type));
+                }
+            }
+            (None, None) => {}
+        }
+    }
+}
@@ -0,0 +1,261 @@
+//! Estimated-minutes recalculation
+//!
+//! Checks each node's declared `estimated_minutes` against an estimate from
+//! the content itself: reading time for lectures, from their word count,
+//! and the median time learners took on timed quiz attempts recorded in an
+//! app database. Where the database has enough learners' heartbeat-tracked
+//! active time on a completed lecture or challenge, its median is used
+//! instead, since it leaves out the time they were idle. Estimates off by
+//! more than `TOLERANCE` are reported and,
+//! with `--apply`, written back to the manifest in place, so its formatting
+//! and any fields this tool doesn't know about are kept.
+
+use anyhow::{Context, Result};
+use colored::*;
+use glp_core::db::connection::Database;
+use glp_core::db::repos::{NodeTimeRepository, QuizRepository};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::validator::load_manifest;
+
+/// How far, as a fraction of the declared time, an estimate can be before
+/// it's reported
+pub const TOLERANCE: f64 = 0.25;
+
+pub struct EstimateOptions {
+    pub path: PathBuf,
+    /// Reading speed for lectures, in words per minute
+    pub wpm: u32,
+    /// App database with recorded quiz attempts
+    pub db: Option<PathBuf>,
+    /// Only use attempts recorded in this curriculum
+    pub curriculum: Option<String>,
+    /// Timed attempts a quiz, or learners with active time on another node,
+    /// needed before it's estimated from them
+    pub min_attempts: usize,
+    pub apply: bool,
+}
+
+pub struct Estimate {
+    pub node_id: String,
+    pub declared: u32,
+    pub estimated: u32,
+    /// What the estimate is based on
+    pub basis: String,
+}
+
+impl Estimate {
+    pub fn is_off(&self) -> bool {
+        let declared = self.declared.max(1) as f64;
+        (self.estimated as f64 - declared).abs() / declared > TOLERANCE
+    }
+}
+
+/// Estimate every lecture, and every quiz with enough timed attempts
+pub fn estimate_minutes(options: &EstimateOptions) -> Result<Vec<Estimate>> {
+    let manifest = load_manifest(&options.path)?;
+    let (solve_seconds, active_seconds) = match &options.db {
+        Some(db) => (
+            solve_seconds(db, options.curriculum.as_deref())?,
+            active_seconds(db, options.curriculum.as_deref())?,
+        ),
+        None => (HashMap::new(), HashMap::new()),
+    };
+
+    let mut estimates = Vec::new();
+    for week in &manifest.weeks {
+        for day in &week.days {
+            for node in &day.nodes {
+                let active = active_seconds
+                    .get(&node.id)
+                    .filter(|seconds| node.node_type != "quiz" && seconds.len() >= options.min_attempts);
+                let estimate = match node.node_type.as_str() {
+                    _ if active.is_some() => active.map(|seconds| {
+                        let minutes = (median(seconds) / 60.0).ceil() as u32;
+                        (minutes, format!("median active time of {} learners", seconds.len()))
+                    }),
+                    "lecture" => {
+                        let Ok(text) = std::fs::read_to_string(options.path.join(&node.content_path)) else {
+                            continue;
+                        };
+                        let words = text.split_whitespace().count();
+                        let minutes = (words as f64 / options.wpm.max(1) as f64).ceil() as u32;
+                        Some((minutes, format!("{} words at {} wpm", words, options.wpm)))
+                    }
+                    "quiz" => match solve_seconds.get(&node.id) {
+                        Some(seconds) if seconds.len() >= options.min_attempts => {
+                            let minutes = (median(seconds) / 60.0).ceil() as u32;
+                            Some((minutes, format!("median of {} timed attempts", seconds.len())))
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                if let Some((minutes, basis)) = estimate {
+                    estimates.push(Estimate {
+                        node_id: node.id.clone(),
+                        declared: node.estimated_minutes,
+                        estimated: minutes.max(1),
+                        basis,
+                    });
+                }
+            }
+        }
+    }
+    Ok(estimates)
+}
+
+/// Seconds each timed attempt took, by node
+fn solve_seconds(db_path: &Path, curriculum_id: Option<&str>) -> Result<HashMap<String, Vec<i64>>> {
+    let db = Database::new(db_path.to_path_buf())
+        .with_context(|| format!("Failed to open database {}", db_path.display()))?;
+    let attempts = QuizRepository::get_timed(db.connection()).context("Failed to read quiz attempts")?;
+
+    let mut seconds: HashMap<String, Vec<i64>> = HashMap::new();
+    for attempt in attempts {
+        if curriculum_id.is_some() && attempt.curriculum_id.as_deref() != curriculum_id {
+            continue;
+        }
+        if let Some(started_at) = attempt.started_at {
+            let taken = (attempt.submitted_at - started_at).num_seconds();
+            if taken > 0 {
+                seconds.entry(attempt.node_id).or_default().push(taken);
+            }
+        }
+    }
+    Ok(seconds)
+}
+
+/// Active seconds learners took on each node they completed, from their
+/// heartbeats
+fn active_seconds(db_path: &Path, curriculum_id: Option<&str>) -> Result<HashMap<String, Vec<i64>>> {
+    let db = Database::new(db_path.to_path_buf())
+        .with_context(|| format!("Failed to open database {}", db_path.display()))?;
+    NodeTimeRepository::get_completed_seconds(db.connection(), curriculum_id).context("Failed to read node active time")
+}
+
+fn median(values: &[i64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let middle = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[middle - 1] + sorted[middle]) as f64 / 2.0
+    } else {
+        sorted[middle] as f64
+    }
+}
+
+/// Write the estimates that are off into the manifest. Returns how many
+/// nodes were updated.
+pub fn apply_estimates(content_path: &Path, estimates: &[Estimate]) -> Result<usize> {
+    let updates: HashMap<&str, u32> = estimates
+        .iter()
+        .filter(|estimate| estimate.is_off())
+        .map(|estimate| (estimate.node_id.as_str(), estimate.estimated))
+        .collect();
+    if updates.is_empty() {
+        return Ok(0);
+    }
+
+    let manifest_path = content_path.join("manifest.json");
+    let text = std::fs::read_to_string(&manifest_path).context("Failed to read manifest.json")?;
+    let updated = set_estimated_minutes(&text, &updates);
+
+    // Check every update landed on the node it was meant for
+    let check: serde_json::Value = serde_json::from_str(&updated).context("Updated manifest isn't valid JSON")?;
+    let mut applied = 0;
+    for week in check["weeks"].as_array().into_iter().flatten() {
+        for day in week["days"].as_array().into_iter().flatten() {
+            for node in day["nodes"].as_array().into_iter().flatten() {
+                let Some(&minutes) = node["id"].as_str().and_then(|id| updates.get(id)) else {
+                    continue;
+                };
+                if node["estimated_minutes"].as_u64() != Some(minutes as u64) {
+                    anyhow::bail!("Couldn't update manifest.json in place; node {} wasn't changed", node["id"]);
+                }
+                applied += 1;
+            }
+        }
+    }
+
+    std::fs::write(&manifest_path, updated).context("Failed to write manifest.json")?;
+    Ok(applied)
+}
+
+/// Replace the `estimated_minutes` of each node in `updates`, matching each
+/// one to the nearest `"id"` before it
+fn set_estimated_minutes(text: &str, updates: &HashMap<&str, u32>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut owner: Option<&str> = None;
+    let mut rest = text;
+    while let Some(at) = rest.find('"') {
+        let (before, from_quote) = rest.split_at(at);
+        out.push_str(before);
+        if let Some(value) = key_value(from_quote, "id").and_then(|value| value.strip_prefix('"')) {
+            owner = value.split('"').next();
+        }
+        if let Some(value) = key_value(from_quote, "estimated_minutes") {
+            let digits = value.len() - value.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            if let Some(&minutes) = owner.and_then(|id| updates.get(id)) {
+                if digits > 0 {
+                    let value_at = from_quote.len() - value.len();
+                    out.push_str(&from_quote[..value_at]);
+                    out.push_str(&minutes.to_string());
+                    rest = &from_quote[value_at + digits..];
+                    continue;
+                }
+            }
+        }
+        // Copy the whole string literal, so keys aren't matched inside values
+        let end = string_end(from_quote);
+        out.push_str(&from_quote[..end]);
+        rest = &from_quote[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// For text starting at `"key"` followed by a colon, the text after the colon
+fn key_value<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    let after_key = text.strip_prefix('"')?.strip_prefix(key)?.strip_prefix('"')?;
+    Some(after_key.trim_start().strip_prefix(':')?.trim_start())
+}
+
+/// Length of the JSON string literal at the start of `text`
+fn string_end(text: &str) -> usize {
+    let mut escaped = false;
+    for (i, c) in text.char_indices().skip(1) {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => return i + 1,
+            _ => escaped = false,
+        }
+    }
+    text.len()
+}
+
+pub fn print_estimates(estimates: &[Estimate]) {
+    let off: Vec<&Estimate> = estimates.iter().filter(|estimate| estimate.is_off()).collect();
+    for estimate in &off {
+        println!(
+            "  {} {}: declared {} min, estimated {} min ({})",
+            "⚠".yellow(),
+            estimate.node_id,
+            estimate.declared,
+            estimate.estimated,
+            estimate.basis
+        );
+    }
+    let close = estimates.len() - off.len();
+    if off.is_empty() {
+        println!("\n{}", format!("✓ All {} estimate(s) match", estimates.len()).green().bold());
+    } else {
+        println!(
+            "\n{} of {} estimate(s) are within {:.0}%",
+            close,
+            estimates.len(),
+            TOLERANCE * 100.0
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! Flaky challenge test report
+//!
+//! The runner reruns failed tests before counting them, and records the
+//! ones that passed on a rerun as flaky. This totals those records from the
+//! attempts in an app database, so content authors can find challenge tests
+//! that are timing-sensitive in the sandbox and fix them.
+
+use anyhow::{Context, Result};
+use colored::*;
+use glp_core::db::connection::Database;
+use glp_core::db::repos::ChallengeRepository;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The parts of a recorded verification result the report needs
+#[derive(Deserialize)]
+struct Verification {
+    #[serde(default)]
+    flaky_tests: Vec<String>,
+    #[serde(default)]
+    reruns: u32,
+}
+
+#[derive(Default)]
+pub struct NodeFlakiness {
+    pub node_id: String,
+    /// Attempts with a recorded result
+    pub attempts: usize,
+    /// Attempts whose failed tests were run again
+    pub rerun_attempts: usize,
+    /// Attempts with a test that passed only on a rerun
+    pub flaky_attempts: usize,
+    /// How often each test passed only on a rerun
+    pub flaky_tests: BTreeMap<String, usize>,
+}
+
+/// Flakiness of every challenge with a recorded attempt, the flakiest first
+pub fn flaky_report(db_path: &Path, curriculum_id: Option<&str>) -> Result<Vec<NodeFlakiness>> {
+    let db = Database::new(db_path.to_path_buf())
+        .with_context(|| format!("Failed to open database {}", db_path.display()))?;
+    let attempts = ChallengeRepository::get_verified(db.connection()).context("Failed to read challenge attempts")?;
+
+    let mut nodes: BTreeMap<String, NodeFlakiness> = BTreeMap::new();
+    for attempt in attempts {
+        if curriculum_id.is_some() && attempt.curriculum_id.as_deref() != curriculum_id {
+            continue;
+        }
+        let Some(verification) = attempt
+            .verification_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<Verification>(json).ok())
+        else {
+            continue;
+        };
+        let node = nodes.entry(attempt.node_id.clone()).or_insert_with(|| NodeFlakiness {
+            node_id: attempt.node_id,
+            ..Default::default()
+        });
+        node.attempts += 1;
+        if verification.reruns > 0 {
+            node.rerun_attempts += 1;
+        }
+        if !verification.flaky_tests.is_empty() {
+            node.flaky_attempts += 1;
+        }
+        for test in verification.flaky_tests {
+            *node.flaky_tests.entry(test).or_default() += 1;
+        }
+    }
+
+    let mut report: Vec<NodeFlakiness> = nodes.into_values().collect();
+    report.sort_by(|a, b| b.flaky_attempts.cmp(&a.flaky_attempts).then_with(|| a.node_id.cmp(&b.node_id)));
+    Ok(report)
+}
+
+pub fn print_report(report: &[NodeFlakiness]) {
+    let flaky: Vec<&NodeFlakiness> = report.iter().filter(|node| node.flaky_attempts > 0).collect();
+    for node in &flaky {
+        println!(
+            "  {} {}: flaky in {} of {} attempt(s), {} rerun",
+            "⚠".yellow(),
+            node.node_id,
+            node.flaky_attempts,
+            node.attempts,
+            node.rerun_attempts
+        );
+        let mut tests: Vec<_> = node.flaky_tests.iter().collect();
+        tests.sort_by(|a, b| b.1.cmp(a.1));
+        for (test, count) in tests {
+            println!("      {} ({}×)", test, count);
+        }
+    }
+    if flaky.is_empty() {
+        println!("\n{}", format!("✓ No flaky tests in {} challenge(s)", report.len()).green().bold());
+    } else {
+        println!("\n{} of {} challenge(s) have flaky tests", flaky.len(), report.len());
+    }
+}
@@ -0,0 +1,198 @@
+//! Quiz generation
+//!
+//! Drafts quiz questions from a lecture with the grading provider, runs each
+//! through the content validator and asks the author to accept, edit or
+//! skip it before the quiz file is written.
+
+use anyhow::{Context, Result};
+use colored::*;
+use glp_grader::types::GraderConfig;
+use glp_grader::{LLMGrader, LlmProvider, ProviderCredentials, QuizQuestion};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::validator::{self, Question, Quiz};
+
+pub struct GenerateOptions {
+    pub lecture: PathBuf,
+    pub output: PathBuf,
+    /// Quiz id; defaults to the lecture's file name with `-quiz`
+    pub id: Option<String>,
+    pub questions: usize,
+    pub skills: Vec<String>,
+    pub provider: LlmProvider,
+    pub model: Option<String>,
+    /// Accept every valid question without asking
+    pub accept_all: bool,
+}
+
+enum Review {
+    Accept,
+    Edit,
+    Skip,
+}
+
+pub fn run_generate_quiz(options: &GenerateOptions) -> Result<usize> {
+    if options.output.exists() {
+        anyhow::bail!("{} already exists", options.output.display());
+    }
+    let lecture_md = std::fs::read_to_string(&options.lecture)
+        .with_context(|| format!("Failed to read {}", options.lecture.display()))?;
+
+    let credentials = credentials(options.provider, options.model.clone())?;
+    let grader = LLMGrader::with_credentials(&credentials, GraderConfig::default());
+    let runtime = tokio::runtime::Runtime::new().context("Failed to start async runtime")?;
+    let generated = runtime
+        .block_on(grader.generate_quiz(&lecture_md, options.questions, &options.skills))
+        .context("Failed to generate questions")?;
+    println!(
+        "Generated {} question(s) in {:.1}s",
+        generated.questions.len(),
+        generated.latency_ms as f64 / 1000.0
+    );
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut accepted: Vec<QuizQuestion> = Vec::new();
+    for mut question in generated.questions {
+        loop {
+            print_question(&question);
+            let valid = match validate(&question) {
+                Ok(()) => true,
+                Err(e) => {
+                    println!("  {} {}", "✗".red(), e);
+                    false
+                }
+            };
+
+            let review = if options.accept_all {
+                if valid { Review::Accept } else { Review::Skip }
+            } else {
+                prompt(&mut input, valid)?
+            };
+            match review {
+                Review::Accept => {
+                    accepted.push(question);
+                    break;
+                }
+                Review::Skip => break,
+                Review::Edit => question = edit(&question)?,
+            }
+        }
+    }
+
+    if accepted.is_empty() {
+        anyhow::bail!("No questions accepted; nothing written");
+    }
+    for (i, question) in accepted.iter_mut().enumerate() {
+        question.id = format!("q{}", i + 1);
+    }
+
+    let (default_id, title) = quiz_identity(&options.lecture, &lecture_md);
+    let id = options.id.clone().unwrap_or(default_id);
+    let quiz = serde_json::json!({ "id": id, "title": title, "questions": accepted });
+    let content = serde_json::to_string_pretty(&quiz)?;
+    let parsed: Quiz = serde_json::from_str(&content).context("Invalid quiz JSON")?;
+    validator::validate_quiz(&parsed)?;
+
+    std::fs::write(&options.output, content + "\n")
+        .with_context(|| format!("Failed to write {}", options.output.display()))?;
+    Ok(accepted.len())
+}
+
+/// Provider by the name used in the app's settings
+pub fn parse_provider(name: &str) -> Result<LlmProvider, String> {
+    LlmProvider::ALL
+        .into_iter()
+        .find(|p| p.display_name().eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("unknown provider '{}' (expected openai, anthropic or ollama)", name))
+}
+
+/// Credentials from the provider's API key environment variable
+fn credentials(provider: LlmProvider, model: Option<String>) -> Result<ProviderCredentials> {
+    let mut credentials = ProviderCredentials::new(provider);
+    if let Some(var) = provider.api_key_env_var() {
+        let key = std::env::var(var).with_context(|| format!("Set {} to use {}", var, provider.display_name()))?;
+        credentials = credentials.with_api_key(&key);
+    }
+    credentials.model = model;
+    Ok(credentials)
+}
+
+/// Check a question the way `validate` checks quiz files
+fn validate(question: &QuizQuestion) -> Result<()> {
+    let question: Question = serde_json::from_value(serde_json::to_value(question)?)
+        .context("Invalid question JSON")?;
+    validator::validate_question(&question)
+}
+
+fn print_question(question: &QuizQuestion) {
+    println!("\n{} {}", format!("[{}]", question.question_type).cyan(), question.question.bold());
+    for (i, option) in question.options.iter().enumerate() {
+        let correct = question.correct_answer == Some(i)
+            || question.correct_answers.as_ref().is_some_and(|answers| answers.contains(&i));
+        let marker = if correct { "✓".green() } else { " ".normal() };
+        println!("  {} {}. {}", marker, i, option);
+    }
+    println!("  {} {}", "Why:".dimmed(), question.explanation);
+    println!("  {} {}", "Skills:".dimmed(), question.skills.join(", "));
+}
+
+fn prompt(input: &mut impl BufRead, valid: bool) -> Result<Review> {
+    loop {
+        if valid {
+            print!("[a]ccept, [e]dit or [s]kip? ");
+        } else {
+            print!("[e]dit or [s]kip? ");
+        }
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(Review::Skip);
+        }
+        match line.trim() {
+            "a" | "accept" if valid => return Ok(Review::Accept),
+            "e" | "edit" => return Ok(Review::Edit),
+            "s" | "skip" => return Ok(Review::Skip),
+            _ => continue,
+        }
+    }
+}
+
+/// Open the question as JSON in `$EDITOR` and read back the result
+fn edit(question: &QuizQuestion) -> Result<QuizQuestion> {
+    let path = std::env::temp_dir().join(format!("content-builder-{}.json", question.id));
+    std::fs::write(&path, serde_json::to_string_pretty(question)?)?;
+
+    let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_string());
+    let status = Command::new(&editor)
+        .arg(&path)
+        .status()
+        .with_context(|| format!("Failed to run {}", editor))?;
+    let edited = std::fs::read_to_string(&path)?;
+    let _ = std::fs::remove_file(&path);
+    if !status.success() {
+        anyhow::bail!("{} exited with {}", editor, status);
+    }
+
+    match serde_json::from_str(&edited) {
+        Ok(edited) => Ok(edited),
+        Err(e) => {
+            println!("  {} Couldn't parse the edited question, keeping the original: {}", "✗".red(), e);
+            Ok(question.clone())
+        }
+    }
+}
+
+/// Quiz id from the lecture's file name, and title from its first heading
+fn quiz_identity(lecture: &Path, lecture_md: &str) -> (String, String) {
+    let stem = lecture.file_stem().and_then(|s| s.to_str()).unwrap_or("lecture");
+    let heading = lecture_md
+        .lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(str::trim)
+        .unwrap_or(stem);
+    (format!("{}-quiz", stem), format!("{} Quiz", heading))
+}
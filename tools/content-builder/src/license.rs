@@ -0,0 +1,55 @@
+//! Issuing license keys for premium curricula
+//!
+//! An issuer keeps a signing key, and the app is built to trust its public
+//! half (`GLP_LICENSE_ISSUERS`). License keys are signed for a curriculum by
+//! its title, so the pack they're issued for is read for it.
+
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate};
+use colored::*;
+use content::license::{self, License};
+use std::path::Path;
+
+use crate::validator::load_manifest;
+
+/// A new signing key and the issuer key to build the app with
+pub fn new_issuer() -> (String, String) {
+    let signing_key = license::generate_signing_key();
+    (license::signing_key_to_hex(&signing_key), license::issuer_key(&signing_key))
+}
+
+pub fn print_issuer(signing_key: &str, issuer_key: &str) {
+    println!("{} Created a license issuer", "✓".green().bold());
+    println!("  Signing key (keep it secret): {}", signing_key.bold());
+    println!("  Issuer key, to build the app with in GLP_LICENSE_ISSUERS: {}", issuer_key);
+}
+
+/// Sign a license key for the pack at `content_path`
+pub fn issue(content_path: &Path, signing_key: &str, licensee: &str, expires_on: Option<&str>) -> Result<(License, String)> {
+    let manifest = load_manifest(content_path)?;
+    let signing_key = license::signing_key_from_hex(signing_key)?;
+    if !manifest.requires_license {
+        println!("{} The manifest doesn't set requires_license, so the key won't be needed", "⚠".yellow());
+    }
+
+    let expires_on = expires_on
+        .map(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").with_context(|| format!("Invalid expiry date {}", date)))
+        .transpose()?;
+    let license = License {
+        curriculum: manifest.title.clone(),
+        licensee: licensee.to_string(),
+        issued_on: Local::now().date_naive(),
+        expires_on,
+    };
+    let key = license.sign(&signing_key)?;
+    Ok((license, key))
+}
+
+pub fn print_license(license: &License, key: &str) {
+    let expiry = match license.expires_on {
+        Some(date) => format!("until {}", date),
+        None => "with no expiry".to_string(),
+    };
+    println!("{} Licensed {} to {} {}", "✓".green().bold(), license.curriculum, license.licensee, expiry);
+    println!("  License key: {}", key.bold());
+}
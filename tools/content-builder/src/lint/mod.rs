@@ -0,0 +1,329 @@
+//! House-style rules for content packs
+//!
+//! Beyond the structural checks in `validator`, a pack can be linted against
+//! rules an organization picks: each rule has an id, a default severity and
+//! optional settings, and a pack's `lint.json` turns rules on or off and
+//! overrides their severity:
+//!
+//! ```json
+//! {
+//!   "rules": {
+//!     "quiz-explanations": "error",
+//!     "max-lecture-length": { "level": "warning", "max_words": 2500 },
+//!     "challenge-hints": "off"
+//!   }
+//! }
+//! ```
+//!
+//! Findings listed in the pack's `lint-baseline.txt` are suppressed, so a
+//! rule can be turned on before every existing finding is fixed.
+//!
+//! New rules implement `Rule` and are added to `builtin_rules`.
+
+mod spelling;
+
+use crate::validator::{Challenge, Manifest, Node, Quiz, ValidationReport};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use spelling::{Spelling, Terminology};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Config file read from the root of a content pack
+pub const CONFIG_FILE: &str = "lint.json";
+
+/// Findings to suppress, one per line as `validate` prints them, read from
+/// the root of a content pack and written by `validate --update-baseline`
+pub const BASELINE_FILE: &str = "lint-baseline.txt";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Off,
+    Info,
+    Warning,
+    Error,
+}
+
+impl std::fmt::Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Level::Off => "off",
+            Level::Info => "info",
+            Level::Warning => "warning",
+            Level::Error => "error",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A rule's entry in `lint.json`: just a level, or a level with settings
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum RuleConfig {
+    Level(Level),
+    Options {
+        level: Option<Level>,
+        #[serde(flatten)]
+        options: Map<String, Value>,
+    },
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct LintConfig {
+    #[serde(default)]
+    pub rules: HashMap<String, RuleConfig>,
+}
+
+impl LintConfig {
+    /// Read `path`, or the pack's `lint.json` if there is one
+    pub fn load(content_path: &Path, path: Option<&Path>) -> Result<Self> {
+        let default_path = content_path.join(CONFIG_FILE);
+        let path = match path {
+            Some(path) => path,
+            None if default_path.exists() => &default_path,
+            None => return Ok(Self::default()),
+        };
+        let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+}
+
+/// A content style check. Each `check_*` returns one message per problem
+/// found; the defaults find none.
+pub trait Rule {
+    fn id(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    fn default_level(&self) -> Level;
+
+    /// Apply settings from `lint.json`, with paths relative to the pack.
+    /// Called once before checking, with no settings if there are none.
+    fn configure(&mut self, options: &Map<String, Value>, _content_path: &Path) -> Result<()> {
+        if let Some(key) = options.keys().next() {
+            anyhow::bail!("unknown setting '{}'", key);
+        }
+        Ok(())
+    }
+
+    fn check_lecture(&self, _node: &Node, _text: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn check_quiz(&self, _node: &Node, _quiz: &Quiz) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn check_challenge(&self, _node: &Node, _challenge: &Challenge) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+pub fn builtin_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(QuizExplanations),
+        Box::new(MaxLectureLength { max_words: MaxLectureLength::DEFAULT_MAX_WORDS }),
+        Box::new(ChallengeHints),
+        Box::new(Spelling::default()),
+        Box::new(Terminology::default()),
+    ]
+}
+
+/// Every quiz question explains its answer
+struct QuizExplanations;
+
+impl Rule for QuizExplanations {
+    fn id(&self) -> &'static str {
+        "quiz-explanations"
+    }
+
+    fn description(&self) -> &'static str {
+        "Every quiz question has an explanation"
+    }
+
+    fn default_level(&self) -> Level {
+        Level::Warning
+    }
+
+    fn check_quiz(&self, _node: &Node, quiz: &Quiz) -> Vec<String> {
+        quiz.questions
+            .iter()
+            .filter(|q| q.explanation.trim().is_empty())
+            .map(|q| format!("Question '{}' has no explanation", q.id))
+            .collect()
+    }
+}
+
+/// Lectures stay short enough to read in one sitting
+struct MaxLectureLength {
+    max_words: usize,
+}
+
+impl MaxLectureLength {
+    const DEFAULT_MAX_WORDS: usize = 3000;
+}
+
+impl Rule for MaxLectureLength {
+    fn id(&self) -> &'static str {
+        "max-lecture-length"
+    }
+
+    fn description(&self) -> &'static str {
+        "Lectures are at most `max_words` words long (default 3000)"
+    }
+
+    fn default_level(&self) -> Level {
+        Level::Warning
+    }
+
+    fn configure(&mut self, options: &Map<String, Value>, _content_path: &Path) -> Result<()> {
+        for (key, value) in options {
+            match key.as_str() {
+                "max_words" => {
+                    self.max_words = value
+                        .as_u64()
+                        .context("max_words must be a whole number")? as usize;
+                }
+                _ => anyhow::bail!("unknown setting '{}'", key),
+            }
+        }
+        Ok(())
+    }
+
+    fn check_lecture(&self, _node: &Node, text: &str) -> Vec<String> {
+        let words = text.split_whitespace().count();
+        if words > self.max_words {
+            vec![format!("Lecture is {} words, over the limit of {}", words, self.max_words)]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Challenges give learners somewhere to turn when they're stuck
+struct ChallengeHints;
+
+impl Rule for ChallengeHints {
+    fn id(&self) -> &'static str {
+        "challenge-hints"
+    }
+
+    fn description(&self) -> &'static str {
+        "Every challenge has at least one hint"
+    }
+
+    fn default_level(&self) -> Level {
+        Level::Info
+    }
+
+    fn check_challenge(&self, _node: &Node, challenge: &Challenge) -> Vec<String> {
+        if challenge.hints.iter().all(|hint| hint.trim().is_empty()) {
+            vec![format!("Challenge '{}' has no hints", challenge.id)]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Run the rules `config` leaves on over every node, adding what they find
+/// to `report` at each rule's level, less what the pack's baseline
+/// suppresses. Files that don't parse are skipped; `validator` already
+/// reports them. With `update_baseline`, everything found is written to the
+/// baseline instead.
+pub fn lint_content(
+    content_path: &Path,
+    manifest: &Manifest,
+    config: &LintConfig,
+    update_baseline: bool,
+    report: &mut ValidationReport,
+) -> Result<()> {
+    let mut rules = Vec::new();
+    for mut rule in builtin_rules() {
+        let (level, options) = match config.rules.get(rule.id()) {
+            None => (rule.default_level(), None),
+            Some(RuleConfig::Level(level)) => (*level, None),
+            // Giving a rule settings turns it on, at warning if it's off by default
+            Some(RuleConfig::Options { level, options }) => {
+                let level = level.unwrap_or(match rule.default_level() {
+                    Level::Off => Level::Warning,
+                    level => level,
+                });
+                (level, Some(options))
+            }
+        };
+        if level == Level::Off {
+            continue;
+        }
+        if let Err(e) = rule.configure(options.unwrap_or(&Map::new()), content_path) {
+            report.errors.push(format!("{}: rule '{}': {:#}", CONFIG_FILE, rule.id(), e));
+            continue;
+        }
+        rules.push((rule, level));
+    }
+    for id in config.rules.keys() {
+        if !builtin_rules().iter().any(|rule| rule.id() == id) {
+            report.errors.push(format!("{}: unknown rule '{}'", CONFIG_FILE, id));
+        }
+    }
+
+    let mut findings = Vec::new();
+    for week in &manifest.weeks {
+        for day in &week.days {
+            for node in &day.nodes {
+                let Ok(text) = std::fs::read_to_string(content_path.join(&node.content_path)) else {
+                    continue;
+                };
+                for (rule, level) in &rules {
+                    let found = match node.node_type.as_str() {
+                        "lecture" => rule.check_lecture(node, &text),
+                        "quiz" => match serde_json::from_str::<Quiz>(&text) {
+                            Ok(quiz) => rule.check_quiz(node, &quiz),
+                            Err(_) => continue,
+                        },
+                        "mini-challenge" => match serde_json::from_str::<Challenge>(&text) {
+                            Ok(challenge) => rule.check_challenge(node, &challenge),
+                            Err(_) => continue,
+                        },
+                        _ => continue,
+                    };
+                    for finding in found {
+                        findings.push((*level, format!("[{}] {}: {}", rule.id(), node.content_path, finding)));
+                    }
+                }
+            }
+        }
+    }
+
+    let baseline_path = content_path.join(BASELINE_FILE);
+    if update_baseline {
+        let mut lines: Vec<&str> = findings.iter().map(|(_, finding)| finding.as_str()).collect();
+        lines.sort_unstable();
+        lines.dedup();
+        let mut text = lines.join("\n");
+        text.push('\n');
+        std::fs::write(&baseline_path, text).with_context(|| format!("Failed to write {}", baseline_path.display()))?;
+        report.info.push(format!("Wrote {} finding(s) to {}", lines.len(), BASELINE_FILE));
+        return Ok(());
+    }
+
+    let baseline: HashSet<String> = match std::fs::read_to_string(&baseline_path) {
+        Ok(text) => text.lines().map(str::to_string).collect(),
+        Err(_) => HashSet::new(),
+    };
+    let mut suppressed = 0;
+    for (level, finding) in findings {
+        if baseline.contains(&finding) {
+            suppressed += 1;
+            continue;
+        }
+        match level {
+            Level::Error => report.errors.push(finding),
+            Level::Warning => report.warnings.push(finding),
+            Level::Info | Level::Off => report.info.push(finding),
+        }
+    }
+    if suppressed > 0 {
+        report.info.push(format!("{} finding(s) suppressed by {}", suppressed, BASELINE_FILE));
+    }
+    Ok(())
+}
@@ -0,0 +1,266 @@
+//! Spelling and terminology rules
+//!
+//! Both read the prose of lectures, quiz questions, options and
+//! explanations, and challenge text and hints, leaving out code: fenced
+//! blocks, inline code, link targets and URLs. Both are off unless a pack's
+//! `lint.json` turns them on.
+
+use super::{Level, Rule};
+use crate::validator::{Challenge, Node, Quiz};
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// Word list used when a pack doesn't name its own dictionaries
+pub const DEFAULT_DICTIONARY: &str = "/usr/share/dict/words";
+
+/// Prose from a piece of content, with where in it the text came from
+struct Passage {
+    place: Option<String>,
+    text: String,
+}
+
+fn lecture_passages(text: &str) -> Vec<Passage> {
+    vec![Passage { place: None, text: prose(text) }]
+}
+
+fn quiz_passages(quiz: &Quiz) -> Vec<Passage> {
+    quiz.questions
+        .iter()
+        .flat_map(|q| {
+            let place = Some(format!("question '{}'", q.id));
+            std::iter::once(&q.question)
+                .chain(&q.options)
+                .chain(std::iter::once(&q.explanation))
+                .map(move |text| Passage { place: place.clone(), text: prose(text) })
+        })
+        .collect()
+}
+
+fn challenge_passages(challenge: &Challenge) -> Vec<Passage> {
+    [&challenge.title, &challenge.description, &challenge.instructions]
+        .into_iter()
+        .chain(&challenge.hints)
+        .map(|text| Passage { place: None, text: prose(text) })
+        .collect()
+}
+
+/// Markdown with code, link targets and URLs blanked out
+fn prose(markdown: &str) -> String {
+    let mut out = String::new();
+    let mut in_fence = false;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            out.push('\n');
+            continue;
+        }
+        if in_fence {
+            out.push('\n');
+            continue;
+        }
+        // Odd-numbered pieces between backticks are inline code
+        let text: String = line
+            .split('`')
+            .enumerate()
+            .map(|(i, piece)| if i % 2 == 0 { piece } else { " " })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let text = text
+            .split_whitespace()
+            .map(|word| match word.find("](") {
+                Some(at) => &word[..at],
+                None if word.contains("://") || word.starts_with("www.") => "",
+                None => word,
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        out.push_str(&text);
+        out.push('\n');
+    }
+    out
+}
+
+/// Findings, once each, in the order they were first seen
+fn collect_findings(passages: Vec<Passage>, check: impl Fn(&str) -> Vec<String>) -> Vec<String> {
+    let mut findings = Vec::new();
+    let mut seen = HashSet::new();
+    for passage in passages {
+        for finding in check(&passage.text) {
+            let finding = match &passage.place {
+                Some(place) => format!("{} in {}", finding, place),
+                None => finding,
+            };
+            if seen.insert(finding.clone()) {
+                findings.push(finding);
+            }
+        }
+    }
+    findings
+}
+
+fn string_list(value: &Value, key: &str) -> Result<Vec<String>> {
+    value
+        .as_array()
+        .and_then(|items| items.iter().map(|item| item.as_str().map(str::to_string)).collect())
+        .with_context(|| format!("{} must be a list of strings", key))
+}
+
+/// Words in lectures, quizzes and challenges are in the pack's dictionaries
+#[derive(Default)]
+pub struct Spelling {
+    words: HashSet<String>,
+}
+
+impl Spelling {
+    /// Read a plain word list, or a Hunspell `.dic` file: a word count, then
+    /// one word per line with any affix flags after a `/`
+    fn load_dictionary(&mut self, path: &Path) -> Result<()> {
+        let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        for line in text.lines() {
+            let word = line.split('/').next().unwrap_or_default().trim();
+            if !word.is_empty() && !word.chars().all(|c| c.is_ascii_digit()) {
+                self.words.insert(word.to_lowercase());
+            }
+        }
+        Ok(())
+    }
+
+    fn unknown_words(&self, text: &str) -> Vec<String> {
+        text.split(|c: char| !c.is_alphabetic() && c != '\'')
+            .map(|word| word.trim_matches('\''))
+            .map(|word| word.strip_suffix("'s").unwrap_or(word))
+            // Short words, acronyms and camelCase identifiers are left alone
+            .filter(|word| word.chars().count() > 2 && !word.chars().skip(1).any(char::is_uppercase))
+            .filter(|word| !self.words.contains(&word.to_lowercase()))
+            .map(|word| format!("Unknown word '{}'", word))
+            .collect()
+    }
+}
+
+impl Rule for Spelling {
+    fn id(&self) -> &'static str {
+        "spelling"
+    }
+
+    fn description(&self) -> &'static str {
+        "Words are in `dictionaries` (default /usr/share/dict/words) or `words`"
+    }
+
+    fn default_level(&self) -> Level {
+        Level::Off
+    }
+
+    fn configure(&mut self, options: &Map<String, Value>, content_path: &Path) -> Result<()> {
+        let mut dictionaries = vec![DEFAULT_DICTIONARY.to_string()];
+        for (key, value) in options {
+            match key.as_str() {
+                "dictionaries" => dictionaries = string_list(value, key)?,
+                "words" => self.words.extend(string_list(value, key)?.iter().map(|word| word.to_lowercase())),
+                _ => anyhow::bail!("unknown setting '{}'", key),
+            }
+        }
+        for dictionary in dictionaries {
+            self.load_dictionary(&content_path.join(dictionary))?;
+        }
+        Ok(())
+    }
+
+    fn check_lecture(&self, _node: &Node, text: &str) -> Vec<String> {
+        collect_findings(lecture_passages(text), |text| self.unknown_words(text))
+    }
+
+    fn check_quiz(&self, _node: &Node, quiz: &Quiz) -> Vec<String> {
+        collect_findings(quiz_passages(quiz), |text| self.unknown_words(text))
+    }
+
+    fn check_challenge(&self, _node: &Node, challenge: &Challenge) -> Vec<String> {
+        collect_findings(challenge_passages(challenge), |text| self.unknown_words(text))
+    }
+}
+
+/// Terms are written the way the curriculum's glossary spells them
+#[derive(Default)]
+pub struct Terminology {
+    /// Variant, lowercased, and the term to use instead
+    variants: Vec<(String, String)>,
+}
+
+impl Terminology {
+    /// Add a glossary: each preferred term with the variants to flag
+    fn add_terms(&mut self, terms: &Value, key: &str) -> Result<()> {
+        let terms: BTreeMap<String, Vec<String>> = serde_json::from_value(terms.clone())
+            .with_context(|| format!("{} must map each term to a list of variants", key))?;
+        for (term, variants) in terms {
+            for variant in variants {
+                self.variants.push((variant.to_lowercase(), term.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    fn misused_terms(&self, text: &str) -> Vec<String> {
+        let text = text.to_lowercase();
+        let mut findings = Vec::new();
+        for (variant, term) in &self.variants {
+            let found = text.match_indices(variant.as_str()).any(|(at, _)| {
+                let before = text[..at].chars().next_back();
+                let after = text[at + variant.len()..].chars().next();
+                !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+            });
+            if found {
+                findings.push(format!("Write '{}', not '{}'", term, variant));
+            }
+        }
+        findings
+    }
+}
+
+impl Rule for Terminology {
+    fn id(&self) -> &'static str {
+        "terminology"
+    }
+
+    fn description(&self) -> &'static str {
+        "Terms match the `glossary` file or `terms`, e.g. \"borrow checker\", not \"borrowchecker\""
+    }
+
+    fn default_level(&self) -> Level {
+        Level::Off
+    }
+
+    fn configure(&mut self, options: &Map<String, Value>, content_path: &Path) -> Result<()> {
+        for (key, value) in options {
+            match key.as_str() {
+                "terms" => self.add_terms(value, key)?,
+                "glossary" => {
+                    let path = content_path.join(value.as_str().context("glossary must be a path")?);
+                    let text =
+                        std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+                    let terms: Value =
+                        serde_json::from_str(&text).with_context(|| format!("Failed to parse {}", path.display()))?;
+                    self.add_terms(&terms, key)?;
+                }
+                _ => anyhow::bail!("unknown setting '{}'", key),
+            }
+        }
+        if self.variants.is_empty() {
+            anyhow::bail!("needs a glossary file or terms");
+        }
+        Ok(())
+    }
+
+    fn check_lecture(&self, _node: &Node, text: &str) -> Vec<String> {
+        collect_findings(lecture_passages(text), |text| self.misused_terms(text))
+    }
+
+    fn check_quiz(&self, _node: &Node, quiz: &Quiz) -> Vec<String> {
+        collect_findings(quiz_passages(quiz), |text| self.misused_terms(text))
+    }
+
+    fn check_challenge(&self, _node: &Node, challenge: &Challenge) -> Vec<String> {
+        collect_findings(challenge_passages(challenge), |text| self.misused_terms(text))
+    }
+}
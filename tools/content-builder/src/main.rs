@@ -2,6 +2,14 @@
 //!
 //! Tool for building, validating, and analyzing course content.
 
+mod diff;
+mod estimate;
+mod flaky;
+mod generate;
+mod license;
+mod lint;
+mod seal;
+mod simulate;
 mod validator;
 
 use clap::{Parser, Subcommand};
@@ -23,6 +31,21 @@ enum Commands {
         /// Path to content directory (default: ./content)
         #[arg(short, long, default_value = "./content")]
         path: PathBuf,
+        /// Lint rule config (default: the content directory's lint.json)
+        #[arg(long)]
+        lint_config: Option<PathBuf>,
+        /// Record current lint findings in lint-baseline.txt so they're no longer reported
+        #[arg(long)]
+        update_baseline: bool,
+    },
+    /// List the lint rules validation can run
+    Rules,
+    /// Compare two versions of a content pack
+    Diff {
+        /// Content directory before the change
+        old: PathBuf,
+        /// Content directory after the change
+        new: PathBuf,
     },
     /// Show content statistics
     Stats {
@@ -30,15 +53,109 @@ enum Commands {
         #[arg(short, long, default_value = "./content")]
         path: PathBuf,
     },
+    /// Copy a pack with its lectures, quizzes and challenges encrypted, for paid distribution
+    Seal {
+        /// Path to content directory (default: ./content)
+        #[arg(short, long, default_value = "./content")]
+        path: PathBuf,
+        /// Directory to write the sealed pack to
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Content key as 64 hex digits (default: a new random key)
+        #[arg(long)]
+        key: Option<String>,
+    },
+    /// Create a signing key for issuing license keys for premium curricula
+    IssuerKey,
+    /// Sign a license key for a premium curriculum
+    IssueLicense {
+        /// Path to content directory (default: ./content)
+        #[arg(short, long, default_value = "./content")]
+        path: PathBuf,
+        /// Signing key from issuer-key, as 64 hex digits
+        #[arg(long)]
+        signing_key: String,
+        /// Who the license is for
+        #[arg(long)]
+        licensee: String,
+        /// Last day of the license, YYYY-MM-DD (default: no expiry)
+        #[arg(long)]
+        expires: Option<String>,
+    },
+    /// Simulate learners through the content and check XP/level pacing
+    Simulate {
+        /// Path to content directory (default: ./content)
+        #[arg(short, long, default_value = "./content")]
+        path: PathBuf,
+        /// JSON file with learner archetypes (default: daily, binge, casual)
+        #[arg(short, long)]
+        archetypes: Option<PathBuf>,
+    },
+    /// Check declared estimated_minutes against reading time, recorded quiz attempts and learners' active time
+    Estimate {
+        /// Path to content directory (default: ./content)
+        #[arg(short, long, default_value = "./content")]
+        path: PathBuf,
+        /// Lecture reading speed in words per minute
+        #[arg(long, default_value_t = 200)]
+        wpm: u32,
+        /// App database to read timed quiz attempts from
+        #[arg(long)]
+        db: Option<PathBuf>,
+        /// Only use attempts from this curriculum
+        #[arg(long)]
+        curriculum: Option<String>,
+        /// Timed quiz attempts, or learners with active time on another node, needed to estimate from them
+        #[arg(long, default_value_t = 5)]
+        min_attempts: usize,
+        /// Write estimates that are off into the manifest
+        #[arg(long)]
+        apply: bool,
+    },
+    /// Report challenge tests that passed only when the runner reran them
+    Flaky {
+        /// App database to read challenge attempts from
+        #[arg(long)]
+        db: PathBuf,
+        /// Only use attempts from this curriculum
+        #[arg(long)]
+        curriculum: Option<String>,
+    },
+    /// Draft quiz questions from a lecture with an LLM, then review each one
+    GenerateQuiz {
+        /// Lecture markdown file
+        lecture: PathBuf,
+        /// Quiz JSON file to write
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Quiz id (default: the lecture's file name with -quiz)
+        #[arg(long)]
+        id: Option<String>,
+        /// Number of questions to draft
+        #[arg(short = 'n', long, default_value_t = 5)]
+        questions: usize,
+        /// Skill ids to tag questions with, comma separated
+        #[arg(short, long, value_delimiter = ',')]
+        skills: Vec<String>,
+        /// LLM provider: openai, anthropic or ollama
+        #[arg(long, default_value = "openai", value_parser = generate::parse_provider)]
+        provider: glp_grader::LlmProvider,
+        /// Model to use instead of the provider's default
+        #[arg(long)]
+        model: Option<String>,
+        /// Accept every question that passes validation without asking
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
 }
 
 fn main() {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Validate { path } => {
+        Commands::Validate { path, lint_config, update_baseline } => {
             println!("{}", "Validating content...".cyan().bold());
-            match validator::validate_content(&path) {
+            match validator::validate_content(&path, lint_config.as_deref(), update_baseline) {
                 Ok(report) => {
                     println!("\n{}", "Validation Results:".green().bold());
                     println!("{}", report);
@@ -49,6 +166,22 @@ fn main() {
                 }
             }
         }
+        Commands::Rules => {
+            println!("{}", "Lint rules:".cyan().bold());
+            for rule in lint::builtin_rules() {
+                println!("  {:<22} {:<8} {}", rule.id().bold(), rule.default_level().to_string(), rule.description());
+            }
+        }
+        Commands::Diff { old, new } => {
+            println!("{}", "Comparing content...".cyan().bold());
+            match diff::diff_content(&old, &new) {
+                Ok(diff) => println!("{}", diff),
+                Err(e) => {
+                    eprintln!("{} {:#}", "Error:".red().bold(), e);
+                    std::process::exit(1);
+                }
+            }
+        }
         Commands::Stats { path } => {
             println!("{}", "Content Statistics:".cyan().bold());
             match validator::content_stats(&path) {
@@ -59,5 +192,94 @@ fn main() {
                 }
             }
         }
+        Commands::Seal { path, output, key } => {
+            println!("{}", "Sealing content...".cyan().bold());
+            match seal::seal_pack(&path, &output, key.as_deref()) {
+                Ok((key, sealed)) => seal::print_sealed(&output, &key, sealed),
+                Err(e) => {
+                    eprintln!("{} {:#}", "Error:".red().bold(), e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::IssuerKey => {
+            let (signing_key, issuer_key) = license::new_issuer();
+            license::print_issuer(&signing_key, &issuer_key);
+        }
+        Commands::IssueLicense { path, signing_key, licensee, expires } => {
+            match license::issue(&path, &signing_key, &licensee, expires.as_deref()) {
+                Ok((issued, key)) => license::print_license(&issued, &key),
+                Err(e) => {
+                    eprintln!("{} {:#}", "Error:".red().bold(), e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Simulate { path, archetypes } => {
+            println!("{}", "Simulating learners...".cyan().bold());
+            match simulate::run_simulation(&path, archetypes.as_deref()) {
+                Ok(report) => {
+                    simulate::print_report(&report);
+                    if !report.is_balanced() {
+                        std::process::exit(1);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{} {}", "Error:".red().bold(), e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Estimate { path, wpm, db, curriculum, min_attempts, apply } => {
+            println!("{}", "Estimating minutes...".cyan().bold());
+            let options = estimate::EstimateOptions { path, wpm, db, curriculum, min_attempts, apply };
+            let result = estimate::estimate_minutes(&options).and_then(|estimates| {
+                estimate::print_estimates(&estimates);
+                if options.apply {
+                    let applied = estimate::apply_estimates(&options.path, &estimates)?;
+                    println!("{} Updated {} node(s) in manifest.json", "✓".green().bold(), applied);
+                }
+                Ok(())
+            });
+            if let Err(e) = result {
+                eprintln!("{} {:#}", "Error:".red().bold(), e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Flaky { db, curriculum } => {
+            println!("{}", "Checking for flaky tests...".cyan().bold());
+            match flaky::flaky_report(&db, curriculum.as_deref()) {
+                Ok(report) => flaky::print_report(&report),
+                Err(e) => {
+                    eprintln!("{} {:#}", "Error:".red().bold(), e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::GenerateQuiz { lecture, output, id, questions, skills, provider, model, yes } => {
+            println!("{}", "Generating quiz...".cyan().bold());
+            let options = generate::GenerateOptions {
+                lecture,
+                output,
+                id,
+                questions,
+                skills,
+                provider,
+                model,
+                accept_all: yes,
+            };
+            match generate::run_generate_quiz(&options) {
+                Ok(count) => println!(
+                    "\n{} Wrote {} question(s) to {}",
+                    "✓".green().bold(),
+                    count,
+                    options.output.display()
+                ),
+                Err(e) => {
+                    eprintln!("{} {:#}", "Error:".red().bold(), e);
+                    std::process::exit(1);
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,77 @@
+//! Sealing packs for distribution
+//!
+//! Copies a pack with its lecture, quiz and challenge files encrypted under a
+//! content key, renamed to `<path>.enc`, and the manifest pointing at them.
+//! Everything else is copied as is. Learners unlock the pack by entering the
+//! key when they activate it.
+
+use anyhow::{Context, Result};
+use colored::*;
+use content::encryption::{self, ContentKey};
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Node types whose files are sealed
+const SEALED_TYPES: [&str; 3] = ["lecture", "quiz", "mini-challenge"];
+
+/// Seal the pack at `source` into `output`, which mustn't exist yet, under
+/// `key` or a new one. Returns the key and how many files were sealed.
+pub fn seal_pack(source: &Path, output: &Path, key: Option<&str>) -> Result<(ContentKey, usize)> {
+    if output.exists() {
+        anyhow::bail!("{} already exists; seal into a new directory", output.display());
+    }
+    let key = match key {
+        Some(hex) => ContentKey::from_hex(hex)?,
+        None => ContentKey::generate(),
+    };
+
+    let manifest_text = std::fs::read_to_string(source.join("manifest.json")).context("Failed to read manifest.json")?;
+    let mut manifest: serde_json::Value = serde_json::from_str(&manifest_text).context("Invalid manifest.json")?;
+    if manifest.get("encryption").is_some() {
+        anyhow::bail!("{} is already sealed", source.display());
+    }
+
+    for entry in WalkDir::new(source) {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(source)?;
+        let target = output.join(relative);
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(&target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+
+    let mut sealed = 0;
+    for week in manifest["weeks"].as_array_mut().into_iter().flatten() {
+        for day in week["days"].as_array_mut().into_iter().flatten() {
+            for node in day["nodes"].as_array_mut().into_iter().flatten() {
+                let Some(path) = node["content_path"].as_str().map(str::to_string) else {
+                    continue;
+                };
+                let node_type = node["type"].as_str().unwrap_or_default();
+                if !SEALED_TYPES.contains(&node_type) || encryption::is_sealed(&path) {
+                    continue;
+                }
+
+                let sealed_path = format!("{}{}", path, encryption::SEALED_EXTENSION);
+                let plaintext = std::fs::read(source.join(&path)).with_context(|| format!("Failed to read {}", path))?;
+                std::fs::write(output.join(&sealed_path), encryption::seal(&key, &sealed_path, &plaintext)?)?;
+                std::fs::remove_file(output.join(&path))?;
+                node["content_path"] = sealed_path.into();
+                sealed += 1;
+            }
+        }
+    }
+
+    manifest["encryption"] = serde_json::json!({ "key_check": key.check() });
+    std::fs::write(output.join("manifest.json"), serde_json::to_string_pretty(&manifest)?)
+        .context("Failed to write manifest.json")?;
+    Ok((key, sealed))
+}
+
+pub fn print_sealed(output: &Path, key: &ContentKey, sealed: usize) {
+    println!("{} Sealed {} file(s) into {}", "✓".green().bold(), sealed, output.display());
+    println!("  Content key: {}", key.to_hex().bold());
+    println!("  Give it to learners on purchase; they enter it when activating the curriculum.");
+}
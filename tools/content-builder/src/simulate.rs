@@ -0,0 +1,128 @@
+//! Gamification balance simulation
+//!
+//! Plays learner archetypes through a content pack with the real formulas from
+//! `glp_core::simulation` and reports whether XP and level pacing is balanced.
+
+use anyhow::{Context, Result};
+use colored::*;
+use glp_core::gamification::{Difficulty, XpActivity};
+use glp_core::simulation::{evaluate_balance, Archetype, BalanceReport, BalanceTargets, SimCurriculum, SimNode};
+use std::path::Path;
+
+use crate::validator::Manifest;
+
+/// Convert a content manifest into simulation order (week, day, node)
+pub fn load_curriculum(content_path: &Path) -> Result<(SimCurriculum, Vec<String>)> {
+    let manifest_path = content_path.join("manifest.json");
+    let manifest_content = std::fs::read_to_string(&manifest_path)
+        .context("Failed to read manifest.json")?;
+
+    let manifest: Manifest = serde_json::from_str(&manifest_content)
+        .context("Failed to parse manifest.json")?;
+
+    let mut nodes = Vec::new();
+    let mut skipped = Vec::new();
+
+    for week in &manifest.weeks {
+        for day in &week.days {
+            for node in &day.nodes {
+                let Some(activity) = XpActivity::from_node_type(&node.node_type) else {
+                    skipped.push(format!("{} (unknown type '{}')", node.id, node.node_type));
+                    continue;
+                };
+                let difficulty = node.difficulty.parse().unwrap_or_else(|_| {
+                    skipped.push(format!("{} (unknown difficulty '{}', using easy)", node.id, node.difficulty));
+                    Difficulty::Easy
+                });
+
+                nodes.push(SimNode {
+                    id: node.id.clone(),
+                    activity,
+                    difficulty,
+                    estimated_minutes: node.estimated_minutes,
+                    skills: node.skills.clone(),
+                });
+            }
+        }
+    }
+
+    Ok((SimCurriculum::new(nodes), skipped))
+}
+
+/// Load archetypes from a JSON array, or use the built-in presets
+pub fn load_archetypes(path: Option<&Path>) -> Result<Vec<Archetype>> {
+    match path {
+        Some(path) => {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            serde_json::from_str(&content).context("Failed to parse archetypes")
+        }
+        None => Ok(Archetype::presets()),
+    }
+}
+
+pub fn run_simulation(content_path: &Path, archetypes_path: Option<&Path>) -> Result<BalanceReport> {
+    let (curriculum, skipped) = load_curriculum(content_path)?;
+    let archetypes = load_archetypes(archetypes_path)?;
+
+    for note in &skipped {
+        println!("  {} {}", "⚠".yellow(), note);
+    }
+    println!(
+        "  {} nodes, {:.1} hours of content, {} archetypes",
+        curriculum.nodes.len(),
+        curriculum.total_minutes() as f64 / 60.0,
+        archetypes.len()
+    );
+
+    Ok(evaluate_balance(&curriculum, &archetypes, &BalanceTargets::default()))
+}
+
+pub fn print_report(report: &BalanceReport) {
+    for result in &report.results {
+        println!("\n{}", format!("=== {} ===", result.archetype).bold());
+        println!("  Total XP: {}", result.total_xp);
+        println!("  Final level: {}", result.final_level);
+        println!("  Max streak: {} days", result.max_streak);
+        println!("  Average mastery: {:.1}%", result.average_mastery * 100.0);
+        println!("  Completed: {}/{} nodes", result.nodes_completed, result.total_nodes);
+        println!("  Badges earned: {}", result.badges_earned);
+        match result.weeks_to_complete {
+            Some(weeks) => println!("  Weeks to finish: {}", weeks),
+            None => println!("  Weeks to finish: did not finish"),
+        }
+
+        for check in report
+            .checks
+            .iter()
+            .filter(|c| c.archetype.as_deref() == Some(result.archetype.as_str()))
+        {
+            print_check(&check.name, check.passed, &check.detail);
+        }
+    }
+
+    let overall: Vec<_> = report.checks.iter().filter(|c| c.archetype.is_none()).collect();
+    if !overall.is_empty() {
+        println!("\n{}", "=== Across archetypes ===".bold());
+        for check in overall {
+            print_check(&check.name, check.passed, &check.detail);
+        }
+    }
+
+    if report.is_balanced() {
+        println!("\n{}", "✓ Curriculum pacing is balanced".green().bold());
+    } else {
+        println!(
+            "\n{}",
+            format!("✗ {} balance check(s) failed", report.failures().len()).red().bold()
+        );
+    }
+}
+
+fn print_check(name: &str, passed: bool, detail: &str) {
+    if passed {
+        println!("  {} {}: {}", "✓".green(), name, detail);
+    } else {
+        println!("  {} {}: {}", "✗".red(), name, detail);
+    }
+}
@@ -2,10 +2,15 @@
 //!
 //! Validates manifest.json structure, file paths, and content schemas.
 
+// The structs mirror the full content schema, including fields validation never reads
+#![allow(dead_code)]
+
+use crate::lint::{lint_content, LintConfig};
 use anyhow::{Context, Result};
 use colored::*;
-use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use content::encryption;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 #[derive(Debug, Deserialize)]
@@ -18,6 +23,16 @@ pub struct Manifest {
     pub weeks: Vec<Week>,
     pub checkpoints: Vec<Checkpoint>,
     pub skills: Vec<Skill>,
+    /// Set on sealed packs
+    #[serde(default)]
+    pub encryption: Option<Encryption>,
+    #[serde(default)]
+    pub requires_license: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Encryption {
+    pub key_check: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -49,6 +64,11 @@ pub struct Node {
     pub content_path: String,
     pub skills: Vec<String>,
     pub prerequisites: Vec<String>,
+    #[serde(default)]
+    pub deprecated: bool,
+    /// Node replacing this one; implies `deprecated`
+    #[serde(default)]
+    pub superseded_by: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -56,6 +76,13 @@ pub struct Checkpoint {
     pub id: String,
     pub title: String,
     pub week: String,
+    #[serde(default)]
+    pub xp_reward: u32,
+    #[serde(default)]
+    pub prerequisites: Vec<String>,
+    /// Rubric file by artifact type
+    #[serde(default)]
+    pub rubrics: HashMap<String, String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -99,6 +126,12 @@ pub struct Challenge {
     pub hints: Vec<String>,
     pub difficulty: String,
     pub skills: Vec<String>,
+    /// Template under `templates/` the challenge's crate is built from
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Crates learners may add to an exported workspace's `Cargo.toml`
+    #[serde(default)]
+    pub dependencies_allowlist: Option<Vec<String>>,
 }
 
 pub struct ValidationReport {
@@ -140,7 +173,10 @@ impl std::fmt::Display for ValidationReport {
     }
 }
 
-pub fn validate_content(content_path: &Path) -> Result<ValidationReport> {
+/// Validate the pack at `content_path`, then lint it with the rules in
+/// `lint_config`, or the pack's own `lint.json`. With `update_baseline`, lint
+/// findings are written to the pack's baseline rather than reported.
+pub fn validate_content(content_path: &Path, lint_config: Option<&Path>, update_baseline: bool) -> Result<ValidationReport> {
     let mut report = ValidationReport {
         errors: Vec::new(),
         warnings: Vec::new(),
@@ -161,6 +197,10 @@ pub fn validate_content(content_path: &Path) -> Result<ValidationReport> {
         .context("Failed to parse manifest.json")?;
     
     report.info.push(format!("Found manifest: {}", manifest.title));
+
+    if manifest.requires_license {
+        report.info.push("Content only opens with a license key".to_string());
+    }
     
     // Collect all defined skill IDs
     let skill_ids: HashSet<&str> = manifest.skills.iter().map(|s| s.id.as_str()).collect();
@@ -184,9 +224,19 @@ pub fn validate_content(content_path: &Path) -> Result<ValidationReport> {
                         "Missing content file for '{}': {}",
                         node.id, node.content_path
                     ));
+                } else if encryption::is_sealed(&node.content_path) {
+                    // Sealed files can't be checked without the key; validate the pack before sealing it
+                    if manifest.encryption.is_none() {
+                        report.errors.push(format!(
+                            "Node '{}' has sealed content {} but the manifest has no encryption section",
+                            node.id, node.content_path
+                        ));
+                    } else {
+                        report.info.push(format!("Node '{}' is sealed; its content wasn't checked", node.id));
+                    }
                 } else {
                     // Validate content file based on type
-                    if let Err(e) = validate_content_file(&content_file, &node.node_type) {
+                    if let Err(e) = validate_content_file(content_path, &content_file, &node.node_type) {
                         report.errors.push(format!(
                             "Invalid content file '{}': {}",
                             node.content_path, e
@@ -223,7 +273,14 @@ pub fn validate_content(content_path: &Path) -> Result<ValidationReport> {
         }
     }
     
-    // Validate prerequisites (second pass)
+    // Validate prerequisites and replacements (second pass)
+    let replacements: HashMap<&str, &str> = manifest
+        .weeks
+        .iter()
+        .flat_map(|w| &w.days)
+        .flat_map(|d| &d.nodes)
+        .filter_map(|n| n.superseded_by.as_deref().map(|by| (n.id.as_str(), by)))
+        .collect();
     for week in &manifest.weeks {
         for day in &week.days {
             for node in &day.nodes {
@@ -233,16 +290,51 @@ pub fn validate_content(content_path: &Path) -> Result<ValidationReport> {
                             "Node '{}' has invalid prerequisite: {}",
                             node.id, prereq
                         ));
+                    } else if let Some(replacement) = replacements.get(prereq.as_str()) {
+                        if !node.deprecated && !replacements.contains_key(node.id.as_str()) {
+                            report.info.push(format!(
+                                "Node '{}' has superseded prerequisite '{}'; completing '{}' also meets it",
+                                node.id, prereq, replacement
+                            ));
+                        }
+                    }
+                }
+
+                let Some(replacement) = &node.superseded_by else {
+                    continue;
+                };
+                if !node_ids.contains(replacement) {
+                    report.errors.push(format!(
+                        "Node '{}' is superseded by unknown node: {}",
+                        node.id, replacement
+                    ));
+                    continue;
+                }
+                let mut current = replacement.as_str();
+                for _ in 0..replacements.len() {
+                    if current == node.id {
+                        report.errors.push(format!(
+                            "Node '{}' is superseded by itself through its replacements",
+                            node.id
+                        ));
+                        break;
+                    }
+                    match replacements.get(current) {
+                        Some(next) => current = next,
+                        None => break,
                     }
                 }
             }
         }
     }
     
+    let config = LintConfig::load(content_path, lint_config)?;
+    lint_content(content_path, &manifest, &config, update_baseline, &mut report)?;
+    
     Ok(report)
 }
 
-fn validate_content_file(path: &Path, node_type: &str) -> Result<()> {
+fn validate_content_file(content_path: &Path, path: &Path, node_type: &str) -> Result<()> {
     let content = std::fs::read_to_string(path)?;
     
     match node_type {
@@ -258,20 +350,7 @@ fn validate_content_file(path: &Path, node_type: &str) -> Result<()> {
         "quiz" => {
             let quiz: Quiz = serde_json::from_str(&content)
                 .context("Invalid quiz JSON")?;
-            if quiz.questions.is_empty() {
-                anyhow::bail!("Quiz has no questions");
-            }
-            for q in &quiz.questions {
-                if q.options.len() < 2 {
-                    anyhow::bail!("Question '{}' needs at least 2 options", q.id);
-                }
-                // Validate correct answer is within bounds
-                if let Some(idx) = q.correct_answer {
-                    if idx >= q.options.len() {
-                        anyhow::bail!("Question '{}' correct_answer index out of bounds", q.id);
-                    }
-                }
-            }
+            validate_quiz(&quiz)?;
         }
         "mini-challenge" => {
             let challenge: Challenge = serde_json::from_str(&content)
@@ -282,6 +361,16 @@ fn validate_content_file(path: &Path, node_type: &str) -> Result<()> {
             if challenge.test_code.is_empty() {
                 anyhow::bail!("Challenge has no test code");
             }
+            if let Some(template) = &challenge.template {
+                if !content_path.join("templates").join(template).join("template.json").exists() {
+                    anyhow::bail!("Challenge uses missing template '{}'", template);
+                }
+            }
+            for name in challenge.dependencies_allowlist.iter().flatten() {
+                if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                    anyhow::bail!("Invalid crate name in dependencies_allowlist: '{}'", name);
+                }
+            }
         }
         _ => {}
     }
@@ -289,13 +378,47 @@ fn validate_content_file(path: &Path, node_type: &str) -> Result<()> {
     Ok(())
 }
 
-pub fn content_stats(content_path: &Path) -> Result<String> {
+pub fn validate_quiz(quiz: &Quiz) -> Result<()> {
+    if quiz.questions.is_empty() {
+        anyhow::bail!("Quiz has no questions");
+    }
+    for q in &quiz.questions {
+        validate_question(q)?;
+    }
+    Ok(())
+}
+
+pub fn validate_question(q: &Question) -> Result<()> {
+    if q.options.len() < 2 {
+        anyhow::bail!("Question '{}' needs at least 2 options", q.id);
+    }
+    if q.correct_answer.is_none() && q.correct_answers.as_ref().is_none_or(|a| a.is_empty()) {
+        anyhow::bail!("Question '{}' has no correct answer", q.id);
+    }
+    // Validate correct answers are within bounds
+    if let Some(idx) = q.correct_answer {
+        if idx >= q.options.len() {
+            anyhow::bail!("Question '{}' correct_answer index out of bounds", q.id);
+        }
+    }
+    if let Some(indices) = &q.correct_answers {
+        if indices.iter().any(|&idx| idx >= q.options.len()) {
+            anyhow::bail!("Question '{}' correct_answers index out of bounds", q.id);
+        }
+    }
+    Ok(())
+}
+
+pub fn load_manifest(content_path: &Path) -> Result<Manifest> {
     let manifest_path = content_path.join("manifest.json");
     let manifest_content = std::fs::read_to_string(&manifest_path)
         .context("Failed to read manifest.json")?;
     
-    let manifest: Manifest = serde_json::from_str(&manifest_content)
-        .context("Failed to parse manifest.json")?;
+    serde_json::from_str(&manifest_content).context("Failed to parse manifest.json")
+}
+
+pub fn content_stats(content_path: &Path) -> Result<String> {
+    let manifest = load_manifest(content_path)?;
     
     let mut total_nodes = 0;
     let mut lectures = 0;